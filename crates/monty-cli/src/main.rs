@@ -2,13 +2,13 @@ use std::{
     fs,
     io::{self, BufRead, Write},
     process::ExitCode,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use monty::{
-    MontyObject, MontyRepl, MontyRun, NoLimitTracker, PrintWriter, ReplContinuationMode, RunProgress,
-    detect_repl_continuation_mode,
+    ExcType, LimitedTracker, MontyException, MontyObject, MontyRepl, MontyRun, NoLimitTracker, PrintWriter,
+    ReplContinuationMode, ResourceLimits, RunProgress, detect_repl_continuation_mode,
 };
 // disabled due to format failing on https://github.com/pydantic/monty/pull/75 where CI and local wanted imports ordered differently
 // TODO re-enabled soon!
@@ -21,9 +21,17 @@ use monty_type_checking::{SourceFile, type_check};
 /// - `monty <file>` runs the file in script mode
 /// - `monty -i` starts an empty interactive REPL
 /// - `monty -i <file>` seeds the REPL with file contents
+/// - `monty run <file>` runs the file with resource limits
+/// - `monty repl [file]` starts a REPL with per-snippet resource limits
+/// - `monty check <file>` validates the file without running it
+/// - `monty parse <file> --ast` prints the parsed syntax tree
+/// - `monty parse <file> --json` prints Monty's prepared syntax tree as JSON
 #[derive(Parser)]
-#[command(version)]
+#[command(version, args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Start interactive REPL mode.
     #[arg(short = 'i', long = "interactive")]
     interactive: bool,
@@ -32,11 +40,107 @@ struct Cli {
     file: Option<String>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Run a Python file, optionally with resource limits.
+    Run(RunArgs),
+    /// Start an interactive REPL, applying resource limits to each snippet.
+    Repl(ReplArgs),
+    /// Check that a Python file parses and compiles, without running it.
+    Check {
+        /// Python file to check.
+        file: String,
+    },
+    /// Parse a Python file and report syntax errors.
+    Parse {
+        /// Python file to parse.
+        file: String,
+        /// Print the parsed syntax tree.
+        #[arg(long)]
+        ast: bool,
+        /// Print Monty's prepared syntax tree as JSON, with names resolved to their scopes.
+        #[arg(long, conflicts_with = "ast")]
+        json: bool,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Python file to execute.
+    file: String,
+
+    #[command(flatten)]
+    limits: LimitArgs,
+
+    /// Print the result, or the error, as a JSON object on stdout.
+    ///
+    /// Output from `print()` is collected and included in the object rather than
+    /// written directly to stdout.
+    #[arg(long)]
+    json_output: bool,
+}
+
+#[derive(Args)]
+struct ReplArgs {
+    /// Python file to run before the first prompt.
+    file: Option<String>,
+
+    #[command(flatten)]
+    limits: LimitArgs,
+}
+
+/// Resource limit flags shared by `monty run` and `monty repl`.
+#[derive(Args)]
+struct LimitArgs {
+    /// Maximum execution time, e.g. `5s`, `250ms` or `1.5` (seconds).
+    #[arg(long, value_parser = parse_duration)]
+    time_limit: Option<Duration>,
+
+    /// Maximum heap memory, e.g. `64MB`, `512KB` or `1048576` (bytes).
+    #[arg(long, value_parser = parse_size)]
+    memory_limit: Option<usize>,
+
+    /// Maximum number of heap allocations.
+    #[arg(long)]
+    max_allocations: Option<usize>,
+
+    /// Maximum number of bytecode instructions and builtin loop iterations.
+    #[arg(long)]
+    max_steps: Option<u64>,
+}
+
+impl LimitArgs {
+    fn resource_limits(&self) -> ResourceLimits {
+        let mut limits = ResourceLimits::new();
+        if let Some(time_limit) = self.time_limit {
+            limits = limits.max_duration(time_limit);
+        }
+        if let Some(memory_limit) = self.memory_limit {
+            limits = limits.max_memory(memory_limit);
+        }
+        if let Some(max_allocations) = self.max_allocations {
+            limits = limits.max_allocations(max_allocations);
+        }
+        if let Some(max_steps) = self.max_steps {
+            limits = limits.max_steps(max_steps);
+        }
+        limits
+    }
+}
+
 const EXT_FUNCTIONS: bool = false;
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    match cli.command {
+        Some(Command::Run(args)) => return run_command(&args),
+        Some(Command::Repl(args)) => return repl_command(&args),
+        Some(Command::Check { file }) => return check_command(&file),
+        Some(Command::Parse { file, ast, json }) => return parse_command(&file, ast, json),
+        None => {}
+    }
+
     if let Some(file_path) = cli.file.as_deref() {
         let code = match read_file(file_path) {
             Ok(code) => code,
@@ -46,14 +150,14 @@ fn main() -> ExitCode {
             }
         };
         return if cli.interactive {
-            run_repl(file_path, code)
+            run_repl(file_path, code, ResourceLimits::new())
         } else {
             run_script(file_path, code)
         };
     }
 
     if cli.interactive {
-        return run_repl("repl.py", String::new());
+        return run_repl("repl.py", String::new(), ResourceLimits::new());
     }
 
     let file_path = "example.py";
@@ -138,6 +242,195 @@ fn run_script(file_path: &str, code: String) -> ExitCode {
     }
 }
 
+/// Runs a Python file for `monty run`, applying any resource limits from the command line.
+///
+/// Unlike the legacy script mode this skips type checking and timing output, so stdout
+/// only contains what the program prints, or a single JSON object with `--json-output`.
+///
+/// Returns `ExitCode::FAILURE` if the file can't be read, compiled or run.
+fn run_command(args: &RunArgs) -> ExitCode {
+    let code = match read_file(&args.file) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut print = if args.json_output {
+        PrintWriter::Collect(String::new())
+    } else {
+        PrintWriter::Stdout
+    };
+    let result = MontyRun::new(code, &args.file, vec![], vec![])
+        .and_then(|runner| runner.run(vec![], LimitedTracker::new(args.limits.resource_limits()), &mut print));
+
+    if args.json_output {
+        let stdout = match print {
+            PrintWriter::Collect(output) => output,
+            _ => String::new(),
+        };
+        let json = match &result {
+            Ok(value) => serde_json::json!({ "output": value, "stdout": stdout }),
+            Err(err) => serde_json::json!({ "error": exception_json(err), "stdout": stdout }),
+        };
+        println!("{json}");
+    } else {
+        match &result {
+            Ok(MontyObject::None) => {}
+            Ok(value) => println!("{value}"),
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+
+    if result.is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Starts the REPL for `monty repl`, seeded with the file's contents if one is given.
+fn repl_command(args: &ReplArgs) -> ExitCode {
+    let limits = args.limits.resource_limits();
+    match args.file.as_deref() {
+        Some(file_path) => match read_file(file_path) {
+            Ok(code) => run_repl(file_path, code, limits),
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        None => run_repl("repl.py", String::new(), limits),
+    }
+}
+
+/// Validates a Python file for `monty check` by parsing and compiling it without running it.
+///
+/// This catches syntax errors and unsupported language features, which Monty reports when
+/// preparing the code rather than at runtime.
+fn check_command(file_path: &str) -> ExitCode {
+    let code = match read_file(file_path) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match MontyRun::new(code, file_path, vec![], vec![]) {
+        Ok(_) => {
+            println!("{file_path}: ok");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses a Python file for `monty parse`, printing the syntax tree when `ast` is set, or
+/// Monty's prepared syntax tree as JSON when `json` is set.
+fn parse_command(file_path: &str, ast: bool, json: bool) -> ExitCode {
+    let code = match read_file(file_path) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if json {
+        return match MontyRun::ast_json(&code, file_path, &[], &[]) {
+            Ok(ast) => {
+                println!("{ast:#}");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    match ruff_python_parser::parse_module(&code) {
+        Ok(parsed) => {
+            if ast {
+                println!("{:#?}", parsed.syntax());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{file_path}: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Builds the JSON description of an exception used by `monty run --json-output`.
+fn exception_json(exc: &MontyException) -> serde_json::Value {
+    let location = exc.location().map(|frame| {
+        serde_json::json!({
+            "filename": frame.filename,
+            "line": frame.start.line,
+            "column": frame.start.column,
+            "end_line": frame.end.line,
+            "end_column": frame.end.column,
+        })
+    });
+    serde_json::json!({
+        "type": exc.type_name(),
+        "message": exc.message(),
+        "location": location,
+        "traceback": exc.to_string(),
+    })
+}
+
+/// Parses a duration given as seconds with an optional `ms`, `s`, `m` or `h` suffix.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60.0)
+    } else if let Some(number) = value.strip_suffix('h') {
+        (number, 3600.0)
+    } else {
+        (value, 1.0)
+    };
+    let number: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}', expected e.g. '5s' or '250ms'"))?;
+    Duration::try_from_secs_f64(number * scale).map_err(|err| format!("invalid duration '{value}': {err}"))
+}
+
+/// Parses a size in bytes with an optional `KB`, `MB` or `GB` suffix (powers of 1024).
+fn parse_size(value: &str) -> Result<usize, String> {
+    let value = value.trim();
+    let upper = value.to_ascii_uppercase();
+    let (number, scale) = if let Some(number) = upper.strip_suffix("KB") {
+        (number, 1 << 10)
+    } else if let Some(number) = upper.strip_suffix("MB") {
+        (number, 1 << 20)
+    } else if let Some(number) = upper.strip_suffix("GB") {
+        (number, 1 << 30)
+    } else if let Some(number) = upper.strip_suffix('B') {
+        (number, 1)
+    } else {
+        (upper.as_str(), 1)
+    };
+    number
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .ok_or_else(|| format!("invalid size '{value}', expected e.g. '64MB' or '1048576'"))
+}
+
 /// Starts an interactive line-by-line REPL session.
 ///
 /// Initializes `MontyRepl` once and incrementally feeds entered snippets without
@@ -146,9 +439,13 @@ fn run_script(file_path: &str, code: String) -> ExitCode {
 /// - `>>> ` for a new statement
 /// - `... ` for continuation
 ///
+/// `limits` apply to the initial code and then to each snippet separately: the time,
+/// step and allocation budgets are reset before every snippet, while the memory limit
+/// covers everything kept alive in the session.
+///
 /// Returns `ExitCode::SUCCESS` on EOF or `exit`, and `ExitCode::FAILURE` on
 /// initialization or I/O errors.
-fn run_repl(file_path: &str, code: String) -> ExitCode {
+fn run_repl(file_path: &str, code: String, limits: ResourceLimits) -> ExitCode {
     let input_names = vec![];
     let inputs = vec![];
     let ext_functions = vec!["add_ints".to_owned()];
@@ -159,7 +456,7 @@ fn run_repl(file_path: &str, code: String) -> ExitCode {
         input_names,
         ext_functions,
         inputs,
-        NoLimitTracker,
+        LimitedTracker::new(limits),
         &mut PrintWriter::Stdout,
     ) {
         Ok(v) => v,
@@ -243,7 +540,10 @@ fn run_repl(file_path: &str, code: String) -> ExitCode {
 }
 
 /// Executes one collected REPL snippet and prints value/errors for interactive use.
-fn execute_repl_snippet(repl: &mut MontyRepl<NoLimitTracker>, snippet: &str) {
+///
+/// Resets the tracker's budget first so resource limits apply per snippet.
+fn execute_repl_snippet(repl: &mut MontyRepl<LimitedTracker>, snippet: &str) {
+    repl.tracker_mut().reset_budget();
     match repl.feed_no_print(snippet) {
         Ok(output) => {
             if output != MontyObject::None {
@@ -260,8 +560,9 @@ fn execute_repl_snippet(repl: &mut MontyRepl<NoLimitTracker>, snippet: &str) {
 /// external calls and returns the final value when execution reaches
 /// `RunProgress::Complete`.
 ///
-/// Returns an error string for unsupported suspend points (OS calls or async
-/// futures) or invalid external-function dispatch.
+/// Errors from external functions are raised in the sandboxed code, so they can
+/// be caught there. Returns an error string for unsupported suspend points (OS
+/// calls or async futures) or an uncaught exception.
 fn run_until_complete(mut progress: RunProgress<NoLimitTracker>) -> Result<MontyObject, String> {
     loop {
         match progress {
@@ -272,9 +573,9 @@ fn run_until_complete(mut progress: RunProgress<NoLimitTracker>) -> Result<Monty
                 state,
                 ..
             } => {
-                let return_value = resolve_external_call(&function_name, &args)?;
+                let result = resolve_external_call(&function_name, &args);
                 progress = state
-                    .run(return_value, &mut PrintWriter::Stdout)
+                    .run(result, &mut PrintWriter::Stdout)
                     .map_err(|err| format!("{err}"))?;
             }
             RunProgress::ResolveFutures(state) => {
@@ -286,6 +587,10 @@ fn run_until_complete(mut progress: RunProgress<NoLimitTracker>) -> Result<Monty
             RunProgress::OsCall { function, args, .. } => {
                 return Err(format!("OS calls not supported in CLI: {function:?}({args:?})"));
             }
+            // the CLI runs without a time limit, but resuming is the right response to a pause
+            RunProgress::Paused(state) => {
+                progress = state.resume(&mut PrintWriter::Stdout).map_err(|err| format!("{err}"))?;
+            }
         }
     }
 }
@@ -295,21 +600,33 @@ fn run_until_complete(mut progress: RunProgress<NoLimitTracker>) -> Result<Monty
 /// The CLI currently supports only `add_ints(int, int)`, which makes it
 /// possible to exercise the suspend/resume path in a deterministic way.
 ///
-/// Returns a runtime-like error string for unknown function names, wrong arity,
-/// or incorrect argument types.
-fn resolve_external_call(function_name: &str, args: &[MontyObject]) -> Result<MontyObject, String> {
+/// Returns a `KeyError` for unknown function names and a `TypeError` for
+/// wrong arity or incorrect argument types, to be raised at the call site.
+fn resolve_external_call(function_name: &str, args: &[MontyObject]) -> Result<MontyObject, MontyException> {
     if function_name != "add_ints" {
-        return Err(format!("unknown external function: {function_name}({args:?})"));
+        return Err(MontyException::new(
+            ExcType::KeyError,
+            Some(format!("\"External function '{function_name}' not found\"")),
+        ));
     }
 
     if args.len() != 2 {
-        return Err(format!("add_ints requires exactly 2 arguments, got {}", args.len()));
+        return Err(MontyException::new(
+            ExcType::TypeError,
+            Some(format!(
+                "add_ints() takes 2 positional arguments but {} were given",
+                args.len()
+            )),
+        ));
     }
 
     if let (MontyObject::Int(a), MontyObject::Int(b)) = (&args[0], &args[1]) {
         Ok(MontyObject::Int(a + b))
     } else {
-        Err(format!("add_ints requires integer arguments, got {args:?}"))
+        Err(MontyException::new(
+            ExcType::TypeError,
+            Some("add_ints() requires integer arguments".to_owned()),
+        ))
     }
 }
 
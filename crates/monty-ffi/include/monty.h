@@ -0,0 +1,40 @@
+/*
+ * C API for the Monty sandboxed Python interpreter.
+ *
+ * Values are exchanged as JSON in MontyObject's format, e.g. {"Int": 1}.
+ * Errors are JSON objects with "type", "message", "location" and "traceback" keys;
+ * "type" is "InterfaceError" for invalid arguments rather than Python exceptions.
+ */
+#ifndef MONTY_H
+#define MONTY_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct MontyProgram MontyProgram;
+typedef struct MontyResult MontyResult;
+
+/* Compiles code. Returns NULL on failure and sets *error_out (if not NULL) to the error. */
+MontyProgram *monty_compile(const char *code, const char *script_name, const char *input_names_json,
+                            MontyResult **error_out);
+
+/* Runs a program. inputs_json and limits_json may be NULL. Never returns NULL. */
+MontyResult *monty_run(const MontyProgram *program, const char *inputs_json, const char *limits_json);
+
+bool monty_result_is_error(const MontyResult *result);
+/* Strings are owned by the result and valid until monty_result_free. */
+const char *monty_result_json(const MontyResult *result);
+const char *monty_result_stdout(const MontyResult *result);
+const char *monty_result_stderr(const MontyResult *result);
+
+void monty_result_free(MontyResult *result);
+void monty_program_free(MontyProgram *program);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MONTY_H */
@@ -0,0 +1,35 @@
+//! JSON representation of errors returned through the C ABI.
+
+use monty::MontyException;
+use serde_json::{Value, json};
+
+/// Describes an exception raised by the sandboxed code, or a syntax error.
+pub fn exception_json(exc: &MontyException) -> Value {
+    let location = exc.location().map(|frame| {
+        json!({
+            "filename": frame.filename,
+            "line": frame.start.line,
+            "column": frame.start.column,
+            "end_line": frame.end.line,
+            "end_column": frame.end.column,
+        })
+    });
+    json!({
+        "type": exc.type_name(),
+        "message": exc.message(),
+        "location": location,
+        "traceback": exc.to_string(),
+    })
+}
+
+/// Describes a host error, like invalid arguments, in the same shape as `exception_json`.
+///
+/// `type` is `"InterfaceError"` so hosts can tell these apart from Python exceptions.
+pub fn interface_error_json(message: &str) -> Value {
+    json!({
+        "type": "InterfaceError",
+        "message": message,
+        "location": null,
+        "traceback": null,
+    })
+}
@@ -0,0 +1,261 @@
+//! C ABI bindings for the Monty sandboxed Python interpreter.
+//!
+//! Hosts compile code once into a `MontyProgram` handle with `monty_compile`, then call
+//! `monty_run` any number of times with JSON inputs. Each run returns a `MontyResult` holding
+//! either the JSON-encoded result or a JSON error, plus captured print output.
+//!
+//! Values use `MontyObject`'s JSON format, e.g. `{"Int": 1}`. Every handle returned by this
+//! library must be released with the matching `*_free` function. See `include/monty.h` for
+//! the C declarations.
+
+mod exceptions;
+mod limits;
+
+use std::{
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr,
+};
+
+use monty::{LimitedTracker, MontyObject, MontyRun};
+
+use crate::{
+    exceptions::{exception_json, interface_error_json},
+    limits::LimitsJson,
+};
+
+/// A compiled program, created by `monty_compile` and freed with `monty_program_free`.
+///
+/// A program can be run many times, including concurrently from several threads.
+pub struct MontyProgram {
+    runner: MontyRun,
+}
+
+/// The outcome of `monty_compile` or `monty_run`, freed with `monty_result_free`.
+pub struct MontyResult {
+    /// JSON-encoded result value, or error description if `is_error` is set.
+    json: CString,
+    is_error: bool,
+    stdout: CString,
+    stderr: CString,
+}
+
+impl MontyResult {
+    fn new(json: &str, is_error: bool, stdout: String, stderr: String) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            json: to_c_string(json.to_owned()),
+            is_error,
+            stdout: to_c_string(stdout),
+            stderr: to_c_string(stderr),
+        }))
+    }
+
+    fn error(error: &serde_json::Value) -> *mut Self {
+        Self::new(&error.to_string(), true, String::new(), String::new())
+    }
+}
+
+/// Compiles `code` into a program that takes the inputs named in `input_names_json`.
+///
+/// `input_names_json` is a JSON array of strings, or NULL if the code takes no inputs.
+/// Returns NULL on failure, in which case `*error_out` (if `error_out` is not NULL) is set to
+/// a `MontyResult` describing the error.
+///
+/// # Safety
+/// `code`, `script_name` and `input_names_json` (if not NULL) must be valid NUL-terminated
+/// strings, and `error_out` must be NULL or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_compile(
+    code: *const c_char,
+    script_name: *const c_char,
+    input_names_json: *const c_char,
+    error_out: *mut *mut MontyResult,
+) -> *mut MontyProgram {
+    let compiled = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: the caller guarantees the pointers are valid strings or NULL
+        let (code, script_name, input_names_json) = unsafe {
+            (
+                str_arg(code, "code")?,
+                str_arg(script_name, "script_name")?,
+                optional_str_arg(input_names_json, "input_names_json")?,
+            )
+        };
+        let input_names: Vec<String> = match input_names_json {
+            Some(json) => serde_json::from_str(json)
+                .map_err(|err| interface_error_json(&format!("invalid input_names_json: {err}")))?,
+            None => Vec::new(),
+        };
+        MontyRun::new(code.to_owned(), script_name, input_names, vec![]).map_err(|exc| exception_json(&exc))
+    }))
+    .unwrap_or_else(|_| Err(interface_error_json("internal error: panic while compiling")));
+
+    match compiled {
+        Ok(runner) => Box::into_raw(Box::new(MontyProgram { runner })),
+        Err(error) => {
+            if !error_out.is_null() {
+                // SAFETY: the caller guarantees `error_out` is valid for writes
+                unsafe { *error_out = MontyResult::error(&error) };
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Runs a compiled program, always returning a result that must be freed with `monty_result_free`.
+///
+/// `inputs_json` is a JSON array of values in the order of the program's input names, or NULL
+/// if it takes no inputs. `limits_json` is a JSON object of resource limits using the same keys
+/// as the Python package (`max_duration_secs`, `max_memory`, ...), or NULL for defaults.
+///
+/// # Safety
+/// `program` must be a live handle from `monty_compile`, and `inputs_json` and `limits_json`
+/// must be NULL or valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_run(
+    program: *const MontyProgram,
+    inputs_json: *const c_char,
+    limits_json: *const c_char,
+) -> *mut MontyResult {
+    if program.is_null() {
+        return MontyResult::error(&interface_error_json("program must not be null"));
+    }
+    // SAFETY: the caller guarantees `program` is a live handle
+    let runner = unsafe { &(*program).runner };
+
+    catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: the caller guarantees the pointers are valid strings or NULL
+        let (inputs_json, limits_json) = unsafe {
+            (
+                optional_str_arg(inputs_json, "inputs_json")?,
+                optional_str_arg(limits_json, "limits_json")?,
+            )
+        };
+        let inputs: Vec<MontyObject> = match inputs_json {
+            Some(json) => serde_json::from_str(json)
+                .map_err(|err| interface_error_json(&format!("invalid inputs_json: {err}")))?,
+            None => Vec::new(),
+        };
+        let limits: LimitsJson = match limits_json {
+            Some(json) => serde_json::from_str(json)
+                .map_err(|err| interface_error_json(&format!("invalid limits_json: {err}")))?,
+            None => LimitsJson::default(),
+        };
+
+        let tracker = LimitedTracker::new(limits.into_limits());
+        let (result, stdout, stderr) = runner.run_capture(inputs, tracker);
+        Ok(match result {
+            Ok(value) => match serde_json::to_string(&value) {
+                Ok(json) => MontyResult::new(&json, false, stdout, stderr),
+                Err(err) => MontyResult::new(
+                    &interface_error_json(&format!("result could not be encoded as JSON: {err}")).to_string(),
+                    true,
+                    stdout,
+                    stderr,
+                ),
+            },
+            Err(exc) => MontyResult::new(&exception_json(&exc).to_string(), true, stdout, stderr),
+        })
+    }))
+    .unwrap_or_else(|_| Err(interface_error_json("internal error: panic while running")))
+    .unwrap_or_else(|error| MontyResult::error(&error))
+}
+
+/// Returns whether the result describes an error rather than a value.
+///
+/// # Safety
+/// `result` must be a live handle from `monty_compile` or `monty_run`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_result_is_error(result: *const MontyResult) -> bool {
+    // SAFETY: the caller guarantees `result` is a live handle
+    unsafe { (*result).is_error }
+}
+
+/// Returns the JSON-encoded value, or the JSON error if `monty_result_is_error` is true.
+///
+/// The string is owned by the result and valid until `monty_result_free`.
+///
+/// # Safety
+/// `result` must be a live handle from `monty_compile` or `monty_run`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_result_json(result: *const MontyResult) -> *const c_char {
+    // SAFETY: the caller guarantees `result` is a live handle
+    unsafe { (*result).json.as_ptr() }
+}
+
+/// Returns everything the run printed to stdout, valid until `monty_result_free`.
+///
+/// # Safety
+/// `result` must be a live handle from `monty_compile` or `monty_run`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_result_stdout(result: *const MontyResult) -> *const c_char {
+    // SAFETY: the caller guarantees `result` is a live handle
+    unsafe { (*result).stdout.as_ptr() }
+}
+
+/// Returns everything the run printed to stderr, valid until `monty_result_free`.
+///
+/// # Safety
+/// `result` must be a live handle from `monty_compile` or `monty_run`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_result_stderr(result: *const MontyResult) -> *const c_char {
+    // SAFETY: the caller guarantees `result` is a live handle
+    unsafe { (*result).stderr.as_ptr() }
+}
+
+/// Frees a result. Passing NULL is a no-op.
+///
+/// # Safety
+/// `result` must be NULL or a live handle from `monty_compile` or `monty_run`, and must not
+/// be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_result_free(result: *mut MontyResult) {
+    if !result.is_null() {
+        // SAFETY: the caller guarantees `result` came from `Box::into_raw` and is not used again
+        drop(unsafe { Box::from_raw(result) });
+    }
+}
+
+/// Frees a compiled program. Passing NULL is a no-op.
+///
+/// # Safety
+/// `program` must be NULL or a live handle from `monty_compile`, with no `monty_run` calls
+/// still in progress, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn monty_program_free(program: *mut MontyProgram) {
+    if !program.is_null() {
+        // SAFETY: the caller guarantees `program` came from `Box::into_raw` and is not used again
+        drop(unsafe { Box::from_raw(program) });
+    }
+}
+
+/// Borrows a required string argument.
+///
+/// # Safety
+/// `ptr` must be NULL or a valid NUL-terminated string that outlives `'a`.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, serde_json::Value> {
+    // SAFETY: forwarded from the caller
+    unsafe { optional_str_arg(ptr, name) }?.ok_or_else(|| interface_error_json(&format!("{name} must not be null")))
+}
+
+/// Borrows an optional string argument, where NULL means absent.
+///
+/// # Safety
+/// `ptr` must be NULL or a valid NUL-terminated string that outlives `'a`.
+unsafe fn optional_str_arg<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, serde_json::Value> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: forwarded from the caller
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| interface_error_json(&format!("{name} is not valid UTF-8")))
+}
+
+/// Converts to a C string, replacing interior NULs which C can't represent.
+fn to_c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|err| {
+        let s = String::from_utf8_lossy(&err.into_vec()).replace('\0', "\u{FFFD}");
+        CString::new(s).expect("NULs were replaced")
+    })
+}
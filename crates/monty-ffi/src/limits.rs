@@ -0,0 +1,48 @@
+//! JSON form of Monty's `ResourceLimits`, using the same keys as the Python package.
+
+use std::time::Duration;
+
+use monty::{DEFAULT_MAX_RECURSION_DEPTH, ResourceLimits};
+
+/// Resource limits as passed to `monty_run`.
+///
+/// Missing or `null` keys are not applied, except `max_recursion_depth`
+/// which defaults to `DEFAULT_MAX_RECURSION_DEPTH`.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitsJson {
+    max_allocations: Option<usize>,
+    max_duration_secs: Option<f64>,
+    max_steps: Option<u64>,
+    max_memory: Option<usize>,
+    gc_interval: Option<usize>,
+    max_recursion_depth: Option<usize>,
+    random_seed: Option<u64>,
+}
+
+impl LimitsJson {
+    /// Converts to the core `ResourceLimits`.
+    pub fn into_limits(self) -> ResourceLimits {
+        let mut limits =
+            ResourceLimits::new().max_recursion_depth(self.max_recursion_depth.or(Some(DEFAULT_MAX_RECURSION_DEPTH)));
+        if let Some(max) = self.max_allocations {
+            limits = limits.max_allocations(max);
+        }
+        if let Some(secs) = self.max_duration_secs {
+            limits = limits.max_duration(Duration::from_secs_f64(secs));
+        }
+        if let Some(max) = self.max_steps {
+            limits = limits.max_steps(max);
+        }
+        if let Some(max) = self.max_memory {
+            limits = limits.max_memory(max);
+        }
+        if let Some(interval) = self.gc_interval {
+            limits = limits.gc_interval(interval);
+        }
+        if let Some(seed) = self.random_seed {
+            limits = limits.random_seed(seed);
+        }
+        limits
+    }
+}
@@ -0,0 +1,131 @@
+//! Tests for the C ABI, calling the exported functions as a C host would.
+
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+};
+
+use monty_ffi::{
+    MontyProgram, MontyResult, monty_compile, monty_program_free, monty_result_free, monty_result_is_error,
+    monty_result_json, monty_result_stdout, monty_run,
+};
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+fn optional_ptr(s: Option<&CString>) -> *const std::ffi::c_char {
+    s.map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Reads and frees a result, returning `(is_error, json, stdout)`.
+fn take_result(result: *mut MontyResult) -> (bool, serde_json::Value, String) {
+    assert!(!result.is_null());
+    // SAFETY: `result` is a live handle, and its strings are copied before it is freed
+    unsafe {
+        let is_error = monty_result_is_error(result);
+        let json = CStr::from_ptr(monty_result_json(result)).to_str().unwrap();
+        let json = serde_json::from_str(json).unwrap();
+        let stdout = CStr::from_ptr(monty_result_stdout(result)).to_str().unwrap().to_owned();
+        monty_result_free(result);
+        (is_error, json, stdout)
+    }
+}
+
+fn compile(code: &str, input_names_json: Option<&str>) -> Result<*mut MontyProgram, serde_json::Value> {
+    let code = c(code);
+    let script_name = c("main.py");
+    let input_names = input_names_json.map(c);
+    let mut error = ptr::null_mut();
+    // SAFETY: all strings are valid C strings or NULL, and `error` is valid for writes
+    let program = unsafe {
+        monty_compile(
+            code.as_ptr(),
+            script_name.as_ptr(),
+            optional_ptr(input_names.as_ref()),
+            &raw mut error,
+        )
+    };
+    if program.is_null() {
+        Err(take_result(error).1)
+    } else {
+        assert!(error.is_null());
+        Ok(program)
+    }
+}
+
+fn run(
+    program: *const MontyProgram,
+    inputs_json: Option<&str>,
+    limits_json: Option<&str>,
+) -> (bool, serde_json::Value, String) {
+    let inputs = inputs_json.map(c);
+    let limits = limits_json.map(c);
+    // SAFETY: `program` is a live handle or NULL, and the strings are valid C strings or NULL
+    let result = unsafe { monty_run(program, optional_ptr(inputs.as_ref()), optional_ptr(limits.as_ref())) };
+    take_result(result)
+}
+
+fn free(program: *mut MontyProgram) {
+    // SAFETY: `program` came from `monty_compile` and is not used again
+    unsafe { monty_program_free(program) };
+}
+
+#[test]
+fn compile_and_run_with_inputs() {
+    let program = compile("print('hi')\nx + y", Some(r#"["x", "y"]"#)).unwrap();
+
+    // a program can be run more than once
+    for _ in 0..2 {
+        let (is_error, json, stdout) = run(program, Some(r#"[{"Int": 40}, {"Int": 2}]"#), None);
+        assert!(!is_error);
+        assert_eq!(json, serde_json::json!({"Int": 42}));
+        assert_eq!(stdout, "hi\n");
+    }
+    free(program);
+}
+
+#[test]
+fn syntax_error_sets_error_out() {
+    let error = compile("def", None).unwrap_err();
+    assert_eq!(error["type"], "SyntaxError");
+    assert_eq!(error["location"]["line"], 1);
+}
+
+#[test]
+fn runtime_error_is_json() {
+    let program = compile("1 / 0", None).unwrap();
+    let (is_error, json, _) = run(program, None, None);
+    assert!(is_error);
+    assert_eq!(json["type"], "ZeroDivisionError");
+    assert!(json["traceback"].as_str().unwrap().contains("ZeroDivisionError"));
+    free(program);
+}
+
+#[test]
+fn limits_are_applied() {
+    let program = compile("x = 0\nwhile True:\n    x += 1", None).unwrap();
+    let (is_error, json, _) = run(program, None, Some(r#"{"max_steps": 1000}"#));
+    assert!(is_error);
+    assert_eq!(json["type"], "TimeoutError");
+    free(program);
+}
+
+#[test]
+fn invalid_arguments_are_interface_errors() {
+    let program = compile("x", Some(r#"["x"]"#)).unwrap();
+
+    let (is_error, json, _) = run(program, Some("not json"), None);
+    assert!(is_error);
+    assert_eq!(json["type"], "InterfaceError");
+
+    let (is_error, json, _) = run(program, Some(r#"[{"Int": 1}]"#), Some(r#"{"max_stepz": 1}"#));
+    assert!(is_error);
+    assert_eq!(json["type"], "InterfaceError");
+
+    let (is_error, json, _) = run(ptr::null(), None, None);
+    assert!(is_error);
+    assert_eq!(json["message"], "program must not be null");
+
+    free(program);
+}
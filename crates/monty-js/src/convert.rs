@@ -17,6 +17,8 @@
 //! - `MontyObject::List` ↔ `Array`
 //! - `MontyObject::Dict` ↔ `Map` (preserves key types and insertion order)
 //! - `MontyObject::Set` ↔ `Set`
+//! - `MontyObject::DefaultDict`, `MontyObject::Counter` → `Map`
+//! - `MontyObject::Deque` → `Array`
 //! - `MontyObject::FrozenSet` ↔ `Set` (JS has no frozen set)
 //!
 //! ### Marked JS types (with `__monty_type__` property):
@@ -27,6 +29,7 @@
 //! - `MontyObject::BuiltinFunction` → `{ __monty_type__: 'BuiltinFunction', value }`
 //! - `MontyObject::Dataclass` → `{ __monty_type__: 'Dataclass', name, fields, ... }`
 //! - `MontyObject::Repr` → plain `string`
+//! - `MontyObject::ExternalObject` → its repr `string`, e.g. `'<external Database #1>'`
 //! - `MontyObject::Cycle` → placeholder `string`
 
 use std::collections::HashMap;
@@ -82,7 +85,11 @@ pub fn monty_to_js<'e>(obj: &MontyObject, env: &'e Env) -> Result<JsMontyObject<
         MontyObject::Tuple(items) => create_js_tuple(items, env)?,
         // NamedTuple is converted to a tuple (loses named access in JS)
         MontyObject::NamedTuple { values, .. } => create_js_tuple(values, env)?,
-        MontyObject::Dict(pairs) => create_js_map(pairs, env)?,
+        // defaultdicts and Counters are converted to plain Maps (the factory is dropped)
+        MontyObject::Dict(pairs) | MontyObject::DefaultDict { items: pairs, .. } | MontyObject::Counter(pairs) => {
+            create_js_map(pairs, env)?
+        }
+        MontyObject::Deque { items, .. } => create_js_array(items, env)?.into_unknown(env)?,
         MontyObject::Set(items) | MontyObject::FrozenSet(items) => create_js_set(items, env)?,
         MontyObject::Exception { exc_type, arg } => create_js_exception(*exc_type, arg.as_deref(), env)?,
         MontyObject::Type(t) => create_js_type_marker(&t.to_string(), env)?,
@@ -95,6 +102,12 @@ pub fn monty_to_js<'e>(obj: &MontyObject, env: &'e Env) -> Result<JsMontyObject<
             frozen,
         } => create_js_dataclass(name, *type_id, field_names, attrs, *frozen, env)?,
         MontyObject::Path(p) => env.create_string(p)?.into_unknown(env)?,
+        // Dates, datetimes and timedeltas have no lossless JS equivalent, so use their repr
+        MontyObject::Date { .. } | MontyObject::DateTime { .. } | MontyObject::TimeDelta { .. } => {
+            env.create_string(&obj.to_string())?.into_unknown(env)?
+        }
+        // External objects are only resolvable by a host that tracks their handles
+        MontyObject::ExternalObject { .. } => env.create_string(&obj.to_string())?.into_unknown(env)?,
         MontyObject::Repr(s) | MontyObject::Cycle(_, s) => env.create_string(s)?.into_unknown(env)?,
     };
     Ok(JsMontyObject(unknown))
@@ -5,7 +5,7 @@
 
 use std::time::Duration;
 
-use monty::{ResourceLimits, DEFAULT_MAX_RECURSION_DEPTH};
+use monty::{DEFAULT_MAX_RECURSION_DEPTH, ResourceLimits};
 use napi_derive::napi;
 
 /// Resource limits configuration from JavaScript.
@@ -18,6 +18,8 @@ pub struct JsResourceLimits {
     pub max_allocations: Option<u32>,
     /// Maximum execution time in seconds.
     pub max_duration_secs: Option<f64>,
+    /// Maximum number of execution steps (deterministic, unlike the time limit).
+    pub max_steps: Option<u32>,
     /// Maximum heap memory in bytes.
     pub max_memory: Option<u32>,
     /// Run garbage collection every N allocations.
@@ -41,6 +43,9 @@ impl From<JsResourceLimits> for ResourceLimits {
         if let Some(secs) = js_limits.max_duration_secs {
             limits = limits.max_duration(Duration::from_secs_f64(secs));
         }
+        if let Some(max) = js_limits.max_steps {
+            limits = limits.max_steps(u64::from(max));
+        }
         if let Some(max) = js_limits.max_memory {
             limits = limits.max_memory(max as usize);
         }
@@ -49,13 +49,13 @@ use monty::{
     ExcType, ExternalResult, LimitedTracker, MontyException, MontyObject, MontyRepl as CoreMontyRepl, MontyRun,
     NoLimitTracker, PrintWriter, PrintWriterCallback, ResourceTracker, RunProgress, Snapshot,
 };
-use monty_type_checking::{type_check, SourceFile};
+use monty_type_checking::{SourceFile, type_check};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
 use crate::{
-    convert::{js_to_monty, monty_to_js, JsMontyObject},
-    exceptions::{exc_js_to_monty, JsMontyException, MontyTypingError},
+    convert::{JsMontyObject, js_to_monty, monty_to_js},
+    exceptions::{JsMontyException, MontyTypingError, exc_js_to_monty},
     limits::JsResourceLimits,
 };
 
@@ -282,6 +282,9 @@ impl Monty {
                                 "OS calls are not supported: {function:?}",
                             )));
                         }
+                        RunProgress::Paused(_) => {
+                            return Err(Error::from_reason("Pausing on timeout is not supported in run()"));
+                        }
                     }
                 }
             }};
@@ -357,8 +360,8 @@ impl Monty {
             input_names: self.input_names.clone(),
             external_function_names: self.external_function_names.clone(),
         };
-        let bytes =
-            postcard::to_allocvec(&serialized).map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
+        let bytes = monty::to_snapshot_bytes(&serialized)
+            .map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
         Ok(Buffer::from(bytes))
     }
 
@@ -368,8 +371,8 @@ impl Monty {
     /// @returns A new Monty instance
     #[napi(factory)]
     pub fn load(data: Buffer) -> Result<Self> {
-        let serialized: SerializedMonty =
-            postcard::from_bytes(&data).map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
+        let serialized: SerializedMonty = monty::from_snapshot_bytes(&data)
+            .map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
 
         Ok(Self {
             runner: serialized.runner,
@@ -577,16 +580,16 @@ impl MontyRepl {
             repl: &self.repl,
             script_name: &self.script_name,
         };
-        let bytes =
-            postcard::to_allocvec(&serialized).map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
+        let bytes = monty::to_snapshot_bytes(&serialized)
+            .map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
         Ok(Buffer::from(bytes))
     }
 
     /// Restores a REPL session from bytes produced by `dump()`.
     #[napi(factory)]
     pub fn load(data: Buffer) -> Result<Self> {
-        let serialized: SerializedReplOwned =
-            postcard::from_bytes(&data).map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
+        let serialized: SerializedReplOwned = monty::from_snapshot_bytes(&data)
+            .map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
         Ok(Self {
             repl: serialized.repl,
             script_name: serialized.script_name,
@@ -860,8 +863,8 @@ impl MontySnapshot {
             kwargs: &self.kwargs,
         };
 
-        let bytes =
-            postcard::to_allocvec(&serialized).map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
+        let bytes = monty::to_snapshot_bytes(&serialized)
+            .map_err(|e| Error::from_reason(format!("Serialization failed: {e}")))?;
         Ok(Buffer::from(bytes))
     }
 
@@ -872,8 +875,8 @@ impl MontySnapshot {
     /// @returns A new MontySnapshot instance
     #[napi(factory)]
     pub fn load(data: Buffer, options: Option<SnapshotLoadOptions>) -> Result<Self> {
-        let serialized: SerializedSnapshotOwned =
-            postcard::from_bytes(&data).map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
+        let serialized: SerializedSnapshotOwned = monty::from_snapshot_bytes(&data)
+            .map_err(|e| Error::from_reason(format!("Deserialization failed: {e}")))?;
 
         Ok(Self {
             snapshot: serialized.snapshot,
@@ -965,6 +968,20 @@ impl PrintWriterCallback for CallbackStringPrint<'_> {
             .map_err(exc_js_to_monty)?;
         Ok(())
     }
+
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> std::result::Result<(), MontyException> {
+        self.0
+            .call(("stderr", output.as_ref().to_owned()).into())
+            .map_err(exc_js_to_monty)?;
+        Ok(())
+    }
+
+    fn stderr_push(&mut self, end: char) -> std::result::Result<(), MontyException> {
+        self.0
+            .call(("stderr", end.to_string()).into())
+            .map_err(exc_js_to_monty)?;
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -1009,6 +1026,9 @@ where
         RunProgress::OsCall { function, .. } => {
             panic!("OS calls are not yet supported in the JS bindings: {function:?}")
         }
+        RunProgress::Paused(_) => {
+            panic!("Pausing on timeout (Paused) is not yet supported in the JS bindings")
+        }
     }
 }
 
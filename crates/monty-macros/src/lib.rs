@@ -0,0 +1,131 @@
+//! Procedural macros for Monty, re-exported from the `monty` crate behind its `macros` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{FnArg, Ident, ItemFn, Pat, ReturnType, Type, parse_macro_input, spanned::Spanned};
+
+/// Wraps a typed Rust function in Monty's external function calling convention.
+///
+/// The annotated function keeps its name but takes `(args, kwargs)` and returns
+/// `Result<MontyObject, MontyException>`, so it can be passed to `NativeModule::function`.
+/// Arguments can be given positionally or by keyword using the Rust parameter names, and
+/// are converted with `FromMontyArg`; `Option<T>` parameters may be omitted. Wrong arity,
+/// unknown keywords and wrongly typed arguments raise `TypeError` in the sandboxed code.
+///
+/// The return type must implement `IntoMontyObject`, or be a `Result` whose `Ok` type does
+/// and whose error converts into `MontyException`.
+///
+/// ```ignore
+/// #[monty::external_fn]
+/// fn repeat(word: &str, times: i64) -> Vec<String> {
+///     (0..times).map(|_| word.to_owned()).collect()
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn external_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(Span::call_site(), "`external_fn` takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let func = parse_macro_input!(item as ItemFn);
+    expand_external_fn(func)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_external_fn(func: ItemFn) -> syn::Result<TokenStream2> {
+    let ItemFn { attrs, vis, sig, block } = func;
+    if let Some(asyncness) = sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "`external_fn` functions cannot be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "`external_fn` functions cannot be generic",
+        ));
+    }
+
+    let name = &sig.ident;
+    let py_name = name.to_string();
+    let mut idents = Vec::new();
+    let mut types = Vec::new();
+    let mut required = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new(
+                input.span(),
+                "`external_fn` functions cannot take `self`",
+            ));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new(
+                arg.pat.span(),
+                "`external_fn` parameters must be plain identifiers",
+            ));
+        };
+        idents.push(pat.ident.clone());
+        required.push(!is_path_named(&arg.ty, "Option"));
+        types.push(arg.ty.clone());
+    }
+    let param_names: Vec<String> = idents
+        .iter()
+        .map(|ident| ident.to_string().trim_start_matches("r#").to_owned())
+        .collect();
+    let indices = 0..idents.len();
+
+    let inner = Ident::new("__monty_inner", name.span());
+    let inner_sig = syn::Signature {
+        ident: inner.clone(),
+        ..sig.clone()
+    };
+    let call = quote! { #inner(#(#idents),*) };
+    let result = match &sig.output {
+        ReturnType::Default => quote! {
+            #call;
+            ::core::result::Result::Ok(::monty::MontyObject::None)
+        },
+        ReturnType::Type(_, ty) if is_path_named(ty, "Result") => quote! {
+            #call
+                .map(::monty::IntoMontyObject::into_monty_object)
+                .map_err(::core::convert::Into::into)
+        },
+        ReturnType::Type(..) => quote! {
+            ::core::result::Result::Ok(::monty::IntoMontyObject::into_monty_object(#call))
+        },
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis fn #name(
+            args: ::std::vec::Vec<::monty::MontyObject>,
+            kwargs: ::std::vec::Vec<(::monty::MontyObject, ::monty::MontyObject)>,
+        ) -> ::core::result::Result<::monty::MontyObject, ::monty::MontyException> {
+            #inner_sig #block
+
+            let __monty_bound = ::monty::__macro_support::bind_args(
+                #py_name,
+                &[#((#param_names, #required)),*],
+                args,
+                kwargs,
+            )?;
+            #(
+                let #idents: #types =
+                    ::monty::__macro_support::extract_arg(__monty_bound[#indices].as_ref(), #py_name, #param_names)?;
+            )*
+            #result
+        }
+    })
+}
+
+/// Whether `ty` is a path type whose last segment is `name`, e.g. `std::result::Result<..>`.
+fn is_path_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|seg| seg.ident == name),
+        _ => false,
+    }
+}
@@ -17,6 +17,11 @@ from ._monty import (
     MontySnapshot,
     MontySyntaxError,
     MontyTypingError,
+    PrintEvent,
+    PrintEvents,
+    ResourceLimitExceeded,
+    SandboxMemoryError,
+    SandboxTimeout,
     __version__,
 )
 from .os_access import AbstractFile, AbstractOS, CallbackFile, MemoryFile, OSAccess, OsFunction, StatResult
@@ -37,7 +42,12 @@ __all__ = (
     'MontySyntaxError',
     'MontyRuntimeError',
     'MontyTypingError',
+    'ResourceLimitExceeded',
+    'SandboxTimeout',
+    'SandboxMemoryError',
     'Frame',
+    'PrintEvents',
+    'PrintEvent',
     # os_access
     'StatResult',
     'OsFunction',
@@ -56,7 +66,7 @@ async def run_monty_async(
     inputs: dict[str, Any] | None = None,
     external_functions: dict[str, Callable[..., Any]] | None = None,
     limits: ResourceLimits | None = None,
-    print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+    print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
     os: AbstractOS | None = None,
 ) -> Any:
     """Run a Monty script with async external functions and optional OS access.
@@ -179,6 +189,11 @@ class ResourceLimits(TypedDict, total=False):
     max_duration_secs: float
     """Maximum execution time in seconds."""
 
+    max_steps: int
+    """Maximum execution steps (bytecode instructions plus loop iterations inside builtins).
+
+    Unlike `max_duration_secs`, this bounds work deterministically, independent of machine speed."""
+
     max_memory: int
     """Maximum heap memory in bytes."""
 
@@ -188,6 +203,16 @@ class ResourceLimits(TypedDict, total=False):
     max_recursion_depth: int
     """Maximum function call stack depth (default: 1000)."""
 
+    random_seed: int
+    """Seed for the `random` module, making its results reproducible across runs."""
+
+    deterministic: bool
+    """Remove all sources of nondeterminism, so identical snapshots replay to identical results.
+
+    `max_duration_secs` is measured on a virtual clock of one microsecond per execution step, `random` is
+    seeded with `random_seed` (or zero), and `time.time()`, `datetime.now()`, `date.today()` and
+    `random.seed()` without an argument raise `RuntimeError`."""
+
 
 class ExternalReturnValue(TypedDict):
     return_value: Any
@@ -84,8 +84,11 @@ class Monty:
         inputs: dict[str, Any] | None = None,
         limits: ResourceLimits | None = None,
         external_functions: dict[str, Callable[..., Any]] | None = None,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
         os: Callable[[OsFunction, tuple[Any, ...]], Any] | None = None,
+        trace_callback: Callable[[int, str, dict[str, str] | None], None] | None = None,
+        trace_locals: bool = False,
+        coverage: bool = False,
     ) -> Any:
         """
         Execute the code and return the result.
@@ -101,6 +104,72 @@ class Monty:
                 Called with (function_name, args) where function_name is like 'Path.exists'
                 and args is a tuple of arguments. Must return the appropriate value for the
                 OS function (e.g., bool for exists(), stat_result for stat()).
+            trace_callback: Optional callback called before each statement runs, with the
+                statement's line number, its kind (e.g. `'assign'`, `'if'`, `'return'`) and
+                the frame's local variables. If it raises, execution stops with that exception,
+                which the code can't catch.
+            trace_locals: Whether `trace_callback` receives a dict mapping local variable names
+                to their reprs, otherwise it receives `None`. Off by default since formatting
+                every local before each statement is slow.
+            coverage: Whether to count how many times each line of the script started a
+                statement. Lines with no statement that runs, e.g. comments or `pass`, are
+                left out, as are statements in imported modules and `exec()`-ed code.
+
+        Returns:
+            The result of the last expression in the code, or a `(result, line_hits)` tuple
+                when `coverage` is true, where `line_hits` maps line numbers to hit counts
+
+        Raises:
+            MontyRuntimeError: If the code raises an exception during execution
+        """
+
+    def run_capture(
+        self,
+        *,
+        inputs: dict[str, Any] | None = None,
+        limits: ResourceLimits | None = None,
+        external_functions: dict[str, Callable[..., Any]] | None = None,
+        os: Callable[[OsFunction, tuple[Any, ...], dict[str, Any]], Any] | None = None,
+    ) -> tuple[Any, str, str]:
+        """
+        Execute the code, capturing printed output instead of writing it to stdout.
+
+        Arguments:
+            inputs: Dict of input variable values (must match names from __init__)
+            limits: Optional resource limits configuration
+            external_functions: Dict of external function callbacks
+            os: Optional callback for OS calls, e.g. an `OSAccess` instance
+
+        Returns:
+            A `(result, stdout, stderr)` tuple, where `result` is the value of the last
+            expression and `stderr` holds output printed with `file=sys.stderr`
+
+        Raises:
+            MontyRuntimeError: If the code raises an exception during execution
+        """
+
+    async def run_async(
+        self,
+        *,
+        inputs: dict[str, Any] | None = None,
+        limits: ResourceLimits | None = None,
+        external_functions: dict[str, Callable[..., Any]] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
+        os: Callable[[OsFunction, tuple[Any, ...], dict[str, Any]], Any] | None = None,
+    ) -> Any:
+        """
+        Execute the code, awaiting any coroutines returned by external functions.
+
+        External functions may be sync or `async def`. The interpreter runs in a thread pool
+        between steps, so the event loop stays responsive. Equivalent to
+        `run_monty_async(self, ...)`.
+
+        Arguments:
+            inputs: Dict of input variable values (must match names from __init__)
+            limits: Optional resource limits configuration
+            external_functions: Dict of sync or async external function callbacks
+            print_callback: Optional callback for print output
+            os: Optional callback for OS calls, e.g. an `OSAccess` instance
 
         Returns:
             The result of the last expression in the code
@@ -114,7 +183,7 @@ class Monty:
         *,
         inputs: dict[str, Any] | None = None,
         limits: ResourceLimits | None = None,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
     ) -> MontySnapshot | MontyFutureSnapshot | MontyComplete:
         """
         Start the code execution and return a progress object, or completion.
@@ -207,7 +276,7 @@ class MontyRepl:
         external_functions: list[str] | None = None,
         start_inputs: dict[str, Any] | None = None,
         limits: ResourceLimits | None = None,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
         dataclass_registry: list[type] | None = None,
     ) -> tuple['MontyRepl', Any]:
         """
@@ -224,7 +293,7 @@ class MontyRepl:
         self,
         code: str,
         *,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
     ) -> Any:
         """
         Execute one incremental snippet and return its output.
@@ -237,7 +306,7 @@ class MontyRepl:
     def load(
         data: bytes,
         *,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
         dataclass_registry: list[type] | None = None,
     ) -> 'MontyRepl':
         """Restore a REPL session from bytes."""
@@ -340,7 +409,7 @@ class MontySnapshot:
     def load(
         data: bytes,
         *,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
         dataclass_registry: list[type] | None = None,
     ) -> 'MontySnapshot':
         """
@@ -431,7 +500,7 @@ class MontyFutureSnapshot:
     def load(
         data: bytes,
         *,
-        print_callback: Callable[[Literal['stdout'], str], None] | None = None,
+        print_callback: Callable[[Literal['stdout', 'stderr'], str], None] | PrintEvents | None = None,
         dataclass_registry: list[type] | None = None,
     ) -> 'MontyFutureSnapshot':
         """
@@ -475,6 +544,13 @@ class MontyError(Exception):
     def exception(self) -> BaseException:
         """Returns the inner exception as a Python exception object."""
 
+    def location(self) -> Frame | None:
+        """Returns the frame where the error was raised, or None if it has no location.
+
+        For syntax errors this is the position of the invalid code, for runtime errors
+        the innermost traceback frame.
+        """
+
     def __str__(self) -> str:
         """Returns the exception message."""
 
@@ -519,7 +595,6 @@ class MontyTypingError(MontyError):
             color: Whether to include ANSI color codes. Defaults to False.
         """
 
-@final
 class MontyRuntimeError(MontyError):
     """Raised when Monty code fails during execution.
 
@@ -539,6 +614,69 @@ class MontyRuntimeError(MontyError):
                   'msg' - just the message
         """
 
+class ResourceLimitExceeded(MontyRuntimeError):
+    """Raised when Monty code exceeds a resource limit set via `ResourceLimits`.
+
+    Time limits are reported in seconds, all other limits as counts or bytes.
+    Inherits traceback() and display() from MontyRuntimeError.
+    """
+
+    @property
+    def kind(self) -> Literal['time', 'steps', 'memory', 'allocations', 'recursion']:
+        """Which limit was exceeded."""
+
+    @property
+    def limit(self) -> int | float:
+        """The configured limit."""
+
+    @property
+    def used(self) -> int | float:
+        """The amount used when the limit was hit."""
+
+    @property
+    def elapsed(self) -> float | None:
+        """Seconds of execution when the time limit was hit, or None for other limits."""
+
+@final
+class SandboxTimeout(ResourceLimitExceeded):
+    """Raised when Monty code exceeds the time limit or the step limit."""
+
+@final
+class SandboxMemoryError(ResourceLimitExceeded):
+    """Raised when Monty code exceeds the memory limit or the allocation limit."""
+
+@final
+class PrintEvents:
+    """Wraps a handler so print output is delivered as one `PrintEvent` per `print()` call.
+
+    Pass an instance as `print_callback` anywhere a `(stream, text)` callback is accepted.
+    """
+
+    def __new__(cls, handler: Callable[[PrintEvent], None]) -> PrintEvents: ...
+    @property
+    def handler(self) -> Callable[[PrintEvent], None]:
+        """The handler called with each `PrintEvent`."""
+
+@final
+class PrintEvent:
+    """The output of one `print()` call, delivered to a `PrintEvents` handler."""
+
+    @property
+    def stream(self) -> Literal['stdout', 'stderr']:
+        """The stream the call wrote to."""
+
+    @property
+    def text(self) -> str:
+        """Everything the call wrote, including separators and the `end` string."""
+
+    @property
+    def timestamp(self) -> float:
+        """Wall-clock time when the call finished, as seconds since the epoch like `time.time()`."""
+
+    @property
+    def index(self) -> int:
+        """Zero-based position of the call among the `print()` calls since execution was started or resumed."""
+
 @final
 class Frame:
     """A single frame in a Monty traceback."""
@@ -1,5 +1,7 @@
 from __future__ import annotations
 
+import datetime
+import time
 from abc import ABC, abstractmethod
 from pathlib import PurePosixPath
 from typing import TYPE_CHECKING, Any, Callable, Literal, NamedTuple, Protocol, Sequence, TypeAlias, TypeGuard
@@ -29,6 +31,10 @@ OsFunction = Literal[
     'Path.absolute',
     'os.getenv',
     'os.environ',
+    'time.time',
+    'datetime.now',
+    'date.today',
+    'input',
 ]
 
 
@@ -172,6 +178,14 @@ class AbstractOS(ABC):
                 return self.getenv(*args)
             case 'os.environ':
                 return self.get_environ()
+            case 'time.time':
+                return self.time()
+            case 'datetime.now':
+                return self.datetime_now()
+            case 'date.today':
+                return self.date_today()
+            case 'input':
+                return self.input()
 
     @abstractmethod
     def path_exists(self, path: PurePosixPath) -> bool:
@@ -422,6 +436,47 @@ class AbstractOS(ABC):
         """
         raise NotImplementedError
 
+    def time(self) -> float:
+        """Get the current time, used by `time.time()`.
+
+        Defaults to the host clock; override to give Monty code a fixed or simulated clock.
+
+        Returns:
+            The current time in seconds since the Unix epoch.
+        """
+        return time.time()
+
+    def datetime_now(self) -> datetime.datetime:
+        """Get the current local date and time, used by `datetime.datetime.now()`.
+
+        Defaults to converting `self.time()` to a naive local datetime.
+
+        Returns:
+            A naive `datetime.datetime`.
+        """
+        return datetime.datetime.fromtimestamp(self.time())
+
+    def date_today(self) -> datetime.date:
+        """Get the current local date, used by `datetime.date.today()`.
+
+        Defaults to the date part of `self.datetime_now()`.
+
+        Returns:
+            A `datetime.date`.
+        """
+        return self.datetime_now().date()
+
+    def input(self) -> str:
+        """Read a line of input, used by the `input()` builtin after its prompt is printed.
+
+        There's no stdin in the sandbox, so this raises by default; override it to feed lines
+        to interactive scripts.
+
+        Returns:
+            The line read, without the trailing newline.
+        """
+        raise NotImplementedError('input() is not supported')
+
 
 class AbstractFile(Protocol):
     """Protocol defining the interface for files used with OSAccess.
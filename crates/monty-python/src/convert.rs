@@ -4,14 +4,17 @@
 //! - `py_to_monty`: Convert Python objects to Monty's `MontyObject` for input
 //! - `monty_to_py`: Convert Monty's `MontyObject` back to Python objects for output
 
-use ::monty::MontyObject;
+use ::monty::{DictPairs, MontyObject};
 use monty::MontyException;
 use num_bigint::BigInt;
 use pyo3::{
     exceptions::{PyBaseException, PyTypeError},
     prelude::*,
     sync::PyOnceLock,
-    types::{PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple},
+    types::{
+        PyBool, PyBytes, PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyDict, PyFloat, PyFrozenSet, PyInt,
+        PyList, PySet, PyString, PyTimeAccess, PyTuple, PyTzInfoAccess,
+    },
 };
 
 use crate::{
@@ -29,7 +32,8 @@ use crate::{
 /// This applies recursively to nested dataclasses in fields, lists, dicts, etc.
 ///
 /// # Important
-/// Checks `bool` before `int` since `bool` is a subclass of `int` in Python.
+/// Checks `bool` before `int` since `bool` is a subclass of `int` in Python, and
+/// `datetime` before `date` for the same reason.
 pub fn py_to_monty(obj: &Bound<'_, PyAny>, dc_registry: &DcRegistry) -> PyResult<MontyObject> {
     if obj.is_none() {
         Ok(MontyObject::None)
@@ -85,6 +89,23 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>, dc_registry: &DcRegistry) -> PyResult
         // Regular tuple
         let items: PyResult<Vec<MontyObject>> = tuple.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::Tuple(items?))
+    } else if obj.is_instance(get_defaultdict(obj.py())?)? {
+        // Check collections' dict subclasses BEFORE dict
+        let factory = obj.getattr("default_factory")?;
+        let default_factory = if factory.is_none() {
+            MontyObject::None
+        } else {
+            builtin_type_to_monty(&factory)?
+        };
+        Ok(MontyObject::DefaultDict {
+            default_factory: Box::new(default_factory),
+            items: dict_pairs_to_monty(obj.cast::<PyDict>()?, dc_registry)?,
+        })
+    } else if obj.is_instance(get_counter(obj.py())?)? {
+        Ok(MontyObject::Counter(dict_pairs_to_monty(
+            obj.cast::<PyDict>()?,
+            dc_registry,
+        )?))
     } else if let Ok(dict) = obj.cast::<PyDict>() {
         // in theory we could provide a way of passing the iterator direct to the internal MontyObject construct
         // it's probably not worth it right now
@@ -99,8 +120,42 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>, dc_registry: &DcRegistry) -> PyResult
     } else if let Ok(frozenset) = obj.cast::<PyFrozenSet>() {
         let items: PyResult<Vec<MontyObject>> = frozenset.iter().map(|item| py_to_monty(&item, dc_registry)).collect();
         Ok(MontyObject::FrozenSet(items?))
+    } else if obj.is_instance(get_deque(obj.py())?)? {
+        let items: PyResult<Vec<MontyObject>> = obj.try_iter()?.map(|item| py_to_monty(&item?, dc_registry)).collect();
+        Ok(MontyObject::Deque {
+            items: items?,
+            maxlen: obj.getattr("maxlen")?.extract()?,
+        })
     } else if obj.is(obj.py().Ellipsis()) {
         Ok(MontyObject::Ellipsis)
+    } else if let Ok(dt) = obj.cast::<PyDateTime>() {
+        // Check datetime BEFORE date since datetime is a subclass of date in Python
+        if dt.get_tzinfo().is_some() {
+            return Err(PyTypeError::new_err(
+                "Cannot convert timezone-aware datetime to Monty value",
+            ));
+        }
+        Ok(MontyObject::DateTime {
+            year: dt.get_year(),
+            month: dt.get_month(),
+            day: dt.get_day(),
+            hour: dt.get_hour(),
+            minute: dt.get_minute(),
+            second: dt.get_second(),
+            microsecond: dt.get_microsecond(),
+        })
+    } else if let Ok(date) = obj.cast::<PyDate>() {
+        Ok(MontyObject::Date {
+            year: date.get_year(),
+            month: date.get_month(),
+            day: date.get_day(),
+        })
+    } else if let Ok(delta) = obj.cast::<PyDelta>() {
+        Ok(MontyObject::TimeDelta {
+            days: delta.get_days(),
+            seconds: delta.get_seconds(),
+            microseconds: delta.get_microseconds(),
+        })
     } else if let Ok(exc) = obj.cast::<PyBaseException>() {
         Ok(exc_to_monty_object(exc))
     } else if is_dataclass(obj) {
@@ -118,6 +173,29 @@ pub fn py_to_monty(obj: &Bound<'_, PyAny>, dc_registry: &DcRegistry) -> PyResult
     }
 }
 
+/// Converts the entries of a dict (or dict subclass) to `DictPairs` for `MontyObject`.
+fn dict_pairs_to_monty(dict: &Bound<'_, PyDict>, dc_registry: &DcRegistry) -> PyResult<DictPairs> {
+    dict.iter()
+        .map(|(k, v)| Ok((py_to_monty(&k, dc_registry)?, py_to_monty(&v, dc_registry)?)))
+        .collect()
+}
+
+/// Converts a defaultdict's `default_factory`, which must be a builtin type like `list` or `int`.
+fn builtin_type_to_monty(factory: &Bound<'_, PyAny>) -> PyResult<MontyObject> {
+    let builtin = import_builtins(factory.py())?.bind(factory.py());
+    if let Ok(name) = factory.getattr("__name__")
+        && let Ok(name) = name.extract::<String>()
+        && builtin.getattr(name.as_str()).is_ok_and(|b| b.is(factory))
+        && let Some(t) = MontyObject::builtin_type(&name)
+    {
+        Ok(t)
+    } else {
+        Err(PyTypeError::new_err(
+            "Cannot convert defaultdict to Monty value: default_factory must be None or a builtin type",
+        ))
+    }
+}
+
 /// Converts Monty's `MontyObject` to a native Python object, using the dataclass registry.
 ///
 /// When a dataclass is converted and its class name is found in the registry,
@@ -184,6 +262,26 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &DcRegistry)
             }
             Ok(dict.into_any().unbind())
         }
+        MontyObject::DefaultDict { default_factory, items } => {
+            let factory = monty_to_py(py, default_factory, dc_registry)?;
+            let dict = get_defaultdict(py)?.call1((factory,))?;
+            for (k, v) in items {
+                dict.set_item(monty_to_py(py, k, dc_registry)?, monty_to_py(py, v, dc_registry)?)?;
+            }
+            Ok(dict.unbind())
+        }
+        MontyObject::Counter(items) => {
+            let counter = get_counter(py)?.call0()?;
+            for (k, v) in items {
+                counter.set_item(monty_to_py(py, k, dc_registry)?, monty_to_py(py, v, dc_registry)?)?;
+            }
+            Ok(counter.unbind())
+        }
+        MontyObject::Deque { items, maxlen } => {
+            let py_items: PyResult<Vec<Py<PyAny>>> =
+                items.iter().map(|item| monty_to_py(py, item, dc_registry)).collect();
+            Ok(get_deque(py)?.call1((PyList::new(py, py_items?)?, *maxlen))?.unbind())
+        }
         MontyObject::Set(items) => {
             let set = PySet::empty(py)?;
             for item in items {
@@ -202,7 +300,15 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &DcRegistry)
             Ok(exc.into_value(py).into_any())
         }
         // Return Python's built-in type object
-        MontyObject::Type(t) => import_builtins(py)?.getattr(py, t.to_string()),
+        MontyObject::Type(t) => {
+            // Types from modules display as e.g. `collections.deque`
+            let name = t.to_string();
+            if let Some((module, attr)) = name.rsplit_once('.') {
+                Ok(py.import(module)?.getattr(attr)?.unbind())
+            } else {
+                import_builtins(py)?.getattr(py, name)
+            }
+        }
         MontyObject::BuiltinFunction(f) => import_builtins(py)?.getattr(py, f.to_string()),
         // Dataclass - use registry to reconstruct original type if available
         MontyObject::Dataclass {
@@ -218,7 +324,29 @@ pub fn monty_to_py(py: Python<'_>, obj: &MontyObject, dc_registry: &DcRegistry)
             let path_obj = pure_posix_path.call1((p,))?;
             Ok(path_obj.into_any().unbind())
         }
+        MontyObject::Date { year, month, day } => Ok(PyDate::new(py, *year, *month, *day)?.into_any().unbind()),
+        MontyObject::DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            microsecond,
+        } => Ok(
+            PyDateTime::new(py, *year, *month, *day, *hour, *minute, *second, *microsecond, None)?
+                .into_any()
+                .unbind(),
+        ),
+        MontyObject::TimeDelta {
+            days,
+            seconds,
+            microseconds,
+        } => Ok(PyDelta::new(py, *days, *seconds, *microseconds, true)?
+            .into_any()
+            .unbind()),
         // Output-only types - convert to string representation
+        MontyObject::ExternalObject { .. } => Ok(PyString::new(py, &obj.to_string()).into_any().unbind()),
         MontyObject::Repr(s) => Ok(PyString::new(py, s).into_any().unbind()),
         MontyObject::Cycle(_, placeholder) => Ok(PyString::new(py, placeholder).into_any().unbind()),
     }
@@ -237,6 +365,27 @@ fn get_namedtuple(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
     NAMEDTUPLE.import(py, "collections", "namedtuple")
 }
 
+/// Cached import of `collections.defaultdict` class.
+fn get_defaultdict(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static DEFAULTDICT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    DEFAULTDICT.import(py, "collections", "defaultdict")
+}
+
+/// Cached import of `collections.Counter` class.
+fn get_counter(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static COUNTER: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    COUNTER.import(py, "collections", "Counter")
+}
+
+/// Cached import of `collections.deque` class.
+fn get_deque(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static DEQUE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    DEQUE.import(py, "collections", "deque")
+}
+
 /// Cached import of `pathlib.PurePosixPath` class.
 fn get_pure_posix_path(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
     static PUREPOSIX: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
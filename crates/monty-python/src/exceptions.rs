@@ -7,18 +7,22 @@
 //! ## Exception Hierarchy
 //!
 //! ```text
-//! MontyError(Exception)        # Base class for all Monty exceptions
-//! ├── MontySyntaxError         # Raised when syntax is invalid or Monty can't parse the code
-//! ├── MontyRuntimeError        # Raised when code fails during execution
-//! └── MontyTypingError         # Raised when type checking finds errors in the code
+//! MontyError(Exception)           # Base class for all Monty exceptions
+//! ├── MontySyntaxError            # Raised when syntax is invalid or Monty can't parse the code
+//! ├── MontyRuntimeError           # Raised when code fails during execution
+//! │   └── ResourceLimitExceeded   # Raised when execution exceeds a configured resource limit
+//! │       ├── SandboxTimeout      # The time or step limit was exceeded
+//! │       └── SandboxMemoryError  # The memory or allocation limit was exceeded
+//! └── MontyTypingError            # Raised when type checking finds errors in the code
 //! ```
 
-use ::monty::{ExcType, MontyException, StackFrame};
+use ::monty::{ExcType, LimitExceeded, MontyException, StackFrame};
 use monty_type_checking::TypeCheckingDiagnostics;
 use pyo3::{
     PyClassInitializer, PyTypeCheck,
     exceptions::{self},
     prelude::*,
+    sync::PyOnceLock,
     types::{PyDict, PyList, PyString},
 };
 
@@ -39,6 +43,7 @@ impl MontyError {
     /// Converts a Monty exception to a `PyErr`.
     ///
     /// For `SyntaxError` exceptions, creates a `MontySyntaxError`.
+    /// For exceptions caused by a resource limit, creates a `ResourceLimitExceeded` subclass.
     /// For all other exceptions, creates a `MontyRuntimeError` with all the exception
     /// information preserved, including the traceback frames and display string.
     #[must_use]
@@ -46,6 +51,8 @@ impl MontyError {
         // Syntax errors get their own exception type
         if exc.exc_type() == ExcType::SyntaxError {
             MontySyntaxError::new_err(py, exc)
+        } else if let Some(limit) = exc.limit_exceeded() {
+            ResourceLimitExceeded::new_err(py, exc, limit)
         } else {
             MontyRuntimeError::new_err(py, exc)
         }
@@ -81,6 +88,17 @@ impl MontyError {
         py_err.into_value(py).into_any()
     }
 
+    /// Returns the frame where the error was raised, or `None` if it has no location.
+    ///
+    /// For syntax errors this is the position of the invalid code, for runtime errors
+    /// the innermost traceback frame.
+    fn location(&self, py: Python<'_>) -> PyResult<Option<Py<PyFrame>>> {
+        self.exc
+            .location()
+            .map(|frame| Py::new(py, PyFrame::from_stack_frame(frame)))
+            .transpose()
+    }
+
     fn __str__(&self) -> String {
         self.message().unwrap_or_default().to_string()
     }
@@ -205,7 +223,7 @@ impl MontyTypingError {
 ///
 /// Inherits from `MontyError`. Additionally provides `traceback()` to access
 /// the Monty stack frames where the error occurred.
-#[pyclass(extends=MontyError, module="pydantic_monty")]
+#[pyclass(extends=MontyError, module="pydantic_monty", subclass)]
 pub struct MontyRuntimeError {
     /// The traceback frames where the error occurred (pre-converted to Python objects).
     frames: Vec<Py<PyFrame>>,
@@ -215,28 +233,28 @@ impl MontyRuntimeError {
     /// Creates a new `MontyRuntimeError` from the given exception data.
     #[must_use]
     pub fn new_err(py: Python<'_>, exc: MontyException) -> PyErr {
-        // Convert stack frames to PyFrame objects
-        let frames_result: PyResult<Vec<Py<PyFrame>>> = exc
-            .traceback()
-            .iter()
-            .map(|f| Py::new(py, PyFrame::from_stack_frame(f)))
-            .collect();
-
-        let frames = match frames_result {
-            Ok(frames) => frames,
+        let init = match Self::initializer(py, exc) {
+            Ok(init) => init,
             Err(e) => return e,
         };
-
-        let base_error = MontyError::new(exc);
-        // Create the MontyRuntimeError with proper initialization
-        let runtime_error = Self { frames };
-
-        let init = pyo3::PyClassInitializer::from(base_error).add_subclass(runtime_error);
         match Py::new(py, init) {
             Ok(err) => PyErr::from_value(err.into_bound(py).into_any()),
             Err(e) => e,
         }
     }
+
+    /// Builds the initializer shared by `MontyRuntimeError` and its subclasses.
+    fn initializer(py: Python<'_>, exc: MontyException) -> PyResult<PyClassInitializer<Self>> {
+        // Convert stack frames to PyFrame objects
+        let frames = exc
+            .traceback()
+            .iter()
+            .map(|f| Py::new(py, PyFrame::from_stack_frame(f)))
+            .collect::<PyResult<Vec<Py<PyFrame>>>>()?;
+
+        let base_error = MontyError::new(exc);
+        Ok(PyClassInitializer::from(base_error).add_subclass(Self { frames }))
+    }
 }
 
 #[pymethods]
@@ -289,6 +307,111 @@ impl MontyRuntimeError {
     }
 }
 
+/// Raised when Monty code exceeds a resource limit set via `ResourceLimits`.
+///
+/// Inherits from `MontyRuntimeError`. `kind` names the limit that was exceeded,
+/// `limit` and `used` give the configured limit and the amount used when execution stopped.
+/// Time limits are reported in seconds, all other limits as counts or bytes.
+#[pyclass(extends=MontyRuntimeError, module="pydantic_monty", subclass)]
+pub struct ResourceLimitExceeded {
+    limit: LimitExceeded,
+}
+
+impl ResourceLimitExceeded {
+    /// Creates the exception for `limit`, using the most specific subclass available.
+    #[must_use]
+    pub fn new_err(py: Python<'_>, exc: MontyException, limit: LimitExceeded) -> PyErr {
+        let init = match MontyRuntimeError::initializer(py, exc) {
+            Ok(init) => init.add_subclass(Self { limit }),
+            Err(e) => return e,
+        };
+        let err = match limit {
+            LimitExceeded::Time { .. } | LimitExceeded::Steps { .. } => {
+                Py::new(py, init.add_subclass(SandboxTimeout)).map(|e| e.into_bound(py).into_any())
+            }
+            LimitExceeded::Allocation { .. } | LimitExceeded::Memory { .. } => {
+                Py::new(py, init.add_subclass(SandboxMemoryError)).map(|e| e.into_bound(py).into_any())
+            }
+            LimitExceeded::Recursion { .. } => Py::new(py, init).map(|e| e.into_bound(py).into_any()),
+        };
+        match err {
+            Ok(err) => PyErr::from_value(err),
+            Err(e) => e,
+        }
+    }
+}
+
+#[pymethods]
+impl ResourceLimitExceeded {
+    /// Which limit was exceeded: 'time', 'steps', 'memory', 'allocations' or 'recursion'.
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.limit {
+            LimitExceeded::Time { .. } => "time",
+            LimitExceeded::Steps { .. } => "steps",
+            LimitExceeded::Memory { .. } => "memory",
+            LimitExceeded::Allocation { .. } => "allocations",
+            LimitExceeded::Recursion { .. } => "recursion",
+        }
+    }
+
+    /// The configured limit, in seconds for time limits.
+    #[getter]
+    fn limit<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self.limit {
+            LimitExceeded::Time { limit, .. } => limit.as_secs_f64().into_pyobject(py)?.into_any(),
+            LimitExceeded::Steps { limit, .. } => limit.into_pyobject(py)?.into_any(),
+            LimitExceeded::Memory { limit, .. }
+            | LimitExceeded::Allocation { limit, .. }
+            | LimitExceeded::Recursion { limit, .. } => limit.into_pyobject(py)?.into_any(),
+        })
+    }
+
+    /// The amount used when the limit was hit, in seconds for time limits.
+    #[getter]
+    fn used<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self.limit {
+            LimitExceeded::Time { elapsed, .. } => elapsed.as_secs_f64().into_pyobject(py)?.into_any(),
+            LimitExceeded::Steps { count, .. } => count.into_pyobject(py)?.into_any(),
+            LimitExceeded::Memory { used: count, .. }
+            | LimitExceeded::Allocation { count, .. }
+            | LimitExceeded::Recursion { depth: count, .. } => count.into_pyobject(py)?.into_any(),
+        })
+    }
+
+    /// Seconds of execution when the time limit was hit, or `None` for other limits.
+    #[getter]
+    fn elapsed(&self) -> Option<f64> {
+        match self.limit {
+            LimitExceeded::Time { elapsed, .. } => Some(elapsed.as_secs_f64()),
+            _ => None,
+        }
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let type_name = slf.get_type().name()?;
+        let this = slf.borrow();
+        let parent = this.as_super().as_super();
+        let exc_type_name = parent.exc_type();
+        match parent.message() {
+            Some(msg) if !msg.is_empty() => Ok(format!("{type_name}({exc_type_name}: {msg})")),
+            _ => Ok(format!("{type_name}({exc_type_name})")),
+        }
+    }
+}
+
+/// Raised when Monty code exceeds the time limit or the step limit.
+///
+/// Inherits from `ResourceLimitExceeded`.
+#[pyclass(extends=ResourceLimitExceeded, module="pydantic_monty")]
+pub struct SandboxTimeout;
+
+/// Raised when Monty code exceeds the memory limit or the allocation limit.
+///
+/// Inherits from `ResourceLimitExceeded`.
+#[pyclass(extends=ResourceLimitExceeded, module="pydantic_monty")]
+pub struct SandboxMemoryError;
+
 /// A single frame in a Monty traceback.
 ///
 /// Contains all the information needed to display a traceback line:
@@ -401,6 +524,19 @@ pub fn exc_monty_to_py(py: Python<'_>, exc: MontyException) -> PyErr {
         ExcType::TypeError => exceptions::PyTypeError::new_err(msg),
         ExcType::ValueError => exceptions::PyValueError::new_err(msg),
         ExcType::UnicodeDecodeError => exceptions::PyUnicodeDecodeError::new_err(msg),
+        // `UnicodeEncodeError` needs the source string and positions to construct, so fall back to its base class
+        ExcType::UnicodeEncodeError => exceptions::PyValueError::new_err(msg),
+        // `json.JSONDecodeError` needs the source document to construct, so fall back to its base class
+        ExcType::JSONDecodeError => exceptions::PyValueError::new_err(msg),
+        ExcType::PatternError => {
+            if let Ok(exc_cls) = get_re_error(py)
+                && let Ok(exc_instance) = exc_cls.call1((PyString::new(py, &msg),))
+            {
+                return PyErr::from_value(exc_instance);
+            }
+            // if creating the right exception fails, fallback to Exception which it's a subclass of
+            exceptions::PyException::new_err(msg)
+        }
         ExcType::ImportError => exceptions::PyImportError::new_err(msg),
         ExcType::ModuleNotFoundError => exceptions::PyModuleNotFoundError::new_err(msg),
         ExcType::OSError => exceptions::PyOSError::new_err(msg),
@@ -440,10 +576,12 @@ fn py_err_to_exc_type(exc: &Bound<'_, exceptions::PyBaseException>) -> ExcType {
         // put the most commonly used exceptions first
         if exceptions::PyTypeError::type_check(exc) {
             ExcType::TypeError
-        // ValueError hierarchy (check UnicodeDecodeError first as it's a subclass)
+        // ValueError hierarchy (check UnicodeDecodeError and UnicodeEncodeError first as they're subclasses)
         } else if exceptions::PyValueError::type_check(exc) {
             if exceptions::PyUnicodeDecodeError::type_check(exc) {
                 ExcType::UnicodeDecodeError
+            } else if exceptions::PyUnicodeEncodeError::type_check(exc) {
+                ExcType::UnicodeEncodeError
             } else {
                 ExcType::ValueError
             }
@@ -535,3 +673,10 @@ fn is_frozen_instance_error(exc: &Bound<'_, exceptions::PyBaseException>) -> boo
         false
     }
 }
+
+/// Cached import of the `re.error` exception class, which Monty's `re.PatternError` maps to.
+fn get_re_error(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static RE_ERROR: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+    RE_ERROR.import(py, "re", "error")
+}
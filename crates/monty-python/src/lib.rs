@@ -14,8 +14,13 @@ mod monty_cls;
 use std::sync::OnceLock;
 
 // Use `::monty` to refer to the external crate (not the pymodule)
-pub use exceptions::{MontyError, MontyRuntimeError, MontySyntaxError, MontyTypingError, PyFrame};
-pub use monty_cls::{PyMonty, PyMontyComplete, PyMontyFutureSnapshot, PyMontyRepl, PyMontySnapshot};
+pub use exceptions::{
+    MontyError, MontyRuntimeError, MontySyntaxError, MontyTypingError, PyFrame, ResourceLimitExceeded,
+    SandboxMemoryError, SandboxTimeout,
+};
+pub use monty_cls::{
+    PyMonty, PyMontyComplete, PyMontyFutureSnapshot, PyMontyRepl, PyMontySnapshot, PyPrintEvent, PyPrintEvents,
+};
 use pyo3::prelude::*;
 
 /// Copied from `get_pydantic_core_version` in pydantic
@@ -58,6 +63,16 @@ mod _monty {
     use super::PyMontyRepl as MontyRepl;
     #[pymodule_export]
     use super::PyMontySnapshot as MontySnapshot;
+    #[pymodule_export]
+    use super::PyPrintEvent as PrintEvent;
+    #[pymodule_export]
+    use super::PyPrintEvents as PrintEvents;
+    #[pymodule_export]
+    use super::ResourceLimitExceeded;
+    #[pymodule_export]
+    use super::SandboxMemoryError;
+    #[pymodule_export]
+    use super::SandboxTimeout;
     use super::get_version;
 
     #[pymodule_init]
@@ -8,7 +8,7 @@ use std::{
     time::Duration,
 };
 
-use monty::{DEFAULT_MAX_RECURSION_DEPTH, ResourceError, ResourceTracker};
+use monty::{DEFAULT_MAX_RECURSION_DEPTH, GcStats, HeapSlotStats, ResourceError, ResourceTracker};
 use pyo3::{prelude::*, types::PyDict};
 
 use crate::exceptions::exc_py_to_monty;
@@ -18,9 +18,12 @@ use crate::exceptions::exc_py_to_monty;
 /// The dict should have the following optional keys:
 /// - `max_allocations`: Maximum number of heap allocations allowed (int)
 /// - `max_duration_secs`: Maximum execution time in seconds (float)
+/// - `max_steps`: Maximum number of execution steps (int)
 /// - `max_memory`: Maximum heap memory in bytes (int)
 /// - `gc_interval`: Run garbage collection every N allocations (int)
 /// - `max_recursion_depth`: Maximum function call stack depth (int, default: 1000)
+/// - `random_seed`: Seed for the `random` module, for reproducible runs (int)
+/// - `deterministic`: Remove all sources of nondeterminism (bool)
 ///
 /// If a key is missing or set to `None`, that limit is not applied
 /// (except `max_recursion_depth` which defaults to 1000).
@@ -29,8 +32,11 @@ use crate::exceptions::exc_py_to_monty;
 pub fn extract_limits(dict: &Bound<'_, PyDict>) -> PyResult<monty::ResourceLimits> {
     let max_allocations = extract_optional_usize(dict, "max_allocations")?;
     let max_duration_secs = extract_optional_f64(dict, "max_duration_secs")?;
+    let max_steps = extract_optional_u64(dict, "max_steps")?;
     let max_memory = extract_optional_usize(dict, "max_memory")?;
     let gc_interval = extract_optional_usize(dict, "gc_interval")?;
+    let random_seed = extract_optional_u64(dict, "random_seed")?;
+    let deterministic = extract_optional_bool(dict, "deterministic")?;
     let max_recursion_depth =
         extract_optional_usize(dict, "max_recursion_depth")?.or(Some(DEFAULT_MAX_RECURSION_DEPTH));
 
@@ -42,12 +48,21 @@ pub fn extract_limits(dict: &Bound<'_, PyDict>) -> PyResult<monty::ResourceLimit
     if let Some(secs) = max_duration_secs {
         limits = limits.max_duration(Duration::from_secs_f64(secs));
     }
+    if let Some(max) = max_steps {
+        limits = limits.max_steps(max);
+    }
     if let Some(max) = max_memory {
         limits = limits.max_memory(max);
     }
     if let Some(interval) = gc_interval {
         limits = limits.gc_interval(interval);
     }
+    if let Some(seed) = random_seed {
+        limits = limits.random_seed(seed);
+    }
+    if let Some(deterministic) = deterministic {
+        limits = limits.deterministic(deterministic);
+    }
 
     Ok(limits)
 }
@@ -61,6 +76,15 @@ fn extract_optional_usize(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Optio
     }
 }
 
+/// Extracts an optional u64 from a dict, raising `TypeError` if the value has the wrong type.
+fn extract_optional_u64(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<u64>> {
+    match dict.get_item(key)? {
+        None => Ok(None),
+        Some(value) if value.is_none() => Ok(None),
+        Some(value) => Ok(Some(value.extract()?)),
+    }
+}
+
 /// Extracts an optional f64 from a dict, raising `TypeError` if the value has the wrong type.
 fn extract_optional_f64(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<f64>> {
     match dict.get_item(key)? {
@@ -70,6 +94,15 @@ fn extract_optional_f64(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<
     }
 }
 
+/// Extracts an optional bool from a dict, raising `TypeError` if the value has the wrong type.
+fn extract_optional_bool(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<bool>> {
+    match dict.get_item(key)? {
+        None => Ok(None),
+        Some(value) if value.is_none() => Ok(None),
+        Some(value) => Ok(Some(value.extract()?)),
+    }
+}
+
 /// How often to check Python signals (every N calls to `check_time`).
 ///
 /// This balances responsiveness to Ctrl+C against performance overhead.
@@ -124,6 +157,14 @@ impl<T: ResourceTracker> ResourceTracker for PySignalTracker<T> {
         self.inner.on_free(get_size);
     }
 
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        self.inner.on_heap_slots(stats);
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        self.inner.on_gc(stats);
+    }
+
     fn check_time(&self) -> Result<(), ResourceError> {
         // First check inner tracker's time limit
         self.inner.check_time()?;
@@ -139,4 +180,40 @@ impl<T: ResourceTracker> ResourceTracker for PySignalTracker<T> {
     fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError> {
         self.inner.check_large_result(estimated_bytes)
     }
+
+    fn random_seed(&self) -> Option<u64> {
+        self.inner.random_seed()
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        self.inner.pause_on_timeout()
+    }
+
+    fn restart_timer(&mut self) {
+        self.inner.restart_timer();
+    }
+
+    fn deterministic(&self) -> bool {
+        self.inner.deterministic()
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        self.inner.max_snapshot_size()
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        self.inner.checkpoints_enabled()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.inner.on_statement()
+    }
+
+    fn profiling_enabled(&self) -> bool {
+        self.inner.profiling_enabled()
+    }
+
+    fn on_profile_statement(&mut self, stack: &[&str], line: u16) {
+        self.inner.on_profile_statement(stack, line);
+    }
 }
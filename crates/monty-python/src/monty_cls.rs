@@ -1,9 +1,10 @@
-use std::{borrow::Cow, fmt::Write};
+use std::{borrow::Cow, fmt::Write, time::SystemTime};
 
 // Use `::monty` to refer to the external crate (not the pymodule)
 use ::monty::{
-    ExternalResult, LimitedTracker, MontyException, MontyObject, MontyRepl as CoreMontyRepl, MontyRun, NoLimitTracker,
-    PrintWriter, PrintWriterCallback, ResourceTracker, RunProgress, Snapshot,
+    CapturedOutput, Coverage, ExternalResult, LimitedTracker, MontyException, MontyObject, MontyRepl as CoreMontyRepl,
+    MontyRun, NoLimitTracker, PrintWriter, PrintWriterCallback, ResourceTracker, RunProgress, Snapshot, TraceEvent,
+    TraceHook,
 };
 use monty::{ExcType, FutureSnapshot, OsFunction};
 use monty_type_checking::{SourceFile, type_check};
@@ -12,7 +13,7 @@ use pyo3::{
     exceptions::{PyKeyError, PyRuntimeError, PyTypeError, PyValueError},
     intern,
     prelude::*,
-    types::{PyBytes, PyDict, PyList, PyTuple, PyType},
+    types::{PyBytes, PyDict, PyList, PyString, PyTuple, PyType},
 };
 use send_wrapper::SendWrapper;
 
@@ -124,12 +125,20 @@ impl PyMonty {
 
     /// Executes the code and returns the result.
     ///
+    /// # Args
+    /// * `trace_callback` - Called with `(line, kind, locals)` before each statement
+    /// * `trace_locals` - Whether `trace_callback` receives a dict of local variable reprs,
+    ///   otherwise `locals` is `None`
+    /// * `coverage` - Whether to count the statements run on each line of the script
+    ///
     /// # Returns
-    /// The result of the last expression in the code
+    /// The result of the last expression in the code, or a `(result, line_hits)` tuple when
+    /// `coverage` is true
     ///
     /// # Raises
     /// Various Python exceptions matching what the code would raise
-    #[pyo3(signature = (*, inputs=None, limits=None, external_functions=None, print_callback=None, os=None))]
+    #[pyo3(signature = (*, inputs=None, limits=None, external_functions=None, print_callback=None, os=None, trace_callback=None, trace_locals=false, coverage=false))]
+    #[expect(clippy::too_many_arguments)]
     fn run(
         &self,
         py: Python<'_>,
@@ -138,17 +147,26 @@ impl PyMonty {
         external_functions: Option<&Bound<'_, PyDict>>,
         print_callback: Option<&Bound<'_, PyAny>>,
         os: Option<&Bound<'_, PyAny>>,
+        trace_callback: Option<&Bound<'_, PyAny>>,
+        trace_locals: bool,
+        coverage: bool,
     ) -> PyResult<Py<PyAny>> {
         // Clone the Arc handle — all clones share the same underlying registry,
         // so auto-registrations during execution are visible to all users.
         let input_values = self.extract_input_values(inputs, &self.dc_registry)?;
 
-        if let Some(os_callback) = os
-            && !os_callback.is_callable()
-        {
-            let msg = format!("TypeError: '{}' object is not callable", os_callback.get_type().name()?);
-            return Err(PyTypeError::new_err(msg));
+        for callback in [os, trace_callback].into_iter().flatten() {
+            if !callback.is_callable() {
+                let msg = format!("TypeError: '{}' object is not callable", callback.get_type().name()?);
+                return Err(PyTypeError::new_err(msg));
+            }
         }
+        let coverage = coverage.then(Coverage::new);
+        let trace_hook = (trace_callback.is_some() || coverage.is_some()).then(|| PyTraceHook {
+            callback: trace_callback.map(|callback| callback.clone().unbind()),
+            locals: trace_locals,
+            coverage: coverage.clone(),
+        });
 
         // Build print writer
         let mut print_cb;
@@ -161,15 +179,106 @@ impl PyMonty {
         };
 
         // Run with appropriate tracker type (must branch due to different generic types)
-        if let Some(limits) = limits {
+        let result = if let Some(limits) = limits {
             let tracker = PySignalTracker::new(LimitedTracker::new(extract_limits(limits)?));
-            self.run_impl(py, input_values, tracker, external_functions, os, print_writer)
+            self.run_impl(
+                py,
+                input_values,
+                tracker,
+                external_functions,
+                os,
+                print_writer,
+                trace_hook,
+            )
         } else {
             let tracker = PySignalTracker::new(NoLimitTracker);
-            self.run_impl(py, input_values, tracker, external_functions, os, print_writer)
+            self.run_impl(
+                py,
+                input_values,
+                tracker,
+                external_functions,
+                os,
+                print_writer,
+                trace_hook,
+            )
+        }?;
+        match coverage {
+            Some(coverage) => {
+                let mut line_hits: Vec<_> = coverage.line_hits(&self.script_name).into_iter().collect();
+                line_hits.sort_unstable();
+                let dict = PyDict::new(py);
+                for (line, hits) in line_hits {
+                    dict.set_item(line, hits)?;
+                }
+                (result, dict).into_py_any(py)
+            }
+            None => Ok(result),
         }
     }
 
+    /// Executes the code, capturing printed output instead of writing it to stdout.
+    ///
+    /// # Returns
+    /// A `(result, stdout, stderr)` tuple, where `stderr` holds output printed with
+    /// `file=sys.stderr`
+    ///
+    /// # Raises
+    /// Various Python exceptions matching what the code would raise
+    #[pyo3(signature = (*, inputs=None, limits=None, external_functions=None, os=None))]
+    fn run_capture(
+        &self,
+        py: Python<'_>,
+        inputs: Option<&Bound<'_, PyDict>>,
+        limits: Option<&Bound<'_, PyDict>>,
+        external_functions: Option<&Bound<'_, PyDict>>,
+        os: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<(Py<PyAny>, String, String)> {
+        let input_values = self.extract_input_values(inputs, &self.dc_registry)?;
+
+        if let Some(os_callback) = os
+            && !os_callback.is_callable()
+        {
+            let msg = format!("TypeError: '{}' object is not callable", os_callback.get_type().name()?);
+            return Err(PyTypeError::new_err(msg));
+        }
+
+        let mut output = CapturedOutput::default();
+        let print_writer = PrintWriter::Callback(&mut output);
+        let result = if let Some(limits) = limits {
+            let tracker = PySignalTracker::new(LimitedTracker::new(extract_limits(limits)?));
+            self.run_impl(py, input_values, tracker, external_functions, os, print_writer, None)?
+        } else {
+            let tracker = PySignalTracker::new(NoLimitTracker);
+            self.run_impl(py, input_values, tracker, external_functions, os, print_writer, None)?
+        };
+        Ok((result, output.stdout, output.stderr))
+    }
+
+    /// Executes the code with sync or async external functions, returning a coroutine.
+    ///
+    /// Delegates to `pydantic_monty.run_monty_async`, which drives `start()`/`resume()`
+    /// and awaits coroutines returned by external functions between steps.
+    #[pyo3(signature = (*, inputs=None, limits=None, external_functions=None, print_callback=None, os=None))]
+    fn run_async<'py>(
+        slf: &Bound<'py, Self>,
+        inputs: Option<&Bound<'py, PyDict>>,
+        limits: Option<&Bound<'py, PyDict>>,
+        external_functions: Option<&Bound<'py, PyDict>>,
+        print_callback: Option<&Bound<'py, PyAny>>,
+        os: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("inputs", inputs)?;
+        kwargs.set_item("limits", limits)?;
+        kwargs.set_item("external_functions", external_functions)?;
+        kwargs.set_item("print_callback", print_callback)?;
+        kwargs.set_item("os", os)?;
+        py.import("pydantic_monty")?
+            .getattr("run_monty_async")?
+            .call((slf,), Some(&kwargs))
+    }
+
     #[pyo3(signature = (*, inputs=None, limits=None, print_callback=None))]
     fn start<'py>(
         &self,
@@ -182,7 +291,7 @@ impl PyMonty {
         let dc_registry = self.dc_registry.clone_ref(py);
         let input_values = self.extract_input_values(inputs, &dc_registry)?;
 
-        // Build print writer - CallbackStringPrint is Send so GIL can be released
+        // Build print writer - wrapped in SendWrapper below so the GIL can be released
         let mut print_cb;
         let print_writer = match &print_callback {
             Some(cb) => {
@@ -236,7 +345,7 @@ impl PyMonty {
             input_names: self.input_names.clone(),
             external_function_names: self.external_function_names.clone(),
         };
-        let bytes = postcard::to_allocvec(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let bytes = monty::to_snapshot_bytes(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(PyBytes::new(py, &bytes))
     }
 
@@ -260,7 +369,7 @@ impl PyMonty {
     ) -> PyResult<Self> {
         let bytes = data.as_bytes();
         let serialized: SerializedMonty =
-            postcard::from_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            monty::from_snapshot_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         Ok(Self {
             runner: serialized.runner,
@@ -345,6 +454,7 @@ impl PyMonty {
     ///
     /// Takes explicit field references instead of `&mut self` so that `run()` can
     /// remain `&self` (required for concurrent thread access in PyO3).
+    #[expect(clippy::too_many_arguments)]
     fn run_impl(
         &self,
         py: Python<'_>,
@@ -353,6 +463,7 @@ impl PyMonty {
         external_functions: Option<&Bound<'_, PyDict>>,
         os: Option<&Bound<'_, PyAny>>,
         mut print_output: PrintWriter<'_>,
+        trace_hook: Option<PyTraceHook>,
     ) -> PyResult<Py<PyAny>> {
         // wrap print_output in SendWrapper so that it can be accessed inside the py.detach calls despite
         // no `Send` bound - py.detach() is overly restrictive to prevent `Bound` types going inside
@@ -364,7 +475,13 @@ impl PyMonty {
         let has_dataclass_inputs = || input_values.iter().any(contains_dataclass);
 
         if self.external_function_names.is_empty() && os.is_none() && !has_dataclass_inputs() {
-            return match py.detach(|| self.runner.run(input_values, tracker, &mut print_output)) {
+            let result = py.detach(|| match trace_hook {
+                Some(hook) => self
+                    .runner
+                    .run_with_trace(input_values, tracker, &mut print_output, hook),
+                None => self.runner.run(input_values, tracker, &mut print_output),
+            });
+            return match result {
                 Ok(v) => monty_to_py(py, &v, &self.dc_registry),
                 Err(err) => Err(MontyError::new_err(py, err)),
             };
@@ -372,7 +489,10 @@ impl PyMonty {
         // Clone the runner since start() consumes it - allows reuse of the parsed code
         let runner = self.runner.clone();
         let mut progress = py
-            .detach(|| runner.start(input_values, tracker, &mut print_output))
+            .detach(|| match trace_hook {
+                Some(hook) => runner.start_with_trace(input_values, tracker, &mut print_output, hook),
+                None => runner.start(input_values, tracker, &mut print_output),
+            })
             .map_err(|e| MontyError::new_err(py, e))?;
 
         loop {
@@ -405,6 +525,11 @@ impl PyMonty {
                 RunProgress::ResolveFutures { .. } => {
                     return Err(PyRuntimeError::new_err("async futures not supported with `Monty.run`"));
                 }
+                RunProgress::Paused(_) => {
+                    return Err(PyRuntimeError::new_err(
+                        "pausing on timeout not supported with `Monty.run`",
+                    ));
+                }
                 RunProgress::OsCall {
                     function,
                     args,
@@ -511,6 +636,9 @@ impl EitherProgress {
                     print_callback,
                     dc_registry,
                 ),
+                RunProgress::Paused(_) => Err(PyRuntimeError::new_err(
+                    "pausing on timeout not supported with `Monty.start`",
+                )),
             },
             Self::Limited(p) => match p {
                 RunProgress::Complete(result) => PyMontyComplete::create(py, &result, &dc_registry),
@@ -556,6 +684,9 @@ impl EitherProgress {
                     print_callback,
                     dc_registry,
                 ),
+                RunProgress::Paused(_) => Err(PyRuntimeError::new_err(
+                    "pausing on timeout not supported with `Monty.start`",
+                )),
             },
         }
     }
@@ -761,7 +892,7 @@ impl PyMontyRepl {
             repl: &self.repl,
             script_name: &self.script_name,
         };
-        let bytes = postcard::to_allocvec(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let bytes = monty::to_snapshot_bytes(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(PyBytes::new(py, &bytes))
     }
 
@@ -781,7 +912,7 @@ impl PyMontyRepl {
         }
 
         let serialized: SerializedReplOwned =
-            postcard::from_bytes(data.as_bytes()).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            monty::from_snapshot_bytes(data.as_bytes()).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         Ok(Self {
             repl: serialized.repl,
@@ -1074,7 +1205,7 @@ impl PyMontySnapshot {
             kwargs,
             call_id: self.call_id,
         };
-        let bytes = postcard::to_allocvec(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let bytes = monty::to_snapshot_bytes(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(PyBytes::new(py, &bytes))
     }
 
@@ -1115,7 +1246,7 @@ impl PyMontySnapshot {
         let bytes = data.as_bytes();
 
         let serialized: SerializedSnapshotOwned =
-            postcard::from_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            monty::from_snapshot_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         let dc_registry = DcRegistry::from_list(py, dataclass_registry)?;
 
@@ -1266,7 +1397,7 @@ impl PyMontyFutureSnapshot {
             snapshot: &self.snapshot,
             script_name: &self.script_name,
         };
-        let bytes = postcard::to_allocvec(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let bytes = monty::to_snapshot_bytes(&serialized).map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(PyBytes::new(py, &bytes))
     }
 
@@ -1302,7 +1433,7 @@ impl PyMontyFutureSnapshot {
         let bytes = data.as_bytes();
 
         let serialized: SerializedSnapshotOwned =
-            postcard::from_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            monty::from_snapshot_bytes(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         Ok(Self {
             snapshot: serialized.snapshot,
@@ -1362,39 +1493,201 @@ fn list_str(arg: Option<&Bound<'_, PyList>>, name: &str) -> PyResult<Vec<String>
 
 /// A `PrintWriter` implementation that calls a Python callback for each print output.
 ///
-/// This struct holds a GIL-independent `Py<PyAny>` reference to the callback,
-/// allowing it to be used across GIL release boundaries. The GIL is re-acquired
-/// briefly for each callback invocation.
-#[derive(Debug)]
-pub struct CallbackStringPrint(Py<PyAny>);
+/// This holds GIL-independent `Py<PyAny>` references to the callback, allowing it
+/// to be used across GIL release boundaries. The GIL is re-acquired briefly for each
+/// callback invocation.
+pub enum CallbackStringPrint {
+    /// Calls the callback with `(stream, text)` for each piece of output.
+    Text(Py<PyAny>),
+    /// Calls the handler of a `PrintEvents` with one `PrintEvent` per `print()` call.
+    Events(PrintEventWriter<Box<dyn FnMut(PrintEvent) -> Result<(), MontyException>>>),
+}
 
 impl CallbackStringPrint {
     /// Creates a new `CallbackStringPrint` from a borrowed Python callback.
     fn new(callback: &Bound<'_, PyAny>) -> Self {
-        Self(callback.clone().unbind())
+        match callback.cast::<PyPrintEvents>() {
+            Ok(events) => Self::events(events.get().handler.clone_ref(callback.py())),
+            Err(_) => Self::Text(callback.clone().unbind()),
+        }
     }
 
     /// Creates a new `CallbackStringPrint` from an owned `Py<PyAny>`.
     fn from_py(callback: Py<PyAny>) -> Self {
-        Self(callback)
+        Python::attach(|py| Self::new(callback.bind(py)))
+    }
+
+    fn events(handler: Py<PyAny>) -> Self {
+        Self::Events(PrintEventWriter::new(Box::new(move |event| {
+            Python::attach(|py| {
+                let event = Py::new(py, PyPrintEvent::from(event))?;
+                handler.bind(py).call1((event,))?;
+                Ok::<_, PyErr>(())
+            })
+            .map_err(|e| Python::attach(|py| exc_py_to_monty(py, &e)))
+        })))
     }
 }
 
-impl PrintWriterCallback for CallbackStringPrint {
-    fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+/// Trace hook calling a Python `trace_callback` with `(line, kind, locals)` before each statement,
+/// and counting line coverage.
+#[derive(Debug)]
+struct PyTraceHook {
+    callback: Option<Py<PyAny>>,
+    /// Whether the callback receives a dict of local variable reprs rather than `None`.
+    locals: bool,
+    coverage: Option<Coverage>,
+}
+
+impl TraceHook for PyTraceHook {
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.on_statement(event)?;
+        }
+        let Some(callback) = &self.callback else {
+            return Ok(());
+        };
         Python::attach(|py| {
-            self.0.bind(py).call1(("stdout", output.as_ref()))?;
+            let locals = match &event.locals {
+                Some(locals) => {
+                    let dict = PyDict::new(py);
+                    for (name, repr) in locals {
+                        dict.set_item(name, repr)?;
+                    }
+                    Some(dict)
+                }
+                None => None,
+            };
+            callback.bind(py).call1((event.line, event.kind.name(), locals))?;
             Ok::<_, PyErr>(())
         })
         .map_err(|e| Python::attach(|py| exc_py_to_monty(py, &e)))
     }
 
+    fn wants_locals(&self) -> bool {
+        self.locals
+    }
+}
+
+/// Calls a `(stream, text)` print callback.
+fn call_print_callback(callback: &Py<PyAny>, stream: &str, text: &str) -> Result<(), MontyException> {
+    Python::attach(|py| {
+        callback.bind(py).call1((stream, text))?;
+        Ok::<_, PyErr>(())
+    })
+    .map_err(|e| Python::attach(|py| exc_py_to_monty(py, &e)))
+}
+
+impl PrintWriterCallback for CallbackStringPrint {
+    fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        match self {
+            Self::Text(callback) => call_print_callback(callback, "stdout", &output),
+            Self::Events(writer) => writer.stdout_write(output),
+        }
+    }
+
     fn stdout_push(&mut self, end: char) -> Result<(), MontyException> {
-        Python::attach(|py| {
-            self.0.bind(py).call1(("stdout", end.to_string()))?;
-            Ok::<_, PyErr>(())
+        match self {
+            Self::Text(callback) => call_print_callback(callback, "stdout", &end.to_string()),
+            Self::Events(writer) => writer.stdout_push(end),
+        }
+    }
+
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        match self {
+            Self::Text(callback) => call_print_callback(callback, "stderr", &output),
+            Self::Events(writer) => writer.stderr_write(output),
+        }
+    }
+
+    fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        match self {
+            Self::Text(callback) => call_print_callback(callback, "stderr", &end.to_string()),
+            Self::Events(writer) => writer.stderr_push(end),
+        }
+    }
+
+    fn print_done(&mut self) -> Result<(), MontyException> {
+        match self {
+            Self::Text(_) => Ok(()),
+            Self::Events(writer) => writer.print_done(),
+        }
+    }
+}
+
+/// Wraps a handler so print output is delivered as one `PrintEvent` per `print()` call.
+///
+/// Pass an instance as `print_callback` anywhere a `(stream, text)` callback is accepted.
+#[pyclass(name = "PrintEvents", module = "pydantic_monty", frozen)]
+pub struct PyPrintEvents {
+    handler: Py<PyAny>,
+}
+
+#[pymethods]
+impl PyPrintEvents {
+    #[new]
+    fn new(handler: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if !handler.is_callable() {
+            let msg = format!("'{}' object is not callable", handler.get_type().name()?);
+            return Err(PyTypeError::new_err(msg));
+        }
+        Ok(Self {
+            handler: handler.clone().unbind(),
         })
-        .map_err(|e| Python::attach(|py| exc_py_to_monty(py, &e)))
+    }
+
+    /// The handler called with each `PrintEvent`.
+    #[getter]
+    fn handler(&self, py: Python<'_>) -> Py<PyAny> {
+        self.handler.clone_ref(py)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("PrintEvents({})", self.handler.bind(py).repr()?))
+    }
+}
+
+/// The output of one `print()` call, delivered to a `PrintEvents` handler.
+#[pyclass(name = "PrintEvent", module = "pydantic_monty", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyPrintEvent {
+    /// The stream the call wrote to, 'stdout' or 'stderr'.
+    #[pyo3(get)]
+    stream: &'static str,
+    /// Everything the call wrote, including separators and the `end` string.
+    #[pyo3(get)]
+    text: String,
+    /// Wall-clock time when the call finished, as seconds since the epoch like `time.time()`.
+    #[pyo3(get)]
+    timestamp: f64,
+    /// Zero-based position of the call among the `print()` calls since execution was started or resumed.
+    #[pyo3(get)]
+    index: usize,
+}
+
+impl From<PrintEvent> for PyPrintEvent {
+    fn from(event: PrintEvent) -> Self {
+        Self {
+            stream: event.stream.into(),
+            text: event.text,
+            timestamp: event
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or_default(),
+            index: event.index,
+        }
+    }
+}
+
+#[pymethods]
+impl PyPrintEvent {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let text = PyString::new(py, &self.text).repr()?;
+        Ok(format!(
+            "PrintEvent(stream='{}', text={text}, index={})",
+            self.stream, self.index
+        ))
     }
 }
 
@@ -94,6 +94,18 @@ async def test_run_monty_async_async_function():
     assert result == snapshot('async result')
 
 
+async def test_monty_run_async_method():
+    """Test Monty.run_async with sync and async external functions and inputs."""
+    m = pydantic_monty.Monty('await fetch(x) + double(x)', inputs=['x'], external_functions=['fetch', 'double'])
+
+    async def fetch(n: int) -> int:
+        await asyncio.sleep(0.001)
+        return n * 10
+
+    result = await m.run_async(inputs={'x': 2}, external_functions={'fetch': fetch, 'double': lambda n: n * 2})
+    assert result == snapshot(24)
+
+
 async def test_run_monty_async_function_not_found():
     """Test that missing external function raises wrapped error."""
     m = pydantic_monty.Monty('missing_func()', external_functions=['missing_func'])
@@ -0,0 +1,50 @@
+import pytest
+from inline_snapshot import snapshot
+
+import pydantic_monty
+
+CODE = """
+def classify(n):
+    if n > 0:
+        return 'positive'
+    return 'other'
+
+labels = []
+for n in [1, 2]:
+    labels.append(classify(n))
+labels
+"""
+
+
+def test_coverage_returns_line_hits() -> None:
+    m = pydantic_monty.Monty(CODE)
+    result, line_hits = m.run(coverage=True)
+    assert result == snapshot(['positive', 'positive'])
+    assert line_hits == snapshot({2: 1, 3: 2, 4: 2, 7: 1, 8: 1, 9: 2, 10: 1})
+
+
+def test_coverage_off_by_default() -> None:
+    m = pydantic_monty.Monty('1 + 1')
+    assert m.run() == snapshot(2)
+
+
+def test_coverage_with_trace_callback() -> None:
+    lines: list[int] = []
+    m = pydantic_monty.Monty('x = 1\ny = x + 1\ny')
+    result, line_hits = m.run(coverage=True, trace_callback=lambda line, kind, local_vars: lines.append(line))
+    assert result == snapshot(2)
+    assert line_hits == snapshot({1: 1, 2: 1, 3: 1})
+    assert lines == snapshot([1, 2, 3])
+
+
+def test_coverage_with_external_functions() -> None:
+    m = pydantic_monty.Monty('a = ext(1)\nif a > 5:\n    a = 0\na', external_functions=['ext'])
+    result, line_hits = m.run(external_functions={'ext': lambda v: v + 1}, coverage=True)
+    assert result == snapshot(2)
+    assert line_hits == snapshot({1: 1, 2: 1, 4: 1})
+
+
+def test_coverage_when_code_raises() -> None:
+    m = pydantic_monty.Monty('x = 1\nraise ValueError(x)')
+    with pytest.raises(pydantic_monty.MontyRuntimeError):
+        m.run(coverage=True)
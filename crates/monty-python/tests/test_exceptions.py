@@ -129,6 +129,15 @@ def test_syntax_error_invalid_syntax():
     assert isinstance(inner, SyntaxError)
 
 
+def test_syntax_error_location():
+    with pytest.raises(pydantic_monty.MontySyntaxError) as exc_info:
+        pydantic_monty.Monty('x = 1\ny = = 2')
+    location = exc_info.value.location()
+    assert location is not None
+    assert location.filename == snapshot('main.py')
+    assert location.line == snapshot(2)
+    assert location.source_line == snapshot('y = = 2')
+
 # === Catching with base class ===
 
 
@@ -330,6 +339,21 @@ foo()
     )
 
 
+def test_runtime_error_location():
+    code = """
+def foo():
+    raise ValueError('test')
+
+foo()
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run()
+    location = exc_info.value.location()
+    assert location is not None
+    assert location.dict() == exc_info.value.traceback()[-1].dict()
+    assert (location.line, location.column, location.end_line, location.end_column) == snapshot((3, 11, 3, 29))
+
 # === Repr tests ===
 
 
@@ -50,6 +50,20 @@ def test_external_function_mixed_args_kwargs():
     assert m.run(external_functions={'func': func}) == snapshot('ok')
 
 
+def test_external_function_unpacked_kwargs():
+    code = """
+opts = {'b': 2, 'c': 3}
+func(a=1, **opts)
+"""
+    m = pydantic_monty.Monty(code, external_functions=['func'])
+
+    def func(*args: Any, **kwargs: Any) -> list[str]:
+        assert args == snapshot(())
+        return list(kwargs)
+
+    assert m.run(external_functions={'func': func}) == snapshot(['a', 'b', 'c'])
+
+
 def test_external_function_complex_types():
     m = pydantic_monty.Monty('func([1, 2], {"key": "value"})', external_functions=['func'])
 
@@ -37,6 +37,118 @@ def test_run_with_limits():
     assert m.run(limits=limits) == snapshot(2)
 
 
+def test_random_seed_is_reproducible():
+    m = pydantic_monty.Monty('import random\n[random.randint(1, 1000) for _ in range(5)]')
+    limits = pydantic_monty.ResourceLimits(random_seed=42)
+    assert m.run(limits=limits) == m.run(limits=limits)
+
+
+def test_deterministic_seeds_random():
+    m = pydantic_monty.Monty('import random\n[random.randint(1, 1000) for _ in range(5)]')
+    limits = pydantic_monty.ResourceLimits(deterministic=True)
+    assert m.run(limits=limits) == m.run(limits=limits)
+
+
+def test_deterministic_refuses_clock():
+    m = pydantic_monty.Monty('import time\ntime.time()')
+    limits = pydantic_monty.ResourceLimits(deterministic=True)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(limits=limits)
+    assert str(exc_info.value.exception()) == snapshot('time.time() is not allowed in deterministic mode')
+
+
+def test_step_limit():
+    m = pydantic_monty.Monty('x = 0\nwhile True:\n    x += 1')
+    limits = pydantic_monty.ResourceLimits(max_steps=1000)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(limits=limits)
+    exc = exc_info.value.exception()
+    assert isinstance(exc, TimeoutError)
+    assert str(exc) == snapshot('step limit exceeded: 1001 > 1000')
+
+
+def test_step_limit_exception_type():
+    m = pydantic_monty.Monty('x = 0\nwhile True:\n    x += 1')
+    limits = pydantic_monty.ResourceLimits(max_steps=1000)
+    with pytest.raises(pydantic_monty.SandboxTimeout) as exc_info:
+        m.run(limits=limits)
+    exc = exc_info.value
+    assert isinstance(exc, pydantic_monty.ResourceLimitExceeded)
+    assert isinstance(exc, pydantic_monty.MontyRuntimeError)
+    assert exc.kind == snapshot('steps')
+    assert exc.limit == snapshot(1000)
+    assert exc.used == snapshot(1001)
+    assert exc.elapsed is None
+    assert repr(exc) == snapshot('SandboxTimeout(TimeoutError: step limit exceeded: 1001 > 1000)')
+
+
+def test_time_limit_exception_type():
+    m = pydantic_monty.Monty('x = 0\nwhile True:\n    x += 1')
+    limits = pydantic_monty.ResourceLimits(max_duration_secs=0.05)
+    with pytest.raises(pydantic_monty.SandboxTimeout) as exc_info:
+        m.run(limits=limits)
+    exc = exc_info.value
+    assert exc.kind == snapshot('time')
+    assert exc.limit == snapshot(0.05)
+    assert exc.elapsed is not None
+    assert exc.elapsed > 0.05
+    assert exc.used == exc.elapsed
+
+
+def test_memory_limit_exception_type():
+    code = """
+result = []
+for i in range(1000):
+    result.append('x' * 100)
+"""
+    m = pydantic_monty.Monty(code)
+    limits = pydantic_monty.ResourceLimits(max_memory=100)
+    with pytest.raises(pydantic_monty.SandboxMemoryError) as exc_info:
+        m.run(limits=limits)
+    exc = exc_info.value
+    assert isinstance(exc, pydantic_monty.ResourceLimitExceeded)
+    assert exc.kind == snapshot('memory')
+    assert exc.limit == snapshot(100)
+    assert exc.used > 100
+    assert exc.elapsed is None
+
+
+def test_allocation_limit_exception_type():
+    m = pydantic_monty.Monty('[[i] for i in range(100)]')
+    limits = pydantic_monty.ResourceLimits(max_allocations=5)
+    with pytest.raises(pydantic_monty.SandboxMemoryError) as exc_info:
+        m.run(limits=limits)
+    assert exc_info.value.kind == snapshot('allocations')
+    assert exc_info.value.limit == snapshot(5)
+
+
+def test_recursion_limit_exception_type():
+    code = """
+def recurse(n):
+    return recurse(n + 1)
+
+recurse(0)
+"""
+    m = pydantic_monty.Monty(code)
+    limits = pydantic_monty.ResourceLimits(max_recursion_depth=5)
+    with pytest.raises(pydantic_monty.ResourceLimitExceeded) as exc_info:
+        m.run(limits=limits)
+    exc = exc_info.value
+    assert type(exc) is pydantic_monty.ResourceLimitExceeded
+    assert exc.kind == snapshot('recursion')
+    assert exc.limit == snapshot(5)
+    assert isinstance(exc.exception(), RecursionError)
+
+
+def test_raised_memory_error_is_not_limit():
+    m = pydantic_monty.Monty("raise MemoryError('oops')")
+    limits = pydantic_monty.ResourceLimits(max_memory=1_000_000)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(limits=limits)
+    assert type(exc_info.value) is pydantic_monty.MontyRuntimeError
+    assert isinstance(exc_info.value.exception(), MemoryError)
+
+
 def test_recursion_limit():
     code = """
 def recurse(n):
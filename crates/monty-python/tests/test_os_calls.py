@@ -817,3 +817,37 @@ Path('/tmp/mydir/file.txt').read_text()
             ('Path.read_text', (PurePosixPath('/tmp/mydir/file.txt'),)),
         ]
     )
+
+
+# =============================================================================
+# input()
+# =============================================================================
+
+
+def test_input_yields_oscall():
+    """input() prints its prompt then yields an 'input' OS call resumed with the line read."""
+    output: list[str] = []
+    m = pydantic_monty.Monty('name = input("name? ")\nf"hello {name}"')
+    snapshot_result = m.start(print_callback=lambda stream, text: output.append(text))
+
+    assert isinstance(snapshot_result, pydantic_monty.MontySnapshot)
+    assert snapshot_result.is_os_function is True
+    assert snapshot_result.function_name == snapshot('input')
+    assert snapshot_result.args == snapshot(())
+    assert output == snapshot(['name? '])
+
+    result = snapshot_result.resume(return_value='monty')
+    assert isinstance(result, pydantic_monty.MontyComplete)
+    assert result.output == snapshot('hello monty')
+
+
+def test_input_with_os_callback():
+    """Monty.run() routes input() to the os callback."""
+    lines = iter(['3', '4'])
+
+    def os_handler(function_name: str, args: tuple[Any, ...], kwargs: dict[str, Any]) -> Any:
+        assert function_name == 'input'
+        return next(lines)
+
+    m = pydantic_monty.Monty('int(input()) * int(input())')
+    assert m.run(os=os_handler) == snapshot(12)
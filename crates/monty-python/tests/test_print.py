@@ -1,18 +1,19 @@
-from typing import Callable, Literal
+import time
+from typing import Any, Callable, Literal
 
 import pytest
 from inline_snapshot import snapshot
 
 import pydantic_monty
 
-PrintCallback = Callable[[Literal['stdout'], str], None]
+PrintCallback = Callable[[Literal['stdout', 'stderr'], str], None]
 
 
 def make_print_collector() -> tuple[list[str], PrintCallback]:
     """Create a print callback that collects output into a list."""
     output: list[str] = []
 
-    def callback(stream: Literal['stdout'], text: str) -> None:
+    def callback(stream: Literal['stdout', 'stderr'], text: str) -> None:
         assert stream == 'stdout'
         output.append(text)
 
@@ -107,10 +108,34 @@ def test_print_mixed_types() -> None:
     assert ''.join(output) == snapshot('1 hello True None\n')
 
 
+def test_print_to_stderr() -> None:
+    code = """
+import sys
+print("out")
+print("err", 1, file=sys.stderr)
+print("out again", file=sys.stdout)
+"""
+    m = pydantic_monty.Monty(code)
+    output: list[tuple[str, str]] = []
+    m.run(print_callback=lambda stream, text: output.append((stream, text)))
+    assert output == snapshot(
+        [
+            ('stdout', 'out'),
+            ('stdout', '\n'),
+            ('stderr', 'err'),
+            ('stderr', ' '),
+            ('stderr', '1'),
+            ('stderr', '\n'),
+            ('stdout', 'out again'),
+            ('stdout', '\n'),
+        ]
+    )
+
+
 def make_error_callback(error: Exception) -> PrintCallback:
     """Create a print callback that raises an exception."""
 
-    def callback(stream: Literal['stdout'], text: str) -> None:
+    def callback(stream: Literal['stdout', 'stderr'], text: str) -> None:
         raise error
 
     return callback
@@ -138,6 +163,20 @@ def test_print_callback_raises_type_error() -> None:
     assert inner.args[0] == snapshot('wrong type')
 
 
+def test_print_callback_error_catchable_in_sandbox() -> None:
+    """Test that sandboxed code can catch an exception raised by the print callback."""
+    code = """
+try:
+    print("hello")
+except OSError as e:
+    result = str(e)
+result
+"""
+    m = pydantic_monty.Monty(code)
+    callback = make_error_callback(BrokenPipeError('pipe closed'))
+    assert m.run(print_callback=callback) == snapshot('pipe closed')
+
+
 def test_print_callback_raises_in_function() -> None:
     """Test exception from callback when print is called inside a function."""
     code = """
@@ -183,7 +222,7 @@ for i in range(5):
     m = pydantic_monty.Monty(code)
     call_count = 0
 
-    def callback(stream: Literal['stdout'], text: str) -> None:
+    def callback(stream: Literal['stdout', 'stderr'], text: str) -> None:
         nonlocal call_count
         call_count += 1
         if call_count >= 3:
@@ -206,3 +245,55 @@ list(map(print, [1, 2, 3]))
     output, callback = make_print_collector()
     m.run(print_callback=callback)
     assert ''.join(output) == snapshot('1\n2\n3\n')
+
+
+def test_run_capture() -> None:
+    code = """
+import sys
+print('hello', 'world')
+print('oops', file=sys.stderr)
+1 + 2
+"""
+    m = pydantic_monty.Monty(code)
+    assert m.run_capture() == snapshot((3, 'hello world\n', 'oops\n'))
+
+
+def test_run_capture_with_limits() -> None:
+    m = pydantic_monty.Monty('print(x)\nx * 2', inputs=['x'])
+    limits = pydantic_monty.ResourceLimits(max_duration_secs=5.0)
+    assert m.run_capture(inputs={'x': 21}, limits=limits) == snapshot((42, '21\n', ''))
+
+
+def test_print_events() -> None:
+    code = """
+import sys
+print('a', 'b', sep='-')
+print('oops', file=sys.stderr, end='')
+print()
+"""
+    events: list[pydantic_monty.PrintEvent] = []
+    before = time.time()
+    pydantic_monty.Monty(code).run(print_callback=pydantic_monty.PrintEvents(events.append))
+    after = time.time()
+
+    assert [(e.stream, e.text, e.index) for e in events] == snapshot(
+        [('stdout', 'a-b\n', 0), ('stderr', 'oops', 1), ('stdout', '\n', 2)]
+    )
+    assert all(before <= e.timestamp <= after for e in events)
+    assert repr(events[0]) == snapshot("PrintEvent(stream='stdout', text='a-b\\n', index=0)")
+
+
+def test_print_events_handler_error() -> None:
+    def handler(event: pydantic_monty.PrintEvent) -> None:
+        raise ValueError(f'rejected {event.text!r}')
+
+    m = pydantic_monty.Monty("print('x')")
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(print_callback=pydantic_monty.PrintEvents(handler))
+    assert exc_info.value.display('type-msg') == snapshot("ValueError: rejected 'x\\n'")
+
+
+def test_print_events_not_callable() -> None:
+    not_callable: Any = 1
+    with pytest.raises(TypeError, match="'int' object is not callable"):
+        pydantic_monty.PrintEvents(not_callable)
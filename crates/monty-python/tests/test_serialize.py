@@ -46,7 +46,18 @@ def test_monty_dump_load_preserves_external_functions():
 def test_monty_load_invalid_data():
     with pytest.raises(ValueError) as exc_info:
         pydantic_monty.Monty.load(b'invalid data')
-    assert str(exc_info.value) == snapshot('Hit the end of buffer, expected more data')
+    assert str(exc_info.value) == snapshot('data is not a Monty snapshot, the header is missing')
+
+
+def test_monty_load_unsupported_version():
+    data = bytearray(pydantic_monty.Monty('1').dump())
+    data[4:6] = (999).to_bytes(2, 'little')
+    with pytest.raises(ValueError) as exc_info:
+        pydantic_monty.Monty.load(bytes(data))
+    assert str(exc_info.value) == snapshot(
+        'snapshot format version 999 is not supported, this build of Monty supports versions 1 to 1, '
+        'upgrade Monty to load it'
+    )
 
 
 def test_progress_dump_load_roundtrip():
@@ -0,0 +1,89 @@
+from typing import Any
+
+import pytest
+from inline_snapshot import snapshot
+
+import pydantic_monty
+
+CODE = """
+def double(n):
+    result = n * 2
+    return result
+
+x = 1
+if x:
+    y = double(x)
+y
+"""
+
+
+def test_trace_callback_sees_each_statement() -> None:
+    events: list[tuple[int, str]] = []
+
+    def trace(line: int, kind: str, local_vars: dict[str, str] | None) -> None:
+        assert local_vars is None
+        events.append((line, kind))
+
+    m = pydantic_monty.Monty(CODE)
+    assert m.run(trace_callback=trace) == snapshot(2)
+    assert events == snapshot(
+        [
+            (2, 'function_def'),
+            (6, 'assign'),
+            (7, 'if'),
+            (8, 'assign'),
+            (3, 'assign'),
+            (4, 'return'),
+            (9, 'return'),
+        ]
+    )
+
+
+def test_trace_locals() -> None:
+    locals_by_line: dict[int, dict[str, str]] = {}
+
+    def trace(line: int, kind: str, local_vars: dict[str, str] | None) -> None:
+        assert local_vars is not None
+        locals_by_line[line] = local_vars
+
+    m = pydantic_monty.Monty(CODE)
+    m.run(trace_callback=trace, trace_locals=True)
+    assert locals_by_line[4] == snapshot({'n': '1', 'result': '2'})
+
+
+def test_trace_with_external_functions() -> None:
+    lines: list[int] = []
+    m = pydantic_monty.Monty('a = ext(1)\nb = ext(a)\nb', external_functions=['ext'])
+    result = m.run(
+        external_functions={'ext': lambda v: v + 1},
+        trace_callback=lambda line, kind, local_vars: lines.append(line),
+    )
+    assert result == snapshot(3)
+    assert lines == snapshot([1, 2, 3])
+
+
+def test_trace_callback_error_stops_execution() -> None:
+    def trace(line: int, kind: str, local_vars: dict[str, str] | None) -> None:
+        if line == 3:
+            raise ValueError('stop here')
+
+    code = """
+try:
+    x = 1
+except ValueError:
+    x = 2
+x
+"""
+    m = pydantic_monty.Monty(code)
+    with pytest.raises(pydantic_monty.MontyRuntimeError) as exc_info:
+        m.run(trace_callback=trace)
+    inner = exc_info.value.exception()
+    assert isinstance(inner, ValueError)
+    assert inner.args[0] == snapshot('stop here')
+
+
+def test_trace_callback_not_callable() -> None:
+    m = pydantic_monty.Monty('1')
+    not_callable: Any = 1
+    with pytest.raises(TypeError, match="'int' object is not callable"):
+        m.run(trace_callback=not_callable)
@@ -24,6 +24,7 @@ ALLOWED_FUNCTIONS = {
     'bin',
     'chr',
     'divmod',
+    'format',
     'hash',
     'hex',
     'id',
@@ -53,6 +54,7 @@ ALLOWED_CLASSES = {
     # String/bytes types
     'str',
     'bytes',
+    'bytearray',
     # Container types
     'list',
     'tuple',
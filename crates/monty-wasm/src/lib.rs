@@ -0,0 +1,49 @@
+//! WebAssembly bindings for Monty, built for `wasm32-unknown-unknown` with `wasm-bindgen`.
+//!
+//! Values cross the boundary as JSON in `MontyObject`'s serde format, e.g. `{"Int": 1}`.
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use monty::{LimitedTracker, MontyObject, MontyRun, PrintWriter, ResourceLimits};
+use wasm_bindgen::prelude::*;
+
+/// Parses and runs `code`, returning the result as JSON.
+///
+/// `inputs_json` is a JSON object mapping input names to values, e.g. `{"x": {"Int": 41}}`.
+/// Print output is discarded.
+///
+/// # Errors
+/// Returns an error if the inputs aren't valid JSON, or if the code fails to parse or raises.
+#[wasm_bindgen]
+pub fn run(code: &str, inputs_json: &str) -> Result<String, JsError> {
+    run_with_limits(code, inputs_json, ResourceLimits::new())
+}
+
+/// Like `run`, but raises `TimeoutError` if the code runs for longer than `timeout_ms`.
+///
+/// # Errors
+/// Returns an error if the inputs aren't valid JSON, or if the code fails to parse, raises
+/// or times out.
+#[wasm_bindgen]
+pub fn run_with_timeout(code: &str, inputs_json: &str, timeout_ms: f64) -> Result<String, JsError> {
+    let limits = ResourceLimits::new().max_duration(Duration::from_secs_f64(timeout_ms / 1000.0));
+    run_with_limits(code, inputs_json, limits)
+}
+
+fn run_with_limits(code: &str, inputs_json: &str, limits: ResourceLimits) -> Result<String, JsError> {
+    let inputs: IndexMap<String, MontyObject> = serde_json::from_str(inputs_json)?;
+    let (names, values) = inputs.into_iter().unzip();
+
+    let runner =
+        MontyRun::new(code.to_owned(), "main.py", names, vec![]).map_err(|exc| JsError::new(&exc.to_string()))?;
+    let tracker = LimitedTracker::new(limits).with_clock(js_clock);
+    let result = runner
+        .run(values, tracker, &mut PrintWriter::Disabled)
+        .map_err(|exc| JsError::new(&exc.to_string()))?;
+    Ok(serde_json::to_string(&result)?)
+}
+
+/// Millisecond clock from `Date.now()`, since `std::time::Instant` isn't available in the browser.
+fn js_clock() -> Duration {
+    Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
@@ -0,0 +1,161 @@
+//! Static analysis of code without running it, see `MontyRun::analyze`.
+
+use std::fmt;
+
+use crate::{
+    ExcType,
+    exception_public::StackFrame,
+    namespace::NamespaceId,
+    parse::{ParseError, parse_reporting_all},
+    prepare::prepare,
+};
+
+/// What `MontyRun::analyze` found wrong with some code.
+///
+/// An analysis without diagnostics doesn't mean the code will run without errors, only that
+/// the problems it looks for weren't found.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// The problems found: errors parsing the code in source order, or else undefined names
+    /// in source order followed by unused inputs.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Analysis {
+    /// Returns whether no problems were found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Returns the problems of one kind.
+    pub fn of_kind(&self, kind: DiagnosticKind) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(move |diagnostic| diagnostic.kind == kind)
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem found by `MontyRun::analyze`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// What kind of problem this is.
+    pub kind: DiagnosticKind,
+    /// Describes the problem, e.g. `name 'x' is not defined`.
+    pub message: String,
+    /// Where the problem is, `None` for problems with the inputs.
+    pub location: Option<StackFrame>,
+}
+
+impl Diagnostic {
+    /// Creates the diagnostic of an error parsing or preparing the code.
+    fn from_parse_error(error: ParseError, script_name: &str, code: &str) -> Self {
+        let exc = error.into_python_exc(script_name, code);
+        let kind = if exc.exc_type() == ExcType::SyntaxError {
+            DiagnosticKind::SyntaxError
+        } else {
+            DiagnosticKind::Unsupported
+        };
+        Self {
+            kind,
+            message: exc.message().unwrap_or_default().to_owned(),
+            location: exc.traceback().first().cloned(),
+        }
+    }
+}
+
+/// Formats the diagnostic as `file:line:column: message`.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(
+                f,
+                "{}:{}:{}: ",
+                location.filename, location.start.line, location.start.column
+            )?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The kinds of problem `MontyRun::analyze` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The code isn't valid Python.
+    SyntaxError,
+    /// The code uses a feature Monty doesn't support, e.g. `match` statements.
+    Unsupported,
+    /// A name is read but bound nowhere, so reading it raises `NameError`.
+    UndefinedName,
+    /// A name is called but bound nowhere, often an external function that wasn't registered.
+    UnknownFunction,
+    /// An input is never referred to.
+    UnusedInput,
+}
+
+/// Analyzes code, see `MontyRun::analyze`.
+///
+/// Names are only checked once the code parses, since the statements that failed to parse
+/// may bind names.
+pub(crate) fn analyze(
+    code: &str,
+    script_name: &str,
+    input_names: &[String],
+    external_functions: &[String],
+) -> Analysis {
+    let diagnostics = |errors: Vec<ParseError>| Analysis {
+        diagnostics: errors
+            .into_iter()
+            .map(|error| Diagnostic::from_parse_error(error, script_name, code))
+            .collect(),
+    };
+    let parse_result = match parse_reporting_all(code, script_name) {
+        Ok(parse_result) => parse_result,
+        Err(errors) => return diagnostics(errors),
+    };
+    let prepared = match prepare(parse_result, input_names.to_vec(), external_functions) {
+        Ok(prepared) => prepared,
+        Err(error) => return diagnostics(vec![error]),
+    };
+
+    let mut analysis = Analysis::default();
+    for undefined in &prepared.undefined_names {
+        let name = prepared.interner.get_str(undefined.name.name_id);
+        let (kind, message) = if undefined.is_call {
+            (
+                DiagnosticKind::UnknownFunction,
+                format!("name '{name}' is not defined, is it an external function that wasn't registered?"),
+            )
+        } else {
+            (DiagnosticKind::UndefinedName, format!("name '{name}' is not defined"))
+        };
+        analysis.diagnostics.push(Diagnostic {
+            kind,
+            message,
+            location: Some(StackFrame::from_position(undefined.name.position, script_name, code)),
+        });
+    }
+    // inputs take the slots after the external functions
+    for (index, name) in input_names.iter().enumerate() {
+        if !prepared
+            .used_globals
+            .contains(&NamespaceId::new(external_functions.len() + index))
+        {
+            analysis.diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::UnusedInput,
+                message: format!("input '{name}' is never used"),
+                location: None,
+            });
+        }
+    }
+    analysis
+}
@@ -192,6 +192,69 @@ impl ArgValues {
         Ok((val1_guard.into_inner(), val2_guard.into_inner()))
     }
 
+    /// Binds the arguments to the parameters of a plain Python signature such as
+    /// `def search(pattern, string, flags=0)`, where every parameter can be passed by
+    /// position or by keyword.
+    ///
+    /// The first `required` parameters must be given. Returns one slot per parameter,
+    /// `None` for optional parameters that were not passed.
+    pub fn bind<const N: usize>(
+        self,
+        name: &str,
+        params: [&str; N],
+        required: usize,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<[Option<Value>; N]> {
+        let (pos, kwargs) = self.into_parts();
+        let pos_count = pos.len();
+        if pos_count > N {
+            let err = ExcType::type_error_too_many_positional(name, N, pos_count, 0);
+            pos.drop_with_heap(heap);
+            kwargs.drop_with_heap(heap);
+            return Err(err);
+        }
+
+        let kwargs = kwargs.into_iter();
+        defer_drop_mut!(kwargs, heap);
+        let mut bound_guard = HeapGuard::new(Vec::with_capacity(N), heap);
+        let (bound, heap) = bound_guard.as_parts_mut();
+        bound.extend(pos.map(Some));
+        bound.resize_with(N, || None);
+
+        for (key, value) in kwargs {
+            defer_drop!(key, heap);
+            let mut value = HeapGuard::new(value, heap);
+
+            let Some(keyword_name) = key.as_either_str(value.heap()) else {
+                return Err(ExcType::type_error("keywords must be strings"));
+            };
+            let key_str = keyword_name.as_str(interns);
+            let Some(index) = params.iter().position(|param| *param == key_str) else {
+                return Err(ExcType::type_error_unexpected_keyword(name, key_str));
+            };
+            if bound[index].is_some() {
+                return Err(ExcType::type_error_duplicate_arg(name, key_str));
+            }
+            bound[index] = Some(value.into_inner());
+        }
+
+        let missing: Vec<&str> = params[..required]
+            .iter()
+            .zip(bound.iter())
+            .filter(|(_, value)| value.is_none())
+            .map(|(param, _)| *param)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ExcType::type_error_missing_positional_with_names(name, &missing));
+        }
+
+        let Ok(bound) = bound_guard.into_inner().try_into() else {
+            unreachable!("one slot per parameter")
+        };
+        Ok(bound)
+    }
+
     /// Prepends a value as the first positional argument.
     ///
     /// Used to insert `self` when dispatching dataclass method calls to the host.
@@ -228,6 +291,28 @@ impl ArgValues {
         }
     }
 
+    /// Inverse of [`into_parts()`](Self::into_parts): builds arguments from positional values
+    /// and keyword values, using the compact variants where possible.
+    pub fn from_parts(mut args: Vec<Value>, kwargs: KwargsValues) -> Self {
+        if !kwargs.is_empty() {
+            return if args.is_empty() {
+                Self::Kwargs(kwargs)
+            } else {
+                Self::ArgsKargs { args, kwargs }
+            };
+        }
+        match args.len() {
+            0 => Self::Empty,
+            1 => Self::One(args.pop().expect("length checked")),
+            2 => {
+                let b = args.pop().expect("length checked");
+                let a = args.pop().expect("length checked");
+                Self::Two(a, b)
+            }
+            _ => Self::ArgsKargs { args, kwargs },
+        }
+    }
+
     /// Variant of [`into_parts()`](Self::into_parts) that accepts no kwargs, returning an error if any are present.
     pub fn into_pos_only(self, method_name: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<ArgPosIter> {
         match self {
@@ -554,6 +639,27 @@ pub struct Kwarg {
     pub value: ExprLoc,
 }
 
+/// A positional argument at or after the first `*iterable` unpacking in a call.
+///
+/// These are kept in source order so that calls like `f(a, *xs, b, *ys)` build their
+/// argument tuple by appending plain values and extending with unpacked iterables.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum StarredArg {
+    /// A plain positional argument following an earlier `*iterable`.
+    Value(ExprLoc),
+    /// An `*iterable` unpacking.
+    Unpack(ExprLoc),
+}
+
+impl StarredArg {
+    /// Returns the underlying expression, whether it is unpacked or not.
+    pub fn expr(&self) -> &ExprLoc {
+        match self {
+            Self::Value(expr) | Self::Unpack(expr) => expr,
+        }
+    }
+}
+
 /// Expressions that make up a function call's arguments.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum ArgExprs {
@@ -563,26 +669,30 @@ pub enum ArgExprs {
     Args(Vec<ExprLoc>),
     Kwargs(Vec<Kwarg>),
     ArgsKargs {
+        /// Positional arguments before the first `*iterable`.
         args: Option<Vec<ExprLoc>>,
-        var_args: Option<ExprLoc>,
+        /// The first `*iterable` and every positional argument after it, in source order.
+        var_args: Vec<StarredArg>,
+        /// Named keyword arguments (`key=value`).
         kwargs: Option<Vec<Kwarg>>,
-        var_kwargs: Option<ExprLoc>,
+        /// `**mapping` unpackings, merged in source order.
+        var_kwargs: Vec<ExprLoc>,
     },
 }
 
 impl ArgExprs {
-    /// Creates a new `ArgExprs` with optional `*args` and `**kwargs` unpacking expressions.
+    /// Creates a new `ArgExprs` with any `*args` and `**kwargs` unpacking expressions.
     ///
     /// This is used when parsing function calls that may include `*expr` / `**expr`
     /// syntax for unpacking iterables or mappings into arguments.
     pub fn new_with_var_kwargs(
         args: Vec<ExprLoc>,
-        var_args: Option<ExprLoc>,
+        var_args: Vec<StarredArg>,
         kwargs: Vec<Kwarg>,
-        var_kwargs: Option<ExprLoc>,
+        var_kwargs: Vec<ExprLoc>,
     ) -> Self {
         // Full generality requires ArgsKargs when we have unpacking or mixed arg/kwarg usage
-        if var_args.is_some() || var_kwargs.is_some() || (!kwargs.is_empty() && !args.is_empty()) {
+        if !var_args.is_empty() || !var_kwargs.is_empty() || (!kwargs.is_empty() && !args.is_empty()) {
             Self::ArgsKargs {
                 args: if args.is_empty() { None } else { Some(args) },
                 var_args,
@@ -642,7 +752,13 @@ impl ArgExprs {
                 let args = args
                     .map(|a| a.into_iter().map(&mut f).collect::<Result<Vec<_>, ParseError>>())
                     .transpose()?;
-                let var_args = var_args.map(&mut f).transpose()?;
+                let var_args = var_args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        StarredArg::Value(expr) => f(expr).map(StarredArg::Value),
+                        StarredArg::Unpack(expr) => f(expr).map(StarredArg::Unpack),
+                    })
+                    .collect::<Result<Vec<_>, ParseError>>()?;
                 let kwargs = kwargs
                     .map(|k| {
                         k.into_iter()
@@ -655,7 +771,10 @@ impl ArgExprs {
                             .collect::<Result<Vec<_>, ParseError>>()
                     })
                     .transpose()?;
-                let var_kwargs = var_kwargs.map(&mut f).transpose()?;
+                let var_kwargs = var_kwargs
+                    .into_iter()
+                    .map(&mut f)
+                    .collect::<Result<Vec<_>, ParseError>>()?;
                 Self::ArgsKargs {
                     args,
                     var_args,
@@ -0,0 +1,688 @@
+//! JSON representation of the prepared AST, see `MontyRun::ast_json`.
+//!
+//! The format follows Python's `ast` module where Monty's AST has an equivalent, so
+//! `obj.method(x)` is a `Call` of an `Attribute` even though Monty has a separate node for
+//! method calls, and optimizations made while preparing the code are undone. Unlike `ast`,
+//! names say which scope they were resolved to and which namespace slot they use.
+
+use ahash::AHashSet;
+use serde_json::{Value as JsonValue, json};
+
+use crate::{
+    args::{ArgExprs, Kwarg, StarredArg},
+    builtins::Builtins,
+    exception_public::MontyException,
+    expressions::{
+        AssignTarget, Callable, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        NameScope, Node, Operator, PreparedFunctionDef, PreparedNode, UnpackTarget,
+    },
+    fstring::{ConversionFlag, FStringPart, FormatSpec, ParsedFormatSpec},
+    intern::{Interns, StringId},
+    parse::{CodeRange, ExceptHandler, Try, parse},
+    prepare::prepare,
+    signature::ParamKind,
+    types::BUILTIN_TYPES,
+    value::EitherStr,
+};
+
+/// Parses and prepares code and returns its AST as JSON, see `MontyRun::ast_json`.
+pub(crate) fn ast_json(
+    code: &str,
+    script_name: &str,
+    input_names: &[String],
+    external_functions: &[String],
+) -> Result<JsonValue, MontyException> {
+    let parse_result = parse(code, script_name).map_err(|e| e.into_python_exc(script_name, code))?;
+    let mut prepared = prepare(parse_result, input_names.to_vec(), external_functions)
+        .map_err(|e| e.into_python_exc(script_name, code))?;
+    let slot_names = prepared.slot_names();
+    let undefined: AHashSet<CodeRange> = prepared
+        .undefined_names
+        .iter()
+        .map(|undefined| undefined.name.position)
+        .collect();
+    let interns = Interns::new(prepared.interner, Vec::new(), external_functions.to_vec());
+    let writer = AstWriter {
+        interns: &interns,
+        undefined: &undefined,
+        at_module: true,
+    };
+    Ok(json!({
+        "type": "Module",
+        "body": writer.nodes(&prepared.nodes),
+        "slot_names": writer.strs(&slot_names),
+    }))
+}
+
+/// Converts prepared AST nodes to JSON, looking up interned names in `interns`.
+#[derive(Clone, Copy)]
+struct AstWriter<'a> {
+    interns: &'a Interns,
+    /// Positions of the names that aren't bound anywhere.
+    undefined: &'a AHashSet<CodeRange>,
+    /// Whether the nodes are module-level code rather than in a function, where the
+    /// module namespace holds the names even though they're resolved as locals.
+    at_module: bool,
+}
+
+impl AstWriter<'_> {
+    fn nodes(&self, nodes: &[PreparedNode]) -> Vec<JsonValue> {
+        nodes.iter().map(|node| self.node(node)).collect()
+    }
+
+    fn node(&self, node: &PreparedNode) -> JsonValue {
+        match node {
+            Node::Pass => json!({"type": "Pass"}),
+            Node::Expr(value) => json!({"type": "Expr", "value": self.expr(value)}),
+            Node::Return(value) => json!({"type": "Return", "value": self.expr(value)}),
+            Node::ReturnNone => json!({"type": "Return", "value": null}),
+            Node::Raise { exc, cause } => json!({
+                "type": "Raise",
+                "exc": self.opt_expr(exc.as_ref()),
+                "cause": self.opt_expr(cause.as_ref()),
+            }),
+            Node::Assert { test, msg } => json!({
+                "type": "Assert",
+                "test": self.expr(test),
+                "msg": self.opt_expr(msg.as_ref()),
+            }),
+            Node::Assign { target, object } => self.assign(vec![self.name(target)], object),
+            Node::UnpackAssign {
+                targets,
+                targets_position,
+                object,
+            } => self.assign(vec![self.unpack_tuple(targets, *targets_position)], object),
+            Node::OpAssign { target, op, object } => json!({
+                "type": "AugAssign",
+                "target": self.name(target),
+                "op": operator_name(op),
+                "value": self.expr(object),
+            }),
+            Node::SubscriptAssign {
+                target,
+                index,
+                value,
+                target_position,
+            } => self.assign(vec![self.subscript(self.name(target), index, *target_position)], value),
+            Node::AttrAssign {
+                object,
+                attr,
+                target_position,
+                value,
+            } => self.assign(
+                vec![self.attribute(self.expr(object), attr, Some(*target_position))],
+                value,
+            ),
+            Node::ChainAssign { targets, object } => {
+                let targets = targets.iter().map(|target| self.assign_target(target)).collect();
+                self.assign(targets, object)
+            }
+            Node::Delete { targets } => {
+                let targets: Vec<_> = targets
+                    .iter()
+                    .map(|target| match target {
+                        DeleteTarget::Name(name) => self.name(name),
+                        DeleteTarget::Subscript {
+                            object,
+                            index,
+                            target_position,
+                        } => self.subscript(self.expr(object), index, *target_position),
+                    })
+                    .collect();
+                json!({"type": "Delete", "targets": targets})
+            }
+            Node::For {
+                target,
+                iter,
+                body,
+                or_else,
+            } => json!({
+                "type": "For",
+                "target": self.unpack_target(target),
+                "iter": self.expr(iter),
+                "body": self.nodes(body),
+                "orelse": self.nodes(or_else),
+            }),
+            Node::While { test, body, or_else } => json!({
+                "type": "While",
+                "test": self.expr(test),
+                "body": self.nodes(body),
+                "orelse": self.nodes(or_else),
+            }),
+            Node::Break { position } => json!({"type": "Break", "span": span(*position)}),
+            Node::Continue { position } => json!({"type": "Continue", "span": span(*position)}),
+            Node::If { test, body, or_else } => json!({
+                "type": "If",
+                "test": self.expr(test),
+                "body": self.nodes(body),
+                "orelse": self.nodes(or_else),
+            }),
+            Node::FunctionDef(func_def) => self.function_def(func_def),
+            Node::Global { position, names } => {
+                json!({"type": "Global", "names": self.strs(names), "span": span(*position)})
+            }
+            Node::Nonlocal { position, names } => {
+                json!({"type": "Nonlocal", "names": self.strs(names), "span": span(*position)})
+            }
+            Node::Try(Try {
+                body,
+                handlers,
+                or_else,
+                finally,
+            }) => json!({
+                "type": "Try",
+                "body": self.nodes(body),
+                "handlers": handlers.iter().map(|handler| self.except_handler(handler)).collect::<Vec<_>>(),
+                "orelse": self.nodes(or_else),
+                "finalbody": self.nodes(finally),
+            }),
+            Node::Import { module_name, binding } => json!({
+                "type": "Import",
+                "module": self.str(*module_name),
+                "target": self.name(binding),
+            }),
+            Node::ImportFrom {
+                module_name,
+                names,
+                position,
+            } => {
+                let names: Vec<_> = names
+                    .iter()
+                    .map(|(name, binding)| json!({"name": self.str(*name), "target": self.name(binding)}))
+                    .collect();
+                json!({
+                    "type": "ImportFrom",
+                    "module": self.str(*module_name),
+                    "names": names,
+                    "span": span(*position),
+                })
+            }
+        }
+    }
+
+    fn assign(&self, targets: Vec<JsonValue>, value: &ExprLoc) -> JsonValue {
+        let mut assign = json!({"type": "Assign", "value": self.expr(value)});
+        assign["targets"] = targets.into();
+        assign
+    }
+
+    fn assign_target(&self, target: &AssignTarget) -> JsonValue {
+        match target {
+            AssignTarget::Unpack(target) => self.unpack_target(target),
+            AssignTarget::Subscript {
+                target,
+                index,
+                target_position,
+            } => self.subscript(self.name(target), index, *target_position),
+            AssignTarget::Attr {
+                object,
+                attr,
+                target_position,
+            } => self.attribute(self.expr(object), attr, Some(*target_position)),
+        }
+    }
+
+    fn unpack_target(&self, target: &UnpackTarget) -> JsonValue {
+        match target {
+            UnpackTarget::Name(name) => self.name(name),
+            UnpackTarget::Tuple { targets, position } => self.unpack_tuple(targets, *position),
+            UnpackTarget::Starred(name) => json!({"type": "Starred", "value": self.name(name)}),
+        }
+    }
+
+    fn unpack_tuple(&self, targets: &[UnpackTarget], position: CodeRange) -> JsonValue {
+        let elts: Vec<_> = targets.iter().map(|target| self.unpack_target(target)).collect();
+        json!({"type": "Tuple", "elts": elts, "span": span(position)})
+    }
+
+    fn except_handler(&self, handler: &ExceptHandler<PreparedNode>) -> JsonValue {
+        json!({
+            "type": "ExceptHandler",
+            "exc_type": self.opt_expr(handler.exc_type.as_ref()),
+            "name": handler.name.as_ref().map(|name| self.name(name)),
+            "body": self.nodes(&handler.body),
+        })
+    }
+
+    fn function_def(&self, func_def: &PreparedFunctionDef) -> JsonValue {
+        json!({
+            "type": "FunctionDef",
+            "name": self.name(&func_def.name),
+            "is_async": func_def.is_async,
+            "params": self.params(func_def),
+            "decorator_list": self.exprs(&func_def.decorators),
+            "body": self.in_function().nodes(&func_def.body),
+            "slot_names": self.strs(&func_def.slot_names),
+        })
+    }
+
+    /// Returns each parameter with its kind and default value.
+    fn params(&self, func_def: &PreparedFunctionDef) -> Vec<JsonValue> {
+        func_def
+            .signature
+            .params()
+            .into_iter()
+            .map(|(name, kind, default)| {
+                let kind = match kind {
+                    ParamKind::PositionalOnly => "positional_only",
+                    ParamKind::PositionalOrKeyword => "positional_or_keyword",
+                    ParamKind::VarPositional => "var_positional",
+                    ParamKind::KeywordOnly => "keyword_only",
+                    ParamKind::VarKeyword => "var_keyword",
+                };
+                json!({
+                    "name": self.str(name),
+                    "kind": kind,
+                    "default": self.opt_expr(default.map(|index| &func_def.default_exprs[index])),
+                })
+            })
+            .collect()
+    }
+
+    fn exprs(&self, exprs: &[ExprLoc]) -> Vec<JsonValue> {
+        exprs.iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    fn opt_expr(&self, expr: Option<&ExprLoc>) -> JsonValue {
+        expr.map_or(JsonValue::Null, |expr| self.expr(expr))
+    }
+
+    fn expr(&self, expr_loc: &ExprLoc) -> JsonValue {
+        let mut value = self.expr_without_span(&expr_loc.expr);
+        if let JsonValue::Object(object) = &mut value
+            && !object.contains_key("span")
+        {
+            object.insert("span".to_owned(), span(expr_loc.position));
+        }
+        value
+    }
+
+    /// Converts an expression, only adding a span if the expression has one of its own.
+    fn expr_without_span(&self, expr: &Expr) -> JsonValue {
+        match expr {
+            Expr::Literal(literal) => self.literal(*literal),
+            Expr::Builtin(builtin) => self.builtin(*builtin),
+            Expr::Name(name) => self.name(name),
+            Expr::Call { callable, args } => {
+                let func = match callable {
+                    Callable::Builtin(builtin) => self.builtin(*builtin),
+                    Callable::Name(name) => self.name(name),
+                };
+                self.call(func, args)
+            }
+            Expr::AttrCall { object, attr, args } => self.call(self.attribute(self.expr(object), attr, None), args),
+            Expr::IndirectCall { callable, args } => self.call(self.expr(callable), args),
+            Expr::AttrGet { object, attr } => self.attribute(self.expr(object), attr, None),
+            Expr::Op { left, op, right } => bin_op(self.expr(left), op, self.expr(right)),
+            Expr::CmpOp {
+                left,
+                op: CmpOperator::ModEq(value),
+                right,
+            } => {
+                // prepared from `left % right == value`
+                let modulo = bin_op(self.expr(left), &Operator::Mod, self.expr(right));
+                json!({
+                    "type": "Compare",
+                    "left": modulo,
+                    "ops": ["Eq"],
+                    "comparators": [self.literal(Literal::Int(*value))],
+                })
+            }
+            Expr::CmpOp { left, op, right } => json!({
+                "type": "Compare",
+                "left": self.expr(left),
+                "ops": [cmp_operator_name(op)],
+                "comparators": [self.expr(right)],
+            }),
+            Expr::ChainCmp { left, comparisons } => {
+                let ops: Vec<_> = comparisons.iter().map(|(op, _)| cmp_operator_name(op)).collect();
+                let comparators: Vec<_> = comparisons.iter().map(|(_, right)| self.expr(right)).collect();
+                json!({
+                    "type": "Compare",
+                    "left": self.expr(left),
+                    "ops": ops,
+                    "comparators": comparators,
+                })
+            }
+            Expr::List(elts) => json!({"type": "List", "elts": self.exprs(elts)}),
+            Expr::Tuple(elts) => json!({"type": "Tuple", "elts": self.exprs(elts)}),
+            Expr::Subscript { object, index } => json!({
+                "type": "Subscript",
+                "value": self.expr(object),
+                "slice": self.expr(index),
+            }),
+            Expr::Slice { lower, upper, step } => json!({
+                "type": "Slice",
+                "lower": self.opt_expr(lower.as_deref()),
+                "upper": self.opt_expr(upper.as_deref()),
+                "step": self.opt_expr(step.as_deref()),
+            }),
+            Expr::Dict(items) => {
+                let keys: Vec<_> = items.iter().map(|(key, _)| self.expr(key)).collect();
+                let values: Vec<_> = items.iter().map(|(_, value)| self.expr(value)).collect();
+                json!({"type": "Dict", "keys": keys, "values": values})
+            }
+            Expr::Set(elts) => json!({"type": "Set", "elts": self.exprs(elts)}),
+            Expr::Not(operand) => self.unary_op("Not", operand),
+            Expr::UnaryMinus(operand) => self.unary_op("USub", operand),
+            Expr::UnaryPlus(operand) => self.unary_op("UAdd", operand),
+            Expr::UnaryInvert(operand) => self.unary_op("Invert", operand),
+            Expr::Await(value) => json!({"type": "Await", "value": self.expr(value)}),
+            Expr::ExcClass { class_id, base } => json!({
+                "type": "ExcClass",
+                "name": self.str(self.interns.get_exc_class(*class_id).name),
+                "base": self.expr(base),
+            }),
+            Expr::FString(parts) => self.fstring(parts),
+            Expr::IfElse { test, body, orelse } => json!({
+                "type": "IfExp",
+                "test": self.expr(test),
+                "body": self.expr(body),
+                "orelse": self.expr(orelse),
+            }),
+            Expr::ListComp { elt, generators } => json!({
+                "type": "ListComp",
+                "elt": self.expr(elt),
+                "generators": self.generators(generators),
+            }),
+            Expr::SetComp { elt, generators } => json!({
+                "type": "SetComp",
+                "elt": self.expr(elt),
+                "generators": self.generators(generators),
+            }),
+            Expr::DictComp { key, value, generators } => json!({
+                "type": "DictComp",
+                "key": self.expr(key),
+                "value": self.expr(value),
+                "generators": self.generators(generators),
+            }),
+            Expr::LambdaRaw { .. } => unreachable!("Expr::LambdaRaw should not exist after prepare phase"),
+            Expr::Lambda { func_def } => {
+                // the body is prepared as `return <expression>`
+                let writer = self.in_function();
+                let body = match func_def.body.as_slice() {
+                    [Node::Return(body)] => writer.expr(body),
+                    body => JsonValue::Array(writer.nodes(body)),
+                };
+                json!({
+                    "type": "Lambda",
+                    "params": self.params(func_def),
+                    "body": body,
+                    "slot_names": self.strs(&func_def.slot_names),
+                })
+            }
+            Expr::Named { target, value } => json!({
+                "type": "NamedExpr",
+                "target": self.name(target),
+                "value": self.expr(value),
+            }),
+        }
+    }
+
+    fn call(&self, func: JsonValue, args: &ArgExprs) -> JsonValue {
+        let mut positional = Vec::new();
+        let mut keywords = Vec::new();
+        let kwargs_json = |kwargs: &[Kwarg]| -> Vec<JsonValue> {
+            kwargs
+                .iter()
+                .map(|kwarg| json!({"arg": self.str(kwarg.key.name_id), "value": self.expr(&kwarg.value)}))
+                .collect()
+        };
+        match args {
+            ArgExprs::Empty => {}
+            ArgExprs::One(arg) => positional.push(self.expr(arg)),
+            ArgExprs::Two(first, second) => positional.extend([self.expr(first), self.expr(second)]),
+            ArgExprs::Args(args) => positional = self.exprs(args),
+            ArgExprs::Kwargs(kwargs) => keywords = kwargs_json(kwargs),
+            ArgExprs::ArgsKargs {
+                args,
+                var_args,
+                kwargs,
+                var_kwargs,
+            } => {
+                positional = args.as_deref().map(|args| self.exprs(args)).unwrap_or_default();
+                positional.extend(var_args.iter().map(|arg| match arg {
+                    StarredArg::Value(value) => self.expr(value),
+                    StarredArg::Unpack(value) => json!({"type": "Starred", "value": self.expr(value)}),
+                }));
+                keywords = kwargs.as_deref().map(kwargs_json).unwrap_or_default();
+                keywords.extend(
+                    var_kwargs
+                        .iter()
+                        .map(|value| json!({"arg": null, "value": self.expr(value)})),
+                );
+            }
+        }
+        let mut call = json!({"type": "Call", "args": positional, "keywords": keywords});
+        call["func"] = func;
+        call
+    }
+
+    fn attribute(&self, value: JsonValue, attr: &EitherStr, position: Option<CodeRange>) -> JsonValue {
+        let mut attribute = json!({"type": "Attribute", "attr": attr.as_str(self.interns)});
+        attribute["value"] = value;
+        if let Some(position) = position {
+            attribute["span"] = span(position);
+        }
+        attribute
+    }
+
+    fn subscript(&self, value: JsonValue, index: &ExprLoc, position: CodeRange) -> JsonValue {
+        json!({"type": "Subscript", "value": value, "slice": self.expr(index), "span": span(position)})
+    }
+
+    fn unary_op(&self, op: &str, operand: &ExprLoc) -> JsonValue {
+        json!({"type": "UnaryOp", "op": op, "operand": self.expr(operand)})
+    }
+
+    fn generators(&self, generators: &[Comprehension]) -> Vec<JsonValue> {
+        generators
+            .iter()
+            .map(|generator| {
+                json!({
+                    "type": "comprehension",
+                    "target": self.unpack_target(&generator.target),
+                    "iter": self.expr(&generator.iter),
+                    "ifs": self.exprs(&generator.ifs),
+                })
+            })
+            .collect()
+    }
+
+    fn fstring(&self, parts: &[FStringPart]) -> JsonValue {
+        let values: Vec<_> = parts
+            .iter()
+            .map(|part| match part {
+                FStringPart::Literal(text) => json!({"type": "Constant", "kind": "str", "value": self.str(*text)}),
+                FStringPart::Interpolation {
+                    expr,
+                    conversion,
+                    format_spec,
+                    debug_prefix,
+                } => {
+                    let conversion = match conversion {
+                        ConversionFlag::None => JsonValue::Null,
+                        ConversionFlag::Str => "s".into(),
+                        ConversionFlag::Repr => "r".into(),
+                        ConversionFlag::Ascii => "a".into(),
+                    };
+                    let format_spec = match format_spec {
+                        None => JsonValue::Null,
+                        Some(FormatSpec::Static(spec)) => json!({
+                            "type": "JoinedStr",
+                            "values": [{"type": "Constant", "kind": "str", "value": format_spec_str(spec)}],
+                        }),
+                        Some(FormatSpec::Dynamic(parts)) => self.fstring(parts),
+                    };
+                    json!({
+                        "type": "FormattedValue",
+                        "value": self.expr(expr),
+                        "conversion": conversion,
+                        "format_spec": format_spec,
+                        "debug_text": debug_prefix.map(|prefix| self.str(prefix)),
+                    })
+                }
+            })
+            .collect();
+        json!({"type": "JoinedStr", "values": values})
+    }
+
+    fn literal(&self, literal: Literal) -> JsonValue {
+        let (kind, value) = match literal {
+            Literal::Ellipsis => ("ellipsis", JsonValue::Null),
+            Literal::None => ("none", JsonValue::Null),
+            Literal::Bool(value) => ("bool", value.into()),
+            Literal::Int(value) => ("int", value.into()),
+            Literal::Float(value) => ("float", value.into()),
+            Literal::Str(id) => ("str", self.str(id).into()),
+            Literal::Bytes(id) => ("bytes", self.interns.get_bytes(id).into()),
+            // too big for a JSON number in most parsers
+            Literal::LongInt(id) => ("int", self.interns.get_long_int(id).to_string().into()),
+            Literal::Marker(marker) => ("marker", <&str>::from(marker.0).into()),
+        };
+        json!({"type": "Constant", "kind": kind, "value": value})
+    }
+
+    /// Converts a builtin to a `Name` in the `"builtin"` scope, or `"exception_class"` for
+    /// exception classes defined by the code.
+    fn builtin(&self, builtin: Builtins) -> JsonValue {
+        let (id, scope) = match builtin {
+            Builtins::Function(function) => (function.to_string(), "builtin"),
+            Builtins::ExcType(exc_type) => (exc_type.to_string(), "builtin"),
+            Builtins::Type(t) => {
+                let name = BUILTIN_TYPES
+                    .iter()
+                    .find(|&&(_, builtin_type)| builtin_type == t)
+                    .map_or_else(|| t.to_string(), |(name, _)| (*name).to_owned());
+                (name, "builtin")
+            }
+            Builtins::ExcClass(class_id) => (
+                self.str(self.interns.get_exc_class(class_id).name).to_owned(),
+                "exception_class",
+            ),
+        };
+        json!({"type": "Name", "id": id, "scope": scope, "slot": null})
+    }
+
+    fn name(&self, name: &Identifier) -> JsonValue {
+        let scope = match name.scope {
+            _ if self.undefined.contains(&name.position) => "undefined",
+            NameScope::Local | NameScope::LocalUnassigned if self.at_module => "global",
+            NameScope::Local => "local",
+            NameScope::LocalUnassigned => "undefined",
+            NameScope::Global => "global",
+            NameScope::Cell => "cell",
+        };
+        json!({
+            "type": "Name",
+            "id": self.str(name.name_id),
+            "scope": scope,
+            "slot": name.opt_namespace_id().map(|slot| slot.index()),
+            "span": span(name.position),
+        })
+    }
+
+    /// Returns a writer for the body of a function defined here.
+    fn in_function(self) -> Self {
+        Self {
+            at_module: false,
+            ..self
+        }
+    }
+
+    fn str(&self, id: StringId) -> &str {
+        self.interns.get_str(id)
+    }
+
+    fn strs(&self, ids: &[StringId]) -> Vec<&str> {
+        ids.iter().map(|&id| self.str(id)).collect()
+    }
+}
+
+/// Converts a binary operation, using `BoolOp` for `and` and `or` like Python's `ast`.
+fn bin_op(left: JsonValue, op: &Operator, right: JsonValue) -> JsonValue {
+    let mut value = json!({"op": operator_name(op)});
+    if matches!(op, Operator::And | Operator::Or) {
+        value["type"] = "BoolOp".into();
+        value["values"] = JsonValue::Array(vec![left, right]);
+    } else {
+        value["type"] = "BinOp".into();
+        value["left"] = left;
+        value["right"] = right;
+    }
+    value
+}
+
+/// Returns the start and end of a range as 1-based lines and columns.
+fn span(position: CodeRange) -> JsonValue {
+    let (start, end) = (position.start(), position.end());
+    json!({
+        "start": {"line": start.line, "column": start.column},
+        "end": {"line": end.line, "column": end.column},
+    })
+}
+
+/// Returns the name of the operator in Python's `ast` module.
+fn operator_name(op: &Operator) -> &'static str {
+    match op {
+        Operator::Add => "Add",
+        Operator::Sub => "Sub",
+        Operator::Mult => "Mult",
+        Operator::MatMult => "MatMult",
+        Operator::Div => "Div",
+        Operator::Mod => "Mod",
+        Operator::Pow => "Pow",
+        Operator::LShift => "LShift",
+        Operator::RShift => "RShift",
+        Operator::BitOr => "BitOr",
+        Operator::BitXor => "BitXor",
+        Operator::BitAnd => "BitAnd",
+        Operator::FloorDiv => "FloorDiv",
+        Operator::And => "And",
+        Operator::Or => "Or",
+    }
+}
+
+/// Returns the name of the comparison in Python's `ast` module.
+fn cmp_operator_name(op: &CmpOperator) -> &'static str {
+    match op {
+        CmpOperator::Eq | CmpOperator::ModEq(_) => "Eq",
+        CmpOperator::NotEq => "NotEq",
+        CmpOperator::Lt => "Lt",
+        CmpOperator::LtE => "LtE",
+        CmpOperator::Gt => "Gt",
+        CmpOperator::GtE => "GtE",
+        CmpOperator::Is => "Is",
+        CmpOperator::IsNot => "IsNot",
+        CmpOperator::In => "In",
+        CmpOperator::NotIn => "NotIn",
+    }
+}
+
+/// Writes a parsed format spec back out, e.g. `>10.2f`.
+fn format_spec_str(spec: &ParsedFormatSpec) -> String {
+    let mut text = String::new();
+    if let Some(align) = spec.align {
+        if spec.fill != ' ' {
+            text.push(spec.fill);
+        }
+        text.push(align);
+    }
+    text.extend(spec.sign);
+    if spec.alternate {
+        text.push('#');
+    }
+    if spec.zero_pad {
+        text.push('0');
+    }
+    if spec.width > 0 {
+        text.push_str(&spec.width.to_string());
+    }
+    text.extend(spec.grouping);
+    if let Some(precision) = spec.precision {
+        text.push('.');
+        text.push_str(&precision.to_string());
+    }
+    text.extend(spec.type_char);
+    text
+}
@@ -15,7 +15,7 @@ use crate::{
 
 /// Implementation of the abs() builtin function.
 ///
-/// Returns the absolute value of a number. Works with integers, floats, and LongInts.
+/// Returns the absolute value of a number. Works with integers, floats, LongInts and timedeltas.
 /// For `i64::MIN`, which overflows on negation, promotes to LongInt.
 pub fn builtin_abs(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
     let value = args.get_one_arg("abs", heap)?;
@@ -37,6 +37,13 @@ pub fn builtin_abs(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> Ru
         Value::Ref(id) => {
             if let HeapData::LongInt(li) = heap.get(*id) {
                 Ok(li.abs().into_value(heap)?)
+            } else if let HeapData::TimeDelta(td) = heap.get(*id) {
+                let td = *td;
+                if td.days() < 0 {
+                    Ok(Value::Ref(heap.allocate(HeapData::TimeDelta(td.negate()?))?))
+                } else {
+                    Ok(value.clone_with_heap(heap))
+                }
             } else {
                 Err(SimpleException::new_msg(
                     ExcType::TypeError,
@@ -0,0 +1,66 @@
+//! Implementation of the dir() builtin function.
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, HeapData},
+    resource::ResourceTracker,
+    types::{List, PyTrait},
+    value::Value,
+};
+
+/// Implementation of the dir() builtin function.
+///
+/// Without an argument, returns the sorted names of the calling function's variables, or
+/// of the globals at module level. With one, returns the sorted attribute names of a
+/// module or dataclass instance; listing the attributes of other objects isn't supported.
+pub fn builtin_dir(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let mut names = match args.get_zero_one_arg("dir", vm.heap)? {
+        None => {
+            let variables = vm.local_variables();
+            let mut names = Vec::with_capacity(variables.len());
+            for (name, value) in variables {
+                value.drop_with_heap(vm.heap);
+                names.push(Value::InternString(name));
+            }
+            names
+        }
+        Some(object) => {
+            defer_drop!(object, vm);
+            attribute_names(object, vm)?
+        }
+    };
+
+    let (heap, interns) = (&*vm.heap, vm.interns);
+    names.sort_by(|a, b| a.as_str_ref(heap, interns).cmp(&b.as_str_ref(heap, interns)));
+    Ok(Value::Ref(vm.heap.allocate(HeapData::List(List::new(names)))?))
+}
+
+/// Returns the attribute names of a module or dataclass instance, unsorted.
+fn attribute_names(object: &Value, vm: &mut VM<impl ResourceTracker>) -> RunResult<Vec<Value>> {
+    if let Value::Ref(id) = object {
+        let heap = &mut *vm.heap;
+        let attrs = match heap.get(*id) {
+            HeapData::Module(module) => Some(module.attrs()),
+            HeapData::Dataclass(dataclass) => Some(dataclass.attrs()),
+            _ => None,
+        };
+        if let Some(attrs) = attrs {
+            let keys: Vec<Value> = attrs.into_iter().map(|(key, _)| key.copy_for_extend()).collect();
+            for key in &keys {
+                if let Value::Ref(id) = key {
+                    heap.inc_ref(*id);
+                }
+            }
+            return Ok(keys);
+        }
+    }
+    let type_name = object.py_type(vm.heap);
+    Err(SimpleException::new_msg(
+        ExcType::NotImplementedError,
+        format!("dir() of '{type_name}' objects is not supported"),
+    )
+    .into())
+}
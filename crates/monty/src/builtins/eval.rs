@@ -0,0 +1,154 @@
+//! Implementation of the eval() and exec() builtin functions.
+//!
+//! Both run code compiled at runtime in a new frame, so the VM calls them directly (see
+//! `VM::call_eval`); this module parses their arguments and source.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    expressions::Node,
+    heap::{DropWithHeap, Heap, HeapData},
+    intern::{InternerBuilder, Interns},
+    parse::{ParseError, ParseResult, parse_with_interner},
+    resource::ResourceTracker,
+    value::Value,
+};
+
+/// Filename reported for code run by `eval()` and `exec()`, like in CPython.
+pub(crate) const DYNAMIC_CODE_FILENAME: &str = "<string>";
+
+/// Variables passed to code run by `eval()` or `exec()`, in order.
+pub(crate) struct Variables {
+    pub names: Vec<String>,
+    pub values: Vec<Value>,
+}
+
+impl Variables {
+    /// Adds the variables of `other`, replacing the values of those already present.
+    pub fn update(&mut self, other: Self, heap: &mut Heap<impl ResourceTracker>) {
+        for (name, value) in other.names.into_iter().zip(other.values) {
+            match self.names.iter().position(|existing| *existing == name) {
+                Some(index) => std::mem::replace(&mut self.values[index], value).drop_with_heap(heap),
+                None => {
+                    self.names.push(name);
+                    self.values.push(value);
+                }
+            }
+        }
+    }
+}
+
+impl DropWithHeap for Variables {
+    fn drop_with_heap<T: ResourceTracker>(self, heap: &mut Heap<T>) {
+        self.values.drop_with_heap(heap);
+    }
+}
+
+/// The arguments of an `eval()` or `exec()` call.
+pub(crate) struct EvalArgs {
+    /// The source code to run.
+    pub source: String,
+    /// Variables of the `globals` dict, if one was passed; the code then only sees these
+    /// and the `locals`.
+    pub globals: Option<Variables>,
+    /// Variables of the `locals` dict, if one was passed.
+    pub locals: Option<Variables>,
+}
+
+/// Parses the `(source, globals=None, locals=None)` arguments of `eval()` or `exec()`.
+///
+/// The source can be a `str` or UTF-8 `bytes`. Dict entries with a key that isn't a string,
+/// or named `__builtins__`, are ignored.
+pub(crate) fn eval_args(
+    name: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<EvalArgs> {
+    let args = args.bind(name, ["source", "globals", "locals"], 1, heap, interns)?;
+    defer_drop!(args, heap);
+    let [Some(source), globals, locals] = args else {
+        unreachable!("source is a required argument")
+    };
+
+    let source = if let Some(source) = source.as_str_ref(heap, interns) {
+        source.to_owned()
+    } else if let Some(bytes) = source.as_bytes_like(heap, interns) {
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| SimpleException::new_msg(ExcType::SyntaxError, "source code cannot be decoded as UTF-8"))?
+    } else {
+        return Err(ExcType::type_error(format!(
+            "{name}() arg 1 must be a string, bytes or code object"
+        )));
+    };
+
+    let globals = match globals {
+        None | Some(Value::None) => None,
+        Some(value) => Some(dict_variables(value, heap, interns).ok_or_else(|| {
+            ExcType::type_error(format!("{name}() globals must be a dict, not {}", value.py_type(heap)))
+        })?),
+    };
+    let locals = match locals {
+        None | Some(Value::None) => None,
+        Some(value) => match dict_variables(value, heap, interns) {
+            Some(locals) => Some(locals),
+            None => {
+                globals.drop_with_heap(heap);
+                return Err(ExcType::type_error(format!(
+                    "{name}() locals must be a dict, not {}",
+                    value.py_type(heap)
+                )));
+            }
+        },
+    };
+    Ok(EvalArgs {
+        source,
+        globals,
+        locals,
+    })
+}
+
+/// Returns the variables in `value` if it is a dict, cloning the values.
+fn dict_variables(value: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Option<Variables> {
+    let Value::Ref(id) = value else { return None };
+    let HeapData::Dict(dict) = heap.get(*id) else {
+        return None;
+    };
+    let (names, values): (Vec<String>, Vec<Value>) = dict
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.as_str_ref(heap, interns)?;
+            (name != "__builtins__").then(|| (name.to_owned(), value.copy_for_extend()))
+        })
+        .unzip();
+    for value in &values {
+        if let Value::Ref(id) = value {
+            heap.inc_ref(*id);
+        }
+    }
+    Some(Variables { names, values })
+}
+
+/// Parses source code passed to `eval()` or `exec()`, seeding the interner from `interns`
+/// so existing ids stay valid.
+///
+/// `eval()` source must be a single expression, which is turned into a `return`. Like in
+/// CPython, leading spaces and tabs are ignored for `eval()` only.
+pub(crate) fn parse_source(source: &str, is_eval: bool, interns: &Interns) -> RunResult<ParseResult> {
+    let source = if is_eval {
+        source.trim_start_matches([' ', '\t'])
+    } else {
+        source
+    };
+    let interner = InternerBuilder::from_interns(interns, source);
+    let mut parsed =
+        parse_with_interner(source, DYNAMIC_CODE_FILENAME, interner).map_err(ParseError::into_run_error)?;
+    if is_eval {
+        match parsed.nodes.pop() {
+            Some(Node::Expr(expr)) if parsed.nodes.is_empty() => parsed.nodes.push(Node::Return(expr)),
+            _ => return Err(SimpleException::new_msg(ExcType::SyntaxError, "invalid syntax").into()),
+        }
+    }
+    Ok(parsed)
+}
@@ -0,0 +1,65 @@
+//! Implementation of the filter() builtin function.
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::RunResult,
+    heap::{HeapData, HeapGuard},
+    resource::ResourceTracker,
+    types::{List, MontyIter, PyTrait, iter::get_iter},
+    value::Value,
+};
+
+/// Implementation of the filter() builtin function.
+///
+/// Returns an iterator over the items of the iterable for which the function returns
+/// a truthy value. If the function is `None`, the items themselves are tested.
+///
+/// Note: In Python the function is applied lazily as the iterator is advanced. Iterators
+/// can't call back into the VM, so, like `map()`, we test every item up front and return
+/// an iterator over the items that passed.
+///
+/// Examples:
+/// ```python
+/// list(filter(None, [0, 1, '', 'a']))       # [1, 'a']
+/// list(filter(lambda x: x > 1, [1, 2, 3])) # [2, 3]
+/// ```
+pub fn builtin_filter(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let (function, iterable) = args.get_two_args("filter", vm.heap)?;
+    defer_drop!(function, vm);
+
+    let iter = MontyIter::new(iterable, vm.heap, vm.interns)?;
+    defer_drop_mut!(iter, vm);
+
+    let out: Vec<Value> = Vec::with_capacity(iter.size_hint(vm.heap));
+    let mut out_guard = HeapGuard::new(out, vm);
+    let (out, vm) = out_guard.as_parts_mut();
+
+    while let Some(item) = iter.for_next(vm.heap, vm.interns)? {
+        let keep = if matches!(function, Value::None) {
+            item.py_bool(vm.heap, vm.interns)
+        } else {
+            let result = match vm.evaluate_function("filter()", function, ArgValues::One(item.clone_with_heap(vm.heap)))
+            {
+                Ok(result) => result,
+                Err(err) => {
+                    item.drop_with_heap(vm.heap);
+                    return Err(err);
+                }
+            };
+            let keep = result.py_bool(vm.heap, vm.interns);
+            result.drop_with_heap(vm.heap);
+            keep
+        };
+        if keep {
+            out.push(item);
+        } else {
+            item.drop_with_heap(vm.heap);
+        }
+    }
+
+    let (out, vm) = out_guard.into_parts();
+    let list_id = vm.heap.allocate(HeapData::List(List::new(out)))?;
+    get_iter(Value::Ref(list_id), vm.heap, vm.interns)
+}
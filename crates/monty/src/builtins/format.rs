@@ -0,0 +1,38 @@
+//! Implementation of the format() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    fstring::{ConversionFlag, format_value_with_spec_str},
+    heap::Heap,
+    intern::Interns,
+    resource::ResourceTracker,
+    types::{PyTrait, str::allocate_string},
+    value::Value,
+};
+
+/// Implementation of the format() builtin function.
+///
+/// Converts a value to a formatted string using the format spec mini-language,
+/// exactly as an f-string replacement field `{value:format_spec}` would.
+/// The format spec defaults to the empty string, which is equivalent to `str(value)`.
+pub fn builtin_format(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let (value, format_spec) = args.get_one_two_args("format", heap)?;
+    defer_drop!(value, heap);
+    defer_drop!(format_spec, heap);
+
+    let spec = match format_spec {
+        None => String::new(),
+        Some(spec) => match spec.as_either_str(heap) {
+            Some(spec) => spec.as_str(interns).to_owned(),
+            None => {
+                let t = spec.py_type(heap);
+                return Err(ExcType::type_error(format!("format() argument 2 must be str, not {t}")));
+            }
+        },
+    };
+
+    let formatted = format_value_with_spec_str(value, ConversionFlag::None, &spec, heap, interns)?;
+    allocate_string(formatted, heap)
+}
@@ -0,0 +1,36 @@
+//! Implementation of the globals() and locals() builtin functions.
+
+use crate::{
+    args::ArgValues, bytecode::VM, exception_private::RunResult, heap::HeapData, intern::StringId,
+    resource::ResourceTracker, types::Dict, value::Value,
+};
+
+/// Implementation of the globals() builtin function.
+///
+/// Returns a new dict of the bound global variables of the calling code. Unlike CPython
+/// the dict is a copy, so assigning to it doesn't create or change variables.
+pub fn builtin_globals(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    args.check_zero_args("globals", vm.heap)?;
+    let variables = vm.global_variables();
+    variables_dict(variables, vm)
+}
+
+/// Implementation of the locals() builtin function.
+///
+/// Returns a new dict of the bound variables of the calling function, including variables
+/// captured from enclosing functions. At module level this is the same as `globals()`.
+pub fn builtin_locals(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    args.check_zero_args("locals", vm.heap)?;
+    let variables = vm.local_variables();
+    variables_dict(variables, vm)
+}
+
+/// Builds a dict mapping variable names to values, taking ownership of the values.
+fn variables_dict(variables: Vec<(StringId, Value)>, vm: &mut VM<impl ResourceTracker>) -> RunResult<Value> {
+    let pairs = variables
+        .into_iter()
+        .map(|(name, value)| (Value::InternString(name), value))
+        .collect();
+    let dict = Dict::from_pairs(pairs, vm.heap, vm.interns)?;
+    Ok(Value::Ref(vm.heap.allocate(HeapData::Dict(dict))?))
+}
@@ -0,0 +1,34 @@
+//! Implementation of the input() builtin function.
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::RunResult,
+    heap::Heap,
+    intern::Interns,
+    io::PrintWriter,
+    resource::{DepthGuard, ResourceTracker},
+    types::PyTrait,
+};
+
+/// Writes the prompt for the input() builtin function.
+///
+/// Reading a line is host I/O, so `input()` can't produce its result here: after the
+/// prompt is written the VM yields an `OsFunction::Input` call, and the host resumes
+/// execution with the line it read (without the trailing newline).
+pub fn write_input_prompt(
+    heap: &mut Heap<impl ResourceTracker>,
+    args: ArgValues,
+    interns: &Interns,
+    print: &mut PrintWriter<'_>,
+) -> RunResult<()> {
+    let Some(prompt) = args.get_zero_one_arg("input", heap)? else {
+        return Ok(());
+    };
+    defer_drop!(prompt, heap);
+    let mut guard = DepthGuard::default();
+    print.stdout_write(prompt.py_str(heap, &mut guard, interns))?;
+    // the prompt is complete output on its own, e.g. a separate `PrintEvent`
+    print.print_done()?;
+    Ok(())
+}
@@ -4,8 +4,9 @@ use super::Builtins;
 use crate::{
     args::ArgValues,
     defer_drop,
-    exception_private::{ExcType, RunResult},
+    exception_private::{ExcType, RunError, RunResult},
     heap::{Heap, HeapData},
+    intern::ExcClassId,
     resource::ResourceTracker,
     types::{PyTrait, Type},
     value::Value,
@@ -20,24 +21,38 @@ pub fn builtin_isinstance(heap: &mut Heap<impl ResourceTracker>, args: ArgValues
     defer_drop!(classinfo, heap);
 
     let obj_type = obj.py_type(heap);
+    let obj_class = match obj {
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Exception(exc) => exc.class_id(),
+            _ => None,
+        },
+        _ => None,
+    };
 
-    match isinstance_check(obj_type, classinfo, heap) {
-        Ok(result) => Ok(Value::Bool(result)),
-        Err(()) => Err(ExcType::isinstance_arg2_error()),
-    }
+    let result = isinstance_check(obj_type, obj_class, classinfo, heap, ExcType::isinstance_arg2_error)?;
+    Ok(Value::Bool(result))
 }
 
 /// Recursively checks if obj_type matches classinfo for isinstance().
 ///
 /// Returns `Ok(true)` if the type matches, `Ok(false)` if it doesn't,
-/// or `Err(())` if classinfo is invalid (not a type or tuple of types).
+/// or the error built by `invalid` if classinfo is invalid (not a type or tuple of types).
 ///
 /// Supports:
 /// - Single types: `isinstance(x, int)`
 /// - Exception types: `isinstance(err, ValueError)`
 /// - Exception hierarchy: `isinstance(err, LookupError)` for KeyError/IndexError
+/// - User-defined exception classes: `isinstance(err, MyError)`
 /// - Nested tuples: `isinstance(x, (int, (str, bytes)))`
-fn isinstance_check(obj_type: Type, classinfo: &Value, heap: &Heap<impl ResourceTracker>) -> Result<bool, ()> {
+///
+/// Also used by `contextlib.suppress` to match exception classes.
+pub(crate) fn isinstance_check(
+    obj_type: Type,
+    obj_class: Option<ExcClassId>,
+    classinfo: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    invalid: fn() -> RunError,
+) -> RunResult<bool> {
     match classinfo {
         // Single type: isinstance(x, int)
         Value::Builtin(Builtins::Type(t)) => Ok(obj_type.is_instance_of(*t)),
@@ -48,19 +63,25 @@ fn isinstance_check(obj_type: Type, classinfo: &Value, heap: &Heap<impl Resource
             Ok(matches!(obj_type, Type::Exception(exc_type) if exc_type.is_subclass_of(*handler_type)))
         }
 
+        // User-defined exception class: isinstance(err, MyError)
+        Value::Builtin(Builtins::ExcClass(class_id)) => match obj_class {
+            Some(own) => heap.exc_class_is_subclass_of(own, *class_id),
+            None => Ok(false),
+        },
+
         // Tuple of types (possibly nested): isinstance(x, (int, (str, bytes)))
         Value::Ref(id) => {
             if let HeapData::Tuple(tuple) = heap.get(*id) {
                 for v in tuple.as_slice() {
-                    if isinstance_check(obj_type, v, heap)? {
+                    if isinstance_check(obj_type, obj_class, v, heap, invalid)? {
                         return Ok(true);
                     }
                 }
                 Ok(false)
             } else {
-                Err(()) // Not a tuple - invalid
+                Err(invalid()) // Not a tuple - invalid
             }
         }
-        _ => Err(()), // Invalid classinfo
+        _ => Err(invalid()), // Invalid classinfo
     }
 }
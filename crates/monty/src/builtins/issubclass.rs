@@ -0,0 +1,38 @@
+//! Implementation of the issubclass() builtin function.
+
+use super::{Builtins, isinstance_check};
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::Heap,
+    resource::ResourceTracker,
+    types::Type,
+    value::Value,
+};
+
+/// Implementation of the issubclass() builtin function.
+///
+/// Checks if a class is a subclass of a class or a tuple of classes. Every class
+/// counts as a subclass of itself, and `bool` is a subclass of `int`.
+///
+/// An instance of `cls` would be an instance of `classinfo` exactly when `cls` is a
+/// subclass of it, so this reuses the `isinstance()` check with the class's type.
+pub fn builtin_issubclass(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let (cls, classinfo) = args.get_two_args("issubclass", heap)?;
+    defer_drop!(cls, heap);
+    defer_drop!(classinfo, heap);
+
+    let (cls_type, cls_class) = match cls {
+        Value::Builtin(Builtins::Type(t)) => (*t, None),
+        Value::Builtin(Builtins::ExcType(exc_type)) => (Type::Exception(*exc_type), None),
+        Value::Builtin(Builtins::ExcClass(class_id)) => {
+            let base = heap.exc_class(*class_id)?.base;
+            (Type::Exception(base), Some(*class_id))
+        }
+        _ => return Err(ExcType::issubclass_arg1_error()),
+    };
+
+    let result = isinstance_check(cls_type, cls_class, classinfo, heap, ExcType::issubclass_arg2_error)?;
+    Ok(Value::Bool(result))
+}
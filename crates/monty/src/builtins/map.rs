@@ -7,23 +7,25 @@ use crate::{
     exception_private::{ExcType, RunResult, SimpleException},
     heap::{DropWithHeap, HeapData},
     resource::ResourceTracker,
-    types::{List, MontyIter},
+    types::{List, MontyIter, iter::get_iter},
     value::Value,
 };
 
 /// Implementation of the map() builtin function.
 ///
-/// Applies a function to every item of one or more iterables and returns a list of results.
+/// Applies a function to every item of one or more iterables and returns an iterator over the results.
 /// With multiple iterables, stops when the shortest iterable is exhausted.
 ///
-/// Note: In Python this returns an iterator, but we return a list for simplicity.
+/// Note: In Python the function is applied lazily as the iterator is advanced. Iterators
+/// can't call back into the VM, so we apply it to every item up front and return an
+/// iterator over the collected results.
 /// Note: The `strict=` parameter is not yet supported.
 ///
 /// Examples:
 /// ```python
-/// map(abs, [-1, 0, 1, 2])           # [1, 0, 1, 2]
-/// map(pow, [2, 3], [3, 2])          # [8, 9]
-/// map(str, [1, 2, 3])               # ['1', '2', '3']
+/// list(map(abs, [-1, 0, 1, 2]))     # [1, 0, 1, 2]
+/// list(map(pow, [2, 3], [3, 2]))    # [8, 9]
+/// list(map(str, [1, 2, 3]))         # ['1', '2', '3']
 /// ```
 pub fn builtin_map(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
     let (positional, kwargs) = args.into_parts();
@@ -93,6 +95,6 @@ pub fn builtin_map(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunRes
         },
     }
 
-    let heap_id = vm.heap.allocate(HeapData::List(List::new(out)))?;
-    Ok(Value::Ref(heap_id))
+    let list_id = vm.heap.allocate(HeapData::List(List::new(out)))?;
+    get_iter(Value::Ref(list_id), vm.heap, vm.interns)
 }
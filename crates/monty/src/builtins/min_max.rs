@@ -3,10 +3,11 @@
 use std::cmp::Ordering;
 
 use crate::{
-    args::ArgValues,
-    defer_drop_mut,
+    args::{ArgValues, KwargsValues},
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunError, RunResult, SimpleException},
-    heap::{Heap, HeapGuard},
+    heap::{DropWithHeap, Heap, HeapGuard},
     intern::Interns,
     resource::{DepthGuard, ResourceTracker},
     types::{MontyIter, PyTrait},
@@ -17,104 +18,197 @@ use crate::{
 ///
 /// Returns the smallest item in an iterable or the smallest of two or more arguments.
 /// Supports two forms:
-/// - `min(iterable)` - returns smallest item from iterable
-/// - `min(arg1, arg2, ...)` - returns smallest of the arguments
-pub fn builtin_min(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
-    builtin_min_max(heap, args, interns, true)
+/// - `min(iterable, *, key=None, default=...)` - returns smallest item from iterable,
+///   or `default` if the iterable is empty
+/// - `min(arg1, arg2, *args, key=None)` - returns smallest of the arguments
+pub fn builtin_min(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    builtin_min_max(vm, args, true)
 }
 
 /// Implementation of the max() builtin function.
 ///
 /// Returns the largest item in an iterable or the largest of two or more arguments.
 /// Supports two forms:
-/// - `max(iterable)` - returns largest item from iterable
-/// - `max(arg1, arg2, ...)` - returns largest of the arguments
-pub fn builtin_max(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
-    builtin_min_max(heap, args, interns, false)
+/// - `max(iterable, *, key=None, default=...)` - returns largest item from iterable,
+///   or `default` if the iterable is empty
+/// - `max(arg1, arg2, *args, key=None)` - returns largest of the arguments
+pub fn builtin_max(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    builtin_min_max(vm, args, false)
 }
 
+/// The best item seen so far, paired with its key when a key function was given.
+type Candidate = (Value, Option<Value>);
+
 /// Shared implementation for min() and max().
 ///
 /// When `is_min` is true, returns the minimum; otherwise returns the maximum.
-fn builtin_min_max(
-    heap: &mut Heap<impl ResourceTracker>,
-    args: ArgValues,
-    interns: &Interns,
-    is_min: bool,
-) -> RunResult<Value> {
+/// Like CPython, the first of several equal items wins.
+fn builtin_min_max(vm: &mut VM<impl ResourceTracker>, args: ArgValues, is_min: bool) -> RunResult<Value> {
     let func_name = if is_min { "min" } else { "max" };
     let (positional, kwargs) = args.into_parts();
-    defer_drop_mut!(positional, heap);
+    defer_drop_mut!(positional, vm);
 
-    // TODO: support kwargs (key, default)
-    kwargs.not_supported_yet(func_name, heap)?;
-
-    let Some(first_arg) = positional.next() else {
+    if positional.len() == 0 {
+        kwargs.drop_with_heap(vm.heap);
         return Err(SimpleException::new_msg(
             ExcType::TypeError,
-            format!("{func_name}() expected at least 1 argument, got 0"),
+            format!("{func_name} expected at least 1 argument, got 0"),
         )
         .into());
-    };
+    }
 
-    // decide what to do based on remaining arguments
-    if positional.len() == 0 {
+    let (key_fn, default) = parse_min_max_kwargs(kwargs, func_name, vm.heap, vm.interns)?;
+    defer_drop!(key_fn, vm);
+    let mut default_guard = HeapGuard::new(default, vm);
+    let (default, vm) = default_guard.as_parts_mut();
+
+    let best: Option<Candidate> = None;
+    defer_drop_mut!(best, vm);
+
+    if positional.len() == 1 {
         // Single argument: iterate over it
-        let iter = MontyIter::new(first_arg, heap, interns)?;
-        defer_drop_mut!(iter, heap);
+        let iterable = positional.next().expect("checked length above");
+        let iter = MontyIter::new(iterable, vm.heap, vm.interns)?;
+        defer_drop_mut!(iter, vm);
 
-        let Some(result) = iter.for_next(heap, interns)? else {
+        while let Some(item) = iter.for_next(vm.heap, vm.interns)? {
+            consider(vm, best, item, key_fn.as_ref(), is_min)?;
+        }
+    } else {
+        // Multiple arguments: compare them directly
+        if default.is_some() {
             return Err(SimpleException::new_msg(
-                ExcType::ValueError,
-                format!("{func_name}() iterable argument is empty"),
+                ExcType::TypeError,
+                format!("Cannot specify a default for {func_name}() with multiple positional arguments"),
             )
             .into());
-        };
-
-        let mut result_guard = HeapGuard::new(result, heap);
-        let (result, heap) = result_guard.as_parts_mut();
-        let mut guard = DepthGuard::default();
+        }
+        for item in positional {
+            consider(vm, best, item, key_fn.as_ref(), is_min)?;
+        }
+    }
 
-        while let Some(item) = iter.for_next(heap, interns)? {
-            defer_drop_mut!(item, heap);
+    if let Some((item, key)) = best.take() {
+        key.drop_with_heap(vm.heap);
+        Ok(item)
+    } else if let Some(default) = default.take() {
+        Ok(default)
+    } else {
+        Err(SimpleException::new_msg(ExcType::ValueError, format!("{func_name}() iterable argument is empty")).into())
+    }
+}
 
-            let Some(ordering) = result.py_cmp(item, heap, &mut guard, interns)? else {
-                return Err(ord_not_supported(result, item, heap));
+/// Compares `item` against the best item so far and keeps whichever should win.
+///
+/// Mirrors CPython, which evaluates `key(item) < best_key` for min() and
+/// `key(item) > best_key` for max(), so the earliest of equal items is kept.
+fn consider(
+    vm: &mut VM<impl ResourceTracker>,
+    best: &mut Option<Candidate>,
+    item: Value,
+    key_fn: Option<&Value>,
+    is_min: bool,
+) -> RunResult<()> {
+    let key = match key_fn {
+        Some(f) => {
+            let ctx = if is_min {
+                "min() key argument"
+            } else {
+                "max() key argument"
             };
-
-            if (is_min && ordering == Ordering::Greater) || (!is_min && ordering == Ordering::Less) {
-                std::mem::swap(result, item);
+            let arg = item.clone_with_heap(vm.heap);
+            match vm.evaluate_function(ctx, f, ArgValues::One(arg)) {
+                Ok(key) => Some(key),
+                Err(err) => {
+                    item.drop_with_heap(vm.heap);
+                    return Err(err);
+                }
             }
         }
+        None => None,
+    };
+    let mut candidate_guard = HeapGuard::new((item, key), vm);
+    let (candidate, vm) = candidate_guard.as_parts_mut();
 
-        Ok(result_guard.into_inner())
-    } else {
-        // Multiple arguments: compare them directly
-        let mut result_guard = HeapGuard::new(first_arg, heap);
-        let (result, heap) = result_guard.as_parts_mut();
-        let mut guard = DepthGuard::default();
+    let Some(current) = best else {
+        *best = Some(candidate_guard.into_inner());
+        return Ok(());
+    };
 
-        for item in positional {
-            defer_drop_mut!(item, heap);
+    let candidate_key = candidate.1.as_ref().unwrap_or(&candidate.0);
+    let current_key = current.1.as_ref().unwrap_or(&current.0);
+    let Some(ordering) = candidate_key.py_cmp(current_key, vm.heap, &mut DepthGuard::default(), vm.interns)? else {
+        return Err(ord_not_supported(candidate_key, current_key, is_min, vm.heap));
+    };
 
-            let Some(ordering) = result.py_cmp(item, heap, &mut guard, interns)? else {
-                return Err(ord_not_supported(result, item, heap));
-            };
+    let wanted = if is_min { Ordering::Less } else { Ordering::Greater };
+    if ordering == wanted {
+        // The previous best is dropped with the candidate guard
+        std::mem::swap(current, candidate);
+    }
+    Ok(())
+}
 
-            if (is_min && ordering == Ordering::Greater) || (!is_min && ordering == Ordering::Less) {
-                std::mem::swap(result, item);
-            }
-        }
+/// Parses the `key` and `default` keyword arguments of min() and max().
+///
+/// Returns `(key_fn, default)`; `key=None` is treated the same as no key function.
+fn parse_min_max_kwargs(
+    kwargs: KwargsValues,
+    func_name: &str,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<(Option<Value>, Option<Value>)> {
+    let kwargs_len = kwargs.len();
+    if kwargs_len > 2 {
+        kwargs.drop_with_heap(heap);
+        return Err(ExcType::type_error(format!(
+            "{func_name}() takes at most 2 keyword arguments ({kwargs_len} given)"
+        )));
+    }
+
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+    let mut key_guard = HeapGuard::new(None::<Value>, heap);
+    let (key_val, heap) = key_guard.as_parts_mut();
+    let mut default_guard = HeapGuard::new(None::<Value>, heap);
+    let (default_val, heap) = default_guard.as_parts_mut();
+
+    for (kw_key, value) in kwargs {
+        defer_drop!(kw_key, heap);
+        let mut value = HeapGuard::new(value, heap);
 
-        Ok(result_guard.into_inner())
+        let Some(keyword_name) = kw_key.as_either_str(value.heap()) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+
+        let key_str = keyword_name.as_str(interns);
+        let old = if key_str == "key" {
+            key_val.replace(value.into_inner())
+        } else if key_str == "default" {
+            default_val.replace(value.into_inner())
+        } else {
+            return Err(ExcType::type_error_unexpected_keyword(func_name, key_str));
+        };
+
+        old.drop_with_heap(heap);
     }
+
+    let default_val = default_guard.into_inner();
+    let key_val = key_guard.into_inner();
+    // key=None means no key function
+    let key_val = match key_val {
+        Some(Value::None) => None,
+        other => other,
+    };
+    Ok((key_val, default_val))
 }
 
 #[cold]
-fn ord_not_supported(left: &Value, right: &Value, heap: &Heap<impl ResourceTracker>) -> RunError {
+fn ord_not_supported(left: &Value, right: &Value, is_min: bool, heap: &Heap<impl ResourceTracker>) -> RunError {
+    let op = if is_min { '<' } else { '>' };
     let left_type = left.py_type(heap);
     let right_type = right.py_type(heap);
     ExcType::type_error(format!(
-        "'<' not supported between instances of '{left_type}' and '{right_type}'"
+        "'{op}' not supported between instances of '{left_type}' and '{right_type}'"
     ))
 }
@@ -8,12 +8,19 @@ mod all;
 mod any;
 mod bin;
 mod chr;
+mod dir;
 mod divmod;
 mod enumerate;
+mod eval; // eval and exec share implementation
+mod filter;
+mod format;
+mod globals; // globals and locals share implementation
 mod hash;
 mod hex;
 mod id;
+mod input;
 mod isinstance;
+mod issubclass;
 mod len;
 mod map;
 mod min_max; // min and max share implementation
@@ -28,16 +35,23 @@ mod round;
 mod sorted;
 mod sum;
 mod type_;
+mod vars;
 mod zip;
 
 use std::{fmt::Write, str::FromStr};
 
+pub(crate) use eval::{DYNAMIC_CODE_FILENAME, EvalArgs, Variables, eval_args, parse_source};
+pub(crate) use input::write_input_prompt;
+pub(crate) use isinstance::isinstance_check;
+
 use strum::{Display, EnumString, FromRepr, IntoStaticStr};
 
 use crate::{
     args::ArgValues,
     bytecode::VM,
-    exception_private::{ExcType, RunResult},
+    exception_private::{ExcType, RunResult, SimpleException, call_exc_class},
+    heap::Heap,
+    intern::{ExcClassId, Interns},
     resource::ResourceTracker,
     types::Type,
     value::Value,
@@ -55,6 +69,11 @@ pub(crate) enum Builtins {
     ExcType(ExcType),
     /// A type constructor like `list`, `dict`, `int`, etc.
     Type(Type),
+    /// A user-defined exception class like `class MyError(ValueError): pass`.
+    ///
+    /// Not a true builtin, but like builtin exception types it is an immutable,
+    /// callable type object, so it shares their representation and call path.
+    ExcClass(ExcClassId),
 }
 
 impl Builtins {
@@ -70,15 +89,39 @@ impl Builtins {
             Self::Function(b) => b.call(vm, args),
             Self::ExcType(exc) => exc.call(vm.heap, args, vm.interns),
             Self::Type(t) => t.call(vm.heap, args, vm.interns),
+            Self::ExcClass(class_id) => call_exc_class(class_id, vm.heap, args, vm.interns),
         }
     }
 
     /// Writes the Python repr() string for this callable to a formatter.
-    pub fn py_repr_fmt<W: Write>(self, f: &mut W) -> std::fmt::Result {
+    pub fn py_repr_fmt<W: Write>(
+        self,
+        f: &mut W,
+        heap: &Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> std::fmt::Result {
         match self {
             Self::Function(b) => write!(f, "<built-in function {b}>"),
             Self::ExcType(e) => write!(f, "<class '{e}'>"),
             Self::Type(t) => write!(f, "<class '{t}'>"),
+            Self::ExcClass(class_id) => {
+                let name = interns.get_str(interns.get_exc_class(class_id).name);
+                match heap.defined_exc_class(class_id) {
+                    Some(info) => write!(f, "<class '{}.{name}'>", interns.get_str(info.module)),
+                    None => write!(f, "<class '{name}'>"),
+                }
+            }
+        }
+    }
+
+    /// Returns the builtin value for the type object `t`, as returned by `type(x)`.
+    ///
+    /// Builtin exception types map to `ExcType` so that `type(ValueError()) is ValueError`.
+    #[must_use]
+    pub fn from_type(t: Type) -> Self {
+        match t {
+            Type::Exception(exc_type) => Self::ExcType(exc_type),
+            t => Self::Type(t),
         }
     }
 
@@ -88,6 +131,7 @@ impl Builtins {
             Self::Function(_) => Type::BuiltinFunction,
             Self::ExcType(_) => Type::Type,
             Self::Type(_) => Type::Type,
+            Self::ExcClass(_) => Type::Type,
         }
     }
 }
@@ -100,7 +144,10 @@ impl FromStr for Builtins {
         // Only matches names that are true Python builtins (accessible without imports).
         if let Ok(b) = BuiltinsFunctions::from_str(s) {
             Ok(Self::Function(b))
-        } else if let Ok(exc) = ExcType::from_str(s) {
+        } else if let Ok(exc) = ExcType::from_str(s)
+            && !matches!(exc, ExcType::JSONDecodeError | ExcType::PatternError)
+        {
+            // `JSONDecodeError` and `PatternError` are only reachable through their modules
             Ok(Self::ExcType(exc))
         } else if let Some(t) = Type::from_builtin_name(s) {
             Ok(Self::Type(t))
@@ -154,30 +201,30 @@ pub enum BuiltinsFunctions {
     // complex - handled by Type enum
     // Delattr,
     // dict - handled by Type enum
-    // Dir,
+    Dir,
     Divmod,
     Enumerate,
-    // Eval,
-    // Exec,
-    // Filter,
+    Eval,
+    Exec,
+    Filter,
     // float - handled by Type enum
-    // Format,
+    Format,
     // frozenset - handled by Type enum
     // Getattr,
-    // Globals,
+    Globals,
     // Hasattr,
     Hash,
     // Help,
     Hex,
     Id,
-    // Input,
+    Input,
     // int - handled by Type enum
     Isinstance,
-    // Issubclass,
+    Issubclass,
     // Iter - handled by Type enum
     Len,
     // list - handled by Type enum
-    // Locals,
+    Locals,
     Map,
     Max,
     // memoryview - handled by Type enum
@@ -204,7 +251,7 @@ pub enum BuiltinsFunctions {
     // Super,
     // tuple - handled by Type enum
     Type,
-    // Vars,
+    Vars,
     Zip,
     // __import__ - not planned
 }
@@ -221,16 +268,41 @@ impl BuiltinsFunctions {
             Self::Any => any::builtin_any(vm.heap, args, vm.interns),
             Self::Bin => bin::builtin_bin(vm.heap, args),
             Self::Chr => chr::builtin_chr(vm.heap, args),
+            Self::Dir => dir::builtin_dir(vm, args),
             Self::Divmod => divmod::builtin_divmod(vm.heap, args),
             Self::Enumerate => enumerate::builtin_enumerate(vm.heap, args, vm.interns),
+            // Like `input`, direct calls are run by `VM::call_eval`, which pushes a frame
+            Self::Eval | Self::Exec => {
+                args.drop_with_heap(vm.heap);
+                Err(SimpleException::new_msg(
+                    ExcType::RuntimeError,
+                    format!("{self}() can only be called directly, not by another builtin"),
+                )
+                .into())
+            }
+            Self::Filter => filter::builtin_filter(vm, args),
+            Self::Format => format::builtin_format(vm.heap, args, vm.interns),
+            Self::Globals => globals::builtin_globals(vm, args),
             Self::Hash => hash::builtin_hash(vm.heap, args, vm.interns),
             Self::Hex => hex::builtin_hex(vm.heap, args),
             Self::Id => id::builtin_id(vm.heap, args),
+            // Direct calls yield to the host from `VM::call_input`; only reached when
+            // `input` is passed as a callback to another builtin, e.g. `map(input, ...)`
+            Self::Input => {
+                args.drop_with_heap(vm.heap);
+                Err(SimpleException::new_msg(
+                    ExcType::RuntimeError,
+                    "input() can only be called directly, not by another builtin",
+                )
+                .into())
+            }
             Self::Isinstance => isinstance::builtin_isinstance(vm.heap, args),
+            Self::Issubclass => issubclass::builtin_issubclass(vm.heap, args),
             Self::Len => len::builtin_len(vm.heap, args, vm.interns),
+            Self::Locals => globals::builtin_locals(vm, args),
             Self::Map => map::builtin_map(vm, args),
-            Self::Max => min_max::builtin_max(vm.heap, args, vm.interns),
-            Self::Min => min_max::builtin_min(vm.heap, args, vm.interns),
+            Self::Max => min_max::builtin_max(vm, args),
+            Self::Min => min_max::builtin_min(vm, args),
             Self::Next => next::builtin_next(vm.heap, args, vm.interns),
             Self::Oct => oct::builtin_oct(vm.heap, args),
             Self::Ord => ord::builtin_ord(vm.heap, args, vm.interns),
@@ -242,6 +314,7 @@ impl BuiltinsFunctions {
             Self::Sorted => sorted::builtin_sorted(vm, args),
             Self::Sum => sum::builtin_sum(vm.heap, args, vm.interns),
             Self::Type => type_::builtin_type(vm.heap, args),
+            Self::Vars => vars::builtin_vars(vm, args),
             Self::Zip => zip::builtin_zip(vm.heap, args, vm.interns),
         }
     }
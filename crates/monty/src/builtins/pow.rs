@@ -38,11 +38,15 @@ pub fn builtin_pow(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> Ru
                     if *m_val == 0 {
                         Err(SimpleException::new_msg(ExcType::ValueError, "pow() 3rd argument cannot be 0").into())
                     } else if *e < 0 {
-                        Err(SimpleException::new_msg(
-                            ExcType::ValueError,
-                            "pow() 2nd argument cannot be negative when 3rd argument specified",
-                        )
-                        .into())
+                        // A negative exponent raises the modular inverse of the base to `-e`
+                        let Some(inverse) = mod_inverse(*b, *m_val) else {
+                            return Err(SimpleException::new_msg(
+                                ExcType::ValueError,
+                                "base is not invertible for the given modulus",
+                            )
+                            .into());
+                        };
+                        Ok(Value::Int(mod_pow(inverse, e.unsigned_abs(), *m_val)))
                     } else {
                         // Use modular exponentiation
                         let result = mod_pow(
@@ -113,6 +117,25 @@ fn mod_pow(base: i64, exp: u64, modulo: i64) -> i64 {
     }
 }
 
+/// Computes the inverse of `base` modulo `|modulo|` using the extended Euclidean algorithm.
+///
+/// Returns a value in `0..|modulo|`, or `None` if `base` and `modulo` are not coprime.
+fn mod_inverse(base: i64, modulo: i64) -> Option<i64> {
+    let modulo = i128::from(modulo.unsigned_abs());
+    let (mut old_r, mut r) = (i128::from(base).rem_euclid(modulo), modulo);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    if old_r == 1 {
+        Some(i64::try_from(old_s.rem_euclid(modulo)).expect("inverse is less than the modulus"))
+    } else {
+        None
+    }
+}
+
 fn checked_pow_i64(mut base: i64, mut exp: u32) -> Option<i64> {
     let mut result: i64 = 1;
 
@@ -1,15 +1,18 @@
 //! Implementation of the print() builtin function.
 
+use std::borrow::Cow;
+
 use crate::{
     args::{ArgValues, KwargsValues},
     defer_drop,
     exception_private::{ExcType, RunError, RunResult, SimpleException},
+    exception_public::MontyException,
     heap::{Heap, HeapData},
-    intern::Interns,
+    intern::{Interns, StaticStrings},
     io::PrintWriter,
     resource::{DepthGuard, ResourceTracker},
     types::PyTrait,
-    value::Value,
+    value::{Marker, Value},
 };
 
 /// Implementation of the print() builtin function.
@@ -17,9 +20,8 @@ use crate::{
 /// Supports the following keyword arguments:
 /// - `sep`: separator between values (default: " ")
 /// - `end`: string appended after the last value (default: "\n")
+/// - `file`: `sys.stdout` (the default) or `sys.stderr`; other file objects are not supported
 /// - `flush`: whether to flush the stream (accepted but ignored)
-///
-/// The `file` kwarg is not supported.
 pub fn builtin_print(
     heap: &mut Heap<impl ResourceTracker>,
     args: ArgValues,
@@ -31,7 +33,8 @@ pub fn builtin_print(
     defer_drop!(positional, heap);
 
     // Extract kwargs first
-    let (sep, end) = extract_print_kwargs(kwargs, heap, interns)?;
+    let PrintKwargs { sep, end, stderr } = extract_print_kwargs(kwargs, heap, interns)?;
+    let mut out = Stream { print, stderr };
 
     // Print positional args with separator, dropping each value after use
     let mut first = true;
@@ -40,34 +43,67 @@ pub fn builtin_print(
         if first {
             first = false;
         } else if let Some(sep) = &sep {
-            print.stdout_write(sep.as_str().into())?;
+            out.write(sep.as_str().into())?;
         } else {
-            print.stdout_push(' ')?;
+            out.push(' ')?;
         }
-        print.stdout_write(value.py_str(heap, &mut guard, interns))?;
+        out.write(value.py_str(heap, &mut guard, interns))?;
     }
 
     // Append end string
     if let Some(end) = end {
-        print.stdout_write(end.into())?;
+        out.write(end.into())?;
     } else {
-        print.stdout_push('\n')?;
+        out.push('\n')?;
     }
+    out.print.print_done()?;
 
     Ok(Value::None)
 }
 
-/// Extracts sep and end kwargs from print() arguments.
+/// The stream selected by print()'s `file` kwarg.
+struct Stream<'p, 'a> {
+    print: &'p mut PrintWriter<'a>,
+    stderr: bool,
+}
+
+impl Stream<'_, '_> {
+    fn write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        if self.stderr {
+            self.print.stderr_write(output)
+        } else {
+            self.print.stdout_write(output)
+        }
+    }
+
+    fn push(&mut self, end: char) -> Result<(), MontyException> {
+        if self.stderr {
+            self.print.stderr_push(end)
+        } else {
+            self.print.stdout_push(end)
+        }
+    }
+}
+
+/// Keyword arguments accepted by print().
+struct PrintKwargs {
+    sep: Option<String>,
+    end: Option<String>,
+    /// Whether `file=sys.stderr` was passed.
+    stderr: bool,
+}
+
+/// Extracts sep, end and file kwargs from print() arguments.
 ///
 /// Consumes the kwargs, dropping all values after extraction.
-/// Returns (sep, end, error) where error is Some if a kwarg error occurred.
 fn extract_print_kwargs(
     kwargs: KwargsValues,
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
-) -> RunResult<(Option<String>, Option<String>)> {
+) -> RunResult<PrintKwargs> {
     let mut sep: Option<String> = None;
     let mut end: Option<String> = None;
+    let mut stderr = false;
     let mut error: Option<RunError> = None;
 
     for (key, value) in kwargs {
@@ -97,11 +133,19 @@ fn extract_print_kwargs(
                 Err(e) => error = Some(e),
             },
             "flush" => {} // Accepted but ignored (we don't buffer output)
-            "file" => {
-                error = Some(
-                    SimpleException::new_msg(ExcType::TypeError, "print() 'file' argument is not supported").into(),
-                );
-            }
+            "file" => match value {
+                Value::None | Value::Marker(Marker(StaticStrings::Stdout)) => stderr = false,
+                Value::Marker(Marker(StaticStrings::Stderr)) => stderr = true,
+                _ => {
+                    error = Some(
+                        SimpleException::new_msg(
+                            ExcType::TypeError,
+                            "print() 'file' argument must be sys.stdout or sys.stderr",
+                        )
+                        .into(),
+                    );
+                }
+            },
             _ => {
                 error = Some(ExcType::type_error_unexpected_keyword("print", key_str));
             }
@@ -111,7 +155,7 @@ fn extract_print_kwargs(
     if let Some(error) = error {
         Err(error)
     } else {
-        Ok((sep, end))
+        Ok(PrintKwargs { sep, end, stderr })
     }
 }
 
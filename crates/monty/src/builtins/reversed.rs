@@ -6,23 +6,17 @@ use crate::{
     heap::{Heap, HeapData},
     intern::Interns,
     resource::ResourceTracker,
-    types::{List, MontyIter},
+    types::MontyIter,
     value::Value,
 };
 
 /// Implementation of the reversed() builtin function.
 ///
-/// Returns a list with elements in reverse order.
-/// Note: In Python this returns an iterator, but we return a list for simplicity.
+/// Returns a lazy iterator over the elements of a sequence (list, tuple, str, bytes,
+/// range, dict or dict view) in reverse order.
 pub fn builtin_reversed(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
     let value = args.get_one_arg("reversed", heap)?;
-
-    // Collect all items
-    let mut items: Vec<_> = MontyIter::new(value, heap, interns)?.collect(heap, interns)?;
-
-    // Reverse in place
-    items.reverse();
-
-    let heap_id = heap.allocate(HeapData::List(List::new(items)))?;
+    let iter = MontyIter::reversed(value, heap, interns)?;
+    let heap_id = heap.allocate(HeapData::Iter(iter))?;
     Ok(Value::Ref(heap_id))
 }
@@ -2,15 +2,27 @@
 
 use super::Builtins;
 use crate::{
-    args::ArgValues, defer_drop, exception_private::RunResult, heap::Heap, resource::ResourceTracker, types::PyTrait,
+    args::ArgValues,
+    defer_drop,
+    exception_private::RunResult,
+    heap::{Heap, HeapData},
+    resource::ResourceTracker,
+    types::PyTrait,
     value::Value,
 };
 
 /// Implementation of the type() builtin function.
 ///
-/// Returns the type of an object.
+/// Returns the type of an object. Instances of user-defined exception classes
+/// return their class rather than the builtin base type.
 pub fn builtin_type(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
     let value = args.get_one_arg("type", heap)?;
     defer_drop!(value, heap);
-    Ok(Value::Builtin(Builtins::Type(value.py_type(heap))))
+    if let Value::Ref(id) = value
+        && let HeapData::Exception(exc) = heap.get(*id)
+        && let Some(class_id) = exc.class_id()
+    {
+        return Ok(Value::Builtin(Builtins::ExcClass(class_id)));
+    }
+    Ok(Value::Builtin(Builtins::from_type(value.py_type(heap))))
 }
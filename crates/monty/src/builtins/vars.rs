@@ -0,0 +1,33 @@
+//! Implementation of the vars() builtin function.
+
+use super::globals::builtin_locals;
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::HeapData,
+    resource::ResourceTracker,
+    types::dict_copy,
+    value::Value,
+};
+
+/// Implementation of the vars() builtin function.
+///
+/// Without an argument this is `locals()`. With one, returns a copy of the object's
+/// `__dict__`, which only modules and dataclass instances have.
+pub fn builtin_vars(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let Some(object) = args.get_zero_one_arg("vars", vm.heap)? else {
+        return builtin_locals(vm, ArgValues::Empty);
+    };
+    defer_drop!(object, vm);
+
+    let Value::Ref(id) = object else {
+        return Err(ExcType::type_error("vars() argument must have __dict__ attribute"));
+    };
+    vm.heap.with_entry_mut(*id, |heap, data| match data {
+        HeapData::Module(module) => dict_copy(module.attrs(), heap),
+        HeapData::Dataclass(dataclass) => dict_copy(dataclass.attrs(), heap),
+        _ => Err(ExcType::type_error("vars() argument must have __dict__ attribute")),
+    })
+}
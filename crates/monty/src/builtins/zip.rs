@@ -7,15 +7,15 @@ use crate::{
     heap::{Heap, HeapData},
     intern::Interns,
     resource::ResourceTracker,
-    types::{List, MontyIter, allocate_tuple, tuple::TupleVec},
+    types::MontyIter,
     value::Value,
 };
 
 /// Implementation of the zip() builtin function.
 ///
-/// Returns a list of tuples, where the i-th tuple contains the i-th element
+/// Returns an iterator of tuples, where the i-th tuple contains the i-th element
 /// from each of the argument iterables. Stops when the shortest iterable is exhausted.
-/// Note: In Python this returns an iterator, but we return a list for simplicity.
+/// Items are pulled from the iterables lazily, one tuple per step.
 pub fn builtin_zip(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
     let (positional, kwargs) = args.into_parts();
     defer_drop_mut!(positional, heap);
@@ -23,55 +23,7 @@ pub fn builtin_zip(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, inter
     // TODO: support kwargs (strict)
     kwargs.not_supported_yet("zip", heap)?;
 
-    if positional.len() == 0 {
-        // zip() with no arguments returns empty list
-        let heap_id = heap.allocate(HeapData::List(List::new(Vec::new())))?;
-        return Ok(Value::Ref(heap_id));
-    }
-
-    // Create iterators for each iterable
-    let mut iterators: Vec<MontyIter> = Vec::with_capacity(positional.len());
-    for iterable in positional {
-        match MontyIter::new(iterable, heap, interns) {
-            Ok(iter) => iterators.push(iter),
-            Err(e) => {
-                // Clean up already-created iterators
-                for iter in iterators {
-                    iter.drop_with_heap(heap);
-                }
-                return Err(e);
-            }
-        }
-    }
-
-    let mut result: Vec<Value> = Vec::new();
-
-    // Zip until shortest iterator is exhausted
-    'outer: loop {
-        let mut tuple_items = TupleVec::with_capacity(iterators.len());
-
-        for iter in &mut iterators {
-            if let Some(item) = iter.for_next(heap, interns)? {
-                tuple_items.push(item);
-            } else {
-                // This iterator is exhausted - drop partial tuple items and stop
-                for item in tuple_items {
-                    item.drop_with_heap(heap);
-                }
-                break 'outer;
-            }
-        }
-
-        // Create tuple from collected items
-        let tuple_val = allocate_tuple(tuple_items, heap)?;
-        result.push(tuple_val);
-    }
-
-    // Clean up iterators
-    for iter in iterators {
-        iter.drop_with_heap(heap);
-    }
-
-    let heap_id = heap.allocate(HeapData::List(List::new(result)))?;
+    let iter = MontyIter::zip(&mut *positional, heap, interns)?;
+    let heap_id = heap.allocate(HeapData::Iter(iter))?;
     Ok(Value::Ref(heap_id))
 }
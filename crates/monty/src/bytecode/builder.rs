@@ -9,7 +9,7 @@ use super::{
     code::{Code, ConstPool, ExceptionEntry, LocationEntry},
     op::Opcode,
 };
-use crate::{intern::StringId, parse::CodeRange, value::Value};
+use crate::{intern::StringId, parse::CodeRange, trace::StatementKind, value::Value};
 
 /// Builder for emitting bytecode during compilation.
 ///
@@ -65,6 +65,12 @@ pub struct CodeBuilder {
     /// Used to determine whether to raise `UnboundLocalError` or `NameError`
     /// when loading an undefined local variable.
     assigned_locals: HashSet<u16>,
+
+    /// Bitset of bytecode offsets where a statement starts, see `Code::is_statement_start`.
+    statement_starts: Vec<u64>,
+
+    /// Kind of each statement start, see `Code::statement_kind`.
+    statement_kinds: Vec<(u32, StatementKind)>,
 }
 
 impl CodeBuilder {
@@ -344,6 +350,27 @@ impl CodeBuilder {
         self.bytecode.len()
     }
 
+    /// Marks the current offset as the start of a statement of the given kind.
+    ///
+    /// Called before compiling each statement, so checkpoints can stop there and trace hooks
+    /// are told about it. When statements start at the same offset, e.g. a `try` and the first
+    /// statement of its body, the innermost one is reported since it's the one whose location
+    /// the first instruction carries.
+    pub fn mark_statement_start(&mut self, kind: StatementKind) {
+        let offset = self.bytecode.len();
+        let word = offset / 64;
+        if word >= self.statement_starts.len() {
+            self.statement_starts.resize(word + 1, 0);
+        }
+        self.statement_starts[word] |= 1 << (offset % 64);
+
+        let offset = u32::try_from(offset).expect("bytecode length exceeds u32");
+        match self.statement_kinds.last_mut() {
+            Some((last, last_kind)) if *last == offset => *last_kind = kind,
+            _ => self.statement_kinds.push((offset, kind)),
+        }
+    }
+
     /// Emits `LoadLocal`, using specialized opcodes for slots 0-3.
     ///
     /// Slots 0-3 use zero-operand opcodes (`LoadLocal0`, etc.) for efficiency.
@@ -446,6 +473,8 @@ impl CodeBuilder {
             self.max_stack_depth,
             local_names,
             self.assigned_locals,
+            self.statement_starts,
+            self.statement_kinds,
         )
     }
 
@@ -6,7 +6,7 @@
 
 use std::collections::HashSet;
 
-use crate::{intern::StringId, parse::CodeRange, value::Value};
+use crate::{intern::StringId, parse::CodeRange, trace::StatementKind, value::Value};
 
 /// Compiled bytecode for a function or module.
 ///
@@ -62,6 +62,16 @@ pub struct Code {
     /// Used to determine whether to raise `UnboundLocalError` (slot is assigned somewhere
     /// but accessed before assignment) or `NameError` (name doesn't exist in any scope).
     assigned_locals: HashSet<u16>,
+
+    /// Bitset of bytecode offsets where a statement starts, one bit per offset.
+    ///
+    /// Used to take checkpoints at statement boundaries.
+    statement_starts: Vec<u64>,
+
+    /// Kind of the statement starting at each offset set in `statement_starts`, sorted by offset.
+    ///
+    /// Only consulted when a trace hook is installed.
+    statement_kinds: Vec<(u32, StatementKind)>,
 }
 
 impl Code {
@@ -79,6 +89,8 @@ impl Code {
         stack_size: u16,
         local_names: Vec<StringId>,
         assigned_locals: HashSet<u16>,
+        statement_starts: Vec<u64>,
+        statement_kinds: Vec<(u32, StatementKind)>,
     ) -> Self {
         Self {
             bytecode,
@@ -89,6 +101,8 @@ impl Code {
             stack_size,
             local_names,
             assigned_locals,
+            statement_starts,
+            statement_kinds,
         }
     }
 
@@ -107,9 +121,13 @@ impl Code {
     /// Returns the local variable name for a given slot index.
     ///
     /// Used to generate proper NameError messages when accessing undefined locals.
+    /// Returns `None` for slots the code never names, e.g. those past the last named slot.
     #[must_use]
     pub fn local_name(&self, slot: u16) -> Option<StringId> {
-        self.local_names.get(slot as usize).copied()
+        self.local_names
+            .get(slot as usize)
+            .copied()
+            .filter(|&name| name != StringId::default())
     }
 
     /// Returns whether the slot is an assigned local (vs an undefined reference).
@@ -121,6 +139,24 @@ impl Code {
         self.assigned_locals.contains(&slot)
     }
 
+    /// Returns whether a statement starts at the given bytecode offset.
+    #[must_use]
+    pub fn is_statement_start(&self, offset: usize) -> bool {
+        self.statement_starts
+            .get(offset / 64)
+            .is_some_and(|word| word & (1 << (offset % 64)) != 0)
+    }
+
+    /// Returns the kind of the statement starting at the given bytecode offset, if any.
+    #[must_use]
+    pub fn statement_kind(&self, offset: usize) -> Option<StatementKind> {
+        let offset = u32::try_from(offset).ok()?;
+        self.statement_kinds
+            .binary_search_by_key(&offset, |(start, _)| *start)
+            .ok()
+            .map(|index| self.statement_kinds[index].1)
+    }
+
     /// Finds the location entry for a given bytecode offset.
     ///
     /// Location entries are recorded at instruction boundaries. This method finds
@@ -150,6 +186,20 @@ impl Code {
     pub fn find_exception_handler(&self, offset: u32) -> Option<&ExceptionEntry> {
         self.exception_table.iter().find(|entry| entry.contains(offset))
     }
+
+    /// Estimates the memory used by this code in bytes, to charge code compiled at runtime.
+    #[must_use]
+    pub fn estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.bytecode.len()
+            + self.constants.values.len() * std::mem::size_of::<Value>()
+            + self.location_table.len() * std::mem::size_of::<LocationEntry>()
+            + self.exception_table.len() * std::mem::size_of::<ExceptionEntry>()
+            + self.local_names.len() * std::mem::size_of::<StringId>()
+            + self.assigned_locals.len() * std::mem::size_of::<u16>()
+            + self.statement_starts.len() * std::mem::size_of::<u64>()
+            + self.statement_kinds.len() * std::mem::size_of::<(u32, StatementKind)>()
+    }
 }
 
 /// TODO remove, this doesn't add any value
@@ -13,22 +13,26 @@ use std::borrow::Cow;
 use super::{
     builder::{CodeBuilder, JumpLabel},
     code::{Code, ExceptionEntry},
+    fold::{const_truthiness, fold_expr},
     op::Opcode,
 };
 use crate::{
-    args::{ArgExprs, Kwarg},
+    args::{ArgExprs, Kwarg, StarredArg},
     builtins::Builtins,
-    exception_private::ExcType,
+    exception_private::{ExcType, RunError, SimpleException},
     exception_public::{MontyException, StackFrame},
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, NameScope, Node, Operator,
-        PreparedFunctionDef, PreparedNode, UnpackTarget,
+        AssignTarget, Callable, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        NameScope, Node, Operator, PreparedFunctionDef, PreparedNode, UnpackTarget,
     },
     fstring::{ConversionFlag, FStringPart, FormatSpec, ParsedFormatSpec, encode_format_spec},
     function::Function,
-    intern::{Interns, StringId},
+    intern::{FunctionId, Interns, StaticStrings, StringId},
     modules::BuiltinModule,
+    namespace::NamespaceId,
     parse::{CodeRange, ExceptHandler, Try},
+    signature::Signature,
+    trace::StatementKind,
     value::{EitherStr, Value},
 };
 
@@ -63,6 +67,11 @@ pub struct Compiler<'a> {
     /// finishes, so inner functions have lower indices.
     functions: Vec<Function>,
 
+    /// Function ID of `functions[0]`.
+    ///
+    /// Non-zero when compiling code at runtime, which only builds the functions it adds.
+    function_base: usize,
+
     /// Loop stack for break/continue handling.
     /// Each entry tracks the loop start offset and pending break jumps.
     loop_stack: Vec<LoopInfo>,
@@ -87,6 +96,52 @@ pub struct Compiler<'a> {
     /// clear the current exception (`ClearException`) and pop the exception
     /// value from the stack before jumping to the finally path or loop target.
     except_handler_depth: usize,
+
+    /// Options controlling code generation, shared by all nested function compilers.
+    options: CompileOptions,
+}
+
+/// Options controlling how Python source is compiled to bytecode.
+///
+/// Use `CompileOptions::default()` to match CPython's default behavior, or build custom
+/// options with the builder pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CompileOptions {
+    /// Skip `assert` statements entirely, like running CPython with `-O`.
+    ///
+    /// Neither the condition nor the message expression is evaluated.
+    pub strip_asserts: bool,
+    /// Fold constant expressions like `60 * 60 * 24` and drop branches guarded by a
+    /// constant condition like `if False:`. Enabled by default.
+    ///
+    /// Results are identical either way; disabling this keeps the bytecode a direct
+    /// translation of the source, which can help when debugging the compiler or VM.
+    pub optimize: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            strip_asserts: false,
+            optimize: true,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Sets whether `assert` statements are removed during compilation.
+    #[must_use]
+    pub fn strip_asserts(mut self, strip: bool) -> Self {
+        self.strip_asserts = strip;
+        self
+    }
+
+    /// Sets whether constant folding and dead branch elimination are applied.
+    #[must_use]
+    pub fn optimize(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
 }
 
 /// Information about a loop for break/continue handling.
@@ -146,28 +201,27 @@ pub struct CompileResult {
 
 impl<'a> Compiler<'a> {
     /// Creates a new compiler with access to the string interner.
-    fn new(interns: &'a Interns, functions: Vec<Function>) -> Self {
-        Self {
-            code: CodeBuilder::new(),
-            interns,
-            functions,
-            loop_stack: Vec::new(),
-            cell_base: 0,
-            finally_targets: Vec::new(),
-            except_handler_depth: 0,
-        }
+    fn new(interns: &'a Interns, functions: Vec<Function>, options: CompileOptions) -> Self {
+        Self::new_with_cell_base(interns, functions, 0, options)
     }
 
     /// Creates a new compiler with a specific cell base offset.
-    fn new_with_cell_base(interns: &'a Interns, functions: Vec<Function>, cell_base: u16) -> Self {
+    fn new_with_cell_base(
+        interns: &'a Interns,
+        functions: Vec<Function>,
+        cell_base: u16,
+        options: CompileOptions,
+    ) -> Self {
         Self {
             code: CodeBuilder::new(),
             interns,
             functions,
+            function_base: 0,
             loop_stack: Vec::new(),
             cell_base,
             finally_targets: Vec::new(),
             except_handler_depth: 0,
+            options,
         }
     }
 
@@ -180,8 +234,9 @@ impl<'a> Compiler<'a> {
         nodes: &[PreparedNode],
         interns: &Interns,
         num_locals: u16,
+        options: CompileOptions,
     ) -> Result<CompileResult, CompileError> {
-        Self::compile_module_with_functions(nodes, interns, num_locals, Vec::new())
+        Self::compile_module_with_functions(nodes, interns, num_locals, Vec::new(), options)
     }
 
     /// Compiles module-level code while preserving an existing function table prefix.
@@ -194,8 +249,9 @@ impl<'a> Compiler<'a> {
         interns: &Interns,
         num_locals: u16,
         existing_functions: Vec<Function>,
+        options: CompileOptions,
     ) -> Result<CompileResult, CompileError> {
-        let mut compiler = Compiler::new(interns, Vec::new());
+        let mut compiler = Compiler::new(interns, Vec::new(), options);
         compiler.functions = existing_functions;
         compiler.compile_block(nodes)?;
 
@@ -209,6 +265,124 @@ impl<'a> Compiler<'a> {
         })
     }
 
+    /// Compiles the body of the imported Python module at `module_index`.
+    ///
+    /// The body is compiled like module-level code, but ends by building the module object
+    /// (see `Opcode::BuildPythonModule`) instead of returning `None`. It is appended to
+    /// `existing_functions` as a function named `<module>`, and it and all functions defined
+    /// in it use the namespace reserved for the module as their globals.
+    ///
+    /// Returns the `FunctionId` of the module body along with the extended function table.
+    pub fn compile_python_module(
+        nodes: &[PreparedNode],
+        interns: &Interns,
+        num_locals: u16,
+        existing_functions: Vec<Function>,
+        module_index: u16,
+        options: CompileOptions,
+    ) -> Result<(FunctionId, Vec<Function>), CompileError> {
+        let first_function = existing_functions.len();
+        let mut compiler = Compiler::new(interns, Vec::new(), options);
+        compiler.functions = existing_functions;
+        compiler.compile_block(nodes)?;
+
+        compiler.code.emit_u16(Opcode::BuildPythonModule, module_index);
+        compiler.code.emit(Opcode::ReturnValue);
+
+        let code = compiler.code.build(num_locals);
+        let mut functions = compiler.functions;
+        let body_id = FunctionId::from_index(u16::try_from(functions.len()).expect("function count exceeds u16"));
+        functions.push(Function::new(
+            Identifier::new(StaticStrings::Module.into(), CodeRange::default()),
+            Signature::default(),
+            usize::from(num_locals),
+            Vec::new(),
+            Vec::new(),
+            0,
+            Vec::new(),
+            0,
+            false,
+            code,
+        ));
+
+        let globals = NamespaceId::python_module(usize::from(module_index));
+        for function in &mut functions[first_function..] {
+            function.globals = globals;
+        }
+        Ok((body_id, functions))
+    }
+
+    /// Compiles code run by `exec()` or `eval()` in the main module's global namespace.
+    ///
+    /// The code is compiled like module-level code (see `prepare_dynamic_module`) into a
+    /// function named `<module>`, whose frame uses the global namespace as its locals.
+    ///
+    /// Returns the functions the code defines, numbered from `function_base`, then the
+    /// code's function itself.
+    pub fn compile_dynamic_module(
+        nodes: &[PreparedNode],
+        interns: &Interns,
+        num_locals: u16,
+        function_base: usize,
+        options: CompileOptions,
+    ) -> Result<Vec<Function>, CompileError> {
+        let mut compiler = Compiler::new(interns, Vec::new(), options);
+        compiler.function_base = function_base;
+        compiler.compile_block(nodes)?;
+
+        compiler.code.emit(Opcode::LoadNone);
+        compiler.code.emit(Opcode::ReturnValue);
+
+        let code = compiler.code.build(num_locals);
+        let mut functions = compiler.functions;
+        functions.push(Function::new(
+            Identifier::new(StaticStrings::Module.into(), CodeRange::default()),
+            Signature::default(),
+            usize::from(num_locals),
+            Vec::new(),
+            Vec::new(),
+            0,
+            Vec::new(),
+            0,
+            false,
+            code,
+        ));
+        Ok(functions)
+    }
+
+    /// Compiles code run by `exec()` or `eval()` as a function, see `prepare_dynamic_function`.
+    ///
+    /// Returns the functions the code defines, numbered from `function_base`, then the
+    /// code's function itself.
+    pub fn compile_dynamic_function(
+        func_def: &PreparedFunctionDef,
+        interns: &Interns,
+        function_base: usize,
+        options: CompileOptions,
+    ) -> Result<Vec<Function>, CompileError> {
+        let mut compiler = Compiler::new(interns, Vec::new(), options);
+        compiler.function_base = function_base;
+        let cell_base = u16::try_from(func_def.signature.param_count())
+            .map_err(|_| CompileError::new("too many variables", func_def.name.position))?;
+        let namespace_size = u16::try_from(func_def.namespace_size)
+            .map_err(|_| CompileError::new("too many variables", func_def.name.position))?;
+        let (code, mut functions) =
+            compiler.compile_function_body(&func_def.body, Vec::new(), namespace_size, cell_base)?;
+        functions.push(Function::new(
+            func_def.name,
+            func_def.signature.clone(),
+            func_def.namespace_size,
+            func_def.slot_names.clone(),
+            func_def.free_var_enclosing_slots.clone(),
+            func_def.cell_var_count,
+            func_def.cell_param_indices.clone(),
+            0,
+            false,
+            code,
+        ));
+        Ok(functions)
+    }
+
     /// Compiles a function body to bytecode, returning the Code and any nested functions.
     ///
     /// Used internally when compiling function definitions. The function body is
@@ -221,13 +395,14 @@ impl<'a> Compiler<'a> {
     /// The `functions` parameter receives any previously compiled functions, and
     /// any nested functions found in the body will be added to it.
     fn compile_function_body(
+        &self,
         body: &[PreparedNode],
-        interns: &Interns,
         functions: Vec<Function>,
         num_locals: u16,
         cell_base: u16,
     ) -> Result<(Code, Vec<Function>), CompileError> {
-        let mut compiler = Compiler::new_with_cell_base(interns, functions, cell_base);
+        let mut compiler = Compiler::new_with_cell_base(self.interns, functions, cell_base, self.options);
+        compiler.function_base = self.function_base;
         compiler.compile_block(body)?;
 
         // Implicit return None if no explicit return
@@ -251,6 +426,9 @@ impl<'a> Compiler<'a> {
 
     /// Compiles a single statement.
     fn compile_stmt(&mut self, node: &PreparedNode) -> Result<(), CompileError> {
+        if let Some(kind) = statement_kind(node) {
+            self.code.mark_statement_start(kind);
+        }
         // Node is an alias, use qualified path for matching
         match node {
             Node::Expr(expr) => {
@@ -299,6 +477,17 @@ impl<'a> Compiler<'a> {
                     self.compile_unpack_target(target);
                 }
             }
+            Node::ChainAssign { targets, object } => {
+                self.compile_expr(object)?;
+                // Every target but the last consumes a duplicate of the value
+                if let Some((last, rest)) = targets.split_last() {
+                    for target in rest {
+                        self.code.emit(Opcode::Dup);
+                        self.compile_assign_target(target)?;
+                    }
+                    self.compile_assign_target(last)?;
+                }
+            }
             Node::OpAssign { target, op, object } => {
                 let Some(opcode) = operator_to_inplace_opcode(op) else {
                     return Err(CompileError::new(
@@ -306,7 +495,7 @@ impl<'a> Compiler<'a> {
                         target.position,
                     ));
                 };
-                self.compile_name(target);
+                self.compile_name_with_position(target);
                 self.compile_expr(object)?;
                 self.code.emit(opcode);
                 self.compile_store(target);
@@ -325,6 +514,11 @@ impl<'a> Compiler<'a> {
                 self.code.set_location(*target_position, None);
                 self.code.emit(Opcode::StoreSubscr);
             }
+            Node::Delete { targets } => {
+                for target in targets {
+                    self.compile_delete_target(target)?;
+                }
+            }
             Node::AttrAssign {
                 object,
                 attr,
@@ -351,14 +545,18 @@ impl<'a> Compiler<'a> {
             } => self.compile_for(target, iter, body, or_else)?,
             Node::While { test, body, or_else } => self.compile_while(test, body, or_else)?,
             Node::Assert { test, msg } => self.compile_assert(test, msg.as_ref())?,
-            Node::Raise(expr) => {
-                if let Some(exc) = expr {
+            Node::Raise { exc, cause } => match (exc, cause) {
+                (Some(exc), Some(cause)) => {
+                    self.compile_expr(exc)?;
+                    self.compile_expr(cause)?;
+                    self.code.emit(Opcode::RaiseFrom);
+                }
+                (Some(exc), None) => {
                     self.compile_expr(exc)?;
                     self.code.emit(Opcode::Raise);
-                } else {
-                    self.code.emit(Opcode::Reraise);
                 }
-            }
+                (None, _) => self.code.emit(Opcode::Reraise),
+            },
             Node::FunctionDef(func_def) => self.compile_function_def(func_def)?,
             Node::Try(try_block) => self.compile_try(try_block)?,
             Node::Import { module_name, binding } => self.compile_import(*module_name, binding),
@@ -381,7 +579,8 @@ impl<'a> Compiler<'a> {
     /// 1. Recursively compiling the function body to bytecode
     /// 2. Creating a Function struct with the compiled Code
     /// 3. Adding the Function to the compiler's functions vector
-    /// 4. Emitting bytecode to evaluate defaults and create the function at runtime
+    /// 4. Emitting bytecode to evaluate decorators and defaults and create the function at runtime
+    /// 5. Applying any decorators and storing the result to the function name
     fn compile_function_def(&mut self, func_def: &PreparedFunctionDef) -> Result<(), CompileError> {
         let func_pos = func_def.name.position;
 
@@ -405,14 +604,15 @@ impl<'a> Compiler<'a> {
         let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
         let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
         let (body_code, mut functions) =
-            Self::compile_function_body(&func_def.body, self.interns, functions, namespace_size, cell_base)?;
+            self.compile_function_body(&func_def.body, functions, namespace_size, cell_base)?;
 
         // 2. Create the compiled Function and add to the vector
-        let func_id = functions.len();
+        let func_id = self.function_base + functions.len();
         let function = Function::new(
             func_def.name,
             func_def.signature.clone(),
             func_def.namespace_size,
+            func_def.slot_names.clone(),
             func_def.free_var_enclosing_slots.clone(),
             func_def.cell_var_count,
             func_def.cell_param_indices.clone(),
@@ -425,15 +625,25 @@ impl<'a> Compiler<'a> {
         // Restore functions to self
         self.functions = functions;
 
-        // 3. Compile and push default values (evaluated at definition time)
+        // Locate the statement at the function name, so tracing reports the `def` line;
+        // decorators and defaults set their own locations
+        self.code.set_location(func_pos, None);
+
+        // 3. Push decorators (evaluated before defaults, outermost first)
+        for decorator in &func_def.decorators {
+            self.compile_expr(decorator)?;
+        }
+
+        // 4. Compile and push default values (evaluated at definition time)
         for default_expr in &func_def.default_exprs {
             self.compile_expr(default_expr)?;
         }
         let defaults_count =
             u8::try_from(func_def.default_exprs.len()).expect("function default argument count exceeds u8");
-        let func_id_u16 = u16::try_from(func_id).expect("function count exceeds u16");
+        let func_id_u16 =
+            u16::try_from(func_id).map_err(|_| CompileError::new("too many functions in program", func_pos))?;
 
-        // 4. Emit MakeFunction or MakeClosure (if has free vars)
+        // 5. Emit MakeFunction or MakeClosure (if has free vars)
         if func_def.free_var_enclosing_slots.is_empty() {
             // MakeFunction: func_id (u16) + defaults_count (u8)
             self.code.emit_u16_u8(Opcode::MakeFunction, func_id_u16, defaults_count);
@@ -451,7 +661,14 @@ impl<'a> Compiler<'a> {
                 .emit_u16_u8_u8(Opcode::MakeClosure, func_id_u16, defaults_count, cell_count);
         }
 
-        // 5. Store the function object to its name slot
+        // 6. Apply decorators innermost first: each call consumes the decorator pushed
+        // in step 3 and the function (or previous decorator result) above it
+        for decorator in func_def.decorators.iter().rev() {
+            self.code.set_location(decorator.position, None);
+            self.code.emit_u8(Opcode::CallFunction, 1);
+        }
+
+        // 7. Store the function object to its name slot
         self.compile_store(&func_def.name);
 
         Ok(())
@@ -485,14 +702,15 @@ impl<'a> Compiler<'a> {
         let cell_base = u16::try_from(func_def.signature.param_count()).expect("function parameter count exceeds u16");
         let namespace_size = u16::try_from(func_def.namespace_size).expect("function namespace size exceeds u16");
         let (body_code, mut functions) =
-            Self::compile_function_body(&func_def.body, self.interns, functions, namespace_size, cell_base)?;
+            self.compile_function_body(&func_def.body, functions, namespace_size, cell_base)?;
 
         // 2. Create the compiled Function and add to the vector
-        let func_id = functions.len();
+        let func_id = self.function_base + functions.len();
         let function = Function::new(
             func_def.name,
             func_def.signature.clone(),
             func_def.namespace_size,
+            func_def.slot_names.clone(),
             func_def.free_var_enclosing_slots.clone(),
             func_def.cell_var_count,
             func_def.cell_param_indices.clone(),
@@ -511,7 +729,8 @@ impl<'a> Compiler<'a> {
         }
         let defaults_count =
             u8::try_from(func_def.default_exprs.len()).expect("function default argument count exceeds u8");
-        let func_id_u16 = u16::try_from(func_id).expect("function count exceeds u16");
+        let func_id_u16 =
+            u16::try_from(func_id).map_err(|_| CompileError::new("too many functions in program", func_pos))?;
 
         // 4. Emit MakeFunction or MakeClosure (if has free vars)
         if func_def.free_var_enclosing_slots.is_empty() {
@@ -538,24 +757,16 @@ impl<'a> Compiler<'a> {
 
     /// Compiles an import statement.
     ///
-    /// Emits `LoadModule` to create the module, then stores it to the binding name.
-    /// If the module is unknown, emits `RaiseImportError` to defer the error to runtime.
-    /// This allows imports inside `if TYPE_CHECKING:` blocks to compile successfully.
+    /// Emits `LoadModule` or `LoadNativeModule` to create the module, then stores it to the
+    /// binding name. If the module is unknown, emits `RaiseImportError` to defer the error
+    /// to runtime. This allows imports inside `if TYPE_CHECKING:` blocks to compile successfully.
     fn compile_import(&mut self, module_name: StringId, binding: &Identifier) {
         let position = binding.position;
         self.code.set_location(position, None);
 
-        // Look up the module by name
-        if let Some(builtin_module) = BuiltinModule::from_string_id(module_name) {
-            // Known module - emit LoadModule
-            self.code.emit_u8(Opcode::LoadModule, builtin_module as u8);
+        if self.compile_load_module(module_name) {
             // Store to the binding (respects Local/Global/Cell scope)
             self.compile_store(binding);
-        } else {
-            // Unknown module - defer error to runtime with RaiseImportError
-            // This allows TYPE_CHECKING imports to compile without error
-            let name_const = self.code.add_const(Value::InternString(module_name));
-            self.code.emit_u16(Opcode::RaiseImportError, name_const);
         }
     }
 
@@ -568,30 +779,49 @@ impl<'a> Compiler<'a> {
     fn compile_import_from(&mut self, module_name: StringId, names: &[(StringId, Identifier)], position: CodeRange) {
         self.code.set_location(position, None);
 
-        // Look up the module
-        if let Some(builtin_module) = BuiltinModule::from_string_id(module_name) {
-            // Known module - emit LoadModule
-            self.code.emit_u8(Opcode::LoadModule, builtin_module as u8);
+        if !self.compile_load_module(module_name) {
+            return;
+        }
 
-            // For each name to import
-            for (i, (import_name, binding)) in names.iter().enumerate() {
-                // Dup the module if this isn't the last import (last one consumes the module)
-                if i < names.len() - 1 {
-                    self.code.emit(Opcode::Dup);
-                }
+        // For each name to import
+        for (i, (import_name, binding)) in names.iter().enumerate() {
+            // Dup the module if this isn't the last import (last one consumes the module)
+            if i < names.len() - 1 {
+                self.code.emit(Opcode::Dup);
+            }
 
-                // Load the attribute from the module (raises ImportError if not found)
-                let name_idx = u16::try_from(import_name.index()).expect("name index exceeds u16");
-                self.code.emit_u16(Opcode::LoadAttrImport, name_idx);
+            // Load the attribute from the module (raises ImportError if not found)
+            let name_idx = u16::try_from(import_name.index()).expect("name index exceeds u16");
+            self.code.emit_u16(Opcode::LoadAttrImport, name_idx);
 
-                // Store to the binding
-                self.compile_store(binding);
-            }
+            // Store to the binding
+            self.compile_store(binding);
+        }
+    }
+
+    /// Emits the instruction that pushes the module named `module_name`.
+    ///
+    /// Built-in modules take precedence over host-provided native modules, which take
+    /// precedence over host-supplied Python modules. For unknown modules, emits
+    /// `RaiseImportError` instead and returns `false`.
+    fn compile_load_module(&mut self, module_name: StringId) -> bool {
+        if let Some(builtin_module) = BuiltinModule::from_string_id(module_name) {
+            self.code.emit_u8(Opcode::LoadModule, builtin_module as u8);
+            true
+        } else if let Some(index) = self.interns.find_native_module(module_name) {
+            let index_u16 = u16::try_from(index).expect("native module index exceeds u16");
+            self.code.emit_u16(Opcode::LoadNativeModule, index_u16);
+            true
+        } else if let Some(index) = self.interns.find_python_module(module_name) {
+            let index_u16 = u16::try_from(index).expect("python module index exceeds u16");
+            self.code.emit_u16(Opcode::ImportPythonModule, index_u16);
+            true
         } else {
             // Unknown module - defer error to runtime with RaiseImportError
             // This allows TYPE_CHECKING imports to compile without error
             let name_const = self.code.add_const(Value::InternString(module_name));
             self.code.emit_u16(Opcode::RaiseImportError, name_const);
+            false
         }
     }
 
@@ -604,6 +834,17 @@ impl<'a> Compiler<'a> {
         // Set source location for traceback info
         self.code.set_location(expr_loc.position, None);
 
+        if self.options.optimize
+            && matches!(
+                expr_loc.expr,
+                Expr::Op { .. } | Expr::UnaryMinus(_) | Expr::UnaryPlus(_) | Expr::UnaryInvert(_) | Expr::Not(_)
+            )
+            && let Some(literal) = fold_expr(expr_loc, self.interns)
+        {
+            self.compile_literal(&literal);
+            return Ok(());
+        }
+
         match &expr_loc.expr {
             Expr::Literal(lit) => self.compile_literal(lit),
 
@@ -784,6 +1025,14 @@ impl<'a> Compiler<'a> {
                 self.code.emit(Opcode::Await);
             }
 
+            Expr::ExcClass { class_id, base } => {
+                self.compile_expr(base)?;
+                let class_id_u16 = u16::try_from(class_id.index())
+                    .map_err(|_| CompileError::new("too many exception classes in program", expr_loc.position))?;
+                self.code.set_location(expr_loc.position, None);
+                self.code.emit_u16(Opcode::MakeExcClass, class_id_u16);
+            }
+
             Expr::Slice { lower, upper, step } => {
                 // Compile slice components: start, stop, step (push None for missing)
                 if let Some(lower) = lower {
@@ -1056,6 +1305,13 @@ impl<'a> Compiler<'a> {
         body: &[PreparedNode],
         or_else: &[PreparedNode],
     ) -> Result<(), CompileError> {
+        // A constant condition means only one branch can ever run
+        if self.options.optimize
+            && let Some(truthy) = const_truthiness(test, self.interns)
+        {
+            return self.compile_block(if truthy { body } else { or_else });
+        }
+
         self.compile_expr(test)?;
 
         if or_else.is_empty() {
@@ -1077,6 +1333,11 @@ impl<'a> Compiler<'a> {
 
     /// Compiles a ternary conditional expression.
     fn compile_if_else_expr(&mut self, test: &ExprLoc, body: &ExprLoc, orelse: &ExprLoc) -> Result<(), CompileError> {
+        if self.options.optimize
+            && let Some(truthy) = const_truthiness(test, self.interns)
+        {
+            return self.compile_expr(if truthy { body } else { orelse });
+        }
         self.compile_expr(test)?;
         let else_jump = self.code.emit_jump(Opcode::JumpIfFalse);
         self.compile_expr(body)?;
@@ -1194,15 +1455,15 @@ impl<'a> Compiler<'a> {
                 var_kwargs,
             } => {
                 // Mixed positional and keyword arguments - may include *args or **kwargs unpacking
-                if var_args.is_some() || var_kwargs.is_some() {
+                if !var_args.is_empty() || !var_kwargs.is_empty() {
                     // Use CallFunctionEx for unpacking - no limit on this path since
                     // args are built into a tuple dynamically at runtime
                     self.compile_call_with_unpacking(
                         callable,
                         args.as_ref(),
-                        var_args.as_ref(),
+                        var_args,
                         kwargs.as_ref(),
-                        var_kwargs.as_ref(),
+                        var_kwargs,
                         call_pos,
                     )?;
                 } else {
@@ -1308,15 +1569,15 @@ impl<'a> Compiler<'a> {
                 var_kwargs,
             } => {
                 // Mixed positional and keyword arguments - may include *args or **kwargs unpacking
-                if var_args.is_some() || var_kwargs.is_some() {
+                if !var_args.is_empty() || !var_kwargs.is_empty() {
                     // Use CallFunctionExtended for unpacking - no limit on this path since
                     // args are built into a tuple dynamically at runtime.
                     // Callable is already on stack, so we just need to build args and kwargs.
                     self.compile_call_args_with_unpacking(
                         args.as_ref(),
-                        var_args.as_ref(),
+                        var_args,
                         kwargs.as_ref(),
-                        var_kwargs.as_ref(),
+                        var_kwargs,
                         call_pos,
                     )?;
                 } else {
@@ -1363,20 +1624,24 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
-    /// Compiles arguments with `*args` and/or `**kwargs` unpacking when callable is already on stack.
+    /// Builds the args tuple and optional kwargs dict for a call with `*args`/`**kwargs` unpacking.
     ///
-    /// This is used for expression calls (e.g., `(lambda *a: a)(*xs)`) where the callable
-    /// is compiled as an expression and is already on the stack.
+    /// Leading positional args are collected with `BuildList`, then each later argument is
+    /// appended (`ListAppend`) or unpacked (`ListExtend`) in source order before converting
+    /// to a tuple. Named kwargs go into a `BuildDict`, and each `**mapping` is merged with
+    /// `DictMerge`, which uses `merge_name_id` for its error messages.
     ///
-    /// Stack layout: callable (on stack) -> callable, args_tuple, kwargs_dict?
-    fn compile_call_args_with_unpacking(
+    /// Stack: [...] -> [..., args_tuple, kwargs_dict?]
+    ///
+    /// Returns whether a kwargs dict was pushed.
+    fn compile_unpacked_args(
         &mut self,
         args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
+        var_args: &[StarredArg],
         kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
-        call_pos: CodeRange,
-    ) -> Result<(), CompileError> {
+        var_kwargs: &[ExprLoc],
+        merge_name_id: u16,
+    ) -> Result<bool, CompileError> {
         // 1. Build args tuple
         // Push regular positional args and build list
         let pos_count = args.map_or(0, Vec::len);
@@ -1390,17 +1655,25 @@ impl<'a> Compiler<'a> {
             u16::try_from(pos_count).expect("positional arg count exceeds u16"),
         );
 
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
+        // Append or extend with the remaining args in source order
+        for arg in var_args {
+            match arg {
+                StarredArg::Value(expr) => {
+                    self.compile_expr(expr)?;
+                    self.code.emit_u8(Opcode::ListAppend, 0);
+                }
+                StarredArg::Unpack(expr) => {
+                    self.compile_expr(expr)?;
+                    self.code.emit(Opcode::ListExtend);
+                }
+            }
         }
 
         // Convert list to tuple
         self.code.emit(Opcode::ListToTuple);
 
         // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
+        let has_kwargs = kwargs.is_some() || !var_kwargs.is_empty();
         if has_kwargs {
             // Build dict from regular kwargs
             let kw_count = kwargs.map_or(0, Vec::len);
@@ -1418,15 +1691,33 @@ impl<'a> Compiler<'a> {
                 u16::try_from(kw_count).expect("keyword count exceeds u16"),
             );
 
-            // Merge **kwargs if present
-            // Use 0xFFFF for func_name_id (like builtins) since we don't have a name
-            if let Some(var_kwargs_expr) = var_kwargs {
+            // Merge each **kwargs mapping
+            for var_kwargs_expr in var_kwargs {
                 self.compile_expr(var_kwargs_expr)?;
-                self.code.emit_u16(Opcode::DictMerge, 0xFFFF);
+                self.code.emit_u16(Opcode::DictMerge, merge_name_id);
             }
         }
+        Ok(has_kwargs)
+    }
 
-        // 3. Call the function
+    /// Compiles arguments with `*args` and/or `**kwargs` unpacking when callable is already on stack.
+    ///
+    /// This is used for expression calls (e.g., `(lambda *a: a)(*xs)`) where the callable
+    /// is compiled as an expression and is already on the stack.
+    ///
+    /// Stack layout: callable (on stack) -> callable, args_tuple, kwargs_dict?
+    fn compile_call_args_with_unpacking(
+        &mut self,
+        args: Option<&Vec<ExprLoc>>,
+        var_args: &[StarredArg],
+        kwargs: Option<&Vec<Kwarg>>,
+        var_kwargs: &[ExprLoc],
+        call_pos: CodeRange,
+    ) -> Result<(), CompileError> {
+        // Use 0xFFFF for the DictMerge name (like builtins) since we don't have a name
+        let has_kwargs = self.compile_unpacked_args(args, var_args, kwargs, var_kwargs, 0xFFFF)?;
+
+        // Call the function
         self.code.set_location(call_pos, None);
         let flags = u8::from(has_kwargs);
         self.code.emit_u8(Opcode::CallFunctionExtended, flags);
@@ -1481,9 +1772,9 @@ impl<'a> Compiler<'a> {
         &mut self,
         callable: &Callable,
         args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
+        var_args: &[StarredArg],
         kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
+        var_kwargs: &[ExprLoc],
         call_pos: CodeRange,
     ) -> Result<(), CompileError> {
         // Get function name for error messages (0xFFFF for builtins)
@@ -1492,55 +1783,9 @@ impl<'a> Compiler<'a> {
             Callable::Builtin(_) => 0xFFFF,
         };
 
-        // 1. Build args tuple
-        // Push regular positional args and build list
-        let pos_count = args.map_or(0, Vec::len);
-        if let Some(args) = args {
-            for arg in args {
-                self.compile_expr(arg)?;
-            }
-        }
-        self.code.emit_u16(
-            Opcode::BuildList,
-            u16::try_from(pos_count).expect("positional arg count exceeds u16"),
-        );
-
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
-        }
-
-        // Convert list to tuple
-        self.code.emit(Opcode::ListToTuple);
-
-        // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
-        if has_kwargs {
-            // Build dict from regular kwargs
-            let kw_count = kwargs.map_or(0, Vec::len);
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    // Push key as interned string constant
-                    let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
-                    self.code.emit_u16(Opcode::LoadConst, key_const);
-                    // Push value
-                    self.compile_expr(&kwarg.value)?;
-                }
-            }
-            self.code.emit_u16(
-                Opcode::BuildDict,
-                u16::try_from(kw_count).expect("keyword count exceeds u16"),
-            );
-
-            // Merge **kwargs if present
-            if let Some(var_kwargs_expr) = var_kwargs {
-                self.compile_expr(var_kwargs_expr)?;
-                self.code.emit_u16(Opcode::DictMerge, func_name_id);
-            }
-        }
+        let has_kwargs = self.compile_unpacked_args(args, var_args, kwargs, var_kwargs, func_name_id)?;
 
-        // 3. Call the function
+        // Call the function
         self.code.set_location(call_pos, None);
         let flags = u8::from(has_kwargs);
         self.code.emit_u8(Opcode::CallFunctionExtended, flags);
@@ -1636,13 +1881,13 @@ impl<'a> Compiler<'a> {
                 var_kwargs,
             } => {
                 // Check if there's unpacking - use CallAttrExtended
-                if var_args.is_some() || var_kwargs.is_some() {
+                if !var_args.is_empty() || !var_kwargs.is_empty() {
                     return self.compile_method_call_with_unpacking(
                         name_id,
                         args.as_ref(),
-                        var_args.as_ref(),
+                        var_args,
                         kwargs.as_ref(),
-                        var_kwargs.as_ref(),
+                        var_kwargs,
                         call_pos,
                     );
                 }
@@ -1699,66 +1944,17 @@ impl<'a> Compiler<'a> {
         &mut self,
         name_id: StringId,
         args: Option<&Vec<ExprLoc>>,
-        var_args: Option<&ExprLoc>,
+        var_args: &[StarredArg],
         kwargs: Option<&Vec<Kwarg>>,
-        var_kwargs: Option<&ExprLoc>,
+        var_kwargs: &[ExprLoc],
         call_pos: CodeRange,
     ) -> Result<(), CompileError> {
-        // 1. Build args tuple
-        // Push regular positional args and build list
-        let pos_count = args.map_or(0, Vec::len);
-        if let Some(args) = args {
-            for arg in args {
-                self.compile_expr(arg)?;
-            }
-        }
-        self.code.emit_u16(
-            Opcode::BuildList,
-            u16::try_from(pos_count).expect("positional arg count exceeds u16"),
-        );
-
-        // Extend with *args if present
-        if let Some(var_args_expr) = var_args {
-            self.compile_expr(var_args_expr)?;
-            self.code.emit(Opcode::ListExtend);
-        }
-
-        // Convert list to tuple
-        self.code.emit(Opcode::ListToTuple);
-
-        // 2. Build kwargs dict (if we have kwargs or var_kwargs)
-        let has_kwargs = kwargs.is_some() || var_kwargs.is_some();
-        if has_kwargs {
-            // Build dict from regular kwargs
-            let kw_count = kwargs.map_or(0, Vec::len);
-            if let Some(kwargs) = kwargs {
-                for kwarg in kwargs {
-                    // Push key as interned string constant
-                    let key_const = self.code.add_const(Value::InternString(kwarg.key.name_id));
-                    self.code.emit_u16(Opcode::LoadConst, key_const);
-                    // Push value
-                    self.compile_expr(&kwarg.value)?;
-                }
-            }
-            self.code.emit_u16(
-                Opcode::BuildDict,
-                u16::try_from(kw_count).expect("keyword count exceeds u16"),
-            );
-
-            // Merge **kwargs if present
-            if let Some(var_kwargs_expr) = var_kwargs {
-                self.compile_expr(var_kwargs_expr)?;
-                // Use the method name for error messages
-                self.code.emit_u16(
-                    Opcode::DictMerge,
-                    u16::try_from(name_id.index()).expect("name index exceeds u16"),
-                );
-            }
-        }
+        // Use the method name for DictMerge error messages
+        let name_idx = u16::try_from(name_id.index()).expect("name index exceeds u16");
+        let has_kwargs = self.compile_unpacked_args(args, var_args, kwargs, var_kwargs, name_idx)?;
 
-        // 3. Call the method with CallAttrExtended
+        // Call the method with CallAttrExtended
         self.code.set_location(call_pos, None);
-        let name_idx = u16::try_from(name_id.index()).expect("name index exceeds u16");
         let flags = u8::from(has_kwargs);
         self.code.emit_u16_u8(Opcode::CallAttrExtended, name_idx, flags);
         Ok(())
@@ -1843,6 +2039,17 @@ impl<'a> Compiler<'a> {
         body: &[PreparedNode],
         or_else: &[PreparedNode],
     ) -> Result<(), CompileError> {
+        // `while False:` never runs its body, `while True:` needs no test and can only
+        // be left by `break`, which skips the else block
+        let const_test = if self.options.optimize {
+            const_truthiness(test, self.interns)
+        } else {
+            None
+        };
+        if const_test == Some(false) {
+            return self.compile_block(or_else);
+        }
+
         let loop_start = self.code.current_offset();
 
         self.loop_stack.push(LoopInfo {
@@ -1851,17 +2058,22 @@ impl<'a> Compiler<'a> {
             has_iterator_on_stack: false,
         });
 
-        self.compile_expr(test)?;
-        let end_jump = self.code.emit_jump(Opcode::JumpIfFalse);
+        let end_jump = if const_test.is_none() {
+            self.compile_expr(test)?;
+            Some(self.code.emit_jump(Opcode::JumpIfFalse))
+        } else {
+            None
+        };
 
         self.compile_block(body)?;
         self.code.emit_jump_to(Opcode::Jump, loop_start);
 
-        self.code.patch_jump(end_jump);
         let loop_info = self.loop_stack.pop().expect("loop stack underflow");
-
-        if !or_else.is_empty() {
-            self.compile_block(or_else)?;
+        if let Some(end_jump) = end_jump {
+            self.code.patch_jump(end_jump);
+            if !or_else.is_empty() {
+                self.compile_block(or_else)?;
+            }
         }
 
         for break_jump in loop_info.break_jumps {
@@ -1890,6 +2102,7 @@ impl<'a> Compiler<'a> {
         if self.loop_stack.is_empty() {
             return Err(CompileError::new("'break' outside loop", position));
         }
+        self.code.set_location(position, None);
 
         let target_loop_depth = self.loop_stack.len() - 1;
 
@@ -1952,6 +2165,7 @@ impl<'a> Compiler<'a> {
         if self.loop_stack.is_empty() {
             return Err(CompileError::new("'continue' not properly in loop", position));
         }
+        self.code.set_location(position, None);
 
         let target_loop_depth = self.loop_stack.len() - 1;
 
@@ -2221,12 +2435,75 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Compiles a store of the value on top of the stack into one chained assignment target.
+    ///
+    /// Stack: [..., value] -> [...]
+    fn compile_assign_target(&mut self, target: &AssignTarget) -> Result<(), CompileError> {
+        match target {
+            AssignTarget::Unpack(target) => self.compile_unpack_target(target),
+            AssignTarget::Subscript {
+                target,
+                index,
+                target_position,
+            } => {
+                // Stack order for StoreSubscr: value, obj, index
+                self.compile_name(target);
+                self.compile_expr(index)?;
+                self.code.set_location(*target_position, None);
+                self.code.emit(Opcode::StoreSubscr);
+            }
+            AssignTarget::Attr {
+                object,
+                attr,
+                target_position,
+            } => {
+                // Stack order for StoreAttr: value, obj
+                self.compile_expr(object)?;
+                let name_id = attr.string_id().expect("StoreAttr requires interned attr name");
+                self.code.set_location(*target_position, None);
+                self.code.emit_u16(
+                    Opcode::StoreAttr,
+                    u16::try_from(name_id.index()).expect("name index exceeds u16"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Compiles one target of a `del` statement.
+    fn compile_delete_target(&mut self, target: &DeleteTarget) -> Result<(), CompileError> {
+        match target {
+            DeleteTarget::Name(ident) => {
+                // Set location so an unbound name error points at the name
+                self.code.set_location(ident.position, None);
+                self.compile_delete(ident);
+            }
+            DeleteTarget::Subscript {
+                object,
+                index,
+                target_position,
+            } => {
+                // Stack order for DeleteSubscr: obj, index
+                self.compile_expr(object)?;
+                self.compile_expr(index)?;
+                self.code.set_location(*target_position, None);
+                self.code.emit(Opcode::DeleteSubscr);
+            }
+        }
+        Ok(())
+    }
+
     // ========================================================================
     // Statement Helpers
     // ========================================================================
 
     /// Compiles an assert statement.
+    ///
+    /// Emits nothing when `CompileOptions::strip_asserts` is set.
     fn compile_assert(&mut self, test: &ExprLoc, msg: Option<&ExprLoc>) -> Result<(), CompileError> {
+        if self.options.strip_asserts {
+            return Ok(());
+        }
         // Compile test
         self.compile_expr(test)?;
         // Jump over raise if truthy
@@ -2692,7 +2969,7 @@ impl<'a> Compiler<'a> {
 
                 // Delete exception variable (Python 3 behavior)
                 if let Some(name) = &handler.name {
-                    self.compile_delete(name);
+                    self.compile_except_name_cleanup(name);
                 }
 
                 // Clear current_exception
@@ -2733,9 +3010,9 @@ impl<'a> Compiler<'a> {
                 // Exit except handler context
                 self.except_handler_depth -= 1;
 
-                // Delete exception variable
+                // Delete exception variable (Python 3 behavior)
                 if let Some(name) = &handler.name {
-                    self.compile_delete(name);
+                    self.compile_except_name_cleanup(name);
                 }
 
                 // Clear current_exception
@@ -2752,23 +3029,39 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
+    /// Unbinds an `except ... as name` variable at the end of its handler.
+    ///
+    /// Like CPython, the name is set to None before deleting it, so the delete
+    /// can't fail if the handler body already deleted the name itself.
+    fn compile_except_name_cleanup(&mut self, name: &Identifier) {
+        self.code.emit(Opcode::LoadNone);
+        self.compile_store(name);
+        self.compile_delete(name);
+    }
+
     /// Compiles deletion of a variable.
+    ///
+    /// At runtime, deleting an unbound variable raises the same error as loading it.
     fn compile_delete(&mut self, target: &Identifier) {
         let slot = u16::try_from(target.namespace_id().index()).expect("local slot exceeds u16");
         match target.scope {
             NameScope::Local | NameScope::LocalUnassigned => {
+                self.code.register_local_name(slot, target.name_id);
                 if let Ok(s) = u8::try_from(slot) {
                     self.code.emit_u8(Opcode::DeleteLocal, s);
                 } else {
-                    // Wide variant not implemented yet
-                    todo!("DeleteLocalW for slot > 255");
+                    self.code.emit_u16(Opcode::DeleteLocalW, slot);
                 }
             }
-            NameScope::Global | NameScope::Cell => {
-                // Delete global/cell not commonly needed
-                // For now, just store Undefined
-                self.code.emit(Opcode::LoadNone);
-                self.compile_store(target);
+            NameScope::Global => {
+                self.code.emit_u16(Opcode::DeleteGlobal, slot);
+            }
+            NameScope::Cell => {
+                // Convert namespace slot to cells array index
+                let cell_index = slot.saturating_sub(self.cell_base);
+                // Register the name for NameError messages (unbound free variable)
+                self.code.register_local_name(cell_index, target.name_id);
+                self.code.emit_u16(Opcode::DeleteCell, cell_index);
             }
         }
     }
@@ -2819,6 +3112,12 @@ impl CompileError {
         }
         MontyException::new_full(self.exc_type, Some(self.message.into_owned()), vec![frame])
     }
+
+    /// Converts this compile error into an exception raised at runtime, for code compiled
+    /// by `eval()` or `exec()`.
+    pub(crate) fn into_run_error(self) -> RunError {
+        SimpleException::new_msg(self.exc_type, self.message).into()
+    }
 }
 
 // ============================================================================
@@ -2848,6 +3147,34 @@ fn operator_to_opcode(op: &Operator) -> Opcode {
     }
 }
 
+/// Returns the kind reported to trace hooks for a statement, or `None` for statements
+/// that compile to no code and so never run.
+fn statement_kind(node: &PreparedNode) -> Option<StatementKind> {
+    let kind = match node {
+        Node::Expr(_) => StatementKind::Expr,
+        Node::Return(_) | Node::ReturnNone => StatementKind::Return,
+        Node::Raise { .. } => StatementKind::Raise,
+        Node::Assert { .. } => StatementKind::Assert,
+        Node::Assign { .. }
+        | Node::UnpackAssign { .. }
+        | Node::ChainAssign { .. }
+        | Node::SubscriptAssign { .. }
+        | Node::AttrAssign { .. } => StatementKind::Assign,
+        Node::OpAssign { .. } => StatementKind::AugAssign,
+        Node::Delete { .. } => StatementKind::Delete,
+        Node::For { .. } => StatementKind::For,
+        Node::While { .. } => StatementKind::While,
+        Node::Break { .. } => StatementKind::Break,
+        Node::Continue { .. } => StatementKind::Continue,
+        Node::If { .. } => StatementKind::If,
+        Node::FunctionDef(_) => StatementKind::FunctionDef,
+        Node::Try(_) => StatementKind::Try,
+        Node::Import { .. } | Node::ImportFrom { .. } => StatementKind::Import,
+        Node::Pass | Node::Global { .. } | Node::Nonlocal { .. } => return None,
+    };
+    Some(kind)
+}
+
 /// Maps an `Operator` to its in-place (augmented assignment) `Opcode`.
 ///
 /// Returns `None` for operators that don't have an in-place opcode (currently `MatMult`,
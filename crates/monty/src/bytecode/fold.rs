@@ -0,0 +1,133 @@
+//! Compile-time constant folding.
+//!
+//! Evaluates expressions built only from literals (e.g. `60 * 60 * 24`, `-1`, `not 0`)
+//! so the compiler can emit a single constant load instead of the operations. Folding
+//! is deliberately conservative: anything that could raise, overflow into a `LongInt`,
+//! or lose precision compared to the runtime result is left for the VM to evaluate,
+//! so folded and unfolded code always behave identically.
+
+use crate::{
+    expressions::{Expr, ExprLoc, Literal, Operator},
+    intern::Interns,
+    value::floor_divmod,
+};
+
+/// Largest integer magnitude that converts to `f64` exactly, used to guard `int / int`.
+const MAX_EXACT_F64_INT: u64 = 1 << 53;
+
+/// Returns the literal `expr` evaluates to, if it can be computed at compile time.
+pub(super) fn fold_expr(expr_loc: &ExprLoc, interns: &Interns) -> Option<Literal> {
+    match &expr_loc.expr {
+        Expr::Literal(literal) => Some(*literal),
+        Expr::Op { left, op, right } => {
+            let left = fold_expr(left, interns)?;
+            match op {
+                // Short-circuiting: the right operand only matters if the left doesn't decide
+                Operator::And => match literal_truthiness(&left, interns)? {
+                    false => Some(left),
+                    true => fold_expr(right, interns),
+                },
+                Operator::Or => match literal_truthiness(&left, interns)? {
+                    true => Some(left),
+                    false => fold_expr(right, interns),
+                },
+                _ => fold_binary(left, op, fold_expr(right, interns)?),
+            }
+        }
+        Expr::UnaryMinus(operand) => match fold_expr(operand, interns)? {
+            Literal::Int(n) => n.checked_neg().map(Literal::Int),
+            Literal::Float(f) => Some(Literal::Float(-f)),
+            _ => None,
+        },
+        Expr::UnaryPlus(operand) => match fold_expr(operand, interns)? {
+            literal @ (Literal::Int(_) | Literal::Float(_)) => Some(literal),
+            _ => None,
+        },
+        Expr::UnaryInvert(operand) => match fold_expr(operand, interns)? {
+            Literal::Int(n) => Some(Literal::Int(!n)),
+            _ => None,
+        },
+        Expr::Not(operand) => {
+            let literal = fold_expr(operand, interns)?;
+            literal_truthiness(&literal, interns).map(|truthy| Literal::Bool(!truthy))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether `expr` is a constant, and if so its truthiness.
+///
+/// Used to drop the dead branch of `if False:` / `while True:` style statements.
+pub(super) fn const_truthiness(expr_loc: &ExprLoc, interns: &Interns) -> Option<bool> {
+    literal_truthiness(&fold_expr(expr_loc, interns)?, interns)
+}
+
+/// Python truthiness of a literal, `None` for markers whose truthiness isn't known here.
+fn literal_truthiness(literal: &Literal, interns: &Interns) -> Option<bool> {
+    match literal {
+        Literal::None => Some(false),
+        Literal::Ellipsis | Literal::LongInt(_) => Some(true),
+        Literal::Bool(b) => Some(*b),
+        Literal::Int(n) => Some(*n != 0),
+        Literal::Float(f) => Some(*f != 0.0),
+        Literal::Str(id) => Some(!interns.get_str(*id).is_empty()),
+        Literal::Bytes(id) => Some(!interns.get_bytes(*id).is_empty()),
+        Literal::Marker(_) => None,
+    }
+}
+
+/// Folds a binary operation on two numeric literals.
+///
+/// Bools are not folded since bitwise operators on two bools return a bool, which
+/// isn't worth special-casing. Float `//`, `%` and `**` are left to the runtime.
+fn fold_binary(left: Literal, op: &Operator, right: Literal) -> Option<Literal> {
+    match (left, right) {
+        (Literal::Int(a), Literal::Int(b)) => fold_int(a, op, b),
+        (Literal::Float(a), Literal::Float(b)) => fold_float(a, op, b),
+        (Literal::Int(a), Literal::Float(b)) if a.unsigned_abs() <= MAX_EXACT_F64_INT => fold_float(a as f64, op, b),
+        (Literal::Float(a), Literal::Int(b)) if b.unsigned_abs() <= MAX_EXACT_F64_INT => fold_float(a, op, b as f64),
+        _ => None,
+    }
+}
+
+fn fold_int(a: i64, op: &Operator, b: i64) -> Option<Literal> {
+    let result = match op {
+        Operator::Add => a.checked_add(b)?,
+        Operator::Sub => a.checked_sub(b)?,
+        Operator::Mult => a.checked_mul(b)?,
+        Operator::FloorDiv => floor_divmod(a, b)?.0,
+        Operator::Mod => floor_divmod(a, b)?.1,
+        Operator::Pow => a.checked_pow(u32::try_from(b).ok()?)?,
+        Operator::LShift => {
+            let shift = u32::try_from(b).ok().filter(|s| *s < 64)?;
+            let shifted = a << shift;
+            // Bits shifted out mean the runtime would promote to a LongInt
+            (shifted >> shift == a).then_some(shifted)?
+        }
+        Operator::RShift => a >> u32::try_from(b).ok()?.min(63),
+        Operator::BitAnd => a & b,
+        Operator::BitOr => a | b,
+        Operator::BitXor => a ^ b,
+        Operator::Div => {
+            // True division of ints is correctly rounded at runtime; only fold when both
+            // operands convert to `f64` exactly so the quotient matches
+            if b == 0 || a.unsigned_abs() > MAX_EXACT_F64_INT || b.unsigned_abs() > MAX_EXACT_F64_INT {
+                return None;
+            }
+            return Some(Literal::Float(a as f64 / b as f64));
+        }
+        Operator::MatMult | Operator::And | Operator::Or => return None,
+    };
+    Some(Literal::Int(result))
+}
+
+fn fold_float(a: f64, op: &Operator, b: f64) -> Option<Literal> {
+    let result = match op {
+        Operator::Add => a + b,
+        Operator::Sub => a - b,
+        Operator::Mult => a * b,
+        Operator::Div if b != 0.0 => a / b,
+        _ => return None,
+    };
+    Some(Literal::Float(result))
+}
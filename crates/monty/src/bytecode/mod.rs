@@ -10,14 +10,16 @@
 //! - `code` - Code object containing bytecode and metadata
 //! - `builder` - CodeBuilder for emitting bytecode during compilation
 //! - `compiler` - AST to bytecode compiler
+//! - `fold` - Compile-time constant folding used by the compiler
 //! - `vm` - Virtual machine for bytecode execution
 
 mod builder;
 mod code;
 mod compiler;
+mod fold;
 mod op;
 mod vm;
 
 pub use code::Code;
-pub use compiler::Compiler;
+pub use compiler::{CompileOptions, Compiler};
 pub use vm::{FrameExit, VM, VMSnapshot};
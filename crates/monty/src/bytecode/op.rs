@@ -69,8 +69,14 @@ pub enum Opcode {
     LoadCell,
     /// Store to closure cell. Operand: u16 slot.
     StoreCell,
-    /// Delete local variable. Operand: u8 slot.
+    /// Delete local variable, raising if it is unbound. Operand: u8 slot.
     DeleteLocal,
+    /// Delete local (wide). Operand: u16 slot.
+    DeleteLocalW,
+    /// Delete from global namespace, raising `NameError` if unbound. Operand: u16 slot.
+    DeleteGlobal,
+    /// Clear a closure cell, raising if it is unbound. Operand: u16 slot.
+    DeleteCell,
 
     // === Binary Operations (no operand) ===
     /// Add: a + b.
@@ -231,7 +237,8 @@ pub enum Opcode {
     BinarySubscr,
     /// a[b] = c: pop value, pop index, pop obj.
     StoreSubscr,
-    // NOTE: DeleteSubscr removed - `del` statement not supported by parser
+    /// del a[b]: pop index, pop obj.
+    DeleteSubscr,
     /// Pop obj, push obj.attr. Operand: u16 name_id.
     LoadAttr,
     /// Pop module, push module.attr for `from ... import`. Operand: u16 name_id.
@@ -327,12 +334,18 @@ pub enum Opcode {
     MakeFunction,
     /// Create closure. Operands: u16 func_id, u8 cell_count.
     MakeClosure,
+    /// Create a user-defined exception class from the base on TOS. Operand: u16 class_id.
+    ///
+    /// Records the class's place in the exception hierarchy and replaces the base with
+    /// the class. Raises `NotImplementedError` if the base isn't an exception class.
+    MakeExcClass,
 
     // === Exception Handling ===
     // Note: No SetupTry/PopExceptHandler - we use static exception_table
     /// Raise TOS as exception.
     Raise,
-    // NOTE: RaiseFrom removed - `raise ... from ...` not supported by parser
+    /// Raise TOS1 as exception with TOS as its `__cause__` (`raise exc from cause`).
+    RaiseFrom,
     /// Re-raise current exception (bare `raise`).
     Reraise,
     /// Clear current_exception when exiting except block.
@@ -376,6 +389,22 @@ pub enum Opcode {
     /// The module_id maps to `BuiltinModule` (0=sys, 1=typing).
     /// Creates the module on the heap and pushes a `Value::Ref` to it.
     LoadModule,
+    /// Load a host-provided native module onto the stack. Operand: u16 module index.
+    ///
+    /// The index maps to a `NativeModuleDef` registered in `Interns`.
+    /// Creates the module on the heap and pushes a `Value::Ref` to it.
+    LoadNativeModule,
+    /// Import a host-supplied Python module onto the stack. Operand: u16 module index.
+    ///
+    /// The index maps to a `PythonModuleDef` registered in `Interns`. Pushes the cached
+    /// module if it was already imported, otherwise pushes a frame running the module body,
+    /// whose return value is the module.
+    ImportPythonModule,
+    /// Build the module object at the end of a Python module body. Operand: u16 module index.
+    ///
+    /// Creates a module from the current namespace's defined variables, caches it in the
+    /// namespace and pushes it.
+    BuildPythonModule,
     /// Raises `ModuleNotFoundError` at runtime. Operand: u16 constant index for module name.
     ///
     /// This opcode is emitted when the compiler encounters an import of an unknown module.
@@ -407,17 +436,18 @@ impl Opcode {
         use Opcode::{
             Await, BinaryAdd, BinaryAnd, BinaryDiv, BinaryFloorDiv, BinaryLShift, BinaryMatMul, BinaryMod, BinaryMul,
             BinaryOr, BinaryPow, BinaryRShift, BinarySub, BinarySubscr, BinaryXor, BuildDict, BuildFString, BuildList,
-            BuildSet, BuildSlice, BuildTuple, CallAttr, CallAttrExtended, CallAttrKw, CallBuiltinFunction,
-            CallBuiltinType, CallFunction, CallFunctionExtended, CallFunctionKw, CheckExcMatch, ClearException,
-            CompareEq, CompareGe, CompareGt, CompareIn, CompareIs, CompareIsNot, CompareLe, CompareLt, CompareModEq,
-            CompareNe, CompareNotIn, DeleteLocal, DictMerge, DictSetItem, Dup, ForIter, FormatValue, GetIter,
-            InplaceAdd, InplaceAnd, InplaceDiv, InplaceFloorDiv, InplaceLShift, InplaceMod, InplaceMul, InplaceOr,
-            InplacePow, InplaceRShift, InplaceSub, InplaceXor, Jump, JumpIfFalse, JumpIfFalseOrPop, JumpIfTrue,
-            JumpIfTrueOrPop, ListAppend, ListExtend, ListToTuple, LoadAttr, LoadAttrImport, LoadCell, LoadConst,
-            LoadFalse, LoadGlobal, LoadLocal, LoadLocal0, LoadLocal1, LoadLocal2, LoadLocal3, LoadLocalW, LoadModule,
-            LoadNone, LoadSmallInt, LoadTrue, MakeClosure, MakeFunction, Nop, Pop, Raise, RaiseImportError, Reraise,
-            ReturnValue, Rot2, Rot3, SetAdd, StoreAttr, StoreCell, StoreGlobal, StoreLocal, StoreLocalW, StoreSubscr,
-            UnaryInvert, UnaryNeg, UnaryNot, UnaryPos, UnpackEx, UnpackSequence,
+            BuildPythonModule, BuildSet, BuildSlice, BuildTuple, CallAttr, CallAttrExtended, CallAttrKw,
+            CallBuiltinFunction, CallBuiltinType, CallFunction, CallFunctionExtended, CallFunctionKw, CheckExcMatch,
+            ClearException, CompareEq, CompareGe, CompareGt, CompareIn, CompareIs, CompareIsNot, CompareLe, CompareLt,
+            CompareModEq, CompareNe, CompareNotIn, DeleteCell, DeleteGlobal, DeleteLocal, DeleteLocalW, DeleteSubscr,
+            DictMerge, DictSetItem, Dup, ForIter, FormatValue, GetIter, ImportPythonModule, InplaceAdd, InplaceAnd,
+            InplaceDiv, InplaceFloorDiv, InplaceLShift, InplaceMod, InplaceMul, InplaceOr, InplacePow, InplaceRShift,
+            InplaceSub, InplaceXor, Jump, JumpIfFalse, JumpIfFalseOrPop, JumpIfTrue, JumpIfTrueOrPop, ListAppend,
+            ListExtend, ListToTuple, LoadAttr, LoadAttrImport, LoadCell, LoadConst, LoadFalse, LoadGlobal, LoadLocal,
+            LoadLocal0, LoadLocal1, LoadLocal2, LoadLocal3, LoadLocalW, LoadModule, LoadNativeModule, LoadNone,
+            LoadSmallInt, LoadTrue, MakeClosure, MakeExcClass, MakeFunction, Nop, Pop, Raise, RaiseFrom,
+            RaiseImportError, Reraise, ReturnValue, Rot2, Rot3, SetAdd, StoreAttr, StoreCell, StoreGlobal, StoreLocal,
+            StoreLocalW, StoreSubscr, UnaryInvert, UnaryNeg, UnaryNot, UnaryPos, UnpackEx, UnpackSequence,
         };
         Some(match self {
             // Stack operations
@@ -432,7 +462,7 @@ impl Opcode {
             LoadLocal0 | LoadLocal1 | LoadLocal2 | LoadLocal3 => 1,
             LoadLocal | LoadLocalW | LoadGlobal | LoadCell => 1,
             StoreLocal | StoreLocalW | StoreGlobal | StoreCell => -1,
-            DeleteLocal => 0, // doesn't affect stack
+            DeleteLocal | DeleteLocalW | DeleteGlobal | DeleteCell => 0, // doesn't affect stack
 
             // Binary operations: pop 2, push 1 = -1
             BinaryAdd | BinarySub | BinaryMul | BinaryDiv | BinaryFloorDiv | BinaryMod | BinaryPow | BinaryAnd
@@ -469,6 +499,7 @@ impl Opcode {
             // Subscript & Attribute
             BinarySubscr => -1,             // pop 2, push 1
             StoreSubscr => -3,              // pop 3, push 0
+            DeleteSubscr => -2,             // pop 2, push 0
             LoadAttr | LoadAttrImport => 0, // pop 1, push 1
             StoreAttr => -2,                // pop 2, push 0
 
@@ -490,9 +521,12 @@ impl Opcode {
 
             // Function definition - push 1 (the function/closure)
             MakeFunction | MakeClosure => 1,
+            // Class definition - pop base, push class
+            MakeExcClass => 0,
 
             // Exception handling
             Raise => -1,         // pop exception
+            RaiseFrom => -2,     // pop exception and cause
             Reraise => 0,        // no stack change (reads from exception_stack)
             ClearException => 0, // clears exception_stack, no operand stack change
             CheckExcMatch => 0,  // pop exc_type, push bool (net 0, but exc stays)
@@ -507,7 +541,7 @@ impl Opcode {
             Nop => 0,
 
             // Module
-            LoadModule => 1,       // push module
+            LoadModule | LoadNativeModule | ImportPythonModule | BuildPythonModule => 1, // push module
             RaiseImportError => 0, // raises exception, no stack change before that
         })
     }
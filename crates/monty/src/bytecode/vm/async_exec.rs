@@ -671,6 +671,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                         cells: sf.cells,
                         call_position: sf.call_position,
                         should_return: false,
+                        cache_results: sf.cache_results,
                     }
                 })
                 .collect();
@@ -3,14 +3,37 @@
 use super::VM;
 use crate::{
     defer_drop,
-    exception_private::{ExcType, RunError},
-    heap::HeapGuard,
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{Heap, HeapGuard},
+    intern::Interns,
     resource::ResourceTracker,
-    types::PyTrait,
-    value::BitwiseOp,
+    types::{
+        PyTrait, SetBinaryOp, TemporalOp, py_dict_inplace_merge, py_dict_merge, py_dict_view_set_op, py_set_binary_op,
+        py_set_inplace_op, py_temporal_op, str::py_str_mod,
+    },
+    value::{BitwiseOp, Value},
 };
 
 impl<T: ResourceTracker> VM<'_, '_, T> {
+    /// Fast path for arithmetic on two `Int`s, the overwhelmingly common case in hot loops.
+    ///
+    /// If the top two stack values are both `Value::Int` and `op` produces a result, the pair
+    /// is replaced with that result in place, skipping the pop/`defer_drop!`/`py_*` dispatch.
+    /// Returns `false` with the stack untouched otherwise (non-int operands, overflow, division
+    /// by zero) so the caller falls back to the generic handler, which raises or promotes.
+    #[inline]
+    pub(super) fn try_int_binary_op(&mut self, op: impl FnOnce(i64, i64) -> Option<i64>) -> bool {
+        if let [.., Value::Int(a), Value::Int(b)] = self.stack.as_slice()
+            && let Some(result) = op(*a, *b)
+        {
+            self.stack.pop();
+            *self.stack.last_mut().expect("stack underflow") = Value::Int(result);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Binary addition with proper refcount handling.
     ///
     /// Uses lazy type capture: only calls `py_type()` in error paths to avoid
@@ -29,6 +52,10 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Add, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("+", lhs_type, rhs_type))
@@ -41,6 +68,13 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     ///
     /// Uses lazy type capture: only calls `py_type()` in error paths.
     pub(super) fn binary_sub(&mut self) -> Result<(), RunError> {
+        self.subtract("-")
+    }
+
+    /// Subtraction, naming `symbol` in the `TypeError` for unsupported operands.
+    ///
+    /// `symbol` is `-=` when this is the fallback of an in-place subtraction.
+    fn subtract(&mut self, symbol: &str) -> Result<(), RunError> {
         let this = self;
 
         let rhs = this.pop();
@@ -54,9 +88,21 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_set_binary_op(lhs, rhs, SetBinaryOp::Difference, this.heap, this.interns)? {
+                    this.push(v);
+                    return Ok(());
+                }
+                if let Some(v) = py_dict_view_set_op(lhs, rhs, SetBinaryOp::Difference, this.heap, this.interns)? {
+                    this.push(v);
+                    return Ok(());
+                }
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Sub, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
-                Err(ExcType::binary_type_error("-", lhs_type, rhs_type))
+                Err(ExcType::binary_type_error(symbol, lhs_type, rhs_type))
             }
             Err(e) => Err(e.into()),
         }
@@ -79,6 +125,10 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Mult, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("*", lhs_type, rhs_type))
@@ -104,6 +154,10 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Div, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("/", lhs_type, rhs_type))
@@ -129,6 +183,10 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::FloorDiv, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("//", lhs_type, rhs_type))
@@ -154,6 +212,14 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 Ok(())
             }
             Ok(None) => {
+                if let Some(v) = py_str_mod(lhs, rhs, this.heap, this.interns)? {
+                    this.push(v);
+                    return Ok(());
+                }
+                if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Mod, this.heap)? {
+                    this.push(v);
+                    return Ok(());
+                }
                 let lhs_type = lhs.py_type(this.heap);
                 let rhs_type = rhs.py_type(this.heap);
                 Err(ExcType::binary_type_error("%", lhs_type, rhs_type))
@@ -188,10 +254,19 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         }
     }
 
-    /// Binary bitwise operation on integers.
+    /// Binary bitwise operation on integers, or the equivalent set or dict operator.
     ///
     /// Pops two values, performs the bitwise operation, and pushes the result.
+    /// `|`, `&` and `^` between sets/frozensets (or dict keys/items views) compute union,
+    /// intersection and symmetric difference; `|` between dicts merges them.
     pub(super) fn binary_bitwise(&mut self, op: BitwiseOp) -> Result<(), RunError> {
+        self.bitwise(op, op.as_str())
+    }
+
+    /// Bitwise operation, naming `symbol` in the `TypeError` for unsupported operands.
+    ///
+    /// `symbol` is the in-place operator when this is the fallback of an in-place operation.
+    fn bitwise(&mut self, op: BitwiseOp, symbol: &str) -> Result<(), RunError> {
         let this = self;
 
         let rhs = this.pop();
@@ -199,9 +274,30 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         let lhs = this.pop();
         defer_drop!(lhs, this);
 
-        let result = lhs.py_bitwise(rhs, op, this.heap)?;
-        this.push(result);
-        Ok(())
+        if let Some(set_op) = SetBinaryOp::from_bitwise(op) {
+            if let Some(v) = py_set_binary_op(lhs, rhs, set_op, this.heap, this.interns)? {
+                this.push(v);
+                return Ok(());
+            }
+            if let Some(v) = py_dict_view_set_op(lhs, rhs, set_op, this.heap, this.interns)? {
+                this.push(v);
+                return Ok(());
+            }
+        }
+        if matches!(op, BitwiseOp::Or)
+            && let Some(v) = py_dict_merge(lhs, rhs, this.heap, this.interns)?
+        {
+            this.push(v);
+            return Ok(());
+        }
+
+        if let Some(v) = lhs.py_bitwise(rhs, op, this.heap)? {
+            this.push(v);
+            return Ok(());
+        }
+        let lhs_type = lhs.py_type(this.heap);
+        let rhs_type = rhs.py_type(this.heap);
+        Err(ExcType::binary_type_error(symbol, lhs_type, rhs_type))
     }
 
     /// In-place addition (uses py_iadd for mutable containers, falls back to py_add).
@@ -236,11 +332,77 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
             return Ok(());
         }
 
+        if let Some(v) = py_temporal_op(lhs, rhs, TemporalOp::Add, this.heap)? {
+            this.push(v);
+            return Ok(());
+        }
+
         let lhs_type = lhs.py_type(this.heap);
         let rhs_type = rhs.py_type(this.heap);
         Err(ExcType::binary_type_error("+=", lhs_type, rhs_type))
     }
 
+    /// In-place subtraction (`-=`).
+    ///
+    /// Mutable sets are updated in place (`set.difference_update` semantics), so aliases
+    /// of the set observe the change. Everything else falls back to binary subtraction.
+    pub(super) fn inplace_sub(&mut self) -> Result<(), RunError> {
+        self.inplace_update(
+            |lhs, rhs, heap, interns| py_set_inplace_op(lhs, rhs, SetBinaryOp::Difference, heap, interns),
+            |this| this.subtract("-="),
+        )
+    }
+
+    /// In-place bitwise operation (`&=`, `|=`, `^=`, `<<=`, `>>=`).
+    ///
+    /// Mutable sets are updated in place for `&=`, `|=` and `^=`, and dicts are updated
+    /// in place for `|=`. Everything else falls back to the binary bitwise operation.
+    pub(super) fn inplace_bitwise(&mut self, op: BitwiseOp) -> Result<(), RunError> {
+        let Some(set_op) = SetBinaryOp::from_bitwise(op) else {
+            return self.bitwise(op, op.as_inplace_str());
+        };
+        self.inplace_update(
+            |lhs, rhs, heap, interns| {
+                if matches!(op, BitwiseOp::Or) && py_dict_inplace_merge(lhs, rhs, heap, interns)? {
+                    return Ok(true);
+                }
+                py_set_inplace_op(lhs, rhs, set_op, heap, interns)
+            },
+            |this| this.bitwise(op, op.as_inplace_str()),
+        )
+    }
+
+    /// Shared implementation of in-place operators on mutable containers.
+    ///
+    /// Peeks at the two operands and calls `update`: if it mutates the left operand
+    /// (returning `true`), the left operand is left on the stack. Otherwise the operands
+    /// are left untouched and `fallback` performs the binary operation.
+    fn inplace_update(
+        &mut self,
+        update: impl FnOnce(&Value, &Value, &mut Heap<T>, &Interns) -> RunResult<bool>,
+        fallback: impl FnOnce(&mut Self) -> Result<(), RunError>,
+    ) -> Result<(), RunError> {
+        let rhs = self.pop();
+        let lhs = self.stack.last().expect("stack underflow");
+        let updated = update(lhs, &rhs, self.heap, self.interns);
+        match updated {
+            Ok(true) => {
+                rhs.drop_with_heap(self.heap);
+                Ok(())
+            }
+            Ok(false) => {
+                self.push(rhs);
+                fallback(self)
+            }
+            Err(e) => {
+                rhs.drop_with_heap(self.heap);
+                let lhs = self.pop();
+                lhs.drop_with_heap(self.heap);
+                Err(e)
+            }
+        }
+    }
+
     /// Binary matrix multiplication (`@` operator).
     ///
     /// Currently not implemented - returns a `NotImplementedError`.
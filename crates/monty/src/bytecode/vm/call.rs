@@ -6,21 +6,26 @@
 
 use super::{CallFrame, VM};
 use crate::{
+    InvalidInputError, MontyObject,
     args::{ArgValues, KwargsValues},
     asyncio::Coroutine,
-    builtins::{Builtins, BuiltinsFunctions},
+    builtins::{Builtins, BuiltinsFunctions, write_input_prompt},
     bytecode::FrameExit,
     defer_drop,
-    exception_private::{ExcType, RunError},
+    exception_private::{ExcType, RunError, SimpleException},
     heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
     intern::{ExtFunctionId, FunctionId, Interns, StaticStrings, StringId},
+    namespace::GLOBAL_NS_IDX,
     os::OsFunction,
     resource::ResourceTracker,
     types::{
-        AttrCallResult, Dict, PyTrait, Type,
+        AttrCallResult, Date, DateTime, Dict, PyTrait, Type,
+        bytearray::bytearray_fromhex,
         bytes::{bytes_fromhex, call_bytes_method},
         dict::dict_fromkeys,
-        str::call_str_method,
+        lru_cache::lru_cache_key,
+        partial::bind_partial_args,
+        str::{call_str_method, str_maketrans},
     },
     value::{EitherStr, Value},
 };
@@ -91,11 +96,21 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     ///
     /// Calls a builtin function directly without stack manipulation for the callable.
     /// This is an optimization that avoids constant pool lookup and stack manipulation.
-    pub(super) fn exec_call_builtin_function(&mut self, builtin_id: u8, arg_count: usize) -> Result<Value, RunError> {
+    pub(super) fn exec_call_builtin_function(
+        &mut self,
+        builtin_id: u8,
+        arg_count: usize,
+    ) -> Result<CallResult, RunError> {
         // Convert u8 to BuiltinsFunctions via FromRepr
         if let Some(builtin) = BuiltinsFunctions::from_repr(builtin_id) {
             let args = self.pop_n_args(arg_count);
-            builtin.call(self, args)
+            match builtin {
+                BuiltinsFunctions::Input => return self.call_input(args),
+                BuiltinsFunctions::Eval => return self.call_eval(true, args),
+                BuiltinsFunctions::Exec => return self.call_eval(false, args),
+                _ => {}
+            }
+            builtin.call(self, args).map(CallResult::Push)
         } else {
             Err(RunError::internal("CallBuiltinFunction: invalid builtin_id"))
         }
@@ -295,7 +310,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
             }
             Value::Builtin(Builtins::Type(t)) => {
                 // Handle classmethods on type objects like dict.fromkeys()
-                call_type_method(t, name_id, args, this.heap, this.interns).map(CallResult::Push)
+                call_type_method(t, name_id, args, this.heap, this.interns)
             }
             _ => {
                 // Non-heap values without method support
@@ -330,6 +345,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 match self.run()? {
                     FrameExit::Return(v) => Ok(v),
                     FrameExit::ResolveFutures(_)
+                    | FrameExit::Paused(_)
                     | FrameExit::ExternalCall { .. }
                     | FrameExit::OsCall { .. }
                     | FrameExit::MethodCall { .. } => {
@@ -355,6 +371,58 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         }
     }
 
+    /// Calls the main module's global `name` with arguments from the host, running it until it
+    /// returns or needs the host.
+    ///
+    /// Used once the module has run: its frame is still on the stack, so the call has a
+    /// position for tracebacks and returns from `run()` like any call marked `should_return`.
+    pub fn call_global(
+        &mut self,
+        name: &str,
+        args: Vec<MontyObject>,
+        kwargs: Vec<(String, MontyObject)>,
+    ) -> Result<FrameExit, RunError> {
+        let interns = self.interns;
+        let slot = interns
+            .global_names()
+            .iter()
+            .position(|id| *id != StringId::default() && interns.get_str(*id) == name);
+        let callable = match slot.map(|slot| &self.namespaces.get(GLOBAL_NS_IDX).values()[slot]) {
+            Some(Value::Undefined) | None => return Err(ExcType::name_error(name).into()),
+            Some(value) => value.clone_with_heap(self.heap),
+        };
+        let args = match host_args(args, kwargs, self.heap, interns) {
+            Ok(args) => args,
+            Err(error) => {
+                callable.drop_with_heap(self.heap);
+                return Err(error);
+            }
+        };
+        let result = self.call_function(&callable, args);
+        callable.drop_with_heap(self.heap);
+        match result? {
+            CallResult::Push(value) => Ok(FrameExit::Return(value)),
+            CallResult::FramePushed => {
+                self.current_frame_mut().should_return = true;
+                self.run()
+            }
+            CallResult::External(_, args) | CallResult::OsCall(_, args) | CallResult::MethodCall(_, args) => {
+                args.drop_with_heap(self.heap);
+                Err(not_defined_function(name))
+            }
+            CallResult::AwaitValue(value) => {
+                value.drop_with_heap(self.heap);
+                Err(not_defined_function(name))
+            }
+        }
+    }
+
+    /// Calls the `input()` builtin: writes the prompt, then yields to the host to read a line.
+    fn call_input(&mut self, args: ArgValues) -> Result<CallResult, RunError> {
+        write_input_prompt(self.heap, args, self.interns, self.print_writer)?;
+        Ok(CallResult::OsCall(OsFunction::Input, ArgValues::Empty))
+    }
+
     /// Calls a callable value with the given arguments.
     ///
     /// Dispatches based on the callable type:
@@ -362,15 +430,18 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     /// - `Value::ModuleFunction`: calls module function directly, returns `Push`
     /// - `Value::ExtFunction`: returns `External` for caller to execute
     /// - `Value::DefFunction`: pushes a new frame, returns `FramePushed`
-    /// - `Value::Ref`: checks for closure/function on heap
+    /// - `Value::Ref`: checks for closure/function, partial or `lru_cache` wrapper on heap
     fn call_function(&mut self, callable: &Value, args: ArgValues) -> Result<CallResult, RunError> {
         match callable {
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Input)) => self.call_input(args),
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Eval)) => self.call_eval(true, args),
+            Value::Builtin(Builtins::Function(BuiltinsFunctions::Exec)) => self.call_eval(false, args),
             Value::Builtin(builtin) => {
                 let result = builtin.call(self, args)?;
                 Ok(CallResult::Push(result))
             }
             Value::ModuleFunction(mf) => {
-                let result = mf.call(self.heap, args)?;
+                let result = mf.call(self, args)?;
                 Ok(result.into())
             }
             Value::ExtFunction(ext_id) => {
@@ -387,17 +458,34 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
             }
             _ => {
                 args.drop_with_heap(self.heap);
-                Err(ExcType::type_error("object is not callable"))
+                Err(ExcType::type_error_not_callable_object(callable.py_type(self.heap)))
             }
         }
     }
 
-    /// Handles calling a heap-allocated callable (closure or function with defaults).
+    /// Handles calling a heap-allocated callable (closure, function with defaults,
+    /// `functools.partial`, `functools.lru_cache` wrapper or named tuple class).
     ///
     /// Uses a two-phase approach to avoid borrow conflicts:
     /// 1. Copy data without incrementing refcounts
     /// 2. Increment refcounts after the borrow ends
     fn call_heap_callable(&mut self, heap_id: HeapId, args: ArgValues) -> Result<CallResult, RunError> {
+        match self.heap.get(heap_id) {
+            HeapData::Partial(_) => return self.call_partial(heap_id, args),
+            HeapData::LruCache(_) => return self.call_lru_cache(heap_id, args),
+            HeapData::NamedTupleType(_) => {
+                let interns = self.interns;
+                let value = self.heap.with_entry_mut(heap_id, |heap, data| {
+                    let HeapData::NamedTupleType(nt_type) = data else {
+                        unreachable!("checked above")
+                    };
+                    nt_type.instantiate(args, heap, interns)
+                })?;
+                return Ok(CallResult::Push(value));
+            }
+            _ => {}
+        }
+
         // Phase 1: Copy data (func_id, cells, defaults) without refcount changes
         let (func_id, cells, defaults) = match self.heap.get(heap_id) {
             HeapData::Closure(fid, cells, defaults) => {
@@ -409,9 +497,10 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 let cloned_defaults: Vec<Value> = defaults.iter().map(Value::copy_for_extend).collect();
                 (*fid, Vec::new(), cloned_defaults)
             }
-            _ => {
+            other => {
+                let type_ = other.py_type(self.heap);
                 args.drop_with_heap(self.heap);
-                return Err(ExcType::type_error("object is not callable"));
+                return Err(ExcType::type_error_not_callable_object(type_));
             }
         };
 
@@ -429,6 +518,97 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         self.call_def_function(func_id, &cells, defaults, args)
     }
 
+    /// Calls a `functools.partial` object: its callable is called with the stored arguments
+    /// merged into `args`.
+    fn call_partial(&mut self, heap_id: HeapId, args: ArgValues) -> Result<CallResult, RunError> {
+        let (func, args) = bind_partial_args(heap_id, args, self.heap, self.interns)?;
+        let this = self;
+        defer_drop!(func, this);
+        this.call_function(func, args)
+    }
+
+    /// Calls a `functools.lru_cache` wrapper.
+    ///
+    /// A cached result is pushed directly. Otherwise the wrapped callable is called, with the
+    /// wrapper and the cache key pushed onto the stack until its result is known: either
+    /// immediately, or when the frame it pushed returns (see `CallFrame::cache_results`).
+    fn call_lru_cache(&mut self, heap_id: HeapId, args: ArgValues) -> Result<CallResult, RunError> {
+        let (key, args) = lru_cache_key(args, self.heap, self.interns)?;
+        let interns = self.interns;
+        let cached = self.heap.with_entry_mut(heap_id, |heap, data| {
+            let HeapData::LruCache(lru) = data else {
+                unreachable!("call_lru_cache called on a non-lru_cache")
+            };
+            lru.lookup(&key, heap, interns)
+        });
+        let cached = match cached {
+            Ok(cached) => cached,
+            Err(err) => {
+                key.drop_with_heap(self.heap);
+                args.drop_with_heap(self.heap);
+                return Err(err);
+            }
+        };
+        if let Some(value) = cached {
+            key.drop_with_heap(self.heap);
+            args.drop_with_heap(self.heap);
+            return Ok(CallResult::Push(value));
+        }
+
+        let HeapData::LruCache(lru) = self.heap.get(heap_id) else {
+            unreachable!("call_lru_cache called on a non-lru_cache")
+        };
+        let func = lru.func().copy_for_extend();
+        if let Value::Ref(id) = &func {
+            self.heap.inc_ref(*id);
+        }
+        self.heap.inc_ref(heap_id);
+        self.push(Value::Ref(heap_id));
+        self.push(key);
+
+        let this = self;
+        defer_drop!(func, this);
+        match this.call_function(func, args) {
+            Ok(CallResult::Push(value)) => match this.store_lru_result(&value) {
+                Ok(()) => Ok(CallResult::Push(value)),
+                Err(err) => {
+                    value.drop_with_heap(this.heap);
+                    Err(err)
+                }
+            },
+            Ok(CallResult::FramePushed) => {
+                this.current_frame_mut().cache_results += 1;
+                Ok(CallResult::FramePushed)
+            }
+            // Results the host provides later are not cached
+            result => {
+                this.pop().drop_with_heap(this.heap);
+                this.pop().drop_with_heap(this.heap);
+                result
+            }
+        }
+    }
+
+    /// Stores `value` as the result of an `lru_cache` call, popping the cache key and the
+    /// wrapper pushed by `call_lru_cache`.
+    pub(super) fn store_lru_result(&mut self, value: &Value) -> Result<(), RunError> {
+        let key = self.pop();
+        let wrapper = self.pop();
+        let Value::Ref(wrapper_id) = wrapper else {
+            unreachable!("store_lru_result expects an lru_cache wrapper on the stack")
+        };
+        let value = value.clone_with_heap(self.heap);
+        let interns = self.interns;
+        let result = self.heap.with_entry_mut(wrapper_id, |heap, data| {
+            let HeapData::LruCache(lru) = data else {
+                unreachable!("store_lru_result expects an lru_cache wrapper on the stack")
+            };
+            lru.store(key, value, heap, interns)
+        });
+        wrapper.drop_with_heap(self.heap);
+        result
+    }
+
     /// Calls a function with unpacked args tuple and optional kwargs dict.
     ///
     /// Used for `f(*args)` and `f(**kwargs)` style calls.
@@ -663,7 +843,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     /// For async functions: binds arguments immediately but returns a Coroutine
     /// instead of pushing a frame. The coroutine stores the pre-bound namespace
     /// and will be executed when awaited.
-    fn call_def_function(
+    pub(super) fn call_def_function(
         &mut self,
         func_id: FunctionId,
         cells: &[HeapId],
@@ -839,21 +1019,103 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
 
 /// Dispatches a classmethod call on a type object.
 ///
-/// Handles classmethods like `dict.fromkeys()` and `bytes.fromhex()` that are
-/// called on the type itself rather than on an instance.
+/// Handles classmethods like `dict.fromkeys()` and `bytes.fromhex()`, and the static
+/// method `str.maketrans()`, that are called on the type itself rather than on an
+/// instance. `date.today()` and `datetime.now()` read the host's clock, so they yield
+/// an OS call.
 fn call_type_method(
     t: Type,
     method_id: StringId,
     args: ArgValues,
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
-) -> Result<Value, RunError> {
+) -> Result<CallResult, RunError> {
+    let push = |result: Result<Value, RunError>| result.map(CallResult::Push);
     match (t, method_id) {
-        (Type::Dict, m) if m == StaticStrings::Fromkeys => return dict_fromkeys(args, heap, interns),
-        (Type::Bytes, m) if m == StaticStrings::Fromhex => return bytes_fromhex(args, heap, interns),
+        (Type::Dict, m) if m == StaticStrings::Fromkeys => return push(dict_fromkeys(args, heap, interns)),
+        (Type::Str, m) if m == StaticStrings::Maketrans => return push(str_maketrans(args, heap, interns)),
+        (Type::Bytes, m) if m == StaticStrings::Fromhex => return push(bytes_fromhex(args, heap, interns)),
+        (Type::ByteArray, m) if m == StaticStrings::Fromhex => return push(bytearray_fromhex(args, heap, interns)),
+        (Type::Date, m) if m == StaticStrings::Today => {
+            args.check_zero_args("today", heap)?;
+            heap.check_deterministic("date.today()")?;
+            return Ok(CallResult::OsCall(OsFunction::DateToday, ArgValues::Empty));
+        }
+        (Type::DateTime, m) if m == StaticStrings::Now || m == StaticStrings::Today => {
+            args.check_zero_args(interns.get_str(method_id), heap)?;
+            heap.check_deterministic(&format!("datetime.{}()", interns.get_str(method_id)))?;
+            return Ok(CallResult::OsCall(OsFunction::DateTimeNow, ArgValues::Empty));
+        }
+        (Type::Date, m) if m == StaticStrings::Fromisoformat => return push(Date::fromisoformat(heap, args, interns)),
+        (Type::Date, m) if m == StaticStrings::Fromordinal => return push(Date::fromordinal(heap, args)),
+        (Type::DateTime, m) if m == StaticStrings::Fromisoformat => {
+            return push(DateTime::fromisoformat(heap, args, interns));
+        }
         _ => {}
     }
     // Other types or unknown methods - report actual type name, not 'type'
     args.drop_with_heap(heap);
     Err(ExcType::attribute_error(t, interns.get_str(method_id)))
 }
+
+/// Converts arguments from the host for `VM::call_global`, passing keyword arguments as a dict.
+fn host_args(
+    args: Vec<MontyObject>,
+    kwargs: Vec<(String, MontyObject)>,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<ArgValues, RunError> {
+    let mut values = Vec::with_capacity(args.len());
+    let mut dict = Dict::new();
+    match fill_host_args(args, kwargs, &mut values, &mut dict, heap, interns) {
+        Ok(()) if dict.is_empty() => Ok(ArgValues::from_parts(values, KwargsValues::Empty)),
+        Ok(()) => Ok(ArgValues::from_parts(values, KwargsValues::Dict(dict))),
+        Err(error) => {
+            values.drop_with_heap(heap);
+            dict.drop_with_heap(heap);
+            Err(error)
+        }
+    }
+}
+
+/// Converts each argument from the host, see `host_args`.
+fn fill_host_args(
+    args: Vec<MontyObject>,
+    kwargs: Vec<(String, MontyObject)>,
+    values: &mut Vec<Value>,
+    dict: &mut Dict,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<(), RunError> {
+    let invalid = |e: InvalidInputError| {
+        RunError::from(SimpleException::new_msg(
+            ExcType::RuntimeError,
+            format!("invalid argument type: {e}"),
+        ))
+    };
+    for arg in args {
+        values.push(arg.to_value(heap, interns).map_err(invalid)?);
+    }
+    for (name, arg) in kwargs {
+        let key = Value::Ref(heap.allocate(HeapData::Str(name.into()))?);
+        let value = match arg.to_value(heap, interns) {
+            Ok(value) => value,
+            Err(e) => {
+                key.drop_with_heap(heap);
+                return Err(invalid(e));
+            }
+        };
+        if let Some(old_value) = dict.set(key, value, heap, interns)? {
+            old_value.drop_with_heap(heap);
+        }
+    }
+    Ok(())
+}
+
+/// Error raised by `VM::call_global` when the global is a callable the code doesn't run itself.
+fn not_defined_function(name: &str) -> RunError {
+    ExcType::not_implemented(format!(
+        "calling '{name}' from the host is not supported, it must be a function defined by the code"
+    ))
+    .into()
+}
@@ -4,12 +4,16 @@ use smallvec::SmallVec;
 
 use super::VM;
 use crate::{
+    args::ArgValues,
     defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunError, SimpleException},
-    heap::{HeapData, HeapGuard},
+    heap::{DropWithHeap, HeapData, HeapGuard},
     intern::StringId,
     resource::ResourceTracker,
-    types::{Dict, List, PyTrait, Set, Slice, Type, allocate_tuple, slice::value_to_option_i64, str::allocate_char},
+    types::{
+        Dict, List, MontyIter, PyTrait, Set, Slice, Type, allocate_tuple, slice::value_to_option_i64,
+        str::allocate_char,
+    },
     value::Value,
 };
 
@@ -37,12 +41,14 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     /// Builds a dict from the top 2n stack values (key/value pairs).
     pub(super) fn build_dict(&mut self, count: usize) -> Result<(), RunError> {
         let items = self.pop_n(count * 2);
-        let mut dict = Dict::new();
         // Use into_iter to consume items by value, avoiding clone and proper ownership transfer
         let mut iter = items.into_iter();
+        let mut pairs = Vec::with_capacity(count);
         while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
-            dict.set(key, value, self.heap, self.interns)?;
+            pairs.push((key, value));
         }
+        // Sized for every pair up front, so only duplicate keys leave slots unused
+        let dict = Dict::from_pairs(pairs, self.heap, self.interns)?;
         let heap_id = self.heap.allocate(HeapData::Dict(dict))?;
         self.push(Value::Ref(heap_id));
         Ok(())
@@ -51,7 +57,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     /// Builds a set from the top n stack values.
     pub(super) fn build_set(&mut self, count: usize) -> Result<(), RunError> {
         let items = self.pop_n(count);
-        let mut set = Set::new();
+        let mut set = Set::with_capacity(count);
         for item in items {
             set.add(item, self.heap, self.interns)?;
         }
@@ -89,6 +95,9 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     /// Stack: [list, iterable] -> [list]
     /// Pops the iterable, extends the list in place, leaves list on stack.
     ///
+    /// Lists, tuples, sets, dicts and strings are copied directly; any other iterable
+    /// (ranges, iterators, etc.) is drained through `MontyIter`.
+    ///
     /// Uses `HeapGuard` for `list_ref` because it is pushed back on success,
     /// and `defer_drop!` for `iterable` because it is always dropped.
     pub(super) fn list_extend(&mut self) -> Result<(), RunError> {
@@ -101,13 +110,14 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         let (list_ref, this) = list_ref_guard.as_parts();
 
         // Two-phase approach to avoid borrow conflicts:
-        // Phase 1: Copy items without refcount changes
-        let copied_items: Vec<Value> = match iterable {
+        // Phase 1: Copy items without refcount changes. Freshly allocated characters and
+        // items collected through the generic iterator fallback already own their references.
+        let (copied_items, owned) = match iterable {
             Value::Ref(id) => match this.heap.get(*id) {
-                HeapData::List(list) => list.as_slice().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Tuple(tuple) => tuple.as_slice().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Set(set) => set.storage().iter().map(Value::copy_for_extend).collect(),
-                HeapData::Dict(dict) => dict.iter().map(|(k, _)| Value::copy_for_extend(k)).collect(),
+                HeapData::List(list) => (list.as_slice().iter().map(Value::copy_for_extend).collect(), false),
+                HeapData::Tuple(tuple) => (tuple.as_slice().iter().map(Value::copy_for_extend).collect(), false),
+                HeapData::Set(set) => (set.storage().iter().map(Value::copy_for_extend).collect(), false),
+                HeapData::Dict(dict) => (dict.iter().map(|(k, _)| Value::copy_for_extend(k)).collect(), false),
                 HeapData::Str(s) => {
                     // Need to allocate strings for each character
                     let chars: Vec<char> = s.as_str().chars().collect();
@@ -115,12 +125,9 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                     for c in chars {
                         items.push(allocate_char(c, this.heap)?);
                     }
-                    items
-                }
-                _ => {
-                    let type_ = iterable.py_type(this.heap);
-                    return Err(ExcType::type_error_not_iterable(type_));
+                    (items, true)
                 }
+                _ => (Self::collect_iterable(iterable, this)?, true),
             },
             Value::InternString(id) => {
                 let s = this.interns.get_str(*id);
@@ -129,18 +136,17 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 for c in chars {
                     items.push(allocate_char(c, this.heap)?);
                 }
-                items
-            }
-            _ => {
-                let type_ = iterable.py_type(this.heap);
-                return Err(ExcType::type_error_not_iterable(type_));
+                (items, true)
             }
+            _ => (Self::collect_iterable(iterable, this)?, true),
         };
 
         // Phase 2: Increment refcounts now that the borrow has ended
-        for item in &copied_items {
-            if let Value::Ref(id) = item {
-                this.heap.inc_ref(*id);
+        if !owned {
+            for item in &copied_items {
+                if let Value::Ref(id) = item {
+                    this.heap.inc_ref(*id);
+                }
             }
         }
 
@@ -169,6 +175,14 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         Ok(())
     }
 
+    /// Collects every item of an arbitrary iterable, returning owned values.
+    ///
+    /// Used by `list_extend` for iterables without a direct copy fast path.
+    fn collect_iterable(iterable: &Value, this: &mut Self) -> Result<Vec<Value>, RunError> {
+        let iter = MontyIter::new(iterable.clone_with_heap(this.heap), this.heap, this.interns)?;
+        iter.collect(this.heap, this.interns)
+    }
+
     /// Converts a list to a tuple.
     ///
     /// Stack: [list] -> [tuple]
@@ -405,14 +419,67 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         Ok(())
     }
 
+    /// Handles `d[key]` for a defaultdict `d` missing `key`, like `defaultdict.__missing__`:
+    /// calls the `default_factory`, stores its result under `key` and returns it.
+    ///
+    /// Returns `Ok(None)` if `obj` is not a defaultdict with a factory or already has `key`,
+    /// in which case the caller does a regular `py_getitem`.
+    pub(super) fn defaultdict_missing(&mut self, obj: &Value, key: &Value) -> Result<Option<Value>, RunError> {
+        let Value::Ref(dict_id) = obj else {
+            return Ok(None);
+        };
+        let dict_id = *dict_id;
+        if !matches!(self.heap.get(dict_id), HeapData::Dict(dict) if dict.default_factory().is_some()) {
+            return Ok(None);
+        }
+
+        let factory = self.heap.with_entry_mut(dict_id, |heap, data| {
+            let HeapData::Dict(dict) = data else {
+                return Ok(None);
+            };
+            if dict.get(key, heap, self.interns)?.is_some() {
+                return Ok(None);
+            }
+            Ok::<_, RunError>(dict.default_factory().map(|factory| factory.clone_with_heap(heap)))
+        })?;
+        let Some(factory) = factory else {
+            return Ok(None);
+        };
+        let result = self.evaluate_function("defaultdict", &factory, ArgValues::Empty);
+        factory.drop_with_heap(self.heap);
+        let value = result?;
+
+        let stored = value.clone_with_heap(self.heap);
+        let key = key.clone_with_heap(self.heap);
+        let old_value = self.heap.with_entry_mut(dict_id, |heap, data| {
+            if let HeapData::Dict(dict) = data {
+                dict.set(key, stored, heap, self.interns)
+            } else {
+                key.drop_with_heap(heap);
+                stored.drop_with_heap(heap);
+                Err(RunError::internal("defaultdict_missing: expected dict on heap"))
+            }
+        });
+        match old_value {
+            Ok(old_value) => {
+                old_value.drop_with_heap(self.heap);
+                Ok(Some(value))
+            }
+            Err(err) => {
+                value.drop_with_heap(self.heap);
+                Err(err)
+            }
+        }
+    }
+
     // ========================================================================
     // Unpacking
     // ========================================================================
 
     /// Unpacks a sequence into n values on the stack.
     ///
-    /// Supports lists, tuples, and strings. For strings, each character becomes
-    /// a separate single-character string.
+    /// Lists, tuples, and strings take a fast path; for strings, each character becomes
+    /// a separate single-character string. Any other iterable is consumed item by item.
     pub(super) fn unpack_sequence(&mut self, count: usize) -> Result<(), RunError> {
         let this = self;
 
@@ -475,17 +542,11 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                         }
                         return Ok(());
                     }
-                    other => {
-                        let type_name = other.py_type(this.heap);
-                        return Err(unpack_type_error(type_name));
-                    }
+                    _ => return this.unpack_iterable(value, count),
                 }
             }
-            // Non-iterable types
-            _ => {
-                let type_name = value.py_type(this.heap);
-                return Err(unpack_type_error(type_name));
-            }
+            // Other iterables (ranges, etc.) and non-iterable types
+            _ => return this.unpack_iterable(value, count),
         };
 
         // IMPORTANT: Increment refcounts BEFORE dropping the container.
@@ -535,8 +596,7 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                     items.push(allocate_char(c, this.heap)?);
                 }
                 // String items are newly allocated, push and return
-                this.push_unpack_ex_results(&items, before, after)?;
-                return Ok(());
+                return this.push_unpack_ex_results(items, before, after);
             }
             Value::Ref(heap_id) => {
                 match this.heap.get(*heap_id) {
@@ -565,19 +625,12 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                             items.push(allocate_char(c, this.heap)?);
                         }
                         // String items are newly allocated, push and return
-                        this.push_unpack_ex_results(&items, before, after)?;
-                        return Ok(());
-                    }
-                    other => {
-                        let type_name = other.py_type(this.heap);
-                        return Err(unpack_type_error(type_name));
+                        return this.push_unpack_ex_results(items, before, after);
                     }
+                    _ => return this.unpack_ex_iterable(value, before, after),
                 }
             }
-            _ => {
-                let type_name = value.py_type(this.heap);
-                return Err(unpack_type_error(type_name));
-            }
+            _ => return this.unpack_ex_iterable(value, before, after),
         };
 
         // Increment refcounts BEFORE dropping the container.
@@ -594,44 +647,95 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         }
 
         // Now push the results
-        this.push_unpack_ex_results(&items, before, after)?;
-        Ok(())
+        this.push_unpack_ex_results(items, before, after)
     }
 
-    /// Helper to push unpacked items with starred target onto the stack.
+    /// Unpacks an arbitrary iterable (ranges, sets, dicts, iterators) into exactly `count`
+    /// values on the stack.
     ///
-    /// Takes a slice of items and creates the middle list.
-    fn push_unpack_ex_results(&mut self, items: &[Value], before: usize, after: usize) -> Result<(), RunError> {
-        let total = items.len();
-
-        // Collect results: before items, middle list, after items
-        let mut results = Vec::with_capacity(before + 1 + after);
-
-        // Before items
-        for item in items.iter().take(before) {
-            results.push(item.copy_for_extend());
+    /// At most `count + 1` items are pulled from the iterable, so like CPython the
+    /// "too many values" error does not report the total length.
+    fn unpack_iterable(&mut self, value: &Value, count: usize) -> Result<(), RunError> {
+        let items = self.collect_unpack_items(value, count + 1)?;
+        if items.len() != count {
+            let err = if items.len() < count {
+                unpack_size_error(count, items.len())
+            } else {
+                unpack_too_many_error(count)
+            };
+            items.drop_with_heap(self.heap);
+            return Err(err);
         }
+        // Push items in reverse order so first item is on top
+        for item in items.into_iter().rev() {
+            self.push(item);
+        }
+        Ok(())
+    }
 
-        // Middle items as a list (starred target)
-        // Items already have their refcount incremented in unpack_ex (for list/tuple)
-        // or are freshly allocated (for strings), so no additional inc_ref needed here.
-        let middle_start = before;
-        let middle_end = total - after;
-        let mut middle = Vec::with_capacity(middle_end - middle_start);
-        for item in &items[middle_start..middle_end] {
-            middle.push(item.copy_for_extend());
+    /// Unpacks an arbitrary iterable into a starred target, see `unpack_ex`.
+    fn unpack_ex_iterable(&mut self, value: &Value, before: usize, after: usize) -> Result<(), RunError> {
+        let items = self.collect_unpack_items(value, usize::MAX)?;
+        let min_items = before + after;
+        if items.len() < min_items {
+            let err = unpack_ex_too_few_error(min_items, items.len());
+            items.drop_with_heap(self.heap);
+            return Err(err);
         }
-        let middle_list = List::new(middle);
-        let list_id = self.heap.allocate(HeapData::List(middle_list))?;
-        results.push(Value::Ref(list_id));
+        self.push_unpack_ex_results(items, before, after)
+    }
 
-        // After items
-        for item in items.iter().skip(total - after) {
-            results.push(item.copy_for_extend());
+    /// Collects up to `limit` owned items from an iterable being unpacked.
+    ///
+    /// Raises the unpacking-specific TypeError if the value is not iterable.
+    fn collect_unpack_items(&mut self, value: &Value, limit: usize) -> Result<Vec<Value>, RunError> {
+        let Ok(iter) = MontyIter::new(value.clone_with_heap(self.heap), self.heap, self.interns) else {
+            return Err(unpack_type_error(value.py_type(self.heap)));
+        };
+        let mut iter_guard = HeapGuard::new(iter, self.heap);
+        let (iter, heap) = iter_guard.as_parts_mut();
+        let mut items = Vec::new();
+        while items.len() < limit {
+            match iter.for_next(heap, self.interns) {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => break,
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
         }
+        Ok(items)
+    }
+
+    /// Helper to push unpacked items with starred target onto the stack.
+    ///
+    /// Takes ownership of the items (each must already hold its own reference) and
+    /// moves the middle ones into a new list for the starred target.
+    fn push_unpack_ex_results(&mut self, items: Vec<Value>, before: usize, after: usize) -> Result<(), RunError> {
+        let middle_len = items.len() - before - after;
+        let mut items = items.into_iter();
+
+        // Before items, middle items as a list (starred target), then after items
+        let before_items: Vec<Value> = items.by_ref().take(before).collect();
+        let middle: Vec<Value> = items.by_ref().take(middle_len).collect();
+        let after_items: Vec<Value> = items.collect();
+
+        let list_id = match self.heap.allocate(HeapData::List(List::new(middle))) {
+            Ok(id) => id,
+            Err(err) => {
+                before_items.drop_with_heap(self.heap);
+                after_items.drop_with_heap(self.heap);
+                return Err(err.into());
+            }
+        };
 
         // Push in reverse order so first item is on top
-        for item in results.into_iter().rev() {
+        for item in after_items.into_iter().rev() {
+            self.push(item);
+        }
+        self.push(Value::Ref(list_id));
+        for item in before_items.into_iter().rev() {
             self.push(item);
         }
 
@@ -659,6 +763,15 @@ fn unpack_size_error(expected: usize, actual: usize) -> RunError {
     SimpleException::new_msg(ExcType::ValueError, message).into()
 }
 
+/// Creates the ValueError for unpacking an iterable with too many values.
+///
+/// Unlike `unpack_size_error`, the actual length is unknown because the iterable
+/// is only consumed one item past the expected count.
+fn unpack_too_many_error(expected: usize) -> RunError {
+    let message = format!("too many values to unpack (expected {expected})");
+    SimpleException::new_msg(ExcType::ValueError, message).into()
+}
+
 /// Creates a TypeError for attempting to unpack a non-iterable type.
 fn unpack_type_error(type_name: Type) -> RunError {
     SimpleException::new_msg(
@@ -5,11 +5,27 @@ use crate::{
     defer_drop,
     exception_private::{ExcType, RunError},
     resource::{DepthGuard, ResourceTracker},
-    types::{LongInt, PyTrait},
+    types::{LongInt, PyTrait, str::py_str_mod},
     value::Value,
 };
 
 impl<T: ResourceTracker> VM<'_, '_, T> {
+    /// Fast path for comparing two `Int`s, mirroring `try_int_binary_op`.
+    ///
+    /// Replaces the top two stack values with the `Bool` result of `check` when both are
+    /// `Value::Int`; returns `false` with the stack untouched otherwise.
+    #[inline]
+    pub(super) fn try_int_compare(&mut self, check: impl FnOnce(i64, i64) -> bool) -> bool {
+        if let [.., Value::Int(a), Value::Int(b)] = self.stack.as_slice() {
+            let result = check(*a, *b);
+            self.stack.pop();
+            *self.stack.last_mut().expect("stack underflow") = Value::Bool(result);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Equality comparison.
     pub(super) fn compare_eq(&mut self) -> Result<(), RunError> {
         let this = self;
@@ -144,7 +160,15 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                     this.push(Value::Bool(is_equal));
                     Ok(())
                 }
-                Ok(None) => Err(ExcType::type_error("unsupported operand type(s) for %")),
+                Ok(None) => match py_str_mod(lhs, rhs, this.heap, this.interns)? {
+                    // A formatted string never equals the int constant
+                    Some(v) => {
+                        v.drop_with_heap(this.heap);
+                        this.push(Value::Bool(false));
+                        Ok(())
+                    }
+                    None => Err(ExcType::type_error("unsupported operand type(s) for %")),
+                },
                 Err(e) => Err(e),
             }
         }
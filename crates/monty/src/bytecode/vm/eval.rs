@@ -0,0 +1,275 @@
+//! Running code compiled at runtime by the `eval()` and `exec()` builtins.
+
+use ahash::AHashMap;
+
+use super::{CallFrame, VM, call::CallResult};
+use crate::{
+    args::{ArgValues, KwargsValues},
+    builtins::{EvalArgs, Variables, eval_args, parse_source},
+    bytecode::compiler::{CompileError, Compiler},
+    exception_private::{ExcType, RunError, SimpleException},
+    heap::DropWithHeap,
+    intern::{DynamicCodeKey, FunctionId, Interns, StringId},
+    namespace::{GLOBAL_NS_IDX, NamespaceId},
+    parse::ParseError,
+    prepare::{prepare_dynamic_function, prepare_dynamic_module},
+    resource::ResourceTracker,
+    value::Value,
+};
+
+impl<T: ResourceTracker> VM<'_, '_, T> {
+    /// Calls `eval()` or `exec()`, pushing a frame that runs the source.
+    ///
+    /// The frame returns the value of the expression for `eval()`, and `None` for `exec()`.
+    /// Code is compiled once per source and scope, later calls reuse it. Compiled code is
+    /// kept for the rest of the run, so it's charged to the resource tracker and at most
+    /// `max_eval_compiled` sources are compiled.
+    ///
+    /// At the main module's top level the code runs in the global namespace, so `exec()` can
+    /// define globals. Anywhere else it runs as a function whose parameters are the caller's
+    /// variables, and which can use the caller's globals: like in CPython, assigning to a
+    /// variable doesn't change the caller's. With a `globals` dict the code only sees the
+    /// variables of the dict and of `locals`.
+    pub(super) fn call_eval(&mut self, is_eval: bool, args: ArgValues) -> Result<CallResult, RunError> {
+        let name = if is_eval { "eval" } else { "exec" };
+        let interns = self.interns;
+        let options = interns.execution_options();
+        if !options.allow_eval {
+            args.drop_with_heap(self.heap);
+            return Err(SimpleException::new_msg(ExcType::RuntimeError, format!("{name}() is not enabled")).into());
+        }
+
+        let EvalArgs {
+            source,
+            globals,
+            locals,
+        } = eval_args(name, args, self.heap, interns)?;
+        let depth = self
+            .frames
+            .iter()
+            .filter(|frame| frame.function_id.is_some_and(|id| interns.is_dynamic_body(id)))
+            .count();
+        let error = if source.len() > options.max_eval_source_len {
+            Some(SimpleException::new_msg(
+                ExcType::ValueError,
+                format!(
+                    "{name}() source is longer than the limit of {} bytes",
+                    options.max_eval_source_len
+                ),
+            ))
+        } else if depth >= options.max_eval_depth {
+            Some(SimpleException::new_msg(
+                ExcType::RecursionError,
+                format!("maximum {name}() nesting depth of {} exceeded", options.max_eval_depth),
+            ))
+        } else if !is_eval && (globals.is_some() || locals.is_some()) {
+            Some(SimpleException::new_msg(
+                ExcType::NotImplementedError,
+                "exec() with a globals or locals dict is not supported",
+            ))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            globals.drop_with_heap(self.heap);
+            locals.drop_with_heap(self.heap);
+            return Err(error.into());
+        }
+
+        let namespace_idx = self.current_frame().namespace_idx;
+        let globals_idx = self.globals_idx();
+        match (globals, locals) {
+            (Some(mut globals), locals) => {
+                if let Some(locals) = locals {
+                    globals.update(locals, self.heap);
+                }
+                self.run_dynamic_function(source, is_eval, globals, None)
+            }
+            (None, Some(locals)) => self.run_dynamic_function(source, is_eval, locals, Some(globals_idx)),
+            (None, None) if namespace_idx == GLOBAL_NS_IDX => self.run_dynamic_module(source, is_eval),
+            (None, None) if namespace_idx == globals_idx => Err(SimpleException::new_msg(
+                ExcType::NotImplementedError,
+                format!("{name}() at the top level of an imported module is not supported"),
+            )
+            .into()),
+            (None, None) => {
+                let (names, values) = self
+                    .local_variables()
+                    .into_iter()
+                    .map(|(name, value)| (interns.get_str(name).to_owned(), value))
+                    .unzip();
+                let variables = Variables { names, values };
+                self.run_dynamic_function(source, is_eval, variables, Some(globals_idx))
+            }
+        }
+    }
+
+    /// Runs code in the main module's global namespace, growing it for new globals.
+    fn run_dynamic_module(&mut self, source: String, is_eval: bool) -> Result<CallResult, RunError> {
+        let interns = self.interns;
+        let key = DynamicCodeKey {
+            source,
+            is_eval,
+            params: None,
+            globals: Some(GLOBAL_NS_IDX),
+        };
+        let function_id = match interns.find_dynamic_code(&key) {
+            Some(function_id) => function_id,
+            None => compile_dynamic_module(key, interns, self.heap.tracker_mut())?,
+        };
+        let func = interns.get_function(function_id);
+
+        let namespace = self.namespaces.get_mut(GLOBAL_NS_IDX).mut_vec();
+        if namespace.len() < func.namespace_size {
+            let size = (func.namespace_size - namespace.len()) * std::mem::size_of::<Value>();
+            self.heap.tracker_mut().on_allocate(|| size)?;
+            namespace.resize_with(func.namespace_size, || Value::Undefined);
+        }
+
+        let call_position = self.current_position();
+        self.frames.push(CallFrame::new_function(
+            &func.code,
+            self.stack.len(),
+            GLOBAL_NS_IDX,
+            function_id,
+            Vec::new(),
+            Some(call_position),
+        ));
+        Ok(CallResult::FramePushed)
+    }
+
+    /// Runs code as a function taking `variables` as parameters, which can use the globals
+    /// in `globals` if given.
+    fn run_dynamic_function(
+        &mut self,
+        source: String,
+        is_eval: bool,
+        variables: Variables,
+        globals: Option<NamespaceId>,
+    ) -> Result<CallResult, RunError> {
+        let interns = self.interns;
+        let Variables { names, values } = variables;
+        let key = DynamicCodeKey {
+            source,
+            is_eval,
+            params: Some(names),
+            globals,
+        };
+        let function_id = match interns.find_dynamic_code(&key) {
+            Some(function_id) => Ok(function_id),
+            None => {
+                let global_names = if globals.is_some() { self.global_names() } else { &[] };
+                compile_dynamic_function(key, global_names, interns, self.heap.tracker_mut())
+            }
+        };
+        match function_id {
+            Ok(function_id) => {
+                let args = ArgValues::from_parts(values, KwargsValues::Empty);
+                self.call_def_function(function_id, &[], Vec::new(), args)
+            }
+            Err(error) => {
+                values.drop_with_heap(self.heap);
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Compiles code run in the main module's global namespace, see `VM::run_dynamic_module`.
+fn compile_dynamic_module(
+    key: DynamicCodeKey,
+    interns: &Interns,
+    tracker: &mut impl ResourceTracker,
+) -> Result<FunctionId, RunError> {
+    check_compiled_count(&key, interns)?;
+    let parsed = parse_source(&key.source, key.is_eval, interns)?;
+    let global_names = interns.global_names();
+    let mut prepared = prepare_dynamic_module(parsed, name_map(global_names, interns), global_names.len())
+        .map_err(ParseError::into_run_error)?;
+    let new_global_names = (prepared.namespace_size > global_names.len()).then(|| prepared.slot_names());
+
+    let num_locals = u16::try_from(prepared.namespace_size).map_err(|_| too_many_names())?;
+    if !prepared.interner.ids_fit_operands() {
+        return Err(too_many_names());
+    }
+    let compiled = interns.for_dynamic_code(prepared.interner);
+    let functions = Compiler::compile_dynamic_module(
+        &prepared.nodes,
+        &compiled,
+        num_locals,
+        interns.function_count(),
+        interns.compile_options(),
+    )
+    .map_err(CompileError::into_run_error)?;
+    Ok(interns.add_dynamic_code(key, compiled, functions, new_global_names, tracker)?)
+}
+
+/// Compiles code run as a function, see `VM::run_dynamic_function`.
+///
+/// `global_names` holds the names of the slots of the globals the code can use.
+fn compile_dynamic_function(
+    key: DynamicCodeKey,
+    global_names: &[StringId],
+    interns: &Interns,
+    tracker: &mut impl ResourceTracker,
+) -> Result<FunctionId, RunError> {
+    check_compiled_count(&key, interns)?;
+    let mut parsed = parse_source(&key.source, key.is_eval, interns)?;
+    let params = key
+        .params
+        .iter()
+        .flatten()
+        .map(|name| parsed.interner.intern(name))
+        .collect();
+    let (func_def, interner) = prepare_dynamic_function(parsed, params, name_map(global_names, interns))
+        .map_err(ParseError::into_run_error)?;
+
+    if !interner.ids_fit_operands() {
+        return Err(too_many_names());
+    }
+    let compiled = interns.for_dynamic_code(interner);
+    let mut functions = Compiler::compile_dynamic_function(
+        &func_def,
+        &compiled,
+        interns.function_count(),
+        interns.compile_options(),
+    )
+    .map_err(CompileError::into_run_error)?;
+    // functions defined by the code share the caller's globals
+    if let Some(globals) = key.globals {
+        for function in &mut functions {
+            function.globals = globals;
+        }
+    }
+    Ok(interns.add_dynamic_code(key, compiled, functions, None, tracker)?)
+}
+
+/// Checks that compiling another source stays within `ExecutionOptions::max_eval_compiled`.
+fn check_compiled_count(key: &DynamicCodeKey, interns: &Interns) -> Result<(), RunError> {
+    let limit = interns.execution_options().max_eval_compiled;
+    if interns.dynamic_code_count() < limit {
+        return Ok(());
+    }
+    let name = if key.is_eval { "eval" } else { "exec" };
+    Err(SimpleException::new_msg(
+        ExcType::MemoryError,
+        format!("{name}() can't compile more than {limit} distinct sources"),
+    )
+    .into())
+}
+
+/// Maps the names of namespace slots to the slots, skipping unnamed slots.
+fn name_map(names: &[StringId], interns: &Interns) -> AHashMap<String, NamespaceId> {
+    names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| **name != StringId::default())
+        .map(|(slot, name)| (interns.get_str(*name).to_owned(), NamespaceId::new(slot)))
+        .collect()
+}
+
+/// Error raised when code run by `eval()` or `exec()` would need more names than bytecode
+/// operands can refer to.
+fn too_many_names() -> RunError {
+    SimpleException::new_msg(ExcType::MemoryError, "too many names in code compiled at runtime").into()
+}
@@ -1,12 +1,13 @@
 //! Exception handling helpers for the VM.
 
-use super::VM;
+use super::{CallFrame, VM};
 use crate::{
     builtins::Builtins,
-    defer_drop,
-    exception_private::{ExcType, ExceptionRaise, RawStackFrame, RunError, SimpleException},
+    exception_private::{
+        ExcClassInfo, ExcType, ExceptionRaise, RawStackFrame, RunError, RunResult, SimpleException, allocate_exception,
+    },
     heap::{HeapData, HeapGuard},
-    intern::{StaticStrings, StringId},
+    intern::{ExcClassId, StaticStrings, StringId},
     resource::ResourceTracker,
     types::{PyTrait, Type},
     value::Value,
@@ -17,8 +18,12 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
     ///
     /// Returns the function name for user-defined functions, or `<module>` for
     /// module-level code.
-    fn current_frame_name(&self) -> StringId {
-        let frame = self.current_frame();
+    pub(super) fn current_frame_name(&self) -> StringId {
+        self.frame_name(self.current_frame())
+    }
+
+    /// Returns the name of the function `frame` runs, or `<module>` for module-level code.
+    pub(super) fn frame_name(&self, frame: &CallFrame<'_>) -> StringId {
         match frame.function_id {
             Some(func_id) => self.interns.get_function(func_id).name.name_id,
             None => StaticStrings::Module.into(),
@@ -65,17 +70,16 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
 
     /// Creates a RunError from a Value that should be an exception.
     ///
-    /// Takes ownership of the exception value and drops it properly.
+    /// Takes ownership of the exception value. A heap exception is kept in `raised_exception`
+    /// until the error is handled, so the `except` clause binds the object that was raised.
     /// The `is_raise` flag indicates if this is from a `raise` statement (hide caret).
     pub(super) fn make_exception(&mut self, exc_value: Value, is_raise: bool) -> RunError {
-        let this = self;
-        defer_drop!(exc_value, this);
-
-        let simple_exc = match exc_value {
+        let mut raised_id = None;
+        let simple_exc = match &exc_value {
             // Exception instance on heap
             Value::Ref(heap_id) => {
-                if let HeapData::Exception(exc) = this.heap.get(*heap_id) {
-                    // Clone the exception (guard handles cleanup at scope exit)
+                if let HeapData::Exception(exc) = self.heap.get(*heap_id) {
+                    raised_id = Some(*heap_id);
                     exc.clone()
                 } else {
                     // Not an exception type
@@ -85,24 +89,171 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
             // Exception type (e.g., `raise ValueError` instead of `raise ValueError()`)
             // Instantiate with no message
             Value::Builtin(Builtins::ExcType(exc_type)) => SimpleException::new_none(*exc_type),
+            // User-defined exception class (e.g., `raise MyError`)
+            Value::Builtin(Builtins::ExcClass(class_id)) => {
+                match SimpleException::new_class_instance(*class_id, self.heap, None) {
+                    Ok(exc) => exc,
+                    Err(err) => return err,
+                }
+            }
             // Invalid exception value
             _ => SimpleException::new_msg(ExcType::TypeError, "exceptions must derive from BaseException"),
         };
 
+        let unused = if raised_id.is_some() {
+            self.raised_exception.replace(exc_value)
+        } else {
+            Some(exc_value)
+        };
+        if let Some(value) = unused {
+            value.drop_with_heap(self.heap);
+        }
+
         // Create frame with appropriate hide_caret setting
         let frame = if is_raise {
-            RawStackFrame::from_raise(this.current_position(), this.current_frame_name())
+            RawStackFrame::from_raise(self.current_position(), self.current_frame_name())
         } else {
-            this.make_stack_frame()
+            self.make_stack_frame()
         };
 
         RunError::Exc(ExceptionRaise {
             exc: simple_exc,
             frame: Some(frame),
             hide_caret: false,
+            limit: None,
+            raised_id,
         })
     }
 
+    /// Creates a RunError for `raise exc from cause`.
+    ///
+    /// Takes ownership of both values. `cause` may be an exception instance, an exception
+    /// type (instantiated with no message) or `None` (explicitly no cause). Anything else
+    /// raises `TypeError`, as in CPython. The cause object is stored on the raised exception,
+    /// so `__cause__` returns that same object.
+    pub(super) fn make_exception_from(&mut self, exc_value: Value, cause_value: Value) -> RunError {
+        // An invalid exception takes precedence over an invalid cause, and is reported
+        // by `make_exception` without attaching any cause.
+        if !self.is_exception(&exc_value) {
+            cause_value.drop_with_heap(self.heap);
+            return self.make_exception(exc_value, true);
+        }
+        if !matches!(cause_value, Value::None) && !self.is_exception(&cause_value) {
+            exc_value.drop_with_heap(self.heap);
+            cause_value.drop_with_heap(self.heap);
+            let frame = RawStackFrame::from_raise(self.current_position(), self.current_frame_name());
+            return RunError::Exc(
+                SimpleException::new_msg(ExcType::TypeError, "exception causes must derive from BaseException")
+                    .with_frame(frame),
+            );
+        }
+
+        // Both ends of the chain must be heap objects for the exception to reference its cause
+        let exc_value = match self.instantiate_exception(exc_value) {
+            Ok(value) => value,
+            Err(err) => {
+                cause_value.drop_with_heap(self.heap);
+                return err;
+            }
+        };
+        let cause_value = match self.instantiate_exception(cause_value) {
+            Ok(value) => value,
+            Err(err) => {
+                exc_value.drop_with_heap(self.heap);
+                return err;
+            }
+        };
+
+        let cause = match cause_value.ref_id() {
+            Some(cause_id) => {
+                let HeapData::Exception(cause) = self.heap.get(cause_id) else {
+                    unreachable!("cause was checked to be an exception")
+                };
+                let cause = cause.clone();
+                // The exception takes over this reference
+                self.heap.inc_ref(cause_id);
+                Some((cause, cause_id))
+            }
+            None => None,
+        };
+        cause_value.drop_with_heap(self.heap);
+        let has_cause = cause.is_some();
+
+        let exc_id = exc_value.ref_id().expect("exception was instantiated on the heap");
+        let HeapData::Exception(exc) = self.heap.get_mut(exc_id) else {
+            unreachable!("exception was checked to be an exception")
+        };
+        if let Some(old_cause_id) = exc.set_cause(cause) {
+            self.heap.dec_ref(old_cause_id);
+        }
+        if has_cause {
+            // `a` caused by `b` caused by `a` is a cycle
+            self.heap.mark_potential_cycle();
+        }
+
+        self.make_exception(exc_value, true)
+    }
+
+    /// Defines user-defined exception class `class_id`, replacing the base on top of the
+    /// stack with the class.
+    ///
+    /// The base is whatever the `class` statement's base expression evaluated to, which
+    /// must be a builtin exception type or another user-defined exception class.
+    ///
+    /// A class is identified by its `class` statement, so when the statement runs again,
+    /// e.g. in a function called twice, the class must have the same base: otherwise
+    /// instances created by the earlier run would change their ancestry.
+    pub(super) fn make_exc_class(&mut self, class_id: ExcClassId) -> RunResult<()> {
+        let base = self.pop();
+        let (parent, base_type) = match &base {
+            Value::Builtin(Builtins::ExcType(exc_type)) => (None, *exc_type),
+            Value::Builtin(Builtins::ExcClass(parent_id)) => (Some(*parent_id), self.heap.exc_class(*parent_id)?.base),
+            _ => {
+                base.drop_with_heap(self.heap);
+                return Err(ExcType::not_implemented("classes that do not subclass an exception").into());
+            }
+        };
+        let info = ExcClassInfo {
+            parent,
+            base: base_type,
+            module: self.module_name(),
+        };
+        match self.heap.defined_exc_class(class_id) {
+            Some(existing) if existing != info => {
+                let name = self.interns.get_str(self.interns.get_exc_class(class_id).name);
+                return Err(ExcType::not_implemented(format!(
+                    "redefining exception class '{name}' with a different base is not supported"
+                ))
+                .into());
+            }
+            Some(_) => {}
+            None => self.heap.define_exc_class(class_id, info),
+        }
+        self.push(Value::Builtin(Builtins::ExcClass(class_id)));
+        Ok(())
+    }
+
+    /// Returns true if `value` is an exception instance, exception type or exception class.
+    fn is_exception(&self, value: &Value) -> bool {
+        match value {
+            Value::Ref(heap_id) => matches!(self.heap.get(*heap_id), HeapData::Exception(_)),
+            Value::Builtin(Builtins::ExcType(_) | Builtins::ExcClass(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Instantiates an exception type or class with no message, returning other values unchanged.
+    fn instantiate_exception(&mut self, value: Value) -> RunResult<Value> {
+        let exc = match value {
+            Value::Builtin(Builtins::ExcType(exc_type)) => SimpleException::new_none(exc_type),
+            Value::Builtin(Builtins::ExcClass(class_id)) => {
+                SimpleException::new_class_instance(class_id, self.heap, None)?
+            }
+            _ => return Ok(value),
+        };
+        Ok(Value::Ref(self.heap.allocate(HeapData::Exception(exc))?))
+    }
+
     /// Handles an exception by searching for a handler in the exception table.
     ///
     /// Returns:
@@ -252,35 +403,62 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
 
     /// Creates an exception Value from exception info.
     ///
-    /// Allocates an Exception on the heap and returns a Value::Ref to it.
+    /// Returns the raised object if `make_exception` kept it, otherwise allocates an
+    /// Exception on the heap and returns a Value::Ref to it.
     fn create_exception_value(&mut self, exc: &ExceptionRaise) -> Result<Value, RunError> {
-        let exception = exc.exc.clone();
-        let heap_id = self.heap.allocate(HeapData::Exception(exception))?;
+        if let Some(raised) = self.raised_exception.take() {
+            if exc.raised_id.is_some() && raised.ref_id() == exc.raised_id {
+                return Ok(raised);
+            }
+            raised.drop_with_heap(self.heap);
+        }
+        let heap_id = allocate_exception(exc.exc.clone(), self.heap)?;
         Ok(Value::Ref(heap_id))
     }
 
     /// Checks if an exception matches an exception type for except clause matching.
     ///
-    /// Validates that `exc_type` is a valid exception type (ExcType or tuple of ExcTypes).
+    /// Validates that `exc_type` is a valid exception type (ExcType, user-defined exception
+    /// class, or tuple of those).
     /// Returns `Ok(true)` if exception matches, `Ok(false)` if not, or `Err` if exc_type is invalid.
     pub(super) fn check_exc_match(&self, exception: &Value, exc_type: &Value) -> Result<bool, RunError> {
         let exc_type_enum = exception.py_type(self.heap);
-        self.check_exc_match_inner(exc_type_enum, exc_type)
+        let exc_class = match exception {
+            Value::Ref(id) => match self.heap.get(*id) {
+                HeapData::Exception(exc) => exc.class_id(),
+                _ => None,
+            },
+            _ => None,
+        };
+        self.check_exc_match_inner(exc_type_enum, exc_class, exc_type)
     }
 
     /// Inner recursive helper for check_exc_match that handles tuples.
-    fn check_exc_match_inner(&self, exc_type_enum: Type, exc_type: &Value) -> Result<bool, RunError> {
+    ///
+    /// `exc_class` is the user-defined class of the exception, if any; `exc_type_enum`
+    /// is always its builtin type (the class's nearest builtin ancestor for user classes).
+    fn check_exc_match_inner(
+        &self,
+        exc_type_enum: Type,
+        exc_class: Option<ExcClassId>,
+        exc_type: &Value,
+    ) -> Result<bool, RunError> {
         match exc_type {
             // Valid exception type
             Value::Builtin(Builtins::ExcType(handler_type)) => {
                 // Check if exception is an instance of handler_type
                 Ok(matches!(exc_type_enum, Type::Exception(et) if et.is_subclass_of(*handler_type)))
             }
+            // User-defined exception class
+            Value::Builtin(Builtins::ExcClass(handler_class)) => match exc_class {
+                Some(class_id) => self.heap.exc_class_is_subclass_of(class_id, *handler_class),
+                None => Ok(false),
+            },
             // Tuple of exception types
             Value::Ref(id) => {
                 if let HeapData::Tuple(tuple) = self.heap.get(*id) {
                     for v in tuple.as_slice() {
-                        if self.check_exc_match_inner(exc_type_enum, v)? {
+                        if self.check_exc_match_inner(exc_type_enum, exc_class, v)? {
                             return Ok(true);
                         }
                     }
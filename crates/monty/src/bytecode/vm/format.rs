@@ -3,8 +3,10 @@
 use super::VM;
 use crate::{
     defer_drop,
-    exception_private::{ExcType, RunError, SimpleException},
-    fstring::{ParsedFormatSpec, ascii_escape, decode_format_spec, format_string, format_with_spec},
+    exception_private::RunError,
+    fstring::{
+        ConversionFlag, ParsedFormatSpec, ascii_escape, decode_format_spec, format_converted, format_spec_error,
+    },
     resource::{DepthGuard, ResourceTracker},
     types::{PyTrait, str::allocate_string},
     value::Value,
@@ -56,42 +58,28 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
         let value = this.pop();
         defer_drop!(value, this);
 
+        let conversion = match conversion {
+            1 => ConversionFlag::Str,
+            2 => ConversionFlag::Repr,
+            3 => ConversionFlag::Ascii,
+            _ => ConversionFlag::None,
+        };
+
         // Format with spec applied to original value type, or convert and format as string
+        let mut guard = DepthGuard::default();
         let formatted = if let Some(spec_value) = format_spec {
             defer_drop!(spec_value, this);
 
             let spec = this.get_format_spec(spec_value, value)?;
-
-            let mut guard = DepthGuard::default();
-            match conversion {
-                // No conversion - format original value
-                0 => format_with_spec(value, &spec, this.heap, &mut guard, this.interns)?,
-                // !s - convert to str, format as string
-                1 => {
-                    let s = value.py_str(this.heap, &mut guard, this.interns);
-                    format_string(&s, &spec)?
-                }
-                // !r - convert to repr, format as string
-                2 => {
-                    let s = value.py_repr(this.heap, &mut guard, this.interns);
-                    format_string(&s, &spec)?
-                }
-                // !a - convert to ascii, format as string
-                3 => {
-                    let s = ascii_escape(&value.py_repr(this.heap, &mut guard, this.interns));
-                    format_string(&s, &spec)?
-                }
-                _ => format_with_spec(value, &spec, this.heap, &mut guard, this.interns)?,
-            }
+            format_converted(value, conversion, &spec, this.heap, &mut guard, this.interns)?
         } else {
             // No format spec - just convert based on conversion flag
-            let mut guard = DepthGuard::default();
             match conversion {
-                0 => value.py_str(this.heap, &mut guard, this.interns).into_owned(),
-                1 => value.py_str(this.heap, &mut guard, this.interns).into_owned(),
-                2 => value.py_repr(this.heap, &mut guard, this.interns).into_owned(),
-                3 => ascii_escape(&value.py_repr(this.heap, &mut guard, this.interns)),
-                _ => value.py_str(this.heap, &mut guard, this.interns).into_owned(),
+                ConversionFlag::None | ConversionFlag::Str => {
+                    value.py_str(this.heap, &mut guard, this.interns).into_owned()
+                }
+                ConversionFlag::Repr => value.py_repr(this.heap, &mut guard, this.interns).into_owned(),
+                ConversionFlag::Ascii => ascii_escape(&value.py_repr(this.heap, &mut guard, this.interns)),
             }
         };
 
@@ -115,16 +103,9 @@ impl<T: ResourceTracker> VM<'_, '_, T> {
                 // Dynamic format spec - parse the string
                 let mut guard = DepthGuard::default();
                 let spec_str = spec_value.py_str(self.heap, &mut guard, self.interns);
-                spec_str.parse::<ParsedFormatSpec>().map_err(|invalid| {
+                spec_str.parse::<ParsedFormatSpec>().map_err(|err| {
                     // Only fetch type in error path
-                    let value_type = value_for_error.py_type(self.heap);
-                    RunError::Exc(
-                        SimpleException::new_msg(
-                            ExcType::ValueError,
-                            format!("Invalid format specifier '{invalid}' for object of type '{value_type}'"),
-                        )
-                        .into(),
-                    )
+                    format_spec_error(err, value_for_error.py_type(self.heap))
                 })
             }
         }
@@ -9,12 +9,14 @@ mod binary;
 mod call;
 mod collections;
 mod compare;
+mod eval;
 mod exceptions;
 mod format;
 mod scheduler;
 
 use std::cmp::Ordering;
 
+use ahash::AHashSet;
 use call::CallResult;
 use scheduler::Scheduler;
 
@@ -22,18 +24,25 @@ use crate::{
     MontyObject,
     args::ArgValues,
     asyncio::{CallId, TaskId},
-    bytecode::{code::Code, op::Opcode},
+    bytecode::{
+        code::{Code, LocationEntry},
+        op::Opcode,
+    },
     exception_private::{ExcType, RunError, RunResult, SimpleException},
     heap::{ContainsHeap, Heap, HeapData, HeapId},
-    intern::{ExtFunctionId, FunctionId, Interns, StringId},
+    intern::{ExcClassId, ExtFunctionId, FunctionId, Interns, StaticStrings, StringId},
     io::PrintWriter,
     modules::BuiltinModule,
     namespace::{GLOBAL_NS_IDX, NamespaceId, Namespaces},
     os::OsFunction,
     parse::CodeRange,
-    resource::ResourceTracker,
-    types::{LongInt, MontyIter, PyTrait, iter::advance_on_heap},
-    value::{BitwiseOp, EitherStr, Value},
+    resource::{DepthGuard, PauseReason, ResourceError, ResourceTracker},
+    trace::{StatementKind, TraceEvent, truncate_repr},
+    types::{
+        LongInt, Module, PyTrait,
+        iter::{advance_on_heap, get_iter},
+    },
+    value::{BitwiseOp, EitherStr, Value, floor_divmod},
 };
 
 /// Result of executing Await opcode.
@@ -277,6 +286,13 @@ pub enum FrameExit {
     /// This happens when await is called on an ExternalFuture that hasn't
     /// been resolved yet, and there are no other ready tasks to switch to.
     ResolveFutures(Vec<CallId>),
+
+    /// Execution paused at an instruction boundary, either because the time limit was
+    /// exceeded and the resource tracker asked to pause rather than raise `TimeoutError`,
+    /// or because the tracker requested a checkpoint at the start of a statement.
+    ///
+    /// The caller resumes by calling `run()` again, without pushing a value.
+    Paused(PauseReason),
 }
 
 /// A single function activation record.
@@ -311,6 +327,11 @@ pub struct CallFrame<'code> {
     /// When this frame returns (or exits with an exception) the VM should exit the run loop
     /// and return to the caller. Supports `evaluate_function`.
     should_return: bool,
+
+    /// Number of `functools.lru_cache` wrappers waiting for this frame's result: when it
+    /// returns, the result is stored using each wrapper and cache key pair left on the
+    /// caller's stack by `call_lru_cache`.
+    cache_results: u16,
 }
 
 impl<'code> CallFrame<'code> {
@@ -325,6 +346,7 @@ impl<'code> CallFrame<'code> {
             cells: Vec::new(),
             call_position: None,
             should_return: false,
+            cache_results: 0,
         }
     }
 
@@ -346,6 +368,7 @@ impl<'code> CallFrame<'code> {
             cells,
             call_position,
             should_return: false,
+            cache_results: 0,
         }
     }
 }
@@ -396,6 +419,9 @@ pub struct SerializedFrame {
 
     /// Call site position (for tracebacks).
     call_position: Option<CodeRange>,
+
+    /// Number of `lru_cache` wrappers storing the frame's result when it returns.
+    cache_results: u16,
 }
 
 impl CallFrame<'_> {
@@ -412,6 +438,7 @@ impl CallFrame<'_> {
             namespace_idx: self.namespace_idx,
             cells: self.cells.clone(),
             call_position: self.call_position,
+            cache_results: self.cache_results,
         }
     }
 }
@@ -454,6 +481,28 @@ pub struct VMSnapshot {
     /// This enables async execution to be paused and resumed across host calls.
     /// None if no async operations have been performed yet.
     scheduler: Option<Scheduler>,
+
+    /// Whether a checkpoint was requested where execution couldn't be suspended.
+    checkpoint_pending: bool,
+
+    /// Whether execution stopped at a checkpoint, so the statement it resumes at was already counted.
+    at_checkpoint: bool,
+
+    /// Whether execution stopped at a breakpoint, so the statement it resumes at was already traced.
+    at_breakpoint: bool,
+}
+
+impl VMSnapshot {
+    /// Returns the function (`None` for module-level code) and namespace of the innermost
+    /// frame, or `None` if there are no frames.
+    pub fn current_frame(&self) -> Option<(Option<FunctionId>, NamespaceId)> {
+        self.frames.last().map(|frame| (frame.function_id, frame.namespace_idx))
+    }
+
+    /// Returns the function and namespace of each frame, outermost first.
+    pub fn frames(&self) -> impl Iterator<Item = (Option<FunctionId>, NamespaceId)> + '_ {
+        self.frames.iter().map(|frame| (frame.function_id, frame.namespace_idx))
+    }
 }
 
 // ============================================================================
@@ -496,6 +545,13 @@ pub struct VM<'a, 'p, T: ResourceTracker> {
     /// except handlers to restore the outer exception context.
     exception_stack: Vec<Value>,
 
+    /// The exception object most recently raised by `raise`, held until the error is
+    /// handled so the `except` clause binds that object rather than a copy.
+    ///
+    /// Not part of snapshots: it is only set between a `raise` and the handler search
+    /// that follows, and execution never pauses in between.
+    raised_exception: Option<Value>,
+
     /// IP of the instruction being executed (for exception table lookup).
     ///
     /// Updated at the start of each instruction before operands are fetched.
@@ -519,6 +575,18 @@ pub struct VM<'a, 'p, T: ResourceTracker> {
     /// Stored here because the main task's frames have `function_id: None` and
     /// need a reference to the module code when being restored after task switching.
     module_code: Option<&'a Code>,
+
+    /// A checkpoint was requested inside a nested VM loop and is taken at the next
+    /// statement where execution can be suspended.
+    checkpoint_pending: bool,
+
+    /// Skip the `on_statement` call at the next statement start, set when pausing for a
+    /// checkpoint so the statement execution resumes at isn't counted twice.
+    at_checkpoint: bool,
+
+    /// Skip the trace hook at the next statement start, set when the hook requested a
+    /// pause so the statement execution resumes at isn't reported twice.
+    at_breakpoint: bool,
 }
 
 impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
@@ -537,10 +605,14 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             interns,
             print_writer,
             exception_stack: Vec::new(),
+            raised_exception: None,
             instruction_ip: 0,
             next_call_id: 0,
             scheduler: None, // Lazy - no allocation for sync code
             module_code: None,
+            checkpoint_pending: false,
+            at_checkpoint: false,
+            at_breakpoint: false,
         }
     }
 
@@ -583,6 +655,7 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     cells: sf.cells,
                     call_position: sf.call_position,
                     should_return: false,
+                    cache_results: sf.cache_results,
                 }
             })
             .collect();
@@ -595,10 +668,14 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             interns,
             print_writer,
             exception_stack: snapshot.exception_stack,
+            raised_exception: None,
             instruction_ip: snapshot.instruction_ip,
             next_call_id: snapshot.next_call_id,
             scheduler: snapshot.scheduler,
             module_code: Some(module_code),
+            checkpoint_pending: snapshot.checkpoint_pending,
+            at_checkpoint: snapshot.at_checkpoint,
+            at_breakpoint: snapshot.at_breakpoint,
         }
     }
     /// Consumes the VM and creates a snapshot for pause/resume if needed.
@@ -608,7 +685,8 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             Ok(FrameExit::ExternalCall { .. }
                 | FrameExit::OsCall { .. }
                 | FrameExit::MethodCall { .. }
-                | FrameExit::ResolveFutures(_))
+                | FrameExit::ResolveFutures(_)
+                | FrameExit::Paused(_))
         ) {
             Some(self.snapshot())
         } else {
@@ -625,7 +703,10 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
     ///
     /// This is NOT a clone - it's a transfer. After calling this, the original VM
     /// is gone and only the snapshot (+ serialized heap/namespaces) represents the state.
-    pub fn snapshot(self) -> VMSnapshot {
+    pub fn snapshot(mut self) -> VMSnapshot {
+        if let Some(raised) = self.raised_exception.take() {
+            raised.drop_with_heap(self.heap);
+        }
         VMSnapshot {
             // Move values directly - no clone, no refcount increment needed
             // (the VM owned them, now the snapshot owns them)
@@ -635,6 +716,9 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             instruction_ip: self.instruction_ip,
             next_call_id: self.next_call_id,
             scheduler: self.scheduler,
+            checkpoint_pending: self.checkpoint_pending,
+            at_checkpoint: self.at_checkpoint,
+            at_breakpoint: self.at_breakpoint,
         }
     }
 
@@ -655,6 +739,9 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
         for exc in self.exception_stack.drain(..) {
             exc.drop_with_heap(self.heap);
         }
+        if let Some(raised) = self.raised_exception.take() {
+            raised.drop_with_heap(self.heap);
+        }
         // Stack should be empty, but clean up just in case
         for value in self.stack.drain(..) {
             value.drop_with_heap(self.heap);
@@ -687,8 +774,8 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                 for cell_id in frame.cells {
                     self.heap.dec_ref(cell_id);
                 }
-                // Clean up the namespace (but not the global namespace)
-                if frame.namespace_idx != GLOBAL_NS_IDX {
+                // Clean up the namespace (but not the global or a module namespace)
+                if !self.namespaces.is_persistent(frame.namespace_idx) {
                     self.namespaces.drop_with_heap(frame.namespace_idx, self.heap);
                 }
             }
@@ -721,6 +808,112 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             .is_none_or(|s| s.current_task_id().is_none_or(TaskId::is_main))
     }
 
+    /// Returns true if exceeding the time limit should pause execution rather than raise.
+    ///
+    /// Pausing is only possible when the VM was entered from the host: frames run to
+    /// completion by a nested `run()` (e.g. a `sorted()` key function) can't be suspended,
+    /// so the time limit raises `TimeoutError` there as usual.
+    fn can_pause(&self) -> bool {
+        self.heap.tracker().pause_on_timeout() && self.can_suspend()
+    }
+
+    /// Returns true if no frame is being run to completion by a nested `run()`.
+    fn can_suspend(&self) -> bool {
+        !self.frames.iter().any(|frame| frame.should_return)
+    }
+
+    /// Reports the start of a statement to the tracker, returning true if execution should
+    /// stop here with a checkpoint.
+    ///
+    /// Only called when the tracker's `checkpoints_enabled()` is true.
+    fn checkpoint_due(&mut self) -> bool {
+        if self.at_checkpoint {
+            // resuming from a checkpoint at this statement, which was counted before pausing
+            self.at_checkpoint = false;
+            return false;
+        }
+        if self.heap.tracker_mut().on_statement() || self.checkpoint_pending {
+            if self.can_suspend() {
+                self.checkpoint_pending = false;
+                self.at_checkpoint = true;
+                return true;
+            }
+            self.checkpoint_pending = true;
+        }
+        false
+    }
+
+    /// Calls the trace hook for the statement of kind `kind` starting at `ip` in the current frame.
+    ///
+    /// Returns true if the hook asked to pause before the statement runs.
+    ///
+    /// Only called when a hook is installed. An error from the hook aborts execution and
+    /// can't be caught by the traced code.
+    fn trace_statement(&mut self, code: &Code, ip: usize, kind: StatementKind) -> Result<bool, RunError> {
+        let Some(mut hook) = self.heap.take_trace_hook() else {
+            return Ok(false);
+        };
+        let locals = hook.wants_locals().then(|| self.locals_summary(code));
+        let range = code.location_for_offset(ip).map(LocationEntry::range);
+        let event = TraceEvent {
+            filename: range.map_or("", |range| self.interns.get_str(range.filename)),
+            line: range.map_or(0, |range| range.start().line),
+            kind,
+            function_name: self.interns.get_str(self.current_frame_name()),
+            depth: self.frames.len(),
+            locals,
+        };
+        let result = hook
+            .on_statement(&event)
+            .map(|()| self.can_suspend() && hook.pause_requested());
+        self.heap.set_trace_hook(Some(hook));
+        result.map_err(|exc| RunError::UncatchableExc(exc.into()))
+    }
+
+    /// Reports the statement starting at `ip` in the current frame to a profiling tracker.
+    fn profile_statement(&mut self, code: &Code, ip: usize) {
+        let line = code
+            .location_for_offset(ip)
+            .map_or(0, |entry| entry.range().start().line);
+        let interns = self.interns;
+        let stack: Vec<&str> = self
+            .frames
+            .iter()
+            .map(|frame| interns.get_str(self.frame_name(frame)))
+            .collect();
+        self.heap.tracker_mut().on_profile_statement(&stack, line);
+    }
+
+    /// Returns the bound local variables of the current frame as `(name, repr)` pairs.
+    ///
+    /// Slots without a recorded name and cell references are skipped, closure variables
+    /// live in the frame's cells rather than its namespace.
+    fn locals_summary(&self, code: &Code) -> Vec<(String, String)> {
+        let namespace = self.namespaces.get(self.current_frame().namespace_idx);
+        let mut guard = DepthGuard::default();
+        let mut locals = Vec::new();
+        for (slot, value) in namespace.values().iter().enumerate() {
+            if matches!(value, Value::Undefined) {
+                continue;
+            }
+            if let Value::Ref(id) = value
+                && matches!(self.heap.get(*id), HeapData::Cell(_))
+            {
+                continue;
+            }
+            let Some(name) = u16::try_from(slot).ok().and_then(|slot| code.local_name(slot)) else {
+                continue;
+            };
+            let name = self.interns.get_str(name);
+            if name.is_empty() {
+                continue;
+            }
+            let repr = value.py_repr(self.heap, &mut guard, self.interns).into_owned();
+            locals.push((name.to_owned(), truncate_repr(repr)));
+        }
+        locals
+    }
+
     /// Main execution loop.
     ///
     /// Fetches opcodes from the current frame's bytecode and executes them.
@@ -734,11 +927,48 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
         // Cache frame state locally to avoid repeated frames.last_mut() calls.
         // The Code reference has lifetime 'a (lives in Interns), independent of frame borrow.
         let mut cached_frame: CachedFrame<'a> = self.new_cached_frame();
+        let checkpoints = self.heap.tracker().checkpoints_enabled();
+        let tracing = self.heap.has_trace_hook();
+        let profiling = self.heap.tracker().profiling_enabled();
 
         loop {
             // Check time limit and trigger GC if needed at each instruction.
             // For NoLimitTracker, these are inlined no-ops that compile away.
-            self.heap.check_time()?;
+            if let Err(err) = self.heap.check_time() {
+                if matches!(err, ResourceError::Time { .. }) && self.can_pause() {
+                    // The instruction at `ip` hasn't been fetched yet, so resuming starts with it
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    return Ok(FrameExit::Paused(PauseReason::Timeout));
+                }
+                return Err(err.into());
+            }
+
+            if checkpoints && cached_frame.code.is_statement_start(cached_frame.ip) && self.checkpoint_due() {
+                self.current_frame_mut().ip = cached_frame.ip;
+                return Ok(FrameExit::Paused(PauseReason::Checkpoint));
+            }
+
+            if tracing
+                && cached_frame.code.is_statement_start(cached_frame.ip)
+                && let Some(kind) = cached_frame.code.statement_kind(cached_frame.ip)
+            {
+                // Sync IP so the hook's error points at this statement
+                self.current_frame_mut().ip = cached_frame.ip;
+                self.instruction_ip = cached_frame.ip;
+                if self.at_breakpoint {
+                    // resuming from a breakpoint at this statement, which was reported before pausing
+                    self.at_breakpoint = false;
+                } else if self.trace_statement(cached_frame.code, cached_frame.ip, kind)? {
+                    self.at_breakpoint = true;
+                    // the statement was already counted if checkpoints are enabled
+                    self.at_checkpoint = checkpoints;
+                    return Ok(FrameExit::Paused(PauseReason::Breakpoint));
+                }
+            }
+
+            if profiling && cached_frame.code.is_statement_start(cached_frame.ip) {
+                self.profile_statement(cached_frame.code, cached_frame.ip);
+            }
 
             if self.heap.should_gc() {
                 // Sync IP before GC for safety
@@ -837,7 +1067,11 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                 }
                 Opcode::DeleteLocal => {
                     let slot = u16::from(fetch_u8!(cached_frame));
-                    self.delete_local(&cached_frame, slot);
+                    try_catch_sync!(self, cached_frame, self.delete_local(&cached_frame, slot));
+                }
+                Opcode::DeleteLocalW => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_local(&cached_frame, slot));
                 }
                 // Variables - Global Operations
                 Opcode::LoadGlobal => {
@@ -848,6 +1082,10 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     let slot = fetch_u16!(cached_frame);
                     self.store_global(slot);
                 }
+                Opcode::DeleteGlobal => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_global(slot));
+                }
                 // Variables - Cell Operations (closures)
                 Opcode::LoadCell => {
                     let slot = fetch_u16!(cached_frame);
@@ -857,13 +1095,38 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     let slot = fetch_u16!(cached_frame);
                     self.store_cell(slot);
                 }
-                // Binary Operations - route through exception handling for tracebacks
-                Opcode::BinaryAdd => try_catch_sync!(self, cached_frame, self.binary_add()),
-                Opcode::BinarySub => try_catch_sync!(self, cached_frame, self.binary_sub()),
-                Opcode::BinaryMul => try_catch_sync!(self, cached_frame, self.binary_mult()),
+                Opcode::DeleteCell => {
+                    let slot = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.delete_cell(slot));
+                }
+                // Binary Operations - route through exception handling for tracebacks.
+                // Int-Int pairs take an inline fast path that never touches the heap.
+                Opcode::BinaryAdd => {
+                    if !self.try_int_binary_op(i64::checked_add) {
+                        try_catch_sync!(self, cached_frame, self.binary_add());
+                    }
+                }
+                Opcode::BinarySub => {
+                    if !self.try_int_binary_op(i64::checked_sub) {
+                        try_catch_sync!(self, cached_frame, self.binary_sub());
+                    }
+                }
+                Opcode::BinaryMul => {
+                    if !self.try_int_binary_op(i64::checked_mul) {
+                        try_catch_sync!(self, cached_frame, self.binary_mult());
+                    }
+                }
                 Opcode::BinaryDiv => try_catch_sync!(self, cached_frame, self.binary_div()),
-                Opcode::BinaryFloorDiv => try_catch_sync!(self, cached_frame, self.binary_floordiv()),
-                Opcode::BinaryMod => try_catch_sync!(self, cached_frame, self.binary_mod()),
+                Opcode::BinaryFloorDiv => {
+                    if !self.try_int_binary_op(|a, b| floor_divmod(a, b).map(|(d, _)| d)) {
+                        try_catch_sync!(self, cached_frame, self.binary_floordiv());
+                    }
+                }
+                Opcode::BinaryMod => {
+                    if !self.try_int_binary_op(|a, b| floor_divmod(a, b).map(|(_, m)| m)) {
+                        try_catch_sync!(self, cached_frame, self.binary_mod());
+                    }
+                }
                 Opcode::BinaryPow => try_catch_sync!(self, cached_frame, self.binary_pow()),
                 // Bitwise operations - only work on integers
                 Opcode::BinaryAnd => try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::And)),
@@ -877,12 +1140,36 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                 }
                 Opcode::BinaryMatMul => try_catch_sync!(self, cached_frame, self.binary_matmul()),
                 // Comparison Operations
-                Opcode::CompareEq => try_catch_sync!(self, cached_frame, self.compare_eq()),
-                Opcode::CompareNe => try_catch_sync!(self, cached_frame, self.compare_ne()),
-                Opcode::CompareLt => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_lt)),
-                Opcode::CompareLe => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_le)),
-                Opcode::CompareGt => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_gt)),
-                Opcode::CompareGe => try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_ge)),
+                Opcode::CompareEq => {
+                    if !self.try_int_compare(|a, b| a == b) {
+                        try_catch_sync!(self, cached_frame, self.compare_eq());
+                    }
+                }
+                Opcode::CompareNe => {
+                    if !self.try_int_compare(|a, b| a != b) {
+                        try_catch_sync!(self, cached_frame, self.compare_ne());
+                    }
+                }
+                Opcode::CompareLt => {
+                    if !self.try_int_compare(|a, b| a < b) {
+                        try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_lt));
+                    }
+                }
+                Opcode::CompareLe => {
+                    if !self.try_int_compare(|a, b| a <= b) {
+                        try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_le));
+                    }
+                }
+                Opcode::CompareGt => {
+                    if !self.try_int_compare(|a, b| a > b) {
+                        try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_gt));
+                    }
+                }
+                Opcode::CompareGe => {
+                    if !self.try_int_compare(|a, b| a >= b) {
+                        try_catch_sync!(self, cached_frame, self.compare_ord(Ordering::is_ge));
+                    }
+                }
                 Opcode::CompareIs => self.compare_is(false),
                 Opcode::CompareIsNot => self.compare_is(true),
                 Opcode::CompareIn => try_catch_sync!(self, cached_frame, self.compare_in(false)),
@@ -926,6 +1213,15 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                                     Ok(v) => self.push(v),
                                     Err(e) => catch_sync!(self, cached_frame, RunError::from(e)),
                                 }
+                            } else if let HeapData::TimeDelta(td) = self.heap.get(id) {
+                                let negated = td.negate();
+                                value.drop_with_heap(self.heap);
+                                match negated
+                                    .and_then(|td| Ok(Value::Ref(self.heap.allocate(HeapData::TimeDelta(td))?)))
+                                {
+                                    Ok(v) => self.push(v),
+                                    Err(e) => catch_sync!(self, cached_frame, e),
+                                }
                             } else {
                                 let value_type = value.py_type(self.heap);
                                 value.drop_with_heap(self.heap);
@@ -946,8 +1242,8 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                         Value::Int(_) | Value::Float(_) => self.push(value),
                         Value::Bool(b) => self.push(Value::Int(i64::from(b))),
                         Value::Ref(id) => {
-                            if matches!(self.heap.get(id), HeapData::LongInt(_)) {
-                                // LongInt - return as-is (value already has correct refcount)
+                            if matches!(self.heap.get(id), HeapData::LongInt(_) | HeapData::TimeDelta(_)) {
+                                // LongInt and timedelta - return as-is (value already has correct refcount)
                                 self.push(value);
                             } else {
                                 let value_type = value.py_type(self.heap);
@@ -991,26 +1287,47 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     }
                 }
                 // In-place Operations - route through exception handling
-                Opcode::InplaceAdd => try_catch_sync!(self, cached_frame, self.inplace_add()),
-                // Other in-place ops use the same logic as binary ops for now
-                Opcode::InplaceSub => try_catch_sync!(self, cached_frame, self.binary_sub()),
-                Opcode::InplaceMul => try_catch_sync!(self, cached_frame, self.binary_mult()),
+                Opcode::InplaceAdd => {
+                    if !self.try_int_binary_op(i64::checked_add) {
+                        try_catch_sync!(self, cached_frame, self.inplace_add());
+                    }
+                }
+                // Other in-place ops use the same logic as binary ops, except for sets which
+                // are mutated in place by `-=`, `&=`, `|=` and `^=`
+                Opcode::InplaceSub => {
+                    if !self.try_int_binary_op(i64::checked_sub) {
+                        try_catch_sync!(self, cached_frame, self.inplace_sub());
+                    }
+                }
+                Opcode::InplaceMul => {
+                    if !self.try_int_binary_op(i64::checked_mul) {
+                        try_catch_sync!(self, cached_frame, self.binary_mult());
+                    }
+                }
                 Opcode::InplaceDiv => try_catch_sync!(self, cached_frame, self.binary_div()),
-                Opcode::InplaceFloorDiv => try_catch_sync!(self, cached_frame, self.binary_floordiv()),
-                Opcode::InplaceMod => try_catch_sync!(self, cached_frame, self.binary_mod()),
+                Opcode::InplaceFloorDiv => {
+                    if !self.try_int_binary_op(|a, b| floor_divmod(a, b).map(|(d, _)| d)) {
+                        try_catch_sync!(self, cached_frame, self.binary_floordiv());
+                    }
+                }
+                Opcode::InplaceMod => {
+                    if !self.try_int_binary_op(|a, b| floor_divmod(a, b).map(|(_, m)| m)) {
+                        try_catch_sync!(self, cached_frame, self.binary_mod());
+                    }
+                }
                 Opcode::InplacePow => try_catch_sync!(self, cached_frame, self.binary_pow()),
                 Opcode::InplaceAnd => {
-                    try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::And));
+                    try_catch_sync!(self, cached_frame, self.inplace_bitwise(BitwiseOp::And));
                 }
-                Opcode::InplaceOr => try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::Or)),
+                Opcode::InplaceOr => try_catch_sync!(self, cached_frame, self.inplace_bitwise(BitwiseOp::Or)),
                 Opcode::InplaceXor => {
-                    try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::Xor));
+                    try_catch_sync!(self, cached_frame, self.inplace_bitwise(BitwiseOp::Xor));
                 }
                 Opcode::InplaceLShift => {
-                    try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::LShift));
+                    try_catch_sync!(self, cached_frame, self.inplace_bitwise(BitwiseOp::LShift));
                 }
                 Opcode::InplaceRShift => {
-                    try_catch_sync!(self, cached_frame, self.binary_bitwise(BitwiseOp::RShift));
+                    try_catch_sync!(self, cached_frame, self.inplace_bitwise(BitwiseOp::RShift));
                 }
                 // Collection Building - route through exception handling
                 Opcode::BuildList => {
@@ -1067,7 +1384,13 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                 Opcode::BinarySubscr => {
                     let index = self.pop();
                     let obj = self.pop();
-                    let result = obj.py_getitem(&index, self.heap, self.interns);
+                    // Sync IP first: a defaultdict's factory may run a nested frame
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    let result = match self.defaultdict_missing(&obj, &index) {
+                        Ok(Some(value)) => Ok(value),
+                        Ok(None) => obj.py_getitem(&index, self.heap, self.interns),
+                        Err(err) => Err(err),
+                    };
                     obj.drop_with_heap(self.heap);
                     index.drop_with_heap(self.heap);
                     match result {
@@ -1086,6 +1409,16 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                         catch_sync!(self, cached_frame, e);
                     }
                 }
+                Opcode::DeleteSubscr => {
+                    // Stack order: obj, index (TOS)
+                    let index = self.pop();
+                    let mut obj = self.pop();
+                    let result = obj.py_delitem(index, self.heap, self.interns);
+                    obj.drop_with_heap(self.heap);
+                    if let Err(e) = result {
+                        catch_sync!(self, cached_frame, e);
+                    }
+                }
                 Opcode::LoadAttr => {
                     let name_idx = fetch_u16!(cached_frame);
                     let name_id = StringId::from_index(name_idx);
@@ -1143,12 +1476,9 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                 // Iteration - route through exception handling
                 Opcode::GetIter => {
                     let value = self.pop();
-                    // Create a MontyIter from the value and store on heap
-                    match MontyIter::new(value, self.heap, self.interns) {
-                        Ok(iter) => match self.heap.allocate(HeapData::Iter(iter)) {
-                            Ok(heap_id) => self.push(Value::Ref(heap_id)),
-                            Err(e) => catch_sync!(self, cached_frame, e.into()),
-                        },
+                    // Get an iterator for the value, reusing it if it already is one
+                    match get_iter(value, self.heap, self.interns) {
+                        Ok(iter) => self.push(iter),
                         Err(e) => catch_sync!(self, cached_frame, e),
                     }
                 }
@@ -1195,10 +1525,11 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     // which pushes frames and runs a nested run() loop)
                     self.current_frame_mut().ip = cached_frame.ip;
 
-                    match self.exec_call_builtin_function(builtin_id, arg_count) {
-                        Ok(result) => self.push(result),
-                        Err(err) => catch_sync!(self, cached_frame, err),
-                    }
+                    handle_call_result!(
+                        self,
+                        cached_frame,
+                        self.exec_call_builtin_function(builtin_id, arg_count)
+                    );
                 }
                 Opcode::CallBuiltinType => {
                     // Fetch operands: type_id (u8) + arg_count (u8)
@@ -1339,12 +1670,22 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     let heap_id = self.heap.allocate(HeapData::Closure(func_id, cells, defaults))?;
                     self.push(Value::Ref(heap_id));
                 }
+                Opcode::MakeExcClass => {
+                    let class_id = ExcClassId::from_index(fetch_u16!(cached_frame));
+                    try_catch_sync!(self, cached_frame, self.make_exc_class(class_id));
+                }
                 // Exception Handling
                 Opcode::Raise => {
                     let exc = self.pop();
                     let error = self.make_exception(exc, true); // is_raise=true, hide caret
                     catch_sync!(self, cached_frame, error);
                 }
+                Opcode::RaiseFrom => {
+                    let cause = self.pop();
+                    let exc = self.pop();
+                    let error = self.make_exception_from(exc, cause);
+                    catch_sync!(self, cached_frame, error);
+                }
                 Opcode::Reraise => {
                     // Pop the current exception from the stack to re-raise it
                     // If caught, handle_exception will push it back
@@ -1405,14 +1746,24 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                         continue;
                     }
                     // Pop current frame and push return value
-                    if self.pop_frame() {
-                        // This frame indicated evaluation should stop - return to host with value
-                        // e.g. `evaluate_function`
-                        return Ok(FrameExit::Return(value));
+                    let cache_results = self.current_frame().cache_results;
+                    let should_return = self.pop_frame();
+                    if let Err(err) = (0..cache_results).try_for_each(|_| self.store_lru_result(&value)) {
+                        value.drop_with_heap(self.heap);
+                        if should_return {
+                            return Err(err);
+                        }
+                        catch_sync!(self, cached_frame, err);
+                    } else {
+                        if should_return {
+                            // This frame indicated evaluation should stop - return to host with value
+                            // e.g. `evaluate_function`
+                            return Ok(FrameExit::Return(value));
+                        }
+                        self.push(value);
+                        // Reload cache from parent frame
+                        reload_cache!(self, cached_frame);
                     }
-                    self.push(value);
-                    // Reload cache from parent frame
-                    reload_cache!(self, cached_frame);
                 }
                 // Async/Await
                 Opcode::Await => {
@@ -1455,6 +1806,20 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
                     let module_id = fetch_u8!(cached_frame);
                     try_catch_sync!(self, cached_frame, self.load_module(module_id));
                 }
+                Opcode::LoadNativeModule => {
+                    let index = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.load_native_module(index));
+                }
+                Opcode::ImportPythonModule => {
+                    let index = fetch_u16!(cached_frame);
+                    // Sync IP before import (the first import pushes a frame for the module body)
+                    self.current_frame_mut().ip = cached_frame.ip;
+                    handle_call_result!(self, cached_frame, self.import_python_module(index));
+                }
+                Opcode::BuildPythonModule => {
+                    let index = fetch_u16!(cached_frame);
+                    try_catch_sync!(self, cached_frame, self.build_python_module(index));
+                }
                 Opcode::RaiseImportError => {
                     // Fetch the module name from the constant pool and raise ModuleNotFoundError
                     let const_idx = fetch_u16!(cached_frame);
@@ -1481,6 +1846,94 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
         Ok(())
     }
 
+    /// Loads a host-provided native module and pushes it onto the stack.
+    fn load_native_module(&mut self, index: u16) -> RunResult<()> {
+        let module = self.interns.get_native_module(usize::from(index));
+        let heap_id = module.create(self.heap, self.interns)?;
+        self.push(Value::Ref(heap_id));
+        Ok(())
+    }
+
+    /// Imports a host-supplied Python module.
+    ///
+    /// The module's namespace has one slot past the body's variables, which holds the module
+    /// object once the body has run (see `build_python_module`). If it is set, the cached
+    /// module is pushed. Otherwise a frame running the module body is pushed, after clearing
+    /// what is left from a previous import whose body raised. Importing a module whose body
+    /// is still running (a circular import) raises an `ImportError`.
+    fn import_python_module(&mut self, index: u16) -> RunResult<CallResult> {
+        let interns = self.interns;
+        let module = interns.get_python_module(usize::from(index));
+        let function_id = module.body();
+        let func = interns.get_function(function_id);
+        let namespace_idx = NamespaceId::python_module(usize::from(index));
+
+        let namespace = self.namespaces.get_mut(namespace_idx).mut_vec();
+        match namespace
+            .get(func.namespace_size)
+            .map(|value| value.clone_with_heap(self.heap))
+        {
+            Some(Value::Undefined) => {
+                if self.frames.iter().any(|frame| frame.namespace_idx == namespace_idx) {
+                    return Err(ExcType::import_error_partially_initialized(
+                        interns.get_str(module.name()),
+                    ));
+                }
+                for value in namespace.iter_mut() {
+                    std::mem::replace(value, Value::Undefined).drop_with_heap(self.heap);
+                }
+            }
+            Some(cached) => return Ok(CallResult::Push(cached)),
+            None => {
+                let size = (func.namespace_size + 1) * std::mem::size_of::<Value>();
+                self.heap.tracker_mut().on_allocate(|| size)?;
+                namespace.resize_with(func.namespace_size + 1, || Value::Undefined);
+            }
+        }
+
+        let call_position = self.current_position();
+        self.frames.push(CallFrame::new_function(
+            &func.code,
+            self.stack.len(),
+            namespace_idx,
+            function_id,
+            Vec::new(),
+            Some(call_position),
+        ));
+        Ok(CallResult::FramePushed)
+    }
+
+    /// Builds the module object at the end of a Python module body and pushes it.
+    ///
+    /// Every defined variable of the module namespace becomes an attribute. The module is
+    /// also cached in the namespace's last slot so later imports reuse it.
+    fn build_python_module(&mut self, index: u16) -> RunResult<()> {
+        let frame = self.current_frame();
+        let code = frame.code;
+        let namespace_idx = frame.namespace_idx;
+        let mut module = Module::new(self.interns.get_python_module(usize::from(index)).name());
+
+        let namespace = self.namespaces.get_mut(namespace_idx).mut_vec();
+        let (cache, values) = namespace
+            .split_last_mut()
+            .expect("python module namespace has a cache slot");
+        for (slot, value) in values.iter().enumerate() {
+            if matches!(value, Value::Undefined) {
+                continue;
+            }
+            let slot = u16::try_from(slot).expect("module namespace size exceeds u16");
+            if let Some(name) = code.local_name(slot) {
+                module.set_attr(name, value.clone_with_heap(self.heap), self.heap, self.interns);
+            }
+        }
+
+        let heap_id = self.heap.allocate(HeapData::Module(module))?;
+        self.heap.inc_ref(heap_id);
+        *cache = Value::Ref(heap_id);
+        self.push(Value::Ref(heap_id));
+        Ok(())
+    }
+
     /// Resumes execution after an external call completes.
     ///
     /// Pushes the return value onto the stack and continues execution.
@@ -1570,8 +2023,8 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             let value = self.stack.pop().unwrap();
             value.drop_with_heap(self.heap);
         }
-        // Clean up the namespace (but not the global namespace)
-        if frame.namespace_idx != GLOBAL_NS_IDX {
+        // Clean up the namespace (but not the global or a module namespace)
+        if !self.namespaces.is_persistent(frame.namespace_idx) {
             self.namespaces.drop_with_heap(frame.namespace_idx, self.heap);
         }
         // Sync instruction_ip to the parent frame so exception table lookups
@@ -1592,8 +2045,8 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
             for cell_id in frame.cells {
                 self.heap.dec_ref(cell_id);
             }
-            // Clean up the namespace (but not the global namespace)
-            if frame.namespace_idx != GLOBAL_NS_IDX {
+            // Clean up the namespace (but not the global or a module namespace)
+            if !self.namespaces.is_persistent(frame.namespace_idx) {
                 self.namespaces.drop_with_heap(frame.namespace_idx, self.heap);
             }
         }
@@ -1601,11 +2054,16 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
 
     /// Runs garbage collection with proper GC roots.
     ///
-    /// GC roots include values in namespaces, the operand stack, and exception stack.
+    /// GC roots include values in namespaces, the operand stack, the exception stack
+    /// and the raised exception.
     fn run_gc(&mut self) {
         // Collect roots from all reachable values
         let stack_roots = self.stack.iter().filter_map(Value::ref_id);
-        let exc_roots = self.exception_stack.iter().filter_map(Value::ref_id);
+        let exc_roots = self
+            .exception_stack
+            .iter()
+            .chain(&self.raised_exception)
+            .filter_map(Value::ref_id);
         let ns_roots = self.namespaces.iter_heap_ids();
 
         // Collect all roots into a vec to avoid lifetime issues
@@ -1625,7 +2083,7 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
         frame
             .code
             .location_for_offset(self.instruction_ip)
-            .map(crate::bytecode::code::LocationEntry::range)
+            .map(LocationEntry::range)
             .unwrap_or_default()
     }
 
@@ -1701,18 +2159,118 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
     }
 
     /// Deletes a local variable (sets it to Undefined).
-    fn delete_local(&mut self, cached_frame: &CachedFrame<'a>, slot: u16) {
+    ///
+    /// Returns the same errors as `load_local` if the variable is already unbound.
+    fn delete_local(&mut self, cached_frame: &CachedFrame<'a>, slot: u16) -> RunResult<()> {
         let namespace = self.namespaces.get_mut(cached_frame.namespace_idx);
         let ns_slot = NamespaceId::new(slot as usize);
         let old_value = std::mem::replace(namespace.get_mut(ns_slot), Value::Undefined);
+        if matches!(old_value, Value::Undefined) {
+            let name = cached_frame.code.local_name(slot);
+            return Err(if cached_frame.code.is_assigned_local(slot) {
+                self.unbound_local_error(slot, name)
+            } else {
+                self.name_error_for_local(slot, name)
+            });
+        }
         old_value.drop_with_heap(self.heap);
+        Ok(())
+    }
+
+    /// Returns the namespace holding the current frame's globals.
+    ///
+    /// This is the global namespace, except for frames of functions defined in an imported
+    /// Python module, which use the module's namespace.
+    fn globals_idx(&self) -> NamespaceId {
+        match self.current_frame().function_id {
+            Some(func_id) => self.interns.get_function(func_id).globals,
+            None => GLOBAL_NS_IDX,
+        }
+    }
+
+    /// Returns the name of the module the current frame's globals belong to: `__main__`
+    /// for the main script, otherwise the imported Python module's name.
+    fn module_name(&self) -> StringId {
+        let interns = self.interns;
+        let globals_idx = self.globals_idx();
+        (0..interns.python_module_count())
+            .find(|&index| NamespaceId::python_module(index) == globals_idx)
+            .map_or(StaticStrings::DunderMain.into(), |index| {
+                interns.get_python_module(index).name()
+            })
+    }
+
+    /// Returns the name of each slot of the current frame's globals, indexed by slot.
+    ///
+    /// Unnamed slots hold the default `StringId`.
+    fn global_names(&self) -> &'a [StringId] {
+        let interns = self.interns;
+        let globals_idx = self.globals_idx();
+        if globals_idx == GLOBAL_NS_IDX {
+            return interns.global_names();
+        }
+        match (0..interns.python_module_count()).find(|&index| NamespaceId::python_module(index) == globals_idx) {
+            Some(index) => interns.get_python_module(index).global_names(),
+            None => &[],
+        }
+    }
+
+    /// Returns the bound variables of the current frame's globals as `(name, value)` pairs
+    /// in slot order, for `globals()`.
+    pub(crate) fn global_variables(&mut self) -> Vec<(StringId, Value)> {
+        let names = self.global_names();
+        self.bound_variables(self.globals_idx(), names)
+    }
+
+    /// Returns the bound variables of the current frame as `(name, value)` pairs in slot
+    /// order, for `locals()`, `vars()` and `dir()`.
+    ///
+    /// At module level these are the globals. Variables captured from an enclosing
+    /// function are included, like in CPython.
+    pub(crate) fn local_variables(&mut self) -> Vec<(StringId, Value)> {
+        let frame = self.current_frame();
+        if frame.namespace_idx == self.globals_idx() {
+            return self.global_variables();
+        }
+        let interns = self.interns;
+        let names = match frame.function_id {
+            Some(func_id) => interns.get_function(func_id).slot_names.as_slice(),
+            None => &[],
+        };
+        self.bound_variables(frame.namespace_idx, names)
+    }
+
+    /// Clones the bound, named slots of a namespace, reading captured variables from their cells.
+    ///
+    /// `names` holds the name of each slot, slots with the default `StringId` are skipped.
+    /// When several slots share a name only the first is returned.
+    fn bound_variables(&mut self, namespace_idx: NamespaceId, names: &[StringId]) -> Vec<(StringId, Value)> {
+        let namespace = self.namespaces.get(namespace_idx);
+        let mut seen = AHashSet::new();
+        let mut variables = Vec::new();
+        for (value, &name) in namespace.values().iter().zip(names) {
+            if name == StringId::default() {
+                continue;
+            }
+            let value = match value {
+                Value::Ref(id) if matches!(self.heap.get(*id), HeapData::Cell(_)) => self.heap.get_cell_value(*id),
+                _ => value.clone_with_heap(self.heap),
+            };
+            if matches!(value, Value::Undefined) || !seen.insert(name) {
+                value.drop_with_heap(self.heap);
+                continue;
+            }
+            variables.push((name, value));
+        }
+        variables
     }
 
     /// Loads a global variable and pushes it onto the stack.
     ///
     /// Returns a NameError if the variable is undefined.
     fn load_global(&mut self, slot: u16) -> RunResult<()> {
-        let namespace = self.namespaces.get(GLOBAL_NS_IDX);
+        let globals_idx = self.globals_idx();
+        let namespace = self.namespaces.get(globals_idx);
         // Copy without incrementing refcount first (avoids borrow conflict)
         let value = namespace
             .get(NamespaceId::new(slot as usize))
@@ -1732,12 +2290,29 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
     /// Pops the top of stack and stores it in a global variable.
     fn store_global(&mut self, slot: u16) {
         let value = self.pop();
-        let namespace = self.namespaces.get_mut(GLOBAL_NS_IDX);
+        let globals_idx = self.globals_idx();
+        let namespace = self.namespaces.get_mut(globals_idx);
         let ns_slot = NamespaceId::new(slot as usize);
         let old_value = std::mem::replace(namespace.get_mut(ns_slot), value);
         old_value.drop_with_heap(self.heap);
     }
 
+    /// Deletes a global variable (sets it to Undefined).
+    ///
+    /// Returns a NameError if the variable is already undefined.
+    fn delete_global(&mut self, slot: u16) -> RunResult<()> {
+        let globals_idx = self.globals_idx();
+        let namespace = self.namespaces.get_mut(globals_idx);
+        let ns_slot = NamespaceId::new(slot as usize);
+        let old_value = std::mem::replace(namespace.get_mut(ns_slot), Value::Undefined);
+        if matches!(old_value, Value::Undefined) {
+            let name = self.current_frame().code.local_name(slot);
+            return Err(self.name_error(slot, name));
+        }
+        old_value.drop_with_heap(self.heap);
+        Ok(())
+    }
+
     /// Loads from a closure cell and pushes onto the stack.
     ///
     /// Returns a NameError if the cell value is undefined (free variable not bound).
@@ -1771,6 +2346,21 @@ impl<'a, 'p, T: ResourceTracker> VM<'a, 'p, T> {
         let cell_id = self.current_frame().cells[slot as usize];
         self.heap.set_cell_value(cell_id, value);
     }
+
+    /// Clears a closure cell (sets it to Undefined).
+    ///
+    /// Returns a NameError if the cell is already unbound.
+    fn delete_cell(&mut self, slot: u16) -> RunResult<()> {
+        let cell_id = self.current_frame().cells[slot as usize];
+        let value = self.heap.get_cell_value(cell_id);
+        if matches!(value, Value::Undefined) {
+            let name = self.current_frame().code.local_name(slot);
+            return Err(self.free_var_error(name));
+        }
+        value.drop_with_heap(self.heap);
+        self.heap.set_cell_value(cell_id, Value::Undefined);
+        Ok(())
+    }
 }
 
 // `heap` is not a public field on VM, so this implementation needs to go here rather than in `heap.rs`
@@ -19,7 +19,7 @@ use crate::{
     asyncio::{CallId, TaskId},
     exception_private::RunError,
     heap::{DropWithHeap, HeapId},
-    namespace::{GLOBAL_NS_IDX, NamespaceId, Namespaces},
+    namespace::{NamespaceId, Namespaces},
     parse::CodeRange,
     value::Value,
 };
@@ -548,8 +548,8 @@ impl Scheduler {
             for cell_id in frame.cells {
                 heap.dec_ref(cell_id);
             }
-            // Clean up the namespace (but not the global namespace)
-            if frame.namespace_idx != GLOBAL_NS_IDX {
+            // Clean up the namespace (but not the global or a module namespace)
+            if !namespaces.is_persistent(frame.namespace_idx) {
                 namespaces.drop_with_heap(frame.namespace_idx, heap);
             }
         }
@@ -0,0 +1,483 @@
+//! A description of the Python surface this build of Monty supports, see [`capabilities`].
+
+use std::{fmt::Write, str::FromStr};
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    builtins::{Builtins, BuiltinsFunctions},
+    exception_private::ExcType,
+    modules::BuiltinModule,
+    object::MontyObject,
+    run::MontyRun,
+    types::BUILTIN_TYPES,
+};
+
+/// Script name of the code run to find out what's supported.
+const PROBE_FILENAME: &str = "capabilities.py";
+
+/// Python syntax features with an example of each, checked by compiling the example.
+///
+/// Features are only listed if some Python code might reasonably use them, whether or not
+/// Monty supports them.
+const SYNTAX_FEATURES: &[(&str, &str)] = &[
+    (
+        "function definitions",
+        "def f(a, b=1, *args, c=2, **kwargs):\n    return a",
+    ),
+    ("lambdas", "f = lambda x, y=1: x + y"),
+    (
+        "closures",
+        "def f():\n    n = 0\n    def g():\n        nonlocal n\n        n += 1\n    return g",
+    ),
+    ("global statements", "def f():\n    global n\n    n = 1"),
+    ("decorators", "@d\ndef f():\n    pass"),
+    ("async functions", "async def f():\n    return await g()"),
+    ("async for", "async def f():\n    async for x in g():\n        pass"),
+    ("async with", "async def f():\n    async with g() as x:\n        pass"),
+    ("generators", "def f():\n    yield 1"),
+    ("yield from", "def f():\n    yield from g()"),
+    ("classes", "class A:\n    def f(self):\n        return 1"),
+    ("exception classes", "class MyError(ValueError):\n    pass"),
+    ("list comprehensions", "[x for x in y if x]"),
+    ("dict comprehensions", "{k: v for k, v in y}"),
+    ("set comprehensions", "{x for x in y}"),
+    ("generator expressions", "sum(x for x in y)"),
+    ("f-strings", "f'{x!r:>10} {y:.2f}'"),
+    ("walrus operator", "if (n := len(x)) > 1:\n    pass"),
+    ("conditional expressions", "a if b else c"),
+    ("chained comparisons", "a < b <= c"),
+    ("star unpacking", "a, *b = c"),
+    ("star arguments", "f(*a, **k)"),
+    ("slicing", "x[1:-1:2]"),
+    ("augmented assignment", "n = 1\nn += 1"),
+    ("annotated assignment", "n: int = 1"),
+    ("del statements", "n = 1\ndel n"),
+    ("assert statements", "assert x, 'message'"),
+    (
+        "try statements",
+        "try:\n    pass\nexcept ValueError as e:\n    pass\nelse:\n    pass\nfinally:\n    pass",
+    ),
+    ("exception groups", "try:\n    pass\nexcept* ValueError:\n    pass"),
+    ("raise from", "raise ValueError('x') from None"),
+    ("with statements", "with a as b, c:\n    pass"),
+    ("for else", "for x in y:\n    break\nelse:\n    pass"),
+    ("while else", "while x:\n    break\nelse:\n    pass"),
+    ("match statements", "match x:\n    case 1:\n        pass"),
+    ("imports", "import math"),
+    ("from imports", "from math import sqrt as root"),
+    ("multi-module imports", "import os, sys"),
+    ("relative imports", "from . import x"),
+    ("type aliases", "type X = int"),
+    ("type parameters", "def f[T](x: T) -> T:\n    return x"),
+];
+
+/// Public methods of the builtin types, as in CPython, checked by calling each one.
+///
+/// Each entry is the type name, an expression for a value of the type, and the methods.
+const TYPE_METHODS: &[(&str, &str, &[&str])] = &[
+    (
+        "str",
+        "''",
+        &[
+            "capitalize",
+            "casefold",
+            "center",
+            "count",
+            "encode",
+            "endswith",
+            "expandtabs",
+            "find",
+            "format",
+            "format_map",
+            "index",
+            "isalnum",
+            "isalpha",
+            "isascii",
+            "isdecimal",
+            "isdigit",
+            "isidentifier",
+            "islower",
+            "isnumeric",
+            "isprintable",
+            "isspace",
+            "istitle",
+            "isupper",
+            "join",
+            "ljust",
+            "lower",
+            "lstrip",
+            "maketrans",
+            "partition",
+            "removeprefix",
+            "removesuffix",
+            "replace",
+            "rfind",
+            "rindex",
+            "rjust",
+            "rpartition",
+            "rsplit",
+            "rstrip",
+            "split",
+            "splitlines",
+            "startswith",
+            "strip",
+            "swapcase",
+            "title",
+            "translate",
+            "upper",
+            "zfill",
+        ],
+    ),
+    (
+        "bytes",
+        "b''",
+        &[
+            "capitalize",
+            "center",
+            "count",
+            "decode",
+            "endswith",
+            "expandtabs",
+            "find",
+            "fromhex",
+            "hex",
+            "index",
+            "isalnum",
+            "isalpha",
+            "isascii",
+            "isdigit",
+            "islower",
+            "isspace",
+            "istitle",
+            "isupper",
+            "join",
+            "ljust",
+            "lower",
+            "lstrip",
+            "maketrans",
+            "partition",
+            "removeprefix",
+            "removesuffix",
+            "replace",
+            "rfind",
+            "rindex",
+            "rjust",
+            "rpartition",
+            "rsplit",
+            "rstrip",
+            "split",
+            "splitlines",
+            "startswith",
+            "strip",
+            "swapcase",
+            "title",
+            "translate",
+            "upper",
+            "zfill",
+        ],
+    ),
+    (
+        "bytearray",
+        "bytearray()",
+        &[
+            "append",
+            "capitalize",
+            "center",
+            "clear",
+            "copy",
+            "count",
+            "decode",
+            "endswith",
+            "expandtabs",
+            "extend",
+            "find",
+            "fromhex",
+            "hex",
+            "index",
+            "insert",
+            "isalnum",
+            "isalpha",
+            "isascii",
+            "isdigit",
+            "islower",
+            "isspace",
+            "istitle",
+            "isupper",
+            "join",
+            "ljust",
+            "lower",
+            "lstrip",
+            "maketrans",
+            "partition",
+            "pop",
+            "remove",
+            "removeprefix",
+            "removesuffix",
+            "replace",
+            "reverse",
+            "rfind",
+            "rindex",
+            "rjust",
+            "rpartition",
+            "rsplit",
+            "rstrip",
+            "split",
+            "splitlines",
+            "startswith",
+            "strip",
+            "swapcase",
+            "title",
+            "translate",
+            "upper",
+            "zfill",
+        ],
+    ),
+    (
+        "list",
+        "[]",
+        &[
+            "append", "clear", "copy", "count", "extend", "index", "insert", "pop", "remove", "reverse", "sort",
+        ],
+    ),
+    ("tuple", "()", &["count", "index"]),
+    (
+        "dict",
+        "{}",
+        &[
+            "clear",
+            "copy",
+            "fromkeys",
+            "get",
+            "items",
+            "keys",
+            "pop",
+            "popitem",
+            "setdefault",
+            "update",
+            "values",
+        ],
+    ),
+    (
+        "set",
+        "set()",
+        &[
+            "add",
+            "clear",
+            "copy",
+            "difference",
+            "difference_update",
+            "discard",
+            "intersection",
+            "intersection_update",
+            "isdisjoint",
+            "issubset",
+            "issuperset",
+            "pop",
+            "remove",
+            "symmetric_difference",
+            "symmetric_difference_update",
+            "union",
+            "update",
+        ],
+    ),
+    (
+        "frozenset",
+        "frozenset()",
+        &[
+            "copy",
+            "difference",
+            "intersection",
+            "isdisjoint",
+            "issubset",
+            "issuperset",
+            "symmetric_difference",
+            "union",
+        ],
+    ),
+    (
+        "int",
+        "0",
+        &[
+            "as_integer_ratio",
+            "bit_count",
+            "bit_length",
+            "conjugate",
+            "from_bytes",
+            "is_integer",
+            "to_bytes",
+        ],
+    ),
+    (
+        "float",
+        "0.0",
+        &["as_integer_ratio", "conjugate", "fromhex", "hex", "is_integer"],
+    ),
+];
+
+/// Methods in `TYPE_METHODS` that are called on the type rather than on a value.
+const CLASS_METHODS: &[&str] = &["fromkeys", "fromhex", "from_bytes", "maketrans"];
+
+/// What this build of Monty supports, see [`capabilities`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Capabilities {
+    /// Version of the `monty` crate.
+    pub version: String,
+    /// Syntax features, supported or not.
+    pub syntax: Vec<SyntaxFeature>,
+    /// Names of the builtin functions, e.g. `"len"`.
+    pub builtin_functions: Vec<String>,
+    /// The builtin types, e.g. `int`, and their methods.
+    pub builtin_types: Vec<TypeCapabilities>,
+    /// Names of the builtin exception types, e.g. `"ValueError"`.
+    pub exceptions: Vec<String>,
+    /// The modules that can be imported and what they contain.
+    pub modules: Vec<ModuleCapabilities>,
+}
+
+impl Capabilities {
+    /// Serializes the capabilities to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("capabilities only contain strings and booleans")
+    }
+}
+
+/// A Python syntax feature, see [`Capabilities::syntax`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyntaxFeature {
+    /// Name of the feature, e.g. `"match statements"`.
+    pub name: String,
+    /// Code using the feature.
+    pub example: String,
+    /// Whether Monty compiles `example`.
+    pub supported: bool,
+}
+
+/// A builtin type, see [`Capabilities::builtin_types`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TypeCapabilities {
+    /// Name of the type, e.g. `"list"`.
+    pub name: String,
+    /// Public methods supported, e.g. `"append"`.
+    ///
+    /// Only listed for the string, bytes, container and number types.
+    pub methods: Vec<String>,
+}
+
+/// A module that can be imported, see [`Capabilities::modules`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModuleCapabilities {
+    /// Name of the module, e.g. `"math"`.
+    pub name: String,
+    /// Names of the module's attributes, sorted, e.g. `"sqrt"`.
+    pub attributes: Vec<String>,
+}
+
+/// Describes the Python surface this build of Monty supports.
+///
+/// Everything is found out from the interpreter itself: syntax features are checked by
+/// compiling an example of each, methods by calling them, and module contents with `dir()`.
+/// This makes the result useful for generating documentation or prompts describing what
+/// code Monty can run, but means it takes a while to build, so callers should keep it.
+///
+/// # Example
+/// ```
+/// let capabilities = monty::capabilities();
+/// assert!(capabilities.builtin_functions.iter().any(|name| name == "len"));
+/// let math = capabilities.modules.iter().find(|module| module.name == "math").unwrap();
+/// assert!(math.attributes.iter().any(|name| name == "sqrt"));
+/// ```
+#[must_use]
+pub fn capabilities() -> Capabilities {
+    let syntax = SYNTAX_FEATURES
+        .iter()
+        .map(|&(name, example)| SyntaxFeature {
+            name: name.to_owned(),
+            example: example.to_owned(),
+            supported: MontyRun::new(example.to_owned(), PROBE_FILENAME, vec![], vec![]).is_ok(),
+        })
+        .collect();
+
+    let builtin_functions = (0..=u8::MAX)
+        .map_while(BuiltinsFunctions::from_repr)
+        .map(|function| function.to_string())
+        .collect();
+
+    let builtin_types = BUILTIN_TYPES
+        .iter()
+        .map(|&(name, _)| {
+            let methods = TYPE_METHODS
+                .iter()
+                .find(|(type_name, _, _)| *type_name == name)
+                .map(|&(_, value, methods)| supported_methods(name, value, methods))
+                .unwrap_or_default();
+            TypeCapabilities {
+                name: name.to_owned(),
+                methods,
+            }
+        })
+        .collect();
+
+    // some exception types are only accessible through their modules, e.g. `json.JSONDecodeError`
+    let exceptions = ExcType::iter()
+        .map(|exc_type| exc_type.to_string())
+        .filter(|name| matches!(Builtins::from_str(name), Ok(Builtins::ExcType(_))))
+        .collect();
+
+    let modules = (0..=u8::MAX)
+        .map_while(BuiltinModule::from_repr)
+        .map(|module| {
+            let name: &str = module.into();
+            ModuleCapabilities {
+                name: name.to_owned(),
+                attributes: run_probe(format!("import {name}\ndir({name})")),
+            }
+        })
+        .collect();
+
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        syntax,
+        builtin_functions,
+        builtin_types,
+        exceptions,
+        modules,
+    }
+}
+
+/// Returns the methods of a type that exist, calling each without arguments on `value` and
+/// treating only an `AttributeError` as the method not existing.
+fn supported_methods(type_name: &str, value: &str, methods: &[&str]) -> Vec<String> {
+    let mut code = String::from("found = []\n");
+    for method in methods {
+        let receiver = if CLASS_METHODS.contains(method) {
+            type_name
+        } else {
+            value
+        };
+        write!(
+            code,
+            "exists = True\ntry:\n    ({receiver}).{method}()\nexcept AttributeError:\n    exists = False\n\
+             except BaseException:\n    pass\nif exists:\n    found.append('{method}')\n"
+        )
+        .expect("writing to a String can't fail");
+    }
+    code.push_str("found");
+    run_probe(code)
+}
+
+/// Runs code whose result is a list of strings and returns them.
+fn run_probe(code: String) -> Vec<String> {
+    let result = MontyRun::new(code, PROBE_FILENAME, vec![], vec![]).and_then(|runner| runner.run_no_limits(vec![]));
+    match result {
+        Ok(MontyObject::List(items)) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                MontyObject::String(name) => Some(name),
+                _ => None,
+            })
+            .collect(),
+        other => panic!("capability probe didn't return a list: {other:?}"),
+    }
+}
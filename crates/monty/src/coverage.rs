@@ -0,0 +1,99 @@
+//! Line coverage, collected with a trace hook.
+//!
+//! A [`Coverage`] counts how many times each line of each source file started a statement.
+//! It is a cheap handle to shared counts: install a clone as the trace hook of one or more
+//! runs, then read the counts from the original once they're done, even if the code raised.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    MontyException,
+    trace::{TraceEvent, TraceHook},
+};
+
+/// Statement hit counts per source file and line, shared between clones.
+///
+/// Lines are counted each time a statement starting on them runs, so a loop body's lines
+/// count every iteration. Lines without a statement the VM runs, e.g. comments, `else:` or
+/// `pass`, never appear. Each file is counted separately, so statements in an imported
+/// module or in code run by `exec()` (file `"<string>"`) don't add to the script's lines.
+///
+/// # Example
+/// ```
+/// use monty::{Coverage, MontyRun, NoLimitTracker, PrintWriter};
+///
+/// let code = "total = 0\nfor i in range(3):\n    total += i\nif total < 0:\n    total = 0\ntotal";
+/// let runner = MontyRun::new(code.to_owned(), "cov.py", vec![], vec![]).unwrap();
+/// let coverage = Coverage::new();
+/// runner
+///     .run_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, coverage.clone())
+///     .unwrap();
+/// assert_eq!(coverage.filenames(), ["cov.py"]);
+/// assert_eq!(coverage.executed_lines("cov.py"), [1, 2, 3, 4, 6]);
+/// assert_eq!(coverage.line_hits("cov.py")[&3], 3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    hits: Arc<Mutex<HashMap<String, HashMap<u32, u64>>>>,
+}
+
+impl Coverage {
+    /// Creates an empty coverage record.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the files that ran at least one statement, in ascending order.
+    #[must_use]
+    pub fn filenames(&self) -> Vec<String> {
+        let mut filenames: Vec<String> = self.lock().keys().cloned().collect();
+        filenames.sort_unstable();
+        filenames
+    }
+
+    /// Returns how many times each executed line of `filename` started a statement.
+    #[must_use]
+    pub fn line_hits(&self, filename: &str) -> HashMap<u32, u64> {
+        self.lock().get(filename).cloned().unwrap_or_default()
+    }
+
+    /// Returns the lines of `filename` that started at least one statement, in ascending order.
+    #[must_use]
+    pub fn executed_lines(&self, filename: &str) -> Vec<u32> {
+        let mut lines: Vec<u32> = self
+            .lock()
+            .get(filename)
+            .map(|hits| hits.keys().copied().collect())
+            .unwrap_or_default();
+        lines.sort_unstable();
+        lines
+    }
+
+    /// Forgets all counts, so the record can be reused for another run.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Locks the shared counts.
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, HashMap<u32, u64>>> {
+        // counting can't panic while holding the lock, so the map is consistent either way
+        self.hits.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl TraceHook for Coverage {
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        let mut hits = self.lock();
+        // check first so the filename is only copied the first time it's seen
+        if !hits.contains_key(event.filename) {
+            hits.insert(event.filename.to_owned(), HashMap::new());
+        }
+        let file_hits = hits.get_mut(event.filename).expect("file hits inserted above");
+        *file_hits.entry(u32::from(event.line)).or_default() += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+//! Interactive step debugging built on trace hooks.
+//!
+//! A [`DebugSession`] installs a trace hook that decides, before each statement, whether
+//! execution should stop there: at a breakpoint line, at the next statement when stepping,
+//! or at the next statement of the same frame (or a caller) when stepping over calls. A
+//! stop pauses the VM with `PauseReason::Breakpoint`, so the session simply holds the
+//! `PausedSnapshot` while the host inspects variables, and resumes it on the next command.
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    ExcType, MontyException,
+    external_fn::ExternalFunctions,
+    io::PrintWriter,
+    object::MontyObject,
+    resource::{PauseReason, ResourceTracker},
+    run::{MontyRun, PausedSnapshot, RunProgress},
+    trace::{StatementKind, TraceEvent, TraceHook},
+};
+
+/// Width `DebugSession::pretty()` wraps containers at, the same as `pprint`'s default.
+const PRETTY_WIDTH: usize = 80;
+
+/// Statement a [`DebugSession`] is paused at, which hasn't run yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugLocation {
+    /// Line of the statement (1-based).
+    pub line: u16,
+    /// Kind of the statement.
+    pub kind: StatementKind,
+    /// Name of the function running the statement, `"<module>"` for module-level code.
+    pub function_name: String,
+    /// Number of active frames, 1 for module-level code.
+    pub depth: usize,
+}
+
+/// State of a [`DebugSession`] after a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugStatus {
+    /// Execution stopped before a statement, see [`DebugSession::locals`] to inspect it.
+    Paused(DebugLocation),
+    /// The code ran to completion with this result.
+    Complete(MontyObject),
+}
+
+/// How far execution runs before the hook pauses it again.
+#[derive(Debug, Clone, Copy)]
+enum StepMode {
+    /// Stop at the next statement, entering calls.
+    Step,
+    /// Stop at the next statement running at this depth or shallower, stepping over calls.
+    Next { depth: usize },
+    /// Only stop at breakpoints.
+    Continue,
+}
+
+/// State shared between a [`DebugSession`] and the trace hook it installs.
+#[derive(Debug)]
+struct Control {
+    breakpoints: BTreeSet<u16>,
+    mode: StepMode,
+    /// The statement to pause at, set by `on_statement` and taken by `pause_requested`.
+    ///
+    /// Kept up to date until the VM polls the request, so a pause requested where execution
+    /// can't be suspended reports the statement it actually stops at.
+    pending: Option<DebugLocation>,
+    /// Where execution stopped, taken by the session once the VM has paused.
+    stopped_at: Option<DebugLocation>,
+}
+
+/// Trace hook pausing execution where the session's [`Control`] says so.
+struct DebugHook(Arc<Mutex<Control>>);
+
+impl TraceHook for DebugHook {
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        let mut control = lock(&self.0);
+        let stop = control.pending.is_some()
+            || control.breakpoints.contains(&event.line)
+            || match control.mode {
+                StepMode::Step => true,
+                StepMode::Next { depth } => event.depth <= depth,
+                StepMode::Continue => false,
+            };
+        if stop {
+            control.pending = Some(DebugLocation {
+                line: event.line,
+                kind: event.kind,
+                function_name: event.function_name.to_owned(),
+                depth: event.depth,
+            });
+        }
+        Ok(())
+    }
+
+    fn pause_requested(&mut self) -> bool {
+        let mut control = lock(&self.0);
+        control.stopped_at = control.pending.take();
+        control.stopped_at.is_some()
+    }
+}
+
+/// Locks the shared state, which the session and its hook only hold briefly.
+fn lock(control: &Mutex<Control>) -> MutexGuard<'_, Control> {
+    // the hook never panics while holding the lock, so the state is consistent either way
+    control.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An execution driven one statement, call or breakpoint at a time.
+///
+/// `start()` stops before the first statement, then `step()`, `next()` and `cont()` run
+/// to the next stop, like pdb's commands of the same names (`continue` being a Rust keyword).
+/// While paused, `locals()`, `globals()` and `pretty()` inspect variables without changing
+/// the execution.
+///
+/// External function calls are answered from an [`ExternalFunctions`] registry, like
+/// `MontyRun::run_with_functions`, OS calls raise `NotImplementedError`, and checkpoints
+/// are resumed transparently. `pause_on_timeout` isn't supported, since giving the code a
+/// fresh time budget is a decision for the host.
+///
+/// # Example
+/// ```
+/// use monty::{DebugSession, DebugStatus, ExternalFunctions, MontyObject, MontyRun, NoLimitTracker, PrintWriter};
+///
+/// let code = "x = 1\ny = x + 1\ny * 10";
+/// let runner = MontyRun::new(code.to_owned(), "debug.py", vec![], vec![]).unwrap();
+/// let print = &mut PrintWriter::Disabled;
+/// let mut session =
+///     DebugSession::start(runner, vec![], ExternalFunctions::new(), NoLimitTracker, print).unwrap();
+/// session.set_breakpoint(3);
+/// let DebugStatus::Paused(location) = session.cont(print).unwrap() else {
+///     panic!("expected to stop at the breakpoint");
+/// };
+/// assert_eq!(location.line, 3);
+/// assert_eq!(session.pretty("y").as_deref(), Some("2"));
+/// assert_eq!(session.cont(print).unwrap(), &DebugStatus::Complete(MontyObject::Int(20)));
+/// ```
+#[derive(Debug)]
+pub struct DebugSession<T: ResourceTracker> {
+    /// The paused execution, `None` once it has completed or raised.
+    state: Option<PausedSnapshot<T>>,
+    status: DebugStatus,
+    control: Arc<Mutex<Control>>,
+    functions: ExternalFunctions,
+}
+
+impl<T: ResourceTracker> DebugSession<T> {
+    /// Starts running `runner` and stops before its first statement.
+    ///
+    /// # Errors
+    /// Returns `MontyException` if the inputs are invalid, or if the code raises before
+    /// reaching a statement.
+    pub fn start(
+        runner: MontyRun,
+        inputs: Vec<MontyObject>,
+        functions: ExternalFunctions,
+        resource_tracker: T,
+        print: &mut PrintWriter<'_>,
+    ) -> Result<Self, MontyException> {
+        let control = Arc::new(Mutex::new(Control {
+            breakpoints: BTreeSet::new(),
+            mode: StepMode::Step,
+            pending: None,
+            stopped_at: None,
+        }));
+        let hook = DebugHook(Arc::clone(&control));
+        let progress = runner.start_with_trace(inputs, resource_tracker, print, hook)?;
+        let mut session = Self {
+            state: None,
+            status: DebugStatus::Complete(MontyObject::None),
+            control,
+            functions,
+        };
+        session.drive(progress, print)?;
+        Ok(session)
+    }
+
+    /// Returns where execution is paused, or its result once complete.
+    #[must_use]
+    pub fn status(&self) -> &DebugStatus {
+        &self.status
+    }
+
+    /// Stops execution before any statement starting on `line`.
+    ///
+    /// Returns false if there already was a breakpoint on that line.
+    pub fn set_breakpoint(&mut self, line: u16) -> bool {
+        lock(&self.control).breakpoints.insert(line)
+    }
+
+    /// Removes the breakpoint on `line`, returning false if there was none.
+    pub fn clear_breakpoint(&mut self, line: u16) -> bool {
+        lock(&self.control).breakpoints.remove(&line)
+    }
+
+    /// Returns the lines with a breakpoint, in ascending order.
+    #[must_use]
+    pub fn breakpoints(&self) -> Vec<u16> {
+        lock(&self.control).breakpoints.iter().copied().collect()
+    }
+
+    /// Runs the current statement and stops before the next one, entering function calls.
+    ///
+    /// # Errors
+    /// Returns `MontyException` if the code raises an uncaught exception, or if the session
+    /// ended with an exception before.
+    pub fn step(&mut self, print: &mut PrintWriter<'_>) -> Result<&DebugStatus, MontyException> {
+        self.resume(StepMode::Step, print)
+    }
+
+    /// Runs the current statement and stops before the next one in the same frame, running
+    /// function calls without stopping unless they hit a breakpoint.
+    ///
+    /// Stops in the caller if the current function returns.
+    ///
+    /// # Errors
+    /// Same as [`Self::step`].
+    pub fn next(&mut self, print: &mut PrintWriter<'_>) -> Result<&DebugStatus, MontyException> {
+        let depth = match &self.status {
+            DebugStatus::Paused(location) => location.depth,
+            DebugStatus::Complete(_) => 0,
+        };
+        self.resume(StepMode::Next { depth }, print)
+    }
+
+    /// Runs until the next breakpoint or until the code completes.
+    ///
+    /// # Errors
+    /// Same as [`Self::step`].
+    pub fn cont(&mut self, print: &mut PrintWriter<'_>) -> Result<&DebugStatus, MontyException> {
+        self.resume(StepMode::Continue, print)
+    }
+
+    /// Returns the bound variables of the paused frame, see `PausedSnapshot::locals()`.
+    ///
+    /// Empty once execution has ended.
+    #[must_use]
+    pub fn locals(&self) -> Vec<(String, MontyObject)> {
+        self.state.as_ref().map(PausedSnapshot::locals).unwrap_or_default()
+    }
+
+    /// Returns the bound module-level variables, see `PausedSnapshot::globals()`.
+    ///
+    /// Empty once execution has ended.
+    #[must_use]
+    pub fn globals(&self) -> Vec<(String, MontyObject)> {
+        self.state.as_ref().map(PausedSnapshot::globals).unwrap_or_default()
+    }
+
+    /// Returns the value of the variable `name` as seen from the paused frame: a local,
+    /// or else a global.
+    #[must_use]
+    pub fn variable(&self, name: &str) -> Option<MontyObject> {
+        let find = |variables: Vec<(String, MontyObject)>| {
+            variables
+                .into_iter()
+                .find_map(|(variable, value)| (variable == name).then_some(value))
+        };
+        find(self.locals()).or_else(|| find(self.globals()))
+    }
+
+    /// Returns the `repr()` of the variable `name`, with lists, tuples, sets and dicts
+    /// longer than 80 characters split over several lines like `pprint.pformat()`, though
+    /// dicts keep their insertion order.
+    #[must_use]
+    pub fn pretty(&self, name: &str) -> Option<String> {
+        self.variable(name).map(|value| pretty_format(&value, 0))
+    }
+
+    /// Resumes the paused execution until the hook stops it again in `mode`.
+    fn resume(&mut self, mode: StepMode, print: &mut PrintWriter<'_>) -> Result<&DebugStatus, MontyException> {
+        let Some(state) = self.state.take() else {
+            return match self.status {
+                DebugStatus::Complete(_) => Ok(&self.status),
+                DebugStatus::Paused(_) => Err(MontyException::runtime_error(
+                    "the debug session ended with an exception",
+                )),
+            };
+        };
+        lock(&self.control).mode = mode;
+        let progress = state.resume(print)?;
+        self.drive(progress, print)
+    }
+
+    /// Runs until the hook pauses execution or it completes, answering external calls.
+    fn drive(
+        &mut self,
+        mut progress: RunProgress<T>,
+        print: &mut PrintWriter<'_>,
+    ) -> Result<&DebugStatus, MontyException> {
+        loop {
+            progress = match progress {
+                RunProgress::Complete(value) => {
+                    self.status = DebugStatus::Complete(value);
+                    return Ok(&self.status);
+                }
+                RunProgress::Paused(state) => match state.reason() {
+                    PauseReason::Breakpoint => {
+                        let location = lock(&self.control).stopped_at.take();
+                        self.status = DebugStatus::Paused(location.expect("the hook records where it paused"));
+                        self.state = Some(state);
+                        return Ok(&self.status);
+                    }
+                    PauseReason::Checkpoint => state.resume(print)?,
+                    PauseReason::Timeout => {
+                        return Err(MontyException::runtime_error(
+                            "pause_on_timeout is not supported by DebugSession",
+                        ));
+                    }
+                },
+                RunProgress::FunctionCall {
+                    function_name,
+                    args,
+                    kwargs,
+                    state,
+                    ..
+                } => state.run(self.functions.call(&function_name, args, kwargs), print)?,
+                RunProgress::OsCall { function, state, .. } => {
+                    let exc = MontyException::new(
+                        ExcType::NotImplementedError,
+                        Some(format!("OS function '{function}' not implemented")),
+                    );
+                    state.run(exc, print)?
+                }
+                // every call is resolved before execution resumes, so no futures are ever pending
+                RunProgress::ResolveFutures(_) => {
+                    return Err(MontyException::runtime_error(
+                        "unexpected pending futures in DebugSession",
+                    ));
+                }
+            };
+        }
+    }
+}
+
+/// Formats `value` like `pprint.pformat()`, for a value printed `indent` columns in.
+///
+/// Containers whose `repr()` doesn't fit in [`PRETTY_WIDTH`] get one item per line,
+/// aligned after the opening bracket; anything else is its plain `repr()`.
+fn pretty_format(value: &MontyObject, indent: usize) -> String {
+    let repr = value.py_repr();
+    if indent + repr.chars().count() <= PRETTY_WIDTH {
+        return repr;
+    }
+    let item = |item: &MontyObject| pretty_format(item, indent + 1);
+    let (open, close, items): (_, _, Vec<_>) = match value {
+        MontyObject::List(items) => ("[", "]", items.iter().map(item).collect()),
+        // single-item tuples need their trailing comma, so they are left alone
+        MontyObject::Tuple(items) if items.len() > 1 => ("(", ")", items.iter().map(item).collect()),
+        MontyObject::Set(items) => ("{", "}", items.iter().map(item).collect()),
+        MontyObject::Dict(pairs) => {
+            let entries = pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = key.py_repr();
+                    let value = pretty_format(value, indent + 1 + key.chars().count() + 2);
+                    format!("{key}: {value}")
+                })
+                .collect();
+            ("{", "}", entries)
+        }
+        _ => return repr,
+    };
+    let separator = format!(",\n{}", " ".repeat(indent + 1));
+    format!("{open}{}{close}", items.join(&separator))
+}
@@ -1,21 +1,22 @@
 use std::{
     borrow::Cow,
     fmt::{self, Display, Write},
+    hash::{Hash, Hasher},
 };
 
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
-use strum::{Display, EnumString, IntoStaticStr};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
 
 use crate::{
     args::ArgValues,
     defer_drop,
     exception_public::{MontyException, StackFrame},
-    fstring::FormatError,
-    heap::{Heap, HeapData},
-    intern::{Interns, StaticStrings, StringId},
+    fstring::{FormatError, ascii_escape},
+    heap::{Heap, HeapData, HeapId},
+    intern::{ExcClassId, Interns, StaticStrings, StringId},
     parse::CodeRange,
-    resource::{DepthGuard, ResourceTracker},
+    resource::{DepthGuard, LimitExceeded, ResourceError, ResourceTracker},
     types::{
         AttrCallResult, PyTrait, Str, Type, allocate_tuple,
         str::{StringRepr, string_repr_fmt},
@@ -30,7 +31,9 @@ pub type RunResult<T> = Result<T, RunError>;
 ///
 /// Uses strum derives for automatic `Display`, `FromStr`, and `Into<&'static str>` implementations.
 /// The string representation matches the variant name exactly (e.g., `ValueError` -> "ValueError").
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, IntoStaticStr, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter, EnumString, IntoStaticStr, Serialize, Deserialize,
+)]
 pub enum ExcType {
     /// primary exception class - matches any exception in isinstance checks.
     Exception,
@@ -76,8 +79,12 @@ pub enum ExcType {
 
     // --- ValueError hierarchy ---
     ValueError,
-    /// Subclass of ValueError - for encoding/decoding errors.
+    /// Subclass of ValueError - for decoding errors.
     UnicodeDecodeError,
+    /// Subclass of ValueError - for encoding errors.
+    UnicodeEncodeError,
+    /// Subclass of ValueError (from json module) - for malformed JSON documents.
+    JSONDecodeError,
 
     // --- ImportError hierarchy ---
     /// Import-related errors (module not found, name not in module).
@@ -100,6 +107,8 @@ pub enum ExcType {
     // --- Standalone exception types ---
     AssertionError,
     MemoryError,
+    /// Raised by the `re` module (as `re.error`) for invalid regular expressions.
+    PatternError,
     StopIteration,
     SyntaxError,
     TimeoutError,
@@ -136,8 +145,11 @@ impl ExcType {
             Self::AttributeError => matches!(self, Self::FrozenInstanceError),
             // NameError catches UnboundLocalError
             Self::NameError => matches!(self, Self::UnboundLocalError),
-            // ValueError catches UnicodeDecodeError
-            Self::ValueError => matches!(self, Self::UnicodeDecodeError),
+            // ValueError catches UnicodeDecodeError, UnicodeEncodeError and JSONDecodeError
+            Self::ValueError => matches!(
+                self,
+                Self::UnicodeDecodeError | Self::UnicodeEncodeError | Self::JSONDecodeError
+            ),
             // ImportError catches ModuleNotFoundError
             Self::ImportError => matches!(self, Self::ModuleNotFoundError),
             // OSError catches FileNotFoundError, FileExistsError, IsADirectoryError, NotADirectoryError
@@ -163,31 +175,8 @@ impl ExcType {
         args: ArgValues,
         interns: &Interns,
     ) -> RunResult<Value> {
-        defer_drop!(args, heap);
-        let exc = match args {
-            ArgValues::Empty => Ok(SimpleException::new_none(self)),
-            ArgValues::One(value) => match value {
-                Value::InternString(string_id) => {
-                    Ok(SimpleException::new_msg(self, interns.get_str(*string_id).to_owned()))
-                }
-                Value::Ref(heap_id) => {
-                    if let HeapData::Str(s) = heap.get(*heap_id) {
-                        Ok(SimpleException::new_msg(self, s.as_str().to_owned()))
-                    } else {
-                        Err(RunError::internal(
-                            "exceptions can only be called with zero or one string argument",
-                        ))
-                    }
-                }
-                _ => Err(RunError::internal(
-                    "exceptions can only be called with zero or one string argument",
-                )),
-            },
-            _ => Err(RunError::internal(
-                "exceptions can only be called with zero or one string argument",
-            )),
-        }?;
-        let heap_id = heap.allocate(HeapData::Exception(exc))?;
+        let arg = exception_call_arg(heap, args, interns)?;
+        let heap_id = heap.allocate(HeapData::Exception(SimpleException::new(self, arg)))?;
         Ok(Value::Ref(heap_id))
     }
 
@@ -204,6 +193,8 @@ impl ExcType {
             exc,
             frame: None,
             hide_caret: true, // CPython doesn't show carets for attribute GET errors
+            limit: None,
+            raised_id: None,
         })
     }
 
@@ -233,6 +224,8 @@ impl ExcType {
             exc,
             frame: None,
             hide_caret: true, // CPython doesn't show carets for attribute GET errors
+            limit: None,
+            raised_id: None,
         })
     }
 
@@ -274,6 +267,30 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a TypeError for `del obj[key]` on a container that doesn't support item deletion.
+    ///
+    /// Matches CPython's format: `TypeError: '{type}' object doesn't support item deletion`
+    #[must_use]
+    pub(crate) fn type_error_item_deletion(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("'{type_}' object doesn't support item deletion"),
+        )
+        .into()
+    }
+
+    /// Creates a TypeError for `del obj[key]` on a non-container type.
+    ///
+    /// Matches CPython's format: `TypeError: '{type}' object does not support item deletion`
+    #[must_use]
+    pub(crate) fn type_error_not_sub_deletion(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("'{type_}' object does not support item deletion"),
+        )
+        .into()
+    }
+
     /// Creates a TypeError for unhashable types when calling `hash()`.
     ///
     /// This matches Python 3.14's error message: `TypeError: unhashable type: 'list'`
@@ -570,6 +587,38 @@ impl ExcType {
         SimpleException::new_msg(Self::TypeError, format!("cannot convert '{type_}' object to bytes")).into()
     }
 
+    /// Creates a TypeError for bytearray() constructor with invalid type.
+    ///
+    /// Matches CPython's format: `TypeError: cannot convert '{type}' object to bytearray`
+    #[must_use]
+    pub(crate) fn type_error_bytearray_init(type_: Type) -> RunError {
+        SimpleException::new_msg(Self::TypeError, format!("cannot convert '{type_}' object to bytearray")).into()
+    }
+
+    /// Creates a TypeError for bytearray() called with a str and no encoding.
+    ///
+    /// Matches CPython's format: `TypeError: string argument without an encoding`
+    #[must_use]
+    pub(crate) fn type_error_string_without_encoding() -> RunError {
+        SimpleException::new_msg(Self::TypeError, "string argument without an encoding").into()
+    }
+
+    /// Creates a TypeError for bytearray.extend() with a non-iterable argument.
+    ///
+    /// Matches CPython's format: `TypeError: can't extend bytearray with {type}`
+    #[must_use]
+    pub(crate) fn type_error_bytearray_extend(type_: Type) -> RunError {
+        SimpleException::new_msg(Self::TypeError, format!("can't extend bytearray with {type_}")).into()
+    }
+
+    /// Creates a TypeError for bytearray.extend() with a str argument.
+    ///
+    /// Matches CPython's format: `TypeError: expected iterable of integers; got: 'str'`
+    #[must_use]
+    pub(crate) fn type_error_bytearray_extend_str() -> RunError {
+        SimpleException::new_msg(Self::TypeError, "expected iterable of integers; got: 'str'").into()
+    }
+
     /// Creates a TypeError for calling a non-callable type.
     ///
     /// Matches CPython's format: `TypeError: cannot create '{type}' instances`
@@ -594,6 +643,26 @@ impl ExcType {
         SimpleException::new_msg(Self::TypeError, format!("'{type_}' object is not iterable")).into()
     }
 
+    /// Creates a TypeError for an operand that must be bytes or a bytearray.
+    ///
+    /// Matches CPython's format: `TypeError: a bytes-like object is required, not '{type}'`
+    #[must_use]
+    pub(crate) fn type_error_bytes_like_required(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("a bytes-like object is required, not '{type_}'"),
+        )
+        .into()
+    }
+
+    /// Creates a TypeError for reversed() with a type that is not a sequence.
+    ///
+    /// Matches CPython's format: `TypeError: '{type}' object is not reversible`
+    #[must_use]
+    pub(crate) fn type_error_not_reversible(type_: Type) -> RunError {
+        SimpleException::new_msg(Self::TypeError, format!("'{type_}' object is not reversible")).into()
+    }
+
     /// Creates a TypeError for int() constructor with invalid type.
     ///
     /// Matches CPython's format: `TypeError: int() argument must be a string, a bytes-like object or a real number, not '{type}'`
@@ -606,6 +675,52 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a ValueError for an int() base outside the supported range.
+    ///
+    /// Matches CPython's format: `ValueError: int() base must be >= 2 and <= 36, or 0`
+    #[must_use]
+    pub(crate) fn value_error_int_base() -> RunError {
+        SimpleException::new_msg(Self::ValueError, "int() base must be >= 2 and <= 36, or 0").into()
+    }
+
+    /// Creates a ValueError for int() with a string that is not a valid literal in `base`.
+    ///
+    /// Matches CPython's format: `ValueError: invalid literal for int() with base {base}: {repr}`
+    #[must_use]
+    pub(crate) fn value_error_invalid_int_literal(base: u32, repr: &str) -> RunError {
+        SimpleException::new_msg(
+            Self::ValueError,
+            format!("invalid literal for int() with base {base}: {repr}"),
+        )
+        .into()
+    }
+
+    /// Creates a ValueError for int() of a NaN float.
+    ///
+    /// Matches CPython's format: `ValueError: cannot convert float NaN to integer`
+    #[must_use]
+    pub(crate) fn value_error_float_nan_to_int() -> RunError {
+        SimpleException::new_msg(Self::ValueError, "cannot convert float NaN to integer").into()
+    }
+
+    /// Creates an OverflowError for int() of an infinite float.
+    ///
+    /// Matches CPython's format: `OverflowError: cannot convert float infinity to integer`
+    #[must_use]
+    pub(crate) fn overflow_error_float_inf_to_int() -> RunError {
+        SimpleException::new_msg(Self::OverflowError, "cannot convert float infinity to integer").into()
+    }
+
+    /// Creates an OverflowError for an `itertools.count()` that ran past the `int` range
+    /// Monty supports for counters.
+    ///
+    /// Monty-specific, CPython switches to arbitrary precision integers:
+    /// `OverflowError: count() value out of range`
+    #[must_use]
+    pub(crate) fn overflow_error_count() -> RunError {
+        SimpleException::new_msg(Self::OverflowError, "count() value out of range").into()
+    }
+
     /// Creates a TypeError for float() constructor with invalid type.
     ///
     /// Matches CPython's format: `TypeError: float() argument must be a string or a real number, not '{type}'`
@@ -638,6 +753,26 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a TypeError for issubclass() arg 1.
+    ///
+    /// Matches CPython's format: `TypeError: issubclass() arg 1 must be a class`
+    #[must_use]
+    pub(crate) fn issubclass_arg1_error() -> RunError {
+        SimpleException::new_msg(Self::TypeError, "issubclass() arg 1 must be a class").into()
+    }
+
+    /// Creates a TypeError for an invalid class argument to `issubclass()`.
+    ///
+    /// Also raised by `contextlib.suppress` when given something other than exception classes.
+    #[must_use]
+    pub(crate) fn issubclass_arg2_error() -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            "issubclass() arg 2 must be a class, a tuple of classes, or a union",
+        )
+        .into()
+    }
+
     /// Creates a TypeError for invalid exception type in except clause.
     ///
     /// Matches CPython's format: `TypeError: catching classes that do not inherit from BaseException is not allowed`
@@ -694,6 +829,28 @@ impl ExcType {
         SimpleException::new_msg(Self::RuntimeError, "Set changed size during iteration").into()
     }
 
+    /// Creates a RuntimeError for deque mutation during iteration.
+    ///
+    /// Matches CPython's format: `RuntimeError: deque mutated during iteration`
+    #[must_use]
+    pub(crate) fn runtime_error_deque_mutated() -> RunError {
+        SimpleException::new_msg(Self::RuntimeError, "deque mutated during iteration").into()
+    }
+
+    /// Creates a RuntimeError for a host-provided native module constant that cannot be
+    /// converted to a Python value.
+    ///
+    /// Monty-specific, there is no CPython equivalent:
+    /// `RuntimeError: invalid constant '{module}.{name}': {error}`
+    #[must_use]
+    pub(crate) fn runtime_error_native_constant(module: &str, name: &str, error: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(
+            Self::RuntimeError,
+            format!("invalid constant '{module}.{name}': {error}"),
+        )
+        .into()
+    }
+
     /// Creates a TypeError for functions that don't accept keyword arguments.
     ///
     /// Matches CPython's format: `TypeError: {name}() takes no keyword arguments`
@@ -742,6 +899,14 @@ impl ExcType {
         SimpleException::new_msg(Self::IndexError, "index out of range").into()
     }
 
+    /// Creates an IndexError for bytearray index out of range.
+    ///
+    /// Matches CPython's format: `IndexError('bytearray index out of range')`
+    #[must_use]
+    pub(crate) fn bytearray_index_error() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "bytearray index out of range").into()
+    }
+
     /// Creates an IndexError for range index out of range.
     ///
     /// Matches CPython's format: `IndexError('range object index out of range')`
@@ -774,6 +939,18 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a TypeError for non-integer bytearray indices.
+    ///
+    /// Matches CPython's format: `TypeError: bytearray indices must be integers or slices, not {type}`
+    #[must_use]
+    pub(crate) fn type_error_bytearray_indices(index_type: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("bytearray indices must be integers or slices, not {index_type}"),
+        )
+        .into()
+    }
+
     /// Creates a NameError for accessing a free variable (nonlocal/closure) before it's assigned.
     ///
     /// Matches CPython's format: `NameError: cannot access free variable 'x' where it is not
@@ -793,7 +970,7 @@ impl ExcType {
     pub(crate) fn name_error(name: &str) -> SimpleException {
         let mut msg = format!("name '{name}' is not defined");
         // add the same suffix as cpython, but only for the modules supported by Monty
-        if matches!(name, "asyncio" | "sys" | "typing" | "types") {
+        if matches!(name, "asyncio" | "json" | "math" | "re" | "sys" | "typing" | "types") {
             write!(&mut msg, ". Did you forget to import '{name}'?").unwrap();
         }
         SimpleException::new_msg(Self::NameError, msg)
@@ -821,6 +998,8 @@ impl ExcType {
             exc,
             frame: None,
             hide_caret: true, // CPython doesn't show carets for module not found errors
+            limit: None,
+            raised_id: None,
         })
     }
 
@@ -873,9 +1052,27 @@ impl ExcType {
             exc,
             frame: None,
             hide_caret: true,
+            limit: None,
+            raised_id: None,
         })
     }
 
+    /// Creates an ImportError for importing a Python module whose body is still running.
+    ///
+    /// Monty-specific: CPython would return the partially initialized module, but Monty only
+    /// builds the module object once its body has finished:
+    /// `ImportError: cannot import partially initialized module 'module' (most likely due to a circular import)`
+    #[must_use]
+    pub(crate) fn import_error_partially_initialized(module_name: &str) -> RunError {
+        SimpleException::new_msg(
+            Self::ImportError,
+            format!(
+                "cannot import partially initialized module '{module_name}' (most likely due to a circular import)"
+            ),
+        )
+        .into()
+    }
+
     /// Creates a ValueError for negative shift count in bitwise shift operations.
     ///
     /// Matches CPython's format: `ValueError: negative shift count`
@@ -996,6 +1193,50 @@ impl ExcType {
         SimpleException::new_msg(Self::ValueError, "empty separator").into()
     }
 
+    /// Creates a ValueError for a malformed `str.format()` template.
+    ///
+    /// Matches CPython's messages, e.g. `ValueError: Single '}' encountered in format string`
+    #[must_use]
+    pub(crate) fn value_error_format_string(msg: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(Self::ValueError, msg).into()
+    }
+
+    /// Creates a TypeError for printf-style `str % args` formatting.
+    ///
+    /// Matches CPython's messages, e.g. `TypeError: not enough arguments for format string`
+    #[must_use]
+    pub(crate) fn type_error_percent_format(msg: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(Self::TypeError, msg).into()
+    }
+
+    /// Creates a ValueError for a malformed printf-style `str % args` template.
+    ///
+    /// Matches CPython's messages, e.g. `ValueError: incomplete format`
+    #[must_use]
+    pub(crate) fn value_error_percent_format(msg: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(Self::ValueError, msg).into()
+    }
+
+    /// Creates an IndexError for a `str.format()` field referring to a missing positional argument.
+    ///
+    /// Matches CPython's format: `IndexError: Replacement index 2 out of range for positional args tuple`
+    #[must_use]
+    pub(crate) fn index_error_format_replacement(index: usize) -> RunError {
+        SimpleException::new_msg(
+            Self::IndexError,
+            format!("Replacement index {index} out of range for positional args tuple"),
+        )
+        .into()
+    }
+
+    /// Creates a KeyError for a `str.format()` field or `%(name)s` conversion with a missing key.
+    ///
+    /// Matches CPython's format: `KeyError: 'name'`
+    #[must_use]
+    pub(crate) fn key_error_format_field(name: &str) -> RunError {
+        SimpleException::new_msg(Self::KeyError, name).into()
+    }
+
     /// Creates a TypeError for fillchar argument that is not a single character.
     ///
     /// Matches CPython's format: `TypeError: The fill character must be exactly one character long`
@@ -1044,6 +1285,38 @@ impl ExcType {
         SimpleException::new_msg(Self::IndexError, "pop from empty list").into()
     }
 
+    /// Creates an IndexError for popping from an empty bytearray.
+    ///
+    /// Matches CPython's format: `IndexError: pop from empty bytearray`
+    #[must_use]
+    pub(crate) fn index_error_pop_empty_bytearray() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "pop from empty bytearray").into()
+    }
+
+    /// Creates an IndexError for popping from an empty deque.
+    ///
+    /// Matches CPython's format: `IndexError: pop from an empty deque`
+    #[must_use]
+    pub(crate) fn index_error_pop_empty_deque() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "pop from an empty deque").into()
+    }
+
+    /// Creates an IndexError for a deque index out of range.
+    ///
+    /// Matches CPython's format: `IndexError: deque index out of range`
+    #[must_use]
+    pub(crate) fn deque_index_error() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "deque index out of range").into()
+    }
+
+    /// Creates a ValueError for bytearray.remove() when the byte is not found.
+    ///
+    /// Matches CPython's format: `ValueError: value not found in bytearray`
+    #[must_use]
+    pub(crate) fn value_error_bytearray_remove() -> RunError {
+        SimpleException::new_msg(Self::ValueError, "value not found in bytearray").into()
+    }
+
     /// Creates an IndexError for list.pop(index) with invalid index.
     ///
     /// Matches CPython's format: `IndexError: pop index out of range`
@@ -1060,6 +1333,22 @@ impl ExcType {
         SimpleException::new_msg(Self::KeyError, "'popitem(): dictionary is empty'").into()
     }
 
+    /// Creates a ValueError for float() with a string that is not a valid literal.
+    ///
+    /// Matches CPython's format: `ValueError: could not convert string to float: {repr}`
+    #[must_use]
+    pub(crate) fn value_error_invalid_float_literal(repr: &str) -> RunError {
+        SimpleException::new_msg(Self::ValueError, format!("could not convert string to float: {repr}")).into()
+    }
+
+    /// Creates an OverflowError for an int too large to convert to float.
+    ///
+    /// Matches CPython's format: `OverflowError: int too large to convert to float`
+    #[must_use]
+    pub(crate) fn overflow_error_int_too_large_for_float() -> RunError {
+        SimpleException::new_msg(Self::OverflowError, "int too large to convert to float").into()
+    }
+
     /// Creates a LookupError for unknown encoding.
     ///
     /// Matches CPython's format: `LookupError: unknown encoding: {encoding}`
@@ -1080,6 +1369,149 @@ impl ExcType {
         .into()
     }
 
+    /// Creates a UnicodeEncodeError for the characters `start..=end` of `s`, which are
+    /// outside the range of the codec (`limit` is the first code point it can't encode).
+    ///
+    /// Matches CPython's format:
+    /// `UnicodeEncodeError: 'ascii' codec can't encode character '\xe9' in position 1: ordinal not in range(128)`
+    #[must_use]
+    pub(crate) fn unicode_encode_error(codec: &str, s: &str, start: usize, end: usize, limit: u32) -> RunError {
+        let msg = if start == end {
+            let c = s.chars().nth(start).map(String::from).unwrap_or_default();
+            format!(
+                "'{codec}' codec can't encode character '{}' in position {start}: ordinal not in range({limit})",
+                ascii_escape(&c)
+            )
+        } else {
+            format!("'{codec}' codec can't encode characters in position {start}-{end}: ordinal not in range({limit})")
+        };
+        SimpleException::new_msg(Self::UnicodeEncodeError, msg).into()
+    }
+
+    /// Creates a `json.JSONDecodeError` for malformed JSON input.
+    ///
+    /// `pos` is a byte offset into `doc`; like CPython the message reports the line, column
+    /// and character offset: `JSONDecodeError: {msg}: line {lineno} column {colno} (char {pos})`
+    #[must_use]
+    pub(crate) fn json_decode_error(msg: &str, doc: &str, pos: usize) -> RunError {
+        let before = &doc[..pos];
+        let lineno = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |nl| nl + 1);
+        let colno = before[line_start..].chars().count() + 1;
+        let char_pos = before.chars().count();
+        SimpleException::new_msg(
+            Self::JSONDecodeError,
+            format!("{msg}: line {lineno} column {colno} (char {char_pos})"),
+        )
+        .into()
+    }
+
+    /// Creates a `re.error` for an invalid regular expression or replacement template.
+    #[must_use]
+    pub(crate) fn re_error(msg: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(Self::PatternError, msg).into()
+    }
+
+    /// Creates a `re.error` for a replacement template problem at character offset `pos`.
+    ///
+    /// Matches CPython's format: `PatternError: {msg} at position {pos}`
+    #[must_use]
+    pub(crate) fn re_error_at(msg: impl fmt::Display, pos: usize) -> RunError {
+        SimpleException::new_msg(Self::PatternError, format!("{msg} at position {pos}")).into()
+    }
+
+    /// Creates an IndexError for a `re.Match` group reference that does not exist.
+    ///
+    /// Matches CPython's format: `IndexError: no such group`
+    #[must_use]
+    pub(crate) fn index_error_no_such_group() -> RunError {
+        SimpleException::new_msg(Self::IndexError, "no such group").into()
+    }
+
+    /// Creates a TypeError for a regular expression subject that is not a string.
+    ///
+    /// Matches CPython's formats: `TypeError: cannot use a string pattern on a bytes-like object`
+    /// and `TypeError: expected string or bytes-like object, got '{type}'`
+    #[must_use]
+    pub(crate) fn type_error_re_subject(type_: Type, bytes_like: bool) -> RunError {
+        if bytes_like {
+            Self::type_error("cannot use a string pattern on a bytes-like object")
+        } else {
+            Self::type_error(format!("expected string or bytes-like object, got '{type_}'"))
+        }
+    }
+
+    /// Creates a TypeError for `json.dumps()` of a value with no JSON representation.
+    ///
+    /// Matches CPython's format: `TypeError: Object of type {type} is not JSON serializable`
+    #[must_use]
+    pub(crate) fn type_error_not_json_serializable(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("Object of type {type_} is not JSON serializable"),
+        )
+        .into()
+    }
+
+    /// Creates an OverflowError for a `timedelta` whose day count is out of range.
+    ///
+    /// Matches CPython's format: `OverflowError: days={days}; must have magnitude <= 999999999`
+    #[must_use]
+    pub(crate) fn overflow_error_timedelta_days(days: impl fmt::Display) -> RunError {
+        SimpleException::new_msg(
+            Self::OverflowError,
+            format!("days={days}; must have magnitude <= 999999999"),
+        )
+        .into()
+    }
+
+    /// Creates a ZeroDivisionError for dividing a `timedelta` by a zero number or floor
+    /// dividing it by a zero `timedelta`.
+    ///
+    /// Matches CPython's format: `ZeroDivisionError: integer division or modulo by zero`
+    #[must_use]
+    pub(crate) fn zero_division_timedelta() -> RunError {
+        SimpleException::new_msg(Self::ZeroDivisionError, "integer division or modulo by zero").into()
+    }
+
+    /// Creates a ZeroDivisionError for `timedelta % timedelta(0)`.
+    ///
+    /// Matches CPython's format: `ZeroDivisionError: integer modulo by zero`
+    #[must_use]
+    pub(crate) fn zero_division_timedelta_modulo() -> RunError {
+        SimpleException::new_msg(Self::ZeroDivisionError, "integer modulo by zero").into()
+    }
+
+    /// Creates the error for converting a NaN or infinite float to an integer ratio.
+    ///
+    /// Matches CPython's `float.as_integer_ratio()` errors:
+    /// `ValueError: cannot convert NaN to integer ratio` and
+    /// `OverflowError: cannot convert Infinity to integer ratio`
+    #[must_use]
+    pub(crate) fn float_integer_ratio_error(is_nan: bool) -> RunError {
+        if is_nan {
+            SimpleException::new_msg(Self::ValueError, "cannot convert NaN to integer ratio").into()
+        } else {
+            SimpleException::new_msg(Self::OverflowError, "cannot convert Infinity to integer ratio").into()
+        }
+    }
+
+    /// Creates an OverflowError for date arithmetic that leaves the supported year range.
+    ///
+    /// Matches CPython's format: `OverflowError: date value out of range`
+    #[must_use]
+    pub(crate) fn overflow_error_date_out_of_range() -> RunError {
+        SimpleException::new_msg(Self::OverflowError, "date value out of range").into()
+    }
+
+    /// Creates a ValueError for a string that `fromisoformat()` cannot parse.
+    ///
+    /// Matches CPython's format: `ValueError: Invalid isoformat string: '{s}'`
+    #[must_use]
+    pub(crate) fn value_error_invalid_isoformat(s: &str) -> RunError {
+        SimpleException::new_msg(Self::ValueError, format!("Invalid isoformat string: {}", StringRepr(s))).into()
+    }
+
     /// Creates a ValueError for subsequence not found in bytes/str.
     ///
     /// Matches CPython's format: `ValueError: subsection not found`
@@ -1088,6 +1520,26 @@ impl ExcType {
         SimpleException::new_msg(Self::ValueError, "subsection not found").into()
     }
 
+    /// Creates a TypeError for a non-string left operand of `in` on a str.
+    ///
+    /// Matches CPython's format: `TypeError: 'in <string>' requires string as left operand, not {type}`
+    #[must_use]
+    pub(crate) fn type_error_str_contains(type_: Type) -> RunError {
+        SimpleException::new_msg(
+            Self::TypeError,
+            format!("'in <string>' requires string as left operand, not {type_}"),
+        )
+        .into()
+    }
+
+    /// Creates a ValueError for an integer outside the byte range.
+    ///
+    /// Matches CPython's format: `ValueError: byte must be in range(0, 256)`
+    #[must_use]
+    pub(crate) fn value_error_byte_range() -> RunError {
+        SimpleException::new_msg(Self::ValueError, "byte must be in range(0, 256)").into()
+    }
+
     /// Creates a LookupError for unknown error handler.
     ///
     /// Matches CPython's format: `LookupError: unknown error handler name '{name}'`
@@ -1097,6 +1549,119 @@ impl ExcType {
     }
 }
 
+/// Extracts the optional message argument of an exception constructor call.
+///
+/// Currently supports zero or one string argument.
+fn exception_call_arg(
+    heap: &mut Heap<impl ResourceTracker>,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Option<String>> {
+    defer_drop!(args, heap);
+    match args {
+        ArgValues::Empty => Ok(None),
+        ArgValues::One(value) => match value {
+            Value::InternString(string_id) => Ok(Some(interns.get_str(*string_id).to_owned())),
+            Value::Ref(heap_id) => {
+                if let HeapData::Str(s) = heap.get(*heap_id) {
+                    Ok(Some(s.as_str().to_owned()))
+                } else {
+                    Err(RunError::internal(
+                        "exceptions can only be called with zero or one string argument",
+                    ))
+                }
+            }
+            _ => Err(RunError::internal(
+                "exceptions can only be called with zero or one string argument",
+            )),
+        },
+        _ => Err(RunError::internal(
+            "exceptions can only be called with zero or one string argument",
+        )),
+    }
+}
+
+/// A user-defined exception class, e.g. `class MyError(ValueError): pass`.
+///
+/// Only exception classes with an empty body (`pass`, a docstring or `...`) are supported.
+/// The base is an ordinary expression, so the class's place in the hierarchy is only known
+/// once its `class` statement runs, which records it on the heap as an `ExcClassInfo`.
+/// Instances are `SimpleException`s of the builtin ancestor type tagged with the class, so
+/// all builtin exception handling applies to them unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ExcClassDef {
+    /// The class name, used for `repr()`, `__name__` and tracebacks.
+    pub name: StringId,
+}
+
+/// The hierarchy of a user-defined exception class, recorded when its `class` statement runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ExcClassInfo {
+    /// The user-defined parent class, or `None` if the class directly subclasses `base`.
+    pub parent: Option<ExcClassId>,
+    /// The nearest builtin exception type in the class's ancestry.
+    pub base: ExcType,
+    /// Name of the module the class was defined in, shown in the class's `repr()`.
+    pub module: StringId,
+}
+
+/// Creates an instance of a user-defined exception class, e.g. `MyError('message')`.
+///
+/// Accepts the same arguments as builtin exception constructors.
+pub(crate) fn call_exc_class(
+    class_id: ExcClassId,
+    heap: &mut Heap<impl ResourceTracker>,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let arg = exception_call_arg(heap, args, interns)?;
+    let exc = SimpleException::new_class_instance(class_id, heap, arg)?;
+    let heap_id = heap.allocate(HeapData::Exception(exc))?;
+    Ok(Value::Ref(heap_id))
+}
+
+/// Allocates `exc` on the heap, first allocating its cause chain so that `__cause__`
+/// returns the same object on every read.
+pub(crate) fn allocate_exception(
+    mut exc: SimpleException,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> Result<HeapId, ResourceError> {
+    let cause_id = match exc.cause() {
+        Some(cause) => Some(allocate_exception(cause.clone(), heap)?),
+        None => None,
+    };
+    exc.cause_ref = CauseRef(cause_id);
+    heap.allocate(HeapData::Exception(exc)).inspect_err(|_| {
+        if let Some(cause_id) = cause_id {
+            heap.dec_ref(cause_id);
+        }
+    })
+}
+
+/// The heap object a heap exception exposes as `__cause__`.
+///
+/// This is a strong reference: the heap counts it as a child of the exception and
+/// releases it when the exception is freed. Clones of a `SimpleException` live outside
+/// the heap, so they start without a reference, and equality and hashing ignore it.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CauseRef(Option<HeapId>);
+
+impl Clone for CauseRef {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl PartialEq for CauseRef {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Hash for CauseRef {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
 /// Simple lightweight representation of an exception.
 ///
 /// This is used for performance reasons for common exception patterns.
@@ -1105,11 +1670,23 @@ impl ExcType {
 pub(crate) struct SimpleException {
     exc_type: ExcType,
     arg: Option<String>,
+    /// The explicit cause set by `raise ... from cause`, exposed as `__cause__`.
+    #[serde(default)]
+    cause: Option<Box<SimpleException>>,
+    /// The heap object for `cause`, set only while this exception is on the heap.
+    #[serde(default)]
+    cause_ref: CauseRef,
+    /// The user-defined class of this exception, if any. `exc_type` is then the
+    /// class's nearest builtin ancestor.
+    #[serde(default)]
+    class: Option<ExcClassId>,
 }
 
+/// Formats the exception's repr using its builtin type name, since user-defined class
+/// names need `Interns` to resolve (see `SimpleException::py_repr_fmt`).
 impl fmt::Display for SimpleException {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.py_repr_fmt(f)
+        self.repr_fmt_with_name(f, self.exc_type.into())
     }
 }
 impl From<MontyException> for SimpleException {
@@ -1117,6 +1694,9 @@ impl From<MontyException> for SimpleException {
         Self {
             exc_type: exc.exc_type(),
             arg: exc.into_message(),
+            cause: None,
+            cause_ref: CauseRef::default(),
+            class: None,
         }
     }
 }
@@ -1125,7 +1705,13 @@ impl SimpleException {
     /// Creates a new exception with the given type and optional argument message.
     #[must_use]
     pub fn new(exc_type: ExcType, arg: Option<String>) -> Self {
-        Self { exc_type, arg }
+        Self {
+            exc_type,
+            arg,
+            cause: None,
+            cause_ref: CauseRef::default(),
+            class: None,
+        }
     }
 
     /// Creates a new exception with the given type and argument message.
@@ -1134,13 +1720,39 @@ impl SimpleException {
         Self {
             exc_type,
             arg: Some(arg.to_string()),
+            cause: None,
+            cause_ref: CauseRef::default(),
+            class: None,
         }
     }
 
     /// Creates a new exception with the given type and no argument message.
     #[must_use]
     pub fn new_none(exc_type: ExcType) -> Self {
-        Self { exc_type, arg: None }
+        Self {
+            exc_type,
+            arg: None,
+            cause: None,
+            cause_ref: CauseRef::default(),
+            class: None,
+        }
+    }
+
+    /// Creates an instance of a user-defined exception class.
+    ///
+    /// The exception's `exc_type` is the class's nearest builtin ancestor.
+    pub fn new_class_instance(
+        class_id: ExcClassId,
+        heap: &Heap<impl ResourceTracker>,
+        arg: Option<String>,
+    ) -> RunResult<Self> {
+        Ok(Self {
+            exc_type: heap.exc_class(class_id)?.base,
+            arg,
+            cause: None,
+            cause_ref: CauseRef::default(),
+            class: Some(class_id),
+        })
     }
 
     #[must_use]
@@ -1153,6 +1765,45 @@ impl SimpleException {
         self.arg.as_ref()
     }
 
+    /// Returns the user-defined class of this exception, if any.
+    #[must_use]
+    pub fn class_id(&self) -> Option<ExcClassId> {
+        self.class
+    }
+
+    /// Returns the name of this exception's type: the user-defined class name if there
+    /// is one, otherwise the builtin type name.
+    #[must_use]
+    pub fn type_name<'a>(&self, interns: &'a Interns) -> &'a str {
+        match self.class {
+            Some(class_id) => interns.get_str(interns.get_exc_class(class_id).name),
+            None => self.exc_type.into(),
+        }
+    }
+
+    /// Returns the explicit cause set by `raise ... from cause`, if any.
+    #[must_use]
+    pub fn cause(&self) -> Option<&Self> {
+        self.cause.as_deref()
+    }
+
+    /// Sets (or clears, with `None`) the explicit cause, as `raise ... from cause` does.
+    ///
+    /// `cause_id` is the heap object of the cause, which this exception takes ownership of.
+    /// Returns the previous cause object, which the caller must release.
+    #[must_use]
+    pub fn set_cause(&mut self, cause: Option<(Self, HeapId)>) -> Option<HeapId> {
+        let (cause, cause_id) = cause.unzip();
+        self.cause = cause.map(Box::new);
+        std::mem::replace(&mut self.cause_ref.0, cause_id)
+    }
+
+    /// Returns the heap object of the explicit cause, if any.
+    #[must_use]
+    pub fn cause_id(&self) -> Option<HeapId> {
+        self.cause_ref.0
+    }
+
     /// str() for an exception
     #[must_use]
     pub fn py_str(&self) -> String {
@@ -1169,9 +1820,13 @@ impl SimpleException {
     }
 
     /// Returns the exception formatted as Python would repr it.
-    pub fn py_repr_fmt(&self, f: &mut impl Write) -> std::fmt::Result {
-        let type_str: &'static str = self.exc_type.into();
-        write!(f, "{type_str}(")?;
+    pub fn py_repr_fmt(&self, f: &mut impl Write, interns: &Interns) -> std::fmt::Result {
+        self.repr_fmt_with_name(f, self.type_name(interns))
+    }
+
+    /// Writes `name(arg)`, the repr of an exception whose type is called `name`.
+    fn repr_fmt_with_name(&self, f: &mut impl Write, name: &str) -> std::fmt::Result {
+        write!(f, "{name}(")?;
 
         if let Some(arg) = &self.arg {
             string_repr_fmt(arg, f)?;
@@ -1185,6 +1840,8 @@ impl SimpleException {
             exc: self,
             frame: Some(frame),
             hide_caret: false,
+            limit: None,
+            raised_id: None,
         }
     }
 
@@ -1193,13 +1850,16 @@ impl SimpleException {
             exc: self,
             frame: Some(RawStackFrame::from_position(position)),
             hide_caret: false,
+            limit: None,
+            raised_id: None,
         }
     }
 
     /// Gets an attribute from this exception.
     ///
-    /// Handles the `.args` attribute by allocating a tuple containing the message.
-    /// Returns `Err(AttributeError)` for all other attributes.
+    /// Handles the `.args` attribute by allocating a tuple containing the message,
+    /// and `.__cause__` by returning the cause object (or `None`).
+    /// Returns `Ok(None)` for all other attributes.
     pub fn py_getattr(
         &self,
         attr_id: StringId,
@@ -1215,6 +1875,15 @@ impl SimpleException {
                 smallvec![]
             };
             Ok(Some(AttrCallResult::Value(allocate_tuple(elements, heap)?)))
+        } else if attr_id == StaticStrings::DunderCause {
+            let value = match self.cause_ref.0 {
+                Some(cause_id) => {
+                    heap.inc_ref(cause_id);
+                    Value::Ref(cause_id)
+                }
+                None => Value::None,
+            };
+            Ok(Some(AttrCallResult::Value(value)))
         } else {
             Ok(None)
         }
@@ -1234,6 +1903,13 @@ pub struct ExceptionRaise {
     /// whether the caret should be hidden.
     #[serde(default)]
     pub hide_caret: bool,
+    /// The resource limit that caused this exception, set only for errors raised by the resource tracker.
+    #[serde(default)]
+    pub limit: Option<LimitExceeded>,
+    /// The heap object that was raised, set by `raise` so the handler can bind that
+    /// object instead of a copy.
+    #[serde(skip)]
+    pub raised_id: Option<HeapId>,
 }
 
 impl From<SimpleException> for ExceptionRaise {
@@ -1242,6 +1918,8 @@ impl From<SimpleException> for ExceptionRaise {
             exc,
             frame: None,
             hide_caret: false,
+            limit: None,
+            raised_id: None,
         }
     }
 }
@@ -1252,6 +1930,8 @@ impl From<MontyException> for ExceptionRaise {
             exc: exc.into(),
             frame: None,
             hide_caret: false,
+            limit: None,
+            raised_id: None,
         }
     }
 }
@@ -1317,7 +1997,13 @@ impl ExceptionRaise {
             })
             .unwrap_or_default();
 
+        let class_name = self
+            .exc
+            .class_id()
+            .map(|class_id| interns.get_str(interns.get_exc_class(class_id).name).to_owned());
         MontyException::new_full(self.exc.exc_type(), self.exc.arg().cloned(), traceback)
+            .with_class_name(class_name)
+            .with_limit_exceeded(self.limit)
     }
 }
 
@@ -1346,6 +2032,7 @@ impl RawStackFrame {
             frame_name: Some(frame_name),
             parent: parent.map(|p| Box::new(p.clone())),
             hide_caret: false,
+            limit: None,
         }
     }
 
@@ -1355,6 +2042,7 @@ impl RawStackFrame {
             frame_name: None,
             parent: None,
             hide_caret: false,
+            limit: None,
         }
     }
 
@@ -1365,6 +2053,7 @@ impl RawStackFrame {
             frame_name: Some(frame_name),
             parent: None,
             hide_caret: true,
+            limit: None,
         }
     }
 }
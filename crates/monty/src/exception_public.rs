@@ -1,9 +1,12 @@
 use std::fmt::{self, Write};
 
 use crate::{
+    builtins::DYNAMIC_CODE_FILENAME,
     exception_private::{ExcType, RawStackFrame},
     intern::Interns,
     parse::CodeRange,
+    resource::LimitExceeded,
+    schema::SchemaMismatch,
     types::str::StringRepr,
 };
 
@@ -11,11 +14,20 @@ use crate::{
 #[derive(Debug, Clone, PartialEq)]
 pub struct MontyException {
     /// The exception type raised
+    ///
+    /// For instances of user-defined exception classes, this is the class's nearest
+    /// builtin ancestor and `class_name` holds the class's own name.
     exc_type: ExcType,
+    /// Name of the user-defined exception class, if the exception is an instance of one.
+    class_name: Option<String>,
     /// Optional exception message explaining what went wrong
     message: Option<String>,
     /// Stack trace of the exception, first is the outermost frame shown first in the traceback
     traceback: Vec<StackFrame>,
+    /// The resource limit that aborted execution, if this exception was raised by the resource tracker.
+    limit_exceeded: Option<LimitExceeded>,
+    /// Where the result didn't match the expected type, if this exception was raised because of it.
+    schema_mismatch: Option<SchemaMismatch>,
 }
 
 /// Number of identical consecutive frames to show before collapsing.
@@ -61,11 +73,7 @@ impl fmt::Display for MontyException {
             }
         }
 
-        if let Some(msg) = &self.message {
-            write!(f, "{}: {}", self.exc_type, msg)
-        } else {
-            write!(f, "{}", self.exc_type)
-        }
+        f.write_str(&self.summary())
     }
 }
 
@@ -79,17 +87,39 @@ impl MontyException {
     pub fn new(exc_type: ExcType, message: Option<String>) -> Self {
         Self {
             exc_type,
+            class_name: None,
             message,
             traceback: vec![],
+            limit_exceeded: None,
+            schema_mismatch: None,
         }
     }
 
     /// The exception type raised.
+    ///
+    /// For instances of user-defined exception classes this is the nearest builtin
+    /// ancestor, e.g. `ValueError` for `class MyError(ValueError): pass`.
     #[must_use]
     pub fn exc_type(&self) -> ExcType {
         self.exc_type
     }
 
+    /// Name of the user-defined exception class, if the exception is an instance of one.
+    #[must_use]
+    pub fn class_name(&self) -> Option<&str> {
+        self.class_name.as_deref()
+    }
+
+    /// Name of the exception's type as Python displays it: the user-defined class name
+    /// if there is one, otherwise the builtin type name.
+    #[must_use]
+    pub fn type_name(&self) -> &str {
+        match &self.class_name {
+            Some(name) => name,
+            None => self.exc_type.into(),
+        }
+    }
+
     /// Optional exception message explaining what went wrong.
     ///
     /// Equivalent of python's `exc.args[0]`
@@ -114,6 +144,36 @@ impl MontyException {
         &self.traceback
     }
 
+    /// The innermost frame of the traceback, i.e. where the exception was raised.
+    ///
+    /// For errors found while parsing this is the position of the syntax error, for runtime
+    /// errors it is the expression that raised. The frame gives start and end line/column
+    /// positions plus the source line, so editors and tools can point at the failing code
+    /// without parsing the formatted traceback.
+    ///
+    /// Returns `None` if the exception has no traceback.
+    #[must_use]
+    pub fn location(&self) -> Option<&StackFrame> {
+        self.traceback.last()
+    }
+
+    /// The resource limit that aborted execution, if this exception was raised because
+    /// a [`ResourceLimits`](crate::ResourceLimits) limit was exceeded.
+    ///
+    /// Returns `None` for exceptions raised by the sandboxed code itself, even if they
+    /// have the same type, e.g. `raise MemoryError()`.
+    #[must_use]
+    pub fn limit_exceeded(&self) -> Option<LimitExceeded> {
+        self.limit_exceeded
+    }
+
+    /// Where and how the result didn't match the [`ResultSchema`](crate::ResultSchema) set with
+    /// `MontyRun::with_result_schema`, if that is why the run failed.
+    #[must_use]
+    pub fn schema_mismatch(&self) -> Option<&SchemaMismatch> {
+        self.schema_mismatch.as_ref()
+    }
+
     /// Returns a compact summary of the exception.
     ///
     /// Format: `ExceptionType: message` (e.g., `NotImplementedError: feature not supported`)
@@ -121,9 +181,9 @@ impl MontyException {
     #[must_use]
     pub fn summary(&self) -> String {
         if let Some(msg) = &self.message {
-            format!("{}: {}", self.exc_type, msg)
+            format!("{}: {}", self.type_name(), msg)
         } else {
-            self.exc_type.to_string()
+            self.type_name().to_owned()
         }
     }
 
@@ -133,7 +193,7 @@ impl MontyException {
     /// Uses appropriate quoting for messages containing quotes.
     #[must_use]
     pub fn py_repr(&self) -> String {
-        let type_str: &'static str = self.exc_type.into();
+        let type_str = self.type_name();
         if let Some(msg) = &self.message {
             format!("{}({})", type_str, StringRepr(msg))
         } else {
@@ -144,16 +204,48 @@ impl MontyException {
     pub(crate) fn new_full(exc_type: ExcType, message: Option<String>, traceback: Vec<StackFrame>) -> Self {
         Self {
             exc_type,
+            class_name: None,
             message,
             traceback,
+            limit_exceeded: None,
+            schema_mismatch: None,
+        }
+    }
+
+    /// Sets the name of the user-defined exception class this exception is an instance of.
+    #[must_use]
+    pub(crate) fn with_class_name(mut self, class_name: Option<String>) -> Self {
+        self.class_name = class_name;
+        self
+    }
+
+    /// Records the resource limit that caused this exception.
+    #[must_use]
+    pub(crate) fn with_limit_exceeded(mut self, limit_exceeded: Option<LimitExceeded>) -> Self {
+        self.limit_exceeded = limit_exceeded;
+        self
+    }
+
+    /// Creates the `TypeError` for a result that doesn't match the expected type.
+    pub(crate) fn schema_mismatch_error(mismatch: SchemaMismatch) -> Self {
+        Self {
+            exc_type: ExcType::TypeError,
+            class_name: None,
+            message: Some(format!("result doesn't match the expected type, {mismatch}")),
+            traceback: vec![],
+            limit_exceeded: None,
+            schema_mismatch: Some(mismatch),
         }
     }
 
     pub(crate) fn runtime_error(err: impl fmt::Display) -> Self {
         Self {
             exc_type: ExcType::RuntimeError,
+            class_name: None,
             message: Some(err.to_string()),
             traceback: vec![],
+            limit_exceeded: None,
+            schema_mismatch: None,
         }
     }
 }
@@ -244,6 +336,13 @@ impl fmt::Display for StackFrame {
 impl StackFrame {
     pub(crate) fn from_raw(f: &RawStackFrame, interns: &Interns, source: &str) -> Self {
         let filename = interns.get_str(f.position.filename).to_string();
+        // Frames inside an imported Python module preview that module's source, and like in
+        // CPython code run by `eval()` or `exec()` has no preview
+        let source = if filename == DYNAMIC_CODE_FILENAME {
+            ""
+        } else {
+            interns.python_module_source(f.position.filename).unwrap_or(source)
+        };
         Self {
             filename,
             start: f.position.start(),
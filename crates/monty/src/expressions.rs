@@ -2,7 +2,7 @@ use crate::{
     args::ArgExprs,
     builtins::Builtins,
     fstring::FStringPart,
-    intern::{BytesId, LongIntId, StringId},
+    intern::{BytesId, ExcClassId, LongIntId, StringId},
     namespace::NamespaceId,
     parse::{CodeRange, ParsedSignature, Try},
     signature::Signature,
@@ -81,6 +81,12 @@ impl Identifier {
         self.opt_namespace_id
             .expect("Identifier not prepared with namespace_id")
     }
+
+    /// Returns the namespace slot, or `None` if the identifier doesn't refer to one, like
+    /// a keyword argument's name.
+    pub fn opt_namespace_id(&self) -> Option<NamespaceId> {
+        self.opt_namespace_id
+    }
 }
 
 /// Target of a function call expression.
@@ -199,6 +205,13 @@ pub enum Expr {
     /// Raises `TypeError` for non-awaitable values.
     /// Unlike standard Python, `await` is allowed at module level (like Jupyter notebooks).
     Await(Box<ExprLoc>),
+    /// A user-defined exception class, the value assigned by its `class` statement.
+    ///
+    /// Evaluates `base` and records the class's place in the exception hierarchy.
+    ExcClass {
+        class_id: ExcClassId,
+        base: Box<ExprLoc>,
+    },
     /// F-string expression containing literal and interpolated parts.
     ///
     /// At evaluation time, each part is processed in sequence:
@@ -280,6 +293,45 @@ pub enum Expr {
     },
 }
 
+/// One target of a chained assignment like `a = b[0] = c.x = d, e = value`.
+///
+/// Each target receives the same value, assigned left to right.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum AssignTarget {
+    /// A plain name or a (possibly nested) tuple/list unpacking.
+    Unpack(UnpackTarget),
+    /// Subscript assignment: `target[index] = value`.
+    Subscript {
+        target: Identifier,
+        index: ExprLoc,
+        /// Position of the subscript expression (e.g., `lst[10]`) for traceback carets.
+        target_position: CodeRange,
+    },
+    /// Attribute assignment: `object.attr = value`.
+    Attr {
+        object: ExprLoc,
+        attr: EitherStr,
+        target_position: CodeRange,
+    },
+}
+
+/// One target of a `del` statement like `del x, d[key], lst[1:3]`.
+///
+/// Tuple and list targets such as `del (a, b)` are flattened by the parser,
+/// since deleting them just deletes each element from left to right.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum DeleteTarget {
+    /// Name deletion: `del x`. Unbinds the name in its namespace slot.
+    Name(Identifier),
+    /// Subscript deletion: `del object[index]`.
+    Subscript {
+        object: ExprLoc,
+        index: ExprLoc,
+        /// Position of the subscript expression (e.g., `lst[10]`) for traceback carets.
+        target_position: CodeRange,
+    },
+}
+
 /// Target for tuple unpacking - can be a single name, nested tuple, or starred target.
 ///
 /// Supports recursive structures like `(a, b), c` or `a, (b, c)`.
@@ -397,7 +449,13 @@ pub enum Node<F> {
     Expr(ExprLoc),
     Return(ExprLoc),
     ReturnNone,
-    Raise(Option<ExprLoc>),
+    /// `raise`, `raise exc` or `raise exc from cause`.
+    ///
+    /// `cause` is only ever set when `exc` is set.
+    Raise {
+        exc: Option<ExprLoc>,
+        cause: Option<ExprLoc>,
+    },
     Assert {
         test: ExprLoc,
         msg: Option<ExprLoc>,
@@ -440,6 +498,20 @@ pub enum Node<F> {
         target_position: CodeRange,
         value: ExprLoc,
     },
+    /// Chained assignment with several targets (e.g., `a = b = []` or `x = y[0] = 1`).
+    ///
+    /// The value is evaluated once and assigned to each target from left to right,
+    /// so every target refers to the same object.
+    ChainAssign {
+        targets: Vec<AssignTarget>,
+        object: ExprLoc,
+    },
+    /// Delete statement (e.g., `del x` or `del d[key], lst[0]`).
+    ///
+    /// Targets are deleted from left to right.
+    Delete {
+        targets: Vec<DeleteTarget>,
+    },
     For {
         /// Loop target - either a single identifier or tuple unpacking pattern.
         target: UnpackTarget,
@@ -537,6 +609,8 @@ pub struct PreparedFunctionDef {
     pub body: Vec<Node<Self>>,
     /// Number of local variable slots needed in the namespace.
     pub namespace_size: usize,
+    /// Name of each namespace slot, empty for unnamed slots, see `Function::slot_names`.
+    pub slot_names: Vec<StringId>,
     /// Enclosing namespace slots for variables captured from enclosing scopes.
     ///
     /// At definition time: look up cell HeapId from enclosing namespace at each slot.
@@ -565,6 +639,11 @@ pub struct PreparedFunctionDef {
     /// When true, calling this function creates a `Coroutine` object instead of
     /// immediately pushing a frame.
     pub is_async: bool,
+    /// Prepared decorator expressions in source order (outermost first).
+    ///
+    /// They are evaluated before the defaults, then applied innermost first to the new
+    /// function; the name is only bound to the final result. Always empty for lambdas.
+    pub decorators: Vec<ExprLoc>,
 }
 
 /// Type alias for prepared AST nodes (output of prepare phase).
@@ -0,0 +1,377 @@
+//! Typed conversions for host functions called from sandboxed code.
+//!
+//! [`FromMontyArg`] and [`IntoMontyObject`] convert between `MontyObject` and plain Rust
+//! argument and return types. The `#[monty::external_fn]` attribute (behind the `macros`
+//! feature) uses them, with [`bind_args`] and [`extract_arg`], to wrap a typed Rust function
+//! in the `(args, kwargs) -> Result<MontyObject, MontyException>` calling convention used by
+//! `NativeModule::function`.
+//!
+//! [`ExternalFunctions`] maps the names of external functions to Rust implementations, so
+//! `MontyRun::run_with_functions` can resolve every call without the host writing the
+//! `RunProgress` loop.
+
+use ahash::AHashMap;
+
+use crate::{ExcType, MontyException, native_module::NativeFunction, object::MontyObject};
+
+/// Rust implementations of external functions, looked up by name.
+///
+/// Functions use the same calling convention as `NativeModule::function`, so functions
+/// annotated with `#[monty::external_fn]` can be registered directly.
+///
+/// # Example
+/// ```
+/// use monty::{ExternalFunctions, MontyObject, MontyRun, NoLimitTracker, PrintWriter};
+///
+/// let funcs = ExternalFunctions::new().function("add", |args, _kwargs| match args.as_slice() {
+///     [MontyObject::Int(a), MontyObject::Int(b)] => Ok(MontyObject::Int(a + b)),
+///     _ => Ok(MontyObject::None),
+/// });
+/// let runner = MontyRun::new("add(40, 2)".to_owned(), "test.py", vec![], vec!["add".to_owned()]).unwrap();
+/// let result = runner
+///     .run_with_functions(vec![], &funcs, NoLimitTracker, &mut PrintWriter::Disabled)
+///     .unwrap();
+/// assert_eq!(result, MontyObject::Int(42));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExternalFunctions {
+    functions: AHashMap<String, NativeFunction>,
+}
+
+impl ExternalFunctions {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `function` as the implementation of the external function `name`,
+    /// replacing any previous implementation.
+    ///
+    /// The function receives the positional and keyword arguments of each call; an
+    /// `Err` is raised as an exception in the sandboxed code.
+    #[must_use]
+    pub fn function(
+        mut self,
+        name: impl Into<String>,
+        function: impl Fn(Vec<MontyObject>, Vec<(MontyObject, MontyObject)>) -> Result<MontyObject, MontyException>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.functions.insert(name.into(), NativeFunction::new(function));
+        self
+    }
+
+    /// Returns whether an implementation is registered for `name`.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// Calls the implementation of `name`, raising `KeyError` if there is none.
+    pub(crate) fn call(
+        &self,
+        name: &str,
+        args: Vec<MontyObject>,
+        kwargs: Vec<(MontyObject, MontyObject)>,
+    ) -> Result<MontyObject, MontyException> {
+        match self.functions.get(name) {
+            Some(function) => function.call(args, kwargs),
+            None => Err(MontyException::new(
+                ExcType::KeyError,
+                Some(format!("\"External function '{name}' not found\"")),
+            )),
+        }
+    }
+}
+
+/// A Rust type that an argument passed from sandboxed code can be converted to.
+///
+/// The lifetime lets `&str` borrow from the argument.
+pub trait FromMontyArg<'a>: Sized {
+    /// Python description of accepted values, used in `TypeError` messages, e.g. `"int"`.
+    fn expected() -> String;
+
+    /// Converts the argument, or returns `None` if it has the wrong type.
+    fn from_monty_arg(value: &'a MontyObject) -> Option<Self>;
+
+    /// The value used when the argument isn't passed, or `None` if it is required.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+impl FromMontyArg<'_> for MontyObject {
+    fn expected() -> String {
+        "object".to_owned()
+    }
+
+    fn from_monty_arg(value: &MontyObject) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromMontyArg<'_> for i64 {
+    fn expected() -> String {
+        "int".to_owned()
+    }
+
+    fn from_monty_arg(value: &MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::Int(i) => Some(*i),
+            MontyObject::Bool(b) => Some(Self::from(*b)),
+            _ => None,
+        }
+    }
+}
+
+impl FromMontyArg<'_> for f64 {
+    fn expected() -> String {
+        "float".to_owned()
+    }
+
+    fn from_monty_arg(value: &MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::Float(f) => Some(*f),
+            MontyObject::Int(i) => Some(*i as Self),
+            _ => None,
+        }
+    }
+}
+
+impl FromMontyArg<'_> for bool {
+    fn expected() -> String {
+        "bool".to_owned()
+    }
+
+    fn from_monty_arg(value: &MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl FromMontyArg<'_> for String {
+    fn expected() -> String {
+        "str".to_owned()
+    }
+
+    fn from_monty_arg(value: &MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> FromMontyArg<'a> for &'a str {
+    fn expected() -> String {
+        "str".to_owned()
+    }
+
+    fn from_monty_arg(value: &'a MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Accepts a `list` or `tuple` whose items all convert to `T`.
+impl<'a, T: FromMontyArg<'a>> FromMontyArg<'a> for Vec<T> {
+    fn expected() -> String {
+        format!("list[{}]", T::expected())
+    }
+
+    fn from_monty_arg(value: &'a MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::List(items) | MontyObject::Tuple(items) => items.iter().map(T::from_monty_arg).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Accepts `None` or a value converting to `T`, and makes the argument optional.
+impl<'a, T: FromMontyArg<'a>> FromMontyArg<'a> for Option<T> {
+    fn expected() -> String {
+        format!("{} or None", T::expected())
+    }
+
+    fn from_monty_arg(value: &'a MontyObject) -> Option<Self> {
+        match value {
+            MontyObject::None => Some(None),
+            _ => T::from_monty_arg(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// A Rust value that can be returned to sandboxed code.
+pub trait IntoMontyObject {
+    /// Converts to the equivalent Python value.
+    fn into_monty_object(self) -> MontyObject;
+}
+
+impl IntoMontyObject for MontyObject {
+    fn into_monty_object(self) -> MontyObject {
+        self
+    }
+}
+
+impl IntoMontyObject for () {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::None
+    }
+}
+
+impl IntoMontyObject for bool {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::Bool(self)
+    }
+}
+
+impl IntoMontyObject for i64 {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::Int(self)
+    }
+}
+
+impl IntoMontyObject for i32 {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::Int(self.into())
+    }
+}
+
+impl IntoMontyObject for u32 {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::Int(self.into())
+    }
+}
+
+impl IntoMontyObject for f64 {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::Float(self)
+    }
+}
+
+impl IntoMontyObject for String {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::String(self)
+    }
+}
+
+impl IntoMontyObject for &str {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::String(self.to_owned())
+    }
+}
+
+/// Becomes a `list`.
+impl<T: IntoMontyObject> IntoMontyObject for Vec<T> {
+    fn into_monty_object(self) -> MontyObject {
+        MontyObject::List(self.into_iter().map(IntoMontyObject::into_monty_object).collect())
+    }
+}
+
+/// `None` becomes Python's `None`.
+impl<T: IntoMontyObject> IntoMontyObject for Option<T> {
+    fn into_monty_object(self) -> MontyObject {
+        self.map_or(MontyObject::None, IntoMontyObject::into_monty_object)
+    }
+}
+
+/// Matches positional and keyword arguments to the parameters of `fn_name`.
+///
+/// `params` lists each parameter's name and whether it is required. Returns the bound
+/// value of each parameter in order, or a `TypeError` worded like CPython's for too many
+/// positional arguments, unknown or repeated keywords, and missing required arguments.
+///
+/// # Errors
+/// Returns a `TypeError` if the arguments don't match the parameters.
+pub fn bind_args(
+    fn_name: &str,
+    params: &[(&str, bool)],
+    args: Vec<MontyObject>,
+    kwargs: Vec<(MontyObject, MontyObject)>,
+) -> Result<Vec<Option<MontyObject>>, MontyException> {
+    if args.len() > params.len() {
+        let expected = params.len();
+        let given = args.len();
+        let plural = if expected == 1 { "" } else { "s" };
+        let verb = if given == 1 { "was" } else { "were" };
+        return Err(type_error(format!(
+            "{fn_name}() takes {expected} positional argument{plural} but {given} {verb} given"
+        )));
+    }
+
+    let mut bound: Vec<Option<MontyObject>> = args.into_iter().map(Some).collect();
+    bound.resize_with(params.len(), || None);
+    for (key, value) in kwargs {
+        let MontyObject::String(key) = key else {
+            return Err(type_error(format!("{fn_name}() keywords must be strings")));
+        };
+        let Some(index) = params.iter().position(|(name, _)| *name == key) else {
+            return Err(type_error(format!(
+                "{fn_name}() got an unexpected keyword argument '{key}'"
+            )));
+        };
+        if bound[index].is_some() {
+            return Err(type_error(format!(
+                "{fn_name}() got multiple values for argument '{key}'"
+            )));
+        }
+        bound[index] = Some(value);
+    }
+
+    let missing: Vec<String> = params
+        .iter()
+        .zip(&bound)
+        .filter(|((_, required), value)| *required && value.is_none())
+        .map(|((name, _), _)| format!("'{name}'"))
+        .collect();
+    if let Some((last, rest)) = missing.split_last() {
+        let names = if rest.is_empty() {
+            last.clone()
+        } else {
+            format!("{} and {last}", rest.join(", "))
+        };
+        let plural = if missing.len() == 1 { "" } else { "s" };
+        return Err(type_error(format!(
+            "{fn_name}() missing {} required argument{plural}: {names}",
+            missing.len()
+        )));
+    }
+    Ok(bound)
+}
+
+/// Converts a bound argument of `fn_name` to `T`, raising a `TypeError` if it has the wrong type.
+///
+/// # Errors
+/// Returns a `TypeError` if the value can't be converted, or is missing and `T` isn't optional.
+pub fn extract_arg<'a, T: FromMontyArg<'a>>(
+    value: Option<&'a MontyObject>,
+    fn_name: &str,
+    param: &str,
+) -> Result<T, MontyException> {
+    match value {
+        Some(value) => T::from_monty_arg(value).ok_or_else(|| {
+            type_error(format!(
+                "{fn_name}() argument '{param}' must be {}, not {}",
+                T::expected(),
+                value.type_name()
+            ))
+        }),
+        None => {
+            T::from_missing().ok_or_else(|| type_error(format!("{fn_name}() missing required argument: '{param}'")))
+        }
+    }
+}
+
+fn type_error(message: String) -> MontyException {
+    MontyException::new(ExcType::TypeError, Some(message))
+}
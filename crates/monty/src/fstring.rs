@@ -5,16 +5,22 @@
 //!
 //! F-strings can contain literal text and interpolated expressions with optional
 //! conversion flags (`!s`, `!r`, `!a`) and format specifications.
+//!
+//! The same formatting functions also back `format()`, `str.format()` and printf-style
+//! `str % args` formatting.
+
+use std::{borrow::Cow, str::FromStr};
 
-use std::str::FromStr;
+use num_bigint::{BigInt, Sign};
+use num_traits::{FromPrimitive, ToPrimitive};
 
 use crate::{
     exception_private::{ExcType, RunError, SimpleException},
     expressions::ExprLoc,
-    heap::Heap,
+    heap::{Heap, HeapData},
     intern::{Interns, StringId},
-    resource::{DepthGuard, ResourceTracker},
-    types::{PyTrait, Type},
+    resource::{DepthGuard, ResourceError, ResourceTracker, check_repeat_size},
+    types::{Dict, PyTrait, Type},
     value::Value,
 };
 
@@ -101,23 +107,36 @@ pub struct ParsedFormatSpec {
     pub align: Option<char>,
     /// Sign handling: '+' (always), '-' (negative only), ' ' (space for positive)
     pub sign: Option<char>,
+    /// Alternate form (`#`): base prefixes for integers, kept decimal point for floats
+    pub alternate: bool,
     /// Whether to zero-pad numbers
     pub zero_pad: bool,
     /// Minimum field width
     pub width: usize,
+    /// Digit grouping separator: ',' or '_'
+    pub grouping: Option<char>,
     /// Precision for floats or max width for strings
     pub precision: Option<usize>,
     /// Type character: 's', 'd', 'f', 'e', 'g', etc.
     pub type_char: Option<char>,
 }
 
+/// Error returned when a format spec string can't be parsed.
+#[derive(Debug, Clone)]
+pub enum FormatSpecError {
+    /// The spec contains invalid or unrecognized characters; holds the original spec.
+    Invalid(String),
+    /// The width or precision has too many digits to fit in a `usize`.
+    TooManyDigits,
+}
+
 impl FromStr for ParsedFormatSpec {
-    type Err = String;
+    type Err = FormatSpecError;
 
     /// Parses a format specification string into its components.
     ///
-    /// Returns an error if the specifier contains invalid or unrecognized characters.
-    /// The error includes the original specifier for use in error messages.
+    /// Returns an error if the specifier contains invalid or unrecognized characters,
+    /// or if the width or precision overflows.
     fn from_str(spec: &str) -> Result<Self, Self::Err> {
         if spec.is_empty() {
             return Ok(Self {
@@ -154,8 +173,9 @@ impl FromStr for ParsedFormatSpec {
             result.sign = chars.next();
         }
 
-        // Skip '#' (alternate form) for now
+        // Parse alternate form flag
         if chars.peek() == Some(&'#') {
+            result.alternate = true;
             chars.next();
         }
 
@@ -176,12 +196,12 @@ impl FromStr for ParsedFormatSpec {
             }
         }
         if !width_str.is_empty() {
-            result.width = width_str.parse().unwrap_or(0);
+            result.width = width_str.parse().map_err(|_| FormatSpecError::TooManyDigits)?;
         }
 
-        // Skip grouping option (comma or underscore)
+        // Parse grouping option (comma or underscore)
         if matches!(chars.peek(), Some(',' | '_')) {
-            chars.next();
+            result.grouping = chars.next();
         }
 
         // Parse precision: .N
@@ -197,7 +217,7 @@ impl FromStr for ParsedFormatSpec {
                 }
             }
             if !prec_str.is_empty() {
-                result.precision = Some(prec_str.parse().unwrap_or(0));
+                result.precision = Some(prec_str.parse().map_err(|_| FormatSpecError::TooManyDigits)?);
             }
         }
 
@@ -214,13 +234,57 @@ impl FromStr for ParsedFormatSpec {
 
         // Error if there are any unconsumed characters
         if chars.peek().is_some() {
-            return Err(spec.to_owned());
+            return Err(FormatSpecError::Invalid(spec.to_owned()));
         }
 
         Ok(result)
     }
 }
 
+impl ParsedFormatSpec {
+    /// Returns true if `encode_format_spec` can store the spec without losing information.
+    ///
+    /// Specs with a non-Latin-1 fill character, or a width or precision that needs more than
+    /// 16 bits, don't fit in the encoding's bit fields and have to be parsed at runtime instead.
+    pub fn is_encodable(&self) -> bool {
+        u32::from(self.fill) <= 0xFF && self.width <= 0xFFFF && self.precision.is_none_or(|p| p < 0xFFFF)
+    }
+
+    /// Returns true if the spec requests no formatting at all, as in `format(x, '')`.
+    ///
+    /// Types without their own formatting rules (lists, `None`, ...) only accept an empty spec.
+    pub fn is_empty(&self) -> bool {
+        self.fill == ' '
+            && self.align.is_none()
+            && self.sign.is_none()
+            && !self.alternate
+            && !self.zero_pad
+            && self.width == 0
+            && self.grouping.is_none()
+            && self.precision.is_none()
+            && self.type_char.is_none()
+    }
+}
+
+/// Parses a format spec string evaluated at runtime, as used by `format()` and `str.format()`.
+///
+/// Returns a `ValueError` naming `value_type` if the spec is malformed.
+pub fn parse_format_spec(spec: &str, value_type: Type) -> Result<ParsedFormatSpec, RunError> {
+    spec.parse::<ParsedFormatSpec>()
+        .map_err(|err| format_spec_error(err, value_type))
+}
+
+/// Creates the `ValueError` raised for a format spec that failed to parse.
+pub fn format_spec_error(err: FormatSpecError, value_type: Type) -> RunError {
+    let msg = match err {
+        FormatSpecError::Invalid(spec) => {
+            format!("Invalid format specifier '{spec}' for object of type '{value_type}'")
+        }
+        FormatSpecError::TooManyDigits => "Too many decimal digits in format string".to_owned(),
+    };
+    SimpleException::new_msg(ExcType::ValueError, msg).into()
+}
+
 // ============================================================================
 // Format errors
 // ============================================================================
@@ -252,9 +316,10 @@ impl std::fmt::Display for FormatError {
 /// Formats a value according to a format specification, applying type-appropriate formatting.
 ///
 /// Dispatches to the appropriate formatting function based on the value type and format spec:
-/// - Integers: `format_int`, `format_int_base`, `format_char`
+/// - Integers (including bools with a non-empty spec and `LongInt`s): `format_int`, `format_char`
 /// - Floats: `format_float_f`, `format_float_e`, `format_float_g`, `format_float_percent`
 /// - Strings: `format_string`
+/// - Anything else: `str()` of the value, provided the spec is empty
 ///
 /// Returns a `ValueError` if the format type character is incompatible with the value type.
 pub fn format_with_spec(
@@ -265,29 +330,52 @@ pub fn format_with_spec(
     interns: &Interns,
 ) -> Result<String, RunError> {
     let value_type = value.py_type(heap);
+    let float_digits =
+        matches!(value, Value::Float(_)) || matches!(spec.type_char, Some('e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%'));
+    check_format_size(spec, float_digits, heap.tracker())?;
+
+    if let Value::Ref(id) = value
+        && let HeapData::LongInt(li) = heap.get(*id)
+    {
+        return match spec.type_char {
+            None | Some('d' | 'n' | 'b' | 'o' | 'x' | 'X') => Ok(format_big_int(&li.0, spec)?),
+            Some('c') => Err(FormatError::Overflow("%c arg not in range(0x110000)".to_owned()).into()),
+            Some('e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%') => {
+                let f = li.0.to_f64().unwrap_or(f64::INFINITY);
+                format_with_spec(&Value::Float(f), spec, heap, guard, interns)
+            }
+            Some(c) => Err(unknown_format_code(c, value_type)),
+        };
+    }
 
     match (value, spec.type_char) {
+        // Bools format as their int value once any formatting is requested
+        (Value::Bool(b), _) if !spec.is_empty() => {
+            format_with_spec(&Value::Int(i64::from(*b)), spec, heap, guard, interns)
+        }
+
         // Integer formatting
-        (Value::Int(n), None | Some('d')) => Ok(format_int(*n, spec)),
-        (Value::Int(n), Some('b')) => Ok(format_int_base(*n, 2, spec)?),
-        (Value::Int(n), Some('o')) => Ok(format_int_base(*n, 8, spec)?),
-        (Value::Int(n), Some('x')) => Ok(format_int_base(*n, 16, spec)?),
-        (Value::Int(n), Some('X')) => Ok(format_int_base(*n, 16, spec)?.to_uppercase()),
+        (Value::Int(n), None | Some('d' | 'n' | 'b' | 'o' | 'x' | 'X')) => Ok(format_int(*n, spec)?),
         (Value::Int(n), Some('c')) => Ok(format_char(*n, spec)?),
 
         // Float formatting
-        (Value::Float(f), None | Some('g' | 'G')) => Ok(format_float_g(*f, spec)),
-        (Value::Float(f), Some('f' | 'F')) => Ok(format_float_f(*f, spec)),
+        (Value::Float(f), None) if spec.precision.is_none() => {
+            let repr = Value::Float(f.abs()).py_str(heap, guard, interns);
+            Ok(format_float_repr(is_float_negative(*f), &repr, spec))
+        }
+        (Value::Float(f), None) => Ok(format_float_g(*f, spec, false, true)),
+        (Value::Float(f), Some('g' | 'n')) => Ok(format_float_g(*f, spec, false, false)),
+        (Value::Float(f), Some('G')) => Ok(format_float_g(*f, spec, true, false)),
+        (Value::Float(f), Some('f')) => Ok(format_float_f(*f, spec, false)),
+        (Value::Float(f), Some('F')) => Ok(format_float_f(*f, spec, true)),
         (Value::Float(f), Some('e')) => Ok(format_float_e(*f, spec, false)),
         (Value::Float(f), Some('E')) => Ok(format_float_e(*f, spec, true)),
         (Value::Float(f), Some('%')) => Ok(format_float_percent(*f, spec)),
 
         // Int to float formatting (Python allows this)
-        (Value::Int(n), Some('f' | 'F')) => Ok(format_float_f(*n as f64, spec)),
-        (Value::Int(n), Some('e')) => Ok(format_float_e(*n as f64, spec, false)),
-        (Value::Int(n), Some('E')) => Ok(format_float_e(*n as f64, spec, true)),
-        (Value::Int(n), Some('g' | 'G')) => Ok(format_float_g(*n as f64, spec)),
-        (Value::Int(n), Some('%')) => Ok(format_float_percent(*n as f64, spec)),
+        (Value::Int(n), Some('f' | 'F' | 'e' | 'E' | 'g' | 'G' | '%')) => {
+            format_with_spec(&Value::Float(*n as f64), spec, heap, guard, interns)
+        }
 
         // String formatting (including InternString and heap strings)
         (_, None | Some('s')) if value_type == Type::Str => {
@@ -295,27 +383,93 @@ pub fn format_with_spec(
             Ok(format_string(&s, spec)?)
         }
 
-        // Bool as int
-        (Value::Bool(b), Some('d')) => Ok(format_int(i64::from(*b), spec)),
+        // Other types only support an empty spec, which is equivalent to `str()`
+        (_, None) if spec.is_empty() => Ok(value.py_str(heap, guard, interns).into_owned()),
+        (Value::Int(_) | Value::Float(_), Some(c)) => Err(unknown_format_code(c, value_type)),
+        (_, Some(c)) if value_type == Type::Str => Err(unknown_format_code(c, value_type)),
+        _ => Err(ExcType::type_error(format!(
+            "unsupported format string passed to {value_type}.__format__"
+        ))),
+    }
+}
 
-        // No type specifier: convert to string and format
-        (_, None) => {
-            let s = value.py_str(heap, guard, interns);
-            Ok(format_string(&s, spec)?)
+/// Formats a value after applying an explicit conversion flag.
+///
+/// With no conversion the value's own formatting rules apply; `!s`, `!r` and `!a` convert
+/// the value to a string first and format that string instead, so only string options are
+/// allowed in the spec.
+pub fn format_converted(
+    value: &Value,
+    conversion: ConversionFlag,
+    spec: &ParsedFormatSpec,
+    heap: &Heap<impl ResourceTracker>,
+    guard: &mut DepthGuard,
+    interns: &Interns,
+) -> Result<String, RunError> {
+    let converted = match conversion {
+        ConversionFlag::None => return format_with_spec(value, spec, heap, guard, interns),
+        ConversionFlag::Str => value.py_str(heap, guard, interns).into_owned(),
+        ConversionFlag::Repr => value.py_repr(heap, guard, interns).into_owned(),
+        ConversionFlag::Ascii => ascii_escape(&value.py_repr(heap, guard, interns)),
+    };
+    match spec.type_char {
+        None | Some('s') => {
+            check_format_size(spec, false, heap.tracker())?;
+            Ok(format_string(&converted, spec)?)
         }
+        Some(c) => Err(unknown_format_code(c, Type::Str)),
+    }
+}
 
-        // Type mismatch errors
-        (_, Some(c)) => Err(SimpleException::new_msg(
-            ExcType::ValueError,
-            format!("Unknown format code '{c}' for object of type '{value_type}'"),
-        )
-        .into()),
+/// Pre-checks that formatting with `spec` won't exceed resource limits before the result is built.
+///
+/// The result is padded to `spec.width` fill characters, and when `float_digits` is set the
+/// value is rendered with `spec.precision` digits; strings and ints never grow with the precision.
+fn check_format_size(
+    spec: &ParsedFormatSpec,
+    float_digits: bool,
+    tracker: &impl ResourceTracker,
+) -> Result<(), ResourceError> {
+    check_repeat_size(spec.fill.len_utf8(), spec.width, tracker)?;
+    match spec.precision {
+        Some(precision) if float_digits => check_repeat_size(1, precision, tracker),
+        _ => Ok(()),
     }
 }
 
+/// Formats a value with a spec string evaluated at runtime, applying a conversion flag first.
+///
+/// This is the shared implementation of the `format()` builtin and `str.format()` replacement
+/// fields. An empty spec is equivalent to `str()` of the (converted) value.
+pub fn format_value_with_spec_str(
+    value: &Value,
+    conversion: ConversionFlag,
+    spec: &str,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<String, RunError> {
+    let value_type = match conversion {
+        ConversionFlag::None => value.py_type(heap),
+        _ => Type::Str,
+    };
+    let parsed = parse_format_spec(spec, value_type)?;
+    format_converted(value, conversion, &parsed, heap, &mut DepthGuard::default(), interns)
+}
+
+/// Creates the `ValueError` raised for a presentation type the value's type doesn't support.
+fn unknown_format_code(c: char, value_type: Type) -> RunError {
+    SimpleException::new_msg(
+        ExcType::ValueError,
+        format!("Unknown format code '{c}' for object of type '{value_type}'"),
+    )
+    .into()
+}
+
 /// Encodes a ParsedFormatSpec into a u64 for storage in bytecode constants.
 ///
-/// Encoding layout (fits in 48 bits):
+/// The spec must satisfy `ParsedFormatSpec::is_encodable`.
+///
+/// Encoding layout (fits in 54 bits):
 /// - bits 0-7: fill character (as ASCII, default space=32)
 /// - bits 8-10: align (0=none, 1='<', 2='>', 3='^', 4='=')
 /// - bits 11-12: sign (0=none, 1='+', 2='-', 3=' ')
@@ -323,6 +477,8 @@ pub fn format_with_spec(
 /// - bits 14-29: width (16 bits, max 65535)
 /// - bits 30-45: precision (16 bits, using 0xFFFF as "no precision")
 /// - bits 46-50: type_char (0=none, 1-15=explicit type mapping: b,c,d,e,E,f,F,g,G,n,o,s,x,X,%)
+/// - bit 51: alternate
+/// - bits 52-53: grouping (0=none, 1=',', 2='_')
 pub fn encode_format_spec(spec: &ParsedFormatSpec) -> u64 {
     let fill = spec.fill as u64;
     let align = match spec.align {
@@ -340,8 +496,14 @@ pub fn encode_format_spec(spec: &ParsedFormatSpec) -> u64 {
         Some(' ') => 3,
         Some(_) => 0,
     };
+    let alternate = u64::from(spec.alternate);
     let zero_pad = u64::from(spec.zero_pad);
     let width = spec.width as u64;
+    let grouping = match spec.grouping {
+        Some(',') => 1u64,
+        Some('_') => 2,
+        _ => 0,
+    };
     let precision = spec.precision.map_or(0xFFFFu64, |p| p as u64);
     let type_char = spec.type_char.map_or(0u64, |c| match c {
         'b' => 1,
@@ -362,7 +524,14 @@ pub fn encode_format_spec(spec: &ParsedFormatSpec) -> u64 {
         _ => 0,
     });
 
-    fill | (align << 8) | (sign << 11) | (zero_pad << 13) | (width << 14) | (precision << 30) | (type_char << 46)
+    fill | (align << 8)
+        | (sign << 11)
+        | (zero_pad << 13)
+        | (width << 14)
+        | (precision << 30)
+        | (type_char << 46)
+        | (alternate << 51)
+        | (grouping << 52)
 }
 
 /// Decodes a u64 back into a ParsedFormatSpec.
@@ -378,6 +547,12 @@ pub fn decode_format_spec(encoded: u64) -> ParsedFormatSpec {
     let width = ((encoded >> 14) & 0xFFFF) as usize;
     let precision_raw = ((encoded >> 30) & 0xFFFF) as usize;
     let type_bits = ((encoded >> 46) & 0x1F) as u8;
+    let alternate = ((encoded >> 51) & 0x01) != 0;
+    let grouping = match (encoded >> 52) & 0x03 {
+        1 => Some(','),
+        2 => Some('_'),
+        _ => None,
+    };
 
     let align = match align_bits {
         1 => Some('<'),
@@ -423,8 +598,10 @@ pub fn decode_format_spec(encoded: u64) -> ParsedFormatSpec {
         fill,
         align,
         sign,
+        alternate,
         zero_pad,
         width,
+        grouping,
         precision,
         type_char,
     }
@@ -440,14 +617,21 @@ pub fn decode_format_spec(encoded: u64) -> ParsedFormatSpec {
 /// 1. Truncation: If `precision` is set, limits the string to that many characters
 /// 2. Alignment: Pads to `width` using `fill` character (default left-aligned for strings)
 ///
-/// Returns an error if `=` alignment is used (sign-aware padding only valid for numbers).
+/// Returns an error for number-only options: a sign, `#`, grouping, or `=` alignment.
 pub fn format_string(value: &str, spec: &ParsedFormatSpec) -> Result<String, FormatError> {
-    // Handle precision (string truncation)
-    let value = if let Some(prec) = spec.precision {
-        value.chars().take(prec).collect::<String>()
-    } else {
-        value.to_owned()
-    };
+    if spec.sign.is_some() {
+        return Err(FormatError::ValueError(
+            "Sign not allowed in string format specifier".to_owned(),
+        ));
+    }
+    if spec.alternate {
+        return Err(FormatError::ValueError(
+            "Alternate form (#) not allowed in string format specifier".to_owned(),
+        ));
+    }
+    if let Some(sep) = spec.grouping {
+        return Err(FormatError::ValueError(format!("Cannot specify '{sep}' with 's'.")));
+    }
 
     // Validate alignment for strings (= is only for numbers)
     if spec.align == Some('=') {
@@ -456,73 +640,77 @@ pub fn format_string(value: &str, spec: &ParsedFormatSpec) -> Result<String, For
         ));
     }
 
-    // Default alignment for strings is left ('<')
-    let align = spec.align.unwrap_or('<');
-    Ok(pad_string(&value, spec.width, align, spec.fill))
-}
-
-/// Formats an integer in decimal with a format specification.
-///
-/// Applies the following:
-/// - Sign prefix based on `sign` spec: `+` (always show), `-` (negatives only), ` ` (space for positive)
-/// - Zero-padding: When `zero_pad` is true or `=` alignment, inserts zeros between sign and digits
-/// - Alignment: Right-aligned by default for numbers, pads to `width` with `fill` character
-pub fn format_int(n: i64, spec: &ParsedFormatSpec) -> String {
-    let is_negative = n < 0;
-    let abs_str = n.abs().to_string();
-
-    // Build the sign prefix
-    let sign = if is_negative {
-        "-"
+    // Handle precision (string truncation)
+    let value = if let Some(prec) = spec.precision {
+        value.chars().take(prec).collect::<String>()
     } else {
-        match spec.sign {
-            Some('+') => "+",
-            Some(' ') => " ",
-            _ => "",
-        }
+        value.to_owned()
     };
 
-    // Default alignment for numbers is right ('>')
-    let align = spec.align.unwrap_or('>');
-
-    // Handle sign-aware zero-padding or regular padding
-    if spec.zero_pad || align == '=' {
-        let fill = if spec.zero_pad { '0' } else { spec.fill };
-        let total_len = sign.len() + abs_str.len();
-        if spec.width > total_len {
-            let padding = spec.width - total_len;
-            let pad_str: String = std::iter::repeat_n(fill, padding).collect();
-            format!("{sign}{pad_str}{abs_str}")
-        } else {
-            format!("{sign}{abs_str}")
-        }
+    // Default alignment for strings is left ('<'); the '0' flag only changes the fill
+    let align = spec.align.unwrap_or('<');
+    let fill = if spec.zero_pad && spec.fill == ' ' {
+        '0'
     } else {
-        let value = format!("{sign}{abs_str}");
-        pad_string(&value, spec.width, align, spec.fill)
-    }
+        spec.fill
+    };
+    Ok(pad_string(&value, spec.width, align, fill))
 }
 
-/// Formats an integer in binary (base 2), octal (base 8), or hexadecimal (base 16).
+/// Formats an `i64` with an integer presentation type: `d`, `n`, `b`, `o`, `x`, `X` or none.
 ///
-/// Used for format types `b`, `o`, `x`, and `X`. The sign is prepended for negative numbers.
-/// Does not include base prefixes like `0b`, `0o`, `0x` (those require the `#` flag which
-/// is not yet implemented). Returns an error for invalid base values.
-pub fn format_int_base(n: i64, base: u32, spec: &ParsedFormatSpec) -> Result<String, FormatError> {
-    let is_negative = n < 0;
-    let abs_val = n.unsigned_abs();
+/// See [`format_int_digits`] for the shared sign, prefix, grouping and padding handling.
+pub fn format_int(n: i64, spec: &ParsedFormatSpec) -> Result<String, FormatError> {
+    let magnitude = n.unsigned_abs();
+    format_int_digits(n < 0, spec, |radix| match radix {
+        2 => format!("{magnitude:b}"),
+        8 => format!("{magnitude:o}"),
+        16 => format!("{magnitude:x}"),
+        _ => magnitude.to_string(),
+    })
+}
 
-    let abs_str = match base {
-        2 => format!("{abs_val:b}"),
-        8 => format!("{abs_val:o}"),
-        16 => format!("{abs_val:x}"),
-        _ => return Err(FormatError::ValueError("Invalid base".to_owned())),
-    };
+/// Formats an arbitrary precision integer with an integer presentation type.
+///
+/// Same as [`format_int`] but for values that have been promoted to `LongInt`.
+pub fn format_big_int(n: &BigInt, spec: &ParsedFormatSpec) -> Result<String, FormatError> {
+    format_int_digits(n.sign() == Sign::Minus, spec, |radix| n.magnitude().to_str_radix(radix))
+}
 
-    let sign = if is_negative { "-" } else { "" };
-    let value = format!("{sign}{abs_str}");
+/// Shared implementation of integer formatting.
+///
+/// `render` produces the magnitude's digits in the requested radix. Applies:
+/// - Base selection from the type: `b` (2), `o` (8), `x`/`X` (16), otherwise decimal
+/// - Base prefixes `0b`, `0o`, `0x`/`0X` when the `#` flag is set
+/// - Grouping every 3 decimal digits, or every 4 digits for the other bases
+/// - Sign, zero-padding and alignment via [`finish_number`]
+///
+/// Returns an error if a precision is given, which integers don't support.
+fn format_int_digits(
+    is_negative: bool,
+    spec: &ParsedFormatSpec,
+    render: impl FnOnce(u32) -> String,
+) -> Result<String, FormatError> {
+    if spec.precision.is_some() {
+        return Err(FormatError::ValueError(
+            "Precision not allowed in integer format specifier".to_owned(),
+        ));
+    }
 
-    let align = spec.align.unwrap_or('>');
-    Ok(pad_string(&value, spec.width, align, spec.fill))
+    let (radix, prefix) = match spec.type_char {
+        Some('b') => (2, "0b"),
+        Some('o') => (8, "0o"),
+        Some('x') => (16, "0x"),
+        Some('X') => (16, "0X"),
+        _ => (10, ""),
+    };
+    let mut digits = render(radix);
+    if spec.type_char == Some('X') {
+        digits.make_ascii_uppercase();
+    }
+    let prefix = if spec.alternate { prefix } else { "" };
+    let group_size = if radix == 10 { 3 } else { 4 };
+    Ok(finish_number(is_negative, prefix, &digits, "", group_size, spec))
 }
 
 /// Formats an integer as a Unicode character (format type `c`).
@@ -541,44 +729,35 @@ pub fn format_char(n: i64, spec: &ParsedFormatSpec) -> Result<String, FormatErro
     Ok(pad_string(&value, spec.width, align, spec.fill))
 }
 
+/// Formats a float with no presentation type and no precision (e.g. `f"{x:>10}"`, `f"{x:,}"`).
+///
+/// `repr` is `str()` of the float's absolute value; this adds the sign, grouping and padding
+/// so the output matches `str(x)` when no other options are given.
+pub fn format_float_repr(is_negative: bool, repr: &str, spec: &ParsedFormatSpec) -> String {
+    let (int_part, rest) = split_int_part(repr);
+    finish_number(is_negative, "", int_part, rest, 3, spec)
+}
+
 /// Formats a float in fixed-point notation (format types `f` and `F`).
 ///
-/// Always includes a decimal point with `precision` digits after it (default 6).
-/// Handles sign prefix, zero-padding between sign and digits when `zero_pad` or `=` alignment.
-/// Right-aligned by default. NaN and infinity are formatted as `nan`/`inf` (or `NAN`/`INF` for `F`).
-pub fn format_float_f(f: f64, spec: &ParsedFormatSpec) -> String {
+/// Always includes a decimal point with `precision` digits after it (default 6); with the `#`
+/// flag the decimal point is kept even for a precision of 0. NaN and infinity are formatted
+/// as `nan`/`inf`, or `NAN`/`INF` when `uppercase` is set (the `F` type).
+pub fn format_float_f(f: f64, spec: &ParsedFormatSpec, uppercase: bool) -> String {
     let precision = spec.precision.unwrap_or(6);
-    let is_negative = f.is_sign_negative() && !f.is_nan();
-    let abs_val = f.abs();
-
-    let abs_str = format!("{abs_val:.precision$}");
-
-    let sign = if is_negative {
-        "-"
-    } else {
-        match spec.sign {
-            Some('+') => "+",
-            Some(' ') => " ",
-            _ => "",
-        }
+    let mut abs_str = match non_finite_str(f) {
+        Some(s) => s.to_owned(),
+        None => format!("{:.precision$}", f.abs()),
     };
-
-    let align = spec.align.unwrap_or('>');
-
-    if spec.zero_pad || align == '=' {
-        let fill = if spec.zero_pad { '0' } else { spec.fill };
-        let total_len = sign.len() + abs_str.len();
-        if spec.width > total_len {
-            let padding = spec.width - total_len;
-            let pad_str: String = std::iter::repeat_n(fill, padding).collect();
-            format!("{sign}{pad_str}{abs_str}")
-        } else {
-            format!("{sign}{abs_str}")
-        }
-    } else {
-        let value = format!("{sign}{abs_str}");
-        pad_string(&value, spec.width, align, spec.fill)
+    if spec.alternate && precision == 0 && f.is_finite() {
+        abs_str.push('.');
+    }
+    if uppercase {
+        abs_str.make_ascii_uppercase();
     }
+
+    let (int_part, rest) = split_int_part(&abs_str);
+    finish_number(is_float_negative(f), "", int_part, rest, 3, spec)
 }
 
 /// Formats a float in exponential/scientific notation (format types `e` and `E`).
@@ -588,83 +767,93 @@ pub fn format_float_f(f: f64, spec: &ParsedFormatSpec) -> String {
 /// Exponent is always formatted with a sign and at least 2 digits (Python convention).
 pub fn format_float_e(f: f64, spec: &ParsedFormatSpec, uppercase: bool) -> String {
     let precision = spec.precision.unwrap_or(6);
-    let is_negative = f.is_sign_negative() && !f.is_nan();
-    let abs_val = f.abs();
-
-    let abs_str = if uppercase {
-        format!("{abs_val:.precision$E}")
-    } else {
-        format!("{abs_val:.precision$e}")
-    };
-
-    // Fix exponent format to match Python (e+03 not e3)
-    let abs_str = fix_exp_format(&abs_str);
-
-    let sign = if is_negative {
-        "-"
-    } else {
-        match spec.sign {
-            Some('+') => "+",
-            Some(' ') => " ",
-            _ => "",
-        }
+    let mut abs_str = match non_finite_str(f) {
+        Some(s) => s.to_owned(),
+        // Fix exponent format to match Python (e+03 not e3)
+        None => fix_exp_format(&format!("{:.precision$e}", f.abs())),
     };
+    if spec.alternate
+        && precision == 0
+        && let Some(e_pos) = abs_str.find('e')
+    {
+        abs_str.insert(e_pos, '.');
+    }
+    if uppercase {
+        abs_str.make_ascii_uppercase();
+    }
 
-    let value = format!("{sign}{abs_str}");
-    let align = spec.align.unwrap_or('>');
-    pad_string(&value, spec.width, align, spec.fill)
+    let (int_part, rest) = split_int_part(&abs_str);
+    finish_number(is_float_negative(f), "", int_part, rest, 3, spec)
 }
 
-/// Formats a float in "general" format (format types `g` and `G`).
+/// Formats a float in "general" format (format types `g`, `G` and `n`).
 ///
 /// Chooses between fixed-point and exponential notation based on the magnitude:
 /// - Uses exponential if exponent < -4 or >= precision
 /// - Otherwise uses fixed-point notation
 ///
-/// Unlike `f` and `e` formats, trailing zeros are stripped from the result.
-/// Default precision is 6, but minimum is 1 significant digit.
-pub fn format_float_g(f: f64, spec: &ParsedFormatSpec) -> String {
+/// Unlike `f` and `e` formats, trailing zeros are stripped from the result unless the `#`
+/// flag is set. Default precision is 6, but minimum is 1 significant digit.
+///
+/// `no_type` selects the behavior of a spec with a precision but no type (e.g. `.3`), which
+/// is like `g` except that fixed-point output always keeps at least one fractional digit
+/// (switching to exponential notation one digit earlier to make room for it).
+pub fn format_float_g(f: f64, spec: &ParsedFormatSpec, uppercase: bool, no_type: bool) -> String {
     let precision = spec.precision.unwrap_or(6).max(1);
-    let is_negative = f.is_sign_negative() && !f.is_nan();
     let abs_val = f.abs();
 
-    // Python's g format: use exponential if exponent < -4 or >= precision
-    let exp = if abs_val == 0.0 {
-        0
-    } else {
-        // log10 of valid floats fits in i32; floor() returns a finite f64
-        f64_to_i32_trunc(abs_val.log10().floor())
-    };
-
-    // precision is typically small (default 6), safe to convert to i32
-    let prec_i32 = i32::try_from(precision).unwrap_or(i32::MAX);
-    let abs_str = if exp < -4 || exp >= prec_i32 {
-        // Use exponential notation
-        let exp_prec = precision.saturating_sub(1);
-        let formatted = format!("{abs_val:.exp_prec$e}");
-        // Python strips trailing zeros from the mantissa
-        strip_trailing_zeros_exp(&formatted)
+    let mut abs_str = if let Some(s) = non_finite_str(f) {
+        s.to_owned()
     } else {
-        // Use fixed notation - result is non-negative due to .max(0)
-        let sig_digits_i32 = (prec_i32 - exp - 1).max(0);
-        let sig_digits = usize::try_from(sig_digits_i32).expect("sig_digits guaranteed non-negative");
-        let formatted = format!("{abs_val:.sig_digits$}");
-        strip_trailing_zeros(&formatted)
-    };
+        // Python's g format: use exponential if exponent < -4 or >= precision
+        let exp = if abs_val == 0.0 {
+            0
+        } else {
+            // log10 of valid floats fits in i32; floor() returns a finite f64
+            f64_to_i32_trunc(abs_val.log10().floor())
+        };
 
-    let sign = if is_negative {
-        "-"
-    } else {
-        match spec.sign {
-            Some('+') => "+",
-            Some(' ') => " ",
-            _ => "",
+        // precision is typically small (default 6), safe to convert to i32
+        let prec_i32 = i32::try_from(precision).unwrap_or(i32::MAX);
+        // Without a type the extra fractional digit counts towards the precision
+        let exp_limit = if no_type { prec_i32 - 1 } else { prec_i32 };
+        if exp < -4 || exp >= exp_limit {
+            // Use exponential notation
+            let exp_prec = precision.saturating_sub(1);
+            let formatted = format!("{abs_val:.exp_prec$e}");
+            if spec.alternate {
+                fix_exp_format(&formatted)
+            } else {
+                // Python strips trailing zeros from the mantissa
+                strip_trailing_zeros_exp(&formatted)
+            }
+        } else {
+            // Use fixed notation - result is non-negative due to .max(0)
+            let sig_digits_i32 = (prec_i32 - exp - 1).max(0);
+            let sig_digits = usize::try_from(sig_digits_i32).expect("sig_digits guaranteed non-negative");
+            let formatted = format!("{abs_val:.sig_digits$}");
+            if spec.alternate {
+                if sig_digits == 0 {
+                    format!("{formatted}.")
+                } else {
+                    formatted
+                }
+            } else {
+                let stripped = strip_trailing_zeros(&formatted);
+                if no_type && !stripped.contains('.') {
+                    format!("{stripped}.0")
+                } else {
+                    stripped
+                }
+            }
         }
     };
+    if uppercase {
+        abs_str.make_ascii_uppercase();
+    }
 
-    let value = format!("{sign}{abs_str}");
-    let align = spec.align.unwrap_or('>');
-    pad_string(&value, spec.width, align, spec.fill)
+    let (int_part, rest) = split_int_part(&abs_str);
+    finish_number(is_float_negative(f), "", int_part, rest, 3, spec)
 }
 
 /// Applies ASCII conversion to a string (escapes non-ASCII characters).
@@ -699,11 +888,396 @@ pub fn ascii_escape(s: &str) -> String {
 pub fn format_float_percent(f: f64, spec: &ParsedFormatSpec) -> String {
     let precision = spec.precision.unwrap_or(6);
     let percent_val = f * 100.0;
-    let is_negative = percent_val.is_sign_negative() && !percent_val.is_nan();
-    let abs_val = percent_val.abs();
+    let mut abs_str = match non_finite_str(percent_val) {
+        Some(s) => s.to_owned(),
+        None => format!("{:.precision$}", percent_val.abs()),
+    };
+    if spec.alternate && precision == 0 && percent_val.is_finite() {
+        abs_str.push('.');
+    }
+    abs_str.push('%');
+
+    let (int_part, rest) = split_int_part(&abs_str);
+    finish_number(is_float_negative(percent_val), "", int_part, rest, 3, spec)
+}
 
-    let abs_str = format!("{abs_val:.precision$}%");
+// ============================================================================
+// printf-style formatting
+// ============================================================================
 
+/// Formats `template % args`, Python's printf-style string formatting.
+///
+/// A tuple supplies one item per conversion; any other value is a single item. A dict is
+/// additionally available for `%(key)s` conversions, and (as for lists) leaving it unused is
+/// not an error. Each conversion supports the flags `-+ #0`, a width and precision (either may be
+/// `*` to take it from the arguments), an ignored length modifier (`h`, `l`, `L`) and the
+/// types `d i u o x X e E f F g G c s r a`, plus `%%` for a literal percent sign.
+pub fn percent_format(
+    template: &str,
+    args: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<String, RunError> {
+    let (items, mapping): (&[Value], Option<&Dict>) = match args {
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Tuple(tuple) => (tuple.as_slice(), None),
+            HeapData::NamedTuple(tuple) => (tuple.as_vec().as_slice(), None),
+            HeapData::Dict(dict) => (std::slice::from_ref(args), Some(dict)),
+            _ => (std::slice::from_ref(args), None),
+        },
+        _ => (std::slice::from_ref(args), None),
+    };
+    // CPython skips the unused argument check for any mapping-like right operand
+    let is_mapping_like = mapping.is_some()
+        || matches!(args, Value::Ref(id) if matches!(heap.get(*id), HeapData::List(_) | HeapData::Bytes(_) | HeapData::Range(_)));
+
+    let chars: Vec<char> = template.chars().collect();
+    let mut next_item = 0;
+    let mut result = String::with_capacity(template.len());
+    let mut guard = DepthGuard::default();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        pos += 1;
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        if chars.get(pos) == Some(&'%') {
+            result.push('%');
+            pos += 1;
+            continue;
+        }
+
+        // `%(key)` selects the value from the mapping; parentheses may nest inside the key
+        let mut keyed_value = None;
+        if chars.get(pos) == Some(&'(') {
+            let Some(mapping) = mapping else {
+                return Err(ExcType::type_error_percent_format("format requires a mapping"));
+            };
+            let key_start = pos + 1;
+            let mut depth = 1;
+            pos = key_start;
+            while depth > 0 {
+                match chars.get(pos) {
+                    Some('(') => depth += 1,
+                    Some(')') => depth -= 1,
+                    Some(_) => {}
+                    None => return Err(ExcType::value_error_percent_format("incomplete format key")),
+                }
+                pos += 1;
+            }
+            let key: String = chars[key_start..pos - 1].iter().collect();
+            let Some(value) = mapping.get_by_str(&key, heap, interns) else {
+                return Err(ExcType::key_error_format_field(&key));
+            };
+            keyed_value = Some(value);
+            // Like CPython, a keyed conversion leaves nothing for unkeyed ones
+            next_item = items.len();
+        }
+
+        let mut spec = ParsedFormatSpec {
+            fill: ' ',
+            ..ParsedFormatSpec::default()
+        };
+        let mut left_align = false;
+        while let Some(&flag) = chars.get(pos) {
+            match flag {
+                '-' => left_align = true,
+                '+' => spec.sign = Some('+'),
+                ' ' if spec.sign.is_none() => spec.sign = Some(' '),
+                ' ' => {}
+                '#' => spec.alternate = true,
+                '0' => spec.zero_pad = true,
+                _ => break,
+            }
+            pos += 1;
+        }
+
+        if chars.get(pos) == Some(&'*') {
+            pos += 1;
+            let width = star_arg(items, &mut next_item)?;
+            left_align |= width < 0;
+            spec.width = usize::try_from(width.unsigned_abs()).unwrap_or(usize::MAX);
+        } else {
+            spec.width = parse_percent_number(&chars, &mut pos, MAX_PERCENT_WIDTH)
+                .ok_or_else(|| ExcType::value_error_percent_format("width too big"))?;
+        }
+        if chars.get(pos) == Some(&'.') {
+            pos += 1;
+            if chars.get(pos) == Some(&'*') {
+                pos += 1;
+                let precision = star_arg(items, &mut next_item)?;
+                if precision > i64::from(i32::MAX) {
+                    return Err(SimpleException::new_msg(
+                        ExcType::OverflowError,
+                        "Python int too large to convert to C int",
+                    )
+                    .into());
+                }
+                spec.precision = Some(usize::try_from(precision).unwrap_or(0));
+            } else {
+                let precision = parse_percent_number(&chars, &mut pos, MAX_PERCENT_PRECISION)
+                    .ok_or_else(|| ExcType::value_error_percent_format("precision too big"))?;
+                spec.precision = Some(precision);
+            }
+        }
+        while matches!(chars.get(pos), Some('h' | 'l' | 'L')) {
+            pos += 1;
+        }
+
+        let Some(&type_char) = chars.get(pos) else {
+            return Err(ExcType::value_error_percent_format("incomplete format"));
+        };
+        pos += 1;
+
+        let value = match keyed_value {
+            Some(value) => value,
+            None => {
+                let Some(value) = items.get(next_item) else {
+                    return Err(ExcType::type_error_percent_format(
+                        "not enough arguments for format string",
+                    ));
+                };
+                next_item += 1;
+                value
+            }
+        };
+
+        if left_align {
+            spec.align = Some('<');
+            spec.zero_pad = false;
+        }
+        let formatted = percent_format_value(value, type_char, &mut spec, heap, &mut guard, interns)
+            .ok_or_else(|| unsupported_percent_char(type_char, pos - 1))??;
+        result.push_str(&formatted);
+    }
+
+    if next_item < items.len() && !is_mapping_like {
+        return Err(ExcType::type_error_percent_format(
+            "not all arguments converted during string formatting",
+        ));
+    }
+    Ok(result)
+}
+
+/// Creates the `ValueError` raised for an unknown `%` conversion type at character `index`.
+///
+/// Non-printable characters are shown as `?`, like CPython.
+fn unsupported_percent_char(c: char, index: usize) -> RunError {
+    let shown = if (' '..='~').contains(&c) { c } else { '?' };
+    ExcType::value_error_percent_format(format!(
+        "unsupported format character '{shown}' (0x{:x}) at index {index}",
+        u32::from(c)
+    ))
+}
+
+/// Formats a single `%` conversion, returning `None` for an unsupported conversion type.
+///
+/// `spec` holds the parsed flags, width and precision; this fills in the alignment and
+/// presentation type expected by the shared formatting functions.
+fn percent_format_value(
+    value: &Value,
+    type_char: char,
+    spec: &mut ParsedFormatSpec,
+    heap: &Heap<impl ResourceTracker>,
+    guard: &mut DepthGuard,
+    interns: &Interns,
+) -> Option<Result<String, RunError>> {
+    // Unlike str.format(), everything is right-aligned by default
+    let align = spec.align.unwrap_or('>');
+    // Numbers are zero-filled to the precision, while strings are only truncated by it
+    let precision = match type_char {
+        'd' | 'i' | 'u' | 'o' | 'x' | 'X' | 'e' | 'E' | 'f' | 'F' | 'g' | 'G' => spec.precision.unwrap_or(0),
+        'c' | 's' | 'r' | 'a' => 0,
+        _ => return None,
+    };
+    if let Err(err) = check_repeat_size(1, spec.width.max(precision), heap.tracker()) {
+        return Some(Err(err.into()));
+    }
+    let formatted = match type_char {
+        'd' | 'i' | 'u' | 'o' | 'x' | 'X' => percent_format_int(value, type_char, spec, heap),
+        'e' | 'E' | 'f' | 'F' | 'g' | 'G' => percent_to_float(value, heap).map(|f| {
+            spec.type_char = Some(type_char);
+            match type_char {
+                'e' | 'E' => format_float_e(f, spec, type_char == 'E'),
+                'f' | 'F' => format_float_f(f, spec, type_char == 'F'),
+                _ => format_float_g(f, spec, type_char == 'G', false),
+            }
+        }),
+        'c' => {
+            let c = match value {
+                Value::Int(n) => format_char(*n, &ParsedFormatSpec::default()),
+                Value::Bool(b) => format_char(i64::from(*b), &ParsedFormatSpec::default()),
+                Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+                    Err(FormatError::Overflow("%c arg not in range(0x110000)".to_owned()))
+                }
+                _ => match value.as_either_str(heap) {
+                    Some(s) if s.as_str(interns).chars().count() == 1 => Ok(s.as_str(interns).to_owned()),
+                    _ => return Some(Err(ExcType::type_error_percent_format("%c requires int or char"))),
+                },
+            };
+            c.map(|c| pad_string(&c, spec.width, align, ' ')).map_err(Into::into)
+        }
+        's' | 'r' | 'a' => {
+            let s = match type_char {
+                's' => value.py_str(heap, guard, interns).into_owned(),
+                'r' => value.py_repr(heap, guard, interns).into_owned(),
+                _ => ascii_escape(&value.py_repr(heap, guard, interns)),
+            };
+            let s = match spec.precision {
+                Some(precision) => s.chars().take(precision).collect(),
+                None => s,
+            };
+            Ok(pad_string(&s, spec.width, align, ' '))
+        }
+        _ => return None,
+    };
+    Some(formatted)
+}
+
+/// Formats an integer conversion (`%d`, `%i`, `%u`, `%o`, `%x`, `%X`).
+///
+/// The decimal types also accept floats, which are truncated towards zero. The precision is
+/// the minimum number of digits, so `'%.3d' % 5` gives `005`.
+fn percent_format_int(
+    value: &Value,
+    type_char: char,
+    spec: &ParsedFormatSpec,
+    heap: &Heap<impl ResourceTracker>,
+) -> Result<String, RunError> {
+    let decimal = matches!(type_char, 'd' | 'i' | 'u');
+    let n: Cow<'_, BigInt> = match (value, long_int_ref(value, heap)) {
+        (_, Some(big)) => Cow::Borrowed(big),
+        (Value::Int(n), _) => Cow::Owned(BigInt::from(*n)),
+        (Value::Bool(b), _) => Cow::Owned(BigInt::from(u8::from(*b))),
+        (Value::Float(f), _) if decimal => {
+            if f.is_nan() {
+                return Err(
+                    SimpleException::new_msg(ExcType::ValueError, "cannot convert float NaN to integer").into(),
+                );
+            }
+            let truncated = BigInt::from_f64(f.trunc()).ok_or_else(|| {
+                RunError::from(SimpleException::new_msg(
+                    ExcType::OverflowError,
+                    "cannot convert float infinity to integer",
+                ))
+            })?;
+            Cow::Owned(truncated)
+        }
+        _ => {
+            let required = if decimal { "a real number" } else { "an integer" };
+            return Err(ExcType::type_error_percent_format(format!(
+                "%{type_char} format: {required} is required, not {}",
+                value.py_type(heap)
+            )));
+        }
+    };
+
+    let (radix, prefix) = match type_char {
+        'o' => (8, "0o"),
+        'x' => (16, "0x"),
+        'X' => (16, "0X"),
+        _ => (10, ""),
+    };
+    let mut digits = n.magnitude().to_str_radix(radix);
+    if type_char == 'X' {
+        digits.make_ascii_uppercase();
+    }
+    if let Some(precision) = spec.precision
+        && digits.len() < precision
+    {
+        digits.insert_str(0, &"0".repeat(precision - digits.len()));
+    }
+    let prefix = if spec.alternate { prefix } else { "" };
+    let spec = ParsedFormatSpec {
+        precision: None,
+        ..spec.clone()
+    };
+    Ok(finish_number(n.sign() == Sign::Minus, prefix, &digits, "", 3, &spec))
+}
+
+/// Converts the argument of a float conversion (`%e`, `%f`, `%g`, ...) to a float.
+fn percent_to_float(value: &Value, heap: &Heap<impl ResourceTracker>) -> Result<f64, RunError> {
+    match (value, long_int_ref(value, heap)) {
+        (Value::Float(f), _) => Ok(*f),
+        (Value::Int(n), _) => Ok(*n as f64),
+        (Value::Bool(b), _) => Ok(f64::from(u8::from(*b))),
+        (_, Some(big)) => big.to_f64().filter(|f| f.is_finite()).ok_or_else(|| {
+            SimpleException::new_msg(ExcType::OverflowError, "int too large to convert to float").into()
+        }),
+        _ => Err(ExcType::type_error_percent_format(format!(
+            "must be real number, not {}",
+            value.py_type(heap)
+        ))),
+    }
+}
+
+/// Returns the value's arbitrary precision integer if it is a heap `LongInt`.
+fn long_int_ref<'h>(value: &Value, heap: &'h Heap<impl ResourceTracker>) -> Option<&'h BigInt> {
+    match value {
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) => Some(&li.0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Takes the next argument as a `*` width or precision, which must be an int.
+fn star_arg(items: &[Value], next_item: &mut usize) -> Result<i64, RunError> {
+    let Some(value) = items.get(*next_item) else {
+        return Err(ExcType::type_error_percent_format(
+            "not enough arguments for format string",
+        ));
+    };
+    *next_item += 1;
+    match value {
+        Value::Int(n) => Ok(*n),
+        Value::Bool(b) => Ok(i64::from(*b)),
+        _ => Err(ExcType::type_error_percent_format("* wants int")),
+    }
+}
+
+/// Largest width accepted in a `%` template, CPython's `PY_SSIZE_T_MAX`.
+const MAX_PERCENT_WIDTH: usize = isize::MAX.unsigned_abs();
+
+/// Largest precision accepted in a `%` template, CPython's `INT_MAX`.
+const MAX_PERCENT_PRECISION: usize = i32::MAX.unsigned_abs() as usize;
+
+/// Parses a run of decimal digits at `pos` (a width or precision), returning 0 if there are none.
+///
+/// Returns `None` if the number is larger than `max`.
+fn parse_percent_number(chars: &[char], pos: &mut usize, max: usize) -> Option<usize> {
+    let mut n: usize = 0;
+    while let Some(digit) = chars.get(*pos).and_then(|c| c.to_digit(10)) {
+        n = n.checked_mul(10)?.checked_add(digit as usize).filter(|&n| n <= max)?;
+        *pos += 1;
+    }
+    Some(n)
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Assembles a formatted number from its sign, base prefix and rendered magnitude.
+///
+/// `int_digits` is the integer part of the magnitude, which gets `spec.grouping` inserted
+/// every `group_size` digits; `rest` (fraction, exponent, `%`) follows it unchanged.
+///
+/// The `0` flag without an explicit alignment means sign-aware zero padding, where (like
+/// `=` alignment) the padding goes between the sign/prefix and the digits. Zero padding is
+/// grouped too, so `format(1234, '08,')` gives `0,001,234`. Otherwise numbers are right-aligned.
+fn finish_number(
+    is_negative: bool,
+    prefix: &str,
+    int_digits: &str,
+    rest: &str,
+    group_size: usize,
+    spec: &ParsedFormatSpec,
+) -> String {
     let sign = if is_negative {
         "-"
     } else {
@@ -713,15 +1287,76 @@ pub fn format_float_percent(f: f64, spec: &ParsedFormatSpec) -> String {
             _ => "",
         }
     };
+    let (fill, align) = match spec.align {
+        None if spec.zero_pad => ('0', '='),
+        Some(align) if spec.zero_pad && spec.fill == ' ' => ('0', align),
+        align => (spec.fill, align.unwrap_or('>')),
+    };
 
-    let value = format!("{sign}{abs_str}");
-    let align = spec.align.unwrap_or('>');
-    pad_string(&value, spec.width, align, spec.fill)
+    let mut digits = group_digits(int_digits, spec.grouping, group_size);
+    if align != '=' {
+        return pad_string(&format!("{sign}{prefix}{digits}{rest}"), spec.width, align, fill);
+    }
+
+    let digits_width = spec
+        .width
+        .saturating_sub(sign.len() + prefix.len() + rest.chars().count());
+    if fill == '0'
+        && let Some(separator) = spec.grouping
+    {
+        let mut padded = int_digits.to_owned();
+        while digits.chars().count() < digits_width {
+            padded.insert(0, '0');
+            digits = group_digits(&padded, Some(separator), group_size);
+        }
+    } else {
+        digits = pad_string(&digits, digits_width, '>', fill);
+    }
+    format!("{sign}{prefix}{digits}{rest}")
 }
 
-// ============================================================================
-// Helper functions
-// ============================================================================
+/// Inserts `separator` between every `group_size` digits, counting from the right.
+fn group_digits(digits: &str, separator: Option<char>, group_size: usize) -> String {
+    let Some(separator) = separator else {
+        return digits.to_owned();
+    };
+    let len = digits.chars().count();
+    let mut result = String::with_capacity(len + len / group_size);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(group_size) {
+            result.push(separator);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Splits a rendered float magnitude into its leading integer digits and the remainder.
+///
+/// Only the integer digits are grouped and zero-padded. For `inf`/`nan` the integer
+/// part is empty, so padding still goes in front of them.
+fn split_int_part(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Returns Python's spelling of a non-finite float's magnitude, or `None` for finite values.
+///
+/// Rust renders NaN as `NaN`, while Python always uses `nan` (or `NAN` for uppercase types).
+fn non_finite_str(f: f64) -> Option<&'static str> {
+    if f.is_nan() {
+        Some("nan")
+    } else if f.is_infinite() {
+        Some("inf")
+    } else {
+        None
+    }
+}
+
+/// Returns whether a float should be formatted with a minus sign (true for `-0.0`, false for NaN).
+fn is_float_negative(f: f64) -> bool {
+    f.is_sign_negative() && !f.is_nan()
+}
 
 /// Pads a string to a given width with alignment.
 ///
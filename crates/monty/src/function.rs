@@ -1,6 +1,12 @@
 use std::fmt::Write;
 
-use crate::{bytecode::Code, expressions::Identifier, intern::Interns, namespace::NamespaceId, signature::Signature};
+use crate::{
+    bytecode::Code,
+    expressions::Identifier,
+    intern::{Interns, StringId},
+    namespace::{GLOBAL_NS_IDX, NamespaceId},
+    signature::Signature,
+};
 
 /// A defined function once compiled and ready for execution.
 ///
@@ -35,6 +41,11 @@ pub(crate) struct Function {
     pub signature: Signature,
     /// Size of the initial namespace (number of local variable slots).
     pub namespace_size: usize,
+    /// Name of each namespace slot from the prepare phase, empty for unnamed slots.
+    ///
+    /// Unlike the names recorded in `code`, which only cover the slots the body accesses,
+    /// these name every parameter and variable, for `locals()` and `dir()`.
+    pub slot_names: Vec<StringId>,
     /// Enclosing namespace slots for variables captured from enclosing scopes.
     ///
     /// At definition time: look up cell HeapId from enclosing namespace at each slot.
@@ -63,6 +74,11 @@ pub(crate) struct Function {
     /// immediately pushing a frame. The coroutine captures the bound arguments
     /// and starts execution only when awaited.
     pub is_async: bool,
+    /// Namespace holding this function's globals.
+    ///
+    /// `GLOBAL_NS_IDX` for functions of the main script; functions defined in an imported
+    /// Python module (and the module body itself) use the namespace reserved for that module.
+    pub globals: NamespaceId,
     /// Compiled bytecode for this function body.
     pub code: Code,
 }
@@ -87,6 +103,7 @@ impl Function {
         name: Identifier,
         signature: Signature,
         namespace_size: usize,
+        slot_names: Vec<StringId>,
         free_var_enclosing_slots: Vec<NamespaceId>,
         cell_var_count: usize,
         cell_param_indices: Vec<Option<usize>>,
@@ -98,11 +115,13 @@ impl Function {
             name,
             signature,
             namespace_size,
+            slot_names,
             free_var_enclosing_slots,
             cell_var_count,
             cell_param_indices,
             defaults_count,
             is_async,
+            globals: GLOBAL_NS_IDX,
             code,
         }
     }
@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    collections::hash_map::DefaultHasher,
+    collections::hash_map::{DefaultHasher, Entry},
     fmt::Write,
     hash::{Hash, Hasher},
     mem::{ManuallyDrop, discriminant, size_of},
@@ -8,7 +8,7 @@ use std::{
     vec,
 };
 
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 use num_integer::Integer;
 use smallvec::SmallVec;
 
@@ -16,30 +16,46 @@ use crate::{
     args::ArgValues,
     asyncio::{Coroutine, GatherFuture, GatherItem},
     bytecode::VM,
-    exception_private::{ExcType, RunResult, SimpleException},
-    intern::{FunctionId, Interns, StringId},
-    resource::{DepthGuard, ResourceError, ResourceTracker, check_mult_size, check_repeat_size},
+    exception_private::{ExcClassInfo, ExcType, RunError, RunResult, SimpleException},
+    intern::{ExcClassId, FunctionId, Interns, StringId},
+    modules::random::Rng,
+    resource::{
+        DepthGuard, GcKind, GcStats, HeapSlotStats, ResourceError, ResourceTracker, check_mult_size, check_repeat_size,
+        monotonic_clock,
+    },
+    trace::TraceHook,
     types::{
-        AttrCallResult, Bytes, Dataclass, Dict, FrozenSet, List, LongInt, Module, MontyIter, NamedTuple, Path, PyTrait,
-        Range, Set, Slice, Str, Tuple, Type, allocate_tuple,
+        AttrCallResult, ByteArray, Bytes, ContextManager, Dataclass, Date, DateTime, Deque, Dict, DictView,
+        ExternalObject, FrozenSet, List, LongInt, LruCache, Module, MontyIter, NamedTuple, NamedTupleType, Partial,
+        Path, PyTrait, Range, ReMatch, RePattern, Set, Slice, Str, TimeDelta, Tuple, Type, allocate_tuple,
     },
     value::{EitherStr, Value},
 };
 
 /// Unique identifier for values stored inside the heap arena.
+///
+/// Pairs the slot index with the slot's generation at allocation time. Slots are reused
+/// once freed, and each reuse bumps the generation, so an ID held past its value's
+/// lifetime can be told apart from the ID of whatever now occupies the slot.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
-pub struct HeapId(usize);
+pub struct HeapId {
+    index: u32,
+    generation: u32,
+}
 
 impl HeapId {
     /// Returns the raw index value.
     #[inline]
     pub fn index(self) -> usize {
-        self.0
+        self.index as usize
     }
 }
 
 /// The empty tuple is a singleton which is allocated at startup.
-const EMPTY_TUPLE_ID: HeapId = HeapId(0);
+const EMPTY_TUPLE_ID: HeapId = HeapId {
+    index: 0,
+    generation: 0,
+};
 
 /// HeapData captures every runtime value that must live in the arena.
 ///
@@ -53,10 +69,19 @@ const EMPTY_TUPLE_ID: HeapId = HeapId(0);
 pub(crate) enum HeapData {
     Str(Str),
     Bytes(Bytes),
+    /// A mutable byte sequence (`bytearray`).
+    ///
+    /// Unlike `Bytes`, it is never hashable and supports in-place modification.
+    ByteArray(ByteArray),
     List(List),
     Tuple(Tuple),
     NamedTuple(NamedTuple),
     Dict(Dict),
+    /// A live view over a dict's keys, values or items (`dict.keys()` etc.).
+    ///
+    /// Holds a reference to the dict rather than a copy of its entries, so the view
+    /// reflects later mutations of the dict.
+    DictView(DictView),
     Set(Set),
     FrozenSet(FrozenSet),
     /// A closure: a function that captures variables from enclosing scopes.
@@ -131,14 +156,42 @@ pub(crate) enum HeapData {
     /// Pure methods (name, parent, etc.) are handled directly by the VM.
     /// I/O methods (exists, read_text, etc.) yield external function calls.
     Path(Path),
+    /// A built-in context manager from `contextlib` (e.g. `suppress(ValueError)`).
+    ///
+    /// Responds to the `__enter__`/`__exit__` calls emitted for `with` statements.
+    ContextManager(ContextManager),
+    /// A compiled regular expression from `re.compile()`.
+    ///
+    /// Immutable, so attribute calls clone it instead of taking it out of the heap.
+    RePattern(RePattern),
+    /// A regular expression match, holding the subject string and its pattern.
+    ReMatch(ReMatch),
+    /// A calendar date from `datetime.date`.
+    Date(Date),
+    /// A date and time from `datetime.datetime`.
+    DateTime(DateTime),
+    /// A duration from `datetime.timedelta`.
+    TimeDelta(TimeDelta),
+    /// An opaque host object whose attributes and methods are resolved by the host.
+    ExternalObject(ExternalObject),
+    /// A `functools.partial` object, called by the VM with its stored arguments.
+    Partial(Partial),
+    /// A `functools.lru_cache` wrapper, called by the VM through its result cache.
+    LruCache(LruCache),
+    /// A double-ended queue from `collections.deque`.
+    Deque(Deque),
+    /// A named tuple class from `collections.namedtuple()`, called by the VM to create
+    /// `NamedTuple` instances.
+    NamedTupleType(NamedTupleType),
 }
 
 impl HeapData {
     /// Returns whether this heap data type can participate in reference cycles.
     ///
     /// Only container types that can hold references to other heap objects need to be
-    /// tracked for GC purposes. Leaf types like Str, Bytes, Range, and Exception cannot
-    /// form cycles and should not count toward the GC allocation threshold.
+    /// tracked for GC purposes. Leaf types like Str, Bytes, and Range cannot form cycles
+    /// and should not count toward the GC allocation threshold. Exceptions only form cycles
+    /// through `raise ... from`, which marks the potential cycle itself.
     ///
     /// This optimization allows programs that allocate many leaf objects (like strings)
     /// to avoid triggering unnecessary GC cycles.
@@ -150,6 +203,7 @@ impl HeapData {
                 | Self::Tuple(_)
                 | Self::NamedTuple(_)
                 | Self::Dict(_)
+                | Self::DictView(_)
                 | Self::Set(_)
                 | Self::FrozenSet(_)
                 | Self::Closure(_, _, _)
@@ -160,6 +214,12 @@ impl HeapData {
                 | Self::Module(_)
                 | Self::Coroutine(_)
                 | Self::GatherFuture(_)
+                | Self::ContextManager(_)
+                | Self::ReMatch(_)
+                | Self::Partial(_)
+                | Self::LruCache(_)
+                | Self::Deque(_)
+                | Self::NamedTupleType(_)
         )
     }
 
@@ -177,6 +237,8 @@ impl HeapData {
             Self::Tuple(tuple) => tuple.contains_refs(),
             Self::NamedTuple(nt) => nt.contains_refs(),
             Self::Dict(dict) => dict.has_refs(),
+            // Views always reference their dict
+            Self::DictView(_) => true,
             Self::Set(set) => set.has_refs(),
             Self::FrozenSet(fset) => fset.has_refs(),
             // Closures always have refs when they have captured cells (HeapIds)
@@ -203,14 +265,41 @@ impl HeapData {
                         .iter()
                         .any(|r| r.as_ref().is_some_and(|v| matches!(v, Value::Ref(_))))
             }
+            Self::ContextManager(cm) => cm.values().iter().any(|v| matches!(v, Value::Ref(_))),
+            // Matches always reference their pattern
+            Self::ReMatch(_) => true,
+            Self::Partial(partial) => partial.has_refs(),
+            Self::LruCache(lru) => lru.has_refs(),
+            Self::Deque(deque) => deque.contains_refs(),
+            Self::NamedTupleType(nt_type) => nt_type.has_refs(),
+            // Exceptions reference their `__cause__`
+            Self::Exception(exc) => exc.cause_id().is_some(),
             // Leaf types cannot have refs
             Self::Str(_)
             | Self::Bytes(_)
+            | Self::ByteArray(_)
             | Self::Range(_)
             | Self::Slice(_)
-            | Self::Exception(_)
             | Self::LongInt(_)
-            | Self::Path(_) => false,
+            | Self::Path(_)
+            | Self::RePattern(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::ExternalObject(_) => false,
+        }
+    }
+
+    /// Returns the contents of `bytes` or `bytearray` data.
+    ///
+    /// Used wherever CPython accepts any bytes-like object (concatenation, comparison,
+    /// `in`, and the arguments of bytes methods).
+    #[inline]
+    pub fn as_bytes_like(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(b) => Some(b.as_slice()),
+            Self::ByteArray(b) => Some(b.as_slice()),
+            _ => None,
         }
     }
 
@@ -298,17 +387,58 @@ impl HeapData {
                 path.as_str().hash(&mut hasher);
                 Some(hasher.finish())
             }
+            // Patterns are immutable and hash like they compare, by source and flags
+            Self::RePattern(pattern) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                pattern.pattern().hash(&mut hasher);
+                pattern.flags().hash(&mut hasher);
+                Some(hasher.finish())
+            }
+            // Dates, datetimes and timedeltas are immutable and hash by value
+            Self::Date(date) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                date.hash(&mut hasher);
+                Some(hasher.finish())
+            }
+            Self::DateTime(dt) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                dt.hash(&mut hasher);
+                Some(hasher.finish())
+            }
+            Self::TimeDelta(td) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                td.hash(&mut hasher);
+                Some(hasher.finish())
+            }
+            Self::ExternalObject(obj) => {
+                let mut hasher = DefaultHasher::new();
+                discriminant(self).hash(&mut hasher);
+                obj.hash(&mut hasher);
+                Some(hasher.finish())
+            }
             // Mutable types, exceptions, iterators, modules, and async types cannot be hashed
-            // (Cell is handled specially in get_or_compute_hash)
+            // (Cell and NamedTupleType are handled specially in get_or_compute_hash)
             Self::List(_)
+            | Self::ByteArray(_)
             | Self::Dict(_)
+            | Self::DictView(_)
             | Self::Set(_)
             | Self::Cell(_)
+            | Self::NamedTupleType(_)
             | Self::Exception(_)
             | Self::Iter(_)
             | Self::Module(_)
             | Self::Coroutine(_)
-            | Self::GatherFuture(_) => None,
+            | Self::GatherFuture(_)
+            | Self::ContextManager(_)
+            | Self::ReMatch(_)
+            | Self::Partial(_)
+            | Self::LruCache(_)
+            | Self::Deque(_) => None,
             // LongInt is immutable and hashable
             Self::LongInt(li) => Some(li.hash()),
         }
@@ -324,10 +454,12 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_type(heap),
             Self::Bytes(b) => b.py_type(heap),
+            Self::ByteArray(b) => b.py_type(heap),
             Self::List(l) => l.py_type(heap),
             Self::Tuple(t) => t.py_type(heap),
             Self::NamedTuple(nt) => nt.py_type(heap),
             Self::Dict(d) => d.py_type(heap),
+            Self::DictView(v) => v.py_type(heap),
             Self::Set(s) => s.py_type(heap),
             Self::FrozenSet(fs) => fs.py_type(heap),
             Self::Closure(_, _, _) | Self::FunctionDefaults(_, _) => Type::Function,
@@ -342,6 +474,17 @@ impl PyTrait for HeapData {
             Self::Module(_) => Type::Module,
             Self::Coroutine(_) | Self::GatherFuture(_) => Type::Coroutine,
             Self::Path(p) => p.py_type(heap),
+            Self::ContextManager(cm) => cm.py_type(heap),
+            Self::RePattern(p) => p.py_type(heap),
+            Self::ReMatch(m) => m.py_type(heap),
+            Self::Date(d) => d.py_type(heap),
+            Self::DateTime(dt) => dt.py_type(heap),
+            Self::TimeDelta(td) => td.py_type(heap),
+            Self::ExternalObject(obj) => obj.py_type(heap),
+            Self::Partial(p) => p.py_type(heap),
+            Self::LruCache(lru) => lru.py_type(heap),
+            Self::Deque(deque) => deque.py_type(heap),
+            Self::NamedTupleType(nt_type) => nt_type.py_type(heap),
         }
     }
 
@@ -349,10 +492,12 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_estimate_size(),
             Self::Bytes(b) => b.py_estimate_size(),
+            Self::ByteArray(b) => b.py_estimate_size(),
             Self::List(l) => l.py_estimate_size(),
             Self::Tuple(t) => t.py_estimate_size(),
             Self::NamedTuple(nt) => nt.py_estimate_size(),
             Self::Dict(d) => d.py_estimate_size(),
+            Self::DictView(v) => v.py_estimate_size(),
             Self::Set(s) => s.py_estimate_size(),
             Self::FrozenSet(fs) => fs.py_estimate_size(),
             // TODO: should include size of captured cells and defaults
@@ -377,6 +522,17 @@ impl PyTrait for HeapData {
                     + gather.pending_calls.len() * std::mem::size_of::<crate::asyncio::CallId>()
             }
             Self::Path(p) => p.py_estimate_size(),
+            Self::ContextManager(cm) => cm.py_estimate_size(),
+            Self::RePattern(p) => p.py_estimate_size(),
+            Self::ReMatch(m) => m.py_estimate_size(),
+            Self::Date(d) => d.py_estimate_size(),
+            Self::DateTime(dt) => dt.py_estimate_size(),
+            Self::TimeDelta(td) => td.py_estimate_size(),
+            Self::ExternalObject(obj) => obj.py_estimate_size(),
+            Self::Partial(p) => p.py_estimate_size(),
+            Self::LruCache(lru) => lru.py_estimate_size(),
+            Self::Deque(deque) => deque.py_estimate_size(),
+            Self::NamedTupleType(nt_type) => nt_type.py_estimate_size(),
         }
     }
 
@@ -384,12 +540,15 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => PyTrait::py_len(s, heap, interns),
             Self::Bytes(b) => PyTrait::py_len(b, heap, interns),
+            Self::ByteArray(b) => PyTrait::py_len(b, heap, interns),
             Self::List(l) => PyTrait::py_len(l, heap, interns),
             Self::Tuple(t) => PyTrait::py_len(t, heap, interns),
             Self::NamedTuple(nt) => PyTrait::py_len(nt, heap, interns),
             Self::Dict(d) => PyTrait::py_len(d, heap, interns),
+            Self::DictView(v) => PyTrait::py_len(v, heap, interns),
             Self::Set(s) => PyTrait::py_len(s, heap, interns),
             Self::FrozenSet(fs) => PyTrait::py_len(fs, heap, interns),
+            Self::Deque(deque) => PyTrait::py_len(deque, heap, interns),
             Self::Range(r) => Some(r.len()),
             // Cells, Slices, Exceptions, Dataclasses, Iterators, LongInts, Modules, Paths, and async types don't have length
             Self::Cell(_)
@@ -403,7 +562,17 @@ impl PyTrait for HeapData {
             | Self::Module(_)
             | Self::Coroutine(_)
             | Self::GatherFuture(_)
-            | Self::Path(_) => None,
+            | Self::Path(_)
+            | Self::ContextManager(_)
+            | Self::RePattern(_)
+            | Self::ReMatch(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::ExternalObject(_)
+            | Self::Partial(_)
+            | Self::LruCache(_)
+            | Self::NamedTupleType(_) => None,
         }
     }
 
@@ -417,6 +586,11 @@ impl PyTrait for HeapData {
         match (self, other) {
             (Self::Str(a), Self::Str(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Bytes(a), Self::Bytes(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.py_eq(b, heap, guard, interns),
+            // bytes and bytearray compare equal by content (matching CPython behavior)
+            (Self::Bytes(_) | Self::ByteArray(_), Self::Bytes(_) | Self::ByteArray(_)) => {
+                Ok(self.as_bytes_like() == other.as_bytes_like())
+            }
             (Self::List(a), Self::List(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Tuple(a), Self::Tuple(b)) => a.py_eq(b, heap, guard, interns),
             (Self::NamedTuple(a), Self::NamedTuple(b)) => a.py_eq(b, heap, guard, interns),
@@ -438,8 +612,12 @@ impl PyTrait for HeapData {
                 Ok(true)
             }
             (Self::Dict(a), Self::Dict(b)) => a.py_eq(b, heap, guard, interns),
+            // Keys and items views compare like sets, against each other and against sets
+            (Self::DictView(a), _) => a.py_eq_set_like(other, heap, guard, interns),
+            (_, Self::DictView(b)) => b.py_eq_set_like(self, heap, guard, interns),
             (Self::Set(a), Self::Set(b)) => a.py_eq(b, heap, guard, interns),
             (Self::FrozenSet(a), Self::FrozenSet(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::Deque(a), Self::Deque(b)) => a.py_eq(b, heap, guard, interns),
             (Self::Closure(a_id, a_cells, _), Self::Closure(b_id, b_cells, _)) => {
                 Ok(*a_id == *b_id && a_cells == b_cells)
             }
@@ -458,7 +636,17 @@ impl PyTrait for HeapData {
             | (Self::Iter(_), Self::Iter(_))
             | (Self::Module(_), Self::Module(_))
             | (Self::Coroutine(_), Self::Coroutine(_))
-            | (Self::GatherFuture(_), Self::GatherFuture(_)) => Ok(false),
+            | (Self::GatherFuture(_), Self::GatherFuture(_))
+            | (Self::ContextManager(_), Self::ContextManager(_))
+            | (Self::ReMatch(_), Self::ReMatch(_))
+            | (Self::Partial(_), Self::Partial(_))
+            | (Self::LruCache(_), Self::LruCache(_))
+            | (Self::NamedTupleType(_), Self::NamedTupleType(_)) => Ok(false),
+            (Self::RePattern(a), Self::RePattern(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::Date(a), Self::Date(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::DateTime(a), Self::DateTime(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::TimeDelta(a), Self::TimeDelta(b)) => a.py_eq(b, heap, guard, interns),
+            (Self::ExternalObject(a), Self::ExternalObject(b)) => a.py_eq(b, heap, guard, interns),
             _ => Ok(false), // Different types are never equal
         }
     }
@@ -467,10 +655,12 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_dec_ref_ids(stack),
             Self::Bytes(b) => b.py_dec_ref_ids(stack),
+            Self::ByteArray(b) => b.py_dec_ref_ids(stack),
             Self::List(l) => l.py_dec_ref_ids(stack),
             Self::Tuple(t) => t.py_dec_ref_ids(stack),
             Self::NamedTuple(nt) => nt.py_dec_ref_ids(stack),
             Self::Dict(d) => d.py_dec_ref_ids(stack),
+            Self::DictView(v) => v.py_dec_ref_ids(stack),
             Self::Set(s) => s.py_dec_ref_ids(stack),
             Self::FrozenSet(fs) => fs.py_dec_ref_ids(stack),
             Self::Closure(_, cells, defaults) => {
@@ -511,8 +701,23 @@ impl PyTrait for HeapData {
                     result.py_dec_ref_ids(stack);
                 }
             }
-            // Range, Slice, Exception, LongInt, and Path have no nested heap references
-            Self::Range(_) | Self::Slice(_) | Self::Exception(_) | Self::LongInt(_) | Self::Path(_) => {}
+            Self::ContextManager(cm) => cm.py_dec_ref_ids(stack),
+            Self::ReMatch(m) => m.py_dec_ref_ids(stack),
+            Self::Partial(p) => p.py_dec_ref_ids(stack),
+            Self::LruCache(lru) => lru.py_dec_ref_ids(stack),
+            Self::Deque(deque) => deque.py_dec_ref_ids(stack),
+            Self::NamedTupleType(nt_type) => nt_type.py_dec_ref_ids(stack),
+            Self::Exception(exc) => stack.extend(exc.cause_id()),
+            // Range, Slice, LongInt, Path, RePattern and the datetime types have no nested heap references
+            Self::Range(_)
+            | Self::Slice(_)
+            | Self::LongInt(_)
+            | Self::Path(_)
+            | Self::RePattern(_)
+            | Self::Date(_)
+            | Self::DateTime(_)
+            | Self::TimeDelta(_)
+            | Self::ExternalObject(_) => {}
         }
     }
 
@@ -520,10 +725,12 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_bool(heap, interns),
             Self::Bytes(b) => b.py_bool(heap, interns),
+            Self::ByteArray(b) => b.py_bool(heap, interns),
             Self::List(l) => l.py_bool(heap, interns),
             Self::Tuple(t) => t.py_bool(heap, interns),
             Self::NamedTuple(nt) => nt.py_bool(heap, interns),
             Self::Dict(d) => d.py_bool(heap, interns),
+            Self::DictView(v) => v.py_bool(heap, interns),
             Self::Set(s) => s.py_bool(heap, interns),
             Self::FrozenSet(fs) => fs.py_bool(heap, interns),
             Self::Closure(_, _, _) | Self::FunctionDefaults(_, _) => true,
@@ -538,6 +745,17 @@ impl PyTrait for HeapData {
             Self::Coroutine(_) => true,    // Coroutines are always truthy
             Self::GatherFuture(_) => true, // GatherFutures are always truthy
             Self::Path(p) => p.py_bool(heap, interns),
+            Self::ContextManager(cm) => cm.py_bool(heap, interns),
+            Self::RePattern(p) => p.py_bool(heap, interns),
+            Self::ReMatch(m) => m.py_bool(heap, interns),
+            Self::Date(d) => d.py_bool(heap, interns),
+            Self::DateTime(dt) => dt.py_bool(heap, interns),
+            Self::TimeDelta(td) => td.py_bool(heap, interns),
+            Self::ExternalObject(obj) => obj.py_bool(heap, interns),
+            Self::Partial(p) => p.py_bool(heap, interns),
+            Self::LruCache(lru) => lru.py_bool(heap, interns),
+            Self::Deque(deque) => deque.py_bool(heap, interns),
+            Self::NamedTupleType(nt_type) => nt_type.py_bool(heap, interns),
         }
     }
 
@@ -552,10 +770,12 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Bytes(b) => b.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ByteArray(b) => b.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::List(l) => l.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Tuple(t) => t.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::NamedTuple(nt) => nt.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Dict(d) => d.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::DictView(v) => v.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Set(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::FrozenSet(fs) => fs.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Closure(f_id, _, _) | Self::FunctionDefaults(f_id, _) => {
@@ -565,7 +785,7 @@ impl PyTrait for HeapData {
             Self::Cell(v) => write!(f, "<cell: {} object>", v.py_type(heap)),
             Self::Range(r) => r.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Slice(s) => s.py_repr_fmt(f, heap, heap_ids, guard, interns),
-            Self::Exception(e) => e.py_repr_fmt(f),
+            Self::Exception(e) => e.py_repr_fmt(f, interns),
             Self::Dataclass(dc) => dc.py_repr_fmt(f, heap, heap_ids, guard, interns),
             Self::Iter(_) => write!(f, "<iterator>"),
             Self::LongInt(li) => write!(f, "{li}"),
@@ -577,6 +797,17 @@ impl PyTrait for HeapData {
             }
             Self::GatherFuture(gather) => write!(f, "<gather({})>", gather.item_count()),
             Self::Path(p) => p.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ContextManager(cm) => cm.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::RePattern(p) => p.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ReMatch(m) => m.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Date(d) => d.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::DateTime(dt) => dt.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::TimeDelta(td) => td.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::ExternalObject(obj) => obj.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Partial(p) => p.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::LruCache(lru) => lru.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::Deque(deque) => deque.py_repr_fmt(f, heap, heap_ids, guard, interns),
+            Self::NamedTupleType(nt_type) => nt_type.py_repr_fmt(f, heap, heap_ids, guard, interns),
         }
     }
 
@@ -595,6 +826,10 @@ impl PyTrait for HeapData {
             Self::Exception(e) => Cow::Owned(e.py_str()),
             // Paths return the path string without the PosixPath() wrapper
             Self::Path(p) => Cow::Owned(p.as_str().to_owned()),
+            // Dates, datetimes and timedeltas use their ISO-style formats
+            Self::Date(d) => Cow::Owned(d.to_string()),
+            Self::DateTime(dt) => Cow::Owned(dt.to_string()),
+            Self::TimeDelta(td) => Cow::Owned(td.to_string()),
             // All other types use repr
             _ => self.py_repr(heap, guard, interns),
         }
@@ -609,6 +844,16 @@ impl PyTrait for HeapData {
         match (self, other) {
             (Self::Str(a), Self::Str(b)) => a.py_add(b, heap, interns),
             (Self::Bytes(a), Self::Bytes(b)) => a.py_add(b, heap, interns),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.py_add(b, heap, interns),
+            // Mixed bytes/bytearray concatenation takes the type of the left operand
+            (Self::Bytes(a), Self::ByteArray(b)) => {
+                let concat = [a.as_slice(), b.as_slice()].concat();
+                Ok(Some(Value::Ref(heap.allocate(Self::Bytes(concat.into()))?)))
+            }
+            (Self::ByteArray(a), Self::Bytes(b)) => {
+                let concat = [a.as_slice(), b.as_slice()].concat();
+                Ok(Some(Value::Ref(heap.allocate(Self::ByteArray(concat.into()))?)))
+            }
             (Self::List(a), Self::List(b)) => a.py_add(b, heap, interns),
             (Self::Tuple(a), Self::Tuple(b)) => a.py_add(b, heap, interns),
             (Self::Dict(a), Self::Dict(b)) => a.py_add(b, heap, interns),
@@ -681,6 +926,7 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_iadd(other, heap, self_id, interns),
             Self::Bytes(b) => b.py_iadd(other, heap, self_id, interns),
+            Self::ByteArray(b) => b.py_iadd(other, heap, self_id, interns),
             Self::List(l) => l.py_iadd(other, heap, self_id, interns),
             Self::Tuple(t) => t.py_iadd(other, heap, self_id, interns),
             Self::Dict(d) => d.py_iadd(other, heap, self_id, interns),
@@ -702,6 +948,7 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_call_attr(heap, attr, args, interns),
             Self::Bytes(b) => b.py_call_attr(heap, attr, args, interns),
+            Self::ByteArray(b) => b.py_call_attr(heap, attr, args, interns),
             Self::List(l) => l.py_call_attr(heap, attr, args, interns),
             Self::Tuple(t) => t.py_call_attr(heap, attr, args, interns),
             Self::Dict(d) => d.py_call_attr(heap, attr, args, interns),
@@ -709,6 +956,16 @@ impl PyTrait for HeapData {
             Self::FrozenSet(fs) => fs.py_call_attr(heap, attr, args, interns),
             Self::Dataclass(dc) => dc.py_call_attr(heap, attr, args, interns),
             Self::Path(p) => p.py_call_attr(heap, attr, args, interns),
+            Self::ContextManager(cm) => cm.py_call_attr(heap, attr, args, interns),
+            Self::ReMatch(m) => m.py_call_attr(heap, attr, args, interns),
+            Self::Date(d) => d.py_call_attr(heap, attr, args, interns),
+            Self::DateTime(dt) => dt.py_call_attr(heap, attr, args, interns),
+            Self::TimeDelta(td) => td.py_call_attr(heap, attr, args, interns),
+            Self::ExternalObject(obj) => obj.py_call_attr(heap, attr, args, interns),
+            Self::LruCache(lru) => lru.py_call_attr(heap, attr, args, interns),
+            Self::NamedTuple(nt) => nt.py_call_attr(heap, attr, args, interns),
+            Self::Deque(deque) => deque.py_call_attr(heap, attr, args, interns),
+            Self::NamedTupleType(nt_type) => nt_type.py_call_attr(heap, attr, args, interns),
             _ => Err(ExcType::attribute_error(self.py_type(heap), attr.as_str(interns))),
         }
     }
@@ -723,12 +980,22 @@ impl PyTrait for HeapData {
         match self {
             // List intercepts sort for key function support via PrintWriter
             Self::List(l) => l.py_call_attr_raw(self_id, vm, attr, args),
+            // ByteArray swaps arguments that alias the receiver for a copy
+            Self::ByteArray(b) => b.py_call_attr_raw(self_id, vm, attr, args),
+            // Dict returns views that reference it by id from keys/values/items
+            Self::Dict(d) => d.py_call_attr_raw(self_id, vm, attr, args),
+            // Deque duplicates its items when extended with itself
+            Self::Deque(deque) => deque.py_call_attr_raw(self_id, vm, attr, args),
             // Dataclass detects public method calls and returns MethodCall
             Self::Dataclass(dc) => dc.py_call_attr_raw(self_id, vm, attr, args),
+            // External objects forward all public method calls to the host
+            Self::ExternalObject(obj) => obj.py_call_attr_raw(self_id, vm, attr, args),
             // Path has special handling for OS calls (exists, read_text, etc.)
             Self::Path(p) => p.py_call_attr_raw(self_id, vm, attr, args),
             // Module has special handling for OS calls (os.getenv, etc.)
             Self::Module(m) => m.py_call_attr_raw(self_id, vm, attr, args),
+            // Pattern methods create matches referring to the pattern and call `sub()` callbacks
+            Self::RePattern(p) => p.py_call_attr_raw(self_id, vm, attr, args),
             // All other types use the default implementation (wrap py_call_attr)
             _ => self
                 .py_call_attr(vm.heap, attr, args, vm.interns)
@@ -740,11 +1007,14 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_getitem(key, heap, interns),
             Self::Bytes(b) => b.py_getitem(key, heap, interns),
+            Self::ByteArray(b) => b.py_getitem(key, heap, interns),
             Self::List(l) => l.py_getitem(key, heap, interns),
             Self::Tuple(t) => t.py_getitem(key, heap, interns),
             Self::NamedTuple(nt) => nt.py_getitem(key, heap, interns),
             Self::Dict(d) => d.py_getitem(key, heap, interns),
+            Self::Deque(deque) => deque.py_getitem(key, heap, interns),
             Self::Range(r) => r.py_getitem(key, heap, interns),
+            Self::ReMatch(m) => m.py_getitem(key, heap, interns),
             _ => Err(ExcType::type_error_not_sub(self.py_type(heap))),
         }
     }
@@ -759,13 +1029,39 @@ impl PyTrait for HeapData {
         match self {
             Self::Str(s) => s.py_setitem(key, value, heap, interns),
             Self::Bytes(b) => b.py_setitem(key, value, heap, interns),
+            Self::ByteArray(b) => b.py_setitem(key, value, heap, interns),
             Self::List(l) => l.py_setitem(key, value, heap, interns),
             Self::Tuple(t) => t.py_setitem(key, value, heap, interns),
             Self::Dict(d) => d.py_setitem(key, value, heap, interns),
+            Self::Deque(deque) => deque.py_setitem(key, value, heap, interns),
             _ => Err(ExcType::type_error_not_sub_assignment(self.py_type(heap))),
         }
     }
 
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match self {
+            Self::List(l) => l.py_delitem(key, heap, interns),
+            Self::ByteArray(b) => b.py_delitem(key, heap, interns),
+            Self::Dict(d) => d.py_delitem(key, heap, interns),
+            Self::Deque(deque) => deque.py_delitem(key, heap, interns),
+            // Sequence and set types report "doesn't support", matching CPython
+            Self::Str(_)
+            | Self::Bytes(_)
+            | Self::Tuple(_)
+            | Self::NamedTuple(_)
+            | Self::Range(_)
+            | Self::Set(_)
+            | Self::FrozenSet(_) => {
+                key.drop_with_heap(heap);
+                Err(ExcType::type_error_item_deletion(self.py_type(heap)))
+            }
+            _ => {
+                key.drop_with_heap(heap);
+                Err(ExcType::type_error_not_sub_deletion(self.py_type(heap)))
+            }
+        }
+    }
+
     fn py_getattr(
         &self,
         attr_id: StringId,
@@ -779,6 +1075,16 @@ impl PyTrait for HeapData {
             Self::Slice(s) => s.py_getattr(attr_id, heap, interns),
             Self::Exception(exc) => exc.py_getattr(attr_id, heap, interns),
             Self::Path(p) => p.py_getattr(attr_id, heap, interns),
+            Self::RePattern(p) => p.py_getattr(attr_id, heap, interns),
+            Self::ReMatch(m) => m.py_getattr(attr_id, heap, interns),
+            Self::Date(d) => d.py_getattr(attr_id, heap, interns),
+            Self::DateTime(dt) => dt.py_getattr(attr_id, heap, interns),
+            Self::TimeDelta(td) => td.py_getattr(attr_id, heap, interns),
+            Self::Partial(p) => p.py_getattr(attr_id, heap, interns),
+            Self::LruCache(lru) => lru.py_getattr(attr_id, heap, interns),
+            Self::Dict(d) => d.py_getattr(attr_id, heap, interns),
+            Self::Deque(deque) => deque.py_getattr(attr_id, heap, interns),
+            Self::NamedTupleType(nt_type) => nt_type.py_getattr(attr_id, heap, interns),
             // All other types don't support attribute access via py_getattr
             _ => Ok(None),
         }
@@ -813,6 +1119,7 @@ impl HashState {
             | HeapData::Cell(_)
             | HeapData::Closure(_, _, _)
             | HeapData::FunctionDefaults(_, _)
+            | HeapData::NamedTupleType(_)
             | HeapData::Range(_)
             | HeapData::Slice(_)
             | HeapData::LongInt(_) => Self::Unknown,
@@ -824,17 +1131,29 @@ impl HashState {
                     Self::Unhashable
                 }
             }
-            // Path is immutable and hashable
-            HeapData::Path(_) => Self::Unknown,
+            // Path, RePattern, the datetime types and external objects are immutable and hashable
+            HeapData::Path(_)
+            | HeapData::RePattern(_)
+            | HeapData::Date(_)
+            | HeapData::DateTime(_)
+            | HeapData::TimeDelta(_)
+            | HeapData::ExternalObject(_) => Self::Unknown,
             // Mutable containers, exceptions, iterators, modules, and async types are unhashable
             HeapData::List(_)
+            | HeapData::ByteArray(_)
             | HeapData::Dict(_)
+            | HeapData::DictView(_)
             | HeapData::Set(_)
             | HeapData::Exception(_)
             | HeapData::Iter(_)
             | HeapData::Module(_)
             | HeapData::Coroutine(_)
-            | HeapData::GatherFuture(_) => Self::Unhashable,
+            | HeapData::GatherFuture(_)
+            | HeapData::ContextManager(_)
+            | HeapData::ReMatch(_)
+            | HeapData::Partial(_)
+            | HeapData::LruCache(_)
+            | HeapData::Deque(_) => Self::Unhashable,
         }
     }
 }
@@ -874,7 +1193,10 @@ pub struct HeapValue {
 #[derive(Debug)]
 pub(crate) struct Heap<T: ResourceTracker> {
     entries: Vec<Option<HeapValue>>,
-    /// IDs of freed slots available for reuse. Populated by `dec_ref`, consumed by `allocate`.
+    /// Current generation of each slot in `entries`, bumped every time the slot is freed.
+    generations: Vec<u32>,
+    /// IDs of freed slots available for reuse, already carrying the slot's next generation.
+    /// Populated by `dec_ref` and GC, consumed by `allocate`.
     free_list: Vec<HeapId>,
     /// Resource tracker for enforcing limits and scheduling GC.
     tracker: T,
@@ -883,17 +1205,42 @@ pub(crate) struct Heap<T: ResourceTracker> {
     may_have_cycles: bool,
     /// Number of GC applicable allocations since the last GC.
     allocations_since_gc: u32,
+    /// Generator backing the `random` module, serialized so snapshots keep its state.
+    rng: Rng,
+    /// Hierarchy of each user-defined exception class whose `class` statement has run,
+    /// indexed by `ExcClassId`.
+    exc_classes: Vec<Option<ExcClassInfo>>,
+    /// Direct-mapped cache of recently allocated short strings, see `allocate_str`.
+    ///
+    /// Slots hold no reference: a cached ID is only reused after checking the entry is
+    /// still a live `Str` with the same content. Not serialized, a restored heap starts cold.
+    small_strs: Box<[Option<HeapId>]>,
+    /// GC-tracked values allocated since the last collection, the young generation.
+    ///
+    /// Not serialized: after a restore these are treated as old and left to the next
+    /// full collection.
+    young: Vec<HeapId>,
+    /// Number of young collections since the last full collection.
+    young_collections: u32,
+    /// Hook called before each statement, see `crate::trace`.
+    ///
+    /// Kept on the heap so it follows the execution through snapshots in the same process.
+    /// Not serialized, a restored heap runs untraced.
+    trace_hook: Option<Box<dyn TraceHook>>,
 }
 
 impl<T: ResourceTracker + serde::Serialize> serde::Serialize for Heap<T> {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Heap", 6)?;
+        let mut state = serializer.serialize_struct("Heap", 8)?;
         state.serialize_field("entries", &self.entries)?;
+        state.serialize_field("generations", &self.generations)?;
         state.serialize_field("free_list", &self.free_list)?;
         state.serialize_field("tracker", &self.tracker)?;
         state.serialize_field("may_have_cycles", &self.may_have_cycles)?;
         state.serialize_field("allocations_since_gc", &self.allocations_since_gc)?;
+        state.serialize_field("rng", &self.rng)?;
+        state.serialize_field("exc_classes", &self.exc_classes)?;
         state.end()
     }
 }
@@ -903,24 +1250,35 @@ impl<'de, T: ResourceTracker + serde::Deserialize<'de>> serde::Deserialize<'de>
         #[derive(serde::Deserialize)]
         struct HeapFields<T> {
             entries: Vec<Option<HeapValue>>,
+            generations: Vec<u32>,
             free_list: Vec<HeapId>,
             tracker: T,
             may_have_cycles: bool,
             allocations_since_gc: u32,
+            rng: Rng,
+            exc_classes: Vec<Option<ExcClassInfo>>,
         }
         let fields = HeapFields::<T>::deserialize(deserializer)?;
         Ok(Self {
             entries: fields.entries,
+            generations: fields.generations,
             free_list: fields.free_list,
             tracker: fields.tracker,
             may_have_cycles: fields.may_have_cycles,
             allocations_since_gc: fields.allocations_since_gc,
+            rng: fields.rng,
+            exc_classes: fields.exc_classes,
+            small_strs: empty_small_str_cache(),
+            young: Vec::new(),
+            young_collections: 0,
+            trace_hook: None,
         })
     }
 }
 
 macro_rules! take_data {
-    ($self:ident, $id:expr, $func_name:literal) => {
+    ($self:ident, $id:expr, $func_name:literal) => {{
+        $self.debug_check_generation($id);
         $self
             .entries
             .get_mut($id.index())
@@ -930,7 +1288,7 @@ macro_rules! take_data {
             .data
             .take()
             .expect(concat!("Heap::", $func_name, ": data already borrowed"))
-    };
+    }};
 }
 
 macro_rules! restore_data {
@@ -945,23 +1303,55 @@ macro_rules! restore_data {
     }};
 }
 
-/// GC interval - run GC every 100,000 applicable allocations.
+/// GC interval - run a collection every 10,000 applicable allocations.
 ///
-/// This is intentionally infrequent to minimize overhead while still
-/// eventually collecting reference cycles.
-const GC_INTERVAL: u32 = 100_000;
+/// Most of these are young collections, which only examine values allocated since the
+/// previous collection, so their pause is bounded by this interval rather than heap size.
+const GC_INTERVAL: u32 = 10_000;
+
+/// Every this many young collections, the next collection is a full mark-sweep instead,
+/// reclaiming cycles that involve older values. Keeps full collections at their original
+/// cadence of once per 100,000 applicable allocations.
+const FULL_GC_EVERY: u32 = 10;
+
+/// Strings up to this many bytes are deduplicated through the heap's small-string cache.
+const SMALL_STR_MAX_LEN: usize = 15;
+
+/// Number of slots in the small-string cache, must be a power of two.
+const SMALL_STR_CACHE_SIZE: usize = 1024;
+
+fn empty_small_str_cache() -> Box<[Option<HeapId>]> {
+    vec![None; SMALL_STR_CACHE_SIZE].into_boxed_slice()
+}
+
+/// Picks the small-string cache slot for `s` using FNV-1a, cheap for strings this short.
+#[expect(clippy::cast_possible_truncation, reason = "only the low bits select the slot")]
+fn small_str_slot(s: &str) -> usize {
+    let hash = s.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    (hash as usize) & (SMALL_STR_CACHE_SIZE - 1)
+}
 
 impl<T: ResourceTracker> Heap<T> {
     /// Creates a new heap with the given resource tracker.
     ///
     /// Use this to create heaps with custom resource limits or GC scheduling.
     pub fn new(capacity: usize, tracker: T) -> Self {
+        let rng = Rng::new(tracker.random_seed());
         let mut this = Self {
             entries: Vec::with_capacity(capacity),
+            generations: Vec::with_capacity(capacity),
             free_list: Vec::new(),
             tracker,
             may_have_cycles: false,
             allocations_since_gc: 0,
+            rng,
+            exc_classes: Vec::new(),
+            small_strs: empty_small_str_cache(),
+            young: Vec::new(),
+            young_collections: 0,
+            trace_hook: None,
         };
         // TBC: should the empty tuple contribute to the resource limits?
         // If not, can just place it in `entries` directly without going through `allocate()`.
@@ -982,11 +1372,81 @@ impl<T: ResourceTracker> Heap<T> {
         &mut self.tracker
     }
 
-    /// Checks whether the configured time limit has been exceeded.
+    /// Installs or removes the hook called before each statement.
+    pub fn set_trace_hook(&mut self, hook: Option<Box<dyn TraceHook>>) {
+        self.trace_hook = hook;
+    }
+
+    /// Returns whether a trace hook is installed.
+    pub fn has_trace_hook(&self) -> bool {
+        self.trace_hook.is_some()
+    }
+
+    /// Removes the trace hook so it can be called while the heap is borrowed, the caller
+    /// puts it back with `set_trace_hook`.
+    pub fn take_trace_hook(&mut self) -> Option<Box<dyn TraceHook>> {
+        self.trace_hook.take()
+    }
+
+    /// Returns the generator backing the `random` module.
+    pub fn rng_mut(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// Records the hierarchy of user-defined exception class `id`, when its `class` statement runs.
+    pub fn define_exc_class(&mut self, id: ExcClassId, info: ExcClassInfo) {
+        if self.exc_classes.len() <= id.index() {
+            self.exc_classes.resize(id.index() + 1, None);
+        }
+        self.exc_classes[id.index()] = Some(info);
+    }
+
+    /// Returns the hierarchy of user-defined exception class `id`, if its `class` statement has run.
+    pub fn defined_exc_class(&self, id: ExcClassId) -> Option<ExcClassInfo> {
+        self.exc_classes.get(id.index()).copied().flatten()
+    }
+
+    /// Returns the hierarchy of user-defined exception class `id`.
+    ///
+    /// The class only exists as a value once its `class` statement has run, so a missing
+    /// class is a bug in Monty and returns an internal error.
+    pub fn exc_class(&self, id: ExcClassId) -> RunResult<ExcClassInfo> {
+        self.defined_exc_class(id)
+            .ok_or_else(|| RunError::internal("exception class used before its class statement ran"))
+    }
+
+    /// Returns true if exception class `id` is `ancestor` or (transitively) derives from it.
+    pub fn exc_class_is_subclass_of(&self, id: ExcClassId, ancestor: ExcClassId) -> RunResult<bool> {
+        let mut current = Some(id);
+        while let Some(class_id) = current {
+            if class_id == ancestor {
+                return Ok(true);
+            }
+            current = self.exc_class(class_id)?.parent;
+        }
+        Ok(false)
+    }
+
+    /// Raises `RuntimeError` if the tracker asks for deterministic execution.
+    ///
+    /// Called by builtins whose result can't be replayed exactly, e.g. reading the wall clock.
+    pub fn check_deterministic(&self, name: &str) -> RunResult<()> {
+        if self.tracker.deterministic() {
+            Err(SimpleException::new_msg(
+                ExcType::RuntimeError,
+                format!("{name} is not allowed in deterministic mode"),
+            )
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Counts one step of work and checks whether the time or step budget has been exceeded.
     ///
     /// Delegates to the resource tracker's `check_time()`. For `NoLimitTracker`,
-    /// this is inlined as a no-op with zero runtime cost. For `LimitTracker`,
-    /// it compares elapsed time against the configured `max_duration_secs`.
+    /// this is inlined as a no-op with zero runtime cost. For `LimitedTracker`,
+    /// it compares steps and elapsed time against `max_steps` and `max_duration`.
     ///
     /// Call this inside Rust-side loops (builtins, sort, iterator collection)
     /// that execute within a single bytecode instruction and would otherwise
@@ -1001,6 +1461,26 @@ impl<T: ResourceTracker> Heap<T> {
         self.entries.len()
     }
 
+    /// Number of live objects in the heap, excluding freed slots.
+    pub fn live_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    /// Iterates over the live values with their IDs and reference counts, in slot order.
+    ///
+    /// Values temporarily taken out by `with_entry_mut` are skipped.
+    pub fn live_values(&self) -> impl Iterator<Item = (HeapId, &HeapData, usize)> {
+        self.entries.iter().enumerate().filter_map(|(index, entry)| {
+            let entry = entry.as_ref()?;
+            let data = entry.data.as_ref()?;
+            let id = HeapId {
+                index: u32::try_from(index).expect("heap exceeds u32::MAX slots"),
+                generation: self.generations[index],
+            };
+            Some((id, data, entry.refcount))
+        })
+    }
+
     /// Marks that a reference cycle may exist in the heap.
     ///
     /// Call this when a container (list, dict, tuple, etc.) stores a reference
@@ -1032,7 +1512,8 @@ impl<T: ResourceTracker> Heap<T> {
     /// cycles to enable garbage collection.
     pub fn allocate(&mut self, data: HeapData) -> Result<HeapId, ResourceError> {
         self.tracker.on_allocate(|| data.py_estimate_size())?;
-        if data.is_gc_tracked() {
+        let gc_tracked = data.is_gc_tracked();
+        if gc_tracked {
             self.allocations_since_gc = self.allocations_since_gc.wrapping_add(1);
             // Mark potential cycles if this container has heap references.
             // This is essential for types like Dict where setitem doesn't call
@@ -1050,16 +1531,52 @@ impl<T: ResourceTracker> Heap<T> {
         };
 
         let id = if let Some(id) = self.free_list.pop() {
-            // Reuse a freed slot
+            // Reuse a freed slot, its generation was already bumped when it was freed
             self.entries[id.index()] = Some(new_entry);
             id
         } else {
             // No free slots, append new entry
-            let id = self.entries.len();
+            let index = u32::try_from(self.entries.len()).expect("heap exceeds u32::MAX slots");
             self.entries.push(Some(new_entry));
-            HeapId(id)
+            self.generations.push(0);
+            HeapId { index, generation: 0 }
         };
+        if gc_tracked {
+            self.young.push(id);
+        }
+
+        self.tracker.on_heap_slots(HeapSlotStats {
+            total_slots: self.entries.len(),
+            free_slots: self.free_list.len(),
+        });
+        Ok(id)
+    }
 
+    /// Allocates a string, sharing an existing heap entry for short strings where possible.
+    ///
+    /// Strings of at most `SMALL_STR_MAX_LEN` bytes are looked up in a small direct-mapped
+    /// cache; on a hit the cached entry's refcount is incremented and its ID returned, so
+    /// code like `str(i)` or building dict keys in a loop doesn't allocate a fresh `Str`
+    /// for every repeated value. Strings are immutable so sharing is unobservable apart
+    /// from `is`, which CPython doesn't guarantee for equal strings either.
+    ///
+    /// Returns `Err(ResourceError)` if a new allocation would exceed configured limits.
+    pub fn allocate_str(&mut self, s: String) -> Result<HeapId, ResourceError> {
+        if s.len() > SMALL_STR_MAX_LEN {
+            return self.allocate(HeapData::Str(Str::new(s)));
+        }
+        let slot = small_str_slot(&s);
+        if let Some(id) = self.small_strs[slot]
+            && self.generations[id.index()] == id.generation
+            && let Some(Some(entry)) = self.entries.get_mut(id.index())
+            && let Some(HeapData::Str(cached)) = &entry.data
+            && cached.as_str() == s
+        {
+            entry.refcount += 1;
+            return Ok(id);
+        }
+        let id = self.allocate(HeapData::Str(Str::new(s)))?;
+        self.small_strs[slot] = Some(id);
         Ok(id)
     }
 
@@ -1082,6 +1599,7 @@ impl<T: ResourceTracker> Heap<T> {
     /// # Panics
     /// Panics if the value ID is invalid or the value has already been freed.
     pub fn inc_ref(&mut self, id: HeapId) {
+        self.debug_check_generation(id);
         let value = self
             .entries
             .get_mut(id.index())
@@ -1100,13 +1618,15 @@ impl<T: ResourceTracker> Heap<T> {
     /// # Panics
     /// Panics if the value ID is invalid or the value has already been freed.
     pub fn dec_ref(&mut self, id: HeapId) {
+        self.debug_check_generation(id);
         let slot = self.entries.get_mut(id.index()).expect("Heap::dec_ref: slot missing");
         let entry = slot.as_mut().expect("Heap::dec_ref: object already freed");
         if entry.refcount > 1 {
             entry.refcount -= 1;
         } else if let Some(value) = slot.take() {
             // refcount == 1, free the value and add slot to free list for reuse
-            self.free_list.push(id);
+            let next_id = self.next_generation(id.index());
+            self.free_list.push(next_id);
 
             // Notify tracker of freed memory
             if let Some(ref data) = value.data {
@@ -1133,6 +1653,7 @@ impl<T: ResourceTracker> Heap<T> {
     /// or the data is currently borrowed via `with_entry_mut`/`call_attr`.
     #[must_use]
     pub fn get(&self, id: HeapId) -> &HeapData {
+        self.debug_check_generation(id);
         self.entries
             .get(id.index())
             .expect("Heap::get: slot missing")
@@ -1149,6 +1670,7 @@ impl<T: ResourceTracker> Heap<T> {
     /// Panics if the value ID is invalid, the value has already been freed,
     /// or the data is currently borrowed via `with_entry_mut`/`call_attr`.
     pub fn get_mut(&mut self, id: HeapId) -> &mut HeapData {
+        self.debug_check_generation(id);
         self.entries
             .get_mut(id.index())
             .expect("Heap::get_mut: slot missing")
@@ -1181,8 +1703,9 @@ impl<T: ResourceTracker> Heap<T> {
             HashState::Unknown => {}
         }
 
-        // Handle Cell specially - uses identity-based hashing (like Python cell objects)
-        if let Some(HeapData::Cell(_)) = &entry.data {
+        // Handle Cell and named tuple classes specially - they use identity-based hashing
+        // (like Python cell and class objects)
+        if let Some(HeapData::Cell(_) | HeapData::NamedTupleType(_)) = &entry.data {
             let mut hasher = DefaultHasher::new();
             id.hash(&mut hasher);
             let hash = hasher.finish();
@@ -1232,6 +1755,13 @@ impl<T: ResourceTracker> Heap<T> {
         attr: &EitherStr,
         args: ArgValues,
     ) -> RunResult<AttrCallResult> {
+        // Patterns are immutable and cheap to clone, so they stay in place: a `sub()` callback
+        // may use the same pattern, or the matches referring to it, while the call runs
+        if let HeapData::RePattern(pattern) = vm.heap.get(id) {
+            let mut pattern = pattern.clone();
+            return pattern.py_call_attr_raw(id, vm, attr, args);
+        }
+
         // Take data out so the borrow of self.entries ends
         let heap = &mut *vm.heap;
         let mut data = take_data!(heap, id, "call_attr");
@@ -1296,13 +1826,14 @@ impl<T: ResourceTracker> Heap<T> {
 
     /// Returns the reference count for the heap entry at the given ID.
     ///
-    /// This is primarily used for testing reference counting behavior.
+    /// Used by in-place operations on immutable types to check no other holder can
+    /// observe the mutation, and by tests checking reference counting behavior.
     ///
     /// # Panics
     /// Panics if the value ID is invalid or the value has already been freed.
     #[must_use]
-    #[cfg(feature = "ref-count-return")]
     pub fn get_refcount(&self, id: HeapId) -> usize {
+        self.debug_check_generation(id);
         self.entries
             .get(id.index())
             .expect("Heap::get_refcount: slot missing")
@@ -1500,6 +2031,12 @@ impl<T: ResourceTracker> Heap<T> {
                 restore_data!(self, id, data, "mult_sequence");
                 Ok(Some(Value::Ref(self.allocate(HeapData::Bytes(repeated.into()))?)))
             }
+            HeapData::ByteArray(b) => {
+                check_repeat_size(b.len(), count, &self.tracker)?;
+                let repeated = b.as_slice().repeat(count);
+                restore_data!(self, id, data, "mult_sequence");
+                Ok(Some(Value::Ref(self.allocate(HeapData::ByteArray(repeated.into()))?)))
+            }
             HeapData::List(list) => {
                 if count == 0 {
                     restore_data!(self, id, data, "mult_sequence");
@@ -1607,11 +2144,44 @@ impl<T: ResourceTracker> Heap<T> {
         self.may_have_cycles && self.allocations_since_gc >= GC_INTERVAL
     }
 
-    /// Runs mark-sweep garbage collection to free unreachable cycles.
+    /// Bumps the generation of a slot that is being freed, returning the ID its next
+    /// occupant will get.
+    fn next_generation(&mut self, index: usize) -> HeapId {
+        let generation = self.generations[index].wrapping_add(1);
+        self.generations[index] = generation;
+        HeapId {
+            index: u32::try_from(index).expect("heap exceeds u32::MAX slots"),
+            generation,
+        }
+    }
+
+    /// Debug-only check that `id` refers to the current occupant of its slot, catching
+    /// use of an ID whose value was freed and whose slot has since been reused.
+    #[inline]
+    fn debug_check_generation(&self, id: HeapId) {
+        debug_assert!(
+            self.generations.get(id.index()).is_none_or(|g| *g == id.generation),
+            "Heap: stale {id:?}, slot now holds generation {:?}",
+            self.generations.get(id.index())
+        );
+    }
+
+    /// Returns the heap's current slot usage.
+    #[must_use]
+    pub fn slot_stats(&self) -> HeapSlotStats {
+        HeapSlotStats {
+            total_slots: self.entries.len(),
+            free_slots: self.free_list.len(),
+        }
+    }
+
+    /// Runs garbage collection to free unreachable cycles.
     ///
-    /// This method takes a closure that provides an iterator of root HeapIds
-    /// (typically from Namespaces). It marks all reachable objects starting
-    /// from roots, then sweeps (frees) any unreachable objects.
+    /// Collections are generational: usually only the young generation (GC-tracked values
+    /// allocated since the previous collection) is examined, see `collect_young`. Every
+    /// `FULL_GC_EVERY`th collection is a full mark-sweep from `root` instead, which also
+    /// reclaims cycles involving older values. Each collection is reported to the tracker
+    /// via `ResourceTracker::on_gc`.
     ///
     /// This is necessary because reference counting alone cannot free cycles
     /// where objects reference each other but are unreachable from the program.
@@ -1621,8 +2191,35 @@ impl<T: ResourceTracker> Heap<T> {
     /// If no cycles are possible, the caller can skip GC entirely.
     ///
     /// # Arguments
-    /// * `root` - HeapIds that are roots
+    /// * `root` - HeapIds that are roots, only used by full collections
     pub fn collect_garbage(&mut self, root: Vec<HeapId>) {
+        let start = monotonic_clock();
+        let (kind, examined, freed) = if self.young_collections + 1 >= FULL_GC_EVERY {
+            self.young.clear();
+            self.young_collections = 0;
+            let (examined, freed) = self.collect_full(root);
+            // Reset cycle flag after a full GC - cycles have been collected
+            self.may_have_cycles = false;
+            (GcKind::Full, examined, freed)
+        } else {
+            self.young_collections += 1;
+            let (examined, freed) = self.collect_young();
+            // Cycles among older values may remain, so `may_have_cycles` stays set
+            (GcKind::Young, examined, freed)
+        };
+        self.allocations_since_gc = 0;
+        self.tracker.on_gc(GcStats {
+            kind,
+            examined,
+            freed,
+            pause: monotonic_clock().saturating_sub(start),
+        });
+        self.tracker.on_heap_slots(self.slot_stats());
+    }
+
+    /// Full mark-sweep collection: marks everything reachable from `root`, then frees
+    /// every unmarked slot. Returns the number of slots examined and freed.
+    fn collect_full(&mut self, root: Vec<HeapId>) -> (usize, usize) {
         // Mark phase: collect all reachable IDs using BFS
         // Use Vec<bool> instead of HashSet for O(1) operations without hashing overhead
         let mut reachable: Vec<bool> = vec![false; self.entries.len()];
@@ -1645,19 +2242,22 @@ impl<T: ResourceTracker> Heap<T> {
         }
 
         // Sweep phase: free unreachable values
-        for (id, value) in self.entries.iter_mut().enumerate() {
-            if reachable[id] {
+        let mut freed = 0;
+        for index in 0..self.entries.len() {
+            if reachable[index] {
                 continue;
             }
 
             // This entry is unreachable - free it
-            if let Some(value) = value.take() {
+            if let Some(value) = self.entries[index].take() {
                 // Notify tracker of freed memory
                 if let Some(ref data) = value.data {
                     self.tracker.on_free(|| data.py_estimate_size());
                 }
 
-                self.free_list.push(HeapId(id));
+                let next_id = self.next_generation(index);
+                self.free_list.push(next_id);
+                freed += 1;
 
                 // Mark Values as Dereferenced when ref-count-panic is enabled
                 #[cfg(feature = "ref-count-panic")]
@@ -1667,9 +2267,88 @@ impl<T: ResourceTracker> Heap<T> {
             }
         }
 
-        // Reset cycle flag after GC - cycles have been collected
-        self.may_have_cycles = false;
-        self.allocations_since_gc = 0;
+        (self.entries.len(), freed)
+    }
+
+    /// Young collection using CPython-style trial deletion, returning the number of values
+    /// examined and freed.
+    ///
+    /// No roots are needed: a young value whose refcount exceeds the references it receives
+    /// from other young values is held from outside the generation (a namespace, the stack,
+    /// an older container), so it and everything young it reaches are alive. The remaining
+    /// young values are only referenced by each other, i.e. unreachable cycles. Survivors
+    /// are promoted by clearing the young list.
+    fn collect_young(&mut self) -> (usize, usize) {
+        let young = std::mem::take(&mut self.young);
+
+        // Live young values whose data isn't currently borrowed. `gc_refs` starts as the
+        // refcount, then loses one for every reference from another candidate.
+        let mut position: AHashMap<HeapId, usize> = AHashMap::with_capacity(young.len());
+        let mut candidates = Vec::with_capacity(young.len());
+        let mut gc_refs = Vec::with_capacity(young.len());
+        for id in young {
+            if self.generations[id.index()] == id.generation
+                && let Some(Some(entry)) = self.entries.get(id.index())
+                && entry.data.is_some()
+                && let Entry::Vacant(slot) = position.entry(id)
+            {
+                slot.insert(candidates.len());
+                candidates.push(id);
+                gc_refs.push(entry.refcount);
+            }
+        }
+
+        let mut children = Vec::new();
+        for &id in &candidates {
+            children.clear();
+            collect_child_ids(self.get(id), &mut children);
+            for child in &children {
+                if let Some(&pos) = position.get(child) {
+                    gc_refs[pos] = gc_refs[pos].saturating_sub(1);
+                }
+            }
+        }
+
+        // Everything reachable from an externally referenced candidate survives
+        let mut reachable = vec![false; candidates.len()];
+        let mut work_list: Vec<usize> = (0..candidates.len()).filter(|&pos| gc_refs[pos] > 0).collect();
+        while let Some(pos) = work_list.pop() {
+            if reachable[pos] {
+                continue;
+            }
+            reachable[pos] = true;
+            children.clear();
+            collect_child_ids(self.get(candidates[pos]), &mut children);
+            work_list.extend(children.iter().filter_map(|child| position.get(child).copied()));
+        }
+
+        // Free the unreachable cycles, remembering what they referenced
+        let mut released = Vec::new();
+        let mut freed = 0;
+        for (pos, &id) in candidates.iter().enumerate() {
+            if reachable[pos] {
+                continue;
+            }
+            if let Some(value) = self.entries[id.index()].take() {
+                let next_id = self.next_generation(id.index());
+                self.free_list.push(next_id);
+                freed += 1;
+                if let Some(mut data) = value.data {
+                    self.tracker.on_free(|| data.py_estimate_size());
+                    data.py_dec_ref_ids(&mut released);
+                }
+            }
+        }
+
+        // Drop the references garbage held on values that are still alive; references
+        // between garbage values went away with them
+        for id in released {
+            if self.generations[id.index()] == id.generation && self.entries[id.index()].is_some() {
+                self.dec_ref(id);
+            }
+        }
+
+        (candidates.len(), freed)
     }
 }
 
@@ -1712,16 +2391,22 @@ fn longint_to_repeat_count(li: &LongInt) -> RunResult<usize> {
 }
 
 /// Collects child HeapIds from a HeapData value for GC traversal.
-fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
+pub(crate) fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
     match data {
         // Leaf types with no heap references
         HeapData::Str(_)
         | HeapData::Bytes(_)
+        | HeapData::ByteArray(_)
         | HeapData::Range(_)
-        | HeapData::Exception(_)
         | HeapData::LongInt(_)
         | HeapData::Slice(_)
-        | HeapData::Path(_) => {}
+        | HeapData::Path(_)
+        | HeapData::RePattern(_)
+        | HeapData::Date(_)
+        | HeapData::DateTime(_)
+        | HeapData::TimeDelta(_)
+        | HeapData::ExternalObject(_) => {}
+        HeapData::Exception(exc) => work_list.extend(exc.cause_id()),
         HeapData::List(list) => {
             // Skip iteration if no refs - major GC optimization for lists of primitives
             if !list.contains_refs() {
@@ -1760,6 +2445,10 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
             if !dict.has_refs() {
                 return;
             }
+            // A defaultdict also holds its default factory
+            if let Some(Value::Ref(id)) = dict.default_factory() {
+                work_list.push(*id);
+            }
             for (k, v) in dict {
                 if let Value::Ref(id) = k {
                     work_list.push(*id);
@@ -1809,6 +2498,57 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
                 work_list.push(*id);
             }
         }
+        HeapData::ContextManager(cm) => {
+            // nullcontext holds its enter result, suppress holds its exception classes
+            for value in cm.values() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::ReMatch(m) => {
+            // A match holds its subject string and its pattern
+            for value in m.values() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::Partial(p) => {
+            // A partial holds its callable, arguments and keywords
+            for value in p.values() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::Deque(deque) => {
+            // Skip iteration if no refs - GC optimization for deques of primitives
+            if !deque.contains_refs() {
+                return;
+            }
+            for value in deque.iter() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::NamedTupleType(nt_type) => {
+            // A named tuple class holds its default values
+            for value in nt_type.defaults() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
+        HeapData::LruCache(lru) => {
+            // A cache wrapper holds its callable and the cached keys and results
+            for value in lru.values() {
+                if let Value::Ref(id) = value {
+                    work_list.push(*id);
+                }
+            }
+        }
         HeapData::Dataclass(dc) => {
             // Dataclass attrs are stored in a Dict - iterate through entries
             for (k, v) in dc.attrs() {
@@ -1820,6 +2560,10 @@ fn collect_child_ids(data: &HeapData, work_list: &mut Vec<HeapId>) {
                 }
             }
         }
+        HeapData::DictView(view) => {
+            // Views hold a reference to their dict
+            work_list.push(view.dict_id());
+        }
         HeapData::Iter(iter) => {
             // Iterator holds a reference to the iterable being iterated
             if let Value::Ref(id) = iter.value() {
@@ -1877,9 +2621,11 @@ impl<T: ResourceTracker> Drop for Heap<T> {
         // Mark all contained Objects as Dereferenced before dropping.
         // We use py_dec_ref_ids for this since it handles the marking
         // (we ignore the collected IDs since we're dropping everything anyway).
+        // Each value is dropped straight away so items shared between list or dict
+        // copies are marked by whichever copy is dropped last.
         let mut dummy_stack = Vec::new();
         for value in self.entries.iter_mut().flatten() {
-            if let Some(data) = &mut value.data {
+            if let Some(mut data) = value.data.take() {
                 data.py_dec_ref_ids(&mut dummy_stack);
             }
         }
@@ -1952,7 +2698,7 @@ impl<U: DropWithHeap> DropWithHeap for vec::IntoIter<U> {
     }
 }
 
-impl<const N: usize> DropWithHeap for [Value; N] {
+impl<U: DropWithHeap, const N: usize> DropWithHeap for [U; N] {
     fn drop_with_heap<T: ResourceTracker>(self, heap: &mut Heap<T>) {
         for value in self {
             value.drop_with_heap(heap);
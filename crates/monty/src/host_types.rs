@@ -0,0 +1,206 @@
+//! Conversions between `MontyObject` and common Rust host types.
+//!
+//! Each group of conversions is behind a feature named after the crate it supports:
+//! `chrono`, `decimal` (`rust_decimal`) and `uuid`. Conversions to `MontyObject` pick the
+//! closest Python type; conversions from `MontyObject` fail with `ConversionError` when the
+//! value has the wrong type or is out of range.
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc};
+
+    use crate::{
+        object::{ConversionError, MontyObject},
+        types::timedelta::{US_PER_DAY, US_PER_SECOND},
+    };
+
+    /// `chrono::NaiveDate` becomes `datetime.date`.
+    impl From<NaiveDate> for MontyObject {
+        #[expect(clippy::cast_possible_truncation, reason = "month and day always fit in u8")]
+        fn from(date: NaiveDate) -> Self {
+            Self::Date {
+                year: date.year(),
+                month: date.month() as u8,
+                day: date.day() as u8,
+            }
+        }
+    }
+
+    /// `chrono::NaiveDateTime` becomes a naive `datetime.datetime`.
+    ///
+    /// Sub-microsecond precision is truncated, and leap seconds are clamped to the last
+    /// microsecond of the preceding second.
+    impl From<NaiveDateTime> for MontyObject {
+        #[expect(clippy::cast_possible_truncation, reason = "time components always fit in u8")]
+        fn from(dt: NaiveDateTime) -> Self {
+            Self::DateTime {
+                year: dt.year(),
+                month: dt.month() as u8,
+                day: dt.day() as u8,
+                hour: dt.hour() as u8,
+                minute: dt.minute() as u8,
+                second: dt.second() as u8,
+                microsecond: (dt.nanosecond() / 1000).min(999_999),
+            }
+        }
+    }
+
+    /// `chrono::DateTime<Utc>` becomes a naive `datetime.datetime` in UTC.
+    impl From<DateTime<Utc>> for MontyObject {
+        fn from(dt: DateTime<Utc>) -> Self {
+            dt.naive_utc().into()
+        }
+    }
+
+    /// `chrono::TimeDelta` becomes `datetime.timedelta`, normalized like Python.
+    ///
+    /// Fails if the duration exceeds Python's limit of 999999999 days.
+    impl TryFrom<TimeDelta> for MontyObject {
+        type Error = ConversionError;
+
+        fn try_from(delta: TimeDelta) -> Result<Self, Self::Error> {
+            let out_of_range = || ConversionError::new("timedelta within 999999999 days", "TimeDelta");
+            let total_us = i128::from(delta.num_microseconds().ok_or_else(out_of_range)?);
+            let days = i32::try_from(total_us.div_euclid(US_PER_DAY)).map_err(|_| out_of_range())?;
+            if days.unsigned_abs() > 999_999_999 {
+                return Err(out_of_range());
+            }
+            let rem = total_us.rem_euclid(US_PER_DAY);
+            #[expect(clippy::cast_possible_truncation, reason = "remainder is less than a day")]
+            let (seconds, microseconds) = ((rem / US_PER_SECOND) as i32, (rem % US_PER_SECOND) as i32);
+            Ok(Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            })
+        }
+    }
+
+    impl TryFrom<&MontyObject> for NaiveDate {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            match value {
+                MontyObject::Date { year, month, day } => Self::from_ymd_opt(*year, (*month).into(), (*day).into())
+                    .ok_or_else(|| ConversionError::new("valid date", "date")),
+                _ => Err(ConversionError::new("date", value.type_name())),
+            }
+        }
+    }
+
+    impl TryFrom<&MontyObject> for NaiveDateTime {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            match value {
+                MontyObject::DateTime {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minute,
+                    second,
+                    microsecond,
+                } => NaiveDate::from_ymd_opt(*year, (*month).into(), (*day).into())
+                    .and_then(|date| {
+                        date.and_hms_micro_opt((*hour).into(), (*minute).into(), (*second).into(), *microsecond)
+                    })
+                    .ok_or_else(|| ConversionError::new("valid datetime", "datetime")),
+                _ => Err(ConversionError::new("datetime", value.type_name())),
+            }
+        }
+    }
+
+    /// Interprets a naive `datetime.datetime` as UTC.
+    impl TryFrom<&MontyObject> for DateTime<Utc> {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            NaiveDateTime::try_from(value).map(|dt| dt.and_utc())
+        }
+    }
+
+    impl TryFrom<&MontyObject> for TimeDelta {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            match value {
+                MontyObject::TimeDelta {
+                    days,
+                    seconds,
+                    microseconds,
+                } => Ok(Self::days((*days).into())
+                    + Self::seconds((*seconds).into())
+                    + Self::microseconds((*microseconds).into())),
+                _ => Err(ConversionError::new("timedelta", value.type_name())),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+mod decimal_impls {
+    use std::str::FromStr;
+
+    use rust_decimal::{Decimal, prelude::ToPrimitive};
+
+    use crate::object::{ConversionError, MontyObject};
+
+    /// `rust_decimal::Decimal` becomes an `int` if it has no fractional part and fits in
+    /// `i64`, otherwise the nearest `float`, since Monty has no `decimal` module.
+    impl From<Decimal> for MontyObject {
+        fn from(d: Decimal) -> Self {
+            if d.fract().is_zero()
+                && let Some(i) = d.to_i64()
+            {
+                Self::Int(i)
+            } else {
+                Self::Float(d.to_f64().unwrap_or(f64::NAN))
+            }
+        }
+    }
+
+    /// Accepts `int`, `float` and numeric `str` values.
+    impl TryFrom<&MontyObject> for Decimal {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            let err = || ConversionError::new("decimal", value.type_name());
+            match value {
+                MontyObject::Int(i) => Ok(Self::from(*i)),
+                MontyObject::BigInt(i) => Self::from_str(&i.to_string()).map_err(|_| err()),
+                MontyObject::Float(f) => Self::try_from(*f).map_err(|_| err()),
+                MontyObject::String(s) => Self::from_str(s.trim()).map_err(|_| err()),
+                _ => Err(err()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid_impls {
+    use uuid::Uuid;
+
+    use crate::object::{ConversionError, MontyObject};
+
+    /// `uuid::Uuid` becomes its lowercase hyphenated `str`, e.g. `"67e55044-10b1-426f-9247-bb680e5fe0c8"`.
+    impl From<Uuid> for MontyObject {
+        fn from(uuid: Uuid) -> Self {
+            Self::String(uuid.to_string())
+        }
+    }
+
+    /// Accepts a `str` in any format `Uuid::parse_str` understands, or 16 `bytes`.
+    impl TryFrom<&MontyObject> for Uuid {
+        type Error = ConversionError;
+
+        fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+            let err = || ConversionError::new("UUID", value.type_name());
+            match value {
+                MontyObject::String(s) => Self::parse_str(s).map_err(|_| err()),
+                MontyObject::Bytes(b) => Self::from_slice(b).map_err(|_| err()),
+                _ => Err(err()),
+            }
+        }
+    }
+}
@@ -12,13 +12,29 @@
 //! * 1000 to count(StaticStrings) - strings StaticStrings
 //! * 10_000+ - strings interned per executor
 
-use std::{str::FromStr, sync::LazyLock};
+use std::{
+    str::FromStr,
+    sync::{
+        LazyLock, Mutex, MutexGuard, OnceLock, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use ahash::AHashMap;
 use num_bigint::BigInt;
 use strum::{EnumString, FromRepr, IntoStaticStr};
 
-use crate::{function::Function, value::Value};
+use crate::{
+    bytecode::CompileOptions,
+    exception_private::ExcClassDef,
+    function::Function,
+    namespace::NamespaceId,
+    native_module::NativeModuleDef,
+    python_module::PythonModuleDef,
+    resource::{ResourceError, ResourceTracker},
+    run::ExecutionOptions,
+    value::Value,
+};
 
 /// Index into the string interner's storage.
 ///
@@ -69,7 +85,7 @@ static ASCII_STRS: LazyLock<[&'static str; 128]> = LazyLock::new(|| {
 });
 
 /// Static string values which are known at compile time and don't need to be interned.
-#[repr(u8)]
+#[repr(u16)]
 #[derive(
     Debug, Clone, Copy, FromRepr, EnumString, IntoStaticStr, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
 )]
@@ -172,8 +188,13 @@ pub enum StaticStrings {
     Zfill,
     // Additional string methods
     Encode,
+    Format,
     Isidentifier,
     Istitle,
+    FormatMap,
+    Translate,
+    Maketrans,
+    Expandtabs,
 
     // ==========================
     // Bytes methods
@@ -294,11 +315,225 @@ pub enum StaticStrings {
     // ==========================
     // Exception attributes
     Args,
+    #[strum(serialize = "__cause__")]
+    DunderCause,
 
     // ==========================
     // Type attributes
     #[strum(serialize = "__name__")]
     DunderName,
+    #[strum(serialize = "__main__")]
+    DunderMain,
+
+    // ==========================
+    // Context manager protocol
+    #[strum(serialize = "__enter__")]
+    DunderEnter,
+    #[strum(serialize = "__exit__")]
+    DunderExit,
+
+    // ==========================
+    // contextlib module strings
+    Contextlib,
+    Nullcontext,
+    Suppress,
+
+    // ==========================
+    // math module strings
+    Math,
+    Pi,
+    Tau,
+    Inf,
+    Nan,
+    Sqrt,
+    Floor,
+    Ceil,
+    Trunc,
+    Modf,
+    Fabs,
+    Exp,
+    Log,
+    #[strum(serialize = "log2")]
+    Log2,
+    #[strum(serialize = "log10")]
+    Log10,
+    Pow,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    #[strum(serialize = "atan2")]
+    Atan2,
+    Hypot,
+    Degrees,
+    Radians,
+    Copysign,
+    Fmod,
+    Fsum,
+    Isclose,
+    Isnan,
+    Isinf,
+    Isfinite,
+    Gcd,
+    Lcm,
+    Factorial,
+
+    // ==========================
+    // json module strings
+    Json,
+    Loads,
+    Dumps,
+    #[strum(serialize = "JSONDecodeError")]
+    JsonDecodeError,
+
+    // ==========================
+    // datetime and time module strings
+    Datetime,
+    Date,
+    Timedelta,
+    Time,
+    #[strum(serialize = "MINYEAR")]
+    Minyear,
+    #[strum(serialize = "MAXYEAR")]
+    Maxyear,
+    Today,
+    Now,
+    Fromisoformat,
+    Fromordinal,
+
+    // date, datetime and timedelta attributes and methods
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Microsecond,
+    Days,
+    Seconds,
+    Microseconds,
+    Weekday,
+    Isoweekday,
+    Isoformat,
+    Toordinal,
+    Strftime,
+    TotalSeconds,
+
+    // ==========================
+    // random module strings
+    Random,
+    Randint,
+    Uniform,
+    Choice,
+    Shuffle,
+    Seed,
+
+    // ==========================
+    // itertools module strings
+    Itertools,
+    Chain,
+    Product,
+    Islice,
+    Groupby,
+    Repeat,
+    Key,
+
+    // ==========================
+    // functools module strings
+    Functools,
+    Reduce,
+    Partial,
+    LruCache,
+    Cache,
+    Maxsize,
+    Func,
+    Keywords,
+    CacheInfo,
+    CacheClear,
+    #[strum(serialize = "CacheInfo")]
+    CacheInfoType,
+    Hits,
+    Misses,
+    Currsize,
+    #[strum(serialize = "__wrapped__")]
+    Wrapped,
+
+    // ==========================
+    // collections module strings
+    Collections,
+    Defaultdict,
+    #[strum(serialize = "Counter")]
+    Counter,
+    Deque,
+    Namedtuple,
+    DefaultFactory,
+    Subtract,
+    MostCommon,
+    Elements,
+    Total,
+    Appendleft,
+    Popleft,
+    Extendleft,
+    Rotate,
+    Maxlen,
+    Defaults,
+    #[strum(serialize = "_fields")]
+    NamedTupleFields,
+    #[strum(serialize = "_asdict")]
+    NamedTupleAsdict,
+    #[strum(serialize = "_replace")]
+    NamedTupleReplace,
+    #[strum(serialize = "_make")]
+    NamedTupleMake,
+
+    // ==========================
+    // re module strings
+    Re,
+    Compile,
+    Search,
+    Match,
+    Fullmatch,
+    Findall,
+    Finditer,
+    Sub,
+    Subn,
+    Escape,
+    #[strum(serialize = "IGNORECASE")]
+    Ignorecase,
+    #[strum(serialize = "MULTILINE")]
+    Multiline,
+    #[strum(serialize = "DOTALL")]
+    Dotall,
+    #[strum(serialize = "VERBOSE")]
+    Verbose,
+    #[strum(serialize = "ASCII")]
+    AsciiFlag,
+    #[strum(serialize = "UNICODE")]
+    Unicode,
+    #[strum(serialize = "NOFLAG")]
+    Noflag,
+    Error,
+    #[strum(serialize = "PatternError")]
+    PatternError,
+
+    // re.Pattern attributes
+    Pattern,
+    Flags,
+    Groups,
+    Groupindex,
+
+    // re.Match methods and attributes
+    Group,
+    Groupdict,
+    End,
+    Span,
+    Expand,
+    #[strum(serialize = "string")]
+    StringAttr,
+    Pos,
+    Endpos,
 
     // ==========================
     // pathlib module strings
@@ -358,7 +593,7 @@ impl StaticStrings {
     /// (e.g., it's an ASCII char or a dynamically interned string).
     pub fn from_string_id(id: StringId) -> Option<Self> {
         let enum_id = id.0.checked_sub(STATIC_STRING_ID_OFFSET)?;
-        u8::try_from(enum_id).ok().and_then(Self::from_repr)
+        u16::try_from(enum_id).ok().and_then(Self::from_repr)
     }
 }
 
@@ -454,6 +689,27 @@ impl ExtFunctionId {
     }
 }
 
+/// Unique identifier for user-defined exception classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct ExcClassId(u32);
+
+impl ExcClassId {
+    /// Creates an ExcClassId from a raw index value.
+    ///
+    /// Used by the bytecode VM to reconstruct ExcClassIds from operands stored
+    /// in bytecode. The caller is responsible for ensuring the index is valid.
+    #[inline]
+    pub fn from_index(index: u16) -> Self {
+        Self(u32::from(index))
+    }
+
+    /// Returns the raw index value.
+    #[inline]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
 /// A string, bytes, and long integer interner that stores unique values and returns indices for lookup.
 ///
 /// Interns are deduplicated on insertion - interning the same string twice returns
@@ -476,6 +732,8 @@ pub struct InternerBuilder {
     /// Storage for interned long integer literals, indexed by `LongIntId`.
     /// Not deduplicated since long integer literals are rare.
     long_ints: Vec<BigInt>,
+    /// User-defined exception classes, indexed by `ExcClassId`.
+    exc_classes: Vec<ExcClassDef>,
 }
 
 impl InternerBuilder {
@@ -502,6 +760,7 @@ impl InternerBuilder {
             strings: Vec::with_capacity(capacity),
             bytes: Vec::new(),
             long_ints: Vec::new(),
+            exc_classes: Vec::new(),
         }
     }
 
@@ -514,6 +773,13 @@ impl InternerBuilder {
         builder.strings.clone_from(&interns.strings);
         builder.bytes.clone_from(&interns.bytes);
         builder.long_ints.clone_from(&interns.long_ints);
+        builder.exc_classes.clone_from(&interns.exc_classes);
+        for dynamic in interns.dynamic.iter() {
+            builder.strings.extend_from_slice(&dynamic.strings);
+            builder.bytes.extend_from_slice(&dynamic.bytes);
+            builder.long_ints.extend_from_slice(&dynamic.long_ints);
+            builder.exc_classes.extend_from_slice(&dynamic.exc_classes);
+        }
 
         builder.string_map = builder
             .strings
@@ -550,6 +816,17 @@ impl InternerBuilder {
         }
     }
 
+    /// Returns the `StringId` of `s` if it is already interned, without interning it.
+    pub fn lookup(&self, s: &str) -> Option<StringId> {
+        if s.len() == 1 {
+            Some(StringId::from_ascii(s.as_bytes()[0]))
+        } else if let Ok(ss) = StaticStrings::from_str(s) {
+            Some(ss.into())
+        } else {
+            self.string_map.get(s).copied()
+        }
+    }
+
     /// Interns bytes, returning its `BytesId`.
     ///
     /// Unlike interns, bytes are not deduplicated (bytes literals are rare).
@@ -568,11 +845,25 @@ impl InternerBuilder {
         id
     }
 
+    /// Registers a user-defined exception class, returning its `ExcClassId`.
+    pub fn add_exc_class(&mut self, def: ExcClassDef) -> ExcClassId {
+        let id = ExcClassId(self.exc_classes.len().try_into().expect("ExcClassId overflow"));
+        self.exc_classes.push(def);
+        id
+    }
+
     /// Looks up a string by its `StringId`.
     #[inline]
     pub fn get_str(&self, id: StringId) -> &str {
         get_str(&self.strings, id)
     }
+
+    /// Returns whether every interned string can be referenced by a bytecode operand.
+    ///
+    /// Name operands are `u16`, so code interning strings past that can't be compiled.
+    pub fn ids_fit_operands(&self) -> bool {
+        INTERN_STRING_ID_OFFSET + self.strings.len() <= usize::from(u16::MAX) + 1
+    }
 }
 
 /// Looks up a string by its `StringId`.
@@ -601,6 +892,17 @@ pub(crate) struct Interns {
     long_ints: Vec<BigInt>,
     functions: Vec<Function>,
     external_functions: Vec<String>,
+    exc_classes: Vec<ExcClassDef>,
+    native_modules: Vec<NativeModuleDef>,
+    python_modules: Vec<PythonModuleDef>,
+    /// Name of each slot of the main module's global namespace, empty for unnamed slots.
+    global_names: Vec<StringId>,
+    /// Options used to compile code at runtime, matching the main module.
+    compile_options: CompileOptions,
+    /// Options controlling `eval()` and `exec()`.
+    execution_options: ExecutionOptions,
+    /// Code compiled at runtime by `eval()` and `exec()`, see `add_dynamic_code`.
+    dynamic: DynamicCodes,
 }
 
 impl Interns {
@@ -611,6 +913,13 @@ impl Interns {
             long_ints: interner.long_ints,
             functions,
             external_functions,
+            exc_classes: interner.exc_classes,
+            native_modules: Vec::new(),
+            python_modules: Vec::new(),
+            global_names: Vec::new(),
+            compile_options: CompileOptions::default(),
+            execution_options: ExecutionOptions::default(),
+            dynamic: DynamicCodes::default(),
         }
     }
 
@@ -621,7 +930,17 @@ impl Interns {
     /// Panics if the `StringId` is invalid.
     #[inline]
     pub fn get_str(&self, id: StringId) -> &str {
-        get_str(&self.strings, id)
+        match id.index().checked_sub(INTERN_STRING_ID_OFFSET) {
+            Some(index) if index >= self.strings.len() => self
+                .dynamic
+                .get(
+                    index - self.strings.len(),
+                    |offsets| offsets.strings,
+                    |code| code.strings.as_slice(),
+                )
+                .expect("Invalid string ID"),
+            _ => get_str(&self.strings, id),
+        }
     }
 
     /// Looks up bytes by their `BytesId`.
@@ -631,7 +950,17 @@ impl Interns {
     /// Panics if the `BytesId` is invalid.
     #[inline]
     pub fn get_bytes(&self, id: BytesId) -> &[u8] {
-        &self.bytes[id.index()]
+        match self.bytes.get(id.index()) {
+            Some(bytes) => bytes,
+            None => self
+                .dynamic
+                .get(
+                    id.index() - self.bytes.len(),
+                    |offsets| offsets.bytes,
+                    |code| code.bytes.as_slice(),
+                )
+                .expect("Invalid bytes ID"),
+        }
     }
 
     /// Looks up a long integer by its `LongIntId`.
@@ -641,7 +970,17 @@ impl Interns {
     /// Panics if the `LongIntId` is invalid.
     #[inline]
     pub fn get_long_int(&self, id: LongIntId) -> &BigInt {
-        &self.long_ints[id.index()]
+        match self.long_ints.get(id.index()) {
+            Some(long_int) => long_int,
+            None => self
+                .dynamic
+                .get(
+                    id.index() - self.long_ints.len(),
+                    |offsets| offsets.long_ints,
+                    |code| code.long_ints.as_slice(),
+                )
+                .expect("Invalid long integer ID"),
+        }
     }
 
     /// Lookup a function by its `FunctionId`
@@ -651,7 +990,26 @@ impl Interns {
     /// Panics if the `FunctionId` is invalid.
     #[inline]
     pub fn get_function(&self, id: FunctionId) -> &Function {
-        self.functions.get(id.index()).expect("Function not found")
+        match self.functions.get(id.index()) {
+            Some(function) => function,
+            None => self
+                .dynamic
+                .get(
+                    id.index() - self.functions.len(),
+                    |offsets| offsets.functions,
+                    |code| code.functions.as_slice(),
+                )
+                .expect("Function not found"),
+        }
+    }
+
+    /// Returns whether the function is the body of code compiled by `eval()` or `exec()`,
+    /// rather than a function that code defines.
+    pub fn is_dynamic_body(&self, id: FunctionId) -> bool {
+        id.index()
+            .checked_sub(self.functions.len())
+            .and_then(|index| self.dynamic.code_with(index, |offsets| offsets.functions))
+            .is_some_and(|code| code.body == id)
     }
 
     /// Lookup an external function name by its `ExtFunctionId`
@@ -667,6 +1025,26 @@ impl Interns {
             .clone()
     }
 
+    /// Lookup a user-defined exception class by its `ExcClassId`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `ExcClassId` is invalid.
+    #[inline]
+    pub fn get_exc_class(&self, id: ExcClassId) -> &ExcClassDef {
+        match self.exc_classes.get(id.index()) {
+            Some(def) => def,
+            None => self
+                .dynamic
+                .get(
+                    id.index() - self.exc_classes.len(),
+                    |offsets| offsets.exc_classes,
+                    |code| code.exc_classes.as_slice(),
+                )
+                .expect("Exception class not found"),
+        }
+    }
+
     /// Sets the compiled functions.
     ///
     /// This is called after compilation to populate the functions that were
@@ -675,10 +1053,462 @@ impl Interns {
         self.functions = functions;
     }
 
-    /// Returns a clone of the compiled function table.
+    /// Sets the names of the main module's global slots, see `PrepareResult::slot_names`.
+    pub fn set_global_names(&mut self, global_names: Vec<StringId>) {
+        self.global_names = global_names;
+    }
+
+    /// Returns the names of the main module's global slots, indexed by slot.
+    ///
+    /// Unlike the local names recorded in `Code`, these cover every global, including
+    /// those only assigned by functions and inputs the module never reads, or by `exec()`.
+    pub fn global_names(&self) -> &[StringId] {
+        self.dynamic.global_names().unwrap_or(&self.global_names)
+    }
+
+    /// Sets the options used to compile code at runtime.
+    pub fn set_compile_options(&mut self, options: CompileOptions) {
+        self.compile_options = options;
+    }
+
+    /// Returns the options used to compile code at runtime.
+    pub fn compile_options(&self) -> CompileOptions {
+        self.compile_options
+    }
+
+    /// Sets the options controlling `eval()` and `exec()`.
+    pub fn set_execution_options(&mut self, options: ExecutionOptions) {
+        self.execution_options = options;
+    }
+
+    /// Returns the options controlling `eval()` and `exec()`.
+    pub fn execution_options(&self) -> &ExecutionOptions {
+        &self.execution_options
+    }
+
+    /// Returns the body of the code previously compiled for `key`, if any.
+    pub fn find_dynamic_code(&self, key: &DynamicCodeKey) -> Option<FunctionId> {
+        self.dynamic.find(key)
+    }
+
+    /// Returns the number of sources compiled at runtime.
+    pub fn dynamic_code_count(&self) -> usize {
+        self.dynamic.len()
+    }
+
+    /// Returns interns holding the values of `interner`, to compile code at runtime with.
+    ///
+    /// `interner` starts from `InternerBuilder::from_interns`, so it extends this table. The
+    /// result has no functions; once compiled, the code is added with `add_dynamic_code`.
+    pub fn for_dynamic_code(&self, interner: InternerBuilder) -> Self {
+        let mut interns = Self::new(interner, Vec::new(), Vec::new());
+        interns.native_modules.clone_from(&self.native_modules);
+        interns.python_modules.clone_from(&self.python_modules);
+        interns
+    }
+
+    /// Appends code compiled at runtime by `eval()` or `exec()`, returning the id of its body.
+    ///
+    /// `compiled` comes from `for_dynamic_code`, and `functions` are the code's functions,
+    /// numbered from `function_count`, the last of which is its body. Only values past the end of
+    /// this table are added. `global_names` replaces the main module's global slot names if
+    /// the code added globals.
+    ///
+    /// The VM holds the interns by shared reference, so values are only ever appended and
+    /// existing ids stay valid. Interns that can compile code are never shared between
+    /// running VMs, see `Executor::run_interns`.
+    ///
+    /// The code is kept until the interns are dropped, so its memory is charged to `tracker`
+    /// and never freed; nothing is added if that fails.
+    pub fn add_dynamic_code(
+        &self,
+        key: DynamicCodeKey,
+        mut compiled: Self,
+        functions: Vec<Function>,
+        global_names: Option<Vec<StringId>>,
+        tracker: &mut impl ResourceTracker,
+    ) -> Result<FunctionId, ResourceError> {
+        let end = self.dynamic.end_offsets();
+        let body_index = self.functions.len() + end.functions + functions.len() - 1;
+        let body = FunctionId(u32::try_from(body_index).expect("FunctionId overflow"));
+        let code = DynamicCode {
+            body,
+            strings: compiled.strings.split_off(self.strings.len() + end.strings),
+            bytes: compiled.bytes.split_off(self.bytes.len() + end.bytes),
+            long_ints: compiled.long_ints.split_off(self.long_ints.len() + end.long_ints),
+            functions,
+            exc_classes: compiled.exc_classes.split_off(self.exc_classes.len() + end.exc_classes),
+            global_names,
+            offsets: DynamicOffsets::default(),
+        };
+        tracker.on_allocate(|| key.estimate_size() + code.estimate_size())?;
+        self.dynamic.push(key, code);
+        Ok(body)
+    }
+
+    /// Returns the number of functions, including those compiled at runtime.
+    pub fn function_count(&self) -> usize {
+        self.functions.len() + self.dynamic.end_offsets().functions
+    }
+
+    /// Sets the host-provided native modules that `import` can resolve to.
+    pub fn set_native_modules(&mut self, native_modules: Vec<NativeModuleDef>) {
+        self.native_modules = native_modules;
+    }
+
+    /// Returns the index of the native module named `name`, if one is registered.
+    pub fn find_native_module(&self, name: StringId) -> Option<usize> {
+        self.native_modules.iter().position(|module| module.name() == name)
+    }
+
+    /// Lookup a native module by the index returned from `find_native_module`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is invalid.
+    #[inline]
+    pub fn get_native_module(&self, index: usize) -> &NativeModuleDef {
+        self.native_modules.get(index).expect("Native module not found")
+    }
+
+    /// Sets the host-supplied Python modules that `import` can resolve to.
+    pub fn set_python_modules(&mut self, python_modules: Vec<PythonModuleDef>) {
+        self.python_modules = python_modules;
+    }
+
+    /// Records the compiled body of the Python module at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is invalid.
+    pub fn set_python_module_body(&mut self, index: usize, body: FunctionId) {
+        self.python_modules
+            .get_mut(index)
+            .expect("Python module not found")
+            .set_body(body);
+    }
+
+    /// Returns the index of the Python module named `name`, if one is registered.
+    pub fn find_python_module(&self, name: StringId) -> Option<usize> {
+        self.python_modules.iter().position(|module| module.name() == name)
+    }
+
+    /// Lookup a Python module by the index returned from `find_python_module`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is invalid.
+    #[inline]
+    pub fn get_python_module(&self, index: usize) -> &PythonModuleDef {
+        self.python_modules.get(index).expect("Python module not found")
+    }
+
+    /// Returns the number of registered Python modules, each of which gets a reserved namespace.
+    pub fn python_module_count(&self) -> usize {
+        self.python_modules.len()
+    }
+
+    /// Returns the source of the Python module whose code ranges use `filename`, if any.
+    ///
+    /// Used to show traceback previews for frames inside imported modules.
+    pub fn python_module_source(&self, filename: StringId) -> Option<&str> {
+        self.python_modules
+            .iter()
+            .find(|module| module.filename() == filename)
+            .map(PythonModuleDef::source)
+    }
+
+    /// Returns a clone of the compiled function table, including functions compiled at runtime.
     ///
     /// Used by REPL incremental compilation to preserve existing function IDs.
     pub(crate) fn functions_clone(&self) -> Vec<Function> {
-        self.functions.clone()
+        let mut functions = self.functions.clone();
+        for code in self.dynamic.iter() {
+            functions.extend_from_slice(&code.functions);
+        }
+        functions
+    }
+}
+
+/// Identifies code compiled by `eval()` or `exec()`, so running the same source in the
+/// same place again reuses it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DynamicCodeKey {
+    /// The source code.
+    pub source: String,
+    /// Whether the code was compiled by `eval()`, as a single expression.
+    pub is_eval: bool,
+    /// Names of the variables passed to the code as parameters, `None` for code run in the
+    /// main module's global namespace.
+    pub params: Option<Vec<String>>,
+    /// Namespace holding the globals the code can read, `None` if it only sees its parameters.
+    pub globals: Option<NamespaceId>,
+}
+
+impl DynamicCodeKey {
+    /// Estimates the memory used by the key in bytes.
+    fn estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.source.len()
+            + self
+                .params
+                .iter()
+                .flatten()
+                .map(|name| std::mem::size_of::<String>() + name.len())
+                .sum::<usize>()
+    }
+}
+
+/// Code compiled at runtime by `eval()` or `exec()`.
+///
+/// Its strings, bytes, long integers, functions and exception classes are numbered after
+/// those of the code compiled before it, so ids stay unique across the whole `Interns`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct DynamicCode {
+    /// The function running the code.
+    body: FunctionId,
+    strings: Vec<String>,
+    bytes: Vec<Vec<u8>>,
+    long_ints: Vec<BigInt>,
+    functions: Vec<Function>,
+    exc_classes: Vec<ExcClassDef>,
+    /// Names of the main module's global slots once the code is compiled, if it added globals.
+    global_names: Option<Vec<StringId>>,
+    /// Position of the code's values among those of all the code, set when it's added.
+    #[serde(skip)]
+    offsets: DynamicOffsets,
+}
+
+impl DynamicCode {
+    /// Estimates the memory used by the code in bytes.
+    fn estimate_size(&self) -> usize {
+        let strings: usize = self
+            .strings
+            .iter()
+            .map(|string| std::mem::size_of::<String>() + string.len())
+            .sum();
+        let bytes: usize = self
+            .bytes
+            .iter()
+            .map(|bytes| std::mem::size_of::<Vec<u8>>() + bytes.len())
+            .sum();
+        let long_ints: usize = self
+            .long_ints
+            .iter()
+            .map(|long_int| {
+                let bits = usize::try_from(long_int.bits()).unwrap_or(usize::MAX);
+                std::mem::size_of::<BigInt>() + bits.saturating_add(7) / 8
+            })
+            .sum();
+        let functions: usize = self
+            .functions
+            .iter()
+            .map(|function| {
+                std::mem::size_of::<Function>()
+                    + function.slot_names.len() * std::mem::size_of::<StringId>()
+                    + function.code.estimate_size()
+            })
+            .sum();
+        std::mem::size_of::<Self>()
+            + strings
+            + bytes
+            + long_ints
+            + functions
+            + self.exc_classes.len() * std::mem::size_of::<ExcClassDef>()
+            + self.global_names.as_ref().map_or(0, Vec::len) * std::mem::size_of::<StringId>()
+    }
+
+    /// Returns the offsets of the values of the code added after this one.
+    fn end_offsets(&self) -> DynamicOffsets {
+        DynamicOffsets {
+            strings: self.offsets.strings + self.strings.len(),
+            bytes: self.offsets.bytes + self.bytes.len(),
+            long_ints: self.offsets.long_ints + self.long_ints.len(),
+            functions: self.offsets.functions + self.functions.len(),
+            exc_classes: self.offsets.exc_classes + self.exc_classes.len(),
+        }
+    }
+}
+
+/// Index of the first value of each kind of a `DynamicCode`, in the concatenation of the
+/// values of that kind of all the code.
+#[derive(Debug, Clone, Copy, Default)]
+struct DynamicOffsets {
+    strings: usize,
+    bytes: usize,
+    long_ints: usize,
+    functions: usize,
+    exc_classes: usize,
+}
+
+/// Number of segments of `DynamicCodes`, enough for any number of code that fits in memory.
+const DYNAMIC_SEGMENTS: usize = usize::BITS as usize;
+
+/// Append-only storage for code compiled at runtime by `eval()` and `exec()`.
+///
+/// Appending only needs a shared reference, so the VM can compile code while it holds
+/// `&Interns`, and references to existing code stay valid: code is never moved, segment
+/// `k` holds the `2^k` code from index `2^k - 1`, and is allocated when the first of them
+/// is added. Code and values are looked up by index without walking the storage.
+#[derive(Debug)]
+struct DynamicCodes {
+    segments: [OnceLock<Box<[OnceLock<DynamicCode>]>>; DYNAMIC_SEGMENTS],
+    /// Number of code added, only increased once the code is in its slot.
+    len: AtomicUsize,
+    /// One more than the index of the last code that set `global_names`, zero if none did.
+    global_names_end: AtomicUsize,
+    /// Body of the code compiled for each key. Also held while adding code, so code is
+    /// only ever added by one thread at a time.
+    index: Mutex<AHashMap<DynamicCodeKey, FunctionId>>,
+}
+
+impl Default for DynamicCodes {
+    fn default() -> Self {
+        Self {
+            segments: std::array::from_fn(|_| OnceLock::new()),
+            len: AtomicUsize::new(0),
+            global_names_end: AtomicUsize::new(0),
+            index: Mutex::default(),
+        }
+    }
+}
+
+impl DynamicCodes {
+    /// Returns the number of code added.
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns the code at `index`, oldest first.
+    fn code(&self, index: usize) -> Option<&DynamicCode> {
+        if index >= self.len() {
+            return None;
+        }
+        let (segment, slot) = segment_slot(index);
+        self.segments[segment].get()?.get(slot)?.get()
+    }
+
+    /// Iterates over the code, oldest first.
+    fn iter(&self) -> impl Iterator<Item = &DynamicCode> {
+        (0..self.len()).filter_map(|index| self.code(index))
+    }
+
+    /// Returns the offsets the next code added would get, which are also the number of
+    /// values of each kind of all the code.
+    fn end_offsets(&self) -> DynamicOffsets {
+        self.len()
+            .checked_sub(1)
+            .and_then(|last| self.code(last))
+            .map(DynamicCode::end_offsets)
+            .unwrap_or_default()
+    }
+
+    /// Returns the value at `index` in the concatenation of one kind of value of all the code.
+    ///
+    /// `offset` picks the kind's offset and `items` the kind's values out of a code.
+    fn get<'s, T>(
+        &'s self,
+        index: usize,
+        offset: impl Fn(&DynamicOffsets) -> usize,
+        items: impl Fn(&'s DynamicCode) -> &'s [T],
+    ) -> Option<&'s T> {
+        let code = self.code_with(index, &offset)?;
+        items(code).get(index - offset(&code.offsets))
+    }
+
+    /// Returns the last code whose values of one kind start at or before `index`, which is
+    /// the code holding the value at `index` if there is one.
+    fn code_with(&self, index: usize, offset: impl Fn(&DynamicOffsets) -> usize) -> Option<&DynamicCode> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if offset(&self.code(mid)?.offsets) <= index {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        self.code(low.checked_sub(1)?)
+    }
+
+    /// Returns the global slot names set by the last code that added globals, if any.
+    fn global_names(&self) -> Option<&[StringId]> {
+        let end = self.global_names_end.load(Ordering::Acquire);
+        self.code(end.checked_sub(1)?)?.global_names.as_deref()
+    }
+
+    /// Returns the body of the code compiled for `key`, if any.
+    fn find(&self, key: &DynamicCodeKey) -> Option<FunctionId> {
+        self.lock_index().get(key).copied()
+    }
+
+    /// Appends `code`, compiled for `key`.
+    fn push(&self, key: DynamicCodeKey, code: DynamicCode) {
+        let mut index = self.lock_index();
+        index.insert(key, code.body);
+        self.push_code(code);
+    }
+
+    /// Appends `code` at the end of the storage.
+    ///
+    /// Callers hold the index lock, or have the only reference to the storage.
+    fn push_code(&self, mut code: DynamicCode) {
+        let index = self.len();
+        code.offsets = self.end_offsets();
+        let sets_global_names = code.global_names.is_some();
+        let (segment, slot) = segment_slot(index);
+        let segment = self.segments[segment].get_or_init(|| (0..1usize << segment).map(|_| OnceLock::new()).collect());
+        assert!(segment[slot].set(code).is_ok(), "dynamic code slot already filled");
+        if sets_global_names {
+            self.global_names_end.store(index + 1, Ordering::Release);
+        }
+        self.len.store(index + 1, Ordering::Release);
+    }
+
+    /// Locks the index.
+    fn lock_index(&self) -> MutexGuard<'_, AHashMap<DynamicCodeKey, FunctionId>> {
+        // inserting can't leave the map half updated, so it's consistent even if poisoned
+        self.index.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the segment of `DynamicCodes` holding the code at `index`, and its slot there.
+fn segment_slot(index: usize) -> (usize, usize) {
+    let segment = (index + 1).ilog2() as usize;
+    (segment, index + 1 - (1 << segment))
+}
+
+impl Clone for DynamicCodes {
+    fn clone(&self) -> Self {
+        let codes = Self::default();
+        for code in self.iter() {
+            codes.push_code(code.clone());
+        }
+        *codes.lock_index() = self.lock_index().clone();
+        codes
+    }
+}
+
+impl serde::Serialize for DynamicCodes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let codes: Vec<&DynamicCode> = self.iter().collect();
+        let index = self.lock_index();
+        // sorted so the same code always serializes to the same bytes
+        let mut keys: Vec<(&DynamicCodeKey, FunctionId)> = index.iter().map(|(key, body)| (key, *body)).collect();
+        keys.sort_unstable_by_key(|(_, body)| *body);
+        serde::Serialize::serialize(&(codes, keys), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DynamicCodes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (codes, keys): (Vec<DynamicCode>, Vec<(DynamicCodeKey, FunctionId)>) =
+            serde::Deserialize::deserialize(deserializer)?;
+        let dynamic = Self::default();
+        for code in codes {
+            dynamic.push_code(code);
+        }
+        *dynamic.lock_index() = keys.into_iter().collect();
+        Ok(dynamic)
     }
 }
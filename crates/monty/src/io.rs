@@ -1,6 +1,10 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+    time::SystemTime,
+};
 
-use crate::exception_public::MontyException;
+use crate::{exception_private::ExcType, exception_public::MontyException};
 
 /// Output handler for the `print()` builtin function.
 ///
@@ -10,15 +14,15 @@ use crate::exception_public::MontyException;
 ///
 /// # Variants
 /// - `Disabled` - Silently discards all output (useful for benchmarking or suppressing output)
-/// - `Stdout` - Writes to standard output (the default behavior)
-/// - `Collect` - Accumulates output into an owned `String` for programmatic access
+/// - `Stdout` - Writes to standard output and standard error (the default behavior)
+/// - `Collect` - Accumulates output from both streams into an owned `String` for programmatic access
 /// - `Callback` - Delegates to a user-provided [`PrintWriterCallback`] implementation
 pub enum PrintWriter<'a> {
     /// Silently discard all output.
     Disabled,
-    /// Write to standard output.
+    /// Write to standard output, and `print(..., file=sys.stderr)` to standard error.
     Stdout,
-    /// Collect all output into a string.
+    /// Collect all output, from both streams, into a string.
     Collect(String),
     /// Delegate to a custom callback.
     Callback(&'a mut dyn PrintWriterCallback),
@@ -33,10 +37,7 @@ impl PrintWriter<'_> {
     pub fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
         match self {
             Self::Disabled => Ok(()),
-            Self::Stdout => {
-                print!("{output}");
-                Ok(())
-            }
+            Self::Stdout => io::stdout().write_all(output.as_bytes()).map_err(io_error_to_exception),
             Self::Collect(buf) => {
                 buf.push_str(&output);
                 Ok(())
@@ -53,8 +54,10 @@ impl PrintWriter<'_> {
         match self {
             Self::Disabled => Ok(()),
             Self::Stdout => {
-                print!("{end}");
-                Ok(())
+                let mut buf = [0; 4];
+                io::stdout()
+                    .write_all(end.encode_utf8(&mut buf).as_bytes())
+                    .map_err(io_error_to_exception)
             }
             Self::Collect(buf) => {
                 buf.push(end);
@@ -64,6 +67,47 @@ impl PrintWriter<'_> {
         }
     }
 
+    /// Called once for each formatted argument passed to `print(..., file=sys.stderr)`.
+    ///
+    /// Like [`stdout_write`](Self::stdout_write), but for standard error.
+    pub fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        match self {
+            Self::Disabled => Ok(()),
+            Self::Stdout => io::stderr().write_all(output.as_bytes()).map_err(io_error_to_exception),
+            Self::Collect(buf) => {
+                buf.push_str(&output);
+                Ok(())
+            }
+            Self::Callback(cb) => cb.stderr_write(output),
+        }
+    }
+
+    /// Appends a single character to standard error.
+    pub fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        match self {
+            Self::Disabled => Ok(()),
+            Self::Stdout => {
+                let mut buf = [0; 4];
+                io::stderr()
+                    .write_all(end.encode_utf8(&mut buf).as_bytes())
+                    .map_err(io_error_to_exception)
+            }
+            Self::Collect(buf) => {
+                buf.push(end);
+                Ok(())
+            }
+            Self::Callback(cb) => cb.stderr_push(end),
+        }
+    }
+
+    /// Called once at the end of each `print()` call, after the terminator has been written.
+    pub fn print_done(&mut self) -> Result<(), MontyException> {
+        match self {
+            Self::Callback(cb) => cb.print_done(),
+            _ => Ok(()),
+        }
+    }
+
     /// Returns the collected output if this is a `Collect` variant.
     ///
     /// Returns `None` for other variants.
@@ -76,6 +120,12 @@ impl PrintWriter<'_> {
     }
 }
 
+/// Converts a failed write to stdout or stderr (e.g. a closed pipe) into an `OSError`
+/// that sandboxed code can catch, instead of panicking like `print!` would.
+fn io_error_to_exception(err: io::Error) -> MontyException {
+    MontyException::new(ExcType::OSError, Some(err.to_string()))
+}
+
 /// Trait for custom output handling from the `print()` builtin function.
 ///
 /// Implement this trait and pass it via [`PrintWriter::Callback`] to capture
@@ -83,6 +133,9 @@ impl PrintWriter<'_> {
 pub trait PrintWriterCallback {
     /// Called once for each formatted argument passed to `print()`.
     ///
+    /// Returning an error raises it as a Python exception at the `print()` call, where
+    /// sandboxed code can catch it like any other exception.
+    ///
     /// This method is responsible for writing only the given argument's text, and must
     /// not add separators or a trailing newline. Separators (such as spaces) and the
     /// final terminator (such as a newline) are emitted via [`stdout_push`](Self::stdout_push).
@@ -99,4 +152,155 @@ pub trait PrintWriterCallback {
     /// # Arguments
     /// * `end` - The character to print after the formatted output.
     fn stdout_push(&mut self, end: char) -> Result<(), MontyException>;
+
+    /// Called once for each formatted argument passed to `print(..., file=sys.stderr)`.
+    ///
+    /// Defaults to [`stdout_write`](Self::stdout_write), so implementations that don't
+    /// distinguish the streams see all output in one place.
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stdout_write(output)
+    }
+
+    /// Add a single character to stderr.
+    ///
+    /// Defaults to [`stdout_push`](Self::stdout_push).
+    fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stdout_push(end)
+    }
+
+    /// Called once at the end of each `print()` call, after the terminator has been written.
+    ///
+    /// Does nothing by default. Implementations that group output by `print()` call,
+    /// such as [`PrintEventWriter`], use it to know when a call's output is complete.
+    fn print_done(&mut self) -> Result<(), MontyException> {
+        Ok(())
+    }
+}
+
+/// A [`PrintWriterCallback`] that keeps standard output and standard error in separate strings.
+///
+/// Unlike [`PrintWriter::Collect`], which merges both streams, this lets hosts tell
+/// `print(..., file=sys.stderr)` output apart from regular prints.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    /// Everything printed to standard output.
+    pub stdout: String,
+    /// Everything printed with `file=sys.stderr`.
+    pub stderr: String,
+}
+
+impl PrintWriterCallback for CapturedOutput {
+    fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stdout.push_str(&output);
+        Ok(())
+    }
+
+    fn stdout_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stdout.push(end);
+        Ok(())
+    }
+
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stderr.push_str(&output);
+        Ok(())
+    }
+
+    fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stderr.push(end);
+        Ok(())
+    }
+}
+
+/// The stream a `print()` call wrote to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum PrintStream {
+    /// Standard output, the default.
+    Stdout,
+    /// Standard error, selected with `file=sys.stderr`.
+    Stderr,
+}
+
+/// The output of one `print()` call, as delivered by [`PrintEventWriter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintEvent {
+    /// The stream the call wrote to.
+    pub stream: PrintStream,
+    /// Everything the call wrote, including separators and the `end` string.
+    pub text: String,
+    /// Wall-clock time when the call finished, for ordering against host logs.
+    ///
+    /// Always `UNIX_EPOCH` on `wasm32-unknown-unknown`, which has no system clock.
+    pub timestamp: SystemTime,
+    /// Zero-based position of the call among all `print()` calls seen by this writer.
+    pub index: usize,
+}
+
+/// `SystemTime::now()`, or `UNIX_EPOCH` where calling it would panic.
+fn wall_clock_now() -> SystemTime {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        SystemTime::UNIX_EPOCH
+    } else {
+        SystemTime::now()
+    }
+}
+
+/// A [`PrintWriterCallback`] that delivers each `print()` call as a single [`PrintEvent`].
+///
+/// Output is buffered until the call finishes, then passed to `handler`. An error returned
+/// by the handler is raised at the `print()` call, like errors from any other callback.
+pub struct PrintEventWriter<F> {
+    handler: F,
+    stream: PrintStream,
+    text: String,
+    count: usize,
+}
+
+impl<F: FnMut(PrintEvent) -> Result<(), MontyException>> PrintEventWriter<F> {
+    /// Creates a writer that passes each finished `print()` call to `handler`.
+    pub fn new(handler: F) -> Self {
+        Self {
+            handler,
+            stream: PrintStream::Stdout,
+            text: String::new(),
+            count: 0,
+        }
+    }
+}
+
+impl<F: FnMut(PrintEvent) -> Result<(), MontyException>> PrintWriterCallback for PrintEventWriter<F> {
+    fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stream = PrintStream::Stdout;
+        self.text.push_str(&output);
+        Ok(())
+    }
+
+    fn stdout_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stream = PrintStream::Stdout;
+        self.text.push(end);
+        Ok(())
+    }
+
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stream = PrintStream::Stderr;
+        self.text.push_str(&output);
+        Ok(())
+    }
+
+    fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stream = PrintStream::Stderr;
+        self.text.push(end);
+        Ok(())
+    }
+
+    fn print_done(&mut self) -> Result<(), MontyException> {
+        let event = PrintEvent {
+            stream: self.stream,
+            text: std::mem::take(&mut self.text),
+            timestamp: wall_clock_now(),
+            index: self.count,
+        };
+        self.count += 1;
+        (self.handler)(event)
+    }
 }
@@ -0,0 +1,287 @@
+//! Conversion between `MontyObject` and plain JSON values.
+//!
+//! Unlike the derived serde format (`{"Int": 1}`), this produces the JSON a host would
+//! write by hand: `1`, `"a"`, `[1, 2]`, `{"a": 1}`. Types without a JSON equivalent are
+//! handled according to a [`JsonPolicy`].
+
+use std::fmt;
+
+use num_traits::ToPrimitive;
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::object::MontyObject;
+
+/// How `MontyObject::to_json` encodes `bytes`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesPolicy {
+    /// A standard base64 string, with padding.
+    #[default]
+    Base64,
+    /// An array of integers in `0..=255`.
+    Array,
+    /// Fail with a `ToJsonError`.
+    Error,
+}
+
+/// How `MontyObject::to_json` encodes values with no JSON equivalent.
+///
+/// This covers `...`, exceptions, external objects, types, functions, non-finite floats, and dict keys
+/// that aren't strings, numbers, booleans or `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FallbackPolicy {
+    /// The value's `repr()` as a string.
+    #[default]
+    Repr,
+    /// Fail with a `ToJsonError`, like Python's `json.dumps`.
+    Error,
+}
+
+/// Options for `MontyObject::to_json`.
+///
+/// Tuples, sets, deques and named tuples always become arrays, dataclasses become objects
+/// of their attributes, dates and datetimes become ISO 8601 strings, and timedeltas
+/// become a number of seconds.
+///
+/// # Example
+/// ```
+/// use monty::{BytesPolicy, JsonPolicy, MontyObject};
+///
+/// let policy = JsonPolicy::default().bytes(BytesPolicy::Array);
+/// let json = MontyObject::Bytes(b"hi".to_vec()).to_json(&policy).unwrap();
+/// assert_eq!(json, serde_json::json!([104, 105]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JsonPolicy {
+    bytes: BytesPolicy,
+    fallback: FallbackPolicy,
+}
+
+impl JsonPolicy {
+    /// Sets how `bytes` values are encoded.
+    #[must_use]
+    pub fn bytes(mut self, policy: BytesPolicy) -> Self {
+        self.bytes = policy;
+        self
+    }
+
+    /// Sets how values with no JSON equivalent are encoded.
+    #[must_use]
+    pub fn fallback(mut self, policy: FallbackPolicy) -> Self {
+        self.fallback = policy;
+        self
+    }
+}
+
+/// Error returned by `MontyObject::to_json` when the policy forbids encoding a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToJsonError {
+    /// Python type name of the value that couldn't be encoded, e.g. `"bytes"`.
+    pub type_name: &'static str,
+    /// Whether the value was a dict key rather than a value.
+    pub is_key: bool,
+}
+
+impl fmt::Display for ToJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_key {
+            write!(f, "keys must be str, int, float, bool or None, not {}", self.type_name)
+        } else {
+            write!(f, "Object of type {} is not JSON serializable", self.type_name)
+        }
+    }
+}
+
+impl std::error::Error for ToJsonError {}
+
+impl MontyObject {
+    /// Converts to a plain JSON value, e.g. `{"a": [1, 2]}`.
+    ///
+    /// # Errors
+    /// Returns `ToJsonError` if `policy` forbids encoding a value found in `self`.
+    pub fn to_json(&self, policy: &JsonPolicy) -> Result<JsonValue, ToJsonError> {
+        let json = match self {
+            Self::None => JsonValue::Null,
+            Self::Bool(b) => JsonValue::Bool(*b),
+            Self::Int(i) => JsonValue::from(*i),
+            Self::BigInt(i) => {
+                if let Some(n) = i.to_i64() {
+                    JsonValue::from(n)
+                } else if let Some(n) = i.to_u64() {
+                    JsonValue::from(n)
+                } else {
+                    return self.fallback(policy);
+                }
+            }
+            Self::Float(f) => match Number::from_f64(*f) {
+                Some(n) => JsonValue::Number(n),
+                None => return self.fallback(policy),
+            },
+            Self::String(s) | Self::Path(s) => JsonValue::String(s.clone()),
+            Self::Bytes(bytes) => match policy.bytes {
+                BytesPolicy::Base64 => JsonValue::String(base64_encode(bytes)),
+                BytesPolicy::Array => bytes.iter().map(|b| JsonValue::from(*b)).collect(),
+                BytesPolicy::Error => return Err(self.error(false)),
+            },
+            Self::List(items)
+            | Self::Tuple(items)
+            | Self::Set(items)
+            | Self::FrozenSet(items)
+            | Self::Deque { items, .. }
+            | Self::NamedTuple { values: items, .. } => array_json(items, policy)?,
+            Self::Dict(pairs)
+            | Self::Counter(pairs)
+            | Self::DefaultDict { items: pairs, .. }
+            | Self::Dataclass { attrs: pairs, .. } => {
+                let mut map = Map::new();
+                for (key, value) in pairs {
+                    map.insert(key.json_key(policy)?, value.to_json(policy)?);
+                }
+                JsonValue::Object(map)
+            }
+            Self::Date { year, month, day } => JsonValue::String(format!("{year:04}-{month:02}-{day:02}")),
+            Self::DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                microsecond,
+            } => {
+                let s = format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+                if *microsecond == 0 {
+                    JsonValue::String(s)
+                } else {
+                    JsonValue::String(format!("{s}.{microsecond:06}"))
+                }
+            }
+            Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            } => {
+                let total_us = (i64::from(*days) * 86_400 + i64::from(*seconds)) * 1_000_000 + i64::from(*microseconds);
+                if total_us % 1_000_000 == 0 {
+                    JsonValue::from(total_us / 1_000_000)
+                } else {
+                    JsonValue::from(total_us as f64 / 1_000_000.0)
+                }
+            }
+            Self::Ellipsis
+            | Self::Exception { .. }
+            | Self::ExternalObject { .. }
+            | Self::Type(_)
+            | Self::BuiltinFunction(_)
+            | Self::Repr(_)
+            | Self::Cycle(..) => return self.fallback(policy),
+        };
+        Ok(json)
+    }
+
+    /// Converts from a plain JSON value.
+    ///
+    /// Objects become dicts with string keys, arrays become lists, and integers that don't
+    /// fit in `i64` become big ints. This never fails since every JSON value has a Python
+    /// equivalent.
+    #[must_use]
+    pub fn from_json(json: &JsonValue) -> Self {
+        match json {
+            JsonValue::Null => Self::None,
+            JsonValue::Bool(b) => Self::Bool(*b),
+            JsonValue::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    Self::Int(i)
+                } else if let Some(u) = n.as_u64() {
+                    Self::BigInt(u.into())
+                } else {
+                    Self::Float(n.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            JsonValue::String(s) => Self::String(s.clone()),
+            JsonValue::Array(items) => Self::List(items.iter().map(Self::from_json).collect()),
+            JsonValue::Object(map) => Self::dict(
+                map.iter()
+                    .map(|(key, value)| (Self::String(key.clone()), Self::from_json(value)))
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    /// Converts a dict key to a JSON object key, following Python's `json.dumps`.
+    fn json_key(&self, policy: &JsonPolicy) -> Result<String, ToJsonError> {
+        match self {
+            Self::String(s) => Ok(s.clone()),
+            Self::None => Ok("null".to_owned()),
+            Self::Bool(b) => Ok(b.to_string()),
+            Self::Int(i) => Ok(i.to_string()),
+            Self::BigInt(i) => Ok(i.to_string()),
+            Self::Float(f) if f.is_finite() => Ok(self.py_repr()),
+            _ => match policy.fallback {
+                FallbackPolicy::Repr => Ok(self.repr_string()),
+                FallbackPolicy::Error => Err(self.error(true)),
+            },
+        }
+    }
+
+    /// Encodes a value with no JSON equivalent according to `policy.fallback`.
+    fn fallback(&self, policy: &JsonPolicy) -> Result<JsonValue, ToJsonError> {
+        match policy.fallback {
+            FallbackPolicy::Repr => Ok(JsonValue::String(self.repr_string())),
+            FallbackPolicy::Error => Err(self.error(false)),
+        }
+    }
+
+    /// The `repr()` used by `FallbackPolicy::Repr`, without re-quoting `Repr` and `Cycle` placeholders.
+    fn repr_string(&self) -> String {
+        match self {
+            Self::Repr(s) | Self::Cycle(_, s) => s.clone(),
+            _ => self.py_repr(),
+        }
+    }
+
+    fn error(&self, is_key: bool) -> ToJsonError {
+        ToJsonError {
+            type_name: self.type_name(),
+            is_key,
+        }
+    }
+}
+
+/// Same as `MontyObject::from_json`.
+impl From<JsonValue> for MontyObject {
+    fn from(json: JsonValue) -> Self {
+        Self::from_json(&json)
+    }
+}
+
+/// Same as `MontyObject::to_json` with the default `JsonPolicy`.
+impl TryFrom<&MontyObject> for JsonValue {
+    type Error = ToJsonError;
+
+    fn try_from(value: &MontyObject) -> Result<Self, Self::Error> {
+        value.to_json(&JsonPolicy::default())
+    }
+}
+
+fn array_json(items: &[MontyObject], policy: &JsonPolicy) -> Result<JsonValue, ToJsonError> {
+    items.iter().map(|item| item.to_json(policy)).collect()
+}
+
+/// Encodes bytes as standard base64 with padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
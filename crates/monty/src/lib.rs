@@ -2,44 +2,92 @@
 // first to include defer_drop macro
 mod heap;
 
+mod analysis;
 mod args;
+mod ast_json;
 mod asyncio;
 mod builtins;
 mod bytecode;
+mod capabilities;
+mod coverage;
+mod debug;
 mod exception_private;
 mod exception_public;
 mod expressions;
+mod external_fn;
 mod fstring;
 mod function;
+mod host_types;
 mod intern;
 mod io;
+mod json;
+mod memory;
 mod modules;
 mod namespace;
+mod native_module;
 mod object;
 mod os;
 mod parse;
 mod prepare;
+mod profile;
+mod python_module;
 mod repl;
 mod resource;
 mod run;
+mod schema;
 mod signature;
+mod snapshot_format;
 mod sorting;
+mod trace;
 mod types;
 mod value;
 
+#[cfg(feature = "macros")]
+pub use monty_macros::external_fn;
+
 #[cfg(feature = "ref-count-return")]
 pub use crate::run::RefCountOutput;
 pub use crate::{
+    analysis::{Analysis, Diagnostic, DiagnosticKind},
+    bytecode::CompileOptions,
+    capabilities::{Capabilities, ModuleCapabilities, SyntaxFeature, TypeCapabilities, capabilities},
+    coverage::Coverage,
+    debug::{DebugLocation, DebugSession, DebugStatus},
     exception_private::ExcType,
     exception_public::{CodeLoc, MontyException, StackFrame},
-    io::{PrintWriter, PrintWriterCallback},
-    object::{DictPairs, InvalidInputError, MontyObject},
+    external_fn::{ExternalFunctions, FromMontyArg, IntoMontyObject},
+    io::{CapturedOutput, PrintEvent, PrintEventWriter, PrintStream, PrintWriter, PrintWriterCallback},
+    json::{BytesPolicy, FallbackPolicy, JsonPolicy, ToJsonError},
+    memory::{HeapDump, HeapObject, HeapReport, HeapRoot, Retainer, TypeUsage},
+    native_module::NativeModule,
+    object::{ConversionError, DictPairs, InvalidInputError, MontyObject},
     os::{OsFunction, dir_stat, file_stat, stat_result, symlink_stat},
+    profile::{FunctionProfile, LineProfile, ProfileReport, ProfilingTracker},
+    python_module::PythonModule,
     repl::{
-        MontyRepl, ReplContinuationMode, ReplFutureSnapshot, ReplProgress, ReplSnapshot, detect_repl_continuation_mode,
+        MontyRepl, ReplContinuationMode, ReplFutureSnapshot, ReplPausedSnapshot, ReplProgress, ReplSnapshot,
+        detect_repl_continuation_mode,
     },
     resource::{
-        DEFAULT_MAX_RECURSION_DEPTH, LimitedTracker, NoLimitTracker, ResourceError, ResourceLimits, ResourceTracker,
+        CancelToken, CancellableTracker, Clock, DEFAULT_MAX_RECURSION_DEPTH, GcKind, GcStats, GcTotals, HeapSlotStats,
+        LimitExceeded, LimitedTracker, MeteredTracker, NoLimitTracker, PauseReason, ResourceError, ResourceLimits,
+        ResourceTracker, RunMetrics, monotonic_clock,
+    },
+    run::{
+        CollectedRun, ExecutionOptions, ExternalResult, FutureSnapshot, MontyFuture, MontyRun, PausedSnapshot,
+        RunProgress, Snapshot,
     },
-    run::{ExternalResult, FutureSnapshot, MontyFuture, MontyRun, RunProgress, Snapshot},
+    schema::{Property, ResultSchema, SchemaMismatch, SchemaParseError},
+    snapshot_format::{
+        MIN_SNAPSHOT_FORMAT_VERSION, SNAPSHOT_FORMAT_VERSION, SnapshotCompression, SnapshotError, SnapshotStats,
+        from_snapshot_bytes, from_snapshot_bytes_with, snapshot_format_version, to_snapshot_bytes,
+        to_snapshot_bytes_with,
+    },
+    trace::{StatementKind, TraceEvent, TraceHook},
 };
+
+/// Runtime support for code generated by `#[monty::external_fn]`, not part of the public API.
+#[doc(hidden)]
+pub mod __macro_support {
+    pub use crate::external_fn::{bind_args, extract_arg};
+}
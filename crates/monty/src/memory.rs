@@ -0,0 +1,186 @@
+//! Heap introspection: what is using memory, and which variables keep it alive.
+//!
+//! A [`HeapReport`] summarizes the live heap values by type and by the variables they're
+//! reachable from, which is usually enough to see why a script hits `max_memory`. A
+//! [`HeapDump`] lists every live value with the values it references, for tools that want
+//! to walk the object graph themselves.
+
+use ahash::{AHashMap, AHashSet};
+
+use crate::{
+    heap::{Heap, HeapId, collect_child_ids},
+    resource::ResourceTracker,
+    types::PyTrait,
+    value::Value,
+};
+
+/// Live heap values of one type, see [`HeapReport::by_type`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TypeUsage {
+    /// Python type name, e.g. `"list"`.
+    pub type_name: String,
+    /// Number of live values of the type.
+    pub count: usize,
+    /// Estimated bytes used by those values, not counting the values they reference.
+    pub bytes: usize,
+}
+
+/// Heap values reachable from one variable, see [`HeapReport::retainers`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Retainer {
+    /// Variable name, prefixed with the function name for function locals, e.g. `"build.rows"`.
+    pub name: String,
+    /// Number of heap values reachable from the variable, including its own value.
+    pub objects: usize,
+    /// Estimated bytes of those values.
+    pub bytes: usize,
+}
+
+/// Summary of the live heap, produced by `MontyRun::run_with_heap_report` or
+/// `PausedSnapshot::heap_report`.
+///
+/// Sizes are the same estimates the resource tracker checks against `max_memory`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HeapReport {
+    /// Number of live heap values.
+    pub live_objects: usize,
+    /// Estimated bytes of all live heap values.
+    pub total_bytes: usize,
+    /// Usage per type, most bytes first.
+    pub by_type: Vec<TypeUsage>,
+    /// Variables holding heap values, most bytes first.
+    ///
+    /// A value reachable from several variables is counted for each of them, so the
+    /// totals can add up to more than `total_bytes`.
+    pub retainers: Vec<Retainer>,
+}
+
+/// One live heap value, see [`HeapDump::objects`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HeapObject {
+    /// Slot of the value in the heap, unique among live values.
+    pub id: usize,
+    /// Python type name, e.g. `"dict"`.
+    pub type_name: String,
+    /// Estimated bytes used by the value, not counting the values it references.
+    pub bytes: usize,
+    /// Number of references to the value.
+    pub refcount: usize,
+    /// IDs of the heap values the value references directly.
+    pub references: Vec<usize>,
+}
+
+/// A variable holding a heap value, see [`HeapDump::roots`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HeapRoot {
+    /// Variable name, named like [`Retainer::name`].
+    pub name: String,
+    /// ID of the heap value the variable holds.
+    pub id: usize,
+}
+
+/// Every live heap value and the variables holding them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HeapDump {
+    /// Live heap values in slot order.
+    pub objects: Vec<HeapObject>,
+    /// Variables holding heap values.
+    pub roots: Vec<HeapRoot>,
+}
+
+impl HeapDump {
+    /// Serializes the dump to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("heap dumps only contain strings and integers")
+    }
+}
+
+/// Builds the report for `heap`, with `roots` naming the variables to report as retainers.
+pub(crate) fn heap_report(heap: &Heap<impl ResourceTracker>, roots: &[(String, &Value)]) -> HeapReport {
+    let mut sizes = AHashMap::new();
+    let mut by_type: AHashMap<String, TypeUsage> = AHashMap::new();
+    for (id, data, _) in heap.live_values() {
+        let bytes = data.py_estimate_size();
+        sizes.insert(id, bytes);
+        let type_name = data.py_type(heap).to_string();
+        let usage = by_type.entry(type_name.clone()).or_insert_with(|| TypeUsage {
+            type_name,
+            count: 0,
+            bytes: 0,
+        });
+        usage.count += 1;
+        usage.bytes += bytes;
+    }
+
+    let mut by_type: Vec<_> = by_type.into_values().collect();
+    by_type.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.type_name.cmp(&b.type_name)));
+
+    let mut retainers: Vec<_> = roots
+        .iter()
+        .filter_map(|(name, value)| {
+            let Value::Ref(id) = value else {
+                return None;
+            };
+            let reachable = reachable_from(heap, *id);
+            Some(Retainer {
+                name: name.clone(),
+                objects: reachable.len(),
+                bytes: reachable
+                    .iter()
+                    .map(|id| sizes.get(id).copied().unwrap_or_default())
+                    .sum(),
+            })
+        })
+        .collect();
+    retainers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+    HeapReport {
+        live_objects: sizes.len(),
+        total_bytes: sizes.values().sum(),
+        by_type,
+        retainers,
+    }
+}
+
+/// Builds the full dump of `heap`, with `roots` naming the variables holding values.
+pub(crate) fn heap_dump(heap: &Heap<impl ResourceTracker>, roots: &[(String, &Value)]) -> HeapDump {
+    let objects = heap
+        .live_values()
+        .map(|(id, data, refcount)| {
+            let mut children = Vec::new();
+            collect_child_ids(data, &mut children);
+            HeapObject {
+                id: id.index(),
+                type_name: data.py_type(heap).to_string(),
+                bytes: data.py_estimate_size(),
+                refcount,
+                references: children.into_iter().map(HeapId::index).collect(),
+            }
+        })
+        .collect();
+    let roots = roots
+        .iter()
+        .filter_map(|(name, value)| match value {
+            Value::Ref(id) => Some(HeapRoot {
+                name: name.clone(),
+                id: id.index(),
+            }),
+            _ => None,
+        })
+        .collect();
+    HeapDump { objects, roots }
+}
+
+/// Returns the IDs of the live values reachable from `root`, including `root` itself.
+fn reachable_from(heap: &Heap<impl ResourceTracker>, root: HeapId) -> AHashSet<HeapId> {
+    let mut seen = AHashSet::new();
+    let mut work_list = vec![root];
+    while let Some(id) = work_list.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        collect_child_ids(heap.get(id), &mut work_list);
+    }
+    seen
+}
@@ -0,0 +1,177 @@
+//! Implementation of the `collections` module.
+//!
+//! Provides the commonly used container types of Python's `collections` module:
+//! - `defaultdict(default_factory=None, ...)`: A dict calling a factory for missing keys
+//! - `Counter(iterable_or_mapping=None, **kwds)`: A dict counting hashable items
+//! - `deque(iterable=(), maxlen=None)`: A double-ended queue with an optional maximum length
+//! - `namedtuple(typename, field_names, *, rename=False, defaults=None, module=None)`:
+//!   A factory for tuple subclasses with named fields
+//!
+//! The three types are exposed as type objects, see [`crate::types::dict::DictKind`],
+//! [`crate::types::counter`] and [`crate::types::deque`]. `namedtuple()` returns a
+//! [`NamedTupleType`] class whose calls create [`crate::types::NamedTuple`] instances.
+
+use std::collections::HashSet;
+
+use crate::{
+    args::ArgValues,
+    builtins::Builtins,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Module, MontyIter, NamedTupleType, PyTrait, Type, re_pattern::is_identifier},
+    value::{EitherStr, Value},
+};
+
+/// Python's reserved keywords, which `namedtuple()` rejects as type and field names.
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+    "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Collections module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum CollectionsFunctions {
+    Namedtuple,
+}
+
+/// Creates the `collections` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Collections);
+
+    for (name, t) in [
+        (StaticStrings::Defaultdict, Type::DefaultDict),
+        (StaticStrings::Counter, Type::Counter),
+        (StaticStrings::Deque, Type::Deque),
+    ] {
+        module.set_attr(name, Value::Builtin(Builtins::Type(t)), heap, interns);
+    }
+    module.set_attr(
+        StaticStrings::Namedtuple,
+        Value::ModuleFunction(ModuleFunctions::Collections(CollectionsFunctions::Namedtuple)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a collections module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: CollectionsFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        CollectionsFunctions::Namedtuple => namedtuple(args, vm.heap, vm.interns)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implements `namedtuple(typename, field_names, *, rename=False, defaults=None, module=None)`.
+///
+/// `field_names` is either a string of names separated by commas and/or whitespace, or an
+/// iterable of names. Names are validated like CPython does, unless `rename` is true, in
+/// which case invalid field names are replaced with `_<index>`. `module` is accepted but
+/// ignored since classes don't record their module.
+fn namedtuple(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let [typename, field_names, rename, defaults, module] = args.bind(
+        "namedtuple",
+        ["typename", "field_names", "rename", "defaults", "module"],
+        2,
+        heap,
+        interns,
+    )?;
+    let typename = typename.expect("required argument");
+    let field_names = field_names.expect("required argument");
+    defer_drop!(typename, heap);
+    defer_drop!(field_names, heap);
+    defer_drop!(rename, heap);
+    defer_drop!(module, heap);
+    let defaults = match defaults {
+        Some(Value::None) | None => Vec::new(),
+        Some(defaults) => MontyIter::new(defaults, heap, interns)?.collect(heap, interns)?,
+    };
+    defer_drop!(defaults, heap);
+
+    let mut guard = DepthGuard::default();
+    let typename = typename.py_str(heap, &mut guard, interns).into_owned();
+    let mut names: Vec<String> = if let Some(names) = field_names.as_either_str(heap) {
+        names
+            .as_str(interns)
+            .replace(',', " ")
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect()
+    } else {
+        let iter = MontyIter::new(field_names.clone_with_heap(heap), heap, interns)?;
+        let items: Vec<Value> = iter.collect(heap, interns)?;
+        let names = items
+            .iter()
+            .map(|item| item.py_str(heap, &mut guard, interns).into_owned())
+            .collect();
+        items.drop_with_heap(heap);
+        names
+    };
+
+    let rename = rename.as_ref().is_some_and(|rename| rename.py_bool(heap, interns));
+    if rename {
+        let mut seen = HashSet::new();
+        for (index, name) in names.iter_mut().enumerate() {
+            if !is_identifier(name) || KEYWORDS.contains(&name.as_str()) || name.starts_with('_') || seen.contains(name)
+            {
+                *name = format!("_{index}");
+            }
+            seen.insert(name.clone());
+        }
+    }
+
+    for name in std::iter::once(&typename).chain(&names) {
+        if !is_identifier(name) {
+            return Err(value_error(format!(
+                "Type names and field names must be valid identifiers: '{name}'"
+            )));
+        }
+        if KEYWORDS.contains(&name.as_str()) {
+            return Err(value_error(format!(
+                "Type names and field names cannot be a keyword: '{name}'"
+            )));
+        }
+    }
+    let mut seen = HashSet::new();
+    for name in &names {
+        if name.starts_with('_') && !rename {
+            return Err(value_error(format!(
+                "Field names cannot start with an underscore: '{name}'"
+            )));
+        }
+        if !seen.insert(name.as_str()) {
+            return Err(value_error(format!("Encountered duplicate field name: '{name}'")));
+        }
+    }
+    if defaults.len() > names.len() {
+        return Err(ExcType::type_error("Got more default values than field names"));
+    }
+
+    let defaults = defaults.iter().map(|value| value.clone_with_heap(heap)).collect();
+    let field_names = names.into_iter().map(EitherStr::from).collect();
+    let nt_type = NamedTupleType::new(EitherStr::from(typename), field_names, defaults);
+    Ok(Value::Ref(heap.allocate(HeapData::NamedTupleType(nt_type))?))
+}
+
+/// Creates a `ValueError` with the given message.
+fn value_error(msg: String) -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, msg).into()
+}
@@ -0,0 +1,70 @@
+//! Implementation of the `contextlib` module.
+//!
+//! Provides a minimal implementation of Python's `contextlib` module with:
+//! - `nullcontext(enter_result=None)`: A context manager that does nothing
+//! - `suppress(*exceptions)`: A context manager that swallows the given exceptions
+//!
+//! Decorator-based helpers (`contextmanager`, `ExitStack`, etc.) are not implemented.
+
+use crate::{
+    args::ArgValues,
+    exception_private::RunResult,
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, ContextManager, Module},
+    value::Value,
+};
+
+/// Contextlib module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ContextlibFunctions {
+    Nullcontext,
+    Suppress,
+}
+
+/// Creates the `contextlib` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Contextlib);
+
+    module.set_attr(
+        StaticStrings::Nullcontext,
+        Value::ModuleFunction(ModuleFunctions::Contextlib(ContextlibFunctions::Nullcontext)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::Suppress,
+        Value::ModuleFunction(ModuleFunctions::Contextlib(ContextlibFunctions::Suppress)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a contextlib module function.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    functions: ContextlibFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let context_manager = match functions {
+        ContextlibFunctions::Nullcontext => {
+            let enter_result = args.get_zero_one_arg("nullcontext", heap)?;
+            ContextManager::NullContext(enter_result.unwrap_or(Value::None))
+        }
+        // Like CPython, the exception classes are only validated when an exception reaches `__exit__`
+        ContextlibFunctions::Suppress => ContextManager::Suppress(args.into_pos_only("suppress", heap)?.collect()),
+    };
+    let id = heap.allocate(HeapData::ContextManager(context_manager))?;
+    Ok(AttrCallResult::Value(Value::Ref(id)))
+}
@@ -0,0 +1,45 @@
+//! Implementation of the `datetime` module.
+//!
+//! Provides a minimal implementation of Python's `datetime` module with:
+//! - `date`: A calendar date
+//! - `datetime`: A naive date and time with microsecond resolution
+//! - `timedelta`: A duration
+//! - `MINYEAR` and `MAXYEAR`: The supported year range
+//!
+//! Timezones are not supported, so all datetimes are naive. `date.today()` and
+//! `datetime.now()` read the clock through `OsFunction` callbacks to the host.
+
+use crate::{
+    builtins::Builtins,
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{ResourceError, ResourceTracker},
+    types::{
+        Module, Type,
+        date::{MAXYEAR, MINYEAR},
+    },
+    value::Value,
+};
+
+/// Creates the `datetime` module and allocates it on the heap.
+///
+/// Returns a HeapId pointing to the newly allocated module.
+///
+/// # Panics
+///
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Datetime);
+
+    for (name, t) in [
+        (StaticStrings::Date, Type::Date),
+        (StaticStrings::Datetime, Type::DateTime),
+        (StaticStrings::Timedelta, Type::TimeDelta),
+    ] {
+        module.set_attr(name, Value::Builtin(Builtins::Type(t)), heap, interns);
+    }
+    module.set_attr(StaticStrings::Minyear, Value::Int(MINYEAR.into()), heap, interns);
+    module.set_attr(StaticStrings::Maxyear, Value::Int(MAXYEAR.into()), heap, interns);
+
+    heap.allocate(HeapData::Module(module))
+}
@@ -0,0 +1,246 @@
+//! Implementation of the `functools` module.
+//!
+//! Provides the commonly used parts of Python's `functools` module:
+//! - `reduce(function, iterable[, initial])`: Folds an iterable with a two-argument function
+//! - `partial(func, *args, **keywords)`: A callable with some arguments already applied
+//! - `lru_cache(maxsize=128)`: A decorator caching results keyed by the call's arguments
+//! - `cache(func)`: An unbounded `lru_cache`
+//!
+//! `lru_cache` is usable both as `@lru_cache` and as `@lru_cache(maxsize)`, the latter
+//! returning a `functools.partial` of `lru_cache` that wraps the decorated function.
+//! The `typed` argument is not supported. See [`crate::types::partial`] and
+//! [`crate::types::lru_cache`] for how the returned objects are called.
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunError, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, Dict, LruCache, Module, MontyIter, Partial},
+    value::Value,
+};
+
+/// Default `maxsize` of `lru_cache()`, matching CPython.
+const DEFAULT_MAXSIZE: usize = 128;
+
+/// Functools module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum FunctoolsFunctions {
+    Reduce,
+    Partial,
+    LruCache,
+    Cache,
+}
+
+impl FunctoolsFunctions {
+    /// All functools functions with the static string of their attribute name.
+    const ALL: [(StaticStrings, Self); 4] = [
+        (StaticStrings::Reduce, Self::Reduce),
+        (StaticStrings::Partial, Self::Partial),
+        (StaticStrings::LruCache, Self::LruCache),
+        (StaticStrings::Cache, Self::Cache),
+    ];
+}
+
+/// Creates the `functools` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Functools);
+
+    for (name, function) in FunctoolsFunctions::ALL {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Functools(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a functools module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: FunctoolsFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        FunctoolsFunctions::Reduce => reduce(vm, args)?,
+        FunctoolsFunctions::Partial => partial(args, vm.heap, vm.interns)?,
+        FunctoolsFunctions::LruCache => lru_cache(args, vm.heap, vm.interns)?,
+        FunctoolsFunctions::Cache => {
+            let func = args.get_one_arg("cache", vm.heap)?;
+            Value::Ref(vm.heap.allocate(HeapData::LruCache(LruCache::new(func, None)))?)
+        }
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implements `reduce(function, iterable[, initial])`.
+fn reduce(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let args: Vec<Value> = args.into_pos_only("reduce", vm.heap)?.collect();
+    let count = args.len();
+    if !(2..=3).contains(&count) {
+        args.drop_with_heap(vm.heap);
+        return Err(if count < 2 {
+            ExcType::type_error_at_least("reduce", 2, count)
+        } else {
+            ExcType::type_error_at_most("reduce", 3, count)
+        });
+    }
+    let mut args = args.into_iter();
+    let function = args.next().expect("length checked");
+    let iterable = args.next().expect("length checked");
+    let initial = args.next();
+    defer_drop!(function, vm);
+    let iter = match MontyIter::new(iterable, vm.heap, vm.interns) {
+        Ok(iter) => iter,
+        Err(err) => {
+            initial.drop_with_heap(vm.heap);
+            return Err(err);
+        }
+    };
+    defer_drop_mut!(iter, vm);
+
+    let accumulator = match initial {
+        Some(initial) => Some(initial),
+        None => iter.for_next(vm.heap, vm.interns)?,
+    };
+    let Some(mut accumulator) = accumulator else {
+        return Err(ExcType::type_error("reduce() of empty iterable with no initial value"));
+    };
+    loop {
+        let item = match iter.for_next(vm.heap, vm.interns) {
+            Ok(Some(item)) => item,
+            Ok(None) => return Ok(accumulator),
+            Err(err) => {
+                accumulator.drop_with_heap(vm.heap);
+                return Err(err);
+            }
+        };
+        accumulator = vm.evaluate_function("reduce()", function, ArgValues::Two(accumulator, item))?;
+    }
+}
+
+/// Implements `partial(func, /, *args, **keywords)`.
+fn partial(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    let mut positional: Vec<Value> = positional.collect();
+    if positional.is_empty() {
+        kwargs.drop_with_heap(heap);
+        return Err(ExcType::type_error("type 'partial' takes at least one argument"));
+    }
+    let func = positional.remove(0);
+    if !func.is_callable(heap) {
+        func.drop_with_heap(heap);
+        positional.drop_with_heap(heap);
+        kwargs.drop_with_heap(heap);
+        return Err(ExcType::type_error("the first argument must be callable"));
+    }
+    defer_drop_mut!(positional, heap);
+    let keywords = match Dict::from_pairs(kwargs.into_iter().collect(), heap, interns) {
+        Ok(keywords) => keywords,
+        Err(err) => {
+            func.drop_with_heap(heap);
+            return Err(err);
+        }
+    };
+    let positional = std::mem::take(positional);
+    let partial = Partial::new(func, positional, keywords);
+    Ok(Value::Ref(heap.allocate(HeapData::Partial(partial))?))
+}
+
+/// Implements `lru_cache(maxsize=128)`, used either as `@lru_cache` or `@lru_cache(maxsize)`.
+///
+/// The decorator returned for `@lru_cache(maxsize)` is a partial storing `maxsize` as a
+/// keyword, which calls back into this function with the decorated function as the only
+/// positional argument.
+fn lru_cache(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    defer_drop_mut!(positional, heap);
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let mut maxsize = None;
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+        let Some(keyword_name) = key.as_either_str(heap) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        let key_str = keyword_name.as_str(interns);
+        if key_str != "maxsize" {
+            return Err(ExcType::type_error_unexpected_keyword("lru_cache", key_str));
+        }
+        maxsize = Some(lru_cache_maxsize(value)?);
+    }
+
+    let count = positional.len();
+    if count > 1 {
+        return Err(ExcType::type_error_at_most("lru_cache", 1, count));
+    }
+    let Some(arg) = positional.next() else {
+        // `lru_cache()`: a decorator with the default (or keyword) maxsize
+        return lru_cache_decorator(maxsize.unwrap_or(Some(DEFAULT_MAXSIZE)), heap, interns);
+    };
+    if arg.is_callable(heap) {
+        let maxsize = maxsize.unwrap_or(Some(DEFAULT_MAXSIZE));
+        return Ok(Value::Ref(
+            heap.allocate(HeapData::LruCache(LruCache::new(arg, maxsize)))?,
+        ));
+    }
+    defer_drop!(arg, heap);
+    if maxsize.is_some() {
+        return Err(ExcType::type_error_duplicate_arg("lru_cache", "maxsize"));
+    }
+    lru_cache_decorator(lru_cache_maxsize(arg)?, heap, interns)
+}
+
+/// Returns the decorator for `@lru_cache(maxsize)`: `partial(lru_cache, maxsize=maxsize)`.
+fn lru_cache_decorator(
+    maxsize: Option<usize>,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let maxsize = maxsize.map_or(Value::None, |maxsize| {
+        Value::Int(i64::try_from(maxsize).expect("maxsize comes from an i64"))
+    });
+    let mut keywords = Dict::new();
+    keywords.set(
+        Value::InternString(StaticStrings::Maxsize.into()),
+        maxsize,
+        heap,
+        interns,
+    )?;
+    let func = Value::ModuleFunction(ModuleFunctions::Functools(FunctoolsFunctions::LruCache));
+    let partial = Partial::new(func, Vec::new(), keywords);
+    Ok(Value::Ref(heap.allocate(HeapData::Partial(partial))?))
+}
+
+/// Converts a `maxsize` argument: `None` means unbounded, and negative sizes are
+/// treated as `0` like in CPython.
+fn lru_cache_maxsize(value: &Value) -> RunResult<Option<usize>> {
+    let maxsize = match value {
+        Value::None => return Ok(None),
+        Value::Bool(b) => i64::from(*b),
+        Value::Int(i) => *i,
+        _ => return Err(type_error_maxsize()),
+    };
+    Ok(Some(usize::try_from(maxsize).unwrap_or(0)))
+}
+
+/// Creates the `TypeError` raised when `lru_cache()`'s argument has an unsupported type.
+fn type_error_maxsize() -> RunError {
+    ExcType::type_error("Expected first argument to be an integer, a callable, or None")
+}
@@ -0,0 +1,322 @@
+//! Implementation of the `itertools` module.
+//!
+//! Provides the commonly used parts of Python's `itertools` module:
+//! - `chain(*iterables)`: The items of each iterable in turn
+//! - `count(start=0, step=1)`: An endless arithmetic progression
+//! - `islice(iterable, stop)` / `islice(iterable, start, stop[, step])`: A slice of an iterable
+//! - `product(*iterables, repeat=1)`: The cartesian product of the iterables
+//! - `groupby(iterable, key=None)`: Runs of consecutive items with equal keys
+//!
+//! `chain()`, `count()` and `islice()` are lazy, like in CPython. `product()` and `groupby()`
+//! consume their input up front, as `map()` does, since iterators can't call back into the
+//! VM for `groupby()`'s key function; each group is an iterator over a list.
+//! `count()` only supports integers.
+
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, List, Module, MontyIter, PyTrait, allocate_tuple, iter::get_iter},
+    value::Value,
+};
+
+/// Itertools module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ItertoolsFunctions {
+    Chain,
+    Count,
+    Islice,
+    Product,
+    Groupby,
+}
+
+impl ItertoolsFunctions {
+    /// All itertools functions with the static string of their attribute name.
+    const ALL: [(StaticStrings, Self); 5] = [
+        (StaticStrings::Chain, Self::Chain),
+        (StaticStrings::Count, Self::Count),
+        (StaticStrings::Islice, Self::Islice),
+        (StaticStrings::Product, Self::Product),
+        (StaticStrings::Groupby, Self::Groupby),
+    ];
+}
+
+/// Creates the `itertools` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Itertools);
+
+    for (name, function) in ItertoolsFunctions::ALL {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Itertools(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to an itertools module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: ItertoolsFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        ItertoolsFunctions::Chain => {
+            let heap = &mut *vm.heap;
+            let iterables = args.into_pos_only("chain", heap)?;
+            defer_drop_mut!(iterables, heap);
+            let iter = MontyIter::chain(&mut *iterables, heap, vm.interns)?;
+            Value::Ref(heap.allocate(HeapData::Iter(iter))?)
+        }
+        ItertoolsFunctions::Count => {
+            let heap = &mut *vm.heap;
+            let [start, step] = args.bind("count", ["start", "step"], 0, heap, vm.interns)?;
+            defer_drop!(start, heap);
+            defer_drop!(step, heap);
+            let start = start.as_ref().map_or(Ok(0), |start| count_arg(start, heap))?;
+            let step = step.as_ref().map_or(Ok(1), |step| count_arg(step, heap))?;
+            Value::Ref(heap.allocate(HeapData::Iter(MontyIter::count(start, step)))?)
+        }
+        ItertoolsFunctions::Islice => islice(args, vm.heap, vm.interns)?,
+        ItertoolsFunctions::Product => product(args, vm.heap, vm.interns)?,
+        ItertoolsFunctions::Groupby => groupby(vm, args)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Converts a `count()` argument to `i64`, accepting bools like CPython does.
+fn count_arg(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i64> {
+    match value {
+        Value::Bool(b) => Ok(i64::from(*b)),
+        _ => value.as_int(heap),
+    }
+}
+
+/// Implements `islice(iterable, stop)` and `islice(iterable, start, stop[, step])`.
+fn islice(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let args: Vec<Value> = args.into_pos_only("islice", heap)?.collect();
+    let count = args.len();
+    if count < 2 {
+        args.drop_with_heap(heap);
+        return Err(ExcType::type_error_at_least("islice", 2, count));
+    }
+    if count > 4 {
+        args.drop_with_heap(heap);
+        return Err(ExcType::type_error_at_most("islice", 4, count));
+    }
+    let mut args = args.into_iter();
+    let iterable = args.next().expect("length checked");
+    let indices: Vec<Value> = args.collect();
+    defer_drop!(indices, heap);
+    let mut guard = HeapGuard::new(iterable, heap);
+    let heap = guard.heap();
+
+    let (start, stop, step) = match indices.as_slice() {
+        [stop] => (None, stop, None),
+        [start, stop] => (Some(start), stop, None),
+        [start, stop, step] => (Some(start), stop, Some(step)),
+        _ => unreachable!("length checked"),
+    };
+    let Ok(stop) = islice_index(stop, heap) else {
+        return Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            "Stop argument for islice() must be None or an integer: 0 <= x <= sys.maxsize.",
+        )
+        .into());
+    };
+    let start = start.map_or(Ok(None), |start| islice_index(start, heap));
+    let step = step.map_or(Ok(None), |step| islice_index(step, heap));
+    let (Ok(start), Ok(step)) = (start, step) else {
+        return Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            "Indices for islice() must be None or an integer: 0 <= x <= sys.maxsize.",
+        )
+        .into());
+    };
+    if step == Some(0) {
+        return Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            "Step for islice() must be a positive integer or None.",
+        )
+        .into());
+    }
+
+    let (iterable, heap) = guard.into_parts();
+    let iter = MontyIter::islice(iterable, start.unwrap_or(0), stop, step.unwrap_or(1), heap, interns)?;
+    Ok(Value::Ref(heap.allocate(HeapData::Iter(iter))?))
+}
+
+/// Converts an `islice()` index to `usize`, with `None` meaning no index.
+///
+/// Returns `Err(())` for negative numbers and non-integers, for the caller to raise the
+/// `ValueError` CPython raises for the respective argument.
+fn islice_index(value: &Value, heap: &Heap<impl ResourceTracker>) -> Result<Option<usize>, ()> {
+    match value {
+        Value::None => Ok(None),
+        Value::Bool(b) => Ok(Some(usize::from(*b))),
+        Value::Int(i) => usize::try_from(*i).map(Some).map_err(|_| ()),
+        // Larger than any iterable Monty can hold, so equivalent to `sys.maxsize`
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) if !li.is_negative() => Ok(Some(usize::MAX)),
+            _ => Err(()),
+        },
+        _ => Err(()),
+    }
+}
+
+/// Implements `product(*iterables, repeat=1)`, returning an iterator over a list of tuples.
+fn product(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    defer_drop_mut!(positional, heap);
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let mut repeat = 1;
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+        let Some(keyword_name) = key.as_either_str(heap) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        let key_str = keyword_name.as_str(interns);
+        if key_str != "repeat" {
+            return Err(ExcType::type_error(format!(
+                "'{key_str}' is an invalid keyword argument for product()"
+            )));
+        }
+        repeat = count_arg(value, heap)?;
+    }
+    let Ok(repeat) = usize::try_from(repeat) else {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "repeat argument cannot be negative").into());
+    };
+
+    // Materialize every iterable once, then repeat the pools by reference
+    let pools: Vec<Vec<Value>> = Vec::new();
+    defer_drop_mut!(pools, heap);
+    for iterable in positional {
+        let pool = MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?;
+        pools.push(pool);
+    }
+    let pool_count = pools.len() * repeat;
+
+    let results: Vec<Value> = Vec::new();
+    defer_drop_mut!(results, heap);
+    if pool_count == 0 || pools.iter().all(|pool| !pool.is_empty()) {
+        // Odometer over the pools: the last index advances fastest
+        let mut indices = vec![0; pool_count];
+        loop {
+            heap.check_time()?;
+            let item = indices
+                .iter()
+                .enumerate()
+                .map(|(i, &index)| pools[i % pools.len()][index].clone_with_heap(heap))
+                .collect();
+            results.push(allocate_tuple(item, heap)?);
+
+            let Some(position) = (0..pool_count)
+                .rev()
+                .find(|&i| indices[i] + 1 < pools[i % pools.len()].len())
+            else {
+                break;
+            };
+            indices[position] += 1;
+            indices[position + 1..].fill(0);
+        }
+    }
+
+    let results = std::mem::take(results);
+    let list_id = heap.allocate(HeapData::List(List::new(results)))?;
+    get_iter(Value::Ref(list_id), heap, interns)
+}
+
+/// Implements `groupby(iterable, key=None)`, returning an iterator over a list of
+/// `(key, group)` tuples, where each group is an iterator over a list of items.
+fn groupby(vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+    let [iterable, key_fn] = args.bind("groupby", ["iterable", "key"], 1, vm.heap, vm.interns)?;
+    let iterable = iterable.expect("required argument");
+    // `key=None` means grouping by the items themselves
+    let key_fn = key_fn.filter(|key_fn| !matches!(key_fn, Value::None));
+    defer_drop!(key_fn, vm);
+    let iter = MontyIter::new(iterable, vm.heap, vm.interns)?;
+    defer_drop_mut!(iter, vm);
+
+    // The finished `(key, group)` tuples, and the key and items of the group being built
+    let groups: Vec<Value> = Vec::new();
+    defer_drop_mut!(groups, vm);
+    let current: Option<(Value, Vec<Value>)> = None;
+    defer_drop_mut!(current, vm);
+
+    while let Some(item) = iter.for_next(vm.heap, vm.interns)? {
+        let key = match key_fn {
+            Some(f) => {
+                let arg = item.clone_with_heap(vm.heap);
+                match vm.evaluate_function("groupby() key argument", f, ArgValues::One(arg)) {
+                    Ok(key) => key,
+                    Err(err) => {
+                        item.drop_with_heap(vm.heap);
+                        return Err(err);
+                    }
+                }
+            }
+            None => item.clone_with_heap(vm.heap),
+        };
+        if let Some((current_key, items)) = current.as_mut() {
+            let mut guard = DepthGuard::default();
+            match key.py_eq(current_key, vm.heap, &mut guard, vm.interns) {
+                Ok(true) => {
+                    key.drop_with_heap(vm.heap);
+                    items.push(item);
+                    continue;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    key.drop_with_heap(vm.heap);
+                    item.drop_with_heap(vm.heap);
+                    return Err(err.into());
+                }
+            }
+        }
+        let previous = current.replace((key, vec![item]));
+        if let Some(group) = previous {
+            groups.push(allocate_group(group, vm.heap, vm.interns)?);
+        }
+    }
+    if let Some(group) = current.take() {
+        groups.push(allocate_group(group, vm.heap, vm.interns)?);
+    }
+
+    let groups = std::mem::take(groups);
+    let list_id = vm.heap.allocate(HeapData::List(List::new(groups)))?;
+    get_iter(Value::Ref(list_id), vm.heap, vm.interns)
+}
+
+/// Allocates the `(key, iter(items))` tuple for a finished `groupby()` group.
+fn allocate_group(
+    (key, items): (Value, Vec<Value>),
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let mut key_guard = HeapGuard::new(key, heap);
+    let heap = key_guard.heap();
+    let list_id = heap.allocate(HeapData::List(List::new(items)))?;
+    let group = get_iter(Value::Ref(list_id), heap, interns)?;
+    let (key, heap) = key_guard.into_parts();
+    Ok(allocate_tuple(smallvec![key, group], heap)?)
+}
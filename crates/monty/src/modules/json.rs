@@ -0,0 +1,818 @@
+//! Implementation of the `json` module.
+//!
+//! Provides `json.loads()`, `json.dumps()` and `json.JSONDecodeError`, implemented directly
+//! over heap values rather than round-tripping through `MontyObject`.
+//!
+//! `dumps()` supports the `indent`, `sort_keys`, `separators`, `ensure_ascii` and `allow_nan`
+//! options. There is no `default=` hook: values without a JSON representation always raise
+//! `TypeError`, so serialization never runs user code. `loads()` always uses strict mode,
+//! rejecting control characters inside strings.
+//!
+//! Monty strings are UTF-8, so lone surrogate escapes such as `"\ud800"` decode to U+FFFD
+//! instead of an unpaired surrogate.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+use num_bigint::BigInt;
+
+use crate::{
+    args::ArgValues,
+    builtins::Builtins,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{DepthGuard, LARGE_RESULT_THRESHOLD, ResourceError, ResourceTracker, check_repeat_size},
+    sorting::sort_indices,
+    types::{AttrCallResult, Dict, List, LongInt, Module, PyTrait, Type, str::allocate_string},
+    value::{Value, float_repr_fmt},
+};
+
+/// JSON module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum JsonFunctions {
+    Loads,
+    Dumps,
+}
+
+/// Creates the `json` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Json);
+
+    module.set_attr(
+        StaticStrings::Loads,
+        Value::ModuleFunction(ModuleFunctions::Json(JsonFunctions::Loads)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::Dumps,
+        Value::ModuleFunction(ModuleFunctions::Json(JsonFunctions::Dumps)),
+        heap,
+        interns,
+    );
+    module.set_attr(
+        StaticStrings::JsonDecodeError,
+        Value::Builtin(Builtins::ExcType(ExcType::JSONDecodeError)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a json module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: JsonFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        JsonFunctions::Loads => loads(args, vm.heap, vm.interns)?,
+        JsonFunctions::Dumps => dumps(args, vm.heap, vm.interns)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implementation of `json.loads(s)`.
+///
+/// Accepts `str`, `bytes` or `bytearray`; bytes must be UTF-8 and may start with a BOM.
+fn loads(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let s = args.get_one_arg("loads", heap)?;
+    defer_drop!(s, heap);
+
+    // The document is copied so the parser can allocate while reading it
+    let text = if let Some(bytes) = s.as_bytes_like(heap, interns) {
+        let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+        match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_owned(),
+            Err(_) => return Err(ExcType::unicode_decode_error_invalid_utf8()),
+        }
+    } else if let Some(text) = s.as_either_str(heap) {
+        let text = text.as_str(interns);
+        if text.starts_with('\u{feff}') {
+            return Err(ExcType::json_decode_error(
+                "Unexpected UTF-8 BOM (decode using utf-8-sig)",
+                text,
+                0,
+            ));
+        }
+        text.to_owned()
+    } else {
+        return Err(ExcType::type_error(format!(
+            "the JSON object must be str, bytes or bytearray, not {}",
+            s.py_type(heap)
+        )));
+    };
+
+    let mut parser = JsonParser {
+        text: &text,
+        pos: 0,
+        depth: DepthGuard::default(),
+    };
+    parser.skip_whitespace();
+    let value = parser.parse_value(heap, interns)?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        value.drop_with_heap(heap);
+        return Err(parser.error("Extra data", parser.pos));
+    }
+    Ok(value)
+}
+
+/// Recursive descent parser producing heap values from a JSON document.
+///
+/// Positions are byte offsets into `text`; errors convert them to the character
+/// offsets CPython reports.
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: DepthGuard,
+}
+
+impl JsonParser<'_> {
+    /// Creates a `JSONDecodeError` for the given byte position.
+    fn error(&self, msg: &str, pos: usize) -> RunError {
+        ExcType::json_decode_error(msg, self.text, pos)
+    }
+
+    /// Returns the byte at the current position, or `None` at the end of the document.
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    /// Skips the whitespace JSON allows between tokens.
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses the value starting at the current position, which must not be whitespace.
+    fn parse_value(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let rest = &self.text[self.pos..];
+        let constant = if rest.starts_with("null") {
+            Some(("null", Value::None))
+        } else if rest.starts_with("true") {
+            Some(("true", Value::Bool(true)))
+        } else if rest.starts_with("false") {
+            Some(("false", Value::Bool(false)))
+        } else if rest.starts_with("NaN") {
+            Some(("NaN", Value::Float(f64::NAN)))
+        } else if rest.starts_with("Infinity") {
+            Some(("Infinity", Value::Float(f64::INFINITY)))
+        } else if rest.starts_with("-Infinity") {
+            Some(("-Infinity", Value::Float(f64::NEG_INFINITY)))
+        } else {
+            None
+        };
+        if let Some((literal, value)) = constant {
+            self.pos += literal.len();
+            return Ok(value);
+        }
+
+        match self.peek() {
+            Some(b'"') => {
+                let s = self.parse_string()?;
+                allocate_string(s, heap)
+            }
+            Some(b'[') => {
+                self.depth.increase_err()?;
+                let result = self.parse_array(heap, interns);
+                self.depth.decrease();
+                result
+            }
+            Some(b'{') => {
+                self.depth.increase_err()?;
+                let result = self.parse_object(heap, interns);
+                self.depth.decrease();
+                result
+            }
+            _ => self.parse_number(heap),
+        }
+    }
+
+    /// Parses a number: `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][-+]?[0-9]+)?`.
+    ///
+    /// Numbers with a fraction or exponent become floats, all others ints.
+    fn parse_number(&mut self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let is_digit = |pos: usize| bytes.get(pos).is_some_and(u8::is_ascii_digit);
+        let start = self.pos;
+        let mut end = start;
+        if bytes.get(end) == Some(&b'-') {
+            end += 1;
+        }
+        match bytes.get(end) {
+            Some(b'0') => end += 1,
+            Some(b'1'..=b'9') => {
+                while is_digit(end) {
+                    end += 1;
+                }
+            }
+            _ => return Err(self.error("Expecting value", start)),
+        }
+
+        let mut is_float = false;
+        if bytes.get(end) == Some(&b'.') && is_digit(end + 1) {
+            end += 1;
+            while is_digit(end) {
+                end += 1;
+            }
+            is_float = true;
+        }
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let mut exp_end = end + 1;
+            if matches!(bytes.get(exp_end), Some(b'+' | b'-')) {
+                exp_end += 1;
+            }
+            if is_digit(exp_end) {
+                while is_digit(exp_end) {
+                    exp_end += 1;
+                }
+                end = exp_end;
+                is_float = true;
+            }
+        }
+
+        self.pos = end;
+        let literal = &text[start..end];
+        if is_float {
+            Ok(Value::Float(
+                literal.parse().expect("literal matches the float grammar"),
+            ))
+        } else if let Ok(i) = literal.parse::<i64>() {
+            Ok(Value::Int(i))
+        } else {
+            let big: BigInt = literal.parse().expect("literal matches the int grammar");
+            Ok(LongInt::new(big).into_value(heap)?)
+        }
+    }
+
+    /// Parses a string starting at the opening quote, returning its decoded contents.
+    fn parse_string(&mut self) -> RunResult<String> {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let begin = self.pos;
+        let mut out = String::new();
+        self.pos += 1;
+        loop {
+            let rest = &text[self.pos..];
+            let Some(chunk_len) = rest.bytes().position(|b| b == b'"' || b == b'\\' || b < 0x20) else {
+                return Err(self.error("Unterminated string starting at", begin));
+            };
+            out.push_str(&rest[..chunk_len]);
+            self.pos += chunk_len;
+
+            match bytes[self.pos] {
+                b'"' => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                b'\\' => {
+                    let escaped = match bytes.get(self.pos + 1) {
+                        None => return Err(self.error("Unterminated string starting at", begin)),
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            out.push(self.parse_unicode_escape()?);
+                            continue;
+                        }
+                        Some(_) => return Err(self.error("Invalid \\escape", self.pos)),
+                    };
+                    out.push(escaped);
+                    self.pos += 2;
+                }
+                _ => return Err(self.error("Invalid control character at", self.pos)),
+            }
+        }
+    }
+
+    /// Parses a `\uXXXX` escape at the current position, combining surrogate pairs.
+    fn parse_unicode_escape(&mut self) -> RunResult<char> {
+        let code = self.parse_hex4(self.pos + 1)?;
+        self.pos += 6;
+        if (0xd800..0xdc00).contains(&code) && self.text[self.pos..].starts_with("\\u") {
+            let low = self.parse_hex4(self.pos + 1)?;
+            if (0xdc00..0xe000).contains(&low) {
+                self.pos += 6;
+                let combined = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                return Ok(char::from_u32(combined).expect("surrogate pairs combine to a valid char"));
+            }
+        }
+        Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Reads the four hex digits following the `u` of a `\u` escape at `u_pos`.
+    fn parse_hex4(&self, u_pos: usize) -> RunResult<u32> {
+        self.text
+            .get(u_pos + 1..u_pos + 5)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error("Invalid \\uXXXX escape", u_pos))
+    }
+
+    /// Parses an array starting at the opening bracket.
+    fn parse_array(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut items_guard = HeapGuard::new(Vec::new(), heap);
+        let (items, heap) = items_guard.as_parts_mut();
+
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                heap.check_time()?;
+                items.push(self.parse_value(heap, interns)?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(b',') => {
+                        let comma = self.pos;
+                        self.pos += 1;
+                        self.skip_whitespace();
+                        if self.peek() == Some(b']') {
+                            return Err(self.error("Illegal trailing comma before end of array", comma));
+                        }
+                    }
+                    _ => return Err(self.error("Expecting ',' delimiter", self.pos)),
+                }
+            }
+        }
+
+        let (items, heap) = items_guard.into_parts();
+        Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+    }
+
+    /// Parses an object starting at the opening brace.
+    ///
+    /// Later duplicate keys replace earlier ones, as in CPython.
+    fn parse_object(&mut self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let mut dict_guard = HeapGuard::new(Dict::new(), heap);
+        let (dict, heap) = dict_guard.as_parts_mut();
+
+        self.pos += 1;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'}') => self.pos += 1,
+            Some(b'"') => loop {
+                heap.check_time()?;
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                if self.peek() != Some(b':') {
+                    return Err(self.error("Expecting ':' delimiter", self.pos));
+                }
+                self.pos += 1;
+                self.skip_whitespace();
+                let value = self.parse_value(heap, interns)?;
+                let key = match allocate_string(key, heap) {
+                    Ok(key) => key,
+                    Err(err) => {
+                        value.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                };
+                if let Some(old_value) = dict.set(key, value, heap, interns)? {
+                    old_value.drop_with_heap(heap);
+                }
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(b',') => {
+                        let comma = self.pos;
+                        self.pos += 1;
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b'"') => {}
+                            Some(b'}') => {
+                                return Err(self.error("Illegal trailing comma before end of object", comma));
+                            }
+                            _ => {
+                                return Err(self.error("Expecting property name enclosed in double quotes", self.pos));
+                            }
+                        }
+                    }
+                    _ => return Err(self.error("Expecting ',' delimiter", self.pos)),
+                }
+            },
+            _ => return Err(self.error("Expecting property name enclosed in double quotes", self.pos)),
+        }
+
+        let (dict, heap) = dict_guard.into_parts();
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+}
+
+/// Implementation of `json.dumps(obj, *, indent=None, separators=None, sort_keys=False,
+/// ensure_ascii=True, allow_nan=True)`.
+fn dumps(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (pos, kwargs) = args.into_parts();
+    defer_drop_mut!(pos, heap);
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let pos_count = pos.len();
+    if pos_count == 0 {
+        return Err(ExcType::type_error_missing_positional_with_names("dumps", &["obj"]));
+    } else if pos_count > 1 {
+        return Err(ExcType::type_error_too_many_positional(
+            "dumps",
+            1,
+            pos_count,
+            kwargs.len(),
+        ));
+    }
+    let obj = pos.next().expect("checked length above");
+    defer_drop!(obj, heap);
+
+    let mut indent = None;
+    let mut separators = None;
+    let mut sort_keys = false;
+    let mut ensure_ascii = true;
+    let mut allow_nan = true;
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+        let Some(keyword_name) = key.as_either_str(heap) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        match keyword_name.as_str(interns) {
+            "indent" => indent = indent_arg(value, heap, interns)?,
+            "separators" => separators = separators_arg(value, heap, interns)?,
+            "sort_keys" => sort_keys = value.py_bool(heap, interns),
+            "ensure_ascii" => ensure_ascii = value.py_bool(heap, interns),
+            "allow_nan" => allow_nan = value.py_bool(heap, interns),
+            other => return Err(ExcType::type_error_unexpected_keyword("dumps", other)),
+        }
+    }
+
+    // With an indent, items end their lines so the default item separator drops its space
+    let (item_separator, key_separator) = separators.unwrap_or_else(|| {
+        let item_separator = if indent.is_some() { "," } else { ", " };
+        (item_separator.to_owned(), ": ".to_owned())
+    });
+    let mut encoder = JsonEncoder {
+        out: String::new(),
+        indent,
+        item_separator,
+        key_separator,
+        sort_keys,
+        ensure_ascii,
+        allow_nan,
+        level: 0,
+        depth: DepthGuard::default(),
+        visiting: AHashSet::new(),
+        next_size_check: LARGE_RESULT_THRESHOLD,
+    };
+    encoder.encode(obj, heap, interns)?;
+    allocate_string(encoder.out, heap)
+}
+
+/// Converts the `indent` argument of `dumps()`: a number of spaces or a literal indent string.
+fn indent_arg(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Option<String>> {
+    match value {
+        Value::None => Ok(None),
+        Value::Int(_) | Value::Bool(_) => {
+            // Negative indents behave like zero: newlines without indentation
+            let count = value.as_index(heap, Type::Str)?.max(0);
+            let count = usize::try_from(count).map_err(|_| ExcType::overflow_repeat_count())?;
+            check_repeat_size(1, count, heap.tracker())?;
+            Ok(Some(" ".repeat(count)))
+        }
+        _ => {
+            if let Some(indent) = value.as_either_str(heap) {
+                return Ok(Some(indent.as_str(interns).to_owned()));
+            }
+            if let Value::Ref(heap_id) = value
+                && let HeapData::LongInt(li) = heap.get(*heap_id)
+            {
+                return if li.is_negative() {
+                    Ok(Some(String::new()))
+                } else {
+                    Err(ExcType::overflow_repeat_count().into())
+                };
+            }
+            Err(ExcType::type_error(format!(
+                "can't multiply sequence by non-int of type '{}'",
+                value.py_type(heap)
+            )))
+        }
+    }
+}
+
+/// Converts the `separators` argument of `dumps()`: an `(item_separator, key_separator)` pair.
+fn separators_arg(
+    value: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<(String, String)>> {
+    let items = match value {
+        Value::None => return Ok(None),
+        Value::Ref(heap_id) => match heap.get(*heap_id) {
+            HeapData::Tuple(tuple) => tuple.as_slice(),
+            HeapData::List(list) => list.as_slice(),
+            _ => return Err(separators_type_error()),
+        },
+        _ => return Err(separators_type_error()),
+    };
+    match items {
+        [item_separator, key_separator] => {
+            let (Some(item_separator), Some(key_separator)) =
+                (item_separator.as_either_str(heap), key_separator.as_either_str(heap))
+            else {
+                return Err(separators_type_error());
+            };
+            Ok(Some((
+                item_separator.as_str(interns).to_owned(),
+                key_separator.as_str(interns).to_owned(),
+            )))
+        }
+        [_] | [] => Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            format!("not enough values to unpack (expected 2, got {})", items.len()),
+        )
+        .into()),
+        _ => Err(SimpleException::new_msg(ExcType::ValueError, "too many values to unpack (expected 2)").into()),
+    }
+}
+
+/// Creates the TypeError for a `separators` argument that isn't a pair of strings.
+fn separators_type_error() -> RunError {
+    ExcType::type_error("separators must be an (item_separator, key_separator) pair of strings")
+}
+
+/// Serializes heap values to JSON text.
+struct JsonEncoder {
+    out: String,
+    /// Indent for one nesting level, or `None` for single-line output.
+    indent: Option<String>,
+    item_separator: String,
+    key_separator: String,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    allow_nan: bool,
+    /// Current nesting level, used for indentation.
+    level: usize,
+    depth: DepthGuard,
+    /// Containers currently being encoded, to detect reference cycles.
+    visiting: AHashSet<HeapId>,
+    /// Output length at which the resource tracker is next consulted.
+    next_size_check: usize,
+}
+
+impl JsonEncoder {
+    /// Appends the JSON representation of `value` to the output.
+    fn encode(&mut self, value: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match value {
+            Value::None => self.out.push_str("null"),
+            Value::Bool(true) => self.out.push_str("true"),
+            Value::Bool(false) => self.out.push_str("false"),
+            Value::Int(i) => write!(self.out, "{i}").expect("writing to a String never fails"),
+            Value::Float(f) => self.encode_float(*f)?,
+            Value::InternString(string_id) => encode_str(&mut self.out, interns.get_str(*string_id), self.ensure_ascii),
+            Value::InternLongInt(long_int_id) => {
+                write!(self.out, "{}", interns.get_long_int(*long_int_id)).expect("writing to a String never fails");
+            }
+            Value::Ref(heap_id) => match heap.get(*heap_id) {
+                HeapData::Str(s) => encode_str(&mut self.out, s.as_str(), self.ensure_ascii),
+                HeapData::LongInt(li) => write!(self.out, "{li}").expect("writing to a String never fails"),
+                HeapData::List(_) | HeapData::Tuple(_) | HeapData::NamedTuple(_) | HeapData::Dict(_) => {
+                    self.encode_container(*heap_id, heap, interns)?;
+                }
+                _ => return Err(ExcType::type_error_not_json_serializable(value.py_type(heap))),
+            },
+            _ => return Err(ExcType::type_error_not_json_serializable(value.py_type(heap))),
+        }
+        Ok(())
+    }
+
+    /// Encodes a list, tuple or dict, guarding against cycles and excessive nesting.
+    fn encode_container(
+        &mut self,
+        heap_id: HeapId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if !self.visiting.insert(heap_id) {
+            return Err(SimpleException::new_msg(ExcType::ValueError, "Circular reference detected").into());
+        }
+        if let Err(err) = self.depth.increase_err() {
+            self.visiting.remove(&heap_id);
+            return Err(err.into());
+        }
+        self.level += 1;
+
+        // The entry is taken out of the heap while encoding; cycle detection above
+        // guarantees it is not visited again until it has been restored
+        let result = heap.with_entry_mut(heap_id, |heap, data| match data {
+            HeapData::List(list) => self.encode_array(list.as_slice(), heap, interns),
+            HeapData::Tuple(tuple) => self.encode_array(tuple.as_slice(), heap, interns),
+            HeapData::NamedTuple(namedtuple) => self.encode_array(namedtuple.as_vec(), heap, interns),
+            HeapData::Dict(dict) => self.encode_object(dict, heap, interns),
+            _ => unreachable!("encode_container is only called for lists, tuples and dicts"),
+        });
+
+        self.level -= 1;
+        self.depth.decrease();
+        self.visiting.remove(&heap_id);
+        result
+    }
+
+    /// Encodes the items of a list or tuple as a JSON array.
+    fn encode_array(
+        &mut self,
+        items: &[Value],
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if items.is_empty() {
+            self.out.push_str("[]");
+            return Ok(());
+        }
+        self.out.push('[');
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(&self.item_separator);
+            }
+            self.write_newline_indent(self.level);
+            self.encode(item, heap, interns)?;
+            self.check_progress(heap)?;
+        }
+        self.write_newline_indent(self.level - 1);
+        self.out.push(']');
+        Ok(())
+    }
+
+    /// Encodes a dict as a JSON object, ordering its keys when `sort_keys` is set.
+    fn encode_object(
+        &mut self,
+        dict: &Dict,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if dict.is_empty() {
+            self.out.push_str("{}");
+            return Ok(());
+        }
+        let mut order: Vec<usize> = (0..dict.len()).collect();
+        if self.sort_keys {
+            let keys: Vec<&Value> = dict.iter().map(|(key, _)| key).collect();
+            sort_indices(&mut order, &keys, false, heap, interns)?;
+        }
+
+        self.out.push('{');
+        for (i, &index) in order.iter().enumerate() {
+            let (key, value) = dict.item_at(index).expect("index is within the dict");
+            if i > 0 {
+                self.out.push_str(&self.item_separator);
+            }
+            self.write_newline_indent(self.level);
+            self.encode_key(key, heap, interns)?;
+            self.out.push_str(&self.key_separator);
+            self.encode(value, heap, interns)?;
+            self.check_progress(heap)?;
+        }
+        self.write_newline_indent(self.level - 1);
+        self.out.push('}');
+        Ok(())
+    }
+
+    /// Encodes a dict key, converting `int`, `float`, `bool` and `None` keys to strings.
+    fn encode_key(&mut self, key: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        let start = self.out.len();
+        self.out.push('"');
+        match key {
+            Value::InternString(string_id) => {
+                // Strings are quoted by encode_str
+                self.out.truncate(start);
+                encode_str(&mut self.out, interns.get_str(*string_id), self.ensure_ascii);
+                return Ok(());
+            }
+            Value::None => self.out.push_str("null"),
+            Value::Bool(true) => self.out.push_str("true"),
+            Value::Bool(false) => self.out.push_str("false"),
+            Value::Int(i) => write!(self.out, "{i}").expect("writing to a String never fails"),
+            Value::Float(f) => self.encode_float(*f)?,
+            Value::InternLongInt(long_int_id) => {
+                write!(self.out, "{}", interns.get_long_int(*long_int_id)).expect("writing to a String never fails");
+            }
+            Value::Ref(heap_id) => match heap.get(*heap_id) {
+                HeapData::Str(s) => {
+                    self.out.truncate(start);
+                    encode_str(&mut self.out, s.as_str(), self.ensure_ascii);
+                    return Ok(());
+                }
+                HeapData::LongInt(li) => write!(self.out, "{li}").expect("writing to a String never fails"),
+                _ => return Err(key_type_error(key, heap)),
+            },
+            _ => return Err(key_type_error(key, heap)),
+        }
+        self.out.push('"');
+        Ok(())
+    }
+
+    /// Encodes a float using its repr, or `NaN`/`Infinity`/`-Infinity` when `allow_nan` is set.
+    fn encode_float(&mut self, f: f64) -> RunResult<()> {
+        if f.is_finite() {
+            float_repr_fmt(f, &mut self.out).expect("writing to a String never fails");
+            return Ok(());
+        }
+        let (literal, repr) = if f.is_nan() {
+            ("NaN", "nan")
+        } else if f > 0.0 {
+            ("Infinity", "inf")
+        } else {
+            ("-Infinity", "-inf")
+        };
+        if !self.allow_nan {
+            return Err(SimpleException::new_msg(
+                ExcType::ValueError,
+                format!("Out of range float values are not JSON compliant: {repr}"),
+            )
+            .into());
+        }
+        self.out.push_str(literal);
+        Ok(())
+    }
+
+    /// Starts a new line indented to `level` when pretty-printing.
+    fn write_newline_indent(&mut self, level: usize) {
+        if let Some(indent) = &self.indent {
+            self.out.push('\n');
+            for _ in 0..level {
+                self.out.push_str(indent);
+            }
+        }
+    }
+
+    /// Checks the time limit and, as the output grows, the memory limit.
+    fn check_progress(&mut self, heap: &Heap<impl ResourceTracker>) -> Result<(), ResourceError> {
+        heap.check_time()?;
+        if self.out.len() > self.next_size_check {
+            heap.tracker().check_large_result(self.out.len())?;
+            self.next_size_check = self.out.len() + LARGE_RESULT_THRESHOLD;
+        }
+        Ok(())
+    }
+}
+
+/// Creates the TypeError for a dict key that can't be converted to a JSON string.
+fn key_type_error(key: &Value, heap: &Heap<impl ResourceTracker>) -> RunError {
+    ExcType::type_error(format!(
+        "keys must be str, int, float, bool or None, not {}",
+        key.py_type(heap)
+    ))
+}
+
+/// Writes `s` as a quoted JSON string.
+///
+/// With `ensure_ascii`, every character outside printable ASCII is written as a `\uXXXX`
+/// escape (using a surrogate pair above U+FFFF); otherwise only quotes, backslashes and
+/// control characters are escaped.
+fn encode_str(out: &mut String, s: &str, ensure_ascii: bool) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c < ' ' || (ensure_ascii && !(' '..='~').contains(&c)) => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(out, "\\u{unit:04x}").expect("writing to a String never fails");
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
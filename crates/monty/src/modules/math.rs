@@ -0,0 +1,591 @@
+//! Implementation of the `math` module.
+//!
+//! Provides the commonly used parts of Python's `math` module, computed in Rust:
+//! - Constants: `pi`, `e`, `tau`, `inf`, `nan`
+//! - Rounding: `floor()`, `ceil()`, `trunc()`, `modf()`
+//! - Powers and logarithms: `sqrt()`, `exp()`, `log()`, `log2()`, `log10()`, `pow()`
+//! - Trigonometry: `sin()`, `cos()`, `tan()`, `asin()`, `acos()`, `atan()`, `atan2()`,
+//!   `hypot()`, `degrees()`, `radians()`
+//! - Floating point helpers: `fabs()`, `copysign()`, `fmod()`, `fsum()`, `isclose()`,
+//!   `isnan()`, `isinf()`, `isfinite()`
+//! - Integer functions: `gcd()`, `lcm()`, `factorial()`
+//!
+//! Like CPython, invalid inputs raise `ValueError: math domain error` and results too
+//! large for a float raise `OverflowError: math range error`.
+
+use std::{
+    cmp::Ordering,
+    f64::consts::{E, PI, TAU},
+};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker, check_factorial_size},
+    types::{AttrCallResult, LongInt, Module, MontyIter, PyTrait, allocate_tuple, conversions::int_from_float},
+    value::Value,
+};
+
+/// Math module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum MathFunctions {
+    Sqrt,
+    Floor,
+    Ceil,
+    Trunc,
+    Modf,
+    Fabs,
+    Exp,
+    Log,
+    Log2,
+    Log10,
+    Pow,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Hypot,
+    Degrees,
+    Radians,
+    Copysign,
+    Fmod,
+    Fsum,
+    Isclose,
+    Isnan,
+    Isinf,
+    Isfinite,
+    Gcd,
+    Lcm,
+    Factorial,
+}
+
+impl MathFunctions {
+    /// All math functions with the static string of their attribute name.
+    const ALL: [(StaticStrings, Self); 31] = [
+        (StaticStrings::Sqrt, Self::Sqrt),
+        (StaticStrings::Floor, Self::Floor),
+        (StaticStrings::Ceil, Self::Ceil),
+        (StaticStrings::Trunc, Self::Trunc),
+        (StaticStrings::Modf, Self::Modf),
+        (StaticStrings::Fabs, Self::Fabs),
+        (StaticStrings::Exp, Self::Exp),
+        (StaticStrings::Log, Self::Log),
+        (StaticStrings::Log2, Self::Log2),
+        (StaticStrings::Log10, Self::Log10),
+        (StaticStrings::Pow, Self::Pow),
+        (StaticStrings::Sin, Self::Sin),
+        (StaticStrings::Cos, Self::Cos),
+        (StaticStrings::Tan, Self::Tan),
+        (StaticStrings::Asin, Self::Asin),
+        (StaticStrings::Acos, Self::Acos),
+        (StaticStrings::Atan, Self::Atan),
+        (StaticStrings::Atan2, Self::Atan2),
+        (StaticStrings::Hypot, Self::Hypot),
+        (StaticStrings::Degrees, Self::Degrees),
+        (StaticStrings::Radians, Self::Radians),
+        (StaticStrings::Copysign, Self::Copysign),
+        (StaticStrings::Fmod, Self::Fmod),
+        (StaticStrings::Fsum, Self::Fsum),
+        (StaticStrings::Isclose, Self::Isclose),
+        (StaticStrings::Isnan, Self::Isnan),
+        (StaticStrings::Isinf, Self::Isinf),
+        (StaticStrings::Isfinite, Self::Isfinite),
+        (StaticStrings::Gcd, Self::Gcd),
+        (StaticStrings::Lcm, Self::Lcm),
+        (StaticStrings::Factorial, Self::Factorial),
+    ];
+}
+
+/// Creates the `math` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Math);
+
+    module.set_attr(StaticStrings::Pi, Value::Float(PI), heap, interns);
+    // Single ASCII characters are interned separately from static strings
+    module.set_attr(StringId::from_ascii(b'e'), Value::Float(E), heap, interns);
+    module.set_attr(StaticStrings::Tau, Value::Float(TAU), heap, interns);
+    module.set_attr(StaticStrings::Inf, Value::Float(f64::INFINITY), heap, interns);
+    module.set_attr(StaticStrings::Nan, Value::Float(f64::NAN), heap, interns);
+
+    for (name, function) in MathFunctions::ALL {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Math(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a math module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: MathFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let heap = &mut *vm.heap;
+    let value = match function {
+        MathFunctions::Sqrt => {
+            let x = one_float("math.sqrt", args, heap)?;
+            if x < 0.0 {
+                return Err(domain_error());
+            }
+            Value::Float(x.sqrt())
+        }
+        MathFunctions::Floor => round_to_int("math.floor", args, heap, f64::floor)?,
+        MathFunctions::Ceil => round_to_int("math.ceil", args, heap, f64::ceil)?,
+        MathFunctions::Trunc => round_to_int("math.trunc", args, heap, f64::trunc)?,
+        MathFunctions::Modf => {
+            let x = one_float("math.modf", args, heap)?;
+            // modf(inf) is (0.0, inf), keeping the sign of x on the fractional part
+            let int_part = x.trunc();
+            let frac_part = if x.is_infinite() {
+                0.0_f64.copysign(x)
+            } else {
+                x - int_part
+            };
+            allocate_tuple(smallvec![Value::Float(frac_part), Value::Float(int_part)], heap)?
+        }
+        MathFunctions::Fabs => Value::Float(one_float("math.fabs", args, heap)?.abs()),
+        MathFunctions::Exp => Value::Float(check_range(one_float("math.exp", args, heap)?.exp())?),
+        MathFunctions::Log => {
+            let (x, base) = args.get_one_two_args("log", heap)?;
+            defer_drop!(x, heap);
+            defer_drop!(base, heap);
+            let ln_x = value_log(x, heap, f64::ln)?;
+            match base {
+                Some(base) => {
+                    let ln_base = value_log(base, heap, f64::ln)?;
+                    if ln_base == 0.0 {
+                        return Err(SimpleException::new_msg(ExcType::ZeroDivisionError, "division by zero").into());
+                    }
+                    Value::Float(ln_x / ln_base)
+                }
+                None => Value::Float(ln_x),
+            }
+        }
+        MathFunctions::Log2 => {
+            let x = args.get_one_arg("math.log2", heap)?;
+            defer_drop!(x, heap);
+            Value::Float(value_log(x, heap, f64::log2)?)
+        }
+        MathFunctions::Log10 => {
+            let x = args.get_one_arg("math.log10", heap)?;
+            defer_drop!(x, heap);
+            Value::Float(value_log(x, heap, f64::log10)?)
+        }
+        MathFunctions::Pow => {
+            let (x, y) = two_floats("pow", args, heap)?;
+            Value::Float(float_pow(x, y)?)
+        }
+        MathFunctions::Sin => Value::Float(finite_only(one_float("math.sin", args, heap)?, f64::sin)?),
+        MathFunctions::Cos => Value::Float(finite_only(one_float("math.cos", args, heap)?, f64::cos)?),
+        MathFunctions::Tan => Value::Float(finite_only(one_float("math.tan", args, heap)?, f64::tan)?),
+        MathFunctions::Asin => Value::Float(unit_interval_only(one_float("math.asin", args, heap)?, f64::asin)?),
+        MathFunctions::Acos => Value::Float(unit_interval_only(one_float("math.acos", args, heap)?, f64::acos)?),
+        MathFunctions::Atan => Value::Float(one_float("math.atan", args, heap)?.atan()),
+        MathFunctions::Atan2 => {
+            let (y, x) = two_floats("atan2", args, heap)?;
+            Value::Float(y.atan2(x))
+        }
+        MathFunctions::Hypot => {
+            let coords = args.into_pos_only("hypot", heap)?;
+            defer_drop_mut!(coords, heap);
+            let mut result = 0.0_f64;
+            for coord in coords {
+                defer_drop!(coord, heap);
+                result = result.hypot(value_to_f64(coord, heap)?);
+            }
+            Value::Float(check_range(result)?)
+        }
+        MathFunctions::Degrees => Value::Float(one_float("math.degrees", args, heap)?.to_degrees()),
+        MathFunctions::Radians => Value::Float(one_float("math.radians", args, heap)?.to_radians()),
+        MathFunctions::Copysign => {
+            let (x, y) = two_floats("copysign", args, heap)?;
+            Value::Float(x.copysign(y))
+        }
+        MathFunctions::Fmod => {
+            let (x, y) = two_floats("fmod", args, heap)?;
+            if y == 0.0 || x.is_infinite() {
+                if x.is_nan() || y.is_nan() {
+                    return Ok(AttrCallResult::Value(Value::Float(f64::NAN)));
+                }
+                return Err(domain_error());
+            }
+            // Rust's `%` on floats has the same semantics as C's fmod
+            Value::Float(x % y)
+        }
+        MathFunctions::Fsum => {
+            let iterable = args.get_one_arg("math.fsum", heap)?;
+            Value::Float(fsum(iterable, heap, vm.interns)?)
+        }
+        MathFunctions::Isclose => isclose(args, heap, vm.interns)?,
+        MathFunctions::Isnan => Value::Bool(one_float("math.isnan", args, heap)?.is_nan()),
+        MathFunctions::Isinf => Value::Bool(one_float("math.isinf", args, heap)?.is_infinite()),
+        MathFunctions::Isfinite => Value::Bool(one_float("math.isfinite", args, heap)?.is_finite()),
+        MathFunctions::Gcd => {
+            let ints = collect_ints("gcd", args, heap)?;
+            let result = ints.into_iter().fold(BigInt::zero(), |acc, n| acc.gcd(&n));
+            LongInt::new(result).into_value(heap)?
+        }
+        MathFunctions::Lcm => {
+            let ints = collect_ints("lcm", args, heap)?;
+            let result = ints.into_iter().fold(BigInt::one(), |acc, n| acc.lcm(&n));
+            LongInt::new(result).into_value(heap)?
+        }
+        MathFunctions::Factorial => factorial(args, heap)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Creates the `ValueError` raised for arguments outside a function's domain.
+fn domain_error() -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, "math domain error").into()
+}
+
+/// Returns `result`, or `OverflowError: math range error` if it overflowed to infinity.
+fn check_range(result: f64) -> RunResult<f64> {
+    if result.is_infinite() {
+        Err(SimpleException::new_msg(ExcType::OverflowError, "math range error").into())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Converts a real number (int, bool, float or `LongInt`) to `f64`.
+fn value_to_f64(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<f64> {
+    match value {
+        Value::Float(f) => Ok(*f),
+        Value::Int(i) => Ok(*i as f64),
+        Value::Bool(b) => Ok(f64::from(u8::from(*b))),
+        Value::Ref(heap_id) => match heap.get(*heap_id) {
+            HeapData::LongInt(li) => li
+                .to_f64()
+                .filter(|f| f.is_finite())
+                .ok_or_else(ExcType::overflow_error_int_too_large_for_float),
+            _ => Err(type_error_not_real(value, heap)),
+        },
+        _ => Err(type_error_not_real(value, heap)),
+    }
+}
+
+/// Creates the `TypeError` for a non-numeric argument.
+fn type_error_not_real(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunError {
+    ExcType::type_error(format!("must be real number, not {}", value.py_type(heap)))
+}
+
+/// Extracts the single float argument of a one-argument math function.
+fn one_float(name: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<f64> {
+    let x = args.get_one_arg(name, heap)?;
+    defer_drop!(x, heap);
+    value_to_f64(x, heap)
+}
+
+/// Extracts the two float arguments of a two-argument math function.
+fn two_floats(name: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<(f64, f64)> {
+    let (x, y) = args.get_two_args(name, heap)?;
+    defer_drop!(x, heap);
+    defer_drop!(y, heap);
+    Ok((value_to_f64(x, heap)?, value_to_f64(y, heap)?))
+}
+
+/// Applies `f` to a finite argument, raising a domain error for infinities.
+fn finite_only(x: f64, f: fn(f64) -> f64) -> RunResult<f64> {
+    if x.is_infinite() { Err(domain_error()) } else { Ok(f(x)) }
+}
+
+/// Applies `f` to an argument in `[-1, 1]`, raising a domain error otherwise.
+fn unit_interval_only(x: f64, f: fn(f64) -> f64) -> RunResult<f64> {
+    if x.abs() > 1.0 { Err(domain_error()) } else { Ok(f(x)) }
+}
+
+/// Implements `floor()`, `ceil()` and `trunc()`: integers are returned unchanged and
+/// floats are rounded with `round` and converted to an integer.
+fn round_to_int(
+    name: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    round: fn(f64) -> f64,
+) -> RunResult<Value> {
+    let x = args.get_one_arg(name, heap)?;
+    match x {
+        Value::Int(_) => Ok(x),
+        Value::Bool(b) => Ok(Value::Int(i64::from(b))),
+        Value::Ref(heap_id) if matches!(heap.get(heap_id), HeapData::LongInt(_)) => Ok(x),
+        _ => {
+            defer_drop!(x, heap);
+            int_from_float(round(value_to_f64(x, heap)?), heap)
+        }
+    }
+}
+
+/// Applies the logarithm `log` to a positive real number.
+///
+/// `LongInt` values too large for a float are scaled down first, so `log(10**1000)` works.
+fn value_log(value: &Value, heap: &Heap<impl ResourceTracker>, log: fn(f64) -> f64) -> RunResult<f64> {
+    if let Value::Ref(heap_id) = value
+        && let HeapData::LongInt(li) = heap.get(*heap_id)
+    {
+        let big = li.inner();
+        if !big.is_positive() {
+            return Err(domain_error());
+        }
+        // Keep the top 64 bits, then add back the logarithm of the dropped power of two
+        let shift = big.bits().saturating_sub(64);
+        let top = (big >> shift).to_f64().expect("64-bit value converts to f64");
+        return Ok(log(top) + shift as f64 * log(2.0));
+    }
+    let x = value_to_f64(value, heap)?;
+    if x.is_nan() {
+        return Ok(x);
+    }
+    if x <= 0.0 {
+        return Err(domain_error());
+    }
+    Ok(log(x))
+}
+
+/// Implements `math.pow(x, y)` with CPython's special cases.
+fn float_pow(x: f64, y: f64) -> RunResult<f64> {
+    if x.is_finite() && y.is_finite() {
+        if x == 0.0 && y < 0.0 {
+            return Err(domain_error());
+        }
+        if x < 0.0 && y.fract() != 0.0 {
+            return Err(domain_error());
+        }
+        check_range(x.powf(y))
+    } else {
+        // Infinities and NaNs follow C99's pow, which Rust's powf implements
+        Ok(x.powf(y))
+    }
+}
+
+/// Implements `math.fsum(iterable)`: an exactly rounded floating point sum.
+///
+/// Uses Shewchuk's algorithm, keeping a list of non-overlapping partial sums, like CPython.
+fn fsum(iterable: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<f64> {
+    let iter = MontyIter::new(iterable, heap, interns)?;
+    defer_drop_mut!(iter, heap);
+
+    let mut partials: Vec<f64> = Vec::new();
+    let mut special_sum = 0.0_f64;
+    let mut inf_sum = 0.0_f64;
+    while let Some(item) = iter.for_next(heap, interns)? {
+        defer_drop!(item, heap);
+        let mut x = value_to_f64(item, heap)?;
+        if !x.is_finite() {
+            // Sum the non-finite values separately; inf + -inf gives nan
+            if x.is_infinite() {
+                inf_sum += x;
+            }
+            special_sum += x;
+            continue;
+        }
+        let mut i = 0;
+        for j in 0..partials.len() {
+            let mut y = partials[j];
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != 0.0 {
+                partials[i] = lo;
+                i += 1;
+            }
+            x = hi;
+        }
+        if !x.is_finite() {
+            return Err(SimpleException::new_msg(ExcType::OverflowError, "intermediate overflow in fsum").into());
+        }
+        partials.truncate(i);
+        partials.push(x);
+    }
+
+    if special_sum != 0.0 || special_sum.is_nan() {
+        if inf_sum.is_nan() {
+            return Err(SimpleException::new_msg(ExcType::ValueError, "-inf + inf in fsum").into());
+        }
+        return Ok(special_sum);
+    }
+
+    // Sum the partials from the top, correcting for the rounding of the last step
+    let Some(mut hi) = partials.pop() else {
+        return Ok(0.0);
+    };
+    let mut lo = 0.0;
+    while let Some(y) = partials.pop() {
+        let x = hi;
+        hi = x + y;
+        let yr = hi - x;
+        lo = y - yr;
+        if lo != 0.0 {
+            break;
+        }
+    }
+    if let Some(&next) = partials.last()
+        && ((lo < 0.0 && next < 0.0) || (lo > 0.0 && next > 0.0))
+    {
+        let y = lo * 2.0;
+        let x = hi + y;
+        // Finite floats are equal exactly when their difference is zero
+        if (x - hi) - y == 0.0 {
+            hi = x;
+        }
+    }
+    Ok(hi)
+}
+
+/// Implements `math.isclose(a, b, *, rel_tol=1e-09, abs_tol=0.0)`.
+fn isclose(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (pos, kwargs) = args.into_parts();
+    defer_drop_mut!(pos, heap);
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let pos_count = pos.len();
+    if pos_count != 2 {
+        return Err(if pos_count < 2 {
+            let missing = if pos_count == 0 { "'a' and 'b'" } else { "'b'" };
+            let plural = if pos_count == 0 { "s" } else { "" };
+            ExcType::type_error(format!(
+                "isclose() missing {} required positional argument{plural}: {missing}",
+                2 - pos_count
+            ))
+        } else {
+            ExcType::type_error(format!(
+                "isclose() takes exactly 2 positional arguments ({pos_count} given)"
+            ))
+        });
+    }
+    let a = pos.next().expect("checked length above");
+    defer_drop!(a, heap);
+    let b = pos.next().expect("checked length above");
+    defer_drop!(b, heap);
+    let a = value_to_f64(a, heap)?;
+    let b = value_to_f64(b, heap)?;
+
+    let mut rel_tol = 1e-09;
+    let mut abs_tol = 0.0;
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+        let Some(keyword_name) = key.as_either_str(heap) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        match keyword_name.as_str(interns) {
+            "rel_tol" => rel_tol = value_to_f64(value, heap)?,
+            "abs_tol" => abs_tol = value_to_f64(value, heap)?,
+            other => return Err(ExcType::type_error_unexpected_keyword("isclose", other)),
+        }
+    }
+    if rel_tol < 0.0 || abs_tol < 0.0 {
+        return Err(SimpleException::new_msg(ExcType::ValueError, "tolerances must be non-negative").into());
+    }
+
+    if a.partial_cmp(&b) == Some(Ordering::Equal) {
+        return Ok(Value::Bool(true));
+    }
+    // Infinities are only close to themselves, which the check above covers
+    if a.is_infinite() || b.is_infinite() {
+        return Ok(Value::Bool(false));
+    }
+    let diff = (b - a).abs();
+    let close = diff <= (rel_tol * b).abs() || diff <= (rel_tol * a).abs() || diff <= abs_tol;
+    Ok(Value::Bool(close))
+}
+
+/// Collects the integer arguments of `gcd()` and `lcm()` as absolute `BigInt` values.
+fn collect_ints(name: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Vec<BigInt>> {
+    let values = args.into_pos_only(name, heap)?;
+    defer_drop_mut!(values, heap);
+    let mut ints = Vec::with_capacity(values.len());
+    for value in values {
+        defer_drop!(value, heap);
+        ints.push(value_to_bigint(value, heap)?.abs());
+    }
+    Ok(ints)
+}
+
+/// Converts an integer argument (int, bool or `LongInt`) to `BigInt`.
+fn value_to_bigint(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<BigInt> {
+    match value {
+        Value::Int(i) => Ok(BigInt::from(*i)),
+        Value::Bool(b) => Ok(BigInt::from(u8::from(*b))),
+        Value::Ref(heap_id) => match heap.get(*heap_id) {
+            HeapData::LongInt(li) => Ok(li.inner().clone()),
+            _ => Err(ExcType::type_error_not_integer(value.py_type(heap))),
+        },
+        _ => Err(ExcType::type_error_not_integer(value.py_type(heap))),
+    }
+}
+
+/// Implements `math.factorial(n)`.
+///
+/// Checks the estimated result size against the resource limits before computing, and
+/// multiplies small factors together in a machine word before each `BigInt` multiplication.
+fn factorial(args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let n = args.get_one_arg("factorial", heap)?;
+    defer_drop!(n, heap);
+    let n = match n {
+        Value::Int(i) => *i,
+        Value::Bool(b) => i64::from(*b),
+        Value::Ref(heap_id) if matches!(heap.get(*heap_id), HeapData::LongInt(li) if li.is_negative()) => {
+            return Err(factorial_negative());
+        }
+        Value::Ref(heap_id) if matches!(heap.get(*heap_id), HeapData::LongInt(_)) => {
+            return Err(SimpleException::new_msg(
+                ExcType::OverflowError,
+                "factorial() argument should not exceed 9223372036854775807",
+            )
+            .into());
+        }
+        _ => return Err(ExcType::type_error_not_integer(n.py_type(heap))),
+    };
+    let Ok(n) = u64::try_from(n) else {
+        return Err(factorial_negative());
+    };
+    check_factorial_size(n, heap.tracker())?;
+
+    let mut result = BigInt::one();
+    let mut chunk: u64 = 1;
+    for k in 2..=n {
+        if let Some(product) = chunk.checked_mul(k) {
+            chunk = product;
+        } else {
+            heap.check_time()?;
+            result *= chunk;
+            chunk = k;
+        }
+    }
+    result *= chunk;
+    Ok(LongInt::new(result).into_value(heap)?)
+}
+
+/// Creates the `ValueError` raised by `factorial()` for negative arguments.
+fn factorial_negative() -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, "factorial() not defined for negative values").into()
+}
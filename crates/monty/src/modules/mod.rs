@@ -1,14 +1,16 @@
 //! Built-in module implementations.
 //!
 //! This module provides implementations for Python built-in modules like `sys`, `typing`,
-//! and `asyncio`. These are created on-demand when import statements are executed.
+//! `asyncio`, `contextlib`, `math`, `json`, `re`, `datetime`,
+//! `time`, `random`, `itertools`, `functools` and `collections`. These are created on-demand when import statements are executed.
 
 use std::fmt::{self, Write};
 
-use strum::FromRepr;
+use strum::{FromRepr, IntoStaticStr};
 
 use crate::{
     args::ArgValues,
+    bytecode::VM,
     exception_private::RunResult,
     heap::{Heap, HeapId},
     intern::{Interns, StaticStrings, StringId},
@@ -17,14 +19,27 @@ use crate::{
 };
 
 pub(crate) mod asyncio;
+pub(crate) mod collections;
+pub(crate) mod contextlib;
+pub(crate) mod datetime;
+pub(crate) mod functools;
+pub(crate) mod itertools;
+pub(crate) mod json;
+pub(crate) mod math;
 pub(crate) mod os;
 pub(crate) mod pathlib;
+pub(crate) mod random;
+pub(crate) mod re;
 pub(crate) mod sys;
+pub(crate) mod time;
 pub(crate) mod typing;
 
 /// Built-in modules that can be imported.
+///
+/// Converts to the module name with `IntoStaticStr`, e.g. `"math"`.
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr, IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
 pub(crate) enum BuiltinModule {
     /// The `sys` module providing system-specific parameters and functions.
     Sys,
@@ -36,6 +51,26 @@ pub(crate) enum BuiltinModule {
     Pathlib,
     /// The `os` module providing operating system interface (only `getenv()` implemented).
     Os,
+    /// The `contextlib` module providing `nullcontext()` and `suppress()`.
+    Contextlib,
+    /// The `math` module providing mathematical functions and constants.
+    Math,
+    /// The `json` module providing `loads()` and `dumps()`.
+    Json,
+    /// The `re` module providing regular expressions backed by the `regex` crate.
+    Re,
+    /// The `datetime` module providing `date`, `datetime` and `timedelta`.
+    Datetime,
+    /// The `time` module providing `time()`.
+    Time,
+    /// The `random` module providing a seedable pseudo-random number generator.
+    Random,
+    /// The `itertools` module providing `chain()`, `count()`, `islice()`, `product()` and `groupby()`.
+    Itertools,
+    /// The `functools` module providing `reduce()`, `partial()`, `lru_cache()` and `cache()`.
+    Functools,
+    /// The `collections` module providing `defaultdict`, `Counter`, `deque` and `namedtuple()`.
+    Collections,
 }
 
 impl BuiltinModule {
@@ -47,6 +82,16 @@ impl BuiltinModule {
             StaticStrings::Asyncio => Some(Self::Asyncio),
             StaticStrings::Pathlib => Some(Self::Pathlib),
             StaticStrings::Os => Some(Self::Os),
+            StaticStrings::Contextlib => Some(Self::Contextlib),
+            StaticStrings::Math => Some(Self::Math),
+            StaticStrings::Json => Some(Self::Json),
+            StaticStrings::Re => Some(Self::Re),
+            StaticStrings::Datetime => Some(Self::Datetime),
+            StaticStrings::Time => Some(Self::Time),
+            StaticStrings::Random => Some(Self::Random),
+            StaticStrings::Itertools => Some(Self::Itertools),
+            StaticStrings::Functools => Some(Self::Functools),
+            StaticStrings::Collections => Some(Self::Collections),
             _ => None,
         }
     }
@@ -65,6 +110,16 @@ impl BuiltinModule {
             Self::Asyncio => asyncio::create_module(heap, interns),
             Self::Pathlib => pathlib::create_module(heap, interns),
             Self::Os => os::create_module(heap, interns),
+            Self::Contextlib => contextlib::create_module(heap, interns),
+            Self::Math => math::create_module(heap, interns),
+            Self::Json => json::create_module(heap, interns),
+            Self::Re => re::create_module(heap, interns),
+            Self::Datetime => datetime::create_module(heap, interns),
+            Self::Time => time::create_module(heap, interns),
+            Self::Random => random::create_module(heap, interns),
+            Self::Itertools => itertools::create_module(heap, interns),
+            Self::Functools => functools::create_module(heap, interns),
+            Self::Collections => collections::create_module(heap, interns),
         }
     }
 }
@@ -74,6 +129,15 @@ impl BuiltinModule {
 pub(crate) enum ModuleFunctions {
     Asyncio(asyncio::AsyncioFunctions),
     Os(os::OsFunctions),
+    Contextlib(contextlib::ContextlibFunctions),
+    Math(math::MathFunctions),
+    Json(json::JsonFunctions),
+    Re(re::ReFunctions),
+    Time(time::TimeFunctions),
+    Random(random::RandomFunctions),
+    Itertools(itertools::ItertoolsFunctions),
+    Functools(functools::FunctoolsFunctions),
+    Collections(collections::CollectionsFunctions),
 }
 
 impl fmt::Display for ModuleFunctions {
@@ -81,6 +145,15 @@ impl fmt::Display for ModuleFunctions {
         match self {
             Self::Asyncio(func) => write!(f, "{func}"),
             Self::Os(func) => write!(f, "{func}"),
+            Self::Contextlib(func) => write!(f, "{func}"),
+            Self::Math(func) => write!(f, "{func}"),
+            Self::Json(func) => write!(f, "{func}"),
+            Self::Re(func) => write!(f, "{func}"),
+            Self::Time(func) => write!(f, "{func}"),
+            Self::Random(func) => write!(f, "{func}"),
+            Self::Itertools(func) => write!(f, "{func}"),
+            Self::Functools(func) => write!(f, "{func}"),
+            Self::Collections(func) => write!(f, "{func}"),
         }
     }
 }
@@ -90,10 +163,19 @@ impl ModuleFunctions {
     ///
     /// Returns `AttrCallResult` to support both immediate values and OS calls that
     /// require host involvement (e.g., `os.getenv()` needs the host to provide environment variables).
-    pub fn call(self, heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<AttrCallResult> {
+    pub fn call(self, vm: &mut VM<impl ResourceTracker>, args: ArgValues) -> RunResult<AttrCallResult> {
         match self {
-            Self::Asyncio(functions) => asyncio::call(heap, functions, args),
-            Self::Os(functions) => os::call(heap, functions, args),
+            Self::Asyncio(functions) => asyncio::call(vm.heap, functions, args),
+            Self::Os(functions) => os::call(vm.heap, functions, args),
+            Self::Contextlib(functions) => contextlib::call(vm.heap, functions, args),
+            Self::Math(functions) => math::call(vm, functions, args),
+            Self::Json(functions) => json::call(vm, functions, args),
+            Self::Re(functions) => re::call(vm, functions, args),
+            Self::Time(functions) => time::call(vm.heap, functions, args),
+            Self::Random(functions) => random::call(vm, functions, args),
+            Self::Itertools(functions) => itertools::call(vm, functions, args),
+            Self::Functools(functions) => functools::call(vm, functions, args),
+            Self::Collections(functions) => collections::call(vm, functions, args),
         }
     }
 
@@ -0,0 +1,329 @@
+//! Implementation of the `random` module.
+//!
+//! Provides the commonly used parts of Python's `random` module:
+//! - `random()`: A float in `[0.0, 1.0)`
+//! - `randint(a, b)`: An integer in `[a, b]`
+//! - `uniform(a, b)`: A float between `a` and `b`
+//! - `choice(seq)`: A random element of a non-empty sequence
+//! - `shuffle(x)`: Shuffles a list in place
+//! - `seed(a=None)`: Reseeds the generator
+//!
+//! Numbers come from the heap's [`Rng`], a xoshiro256** generator rather than CPython's
+//! Mersenne Twister, so a given seed produces a different sequence than CPython. The generator
+//! is seeded from [`ResourceTracker::random_seed`] when the heap is created (falling back to
+//! host entropy), and is serialized with the heap, so snapshots resume with the same state.
+//! In deterministic mode, reseeding from host entropy with `seed()` raises `RuntimeError`.
+
+use std::hash::{BuildHasher, Hasher};
+
+use num_traits::ToPrimitive;
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, Module, PyTrait},
+    value::Value,
+};
+
+/// Random module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum RandomFunctions {
+    Random,
+    Randint,
+    Uniform,
+    Choice,
+    Shuffle,
+    Seed,
+}
+
+impl RandomFunctions {
+    /// All random functions with the static string of their attribute name.
+    const ALL: [(StaticStrings, Self); 6] = [
+        (StaticStrings::Random, Self::Random),
+        (StaticStrings::Randint, Self::Randint),
+        (StaticStrings::Uniform, Self::Uniform),
+        (StaticStrings::Choice, Self::Choice),
+        (StaticStrings::Shuffle, Self::Shuffle),
+        (StaticStrings::Seed, Self::Seed),
+    ];
+}
+
+/// Creates the `random` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Random);
+
+    for (name, function) in RandomFunctions::ALL {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Random(function)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a random module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: RandomFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let heap = &mut *vm.heap;
+    let value = match function {
+        RandomFunctions::Random => {
+            args.check_zero_args("random.random", heap)?;
+            Value::Float(heap.rng_mut().next_f64())
+        }
+        RandomFunctions::Randint => {
+            let (a, b) = args.get_two_args("random.randint", heap)?;
+            defer_drop!(a, heap);
+            defer_drop!(b, heap);
+            let a = value_to_i64(a, heap)?;
+            let b = value_to_i64(b, heap)?;
+            Value::Int(randint(heap.rng_mut(), a, b)?)
+        }
+        RandomFunctions::Uniform => {
+            let (a, b) = args.get_two_args("random.uniform", heap)?;
+            defer_drop!(a, heap);
+            defer_drop!(b, heap);
+            let (Some(low), Some(high)) = (value_to_f64(a, heap), value_to_f64(b, heap)) else {
+                // CPython computes `b - a` first, so that's the operation reported
+                return Err(ExcType::binary_type_error("-", b.py_type(heap), a.py_type(heap)));
+            };
+            Value::Float(low + (high - low) * heap.rng_mut().next_f64())
+        }
+        RandomFunctions::Choice => {
+            let seq = args.get_one_arg("random.choice", heap)?;
+            defer_drop!(seq, heap);
+            let len = seq
+                .py_len(heap, vm.interns)
+                .ok_or_else(|| type_error_no_len(seq, heap))?;
+            if len == 0 {
+                return Err(
+                    SimpleException::new_msg(ExcType::IndexError, "Cannot choose from an empty sequence").into(),
+                );
+            }
+            let index = heap.rng_mut().below(len as u64);
+            let index = i64::try_from(index).expect("index is below a sequence length");
+            seq.py_getitem(&Value::Int(index), heap, vm.interns)?
+        }
+        RandomFunctions::Shuffle => {
+            let seq = args.get_one_arg("random.shuffle", heap)?;
+            defer_drop!(seq, heap);
+            shuffle(seq, heap, vm.interns)?;
+            Value::None
+        }
+        RandomFunctions::Seed => {
+            let seed = args.get_zero_one_arg("random.seed", heap)?;
+            defer_drop!(seed, heap);
+            let rng = match seed {
+                None | Some(Value::None) => {
+                    heap.check_deterministic("random.seed() without a seed")?;
+                    Rng::from_entropy()
+                }
+                Some(value) => Rng::from_seed(seed_from_value(value, heap, vm.interns)?),
+            };
+            *heap.rng_mut() = rng;
+            Value::None
+        }
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Returns a random integer in `[a, b]`, raising `ValueError` if the range is empty.
+fn randint(rng: &mut Rng, a: i64, b: i64) -> RunResult<i64> {
+    if a > b {
+        // CPython implements randint(a, b) as randrange(a, b + 1)
+        let stop = i128::from(b) + 1;
+        return Err(
+            SimpleException::new_msg(ExcType::ValueError, format!("empty range in randrange({a}, {stop})")).into(),
+        );
+    }
+    // The width is at most 2**64, which only fits in u64 when the range doesn't span all of i64
+    let width = (i128::from(b) - i128::from(a) + 1).unsigned_abs();
+    let offset = match u64::try_from(width) {
+        Ok(width) => rng.below(width),
+        Err(_) => rng.next_u64(),
+    };
+    Ok(i64::try_from(i128::from(a) + i128::from(offset)).expect("result lies within [a, b]"))
+}
+
+/// Shuffles a list in place using the Fisher-Yates algorithm.
+fn shuffle(seq: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+    if let Value::Ref(id) = seq
+        && matches!(heap.get(*id), HeapData::List(_))
+    {
+        let HeapData::List(list) = heap.get_mut(*id) else {
+            unreachable!("checked above")
+        };
+        // Take the items out so the heap's generator can be borrowed while shuffling
+        let mut items = std::mem::take(list.as_vec_mut());
+        let rng = heap.rng_mut();
+        for i in (1..items.len()).rev() {
+            let j = usize::try_from(rng.below(i as u64 + 1)).expect("j is at most i");
+            items.swap(i, j);
+        }
+        let HeapData::List(list) = heap.get_mut(*id) else {
+            unreachable!("checked above")
+        };
+        *list.as_vec_mut() = items;
+        return Ok(());
+    }
+    match seq.py_len(heap, interns) {
+        Some(_) => Err(ExcType::type_error_not_sub_assignment(seq.py_type(heap))),
+        None => Err(type_error_no_len(seq, heap)),
+    }
+}
+
+/// Converts an integer argument to `i64`, accepting bools like CPython does.
+fn value_to_i64(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i64> {
+    match value {
+        Value::Bool(b) => Ok(i64::from(*b)),
+        _ => value.as_int(heap),
+    }
+}
+
+/// Converts a real number (int, bool, float or `LongInt`) to `f64`, or `None` for other types.
+fn value_to_f64(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<f64> {
+    match value {
+        Value::Float(f) => Some(*f),
+        Value::Int(i) => Some(*i as f64),
+        Value::Bool(b) => Some(f64::from(u8::from(*b))),
+        Value::Ref(heap_id) => match heap.get(*heap_id) {
+            HeapData::LongInt(li) => li.to_f64(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Derives a 64-bit seed from an argument to `random.seed()`.
+///
+/// Integers that fit in 64 bits are used directly; strings, bytes, floats and large integers
+/// are hashed, so equal values always produce the same sequence.
+fn seed_from_value(value: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<u64> {
+    match value {
+        Value::Int(i) => Ok(i.cast_unsigned()),
+        Value::Bool(b) => Ok(u64::from(*b)),
+        Value::Float(f) => Ok(hash_bytes(&f.to_le_bytes())),
+        _ => {
+            if let Some(s) = value.as_str_ref(heap, interns) {
+                Ok(hash_bytes(s.as_bytes()))
+            } else if let Some(bytes) = value.as_bytes_like(heap, interns) {
+                Ok(hash_bytes(bytes))
+            } else if let Value::Ref(id) = value
+                && let HeapData::LongInt(li) = heap.get(*id)
+            {
+                Ok(hash_bytes(&li.inner().to_signed_bytes_le()))
+            } else {
+                Err(ExcType::type_error(
+                    "The only supported seed types are:\nNone, int, float, str, bytes, and bytearray.",
+                ))
+            }
+        }
+    }
+}
+
+/// Hashes `bytes` with 64-bit FNV-1a, a stable hash used to turn non-integer seeds into `u64`s.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes
+        .iter()
+        .fold(OFFSET_BASIS, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// Creates the `TypeError` raised for an argument without a length.
+///
+/// Matches CPython's format: `TypeError: object of type '{type}' has no len()`
+fn type_error_no_len(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunError {
+    ExcType::type_error(format!("object of type '{}' has no len()", value.py_type(heap)))
+}
+
+/// A seedable xoshiro256** pseudo-random number generator.
+///
+/// Owned by the heap so the `random` module shares one generator per execution, and
+/// serialized with it so runs resumed from a snapshot continue the same sequence.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a generator from `seed`, or from host entropy if `seed` is `None`.
+    pub fn new(seed: Option<u64>) -> Self {
+        seed.map_or_else(Self::from_entropy, Self::from_seed)
+    }
+
+    /// Creates a generator whose sequence is fully determined by `seed`.
+    ///
+    /// The state is expanded from the seed with splitmix64, as recommended for xoshiro.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    /// Creates a generator seeded from the standard library's per-process random keys.
+    pub fn from_entropy() -> Self {
+        Self::from_seed(std::collections::hash_map::RandomState::new().build_hasher().finish())
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+
+    /// Returns a float in `[0.0, 1.0)` with 53 random bits, like CPython's `random()`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)
+    }
+
+    /// Returns an integer in `[0, n)`, without modulo bias, or 0 if `n` is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n <= 1 {
+            return 0;
+        }
+        // Draw as many bits as `n - 1` needs and reject values out of range, like CPython's
+        // `_randbelow`; each draw succeeds with probability over one half.
+        let mask = u64::MAX >> (n - 1).leading_zeros();
+        loop {
+            let candidate = self.next_u64() & mask;
+            if candidate < n {
+                return candidate;
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Implementation of the `re` module.
+//!
+//! Provides a subset of Python's `re` module backed by the Rust `regex` crate:
+//! - `compile()`, returning a `re.Pattern`
+//! - `search()`, `match()`, `fullmatch()`, returning a `re.Match` or `None`
+//! - `findall()`, `finditer()`, `sub()`, `subn()`, `split()` and `escape()`
+//! - The flags `IGNORECASE`, `MULTILINE`, `DOTALL`, `VERBOSE`, `ASCII`, `UNICODE` and
+//!   `NOFLAG`, with their one-letter aliases, as plain integers
+//! - `error` (also available as `PatternError`)
+//!
+//! The module functions compile their pattern and then behave exactly like the corresponding
+//! `re.Pattern` methods; see `types::re_pattern` for the supported syntax and resource limits.
+//! There is no pattern cache, so code matching in a loop should call `compile()` once.
+
+use crate::{
+    args::ArgValues,
+    builtins::Builtins,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    modules::ModuleFunctions,
+    resource::{ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Module, RePattern, allocate_tuple,
+        re_pattern::{ASCII, DOTALL, IGNORECASE, MULTILINE, MatchMode, UNICODE, VERBOSE, int_arg},
+        str::allocate_string,
+    },
+    value::Value,
+};
+
+/// Re module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ReFunctions {
+    Compile,
+    Search,
+    Match,
+    Fullmatch,
+    Findall,
+    Finditer,
+    Sub,
+    Subn,
+    Split,
+    Escape,
+}
+
+/// Creates the `re` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Re);
+
+    let functions = [
+        (StaticStrings::Compile, ReFunctions::Compile),
+        (StaticStrings::Search, ReFunctions::Search),
+        (StaticStrings::Match, ReFunctions::Match),
+        (StaticStrings::Fullmatch, ReFunctions::Fullmatch),
+        (StaticStrings::Findall, ReFunctions::Findall),
+        (StaticStrings::Finditer, ReFunctions::Finditer),
+        (StaticStrings::Sub, ReFunctions::Sub),
+        (StaticStrings::Subn, ReFunctions::Subn),
+        (StaticStrings::Split, ReFunctions::Split),
+        (StaticStrings::Escape, ReFunctions::Escape),
+    ];
+    for (name, function) in functions {
+        module.set_attr(
+            name,
+            Value::ModuleFunction(ModuleFunctions::Re(function)),
+            heap,
+            interns,
+        );
+    }
+
+    // Flags are exposed as plain integers rather than `re.RegexFlag` members
+    let flags = [
+        (StaticStrings::Ignorecase, b'I', IGNORECASE),
+        (StaticStrings::Multiline, b'M', MULTILINE),
+        (StaticStrings::Dotall, b'S', DOTALL),
+        (StaticStrings::Verbose, b'X', VERBOSE),
+        (StaticStrings::AsciiFlag, b'A', ASCII),
+        (StaticStrings::Unicode, b'U', UNICODE),
+    ];
+    for (name, alias, flag) in flags {
+        module.set_attr(name, Value::Int(flag), heap, interns);
+        module.set_attr(StringId::from_ascii(alias), Value::Int(flag), heap, interns);
+    }
+    module.set_attr(StaticStrings::Noflag, Value::Int(0), heap, interns);
+
+    for name in [StaticStrings::Error, StaticStrings::PatternError] {
+        module.set_attr(
+            name,
+            Value::Builtin(Builtins::ExcType(ExcType::PatternError)),
+            heap,
+            interns,
+        );
+    }
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a re module function.
+pub(super) fn call(
+    vm: &mut VM<impl ResourceTracker>,
+    function: ReFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    let value = match function {
+        ReFunctions::Compile => compile(args, vm.heap, vm.interns)?,
+        ReFunctions::Search => match_function(vm, "search", MatchMode::Search, args)?,
+        ReFunctions::Match => match_function(vm, "match", MatchMode::Match, args)?,
+        ReFunctions::Fullmatch => match_function(vm, "fullmatch", MatchMode::Full, args)?,
+        ReFunctions::Findall | ReFunctions::Finditer => {
+            let name = if function == ReFunctions::Findall {
+                "findall"
+            } else {
+                "finditer"
+            };
+            let [pattern, string, flags] = args.bind(name, ["pattern", "string", "flags"], 2, vm.heap, vm.interns)?;
+            let pattern = pattern.expect("required argument");
+            defer_drop!(pattern, vm);
+            let string = string.expect("required argument");
+            defer_drop!(string, vm);
+            let flags = int_arg(flags, 0, vm.heap)?;
+            let (pattern_id, compiled) = resolve_pattern(pattern, flags, vm.heap, vm.interns)?;
+            let result = if function == ReFunctions::Findall {
+                compiled.findall(string, 0, i64::MAX, vm.heap, vm.interns)
+            } else {
+                compiled.finditer(pattern_id, string, 0, i64::MAX, vm.heap, vm.interns)
+            };
+            vm.heap.dec_ref(pattern_id);
+            result?
+        }
+        ReFunctions::Sub | ReFunctions::Subn => {
+            let name = if function == ReFunctions::Sub { "sub" } else { "subn" };
+            let [pattern, repl, string, count, flags] = args.bind(
+                name,
+                ["pattern", "repl", "string", "count", "flags"],
+                3,
+                vm.heap,
+                vm.interns,
+            )?;
+            let pattern = pattern.expect("required argument");
+            defer_drop!(pattern, vm);
+            let repl = repl.expect("required argument");
+            defer_drop!(repl, vm);
+            let string = string.expect("required argument");
+            defer_drop!(string, vm);
+            let count = int_arg(count, 0, vm.heap)?;
+            let flags = int_arg(flags, 0, vm.heap)?;
+            let (pattern_id, compiled) = resolve_pattern(pattern, flags, vm.heap, vm.interns)?;
+            let result = compiled.sub(pattern_id, repl, string, count, vm);
+            vm.heap.dec_ref(pattern_id);
+            let (result, replaced) = result?;
+            if function == ReFunctions::Sub {
+                result
+            } else {
+                let replaced = i64::try_from(replaced).expect("match count fits in i64");
+                allocate_tuple([result, Value::Int(replaced)].into(), vm.heap)?
+            }
+        }
+        ReFunctions::Split => {
+            let [pattern, string, maxsplit, flags] = args.bind(
+                "split",
+                ["pattern", "string", "maxsplit", "flags"],
+                2,
+                vm.heap,
+                vm.interns,
+            )?;
+            let pattern = pattern.expect("required argument");
+            defer_drop!(pattern, vm);
+            let string = string.expect("required argument");
+            defer_drop!(string, vm);
+            let maxsplit = int_arg(maxsplit, 0, vm.heap)?;
+            let flags = int_arg(flags, 0, vm.heap)?;
+            let (pattern_id, compiled) = resolve_pattern(pattern, flags, vm.heap, vm.interns)?;
+            let result = compiled.split(string, maxsplit, vm.heap, vm.interns);
+            vm.heap.dec_ref(pattern_id);
+            result?
+        }
+        ReFunctions::Escape => escape(args, vm.heap, vm.interns)?,
+    };
+    Ok(AttrCallResult::Value(value))
+}
+
+/// Implementation of `re.compile(pattern, flags=0)`.
+///
+/// Passing an existing pattern returns it unchanged.
+fn compile(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let [pattern, flags] = args.bind("compile", ["pattern", "flags"], 1, heap, interns)?;
+    let pattern = pattern.expect("required argument");
+    defer_drop!(pattern, heap);
+    let flags = int_arg(flags, 0, heap)?;
+    let (pattern_id, _) = resolve_pattern(pattern, flags, heap, interns)?;
+    Ok(Value::Ref(pattern_id))
+}
+
+/// Implementation of `re.search()`, `re.match()` and `re.fullmatch()`, all taking
+/// `(pattern, string, flags=0)`.
+fn match_function(vm: &mut VM<impl ResourceTracker>, name: &str, mode: MatchMode, args: ArgValues) -> RunResult<Value> {
+    let [pattern, string, flags] = args.bind(name, ["pattern", "string", "flags"], 2, vm.heap, vm.interns)?;
+    let pattern = pattern.expect("required argument");
+    defer_drop!(pattern, vm);
+    let string = string.expect("required argument");
+    defer_drop!(string, vm);
+    let flags = int_arg(flags, 0, vm.heap)?;
+    let (pattern_id, compiled) = resolve_pattern(pattern, flags, vm.heap, vm.interns)?;
+    let result = compiled.match_value(pattern_id, string, 0, i64::MAX, mode, vm.heap, vm.interns);
+    vm.heap.dec_ref(pattern_id);
+    result
+}
+
+/// Returns the compiled pattern for the `pattern` argument of a module function, compiling
+/// and allocating it if it is a string.
+///
+/// The caller owns a new reference to the returned heap id.
+fn resolve_pattern(
+    pattern: &Value,
+    flags: i64,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<(HeapId, RePattern)> {
+    if let Value::Ref(id) = pattern
+        && let HeapData::RePattern(compiled) = heap.get(*id)
+    {
+        if flags != 0 {
+            return Err(SimpleException::new_msg(
+                ExcType::ValueError,
+                "cannot process flags argument with a compiled pattern",
+            )
+            .into());
+        }
+        let compiled = compiled.clone();
+        heap.inc_ref(*id);
+        return Ok((*id, compiled));
+    }
+    let Some(source) = pattern.as_str_ref(heap, interns) else {
+        return Err(ExcType::type_error("first argument must be string or compiled pattern"));
+    };
+    let compiled = RePattern::new(source.to_owned(), flags)?;
+    let id = heap.allocate(HeapData::RePattern(compiled.clone()))?;
+    Ok((id, compiled))
+}
+
+/// Implementation of `re.escape(pattern)`.
+///
+/// Backslash-escapes every character that is special in a pattern, like CPython.
+fn escape(args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let pattern = args.get_one_arg("escape", heap)?;
+    defer_drop!(pattern, heap);
+    let Some(source) = pattern.as_str_ref(heap, interns) else {
+        return Err(ExcType::type_error(format!(
+            "escape() argument must be str, not {}",
+            pattern.py_type(heap)
+        )));
+    };
+    let mut escaped = String::with_capacity(source.len());
+    for c in source.chars() {
+        if "()[]{}?*+-|^$\\.&~# \t\n\r\x0b\x0c".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    allocate_string(escaped, heap)
+}
@@ -4,8 +4,8 @@
 //! - `version`: Python version string (e.g., "3.14.0 (Monty)")
 //! - `version_info`: Named tuple (3, 14, 0, 'final', 0)
 //! - `platform`: Platform identifier ("monty")
-//! - `stdout`: Marker for standard output (no real functionality)
-//! - `stderr`: Marker for standard error (no real functionality)
+//! - `stdout`: Marker for standard output, accepted by `print(file=...)`
+//! - `stderr`: Marker for standard error, accepted by `print(file=...)`
 
 use crate::{
     heap::{Heap, HeapData, HeapId},
@@ -0,0 +1,63 @@
+//! Implementation of the `time` module.
+//!
+//! Provides a minimal implementation of Python's `time` module with:
+//! - `time()`: The current time in seconds since the Unix epoch
+//!
+//! The sandbox has no clock of its own, so `time()` yields to the host via the
+//! `OsFunction` callback mechanism and returns whatever the host provides.
+
+use crate::{
+    args::ArgValues,
+    exception_private::RunResult,
+    heap::{Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    modules::ModuleFunctions,
+    os::OsFunction,
+    resource::{ResourceError, ResourceTracker},
+    types::{AttrCallResult, Module},
+    value::Value,
+};
+
+/// Time module functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, serde::Serialize, serde::Deserialize)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum TimeFunctions {
+    Time,
+}
+
+/// Creates the `time` module and allocates it on the heap.
+///
+/// # Returns
+/// A HeapId pointing to the newly allocated module.
+///
+/// # Panics
+/// Panics if the required strings have not been pre-interned during prepare phase.
+pub fn create_module(heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> Result<HeapId, ResourceError> {
+    let mut module = Module::new(StaticStrings::Time);
+
+    module.set_attr(
+        StaticStrings::Time,
+        Value::ModuleFunction(ModuleFunctions::Time(TimeFunctions::Time)),
+        heap,
+        interns,
+    );
+
+    heap.allocate(HeapData::Module(module))
+}
+
+/// Dispatches a call to a time module function.
+///
+/// `time()` returns `AttrCallResult::OsCall` so the host can provide the current time.
+pub(super) fn call(
+    heap: &mut Heap<impl ResourceTracker>,
+    functions: TimeFunctions,
+    args: ArgValues,
+) -> RunResult<AttrCallResult> {
+    match functions {
+        TimeFunctions::Time => {
+            args.check_zero_args("time.time", heap)?;
+            heap.check_deterministic("time.time()")?;
+            Ok(AttrCallResult::OsCall(OsFunction::Time, ArgValues::Empty))
+        }
+    }
+}
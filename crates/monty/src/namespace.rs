@@ -22,6 +22,13 @@ impl NamespaceId {
     pub fn index(self) -> usize {
         self.0 as usize
     }
+
+    /// Returns the namespace reserved for the globals of the imported Python module at `index`.
+    ///
+    /// Module namespaces directly follow the global namespace, see `Namespaces::new_with_modules`.
+    pub fn python_module(index: usize) -> Self {
+        Self::new(index + 1)
+    }
 }
 
 /// Index for the global (module-level) namespace in Namespaces.
@@ -52,6 +59,10 @@ impl Namespace {
     pub fn mut_vec(&mut self) -> &mut Vec<Value> {
         &mut self.0
     }
+
+    pub fn values(&self) -> &[Value] {
+        &self.0
+    }
 }
 
 impl IntoIterator for Namespace {
@@ -66,7 +77,8 @@ impl IntoIterator for Namespace {
 /// Storage for all namespaces during execution.
 ///
 /// This struct owns all namespace data, allowing safe mutable access through indices.
-/// Index 0 is always the global (module-level) namespace.
+/// Index 0 is always the global (module-level) namespace, followed by one namespace reserved
+/// for each imported Python module.
 ///
 /// # Design Rationale
 ///
@@ -81,6 +93,11 @@ impl IntoIterator for Namespace {
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Namespaces {
     stack: Vec<Namespace>,
+    /// Number of namespaces reserved for imported Python modules after the global namespace.
+    ///
+    /// Like the global namespace, these live until execution ends rather than being dropped
+    /// when the frame running in them returns.
+    module_count: usize,
     /// if we have an old namespace to reuse, trace its id
     reuse_ids: Vec<NamespaceId>,
     /// Return values from external function calls or functions that completed after internal external calls.
@@ -111,8 +128,20 @@ impl Namespaces {
     ///
     /// The global namespace is always at index 0.
     pub fn new(namespace: Vec<Value>) -> Self {
+        Self::new_with_modules(namespace, 0)
+    }
+
+    /// Creates namespaces with the global namespace initialized and `module_count` empty
+    /// namespaces reserved for imported Python modules.
+    ///
+    /// A module's namespace is filled in when the module is first imported.
+    pub fn new_with_modules(namespace: Vec<Value>, module_count: usize) -> Self {
+        let mut stack = Vec::with_capacity(module_count + 1);
+        stack.push(Namespace(namespace));
+        stack.extend((0..module_count).map(|_| Namespace(Vec::new())));
         Self {
-            stack: vec![Namespace(namespace)],
+            stack,
+            module_count,
             reuse_ids: vec![],
             ext_return_values: vec![],
             next_ext_return_value: 0,
@@ -139,6 +168,14 @@ impl Namespaces {
         &mut self.stack[idx.index()]
     }
 
+    /// Returns whether `idx` is the global namespace or one reserved for a Python module.
+    ///
+    /// These namespaces outlive the frames executing in them, so popping such a frame must
+    /// not drop its namespace.
+    pub fn is_persistent(&self, idx: NamespaceId) -> bool {
+        idx.index() <= self.module_count
+    }
+
     /// Creates a new namespace for a function call with memory and recursion tracking.
     ///
     /// This method:
@@ -159,8 +196,8 @@ impl Namespaces {
         heap: &mut Heap<impl ResourceTracker>,
     ) -> Result<NamespaceId, ResourceError> {
         // Check recursion depth BEFORE memory allocation (fail fast)
-        // Depth excludes global namespace (stack[0]), so current depth = stack.len() - 1
-        let current_depth = self.stack.len() - 1;
+        // Depth excludes the global and module namespaces, so current depth = stack.len() - 1 - module_count
+        let current_depth = self.stack.len() - 1 - self.module_count;
         heap.tracker().check_recursion_depth(current_depth)?;
 
         // Track the memory used by this namespace's slots
@@ -196,7 +233,7 @@ impl Namespaces {
         heap: &mut Heap<impl ResourceTracker>,
     ) -> Result<NamespaceId, ResourceError> {
         // Check recursion depth BEFORE memory allocation (fail fast)
-        let current_depth = self.stack.len() - 1;
+        let current_depth = self.stack.len() - 1 - self.module_count;
         heap.tracker().check_recursion_depth(current_depth)?;
 
         // Track the memory used by this namespace's slots
@@ -236,19 +273,20 @@ impl Namespaces {
         self.reuse_ids.push(namespace_id);
     }
 
-    /// Cleans up the global namespace by dropping all values with proper ref counting.
+    /// Cleans up the global and module namespaces by dropping all values with proper ref counting.
     ///
     /// Call this before the namespaces is dropped to properly decrement reference counts
-    /// for any `Value::Ref` entries in the global namespace and return values.
+    /// for any `Value::Ref` entries in the global and module namespaces and return values.
     ///
     /// Only needed when `ref-count-panic` is enabled, since the Drop impl panics on unfreed Refs.
     #[cfg(feature = "ref-count-panic")]
     pub fn drop_global_with_heap(&mut self, heap: &mut Heap<impl ResourceTracker>) {
-        // Clean up global namespace
-        let global = self.get_mut(GLOBAL_NS_IDX);
-        for value in &mut global.0 {
-            let v = std::mem::replace(value, Value::Undefined);
-            v.drop_with_heap(heap);
+        // Clean up global and module namespaces
+        for namespace in &mut self.stack[..=self.module_count] {
+            for value in &mut namespace.0 {
+                let v = std::mem::replace(value, Value::Undefined);
+                v.drop_with_heap(heap);
+            }
         }
         // Clean up any remaining return values from external function calls
         for (_, value) in std::mem::take(&mut self.ext_return_values) {
@@ -268,6 +306,11 @@ impl Namespaces {
         self.stack.swap_remove(GLOBAL_NS_IDX.index())
     }
 
+    /// Number of namespaces in use: the global namespace, module namespaces and one per active call.
+    pub fn active_count(&self) -> usize {
+        self.stack.len() - self.reuse_ids.len()
+    }
+
     /// Returns an iterator over all HeapIds referenced by values in all namespaces.
     ///
     /// This is used by garbage collection to find all root references. Any heap
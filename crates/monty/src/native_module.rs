@@ -0,0 +1,238 @@
+//! Host-provided native modules.
+//!
+//! Embedders describe a [`NativeModule`] as a name plus a table of Rust functions and
+//! constants, and pass it to [`MontyRun::new_with_modules`](crate::MontyRun::new_with_modules).
+//! Sandboxed code can then `import` it like a built-in module.
+//!
+//! Native functions are registered as external functions named `module.function`, so a
+//! call leaves the VM through the usual `FrameExit::ExternalCall` path. The runner then
+//! invokes the Rust implementation in place and resumes, so the host never sees the call.
+//! Implementations are not serialized: after `load()`, calls to native functions surface
+//! as `RunProgress::FunctionCall` with the qualified name instead.
+
+use std::{fmt, sync::Arc};
+
+use crate::{
+    MontyException,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{ExtFunctionId, InternerBuilder, Interns, StringId},
+    object::MontyObject,
+    resource::ResourceTracker,
+    types::Module,
+    value::Value,
+};
+
+/// Signature of a native function: positional arguments and keyword arguments in, a
+/// return value or exception out.
+type NativeFn =
+    dyn Fn(Vec<MontyObject>, Vec<(MontyObject, MontyObject)>) -> Result<MontyObject, MontyException> + Send + Sync;
+
+/// A Rust function exposed to sandboxed code through a [`NativeModule`].
+#[derive(Clone)]
+pub(crate) struct NativeFunction(Arc<NativeFn>);
+
+impl NativeFunction {
+    /// Wraps a Rust closure.
+    pub fn new(
+        function: impl Fn(Vec<MontyObject>, Vec<(MontyObject, MontyObject)>) -> Result<MontyObject, MontyException>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        Self(Arc::new(function))
+    }
+
+    /// Calls the function with converted arguments.
+    pub fn call(
+        &self,
+        args: Vec<MontyObject>,
+        kwargs: Vec<(MontyObject, MontyObject)>,
+    ) -> Result<MontyObject, MontyException> {
+        (self.0)(args, kwargs)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NativeFunction(..)")
+    }
+}
+
+/// A module implemented by the host, importable by sandboxed code.
+///
+/// # Example
+/// ```
+/// use monty::{CompileOptions, MontyObject, MontyRun, NativeModule};
+///
+/// let units = NativeModule::new("units")
+///     .constant("INCH", MontyObject::Float(2.54))
+///     .function("double", |args, _kwargs| match args.as_slice() {
+///         [MontyObject::Int(i)] => Ok(MontyObject::Int(i * 2)),
+///         _ => Ok(MontyObject::None),
+///     });
+/// let code = "import units\nunits.double(21) + units.INCH";
+/// let runner = MontyRun::new_with_modules(
+///     code.to_owned(),
+///     "test.py",
+///     vec![],
+///     vec![],
+///     vec![units],
+///     vec![],
+///     CompileOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(runner.run_no_limits(vec![]).unwrap(), MontyObject::Float(44.54));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NativeModule {
+    /// The name used in `import` statements.
+    name: String,
+    /// Functions, in registration order.
+    functions: Vec<(String, NativeFunction)>,
+    /// Constant attributes, in registration order.
+    constants: Vec<(String, MontyObject)>,
+}
+
+impl NativeModule {
+    /// Creates an empty module importable as `name`.
+    ///
+    /// Built-in modules take precedence, so a native module named e.g. `math` is never
+    /// imported.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            functions: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+
+    /// Adds a function attribute implemented by `function`.
+    ///
+    /// The function receives the positional and keyword arguments of each call; an
+    /// `Err` is raised as an exception in the sandboxed code.
+    #[must_use]
+    pub fn function(
+        mut self,
+        name: impl Into<String>,
+        function: impl Fn(Vec<MontyObject>, Vec<(MontyObject, MontyObject)>) -> Result<MontyObject, MontyException>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.functions.push((name.into(), NativeFunction::new(function)));
+        self
+    }
+
+    /// Adds a constant attribute.
+    ///
+    /// A fresh copy of the value is created each time the module is imported.
+    #[must_use]
+    pub fn constant(mut self, name: impl Into<String>, value: MontyObject) -> Self {
+        self.constants.push((name.into(), value));
+        self
+    }
+
+    /// Returns the module name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Registers `modules` for compilation and execution.
+///
+/// Interns the module and attribute names, appends the qualified function names to
+/// `external_functions` so each gets an `ExtFunctionId`, and returns the module
+/// definitions for `Interns` along with the implementations indexed from the first new
+/// `ExtFunctionId`.
+///
+/// # Errors
+/// Returns a `RuntimeError` if two modules share a name.
+pub(crate) fn register_modules(
+    modules: Vec<NativeModule>,
+    interner: &mut InternerBuilder,
+    external_functions: &mut Vec<String>,
+) -> Result<(Vec<NativeModuleDef>, Vec<NativeFunction>), MontyException> {
+    let mut defs: Vec<NativeModuleDef> = Vec::with_capacity(modules.len());
+    let mut implementations = Vec::new();
+    for module in modules {
+        let name = interner.intern(&module.name);
+        if defs.iter().any(|def| def.name == name) {
+            return Err(MontyException::runtime_error(format!(
+                "native module '{}' is registered more than once",
+                module.name
+            )));
+        }
+        let mut attrs = Vec::with_capacity(module.functions.len() + module.constants.len());
+        for (attr_name, function) in module.functions {
+            let ext_id = ExtFunctionId::new(external_functions.len());
+            external_functions.push(format!("{}.{attr_name}", module.name));
+            attrs.push((interner.intern(&attr_name), NativeAttr::Function(ext_id)));
+            implementations.push(function);
+        }
+        for (attr_name, value) in module.constants {
+            attrs.push((interner.intern(&attr_name), NativeAttr::Constant(value)));
+        }
+        defs.push(NativeModuleDef { name, attrs });
+    }
+    Ok((defs, implementations))
+}
+
+/// A registered native module, as stored in `Interns` for the compiler and VM.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct NativeModuleDef {
+    /// The interned module name.
+    name: StringId,
+    /// The module attributes with interned names.
+    attrs: Vec<(StringId, NativeAttr)>,
+}
+
+/// An attribute of a native module.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+enum NativeAttr {
+    /// A function, called through the external function mechanism.
+    Function(ExtFunctionId),
+    /// A constant, converted to a `Value` on each import.
+    Constant(MontyObject),
+}
+
+impl NativeModuleDef {
+    /// Returns the interned module name.
+    pub fn name(&self) -> StringId {
+        self.name
+    }
+
+    /// Creates a new instance of this module on the heap.
+    ///
+    /// # Errors
+    /// Returns a `RuntimeError` if a constant cannot be converted to a value, or a
+    /// resource error if allocation fails.
+    pub fn create(&self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<HeapId> {
+        let mut values: Vec<Value> = Vec::with_capacity(self.attrs.len());
+        for (attr_name, attr) in &self.attrs {
+            let value = match attr {
+                NativeAttr::Function(ext_id) => Value::ExtFunction(*ext_id),
+                NativeAttr::Constant(obj) => match obj.clone().to_value(heap, interns) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        values.drop_with_heap(heap);
+                        return Err(ExcType::runtime_error_native_constant(
+                            interns.get_str(self.name),
+                            interns.get_str(*attr_name),
+                            &e,
+                        ));
+                    }
+                },
+            };
+            values.push(value);
+        }
+
+        let mut module = Module::new(self.name);
+        for ((attr_name, _), value) in self.attrs.iter().zip(values) {
+            module.set_attr(*attr_name, value, heap, interns);
+        }
+        Ok(heap.allocate(HeapData::Module(module))?)
+    }
+}
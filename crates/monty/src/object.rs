@@ -15,14 +15,17 @@ use crate::{
     intern::Interns,
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::{
-        LongInt, NamedTuple, Path, PyTrait, Type, allocate_tuple,
+        Date, DateTime, DictViewKind, ExternalObject, LongInt, NamedTuple, Path, PyTrait, TimeDelta, Type,
+        allocate_tuple,
         bytes::{Bytes, bytes_repr},
-        dict::Dict,
+        deque::Deque,
+        dict::{Dict, DictKind},
         list::List,
         set::{FrozenSet, Set},
         str::{Str, StringRepr, string_repr_fmt},
+        timedelta::{US_PER_DAY, US_PER_SECOND},
     },
-    value::{EitherStr, Value},
+    value::{EitherStr, Value, float_repr_fmt},
 };
 
 /// A Python value that can be passed to or returned from the interpreter.
@@ -43,30 +46,13 @@ use crate::{
 /// Only immutable variants (`None`, `Ellipsis`, `Bool`, `Int`, `Float`, `String`, `Bytes`)
 /// implement `Hash`. Attempting to hash mutable variants (`List`, `Dict`) will panic.
 ///
-/// # JSON Serialization
+/// # Serialization
 ///
-/// `MontyObject` supports JSON serialization with natural mappings:
+/// The derived serde implementation uses serde's externally tagged enum format, e.g.
+/// `{"Int": 1}` or `{"List": [...]}`, which round-trips every variant and is used for
+/// binary serialization with postcard.
 ///
-/// **Bidirectional (can serialize and deserialize):**
-/// - `None` ↔ JSON `null`
-/// - `Bool` ↔ JSON `true`/`false`
-/// - `Int` ↔ JSON integer
-/// - `Float` ↔ JSON float
-/// - `String` ↔ JSON string
-/// - `List` ↔ JSON array
-/// - `Dict` ↔ JSON object (keys must be interns)
-///
-/// **Output-only (serialize only, cannot deserialize from JSON):**
-/// - `Ellipsis` → `{"$ellipsis": true}`
-/// - `Tuple` → `{"$tuple": [...]}`
-/// - `Bytes` → `{"$bytes": [...]}`
-/// - `Exception` → `{"$exception": {"type": "...", "arg": "..."}}`
-/// - `Repr` → `{"$repr": "..."}`
-///
-/// # Binary Serialization
-///
-/// For binary serialization (e.g., with postcard), `MontyObject` uses derived serde
-/// with internally tagged format. This differs from the natural JSON format.
+/// For plain JSON like `{"a": [1, 2]}`, use `to_json()` and `from_json()` instead.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum MontyObject {
     /// Python's `Ellipsis` singleton (`...`).
@@ -104,6 +90,22 @@ pub enum MontyObject {
     },
     /// Python dictionary (insertion-ordered mapping).
     Dict(DictPairs),
+    /// Python `collections.defaultdict`: a dictionary with a factory for missing keys.
+    DefaultDict {
+        /// The `default_factory` callable, or `None`.
+        default_factory: Box<Self>,
+        /// Entries in insertion order.
+        items: DictPairs,
+    },
+    /// Python `collections.Counter`: a dictionary mapping elements to their counts.
+    Counter(DictPairs),
+    /// Python `collections.deque` (double-ended queue).
+    Deque {
+        /// Items from left to right.
+        items: Vec<Self>,
+        /// Maximum length, `None` for an unbounded deque.
+        maxlen: Option<usize>,
+    },
     /// Python set (mutable, unordered collection of unique elements).
     Set(Vec<Self>),
     /// Python frozenset (immutable, unordered collection of unique elements).
@@ -124,6 +126,44 @@ pub enum MontyObject {
     ///
     /// Represents a filesystem path. Can be used both as input (from host) and output.
     Path(String),
+    /// Python `datetime.date` object.
+    Date {
+        /// The year, in `1..=9999`.
+        year: i32,
+        /// The month, in `1..=12`.
+        month: u8,
+        /// The day of the month.
+        day: u8,
+    },
+    /// Python `datetime.datetime` object, always naive (without a timezone).
+    DateTime {
+        /// The year, in `1..=9999`.
+        year: i32,
+        /// The month, in `1..=12`.
+        month: u8,
+        /// The day of the month.
+        day: u8,
+        /// The hour, in `0..24`.
+        hour: u8,
+        /// The minute, in `0..60`.
+        minute: u8,
+        /// The second, in `0..60`.
+        second: u8,
+        /// The microsecond, in `0..1000000`.
+        microsecond: u32,
+    },
+    /// Python `datetime.timedelta` object.
+    ///
+    /// Normalized like Python when converted to a value, so for example
+    /// `seconds: -1` becomes `days: -1, seconds: 86399`.
+    TimeDelta {
+        /// Whole days, whose magnitude must not exceed 999999999.
+        days: i32,
+        /// Seconds within the day.
+        seconds: i32,
+        /// Microseconds within the second.
+        microseconds: i32,
+    },
     /// A dataclass instance with class name, field names, attributes, and mutability.
     ///
     /// Method calls are detected lazily at runtime: when `call_attr` is invoked
@@ -141,6 +181,19 @@ pub enum MontyObject {
         /// Whether this dataclass instance is immutable.
         frozen: bool,
     },
+    /// An opaque host object, e.g. a database connection or API client.
+    ///
+    /// The sandbox can't see inside it. Calling a method, e.g. `db.query(sql)`, pauses
+    /// execution with a `RunProgress::FunctionCall` named after the method, with
+    /// `method_call` set and the object as the first argument. Reading an attribute,
+    /// e.g. `db.name`, does the same with a method named `__getattr__` and arguments
+    /// `(db, 'name')`. Names starting with an underscore raise `AttributeError`.
+    ExternalObject {
+        /// The class name shown in `repr()` and error messages, e.g. `"Database"`.
+        class_name: String,
+        /// Host-defined identifier used to find the real object when resolving calls.
+        handle: u64,
+    },
     /// Fallback for values that cannot be represented as other variants.
     ///
     /// Contains the `repr()` string of the original value.
@@ -181,6 +234,14 @@ impl MontyObject {
         py_obj
     }
 
+    /// Returns the builtin type object with the given name (e.g. `"list"`), if there is one.
+    ///
+    /// Used by hosts to pass builtin types as values, e.g. a defaultdict's `default_factory`.
+    #[must_use]
+    pub fn builtin_type(name: &str) -> Option<Self> {
+        Type::from_builtin_name(name).map(Self::Type)
+    }
+
     /// Creates a new `MontyObject` from something that can be converted into a `DictPairs`.
     pub fn dict(dict: impl Into<DictPairs>) -> Self {
         Self::Dict(dict.into())
@@ -245,6 +306,24 @@ impl MontyObject {
                     .map_err(|_| InvalidInputError::invalid_type("unhashable dict keys"))?;
                 Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
             }
+            Self::DefaultDict { default_factory, items } => {
+                let factory = match *default_factory {
+                    Self::None => Value::None,
+                    Self::Type(t) => Value::Builtin(Builtins::from_type(t)),
+                    Self::BuiltinFunction(f) => Value::Builtin(Builtins::Function(f)),
+                    _ => return Err(InvalidInputError::invalid_type("defaultdict factory")),
+                };
+                fill_dict(Dict::with_kind(DictKind::DefaultDict(factory)), items, heap, interns)
+            }
+            Self::Counter(items) => fill_dict(Dict::with_kind(DictKind::Counter), items, heap, interns),
+            Self::Deque { items, maxlen } => {
+                let values = items
+                    .into_iter()
+                    .map(|item| item.to_value(heap, interns))
+                    .collect::<Result<_, _>>()?;
+                let deque = Deque::new(values, maxlen, heap);
+                Ok(Value::Ref(heap.allocate(HeapData::Deque(deque))?))
+            }
             Self::Set(items) => {
                 let mut set = Set::new();
                 for item in items {
@@ -287,15 +366,48 @@ impl MontyObject {
                 let dc = Dataclass::new(name, type_id, field_names, dict, frozen);
                 Ok(Value::Ref(heap.allocate(HeapData::Dataclass(dc))?))
             }
+            Self::ExternalObject { class_name, handle } => Ok(Value::Ref(
+                heap.allocate(HeapData::ExternalObject(ExternalObject::new(class_name, handle)))?,
+            )),
             Self::Path(s) => Ok(Value::Ref(heap.allocate(HeapData::Path(Path::new(s)))?)),
-            Self::Type(t) => Ok(Value::Builtin(Builtins::Type(t))),
+            Self::Date { year, month, day } => {
+                let date = Date::new(year.into(), month.into(), day.into())
+                    .map_err(|_| InvalidInputError::invalid_type("date"))?;
+                Ok(Value::Ref(heap.allocate(HeapData::Date(date))?))
+            }
+            Self::DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                microsecond,
+            } => {
+                let dt = Date::new(year.into(), month.into(), day.into())
+                    .and_then(|date| DateTime::new(date, hour.into(), minute.into(), second.into(), microsecond.into()))
+                    .map_err(|_| InvalidInputError::invalid_type("datetime"))?;
+                Ok(Value::Ref(heap.allocate(HeapData::DateTime(dt))?))
+            }
+            Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            } => {
+                let total =
+                    i128::from(days) * US_PER_DAY + i128::from(seconds) * US_PER_SECOND + i128::from(microseconds);
+                let td =
+                    TimeDelta::from_microseconds(total).map_err(|_| InvalidInputError::invalid_type("timedelta"))?;
+                Ok(Value::Ref(heap.allocate(HeapData::TimeDelta(td))?))
+            }
+            Self::Type(t) => Ok(Value::Builtin(Builtins::from_type(t))),
             Self::BuiltinFunction(f) => Ok(Value::Builtin(Builtins::Function(f))),
             Self::Repr(_) => Err(InvalidInputError::invalid_type("Repr")),
             Self::Cycle(_, _) => Err(InvalidInputError::invalid_type("Cycle")),
         }
     }
 
-    fn from_value(object: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Self {
+    pub(crate) fn from_value(object: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> Self {
         let mut visited = AHashSet::new();
         let mut guard = DepthGuard::default();
         Self::from_value_inner(object, heap, &mut visited, &mut guard, interns)
@@ -347,7 +459,7 @@ impl MontyObject {
                 if visited.contains(id) {
                     // Cycle detected - return appropriate placeholder
                     return match heap.get(*id) {
-                        HeapData::List(_) => Self::Cycle(*id, "[...]".to_owned()),
+                        HeapData::List(_) | HeapData::Deque(_) => Self::Cycle(*id, "[...]".to_owned()),
                         HeapData::Tuple(_) | HeapData::NamedTuple(_) => Self::Cycle(*id, "(...)".to_owned()),
                         HeapData::Dict(_) => Self::Cycle(*id, "{...}".to_owned()),
                         _ => Self::Cycle(*id, "...".to_owned()),
@@ -360,12 +472,28 @@ impl MontyObject {
                 let result = match heap.get(*id) {
                     HeapData::Str(s) => Self::String(s.as_str().to_owned()),
                     HeapData::Bytes(b) => Self::Bytes(b.as_slice().to_owned()),
+                    // There is no bytearray object variant, so the host sees the contents as bytes
+                    HeapData::ByteArray(b) => Self::Bytes(b.as_slice().to_owned()),
                     HeapData::List(list) => Self::List(
                         list.as_slice()
                             .iter()
                             .map(|obj| Self::from_value_inner(obj, heap, visited, guard, interns))
                             .collect(),
                     ),
+                    // There is no view object variant, so the host sees a list of the view's items
+                    HeapData::DictView(view) => Self::List(
+                        view.dict(heap)
+                            .iter()
+                            .map(|(key, value)| match view.kind() {
+                                DictViewKind::Keys => Self::from_value_inner(key, heap, visited, guard, interns),
+                                DictViewKind::Values => Self::from_value_inner(value, heap, visited, guard, interns),
+                                DictViewKind::Items => Self::Tuple(vec![
+                                    Self::from_value_inner(key, heap, visited, guard, interns),
+                                    Self::from_value_inner(value, heap, visited, guard, interns),
+                                ]),
+                            })
+                            .collect(),
+                    ),
                     HeapData::Tuple(tuple) => Self::Tuple(
                         tuple
                             .as_slice()
@@ -386,16 +514,35 @@ impl MontyObject {
                             .map(|obj| Self::from_value_inner(obj, heap, visited, guard, interns))
                             .collect(),
                     },
-                    HeapData::Dict(dict) => Self::Dict(DictPairs(
-                        dict.into_iter()
-                            .map(|(k, v)| {
-                                (
-                                    Self::from_value_inner(k, heap, visited, guard, interns),
-                                    Self::from_value_inner(v, heap, visited, guard, interns),
-                                )
-                            })
+                    HeapData::Dict(dict) => {
+                        let items = DictPairs(
+                            dict.into_iter()
+                                .map(|(k, v)| {
+                                    (
+                                        Self::from_value_inner(k, heap, visited, guard, interns),
+                                        Self::from_value_inner(v, heap, visited, guard, interns),
+                                    )
+                                })
+                                .collect(),
+                        );
+                        match dict.kind() {
+                            DictKind::Dict => Self::Dict(items),
+                            DictKind::DefaultDict(factory) => Self::DefaultDict {
+                                default_factory: Box::new(Self::from_value_inner(
+                                    factory, heap, visited, guard, interns,
+                                )),
+                                items,
+                            },
+                            DictKind::Counter => Self::Counter(items),
+                        }
+                    }
+                    HeapData::Deque(deque) => Self::Deque {
+                        items: deque
+                            .iter()
+                            .map(|obj| Self::from_value_inner(obj, heap, visited, guard, interns))
                             .collect(),
-                    )),
+                        maxlen: deque.maxlen(),
+                    },
                     HeapData::Set(set) => Self::Set(
                         set.storage()
                             .iter()
@@ -474,6 +621,41 @@ impl MontyObject {
                         Self::Repr(format!("<gather({})>", gather.item_count()))
                     }
                     HeapData::Path(path) => Self::Path(path.as_str().to_owned()),
+                    HeapData::ContextManager(cm) => {
+                        // Context managers are internal objects - represent as a repr string
+                        let mut s = String::new();
+                        let _ = cm.py_repr_fmt(&mut s, heap, visited, guard, interns);
+                        Self::Repr(s)
+                    }
+                    HeapData::Date(date) => Self::Date {
+                        year: date.year(),
+                        month: date.month(),
+                        day: date.day(),
+                    },
+                    HeapData::DateTime(dt) => Self::DateTime {
+                        year: dt.date().year(),
+                        month: dt.date().month(),
+                        day: dt.date().day(),
+                        hour: dt.hour(),
+                        minute: dt.minute(),
+                        second: dt.second(),
+                        microsecond: dt.microsecond(),
+                    },
+                    HeapData::TimeDelta(td) => Self::TimeDelta {
+                        days: td.days(),
+                        seconds: td.seconds(),
+                        microseconds: td.microseconds(),
+                    },
+                    HeapData::ExternalObject(obj) => Self::ExternalObject {
+                        class_name: obj.class_name().to_owned(),
+                        handle: obj.handle(),
+                    },
+                    // Regular expression, functools and namedtuple class objects have no host equivalent - represent as a repr string
+                    HeapData::RePattern(_)
+                    | HeapData::ReMatch(_)
+                    | HeapData::Partial(_)
+                    | HeapData::LruCache(_)
+                    | HeapData::NamedTupleType(_) => Self::Repr(object.py_repr(heap, guard, interns).into_owned()),
                 };
 
                 // Remove from visited set after processing
@@ -508,14 +690,7 @@ impl MontyObject {
             Self::Bool(false) => f.write_str("False"),
             Self::Int(v) => write!(f, "{v}"),
             Self::BigInt(v) => write!(f, "{v}"),
-            Self::Float(v) => {
-                let s = v.to_string();
-                f.write_str(&s)?;
-                if !s.contains('.') {
-                    f.write_str(".0")?;
-                }
-                Ok(())
-            }
+            Self::Float(v) => float_repr_fmt(*v, f),
             Self::String(s) => string_repr_fmt(s, f),
             Self::Bytes(b) => f.write_str(&bytes_repr(b)),
             Self::List(l) => {
@@ -562,21 +737,36 @@ impl MontyObject {
                 }
                 f.write_char(')')
             }
-            Self::Dict(d) => {
-                f.write_char('{')?;
-                let mut iter = d.iter();
-                if let Some((k, v)) = iter.next() {
-                    k.repr_fmt(f)?;
-                    f.write_str(": ")?;
-                    v.repr_fmt(f)?;
-                    for (k, v) in iter {
+            Self::Dict(d) => Self::dict_repr_fmt(d, f),
+            Self::DefaultDict { default_factory, items } => {
+                f.write_str("defaultdict(")?;
+                default_factory.repr_fmt(f)?;
+                f.write_str(", ")?;
+                Self::dict_repr_fmt(items, f)?;
+                f.write_char(')')
+            }
+            Self::Counter(items) => {
+                f.write_str("Counter(")?;
+                if !items.is_empty() {
+                    Self::dict_repr_fmt(items, f)?;
+                }
+                f.write_char(')')
+            }
+            Self::Deque { items, maxlen } => {
+                f.write_str("deque([")?;
+                let mut iter = items.iter();
+                if let Some(first) = iter.next() {
+                    first.repr_fmt(f)?;
+                    for item in iter {
                         f.write_str(", ")?;
-                        k.repr_fmt(f)?;
-                        f.write_str(": ")?;
-                        v.repr_fmt(f)?;
+                        item.repr_fmt(f)?;
                     }
                 }
-                f.write_char('}')
+                f.write_str("]")?;
+                if let Some(maxlen) = maxlen {
+                    write!(f, ", maxlen={maxlen}")?;
+                }
+                f.write_char(')')
             }
             Self::Set(s) => {
                 if s.is_empty() {
@@ -648,6 +838,48 @@ impl MontyObject {
                 f.write_char(')')
             }
             Self::Path(p) => write!(f, "PosixPath('{p}')"),
+            Self::Date { year, month, day } => write!(f, "datetime.date({year}, {month}, {day})"),
+            Self::DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                microsecond,
+            } => {
+                write!(f, "datetime.datetime({year}, {month}, {day}, {hour}, {minute}")?;
+                if *second != 0 || *microsecond != 0 {
+                    write!(f, ", {second}")?;
+                }
+                if *microsecond != 0 {
+                    write!(f, ", {microsecond}")?;
+                }
+                f.write_char(')')
+            }
+            Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            } => {
+                // Like Python, only non-zero fields are shown
+                f.write_str("datetime.timedelta(")?;
+                let mut first = true;
+                for (name, value) in [("days", days), ("seconds", seconds), ("microseconds", microseconds)] {
+                    if *value != 0 {
+                        if !first {
+                            f.write_str(", ")?;
+                        }
+                        write!(f, "{name}={value}")?;
+                        first = false;
+                    }
+                }
+                if first {
+                    f.write_char('0')?;
+                }
+                f.write_char(')')
+            }
+            Self::ExternalObject { class_name, handle } => write!(f, "<external {class_name} #{handle}>"),
             Self::Type(t) => write!(f, "<class '{t}'>"),
             Self::BuiltinFunction(func) => write!(f, "<built-in function {func}>"),
             Self::Repr(s) => write!(f, "Repr({})", StringRepr(s)),
@@ -655,6 +887,24 @@ impl MontyObject {
         }
     }
 
+    /// Writes the `{key: value, ...}` repr of dictionary entries.
+    fn dict_repr_fmt(d: &DictPairs, f: &mut impl Write) -> fmt::Result {
+        f.write_char('{')?;
+        let mut iter = d.iter();
+        if let Some((k, v)) = iter.next() {
+            k.repr_fmt(f)?;
+            f.write_str(": ")?;
+            v.repr_fmt(f)?;
+            for (k, v) in iter {
+                f.write_str(", ")?;
+                k.repr_fmt(f)?;
+                f.write_str(": ")?;
+                v.repr_fmt(f)?;
+            }
+        }
+        f.write_char('}')
+    }
+
     /// Returns `true` if this value is "truthy" according to Python's truth testing rules.
     ///
     /// In Python, the following values are considered falsy:
@@ -678,12 +928,20 @@ impl MontyObject {
             Self::List(l) => !l.is_empty(),
             Self::Tuple(t) => !t.is_empty(),
             Self::NamedTuple { values, .. } => !values.is_empty(),
-            Self::Dict(d) => !d.is_empty(),
+            Self::Dict(d) | Self::DefaultDict { items: d, .. } | Self::Counter(d) => !d.is_empty(),
+            Self::Deque { items, .. } => !items.is_empty(),
             Self::Set(s) => !s.is_empty(),
             Self::FrozenSet(fs) => !fs.is_empty(),
             Self::Exception { .. } => true,
-            Self::Path(_) => true,          // Path instances are always truthy
+            Self::Path(_) => true, // Path instances are always truthy
+            Self::Date { .. } | Self::DateTime { .. } => true,
+            Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            } => *days != 0 || *seconds != 0 || *microseconds != 0,
             Self::Dataclass { .. } => true, // Dataclass instances are always truthy
+            Self::ExternalObject { .. } => true,
             Self::Type(_) | Self::BuiltinFunction(_) | Self::Repr(_) | Self::Cycle(_, _) => true,
         }
     }
@@ -705,11 +963,18 @@ impl MontyObject {
             Self::Tuple(_) => "tuple",
             Self::NamedTuple { .. } => "namedtuple",
             Self::Dict(_) => "dict",
+            Self::DefaultDict { .. } => "defaultdict",
+            Self::Counter(_) => "Counter",
+            Self::Deque { .. } => "deque",
             Self::Set(_) => "set",
             Self::FrozenSet(_) => "frozenset",
             Self::Exception { .. } => "Exception",
             Self::Path(_) => "PosixPath",
+            Self::Date { .. } => "date",
+            Self::DateTime { .. } => "datetime",
+            Self::TimeDelta { .. } => "timedelta",
             Self::Dataclass { .. } => "dataclass",
+            Self::ExternalObject { .. } => "external",
             Self::Type(_) => "type",
             Self::BuiltinFunction(_) => "builtin_function_or_method",
             Self::Repr(_) => "repr",
@@ -718,6 +983,26 @@ impl MontyObject {
     }
 }
 
+/// Converts `items` and inserts them into `dict`, a defaultdict or Counter, then allocates it.
+fn fill_dict(
+    mut dict: Dict,
+    items: DictPairs,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Result<Value, InvalidInputError> {
+    for (k, v) in items {
+        let key = k.to_value(heap, interns)?;
+        let value = v.to_value(heap, interns)?;
+        let old_value = dict
+            .set(key, value, heap, interns)
+            .map_err(|_| InvalidInputError::invalid_type("unhashable dict keys"))?;
+        if let Some(old_value) = old_value {
+            old_value.drop_with_heap(heap);
+        }
+    }
+    Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+}
+
 impl Hash for MontyObject {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // Hash the discriminant first (but Int and BigInt share discriminant for consistency)
@@ -746,6 +1031,22 @@ impl Hash for MontyObject {
             Self::String(string) => string.hash(state),
             Self::Bytes(bytes) => bytes.hash(state),
             Self::Path(path) => path.hash(state),
+            Self::Date { year, month, day } => (year, month, day).hash(state),
+            Self::DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                microsecond,
+            } => (year, month, day, hour, minute, second, microsecond).hash(state),
+            Self::TimeDelta {
+                days,
+                seconds,
+                microseconds,
+            } => (days, seconds, microseconds).hash(state),
+            Self::ExternalObject { class_name, handle } => (class_name, handle).hash(state),
             Self::Type(t) => t.to_string().hash(state),
             Self::Cycle(_, _) => panic!("cycle values are not hashable"),
             _ => panic!("{} python values are not hashable", self.type_name()),
@@ -786,6 +1087,27 @@ impl PartialEq for MontyObject {
                 values == t
             }
             (Self::Dict(a), Self::Dict(b)) => a == b,
+            (
+                Self::DefaultDict {
+                    default_factory: a_factory,
+                    items: a_items,
+                },
+                Self::DefaultDict {
+                    default_factory: b_factory,
+                    items: b_items,
+                },
+            ) => a_factory == b_factory && a_items == b_items,
+            (Self::Counter(a), Self::Counter(b)) => a == b,
+            (
+                Self::Deque {
+                    items: a_items,
+                    maxlen: a_maxlen,
+                },
+                Self::Deque {
+                    items: b_items,
+                    maxlen: b_maxlen,
+                },
+            ) => a_items == b_items && a_maxlen == b_maxlen,
             (Self::Set(a), Self::Set(b)) => a == b,
             (Self::FrozenSet(a), Self::FrozenSet(b)) => a == b,
             (
@@ -821,6 +1143,56 @@ impl PartialEq for MontyObject {
                     && a_frozen == b_frozen
             }
             (Self::Path(a), Self::Path(b)) => a == b,
+            (
+                Self::Date { year, month, day },
+                Self::Date {
+                    year: b_year,
+                    month: b_month,
+                    day: b_day,
+                },
+            ) => (year, month, day) == (b_year, b_month, b_day),
+            (
+                Self::DateTime {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minute,
+                    second,
+                    microsecond,
+                },
+                Self::DateTime {
+                    year: b_year,
+                    month: b_month,
+                    day: b_day,
+                    hour: b_hour,
+                    minute: b_minute,
+                    second: b_second,
+                    microsecond: b_microsecond,
+                },
+            ) => {
+                (year, month, day, hour, minute, second, microsecond)
+                    == (b_year, b_month, b_day, b_hour, b_minute, b_second, b_microsecond)
+            }
+            (
+                Self::TimeDelta {
+                    days,
+                    seconds,
+                    microseconds,
+                },
+                Self::TimeDelta {
+                    days: b_days,
+                    seconds: b_seconds,
+                    microseconds: b_microseconds,
+                },
+            ) => (days, seconds, microseconds) == (b_days, b_seconds, b_microseconds),
+            (
+                Self::ExternalObject { class_name, handle },
+                Self::ExternalObject {
+                    class_name: b_class_name,
+                    handle: b_handle,
+                },
+            ) => (class_name, handle) == (b_class_name, b_handle),
             (Self::Repr(a), Self::Repr(b)) => a == b,
             (Self::Cycle(a, _), Self::Cycle(b, _)) => a == b,
             (Self::Type(a), Self::Type(b)) => a == b,
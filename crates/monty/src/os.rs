@@ -15,7 +15,7 @@ use crate::{MontyObject, intern::StaticStrings};
 /// OS operations that require host system access.
 ///
 /// These represent operations that Monty cannot perform in isolation because
-/// they require interacting with the operating system (filesystem, network, etc.).
+/// they require interacting with the operating system (filesystem, clock, network, etc.).
 /// The host application decides whether to permit and execute these operations.
 ///
 /// # Extension
@@ -81,6 +81,18 @@ pub enum OsFunction {
     /// Get the entire environment as a dictionary
     #[strum(serialize = "os.environ")]
     GetEnviron,
+    /// Get the current time as seconds since the Unix epoch
+    #[strum(serialize = "time.time")]
+    Time,
+    /// Get the current local date and time as a naive datetime
+    #[strum(serialize = "datetime.now")]
+    DateTimeNow,
+    /// Get the current local date
+    #[strum(serialize = "date.today")]
+    DateToday,
+    /// Read a line of input for the `input()` builtin, after its prompt has been printed
+    #[strum(serialize = "input")]
+    Input,
 }
 
 impl TryFrom<StaticStrings> for OsFunction {
@@ -11,14 +11,16 @@ use ruff_text_size::{Ranged, TextRange};
 
 use crate::{
     StackFrame,
-    args::{ArgExprs, Kwarg},
-    exception_private::ExcType,
+    args::{ArgExprs, Kwarg, StarredArg},
+    builtins::{Builtins, BuiltinsFunctions},
+    exception_private::{ExcClassDef, ExcType, RunError, SimpleException},
     exception_public::{CodeLoc, MontyException},
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, Node, Operator, UnpackTarget,
+        AssignTarget, Callable, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal, Node,
+        Operator, UnpackTarget,
     },
-    fstring::{ConversionFlag, FStringPart, FormatSpec},
-    intern::{InternerBuilder, StringId},
+    fstring::{ConversionFlag, FStringPart, FormatSpec, FormatSpecError, ParsedFormatSpec},
+    intern::{InternerBuilder, StaticStrings, StringId},
     value::EitherStr,
 };
 
@@ -91,6 +93,8 @@ pub struct RawFunctionDef {
     pub body: Vec<ParseNode>,
     /// Whether this is an async function (`async def`).
     pub is_async: bool,
+    /// Decorator expressions in source order (outermost first).
+    pub decorators: Vec<ExprLoc>,
 }
 
 /// Type alias for parsed AST nodes (output of the parser).
@@ -152,6 +156,28 @@ pub(crate) fn parse_with_interner(
     })
 }
 
+/// Parses code like `parse()`, but skips statements that fail to parse instead of stopping,
+/// returning the errors of all of them in source order.
+///
+/// Used to report every unsupported feature at once. Code that isn't valid Python has a
+/// single error, since there's no syntax tree to continue with.
+pub(crate) fn parse_reporting_all(code: &str, filename: &str) -> Result<ParseResult, Vec<ParseError>> {
+    let mut parser = Parser::new(code, filename, InternerBuilder::new(code));
+    let parsed =
+        parse_module(code).map_err(|e| vec![ParseError::syntax(e.to_string(), parser.convert_range(e.range()))])?;
+    parser.skipped_errors = Some(Vec::new());
+    let nodes = parser
+        .parse_statements(parsed.into_syntax().body)
+        .map_err(|e| vec![e])?;
+    match parser.skipped_errors {
+        Some(errors) if !errors.is_empty() => Err(errors),
+        _ => Ok(ParseResult {
+            nodes,
+            interner: parser.interner,
+        }),
+    }
+}
+
 /// Parser for converting ruff AST to Monty's intermediate ParseNode representation.
 ///
 /// Holds references to the source code and owns a string interner for names.
@@ -167,6 +193,12 @@ pub struct Parser<'a> {
     /// Starts at MAX_NESTING_DEPTH and decrements on each nested level.
     /// When it reaches zero, we return a "too many nested parentheses" error.
     depth_remaining: u16,
+    /// Number of `with` statements enclosing the statement being parsed, used to give
+    /// each nested `with` its own hidden variables.
+    with_depth: usize,
+    /// Errors of the statements skipped so far, when parsing continues past statements that
+    /// fail to parse. `None` stops at the first error.
+    skipped_errors: Option<Vec<ParseError>>,
 }
 
 impl<'a> Parser<'a> {
@@ -185,11 +217,26 @@ impl<'a> Parser<'a> {
             filename_id,
             interner,
             depth_remaining: MAX_NESTING_DEPTH,
+            with_depth: 0,
+            skipped_errors: None,
         }
     }
 
     fn parse_statements(&mut self, statements: Vec<Stmt>) -> Result<Vec<ParseNode>, ParseError> {
-        statements.into_iter().map(|f| self.parse_statement(f)).collect()
+        let mut nodes = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let (depth_remaining, with_depth) = (self.depth_remaining, self.with_depth);
+            if let Err(error) = self.parse_statement(statement, &mut nodes) {
+                let Some(skipped_errors) = &mut self.skipped_errors else {
+                    return Err(error);
+                };
+                skipped_errors.push(error);
+                // the failed statement may not have restored them
+                self.depth_remaining = depth_remaining;
+                self.with_depth = with_depth;
+            }
+        }
+        Ok(nodes)
     }
 
     fn parse_elif_else_clauses(&mut self, clauses: Vec<ElifElseClause>) -> Result<Vec<ParseNode>, ParseError> {
@@ -228,9 +275,24 @@ impl<'a> Parser<'a> {
         Ok(ExceptHandler { exc_type, name, body })
     }
 
-    fn parse_statement(&mut self, statement: Stmt) -> Result<ParseNode, ParseError> {
+    fn parse_statement(&mut self, statement: Stmt, nodes: &mut Vec<ParseNode>) -> Result<(), ParseError> {
         self.decr_depth_remaining(|| statement.range())?;
-        let result = self.parse_statement_impl(statement);
+        let result = match statement {
+            // `with` lowers to several nodes, so it can't go through `parse_statement_impl`
+            Stmt::With(ast::StmtWith {
+                items,
+                body,
+                is_async: false,
+                ..
+            }) => {
+                let mut items = items.into_iter();
+                match items.next() {
+                    Some(item) => self.parse_with_item(item, &mut items, body, nodes),
+                    None => Ok(()),
+                }
+            }
+            statement => self.parse_statement_impl(statement).map(|node| nodes.push(node)),
+        };
         self.depth_remaining += 1;
         result
     }
@@ -264,6 +326,11 @@ impl<'a> Parser<'a> {
                 };
 
                 let name = self.identifier(&function.name.id, function.name.range);
+                let decorators = function
+                    .decorator_list
+                    .into_iter()
+                    .map(|decorator| self.parse_expression(decorator.expression))
+                    .collect::<Result<Vec<_>, _>>()?;
                 // Parse function body recursively
                 let body = self.parse_statements(function.body)?;
                 let is_async = function.is_async;
@@ -273,21 +340,32 @@ impl<'a> Parser<'a> {
                     signature,
                     body,
                     is_async,
+                    decorators,
                 }))
             }
-            Stmt::ClassDef(c) => Err(ParseError::not_implemented(
-                "class definitions",
-                self.convert_range(c.range),
-            )),
+            Stmt::ClassDef(c) => self.parse_exception_class(c),
             Stmt::Return(ast::StmtReturn { value, .. }) => match value {
                 Some(value) => Ok(Node::Return(self.parse_expression(*value)?)),
                 None => Ok(Node::ReturnNone),
             },
-            Stmt::Delete(d) => Err(ParseError::not_implemented(
-                "the 'del' statement",
-                self.convert_range(d.range),
-            )),
+            Stmt::Delete(ast::StmtDelete { targets, .. }) => {
+                let mut delete_targets = Vec::with_capacity(targets.len());
+                for target in targets {
+                    self.parse_delete_target(target, &mut delete_targets)?;
+                }
+                Ok(Node::Delete {
+                    targets: delete_targets,
+                })
+            }
             Stmt::TypeAlias(t) => Err(ParseError::not_implemented("type aliases", self.convert_range(t.range))),
+            Stmt::Assign(ast::StmtAssign { targets, value, .. }) if targets.len() > 1 => {
+                let object = self.parse_expression(*value)?;
+                let targets = targets
+                    .into_iter()
+                    .map(|target| self.parse_assign_target(target))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Node::ChainAssign { targets, object })
+            }
             Stmt::Assign(ast::StmtAssign {
                 targets, value, range, ..
             }) => self.parse_assignment(first(targets, self.convert_range(range))?, *value),
@@ -338,30 +416,25 @@ impl<'a> Parser<'a> {
                 let or_else = self.parse_elif_else_clauses(elif_else_clauses)?;
                 Ok(Node::If { test, body, or_else })
             }
-            Stmt::With(ast::StmtWith { is_async, range, .. }) => {
-                if is_async {
-                    Err(ParseError::not_implemented(
-                        "async context managers (async with)",
-                        self.convert_range(range),
-                    ))
-                } else {
-                    Err(ParseError::not_implemented(
-                        "context managers (with statements)",
-                        self.convert_range(range),
-                    ))
-                }
-            }
+            // Synchronous `with` statements are handled in `parse_statement`
+            Stmt::With(ast::StmtWith { range, .. }) => Err(ParseError::not_implemented(
+                "async context managers (async with)",
+                self.convert_range(range),
+            )),
             Stmt::Match(m) => Err(ParseError::not_implemented(
                 "pattern matching (match statements)",
                 self.convert_range(m.range),
             )),
-            Stmt::Raise(ast::StmtRaise { exc, .. }) => {
-                // TODO add cause to Node::Raise
-                let expr = match exc {
+            Stmt::Raise(ast::StmtRaise { exc, cause, .. }) => {
+                let exc = match exc {
+                    Some(expr) => Some(self.parse_expression(*expr)?),
+                    None => None,
+                };
+                let cause = match cause {
                     Some(expr) => Some(self.parse_expression(*expr)?),
                     None => None,
                 };
-                Ok(Node::Raise(expr))
+                Ok(Node::Raise { exc, cause })
             }
             Stmt::Try(ast::StmtTry {
                 body,
@@ -508,6 +581,58 @@ impl<'a> Parser<'a> {
     /// Handles simple assignments (x = value), subscript assignments (dict[key] = value),
     /// attribute assignments (obj.attr = value), and tuple unpacking (a, b = value)
     fn parse_assignment(&mut self, lhs: AstExpr, rhs: AstExpr) -> Result<ParseNode, ParseError> {
+        let object = self.parse_expression(rhs)?;
+        self.parse_assignment_to(lhs, object)
+    }
+
+    /// Parses one target of a chained assignment like `a = b[0] = c.x = value`.
+    fn parse_assign_target(&mut self, lhs: AstExpr) -> Result<AssignTarget, ParseError> {
+        match lhs {
+            AstExpr::Subscript(ast::ExprSubscript {
+                value, slice, range, ..
+            }) => Ok(AssignTarget::Subscript {
+                target: self.parse_identifier(*value)?,
+                index: self.parse_expression(*slice)?,
+                target_position: self.convert_range(range),
+            }),
+            AstExpr::Attribute(ast::ExprAttribute { value, attr, range, .. }) => Ok(AssignTarget::Attr {
+                object: self.parse_expression(*value)?,
+                attr: EitherStr::Interned(self.interner.intern(attr.id())),
+                target_position: self.convert_range(range),
+            }),
+            AstExpr::Tuple(_) | AstExpr::List(_) => Ok(AssignTarget::Unpack(self.parse_unpack_target(lhs)?)),
+            _ => Ok(AssignTarget::Unpack(UnpackTarget::Name(self.parse_identifier(lhs)?))),
+        }
+    }
+
+    /// Parses one target of a `del` statement, flattening tuples and lists into `targets`.
+    fn parse_delete_target(&mut self, target: AstExpr, targets: &mut Vec<DeleteTarget>) -> Result<(), ParseError> {
+        match target {
+            AstExpr::Subscript(ast::ExprSubscript {
+                value, slice, range, ..
+            }) => targets.push(DeleteTarget::Subscript {
+                object: self.parse_expression(*value)?,
+                index: self.parse_expression(*slice)?,
+                target_position: self.convert_range(range),
+            }),
+            AstExpr::Attribute(ast::ExprAttribute { range, .. }) => {
+                return Err(ParseError::not_implemented(
+                    "deleting attributes",
+                    self.convert_range(range),
+                ));
+            }
+            AstExpr::Tuple(ast::ExprTuple { elts, .. }) | AstExpr::List(ast::ExprList { elts, .. }) => {
+                for elt in elts {
+                    self.parse_delete_target(elt, targets)?;
+                }
+            }
+            _ => targets.push(DeleteTarget::Name(self.parse_identifier(target)?)),
+        }
+        Ok(())
+    }
+
+    /// Parses an assignment of an already-parsed value to the target `lhs`.
+    fn parse_assignment_to(&mut self, lhs: AstExpr, object: ExprLoc) -> Result<ParseNode, ParseError> {
         match lhs {
             // Subscript assignment like dict[key] = value
             AstExpr::Subscript(ast::ExprSubscript {
@@ -515,7 +640,7 @@ impl<'a> Parser<'a> {
             }) => Ok(Node::SubscriptAssign {
                 target: self.parse_identifier(*value)?,
                 index: self.parse_expression(*slice)?,
-                value: self.parse_expression(rhs)?,
+                value: object,
                 target_position: self.convert_range(range),
             }),
             // Attribute assignment like obj.attr = value (supports chained like a.b.c = value)
@@ -523,7 +648,7 @@ impl<'a> Parser<'a> {
                 object: self.parse_expression(*value)?,
                 attr: EitherStr::Interned(self.interner.intern(attr.id())),
                 target_position: self.convert_range(range),
-                value: self.parse_expression(rhs)?,
+                value: object,
             }),
             // Tuple unpacking like a, b = value or (a, b), c = nested
             AstExpr::Tuple(ast::ExprTuple { elts, range, .. }) => {
@@ -535,7 +660,7 @@ impl<'a> Parser<'a> {
                 Ok(Node::UnpackAssign {
                     targets,
                     targets_position,
-                    object: self.parse_expression(rhs)?,
+                    object,
                 })
             }
             // List unpacking like [a, b] = value or [a, *rest] = value
@@ -548,13 +673,13 @@ impl<'a> Parser<'a> {
                 Ok(Node::UnpackAssign {
                     targets,
                     targets_position,
-                    object: self.parse_expression(rhs)?,
+                    object,
                 })
             }
             // Simple identifier assignment like x = value
             _ => Ok(Node::Assign {
                 target: self.parse_identifier(lhs)?,
-                object: self.parse_expression(rhs)?,
+                object,
             }),
         }
     }
@@ -822,32 +947,22 @@ impl<'a> Parser<'a> {
                 let position = self.convert_range(range);
                 let ast::Arguments { args, keywords, .. } = arguments;
                 let mut positional_args = Vec::new();
-                let mut var_args_expr: Option<ExprLoc> = None;
-                let mut seen_star = false;
+                // Once a `*iterable` appears, it and every later positional argument are kept
+                // in source order so the argument tuple can be built incrementally
+                let mut var_args = Vec::new();
 
                 for arg_expr in args.into_vec() {
                     match arg_expr {
                         AstExpr::Starred(ast::ExprStarred { value, .. }) => {
-                            if var_args_expr.is_some() {
-                                return Err(ParseError::not_implemented("multiple *args unpacking", position));
-                            }
-                            var_args_expr = Some(self.parse_expression(*value)?);
-                            seen_star = true;
-                        }
-                        other => {
-                            if seen_star {
-                                return Err(ParseError::not_implemented(
-                                    "positional arguments after *args unpacking",
-                                    position,
-                                ));
-                            }
-                            positional_args.push(self.parse_expression(other)?);
+                            var_args.push(StarredArg::Unpack(self.parse_expression(*value)?));
                         }
+                        other if var_args.is_empty() => positional_args.push(self.parse_expression(other)?),
+                        other => var_args.push(StarredArg::Value(self.parse_expression(other)?)),
                     }
                 }
                 // Separate regular kwargs (key=value) from var_kwargs (**expr)
                 let (kwargs, var_kwargs) = self.parse_keywords(keywords.into_vec())?;
-                let args = ArgExprs::new_with_var_kwargs(positional_args, var_args_expr, kwargs, var_kwargs);
+                let args = ArgExprs::new_with_var_kwargs(positional_args, var_args, kwargs, var_kwargs);
                 match *func {
                     AstExpr::Name(ast::ExprName { id, range, .. }) => {
                         // Always create Callable::Name — builtin resolution happens in
@@ -1015,10 +1130,10 @@ impl<'a> Parser<'a> {
     /// Parses keyword arguments, separating regular kwargs from var_kwargs (`**expr`).
     ///
     /// Returns `(kwargs, var_kwargs)` where kwargs is a vec of named keyword arguments
-    /// and var_kwargs is an optional expression for `**expr` unpacking.
-    fn parse_keywords(&mut self, keywords: Vec<Keyword>) -> Result<(Vec<Kwarg>, Option<ExprLoc>), ParseError> {
+    /// and var_kwargs holds every `**expr` unpacking in source order.
+    fn parse_keywords(&mut self, keywords: Vec<Keyword>) -> Result<(Vec<Kwarg>, Vec<ExprLoc>), ParseError> {
         let mut kwargs = Vec::new();
-        let mut var_kwargs = None;
+        let mut var_kwargs = Vec::new();
 
         for kwarg in keywords {
             if let Some(key) = kwarg.arg {
@@ -1028,13 +1143,7 @@ impl<'a> Parser<'a> {
                 kwargs.push(Kwarg { key, value });
             } else {
                 // Var kwargs: **expr
-                if var_kwargs.is_some() {
-                    return Err(ParseError::not_implemented(
-                        "multiple **kwargs unpacking",
-                        self.convert_range(kwarg.range),
-                    ));
-                }
-                var_kwargs = Some(self.parse_expression(kwarg.value)?);
+                var_kwargs.push(self.parse_expression(kwarg.value)?);
             }
         }
 
@@ -1152,6 +1261,175 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses a class definition, which is only supported for user-defined exception classes.
+    ///
+    /// The class must have exactly one base and an empty body (`pass`, a docstring or `...`).
+    /// The class is registered with the interner and the statement becomes an assignment of
+    /// the class object to its name. The base is evaluated when the statement runs, and must
+    /// then be a builtin exception or a user-defined exception class.
+    fn parse_exception_class(&mut self, class: ast::StmtClassDef) -> Result<ParseNode, ParseError> {
+        let position = self.convert_range(class.range);
+        if !class.decorator_list.is_empty() || class.type_params.is_some() {
+            return Err(ParseError::not_implemented("class definitions", position));
+        }
+        let base = match class.arguments.map(|arguments| *arguments) {
+            Some(ast::Arguments { args, keywords, .. }) if keywords.is_empty() && args.len() == 1 => {
+                args.into_vec().pop().expect("class has one base")
+            }
+            _ => return Err(ParseError::not_implemented("class definitions", position)),
+        };
+        let empty_body = class.body.iter().all(|stmt| match stmt {
+            Stmt::Pass(_) => true,
+            Stmt::Expr(ast::StmtExpr { value, .. }) => {
+                matches!(**value, AstExpr::StringLiteral(_) | AstExpr::EllipsisLiteral(_))
+            }
+            _ => false,
+        });
+        if !empty_body {
+            return Err(ParseError::not_implemented(
+                "class bodies other than `pass`, a docstring or `...`",
+                position,
+            ));
+        }
+
+        let base = self.parse_expression(base)?;
+        let target = self.identifier(&class.name.id, class.name.range);
+        let class_id = self.interner.add_exc_class(ExcClassDef { name: target.name_id });
+        Ok(Node::Assign {
+            target,
+            object: ExprLoc {
+                position,
+                expr: Expr::ExcClass {
+                    class_id,
+                    base: Box::new(base),
+                },
+            },
+        })
+    }
+
+    /// Parses one item of a `with` statement, lowering it to the equivalent
+    /// try/except/finally nodes as described in PEP 343:
+    ///
+    /// ```text
+    /// .with_manager = EXPR
+    /// VAR = .with_manager.__enter__()
+    /// .with_ok = True
+    /// try:
+    ///     try:
+    ///         BODY
+    ///     except:  # bound to .with_exc
+    ///         .with_ok = False
+    ///         if not .with_manager.__exit__(type(.with_exc), .with_exc, None):
+    ///             raise
+    /// finally:
+    ///     if .with_ok:
+    ///         .with_manager.__exit__(None, None, None)
+    /// ```
+    ///
+    /// The hidden names start with `.` so they can't clash with Python identifiers, and are
+    /// suffixed with the `with` nesting depth. Since the result is ordinary bytecode, an
+    /// external call in the body can suspend and resume with `__exit__` still pending.
+    /// Further items (`with a, b:`) become a nested `with` wrapping `BODY`.
+    fn parse_with_item(
+        &mut self,
+        item: ast::WithItem,
+        rest: &mut std::vec::IntoIter<ast::WithItem>,
+        body: Vec<Stmt>,
+        nodes: &mut Vec<ParseNode>,
+    ) -> Result<(), ParseError> {
+        let position = self.convert_range(item.context_expr.range());
+        let context = self.parse_expression(item.context_expr)?;
+
+        let depth = self.with_depth;
+        let manager = Identifier::new(self.interner.intern(&format!(".with_manager{depth}")), position);
+        let ok = Identifier::new(self.interner.intern(&format!(".with_ok{depth}")), position);
+        let exc = Identifier::new(self.interner.intern(&format!(".with_exc{depth}")), position);
+        let expr = |expr| ExprLoc::new(position, expr);
+        let call_manager = |method: StaticStrings, args| {
+            expr(Expr::AttrCall {
+                object: Box::new(expr(Expr::Name(manager))),
+                attr: EitherStr::Interned(method.into()),
+                args: Box::new(args),
+            })
+        };
+
+        nodes.push(Node::Assign {
+            target: manager,
+            object: context,
+        });
+        let enter = call_manager(StaticStrings::DunderEnter, ArgExprs::Empty);
+        nodes.push(match item.optional_vars {
+            Some(target) => self.parse_assignment_to(*target, enter)?,
+            None => Node::Expr(enter),
+        });
+        nodes.push(Node::Assign {
+            target: ok,
+            object: expr(Expr::Literal(Literal::Bool(true))),
+        });
+
+        self.with_depth += 1;
+        let body = match rest.next() {
+            Some(next) => {
+                let mut inner = Vec::new();
+                self.parse_with_item(next, rest, body, &mut inner).map(|()| inner)
+            }
+            None => self.parse_statements(body),
+        };
+        self.with_depth -= 1;
+        let body = body?;
+
+        let exit_with_exc = call_manager(
+            StaticStrings::DunderExit,
+            ArgExprs::Args(vec![
+                expr(Expr::Call {
+                    callable: Callable::Builtin(Builtins::Function(BuiltinsFunctions::Type)),
+                    args: Box::new(ArgExprs::One(expr(Expr::Name(exc)))),
+                }),
+                expr(Expr::Name(exc)),
+                expr(Expr::Literal(Literal::None)),
+            ]),
+        );
+        let handler = ExceptHandler {
+            exc_type: None,
+            name: Some(exc),
+            body: vec![
+                Node::Assign {
+                    target: ok,
+                    object: expr(Expr::Literal(Literal::Bool(false))),
+                },
+                Node::If {
+                    test: expr(Expr::Not(Box::new(exit_with_exc))),
+                    body: vec![Node::Raise { exc: None, cause: None }],
+                    or_else: Vec::new(),
+                },
+            ],
+        };
+        let exit_without_exc = call_manager(
+            StaticStrings::DunderExit,
+            ArgExprs::Args(vec![
+                expr(Expr::Literal(Literal::None)),
+                expr(Expr::Literal(Literal::None)),
+                expr(Expr::Literal(Literal::None)),
+            ]),
+        );
+        nodes.push(Node::Try(Try {
+            body: vec![Node::Try(Try {
+                body,
+                handlers: vec![handler],
+                or_else: Vec::new(),
+                finally: Vec::new(),
+            })],
+            handlers: Vec::new(),
+            or_else: Vec::new(),
+            finally: vec![Node::If {
+                test: expr(Expr::Name(ok)),
+                body: vec![Node::Expr(exit_without_exc)],
+                or_else: Vec::new(),
+            }],
+        }));
+        Ok(())
+    }
+
     fn identifier(&mut self, id: &Name, range: TextRange) -> Identifier {
         let string_id = self.interner.intern(id);
         Identifier::new(string_id, self.convert_range(range))
@@ -1206,6 +1484,19 @@ impl<'a> Parser<'a> {
             .collect()
     }
 
+    /// Appends a part to a top-level f-string, merging it into the previous part when both
+    /// are literals so implicit concatenation like `'a' f'b{x}'` builds one fewer string at runtime.
+    fn push_fstring_part(&mut self, parts: &mut Vec<FStringPart>, part: FStringPart) {
+        if let FStringPart::Literal(next) = part
+            && let Some(FStringPart::Literal(prev)) = parts.last_mut()
+        {
+            let merged = format!("{}{}", self.interner.get_str(*prev), self.interner.get_str(next));
+            *prev = self.interner.intern(&merged);
+        } else {
+            parts.push(part);
+        }
+    }
+
     /// Parses an f-string value into expression parts.
     ///
     /// F-strings in ruff AST are represented as `FStringValue` containing
@@ -1221,14 +1512,14 @@ impl<'a> Parser<'a> {
                     let processed = lit.value.to_string();
                     if !processed.is_empty() {
                         let string_id = self.interner.intern(&processed);
-                        parts.push(FStringPart::Literal(string_id));
+                        self.push_fstring_part(&mut parts, FStringPart::Literal(string_id));
                     }
                 }
                 ast::FStringPart::FString(fstring) => {
                     // Interpolated f-string section
                     for element in &fstring.elements {
                         let part = self.parse_fstring_element(element)?;
-                        parts.push(part);
+                        self.push_fstring_part(&mut parts, part);
                     }
                 }
             }
@@ -1325,13 +1616,18 @@ impl<'a> Parser<'a> {
                     }
                 })
                 .collect();
-            let parsed = static_spec.parse().map_err(|spec_str| {
-                ParseError::syntax(
+            match static_spec.parse::<ParsedFormatSpec>() {
+                Ok(parsed) if parsed.is_encodable() => Ok(FormatSpec::Static(parsed)),
+                Err(FormatSpecError::Invalid(spec_str)) => Err(ParseError::syntax(
                     format!("Invalid format specifier '{spec_str}'"),
                     self.convert_range(spec.range),
-                )
-            })?;
-            Ok(FormatSpec::Static(parsed))
+                )),
+                // Specs too large to encode are parsed at runtime, which is also where CPython
+                // raises for a width or precision with too many digits
+                Ok(_) | Err(FormatSpecError::TooManyDigits) => Ok(FormatSpec::Dynamic(vec![FStringPart::Literal(
+                    self.interner.intern(&static_spec),
+                )])),
+            }
         }
     }
 
@@ -1580,6 +1876,21 @@ impl ParseError {
             ),
         }
     }
+
+    /// Converts this error into an exception raised at runtime, for code compiled by
+    /// `eval()` or `exec()`.
+    pub(crate) fn into_run_error(self) -> RunError {
+        let (exc_type, msg) = match self {
+            Self::Syntax { msg, .. } => (ExcType::SyntaxError, msg.into_owned()),
+            Self::NotImplemented { msg, .. } => (
+                ExcType::NotImplementedError,
+                format!("The monty syntax parser does not yet support {msg}"),
+            ),
+            Self::NotSupported { msg, .. } => (ExcType::NotImplementedError, msg.into_owned()),
+            Self::Import { msg, .. } => (ExcType::ImportError, msg.into_owned()),
+        };
+        SimpleException::new_msg(exc_type, msg).into()
+    }
 }
 
 /// Parses an integer literal string into a `BigInt`, handling radix prefixes and underscores.
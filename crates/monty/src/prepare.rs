@@ -6,13 +6,15 @@ use crate::{
     args::ArgExprs,
     builtins::Builtins,
     expressions::{
-        Callable, CmpOperator, Comprehension, Expr, ExprLoc, Identifier, Literal, NameScope, Node, Operator,
-        PreparedFunctionDef, PreparedNode, UnpackTarget,
+        AssignTarget, Callable, CmpOperator, Comprehension, DeleteTarget, Expr, ExprLoc, Identifier, Literal,
+        NameScope, Node, Operator, PreparedFunctionDef, PreparedNode, UnpackTarget,
     },
     fstring::{FStringPart, FormatSpec},
-    intern::{InternerBuilder, StringId},
+    intern::{InternerBuilder, StaticStrings, StringId},
     namespace::NamespaceId,
-    parse::{CodeRange, ExceptHandler, ParseError, ParseNode, ParseResult, ParsedSignature, RawFunctionDef, Try},
+    parse::{
+        CodeRange, ExceptHandler, ParseError, ParseNode, ParseResult, ParsedParam, ParsedSignature, RawFunctionDef, Try,
+    },
     signature::Signature,
 };
 
@@ -31,12 +33,55 @@ pub struct PrepareResult {
     /// This map is used by:
     /// - ref-count tests for looking up variables by name
     /// - REPL incremental compilation to preserve stable global slot IDs across snippets
+    /// - `slot_names()`, which keeps the names of global slots for `globals()` and `dir()`
     pub name_map: AHashMap<String, NamespaceId>,
     /// The prepared AST nodes with all names resolved to namespace indices.
     /// Function definitions are inline as `PreparedFunctionDef` variants.
     pub nodes: Vec<PreparedNode>,
     /// The string interner containing all interned identifiers and filenames.
     pub interner: InternerBuilder,
+    /// Reads of names that aren't bound anywhere in the code, in source order.
+    pub undefined_names: Vec<UndefinedName>,
+    /// Module-level slots the code refers to, including from functions.
+    pub used_globals: AHashSet<NamespaceId>,
+}
+
+/// A read of a name that isn't bound in any scope, found while resolving names.
+///
+/// Running the code raises `NameError` if the read is reached. Only names that are never
+/// bound are reported at module level, since a read before the name is bound may not run.
+#[derive(Debug, Clone, Copy)]
+pub struct UndefinedName {
+    /// The name and where it's read.
+    pub name: Identifier,
+    /// Whether the name is called, e.g. an external function that wasn't registered.
+    pub is_call: bool,
+    /// Whether the name is read at module level, where it may be bound later on.
+    at_module: bool,
+}
+
+impl PrepareResult {
+    /// Returns the interned name of each namespace slot, indexed by slot.
+    ///
+    /// Slots without a name in `name_map`, e.g. comprehension variables, and slots holding
+    /// hidden names like the `with` statement's state get the default (empty) `StringId`.
+    pub fn slot_names(&mut self) -> Vec<StringId> {
+        let mut names = vec![StringId::default(); self.namespace_size];
+        for (name, slot) in &self.name_map {
+            if !is_hidden_name(name) {
+                names[slot.index()] = self.interner.intern(name);
+            }
+        }
+        names
+    }
+}
+
+/// Returns whether `name` is a hidden variable added by the parser, such as `.with_manager0`.
+///
+/// Hidden names start with `.` so they can't clash with Python identifiers, and are left out
+/// of the slot names so they don't show up in `globals()`, `locals()` or `dir()`.
+fn is_hidden_name(name: &str) -> bool {
+    name.starts_with('.')
 }
 
 /// Prepares parsed nodes for compilation by resolving names and building the initial namespace.
@@ -63,11 +108,34 @@ pub(crate) fn prepare(
         prepared_nodes.push(Node::Return(new_expr_loc));
     }
 
+    let undefined_names = p.undefined_names();
     Ok(PrepareResult {
         namespace_size: p.namespace_size,
         name_map: p.name_map,
         nodes: prepared_nodes,
         interner,
+        undefined_names,
+        used_globals: p.used_globals,
+    })
+}
+
+/// Prepares parsed nodes of an imported pure-Python module.
+///
+/// Unlike the main module, there are no inputs or external functions to pre-register, and
+/// the last expression is not returned: the module body ends by building the module object.
+pub(crate) fn prepare_python_module(parse_result: ParseResult) -> Result<PrepareResult, ParseError> {
+    let ParseResult { nodes, interner } = parse_result;
+    let mut p = Prepare::new_module(Vec::new(), &[], &interner);
+    let prepared_nodes = p.prepare_nodes(nodes)?;
+
+    let undefined_names = p.undefined_names();
+    Ok(PrepareResult {
+        namespace_size: p.namespace_size,
+        name_map: p.name_map,
+        nodes: prepared_nodes,
+        interner,
+        undefined_names,
+        used_globals: p.used_globals,
     })
 }
 
@@ -92,14 +160,76 @@ pub(crate) fn prepare_with_existing_names(
         prepared_nodes.push(Node::Return(new_expr_loc));
     }
 
+    let undefined_names = p.undefined_names();
+    Ok(PrepareResult {
+        namespace_size: p.namespace_size,
+        name_map: p.name_map,
+        nodes: prepared_nodes,
+        interner,
+        undefined_names,
+        used_globals: p.used_globals,
+    })
+}
+
+/// Prepares code run by `exec()` or `eval()` in the main module's global namespace.
+///
+/// Like `prepare_with_existing_names`, existing globals keep their slots and new names are
+/// appended, but after `namespace_size` since the global namespace may end with unnamed
+/// slots. Code compiled for `eval()` already ends with an explicit return.
+pub(crate) fn prepare_dynamic_module(
+    parse_result: ParseResult,
+    name_map: AHashMap<String, NamespaceId>,
+    namespace_size: usize,
+) -> Result<PrepareResult, ParseError> {
+    let ParseResult { nodes, interner } = parse_result;
+    let mut p = Prepare::new_module_with_name_map(name_map, &interner);
+    p.namespace_size = namespace_size;
+    // globals with a slot were assigned by earlier code, so they shadow builtins
+    p.names_assigned_in_order = p.name_map.keys().cloned().collect();
+    let prepared_nodes = p.prepare_nodes(nodes)?;
+
+    let undefined_names = p.undefined_names();
     Ok(PrepareResult {
         namespace_size: p.namespace_size,
         name_map: p.name_map,
         nodes: prepared_nodes,
         interner,
+        undefined_names,
+        used_globals: p.used_globals,
     })
 }
 
+/// Prepares code run by `exec()` or `eval()` anywhere else, as the body of a function named
+/// `<module>` taking the variables visible to the code as parameters.
+///
+/// `global_name_map` maps the names of the globals the code can use to their slots.
+pub(crate) fn prepare_dynamic_function(
+    parse_result: ParseResult,
+    params: Vec<StringId>,
+    global_name_map: AHashMap<String, NamespaceId>,
+) -> Result<(PreparedFunctionDef, InternerBuilder), ParseError> {
+    let ParseResult { nodes, interner } = parse_result;
+    let signature = ParsedSignature {
+        args: params
+            .into_iter()
+            .map(|name| ParsedParam { name, default: None })
+            .collect(),
+        ..ParsedSignature::default()
+    };
+    let name = Identifier::new(StaticStrings::Module.into(), CodeRange::default());
+    let node = Prepare::new_module_with_name_map(global_name_map, &interner).prepare_function_def(
+        name,
+        &signature,
+        nodes,
+        false,
+        Vec::new(),
+    )?;
+    let Node::FunctionDef(func_def) = node else {
+        unreachable!("prepare_function_def returns a function definition")
+    };
+    Ok((func_def, interner))
+}
+
 /// State machine for the preparation phase that transforms parsed AST nodes into a prepared form.
 ///
 /// This struct maintains the mapping between variable names and their namespace indices,
@@ -119,9 +249,15 @@ struct Prepare<'i> {
     name_map: AHashMap<String, NamespaceId>,
     /// Number of items in the namespace
     pub namespace_size: usize,
+    /// Number of function parameters, which occupy the first slots of the namespace.
+    /// Zero at module level.
+    param_count: usize,
     /// Whether this is the module-level scope.
     /// At module level, all variables are global and `global` keyword is a no-op.
     is_module_scope: bool,
+    /// Whether we are preparing the shadowed part of a comprehension (after its first iterable).
+    /// Comprehension loop variables are true locals even at module level.
+    in_comprehension: bool,
     /// Names declared as `global` in this scope.
     /// These names will resolve to the global namespace instead of local.
     global_names: AHashSet<String>,
@@ -148,6 +284,16 @@ struct Prepare<'i> {
     /// that are both nonlocal and captured by nested functions), then extended as new
     /// captures are discovered during nested function preparation.
     cell_var_map: AHashMap<String, NamespaceId>,
+    /// Reads of names that aren't bound in any scope, including those of nested functions
+    /// once they're prepared. Module-level reads are filtered by `undefined_names()`.
+    undefined_names: Vec<UndefinedName>,
+    /// Module-level slots referred to, including by nested functions.
+    used_globals: AHashSet<NamespaceId>,
+    /// Names assigned by functions declaring them `global`, including nested functions.
+    global_assignments: AHashSet<String>,
+    /// Number of leading module-level slots bound before the code runs, i.e. external
+    /// functions and inputs, or existing globals. Zero in functions.
+    bound_slots: usize,
 }
 
 impl<'i> Prepare<'i> {
@@ -173,7 +319,9 @@ impl<'i> Prepare<'i> {
             interner,
             name_map,
             namespace_size,
+            param_count: 0,
             is_module_scope: true,
+            in_comprehension: false,
             global_names: AHashSet::new(),
             assigned_names: AHashSet::new(),
             names_assigned_in_order: AHashSet::new(),
@@ -181,6 +329,10 @@ impl<'i> Prepare<'i> {
             enclosing_locals: None,
             free_var_map: AHashMap::new(),
             cell_var_map: AHashMap::new(),
+            undefined_names: Vec::new(),
+            used_globals: AHashSet::new(),
+            global_assignments: AHashSet::new(),
+            bound_slots: namespace_size,
         }
     }
 
@@ -198,7 +350,9 @@ impl<'i> Prepare<'i> {
             interner,
             name_map,
             namespace_size,
+            param_count: 0,
             is_module_scope: true,
+            in_comprehension: false,
             global_names: AHashSet::new(),
             assigned_names: AHashSet::new(),
             names_assigned_in_order: AHashSet::new(),
@@ -206,6 +360,10 @@ impl<'i> Prepare<'i> {
             enclosing_locals: None,
             free_var_map: AHashMap::new(),
             cell_var_map: AHashMap::new(),
+            undefined_names: Vec::new(),
+            used_globals: AHashSet::new(),
+            global_assignments: AHashSet::new(),
+            bound_slots: namespace_size,
         }
     }
 
@@ -284,7 +442,9 @@ impl<'i> Prepare<'i> {
             interner,
             name_map,
             namespace_size,
+            param_count: params.len(),
             is_module_scope: false,
+            in_comprehension: false,
             global_names,
             assigned_names,
             names_assigned_in_order: AHashSet::new(),
@@ -292,9 +452,75 @@ impl<'i> Prepare<'i> {
             enclosing_locals,
             free_var_map,
             cell_var_map,
+            undefined_names: Vec::new(),
+            used_globals: AHashSet::new(),
+            global_assignments: AHashSet::new(),
+            bound_slots: 0,
         }
     }
 
+    /// Returns the interned name of each slot of the function's namespace, indexed by slot.
+    ///
+    /// Covers parameters, locals, cell variables and captured variables. Slots without a
+    /// name, e.g. comprehension variables, and hidden names get the default `StringId`.
+    fn slot_names(&self) -> Vec<StringId> {
+        let mut names = vec![StringId::default(); self.namespace_size];
+        let maps = [&self.name_map, &self.cell_var_map, &self.free_var_map];
+        for (name, slot) in maps.into_iter().flatten() {
+            // names declared `global` may have a placeholder slot that never holds a value
+            if self.global_names.contains(name) || is_hidden_name(name) {
+                continue;
+            }
+            if let (Some(slot_name), Some(id)) = (names.get_mut(slot.index()), self.interner.lookup(name)) {
+                *slot_name = id;
+            }
+        }
+        names
+    }
+
+    /// Returns whether `name` is a parameter of the function being prepared.
+    ///
+    /// Parameters are registered first, so they occupy the lowest namespace slots.
+    fn is_param(&self, name: &str) -> bool {
+        self.name_map.get(name).is_some_and(|id| id.index() < self.param_count)
+    }
+
+    /// Returns the reads of names that aren't bound anywhere, in source order.
+    ///
+    /// Module-level reads are only kept if the name is never bound: not before the code
+    /// runs, not at module level, and not by a function declaring it `global`.
+    fn undefined_names(&mut self) -> Vec<UndefinedName> {
+        let mut undefined_names = std::mem::take(&mut self.undefined_names);
+        undefined_names.retain(|undefined| {
+            if !undefined.at_module {
+                return true;
+            }
+            let name_str = self.interner.get_str(undefined.name.name_id);
+            undefined.name.namespace_id().index() >= self.bound_slots
+                && !self.names_assigned_in_order.contains(name_str)
+                && !self.global_assignments.contains(name_str)
+        });
+        undefined_names.sort_by_key(|undefined| {
+            let start = undefined.name.position.start();
+            (start.line, start.column)
+        });
+        undefined_names
+    }
+
+    /// Takes what a nested function's preparer found about names, see `undefined_names`.
+    fn absorb_name_usage(&mut self, inner: &mut Self) {
+        self.undefined_names.append(&mut inner.undefined_names);
+        self.used_globals.extend(inner.used_globals.drain());
+        self.global_assignments.extend(inner.global_assignments.drain());
+        self.global_assignments.extend(
+            inner
+                .global_names
+                .iter()
+                .filter(|name| inner.assigned_names.contains(*name))
+                .cloned(),
+        );
+    }
+
     /// Recursively prepares a sequence of AST nodes by resolving names and transforming expressions.
     ///
     /// This method processes each node type differently:
@@ -314,8 +540,8 @@ impl<'i> Prepare<'i> {
                 Node::Expr(expr) => new_nodes.push(Node::Expr(self.prepare_expression(expr)?)),
                 Node::Return(expr) => new_nodes.push(Node::Return(self.prepare_expression(expr)?)),
                 Node::ReturnNone => new_nodes.push(Node::ReturnNone),
-                Node::Raise(exc) => {
-                    let expr = match exc {
+                Node::Raise { exc, cause } => {
+                    let exc = match exc {
                         Some(expr) => {
                             let prepared = self.prepare_expression(expr)?;
                             match prepared.expr {
@@ -334,7 +560,11 @@ impl<'i> Prepare<'i> {
                         }
                         None => None,
                     };
-                    new_nodes.push(Node::Raise(expr));
+                    let cause = match cause {
+                        Some(expr) => Some(self.prepare_expression(expr)?),
+                        None => None,
+                    };
+                    new_nodes.push(Node::Raise { exc, cause });
                 }
                 Node::Assert { test, msg } => {
                     let test = self.prepare_expression(test)?;
@@ -369,6 +599,21 @@ impl<'i> Prepare<'i> {
                         object,
                     });
                 }
+                Node::ChainAssign { targets, object } => {
+                    let object = self.prepare_expression(object)?;
+                    let targets = targets
+                        .into_iter()
+                        .map(|target| self.prepare_assign_target(target))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    new_nodes.push(Node::ChainAssign { targets, object });
+                }
+                Node::Delete { targets } => {
+                    let targets = targets
+                        .into_iter()
+                        .map(|target| self.prepare_delete_target(target))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    new_nodes.push(Node::Delete { targets });
+                }
                 Node::OpAssign { target, op, object } => {
                     // Track that this name was assigned
                     self.names_assigned_in_order
@@ -449,8 +694,9 @@ impl<'i> Prepare<'i> {
                     signature,
                     body,
                     is_async,
+                    decorators,
                 }) => {
-                    let func_node = self.prepare_function_def(name, &signature, body, is_async)?;
+                    let func_node = self.prepare_function_def(name, &signature, body, is_async, decorators)?;
                     new_nodes.push(func_node);
                 }
                 Node::Global { names, position } => {
@@ -462,7 +708,12 @@ impl<'i> Prepare<'i> {
                         // Validate that names weren't already used/assigned before `global` declaration
                         for string_id in names {
                             let name_str = self.interner.get_str(string_id);
-                            if self.names_assigned_in_order.contains(name_str) {
+                            if self.is_param(name_str) {
+                                return Err(ParseError::syntax(
+                                    format!("name '{name_str}' is parameter and global"),
+                                    position,
+                                ));
+                            } else if self.names_assigned_in_order.contains(name_str) {
                                 // Name was assigned before the global declaration
                                 return Err(ParseError::syntax(
                                     format!("name '{name_str}' is assigned to before global declaration"),
@@ -491,7 +742,19 @@ impl<'i> Prepare<'i> {
                     // and that the binding exists in an enclosing scope
                     for string_id in names {
                         let name_str = self.interner.get_str(string_id);
-                        if self.names_assigned_in_order.contains(name_str) {
+                        if self.is_param(name_str) {
+                            return Err(ParseError::syntax(
+                                format!("name '{name_str}' is parameter and nonlocal"),
+                                position,
+                            ));
+                        } else if self.global_names.contains(name_str) {
+                            // Global names are collected for the whole function in the first pass,
+                            // so this catches the conflict regardless of declaration order
+                            return Err(ParseError::syntax(
+                                format!("name '{name_str}' is nonlocal and global"),
+                                position,
+                            ));
+                        } else if self.names_assigned_in_order.contains(name_str) {
                             // Name was assigned before the nonlocal declaration
                             return Err(ParseError::syntax(
                                 format!("name '{name_str}' is assigned to before nonlocal declaration"),
@@ -617,7 +880,7 @@ impl<'i> Prepare<'i> {
         let expr = match expr {
             Expr::Literal(object) => Expr::Literal(object),
             Expr::Builtin(callable) => Expr::Builtin(callable),
-            Expr::Name(name) => self.resolve_name_or_builtin(name),
+            Expr::Name(name) => self.resolve_name_or_builtin(name, false),
             Expr::Op { left, op, right } => Expr::Op {
                 left: Box::new(self.prepare_expression(*left)?),
                 op,
@@ -641,7 +904,7 @@ impl<'i> Prepare<'i> {
                 // For Name callables, resolve the identifier in the namespace
                 // Don't error here if undefined - let runtime raise NameError with proper traceback
                 let callable = match callable {
-                    Callable::Name(ident) => match self.resolve_name_or_builtin(ident) {
+                    Callable::Name(ident) => match self.resolve_name_or_builtin(ident, true) {
                         Expr::Builtin(b) => Callable::Builtin(b),
                         Expr::Name(resolved) => Callable::Name(resolved),
                         _ => unreachable!("resolve_name_or_builtin returns Name or Builtin"),
@@ -767,6 +1030,10 @@ impl<'i> Prepare<'i> {
                 }
             }
             Expr::Await(value) => Expr::Await(Box::new(self.prepare_expression(*value)?)),
+            Expr::ExcClass { class_id, base } => Expr::ExcClass {
+                class_id,
+                base: Box::new(self.prepare_expression(*base)?),
+            },
         };
 
         // Optimization: Transform `(x % n) == value` with any constant right-hand side into a
@@ -807,7 +1074,10 @@ impl<'i> Prepare<'i> {
     /// We check before calling `get_id` to avoid allocating unnecessary namespace slots.
     /// At module level, a slot allocated for an unassigned builtin would leak into
     /// `global_name_map` for nested functions, causing incorrect resolution.
-    fn resolve_name_or_builtin(&mut self, name: Identifier) -> Expr {
+    ///
+    /// Reads of names that aren't bound are recorded as `UndefinedName`s, `is_call` saying
+    /// whether the name is called.
+    fn resolve_name_or_builtin(&mut self, name: Identifier, is_call: bool) -> Expr {
         let name_str = self.interner.get_str(name.name_id);
 
         // Check if the name is assigned in the current scope. If so, it shadows
@@ -838,7 +1108,16 @@ impl<'i> Prepare<'i> {
             }
         }
 
-        Expr::Name(self.get_id(name).0)
+        let name = self.get_id(name).0;
+        // At module level the name may still be bound later on, see `undefined_names()`
+        if name.scope == NameScope::LocalUnassigned && !(self.is_module_scope && is_locally_assigned) {
+            self.undefined_names.push(UndefinedName {
+                name,
+                is_call,
+                at_module: self.is_module_scope,
+            });
+        }
+        Expr::Name(name)
     }
 
     /// Prepares a comprehension with scope isolation for loop variables.
@@ -902,6 +1181,7 @@ impl<'i> Prepare<'i> {
             .next()
             .expect("comprehension must have at least one generator");
         let first_iter = self.prepare_expression(first_gen.iter)?;
+        let saved_in_comprehension = std::mem::replace(&mut self.in_comprehension, true);
 
         // Step 2: Collect and shadow ALL loop variable names from ALL generators.
         // This must happen BEFORE evaluating any subsequent generator's iter expression.
@@ -967,10 +1247,61 @@ impl<'i> Prepare<'i> {
         self.free_var_map = saved_free_var_map;
         self.cell_var_map = saved_cell_var_map;
         self.enclosing_locals = saved_enclosing_locals;
+        self.in_comprehension = saved_in_comprehension;
 
         Ok((prepared_generators, prepared_elt, prepared_key_value))
     }
 
+    /// Prepares one target of a chained assignment, resolving its names and expressions.
+    fn prepare_assign_target(&mut self, target: AssignTarget) -> Result<AssignTarget, ParseError> {
+        Ok(match target {
+            AssignTarget::Unpack(target) => AssignTarget::Unpack(self.prepare_unpack_target(target)),
+            AssignTarget::Subscript {
+                target,
+                index,
+                target_position,
+            } => AssignTarget::Subscript {
+                // Subscript assignment doesn't assign to the target itself, just modifies it
+                target: self.get_id(target).0,
+                index: self.prepare_expression(index)?,
+                target_position,
+            },
+            AssignTarget::Attr {
+                object,
+                attr,
+                target_position,
+            } => AssignTarget::Attr {
+                object: self.prepare_expression(object)?,
+                attr,
+                target_position,
+            },
+        })
+    }
+
+    /// Prepares one target of a `del` statement, resolving its names and expressions.
+    fn prepare_delete_target(&mut self, target: DeleteTarget) -> Result<DeleteTarget, ParseError> {
+        Ok(match target {
+            DeleteTarget::Name(target) => {
+                // In functions, `del x` binds `x` like an assignment does. At module level a name
+                // that was never assigned stays unassigned so deleting it raises NameError.
+                if !self.is_module_scope {
+                    self.names_assigned_in_order
+                        .insert(self.interner.get_str(target.name_id).to_string());
+                }
+                DeleteTarget::Name(self.get_id(target).0)
+            }
+            DeleteTarget::Subscript {
+                object,
+                index,
+                target_position,
+            } => DeleteTarget::Subscript {
+                object: self.prepare_expression(object)?,
+                index: self.prepare_expression(index)?,
+                target_position,
+            },
+        })
+    }
+
     /// Prepares an unpack target by resolving identifiers recursively.
     ///
     /// Handles both single identifiers and nested tuples like `(a, b), c`.
@@ -1146,7 +1477,14 @@ impl<'i> Prepare<'i> {
         parsed_sig: &ParsedSignature,
         body: Vec<ParseNode>,
         is_async: bool,
+        decorators: Vec<ExprLoc>,
     ) -> Result<PreparedNode, ParseError> {
+        // Decorators are evaluated in the enclosing scope, before the name is bound
+        let decorators = decorators
+            .into_iter()
+            .map(|decorator| self.prepare_expression(decorator))
+            .collect::<Result<Vec<_>, _>>()?;
+
         // Register the function name in the current scope
         let (name, _) = self.get_id(name);
 
@@ -1205,6 +1543,8 @@ impl<'i> Prepare<'i> {
 
         // Prepare the function body
         let prepared_body = inner_prepare.prepare_nodes(body)?;
+        let slot_names = inner_prepare.slot_names();
+        self.absorb_name_usage(&mut inner_prepare);
 
         // Mark variables that the inner function captures as our cell_vars
         // These are the names that appear in inner_prepare.free_var_map
@@ -1331,11 +1671,13 @@ impl<'i> Prepare<'i> {
             signature,
             body: prepared_body,
             namespace_size,
+            slot_names,
             free_var_enclosing_slots,
             cell_var_count,
             cell_param_indices,
             default_exprs,
             is_async,
+            decorators,
         }))
     }
 
@@ -1419,6 +1761,8 @@ impl<'i> Prepare<'i> {
 
         // Prepare the lambda body
         let prepared_body = inner_prepare.prepare_nodes(body_nodes)?;
+        let slot_names = inner_prepare.slot_names();
+        self.absorb_name_usage(&mut inner_prepare);
 
         // Mark variables that the inner function captures as our cell_vars
         for captured_name in inner_prepare.free_var_map.keys() {
@@ -1528,11 +1872,13 @@ impl<'i> Prepare<'i> {
             signature,
             body: prepared_body,
             namespace_size,
+            slot_names,
             free_var_enclosing_slots,
             cell_var_count,
             cell_param_indices,
             default_exprs,
             is_async: false,
+            decorators: Vec::new(),
         };
 
         Ok(ExprLoc::new(
@@ -1567,9 +1913,17 @@ impl<'i> Prepare<'i> {
         if self.is_module_scope {
             return match self.name_map.entry(name_str.to_string()) {
                 Entry::Occupied(e) => {
-                    // Name already exists (from prior assignment or pre-registered)
+                    self.used_globals.insert(*e.get());
+                    // Name already exists (from prior assignment or pre-registered).
+                    // Module-level names raise NameError (not UnboundLocalError) when unbound,
+                    // e.g. after `del x`; only comprehension variables are true locals.
+                    let scope = if self.in_comprehension {
+                        NameScope::Local
+                    } else {
+                        NameScope::LocalUnassigned
+                    };
                     (
-                        Identifier::new_with_scope(ident.name_id, ident.position, *e.get(), NameScope::Local),
+                        Identifier::new_with_scope(ident.name_id, ident.position, *e.get(), scope),
                         false,
                     )
                 }
@@ -1601,6 +1955,7 @@ impl<'i> Prepare<'i> {
                 && let Some(&global_id) = global_map.get(name_str)
             {
                 // Name exists in global namespace
+                self.used_globals.insert(global_id);
                 return (
                     Identifier::new_with_scope(ident.name_id, ident.position, global_id, NameScope::Global),
                     false,
@@ -1704,6 +2059,7 @@ impl<'i> Prepare<'i> {
         if let Some(ref global_map) = self.global_name_map
             && let Some(&global_id) = global_map.get(name_str)
         {
+            self.used_globals.insert(global_id);
             return (
                 Identifier::new_with_scope(ident.name_id, ident.position, global_id, NameScope::Global),
                 false,
@@ -1898,11 +2254,41 @@ fn collect_scope_info_from_node(
             // Scan value expression for walrus operators
             collect_assigned_names_from_expr(object, assigned_names, interner);
         }
+        Node::ChainAssign { targets, object } => {
+            for target in targets {
+                match target {
+                    AssignTarget::Unpack(target) => collect_names_from_unpack_target(target, assigned_names, interner),
+                    // Subscript and attribute targets modify existing objects, but may contain walrus operators
+                    AssignTarget::Subscript { index, .. } => {
+                        collect_assigned_names_from_expr(index, assigned_names, interner);
+                    }
+                    AssignTarget::Attr { object, .. } => {
+                        collect_assigned_names_from_expr(object, assigned_names, interner);
+                    }
+                }
+            }
+            // Scan value expression for walrus operators
+            collect_assigned_names_from_expr(object, assigned_names, interner);
+        }
         Node::OpAssign { target, object, .. } => {
             assigned_names.insert(interner.get_str(target.name_id).to_string());
             // Scan value expression for walrus operators
             collect_assigned_names_from_expr(object, assigned_names, interner);
         }
+        Node::Delete { targets } => {
+            for target in targets {
+                match target {
+                    // Like CPython, deleting a name makes it local to the scope
+                    DeleteTarget::Name(target) => {
+                        assigned_names.insert(interner.get_str(target.name_id).to_string());
+                    }
+                    DeleteTarget::Subscript { object, index, .. } => {
+                        collect_assigned_names_from_expr(object, assigned_names, interner);
+                        collect_assigned_names_from_expr(index, assigned_names, interner);
+                    }
+                }
+            }
+        }
         Node::SubscriptAssign { index, value, .. } => {
             // Subscript assignment doesn't create a new name, it modifies existing container
             // But scan expressions for walrus operators
@@ -1955,10 +2341,20 @@ fn collect_scope_info_from_node(
                 collect_scope_info_from_node(n, global_names, nonlocal_names, assigned_names, interner);
             }
         }
-        Node::FunctionDef(RawFunctionDef { name, .. }) => {
+        Node::FunctionDef(RawFunctionDef {
+            name,
+            signature,
+            decorators,
+            ..
+        }) => {
             // Function definition creates a local binding for the function name
             // But we don't recurse into the function body - that's a separate scope
             assigned_names.insert(interner.get_str(name.name_id).to_string());
+            // Decorators and default values are evaluated in this scope, so walrus targets there bind here
+            for decorator in decorators {
+                collect_assigned_names_from_expr(decorator, assigned_names, interner);
+            }
+            collect_assigned_names_from_defaults(signature, assigned_names, interner);
         }
         Node::Try(Try {
             body,
@@ -2000,8 +2396,10 @@ fn collect_scope_info_from_node(
         Node::Expr(expr) | Node::Return(expr) => {
             collect_assigned_names_from_expr(expr, assigned_names, interner);
         }
-        Node::Raise(Some(expr)) => {
-            collect_assigned_names_from_expr(expr, assigned_names, interner);
+        Node::Raise { exc, cause } => {
+            for expr in [exc, cause].into_iter().flatten() {
+                collect_assigned_names_from_expr(expr, assigned_names, interner);
+            }
         }
         Node::Assert { test, msg } => {
             collect_assigned_names_from_expr(test, assigned_names, interner);
@@ -2010,7 +2408,7 @@ fn collect_scope_info_from_node(
             }
         }
         // These don't create new names
-        Node::Pass | Node::ReturnNone | Node::Raise(None) | Node::Break { .. } | Node::Continue { .. } => {}
+        Node::Pass | Node::ReturnNone | Node::Break { .. } | Node::Continue { .. } => {}
     }
 }
 
@@ -2053,7 +2451,8 @@ fn collect_assigned_names_from_expr(expr: &ExprLoc, assigned_names: &mut AHashSe
         | Expr::UnaryMinus(operand)
         | Expr::UnaryPlus(operand)
         | Expr::UnaryInvert(operand)
-        | Expr::Await(operand) => {
+        | Expr::Await(operand)
+        | Expr::ExcClass { base: operand, .. } => {
             collect_assigned_names_from_expr(operand, assigned_names, interner);
         }
         Expr::Subscript { object, index } => {
@@ -2117,13 +2516,36 @@ fn collect_assigned_names_from_expr(expr: &ExprLoc, assigned_names: &mut AHashSe
                 collect_assigned_names_from_expr(e, assigned_names, interner);
             }
         }
-        // Lambda bodies have their own scope - walrus inside them doesn't affect us
-        Expr::LambdaRaw { .. } | Expr::Lambda { .. } => {}
+        // Lambda bodies have their own scope - walrus inside them doesn't affect us,
+        // but default values are evaluated in the enclosing scope
+        Expr::LambdaRaw { signature, .. } => {
+            collect_assigned_names_from_defaults(signature, assigned_names, interner);
+        }
+        Expr::Lambda { .. } => {}
         // Leaf expressions don't contain walrus operators
         Expr::Literal(_) | Expr::Builtin(_) | Expr::Name(_) => {}
     }
 }
 
+/// Collects walrus targets from the default value expressions of a function or lambda signature.
+///
+/// Defaults are evaluated at definition time in the enclosing scope, so any `:=` inside
+/// them assigns to that scope rather than the function's own.
+fn collect_assigned_names_from_defaults(
+    signature: &ParsedSignature,
+    assigned_names: &mut AHashSet<String>,
+    interner: &InternerBuilder,
+) {
+    let params = signature
+        .pos_args
+        .iter()
+        .chain(&signature.args)
+        .chain(&signature.kwargs);
+    for default in params.filter_map(|param| param.default.as_ref()) {
+        collect_assigned_names_from_expr(default, assigned_names, interner);
+    }
+}
+
 /// Helper to collect assigned names from argument expressions.
 fn collect_assigned_names_from_args(
     args: &ArgExprs,
@@ -2163,10 +2585,10 @@ fn collect_assigned_names_from_args(
                     collect_assigned_names_from_expr(&kwarg.value, assigned_names, interner);
                 }
             }
-            if let Some(var_args) = var_args {
-                collect_assigned_names_from_expr(var_args, assigned_names, interner);
+            for arg in var_args {
+                collect_assigned_names_from_expr(arg.expr(), assigned_names, interner);
             }
-            if let Some(var_kwargs) = var_kwargs {
+            for var_kwargs in var_kwargs {
                 collect_assigned_names_from_expr(var_kwargs, assigned_names, interner);
             }
         }
@@ -2185,7 +2607,17 @@ fn collect_cell_vars_from_node(
     interner: &InternerBuilder,
 ) {
     match node {
-        Node::FunctionDef(RawFunctionDef { signature, body, .. }) => {
+        Node::FunctionDef(RawFunctionDef {
+            signature,
+            body,
+            decorators,
+            ..
+        }) => {
+            // Decorators are evaluated in our scope and may contain lambdas
+            for decorator in decorators {
+                collect_cell_vars_from_expr(decorator, our_locals, cell_vars, interner);
+            }
+
             // Find what names are referenced inside this nested function
             let mut referenced = AHashSet::new();
             for n in body {
@@ -2277,10 +2709,32 @@ fn collect_cell_vars_from_node(
         Node::OpAssign { object, .. } => {
             collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
         }
+        Node::ChainAssign { targets, object } => {
+            for target in targets {
+                match target {
+                    AssignTarget::Unpack(_) => {}
+                    AssignTarget::Subscript { index, .. } => {
+                        collect_cell_vars_from_expr(index, our_locals, cell_vars, interner);
+                    }
+                    AssignTarget::Attr { object, .. } => {
+                        collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+                    }
+                }
+            }
+            collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+        }
         Node::SubscriptAssign { index, value, .. } => {
             collect_cell_vars_from_expr(index, our_locals, cell_vars, interner);
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
+        Node::Delete { targets } => {
+            for target in targets {
+                if let DeleteTarget::Subscript { object, index, .. } = target {
+                    collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
+                    collect_cell_vars_from_expr(index, our_locals, cell_vars, interner);
+                }
+            }
+        }
         Node::AttrAssign { object, value, .. } => {
             collect_cell_vars_from_expr(object, our_locals, cell_vars, interner);
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
@@ -2425,7 +2879,7 @@ fn collect_cell_vars_from_expr(
             // Only scan the value expression for cell vars
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
-        Expr::Await(value) => {
+        Expr::Await(value) | Expr::ExcClass { base: value, .. } => {
             collect_cell_vars_from_expr(value, our_locals, cell_vars, interner);
         }
         // Leaf expressions
@@ -2474,10 +2928,10 @@ fn collect_cell_vars_from_args(
                     collect_cell_vars_from_expr(&kwarg.value, our_locals, cell_vars, interner);
                 }
             }
-            if let Some(var_args) = var_args {
-                collect_cell_vars_from_expr(var_args, our_locals, cell_vars, interner);
+            for arg in var_args {
+                collect_cell_vars_from_expr(arg.expr(), our_locals, cell_vars, interner);
             }
-            if let Some(var_kwargs) = var_kwargs {
+            for var_kwargs in var_kwargs {
                 collect_cell_vars_from_expr(var_kwargs, our_locals, cell_vars, interner);
             }
         }
@@ -2491,8 +2945,11 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
     match node {
         Node::Expr(expr) => collect_referenced_names_from_expr(expr, referenced, interner),
         Node::Return(expr) => collect_referenced_names_from_expr(expr, referenced, interner),
-        Node::Raise(Some(expr)) => collect_referenced_names_from_expr(expr, referenced, interner),
-        Node::Raise(None) => {}
+        Node::Raise { exc, cause } => {
+            for expr in [exc, cause].into_iter().flatten() {
+                collect_referenced_names_from_expr(expr, referenced, interner);
+            }
+        }
         Node::Assert { test, msg } => {
             collect_referenced_names_from_expr(test, referenced, interner);
             if let Some(m) = msg {
@@ -2505,6 +2962,29 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
         Node::UnpackAssign { object, .. } => {
             collect_referenced_names_from_expr(object, referenced, interner);
         }
+        Node::ChainAssign { targets, object } => {
+            for target in targets {
+                match target {
+                    AssignTarget::Unpack(_) => {}
+                    AssignTarget::Subscript { target, index, .. } => {
+                        referenced.insert(interner.get_str(target.name_id).to_string());
+                        collect_referenced_names_from_expr(index, referenced, interner);
+                    }
+                    AssignTarget::Attr { object, .. } => {
+                        collect_referenced_names_from_expr(object, referenced, interner);
+                    }
+                }
+            }
+            collect_referenced_names_from_expr(object, referenced, interner);
+        }
+        Node::Delete { targets } => {
+            for target in targets {
+                if let DeleteTarget::Subscript { object, index, .. } = target {
+                    collect_referenced_names_from_expr(object, referenced, interner);
+                    collect_referenced_names_from_expr(index, referenced, interner);
+                }
+            }
+        }
         Node::OpAssign { target, object, .. } => {
             // OpAssign reads the target before writing
             referenced.insert(interner.get_str(target.name_id).to_string());
@@ -2550,8 +3030,12 @@ fn collect_referenced_names_from_node(node: &ParseNode, referenced: &mut AHashSe
                 collect_referenced_names_from_node(n, referenced, interner);
             }
         }
-        Node::FunctionDef(_) => {
-            // Don't recurse into nested function bodies - they have their own scope
+        Node::FunctionDef(RawFunctionDef { decorators, .. }) => {
+            // Decorators are evaluated in this scope, but don't recurse into nested
+            // function bodies - they have their own scope
+            for decorator in decorators {
+                collect_referenced_names_from_expr(decorator, referenced, interner);
+            }
         }
         Node::Try(Try {
             body,
@@ -2719,7 +3203,7 @@ fn collect_referenced_names_from_expr(
                 collect_referenced_names_from_expr(expr, referenced, interner);
             }
         }
-        Expr::Await(value) => {
+        Expr::Await(value) | Expr::ExcClass { base: value, .. } => {
             collect_referenced_names_from_expr(value, referenced, interner);
         }
     }
@@ -0,0 +1,358 @@
+//! Statement-level profiling: where an execution spends its time and allocations.
+//!
+//! [`ProfilingTracker`] wraps another resource tracker and asks the VM to report the start
+//! of every statement along with the function names on the call stack. Time and heap
+//! allocations between two statement starts are charged to the first of them, which gives
+//! per-line and per-function totals as well as the call stacks a flame graph is drawn from.
+
+use std::time::Duration;
+
+use ahash::AHashMap;
+
+use crate::resource::{GcStats, HeapSlotStats, ResourceError, ResourceTracker, monotonic_clock};
+
+/// Time and allocations of one function, see [`ProfileReport::functions`].
+///
+/// Functions are identified by name, so functions sharing a name, e.g. methods of
+/// different classes, are reported together.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionProfile {
+    /// Name of the function, `"<module>"` for module-level code.
+    pub name: String,
+    /// Number of statements run in the function itself.
+    pub statements: u64,
+    /// Time spent running the function's own statements.
+    pub self_time: Duration,
+    /// Time spent while the function was on the call stack, including the functions it called.
+    pub total_time: Duration,
+    /// Heap allocations made by the function's own statements.
+    pub allocations: u64,
+}
+
+/// Time and allocations of one source line, see [`ProfileReport::lines`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LineProfile {
+    /// Name of the function the line belongs to, `"<module>"` for module-level code.
+    pub function_name: String,
+    /// Line number (1-based).
+    pub line: u16,
+    /// Number of statements started on the line.
+    pub hits: u64,
+    /// Time spent running the line's statements, excluding the statements of functions they called.
+    pub time: Duration,
+    /// Heap allocations made by the line's statements, excluding functions they called.
+    pub allocations: u64,
+}
+
+/// Profile of an execution, produced by [`ProfilingTracker::report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProfileReport {
+    /// Per-function totals, most self time first.
+    pub functions: Vec<FunctionProfile>,
+    /// Per-line totals, most time first.
+    pub lines: Vec<LineProfile>,
+    /// Time spent in each distinct call stack, as function names outermost first.
+    pub stacks: Vec<(Vec<String>, Duration)>,
+}
+
+impl ProfileReport {
+    /// Returns the call stacks in the folded format read by flame graph tools such as
+    /// `flamegraph.pl` and `inferno-flamegraph`: one `outer;inner;leaf microseconds` line
+    /// per stack.
+    #[must_use]
+    pub fn folded_stacks(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(stack, time)| format!("{} {}\n", stack.join(";"), time.as_micros()))
+            .collect()
+    }
+}
+
+/// Totals collected for one function, indexed like `ProfilingTracker::function_names`.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+struct FunctionTotals {
+    statements: u64,
+    self_time: Duration,
+    total_time: Duration,
+    allocations: u64,
+}
+
+/// Totals collected for one line of one function.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+struct LineTotals {
+    hits: u64,
+    time: Duration,
+    allocations: u64,
+}
+
+/// Resource tracker wrapper that profiles time and allocations per function and per line.
+///
+/// All limits are delegated to the wrapped tracker; profiling never fails an execution.
+/// Pass `&mut ProfilingTracker` as the tracker to read the report after a run, or use
+/// `MontyRun::run_with_profile`.
+///
+/// Each statement is charged from its start until the next statement starts, so time the
+/// host spends answering an external call is charged to the statement making it. Reporting
+/// every statement slows execution down noticeably, so only profile runs you want to
+/// investigate.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProfilingTracker<T> {
+    inner: T,
+    /// Names of the functions seen so far; other fields refer to functions by index.
+    function_names: Vec<String>,
+    function_ids: AHashMap<String, usize>,
+    functions: Vec<FunctionTotals>,
+    lines: AHashMap<(usize, u16), LineTotals>,
+    stacks: AHashMap<Vec<usize>, Duration>,
+    /// Call stack of the statement running, empty before the first statement.
+    stack: Vec<usize>,
+    /// Line of the statement running.
+    line: u16,
+    /// `monotonic_clock` reading when the statement running started, reset on deserialization.
+    #[serde(skip, default = "monotonic_clock")]
+    statement_start: Duration,
+    /// Allocations made since the statement running started.
+    statement_allocations: u64,
+}
+
+impl<T: ResourceTracker> ProfilingTracker<T> {
+    /// Wraps `inner`, with an empty profile.
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            function_names: Vec::new(),
+            function_ids: AHashMap::new(),
+            functions: Vec::new(),
+            lines: AHashMap::new(),
+            stacks: AHashMap::new(),
+            stack: Vec::new(),
+            line: 0,
+            statement_start: monotonic_clock(),
+            statement_allocations: 0,
+        }
+    }
+
+    /// Returns the profile collected so far.
+    ///
+    /// The statement running when execution ended is charged up to this call, so call it
+    /// right after the run.
+    #[must_use]
+    pub fn report(&self) -> ProfileReport {
+        let mut functions = self.functions.clone();
+        let mut lines = self.lines.clone();
+        let mut stacks = self.stacks.clone();
+        if let Some(&leaf) = self.stack.last() {
+            let time = monotonic_clock().saturating_sub(self.statement_start);
+            charge(
+                &mut functions,
+                &mut lines,
+                &mut stacks,
+                &self.stack,
+                (leaf, self.line),
+                time,
+                self.statement_allocations,
+            );
+        }
+
+        let name = |index: usize| self.function_names[index].clone();
+        let mut functions: Vec<_> = functions
+            .into_iter()
+            .enumerate()
+            .map(|(index, totals)| FunctionProfile {
+                name: name(index),
+                statements: totals.statements,
+                self_time: totals.self_time,
+                total_time: totals.total_time,
+                allocations: totals.allocations,
+            })
+            .collect();
+        functions.sort_by(|a, b| b.self_time.cmp(&a.self_time).then_with(|| a.name.cmp(&b.name)));
+
+        let mut lines: Vec<_> = lines
+            .into_iter()
+            .map(|((function, line), totals)| LineProfile {
+                function_name: name(function),
+                line,
+                hits: totals.hits,
+                time: totals.time,
+                allocations: totals.allocations,
+            })
+            .collect();
+        lines.sort_by(|a, b| {
+            b.time
+                .cmp(&a.time)
+                .then_with(|| a.function_name.cmp(&b.function_name))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+
+        let mut stacks: Vec<_> = stacks
+            .into_iter()
+            .map(|(stack, time)| (stack.into_iter().map(name).collect::<Vec<_>>(), time))
+            .collect();
+        stacks.sort();
+
+        ProfileReport {
+            functions,
+            lines,
+            stacks,
+        }
+    }
+
+    /// Returns the wrapped tracker.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwraps into the wrapped tracker.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns the index of the function called `name`, registering it if it's new.
+    fn function_id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.function_ids.get(name) {
+            return id;
+        }
+        let id = self.function_names.len();
+        self.function_names.push(name.to_owned());
+        self.function_ids.insert(name.to_owned(), id);
+        self.functions.push(FunctionTotals::default());
+        id
+    }
+}
+
+/// Charges `time` and `allocations` to the statement on `line` of the innermost function of `stack`.
+fn charge(
+    functions: &mut [FunctionTotals],
+    lines: &mut AHashMap<(usize, u16), LineTotals>,
+    stacks: &mut AHashMap<Vec<usize>, Duration>,
+    stack: &[usize],
+    (leaf, line): (usize, u16),
+    time: Duration,
+    allocations: u64,
+) {
+    let own = &mut functions[leaf];
+    own.statements += 1;
+    own.self_time += time;
+    own.allocations += allocations;
+    // recursive functions appear several times in the stack but spend the time only once
+    for (depth, &function) in stack.iter().enumerate() {
+        if !stack[..depth].contains(&function) {
+            functions[function].total_time += time;
+        }
+    }
+
+    let totals = lines.entry((leaf, line)).or_default();
+    totals.hits += 1;
+    totals.time += time;
+    totals.allocations += allocations;
+
+    match stacks.get_mut(stack) {
+        Some(total) => *total += time,
+        None => {
+            stacks.insert(stack.to_vec(), time);
+        }
+    }
+}
+
+impl<T: ResourceTracker> ResourceTracker for ProfilingTracker<T> {
+    fn on_allocate(&mut self, get_size: impl FnOnce() -> usize) -> Result<(), ResourceError> {
+        self.inner.on_allocate(get_size)?;
+        self.statement_allocations += 1;
+        Ok(())
+    }
+
+    fn on_free(&mut self, get_size: impl FnOnce() -> usize) {
+        self.inner.on_free(get_size);
+    }
+
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        self.inner.on_heap_slots(stats);
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        self.inner.on_gc(stats);
+    }
+
+    fn check_time(&self) -> Result<(), ResourceError> {
+        self.inner.check_time()
+    }
+
+    fn check_recursion_depth(&self, current_depth: usize) -> Result<(), ResourceError> {
+        self.inner.check_recursion_depth(current_depth)
+    }
+
+    fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError> {
+        self.inner.check_large_result(estimated_bytes)
+    }
+
+    fn random_seed(&self) -> Option<u64> {
+        self.inner.random_seed()
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        self.inner.pause_on_timeout()
+    }
+
+    fn restart_timer(&mut self) {
+        self.inner.restart_timer();
+    }
+
+    fn deterministic(&self) -> bool {
+        self.inner.deterministic()
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        self.inner.max_snapshot_size()
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        self.inner.checkpoints_enabled()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.inner.on_statement()
+    }
+
+    fn profiling_enabled(&self) -> bool {
+        true
+    }
+
+    fn on_profile_statement(&mut self, stack: &[&str], line: u16) {
+        let now = monotonic_clock();
+        if let Some(&leaf) = self.stack.last() {
+            let time = now.saturating_sub(self.statement_start);
+            charge(
+                &mut self.functions,
+                &mut self.lines,
+                &mut self.stacks,
+                &self.stack,
+                (leaf, self.line),
+                time,
+                self.statement_allocations,
+            );
+        }
+        self.inner.on_profile_statement(stack, line);
+
+        // the stack rarely changes between statements, so only rebuild it when it does
+        let unchanged = self.stack.len() == stack.len()
+            && self
+                .stack
+                .iter()
+                .zip(stack)
+                .all(|(&id, name)| self.function_names[id] == *name);
+        if !unchanged {
+            self.stack.clear();
+            for name in stack {
+                let id = self.function_id(name);
+                self.stack.push(id);
+            }
+        }
+        self.line = line;
+        self.statement_allocations = 0;
+        self.statement_start = now;
+    }
+}
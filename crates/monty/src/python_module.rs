@@ -0,0 +1,205 @@
+//! Host-supplied pure-Python modules.
+//!
+//! Embedders describe a [`PythonModule`] as a module name plus Python source, and pass it to
+//! [`MontyRun::new_with_modules`](crate::MontyRun::new_with_modules). Sandboxed code can then
+//! `import` it, so scripts can be split across several files.
+//!
+//! Module sources are parsed and compiled up front together with the main script, sharing its
+//! interns and function table. Each module body is compiled as a function that runs in a
+//! namespace reserved for the module, the first time the module is imported. The body ends by
+//! building the module object from its namespace and caching it there, so later imports reuse
+//! the same module. Functions defined in a module use that module's namespace as their globals.
+
+use crate::{
+    MontyException,
+    bytecode::{CompileOptions, Compiler},
+    expressions::PreparedNode,
+    function::Function,
+    intern::{FunctionId, InternerBuilder, Interns, StringId},
+    native_module::NativeModuleDef,
+    parse::parse_with_interner,
+    prepare::prepare_python_module,
+};
+
+/// A pure-Python module supplied by the host, importable by sandboxed code.
+///
+/// # Example
+/// ```
+/// use monty::{CompileOptions, MontyObject, MontyRun, PythonModule};
+///
+/// let helpers = PythonModule::new("helpers", "SCALE = 3\n\ndef scale(x):\n    return x * SCALE\n");
+/// let code = "import helpers\nhelpers.scale(14)";
+/// let runner = MontyRun::new_with_modules(
+///     code.to_owned(),
+///     "main.py",
+///     vec![],
+///     vec![],
+///     vec![],
+///     vec![helpers],
+///     CompileOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(runner.run_no_limits(vec![]).unwrap(), MontyObject::Int(42));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PythonModule {
+    /// The name used in `import` statements.
+    name: String,
+    /// The module's Python source.
+    source: String,
+}
+
+impl PythonModule {
+    /// Creates a module importable as `name`, whose body is `source`.
+    ///
+    /// Built-in modules take precedence, so a module named e.g. `math` is never imported.
+    /// Tracebacks show the module's code as coming from `name.py`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: source.into(),
+        }
+    }
+
+    /// Returns the module name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the module's Python source.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// A Python module after parsing and name resolution, ready to be compiled once the main
+/// script's interns exist.
+pub(crate) struct PreparedPythonModule {
+    /// Number of slots in the module's namespace.
+    namespace_size: usize,
+    /// The prepared module body.
+    nodes: Vec<PreparedNode>,
+}
+
+/// Parses and prepares `modules`, interning their names into `interner`.
+///
+/// Returns the module definitions for `Interns`, whose bodies are filled in by
+/// [`compile_python_modules`], along with the prepared modules in the same order.
+///
+/// # Errors
+/// Returns a `SyntaxError` if a module cannot be parsed, or a `RuntimeError` if a module
+/// shares its name with another Python or native module.
+pub(crate) fn prepare_python_modules(
+    modules: Vec<PythonModule>,
+    mut interner: InternerBuilder,
+    native_modules: &[NativeModuleDef],
+) -> Result<(Vec<PythonModuleDef>, Vec<PreparedPythonModule>, InternerBuilder), MontyException> {
+    let mut defs: Vec<PythonModuleDef> = Vec::with_capacity(modules.len());
+    let mut prepared_modules = Vec::with_capacity(modules.len());
+    for PythonModule { name, source } in modules {
+        let name_id = interner.intern(&name);
+        if defs.iter().any(|def| def.name == name_id) || native_modules.iter().any(|def| def.name() == name_id) {
+            return Err(MontyException::runtime_error(format!(
+                "module '{name}' is registered more than once"
+            )));
+        }
+        let filename = format!("{}.py", name.replace('.', "/"));
+        let parse_result =
+            parse_with_interner(&source, &filename, interner).map_err(|e| e.into_python_exc(&filename, &source))?;
+        let mut prepared = prepare_python_module(parse_result).map_err(|e| e.into_python_exc(&filename, &source))?;
+        let global_names = prepared.slot_names();
+        interner = prepared.interner;
+
+        defs.push(PythonModuleDef {
+            name: name_id,
+            filename: interner.intern(&filename),
+            source,
+            body: None,
+            global_names,
+        });
+        prepared_modules.push(PreparedPythonModule {
+            namespace_size: prepared.namespace_size,
+            nodes: prepared.nodes,
+        });
+    }
+    Ok((defs, prepared_modules, interner))
+}
+
+/// Compiles the bodies of the prepared modules registered in `interns`, appending them and
+/// their nested functions to `functions`.
+///
+/// # Errors
+/// Returns a `SyntaxError` if a module body fails to compile.
+pub(crate) fn compile_python_modules(
+    prepared_modules: Vec<PreparedPythonModule>,
+    interns: &mut Interns,
+    mut functions: Vec<Function>,
+    options: CompileOptions,
+) -> Result<Vec<Function>, MontyException> {
+    for (index, module) in prepared_modules.into_iter().enumerate() {
+        let namespace_size = u16::try_from(module.namespace_size).expect("module namespace size exceeds u16");
+        let module_index = u16::try_from(index).expect("python module index exceeds u16");
+        let (body, compiled) =
+            Compiler::compile_python_module(&module.nodes, interns, namespace_size, functions, module_index, options)
+                .map_err(|e| {
+                let def = interns.get_python_module(index);
+                e.into_python_exc(interns.get_str(def.filename), &def.source)
+            })?;
+        functions = compiled;
+        interns.set_python_module_body(index, body);
+    }
+    Ok(functions)
+}
+
+/// A registered Python module, as stored in `Interns` for the compiler and VM.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct PythonModuleDef {
+    /// The interned module name.
+    name: StringId,
+    /// The interned filename used in the module's code ranges.
+    filename: StringId,
+    /// The module's source, for traceback previews.
+    source: String,
+    /// The function holding the compiled module body, set once compiled.
+    body: Option<FunctionId>,
+    /// Name of each slot of the module's namespace, see `Interns::global_names`.
+    global_names: Vec<StringId>,
+}
+
+impl PythonModuleDef {
+    /// Returns the interned module name.
+    pub fn name(&self) -> StringId {
+        self.name
+    }
+
+    /// Returns the interned filename used in the module's code ranges.
+    pub fn filename(&self) -> StringId {
+        self.filename
+    }
+
+    /// Returns the module's source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the function holding the compiled module body.
+    ///
+    /// # Panics
+    /// Panics if the module has not been compiled yet.
+    pub fn body(&self) -> FunctionId {
+        self.body.expect("python module body not compiled")
+    }
+
+    /// Returns the names of the module's global slots, indexed by slot.
+    pub fn global_names(&self) -> &[StringId] {
+        &self.global_names
+    }
+
+    /// Sets the function holding the compiled module body.
+    pub fn set_body(&mut self, body: FunctionId) {
+        self.body = Some(body);
+    }
+}
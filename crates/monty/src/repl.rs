@@ -11,7 +11,7 @@ use ruff_python_parser::{InterpolatedStringErrorType, LexicalErrorType, ParseErr
 use crate::{
     ExcType, MontyException,
     asyncio::CallId,
-    bytecode::{Code, Compiler, FrameExit, VM, VMSnapshot},
+    bytecode::{Code, CompileOptions, Compiler, FrameExit, VM, VMSnapshot},
     exception_private::{RunError, RunResult},
     heap::{DropWithHeap, Heap},
     intern::{ExtFunctionId, InternerBuilder, Interns},
@@ -21,8 +21,12 @@ use crate::{
     os::OsFunction,
     parse::{parse, parse_with_interner},
     prepare::{prepare, prepare_with_existing_names},
-    resource::ResourceTracker,
+    resource::{PauseReason, ResourceTracker},
     run::{ExternalResult, MontyFuture},
+    snapshot_format::{
+        SnapshotCompression, SnapshotError, SnapshotStats, encoded_size, from_snapshot_bytes, from_snapshot_bytes_with,
+        to_snapshot_bytes_with,
+    },
     value::Value,
 };
 
@@ -62,15 +66,18 @@ impl ReplExecutor {
         external_functions: Vec<String>,
     ) -> Result<Self, MontyException> {
         let parse_result = parse(&code, script_name).map_err(|e| e.into_python_exc(script_name, &code))?;
-        let prepared = prepare(parse_result, input_names, &external_functions)
+        let mut prepared = prepare(parse_result, input_names, &external_functions)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let global_names = prepared.slot_names();
 
         let external_function_ids = (0..external_functions.len()).map(ExtFunctionId::new).collect();
 
         let mut interns = Interns::new(prepared.interner, Vec::new(), external_functions);
+        interns.set_global_names(global_names);
         let namespace_size_u16 = u16::try_from(prepared.namespace_size).expect("module namespace size exceeds u16");
-        let compile_result = Compiler::compile_module(&prepared.nodes, &interns, namespace_size_u16)
-            .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let compile_result =
+            Compiler::compile_module(&prepared.nodes, &interns, namespace_size_u16, CompileOptions::default())
+                .map_err(|e| e.into_python_exc(script_name, &code))?;
         interns.set_functions(compile_result.functions);
 
         Ok(Self {
@@ -100,17 +107,24 @@ impl ReplExecutor {
         let seeded_interner = InternerBuilder::from_interns(existing_interns, &code);
         let parse_result = parse_with_interner(&code, script_name, seeded_interner)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
-        let prepared = prepare_with_existing_names(parse_result, existing_name_map)
+        let mut prepared = prepare_with_existing_names(parse_result, existing_name_map)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let global_names = prepared.slot_names();
 
         let external_function_ids = (0..external_functions.len()).map(ExtFunctionId::new).collect();
 
         let existing_functions = existing_interns.functions_clone();
         let mut interns = Interns::new(prepared.interner, Vec::new(), external_functions);
+        interns.set_global_names(global_names);
         let namespace_size_u16 = u16::try_from(prepared.namespace_size).expect("module namespace size exceeds u16");
-        let compile_result =
-            Compiler::compile_module_with_functions(&prepared.nodes, &interns, namespace_size_u16, existing_functions)
-                .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let compile_result = Compiler::compile_module_with_functions(
+            &prepared.nodes,
+            &interns,
+            namespace_size_u16,
+            existing_functions,
+            CompileOptions::default(),
+        )
+        .map_err(|e| e.into_python_exc(script_name, &code))?;
         interns.set_functions(compile_result.functions);
 
         Ok(Self {
@@ -196,6 +210,10 @@ fn frame_exit_to_object(
         FrameExit::ResolveFutures(_) => {
             Err(ExcType::not_implemented("async futures not supported by standard execution.").into())
         }
+        FrameExit::Paused(_) => Err(ExcType::not_implemented(
+            "pausing on timeout and checkpoints not supported by standard execution.",
+        )
+        .into()),
     }
 }
 
@@ -426,6 +444,14 @@ impl<T: ResourceTracker> MontyRepl<T> {
         self.feed(code, &mut PrintWriter::Stdout)
     }
 
+    /// Returns a mutable reference to the resource tracker.
+    ///
+    /// This allows adjusting limits between snippets, e.g. resetting a
+    /// `LimitedTracker`'s budget so limits apply to each snippet separately.
+    pub fn tracker_mut(&mut self) -> &mut T {
+        self.heap.tracker_mut()
+    }
+
     /// Grows the global namespace to at least `namespace_size`.
     ///
     /// Newly introduced slots are initialized to `Undefined` to keep slot alignment
@@ -456,9 +482,32 @@ impl<T: ResourceTracker + serde::Serialize> MontyRepl<T> {
     /// of interactive state between process runs.
     ///
     /// # Errors
+    /// Returns an error if serialization fails, or `SnapshotError::TooLarge` if the snapshot is
+    /// larger than the tracker's `max_snapshot_size`.
+    pub fn dump(&self) -> Result<Vec<u8>, SnapshotError> {
+        self.dump_with(SnapshotCompression::None)
+    }
+
+    /// Serializes the REPL session like `dump()`, compressing the payload with `compression`.
+    ///
+    /// # Errors
+    /// Returns an error if serialization or compression fails, or `SnapshotError::TooLarge` if
+    /// the snapshot is larger than the tracker's `max_snapshot_size`.
+    pub fn dump_with(&self, compression: SnapshotCompression) -> Result<Vec<u8>, SnapshotError> {
+        to_snapshot_bytes_with(self, compression, self.heap.tracker().max_snapshot_size())
+    }
+
+    /// Reports how large the snapshot written by `dump()` is and which parts take up the space.
+    ///
+    /// # Errors
     /// Returns an error if serialization fails.
-    pub fn dump(&self) -> Result<Vec<u8>, postcard::Error> {
-        postcard::to_allocvec(self)
+    pub fn dump_stats(&self) -> Result<SnapshotStats, SnapshotError> {
+        let sections = vec![
+            ("code", encoded_size(&self.interns)?),
+            ("heap", encoded_size(&self.heap)?),
+            ("namespaces", encoded_size(&self.namespaces)?),
+        ];
+        SnapshotStats::new(self, self.heap.live_count(), self.namespaces.active_count(), sections)
     }
 }
 
@@ -466,9 +515,19 @@ impl<T: ResourceTracker + serde::de::DeserializeOwned> MontyRepl<T> {
     /// Restores a REPL session from bytes produced by `MontyRepl::dump`.
     ///
     /// # Errors
-    /// Returns an error if deserialization fails.
-    pub fn load(bytes: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(bytes)
+    /// Returns an error if deserialization fails, or `SnapshotError::UnsupportedVersion` if the
+    /// data was written in a snapshot format this version of Monty can't load.
+    pub fn load(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes(bytes)
+    }
+
+    /// Deserializes like `load()`, for snapshots from an untrusted source.
+    ///
+    /// # Errors
+    /// Returns the errors of `load()`, or `SnapshotError::TooLarge` if the snapshot, or a
+    /// compressed snapshot's payload after decompression, is larger than `max_size` bytes.
+    pub fn load_with_max_size(bytes: &[u8], max_size: usize) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes_with(bytes, Some(max_size))
     }
 }
 
@@ -516,6 +575,8 @@ pub enum ReplProgress<T: ResourceTracker> {
     },
     /// All async tasks are blocked waiting for external futures to resolve.
     ResolveFutures(ReplFutureSnapshot<T>),
+    /// Execution exceeded `max_duration` with `pause_on_timeout` enabled, or stopped at a checkpoint.
+    Paused(ReplPausedSnapshot<T>),
     /// Snippet execution completed with the updated REPL and result value.
     Complete {
         /// Updated REPL session state to continue feeding snippets.
@@ -563,6 +624,15 @@ impl<T: ResourceTracker> ReplProgress<T> {
         }
     }
 
+    /// Consumes the progress and returns the paused state.
+    #[must_use]
+    pub fn into_paused(self) -> Option<ReplPausedSnapshot<T>> {
+        match self {
+            Self::Paused(state) => Some(state),
+            _ => None,
+        }
+    }
+
     /// Consumes the progress and returns the completed REPL and value.
     #[must_use]
     pub fn into_complete(self) -> Option<(MontyRepl<T>, MontyObject)> {
@@ -577,9 +647,25 @@ impl<T: ResourceTracker + serde::Serialize> ReplProgress<T> {
     /// Serializes the REPL execution progress to a binary format.
     ///
     /// # Errors
-    /// Returns an error if serialization fails.
-    pub fn dump(&self) -> Result<Vec<u8>, postcard::Error> {
-        postcard::to_allocvec(self)
+    /// Returns an error if serialization fails, or `SnapshotError::TooLarge` if the snapshot is
+    /// larger than the tracker's `max_snapshot_size`.
+    pub fn dump(&self) -> Result<Vec<u8>, SnapshotError> {
+        self.dump_with(SnapshotCompression::None)
+    }
+
+    /// Serializes the REPL execution progress like `dump()`, compressing the payload with `compression`.
+    ///
+    /// # Errors
+    /// Returns an error if serialization or compression fails, or `SnapshotError::TooLarge` if
+    /// the snapshot is larger than the tracker's `max_snapshot_size`.
+    pub fn dump_with(&self, compression: SnapshotCompression) -> Result<Vec<u8>, SnapshotError> {
+        let repl = match self {
+            Self::FunctionCall { state, .. } | Self::OsCall { state, .. } => &state.repl,
+            Self::ResolveFutures(state) => &state.repl,
+            Self::Paused(state) => &state.repl,
+            Self::Complete { repl, .. } => repl,
+        };
+        to_snapshot_bytes_with(self, compression, repl.heap.tracker().max_snapshot_size())
     }
 }
 
@@ -587,9 +673,19 @@ impl<T: ResourceTracker + serde::de::DeserializeOwned> ReplProgress<T> {
     /// Deserializes REPL execution progress from a binary format.
     ///
     /// # Errors
-    /// Returns an error if deserialization fails.
-    pub fn load(bytes: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(bytes)
+    /// Returns an error if deserialization fails, or `SnapshotError::UnsupportedVersion` if the
+    /// data was written in a snapshot format this version of Monty can't load.
+    pub fn load(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes(bytes)
+    }
+
+    /// Deserializes like `load()`, for snapshots from an untrusted source.
+    ///
+    /// # Errors
+    /// Returns the errors of `load()`, or `SnapshotError::TooLarge` if the snapshot, or a
+    /// compressed snapshot's payload after decompression, is larger than `max_size` bytes.
+    pub fn load_with_max_size(bytes: &[u8], max_size: usize) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes_with(bytes, Some(max_size))
     }
 }
 
@@ -664,6 +760,66 @@ impl<T: ResourceTracker> ReplSnapshot<T> {
     }
 }
 
+/// REPL execution state paused because the time limit was exceeded or a checkpoint was due.
+///
+/// This is the REPL-aware counterpart to `PausedSnapshot`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(bound(serialize = "T: serde::Serialize", deserialize = "T: serde::de::DeserializeOwned"))]
+pub struct ReplPausedSnapshot<T: ResourceTracker> {
+    /// Persistent REPL session state while this snippet is suspended.
+    repl: MontyRepl<T>,
+    /// Compiled snippet and intern/function tables for this execution.
+    executor: ReplExecutor,
+    /// VM stack/frame state at suspension.
+    vm_state: VMSnapshot,
+    /// Why execution paused.
+    reason: PauseReason,
+}
+
+impl<T: ResourceTracker> ReplPausedSnapshot<T> {
+    /// Returns a mutable reference to the resource tracker.
+    pub fn tracker_mut(&mut self) -> &mut T {
+        self.repl.tracker_mut()
+    }
+
+    /// Returns why execution paused.
+    #[must_use]
+    pub fn reason(&self) -> PauseReason {
+        self.reason
+    }
+
+    /// Continues snippet execution, with a fresh time budget if it paused on timeout.
+    ///
+    /// # Arguments
+    /// * `print` - Writer used for Python `print()`
+    pub fn resume(self, print: &mut PrintWriter<'_>) -> Result<ReplProgress<T>, MontyException> {
+        let Self {
+            mut repl,
+            executor,
+            vm_state,
+            reason,
+        } = self;
+
+        if reason == PauseReason::Timeout {
+            repl.heap.tracker_mut().restart_timer();
+        }
+
+        let mut vm = VM::restore(
+            vm_state,
+            &executor.module_code,
+            &mut repl.heap,
+            &mut repl.namespaces,
+            &executor.interns,
+            print,
+        );
+
+        let vm_result = vm.run();
+        let vm_state = vm.check_snapshot(&vm_result);
+
+        handle_repl_vm_result(vm_result, vm_state, executor, repl)
+    }
+}
+
 /// REPL execution state blocked on unresolved external futures.
 ///
 /// This is the REPL-aware counterpart to `FutureSnapshot`.
@@ -866,6 +1022,12 @@ fn handle_repl_vm_result<T: ResourceTracker>(
                 pending_call_ids,
             }))
         }
+        Ok(FrameExit::Paused(reason)) => Ok(ReplProgress::Paused(ReplPausedSnapshot {
+            repl,
+            executor,
+            vm_state: vm_state.expect("snapshot should exist for Paused"),
+            reason,
+        })),
         Err(err) => {
             #[cfg(feature = "ref-count-panic")]
             repl.namespaces.drop_global_with_heap(&mut repl.heap);
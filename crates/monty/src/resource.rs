@@ -1,7 +1,10 @@
 use std::{
     fmt,
-    sync::atomic::{AtomicU16, Ordering},
-    time::{Duration, Instant},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
 use crate::{
@@ -47,6 +50,14 @@ pub fn check_pow_size(base_bits: u64, exponent: u64, tracker: &impl ResourceTrac
     check_estimated_size(result_bytes.saturating_mul(4), tracker)
 }
 
+/// Pre-checks that `math.factorial(n)` won't exceed resource limits before computing.
+///
+/// `n!` has fewer than `n * bits(n)` bits, where `bits(n)` is the bit length of `n`.
+pub fn check_factorial_size(n: u64, tracker: &impl ResourceTracker) -> Result<(), ResourceError> {
+    let n_bits = u64::from(u64::BITS - n.leading_zeros());
+    check_estimated_size(estimate_bits_to_bytes(n.saturating_mul(n_bits)), tracker)
+}
+
 /// Pre-checks that an integer multiplication won't exceed resource limits.
 ///
 /// The result of multiplying two numbers has at most `a_bits + b_bits` bits.
@@ -191,6 +202,10 @@ pub enum ResourceError {
     Memory { limit: usize, used: usize },
     /// Maximum recursion depth exceeded.
     Recursion { limit: usize, depth: usize },
+    /// Maximum number of execution steps exceeded.
+    Steps { limit: u64, count: u64 },
+    /// Execution was cancelled by the host through a `CancelToken`.
+    Cancelled,
     /// Any other error, e.g. when propagating a python exception
     Exception(MontyException),
 }
@@ -210,6 +225,12 @@ impl fmt::Display for ResourceError {
             Self::Recursion { .. } => {
                 write!(f, "maximum recursion depth exceeded")
             }
+            Self::Steps { limit, count } => {
+                write!(f, "step limit exceeded: {count} > {limit}")
+            }
+            Self::Cancelled => {
+                write!(f, "execution cancelled")
+            }
             Self::Exception(exc) => {
                 write!(f, "{exc}")
             }
@@ -219,6 +240,25 @@ impl fmt::Display for ResourceError {
 
 impl std::error::Error for ResourceError {}
 
+/// Which resource limit stopped execution, along with the configured limit and the amount used.
+///
+/// Attached to the exception raised when a [`ResourceError`] aborts a run, so hosts can
+/// tell a sandbox limit apart from an ordinary `MemoryError` or `TimeoutError` raised by code,
+/// see [`MontyException::limit_exceeded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LimitExceeded {
+    /// Maximum number of allocations exceeded.
+    Allocation { limit: usize, count: usize },
+    /// Maximum execution time exceeded.
+    Time { limit: Duration, elapsed: Duration },
+    /// Maximum memory usage exceeded.
+    Memory { limit: usize, used: usize },
+    /// Maximum recursion depth exceeded.
+    Recursion { limit: usize, depth: usize },
+    /// Maximum number of execution steps exceeded.
+    Steps { limit: u64, count: u64 },
+}
+
 impl ResourceError {
     /// Converts this resource error to a Python exception with optional stack frame.
     ///
@@ -226,9 +266,12 @@ impl ResourceError {
     /// - `Allocation` → `MemoryError`
     /// - `Memory` → `MemoryError`
     /// - `Time` → `TimeoutError`
+    /// - `Steps` → `TimeoutError`
     /// - `Recursion` → `RecursionError`
+    /// - `Cancelled` → `KeyboardInterrupt`
     #[must_use]
     pub(crate) fn into_exception(self, frame: Option<RawStackFrame>) -> ExceptionRaise {
+        let limit = self.limit_exceeded();
         let (exc_type, msg) = match self {
             Self::Allocation { limit, count } => (
                 ExcType::MemoryError,
@@ -242,16 +285,36 @@ impl ResourceError {
                 ExcType::TimeoutError,
                 Some(format!("time limit exceeded: {elapsed:?} > {limit:?}")),
             ),
+            Self::Steps { limit, count } => (
+                ExcType::TimeoutError,
+                Some(format!("step limit exceeded: {count} > {limit}")),
+            ),
             Self::Recursion { .. } => (
                 ExcType::RecursionError,
                 Some("maximum recursion depth exceeded".to_string()),
             ),
+            Self::Cancelled => (ExcType::KeyboardInterrupt, Some("execution cancelled".to_string())),
             Self::Exception(exc) => (exc.exc_type(), exc.into_message()),
         };
         let exc = SimpleException::new(exc_type, msg);
-        match frame {
+        let mut raise: ExceptionRaise = match frame {
             Some(f) => exc.with_frame(f),
             None => exc.into(),
+        };
+        raise.limit = limit;
+        raise
+    }
+
+    /// Returns the limit this error reports, or `None` for cancellation and propagated exceptions.
+    #[must_use]
+    pub fn limit_exceeded(&self) -> Option<LimitExceeded> {
+        match *self {
+            Self::Allocation { limit, count } => Some(LimitExceeded::Allocation { limit, count }),
+            Self::Time { limit, elapsed } => Some(LimitExceeded::Time { limit, elapsed }),
+            Self::Memory { limit, used } => Some(LimitExceeded::Memory { limit, used }),
+            Self::Recursion { limit, depth } => Some(LimitExceeded::Recursion { limit, depth }),
+            Self::Steps { limit, count } => Some(LimitExceeded::Steps { limit, count }),
+            Self::Cancelled | Self::Exception(_) => None,
         }
     }
 }
@@ -277,7 +340,8 @@ pub trait ResourceTracker: fmt::Debug {
     /// if a limit would be exceeded.
     ///
     /// # Arguments
-    /// * `size` - Approximate size in bytes of the allocation
+    /// * `size` - Size in bytes of the allocation, counting the slots lists, dicts and
+    ///   sets have reserved rather than just the ones in use
     fn on_allocate(&mut self, get_size: impl FnOnce() -> usize) -> Result<(), ResourceError>;
 
     /// Called when memory is freed (during dec_ref or garbage collection).
@@ -286,10 +350,11 @@ pub trait ResourceTracker: fmt::Debug {
     /// * `size` - Size in bytes of the freed allocation
     fn on_free(&mut self, get_size: impl FnOnce() -> usize);
 
-    /// Called periodically (at statement boundaries) to check time limits.
+    /// Called before every bytecode instruction, and on each iteration of loops inside
+    /// builtins, to check time limits.
     ///
-    /// Returns `Ok(())` if within time limit, or `Err(ResourceError::Time)`
-    /// if the limit is exceeded.
+    /// Each call also counts as one step of work for `max_steps`. Returns `Ok(())` if within
+    /// limits, or `Err(ResourceError::Time)` / `Err(ResourceError::Steps)` if one is exceeded.
     ///
     /// Takes `&self` rather than `&mut self` because checking elapsed time is a
     /// read-only operation. This allows time checks in contexts that only have
@@ -317,6 +382,171 @@ pub trait ResourceTracker: fmt::Debug {
     ///
     /// Returns `Ok(())` to allow the operation, or `Err(ResourceError)` to reject.
     fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError>;
+
+    /// Called after each heap allocation and garbage collection with the heap's slot usage.
+    ///
+    /// Lets trackers monitor fragmentation: slots freed by `dec_ref` or GC stay reserved
+    /// and are reused by later allocations. Does nothing by default.
+    #[inline]
+    fn on_heap_slots(&mut self, _stats: HeapSlotStats) {}
+
+    /// Called after each garbage collection with what it examined, freed and how long
+    /// execution was paused. Does nothing by default.
+    #[inline]
+    fn on_gc(&mut self, _stats: GcStats) {}
+
+    /// Seed for the `random` module's generator, read once when the heap is created.
+    ///
+    /// Returns `None` by default, in which case the generator is seeded from host entropy.
+    /// Return `Some(seed)` to make runs that use `random` reproducible.
+    fn random_seed(&self) -> Option<u64> {
+        None
+    }
+
+    /// Whether exceeding the time limit should pause execution rather than raise `TimeoutError`.
+    ///
+    /// When true, the VM stops at the next instruction boundary and returns
+    /// `RunProgress::Paused`. Returns `false` by default.
+    fn pause_on_timeout(&self) -> bool {
+        false
+    }
+
+    /// Starts a fresh time budget, called when a paused execution is resumed.
+    /// Does nothing by default.
+    fn restart_timer(&mut self) {}
+
+    /// Whether builtins that can't be replayed exactly, such as `time.time()`, should raise.
+    ///
+    /// Returns `false` by default.
+    fn deterministic(&self) -> bool {
+        false
+    }
+
+    /// Maximum size in bytes of a snapshot written by `dump()`, checked after compression.
+    ///
+    /// Returns `None` by default, meaning snapshots can be any size.
+    fn max_snapshot_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the VM should call `on_statement` at the start of every statement.
+    ///
+    /// Returns `false` by default, so statement boundaries cost nothing.
+    fn checkpoints_enabled(&self) -> bool {
+        false
+    }
+
+    /// Called at the start of each statement executed when `checkpoints_enabled()` is true.
+    ///
+    /// Return `true` to stop with `RunProgress::Paused` and `PauseReason::Checkpoint`, so the
+    /// host can `dump()` a checkpoint to recover from if it crashes. This is the hook for custom
+    /// checkpoint schedules, e.g. asking a host callback whether a checkpoint is due.
+    ///
+    /// Statements in frames run by a nested VM loop (e.g. a `sorted()` key function) can't be
+    /// suspended, so a checkpoint requested there is taken at the next statement after it returns.
+    fn on_statement(&mut self) -> bool {
+        false
+    }
+
+    /// Whether the VM should call `on_profile_statement` at the start of every statement.
+    ///
+    /// Returns `false` by default, so profiling costs nothing.
+    fn profiling_enabled(&self) -> bool {
+        false
+    }
+
+    /// Called at the start of each statement executed when `profiling_enabled()` is true.
+    ///
+    /// `stack` holds the names of the functions on the call stack, outermost first, so
+    /// `stack[0]` is `<module>`; `line` is the line of the statement. Does nothing by default.
+    fn on_profile_statement(&mut self, _stack: &[&str], _line: u16) {}
+}
+
+/// Why execution stopped with `RunProgress::Paused`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PauseReason {
+    /// `max_duration` was exceeded with `pause_on_timeout` enabled.
+    Timeout,
+    /// The tracker's `on_statement` requested a checkpoint, e.g. because of
+    /// `ResourceLimits::checkpoint_every`. Execution stopped at the start of a statement.
+    Checkpoint,
+    /// The trace hook's `pause_requested` returned true, e.g. at a debugger breakpoint.
+    /// Execution stopped at the start of the statement that was just reported.
+    Breakpoint,
+}
+
+/// Slot usage of the heap arena, reported through `ResourceTracker::on_heap_slots`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HeapSlotStats {
+    /// Slots reserved by the arena, live or free.
+    pub total_slots: usize,
+    /// Freed slots waiting to be reused by the next allocations.
+    pub free_slots: usize,
+}
+
+impl HeapSlotStats {
+    /// Number of slots holding a live value.
+    #[must_use]
+    pub fn live_slots(&self) -> usize {
+        self.total_slots - self.free_slots
+    }
+
+    /// Fraction of reserved slots that are free, from `0.0` (fully packed) to `1.0`.
+    #[must_use]
+    pub fn fragmentation(&self) -> f64 {
+        if self.total_slots == 0 {
+            0.0
+        } else {
+            self.free_slots as f64 / self.total_slots as f64
+        }
+    }
+}
+
+/// Which values a garbage collection examined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GcKind {
+    /// Only values allocated since the previous collection.
+    Young,
+    /// The whole heap, marked from the VM's roots.
+    Full,
+}
+
+/// Statistics for a single garbage collection, reported through `ResourceTracker::on_gc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GcStats {
+    pub kind: GcKind,
+    /// Number of values (young collections) or heap slots (full collections) examined.
+    pub examined: usize,
+    /// Number of unreachable values freed.
+    pub freed: usize,
+    /// Time execution was paused for the collection.
+    pub pause: Duration,
+}
+
+/// Running garbage collection totals kept by `LimitedTracker` and `MeteredTracker`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GcTotals {
+    pub young_collections: usize,
+    pub full_collections: usize,
+    /// Values freed across all collections.
+    pub freed: usize,
+    /// Sum of all collection pauses.
+    pub total_pause: Duration,
+    /// Longest single collection pause.
+    pub max_pause: Duration,
+}
+
+impl GcTotals {
+    /// Adds one collection to the totals.
+    pub fn record(&mut self, stats: &GcStats) {
+        match stats.kind {
+            GcKind::Young => self.young_collections += 1,
+            GcKind::Full => self.full_collections += 1,
+        }
+        self.freed += stats.freed;
+        self.total_pause += stats.pause;
+        self.max_pause = self.max_pause.max(stats.pause);
+    }
 }
 
 /// A resource tracker that imposes no limits except default recursion limit.
@@ -374,12 +604,41 @@ pub struct ResourceLimits {
     pub max_allocations: Option<usize>,
     /// Maximum execution time.
     pub max_duration: Option<Duration>,
+    /// Maximum number of execution steps (bytecode instructions plus loop iterations
+    /// inside builtins). Unlike `max_duration`, this bounds work deterministically.
+    pub max_steps: Option<u64>,
     /// Maximum heap memory in bytes (approximate).
     pub max_memory: Option<usize>,
     /// Run garbage collection every N allocations.
     pub gc_interval: Option<usize>,
     /// Maximum recursion depth (function call stack depth).
     pub max_recursion_depth: Option<usize>,
+    /// Seed for the `random` module, making its results reproducible across runs.
+    pub random_seed: Option<u64>,
+    /// Pause with `RunProgress::Paused` when `max_duration` is exceeded instead of raising
+    /// `TimeoutError`, so the host can resume later with a fresh time budget.
+    pub pause_on_timeout: bool,
+    /// Remove all sources of nondeterminism, so a snapshot replays to the same result every time.
+    ///
+    /// In deterministic mode:
+    /// - `max_duration` is measured on a virtual clock where each execution step takes one
+    ///   microsecond, so a timeout always happens at the same instruction.
+    /// - The `random` module is seeded with `random_seed`, or zero if none is set.
+    /// - Builtins whose result can't be reproduced raise `RuntimeError`: `time.time()`,
+    ///   `datetime.now()`, `date.today()` and `random.seed()` without an argument.
+    ///
+    /// Hash values use fixed keys and dicts and sets iterate in insertion order in every
+    /// mode, so those are already reproducible.
+    pub deterministic: bool,
+    /// Maximum size in bytes of a snapshot written by `dump()`, after compression.
+    ///
+    /// Dumping a larger snapshot fails with `SnapshotError::TooLarge`, so a host storing
+    /// snapshots can't be made to store arbitrarily large ones by sandboxed code.
+    pub max_snapshot_size: Option<usize>,
+    /// Stop with a checkpoint, `RunProgress::Paused` with `PauseReason::Checkpoint`, every
+    /// N statements executed. Dumping each checkpoint lets a long-running job resume from
+    /// the last one after a crash. Must be nonzero.
+    pub checkpoint_every: Option<u64>,
 }
 
 /// Recommended maximum recursion depth if not otherwise specified.
@@ -409,6 +668,13 @@ impl ResourceLimits {
         self
     }
 
+    /// Sets the maximum number of execution steps.
+    #[must_use]
+    pub fn max_steps(mut self, limit: u64) -> Self {
+        self.max_steps = Some(limit);
+        self
+    }
+
     /// Sets the maximum memory usage in bytes.
     #[must_use]
     pub fn max_memory(mut self, limit: usize) -> Self {
@@ -429,11 +695,75 @@ impl ResourceLimits {
         self.max_recursion_depth = limit;
         self
     }
+
+    /// Sets the seed for the `random` module.
+    #[must_use]
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = Some(seed);
+        self
+    }
+
+    /// Sets whether exceeding `max_duration` pauses execution instead of raising `TimeoutError`.
+    #[must_use]
+    pub fn pause_on_timeout(mut self, pause: bool) -> Self {
+        self.pause_on_timeout = pause;
+        self
+    }
+
+    /// Sets whether execution runs in deterministic mode, see the `deterministic` field.
+    #[must_use]
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Sets the maximum size in bytes of a snapshot written by `dump()`.
+    #[must_use]
+    pub fn max_snapshot_size(mut self, limit: usize) -> Self {
+        self.max_snapshot_size = Some(limit);
+        self
+    }
+
+    /// Sets how many statements to execute between checkpoints.
+    #[must_use]
+    pub fn checkpoint_every(mut self, statements: u64) -> Self {
+        self.checkpoint_every = Some(statements);
+        self
+    }
+}
+
+/// Monotonic clock used to enforce time limits and measure durations.
+///
+/// Returns the time since an arbitrary fixed origin; only differences between readings
+/// are used. Inject one with `LimitedTracker::with_clock` on targets without
+/// `std::time::Instant`, such as `wasm32-unknown-unknown`.
+pub type Clock = fn() -> Duration;
+
+/// The default `Clock`, backed by `std::time::Instant`.
+///
+/// On `wasm32-unknown-unknown`, where `Instant::now()` panics, this always returns zero,
+/// so time limits never trigger unless another clock is injected.
+#[must_use]
+pub fn monotonic_clock() -> Duration {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        static ORIGIN: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        ORIGIN.get_or_init(std::time::Instant::now).elapsed()
+    }
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        Duration::ZERO
+    }
 }
 
-/// How often to actually check `Instant::elapsed()` in `check_time`.
+/// Serde default for skipped `Clock` fields.
+fn default_clock() -> Clock {
+    monotonic_clock
+}
+
+/// How often to actually read the clock in `check_time`.
 ///
-/// Calling `Instant::elapsed()` on every `check_time` invocation adds measurable
+/// Reading the clock on every `check_time` invocation adds measurable
 /// overhead in tight loops (the VM calls `check_time` on every instruction).
 /// By only checking every N calls, we reduce this overhead while still catching
 /// timeouts promptly.
@@ -441,28 +771,46 @@ const TIME_CHECK_INTERVAL: u16 = 10;
 
 /// A resource tracker that enforces configurable limits.
 ///
-/// Tracks allocation count, memory usage, and execution time, returning
+/// Tracks allocation count, memory usage, execution steps and time, returning
 /// errors when limits are exceeded. Also schedules garbage collection
 /// at configurable intervals.
 ///
-/// When serialized/deserialized, the `start_time` is reset to `Instant::now()`.
-/// This means time limits restart from zero after deserialization.
+/// When serialized/deserialized, the `start_time` is reset to now and the clock to
+/// `monotonic_clock`. This means time limits restart from zero after deserialization,
+/// and an injected clock must be injected again. In deterministic mode time is counted
+/// in steps instead, which survives serialization.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct LimitedTracker {
     limits: ResourceLimits,
-    /// When execution started (for time limit checking).
-    /// Reset to `Instant::now()` on deserialization.
-    #[serde(skip, default = "Instant::now")]
-    start_time: Instant,
+    /// Clock used for time limit checking, `monotonic_clock` unless injected.
+    #[serde(skip, default = "default_clock")]
+    clock: Clock,
+    /// Clock reading when execution started (for time limit checking).
+    /// Reset to now on deserialization.
+    #[serde(skip, default = "monotonic_clock")]
+    start_time: Duration,
     /// Total number of allocations made.
     allocation_count: usize,
     /// Current approximate memory usage in bytes.
     current_memory: usize,
-    /// Counter for rate-limiting `Instant::elapsed()` calls in `check_time`.
+    /// Heap slot usage as of the most recent allocation or garbage collection.
+    heap_slots: HeapSlotStats,
+    /// Garbage collection counts and pause times.
+    gc_totals: GcTotals,
+    /// Counter for rate-limiting clock reads in `check_time`.
     ///
     /// Uses `AtomicU16` for interior mutability since `check_time` takes `&self`
     /// and `LimitedTracker` must be `Sync` (it ends up inside PyO3 pyclass types).
     check_counter: AtomicU16,
+    /// Number of `check_time` calls so far, compared against `max_steps`.
+    ///
+    /// Atomic for the same reason as `check_counter`.
+    steps: AtomicU64,
+    /// Step count when the time budget started, used as the virtual clock's start time
+    /// in deterministic mode.
+    virtual_start: u64,
+    /// Number of statements started, counted only when `checkpoint_every` is set.
+    statements: u64,
 }
 
 impl LimitedTracker {
@@ -474,13 +822,30 @@ impl LimitedTracker {
     pub fn new(limits: ResourceLimits) -> Self {
         Self {
             limits,
-            start_time: Instant::now(),
+            clock: monotonic_clock,
+            start_time: monotonic_clock(),
             allocation_count: 0,
             current_memory: 0,
+            heap_slots: HeapSlotStats::default(),
+            gc_totals: GcTotals::default(),
             check_counter: AtomicU16::new(0),
+            steps: AtomicU64::new(0),
+            virtual_start: 0,
+            statements: 0,
         }
     }
 
+    /// Replaces the clock used for time limits and restarts the elapsed time.
+    ///
+    /// Needed for `max_duration` on targets where `std::time::Instant` is unavailable,
+    /// e.g. a clock backed by `performance.now()` on `wasm32-unknown-unknown`.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self.start_time = clock();
+        self
+    }
+
     /// Returns the current allocation count.
     #[must_use]
     pub fn allocation_count(&self) -> usize {
@@ -493,10 +858,46 @@ impl LimitedTracker {
         self.current_memory
     }
 
+    /// Returns the heap slot usage as of the most recent allocation or garbage collection.
+    #[must_use]
+    pub fn heap_slots(&self) -> HeapSlotStats {
+        self.heap_slots
+    }
+
+    /// Returns garbage collection counts and pause times so far.
+    #[must_use]
+    pub fn gc_totals(&self) -> GcTotals {
+        self.gc_totals
+    }
+
+    /// Returns the number of execution steps taken so far.
+    #[must_use]
+    pub fn steps(&self) -> u64 {
+        self.steps.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of statements started so far, counted only when `checkpoint_every` is set.
+    #[must_use]
+    pub fn statements(&self) -> u64 {
+        self.statements
+    }
+
     /// Returns the elapsed time since tracker creation.
+    ///
+    /// In deterministic mode this is virtual time: one microsecond per execution step.
     #[must_use]
     pub fn elapsed(&self) -> Duration {
-        self.start_time.elapsed()
+        if self.limits.deterministic {
+            Duration::from_micros(self.steps().saturating_sub(self.virtual_start))
+        } else {
+            (self.clock)().saturating_sub(self.start_time)
+        }
+    }
+
+    /// Restarts the elapsed time from zero.
+    fn restart_elapsed(&mut self) {
+        self.start_time = (self.clock)();
+        self.virtual_start = self.steps();
     }
 
     /// Sets the maximum execution duration and resets the start time to now.
@@ -506,7 +907,19 @@ impl LimitedTracker {
     /// for the resumed phase without counting the time spent in the host.
     pub fn set_max_duration(&mut self, duration: Duration) {
         self.limits.max_duration = Some(duration);
-        self.start_time = Instant::now();
+        self.restart_elapsed();
+    }
+
+    /// Resets elapsed time, the step count and the allocation count to zero.
+    ///
+    /// Memory usage is left alone since it reflects objects that are still alive.
+    /// Call this before each snippet fed to a `MontyRepl` to apply the time, step
+    /// and allocation limits per snippet rather than to the whole session.
+    pub fn reset_budget(&mut self) {
+        self.allocation_count = 0;
+        self.check_counter.store(0, Ordering::Relaxed);
+        self.steps.store(0, Ordering::Relaxed);
+        self.restart_elapsed();
     }
 }
 
@@ -545,12 +958,29 @@ impl ResourceTracker for LimitedTracker {
         self.current_memory = self.current_memory.saturating_sub(get_size());
     }
 
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        self.heap_slots = stats;
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        self.gc_totals.record(&stats);
+    }
+
     fn check_time(&self) -> Result<(), ResourceError> {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max) = self.limits.max_steps
+            && steps > max
+        {
+            return Err(ResourceError::Steps {
+                limit: max,
+                count: steps,
+            });
+        }
         if let Some(max) = self.limits.max_duration {
             let count = self.check_counter.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
             if count.is_multiple_of(TIME_CHECK_INTERVAL) {
-                // Only call Instant::elapsed() every TIME_CHECK_INTERVAL calls
-                let elapsed = self.start_time.elapsed();
+                // Only read the clock every TIME_CHECK_INTERVAL calls
+                let elapsed = self.elapsed();
                 if elapsed > max {
                     // Reset counter so the very next check_time call also triggers
                     // an elapsed check. This is important because some callers
@@ -591,4 +1021,404 @@ impl ResourceTracker for LimitedTracker {
         }
         Ok(())
     }
+
+    fn random_seed(&self) -> Option<u64> {
+        match self.limits.random_seed {
+            None if self.limits.deterministic => Some(0),
+            seed => seed,
+        }
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        self.limits.pause_on_timeout
+    }
+
+    fn restart_timer(&mut self) {
+        self.restart_elapsed();
+        self.check_counter.store(0, Ordering::Relaxed);
+    }
+
+    fn deterministic(&self) -> bool {
+        self.limits.deterministic
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        self.limits.max_snapshot_size
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        self.limits.checkpoint_every.is_some()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.statements += 1;
+        self.limits
+            .checkpoint_every
+            .is_some_and(|every| self.statements.is_multiple_of(every))
+    }
+}
+
+/// Handle that lets a host thread cancel a running execution.
+///
+/// Clones share the same flag, so keep one clone on the host side and give another to
+/// `CancellableTracker` (or `MontyRun::run_with_cancel`). Cancellation is checked every time
+/// the VM checks its time limit, i.e. before each instruction and inside long-running builtins,
+/// and surfaces as an uncatchable `KeyboardInterrupt`.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that has not been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of every execution watching this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether `cancel` has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Resource tracker wrapper that aborts execution once its `CancelToken` is cancelled.
+///
+/// All other checks are delegated to the wrapped tracker. The token is not serialized:
+/// a deserialized tracker gets a fresh, uncancelled token, which can be replaced with
+/// `set_token`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CancellableTracker<T> {
+    inner: T,
+    #[serde(skip)]
+    token: CancelToken,
+}
+
+impl<T: ResourceTracker> CancellableTracker<T> {
+    /// Wraps `inner` so execution stops as soon as `token` is cancelled.
+    #[must_use]
+    pub fn new(inner: T, token: CancelToken) -> Self {
+        Self { inner, token }
+    }
+
+    /// Returns the cancellation token being watched.
+    #[must_use]
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    /// Replaces the cancellation token, e.g. after loading a snapshot.
+    pub fn set_token(&mut self, token: CancelToken) {
+        self.token = token;
+    }
+
+    /// Returns the wrapped tracker.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the wrapped tracker mutably.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps into the wrapped tracker.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: ResourceTracker> ResourceTracker for CancellableTracker<T> {
+    fn on_allocate(&mut self, get_size: impl FnOnce() -> usize) -> Result<(), ResourceError> {
+        self.inner.on_allocate(get_size)
+    }
+
+    fn on_free(&mut self, get_size: impl FnOnce() -> usize) {
+        self.inner.on_free(get_size);
+    }
+
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        self.inner.on_heap_slots(stats);
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        self.inner.on_gc(stats);
+    }
+
+    #[inline]
+    fn check_time(&self) -> Result<(), ResourceError> {
+        if self.token.is_cancelled() {
+            return Err(ResourceError::Cancelled);
+        }
+        self.inner.check_time()
+    }
+
+    fn check_recursion_depth(&self, current_depth: usize) -> Result<(), ResourceError> {
+        self.inner.check_recursion_depth(current_depth)
+    }
+
+    fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError> {
+        self.inner.check_large_result(estimated_bytes)
+    }
+
+    fn random_seed(&self) -> Option<u64> {
+        self.inner.random_seed()
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        self.inner.pause_on_timeout()
+    }
+
+    fn restart_timer(&mut self) {
+        self.inner.restart_timer();
+    }
+
+    fn deterministic(&self) -> bool {
+        self.inner.deterministic()
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        self.inner.max_snapshot_size()
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        self.inner.checkpoints_enabled()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.inner.on_statement()
+    }
+
+    fn profiling_enabled(&self) -> bool {
+        self.inner.profiling_enabled()
+    }
+
+    fn on_profile_statement(&mut self, stack: &[&str], line: u16) {
+        self.inner.on_profile_statement(stack, line);
+    }
+}
+
+/// Usage figures for one execution, collected by `MeteredTracker`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RunMetrics {
+    /// Number of heap allocations made.
+    pub allocations: usize,
+    /// Highest approximate heap memory usage in bytes.
+    pub peak_memory: usize,
+    /// Approximate heap memory still in use when the metrics were taken.
+    pub current_memory: usize,
+    /// Number of execution steps, as counted by `ResourceLimits::max_steps`.
+    pub steps: u64,
+    /// Garbage collection counts and pause times.
+    pub gc: GcTotals,
+    /// Wall-clock time since the tracker was created (or deserialized).
+    pub elapsed: Duration,
+}
+
+/// Resource tracker wrapper that records `RunMetrics` for hosts to bill or monitor executions.
+///
+/// All limits are delegated to the wrapped tracker; metering never fails an execution.
+/// Pass `&mut MeteredTracker` as the tracker to read the metrics after a run, or use
+/// `MontyRun::run_with_metrics`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct MeteredTracker<T> {
+    inner: T,
+    /// `monotonic_clock` reading at creation, reset on deserialization like `LimitedTracker::start_time`.
+    #[serde(skip, default = "monotonic_clock")]
+    start_time: Duration,
+    allocations: usize,
+    current_memory: usize,
+    peak_memory: usize,
+    gc: GcTotals,
+    /// Atomic because `check_time` takes `&self`.
+    steps: AtomicU64,
+}
+
+impl<T: ResourceTracker> MeteredTracker<T> {
+    /// Wraps `inner`, starting the elapsed time clock now.
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            start_time: monotonic_clock(),
+            allocations: 0,
+            current_memory: 0,
+            peak_memory: 0,
+            gc: GcTotals::default(),
+            steps: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the metrics collected so far.
+    #[must_use]
+    pub fn metrics(&self) -> RunMetrics {
+        RunMetrics {
+            allocations: self.allocations,
+            peak_memory: self.peak_memory,
+            current_memory: self.current_memory,
+            steps: self.steps.load(Ordering::Relaxed),
+            gc: self.gc,
+            elapsed: monotonic_clock().saturating_sub(self.start_time),
+        }
+    }
+
+    /// Returns the wrapped tracker.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwraps into the wrapped tracker.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: ResourceTracker> ResourceTracker for MeteredTracker<T> {
+    fn on_allocate(&mut self, get_size: impl FnOnce() -> usize) -> Result<(), ResourceError> {
+        let size = get_size();
+        self.inner.on_allocate(|| size)?;
+        self.allocations += 1;
+        self.current_memory += size;
+        self.peak_memory = self.peak_memory.max(self.current_memory);
+        Ok(())
+    }
+
+    fn on_free(&mut self, get_size: impl FnOnce() -> usize) {
+        let size = get_size();
+        self.current_memory = self.current_memory.saturating_sub(size);
+        self.inner.on_free(|| size);
+    }
+
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        self.inner.on_heap_slots(stats);
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        self.gc.record(&stats);
+        self.inner.on_gc(stats);
+    }
+
+    fn check_time(&self) -> Result<(), ResourceError> {
+        self.steps.fetch_add(1, Ordering::Relaxed);
+        self.inner.check_time()
+    }
+
+    fn check_recursion_depth(&self, current_depth: usize) -> Result<(), ResourceError> {
+        self.inner.check_recursion_depth(current_depth)
+    }
+
+    fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError> {
+        self.inner.check_large_result(estimated_bytes)
+    }
+
+    fn random_seed(&self) -> Option<u64> {
+        self.inner.random_seed()
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        self.inner.pause_on_timeout()
+    }
+
+    fn restart_timer(&mut self) {
+        self.inner.restart_timer();
+    }
+
+    fn deterministic(&self) -> bool {
+        self.inner.deterministic()
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        self.inner.max_snapshot_size()
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        self.inner.checkpoints_enabled()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.inner.on_statement()
+    }
+
+    fn profiling_enabled(&self) -> bool {
+        self.inner.profiling_enabled()
+    }
+
+    fn on_profile_statement(&mut self, stack: &[&str], line: u16) {
+        self.inner.on_profile_statement(stack, line);
+    }
+}
+
+/// Lets a tracker be lent to an execution so the host can inspect it afterwards.
+impl<T: ResourceTracker> ResourceTracker for &mut T {
+    fn on_allocate(&mut self, get_size: impl FnOnce() -> usize) -> Result<(), ResourceError> {
+        (**self).on_allocate(get_size)
+    }
+
+    fn on_free(&mut self, get_size: impl FnOnce() -> usize) {
+        (**self).on_free(get_size);
+    }
+
+    fn on_heap_slots(&mut self, stats: HeapSlotStats) {
+        (**self).on_heap_slots(stats);
+    }
+
+    fn on_gc(&mut self, stats: GcStats) {
+        (**self).on_gc(stats);
+    }
+
+    fn check_time(&self) -> Result<(), ResourceError> {
+        (**self).check_time()
+    }
+
+    fn check_recursion_depth(&self, current_depth: usize) -> Result<(), ResourceError> {
+        (**self).check_recursion_depth(current_depth)
+    }
+
+    fn check_large_result(&self, estimated_bytes: usize) -> Result<(), ResourceError> {
+        (**self).check_large_result(estimated_bytes)
+    }
+
+    fn random_seed(&self) -> Option<u64> {
+        (**self).random_seed()
+    }
+
+    fn pause_on_timeout(&self) -> bool {
+        (**self).pause_on_timeout()
+    }
+
+    fn restart_timer(&mut self) {
+        (**self).restart_timer();
+    }
+
+    fn deterministic(&self) -> bool {
+        (**self).deterministic()
+    }
+
+    fn max_snapshot_size(&self) -> Option<usize> {
+        (**self).max_snapshot_size()
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        (**self).checkpoints_enabled()
+    }
+
+    fn on_statement(&mut self) -> bool {
+        (**self).on_statement()
+    }
+
+    fn profiling_enabled(&self) -> bool {
+        (**self).profiling_enabled()
+    }
+
+    fn on_profile_statement(&mut self, stack: &[&str], line: u16) {
+        (**self).on_profile_statement(stack, line);
+    }
 }
@@ -1,23 +1,109 @@
 //! Public interface for running Monty code.
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    borrow::Cow,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use indexmap::IndexMap;
 
 use crate::{
     ExcType, MontyException,
+    analysis::{self, Analysis},
+    ast_json,
     asyncio::CallId,
-    bytecode::{Code, Compiler, FrameExit, VM, VMSnapshot},
+    bytecode::{Code, CompileOptions, Compiler, FrameExit, VM, VMSnapshot},
+    coverage::Coverage,
     exception_private::RunResult,
-    heap::{DropWithHeap, Heap},
+    external_fn::ExternalFunctions,
+    heap::{DropWithHeap, Heap, HeapData},
     intern::{ExtFunctionId, Interns},
-    io::PrintWriter,
-    namespace::Namespaces,
+    io::{CapturedOutput, PrintWriter},
+    memory::{self, HeapDump, HeapReport},
+    namespace::{GLOBAL_NS_IDX, NamespaceId, Namespaces},
+    native_module::{NativeFunction, NativeModule, register_modules},
     object::MontyObject,
     os::OsFunction,
     parse::parse,
     prepare::prepare,
-    resource::{NoLimitTracker, ResourceTracker},
+    profile::{ProfileReport, ProfilingTracker},
+    python_module::{PythonModule, compile_python_modules, prepare_python_modules},
+    resource::{
+        CancelToken, CancellableTracker, MeteredTracker, NoLimitTracker, PauseReason, ResourceTracker, RunMetrics,
+    },
+    schema::ResultSchema,
+    snapshot_format::{
+        SnapshotCompression, SnapshotError, SnapshotStats, encoded_size, from_snapshot_bytes, from_snapshot_bytes_with,
+        to_snapshot_bytes_with,
+    },
+    trace::TraceHook,
     value::Value,
 };
 
+/// Options controlling the `eval()` and `exec()` builtins.
+///
+/// Running dynamically built code is disabled by default: calling `eval()` or `exec()`
+/// raises `RuntimeError` unless enabled with `ExecutionOptions::default().allow_eval(true)`
+/// and `MontyRun::with_execution_options`. The code runs in the same sandbox, heap and
+/// resource tracker as the code calling it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExecutionOptions {
+    /// Whether `eval()` and `exec()` can be called. Disabled by default.
+    pub allow_eval: bool,
+    /// Maximum number of `eval()` and `exec()` calls running inside each other.
+    pub max_eval_depth: usize,
+    /// Maximum length in bytes of the source passed to `eval()` or `exec()`.
+    pub max_eval_source_len: usize,
+    /// Maximum number of distinct sources `eval()` and `exec()` compile in one run.
+    ///
+    /// Compiled code is kept until the run ends so later calls can reuse it, and counts
+    /// towards the memory limit.
+    pub max_eval_compiled: usize,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self {
+            allow_eval: false,
+            max_eval_depth: 8,
+            max_eval_source_len: 10_000,
+            max_eval_compiled: 1_000,
+        }
+    }
+}
+
+impl ExecutionOptions {
+    /// Sets whether `eval()` and `exec()` can be called.
+    #[must_use]
+    pub fn allow_eval(mut self, allow: bool) -> Self {
+        self.allow_eval = allow;
+        self
+    }
+
+    /// Sets the maximum number of `eval()` and `exec()` calls running inside each other.
+    #[must_use]
+    pub fn max_eval_depth(mut self, depth: usize) -> Self {
+        self.max_eval_depth = depth;
+        self
+    }
+
+    /// Sets the maximum length in bytes of the source passed to `eval()` or `exec()`.
+    #[must_use]
+    pub fn max_eval_source_len(mut self, len: usize) -> Self {
+        self.max_eval_source_len = len;
+        self
+    }
+
+    /// Sets the maximum number of distinct sources `eval()` and `exec()` compile in one run.
+    #[must_use]
+    pub fn max_eval_compiled(mut self, count: usize) -> Self {
+        self.max_eval_compiled = count;
+        self
+    }
+}
+
 /// Primary interface for running Monty code.
 ///
 /// `MontyRun` supports two execution modes:
@@ -33,10 +119,17 @@ use crate::{
 /// let result = runner.run_no_limits(vec![MontyObject::Int(41)]).unwrap();
 /// assert_eq!(result, MontyObject::Int(42));
 /// ```
+///
+/// # Sharing between threads
+/// `MontyRun` is `Send + Sync` and cloning it is cheap: clones share the compiled program.
+/// Each call to `run()` creates its own heap and uses the tracker it's given, so the same
+/// program can be run concurrently from many threads, e.g. via `Arc<MontyRun>` or
+/// `std::thread::scope`. `start()` takes ownership of the program for the snapshot, and
+/// only copies it if other clones are still alive.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MontyRun {
-    /// The underlying executor containing parsed AST and interns.
-    executor: Executor,
+    /// The underlying executor containing parsed AST and interns, shared between clones.
+    executor: Arc<Executor>,
 }
 
 impl MontyRun {
@@ -58,7 +151,137 @@ impl MontyRun {
         input_names: Vec<String>,
         external_functions: Vec<String>,
     ) -> Result<Self, MontyException> {
-        Executor::new(code, script_name, input_names, external_functions).map(|executor| Self { executor })
+        Self::new_with_options(
+            code,
+            script_name,
+            input_names,
+            external_functions,
+            CompileOptions::default(),
+        )
+    }
+
+    /// Creates a new run snapshot like `new()`, but with custom compile options.
+    ///
+    /// For example, `CompileOptions::default().strip_asserts(true)` removes all `assert`
+    /// statements, like running CPython with `-O`.
+    ///
+    /// # Errors
+    /// Returns `MontyException` if the code cannot be parsed.
+    pub fn new_with_options(
+        code: String,
+        script_name: &str,
+        input_names: Vec<String>,
+        external_functions: Vec<String>,
+        options: CompileOptions,
+    ) -> Result<Self, MontyException> {
+        Self::new_with_modules(
+            code,
+            script_name,
+            input_names,
+            external_functions,
+            Vec::new(),
+            Vec::new(),
+            options,
+        )
+    }
+
+    /// Creates a new run snapshot like `new_with_options()`, with host-provided native
+    /// modules and pure-Python modules that the code can `import`.
+    ///
+    /// Calls to native module functions run the Rust implementation in place, so they
+    /// work with `run()` as well as `start()`. Implementations are not serialized: after
+    /// `load()`, such calls are returned to the host as `RunProgress::FunctionCall` with
+    /// the qualified name, e.g. `"units.convert"`.
+    ///
+    /// Python modules are compiled along with `code`. Each module body runs in its own
+    /// namespace the first time it is imported during a run, and later imports reuse the
+    /// resulting module.
+    ///
+    /// # Errors
+    /// Returns `MontyException` if the code or a Python module cannot be parsed, or if two
+    /// modules share a name.
+    pub fn new_with_modules(
+        code: String,
+        script_name: &str,
+        input_names: Vec<String>,
+        external_functions: Vec<String>,
+        modules: Vec<NativeModule>,
+        python_modules: Vec<PythonModule>,
+        options: CompileOptions,
+    ) -> Result<Self, MontyException> {
+        Executor::new(
+            code,
+            script_name,
+            input_names,
+            external_functions,
+            modules,
+            python_modules,
+            options,
+        )
+        .map(|executor| Self {
+            executor: Arc::new(executor),
+        })
+    }
+
+    /// Checks code without compiling or running it, so hosts can reject bad code before
+    /// paying for execution.
+    ///
+    /// Reports every unsupported feature and syntax error with its location, rather than
+    /// just the first like `new()`. Once the code parses, also reports names that are read
+    /// but bound nowhere, calls to such names, which are often external functions missing
+    /// from `external_functions`, and inputs the code never refers to. Names bound by code
+    /// run with `exec()` aren't known, so reads of them are reported.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::{DiagnosticKind, MontyRun};
+    ///
+    /// let code = "def main():\n    return fetch(url) + total\n\nmain()";
+    /// let analysis = MontyRun::analyze(code, "test.py", &["url".to_owned(), "limit".to_owned()], &[]);
+    /// let kinds: Vec<_> = analysis.diagnostics.iter().map(|diagnostic| diagnostic.kind).collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [DiagnosticKind::UnknownFunction, DiagnosticKind::UndefinedName, DiagnosticKind::UnusedInput]
+    /// );
+    /// assert_eq!(analysis.diagnostics[1].to_string(), "test.py:2:25: name 'total' is not defined");
+    /// ```
+    #[must_use]
+    pub fn analyze(code: &str, script_name: &str, input_names: &[String], external_functions: &[String]) -> Analysis {
+        analysis::analyze(code, script_name, input_names, external_functions)
+    }
+
+    /// Parses and prepares code without compiling or running it, and returns its AST as JSON.
+    ///
+    /// The format follows Python's `ast` module: every node is an object with a `"type"`,
+    /// like `"Assign"` or `"Call"`, and the fields `ast` uses. Nodes Monty keeps the source
+    /// range of have a `"span"` of 1-based `"start"` and `"end"` lines and columns. Each
+    /// `"Name"` also has the `"scope"` it was resolved to, one of `"local"`, `"global"`,
+    /// `"cell"`, `"builtin"`, `"exception_class"` or `"undefined"`, and its namespace
+    /// `"slot"`, which the `"slot_names"` of the module or enclosing function names.
+    ///
+    /// # Errors
+    /// Returns the `SyntaxError` or `NotImplementedError` `new()` would for code that doesn't
+    /// parse or uses unsupported features.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::MontyRun;
+    ///
+    /// let ast = MontyRun::ast_json("x = len(y)", "test.py", &["y".to_owned()], &[]).unwrap();
+    /// let assign = &ast["body"][0];
+    /// assert_eq!(assign["type"], "Assign");
+    /// assert_eq!(assign["targets"][0]["id"], "x");
+    /// assert_eq!(assign["value"]["func"]["scope"], "builtin");
+    /// assert_eq!(assign["value"]["args"][0]["scope"], "global");
+    /// assert_eq!(assign["value"]["span"]["start"], serde_json::json!({"line": 1, "column": 5}));
+    /// ```
+    pub fn ast_json(
+        code: &str,
+        script_name: &str,
+        input_names: &[String],
+        external_functions: &[String],
+    ) -> Result<serde_json::Value, MontyException> {
+        ast_json::ast_json(code, script_name, input_names, external_functions)
     }
 
     /// Returns the code that was parsed to create this snapshot.
@@ -67,6 +290,40 @@ impl MontyRun {
         &self.executor.code
     }
 
+    /// Sets the options controlling `eval()` and `exec()`, see `ExecutionOptions`.
+    ///
+    /// The options are kept by `dump()` and `load()`.
+    #[must_use]
+    pub fn with_execution_options(mut self, options: ExecutionOptions) -> Self {
+        Arc::make_mut(&mut self.executor).interns.set_execution_options(options);
+        self
+    }
+
+    /// Sets the expected type of the result, see `ResultSchema`.
+    ///
+    /// A run whose result doesn't match fails with a `TypeError`, and
+    /// `MontyException::schema_mismatch` says where and why. The schema is checked once the
+    /// code completes, whether it was run with `run()` or `start()`, and is kept by `dump()`
+    /// and `load()`.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::{MontyObject, MontyRun};
+    ///
+    /// let runner = MontyRun::new("[1, 'two']".to_owned(), "test.py", vec![], vec![])
+    ///     .unwrap()
+    ///     .with_result_schema("list[int]".parse().unwrap());
+    /// let err = runner.run_no_limits(vec![]).unwrap_err();
+    /// let mismatch = err.schema_mismatch().unwrap();
+    /// assert_eq!(mismatch.path, "result[1]");
+    /// assert_eq!(mismatch.actual, "str");
+    /// ```
+    #[must_use]
+    pub fn with_result_schema(mut self, schema: ResultSchema) -> Self {
+        Arc::make_mut(&mut self.executor).result_schema = Some(schema);
+        self
+    }
+
     /// Executes the code and returns both the result and reference count data, used for testing only.
     #[cfg(feature = "ref-count-return")]
     pub fn run_ref_counts(&self, inputs: Vec<MontyObject>) -> Result<RefCountOutput, MontyException> {
@@ -88,7 +345,297 @@ impl MontyRun {
         resource_tracker: impl ResourceTracker,
         print: &mut PrintWriter<'_>,
     ) -> Result<MontyObject, MontyException> {
-        self.executor.run(inputs, resource_tracker, print)
+        self.executor
+            .run(inputs, resource_tracker, print, &mut RunOptions::default())
+    }
+
+    /// Executes the code to completion like `run()`, calling `hook` before each statement.
+    ///
+    /// See `TraceHook` for what the hook is told. An error returned by the hook aborts
+    /// execution with that exception, which the code can't catch.
+    pub fn run_with_trace(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+        hook: impl TraceHook + 'static,
+    ) -> Result<MontyObject, MontyException> {
+        let mut options = RunOptions {
+            trace_hook: Some(Box::new(hook)),
+            ..RunOptions::default()
+        };
+        self.executor.run(inputs, resource_tracker, print, &mut options)
+    }
+
+    /// Executes the code to completion like `run()`, counting the statements run on each line.
+    ///
+    /// Returns the coverage along with the result, since it's just as useful when the code
+    /// raised. Lines are counted per file, see `Coverage`. Use a `Coverage` as the hook of
+    /// `run_with_trace()` or `start_with_trace()` to collect coverage of other kinds of runs.
+    pub fn run_with_coverage(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> (Result<MontyObject, MontyException>, Coverage) {
+        let coverage = Coverage::new();
+        let result = self.run_with_trace(inputs, resource_tracker, print, coverage.clone());
+        (result, coverage)
+    }
+
+    /// Executes the code to completion, aborting as soon as `token` is cancelled.
+    ///
+    /// Cancellation is checked before every instruction, so a host thread holding a clone
+    /// of `token` can stop a long-running execution without waiting for a time limit.
+    /// A cancelled run returns an uncatchable `KeyboardInterrupt`; `resource_tracker` enforces
+    /// any other limits.
+    pub fn run_with_cancel(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+        token: CancelToken,
+    ) -> Result<MontyObject, MontyException> {
+        self.run(inputs, CancellableTracker::new(resource_tracker, token), print)
+    }
+
+    /// Executes the code to completion and reports what it used.
+    ///
+    /// The metrics are returned whether or not execution succeeded, so hosts can bill
+    /// or monitor runs that hit a limit too.
+    pub fn run_with_metrics(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> (Result<MontyObject, MontyException>, RunMetrics) {
+        let mut tracker = MeteredTracker::new(resource_tracker);
+        let result = self.run(inputs, &mut tracker, print);
+        (result, tracker.metrics())
+    }
+
+    /// Executes the code to completion and reports where it spent its time and allocations.
+    ///
+    /// Like the metrics of `run_with_metrics()`, the profile is returned whether or not
+    /// execution succeeded, so runs that exceed their time limit can be investigated.
+    pub fn run_with_profile(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> (Result<MontyObject, MontyException>, ProfileReport) {
+        let mut tracker = ProfilingTracker::new(resource_tracker);
+        let result = self.run(inputs, &mut tracker, print);
+        (result, tracker.report())
+    }
+
+    /// Executes the code to completion and reports what was left on the heap.
+    ///
+    /// The report is taken once execution ends, with the global variables as retainers, and
+    /// is returned whether or not execution succeeded, so scripts that exceed `max_memory`
+    /// can be investigated. Values only held by function locals are freed when an exception
+    /// unwinds their frames, so use `PausedSnapshot::heap_report` to look inside running
+    /// functions.
+    pub fn run_with_heap_report(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> (Result<MontyObject, MontyException>, HeapReport) {
+        let mut report = HeapReport::default();
+        let mut options = RunOptions {
+            heap_report: Some(&mut report),
+            ..RunOptions::default()
+        };
+        let result = self.executor.run(inputs, resource_tracker, print, &mut options);
+        (result, report)
+    }
+
+    /// Executes the code to completion like `run()`, also returning the final values of the
+    /// globals named in `names`.
+    ///
+    /// This saves scripts from ending with a tuple of everything the host needs. Names that
+    /// aren't bound globals once the code completes, e.g. because they were never assigned,
+    /// are left out of `CollectedRun::variables`.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::{MontyObject, MontyRun, NoLimitTracker, PrintWriter};
+    ///
+    /// let code = "errors = []\nresult = 6 * 7";
+    /// let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    /// let collected = runner
+    ///     .run_collect(vec![], &["result", "errors", "stats"], NoLimitTracker, &mut PrintWriter::Stdout)
+    ///     .unwrap();
+    /// assert_eq!(collected.variables["result"], MontyObject::Int(42));
+    /// assert_eq!(collected.variables["errors"], MontyObject::List(vec![]));
+    /// assert!(!collected.variables.contains_key("stats"));
+    /// ```
+    pub fn run_collect(
+        &self,
+        inputs: Vec<MontyObject>,
+        names: &[&str],
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> Result<CollectedRun, MontyException> {
+        let mut variables = IndexMap::with_capacity(names.len());
+        let mut options = RunOptions {
+            collect: Some((names, &mut variables)),
+            ..RunOptions::default()
+        };
+        let result = self.executor.run(inputs, resource_tracker, print, &mut options)?;
+        Ok(CollectedRun { result, variables })
+    }
+
+    /// Executes the module, then calls the function `name` it defines with `args` and
+    /// `kwargs`, returning what the function returns.
+    ///
+    /// This suits code structured as `def main(payload): ...` better than using the value of
+    /// the last expression. The module's own result is discarded, and the result schema, if
+    /// any, is checked against the function's result. Raises `NameError` if `name` isn't a
+    /// bound global once the module has run.
+    ///
+    /// # Example
+    /// ```
+    /// use monty::{MontyObject, MontyRun, NoLimitTracker, PrintWriter};
+    ///
+    /// let code = "def main(payload, scale=1):\n    return sum(payload) * scale";
+    /// let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    /// let payload = MontyObject::List(vec![MontyObject::Int(1), MontyObject::Int(2)]);
+    /// let result = runner
+    ///     .call_function(
+    ///         vec![],
+    ///         "main",
+    ///         vec![payload],
+    ///         vec![("scale".to_owned(), MontyObject::Int(10))],
+    ///         NoLimitTracker,
+    ///         &mut PrintWriter::Stdout,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(result, MontyObject::Int(30));
+    /// ```
+    ///
+    /// `resource_tracker` is shared by the module and the call.
+    pub fn call_function(
+        &self,
+        inputs: Vec<MontyObject>,
+        name: &str,
+        args: Vec<MontyObject>,
+        kwargs: Vec<(String, MontyObject)>,
+        resource_tracker: impl ResourceTracker,
+        print: &mut PrintWriter<'_>,
+    ) -> Result<MontyObject, MontyException> {
+        let mut options = RunOptions {
+            call: Some((name, args, kwargs)),
+            ..RunOptions::default()
+        };
+        self.executor.run(inputs, resource_tracker, print, &mut options)
+    }
+
+    /// Executes the code to completion, capturing printed output instead of writing it.
+    ///
+    /// Returns the result together with everything printed to stdout and to stderr
+    /// (via `print(..., file=sys.stderr)`). The output is returned whether or not
+    /// execution succeeded.
+    pub fn run_capture(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: impl ResourceTracker,
+    ) -> (Result<MontyObject, MontyException>, String, String) {
+        let mut output = CapturedOutput::default();
+        let result = self.run(inputs, resource_tracker, &mut PrintWriter::Callback(&mut output));
+        (result, output.stdout, output.stderr)
+    }
+
+    /// Executes the code once for each set of inputs, returning the results in order.
+    ///
+    /// The code is parsed and compiled once; every run gets a fresh heap and a fresh
+    /// tracker from `new_tracker`, so runs cannot affect each other. A run that fails
+    /// doesn't stop the remaining runs.
+    ///
+    /// # Arguments
+    /// * `inputs` - One set of input values per run
+    /// * `new_tracker` - Creates the resource tracker for each run
+    /// * `print` - print output writer shared by all runs
+    pub fn run_many<T: ResourceTracker>(
+        &self,
+        inputs: Vec<Vec<MontyObject>>,
+        mut new_tracker: impl FnMut() -> T,
+        print: &mut PrintWriter<'_>,
+    ) -> Vec<Result<MontyObject, MontyException>> {
+        inputs
+            .into_iter()
+            .map(|run_inputs| self.run(run_inputs, new_tracker(), print))
+            .collect()
+    }
+
+    /// Like `run_many()`, but spreads the runs over the rayon thread pool.
+    ///
+    /// Results are returned in the same order as `inputs`. Print output is discarded
+    /// since runs execute concurrently; use `run_capture()` per run if output is needed.
+    ///
+    /// # Arguments
+    /// * `inputs` - One set of input values per run
+    /// * `new_tracker` - Creates the resource tracker for each run, called on the worker thread
+    #[cfg(feature = "parallel")]
+    pub fn run_many_parallel<T: ResourceTracker>(
+        &self,
+        inputs: Vec<Vec<MontyObject>>,
+        new_tracker: impl Fn() -> T + Sync,
+    ) -> Vec<Result<MontyObject, MontyException>> {
+        use rayon::prelude::*;
+
+        inputs
+            .into_par_iter()
+            .map(|run_inputs| self.run(run_inputs, new_tracker(), &mut PrintWriter::Disabled))
+            .collect()
+    }
+
+    /// Executes the code to completion, resolving external function calls with `functions`.
+    ///
+    /// Drives the `start()`/`run()` loop so hosts don't need to write it themselves.
+    /// Calls to a function missing from `functions` raise `KeyError`, and OS calls raise
+    /// `NotImplementedError`, in the sandboxed code. Dataclass method calls are looked up
+    /// by method name, with the instance as the first positional argument.
+    pub fn run_with_functions<T: ResourceTracker>(
+        &self,
+        inputs: Vec<MontyObject>,
+        functions: &ExternalFunctions,
+        resource_tracker: T,
+        print: &mut PrintWriter<'_>,
+    ) -> Result<MontyObject, MontyException> {
+        let mut progress = self.clone().start(inputs, resource_tracker, print)?;
+        loop {
+            progress = match progress {
+                RunProgress::Complete(value) => return Ok(value),
+                RunProgress::FunctionCall {
+                    function_name,
+                    args,
+                    kwargs,
+                    state,
+                    ..
+                } => state.run(functions.call(&function_name, args, kwargs), print)?,
+                RunProgress::OsCall { function, state, .. } => {
+                    let exc = MontyException::new(
+                        ExcType::NotImplementedError,
+                        Some(format!("OS function '{function}' not implemented")),
+                    );
+                    state.run(exc, print)?
+                }
+                // every call is resolved before execution resumes, so no futures are ever pending
+                RunProgress::ResolveFutures(_) => {
+                    return Err(MontyException::runtime_error(
+                        "unexpected pending futures in run_with_functions",
+                    ));
+                }
+                // pausing hands control back to the host, use `start()` to drive paused runs
+                RunProgress::Paused(_) => {
+                    return Err(MontyException::runtime_error(
+                        "pause_on_timeout and checkpoints are not supported by run_with_functions",
+                    ));
+                }
+            };
+        }
     }
 
     /// Executes the code to completion with no resource limits, printing to stdout/stderr.
@@ -103,8 +650,18 @@ impl MontyRun {
     ///
     /// # Errors
     /// Returns an error if serialization fails.
-    pub fn dump(&self) -> Result<Vec<u8>, postcard::Error> {
-        postcard::to_allocvec(self)
+    pub fn dump(&self) -> Result<Vec<u8>, SnapshotError> {
+        self.dump_with(SnapshotCompression::None)
+    }
+
+    /// Serializes the runner like `dump()`, compressing the payload with `compression`.
+    ///
+    /// `load()` detects the compression, so the result is loaded the same way.
+    ///
+    /// # Errors
+    /// Returns an error if serialization or compression fails.
+    pub fn dump_with(&self, compression: SnapshotCompression) -> Result<Vec<u8>, SnapshotError> {
+        to_snapshot_bytes_with(self, compression, None)
     }
 
     /// Deserializes a runner from binary format.
@@ -113,9 +670,19 @@ impl MontyRun {
     /// * `bytes` - The serialized runner data from `dump()`
     ///
     /// # Errors
-    /// Returns an error if deserialization fails.
-    pub fn load(bytes: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(bytes)
+    /// Returns an error if deserialization fails, or `SnapshotError::UnsupportedVersion` if the
+    /// data was written in a snapshot format this version of Monty can't load.
+    pub fn load(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes(bytes)
+    }
+
+    /// Deserializes like `load()`, for snapshots from an untrusted source.
+    ///
+    /// # Errors
+    /// Returns the errors of `load()`, or `SnapshotError::TooLarge` if the snapshot, or a
+    /// compressed snapshot's payload after decompression, is larger than `max_size` bytes.
+    pub fn load_with_max_size(bytes: &[u8], max_size: usize) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes_with(bytes, Some(max_size))
     }
 
     /// Starts execution with the given inputs and resource tracker, consuming self.
@@ -149,10 +716,40 @@ impl MontyRun {
         resource_tracker: T,
         print: &mut PrintWriter<'_>,
     ) -> Result<RunProgress<T>, MontyException> {
-        let executor = self.executor;
+        self.start_traced(inputs, resource_tracker, print, None)
+    }
+
+    /// Starts execution like `start()`, calling `hook` before each statement.
+    ///
+    /// The hook stays installed through external calls and pauses until execution completes
+    /// or the progress is dumped; use `RunProgress::set_trace_hook` to trace a loaded snapshot.
+    ///
+    /// # Errors
+    /// Returns `MontyException` if an input is invalid, a runtime error occurs, or the hook
+    /// returns an error.
+    pub fn start_with_trace<T: ResourceTracker>(
+        self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: T,
+        print: &mut PrintWriter<'_>,
+        hook: impl TraceHook + 'static,
+    ) -> Result<RunProgress<T>, MontyException> {
+        self.start_traced(inputs, resource_tracker, print, Some(Box::new(hook)))
+    }
+
+    /// Shared implementation of `start()` and `start_with_trace()`.
+    fn start_traced<T: ResourceTracker>(
+        self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: T,
+        print: &mut PrintWriter<'_>,
+        trace_hook: Option<Box<dyn TraceHook>>,
+    ) -> Result<RunProgress<T>, MontyException> {
+        let executor = Arc::unwrap_or_clone(self.executor);
 
         // Create heap and prepare namespaces
         let mut heap = Heap::new(executor.namespace_size, resource_tracker);
+        heap.set_trace_hook(trace_hook);
         let mut namespaces = executor.prepare_namespaces(inputs, &mut heap)?;
 
         // Create and run VM
@@ -160,6 +757,7 @@ impl MontyRun {
 
         // Start execution
         let vm_result = vm.run_module(&executor.module_code);
+        let vm_result = executor.call_native_functions(&mut vm, vm_result);
 
         let vm_state = vm.check_snapshot(&vm_result);
 
@@ -168,6 +766,80 @@ impl MontyRun {
     }
 }
 
+#[cfg(feature = "async")]
+impl MontyRun {
+    /// Executes the code to completion, awaiting `handler` for each external function call.
+    ///
+    /// Drives the `start()`/`run()` loop so hosts with async external functions don't need
+    /// to write it themselves. `handler` is called with the function name, positional and
+    /// keyword arguments; returning `Err` raises the exception in the sandboxed code.
+    ///
+    /// This only uses `std` futures, so it works with any async runtime. The interpreter
+    /// runs synchronously between calls, so use resource limits to bound how long each
+    /// poll can take.
+    ///
+    /// OS calls raise `NotImplementedError` in the sandboxed code. Dataclass method calls
+    /// are passed to `handler` like any other external call, with the instance as the first
+    /// positional argument.
+    pub async fn run_async<T, F>(
+        &self,
+        inputs: Vec<MontyObject>,
+        resource_tracker: T,
+        print: &mut PrintWriter<'_>,
+        handler: F,
+    ) -> Result<MontyObject, MontyException>
+    where
+        T: ResourceTracker,
+        F: AsyncFn(&str, Vec<MontyObject>, Vec<(MontyObject, MontyObject)>) -> Result<MontyObject, MontyException>,
+    {
+        let mut progress = self.clone().start(inputs, resource_tracker, print)?;
+        loop {
+            progress = match progress {
+                RunProgress::Complete(value) => return Ok(value),
+                RunProgress::FunctionCall {
+                    function_name,
+                    args,
+                    kwargs,
+                    state,
+                    ..
+                } => {
+                    let result = match handler(&function_name, args, kwargs).await {
+                        Ok(value) => ExternalResult::Return(value),
+                        Err(exc) => ExternalResult::Error(exc),
+                    };
+                    state.run(result, print)?
+                }
+                RunProgress::OsCall { function, state, .. } => {
+                    let exc = MontyException::new(
+                        ExcType::NotImplementedError,
+                        Some(format!("OS function '{function}' not implemented")),
+                    );
+                    state.run(exc, print)?
+                }
+                // the handler resolves every call before execution resumes, so no futures are ever pending
+                RunProgress::ResolveFutures(_) => {
+                    return Err(MontyException::runtime_error("unexpected pending futures in run_async"));
+                }
+                // pausing hands control back to the host, use `start()` to drive paused runs
+                RunProgress::Paused(_) => {
+                    return Err(MontyException::runtime_error(
+                        "pause_on_timeout and checkpoints are not supported by run_async",
+                    ));
+                }
+            };
+        }
+    }
+}
+
+/// Result of `MontyRun::run_collect()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectedRun {
+    /// Value of the last expression, `None` if the code doesn't end with one.
+    pub result: MontyObject,
+    /// Final values of the requested globals that were bound, in the order they were requested.
+    pub variables: IndexMap<String, MontyObject>,
+}
+
 /// Result of a single step of iterative execution.
 ///
 /// This enum owns the execution state, ensuring type-safe state transitions.
@@ -232,6 +904,12 @@ pub enum RunProgress<T: ResourceTracker> {
     ///
     /// access the pending call ids with `.pending_call_ids()`
     ResolveFutures(FutureSnapshot<T>),
+    /// Execution exceeded `max_duration` with `pause_on_timeout` enabled, or stopped at a
+    /// checkpoint requested by the resource tracker; `state.reason()` says which.
+    ///
+    /// Use `state.resume()` to continue, or `dump()` the progress to continue later,
+    /// e.g. in another process.
+    Paused(PausedSnapshot<T>),
     /// Execution completed with a final result.
     Complete(MontyObject),
 }
@@ -284,25 +962,120 @@ impl<T: ResourceTracker> RunProgress<T> {
             _ => None,
         }
     }
+
+    /// Consumes the `RunProgress` and returns the paused state.
+    ///
+    /// Returns the state if this is a `Paused`, None otherwise.
+    #[must_use]
+    pub fn into_paused(self) -> Option<PausedSnapshot<T>> {
+        match self {
+            Self::Paused(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    /// Installs `hook` to be called before each statement once execution continues.
+    ///
+    /// Replaces any hook installed before. Useful to trace an execution loaded from a
+    /// snapshot, since hooks aren't serialized. Does nothing once execution is complete.
+    pub fn set_trace_hook(&mut self, hook: impl TraceHook + 'static) {
+        let hook: Box<dyn TraceHook> = Box::new(hook);
+        match self {
+            Self::FunctionCall { state, .. } | Self::OsCall { state, .. } => state.heap.set_trace_hook(Some(hook)),
+            Self::ResolveFutures(state) => state.heap.set_trace_hook(Some(hook)),
+            Self::Paused(state) => state.heap.set_trace_hook(Some(hook)),
+            Self::Complete(_) => {}
+        }
+    }
+
+    /// Returns the resource tracker of a suspended execution, `None` once it is complete.
+    fn tracker(&self) -> Option<&T> {
+        match self {
+            Self::FunctionCall { state, .. } | Self::OsCall { state, .. } => Some(state.heap.tracker()),
+            Self::ResolveFutures(state) => Some(state.heap.tracker()),
+            Self::Paused(state) => Some(state.heap.tracker()),
+            Self::Complete(_) => None,
+        }
+    }
 }
 
 impl<T: ResourceTracker + serde::Serialize> RunProgress<T> {
     /// Serializes the execution state to a binary format.
     ///
     /// # Errors
+    /// Returns an error if serialization fails, or `SnapshotError::TooLarge` if the snapshot is
+    /// larger than the tracker's `max_snapshot_size`.
+    pub fn dump(&self) -> Result<Vec<u8>, SnapshotError> {
+        self.dump_with(SnapshotCompression::None)
+    }
+
+    /// Serializes the execution state like `dump()`, compressing the payload with `compression`.
+    ///
+    /// The tracker's `max_snapshot_size` applies to the compressed size, so compression can
+    /// make a snapshot fit that otherwise wouldn't.
+    ///
+    /// # Errors
+    /// Returns an error if serialization or compression fails, or `SnapshotError::TooLarge` if
+    /// the snapshot is larger than the tracker's `max_snapshot_size`.
+    pub fn dump_with(&self, compression: SnapshotCompression) -> Result<Vec<u8>, SnapshotError> {
+        let max_size = self.tracker().and_then(ResourceTracker::max_snapshot_size);
+        to_snapshot_bytes_with(self, compression, max_size)
+    }
+
+    /// Reports how large the snapshot written by `dump()` is and which parts take up the space.
+    ///
+    /// # Errors
     /// Returns an error if serialization fails.
-    pub fn dump(&self) -> Result<Vec<u8>, postcard::Error> {
-        postcard::to_allocvec(self)
+    pub fn dump_stats(&self) -> Result<SnapshotStats, SnapshotError> {
+        match self {
+            Self::FunctionCall { state, .. } | Self::OsCall { state, .. } => {
+                execution_stats(self, &state.executor, &state.vm_state, &state.heap, &state.namespaces)
+            }
+            Self::ResolveFutures(state) => {
+                execution_stats(self, &state.executor, &state.vm_state, &state.heap, &state.namespaces)
+            }
+            Self::Paused(state) => {
+                execution_stats(self, &state.executor, &state.vm_state, &state.heap, &state.namespaces)
+            }
+            Self::Complete(_) => SnapshotStats::new(self, 0, 0, Vec::new()),
+        }
     }
 }
 
+/// Measures the parts of a suspended execution for `RunProgress::dump_stats`.
+fn execution_stats<T: ResourceTracker + serde::Serialize>(
+    progress: &RunProgress<T>,
+    executor: &Executor,
+    vm_state: &VMSnapshot,
+    heap: &Heap<T>,
+    namespaces: &Namespaces,
+) -> Result<SnapshotStats, SnapshotError> {
+    let sections = vec![
+        ("code", encoded_size(executor)?),
+        ("vm_state", encoded_size(vm_state)?),
+        ("heap", encoded_size(heap)?),
+        ("namespaces", encoded_size(namespaces)?),
+    ];
+    SnapshotStats::new(progress, heap.live_count(), namespaces.active_count(), sections)
+}
+
 impl<T: ResourceTracker + serde::de::DeserializeOwned> RunProgress<T> {
     /// Deserializes execution state from binary format.
     ///
     /// # Errors
-    /// Returns an error if deserialization fails.
-    pub fn load(bytes: &[u8]) -> Result<Self, postcard::Error> {
-        postcard::from_bytes(bytes)
+    /// Returns an error if deserialization fails, or `SnapshotError::UnsupportedVersion` if the
+    /// data was written in a snapshot format this version of Monty can't load.
+    pub fn load(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes(bytes)
+    }
+
+    /// Deserializes like `load()`, for snapshots from an untrusted source.
+    ///
+    /// # Errors
+    /// Returns the errors of `load()`, or `SnapshotError::TooLarge` if the snapshot, or a
+    /// compressed snapshot's payload after decompression, is larger than `max_size` bytes.
+    pub fn load_with_max_size(bytes: &[u8], max_size: usize) -> Result<Self, SnapshotError> {
+        from_snapshot_bytes_with(bytes, Some(max_size))
     }
 }
 
@@ -361,6 +1134,16 @@ impl From<MontyException> for ExternalResult {
     }
 }
 
+/// `Ok` returns the value and `Err` raises the exception at the call site.
+impl From<Result<MontyObject, MontyException>> for ExternalResult {
+    fn from(result: Result<MontyObject, MontyException>) -> Self {
+        match result {
+            Ok(value) => Self::Return(value),
+            Err(exception) => Self::Error(exception),
+        }
+    }
+}
+
 impl From<MontyFuture> for ExternalResult {
     fn from(_: MontyFuture) -> Self {
         Self::Future
@@ -424,6 +1207,7 @@ impl<T: ResourceTracker> Snapshot<T> {
                 vm.run()
             }
         };
+        let vm_result = self.executor.call_native_functions(&mut vm, vm_result);
 
         let vm_state = vm.check_snapshot(&vm_result);
 
@@ -613,6 +1397,7 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
 
         // Continue execution
         let result = vm.run();
+        let result = executor.call_native_functions(&mut vm, result);
 
         let vm_state = vm.check_snapshot(&result);
 
@@ -621,6 +1406,189 @@ impl<T: ResourceTracker> FutureSnapshot<T> {
     }
 }
 
+/// Execution state paused because the time limit was exceeded, a checkpoint was due or
+/// the trace hook asked to pause.
+///
+/// Created when the resource tracker's `pause_on_timeout()` returns true, its
+/// `on_statement()` requests a checkpoint, or the trace hook's `pause_requested()` returns
+/// true, see `reason()`. Execution stopped between
+/// two bytecode instructions, so resuming continues exactly where it left off.
+///
+/// # Type Parameters
+/// * `T` - Resource tracker implementation
+///
+/// Serialization requires `T: Serialize + Deserialize`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(bound(serialize = "T: serde::Serialize", deserialize = "T: serde::de::DeserializeOwned"))]
+pub struct PausedSnapshot<T: ResourceTracker> {
+    /// The executor containing compiled code and interns.
+    executor: Executor,
+    /// The VM state containing stack, frames, and exception state.
+    vm_state: VMSnapshot,
+    /// The heap containing all allocated objects.
+    heap: Heap<T>,
+    /// The namespaces containing all variable bindings.
+    namespaces: Namespaces,
+    /// Why execution paused.
+    reason: PauseReason,
+}
+
+impl<T: ResourceTracker> PausedSnapshot<T> {
+    /// Returns why execution paused.
+    #[must_use]
+    pub fn reason(&self) -> PauseReason {
+        self.reason
+    }
+
+    /// Returns a mutable reference to the resource tracker.
+    ///
+    /// This allows changing resource limits before resuming, e.g. giving the next
+    /// time slice a different `max_duration`.
+    pub fn tracker_mut(&mut self) -> &mut T {
+        self.heap.tracker_mut()
+    }
+
+    /// Returns the bound variables of the frame execution paused in, as `(name, value)` pairs
+    /// in slot order.
+    ///
+    /// At module level these are the globals. Values are converted copies, so inspecting them
+    /// never changes the paused execution. Variables captured from an enclosing function
+    /// aren't included.
+    #[must_use]
+    pub fn locals(&self) -> Vec<(String, MontyObject)> {
+        let Some((function_id, namespace_idx)) = self.vm_state.current_frame() else {
+            return Vec::new();
+        };
+        let code = match function_id {
+            Some(function_id) => &self.executor.interns.get_function(function_id).code,
+            None => &self.executor.module_code,
+        };
+        self.variables(code, namespace_idx)
+    }
+
+    /// Returns the bound module-level variables as `(name, value)` pairs in slot order.
+    #[must_use]
+    pub fn globals(&self) -> Vec<(String, MontyObject)> {
+        self.variables(&self.executor.module_code, GLOBAL_NS_IDX)
+    }
+
+    /// Returns a summary of the live heap, with the variables of all frames as retainers.
+    ///
+    /// Function locals are named `function.variable`; see `HeapReport`.
+    #[must_use]
+    pub fn heap_report(&self) -> HeapReport {
+        memory::heap_report(&self.heap, &self.heap_roots())
+    }
+
+    /// Returns every live heap value with the values it references, see `HeapDump`.
+    #[must_use]
+    pub fn heap_dump(&self) -> HeapDump {
+        memory::heap_dump(&self.heap, &self.heap_roots())
+    }
+
+    /// Returns the globals and the locals of every function frame, for heap introspection.
+    fn heap_roots(&self) -> Vec<(String, &Value)> {
+        let interns = &self.executor.interns;
+        let mut roots = named_values(
+            &self.executor.module_code,
+            GLOBAL_NS_IDX,
+            &self.namespaces,
+            &self.heap,
+            interns,
+        );
+        for (function_id, namespace_idx) in self.vm_state.frames() {
+            let Some(function_id) = function_id else {
+                continue;
+            };
+            let function = interns.get_function(function_id);
+            let function_name = interns.get_str(function.name.name_id);
+            let locals = named_values(&function.code, namespace_idx, &self.namespaces, &self.heap, interns);
+            roots.extend(
+                locals
+                    .into_iter()
+                    .map(|(name, value)| (format!("{function_name}.{name}"), value)),
+            );
+        }
+        roots
+    }
+
+    /// Converts the bound, named slots of a namespace run by `code`.
+    fn variables(&self, code: &Code, namespace_idx: NamespaceId) -> Vec<(String, MontyObject)> {
+        let interns = &self.executor.interns;
+        named_values(code, namespace_idx, &self.namespaces, &self.heap, interns)
+            .into_iter()
+            .map(|(name, value)| (name, MontyObject::from_value(value, &self.heap, interns)))
+            .collect()
+    }
+
+    /// Continues execution, with a fresh time budget if it paused on timeout.
+    ///
+    /// Restarts the tracker's timer after a timeout, then runs until the next external call,
+    /// pause, or completion. After a checkpoint the time budget carries on as before.
+    ///
+    /// # Arguments
+    /// * `print` - Writer for print output
+    ///
+    /// # Panics
+    /// This method should not panic under normal operation. Internal assertions
+    /// may panic if the VM reaches an inconsistent state (indicating a bug).
+    pub fn resume(mut self, print: &mut PrintWriter<'_>) -> Result<RunProgress<T>, MontyException> {
+        if self.reason == PauseReason::Timeout {
+            self.heap.tracker_mut().restart_timer();
+        }
+
+        let mut vm = VM::restore(
+            self.vm_state,
+            &self.executor.module_code,
+            &mut self.heap,
+            &mut self.namespaces,
+            &self.executor.interns,
+            print,
+        );
+
+        let vm_result = vm.run();
+        let vm_result = self.executor.call_native_functions(&mut vm, vm_result);
+
+        let vm_state = vm.check_snapshot(&vm_result);
+
+        handle_vm_result(vm_result, vm_state, self.executor, self.heap, self.namespaces)
+    }
+}
+
+/// Returns the bound, named slots of a namespace run by `code` in slot order.
+///
+/// Values of variables captured by inner functions are read from their cells.
+fn named_values<'a>(
+    code: &Code,
+    namespace_idx: NamespaceId,
+    namespaces: &'a Namespaces,
+    heap: &'a Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> Vec<(String, &'a Value)> {
+    let mut variables = Vec::new();
+    for (slot, value) in namespaces.get(namespace_idx).values().iter().enumerate() {
+        // variables captured by inner functions live in cells
+        let value = match value {
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::Cell(inner) => inner,
+                _ => value,
+            },
+            _ => value,
+        };
+        if matches!(value, Value::Undefined) {
+            continue;
+        }
+        let Some(name) = u16::try_from(slot).ok().and_then(|slot| code.local_name(slot)) else {
+            continue;
+        };
+        let name = interns.get_str(name);
+        if !name.is_empty() {
+            variables.push((name.to_owned(), value));
+        }
+    }
+    variables
+}
+
 /// Handles a FrameExit result and converts it to RunProgress for FutureSnapshot.
 ///
 /// This is a standalone function to avoid partial move issues when destructuring FutureSnapshot.
@@ -650,7 +1618,7 @@ fn handle_vm_result<T: ResourceTracker>(
             namespaces.drop_global_with_heap(&mut heap);
 
             let obj = MontyObject::new(value, &mut heap, &executor.interns);
-            Ok(RunProgress::Complete(obj))
+            executor.check_result(obj).map(RunProgress::Complete)
         }
         Ok(FrameExit::ExternalCall {
             ext_function_id,
@@ -711,6 +1679,13 @@ fn handle_vm_result<T: ResourceTracker>(
                 pending_call_ids,
             }))
         }
+        Ok(FrameExit::Paused(reason)) => Ok(RunProgress::Paused(PausedSnapshot {
+            executor,
+            vm_state: vm_state.expect("snapshot should exist for Paused"),
+            heap,
+            namespaces,
+            reason,
+        })),
         Err(err) => {
             #[cfg(feature = "ref-count-panic")]
             namespaces.drop_global_with_heap(&mut heap);
@@ -737,13 +1712,35 @@ struct Executor {
     interns: Interns,
     /// IDs to create values to inject into the the namespace to represent external functions.
     external_function_ids: Vec<ExtFunctionId>,
+    /// Implementations of native module functions, indexed from the `ExtFunctionId`
+    /// following the last flat external function.
+    ///
+    /// Not serialized, so after loading these calls are returned to the host instead.
+    #[serde(skip)]
+    native_functions: Vec<NativeFunction>,
     /// Source code for error reporting (extracting preview lines for tracebacks).
     code: String,
+    /// Expected type of the result, checked when a run completes.
+    result_schema: Option<ResultSchema>,
     /// Estimated heap capacity for pre-allocation on subsequent runs.
     /// Uses AtomicUsize for thread-safety (required by PyO3's Sync bound).
     heap_capacity: AtomicUsize,
 }
 
+/// What a run by `Executor::run` does besides running the module, all off by default.
+#[derive(Default)]
+struct RunOptions<'a> {
+    /// Hook called before each statement.
+    trace_hook: Option<Box<dyn TraceHook>>,
+    /// Filled with a report of the heap left once execution ends.
+    heap_report: Option<&'a mut HeapReport>,
+    /// Names of globals whose final values are added to the map.
+    collect: Option<(&'a [&'a str], &'a mut IndexMap<String, MontyObject>)>,
+    /// Global function called with these arguments once the module has run, whose result
+    /// replaces the module's.
+    call: Option<(&'a str, Vec<MontyObject>, Vec<(String, MontyObject)>)>,
+}
+
 impl Clone for Executor {
     fn clone(&self) -> Self {
         Self {
@@ -753,37 +1750,58 @@ impl Clone for Executor {
             module_code: self.module_code.clone(),
             interns: self.interns.clone(),
             external_function_ids: self.external_function_ids.clone(),
+            native_functions: self.native_functions.clone(),
             code: self.code.clone(),
+            result_schema: self.result_schema.clone(),
             heap_capacity: AtomicUsize::new(self.heap_capacity.load(Ordering::Relaxed)),
         }
     }
 }
 
 impl Executor {
-    /// Creates a new executor with the given code, filename, input names, and external functions.
+    /// Creates a new executor with the given code, filename, input names, external functions,
+    /// native and Python modules, and compile options.
     fn new(
         code: String,
         script_name: &str,
         input_names: Vec<String>,
-        external_functions: Vec<String>,
+        mut external_functions: Vec<String>,
+        modules: Vec<NativeModule>,
+        python_modules: Vec<PythonModule>,
+        options: CompileOptions,
     ) -> Result<Self, MontyException> {
         let parse_result = parse(&code, script_name).map_err(|e| e.into_python_exc(script_name, &code))?;
-        let prepared = prepare(parse_result, input_names, &external_functions)
+        let mut prepared = prepare(parse_result, input_names, &external_functions)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let global_names = prepared.slot_names();
 
         // Incrementing order matches the indexes used in intern::Interns::get_external_function_name
         let external_function_ids = (0..external_functions.len()).map(ExtFunctionId::new).collect();
 
+        // Native module functions are numbered after the flat external functions, but are
+        // not injected into the namespace
+        let (native_modules, native_functions) =
+            register_modules(modules, &mut prepared.interner, &mut external_functions)?;
+
+        // Python modules share the interner so their names and functions live in the same interns
+        let (python_module_defs, prepared_modules, interner) =
+            prepare_python_modules(python_modules, prepared.interner, &native_modules)?;
+
         // Create interns with empty functions (functions will be set after compilation)
-        let mut interns = Interns::new(prepared.interner, Vec::new(), external_functions);
+        let mut interns = Interns::new(interner, Vec::new(), external_functions);
+        interns.set_native_modules(native_modules);
+        interns.set_python_modules(python_module_defs);
+        interns.set_global_names(global_names);
+        interns.set_compile_options(options);
 
         // Compile the module to bytecode, which also compiles all nested functions
         let namespace_size_u16 = u16::try_from(prepared.namespace_size).expect("module namespace size exceeds u16");
-        let compile_result = Compiler::compile_module(&prepared.nodes, &interns, namespace_size_u16)
+        let compile_result = Compiler::compile_module(&prepared.nodes, &interns, namespace_size_u16, options)
             .map_err(|e| e.into_python_exc(script_name, &code))?;
+        let functions = compile_python_modules(prepared_modules, &mut interns, compile_result.functions, options)?;
 
         // Set the compiled functions in the interns
-        interns.set_functions(compile_result.functions);
+        interns.set_functions(functions);
 
         Ok(Self {
             namespace_size: prepared.namespace_size,
@@ -792,7 +1810,9 @@ impl Executor {
             module_code: compile_result.code,
             interns,
             external_function_ids,
+            native_functions,
             code,
+            result_schema: None,
             heap_capacity: AtomicUsize::new(prepared.namespace_size),
         })
     }
@@ -803,23 +1823,35 @@ impl Executor {
     /// scheduling. The tracker is called on each allocation and periodically
     /// during execution to check time limits and trigger GC.
     ///
-    /// # Arguments
-    /// * `inputs` - Values to fill the first N slots of the namespace
-    /// * `resource_tracker` - Custom resource tracker implementation
-    /// * `print` - Print output writer (mutably borrowed so `Collect` data is preserved)
+    /// Arguments are as for `MontyRun::run`; `options` adds what the other `MontyRun::run_*`
+    /// methods need on top of a plain run.
     fn run(
         &self,
         inputs: Vec<MontyObject>,
         resource_tracker: impl ResourceTracker,
         print: &mut PrintWriter<'_>,
+        options: &mut RunOptions<'_>,
     ) -> Result<MontyObject, MontyException> {
         let heap_capacity = self.heap_capacity.load(Ordering::Relaxed);
         let mut heap = Heap::new(heap_capacity, resource_tracker);
+        heap.set_trace_hook(options.trace_hook.take());
         let mut namespaces = self.prepare_namespaces(inputs, &mut heap)?;
+        let interns = self.run_interns();
 
         // Create and run VM
-        let mut vm = VM::new(&mut heap, &mut namespaces, &self.interns, print);
-        let frame_exit_result = vm.run_module(&self.module_code);
+        let mut vm = VM::new(&mut heap, &mut namespaces, &interns, print);
+        let module_result = vm.run_module(&self.module_code);
+        let mut frame_exit_result = self.call_native_functions(&mut vm, module_result);
+        if let Some((name, args, kwargs)) = options.call.take() {
+            frame_exit_result = match frame_exit_result {
+                Ok(FrameExit::Return(value)) => {
+                    value.drop_with_heap(vm.heap);
+                    let call_result = vm.call_global(name, args, kwargs);
+                    self.call_native_functions(&mut vm, call_result)
+                }
+                other => other,
+            };
+        }
 
         // Clean up VM state before it goes out of scope
         vm.cleanup();
@@ -828,12 +1860,51 @@ impl Executor {
             self.heap_capacity.store(heap.size(), Ordering::Relaxed);
         }
 
+        if let Some(report) = options.heap_report.as_deref_mut() {
+            let globals = named_values(&self.module_code, GLOBAL_NS_IDX, &namespaces, &heap, &interns);
+            *report = memory::heap_report(&heap, &globals);
+        }
+
+        if let Some((names, variables)) = &mut options.collect {
+            let globals = named_values(&self.module_code, GLOBAL_NS_IDX, &namespaces, &heap, &interns);
+            for name in *names {
+                if let Some((_, value)) = globals.iter().find(|(global, _)| global == name) {
+                    variables.insert((*name).to_owned(), MontyObject::from_value(value, &heap, &interns));
+                }
+            }
+        }
+
         // Clean up the global namespace before returning (only needed with ref-count-panic)
         #[cfg(feature = "ref-count-panic")]
         namespaces.drop_global_with_heap(&mut heap);
 
-        frame_exit_to_object(frame_exit_result, &mut heap, &self.interns)
-            .map_err(|e| e.into_python_exception(&self.interns, &self.code))
+        let result = frame_exit_to_object(frame_exit_result, &mut heap, &interns)
+            .map_err(|e| e.into_python_exception(&interns, &self.code))?;
+        self.check_result(result)
+    }
+
+    /// Checks the result of a run against the result schema, if there is one.
+    fn check_result(&self, result: MontyObject) -> Result<MontyObject, MontyException> {
+        match &self.result_schema {
+            Some(schema) => match schema.validate(&result) {
+                Ok(()) => Ok(result),
+                Err(mismatch) => Err(MontyException::schema_mismatch_error(mismatch)),
+            },
+            None => Ok(result),
+        }
+    }
+
+    /// Returns the interns to run the code with.
+    ///
+    /// Code compiled by `eval()` and `exec()` is appended to the interns, so when they are
+    /// enabled each run gets its own copy: runs sharing this executor, possibly on other
+    /// threads, don't see each other's code, and the executor doesn't grow.
+    fn run_interns(&self) -> Cow<'_, Interns> {
+        if self.interns.execution_options().allow_eval {
+            Cow::Owned(self.interns.clone())
+        } else {
+            Cow::Borrowed(&self.interns)
+        }
     }
 
     /// Executes the code and returns both the result and reference count data, used for testing only.
@@ -860,6 +1931,7 @@ impl Executor {
         let mut print = PrintWriter::Stdout;
         let mut vm = VM::new(&mut heap, &mut namespaces, &self.interns, &mut print);
         let frame_exit_result = vm.run_module(&self.module_code);
+        let frame_exit_result = self.call_native_functions(&mut vm, frame_exit_result);
 
         // Compute ref counts before consuming the heap - return value is still alive
         let final_namespace = namespaces.into_global();
@@ -895,6 +1967,35 @@ impl Executor {
         })
     }
 
+    /// Runs the native module functions that the VM exits to, resuming it with their
+    /// results until it exits for any other reason.
+    fn call_native_functions(
+        &self,
+        vm: &mut VM<'_, '_, impl ResourceTracker>,
+        mut result: RunResult<FrameExit>,
+    ) -> RunResult<FrameExit> {
+        loop {
+            let function = match &result {
+                Ok(FrameExit::ExternalCall { ext_function_id, .. }) => ext_function_id
+                    .index()
+                    .checked_sub(self.external_function_ids.len())
+                    .and_then(|index| self.native_functions.get(index)),
+                _ => None,
+            };
+            let Some(function) = function else {
+                return result;
+            };
+            let Ok(FrameExit::ExternalCall { args, .. }) = result else {
+                unreachable!("result was matched as an external call above");
+            };
+            let (args, kwargs) = args.into_py_objects(vm.heap, &self.interns);
+            result = match function.call(args, kwargs) {
+                Ok(value) => vm.resume(value),
+                Err(exc) => vm.resume_with_exception(exc.into()),
+            };
+        }
+    }
+
     /// Prepares the namespace namespaces for execution.
     ///
     /// Converts each `MontyObject` input to a `Value`, allocating on the heap if needed.
@@ -926,7 +2027,10 @@ impl Executor {
         if extra > 0 {
             namespace.extend((0..extra).map(|_| Value::Undefined));
         }
-        Ok(Namespaces::new(namespace))
+        Ok(Namespaces::new_with_modules(
+            namespace,
+            self.interns.python_module_count(),
+        ))
     }
 }
 
@@ -965,6 +2069,10 @@ fn frame_exit_to_object(
         FrameExit::ResolveFutures(_) => {
             Err(ExcType::not_implemented("async futures not supported by standard execution.").into())
         }
+        FrameExit::Paused(_) => Err(ExcType::not_implemented(
+            "pausing on timeout and checkpoints not supported by standard execution.",
+        )
+        .into()),
     }
 }
 
@@ -0,0 +1,697 @@
+//! Validation of the result of a run against an expected type.
+//!
+//! A [`ResultSchema`] is parsed from a Python type expression such as `list[str]` or
+//! `dict[str, int | None]`, or converted from a subset of JSON Schema. Once set with
+//! `MontyRun::with_result_schema`, a run whose result doesn't match fails with a
+//! `TypeError` whose [`MontyException::schema_mismatch`](crate::MontyException::schema_mismatch)
+//! says where and why, so hosts don't have to check `MontyObject` trees by hand.
+
+use std::{
+    fmt::{self, Write},
+    iter::Peekable,
+    str::FromStr,
+    vec,
+};
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{object::MontyObject, types::str::StringRepr};
+
+/// The expected type of the result of a run.
+///
+/// Matching follows Python's typing rules where they are unambiguous: `float` also accepts
+/// `int`, `set` also accepts `frozenset`, and `dict` also accepts `defaultdict` and `Counter`.
+/// `int` doesn't accept `bool`, so `True` can't pass for a count.
+///
+/// # Example
+/// ```
+/// use monty::{MontyObject, ResultSchema};
+///
+/// let schema: ResultSchema = "list[str]".parse().unwrap();
+/// let result = MontyObject::List(vec![MontyObject::String("a".to_owned()), MontyObject::Int(1)]);
+/// let mismatch = schema.validate(&result).unwrap_err();
+/// assert_eq!(mismatch.to_string(), "result[1]: expected str, got int");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ResultSchema {
+    /// Any value, written `Any` or `object`.
+    Any,
+    /// `None`.
+    None,
+    /// `bool`.
+    Bool,
+    /// `int`, at any size.
+    Int,
+    /// `float`, also accepting `int`.
+    Float,
+    /// `str`.
+    Str,
+    /// `bytes`.
+    Bytes,
+    /// `list[items]`.
+    List { items: Box<Self> },
+    /// `tuple[a, b]`: a tuple of exactly these items, named tuples included.
+    ///
+    /// The empty tuple is written `tuple[()]`.
+    Tuple { items: Vec<Self> },
+    /// `tuple[items, ...]`: a tuple of any length, named tuples included.
+    TupleOf { items: Box<Self> },
+    /// `set[items]`, also accepting `frozenset`.
+    Set { items: Box<Self> },
+    /// `dict[keys, values]`.
+    Dict { keys: Box<Self>, values: Box<Self> },
+    /// `{'name': str, 'age': NotRequired[int]}`: a dict with string keys, like a `TypedDict`.
+    ///
+    /// Other keys are allowed. Dataclass instances match on their attributes.
+    Object { properties: Vec<Property> },
+    /// `a | b`, also written `Union[a, b]`, or `Optional[a]` for `a | None`.
+    Union { any_of: Vec<Self> },
+}
+
+/// A key of a [`ResultSchema::Object`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Property {
+    /// The key.
+    pub name: String,
+    /// The type of the value.
+    pub schema: ResultSchema,
+    /// Whether the key must be present.
+    pub required: bool,
+}
+
+impl ResultSchema {
+    /// Converts a subset of JSON Schema.
+    ///
+    /// Supported are `true`, `{}`, the types `null`, `boolean`, `integer`, `number`, `string`,
+    /// `array` with `items`, and `object` with `properties` and `required`, or with
+    /// `additionalProperties`, plus `anyOf` and `oneOf`. A list of types is a union.
+    /// Annotations such as `title`, `description` or `format` are ignored.
+    ///
+    /// # Errors
+    /// Returns `SchemaParseError` for anything else, e.g. `enum` or `minimum`, rather than
+    /// accepting values the schema would reject.
+    pub fn from_json_schema(schema: &JsonValue) -> Result<Self, SchemaParseError> {
+        let object = match schema {
+            JsonValue::Bool(true) => return Ok(Self::Any),
+            JsonValue::Object(object) => object,
+            _ => {
+                return Err(SchemaParseError::new(format!(
+                    "expected a JSON schema object, got {schema}"
+                )));
+            }
+        };
+
+        for key in object.keys() {
+            if !matches!(
+                key.as_str(),
+                "type"
+                    | "items"
+                    | "properties"
+                    | "required"
+                    | "additionalProperties"
+                    | "anyOf"
+                    | "oneOf"
+                    | "title"
+                    | "description"
+                    | "format"
+                    | "default"
+                    | "examples"
+                    | "$schema"
+                    | "$id"
+            ) {
+                return Err(SchemaParseError::new(format!(
+                    "unsupported JSON schema keyword {key:?}"
+                )));
+            }
+        }
+
+        if let Some(any_of) = object.get("anyOf").or_else(|| object.get("oneOf")) {
+            let JsonValue::Array(any_of) = any_of else {
+                return Err(SchemaParseError::new("anyOf and oneOf must be arrays"));
+            };
+            return any_of
+                .iter()
+                .map(Self::from_json_schema)
+                .collect::<Result<_, _>>()
+                .map(Self::union);
+        }
+
+        match object.get("type") {
+            None => Ok(Self::Any),
+            Some(JsonValue::String(name)) => json_schema_type(name, object),
+            Some(JsonValue::Array(names)) => names
+                .iter()
+                .map(|name| match name {
+                    JsonValue::String(name) => json_schema_type(name, object),
+                    _ => Err(SchemaParseError::new(format!("expected a type name, got {name}"))),
+                })
+                .collect::<Result<_, _>>()
+                .map(Self::union),
+            Some(other) => Err(SchemaParseError::new(format!("expected a type name, got {other}"))),
+        }
+    }
+
+    /// Checks that `value` matches the schema.
+    ///
+    /// # Errors
+    /// Returns a `SchemaMismatch` describing the first part of `value` that doesn't match.
+    pub fn validate(&self, value: &MontyObject) -> Result<(), SchemaMismatch> {
+        self.check(value, &mut "result".to_owned())
+    }
+
+    /// Checks `value`, found at `path`, against the schema.
+    fn check(&self, value: &MontyObject, path: &mut String) -> Result<(), SchemaMismatch> {
+        match (self, value) {
+            (Self::Any, _)
+            | (Self::None, MontyObject::None)
+            | (Self::Bool, MontyObject::Bool(_))
+            | (Self::Int | Self::Float, MontyObject::Int(_) | MontyObject::BigInt(_))
+            | (Self::Float, MontyObject::Float(_))
+            | (Self::Str, MontyObject::String(_))
+            | (Self::Bytes, MontyObject::Bytes(_)) => Ok(()),
+            (Self::List { items }, MontyObject::List(values))
+            | (Self::TupleOf { items }, MontyObject::Tuple(values) | MontyObject::NamedTuple { values, .. }) => {
+                values.iter().enumerate().try_for_each(|(index, value)| {
+                    with_segment(path, format_args!("[{index}]"), |path| items.check(value, path))
+                })
+            }
+            (Self::Tuple { items }, MontyObject::Tuple(values) | MontyObject::NamedTuple { values, .. }) => {
+                if items.len() != values.len() {
+                    return Err(self.mismatch(path, format!("tuple of length {}", values.len())));
+                }
+                items
+                    .iter()
+                    .zip(values)
+                    .enumerate()
+                    .try_for_each(|(index, (item, value))| {
+                        with_segment(path, format_args!("[{index}]"), |path| item.check(value, path))
+                    })
+            }
+            (Self::Set { items }, MontyObject::Set(values) | MontyObject::FrozenSet(values)) => {
+                values.iter().try_for_each(|value| {
+                    with_segment(path, format_args!(" item {}", value.py_repr()), |path| {
+                        items.check(value, path)
+                    })
+                })
+            }
+            (
+                Self::Dict { keys, values },
+                MontyObject::Dict(pairs) | MontyObject::DefaultDict { items: pairs, .. } | MontyObject::Counter(pairs),
+            ) => pairs.into_iter().try_for_each(|(key, value)| {
+                with_segment(path, format_args!(" key {}", key.py_repr()), |path| {
+                    keys.check(key, path)
+                })?;
+                with_segment(path, format_args!("[{}]", key.py_repr()), |path| {
+                    values.check(value, path)
+                })
+            }),
+            (
+                Self::Object { properties },
+                MontyObject::Dict(pairs)
+                | MontyObject::DefaultDict { items: pairs, .. }
+                | MontyObject::Dataclass { attrs: pairs, .. },
+            ) => properties.iter().try_for_each(|property| {
+                let value = pairs.into_iter().find_map(|(key, value)| match key {
+                    MontyObject::String(key) if *key == property.name => Some(value),
+                    _ => None,
+                });
+                with_segment(
+                    path,
+                    format_args!("[{}]", StringRepr(&property.name)),
+                    |path| match value {
+                        Some(value) => property.schema.check(value, path),
+                        None if property.required => Err(property.schema.mismatch(path, "missing".to_owned())),
+                        None => Ok(()),
+                    },
+                )
+            }),
+            (Self::Union { any_of }, _) => {
+                if any_of.iter().any(|schema| schema.validate(value).is_ok()) {
+                    Ok(())
+                } else {
+                    Err(self.mismatch(path, value.type_name().to_owned()))
+                }
+            }
+            _ => Err(self.mismatch(path, value.type_name().to_owned())),
+        }
+    }
+
+    /// Returns the mismatch of `actual`, found at `path`, with this schema.
+    fn mismatch(&self, path: &str, actual: String) -> SchemaMismatch {
+        SchemaMismatch {
+            path: path.to_owned(),
+            expected: self.to_string(),
+            actual,
+        }
+    }
+
+    /// Returns the union of `schemas`, flattening nested unions.
+    fn union(schemas: Vec<Self>) -> Self {
+        let mut any_of = Vec::with_capacity(schemas.len());
+        for schema in schemas {
+            match schema {
+                Self::Union { any_of: nested } => any_of.extend(nested),
+                schema => any_of.push(schema),
+            }
+        }
+        if any_of.len() == 1 {
+            any_of.pop().expect("union has one member")
+        } else {
+            Self::Union { any_of }
+        }
+    }
+}
+
+/// Runs `check` with `segment` appended to `path`, restoring `path` afterwards.
+fn with_segment(
+    path: &mut String,
+    segment: fmt::Arguments<'_>,
+    check: impl FnOnce(&mut String) -> Result<(), SchemaMismatch>,
+) -> Result<(), SchemaMismatch> {
+    let len = path.len();
+    path.write_fmt(segment).expect("writing to a String can't fail");
+    let result = check(path);
+    path.truncate(len);
+    result
+}
+
+/// Converts the JSON Schema type `name` of `object`, see `ResultSchema::from_json_schema`.
+fn json_schema_type(name: &str, object: &Map<String, JsonValue>) -> Result<ResultSchema, SchemaParseError> {
+    let subschema = |key: &str| -> Result<Box<ResultSchema>, SchemaParseError> {
+        match object.get(key) {
+            Some(schema) => ResultSchema::from_json_schema(schema).map(Box::new),
+            None => Ok(Box::new(ResultSchema::Any)),
+        }
+    };
+    match name {
+        "null" => Ok(ResultSchema::None),
+        "boolean" => Ok(ResultSchema::Bool),
+        "integer" => Ok(ResultSchema::Int),
+        "number" => Ok(ResultSchema::Float),
+        "string" => Ok(ResultSchema::Str),
+        "array" => Ok(ResultSchema::List {
+            items: subschema("items")?,
+        }),
+        "object" => {
+            let Some(properties) = object.get("properties") else {
+                return Ok(ResultSchema::Dict {
+                    keys: Box::new(ResultSchema::Str),
+                    values: subschema("additionalProperties")?,
+                });
+            };
+            let JsonValue::Object(properties) = properties else {
+                return Err(SchemaParseError::new("properties must be an object"));
+            };
+            let required: Vec<&str> = match object.get("required") {
+                None => Vec::new(),
+                Some(JsonValue::Array(required)) => required.iter().filter_map(JsonValue::as_str).collect(),
+                Some(_) => return Err(SchemaParseError::new("required must be an array")),
+            };
+            let properties = properties
+                .iter()
+                .map(|(name, schema)| {
+                    Ok(Property {
+                        name: name.clone(),
+                        schema: ResultSchema::from_json_schema(schema)?,
+                        required: required.contains(&name.as_str()),
+                    })
+                })
+                .collect::<Result<_, SchemaParseError>>()?;
+            Ok(ResultSchema::Object { properties })
+        }
+        _ => Err(SchemaParseError::new(format!("unsupported JSON schema type {name:?}"))),
+    }
+}
+
+/// Writes the schema as the type expression it is parsed from.
+impl fmt::Display for ResultSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => f.write_str("Any"),
+            Self::None => f.write_str("None"),
+            Self::Bool => f.write_str("bool"),
+            Self::Int => f.write_str("int"),
+            Self::Float => f.write_str("float"),
+            Self::Str => f.write_str("str"),
+            Self::Bytes => f.write_str("bytes"),
+            Self::List { items } => write!(f, "list[{items}]"),
+            Self::Tuple { items } if items.is_empty() => f.write_str("tuple[()]"),
+            Self::Tuple { items } => {
+                f.write_str("tuple[")?;
+                write_joined(f, items, ", ")?;
+                f.write_str("]")
+            }
+            Self::TupleOf { items } => write!(f, "tuple[{items}, ...]"),
+            Self::Set { items } => write!(f, "set[{items}]"),
+            Self::Dict { keys, values } => write!(f, "dict[{keys}, {values}]"),
+            Self::Object { properties } => {
+                f.write_str("{")?;
+                for (index, property) in properties.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: ", StringRepr(&property.name))?;
+                    if property.required {
+                        write!(f, "{}", property.schema)?;
+                    } else {
+                        write!(f, "NotRequired[{}]", property.schema)?;
+                    }
+                }
+                f.write_str("}")
+            }
+            Self::Union { any_of } => write_joined(f, any_of, " | "),
+        }
+    }
+}
+
+/// Writes `schemas` separated by `separator`.
+fn write_joined(f: &mut fmt::Formatter<'_>, schemas: &[ResultSchema], separator: &str) -> fmt::Result {
+    for (index, schema) in schemas.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{schema}")?;
+    }
+    Ok(())
+}
+
+/// Parses a Python type expression, e.g. `list[str]` or `dict[str, int | None]`.
+///
+/// Besides builtin types, `Any`, `object`, `Optional`, `Union` and the capitalized `typing`
+/// aliases such as `List` are understood, with or without a `typing.` prefix. A bare `list`,
+/// `set`, `dict` or `tuple` has items of any type.
+impl FromStr for ResultSchema {
+    type Err = SchemaParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?.into_iter().peekable(),
+        };
+        let schema = parser.union()?;
+        match parser.tokens.next() {
+            None => Ok(schema),
+            token => Err(unexpected(token.as_ref(), "end of input")),
+        }
+    }
+}
+
+/// A token of a type expression.
+#[derive(Debug, PartialEq)]
+enum Token<'s> {
+    Name(&'s str),
+    Str(String),
+    Punct(char),
+    Ellipsis,
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "'{name}'"),
+            Self::Str(value) => write!(f, "{}", StringRepr(value)),
+            Self::Punct(c) => write!(f, "'{c}'"),
+            Self::Ellipsis => f.write_str("'...'"),
+        }
+    }
+}
+
+/// Splits a type expression into tokens.
+fn tokenize(s: &str) -> Result<Vec<Token<'_>>, SchemaParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '[' | ']' | '(' | ')' | '{' | '}' | ',' | ':' | '|' => tokens.push(Token::Punct(c)),
+            '.' if s[start..].starts_with("...") => {
+                chars.nth(1);
+                tokens.push(Token::Ellipsis);
+            }
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, end)) if end == c => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => return Err(SchemaParseError::new("unterminated string")),
+                        },
+                        Some((_, other)) => value.push(other),
+                        None => return Err(SchemaParseError::new("unterminated string")),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(index, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '.') {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Name(&s[start..end]));
+            }
+            _ => return Err(SchemaParseError::new(format!("unexpected character {c:?}"))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// A parameter of a generic type, e.g. the `int` and `...` of `tuple[int, ...]`.
+enum Param {
+    Type(ResultSchema),
+    Ellipsis,
+    /// The `()` of `tuple[()]`.
+    EmptyTuple,
+}
+
+/// Recursive descent parser for type expressions.
+struct Parser<'s> {
+    tokens: Peekable<vec::IntoIter<Token<'s>>>,
+}
+
+impl<'s> Parser<'s> {
+    /// Parses `a | b | ...`.
+    fn union(&mut self) -> Result<ResultSchema, SchemaParseError> {
+        let mut any_of = vec![self.primary()?];
+        while self.eat(&Token::Punct('|')) {
+            any_of.push(self.primary()?);
+        }
+        Ok(ResultSchema::union(any_of))
+    }
+
+    /// Parses a type name with optional parameters, or a `{...}` object.
+    fn primary(&mut self) -> Result<ResultSchema, SchemaParseError> {
+        match self.tokens.next() {
+            Some(Token::Name(name)) => {
+                let params = if self.eat(&Token::Punct('[')) {
+                    Some(self.params()?)
+                } else {
+                    None
+                };
+                named_type(name.strip_prefix("typing.").unwrap_or(name), params)
+            }
+            Some(Token::Punct('{')) => self.object(),
+            token => Err(unexpected(token.as_ref(), "a type")),
+        }
+    }
+
+    /// Parses the parameters of a generic type, after the `[`.
+    fn params(&mut self) -> Result<Vec<Param>, SchemaParseError> {
+        let mut params = Vec::new();
+        loop {
+            let param = if self.eat(&Token::Ellipsis) {
+                Param::Ellipsis
+            } else if self.eat(&Token::Punct('(')) {
+                self.expect(')')?;
+                Param::EmptyTuple
+            } else {
+                Param::Type(self.union()?)
+            };
+            params.push(param);
+            if self.eat(&Token::Punct(']')) {
+                return Ok(params);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// Parses the keys of an object, after the `{`.
+    fn object(&mut self) -> Result<ResultSchema, SchemaParseError> {
+        let mut properties = Vec::new();
+        while !self.eat(&Token::Punct('}')) {
+            let name = match self.tokens.next() {
+                Some(Token::Str(name)) => name,
+                token => return Err(unexpected(token.as_ref(), "a quoted key")),
+            };
+            self.expect(':')?;
+            let not_required = self.eat(&Token::Name("NotRequired")) || self.eat(&Token::Name("typing.NotRequired"));
+            let schema = if not_required {
+                self.expect('[')?;
+                let schema = self.union()?;
+                self.expect(']')?;
+                schema
+            } else {
+                self.union()?
+            };
+            properties.push(Property {
+                name,
+                schema,
+                required: !not_required,
+            });
+            if !self.eat(&Token::Punct(',')) {
+                self.expect('}')?;
+                break;
+            }
+        }
+        Ok(ResultSchema::Object { properties })
+    }
+
+    /// Consumes the next token if it is `token`.
+    fn eat(&mut self, token: &Token<'s>) -> bool {
+        self.tokens.next_if_eq(token).is_some()
+    }
+
+    /// Consumes the punctuation `c`, failing if the next token is anything else.
+    fn expect(&mut self, c: char) -> Result<(), SchemaParseError> {
+        match self.tokens.next() {
+            Some(Token::Punct(found)) if found == c => Ok(()),
+            token => Err(unexpected(token.as_ref(), &format!("'{c}'"))),
+        }
+    }
+}
+
+/// Returns the schema for the type `name` with `params`, if it was given any.
+fn named_type(name: &str, params: Option<Vec<Param>>) -> Result<ResultSchema, SchemaParseError> {
+    let any = || Box::new(ResultSchema::Any);
+    let schema = match (name, params) {
+        ("Any" | "object", None) => ResultSchema::Any,
+        ("None" | "NoneType", None) => ResultSchema::None,
+        ("bool", None) => ResultSchema::Bool,
+        ("int", None) => ResultSchema::Int,
+        ("float", None) => ResultSchema::Float,
+        ("str", None) => ResultSchema::Str,
+        ("bytes", None) => ResultSchema::Bytes,
+        ("list" | "List", None) => ResultSchema::List { items: any() },
+        ("list" | "List", Some(params)) => {
+            let [items] = types(name, params)?;
+            ResultSchema::List { items: Box::new(items) }
+        }
+        ("set" | "Set" | "frozenset" | "FrozenSet", None) => ResultSchema::Set { items: any() },
+        ("set" | "Set" | "frozenset" | "FrozenSet", Some(params)) => {
+            let [items] = types(name, params)?;
+            ResultSchema::Set { items: Box::new(items) }
+        }
+        ("dict" | "Dict", None) => ResultSchema::Dict {
+            keys: any(),
+            values: any(),
+        },
+        ("dict" | "Dict", Some(params)) => {
+            let [keys, values] = types(name, params)?;
+            ResultSchema::Dict {
+                keys: Box::new(keys),
+                values: Box::new(values),
+            }
+        }
+        ("tuple" | "Tuple", None) => ResultSchema::TupleOf { items: any() },
+        ("tuple" | "Tuple", Some(params)) => match params.as_slice() {
+            [Param::EmptyTuple] => ResultSchema::Tuple { items: Vec::new() },
+            [Param::Type(_), Param::Ellipsis] => {
+                let Some(Param::Type(items)) = params.into_iter().next() else {
+                    unreachable!("first parameter is a type")
+                };
+                ResultSchema::TupleOf { items: Box::new(items) }
+            }
+            _ => ResultSchema::Tuple {
+                items: all_types(name, params)?,
+            },
+        },
+        ("Optional", Some(params)) => {
+            let [schema] = types(name, params)?;
+            ResultSchema::union(vec![schema, ResultSchema::None])
+        }
+        ("Union", Some(params)) => ResultSchema::union(all_types(name, params)?),
+        ("Any" | "object" | "None" | "NoneType" | "bool" | "int" | "float" | "str" | "bytes", Some(_)) => {
+            return Err(SchemaParseError::new(format!("'{name}' doesn't take parameters")));
+        }
+        ("Optional" | "Union", None) => return Err(SchemaParseError::new(format!("'{name}' needs parameters"))),
+        _ => return Err(SchemaParseError::new(format!("unsupported type '{name}'"))),
+    };
+    Ok(schema)
+}
+
+/// Returns the `N` parameters of the generic type `name`, which must all be types.
+fn types<const N: usize>(name: &str, params: Vec<Param>) -> Result<[ResultSchema; N], SchemaParseError> {
+    all_types(name, params)?.try_into().map_err(|_| {
+        let s = if N == 1 { "" } else { "s" };
+        SchemaParseError::new(format!("'{name}' takes {N} parameter{s}"))
+    })
+}
+
+/// Returns the parameters of the generic type `name`, which must all be types.
+fn all_types(name: &str, params: Vec<Param>) -> Result<Vec<ResultSchema>, SchemaParseError> {
+    params
+        .into_iter()
+        .map(|param| match param {
+            Param::Type(schema) => Ok(schema),
+            Param::Ellipsis | Param::EmptyTuple => {
+                Err(SchemaParseError::new(format!("unexpected parameter in '{name}'")))
+            }
+        })
+        .collect()
+}
+
+/// Returns the error for finding `token`, or the end of input, instead of `expected`.
+fn unexpected(token: Option<&Token<'_>>, expected: &str) -> SchemaParseError {
+    match token {
+        Some(token) => SchemaParseError::new(format!("expected {expected}, got {token}")),
+        None => SchemaParseError::new(format!("expected {expected}, got end of input")),
+    }
+}
+
+/// Error returned when a type expression or JSON schema can't be turned into a `ResultSchema`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaParseError {
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl SchemaParseError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SchemaParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid result schema: {}", self.message)
+    }
+}
+
+impl std::error::Error for SchemaParseError {}
+
+/// Where and how the result of a run doesn't match its `ResultSchema`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// Python expression locating the mismatched value in the result, e.g. `result['users'][2]`.
+    ///
+    /// Set items and dict keys are located with ` item` and ` key`, e.g. `result key 1`.
+    pub path: String,
+    /// The expected type, as a type expression, e.g. `list[str]`.
+    pub expected: String,
+    /// What was found instead: a type name such as `int`, `missing` for a missing required
+    /// key, or e.g. `tuple of length 3`.
+    pub actual: String,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: expected {}, got {}", self.path, self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
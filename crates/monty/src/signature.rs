@@ -87,6 +87,21 @@ pub(crate) struct Signature {
     bind_mode: BindMode,
 }
 
+/// How a parameter can be passed, see `Signature::params`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParamKind {
+    /// Before `/`, only passed by position.
+    PositionalOnly,
+    /// Passed by position or keyword.
+    PositionalOrKeyword,
+    /// `*args`.
+    VarPositional,
+    /// After `*` or `*args`, only passed by keyword.
+    KeywordOnly,
+    /// `**kwargs`.
+    VarKeyword,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 enum BindMode {
     /// If this is a simple signature (no defaults, no *args/**kwargs).
@@ -580,6 +595,41 @@ impl Signature {
         self.kwargs.as_ref().map_or(0, Vec::len)
     }
 
+    /// Returns each parameter's name, kind and the index of its default in the function's
+    /// default expressions, in declaration order.
+    pub fn params(&self) -> Vec<(StringId, ParamKind, Option<usize>)> {
+        let pos_args = self.pos_args.as_deref().unwrap_or_default();
+        let args = self.args.as_deref().unwrap_or_default();
+        // defaults belong to the last parameters of each positional group
+        let first_pos_default = pos_args.len() - self.pos_defaults_count;
+        let first_arg_default = args.len() - self.arg_defaults_count;
+        let kwarg_defaults_start = self.pos_defaults_count + self.arg_defaults_count;
+
+        let mut params = Vec::with_capacity(self.total_slots());
+        for (index, &name) in pos_args.iter().enumerate() {
+            let default = index.checked_sub(first_pos_default);
+            params.push((name, ParamKind::PositionalOnly, default));
+        }
+        for (index, &name) in args.iter().enumerate() {
+            let default = index
+                .checked_sub(first_arg_default)
+                .map(|index| self.pos_defaults_count + index);
+            params.push((name, ParamKind::PositionalOrKeyword, default));
+        }
+        if let Some(name) = self.var_args {
+            params.push((name, ParamKind::VarPositional, None));
+        }
+        let kwarg_defaults = self.kwarg_default_map.as_deref().unwrap_or_default();
+        for (&name, default) in self.kwargs.iter().flatten().zip(kwarg_defaults) {
+            let default = default.map(|index| kwarg_defaults_start + index);
+            params.push((name, ParamKind::KeywordOnly, default));
+        }
+        if let Some(name) = self.var_kwargs {
+            params.push((name, ParamKind::VarKeyword, None));
+        }
+        params
+    }
+
     /// Returns an iterator over all parameter names in namespace slot order.
     ///
     /// Order: pos_args, args, var_args (if present), kwargs, var_kwargs (if present)
@@ -0,0 +1,351 @@
+//! Versioned binary format used by every `dump()` / `load()` method.
+//!
+//! Snapshots are postcard encodings of internal structs, so any change to those structs
+//! changes the format. Each snapshot therefore starts with a short header:
+//!
+//! | bytes | content                                   |
+//! |-------|-------------------------------------------|
+//! | 0..4  | magic `b"MNTY"`                           |
+//! | 4..6  | format version, little-endian `u16`       |
+//! | 6     | payload compression, see [`SnapshotCompression`] |
+//! | 7..   | postcard payload, compressed if byte 6 is nonzero |
+//!
+//! `load()` checks the header before decoding, so a snapshot written by an incompatible
+//! version of Monty fails with [`SnapshotError::UnsupportedVersion`] rather than a confusing
+//! decoding error, or worse, decoding into the wrong state.
+//!
+//! Bump [`SNAPSHOT_FORMAT_VERSION`] whenever a serialized struct changes shape, and commit
+//! golden files for the new version to `tests/snapshot_golden`; the existing ones fail to
+//! load until you do. Leave [`MIN_SNAPSHOT_FORMAT_VERSION`] alone if `load()` migrates the
+//! older versions, otherwise raise it too.
+
+use std::fmt;
+
+use postcard::ser_flavors::Size;
+
+/// Magic bytes at the start of every snapshot.
+const MAGIC: &[u8; 4] = b"MNTY";
+
+/// Length of the header before the payload.
+const HEADER_LEN: usize = MAGIC.len() + 3;
+
+/// Version of the snapshot format written by this build.
+///
+/// Version 1 is the first versioned format. Snapshots from before the header was added
+/// fail with [`SnapshotError::NotASnapshot`].
+pub const SNAPSHOT_FORMAT_VERSION: u16 = 1;
+
+/// Oldest snapshot format version this build can load.
+///
+/// Only differs from [`SNAPSHOT_FORMAT_VERSION`] once a format change ships with a migration
+/// from the versions in between.
+pub const MIN_SNAPSHOT_FORMAT_VERSION: u16 = 1;
+
+/// Header byte for LZ4 compression, defined even without the `lz4` feature so the error
+/// for a compressed snapshot can say which feature is missing.
+const LZ4_ID: u8 = 1;
+
+/// Largest ratio of decompressed to compressed size an LZ4 block can reach.
+///
+/// A match's length is extended one byte at a time, and each byte adds at most 255 bytes of
+/// output, so a payload declaring a larger size is corrupt and rejected before allocating.
+#[cfg(feature = "lz4")]
+const LZ4_MAX_RATIO: usize = 255;
+
+/// Compression applied to a snapshot's payload by `dump_with()`.
+///
+/// `load()` detects the compression from the header, so callers never need to pass it back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotCompression {
+    /// Store the payload as is, the fastest option and what `dump()` uses.
+    #[default]
+    None,
+    /// Compress the payload with LZ4. Heaps full of strings and lists typically shrink
+    /// several times over. Requires the `lz4` feature.
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+impl SnapshotCompression {
+    /// Byte identifying this compression in the snapshot header.
+    fn id(self) -> u8 {
+        match self {
+            Self::None => 0,
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => LZ4_ID,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, SnapshotError> {
+        match id {
+            0 => Ok(Self::None),
+            #[cfg(feature = "lz4")]
+            LZ4_ID => Ok(Self::Lz4),
+            _ => Err(SnapshotError::UnsupportedCompression { id }),
+        }
+    }
+}
+
+/// Error returned when dumping or loading a snapshot fails.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The data doesn't start with the snapshot header, so it wasn't produced by `dump()`.
+    NotASnapshot,
+    /// The snapshot was written in a format version this build can't load.
+    UnsupportedVersion {
+        /// Format version found in the snapshot header.
+        found: u16,
+        /// Oldest format version this build can load.
+        min_supported: u16,
+        /// Newest format version this build can load.
+        max_supported: u16,
+    },
+    /// The snapshot's payload is compressed in a way this build can't decompress.
+    UnsupportedCompression {
+        /// Compression byte found in the snapshot header.
+        id: u8,
+    },
+    /// Decompressing the payload failed, because it's corrupt or declares an impossible size.
+    Compression(std::io::Error),
+    /// The snapshot is larger than `ResourceLimits::max_snapshot_size` allows, or than the
+    /// `max_size` passed when loading it.
+    TooLarge {
+        /// Size of the snapshot in bytes after compression, or when loading a compressed
+        /// snapshot, the size its payload declares after decompression.
+        size: usize,
+        /// Maximum allowed size in bytes.
+        limit: usize,
+    },
+    /// The payload couldn't be encoded or decoded.
+    Postcard(postcard::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotASnapshot => f.write_str("data is not a Monty snapshot, the header is missing"),
+            Self::UnsupportedVersion {
+                found,
+                min_supported,
+                max_supported,
+            } => {
+                write!(
+                    f,
+                    "snapshot format version {found} is not supported, this build of Monty supports versions \
+                     {min_supported} to {max_supported}"
+                )?;
+                if found > max_supported {
+                    f.write_str(", upgrade Monty to load it")
+                } else {
+                    f.write_str(", the snapshot was written by an older version of Monty")
+                }
+            }
+            Self::UnsupportedCompression { id: LZ4_ID } => f.write_str(
+                "snapshot is lz4-compressed, but this build of Monty was compiled without the `lz4` feature",
+            ),
+            Self::UnsupportedCompression { id } => write!(f, "snapshot uses unknown compression {id}"),
+            Self::Compression(err) => write!(f, "snapshot decompression failed: {err}"),
+            Self::TooLarge { size, limit } => {
+                write!(f, "snapshot is {size} bytes, which exceeds the limit of {limit} bytes")
+            }
+            Self::Postcard(err) => write!(f, "invalid snapshot data: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Compression(err) => Some(err),
+            Self::Postcard(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<postcard::Error> for SnapshotError {
+    fn from(err: postcard::Error) -> Self {
+        Self::Postcard(err)
+    }
+}
+
+/// Serializes `value` with the versioned snapshot header and no compression.
+///
+/// Used by language bindings that wrap Monty's state in their own serializable structs.
+///
+/// # Errors
+/// Returns `SnapshotError::Postcard` if serialization fails.
+pub fn to_snapshot_bytes<T: serde::Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, SnapshotError> {
+    to_snapshot_bytes_with(value, SnapshotCompression::None, None)
+}
+
+/// Serializes `value` with the versioned snapshot header, compressing the payload and
+/// checking the result against `max_size`.
+///
+/// Used by all `dump()` and `dump_with()` methods.
+///
+/// # Errors
+/// Returns `SnapshotError::Postcard` if serialization fails, or `SnapshotError::TooLarge` if
+/// the snapshot is larger than `max_size`.
+pub fn to_snapshot_bytes_with<T: serde::Serialize + ?Sized>(
+    value: &T,
+    compression: SnapshotCompression,
+    max_size: Option<usize>,
+) -> Result<Vec<u8>, SnapshotError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+    bytes.push(compression.id());
+    let bytes = match compression {
+        SnapshotCompression::None => postcard::to_extend(value, bytes)?,
+        #[cfg(feature = "lz4")]
+        SnapshotCompression::Lz4 => {
+            let payload = postcard::to_allocvec(value)?;
+            // the block is prefixed with the payload's length as a little-endian `u32`
+            bytes.extend_from_slice(&lz4_flex::block::compress_prepend_size(&payload));
+            bytes
+        }
+    };
+    check_size(bytes.len(), max_size)?;
+    Ok(bytes)
+}
+
+/// Returns `SnapshotError::TooLarge` if `size` is larger than `max_size`.
+fn check_size(size: usize, max_size: Option<usize>) -> Result<(), SnapshotError> {
+    match max_size {
+        Some(limit) if size > limit => Err(SnapshotError::TooLarge { size, limit }),
+        _ => Ok(()),
+    }
+}
+
+/// Deserializes a value written by [`to_snapshot_bytes`], checking the header first.
+///
+/// Used by all `load()` methods. A compressed payload is never decompressed beyond the size
+/// it declares, which must be plausible for its compressed size.
+///
+/// # Errors
+/// Returns `SnapshotError::NotASnapshot` if the header is missing,
+/// `SnapshotError::UnsupportedVersion` if the format version can't be loaded by this build,
+/// `SnapshotError::Compression` if decompression fails, or `SnapshotError::Postcard` if the
+/// payload is invalid.
+pub fn from_snapshot_bytes<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, SnapshotError> {
+    from_snapshot_bytes_with(bytes, None)
+}
+
+/// Deserializes a value written by [`to_snapshot_bytes_with`], rejecting snapshots larger
+/// than `max_size` both as stored and, if compressed, after decompression.
+///
+/// Used by all `load_with_max_size()` methods, so a host loading untrusted snapshots can
+/// bound the memory decoding them takes.
+///
+/// # Errors
+/// Returns the errors of [`from_snapshot_bytes`], or `SnapshotError::TooLarge` if the
+/// snapshot or its decompressed payload is larger than `max_size`.
+pub fn from_snapshot_bytes_with<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+    max_size: Option<usize>,
+) -> Result<T, SnapshotError> {
+    check_size(bytes.len(), max_size)?;
+    let version = snapshot_format_version(bytes)?;
+    if !(MIN_SNAPSHOT_FORMAT_VERSION..=SNAPSHOT_FORMAT_VERSION).contains(&version) {
+        return Err(SnapshotError::UnsupportedVersion {
+            found: version,
+            min_supported: MIN_SNAPSHOT_FORMAT_VERSION,
+            max_supported: SNAPSHOT_FORMAT_VERSION,
+        });
+    }
+    let payload = &bytes[HEADER_LEN..];
+    match SnapshotCompression::from_id(bytes[HEADER_LEN - 1])? {
+        SnapshotCompression::None => Ok(postcard::from_bytes(payload)?),
+        #[cfg(feature = "lz4")]
+        SnapshotCompression::Lz4 => {
+            let payload = decompress_lz4(payload, max_size)?;
+            Ok(postcard::from_bytes(&payload)?)
+        }
+    }
+}
+
+/// Decompresses an LZ4 payload written by `to_snapshot_bytes_with`.
+///
+/// The declared size is checked against `max_size` and [`LZ4_MAX_RATIO`] before anything is
+/// allocated, and decompression fails rather than writing past it.
+#[cfg(feature = "lz4")]
+fn decompress_lz4(payload: &[u8], max_size: Option<usize>) -> Result<Vec<u8>, SnapshotError> {
+    let invalid = |msg: String| SnapshotError::Compression(std::io::Error::new(std::io::ErrorKind::InvalidData, msg));
+    let Some((size, block)) = payload.split_first_chunk::<4>() else {
+        return Err(invalid("lz4 payload is missing its size".to_owned()));
+    };
+    let size = usize::try_from(u32::from_le_bytes(*size)).unwrap_or(usize::MAX);
+    check_size(size, max_size)?;
+    if size > block.len().saturating_mul(LZ4_MAX_RATIO) {
+        return Err(invalid(format!(
+            "lz4 payload declares {size} bytes, which {} compressed bytes can't hold",
+            block.len()
+        )));
+    }
+    let decompressed = lz4_flex::block::decompress(block, size).map_err(|err| invalid(err.to_string()))?;
+    if decompressed.len() != size {
+        return Err(invalid(format!(
+            "lz4 payload declares {size} bytes, but decompressed to {}",
+            decompressed.len()
+        )));
+    }
+    Ok(decompressed)
+}
+
+/// Returns the format version recorded in a snapshot's header, without decoding the payload.
+///
+/// # Errors
+/// Returns `SnapshotError::NotASnapshot` if the header is missing.
+pub fn snapshot_format_version(bytes: &[u8]) -> Result<u16, SnapshotError> {
+    match bytes {
+        [m0, m1, m2, m3, v0, v1, _, ..] if [*m0, *m1, *m2, *m3] == *MAGIC => Ok(u16::from_le_bytes([*v0, *v1])),
+        _ => Err(SnapshotError::NotASnapshot),
+    }
+}
+
+/// Size breakdown of a snapshot, returned by `dump_stats()`.
+///
+/// Useful for finding out why snapshots are large, e.g. a heap holding a big list, before
+/// choosing compression or a `max_snapshot_size`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotStats {
+    /// Size in bytes of the snapshot `dump()` writes, i.e. without compression.
+    pub total_bytes: usize,
+    /// Number of live objects on the heap.
+    pub heap_objects: usize,
+    /// Number of namespaces: the global namespace, module namespaces and one per active call.
+    pub namespaces: usize,
+    /// Encoded size in bytes of each part of the snapshot.
+    ///
+    /// Parts are `"code"` (bytecode and interned strings), `"vm_state"` (stack and frames),
+    /// `"heap"`, `"namespaces"` and `"other"` (header, tracker and call arguments), and only
+    /// those present in the snapshot are listed. The sizes add up to `total_bytes`.
+    pub sections: Vec<(&'static str, usize)>,
+}
+
+impl SnapshotStats {
+    /// Creates stats for a snapshot of `value` from the sizes of its `sections`, measured with
+    /// [`encoded_size`], attributing the remaining bytes to `"other"`.
+    pub(crate) fn new<T: serde::Serialize + ?Sized>(
+        value: &T,
+        heap_objects: usize,
+        namespaces: usize,
+        mut sections: Vec<(&'static str, usize)>,
+    ) -> Result<Self, SnapshotError> {
+        let total_bytes = HEADER_LEN + encoded_size(value)?;
+        let other = total_bytes - sections.iter().map(|(_, size)| size).sum::<usize>();
+        sections.push(("other", other));
+        Ok(Self {
+            total_bytes,
+            heap_objects,
+            namespaces,
+            sections,
+        })
+    }
+}
+
+/// Number of bytes postcard needs to encode `value`, computed without allocating.
+pub(crate) fn encoded_size<T: serde::Serialize + ?Sized>(value: &T) -> Result<usize, SnapshotError> {
+    Ok(postcard::serialize_with_flavor(value, Size::default())?)
+}
@@ -8,7 +8,7 @@
 //! This module provides [`sort_indices`] for the comparison step and
 //! [`apply_permutation`] for the in-place rearrangement step.
 
-use std::{cell::RefCell, cmp::Ordering};
+use std::{borrow::Borrow, cell::RefCell, cmp::Ordering};
 
 use crate::{
     exception_private::{ExcType, RunError},
@@ -26,10 +26,11 @@ use crate::{
 /// sort finishes early and the error is returned.
 ///
 /// The `values` slice is typically either the items themselves (no key function)
-/// or the pre-computed key values.
+/// or the pre-computed key values. It may also hold borrowed values, e.g. the
+/// keys of a dict when `json.dumps(..., sort_keys=True)` orders its output.
 pub fn sort_indices(
     indices: &mut [usize],
-    values: &[Value],
+    values: &[impl Borrow<Value>],
     reverse: bool,
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
@@ -45,7 +46,9 @@ pub fn sort_indices(
             sort_error = Some(e.into());
             return Ordering::Equal;
         }
-        match values[a].py_cmp(&values[b], heap, &mut guard.borrow_mut(), interns) {
+        let value_a: &Value = values[a].borrow();
+        let value_b: &Value = values[b].borrow();
+        match value_a.py_cmp(value_b, heap, &mut guard.borrow_mut(), interns) {
             Ok(Some(ord)) => {
                 if reverse {
                     ord.reverse()
@@ -56,8 +59,8 @@ pub fn sort_indices(
             Ok(None) => {
                 sort_error = Some(ExcType::type_error(format!(
                     "'<' not supported between instances of '{}' and '{}'",
-                    values[a].py_type(heap),
-                    values[b].py_type(heap)
+                    value_a.py_type(heap),
+                    value_b.py_type(heap)
                 )));
                 Ordering::Equal
             }
@@ -0,0 +1,168 @@
+//! Execution tracing: a host callback invoked before each statement runs.
+//!
+//! A [`TraceHook`] sees every statement the VM starts, with its line, its kind and
+//! optionally a summary of the frame's local variables. Hosts use it to build step
+//! debuggers, coverage reports and audit logs; a hook can also pause execution before the
+//! statement it was just shown, which is how [`crate::DebugSession`] implements breakpoints.
+//!
+//! Unlike limits and checkpoints, tracing doesn't go through the `ResourceTracker`: a hook
+//! is installed with `MontyRun::run_with_trace`, `MontyRun::start_with_trace` or
+//! `RunProgress::set_trace_hook`, and stays with the execution until it completes or is
+//! dumped. Hooks are never serialized, so a loaded snapshot runs untraced until a hook is
+//! installed again.
+
+use std::fmt;
+
+use crate::MontyException;
+
+/// Longest `repr()` reported for a local variable, in characters, before it's truncated.
+const MAX_LOCAL_REPR_CHARS: usize = 100;
+
+/// Kind of a statement reported to a [`TraceHook`].
+///
+/// `pass`, `global` and `nonlocal` compile to no code, so they are never reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum StatementKind {
+    /// An expression evaluated for its side effects, e.g. a function call.
+    Expr,
+    /// `return`, with or without a value.
+    Return,
+    /// `raise`.
+    Raise,
+    /// `assert`.
+    Assert,
+    /// Any assignment: to names, unpacked targets, subscripts or attributes, chained or not.
+    Assign,
+    /// Augmented assignment, e.g. `x += 1`.
+    AugAssign,
+    /// `del`.
+    Delete,
+    /// A `for` loop, reported once before the iterable is evaluated.
+    For,
+    /// A `while` loop, reported each time the condition is evaluated.
+    While,
+    /// `break`.
+    Break,
+    /// `continue`.
+    Continue,
+    /// An `if` statement, reported before the condition is evaluated.
+    If,
+    /// A function definition, `def` or `async def`.
+    FunctionDef,
+    /// A `try` statement.
+    ///
+    /// `try` emits no code of its own before its body, so its first body statement is
+    /// reported instead.
+    Try,
+    /// `import` or `from ... import`.
+    Import,
+}
+
+impl StatementKind {
+    /// Returns the lowercase name of the kind, e.g. `"aug_assign"`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Expr => "expr",
+            Self::Return => "return",
+            Self::Raise => "raise",
+            Self::Assert => "assert",
+            Self::Assign => "assign",
+            Self::AugAssign => "aug_assign",
+            Self::Delete => "delete",
+            Self::For => "for",
+            Self::While => "while",
+            Self::Break => "break",
+            Self::Continue => "continue",
+            Self::If => "if",
+            Self::FunctionDef => "function_def",
+            Self::Try => "try",
+            Self::Import => "import",
+        }
+    }
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A statement about to run, passed to [`TraceHook::on_statement`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent<'a> {
+    /// Name of the file the statement is in: the script name, an imported module's
+    /// file, e.g. `"pkg/helpers.py"`, or `"<string>"` for code run by `eval()` and `exec()`.
+    pub filename: &'a str,
+    /// Line of the statement (1-based).
+    pub line: u16,
+    /// Kind of the statement.
+    pub kind: StatementKind,
+    /// Name of the function running the statement, `"<module>"` for module-level code.
+    pub function_name: &'a str,
+    /// Number of active frames, 1 for module-level code.
+    ///
+    /// Frames run by a builtin, e.g. a `sorted()` key function, count too.
+    pub depth: usize,
+    /// Bound local variables of the frame as `(name, repr)` pairs, in slot order.
+    ///
+    /// Only computed when [`TraceHook::wants_locals`] returns true. Reprs longer than
+    /// 100 characters are truncated and end with `...`.
+    pub locals: Option<Vec<(String, String)>>,
+}
+
+/// Callback invoked before each statement the VM runs.
+///
+/// Implemented for closures taking a [`TraceEvent`], which never receive locals.
+///
+/// Hooks must be `Send + Sync` so traced executions can be moved between threads like
+/// untraced ones.
+pub trait TraceHook: Send + Sync {
+    /// Called before the statement described by `event` runs.
+    ///
+    /// # Errors
+    /// Returning an error aborts execution with that exception. It can't be caught by the
+    /// traced code, so a hook can reliably stop a run, e.g. when an audit check fails.
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException>;
+
+    /// Whether events should include [`TraceEvent::locals`].
+    ///
+    /// Building the summary formats every local variable, so it's off by default.
+    fn wants_locals(&self) -> bool {
+        false
+    }
+
+    /// Whether execution should pause before the statement just passed to `on_statement`.
+    ///
+    /// Returning true stops with `RunProgress::Paused` and `PauseReason::Breakpoint`;
+    /// resuming runs the statement without reporting it again. Only polled where execution
+    /// can be suspended: a request made inside a frame run by a builtin, e.g. a `sorted()`
+    /// key function, should keep returning true until it's polled.
+    fn pause_requested(&mut self) -> bool {
+        false
+    }
+}
+
+impl<F> TraceHook for F
+where
+    F: FnMut(&TraceEvent<'_>) -> Result<(), MontyException> + Send + Sync,
+{
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        self(event)
+    }
+}
+
+impl fmt::Debug for dyn TraceHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TraceHook")
+    }
+}
+
+/// Truncates a local variable's `repr()` to [`MAX_LOCAL_REPR_CHARS`].
+pub(crate) fn truncate_repr(mut repr: String) -> String {
+    if let Some((index, _)) = repr.char_indices().nth(MAX_LOCAL_REPR_CHARS) {
+        repr.truncate(index);
+        repr.push_str("...");
+    }
+    repr
+}
@@ -0,0 +1,563 @@
+/// Python bytearray type, a mutable sequence of bytes.
+///
+/// Shares the non-mutating method surface with `bytes` (see [`super::bytes`]): those methods
+/// run on the current contents and any `bytes` results are converted to `bytearray`, so
+/// `bytearray(b'a b').split()` returns a list of bytearrays like CPython.
+///
+/// # Mutating Methods
+/// - `append(item)` - Append a single byte
+/// - `extend(iterable)` - Append bytes from a bytes-like object or an iterable of ints
+/// - `insert(index, item)` - Insert a single byte at index
+/// - `pop([index])` - Remove and return the byte at index (default: last)
+/// - `remove(value)` - Remove the first occurrence of a byte
+/// - `clear()` - Remove all bytes
+/// - `reverse()` - Reverse in place
+/// - `copy()` - Return a copy
+///
+/// Item assignment (`ba[i] = x`) and deletion (`del ba[i]`, `del ba[i:j:k]`) are supported;
+/// slice assignment is not yet.
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use super::{
+    AttrCallResult, MontyIter, PyTrait, Type,
+    bytes::{Bytes, bytes_fromhex, call_bytes_method_impl, get_byte_at_index, get_bytes_slice, write_bytes_literal},
+};
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    value::{EitherStr, Value},
+};
+
+/// Python bytearray value stored on the heap.
+///
+/// Wraps a `Vec<u8>`; unlike [`Bytes`] it is mutable and therefore unhashable.
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ByteArray(Vec<u8>);
+
+impl ByteArray {
+    /// Creates a new ByteArray from a byte vector.
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns a reference to the inner byte slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns a mutable reference to the inner byte vector.
+    pub fn as_vec_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+
+    /// Creates a bytearray from the `bytearray()` constructor call.
+    ///
+    /// - `bytearray()` with no args returns an empty bytearray
+    /// - `bytearray(int)` returns a bytearray of that length filled with zeros
+    /// - `bytearray(bytes_like)` copies a `bytes` or `bytearray`
+    /// - `bytearray(iterable)` collects an iterable of ints in `range(0, 256)`
+    ///
+    /// Strings are rejected since the `encoding` argument is not supported.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let Some(value) = args.get_zero_one_arg("bytearray", heap)? else {
+            let heap_id = heap.allocate(HeapData::ByteArray(Self::default()))?;
+            return Ok(Value::Ref(heap_id));
+        };
+        let new_data = match value {
+            Value::Int(n) => {
+                if n < 0 {
+                    return Err(ExcType::value_error_negative_bytes_count());
+                }
+                let size = usize::try_from(n).expect("bytearray count validated non-negative");
+                vec![0u8; size]
+            }
+            Value::Bool(b) => vec![0u8; usize::from(b)],
+            value if value.is_str(heap) => {
+                value.drop_with_heap(heap);
+                return Err(ExcType::type_error_string_without_encoding());
+            }
+            value => {
+                if let Some(b) = value.as_bytes_like(heap, interns) {
+                    let b = b.to_vec();
+                    value.drop_with_heap(heap);
+                    b
+                } else {
+                    let type_ = value.py_type(heap);
+                    let Ok(iter) = MontyIter::new(value, heap, interns) else {
+                        return Err(ExcType::type_error_bytearray_init(type_));
+                    };
+                    collect_bytes(iter, heap, interns)?
+                }
+            }
+        };
+        let heap_id = heap.allocate(HeapData::ByteArray(Self::new(new_data)))?;
+        Ok(Value::Ref(heap_id))
+    }
+
+    /// Replaces positional arguments that refer back to this bytearray with a `bytes` copy.
+    ///
+    /// The receiver's data is taken out of the heap for the duration of a method call, so
+    /// calls like `ba.extend(ba)` or `ba.find(ba)` could not otherwise read the argument.
+    fn detach_self_args(
+        &self,
+        self_id: HeapId,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+    ) -> RunResult<ArgValues> {
+        let is_self = |value: &Value| matches!(value, Value::Ref(id) if *id == self_id);
+        let refers_to_self = match &args {
+            ArgValues::One(a) => is_self(a),
+            ArgValues::Two(a, b) => is_self(a) || is_self(b),
+            ArgValues::ArgsKargs { args, .. } => args.iter().any(is_self),
+            ArgValues::Empty | ArgValues::Kwargs(_) => false,
+        };
+        if !refers_to_self {
+            return Ok(args);
+        }
+
+        let copy = match heap.allocate(HeapData::Bytes(Bytes::new(self.0.clone()))) {
+            Ok(id) => Value::Ref(id),
+            Err(err) => {
+                args.drop_with_heap(heap);
+                return Err(err.into());
+            }
+        };
+        let mut detach = |value: Value| {
+            if is_self(&value) {
+                value.drop_with_heap(heap);
+                copy.clone_with_heap(heap)
+            } else {
+                value
+            }
+        };
+        let args = match args {
+            ArgValues::One(a) => ArgValues::One(detach(a)),
+            ArgValues::Two(a, b) => {
+                let a = detach(a);
+                ArgValues::Two(a, detach(b))
+            }
+            ArgValues::ArgsKargs { args, kwargs } => ArgValues::ArgsKargs {
+                args: args.into_iter().map(&mut detach).collect(),
+                kwargs,
+            },
+            other => other,
+        };
+        copy.drop_with_heap(heap);
+        Ok(args)
+    }
+
+    /// Normalizes a Python index for item access, returning `None` when out of range.
+    fn normalize_index(&self, index: i64) -> Option<usize> {
+        let len = i64::try_from(self.0.len()).expect("bytearray length exceeds i64::MAX");
+        let normalized = if index < 0 { index + len } else { index };
+        if normalized < 0 || normalized >= len {
+            return None;
+        }
+        usize::try_from(normalized).ok()
+    }
+
+    /// Handles `del ba[slice]`, removing every byte the slice selects.
+    fn delitem_slice(&mut self, slice: &crate::types::Slice) -> RunResult<()> {
+        let (start, stop, step) = slice
+            .indices(self.0.len())
+            .map_err(|()| ExcType::value_error_slice_step_zero())?;
+
+        // Mark the selected indices, iterating in the same way as `get_bytes_slice`
+        let len = self.0.len();
+        let mut remove = vec![false; len];
+        if let Ok(step_usize) = usize::try_from(step) {
+            for i in (start..stop.min(len)).step_by(step_usize) {
+                remove[i] = true;
+            }
+        } else if start < len {
+            // start is the highest index, stop is the sentinel (stop > len means "go to the beginning")
+            let step_abs = usize::try_from(-step).expect("step is negative so -step is positive");
+            let lowest = if stop > len { 0 } else { stop + 1 };
+            for i in (lowest..=start).rev().step_by(step_abs) {
+                remove[i] = true;
+            }
+        }
+
+        let mut remove = remove.into_iter();
+        self.0.retain(|_| !remove.next().expect("one flag per byte"));
+        Ok(())
+    }
+}
+
+impl From<Vec<u8>> for ByteArray {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl std::ops::Deref for ByteArray {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PyTrait for ByteArray {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::ByteArray
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.0.len()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.0.len())
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
+        // Check for slice first (Value::Ref pointing to HeapData::Slice)
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            let (start, stop, step) = slice
+                .indices(self.0.len())
+                .map_err(|()| ExcType::value_error_slice_step_zero())?;
+
+            let sliced_bytes = get_bytes_slice(&self.0, start, stop, step);
+            let heap_id = heap.allocate(HeapData::ByteArray(Self::new(sliced_bytes)))?;
+            return Ok(Value::Ref(heap_id));
+        }
+
+        let index = index_key(key, heap)?;
+        let byte = get_byte_at_index(&self.0, index).ok_or_else(ExcType::bytearray_index_error)?;
+        Ok(Value::Int(i64::from(byte)))
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<()> {
+        defer_drop!(key, heap);
+        defer_drop!(value, heap);
+
+        let index = index_key(key, heap)?;
+        let byte = value_to_byte(value, heap)?;
+        let idx = self.normalize_index(index).ok_or_else(ExcType::bytearray_index_error)?;
+        self.0[idx] = byte;
+        Ok(())
+    }
+
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        defer_drop!(key, heap);
+
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            // Clone the slice to release the borrow on heap before calling delitem_slice
+            let slice = slice.clone();
+            return self.delitem_slice(&slice);
+        }
+
+        let index = index_key(key, heap)?;
+        let idx = self.normalize_index(index).ok_or_else(ExcType::bytearray_index_error)?;
+        self.0.remove(idx);
+        Ok(())
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.0 == other.0)
+    }
+
+    /// Bytearrays don't contain nested heap references.
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // No-op: bytearrays don't hold Value references
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        !self.0.is_empty()
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("bytearray(")?;
+        write_bytes_literal(&self.0, true, f)?;
+        f.write_char(')')
+    }
+
+    fn py_add(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> Result<Option<Value>, ResourceError> {
+        let concat = [self.as_slice(), other.as_slice()].concat();
+        Ok(Some(Value::Ref(heap.allocate(HeapData::ByteArray(concat.into()))?)))
+    }
+
+    /// Extends in place from any bytes-like value, including this bytearray itself.
+    fn py_iadd(
+        &mut self,
+        other: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        self_id: Option<HeapId>,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        if let Value::Ref(other_id) = &other
+            && Some(*other_id) == self_id
+        {
+            self.0.extend_from_within(..);
+        } else if let Some(b) = other.as_bytes_like(heap, interns) {
+            self.0.extend_from_slice(b);
+        } else {
+            other.drop_with_heap(heap);
+            return Ok(false);
+        }
+        other.drop_with_heap(heap);
+        Ok(true)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::ByteArray, attr.as_str(interns)));
+        };
+
+        call_bytearray_method(self, method, args, heap, interns)
+    }
+
+    /// Swaps arguments that alias the receiver for a copy, then delegates to `py_call_attr`.
+    fn py_call_attr_raw(
+        &mut self,
+        self_id: HeapId,
+        vm: &mut VM<'_, '_, impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+    ) -> RunResult<AttrCallResult> {
+        let args = self.detach_self_args(self_id, args, vm.heap)?;
+        self.py_call_attr(vm.heap, attr, args, vm.interns)
+            .map(AttrCallResult::Value)
+    }
+}
+
+/// Dispatches a method call on a bytearray value.
+///
+/// Mutating methods are implemented here; everything else runs the shared bytes
+/// implementation on the current contents and converts `bytes` results to `bytearray`.
+fn call_bytearray_method(
+    bytearray: &mut ByteArray,
+    method: StaticStrings,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    match method {
+        StaticStrings::Append => {
+            let item = args.get_one_arg("bytearray.append", heap)?;
+            defer_drop!(item, heap);
+            bytearray.0.push(value_to_byte(item, heap)?);
+            Ok(Value::None)
+        }
+        StaticStrings::Extend => {
+            let iterable = args.get_one_arg("bytearray.extend", heap)?;
+            bytearray_extend(bytearray, iterable, heap, interns)?;
+            Ok(Value::None)
+        }
+        StaticStrings::Insert => {
+            let (index_obj, item) = args.get_two_args("bytearray.insert", heap)?;
+            defer_drop!(index_obj, heap);
+            defer_drop!(item, heap);
+            let index_i64 = index_obj.as_int(heap)?;
+            let byte = value_to_byte(item, heap)?;
+            // Like list.insert(), out-of-range indices clamp to the start or end
+            let len = bytearray.0.len();
+            let len_i64 = i64::try_from(len).expect("bytearray length exceeds i64::MAX");
+            let index = if index_i64 < 0 {
+                usize::try_from(index_i64 + len_i64).unwrap_or(0)
+            } else {
+                usize::try_from(index_i64).unwrap_or(len).min(len)
+            };
+            bytearray.0.insert(index, byte);
+            Ok(Value::None)
+        }
+        StaticStrings::Pop => {
+            let index_arg = args.get_zero_one_arg("bytearray.pop", heap)?;
+            let index = if let Some(v) = index_arg {
+                let result = v.as_int(heap);
+                v.drop_with_heap(heap);
+                result?
+            } else {
+                -1
+            };
+            if bytearray.0.is_empty() {
+                return Err(ExcType::index_error_pop_empty_bytearray());
+            }
+            let idx = bytearray
+                .normalize_index(index)
+                .ok_or_else(ExcType::index_error_pop_out_of_range)?;
+            Ok(Value::Int(i64::from(bytearray.0.remove(idx))))
+        }
+        StaticStrings::Remove => {
+            let value = args.get_one_arg("bytearray.remove", heap)?;
+            defer_drop!(value, heap);
+            let byte = value_to_byte(value, heap)?;
+            let idx = bytearray
+                .0
+                .iter()
+                .position(|&b| b == byte)
+                .ok_or_else(ExcType::value_error_bytearray_remove)?;
+            bytearray.0.remove(idx);
+            Ok(Value::None)
+        }
+        StaticStrings::Clear => {
+            args.check_zero_args("bytearray.clear", heap)?;
+            bytearray.0.clear();
+            Ok(Value::None)
+        }
+        StaticStrings::Reverse => {
+            args.check_zero_args("bytearray.reverse", heap)?;
+            bytearray.0.reverse();
+            Ok(Value::None)
+        }
+        StaticStrings::Copy => {
+            args.check_zero_args("bytearray.copy", heap)?;
+            let heap_id = heap.allocate(HeapData::ByteArray(bytearray.clone()))?;
+            Ok(Value::Ref(heap_id))
+        }
+        _ => {
+            let result = call_bytes_method_impl(&bytearray.0, method, args, heap, interns)?;
+            bytes_result_to_bytearray(&result, heap);
+            Ok(result)
+        }
+    }
+}
+
+/// Implements Python's `bytearray.fromhex(string)` classmethod.
+///
+/// Parses the string like `bytes.fromhex` and returns the result as a bytearray.
+pub(crate) fn bytearray_fromhex(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let result = bytes_fromhex(args, heap, interns)?;
+    bytes_result_to_bytearray(&result, heap);
+    Ok(result)
+}
+
+/// Implements `bytearray.extend(iterable)`.
+///
+/// Bytes-like arguments are copied directly; any other iterable must yield ints
+/// in `range(0, 256)`.
+fn bytearray_extend(
+    bytearray: &mut ByteArray,
+    iterable: Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    if let Some(b) = iterable.as_bytes_like(heap, interns) {
+        bytearray.0.extend_from_slice(b);
+        iterable.drop_with_heap(heap);
+        return Ok(());
+    }
+    if iterable.is_str(heap) {
+        iterable.drop_with_heap(heap);
+        return Err(ExcType::type_error_bytearray_extend_str());
+    }
+    let type_ = iterable.py_type(heap);
+    let Ok(iter) = MontyIter::new(iterable, heap, interns) else {
+        return Err(ExcType::type_error_bytearray_extend(type_));
+    };
+    let new_bytes = collect_bytes(iter, heap, interns)?;
+    bytearray.0.extend(new_bytes);
+    Ok(())
+}
+
+/// Collects an iterator of ints into bytes, validating each item is in `range(0, 256)`.
+fn collect_bytes(iter: MontyIter, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Vec<u8>> {
+    let items: Vec<Value> = iter.collect(heap, interns)?;
+    let result: RunResult<Vec<u8>> = items.iter().map(|item| value_to_byte(item, heap)).collect();
+    items.drop_with_heap(heap);
+    result
+}
+
+/// Converts a value to a single byte.
+///
+/// Matches CPython: non-integers raise `TypeError` and integers outside
+/// `range(0, 256)` raise `ValueError`.
+fn value_to_byte(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<u8> {
+    let n = match value {
+        Value::Int(i) => *i,
+        Value::Bool(b) => i64::from(*b),
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => {
+            return Err(ExcType::value_error_byte_range());
+        }
+        _ => return Err(ExcType::type_error_not_integer(value.py_type(heap))),
+    };
+    u8::try_from(n).map_err(|_| ExcType::value_error_byte_range())
+}
+
+/// Extracts an integer index from a subscript key, with bytearray's error message.
+fn index_key(key: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i64> {
+    match key {
+        Value::Int(_) | Value::Bool(_) => key.as_index(heap, Type::ByteArray),
+        Value::Ref(id) if matches!(heap.get(*id), HeapData::LongInt(_)) => key.as_index(heap, Type::ByteArray),
+        _ => Err(ExcType::type_error_bytearray_indices(key.py_type(heap))),
+    }
+}
+
+/// Converts `bytes` results of a shared bytes method into `bytearray`s in place.
+///
+/// Methods like `split` and `partition` return containers of bytes, so their
+/// elements are converted too. Results are always freshly allocated, so no other
+/// reference can observe the change of type.
+fn bytes_result_to_bytearray(result: &Value, heap: &mut Heap<impl ResourceTracker>) {
+    let Value::Ref(id) = result else { return };
+    let item_ids = |items: &[Value]| {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Ref(id) => Some(*id),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let ids = match heap.get(*id) {
+        HeapData::Bytes(_) => vec![*id],
+        HeapData::List(list) => item_ids(list.as_slice()),
+        HeapData::Tuple(tuple) => item_ids(tuple.as_slice()),
+        _ => return,
+    };
+    for id in ids {
+        let data = heap.get_mut(id);
+        if let HeapData::Bytes(b) = data {
+            *data = HeapData::ByteArray(ByteArray::new(std::mem::take(b.as_vec_mut())));
+        }
+    }
+}
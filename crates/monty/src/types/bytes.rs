@@ -186,7 +186,7 @@ impl Bytes {
     /// - `bytes()` with no args returns empty bytes
     /// - `bytes(int)` returns bytes of that length filled with zeros
     /// - `bytes(string)` encodes the string as UTF-8 (simplified, no encoding param)
-    /// - `bytes(bytes)` returns a copy of the bytes
+    /// - `bytes(bytes)` or `bytes(bytearray)` returns a copy of the bytes
     ///
     /// Note: Full Python semantics for bytes() are more complex (encoding, errors params).
     pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
@@ -212,6 +212,7 @@ impl Bytes {
             Some(v @ Value::Ref(id)) => match heap.get(*id) {
                 HeapData::Str(s) => s.as_str().as_bytes().to_vec(),
                 HeapData::Bytes(b) => b.as_slice().to_vec(),
+                HeapData::ByteArray(b) => b.as_slice().to_vec(),
                 _ => return Err(ExcType::type_error_bytes_init(v.py_type(heap))),
             },
             Some(v) => return Err(ExcType::type_error_bytes_init(v.py_type(heap))),
@@ -348,10 +349,10 @@ pub fn call_bytes_method(
 
 /// Calls a bytes method on a byte slice.
 ///
-/// This is the unified implementation for bytes method calls, used by both
-/// heap-allocated `Bytes` (via `py_call_attr`) and interned bytes literals
-/// (`Value::InternBytes`).
-fn call_bytes_method_impl(
+/// This is the unified implementation for bytes method calls, used by
+/// heap-allocated `Bytes` (via `py_call_attr`), interned bytes literals
+/// (`Value::InternBytes`) and the non-mutating methods of `ByteArray`.
+pub(crate) fn call_bytes_method_impl(
     bytes: &[u8],
     method: StaticStrings,
     args: ArgValues,
@@ -464,6 +465,18 @@ fn call_bytes_method_impl(
 /// - Switches to double quotes if bytes contain `'` but not `"`
 /// - Escapes: `\\`, `\t`, `\n`, `\r`, `\xNN` for non-printable bytes
 pub fn bytes_repr_fmt(bytes: &[u8], f: &mut impl Write) -> std::fmt::Result {
+    write_bytes_literal(bytes, false, f)
+}
+
+/// Writes a `b'...'` literal for the contents of a `bytes` or `bytearray`.
+///
+/// CPython's bytearray repr escapes single quotes even when it picks double quotes
+/// (`bytearray(b"a\'b")`), which `escape_all_single_quotes` reproduces.
+pub(crate) fn write_bytes_literal(
+    bytes: &[u8],
+    escape_all_single_quotes: bool,
+    f: &mut impl Write,
+) -> std::fmt::Result {
     // Determine quote character: use double quotes if single quote present but not double
     let has_single = bytes.contains(&b'\'');
     let has_double = bytes.contains(&b'"');
@@ -478,7 +491,7 @@ pub fn bytes_repr_fmt(bytes: &[u8], f: &mut impl Write) -> std::fmt::Result {
             b'\t' => f.write_str("\\t")?,
             b'\n' => f.write_str("\\n")?,
             b'\r' => f.write_str("\\r")?,
-            b'\'' if quote == '\'' => f.write_str("\\'")?,
+            b'\'' if quote == '\'' || escape_all_single_quotes => f.write_str("\\'")?,
             b'"' if quote == '"' => f.write_str("\\\"")?,
             // Printable ASCII (32-126)
             0x20..=0x7e => f.write_char(byte as char)?,
@@ -765,6 +778,7 @@ fn extract_bytes_for_prefix_suffix(
         ))),
         Value::Ref(id) => match heap.get(*id) {
             HeapData::Bytes(b) => Ok(PrefixSuffixArg::Single(b.as_slice().to_vec())),
+            HeapData::ByteArray(b) => Ok(PrefixSuffixArg::Single(b.as_slice().to_vec())),
             HeapData::Str(_) => Err(ExcType::type_error(format!(
                 "{method_name} first arg must be bytes or a tuple of bytes, not str"
             ))),
@@ -808,13 +822,14 @@ fn extract_single_bytes_for_prefix_suffix(
         Value::InternString(_) => Err(ExcType::type_error("expected bytes, not str")),
         Value::Ref(id) => match heap.get(*id) {
             HeapData::Bytes(b) => Ok(b.as_slice().to_vec()),
+            HeapData::ByteArray(b) => Ok(b.as_slice().to_vec()),
             _ => Err(ExcType::type_error("expected bytes")),
         },
         _ => Err(ExcType::type_error("expected bytes")),
     }
 }
 
-/// Extracts bytes from a Value (bytes only, NOT str - matches CPython behavior).
+/// Extracts bytes from a Value (bytes or bytearray, NOT str - matches CPython behavior).
 ///
 /// CPython raises `TypeError: a bytes-like object is required, not 'str'` when
 /// a str is passed to bytes methods like find, count, index, startswith, endswith.
@@ -825,13 +840,12 @@ fn extract_bytes_only<'a>(
 ) -> RunResult<&'a [u8]> {
     match value {
         Value::InternBytes(id) => Ok(interns.get_bytes(*id)),
-        Value::InternString(_) => Err(ExcType::type_error("a bytes-like object is required, not 'str'")),
         Value::Ref(id) => match heap.get(*id) {
             HeapData::Bytes(b) => Ok(b.as_slice()),
-            HeapData::Str(_) => Err(ExcType::type_error("a bytes-like object is required, not 'str'")),
-            _ => Err(ExcType::type_error("a bytes-like object is required")),
+            HeapData::ByteArray(b) => Ok(b.as_slice()),
+            other => Err(ExcType::type_error_bytes_like_required(other.py_type(heap))),
         },
-        _ => Err(ExcType::type_error("a bytes-like object is required")),
+        other => Err(ExcType::type_error_bytes_like_required(other.py_type(heap))),
     }
 }
 
@@ -2122,8 +2136,8 @@ fn bytes_join(
                 result.extend_from_slice(interns.get_bytes(*id));
             }
             Value::Ref(heap_id) => {
-                if let HeapData::Bytes(b) = heap.get(*heap_id) {
-                    result.extend_from_slice(b.as_slice());
+                if let Some(b) = heap.get(*heap_id).as_bytes_like() {
+                    result.extend_from_slice(b);
                 } else {
                     let t = item.py_type(heap);
                     return Err(ExcType::type_error(format!(
@@ -2241,6 +2255,7 @@ fn parse_bytes_hex_args(
         Value::Ref(heap_id) => match heap.get(*heap_id) {
             HeapData::Str(s) => s.as_bytes(),
             HeapData::Bytes(b) => b.as_slice(),
+            HeapData::ByteArray(b) => b.as_slice(),
             _ => return Err(ExcType::type_error("sep must be str or bytes")),
         },
         _ => return Err(ExcType::type_error("sep must be str or bytes")),
@@ -0,0 +1,162 @@
+//! Built-in context manager objects from the `contextlib` module.
+//!
+//! These implement the context manager protocol (`__enter__` / `__exit__`) used by
+//! `with` statements. `with` itself is lowered to ordinary method calls on the manager,
+//! so these types only need to respond to those two attribute calls.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    builtins::{Builtins, isinstance_check},
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapId},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{PyTrait, Type},
+    value::{EitherStr, Value},
+};
+
+/// A built-in context manager created by a `contextlib` function.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) enum ContextManager {
+    /// `contextlib.nullcontext(enter_result=None)`: `__enter__` returns the stored
+    /// value and `__exit__` does nothing.
+    NullContext(Value),
+    /// `contextlib.suppress(*exceptions)`: `__exit__` swallows any exception whose
+    /// type is a subclass of one of the stored exception classes.
+    Suppress(Vec<Value>),
+}
+
+impl ContextManager {
+    /// Returns the values held by this context manager, for reference tracking.
+    pub fn values(&self) -> &[Value] {
+        match self {
+            Self::NullContext(value) => std::slice::from_ref(value),
+            Self::Suppress(exceptions) => exceptions,
+        }
+    }
+
+    /// Returns whether `suppress.__exit__` should swallow an exception of type `exc_type`.
+    ///
+    /// `exc_type` is the first argument passed to `__exit__`, the class of the raised exception.
+    fn suppresses(exceptions: &[Value], exc_type: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<bool> {
+        let (exc_type, exc_class) = match exc_type {
+            Value::Builtin(Builtins::ExcType(exc_type)) => (*exc_type, None),
+            Value::Builtin(Builtins::ExcClass(class_id)) => (heap.exc_class(*class_id)?.base, Some(*class_id)),
+            _ => return Ok(false),
+        };
+        for classinfo in exceptions {
+            if isinstance_check(
+                Type::Exception(exc_type),
+                exc_class,
+                classinfo,
+                heap,
+                ExcType::issubclass_arg2_error,
+            )? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl PyTrait for ContextManager {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        match self {
+            Self::NullContext(_) => Type::NullContext,
+            Self::Suppress(_) => Type::Suppress,
+        }
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Context managers compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<contextlib.{} object>", self.py_type(heap))
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        match self {
+            Self::NullContext(value) => value.py_dec_ref_ids(stack),
+            Self::Suppress(exceptions) => {
+                for value in exceptions {
+                    value.py_dec_ref_ids(stack);
+                }
+            }
+        }
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        match self {
+            Self::NullContext(_) => std::mem::size_of::<Self>(),
+            Self::Suppress(exceptions) => std::mem::size_of::<Self>() + exceptions.len() * std::mem::size_of::<Value>(),
+        }
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::DunderEnter) => {
+                args.check_zero_args("__enter__", heap)?;
+                match self {
+                    Self::NullContext(value) => Ok(value.clone_with_heap(heap)),
+                    Self::Suppress(_) => Ok(Value::None),
+                }
+            }
+            Some(StaticStrings::DunderExit) => {
+                let exc_info = args.into_pos_only("__exit__", heap)?;
+                defer_drop!(exc_info, heap);
+                match self {
+                    // nullcontext.__exit__ accepts and ignores any exception info
+                    Self::NullContext(_) => Ok(Value::None),
+                    Self::Suppress(exceptions) => {
+                        let [exc_type, _, _] = exc_info.as_slice() else {
+                            return Err(ExcType::type_error_arg_count("__exit__", 3, exc_info.as_slice().len()));
+                        };
+                        if matches!(exc_type, Value::None) {
+                            Ok(Value::None)
+                        } else {
+                            Self::suppresses(exceptions, exc_type, heap).map(Value::Bool)
+                        }
+                    }
+                }
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(self.py_type(heap), attr.as_str(interns)))
+            }
+        }
+    }
+}
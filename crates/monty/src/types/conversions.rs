@@ -0,0 +1,460 @@
+//! Constructor-style conversions: `int()`, `float()`, `bool()` and `str()`.
+//!
+//! These follow CPython's parsing rules for string arguments: surrounding whitespace is
+//! ignored, `_` is accepted as a digit separator only between digits, `int()` accepts a
+//! `base` (including `0` to infer it from a `0x`/`0o`/`0b` prefix) and `float()` accepts
+//! the special values `inf`, `infinity` and `nan`. Error messages match CPython.
+
+use num_bigint::BigInt;
+use num_traits::FromPrimitive;
+
+use crate::{
+    args::ArgValues,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard},
+    intern::{Interns, StaticStrings},
+    resource::{DepthGuard, ResourceTracker},
+    types::{
+        LongInt, PyTrait,
+        bytes::bytes_repr,
+        str::{StringRepr, allocate_string},
+    },
+    value::Value,
+};
+
+/// Implementation of `int(x=0, /, base=10)`.
+///
+/// Without a base, converts numbers (truncating floats toward zero) and parses strings and
+/// bytes as base 10. With a base, `x` must be a string, bytes or bytearray.
+pub(crate) fn int_new(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let [x, base] = bind_args(args, "int", ["x", "base"], 1, heap, interns)?;
+    defer_drop!(x, heap);
+    defer_drop!(base, heap);
+
+    let Some(base) = base else {
+        return match x {
+            Some(x) => int_from_value(x, heap, interns),
+            None => Ok(Value::Int(0)),
+        };
+    };
+    let base = int_base(base, heap)?;
+    let Some(x) = x else {
+        return Err(ExcType::type_error("int() missing string argument"));
+    };
+    let literal = match TextArg::from_value(x, heap, interns) {
+        Some(text) => parse_int_arg(text, base)?,
+        None => return Err(ExcType::type_error("int() can't convert non-string with explicit base")),
+    };
+    literal.into_value(heap)
+}
+
+/// Implementation of `float(x=0.0, /)`.
+pub(crate) fn float_new(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let [x] = bind_args(args, "float", ["x"], 1, heap, interns)?;
+    let Some(x) = x else {
+        return Ok(Value::Float(0.0));
+    };
+    defer_drop!(x, heap);
+    match x {
+        Value::Float(f) => Ok(Value::Float(*f)),
+        Value::Int(i) => Ok(Value::Float(*i as f64)),
+        Value::Bool(b) => Ok(Value::Float(f64::from(u8::from(*b)))),
+        _ => {
+            if let Value::Ref(heap_id) = x
+                && let HeapData::LongInt(li) = heap.get(*heap_id)
+            {
+                return li
+                    .to_f64()
+                    .filter(|f| f.is_finite())
+                    .map(Value::Float)
+                    .ok_or_else(ExcType::overflow_error_int_too_large_for_float);
+            }
+            match TextArg::from_value(x, heap, interns) {
+                Some(text) => Ok(Value::Float(parse_float_arg(text)?)),
+                None => Err(ExcType::type_error_float_conversion(x.py_type(heap))),
+            }
+        }
+    }
+}
+
+/// Implementation of `bool(x=False, /)`.
+pub(crate) fn bool_new(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let [x] = bind_args(args, "bool", ["x"], 1, heap, interns)?;
+    let Some(x) = x else {
+        return Ok(Value::Bool(false));
+    };
+    defer_drop!(x, heap);
+    Ok(Value::Bool(x.py_bool(heap, interns)))
+}
+
+/// Implementation of `str(object='')` and `str(object=b'', encoding='utf-8', errors='strict')`.
+///
+/// With neither `encoding` nor `errors`, returns `py_str` of the object. Otherwise the object
+/// must be bytes or a bytearray, which is decoded as UTF-8 (the only supported encoding).
+pub(crate) fn str_new(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    let [object, encoding, errors] = bind_args(args, "str", ["object", "encoding", "errors"], 0, heap, interns)?;
+    defer_drop!(object, heap);
+    defer_drop!(encoding, heap);
+    defer_drop!(errors, heap);
+
+    if encoding.is_none() && errors.is_none() {
+        return match object {
+            Some(object) => {
+                let s = object.py_str(heap, &mut DepthGuard::default(), interns).into_owned();
+                allocate_string(s, heap)
+            }
+            None => Ok(Value::InternString(StaticStrings::EmptyString.into())),
+        };
+    }
+
+    let encoding = match encoding {
+        Some(encoding) => str_arg("encoding", encoding, heap, interns)?.to_ascii_lowercase(),
+        None => "utf-8".to_owned(),
+    };
+    let errors = match errors {
+        Some(errors) => str_arg("errors", errors, heap, interns)?,
+        None => "strict",
+    };
+    let bytes: &[u8] = match object {
+        None => b"",
+        Some(object) => match TextArg::from_value(object, heap, interns) {
+            Some(TextArg::Bytes(bytes)) => bytes,
+            Some(TextArg::Str(_)) => return Err(ExcType::type_error("decoding str is not supported")),
+            None => {
+                return Err(ExcType::type_error(format!(
+                    "decoding to str: need a bytes-like object, {} found",
+                    object.py_type(heap)
+                )));
+            }
+        },
+    };
+    if !matches!(encoding.as_str(), "utf-8" | "utf8" | "utf_8") {
+        return Err(ExcType::lookup_error_unknown_encoding(&encoding));
+    }
+    let s = decode_utf8(bytes, errors)?;
+    allocate_string(s, heap)
+}
+
+/// Binds the arguments of a conversion constructor to its parameters.
+///
+/// The first `positional_only` parameters can only be passed by position, the rest also by
+/// keyword. Returns one slot per parameter, `None` for parameters that were not given.
+fn bind_args<const N: usize>(
+    args: ArgValues,
+    name: &str,
+    params: [&str; N],
+    positional_only: usize,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<[Option<Value>; N]> {
+    let (pos, kwargs) = args.into_parts();
+    let pos_count = pos.len();
+    let given = pos_count + kwargs.len();
+    let plural = if N == 1 { "" } else { "s" };
+    let err = if pos_count > N {
+        Some(ExcType::type_error(format!(
+            "{name} expected at most {N} argument{plural}, got {pos_count}"
+        )))
+    } else if positional_only == N && !kwargs.is_empty() {
+        Some(ExcType::type_error_no_kwargs(name))
+    } else if given > N {
+        Some(ExcType::type_error(format!(
+            "{name}() takes at most {N} argument{plural} ({given} given)"
+        )))
+    } else {
+        None
+    };
+    if let Some(err) = err {
+        pos.drop_with_heap(heap);
+        kwargs.drop_with_heap(heap);
+        return Err(err);
+    }
+
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+    let mut bound_guard = HeapGuard::new(Vec::with_capacity(N), heap);
+    let (bound, heap) = bound_guard.as_parts_mut();
+    bound.extend(pos.map(Some));
+    bound.resize_with(N, || None);
+
+    for (key, value) in kwargs {
+        defer_drop!(key, heap);
+        let mut value = HeapGuard::new(value, heap);
+
+        let Some(keyword_name) = key.as_either_str(value.heap()) else {
+            return Err(ExcType::type_error("keywords must be strings"));
+        };
+        let key_str = keyword_name.as_str(interns);
+        let Some(index) = params[positional_only..]
+            .iter()
+            .position(|param| *param == key_str)
+            .map(|i| i + positional_only)
+        else {
+            return Err(ExcType::type_error_unexpected_keyword(name, key_str));
+        };
+        if bound[index].is_some() {
+            return Err(ExcType::type_error(format!(
+                "argument for {name}() given by name ('{key_str}') and position ({})",
+                index + 1
+            )));
+        }
+        bound[index] = Some(value.into_inner());
+    }
+
+    let Ok(bound) = bound_guard.into_inner().try_into() else {
+        unreachable!("one slot per parameter")
+    };
+    Ok(bound)
+}
+
+/// A string-like argument to `int()`, `float()` or `str()`.
+#[derive(Clone, Copy)]
+enum TextArg<'a> {
+    Str(&'a str),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> TextArg<'a> {
+    /// Returns the text of a str, bytes or bytearray value, or `None` for other types.
+    fn from_value(value: &Value, heap: &'a Heap<impl ResourceTracker>, interns: &'a Interns) -> Option<Self> {
+        match value {
+            Value::InternString(string_id) => Some(Self::Str(interns.get_str(*string_id))),
+            Value::InternBytes(bytes_id) => Some(Self::Bytes(interns.get_bytes(*bytes_id))),
+            Value::Ref(heap_id) => match heap.get(*heap_id) {
+                HeapData::Str(s) => Some(Self::Str(s.as_str())),
+                HeapData::Bytes(b) => Some(Self::Bytes(b.as_slice())),
+                HeapData::ByteArray(b) => Some(Self::Bytes(b.as_slice())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the argument as `str`, or `None` for bytes that are not valid UTF-8
+    /// (which can never be a valid numeric literal).
+    fn as_str(self) -> Option<&'a str> {
+        match self {
+            Self::Str(s) => Some(s),
+            Self::Bytes(b) => std::str::from_utf8(b).ok(),
+        }
+    }
+
+    /// Returns the repr of the argument for error messages.
+    fn repr(self) -> String {
+        match self {
+            Self::Str(s) => StringRepr(s).to_string(),
+            Self::Bytes(b) => bytes_repr(b),
+        }
+    }
+}
+
+/// Converts the single argument of `int(x)` to an integer.
+fn int_from_value(x: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    match x {
+        Value::Int(i) => Ok(Value::Int(*i)),
+        Value::Bool(b) => Ok(Value::Int(i64::from(*b))),
+        Value::Float(f) => int_from_float(*f, heap),
+        _ => {
+            if let Value::Ref(heap_id) = x
+                && let HeapData::LongInt(_) = heap.get(*heap_id)
+            {
+                return Ok(x.clone_with_heap(heap));
+            }
+            let literal = match TextArg::from_value(x, heap, interns) {
+                Some(text) => parse_int_arg(text, 10)?,
+                None => return Err(ExcType::type_error_int_conversion(x.py_type(heap))),
+            };
+            literal.into_value(heap)
+        }
+    }
+}
+
+/// Truncates a float toward zero, promoting to `LongInt` when it doesn't fit in i64.
+pub(crate) fn int_from_float(f: f64, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    if f.is_nan() {
+        return Err(ExcType::value_error_float_nan_to_int());
+    }
+    let truncated = f.trunc();
+    // i64::MAX as f64 rounds up to 2**63, which is out of range
+    if truncated >= i64::MIN as f64 && truncated < i64::MAX as f64 {
+        #[expect(clippy::cast_possible_truncation, reason = "bounds checked above")]
+        let result = truncated as i64;
+        return Ok(Value::Int(result));
+    }
+    let Some(big) = BigInt::from_f64(truncated) else {
+        return Err(ExcType::overflow_error_float_inf_to_int());
+    };
+    Ok(LongInt::new(big).into_value(heap)?)
+}
+
+/// Validates the `base` argument of `int()`: an integer that is 0 or between 2 and 36.
+fn int_base(base: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<u32> {
+    let base = match base {
+        Value::Int(i) => Some(*i),
+        Value::Bool(b) => Some(i64::from(*b)),
+        Value::Ref(heap_id) if matches!(heap.get(*heap_id), HeapData::LongInt(_)) => None,
+        _ => return Err(ExcType::type_error_not_integer(base.py_type(heap))),
+    };
+    match base {
+        Some(base @ (0 | 2..=36)) => Ok(u32::try_from(base).expect("base is in range")),
+        _ => Err(ExcType::value_error_int_base()),
+    }
+}
+
+/// An integer parsed from a string, kept unallocated until the string is no longer borrowed.
+enum IntLiteral {
+    Small(i64),
+    Big(BigInt),
+}
+
+impl IntLiteral {
+    /// Converts the literal to `Value::Int`, or a `LongInt` when it doesn't fit in i64.
+    fn into_value(self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        match self {
+            Self::Small(i) => Ok(Value::Int(i)),
+            Self::Big(big) => Ok(LongInt::new(big).into_value(heap)?),
+        }
+    }
+}
+
+/// Parses a string or bytes argument of `int()` in the given base (0 infers it from the prefix).
+fn parse_int_arg(text: TextArg<'_>, base: u32) -> RunResult<IntLiteral> {
+    text.as_str().and_then(|s| parse_int_literal(s, base)).ok_or_else(|| {
+        // CPython truncates the repr to 200 characters
+        let repr: String = text.repr().chars().take(200).collect();
+        ExcType::value_error_invalid_int_literal(base, &repr)
+    })
+}
+
+/// Parses an integer literal following CPython's `int(str, base)` rules.
+///
+/// Accepts surrounding whitespace, a sign, a `0x`/`0o`/`0b` prefix matching the base (or
+/// choosing it when `base` is 0), and single underscores between digits or after the prefix.
+/// With base 0 and no prefix, non-zero numbers can't have leading zeros.
+fn parse_int_literal(text: &str, base: u32) -> Option<IntLiteral> {
+    let s = text.trim();
+    let (negative, s) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+
+    let prefix_base = match s.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => Some(16),
+        Some("0o") => Some(8),
+        Some("0b") => Some(2),
+        _ => None,
+    };
+    let (radix, digits, has_prefix) = match prefix_base {
+        Some(prefix_base) if base == 0 || base == prefix_base => (prefix_base, &s[2..], true),
+        _ => (if base == 0 { 10 } else { base }, s, false),
+    };
+
+    let mut cleaned = String::with_capacity(digits.len() + 1);
+    if negative {
+        cleaned.push('-');
+    }
+    let mut last_was_digit = false;
+    for (i, c) in digits.chars().enumerate() {
+        if c == '_' {
+            if !last_was_digit && !(i == 0 && has_prefix) {
+                return None;
+            }
+            last_was_digit = false;
+        } else {
+            c.to_digit(radix)?;
+            cleaned.push(c);
+            last_was_digit = true;
+        }
+    }
+    // Rejects empty digits and a trailing underscore
+    if !last_was_digit {
+        return None;
+    }
+
+    let unsigned = cleaned.trim_start_matches('-');
+    if base == 0 && !has_prefix && unsigned.starts_with('0') && unsigned.bytes().any(|b| b != b'0') {
+        return None;
+    }
+
+    match i64::from_str_radix(&cleaned, radix) {
+        Ok(i) => Some(IntLiteral::Small(i)),
+        Err(_) => BigInt::parse_bytes(cleaned.as_bytes(), radix).map(IntLiteral::Big),
+    }
+}
+
+/// Parses a string or bytes argument of `float()`.
+fn parse_float_arg(text: TextArg<'_>) -> RunResult<f64> {
+    text.as_str()
+        .and_then(parse_float_literal)
+        .ok_or_else(|| ExcType::value_error_invalid_float_literal(&text.repr()))
+}
+
+/// Parses a float literal following CPython's `float(str)` rules.
+///
+/// Accepts surrounding whitespace, the special values `inf`, `infinity` and `nan` with an
+/// optional sign (case-insensitive), and underscores only between two digits.
+fn parse_float_literal(text: &str) -> Option<f64> {
+    let s = text.trim();
+    let (negative, unsigned) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let special = match unsigned.to_ascii_lowercase().as_str() {
+        "inf" | "infinity" => Some(f64::INFINITY),
+        "nan" => Some(f64::NAN),
+        _ => None,
+    };
+    if let Some(special) = special {
+        return Some(if negative { -special } else { special });
+    }
+
+    let bytes = s.as_bytes();
+    let mut cleaned = String::with_capacity(s.len());
+    for (i, c) in s.char_indices() {
+        if c == '_' {
+            let between_digits =
+                i > 0 && bytes[i - 1].is_ascii_digit() && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+            if !between_digits {
+                return None;
+            }
+        } else if c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E') {
+            cleaned.push(c);
+        } else {
+            // Rules out the special values in forms Python rejects, like "in_f" or "nan(1)"
+            return None;
+        }
+    }
+    cleaned.parse().ok()
+}
+
+/// Returns the text of the `encoding` or `errors` argument of `str()`.
+fn str_arg<'a>(
+    param: &str,
+    value: &Value,
+    heap: &'a Heap<impl ResourceTracker>,
+    interns: &'a Interns,
+) -> RunResult<&'a str> {
+    match TextArg::from_value(value, heap, interns) {
+        Some(TextArg::Str(s)) => Ok(s),
+        _ => Err(ExcType::type_error(format!(
+            "str() argument '{param}' must be str, not {}",
+            value.py_type(heap)
+        ))),
+    }
+}
+
+/// Decodes UTF-8 bytes with the `strict`, `replace` or `ignore` error handler.
+fn decode_utf8(bytes: &[u8], errors: &str) -> RunResult<String> {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return Ok(s.to_owned());
+    }
+    match errors {
+        "strict" => Err(ExcType::unicode_decode_error_invalid_utf8()),
+        "replace" => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        "ignore" => Ok(bytes.utf8_chunks().map(|chunk| chunk.valid()).collect()),
+        _ => Err(ExcType::lookup_error_unknown_error_handler(errors)),
+    }
+}
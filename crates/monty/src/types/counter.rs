@@ -0,0 +1,276 @@
+//! `collections.Counter` support.
+//!
+//! A Counter is a [`Dict`] of kind [`DictKind::Counter`] mapping elements to their counts,
+//! so it behaves like a dict everywhere except for missing keys, which count as zero.
+//! This module implements its constructor and the methods it adds to or changes from
+//! `dict`'s: `update()` and `subtract()` (which add to the counts instead of replacing
+//! them), `most_common()`, `elements()` and `total()`.
+//!
+//! The arithmetic and comparison operators between Counters are not supported.
+
+use std::cmp::Ordering;
+
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::Interns,
+    resource::{ResourceTracker, check_repeat_size},
+    types::{Dict, List, MontyIter, PyTrait, allocate_tuple, dict::DictKind, iter::get_iter},
+    value::Value,
+};
+
+/// Creates a Counter from the `collections.Counter(iterable_or_mapping=None, /, **kwds)`
+/// constructor call.
+pub(crate) fn counter_init(
+    heap: &mut Heap<impl ResourceTracker>,
+    args: ArgValues,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let counter = Dict::with_kind(DictKind::Counter);
+    let mut counter_guard = HeapGuard::new(counter, heap);
+    let (counter, heap) = counter_guard.as_parts_mut();
+    counter_update(counter, None, args, false, heap, interns)?;
+    let (counter, heap) = counter_guard.into_parts();
+    Ok(Value::Ref(heap.allocate(HeapData::Dict(counter))?))
+}
+
+/// Implements `Counter.update()` and `Counter.subtract()`.
+///
+/// Counts the items of an iterable, or adds (subtracts) the counts of a mapping, then
+/// does the same with the keyword arguments. `self_id` is the heap id of `counter` when
+/// it is called as a method: the counter's data is taken out of the heap during the call,
+/// so `c.update(c)` reads the counts from `counter` itself.
+pub(crate) fn counter_update(
+    counter: &mut Dict,
+    self_id: Option<HeapId>,
+    args: ArgValues,
+    subtract: bool,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let (positional, kwargs) = args.into_parts();
+    defer_drop_mut!(positional, heap);
+    let kwargs = kwargs.into_iter();
+    defer_drop_mut!(kwargs, heap);
+
+    let count = positional.len();
+    if count > 1 {
+        return Err(ExcType::type_error(format!("expected at most 1 argument, got {count}")));
+    }
+    if let Some(other) = positional.next() {
+        defer_drop!(other, heap);
+        let pairs = match other {
+            Value::Ref(id) if Some(*id) == self_id => Some(copy_pairs(counter)),
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::Dict(dict) => Some(copy_pairs(dict)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(pairs) = pairs {
+            // A mapping: add its counts, taking references once the borrow is released
+            for (key, value) in &pairs {
+                if let Value::Ref(key_id) = key {
+                    heap.inc_ref(*key_id);
+                }
+                if let Value::Ref(value_id) = value {
+                    heap.inc_ref(*value_id);
+                }
+            }
+            let pairs = pairs.into_iter();
+            defer_drop_mut!(pairs, heap);
+            for (key, value) in pairs {
+                counter_add(counter, key, value, subtract, heap, interns)?;
+            }
+        } else {
+            // An iterable: count its items
+            let iter = MontyIter::new(other.clone_with_heap(heap), heap, interns)?;
+            defer_drop_mut!(iter, heap);
+            while let Some(item) = iter.for_next(heap, interns)? {
+                counter_add(counter, item, Value::Int(1), subtract, heap, interns)?;
+            }
+        }
+    }
+    for (key, value) in kwargs {
+        counter_add(counter, key, value, subtract, heap, interns)?;
+    }
+    Ok(Value::None)
+}
+
+/// Implements `Counter.most_common([n])`: a list of `(element, count)` tuples from the
+/// most common element to the least, with elements of equal counts in insertion order.
+pub(crate) fn counter_most_common(
+    counter: &Dict,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Value> {
+    let n = args.get_zero_one_arg("Counter.most_common", heap)?;
+    defer_drop!(n, heap);
+    let limit = match n {
+        None | Some(Value::None) => counter.len(),
+        Some(Value::Int(n)) => usize::try_from(*n).unwrap_or(0),
+        Some(Value::Bool(b)) => usize::from(*b),
+        Some(other) => {
+            return Err(ExcType::type_error(format!(
+                "'{}' object cannot be interpreted as an integer",
+                other.py_type(heap)
+            )));
+        }
+    };
+    let Some(order) = counter_order(counter, heap) else {
+        return Err(ExcType::type_error("counts must be numbers to be ordered"));
+    };
+
+    let mut items = Vec::with_capacity(limit.min(order.len()));
+    for index in order.into_iter().take(limit) {
+        let (key, value) = counter.item_at(index).expect("index comes from the counter");
+        let (key, value) = (key.clone_with_heap(heap), value.clone_with_heap(heap));
+        match allocate_tuple(smallvec![key, value], heap) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                items.drop_with_heap(heap);
+                return Err(err.into());
+            }
+        }
+    }
+    Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+}
+
+/// Implements `Counter.elements()`: an iterator repeating each element as many times as
+/// its count, skipping elements with a count below one.
+pub(crate) fn counter_elements(
+    counter: &Dict,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    args.check_zero_args("Counter.elements", heap)?;
+    let mut repeats = Vec::with_capacity(counter.len());
+    for (_, count) in counter {
+        let repeat = match count {
+            Value::Int(n) => usize::try_from(*n).unwrap_or(0),
+            Value::Bool(b) => usize::from(*b),
+            other => {
+                return Err(ExcType::type_error(format!(
+                    "'{}' object cannot be interpreted as an integer",
+                    other.py_type(heap)
+                )));
+            }
+        };
+        repeats.push(repeat);
+    }
+    let total = repeats
+        .iter()
+        .fold(0usize, |total, repeat| total.saturating_add(*repeat));
+    check_repeat_size(size_of::<Value>(), total, heap.tracker())?;
+
+    let mut items = Vec::with_capacity(total);
+    for ((key, _), repeat) in counter.iter().zip(repeats) {
+        for _ in 0..repeat {
+            items.push(key.clone_with_heap(heap));
+        }
+    }
+    let list_id = heap.allocate(HeapData::List(List::new(items)))?;
+    get_iter(Value::Ref(list_id), heap, interns)
+}
+
+/// Implements `Counter.total()`: the sum of the counts.
+pub(crate) fn counter_total(
+    counter: &Dict,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    args.check_zero_args("Counter.total", heap)?;
+    let mut total = Value::Int(0);
+    for (_, count) in counter {
+        let sum = match total.py_add(count, heap, interns) {
+            Ok(Some(sum)) => sum,
+            Ok(None) => {
+                let err = ExcType::binary_type_error("+", total.py_type(heap), count.py_type(heap));
+                total.drop_with_heap(heap);
+                return Err(err);
+            }
+            Err(err) => {
+                total.drop_with_heap(heap);
+                return Err(err.into());
+            }
+        };
+        total.drop_with_heap(heap);
+        total = sum;
+    }
+    Ok(total)
+}
+
+/// Returns the indices of the counter's entries from the highest count to the lowest,
+/// keeping insertion order between equal counts like CPython's stable sort.
+///
+/// Returns `None` if any count is not a number, in which case there is no order.
+pub(crate) fn counter_order(counter: &Dict, heap: &Heap<impl ResourceTracker>) -> Option<Vec<usize>> {
+    let counts: Vec<f64> = counter
+        .iter()
+        .map(|(_, count)| count_as_f64(count, heap))
+        .collect::<Option<_>>()?;
+    let mut order: Vec<usize> = (0..counts.len()).collect();
+    order.sort_by(|&a, &b| counts[b].partial_cmp(&counts[a]).unwrap_or(Ordering::Equal));
+    Some(order)
+}
+
+/// Converts a numeric count to `f64` for ordering, returning `None` for other values.
+#[expect(clippy::cast_precision_loss)]
+fn count_as_f64(count: &Value, heap: &Heap<impl ResourceTracker>) -> Option<f64> {
+    match count {
+        Value::Int(i) => Some(*i as f64),
+        Value::Bool(b) => Some(f64::from(u8::from(*b))),
+        Value::Float(f) => Some(*f),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::LongInt(li) => li.to_f64(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds `count` to (or subtracts it from) the count of `key`, which starts at zero.
+fn counter_add(
+    counter: &mut Dict,
+    key: Value,
+    count: Value,
+    subtract: bool,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<()> {
+    defer_drop!(count, heap);
+    let mut key_guard = HeapGuard::new(key, heap);
+    let (key, heap) = key_guard.as_parts();
+    let current = counter.get(key, heap, interns)?.unwrap_or(&Value::Int(0));
+    let result = if subtract {
+        current.py_sub(count, heap)?
+    } else {
+        current.py_add(count, heap, interns)?
+    };
+    let Some(result) = result else {
+        let op = if subtract { "-" } else { "+" };
+        return Err(ExcType::binary_type_error(
+            op,
+            current.py_type(heap),
+            count.py_type(heap),
+        ));
+    };
+    let (key, heap) = key_guard.into_parts();
+    if let Some(old_value) = counter.set(key, result, heap, interns)? {
+        old_value.drop_with_heap(heap);
+    }
+    Ok(())
+}
+
+/// Copies the key-value pairs of `dict` without incrementing their refcounts.
+fn copy_pairs(dict: &Dict) -> Vec<(Value, Value)> {
+    dict.iter()
+        .map(|(key, value)| (key.copy_for_extend(), value.copy_for_extend()))
+        .collect()
+}
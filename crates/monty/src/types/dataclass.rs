@@ -9,7 +9,7 @@ use crate::{
     defer_drop,
     exception_private::{ExcType, RunResult},
     heap::{Heap, HeapId},
-    intern::{Interns, StringId},
+    intern::{Interns, StaticStrings, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::{AttrCallResult, Type},
     value::{EitherStr, Value},
@@ -284,7 +284,8 @@ impl PyTrait for Dataclass {
     ///
     /// If the attribute is a public name (no leading underscore) not found in the
     /// dataclass's attrs dict, returns `MethodCall` so the VM yields to the host.
-    /// Otherwise falls through to `py_call_attr`.
+    /// `__enter__` and `__exit__` are also forwarded so host objects can be used in
+    /// `with` statements. Otherwise falls through to `py_call_attr`.
     fn py_call_attr_raw(
         &mut self,
         self_id: HeapId,
@@ -293,8 +294,14 @@ impl PyTrait for Dataclass {
         args: ArgValues,
     ) -> RunResult<AttrCallResult> {
         let attr_str = attr.as_str(vm.interns);
-        // Only public methods (no underscore prefix = no dunders, no private)
-        if !attr_str.starts_with('_') && self.attrs.get_by_str(attr_str, vm.heap, vm.interns).is_none() {
+        // Only public methods (no underscore prefix = no dunders, no private), plus the
+        // context manager protocol
+        let is_method_name = !attr_str.starts_with('_')
+            || matches!(
+                attr.static_string(),
+                Some(StaticStrings::DunderEnter | StaticStrings::DunderExit)
+            );
+        if is_method_name && self.attrs.get_by_str(attr_str, vm.heap, vm.interns).is_none() {
             // Clone self and prepend to args for the method call
             // inc_ref works even when data is taken out (refcount metadata is separate)
             vm.heap.inc_ref(self_id);
@@ -0,0 +1,496 @@
+//! Python `datetime.date` type implementation, plus the proleptic Gregorian calendar
+//! helpers and `strftime()` formatting shared with `datetime.datetime`.
+
+use std::fmt::{self, Write};
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, PyTrait, Type, str::allocate_string},
+    value::{EitherStr, Value},
+};
+
+/// The smallest year supported by `date` and `datetime`, `datetime.MINYEAR`.
+pub(crate) const MINYEAR: i32 = 1;
+/// The largest year supported by `date` and `datetime`, `datetime.MAXYEAR`.
+pub(crate) const MAXYEAR: i32 = 9999;
+/// The ordinal of `date(9999, 12, 31)`.
+const MAX_ORDINAL: i64 = 3_652_059;
+
+/// Days before the first of each month in a non-leap year, indexed from 1.
+const DAYS_BEFORE_MONTH: [i64; 13] = [0, 0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+const DAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Returns whether `year` is a leap year in the proleptic Gregorian calendar.
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days in `month` of `year`.
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days before January 1st of `year`.
+fn days_before_year(year: i32) -> i64 {
+    let y = i64::from(year) - 1;
+    y * 365 + y / 4 - y / 100 + y / 400
+}
+
+/// Python `datetime.date`, a naive calendar date.
+///
+/// Fields are ordered so the derived ordering is chronological.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Creates a date, validating each field like CPython.
+    pub fn new(year: i64, month: i64, day: i64) -> RunResult<Self> {
+        let year = match i32::try_from(year) {
+            Ok(y) if (MINYEAR..=MAXYEAR).contains(&y) => y,
+            _ => return Err(value_error(format!("year {year} is out of range"))),
+        };
+        let month = match u8::try_from(month) {
+            Ok(m) if (1..=12).contains(&m) => m,
+            _ => return Err(value_error("month must be in 1..12")),
+        };
+        match u8::try_from(day) {
+            Ok(d) if d >= 1 && d <= days_in_month(year, month) => Ok(Self { year, month, day: d }),
+            _ => Err(value_error("day is out of range for month")),
+        }
+    }
+
+    /// Returns the date with the given proleptic Gregorian ordinal, where January 1st of
+    /// year 1 is day 1, or `None` if it is outside the supported range.
+    #[must_use]
+    pub fn from_ordinal(ordinal: i64) -> Option<Self> {
+        if !(1..=MAX_ORDINAL).contains(&ordinal) {
+            return None;
+        }
+        // Same approach as CPython's `_ord2ymd`: peel off 400-, 100-, 4- and 1-year cycles
+        let n = ordinal - 1;
+        let (n400, n) = (n / 146_097, n % 146_097);
+        let (n100, n) = (n / 36_524, n % 36_524);
+        let (n4, n) = (n / 1_461, n % 1_461);
+        let (n1, n) = (n / 365, n % 365);
+        let year = i32::try_from(n400 * 400 + n100 * 100 + n4 * 4 + n1 + 1).expect("year in range");
+        if n1 == 4 || n100 == 4 {
+            // The last day of a leap year
+            return Some(Self {
+                year: year - 1,
+                month: 12,
+                day: 31,
+            });
+        }
+        let leap = i64::from(n1 == 3 && (n4 != 24 || n100 == 3));
+        let mut month = (n + 50) >> 5;
+        let mut preceding =
+            DAYS_BEFORE_MONTH[usize::try_from(month).expect("month in 1..=12")] + if month > 2 { leap } else { 0 };
+        if preceding > n {
+            month -= 1;
+            let month_u8 = u8::try_from(month).expect("month in 1..=12");
+            preceding -= i64::from(days_in_month(year, month_u8));
+        }
+        Some(Self {
+            year,
+            month: u8::try_from(month).expect("month in 1..=12"),
+            day: u8::try_from(n - preceding + 1).expect("day in 1..=31"),
+        })
+    }
+
+    /// Returns the year, in `MINYEAR..=MAXYEAR`.
+    #[must_use]
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, in `1..=12`.
+    #[must_use]
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, starting from 1.
+    #[must_use]
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    /// Returns the proleptic Gregorian ordinal, where January 1st of year 1 is day 1.
+    #[must_use]
+    pub fn to_ordinal(self) -> i64 {
+        let leap_day = i64::from(self.month > 2 && is_leap(self.year));
+        days_before_year(self.year) + DAYS_BEFORE_MONTH[usize::from(self.month)] + leap_day + i64::from(self.day)
+    }
+
+    /// Returns the day of the week, where Monday is 0 and Sunday is 6.
+    #[must_use]
+    pub fn weekday(self) -> i64 {
+        (self.to_ordinal() + 6) % 7
+    }
+
+    /// Returns the ISO 8601 `(year, week, weekday)` of this date, where weeks start on
+    /// Monday and week 1 contains the year's first Thursday.
+    fn iso_calendar(self) -> (i32, i64, i64) {
+        let week1_monday = |year: i32| {
+            let first = days_before_year(year) + 1;
+            let monday = first - (first + 6) % 7;
+            if (first + 6) % 7 > 3 { monday + 7 } else { monday }
+        };
+        let ordinal = self.to_ordinal();
+        let mut year = self.year;
+        let mut start = week1_monday(year);
+        if ordinal < start {
+            year -= 1;
+            start = week1_monday(year);
+        } else if ordinal >= week1_monday(year + 1) {
+            year += 1;
+            start = week1_monday(year);
+        }
+        (year, (ordinal - start) / 7 + 1, self.weekday() + 1)
+    }
+
+    /// Returns the date `days` days later, or an `OverflowError` if it is out of range.
+    pub fn add_days(self, days: i64) -> RunResult<Self> {
+        Self::from_ordinal(self.to_ordinal() + days).ok_or_else(ExcType::overflow_error_date_out_of_range)
+    }
+
+    /// Creates a `date` from the `date(year, month, day)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let fields = args.bind("date", ["year", "month", "day"], 3, heap, interns)?;
+        defer_drop!(fields, heap);
+        let [year, month, day] = int_fields(fields, [0; 3], heap)?;
+        let date = Self::new(year, month, day)?;
+        Ok(Value::Ref(heap.allocate(HeapData::Date(date))?))
+    }
+
+    /// Implementation of the `date.fromisoformat(date_string)` classmethod.
+    pub fn fromisoformat(
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let arg = args.get_one_arg("fromisoformat", heap)?;
+        defer_drop!(arg, heap);
+        let Some(s) = arg.as_str_ref(heap, interns) else {
+            return Err(ExcType::type_error("fromisoformat: argument must be str"));
+        };
+        let date = parse_iso_date(s)?.ok_or_else(|| ExcType::value_error_invalid_isoformat(s))?;
+        Ok(Value::Ref(heap.allocate(HeapData::Date(date))?))
+    }
+
+    /// Implementation of the `date.fromordinal(ordinal)` classmethod.
+    pub fn fromordinal(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
+        let arg = args.get_one_arg("fromordinal", heap)?;
+        defer_drop!(arg, heap);
+        let ordinal = int_field(arg, heap)?;
+        let Some(date) = Self::from_ordinal(ordinal) else {
+            if ordinal < 1 {
+                return Err(value_error("ordinal must be >= 1"));
+            }
+            return Err(value_error(format!("year {} is out of range", ordinal / 365 + 1)));
+        };
+        Ok(Value::Ref(heap.allocate(HeapData::Date(date))?))
+    }
+}
+
+/// Formats like `date.isoformat()`: `YYYY-MM-DD`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl PyTrait for Date {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Date
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "datetime.date({}, {}, {})", self.year, self.month, self.day)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Date doesn't contain heap references, nothing to do
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::Date, attr.as_str(interns)));
+        };
+        match method {
+            StaticStrings::Weekday => {
+                args.check_zero_args("weekday", heap)?;
+                Ok(Value::Int(self.weekday()))
+            }
+            StaticStrings::Isoweekday => {
+                args.check_zero_args("isoweekday", heap)?;
+                Ok(Value::Int(self.weekday() + 1))
+            }
+            StaticStrings::Toordinal => {
+                args.check_zero_args("toordinal", heap)?;
+                Ok(Value::Int(self.to_ordinal()))
+            }
+            StaticStrings::Isoformat => {
+                args.check_zero_args("isoformat", heap)?;
+                allocate_string(self.to_string(), heap)
+            }
+            StaticStrings::Strftime => {
+                let format = args.get_one_arg("strftime", heap)?;
+                defer_drop!(format, heap);
+                let format = format_arg(format, heap, interns)?;
+                allocate_string(strftime(format, *self, [0; 4]), heap)
+            }
+            StaticStrings::Replace => {
+                let fields = args.bind("replace", ["year", "month", "day"], 0, heap, interns)?;
+                defer_drop!(fields, heap);
+                let current = [self.year, self.month.into(), self.day.into()].map(i64::from);
+                let [year, month, day] = int_fields(fields, current, heap)?;
+                let date = Self::new(year, month, day)?;
+                Ok(Value::Ref(heap.allocate(HeapData::Date(date))?))
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::Date, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Year) => i64::from(self.year),
+            Some(StaticStrings::Month) => i64::from(self.month),
+            Some(StaticStrings::Day) => i64::from(self.day),
+            _ => return Err(ExcType::attribute_error(Type::Date, interns.get_str(attr_id))),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(value))))
+    }
+}
+
+/// Creates a `ValueError` with the given message.
+fn value_error(msg: impl fmt::Display) -> RunError {
+    SimpleException::new_msg(ExcType::ValueError, msg).into()
+}
+
+/// Converts an integer field of a date or time, which like CPython may be a bool but not
+/// a float.
+pub(crate) fn int_field(value: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<i64> {
+    match value {
+        Value::Bool(b) => Ok(i64::from(*b)),
+        _ => value.as_int(heap),
+    }
+}
+
+/// Converts the bound integer fields of a constructor or `replace()` call, using
+/// `defaults` for the fields that were not passed.
+pub(crate) fn int_fields<const N: usize>(
+    fields: &[Option<Value>; N],
+    defaults: [i64; N],
+    heap: &Heap<impl ResourceTracker>,
+) -> RunResult<[i64; N]> {
+    let mut result = defaults;
+    for (slot, field) in result.iter_mut().zip(fields) {
+        if let Some(field) = field {
+            *slot = int_field(field, heap)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the string argument of `strftime()`.
+pub(crate) fn format_arg<'a>(
+    format: &Value,
+    heap: &'a Heap<impl ResourceTracker>,
+    interns: &'a Interns,
+) -> RunResult<&'a str> {
+    format.as_str_ref(heap, interns).ok_or_else(|| {
+        ExcType::type_error(format!(
+            "strftime() argument 1 must be str, not {}",
+            format.py_type(heap)
+        ))
+    })
+}
+
+/// Parses the date part of an ISO 8601 string, `YYYY-MM-DD` or `YYYYMMDD`.
+///
+/// Returns `Ok(None)` if the string is not in either form, and a `ValueError` if it is
+/// but names a day that does not exist.
+pub(crate) fn parse_iso_date(s: &str) -> RunResult<Option<Date>> {
+    let digits = |part: &str| -> Option<i64> {
+        if part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    let parts = match s.len() {
+        10 if s.as_bytes()[4] == b'-' && s.as_bytes()[7] == b'-' => s
+            .get(0..4)
+            .zip(s.get(5..7))
+            .zip(s.get(8..10))
+            .map(|((y, m), d)| (y, m, d)),
+        8 => s
+            .get(0..4)
+            .zip(s.get(4..6))
+            .zip(s.get(6..8))
+            .map(|((y, m), d)| (y, m, d)),
+        _ => None,
+    };
+    let Some((year, month, day)) = parts.and_then(|(y, m, d)| Some((digits(y)?, digits(m)?, digits(d)?))) else {
+        return Ok(None);
+    };
+    Date::new(year, month, day).map(Some)
+}
+
+/// Formats a date and time like C's `strftime()` in the C locale, as CPython does on Linux.
+///
+/// `time` holds the hour, minute, second and microsecond, all zero for a `date`. The
+/// timezone directives `%z` and `%Z` produce empty strings since only naive values
+/// exist, and unknown directives are copied through unchanged.
+pub(crate) fn strftime(format: &str, date: Date, time: [u32; 4]) -> String {
+    let [hour, minute, second, microsecond] = time;
+    let weekday = date.weekday();
+    let day_of_year = date.to_ordinal() - days_before_year(date.year);
+    // Week numbers for %U (weeks starting on Sunday) and %W (weeks starting on Monday)
+    let sunday_week = (day_of_year + 6 - (weekday + 1) % 7) / 7;
+    let monday_week = (day_of_year + 6 - weekday) / 7;
+    let day_name = DAY_NAMES[usize::try_from(weekday).expect("weekday in 0..7")];
+    let month_name = MONTH_NAMES[usize::from(date.month - 1)];
+    let hour12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+
+    let mut out = String::with_capacity(format.len() * 2);
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let Some(directive) = chars.next() else {
+            out.push('%');
+            break;
+        };
+        // Writing to a String cannot fail
+        let _ = match directive {
+            'a' => write!(out, "{}", &day_name[..3]),
+            'A' => write!(out, "{day_name}"),
+            'w' => write!(out, "{}", (weekday + 1) % 7),
+            'u' => write!(out, "{}", weekday + 1),
+            'd' => write!(out, "{:02}", date.day),
+            'e' => write!(out, "{:2}", date.day),
+            'b' | 'h' => write!(out, "{}", &month_name[..3]),
+            'B' => write!(out, "{month_name}"),
+            'm' => write!(out, "{:02}", date.month),
+            'y' => write!(out, "{:02}", date.year % 100),
+            'Y' => write!(out, "{}", date.year),
+            'C' => write!(out, "{}", date.year / 100),
+            'G' => write!(out, "{}", date.iso_calendar().0),
+            'V' => write!(out, "{:02}", date.iso_calendar().1),
+            'H' => write!(out, "{hour:02}"),
+            'I' => write!(out, "{hour12:02}"),
+            'p' => write!(out, "{}", if hour < 12 { "AM" } else { "PM" }),
+            'M' => write!(out, "{minute:02}"),
+            'S' => write!(out, "{second:02}"),
+            'f' => write!(out, "{microsecond:06}"),
+            'j' => write!(out, "{day_of_year:03}"),
+            'U' => write!(out, "{sunday_week:02}"),
+            'W' => write!(out, "{monday_week:02}"),
+            'c' => write!(
+                out,
+                "{} {} {:2} {hour:02}:{minute:02}:{second:02} {}",
+                &day_name[..3],
+                &month_name[..3],
+                date.day,
+                date.year
+            ),
+            'x' | 'D' => write!(out, "{:02}/{:02}/{:02}", date.month, date.day, date.year % 100),
+            'X' | 'T' => write!(out, "{hour:02}:{minute:02}:{second:02}"),
+            'R' => write!(out, "{hour:02}:{minute:02}"),
+            'F' => write!(out, "{}-{:02}-{:02}", date.year, date.month, date.day),
+            'n' => out.write_char('\n'),
+            't' => out.write_char('\t'),
+            '%' => out.write_char('%'),
+            'z' | 'Z' => Ok(()),
+            other => write!(out, "%{other}"),
+        };
+    }
+    out
+}
@@ -0,0 +1,398 @@
+//! Python `datetime.datetime` type implementation.
+
+use std::fmt::{self, Write};
+
+use ahash::AHashSet;
+use num_integer::Integer;
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Date, PyTrait, Type,
+        date::{format_arg, int_fields, parse_iso_date, strftime},
+        str::allocate_string,
+        timedelta::{US_PER_DAY, US_PER_SECOND},
+    },
+    value::{EitherStr, Value},
+};
+
+/// Python `datetime.datetime`, a naive date and time with microsecond resolution.
+///
+/// Fields are ordered so the derived ordering is chronological.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DateTime {
+    date: Date,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    microsecond: u32,
+}
+
+impl DateTime {
+    /// Creates a datetime from a date and time fields, validating the time like CPython.
+    pub fn new(date: Date, hour: i64, minute: i64, second: i64, microsecond: i64) -> RunResult<Self> {
+        let field = |value: i64, max: i64, msg: &str| -> RunResult<i64> {
+            if (0..=max).contains(&value) {
+                Ok(value)
+            } else {
+                Err(SimpleException::new_msg(ExcType::ValueError, msg).into())
+            }
+        };
+        let hour = field(hour, 23, "hour must be in 0..23")?;
+        let minute = field(minute, 59, "minute must be in 0..59")?;
+        let second = field(second, 59, "second must be in 0..59")?;
+        let microsecond = field(microsecond, 999_999, "microsecond must be in 0..999999")?;
+        Ok(Self {
+            date,
+            hour: u8::try_from(hour).expect("checked above"),
+            minute: u8::try_from(minute).expect("checked above"),
+            second: u8::try_from(second).expect("checked above"),
+            microsecond: u32::try_from(microsecond).expect("checked above"),
+        })
+    }
+
+    /// Returns the date part.
+    #[must_use]
+    pub fn date(self) -> Date {
+        self.date
+    }
+
+    /// Returns the hour, in `0..24`.
+    #[must_use]
+    pub fn hour(self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute, in `0..60`.
+    #[must_use]
+    pub fn minute(self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second, in `0..60`.
+    #[must_use]
+    pub fn second(self) -> u8 {
+        self.second
+    }
+
+    /// Returns the microsecond, in `0..1000000`.
+    #[must_use]
+    pub fn microsecond(self) -> u32 {
+        self.microsecond
+    }
+
+    /// Returns the microseconds since midnight.
+    fn time_microseconds(self) -> i128 {
+        (i128::from(self.hour) * 3600 + i128::from(self.minute) * 60 + i128::from(self.second)) * US_PER_SECOND
+            + i128::from(self.microsecond)
+    }
+
+    /// Returns the microseconds since midnight before `date(1, 1, 1)`, used for
+    /// arithmetic between datetimes.
+    #[must_use]
+    pub fn total_microseconds(self) -> i128 {
+        i128::from(self.date.to_ordinal()) * US_PER_DAY + self.time_microseconds()
+    }
+
+    /// Returns the datetime `delta` microseconds later, or an `OverflowError` if it is
+    /// out of range.
+    pub fn add_microseconds(self, delta: i128) -> RunResult<Self> {
+        let (ordinal, time) = (self.total_microseconds() + delta).div_mod_floor(&US_PER_DAY);
+        let date = i64::try_from(ordinal)
+            .ok()
+            .and_then(Date::from_ordinal)
+            .ok_or_else(ExcType::overflow_error_date_out_of_range)?;
+        let (seconds, microsecond) = time.div_mod_floor(&US_PER_SECOND);
+        let seconds = u32::try_from(seconds).expect("seconds < 86400");
+        Ok(Self {
+            date,
+            hour: u8::try_from(seconds / 3600).expect("hour < 24"),
+            minute: u8::try_from(seconds / 60 % 60).expect("minute < 60"),
+            second: u8::try_from(seconds % 60).expect("second < 60"),
+            microsecond: u32::try_from(microsecond).expect("microsecond < 1000000"),
+        })
+    }
+
+    /// Returns the `[hour, minute, second, microsecond]` fields for `strftime()`.
+    fn time_fields(self) -> [u32; 4] {
+        [
+            self.hour.into(),
+            self.minute.into(),
+            self.second.into(),
+            self.microsecond,
+        ]
+    }
+
+    /// Formats like `datetime.isoformat(sep)`.
+    fn isoformat(self, sep: char) -> String {
+        let mut s = format!(
+            "{}{sep}{:02}:{:02}:{:02}",
+            self.date, self.hour, self.minute, self.second
+        );
+        if self.microsecond != 0 {
+            write!(s, ".{:06}", self.microsecond).expect("writing to a String cannot fail");
+        }
+        s
+    }
+
+    /// Creates a `datetime` from the
+    /// `datetime(year, month, day, hour=0, minute=0, second=0, microsecond=0)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let fields = args.bind("datetime", FIELDS, 3, heap, interns)?;
+        defer_drop!(fields, heap);
+        let [year, month, day, hour, minute, second, microsecond] = int_fields(fields, [0; 7], heap)?;
+        let dt = Self::new(Date::new(year, month, day)?, hour, minute, second, microsecond)?;
+        Ok(Value::Ref(heap.allocate(HeapData::DateTime(dt))?))
+    }
+
+    /// Implementation of the `datetime.fromisoformat(date_string)` classmethod.
+    ///
+    /// Accepts a date in either ISO 8601 form, optionally followed by any single
+    /// separator character and a time of the form `HH[:MM[:SS[.ffffff]]]` or
+    /// `HH[MM[SS[.ffffff]]]`. Timezone offsets are not supported.
+    pub fn fromisoformat(
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let arg = args.get_one_arg("fromisoformat", heap)?;
+        defer_drop!(arg, heap);
+        let Some(s) = arg.as_str_ref(heap, interns) else {
+            return Err(ExcType::type_error("fromisoformat: argument must be str"));
+        };
+        let invalid = || ExcType::value_error_invalid_isoformat(s);
+        // The date part is 10 characters with dashes, 8 without
+        let date_len = if s.as_bytes().get(4) == Some(&b'-') { 10 } else { 8 };
+        let (date_part, time_part) = match s.get(..date_len) {
+            Some(date_part) if s.len() == date_len => (date_part, None),
+            Some(date_part) => {
+                let mut rest = s[date_len..].chars();
+                rest.next();
+                (date_part, Some(rest.as_str()))
+            }
+            None => return Err(invalid()),
+        };
+        let date = parse_iso_date(date_part)?.ok_or_else(invalid)?;
+        let [hour, minute, second, microsecond] = match time_part {
+            Some(time_part) => parse_iso_time(time_part).ok_or_else(invalid)?,
+            None => [0; 4],
+        };
+        let dt = Self::new(date, hour, minute, second, microsecond)?;
+        Ok(Value::Ref(heap.allocate(HeapData::DateTime(dt))?))
+    }
+}
+
+/// The parameters of the `datetime()` constructor and `datetime.replace()`.
+const FIELDS: [&str; 7] = ["year", "month", "day", "hour", "minute", "second", "microsecond"];
+
+/// Parses an ISO 8601 time into `[hour, minute, second, microsecond]`.
+///
+/// Fractional seconds may have any number of digits and are truncated to microseconds.
+fn parse_iso_time(s: &str) -> Option<[i64; 4]> {
+    let two_digits = |part: &str| -> Option<i64> {
+        if part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    if !s.is_ascii() {
+        return None;
+    }
+    let (hms, fraction) = match s.find(['.', ',']) {
+        Some(pos) => (&s[..pos], Some(&s[pos + 1..])),
+        None => (s, None),
+    };
+    let extended = hms.contains(':');
+    let parts: Vec<&str> = if extended {
+        hms.split(':').collect()
+    } else {
+        (0..hms.len())
+            .step_by(2)
+            .map(|i| &hms[i..(i + 2).min(hms.len())])
+            .collect()
+    };
+    if parts.is_empty() || parts.len() > 3 || (fraction.is_some() && parts.len() != 3) {
+        return None;
+    }
+    let mut fields = [0; 4];
+    for (field, part) in fields.iter_mut().zip(&parts) {
+        *field = two_digits(part)?;
+    }
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let digits = &fraction[..fraction.len().min(6)];
+        let scale = 10_i64.pow(u32::try_from(6 - digits.len()).expect("at most 6 digits"));
+        fields[3] = digits.parse::<i64>().ok()? * scale;
+    }
+    Some(fields)
+}
+
+/// Formats like `datetime.isoformat(' ')`.
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.isoformat(' '))
+    }
+}
+
+impl PyTrait for DateTime {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::DateTime
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        // Like CPython, trailing zero seconds and microseconds are omitted
+        write!(
+            f,
+            "datetime.datetime({}, {}, {}, {}, {}",
+            self.date.year(),
+            self.date.month(),
+            self.date.day(),
+            self.hour,
+            self.minute
+        )?;
+        if self.second != 0 || self.microsecond != 0 {
+            write!(f, ", {}", self.second)?;
+        }
+        if self.microsecond != 0 {
+            write!(f, ", {}", self.microsecond)?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // DateTime doesn't contain heap references, nothing to do
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::DateTime, attr.as_str(interns)));
+        };
+        match method {
+            StaticStrings::Date => {
+                args.check_zero_args("date", heap)?;
+                Ok(Value::Ref(heap.allocate(HeapData::Date(self.date))?))
+            }
+            StaticStrings::Weekday => {
+                args.check_zero_args("weekday", heap)?;
+                Ok(Value::Int(self.date.weekday()))
+            }
+            StaticStrings::Isoweekday => {
+                args.check_zero_args("isoweekday", heap)?;
+                Ok(Value::Int(self.date.weekday() + 1))
+            }
+            StaticStrings::Toordinal => {
+                args.check_zero_args("toordinal", heap)?;
+                Ok(Value::Int(self.date.to_ordinal()))
+            }
+            StaticStrings::Isoformat => {
+                let [sep] = args.bind("isoformat", ["sep"], 0, heap, interns)?;
+                defer_drop!(sep, heap);
+                let sep = match sep {
+                    None => 'T',
+                    Some(value) => {
+                        let mut chars = value.as_str_ref(heap, interns).unwrap_or_default().chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => c,
+                            _ => {
+                                return Err(ExcType::type_error(format!(
+                                    "isoformat() argument 1 must be a unicode character, not {}",
+                                    value.py_type(heap)
+                                )));
+                            }
+                        }
+                    }
+                };
+                allocate_string(self.isoformat(sep), heap)
+            }
+            StaticStrings::Strftime => {
+                let format = args.get_one_arg("strftime", heap)?;
+                defer_drop!(format, heap);
+                let format = format_arg(format, heap, interns)?;
+                allocate_string(strftime(format, self.date, self.time_fields()), heap)
+            }
+            StaticStrings::Replace => {
+                let fields = args.bind("replace", FIELDS, 0, heap, interns)?;
+                defer_drop!(fields, heap);
+                let current = [
+                    i64::from(self.date.year()),
+                    i64::from(self.date.month()),
+                    i64::from(self.date.day()),
+                    i64::from(self.hour),
+                    i64::from(self.minute),
+                    i64::from(self.second),
+                    i64::from(self.microsecond),
+                ];
+                let [year, month, day, hour, minute, second, microsecond] = int_fields(fields, current, heap)?;
+                let dt = Self::new(Date::new(year, month, day)?, hour, minute, second, microsecond)?;
+                Ok(Value::Ref(heap.allocate(HeapData::DateTime(dt))?))
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::DateTime, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Year) => i64::from(self.date.year()),
+            Some(StaticStrings::Month) => i64::from(self.date.month()),
+            Some(StaticStrings::Day) => i64::from(self.date.day()),
+            Some(StaticStrings::Hour) => i64::from(self.hour),
+            Some(StaticStrings::Minute) => i64::from(self.minute),
+            Some(StaticStrings::Second) => i64::from(self.second),
+            Some(StaticStrings::Microsecond) => i64::from(self.microsecond),
+            _ => return Err(ExcType::attribute_error(Type::DateTime, interns.get_str(attr_id))),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(value))))
+    }
+}
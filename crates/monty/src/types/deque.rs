@@ -0,0 +1,473 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use ahash::AHashSet;
+
+use super::{AttrCallResult, MontyIter, PyTrait};
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop, defer_drop_mut,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{Type, list::repr_sequence_fmt},
+    value::{EitherStr, Value},
+};
+
+/// Python `collections.deque` type, a double-ended queue wrapping a `VecDeque` of Values.
+///
+/// Appending and popping at either end is O(1). An optional `maxlen` bounds the length:
+/// once full, adding items at one end discards the same number of items from the other.
+///
+/// # Implemented Methods
+/// - `append(item)` / `appendleft(item)` - Add item to the right / left end
+/// - `pop()` / `popleft()` - Remove and return the rightmost / leftmost item
+/// - `extend(iterable)` / `extendleft(iterable)` - Add items at the right / left end
+/// - `rotate([n])` - Rotate `n` steps to the right (to the left if negative)
+/// - `remove(value)` - Remove first occurrence of value
+/// - `count(value)` - Count occurrences
+/// - `clear()` - Remove all items
+/// - `copy()` - Shallow copy, keeping `maxlen`
+/// - `reverse()` - Reverse in place
+///
+/// # GC Optimization
+/// Like `List`, the `contains_refs` flag lets `collect_child_ids` and `py_dec_ref_ids`
+/// skip iteration when the deque only ever held primitive values.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Deque {
+    items: VecDeque<Value>,
+    /// Maximum length from the `maxlen` argument, `None` for an unbounded deque.
+    maxlen: Option<usize>,
+    /// True if any item in the deque is a `Value::Ref`.
+    contains_refs: bool,
+}
+
+impl Deque {
+    /// Creates a new deque from a vector of values, keeping the last `maxlen` of them.
+    ///
+    /// Note: This does NOT increment reference counts - the caller transfers ownership
+    /// of `items` to the deque.
+    #[must_use]
+    pub fn new(items: Vec<Value>, maxlen: Option<usize>, heap: &mut Heap<impl ResourceTracker>) -> Self {
+        let mut deque = Self {
+            items: VecDeque::with_capacity(items.len()),
+            maxlen,
+            contains_refs: false,
+        };
+        for item in items {
+            deque.push_back(item, heap);
+        }
+        deque
+    }
+
+    /// Returns the items from left to right.
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.items.iter()
+    }
+
+    /// Returns the item at `index` from the left, if in bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.items.get(index)
+    }
+
+    /// Returns the number of elements in the deque.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns the maximum length, `None` for an unbounded deque.
+    #[must_use]
+    pub fn maxlen(&self) -> Option<usize> {
+        self.maxlen
+    }
+
+    /// Returns whether the deque contains any heap references.
+    #[inline]
+    #[must_use]
+    pub fn contains_refs(&self) -> bool {
+        self.contains_refs
+    }
+
+    /// Creates a deque from the `collections.deque(iterable=(), maxlen=None)` constructor call.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        let [iterable, maxlen] = args.bind("deque", ["iterable", "maxlen"], 0, heap, interns)?;
+        defer_drop!(maxlen, heap);
+        let mut iterable_guard = HeapGuard::new(iterable, heap);
+        let heap = iterable_guard.heap();
+        let maxlen = match maxlen {
+            None | Some(Value::None) => None,
+            Some(value) => {
+                let maxlen = value.as_int(heap)?;
+                let Ok(maxlen) = usize::try_from(maxlen) else {
+                    return Err(SimpleException::new_msg(ExcType::ValueError, "maxlen must be non-negative").into());
+                };
+                Some(maxlen)
+            }
+        };
+        let (iterable, heap) = iterable_guard.into_parts();
+        let items = match iterable {
+            Some(iterable) => MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?,
+            None => Vec::new(),
+        };
+        let deque = Self::new(items, maxlen, heap);
+        Ok(Value::Ref(heap.allocate(HeapData::Deque(deque))?))
+    }
+
+    /// Adds an item at the right end, discarding the leftmost item if the deque is full.
+    ///
+    /// The caller transfers ownership of `item` to the deque.
+    pub fn push_back(&mut self, item: Value, heap: &mut Heap<impl ResourceTracker>) {
+        if self.maxlen == Some(0) {
+            item.drop_with_heap(heap);
+            return;
+        }
+        if self.maxlen == Some(self.items.len())
+            && let Some(discarded) = self.items.pop_front()
+        {
+            discarded.drop_with_heap(heap);
+        }
+        self.mark_ref(&item, heap);
+        self.items.push_back(item);
+    }
+
+    /// Adds an item at the left end, discarding the rightmost item if the deque is full.
+    ///
+    /// The caller transfers ownership of `item` to the deque.
+    pub fn push_front(&mut self, item: Value, heap: &mut Heap<impl ResourceTracker>) {
+        if self.maxlen == Some(0) {
+            item.drop_with_heap(heap);
+            return;
+        }
+        if self.maxlen == Some(self.items.len())
+            && let Some(discarded) = self.items.pop_back()
+        {
+            discarded.drop_with_heap(heap);
+        }
+        self.mark_ref(&item, heap);
+        self.items.push_front(item);
+    }
+
+    /// Tracks a heap reference about to be stored in the deque.
+    fn mark_ref(&mut self, item: &Value, heap: &mut Heap<impl ResourceTracker>) {
+        if matches!(item, Value::Ref(_)) {
+            self.contains_refs = true;
+            heap.mark_potential_cycle();
+        }
+    }
+
+    /// Converts a Python index into a position in the deque, supporting negative indices.
+    fn normalize_index(&self, key: &Value, heap: &Heap<impl ResourceTracker>) -> RunResult<usize> {
+        let index = key.as_index(heap, Type::Deque)?;
+        let len = i64::try_from(self.items.len()).expect("deque length exceeds i64::MAX");
+        let normalized = if index < 0 { index + len } else { index };
+        if normalized < 0 || normalized >= len {
+            return Err(ExcType::deque_index_error());
+        }
+        Ok(usize::try_from(normalized).expect("index validated non-negative"))
+    }
+}
+
+impl PyTrait for Deque {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Deque
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.items.len() * std::mem::size_of::<Value>()
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.items.len())
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
+        let index = self.normalize_index(key, heap)?;
+        Ok(self.items[index].clone_with_heap(heap))
+    }
+
+    fn py_setitem(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<()> {
+        defer_drop!(key, heap);
+        defer_drop_mut!(value, heap);
+        let index = self.normalize_index(key, heap)?;
+        if matches!(*value, Value::Ref(_)) {
+            self.contains_refs = true;
+            heap.mark_potential_cycle();
+        }
+        // Replace value (old one dropped by defer_drop_mut guard)
+        std::mem::swap(&mut self.items[index], value);
+        Ok(())
+    }
+
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        defer_drop!(key, heap);
+        let index = self.normalize_index(key, heap)?;
+        if let Some(item) = self.items.remove(index) {
+            item.drop_with_heap(heap);
+        }
+        Ok(())
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        if self.items.len() != other.items.len() {
+            return Ok(false);
+        }
+        guard.increase_err()?;
+        for (i1, i2) in self.items.iter().zip(&other.items) {
+            heap.check_time()?;
+            if !i1.py_eq(i2, heap, guard, interns)? {
+                guard.decrease();
+                return Ok(false);
+            }
+        }
+        guard.decrease();
+        Ok(true)
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        if !self.contains_refs {
+            return;
+        }
+        for obj in &mut self.items {
+            if let Value::Ref(id) = obj {
+                stack.push(*id);
+                #[cfg(feature = "ref-count-panic")]
+                obj.dec_ref_forget();
+            }
+        }
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        !self.items.is_empty()
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("deque(")?;
+        repr_sequence_fmt('[', ']', &self.items, f, heap, heap_ids, guard, interns)?;
+        if let Some(maxlen) = self.maxlen {
+            write!(f, ", maxlen={maxlen}")?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(heap);
+            return Err(ExcType::attribute_error(Type::Deque, attr.as_str(interns)));
+        };
+        call_deque_method(self, method, args, heap, interns)
+    }
+
+    /// Handles `d.extend(d)` and `d.extendleft(d)`, and delegates all other methods to
+    /// `py_call_attr`.
+    fn py_call_attr_raw(
+        &mut self,
+        self_id: HeapId,
+        vm: &mut VM<'_, '_, impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+    ) -> RunResult<AttrCallResult> {
+        // The deque's data is taken out of the heap for the duration of the call, so
+        // `d.extend(d)` cannot iterate its argument; duplicate the items directly.
+        if let Some(method @ (StaticStrings::Extend | StaticStrings::Extendleft)) = attr.static_string()
+            && let ArgValues::One(Value::Ref(arg_id)) = &args
+            && *arg_id == self_id
+        {
+            args.drop_with_heap(vm.heap);
+            let items: Vec<Value> = self.items.iter().map(|item| item.clone_with_heap(vm.heap)).collect();
+            for item in items {
+                if method == StaticStrings::Extend {
+                    self.push_back(item, vm.heap);
+                } else {
+                    self.push_front(item, vm.heap);
+                }
+            }
+            return Ok(AttrCallResult::Value(Value::None));
+        }
+        self.py_call_attr(vm.heap, attr, args, vm.interns)
+            .map(AttrCallResult::Value)
+    }
+
+    /// Returns the `maxlen` attribute, `None` for an unbounded deque.
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        if attr_id == StaticStrings::Maxlen {
+            let maxlen = self.maxlen.map_or(Value::None, |maxlen| {
+                Value::Int(i64::try_from(maxlen).unwrap_or(i64::MAX))
+            });
+            Ok(Some(AttrCallResult::Value(maxlen)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl DropWithHeap for Deque {
+    fn drop_with_heap<T: ResourceTracker>(self, heap: &mut Heap<T>) {
+        for item in self.items {
+            item.drop_with_heap(heap);
+        }
+    }
+}
+
+/// Dispatches a method call on a deque value.
+fn call_deque_method(
+    deque: &mut Deque,
+    method: StaticStrings,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    match method {
+        StaticStrings::Append => {
+            let item = args.get_one_arg("deque.append", heap)?;
+            deque.push_back(item, heap);
+            Ok(Value::None)
+        }
+        StaticStrings::Appendleft => {
+            let item = args.get_one_arg("deque.appendleft", heap)?;
+            deque.push_front(item, heap);
+            Ok(Value::None)
+        }
+        StaticStrings::Pop => {
+            args.check_zero_args("deque.pop", heap)?;
+            deque.items.pop_back().ok_or_else(ExcType::index_error_pop_empty_deque)
+        }
+        StaticStrings::Popleft => {
+            args.check_zero_args("deque.popleft", heap)?;
+            deque.items.pop_front().ok_or_else(ExcType::index_error_pop_empty_deque)
+        }
+        StaticStrings::Extend | StaticStrings::Extendleft => {
+            let left = method == StaticStrings::Extendleft;
+            let iterable = args.get_one_arg(if left { "deque.extendleft" } else { "deque.extend" }, heap)?;
+            let items: Vec<Value> = MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?;
+            for item in items {
+                if left {
+                    deque.push_front(item, heap);
+                } else {
+                    deque.push_back(item, heap);
+                }
+            }
+            Ok(Value::None)
+        }
+        StaticStrings::Rotate => deque_rotate(deque, args, heap),
+        StaticStrings::Remove => deque_remove(deque, args, heap, interns),
+        StaticStrings::Count => deque_count(deque, args, heap, interns),
+        StaticStrings::Clear => {
+            args.check_zero_args("deque.clear", heap)?;
+            for item in deque.items.drain(..) {
+                item.drop_with_heap(heap);
+            }
+            Ok(Value::None)
+        }
+        StaticStrings::Copy => {
+            args.check_zero_args("deque.copy", heap)?;
+            let items: Vec<Value> = deque.items.iter().map(|item| item.clone_with_heap(heap)).collect();
+            let copy = Deque::new(items, deque.maxlen, heap);
+            Ok(Value::Ref(heap.allocate(HeapData::Deque(copy))?))
+        }
+        StaticStrings::Reverse => {
+            args.check_zero_args("deque.reverse", heap)?;
+            deque.items.make_contiguous().reverse();
+            Ok(Value::None)
+        }
+        _ => {
+            args.drop_with_heap(heap);
+            Err(ExcType::attribute_error(Type::Deque, method.into()))
+        }
+    }
+}
+
+/// Implements `deque.rotate(n=1)`: rotates `n` steps to the right, or to the left if
+/// `n` is negative.
+fn deque_rotate(deque: &mut Deque, args: ArgValues, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let n = args.get_zero_one_arg("deque.rotate", heap)?;
+    let n = match n {
+        Some(value) => {
+            let result = value.as_int(heap);
+            value.drop_with_heap(heap);
+            result?
+        }
+        None => 1,
+    };
+    let len = deque.items.len();
+    if len > 1 {
+        let len_i64 = i64::try_from(len).expect("deque length exceeds i64::MAX");
+        let steps = usize::try_from(n.rem_euclid(len_i64)).expect("rem_euclid is non-negative");
+        deque.items.rotate_right(steps);
+    }
+    Ok(Value::None)
+}
+
+/// Implements `deque.remove(value)`: removes the first occurrence of value.
+fn deque_remove(
+    deque: &mut Deque,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let value = args.get_one_arg("deque.remove", heap)?;
+    defer_drop!(value, heap);
+    let mut guard = DepthGuard::default();
+    for index in 0..deque.items.len() {
+        heap.check_time()?;
+        if value.py_eq(&deque.items[index], heap, &mut guard, interns)? {
+            if let Some(removed) = deque.items.remove(index) {
+                removed.drop_with_heap(heap);
+            }
+            return Ok(Value::None);
+        }
+    }
+    Err(SimpleException::new_msg(ExcType::ValueError, "deque.remove(x): x not in deque").into())
+}
+
+/// Implements `deque.count(value)`: the number of items equal to value.
+fn deque_count(
+    deque: &Deque,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let value = args.get_one_arg("deque.count", heap)?;
+    defer_drop!(value, heap);
+    let mut guard = DepthGuard::default();
+    let mut count = 0i64;
+    for item in &deque.items {
+        heap.check_time()?;
+        if value.py_eq(item, heap, &mut guard, interns)? {
+            count += 1;
+        }
+    }
+    Ok(Value::Int(count))
+}
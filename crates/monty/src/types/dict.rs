@@ -2,19 +2,25 @@ use std::{
     collections::hash_map::DefaultHasher,
     fmt::Write,
     hash::{Hash, Hasher},
+    sync::Arc,
 };
 
 use ahash::AHashSet;
-use hashbrown::{HashTable, hash_table::Entry};
+use hashbrown::HashTable;
 use smallvec::smallvec;
 
-use super::{List, MontyIter, PyTrait, allocate_tuple};
+use super::{
+    AttrCallResult, MontyIter, PyTrait, allocate_tuple,
+    counter::{counter_elements, counter_most_common, counter_order, counter_total, counter_update},
+    dict_view::{DictView, DictViewKind},
+};
 use crate::{
     args::{ArgValues, KwargsValues},
+    bytecode::VM,
     defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunResult},
     heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
-    intern::{Interns, StaticStrings},
+    intern::{Interns, StaticStrings, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::Type,
     value::{EitherStr, Value},
@@ -27,9 +33,9 @@ use crate::{
 ///
 /// # Implemented Methods
 /// - `get(key[, default])` - Get value or default
-/// - `keys()` - Return view of keys
-/// - `values()` - Return view of values
-/// - `items()` - Return view of (key, value) pairs
+/// - `keys()` - Return a live view of keys (see `DictView`)
+/// - `values()` - Return a live view of values
+/// - `items()` - Return a live view of (key, value) pairs
 /// - `pop(key[, default])` - Remove and return value
 /// - `clear()` - Remove all items
 /// - `copy()` - Shallow copy
@@ -37,15 +43,23 @@ use crate::{
 /// - `setdefault(key[, default])` - Get or set default value
 /// - `popitem()` - Remove and return last (key, value) pair
 /// - `fromkeys(iterable[, value])` - Create dict from keys (classmethod)
+/// - `d | other` / `d |= other` - Merge operators (see `py_dict_merge`)
 ///
 /// All dict methods from Python's builtins are implemented.
 ///
+/// `collections.defaultdict` and `collections.Counter` are dicts with a different
+/// [`DictKind`], see there for how they differ.
+///
 /// # Storage Strategy
 /// Uses a `HashTable<usize>` for hash lookups combined with a dense `Vec<DictEntry>`
 /// to preserve insertion order (matching Python 3.7+ behavior). The hash table maps
 /// key hashes to indices in the entries vector. This design provides O(1) lookups
 /// while maintaining insertion order for iteration.
 ///
+/// Both live in a [`DictTable`] which copies made by `copy()` and `dict(d)` share (see
+/// `shallow_copy`) until either dict is mutated, so copying a dict that's only read
+/// doesn't rehash or reallocate anything.
+///
 /// # Reference Counting
 /// When values are added via `set()`, their reference counts are incremented.
 /// When using `from_pairs()`, ownership is transferred without incrementing refcounts
@@ -58,14 +72,35 @@ use crate::{
 /// improving GC performance for dicts of primitives.
 #[derive(Debug, Default)]
 pub(crate) struct Dict {
-    /// indices mapping from the entry hash to its index.
-    indices: HashTable<usize>,
-    /// entries is a dense vec maintaining entry order.
-    entries: Vec<DictEntry>,
+    /// The hash table and entries, shared with copies of this dict until one of them is mutated.
+    table: Arc<DictTable>,
     /// True if any key or value in the dict is a `Value::Ref`. Used to skip iteration
     /// in `collect_child_ids` and `py_dec_ref_ids` when no refs are present.
     /// Only transitions from false to true (never back) since tracking removals would be O(n).
+    /// Also true if a defaultdict's `default_factory` is a `Value::Ref`.
     contains_refs: bool,
+    /// Whether this is a plain dict or one of the `collections` dict subclasses.
+    kind: DictKind,
+}
+
+/// The `dict` subclass a [`Dict`] behaves as.
+///
+/// `collections.defaultdict` and `collections.Counter` are stored as dicts, so every dict
+/// operation, view and iterator works on them unchanged. The kind only changes their type,
+/// repr, `copy()`, how missing keys are looked up, and the extra methods they provide
+/// (see `types::counter` for `Counter`'s).
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) enum DictKind {
+    /// A plain `dict`.
+    #[default]
+    Dict,
+    /// A `collections.defaultdict` holding its `default_factory`, which may be `None`.
+    ///
+    /// Looking up a missing key with `d[key]` calls the factory and stores its result,
+    /// which needs the VM, so `BinarySubscr` handles it (see `VM::defaultdict_missing`).
+    DefaultDict(Value),
+    /// A `collections.Counter`, where missing keys count as zero.
+    Counter,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -76,7 +111,45 @@ struct DictEntry {
     hash: u64,
 }
 
+/// A dict's storage, which may be shared by several dicts.
+///
+/// Every dict sharing a table owns a reference to each key and value in it.
+#[derive(Debug, Default)]
+struct DictTable {
+    /// indices mapping from the entry hash to its index.
+    indices: HashTable<usize>,
+    /// entries is a dense vec maintaining entry order.
+    entries: Vec<DictEntry>,
+}
+
+impl DictTable {
+    /// Takes a dict's table, copying it if it's shared with another dict.
+    fn unshare(table: Arc<Self>) -> Self {
+        Arc::try_unwrap(table).unwrap_or_else(|table| table.copy_for_owner())
+    }
+
+    /// Copies the table without touching refcounts, for a dict that already owns a
+    /// reference to each key and value.
+    fn copy_for_owner(&self) -> Self {
+        Self {
+            indices: self.indices.clone(),
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| DictEntry {
+                    key: entry.key.copy_for_extend(),
+                    value: entry.value.copy_for_extend(),
+                    hash: entry.hash,
+                })
+                .collect(),
+        }
+    }
+}
+
 impl Dict {
+    /// Memory reserved for each entry: the entry itself plus its slot in the hash table.
+    pub(crate) const ENTRY_SIZE: usize = std::mem::size_of::<DictEntry>() + std::mem::size_of::<usize>();
+
     /// Creates a new empty dict.
     #[must_use]
     pub fn new() -> Self {
@@ -85,9 +158,91 @@ impl Dict {
 
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            indices: HashTable::with_capacity(capacity),
-            entries: Vec::with_capacity(capacity),
+            table: Arc::new(DictTable {
+                indices: HashTable::with_capacity(capacity),
+                entries: Vec::with_capacity(capacity),
+            }),
             contains_refs: false,
+            kind: DictKind::Dict,
+        }
+    }
+
+    /// Creates a new empty dict of the given kind, taking ownership of a defaultdict's factory.
+    #[must_use]
+    pub fn with_kind(kind: DictKind) -> Self {
+        Self {
+            contains_refs: matches!(kind, DictKind::DefaultDict(Value::Ref(_))),
+            kind,
+            ..Self::default()
+        }
+    }
+
+    /// Returns a copy of the dict that shares its table until either dict is mutated.
+    ///
+    /// Increments the refcount of every key and value (and a defaultdict's factory) since
+    /// both dicts hold a reference to them, but doesn't rehash or allocate a new table.
+    #[must_use]
+    pub fn shallow_copy(&self, heap: &mut Heap<impl ResourceTracker>) -> Self {
+        let kind = match &self.kind {
+            DictKind::Dict => DictKind::Dict,
+            DictKind::DefaultDict(factory) => DictKind::DefaultDict(factory.clone_with_heap(heap)),
+            DictKind::Counter => DictKind::Counter,
+        };
+        let copy = Self {
+            kind,
+            ..self.share_table()
+        };
+        copy.inc_entry_refs(heap);
+        copy
+    }
+
+    /// Returns a plain dict sharing this dict's table, without touching any refcounts.
+    ///
+    /// Callers must follow up with `inc_entry_refs` once they can borrow the heap mutably.
+    fn share_table(&self) -> Self {
+        Self {
+            table: Arc::clone(&self.table),
+            contains_refs: self.contains_refs,
+            kind: DictKind::Dict,
+        }
+    }
+
+    /// Increments the refcount of every key and value, for a dict that now shares them.
+    fn inc_entry_refs(&self, heap: &mut Heap<impl ResourceTracker>) {
+        if self.contains_refs {
+            for entry in &self.table.entries {
+                for id in [entry.key.ref_id(), entry.value.ref_id()].into_iter().flatten() {
+                    heap.inc_ref(id);
+                }
+            }
+        }
+    }
+
+    /// Returns the table for mutation, first copying it if it's shared with another dict.
+    fn table_mut(&mut self) -> &mut DictTable {
+        if Arc::get_mut(&mut self.table).is_none() {
+            self.table = Arc::new(self.table.copy_for_owner());
+        }
+        Arc::get_mut(&mut self.table).expect("the table was just unshared")
+    }
+
+    /// Takes the table out of the dict, copying it if it's shared with another dict.
+    fn into_table(self) -> DictTable {
+        DictTable::unshare(self.table)
+    }
+
+    /// Returns whether this is a plain dict or a `collections` dict subclass.
+    #[must_use]
+    pub fn kind(&self) -> &DictKind {
+        &self.kind
+    }
+
+    /// Returns the `default_factory` of a defaultdict, unless it is `None`.
+    #[must_use]
+    pub fn default_factory(&self) -> Option<&Value> {
+        match &self.kind {
+            DictKind::DefaultDict(factory) if !matches!(factory, Value::None) => Some(factory),
+            _ => None,
         }
     }
 
@@ -141,7 +296,7 @@ impl Dict {
         interns: &Interns,
     ) -> RunResult<Option<&Value>> {
         if let Some(index) = self.find_index_hash(key, heap, interns)?.0 {
-            Ok(Some(&self.entries[index].value))
+            Ok(Some(&self.table.entries[index].value))
         } else {
             Ok(None)
         }
@@ -158,9 +313,10 @@ impl Dict {
         let hash = hasher.finish();
 
         // Find entry with matching hash and key
-        self.indices
+        self.table
+            .indices
             .find(hash, |&idx| {
-                let entry_key = &self.entries[idx].key;
+                let entry_key = &self.table.entries[idx].key;
                 match entry_key {
                     Value::InternString(id) => interns.get_str(*id) == key_str,
                     Value::Ref(id) => {
@@ -173,7 +329,7 @@ impl Dict {
                     _ => false,
                 }
             })
-            .map(|&idx| &self.entries[idx].value)
+            .map(|&idx| &self.table.entries[idx].value)
     }
 
     /// Sets a key-value pair in the dict.
@@ -209,9 +365,10 @@ impl Dict {
         };
 
         let entry = DictEntry { key, value, hash };
+        let table = self.table_mut();
         if let Some(index) = opt_index {
             // Key exists, replace in place to preserve insertion order
-            let old_entry = std::mem::replace(&mut self.entries[index], entry);
+            let old_entry = std::mem::replace(&mut table.entries[index], entry);
 
             // Decrement refcount for old key (we're discarding it)
             old_entry.key.drop_with_heap(heap);
@@ -219,10 +376,11 @@ impl Dict {
             Ok(Some(old_entry.value))
         } else {
             // Key doesn't exist, add new pair to indices and entries
-            let index = self.entries.len();
-            self.entries.push(entry);
-            self.indices
-                .insert_unique(hash, index, |index| self.entries[*index].hash);
+            let index = table.entries.len();
+            table.entries.push(entry);
+            table
+                .indices
+                .insert_unique(hash, index, |index| table.entries[*index].hash);
             Ok(None)
         }
     }
@@ -240,68 +398,49 @@ impl Dict {
         heap: &mut Heap<impl ResourceTracker>,
         interns: &Interns,
     ) -> RunResult<Option<(Value, Value)>> {
-        let hash = key
-            .py_hash(heap, interns)
-            .ok_or_else(|| ExcType::type_error_unhashable_dict_key(key.py_type(heap)))?;
-
-        // Create a guard for key equality comparisons.
-        let mut guard = DepthGuard::default();
-        let entry = self.indices.entry(
-            hash,
-            |v| {
-                key.py_eq(&self.entries[*v].key, heap, &mut guard, interns)
-                    .unwrap_or(false)
-            },
-            |index| self.entries[*index].hash,
-        );
+        let (opt_index, hash) = self.find_index_hash(key, heap, interns)?;
+        let Some(index) = opt_index else {
+            return Ok(None);
+        };
 
-        if let Entry::Occupied(occ_entry) = entry {
-            let entry = self.entries.remove(*occ_entry.get());
+        let table = self.table_mut();
+        let entry = table.entries.remove(index);
+        if let Ok(occ_entry) = table.indices.find_entry(hash, |&i| i == index) {
             occ_entry.remove();
-            // Don't decrement refcounts - caller now owns the values
-            Ok(Some((entry.key, entry.value)))
-        } else {
-            Ok(None)
         }
+        // Entries after the removed one moved down by one
+        for entry_index in table.indices.iter_mut() {
+            if *entry_index > index {
+                *entry_index -= 1;
+            }
+        }
+        // Don't decrement refcounts - caller now owns the values
+        Ok(Some((entry.key, entry.value)))
     }
 
-    /// Returns a vector of all keys in the dict with proper reference counting.
-    ///
-    /// Each key's reference count is incremented since the returned vector
-    /// now holds additional references to these values.
-    #[must_use]
-    pub fn keys(&self, heap: &mut Heap<impl ResourceTracker>) -> Vec<Value> {
-        self.entries
-            .iter()
-            .map(|entry| entry.key.clone_with_heap(heap))
-            .collect()
-    }
-
-    /// Returns a vector of all values in the dict with proper reference counting.
-    ///
-    /// Each value's reference count is incremented since the returned vector
-    /// now holds additional references to these values.
-    #[must_use]
-    pub fn values(&self, heap: &mut Heap<impl ResourceTracker>) -> Vec<Value> {
-        self.entries
-            .iter()
-            .map(|entry| entry.value.clone_with_heap(heap))
-            .collect()
-    }
-
-    /// Returns a vector of all (key, value) pairs in the dict with proper reference counting.
+    /// Removes and returns the first (oldest) key-value pair, if any.
     ///
-    /// Each key and value's reference count is incremented since the returned vector
-    /// now holds additional references to these values.
-    #[must_use]
-    pub fn items(&self) -> impl IntoIterator<Item = (&Value, &Value)> {
-        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    /// Ownership of the key and value is transferred to the caller.
+    pub fn pop_first(&mut self) -> Option<(Value, Value)> {
+        if self.is_empty() {
+            return None;
+        }
+        let table = self.table_mut();
+        let entry = table.entries.remove(0);
+        if let Ok(occ_entry) = table.indices.find_entry(entry.hash, |&index| index == 0) {
+            occ_entry.remove();
+        }
+        // Every remaining entry moved down by one
+        for entry_index in table.indices.iter_mut() {
+            *entry_index -= 1;
+        }
+        Some((entry.key, entry.value))
     }
 
     /// Returns the number of key-value pairs in the dict.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.entries.len()
+        self.table.entries.len()
     }
 
     /// Returns true if the dict is empty.
@@ -320,7 +459,14 @@ impl Dict {
     /// Used for index-based iteration in for loops. Returns a reference to
     /// the key at the given position in insertion order.
     pub fn key_at(&self, index: usize) -> Option<&Value> {
-        self.entries.get(index).map(|e| &e.key)
+        self.table.entries.get(index).map(|e| &e.key)
+    }
+
+    /// Returns the (key, value) pair at the given iteration index, or None if out of bounds.
+    ///
+    /// Used for index-based iteration over dict views.
+    pub fn item_at(&self, index: usize) -> Option<(&Value, &Value)> {
+        self.table.entries.get(index).map(|e| (&e.key, &e.value))
     }
 
     /// Creates a dict from the `dict()` constructor call.
@@ -330,7 +476,7 @@ impl Dict {
     ///
     /// Note: Full Python semantics also support dict(iterable) where iterable
     /// yields (key, value) pairs, and dict(**kwargs) for keyword arguments.
-    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues) -> RunResult<Value> {
         let value = args.get_zero_one_arg("dict", heap)?;
         match value {
             None => {
@@ -348,29 +494,82 @@ impl Dict {
                     return Err(ExcType::type_error_not_iterable(v.py_type(heap)));
                 };
 
-                // Copy all key-value pairs first (without incrementing refcounts)
-                let pairs: Vec<(Value, Value)> = dict
-                    .iter()
-                    .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
-                    .collect();
-
-                // Now we can drop the borrow and increment refcounts
-                for (k, v) in &pairs {
-                    if let Value::Ref(key_id) = k {
-                        heap.inc_ref(*key_id);
-                    }
-                    if let Value::Ref(val_id) = v {
-                        heap.inc_ref(*val_id);
-                    }
-                }
+                // Share the table first (without incrementing refcounts), then increment
+                // them once the borrow is dropped
+                let new_dict = dict.share_table();
+                new_dict.inc_entry_refs(heap);
 
-                let new_dict = Self::from_pairs(pairs, heap, interns)?;
                 let result = heap.allocate(HeapData::Dict(new_dict))?;
                 Ok(Value::Ref(result))
             }
         }
     }
 
+    /// Creates a defaultdict from the `collections.defaultdict(default_factory=None, ...)`
+    /// constructor call.
+    ///
+    /// Arguments after the factory are handled like `dict.update()`'s.
+    pub fn init_defaultdict(
+        heap: &mut Heap<impl ResourceTracker>,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let (mut positional, kwargs) = args.into_parts();
+        let factory = positional.next().unwrap_or(Value::None);
+        let args = ArgValues::from_parts(positional.collect(), kwargs);
+        if !matches!(factory, Value::None) && !factory.is_callable(heap) {
+            factory.drop_with_heap(heap);
+            args.drop_with_heap(heap);
+            return Err(ExcType::type_error("first argument must be callable or None"));
+        }
+        let dict = Self::with_kind(DictKind::DefaultDict(factory));
+        let mut dict_guard = HeapGuard::new(dict, heap);
+        let (dict, heap) = dict_guard.as_parts_mut();
+        dict_update(dict, args, heap, interns)?;
+        let (dict, heap) = dict_guard.into_parts();
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+
+    /// Writes `entries` in dict display form, e.g. `{'a': 1, 'b': 2}`.
+    fn repr_entries<'a>(
+        &self,
+        f: &mut impl Write,
+        entries: impl Iterator<Item = &'a DictEntry>,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("{}");
+        }
+
+        // Check depth limit before recursing
+        if !guard.increase() {
+            return f.write_str("{...}");
+        }
+
+        f.write_char('{')?;
+        let mut first = true;
+        for entry in entries {
+            if !first {
+                if heap.check_time().is_err() {
+                    f.write_str(", ...[timeout]")?;
+                    break;
+                }
+                f.write_str(", ")?;
+            }
+            first = false;
+            entry.key.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+            f.write_str(": ")?;
+            entry.value.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        f.write_char('}')?;
+
+        guard.decrease();
+        Ok(())
+    }
+
     fn find_index_hash(
         &self,
         key: &Value,
@@ -387,9 +586,10 @@ impl Dict {
         // fails but doesn't crash.
         let mut guard = DepthGuard::default();
         let opt_index = self
+            .table
             .indices
             .find(hash, |v| {
-                key.py_eq(&self.entries[*v].key, heap, &mut guard, interns)
+                key.py_eq(&self.table.entries[*v].key, heap, &mut guard, interns)
                     .unwrap_or(false)
             })
             .copied();
@@ -411,7 +611,7 @@ impl<'a> IntoIterator for &'a Dict {
     type Item = (&'a Value, &'a Value);
     type IntoIter = DictIter<'a>;
     fn into_iter(self) -> Self::IntoIter {
-        DictIter(self.entries.iter())
+        DictIter(self.table.entries.iter())
     }
 }
 
@@ -436,18 +636,24 @@ impl IntoIterator for Dict {
     type Item = (Value, Value);
     type IntoIter = DictIntoIter;
     fn into_iter(self) -> Self::IntoIter {
-        DictIntoIter(self.entries.into_iter())
+        DictIntoIter(self.into_table().entries.into_iter())
     }
 }
 
 impl PyTrait for Dict {
     fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
-        Type::Dict
+        match self.kind {
+            DictKind::Dict => Type::Dict,
+            DictKind::DefaultDict(_) => Type::DefaultDict,
+            DictKind::Counter => Type::Counter,
+        }
     }
 
     fn py_estimate_size(&self) -> usize {
-        // Dict size: struct overhead + entries (2 Values per entry for key+value)
-        std::mem::size_of::<Self>() + self.len() * 2 * std::mem::size_of::<Value>()
+        // Dict size: struct overhead + reserved entry and index slots
+        std::mem::size_of::<Self>()
+            + self.table.entries.capacity() * std::mem::size_of::<DictEntry>()
+            + self.table.indices.capacity() * std::mem::size_of::<usize>()
     }
 
     fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
@@ -467,7 +673,7 @@ impl PyTrait for Dict {
 
         guard.increase_err()?;
         // Check that all keys in self exist in other with equal values
-        for entry in &self.entries {
+        for entry in &self.table.entries {
             heap.check_time()?;
             if let Ok(Some(other_v)) = other.get(&entry.key, heap, interns) {
                 if !entry.value.py_eq(other_v, heap, guard, interns)? {
@@ -488,16 +694,32 @@ impl PyTrait for Dict {
         if !self.contains_refs {
             return;
         }
-        for entry in &mut self.entries {
-            if let Value::Ref(id) = &entry.key {
-                stack.push(*id);
-                #[cfg(feature = "ref-count-panic")]
-                entry.key.dec_ref_forget();
+        if let DictKind::DefaultDict(factory) = &mut self.kind
+            && let Value::Ref(id) = factory
+        {
+            stack.push(*id);
+            #[cfg(feature = "ref-count-panic")]
+            factory.dec_ref_forget();
+        }
+        // Every dict sharing the table owns a reference to each key and value, but only
+        // the last one can mark them as released
+        if let Some(table) = Arc::get_mut(&mut self.table) {
+            for entry in &mut table.entries {
+                if let Value::Ref(id) = &entry.key {
+                    stack.push(*id);
+                    #[cfg(feature = "ref-count-panic")]
+                    entry.key.dec_ref_forget();
+                }
+                if let Value::Ref(id) = &entry.value {
+                    stack.push(*id);
+                    #[cfg(feature = "ref-count-panic")]
+                    entry.value.dec_ref_forget();
+                }
             }
-            if let Value::Ref(id) = &entry.value {
-                stack.push(*id);
-                #[cfg(feature = "ref-count-panic")]
-                entry.value.dec_ref_forget();
+        } else {
+            for entry in &self.table.entries {
+                stack.extend(entry.key.ref_id());
+                stack.extend(entry.value.ref_id());
             }
         }
     }
@@ -514,34 +736,30 @@ impl PyTrait for Dict {
         guard: &mut DepthGuard,
         interns: &Interns,
     ) -> std::fmt::Result {
-        if self.is_empty() {
-            return f.write_str("{}");
-        }
-
-        // Check depth limit before recursing
-        if !guard.increase() {
-            return f.write_str("{...}");
-        }
-
-        f.write_char('{')?;
-        let mut first = true;
-        for entry in &self.entries {
-            if !first {
-                if heap.check_time().is_err() {
-                    f.write_str(", ...[timeout]")?;
-                    break;
-                }
+        match &self.kind {
+            DictKind::Dict => self.repr_entries(f, self.table.entries.iter(), heap, heap_ids, guard, interns),
+            DictKind::DefaultDict(factory) => {
+                f.write_str("defaultdict(")?;
+                factory.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
                 f.write_str(", ")?;
+                self.repr_entries(f, self.table.entries.iter(), heap, heap_ids, guard, interns)?;
+                f.write_char(')')
+            }
+            DictKind::Counter => {
+                if self.is_empty() {
+                    return f.write_str("Counter()");
+                }
+                // Like CPython, items are listed from the most common when counts are numbers
+                f.write_str("Counter(")?;
+                if let Some(order) = counter_order(self, heap) {
+                    let entries = order.into_iter().map(|index| &self.table.entries[index]);
+                    self.repr_entries(f, entries, heap, heap_ids, guard, interns)?;
+                } else {
+                    self.repr_entries(f, self.table.entries.iter(), heap, heap_ids, guard, interns)?;
+                }
+                f.write_char(')')
             }
-            first = false;
-            entry.key.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
-            f.write_str(": ")?;
-            entry.value.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
         }
-        f.write_char('}')?;
-
-        guard.decrease();
-        Ok(())
     }
 
     fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
@@ -554,6 +772,8 @@ impl PyTrait for Dict {
                 }
                 Ok(value)
             }
+            // A Counter's missing keys count as zero, without being inserted
+            None if matches!(self.kind, DictKind::Counter) => Ok(Value::Int(0)),
             None => Err(ExcType::key_error(key, heap, interns)),
         }
     }
@@ -572,6 +792,18 @@ impl PyTrait for Dict {
         Ok(())
     }
 
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        defer_drop!(key, heap);
+        match self.pop(key, heap, interns)? {
+            Some((old_key, old_value)) => {
+                old_key.drop_with_heap(heap);
+                old_value.drop_with_heap(heap);
+                Ok(())
+            }
+            None => Err(ExcType::key_error(key, heap, interns)),
+        }
+    }
+
     fn py_call_attr(
         &mut self,
         heap: &mut Heap<impl ResourceTracker>,
@@ -581,8 +813,9 @@ impl PyTrait for Dict {
     ) -> RunResult<Value> {
         let Some(method) = attr.static_string() else {
             args.drop_with_heap(heap);
-            return Err(ExcType::attribute_error(Type::Dict, attr.as_str(interns)));
+            return Err(ExcType::attribute_error(self.py_type(heap), attr.as_str(interns)));
         };
+        let is_counter = matches!(self.kind, DictKind::Counter);
 
         match method {
             StaticStrings::Get => {
@@ -599,29 +832,6 @@ impl PyTrait for Dict {
                 };
                 Ok(value)
             }
-            StaticStrings::Keys => {
-                args.check_zero_args("dict.keys", heap)?;
-                let keys = self.keys(heap);
-                let list_id = heap.allocate(HeapData::List(List::new(keys)))?;
-                Ok(Value::Ref(list_id))
-            }
-            StaticStrings::Values => {
-                args.check_zero_args("dict.values", heap)?;
-                let values = self.values(heap);
-                let list_id = heap.allocate(HeapData::List(List::new(values)))?;
-                Ok(Value::Ref(list_id))
-            }
-            StaticStrings::Items => {
-                args.check_zero_args("dict.items", heap)?;
-                // Return list of tuples
-                let tuples = self
-                    .items()
-                    .into_iter()
-                    .map(|(k, v)| allocate_tuple(smallvec![k.clone_with_heap(heap), v.clone_with_heap(heap)], heap))
-                    .collect::<Result<_, _>>()?;
-                let list_id = heap.allocate(HeapData::List(List::new(tuples)))?;
-                Ok(Value::Ref(list_id))
-            }
             StaticStrings::Pop => {
                 // dict.pop() accepts 1 or 2 arguments (key, optional default)
                 let (key, default) = args.get_one_two_args("pop", heap)?;
@@ -650,8 +860,14 @@ impl PyTrait for Dict {
             }
             StaticStrings::Copy => {
                 args.check_zero_args("dict.copy", heap)?;
-                dict_copy(self, heap, interns)
+                dict_copy(self, heap)
             }
+            // Counter adds to (or subtracts from) the counts instead of replacing them
+            StaticStrings::Update if is_counter => counter_update(self, None, args, false, heap, interns),
+            StaticStrings::Subtract if is_counter => counter_update(self, None, args, true, heap, interns),
+            StaticStrings::MostCommon if is_counter => counter_most_common(self, args, heap),
+            StaticStrings::Elements if is_counter => counter_elements(self, args, heap, interns),
+            StaticStrings::Total if is_counter => counter_total(self, args, heap, interns),
             StaticStrings::Update => dict_update(self, args, heap, interns),
             StaticStrings::Setdefault => dict_setdefault(self, args, heap, interns),
             StaticStrings::Popitem => {
@@ -662,15 +878,67 @@ impl PyTrait for Dict {
             StaticStrings::Fromkeys => dict_fromkeys(args, heap, interns),
             _ => {
                 args.drop_with_heap(heap);
-                Err(ExcType::attribute_error(Type::Dict, attr.as_str(interns)))
+                Err(ExcType::attribute_error(self.py_type(heap), attr.as_str(interns)))
+            }
+        }
+    }
+
+    /// Intercepts `keys`, `values` and `items`, which return views referencing the dict
+    /// by id, and a Counter's `update` and `subtract`, which may be passed the counter
+    /// itself. Delegates all other methods to `py_call_attr`.
+    fn py_call_attr_raw(
+        &mut self,
+        self_id: HeapId,
+        vm: &mut VM<'_, '_, impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+    ) -> RunResult<AttrCallResult> {
+        let (kind, name) = match attr.static_string() {
+            Some(StaticStrings::Keys) => (DictViewKind::Keys, "dict.keys"),
+            Some(StaticStrings::Values) => (DictViewKind::Values, "dict.values"),
+            Some(StaticStrings::Items) => (DictViewKind::Items, "dict.items"),
+            Some(method @ (StaticStrings::Update | StaticStrings::Subtract))
+                if matches!(self.kind, DictKind::Counter) =>
+            {
+                let subtract = method == StaticStrings::Subtract;
+                return counter_update(self, Some(self_id), args, subtract, vm.heap, vm.interns)
+                    .map(AttrCallResult::Value);
+            }
+            _ => {
+                return self
+                    .py_call_attr(vm.heap, attr, args, vm.interns)
+                    .map(AttrCallResult::Value);
+            }
+        };
+        args.check_zero_args(name, vm.heap)?;
+        let view_id = vm.heap.allocate(HeapData::DictView(DictView::new(self_id, kind)))?;
+        // The view holds a reference to the dict
+        vm.heap.inc_ref(self_id);
+        Ok(AttrCallResult::Value(Value::Ref(view_id)))
+    }
+
+    /// Returns a defaultdict's `default_factory`; dicts have no other data attributes.
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        match (&self.kind, StaticStrings::from_string_id(attr_id)) {
+            (DictKind::DefaultDict(factory), Some(StaticStrings::DefaultFactory)) => {
+                Ok(Some(AttrCallResult::Value(factory.clone_with_heap(heap))))
             }
+            _ => Ok(None),
         }
     }
 }
 
 impl DropWithHeap for Dict {
-    fn drop_with_heap<T: ResourceTracker>(self, heap: &mut Heap<T>) {
-        for entry in self.entries {
+    fn drop_with_heap<T: ResourceTracker>(mut self, heap: &mut Heap<T>) {
+        if let DictKind::DefaultDict(factory) = std::mem::take(&mut self.kind) {
+            factory.drop_with_heap(heap);
+        }
+        for entry in self.into_table().entries {
             entry.key.drop_with_heap(heap);
             entry.value.drop_with_heap(heap);
         }
@@ -681,25 +949,20 @@ impl DropWithHeap for Dict {
 ///
 /// Removes all items from the dict.
 fn dict_clear(dict: &mut Dict, heap: &mut Heap<impl ResourceTracker>) {
-    for entry in dict.entries.drain(..) {
+    for entry in DictTable::unshare(std::mem::take(&mut dict.table)).entries {
         entry.key.drop_with_heap(heap);
         entry.value.drop_with_heap(heap);
     }
-    dict.indices.clear();
     // Note: contains_refs stays true even if all refs removed, per conservative GC strategy
 }
 
 /// Implements Python's `dict.copy()` method.
 ///
 /// Returns a shallow copy of the dict.
-fn dict_copy(dict: &Dict, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
-    // Copy all key-value pairs (incrementing refcounts)
-    let pairs: Vec<(Value, Value)> = dict
-        .iter()
-        .map(|(k, v)| (k.clone_with_heap(heap), v.clone_with_heap(heap)))
-        .collect();
-
-    let new_dict = Dict::from_pairs(pairs, heap, interns)?;
+///
+/// Copies of a defaultdict or Counter are of the same kind, sharing the `default_factory`.
+pub(crate) fn dict_copy(dict: &Dict, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let new_dict = dict.shallow_copy(heap);
     let heap_id = heap.allocate(HeapData::Dict(new_dict))?;
     Ok(Value::Ref(heap_id))
 }
@@ -883,30 +1146,103 @@ fn dict_popitem(dict: &mut Dict, heap: &mut Heap<impl ResourceTracker>) -> RunRe
     }
 
     // Remove the last entry (LIFO order)
-    let entry = dict.entries.pop().expect("dict is not empty");
+    let table = dict.table_mut();
+    let entry = table.entries.pop().expect("dict is not empty");
 
     // Remove from indices - need to find the entry with this index
     // Since we removed the last entry, we need to clear and rebuild indices
     // (This is simpler than trying to find and remove the specific hash entry)
     // TODO: This O(n) rebuild could be optimized by finding and removing the
     // specific hash entry directly from the hashbrown table.
-    dict.indices.clear();
-    for (idx, e) in dict.entries.iter().enumerate() {
-        dict.indices.insert_unique(e.hash, idx, |&i| dict.entries[i].hash);
+    table.indices.clear();
+    for (idx, e) in table.entries.iter().enumerate() {
+        table.indices.insert_unique(e.hash, idx, |&i| table.entries[i].hash);
     }
 
     // Create tuple (key, value)
     Ok(allocate_tuple(smallvec![entry.key, entry.value], heap)?)
 }
 
+/// Implements the dict merge operator `d | other`.
+///
+/// Both operands must be dicts; `Ok(None)` is returned otherwise so the caller raises
+/// the usual `unsupported operand type(s)` TypeError. The result is a new dict holding
+/// the left operand's items updated with the right operand's, so `rhs` wins on
+/// duplicate keys.
+pub(crate) fn py_dict_merge(
+    lhs: &Value,
+    rhs: &Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    let (Value::Ref(lhs_id), Value::Ref(rhs_id)) = (lhs, rhs) else {
+        return Ok(None);
+    };
+    let (HeapData::Dict(lhs_dict), HeapData::Dict(rhs_dict)) = (heap.get(*lhs_id), heap.get(*rhs_id)) else {
+        return Ok(None);
+    };
+    let pairs: Vec<(Value, Value)> = lhs_dict
+        .iter()
+        .chain(rhs_dict.iter())
+        .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
+        .collect();
+
+    // Increment refcounts after releasing the borrows
+    for (k, v) in &pairs {
+        if let Value::Ref(key_id) = k {
+            heap.inc_ref(*key_id);
+        }
+        if let Value::Ref(val_id) = v {
+            heap.inc_ref(*val_id);
+        }
+    }
+
+    let dict = Dict::from_pairs(pairs, heap, interns)?;
+    Ok(Some(Value::Ref(heap.allocate(HeapData::Dict(dict))?)))
+}
+
+/// Implements the in-place dict merge operator `d |= other`.
+///
+/// Like CPython, the right operand may be anything `dict.update()` accepts (a dict or
+/// an iterable of pairs). The dict is updated in place so other references observe the
+/// change. Returns `Ok(false)` when the left operand is not a dict, in which case the
+/// caller falls back to other `|=` implementations.
+pub(crate) fn py_dict_inplace_merge(
+    lhs: &Value,
+    rhs: &Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    let Value::Ref(lhs_id) = lhs else {
+        return Ok(false);
+    };
+    if !matches!(heap.get(*lhs_id), HeapData::Dict(_)) {
+        return Ok(false);
+    }
+    // `d |= d` leaves the dict unchanged, and updating from the dict currently being
+    // mutated would otherwise read a slot that `with_entry_mut` has taken out.
+    if rhs.ref_id() == Some(*lhs_id) {
+        return Ok(true);
+    }
+    let other = rhs.clone_with_heap(heap);
+    heap.with_entry_mut(*lhs_id, |heap, data| {
+        let HeapData::Dict(dict) = data else {
+            unreachable!("checked above that lhs is a dict")
+        };
+        dict_update(dict, ArgValues::One(other), heap, interns)
+    })?;
+    Ok(true)
+}
+
 // Custom serde implementation for Dict.
-// Serializes entries and contains_refs; rebuilds the indices hash table on deserialize.
+// Serializes entries, contains_refs and kind; rebuilds the indices hash table on deserialize.
 impl serde::Serialize for Dict {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Dict", 2)?;
-        state.serialize_field("entries", &self.entries)?;
+        let mut state = serializer.serialize_struct("Dict", 3)?;
+        state.serialize_field("entries", &self.table.entries)?;
         state.serialize_field("contains_refs", &self.contains_refs)?;
+        state.serialize_field("kind", &self.kind)?;
         state.end()
     }
 }
@@ -917,6 +1253,7 @@ impl<'de> serde::Deserialize<'de> for Dict {
         struct DictFields {
             entries: Vec<DictEntry>,
             contains_refs: bool,
+            kind: DictKind,
         }
         let fields = DictFields::deserialize(deserializer)?;
         // Rebuild the indices hash table from the entries
@@ -925,9 +1262,12 @@ impl<'de> serde::Deserialize<'de> for Dict {
             indices.insert_unique(entry.hash, idx, |&i| fields.entries[i].hash);
         }
         Ok(Self {
-            indices,
-            entries: fields.entries,
+            table: Arc::new(DictTable {
+                indices,
+                entries: fields.entries,
+            }),
             contains_refs: fields.contains_refs,
+            kind: fields.kind,
         })
     }
 }
@@ -0,0 +1,347 @@
+//! Dict view objects returned by `dict.keys()`, `dict.values()` and `dict.items()`.
+//!
+//! A view holds a reference to its dict rather than a copy of the entries, so it always
+//! reflects the dict's current contents. Keys and items views are set-like: they compare
+//! equal to sets with the same elements and support the `|`, `&`, `-` and `^` operators.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{Heap, HeapData, HeapId},
+    intern::Interns,
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{Dict, PyTrait, Set, SetBinaryOp, Type, allocate_tuple, py_set_binary_op},
+    value::Value,
+};
+
+/// Which part of a dict a view exposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum DictViewKind {
+    /// `dict.keys()`
+    Keys,
+    /// `dict.values()`
+    Values,
+    /// `dict.items()`, yielding `(key, value)` tuples
+    Items,
+}
+
+impl DictViewKind {
+    /// Returns the item at `index` of the dict `dict_id`, with its refcount incremented.
+    ///
+    /// `expected_len` is the dict length captured when iteration started; a different
+    /// current length raises `RuntimeError`, matching iteration over the dict itself.
+    pub(crate) fn item_at(
+        self,
+        dict_id: HeapId,
+        index: usize,
+        expected_len: usize,
+        heap: &mut Heap<impl ResourceTracker>,
+    ) -> RunResult<Value> {
+        let HeapData::Dict(dict) = heap.get(dict_id) else {
+            unreachable!("dict view must reference a dict")
+        };
+        if dict.len() != expected_len {
+            return Err(ExcType::runtime_error_dict_changed_size());
+        }
+        let (key, value) = dict.item_at(index).expect("index should be valid");
+        let (key, value) = (key.copy_for_extend(), value.copy_for_extend());
+        match self {
+            Self::Keys => Ok(inc_ref(key, heap)),
+            Self::Values => Ok(inc_ref(value, heap)),
+            Self::Items => {
+                let pair = smallvec![inc_ref(key, heap), inc_ref(value, heap)];
+                Ok(allocate_tuple(pair, heap)?)
+            }
+        }
+    }
+}
+
+/// A live view over a dict's keys, values or items.
+///
+/// The view owns a reference to the dict, which is released when the view is freed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DictView {
+    /// The dict this view reads from.
+    dict_id: HeapId,
+    /// Which part of the dict the view exposes.
+    kind: DictViewKind,
+}
+
+impl DictView {
+    /// Creates a view over `dict_id`.
+    ///
+    /// The caller is responsible for incrementing the dict's refcount on behalf of the view.
+    #[must_use]
+    pub fn new(dict_id: HeapId, kind: DictViewKind) -> Self {
+        Self { dict_id, kind }
+    }
+
+    /// Returns the id of the underlying dict.
+    #[must_use]
+    pub fn dict_id(&self) -> HeapId {
+        self.dict_id
+    }
+
+    /// Returns which part of the dict the view exposes.
+    #[must_use]
+    pub fn kind(&self) -> DictViewKind {
+        self.kind
+    }
+
+    /// Returns true for keys and items views, which behave like sets.
+    #[must_use]
+    pub fn is_set_like(&self) -> bool {
+        matches!(self.kind, DictViewKind::Keys | DictViewKind::Items)
+    }
+
+    /// Returns the underlying dict.
+    pub fn dict<'h>(&self, heap: &'h Heap<impl ResourceTracker>) -> &'h Dict {
+        match heap.get(self.dict_id) {
+            HeapData::Dict(dict) => dict,
+            _ => unreachable!("dict view must reference a dict"),
+        }
+    }
+
+    /// Returns the current number of entries in the underlying dict.
+    #[must_use]
+    pub fn len(&self, heap: &Heap<impl ResourceTracker>) -> usize {
+        self.dict(heap).len()
+    }
+
+    /// Implements `item in view`.
+    ///
+    /// Keys views use a hash lookup, items views look up the key of a `(key, value)` tuple
+    /// and compare the value, and values views fall back to a linear scan.
+    pub fn contains(&self, item: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<bool> {
+        match self.kind {
+            DictViewKind::Keys => heap.with_entry_mut(self.dict_id, |heap, data| {
+                let HeapData::Dict(dict) = data else {
+                    unreachable!("dict view must reference a dict")
+                };
+                Ok(dict.get(item, heap, interns)?.is_some())
+            }),
+            DictViewKind::Values => heap.with_entry_mut(self.dict_id, |heap, data| {
+                let HeapData::Dict(dict) = data else {
+                    unreachable!("dict view must reference a dict")
+                };
+                let mut guard = DepthGuard::default();
+                for (_, value) in dict {
+                    if item.py_eq(value, heap, &mut guard, interns)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }),
+            DictViewKind::Items => {
+                let Value::Ref(item_id) = item else {
+                    return Ok(false);
+                };
+                let pair = match heap.get(*item_id) {
+                    HeapData::Tuple(tuple) => match tuple.as_slice() {
+                        [key, value] => Some((key.copy_for_extend(), value.copy_for_extend())),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let Some((key, value)) = pair else {
+                    return Ok(false);
+                };
+                let key = inc_ref(key, heap);
+                defer_drop!(key, heap);
+                let value = inc_ref(value, heap);
+                defer_drop!(value, heap);
+                heap.with_entry_mut(self.dict_id, |heap, data| {
+                    let HeapData::Dict(dict) = data else {
+                        unreachable!("dict view must reference a dict")
+                    };
+                    match dict.get(key, heap, interns)? {
+                        Some(existing) => Ok(existing.py_eq(value, heap, &mut DepthGuard::default(), interns)?),
+                        None => Ok(false),
+                    }
+                })
+            }
+        }
+    }
+
+    /// Implements `==` between a keys/items view and another set-like object.
+    ///
+    /// Like CPython, the operands are equal when they have the same length and every
+    /// element of the view is contained in `other`, which may be a set, a frozenset or
+    /// another keys/items view. Anything else (including values views) is never equal.
+    pub fn py_eq_set_like(
+        &self,
+        other: &HeapData,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        match other {
+            HeapData::Set(set) => {
+                self.all_contained_in(set.len(), heap, guard, |item, heap| set.contains(item, heap, interns))
+            }
+            HeapData::FrozenSet(set) => {
+                self.all_contained_in(set.len(), heap, guard, |item, heap| set.contains(item, heap, interns))
+            }
+            HeapData::DictView(view) => self.py_eq(view, heap, guard, interns),
+            _ => Ok(false),
+        }
+    }
+
+    /// Checks that this view has `other_len` elements and that `contains` holds for each.
+    ///
+    /// Lookup errors (e.g. unhashable elements) count as "not contained", as in `Dict::py_eq`.
+    fn all_contained_in<T: ResourceTracker>(
+        &self,
+        other_len: usize,
+        heap: &mut Heap<T>,
+        guard: &mut DepthGuard,
+        mut contains: impl FnMut(&Value, &mut Heap<T>) -> RunResult<bool>,
+    ) -> Result<bool, ResourceError> {
+        if !self.is_set_like() || self.len(heap) != other_len {
+            return Ok(false);
+        }
+        guard.increase_err()?;
+        let len = self.len(heap);
+        let mut equal = true;
+        for index in 0..len {
+            heap.check_time()?;
+            let item = match self.kind.item_at(self.dict_id, index, len, heap) {
+                Ok(item) => item,
+                Err(_) => {
+                    equal = false;
+                    break;
+                }
+            };
+            let contained = contains(&item, heap);
+            item.drop_with_heap(heap);
+            if !matches!(contained, Ok(true)) {
+                equal = false;
+                break;
+            }
+        }
+        guard.decrease();
+        Ok(equal)
+    }
+}
+
+impl PyTrait for DictView {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        match self.kind {
+            DictViewKind::Keys => Type::DictKeys,
+            DictViewKind::Values => Type::DictValues,
+            DictViewKind::Items => Type::DictItems,
+        }
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_len(&self, heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        Some(self.len(heap))
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        heap: &mut Heap<impl ResourceTracker>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        if !other.is_set_like() {
+            return Ok(false);
+        }
+        self.all_contained_in(other.len(heap), heap, guard, |item, heap| {
+            other.contains(item, heap, interns)
+        })
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        stack.push(self.dict_id);
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "{}([", self.py_type(heap))?;
+        if !guard.increase() {
+            return f.write_str("...])");
+        }
+        let mut first = true;
+        for (key, value) in self.dict(heap) {
+            if !first {
+                if heap.check_time().is_err() {
+                    f.write_str(", ...[timeout]")?;
+                    break;
+                }
+                f.write_str(", ")?;
+            }
+            first = false;
+            match self.kind {
+                DictViewKind::Keys => key.py_repr_fmt(f, heap, heap_ids, guard, interns)?,
+                DictViewKind::Values => value.py_repr_fmt(f, heap, heap_ids, guard, interns)?,
+                DictViewKind::Items => {
+                    f.write_char('(')?;
+                    key.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+                    f.write_str(", ")?;
+                    value.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+                    f.write_char(')')?;
+                }
+            }
+        }
+        guard.decrease();
+        f.write_str("])")
+    }
+}
+
+/// Applies a set operator when either operand is a keys or items view.
+///
+/// Like CPython, the other operand may be any iterable and the result is always a
+/// `set`: `d.keys() | [1]` and `{1} - d.keys()` both work. Returns `Ok(None)` when
+/// neither operand is a set-like view.
+pub(crate) fn py_dict_view_set_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: SetBinaryOp,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    if !is_set_like_view(lhs, heap) && !is_set_like_view(rhs, heap) {
+        return Ok(None);
+    }
+    let lhs_set = Set::init(heap, ArgValues::One(lhs.clone_with_heap(heap)), interns)?;
+    defer_drop!(lhs_set, heap);
+    let rhs_set = Set::init(heap, ArgValues::One(rhs.clone_with_heap(heap)), interns)?;
+    defer_drop!(rhs_set, heap);
+    py_set_binary_op(lhs_set, rhs_set, op, heap, interns)
+}
+
+/// Returns true if `value` is a keys or items view.
+fn is_set_like_view(value: &Value, heap: &Heap<impl ResourceTracker>) -> bool {
+    if let Value::Ref(id) = value
+        && let HeapData::DictView(view) = heap.get(*id)
+    {
+        view.is_set_like()
+    } else {
+        false
+    }
+}
+
+/// Increments the refcount of a value copied out of the heap via `copy_for_extend()`.
+fn inc_ref(value: Value, heap: &mut Heap<impl ResourceTracker>) -> Value {
+    if let Value::Ref(id) = &value {
+        heap.inc_ref(*id);
+    }
+    value
+}
@@ -0,0 +1,161 @@
+//! Opaque host objects exposed to sandboxed code.
+//!
+//! An external object is a handle the host passes in as an input or return value. The
+//! sandbox can't see inside it: every method call and attribute read pauses execution
+//! and is resolved by the host, like an external function call.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, PyTrait, Type},
+    value::{EitherStr, Value},
+};
+
+/// Name of the method call the host receives for attribute reads on an external object.
+pub(crate) const EXTERNAL_GETATTR: &str = "__getattr__";
+
+/// A host object identified by a class name and an opaque handle.
+///
+/// # Host protocol
+/// - `obj.method(*args, **kwargs)` yields a method call named `method` with the object
+///   prepended to the positional arguments.
+/// - `obj.attr` yields a method call named `__getattr__` with arguments `(obj, 'attr')`.
+/// - Names starting with an underscore raise `AttributeError` without reaching the host,
+///   except `__enter__` and `__exit__` calls so external objects work in `with` statements.
+/// - Setting attributes raises `AttributeError`.
+///
+/// Two external objects are equal, and hash the same, if their class name and handle match.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ExternalObject {
+    /// The class name shown in `repr()` and error messages, e.g. `"Database"`.
+    class_name: String,
+    /// Host-defined identifier of the object.
+    handle: u64,
+}
+
+impl ExternalObject {
+    /// Creates a new external object.
+    #[must_use]
+    pub fn new(class_name: String, handle: u64) -> Self {
+        Self { class_name, handle }
+    }
+
+    /// Returns the class name.
+    #[must_use]
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    /// Returns the host-defined handle.
+    #[must_use]
+    pub fn handle(&self) -> u64 {
+        self.handle
+    }
+
+    /// Returns the method call the host resolves for reading attribute `attr_id` of the
+    /// external object stored at `self_id`.
+    ///
+    /// The returned arguments hold a new reference to `self_id`, which the caller must
+    /// account for with `Heap::inc_ref`.
+    ///
+    /// # Errors
+    /// Returns `AttributeError` for names starting with an underscore.
+    pub fn getattr(&self, self_id: HeapId, attr_id: StringId, interns: &Interns) -> RunResult<AttrCallResult> {
+        let attr_str = interns.get_str(attr_id);
+        if attr_str.starts_with('_') {
+            return Err(ExcType::attribute_error(&self.class_name, attr_str));
+        }
+        let args = ArgValues::Two(Value::Ref(self_id), Value::InternString(attr_id));
+        Ok(AttrCallResult::MethodCall(
+            EitherStr::Heap(EXTERNAL_GETATTR.to_owned()),
+            args,
+        ))
+    }
+}
+
+impl PyTrait for ExternalObject {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::ExternalObject
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<external {} #{}>", self.class_name, self.handle)
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // External objects don't contain heap references
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.class_name.capacity()
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        args.drop_with_heap(heap);
+        Err(ExcType::attribute_error(&self.class_name, attr.as_str(interns)))
+    }
+
+    /// Forwards public method calls, and `__enter__`/`__exit__`, to the host.
+    fn py_call_attr_raw(
+        &mut self,
+        self_id: HeapId,
+        vm: &mut VM<'_, '_, impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+    ) -> RunResult<AttrCallResult> {
+        let is_method_name = !attr.as_str(vm.interns).starts_with('_')
+            || matches!(
+                attr.static_string(),
+                Some(StaticStrings::DunderEnter | StaticStrings::DunderExit)
+            );
+        if is_method_name {
+            vm.heap.inc_ref(self_id);
+            Ok(AttrCallResult::MethodCall(
+                attr.clone(),
+                args.prepend(Value::Ref(self_id)),
+            ))
+        } else {
+            self.py_call_attr(vm.heap, attr, args, vm.interns)
+                .map(AttrCallResult::Value)
+        }
+    }
+}
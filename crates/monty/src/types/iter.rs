@@ -26,17 +26,24 @@
 //! This allows `advance_on_heap()` to coordinate access without extracting
 //! the iterator from the heap (avoiding `std::mem::replace` overhead).
 //!
+//! Composite iterators (`enumerate()`, `zip()` and iterators wrapping other iterators)
+//! advance their inner iterators through `advance_on_heap()`, so they are taken out of the
+//! heap with `with_entry_mut()` while they run.
+//!
 //! ## Builtin Support
 //!
-//! The `iterator_next()` helper implements the `next()` builtin.
+//! The `iterator_next()` helper implements the `next()` builtin, and `get_iter()`
+//! implements `iter()` for the builtin and the VM's `GetIter` opcode.
+
+use smallvec::smallvec;
 
 use crate::{
     args::ArgValues,
     exception_private::{ExcType, RunResult},
     heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
     intern::{BytesId, Interns, StringId},
-    resource::ResourceTracker,
-    types::{PyTrait, Range, str::allocate_char},
+    resource::{MAX_DATA_RECURSION_DEPTH, ResourceError, ResourceTracker},
+    types::{DictViewKind, PyTrait, Range, allocate_tuple, str::allocate_char, tuple::TupleVec},
     value::Value,
 };
 
@@ -73,18 +80,226 @@ impl MontyIter {
             return Err(ExcType::type_error("iter(callable, sentinel) is not yet supported"));
         }
 
-        // Check if already an iterator - return self
-        if let Value::Ref(id) = &iterable
-            && matches!(heap.get(*id), HeapData::Iter(_))
-        {
-            // Already an iterator - return it (refcount already correct from caller)
-            return Ok(iterable);
+        get_iter(iterable, heap, interns)
+    }
+
+    /// Creates the iterator returned by `enumerate(iterable, start)`.
+    ///
+    /// Yields `(index, item)` tuples lazily, pulling each item from an iterator over `iterable`.
+    pub fn enumerate(
+        iterable: Value,
+        start: i64,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Self> {
+        let inner = get_iter(iterable, heap, interns)?;
+        let Value::Ref(iter_id) = inner else {
+            unreachable!("get_iter always returns a heap iterator")
+        };
+        let depth = match nesting_depth(heap, &[iter_id]) {
+            Ok(depth) => depth,
+            Err(err) => {
+                inner.drop_with_heap(heap);
+                return Err(err.into());
+            }
+        };
+        Ok(Self {
+            index: 0,
+            iter_value: IterValue::Enumerate {
+                iter_id,
+                next: start,
+                depth,
+            },
+            value: inner,
+        })
+    }
+
+    /// Creates the iterator returned by `zip(*iterables)`.
+    ///
+    /// Yields tuples lazily until the shortest iterable is exhausted. The inner iterators are
+    /// kept alive by a tuple stored as the iterator's value. Iterables not yet consumed when
+    /// an error is raised are left to the caller to drop.
+    pub fn zip(
+        iterables: impl Iterator<Item = Value>,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Self> {
+        let (iter_ids, depth, value) = inner_iters(iterables, heap, interns)?;
+        Ok(Self {
+            index: 0,
+            iter_value: IterValue::Zip { iter_ids, depth },
+            value,
+        })
+    }
+
+    /// Creates the iterator returned by `itertools.chain(*iterables)`.
+    ///
+    /// Yields every item of the first iterable, then of the second, and so on. Like `zip()`,
+    /// the inner iterators are kept alive by a tuple stored as the iterator's value, and
+    /// iterables not yet consumed when an error is raised are left to the caller to drop.
+    pub fn chain(
+        iterables: impl Iterator<Item = Value>,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Self> {
+        let (iter_ids, depth, value) = inner_iters(iterables, heap, interns)?;
+        Ok(Self {
+            index: 0,
+            iter_value: IterValue::Chain {
+                iter_ids,
+                current: 0,
+                depth,
+            },
+            value,
+        })
+    }
+
+    /// Creates the iterator returned by `itertools.count(start, step)`.
+    ///
+    /// Yields `start`, `start + step`, `start + 2 * step`, ... without end.
+    pub fn count(start: i64, step: i64) -> Self {
+        Self {
+            index: 0,
+            iter_value: IterValue::Count {
+                next: Some(start),
+                step,
+            },
+            value: Value::None,
+        }
+    }
+
+    /// Creates the iterator returned by `itertools.islice(iterable, start, stop, step)`.
+    ///
+    /// Skips the first `start` items of `iterable`, then yields every `step`th item until
+    /// `stop` items have been consumed (or the iterable is exhausted when `stop` is `None`).
+    pub fn islice(
+        iterable: Value,
+        start: usize,
+        stop: Option<usize>,
+        step: usize,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Self> {
+        let inner = get_iter(iterable, heap, interns)?;
+        let Value::Ref(iter_id) = inner else {
+            unreachable!("get_iter always returns a heap iterator")
+        };
+        let depth = match nesting_depth(heap, &[iter_id]) {
+            Ok(depth) => depth,
+            Err(err) => {
+                inner.drop_with_heap(heap);
+                return Err(err.into());
+            }
+        };
+        Ok(Self {
+            index: 0,
+            iter_value: IterValue::Islice {
+                iter_id,
+                next: start,
+                stop,
+                step,
+                depth,
+            },
+            value: inner,
+        })
+    }
+
+    /// Creates the iterator returned by `reversed(seq)`.
+    ///
+    /// Lists, tuples, bytes, dicts, deques and dict views are read lazily back to front from the
+    /// sequence itself. Strings and ranges are copied like in `new()`, so the source value is
+    /// dropped immediately. Other types raise `TypeError: '{type}' object is not reversible`.
+    pub fn reversed(value: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Self> {
+        let iter_value = match &value {
+            Value::InternString(string_id) => Some(IterValue::from_str_reversed(interns.get_str(*string_id))),
+            Value::InternBytes(bytes_id) => Some(IterValue::Reversed {
+                len: interns.get_bytes(*bytes_id).len(),
+                checks_mutation: false,
+            }),
+            Value::Ref(heap_id) => match heap.get(*heap_id) {
+                HeapData::List(list) => Some(IterValue::Reversed {
+                    len: list.len(),
+                    checks_mutation: false,
+                }),
+                HeapData::Tuple(tuple) => Some(IterValue::Reversed {
+                    len: tuple.as_slice().len(),
+                    checks_mutation: false,
+                }),
+                HeapData::NamedTuple(namedtuple) => Some(IterValue::Reversed {
+                    len: namedtuple.len(),
+                    checks_mutation: false,
+                }),
+                HeapData::Bytes(b) => Some(IterValue::Reversed {
+                    len: b.len(),
+                    checks_mutation: false,
+                }),
+                HeapData::ByteArray(b) => Some(IterValue::Reversed {
+                    len: b.len(),
+                    checks_mutation: false,
+                }),
+                HeapData::Dict(dict) => Some(IterValue::Reversed {
+                    len: dict.len(),
+                    checks_mutation: true,
+                }),
+                HeapData::Deque(deque) => Some(IterValue::Reversed {
+                    len: deque.len(),
+                    checks_mutation: true,
+                }),
+                HeapData::DictView(view) => Some(IterValue::DictView {
+                    dict_id: view.dict_id(),
+                    kind: view.kind(),
+                    len: view.len(heap),
+                    reversed: true,
+                }),
+                HeapData::Str(s) => Some(IterValue::from_str_reversed(s.as_str())),
+                HeapData::Range(range) => Some(IterValue::from_range_reversed(range)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(iter_value) = iter_value else {
+            let err = ExcType::type_error_not_reversible(value.py_type(heap));
+            value.drop_with_heap(heap);
+            return Err(err);
+        };
+        // Strings and ranges are copied into the iterator value, see `new()`
+        let value = if matches!(iter_value, IterValue::Range { .. } | IterValue::IterStr { .. }) {
+            value.drop_with_heap(heap);
+            Value::None
+        } else {
+            value
+        };
+        Ok(Self {
+            index: 0,
+            iter_value,
+            value,
+        })
+    }
+
+    /// Returns how many composite iterators (`enumerate()`, `zip()`, ...) are nested in this one.
+    ///
+    /// Advancing a composite iterator recurses into its inner iterators, so construction
+    /// limits this depth to protect the Rust stack.
+    fn nesting_depth(&self) -> u16 {
+        match &self.iter_value {
+            IterValue::Enumerate { depth, .. }
+            | IterValue::Zip { depth, .. }
+            | IterValue::Chain { depth, .. }
+            | IterValue::Islice { depth, .. } => *depth,
+            _ => 0,
         }
+    }
 
-        // Create new iterator
-        let iter = Self::new(iterable, heap, interns)?;
-        let id = heap.allocate(HeapData::Iter(iter))?;
-        Ok(Value::Ref(id))
+    /// Returns true for iterators that advance other heap iterators.
+    fn is_composite(&self) -> bool {
+        matches!(
+            self.iter_value,
+            IterValue::Iterator { .. }
+                | IterValue::Enumerate { .. }
+                | IterValue::Zip { .. }
+                | IterValue::Chain { .. }
+                | IterValue::Islice { .. }
+        )
     }
 
     /// Creates a new MontyIter from a Value.
@@ -179,7 +394,7 @@ impl MontyIter {
                 checks_mutation,
             } => {
                 // For types with captured len, check exhaustion here.
-                // For List (len=None), exhaustion is checked in advance_on_heap().
+                // For List and ByteArray (len=None), exhaustion is checked in advance_on_heap().
                 if let Some(l) = len
                     && self.index >= *l
                 {
@@ -191,6 +406,43 @@ impl MontyIter {
                     expected_len: if *checks_mutation { *len } else { None },
                 })
             }
+            IterValue::DictView {
+                dict_id,
+                kind,
+                len,
+                reversed,
+            } => {
+                if self.index >= *len {
+                    return None;
+                }
+                Some(IterState::DictView {
+                    dict_id: *dict_id,
+                    kind: *kind,
+                    index: if *reversed { *len - 1 - self.index } else { self.index },
+                    len: *len,
+                })
+            }
+            IterValue::Reversed { len, checks_mutation } => {
+                if self.index >= *len {
+                    return None;
+                }
+                let Value::Ref(heap_id) = self.value else {
+                    unreachable!("reversed interned bytes use fast path, not iter_state")
+                };
+                Some(IterState::HeapIndex {
+                    heap_id,
+                    index: *len - 1 - self.index,
+                    expected_len: checks_mutation.then_some(*len),
+                })
+            }
+            IterValue::Count { .. } => unreachable!("count uses fast path, not iter_state"),
+            IterValue::Iterator { .. }
+            | IterValue::Enumerate { .. }
+            | IterValue::Zip { .. }
+            | IterValue::Chain { .. }
+            | IterValue::Islice { .. } => {
+                unreachable!("composite iterators are advanced with for_next, not iter_state")
+            }
         }
     }
 
@@ -227,7 +479,8 @@ impl MontyIter {
                     Some(Ok(None))
                 } else {
                     let value = *next;
-                    *next += *step;
+                    // Wrapping: the value past the last element is never yielded
+                    *next = next.wrapping_add(*step);
                     self.index += 1;
                     Some(Ok(Some(Value::Int(value))))
                 }
@@ -259,7 +512,27 @@ impl MontyIter {
                     Some(Ok(Some(Value::Int(i64::from(bytes[i])))))
                 }
             }
-            IterValue::HeapRef { .. } => None,
+            IterValue::Reversed { len, .. } => {
+                let Value::InternBytes(bytes_id) = self.value else {
+                    return None;
+                };
+                if self.index >= *len {
+                    Some(Ok(None))
+                } else {
+                    let i = *len - 1 - self.index;
+                    self.index += 1;
+                    let bytes = interns.get_bytes(bytes_id);
+                    Some(Ok(Some(Value::Int(i64::from(bytes[i])))))
+                }
+            }
+            IterValue::Count { next, step } => Some(count_next(next, *step)),
+            IterValue::HeapRef { .. }
+            | IterValue::DictView { .. }
+            | IterValue::Iterator { .. }
+            | IterValue::Enumerate { .. }
+            | IterValue::Zip { .. }
+            | IterValue::Chain { .. }
+            | IterValue::Islice { .. } => None,
         }
     }
 
@@ -280,7 +553,7 @@ impl MontyIter {
                     return Ok(None);
                 }
                 let value = *next;
-                *next += *step;
+                *next = next.wrapping_add(*step);
                 self.index += 1;
                 Ok(Some(Value::Int(value)))
             }
@@ -338,6 +611,106 @@ impl MontyIter {
                 self.index += 1;
                 Ok(Some(clone_and_inc_ref(item, heap)))
             }
+            IterValue::DictView {
+                dict_id,
+                kind,
+                len,
+                reversed,
+            } => {
+                if self.index >= *len {
+                    return Ok(None);
+                }
+                let index = if *reversed { *len - 1 - self.index } else { self.index };
+                let item = kind.item_at(*dict_id, index, *len, heap)?;
+                self.index += 1;
+                Ok(Some(item))
+            }
+            IterValue::Reversed { len, checks_mutation } => {
+                if self.index >= *len {
+                    return Ok(None);
+                }
+                let i = *len - 1 - self.index;
+                let item = match &self.value {
+                    Value::InternBytes(bytes_id) => Value::Int(i64::from(interns.get_bytes(*bytes_id)[i])),
+                    Value::Ref(heap_id) => {
+                        let expected_len = checks_mutation.then_some(*len);
+                        // A list or bytearray may have shrunk below the next index
+                        let Some(item) = get_heap_item(heap, *heap_id, i, expected_len)? else {
+                            return Ok(None);
+                        };
+                        clone_and_inc_ref(item, heap)
+                    }
+                    _ => unreachable!("reversed iterators only hold interned bytes or heap sequences"),
+                };
+                self.index += 1;
+                Ok(Some(item))
+            }
+            IterValue::Iterator { iter_id } => advance_on_heap(heap, *iter_id, interns),
+            IterValue::Enumerate { iter_id, next, .. } => {
+                let Some(item) = advance_on_heap(heap, *iter_id, interns)? else {
+                    return Ok(None);
+                };
+                let index = Value::Int(*next);
+                *next += 1;
+                Ok(Some(allocate_tuple(smallvec![index, item], heap)?))
+            }
+            IterValue::Zip { iter_ids, .. } => {
+                if iter_ids.is_empty() {
+                    return Ok(None);
+                }
+                let mut items = TupleVec::with_capacity(iter_ids.len());
+                for &iter_id in &*iter_ids {
+                    match advance_on_heap(heap, iter_id, interns) {
+                        Ok(Some(item)) => items.push(item),
+                        result => {
+                            // Exhausted or failed - drop the partial tuple items
+                            for item in items {
+                                item.drop_with_heap(heap);
+                            }
+                            return result.map(|_| None);
+                        }
+                    }
+                }
+                Ok(Some(allocate_tuple(items, heap)?))
+            }
+            IterValue::Chain { iter_ids, current, .. } => {
+                while let Some(&iter_id) = iter_ids.get(*current) {
+                    if let Some(item) = advance_on_heap(heap, iter_id, interns)? {
+                        return Ok(Some(item));
+                    }
+                    *current += 1;
+                }
+                Ok(None)
+            }
+            IterValue::Count { next, step } => count_next(next, *step),
+            IterValue::Islice {
+                iter_id,
+                next,
+                stop,
+                step,
+                ..
+            } => {
+                // Follows CPython: skip up to the `next` index, which never passes `stop`
+                while self.index < *next {
+                    let Some(item) = advance_on_heap(heap, *iter_id, interns)? else {
+                        return Ok(None);
+                    };
+                    item.drop_with_heap(heap);
+                    self.index += 1;
+                }
+                if stop.is_some_and(|stop| self.index >= stop) {
+                    return Ok(None);
+                }
+                let Some(item) = advance_on_heap(heap, *iter_id, interns)? else {
+                    return Ok(None);
+                };
+                self.index += 1;
+                *next = next.saturating_add(*step);
+                if let Some(stop) = *stop {
+                    *next = (*next).min(stop);
+                }
+                Ok(Some(item))
+            }
         }
     }
 
@@ -345,17 +718,42 @@ impl MontyIter {
     ///
     /// For immutable types (Range, Tuple, Str, Bytes, FrozenSet), returns the exact remaining count.
     /// For List, returns current length minus index (may change if list is mutated).
-    /// For Dict and Set, returns the captured length minus index (used for size-change detection).
+    /// For Dict, dict views and Set, returns the captured length minus index (used for size-change detection).
+    /// For composite iterators, returns the smallest remaining size of the inner iterators.
     pub fn size_hint(&self, heap: &Heap<impl ResourceTracker>) -> usize {
         let len = match &self.iter_value {
-            IterValue::Range { len, .. } | IterValue::IterStr { len, .. } | IterValue::InternBytes { len, .. } => *len,
+            IterValue::Iterator { iter_id } | IterValue::Enumerate { iter_id, .. } => {
+                return inner_size_hint(heap, *iter_id);
+            }
+            IterValue::Zip { iter_ids, .. } => {
+                return iter_ids
+                    .iter()
+                    .map(|iter_id| inner_size_hint(heap, *iter_id))
+                    .min()
+                    .unwrap_or(0);
+            }
+            IterValue::Chain { iter_ids, current, .. } => {
+                return iter_ids.iter().skip(*current).fold(0, |total, iter_id| {
+                    total.saturating_add(inner_size_hint(heap, *iter_id))
+                });
+            }
+            IterValue::Islice { iter_id, stop, .. } => {
+                let inner = inner_size_hint(heap, *iter_id);
+                return stop.map_or(inner, |stop| inner.min(stop.saturating_sub(self.index)));
+            }
+            // Endless, so there is no meaningful size to preallocate
+            IterValue::Count { .. } => return 0,
+            IterValue::Range { len, .. }
+            | IterValue::IterStr { len, .. }
+            | IterValue::InternBytes { len, .. }
+            | IterValue::DictView { len, .. }
+            | IterValue::Reversed { len, .. } => *len,
             IterValue::HeapRef { heap_id, len, .. } => {
-                // For List (len=None), check current length dynamically
-                len.unwrap_or_else(|| {
-                    let HeapData::List(list) = heap.get(*heap_id) else {
-                        panic!("HeapRef with len=None should only be List")
-                    };
-                    list.len()
+                // For List and ByteArray (len=None), check current length dynamically
+                len.unwrap_or_else(|| match heap.get(*heap_id) {
+                    HeapData::List(list) => list.len(),
+                    HeapData::ByteArray(bytes) => bytes.len(),
+                    _ => panic!("HeapRef with len=None should only be List or ByteArray"),
                 })
             }
         };
@@ -367,30 +765,115 @@ impl MontyIter {
     /// Consumes the iterator and returns all items. Used by `list()`, `tuple()`,
     /// and similar constructors that need to materialize all items.
     ///
-    /// Pre-allocates capacity based on `size_hint()` for better performance.
-    pub fn collect<T: FromIterator<Value>>(
-        self,
-        heap: &mut Heap<impl ResourceTracker>,
-        interns: &Interns,
-    ) -> RunResult<T> {
-        let mut guard = HeapGuard::new(self, heap);
-        let (this, heap) = guard.as_parts_mut();
-        HeapedMontyIter(this, heap, interns).collect()
+    /// Reserves `size_hint()` slots up front so sized iterables like ranges and lists
+    /// are collected with a single allocation. Any slots left unused, say because the
+    /// hint was off, are given back so the list or tuple is charged for what it holds.
+    pub fn collect(self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Vec<Value>> {
+        let mut iter_guard = HeapGuard::new(self, heap);
+        let (this, heap) = iter_guard.as_parts_mut();
+        let mut items = Vec::new();
+        // A hint too large to reserve (an astronomically long range) just grows as usual
+        let _ = items.try_reserve_exact(this.size_hint(heap));
+        let mut items_guard = HeapGuard::new(items, heap);
+        let (items, heap) = items_guard.as_parts_mut();
+        while let Some(item) = this.for_next(heap, interns)? {
+            items.push(item);
+        }
+        let mut items = items_guard.into_inner();
+        items.shrink_to_fit();
+        Ok(items)
     }
 }
 
-struct HeapedMontyIter<'a, T: ResourceTracker>(&'a mut MontyIter, &'a mut Heap<T>, &'a Interns);
+/// Returns an iterator over `value`, implementing `iter(value)`.
+///
+/// Iterators are returned unchanged (`iter(it) is it`); any other iterable gets a new
+/// `MontyIter` allocated on the heap. Raises `TypeError` if the value is not iterable.
+pub(crate) fn get_iter(value: Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    if let Value::Ref(id) = &value
+        && matches!(heap.get(*id), HeapData::Iter(_))
+    {
+        // Already an iterator - return it (refcount already correct from caller)
+        return Ok(value);
+    }
+    let iter = MontyIter::new(value, heap, interns)?;
+    let id = heap.allocate(HeapData::Iter(iter))?;
+    Ok(Value::Ref(id))
+}
 
-impl<T: ResourceTracker> Iterator for HeapedMontyIter<'_, T> {
-    type Item = RunResult<Value>;
+/// Returns the nesting depth for a composite iterator over the heap iterators `iter_ids`.
+///
+/// Raises a recursion error when the depth would exceed `MAX_DATA_RECURSION_DEPTH`.
+fn nesting_depth(heap: &Heap<impl ResourceTracker>, iter_ids: &[HeapId]) -> Result<u16, ResourceError> {
+    let inner = iter_ids
+        .iter()
+        .map(|iter_id| match heap.get(*iter_id) {
+            HeapData::Iter(iter) => iter.nesting_depth(),
+            _ => unreachable!("composite iterators only wrap heap iterators"),
+        })
+        .max()
+        .unwrap_or(0);
+    if inner >= MAX_DATA_RECURSION_DEPTH {
+        return Err(ResourceError::Recursion {
+            limit: MAX_DATA_RECURSION_DEPTH as usize,
+            depth: MAX_DATA_RECURSION_DEPTH as usize + 1,
+        });
+    }
+    Ok(inner + 1)
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.for_next(self.1, self.2).transpose()
+/// Calls `get_iter()` on each of `iterables` for a composite iterator over all of them.
+///
+/// Returns the inner iterator ids, their nesting depth, and a tuple holding the references
+/// to the inner iterators, to be stored as the composite iterator's value.
+fn inner_iters(
+    iterables: impl Iterator<Item = Value>,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<(Vec<HeapId>, u16, Value)> {
+    let mut iters = TupleVec::new();
+    let mut iter_ids = Vec::new();
+    for iterable in iterables {
+        match get_iter(iterable, heap, interns) {
+            Ok(inner) => {
+                if let Value::Ref(iter_id) = &inner {
+                    iter_ids.push(*iter_id);
+                }
+                iters.push(inner);
+            }
+            Err(err) => {
+                for inner in iters {
+                    inner.drop_with_heap(heap);
+                }
+                return Err(err);
+            }
+        }
     }
+    let depth = match nesting_depth(heap, &iter_ids) {
+        Ok(depth) => depth,
+        Err(err) => {
+            for inner in iters {
+                inner.drop_with_heap(heap);
+            }
+            return Err(err.into());
+        }
+    };
+    let value = allocate_tuple(iters, heap)?;
+    Ok((iter_ids, depth, value))
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.0.size_hint(self.1);
-        (remaining, Some(remaining))
+/// Yields the next value of an `itertools.count()` counter and advances it by `step`.
+fn count_next(next: &mut Option<i64>, step: i64) -> RunResult<Option<Value>> {
+    let value = next.ok_or_else(ExcType::overflow_error_count)?;
+    *next = value.checked_add(step);
+    Ok(Some(Value::Int(value)))
+}
+
+/// Returns the remaining size of the heap iterator `iter_id`.
+fn inner_size_hint(heap: &Heap<impl ResourceTracker>, iter_id: HeapId) -> usize {
+    match heap.get(iter_id) {
+        HeapData::Iter(iter) => iter.size_hint(heap),
+        _ => unreachable!("composite iterators only wrap heap iterators"),
     }
 }
 
@@ -431,6 +914,15 @@ pub(crate) fn advance_on_heap(
     let HeapData::Iter(iter) = heap.get(iter_id) else {
         panic!("advance_on_heap: expected Iterator on heap");
     };
+    if iter.is_composite() {
+        // Composite iterators advance other heap iterators, so take this one out of the heap
+        return heap.with_entry_mut(iter_id, |heap, data| {
+            let HeapData::Iter(iter) = data else {
+                panic!("advance_on_heap: expected Iterator on heap");
+            };
+            iter.for_next(heap, interns)
+        });
+    }
     let Some(state) = iter.iter_state() else {
         return Ok(None); // Iterator exhausted
     };
@@ -457,6 +949,12 @@ pub(crate) fn advance_on_heap(
             }
             (item, None)
         }
+        IterState::DictView {
+            dict_id,
+            kind,
+            index,
+            len,
+        } => (kind.item_at(dict_id, index, len, heap)?, None),
     };
 
     // Phase 3: Advance the iterator
@@ -499,7 +997,18 @@ fn get_heap_item(
                 dict.key_at(index).expect("index should be valid").copy_for_extend(),
             ))
         }
+        HeapData::Deque(deque) => {
+            // Check for deque mutation
+            if let Some(expected) = expected_len
+                && deque.len() != expected
+            {
+                return Err(ExcType::runtime_error_deque_mutated());
+            }
+            Ok(Some(deque.get(index).expect("index should be valid").copy_for_extend()))
+        }
         HeapData::Bytes(bytes) => Ok(Some(Value::Int(i64::from(bytes.as_slice()[index])))),
+        // Bytearrays can shrink during iteration, like lists
+        HeapData::ByteArray(bytes) => Ok(bytes.as_slice().get(index).map(|&b| Value::Int(i64::from(b)))),
         HeapData::Set(set) => {
             // Check for set mutation
             if let Some(expected) = expected_len
@@ -552,7 +1061,8 @@ pub fn iterator_next(
         if let Some(d) = default {
             d.drop_with_heap(heap);
         }
-        return Err(ExcType::type_error_not_iterable(iter_value.py_type(heap)));
+        let value_type = iter_value.py_type(heap);
+        return Err(ExcType::type_error(format!("'{value_type}' object is not an iterator")));
     };
 
     // Check that it's actually an iterator
@@ -597,13 +1107,20 @@ pub fn iterator_next(
 enum IterState {
     /// String iterator yields this character; char_len is UTF-8 byte length for advance().
     IterStr { char: char, char_len: usize },
-    /// Heap-based iterator (List, Tuple, NamedTuple, Dict, Bytes, Set, FrozenSet).
+    /// Heap-based iterator (List, Tuple, NamedTuple, Dict, Bytes, ByteArray, Set, FrozenSet).
     /// The expected_len is Some for types that check for mutation (Dict, Set).
     HeapIndex {
         heap_id: HeapId,
         index: usize,
         expected_len: Option<usize>,
     },
+    /// Dict view iterator yields the item at `index` of the dict, checking it still has `len` entries.
+    DictView {
+        dict_id: HeapId,
+        kind: DictViewKind,
+        index: usize,
+        len: usize,
+    },
 }
 
 /// Increments the reference count for a value copied via `copy_for_extend()`.
@@ -651,17 +1168,75 @@ enum IterValue {
     },
     /// Iterating over interned bytes, yields `Value::Int` for each byte.
     InternBytes { bytes_id: BytesId, len: usize },
-    /// Iterating over a heap-allocated container (List, Tuple, NamedTuple, Dict, Bytes, Set, FrozenSet).
+    /// Iterating over a heap-allocated container (List, Tuple, NamedTuple, Dict, Deque, Bytes, ByteArray, Set, FrozenSet).
     ///
-    /// - `len`: `None` for List (checked dynamically since lists can mutate during iteration),
+    /// - `len`: `None` for List and ByteArray (checked dynamically since they can mutate during iteration),
     ///   `Some(n)` for other types (captured at construction for exhaustion checking).
-    /// - `checks_mutation`: `true` for Dict/Set (raises RuntimeError if size changes),
+    /// - `checks_mutation`: `true` for Dict/Set/Deque (raises RuntimeError if size changes),
     ///   `false` for other types.
     HeapRef {
         heap_id: HeapId,
         len: Option<usize>,
         checks_mutation: bool,
     },
+    /// Iterating over a dict view, yields keys, values or `(key, value)` tuples.
+    ///
+    /// Reads the underlying dict directly; the iterator's `value` keeps the view (and so
+    /// the dict) alive. `len` is captured at construction so that resizing the dict during
+    /// iteration raises RuntimeError, like iterating the dict itself.
+    ///
+    /// `reversed` is set for `reversed(view)`, which walks the entries back to front.
+    DictView {
+        dict_id: HeapId,
+        kind: DictViewKind,
+        len: usize,
+        reversed: bool,
+    },
+    /// The iterator returned by `reversed()` for interned bytes and heap sequences
+    /// (List, Tuple, NamedTuple, Bytes, ByteArray, Dict, Deque), read back to front from the iterator's `value`.
+    ///
+    /// `len` is captured at construction, so items appended later are not visited. A list or
+    /// bytearray that shrinks below the next index ends the iteration, like CPython.
+    /// `checks_mutation` is `true` for Dict and Deque (raises RuntimeError if their size changes).
+    Reversed { len: usize, checks_mutation: bool },
+    /// Iterating over another iterator, e.g. `list(it)`, yields that iterator's items.
+    ///
+    /// The iterator's `value` holds the reference to the inner iterator. These wrappers are
+    /// only created transiently by consumers like `list()` and never stored on the heap.
+    Iterator { iter_id: HeapId },
+    /// The iterator returned by `enumerate()`, yields `(next, item)` tuples.
+    ///
+    /// The iterator's `value` holds the reference to the inner iterator `iter_id`.
+    Enumerate { iter_id: HeapId, next: i64, depth: u16 },
+    /// The iterator returned by `zip()`, yields a tuple with one item from each inner iterator.
+    ///
+    /// The iterator's `value` is a tuple holding the references to the inner iterators.
+    Zip { iter_ids: Vec<HeapId>, depth: u16 },
+    /// The iterator returned by `itertools.chain()`, yields the items of each inner iterator
+    /// in turn, starting with `iter_ids[current]`.
+    ///
+    /// The iterator's `value` is a tuple holding the references to the inner iterators.
+    Chain {
+        iter_ids: Vec<HeapId>,
+        current: usize,
+        depth: u16,
+    },
+    /// The iterator returned by `itertools.count()`, yields `next` and then adds `step`.
+    ///
+    /// `next` is `None` once the counter has run past the `i64` range.
+    Count { next: Option<i64>, step: i64 },
+    /// The iterator returned by `itertools.islice()`, yields the item at index `next` of the
+    /// inner iterator `iter_id` and then moves `next` on by `step`, up to `stop`.
+    ///
+    /// The iterator's `index` counts the items consumed from the inner iterator, and its
+    /// `value` holds the reference to the inner iterator.
+    Islice {
+        iter_id: HeapId,
+        next: usize,
+        stop: Option<usize>,
+        step: usize,
+        depth: u16,
+    },
 }
 
 impl IterValue {
@@ -683,6 +1258,19 @@ impl IterValue {
         }
     }
 
+    /// Creates a Range iterator value that walks the range from its last element back to `start`.
+    fn from_range_reversed(range: &Range) -> Self {
+        let len = range.len();
+        // The last element is within the range, so it fits in i64 even if the intermediate product doesn't
+        let offset = i128::try_from(len.saturating_sub(1)).expect("range length fits in i128") * i128::from(range.step);
+        let last = i64::try_from(i128::from(range.start) + offset).expect("last range element fits in i64");
+        Self::Range {
+            next: last,
+            step: range.step.wrapping_neg(),
+            len,
+        }
+    }
+
     /// Creates an iterator value over a string.
     ///
     /// Copies the string content and counts characters for the length field.
@@ -697,6 +1285,11 @@ impl IterValue {
         }
     }
 
+    /// Creates an iterator value over the characters of a string in reverse order.
+    fn from_str_reversed(s: &str) -> Self {
+        Self::from_str(&s.chars().rev().collect::<String>())
+    }
+
     /// Creates an iterator value over interned bytes.
     fn from_intern_bytes(bytes_id: BytesId, interns: &Interns) -> Self {
         let bytes = interns.get_bytes(bytes_id);
@@ -709,8 +1302,8 @@ impl IterValue {
     /// Creates an iterator value from heap data.
     fn from_heap_data(heap_id: HeapId, heap: &Heap<impl ResourceTracker>) -> Option<Self> {
         match heap.get(heap_id) {
-            // List: no captured len (checked dynamically), no mutation check
-            HeapData::List(_) => Some(Self::HeapRef {
+            // List/ByteArray: no captured len (checked dynamically), no mutation check
+            HeapData::List(_) | HeapData::ByteArray(_) => Some(Self::HeapRef {
                 heap_id,
                 len: None,
                 checks_mutation: false,
@@ -747,22 +1340,45 @@ impl IterValue {
                 len: Some(set.len()),
                 checks_mutation: true,
             }),
+            HeapData::Deque(deque) => Some(Self::HeapRef {
+                heap_id,
+                len: Some(deque.len()),
+                checks_mutation: true,
+            }),
+            HeapData::DictView(view) => Some(Self::DictView {
+                dict_id: view.dict_id(),
+                kind: view.kind(),
+                len: view.len(heap),
+                reversed: false,
+            }),
             // String: copy content for iteration
             HeapData::Str(s) => Some(Self::from_str(s.as_str())),
             // Range: copy values for iteration
             HeapData::Range(range) => Some(Self::from_range(range)),
-            // Closures, FunctionDefaults, Cells, Exceptions, Dataclasses, Iterators, LongInts, Slices, Modules,
-            // Paths, and async types are not iterable
+            // Iterator: advance the inner iterator
+            HeapData::Iter(_) => Some(Self::Iterator { iter_id: heap_id }),
+            // Closures, FunctionDefaults, Cells, Exceptions, Dataclasses, LongInts, Slices, Modules,
+            // Paths, datetime types, external objects, functools objects, named tuple classes and async types
+            // are not iterable
             HeapData::Closure(_, _, _)
             | HeapData::FunctionDefaults(_, _)
             | HeapData::Cell(_)
             | HeapData::Exception(_)
             | HeapData::Dataclass(_)
-            | HeapData::Iter(_)
             | HeapData::LongInt(_)
             | HeapData::Slice(_)
             | HeapData::Module(_)
             | HeapData::Path(_)
+            | HeapData::ContextManager(_)
+            | HeapData::RePattern(_)
+            | HeapData::ReMatch(_)
+            | HeapData::Date(_)
+            | HeapData::DateTime(_)
+            | HeapData::TimeDelta(_)
+            | HeapData::ExternalObject(_)
+            | HeapData::Partial(_)
+            | HeapData::LruCache(_)
+            | HeapData::NamedTupleType(_)
             | HeapData::Coroutine(_)
             | HeapData::GatherFuture(_) => None,
         }
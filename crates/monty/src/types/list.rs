@@ -1,8 +1,7 @@
-use std::fmt::Write;
+use std::{fmt::Write, sync::Arc};
 
 use ahash::AHashSet;
 use itertools::Itertools;
-use smallvec::SmallVec;
 
 use super::{AttrCallResult, MontyIter, PyTrait};
 use crate::{
@@ -47,6 +46,12 @@ use crate::{
 /// reference counts are incremented if they are heap-allocated (Ref variants).
 /// This ensures values remain valid while referenced by the list.
 ///
+/// # Copy-on-Write
+/// Copies made by `copy()`, `list(lst)` and `lst[:]` share the original's items
+/// (see `shallow_copy`) until either list is mutated, at which point the mutated list
+/// takes a private copy of the items. Each list sharing the items still owns a reference
+/// to every one of them, so dropping a list works the same whether or not it's shared.
+///
 /// # GC Optimization
 /// The `contains_refs` flag tracks whether the list contains any `Value::Ref` items.
 /// This allows `collect_child_ids` and `py_dec_ref_ids` to skip iteration when the
@@ -54,7 +59,8 @@ use crate::{
 /// improving GC performance for lists of primitives.
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 pub(crate) struct List {
-    items: Vec<Value>,
+    /// The items, shared with copies of this list until one of them is mutated.
+    items: Arc<Vec<Value>>,
     /// True if any item in the list is a `Value::Ref`. Used to skip iteration
     /// in `collect_child_ids` and `py_dec_ref_ids` when no refs are present.
     contains_refs: bool,
@@ -72,11 +78,42 @@ impl List {
     pub fn new(vec: Vec<Value>) -> Self {
         let contains_refs = vec.iter().any(|v| matches!(v, Value::Ref(_)));
         Self {
-            items: vec,
+            items: Arc::new(vec),
             contains_refs,
         }
     }
 
+    /// Returns a copy of the list that shares its items until either list is mutated.
+    ///
+    /// Increments the refcount of every item since both lists hold a reference to it,
+    /// but doesn't allocate a new vector.
+    #[must_use]
+    pub fn shallow_copy(&self, heap: &mut Heap<impl ResourceTracker>) -> Self {
+        let copy = self.share_items();
+        copy.inc_item_refs(heap);
+        copy
+    }
+
+    /// Returns a list sharing this list's items without incrementing their refcounts.
+    ///
+    /// The caller must call `inc_item_refs` on the result. This is split out of `shallow_copy`
+    /// so a list borrowed from the heap can be copied.
+    fn share_items(&self) -> Self {
+        Self {
+            items: Arc::clone(&self.items),
+            contains_refs: self.contains_refs,
+        }
+    }
+
+    /// Increments the refcount of every item, for a list that now shares them.
+    fn inc_item_refs(&self, heap: &mut Heap<impl ResourceTracker>) {
+        if self.contains_refs {
+            for id in self.items.iter().filter_map(Value::ref_id) {
+                heap.inc_ref(id);
+            }
+        }
+    }
+
     /// Returns a reference to the underlying vector.
     #[must_use]
     pub fn as_slice(&self) -> &[Value] {
@@ -90,8 +127,15 @@ impl List {
     /// manage reference counts for any heap values you add or remove.
     /// The `contains_refs` flag is NOT automatically updated by direct
     /// vector mutations. Prefer using `append()` or `insert()` instead.
+    ///
+    /// If the items are shared with a copy of the list, they're copied first.
     pub fn as_vec_mut(&mut self) -> &mut Vec<Value> {
-        &mut self.items
+        if Arc::get_mut(&mut self.items).is_none() {
+            // This list already owns a reference to each item, so copying the values
+            // without touching refcounts gives it items of its own
+            self.items = Arc::new(self.items.iter().map(Value::copy_for_extend).collect());
+        }
+        Arc::get_mut(&mut self.items).expect("items were just unshared")
     }
 
     /// Returns the number of elements in the list.
@@ -132,7 +176,7 @@ impl List {
             heap.mark_potential_cycle();
         }
         // Ownership transfer - refcount was already handled by caller
-        self.items.push(item);
+        self.as_vec_mut().push(item);
     }
 
     /// Inserts an element at the specified index.
@@ -154,10 +198,11 @@ impl List {
         }
         // Ownership transfer - refcount was already handled by caller
         // Python's insert() appends if index is out of bounds
-        if index >= self.items.len() {
-            self.items.push(item);
+        let items = self.as_vec_mut();
+        if index >= items.len() {
+            items.push(item);
         } else {
-            self.items.insert(index, item);
+            items.insert(index, item);
         }
     }
 
@@ -173,8 +218,17 @@ impl List {
                 Ok(Value::Ref(heap_id))
             }
             Some(v) => {
-                let items = MontyIter::new(v, heap, interns)?.collect(heap, interns)?;
-                let heap_id = heap.allocate(HeapData::List(Self::new(items)))?;
+                let list = if let Value::Ref(id) = &v
+                    && let HeapData::List(source) = heap.get(*id)
+                {
+                    let copy = source.share_items();
+                    copy.inc_item_refs(heap);
+                    v.drop_with_heap(heap);
+                    copy
+                } else {
+                    Self::new(MontyIter::new(v, heap, interns)?.collect(heap, interns)?)
+                };
+                let heap_id = heap.allocate(HeapData::List(list))?;
                 Ok(Value::Ref(heap_id))
             }
         }
@@ -188,15 +242,54 @@ impl List {
             .indices(self.items.len())
             .map_err(|()| ExcType::value_error_slice_step_zero())?;
 
-        let items = get_slice_items(&self.items, start, stop, step, heap)?;
-        let heap_id = heap.allocate(HeapData::List(Self::new(items)))?;
+        let list = if step == 1 && start == 0 && stop == self.items.len() {
+            // `lst[:]` is a copy, which can share the items
+            self.shallow_copy(heap)
+        } else {
+            Self::new(get_slice_items(&self.items, start, stop, step, heap)?)
+        };
+        let heap_id = heap.allocate(HeapData::List(list))?;
         Ok(Value::Ref(heap_id))
     }
+
+    /// Handles `del lst[slice]`, removing every element the slice selects.
+    fn delitem_slice(&mut self, slice: &crate::types::Slice, heap: &mut Heap<impl ResourceTracker>) -> RunResult<()> {
+        let (start, stop, step) = slice
+            .indices(self.items.len())
+            .map_err(|()| ExcType::value_error_slice_step_zero())?;
+
+        // Mark the selected indices, iterating in the same way as `get_slice_items`
+        let len = self.items.len();
+        let mut remove = vec![false; len];
+        if let Ok(step_usize) = usize::try_from(step) {
+            for i in (start..stop.min(len)).step_by(step_usize) {
+                remove[i] = true;
+            }
+        } else if start < len {
+            // start is the highest index, stop is the sentinel (stop > len means "go to the beginning")
+            let step_abs = usize::try_from(-step).expect("step is negative so -step is positive");
+            let lowest = if stop > len { 0 } else { stop + 1 };
+            for i in (lowest..=start).rev().step_by(step_abs) {
+                remove[i] = true;
+            }
+        }
+
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(self.as_vec_mut())
+            .into_iter()
+            .zip(remove)
+            .partition(|(_, remove)| *remove);
+        *self.as_vec_mut() = kept.into_iter().map(|(item, _)| item).collect();
+        for (item, _) in removed {
+            item.drop_with_heap(heap);
+        }
+        Ok(())
+    }
 }
 
 impl From<List> for Vec<Value> {
     fn from(list: List) -> Self {
-        list.items
+        // the list owns a reference to each item even if another list shares them
+        Arc::try_unwrap(list.items).unwrap_or_else(|items| items.iter().map(Value::copy_for_extend).collect())
     }
 }
 
@@ -206,7 +299,8 @@ impl PyTrait for List {
     }
 
     fn py_estimate_size(&self) -> usize {
-        std::mem::size_of::<Self>() + self.items.len() * std::mem::size_of::<Value>()
+        // Counts the reserved slots rather than the items, since that's what's allocated
+        std::mem::size_of::<Self>() + self.items.capacity() * std::mem::size_of::<Value>()
     }
 
     fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
@@ -296,8 +390,45 @@ impl PyTrait for List {
         }
 
         // Replace value (old one dropped by defer_drop_mut guard)
-        std::mem::swap(&mut self.items[idx], value);
+        std::mem::swap(&mut self.as_vec_mut()[idx], value);
+
+        Ok(())
+    }
 
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        defer_drop!(key, heap);
+
+        if let Value::Ref(id) = key
+            && let HeapData::Slice(slice) = heap.get(*id)
+        {
+            // Clone the slice to release the borrow on heap before calling delitem_slice
+            let slice = slice.clone();
+            return self.delitem_slice(&slice, heap);
+        }
+
+        // Extract integer index, accepting Int, Bool (True=1, False=0), and LongInt
+        let index = match key {
+            Value::Int(i) => *i,
+            Value::Bool(b) => i64::from(*b),
+            Value::Ref(heap_id) => {
+                if let HeapData::LongInt(li) = heap.get(*heap_id) {
+                    li.to_i64().ok_or_else(ExcType::index_error_int_too_large)?
+                } else {
+                    return Err(ExcType::type_error_list_assignment_indices(key.py_type(heap)));
+                }
+            }
+            _ => return Err(ExcType::type_error_list_assignment_indices(key.py_type(heap))),
+        };
+
+        // Normalize negative indices (Python-style: -1 = last element)
+        let len = i64::try_from(self.items.len()).expect("list length exceeds i64::MAX");
+        let normalized_index = if index < 0 { index + len } else { index };
+        if normalized_index < 0 || normalized_index >= len {
+            return Err(ExcType::list_assignment_index_error());
+        }
+
+        let idx = usize::try_from(normalized_index).expect("index validated non-negative");
+        self.as_vec_mut().remove(idx).drop_with_heap(heap);
         Ok(())
     }
 
@@ -313,7 +444,7 @@ impl PyTrait for List {
         }
         guard.increase_err()?;
 
-        for (i1, i2) in self.items.iter().zip(&other.items) {
+        for (i1, i2) in self.items.iter().zip(other.items.iter()) {
             heap.check_time()?;
             if !i1.py_eq(i2, heap, guard, interns)? {
                 guard.decrease();
@@ -329,12 +460,18 @@ impl PyTrait for List {
         if !self.contains_refs {
             return;
         }
-        for obj in &mut self.items {
-            if let Value::Ref(id) = obj {
-                stack.push(*id);
-                #[cfg(feature = "ref-count-panic")]
-                obj.dec_ref_forget();
+        // Every list sharing the items owns a reference to each of them, but only the
+        // last one can mark them as released
+        if let Some(items) = Arc::get_mut(&mut self.items) {
+            for obj in items {
+                if let Value::Ref(id) = obj {
+                    stack.push(*id);
+                    #[cfg(feature = "ref-count-panic")]
+                    obj.dec_ref_forget();
+                }
             }
+        } else {
+            stack.extend(self.items.iter().filter_map(Value::ref_id));
         }
     }
 
@@ -350,7 +487,7 @@ impl PyTrait for List {
         guard: &mut DepthGuard,
         interns: &Interns,
     ) -> std::fmt::Result {
-        repr_sequence_fmt('[', ']', &self.items, f, heap, heap_ids, guard, interns)
+        repr_sequence_fmt('[', ']', self.items.iter(), f, heap, heap_ids, guard, interns)
     }
 
     fn py_add(
@@ -388,12 +525,12 @@ impl PyTrait for List {
             if self.contains_refs {
                 heap.mark_potential_cycle();
             }
-            self.items.extend(items);
+            self.as_vec_mut().extend(items);
         } else {
             // Get items from other list using iadd_extend_from_heap helper
             // This handles the borrow checker limitations with lifetime propagation
             let prev_len = self.items.len();
-            if !heap.iadd_extend_list(*other_id, &mut self.items) {
+            if !heap.iadd_extend_list(*other_id, self.as_vec_mut()) {
                 return Ok(false);
             }
             // Check if we added any refs and mark potential cycle
@@ -433,10 +570,10 @@ impl PyTrait for List {
     }
 
     /// Intercepts `sort` to call `do_list_sort` (which needs `PrintWriter` for key functions),
-    /// and delegates all other methods to `py_call_attr`.
+    /// handles `lst.extend(lst)`, and delegates all other methods to `py_call_attr`.
     fn py_call_attr_raw(
         &mut self,
-        _self_id: HeapId,
+        self_id: HeapId,
         vm: &mut VM<'_, '_, impl ResourceTracker>,
         attr: &EitherStr,
         args: ArgValues,
@@ -445,6 +582,19 @@ impl PyTrait for List {
             do_list_sort(self, args, vm)?;
             return Ok(AttrCallResult::Value(Value::None));
         }
+        // The list's data is taken out of the heap for the duration of the call, so
+        // `lst.extend(lst)` cannot iterate its argument; duplicate the items directly.
+        if attr.static_string() == Some(StaticStrings::Extend)
+            && let ArgValues::One(Value::Ref(arg_id)) = &args
+            && *arg_id == self_id
+        {
+            args.drop_with_heap(vm.heap);
+            let items: Vec<Value> = self.items.iter().map(|item| item.clone_with_heap(vm.heap)).collect();
+            for item in items {
+                self.append(vm.heap, item);
+            }
+            return Ok(AttrCallResult::Value(Value::None));
+        }
         self.py_call_attr(vm.heap, attr, args, vm.interns)
             .map(AttrCallResult::Value)
     }
@@ -490,7 +640,7 @@ fn call_list_method(
         StaticStrings::Count => list_count(list, args, heap, interns),
         StaticStrings::Reverse => {
             args.check_zero_args("list.reverse", heap)?;
-            list.items.reverse();
+            list.as_vec_mut().reverse();
             Ok(Value::None)
         }
         // Note: list.sort is handled by py_call_attr_raw which intercepts it
@@ -560,7 +710,7 @@ fn list_pop(list: &mut List, args: ArgValues, heap: &mut Heap<impl ResourceTrack
 
     // Remove and return the item
     let idx = usize::try_from(normalized).expect("index validated non-negative");
-    Ok(list.items.remove(idx))
+    Ok(list.as_vec_mut().remove(idx))
 }
 
 /// Implements Python's `list.remove(value)` method.
@@ -589,7 +739,7 @@ fn list_remove(
     match found_idx {
         Some(idx) => {
             // Remove the element and drop its refcount
-            let removed = list.items.remove(idx);
+            let removed = list.as_vec_mut().remove(idx);
             removed.drop_with_heap(heap);
             Ok(Value::None)
         }
@@ -601,7 +751,7 @@ fn list_remove(
 ///
 /// Removes all items from the list.
 fn list_clear(list: &mut List, heap: &mut Heap<impl ResourceTracker>) {
-    for item in list.items.drain(..) {
+    for item in list.as_vec_mut().drain(..) {
         item.drop_with_heap(heap);
     }
     // Note: contains_refs stays true even if all refs removed, per conservative GC strategy
@@ -609,10 +759,10 @@ fn list_clear(list: &mut List, heap: &mut Heap<impl ResourceTracker>) {
 
 /// Implements Python's `list.copy()` method.
 ///
-/// Returns a shallow copy of the list.
+/// Returns a shallow copy of the list, sharing its items until either list is mutated.
 fn list_copy(list: &List, heap: &mut Heap<impl ResourceTracker>) -> Result<Value, ResourceError> {
-    let items: Vec<Value> = list.items.iter().map(|v| v.clone_with_heap(heap)).collect();
-    let heap_id = heap.allocate(HeapData::List(List::new(items)))?;
+    let copy = list.shallow_copy(heap);
+    let heap_id = heap.allocate(HeapData::List(copy))?;
     Ok(Value::Ref(heap_id))
 }
 
@@ -626,7 +776,7 @@ fn list_extend(
     interns: &Interns,
 ) -> RunResult<Value> {
     let iterable = args.get_one_arg("list.extend", heap)?;
-    let items: SmallVec<[_; 2]> = MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?;
+    let items = MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?;
 
     // Add each item to the list
     for item in items {
@@ -692,7 +842,7 @@ fn list_count(
 
     let mut guard = DepthGuard::default();
     let mut count: usize = 0;
-    for item in &list.items {
+    for item in list.items.iter() {
         heap.check_time()?;
         if value.py_eq(item, heap, &mut guard, interns)? {
             count += 1;
@@ -779,17 +929,17 @@ fn do_list_sort(list: &mut List, args: ArgValues, vm: &mut VM<impl ResourceTrack
 /// # Arguments
 /// * `start` - The opening character (e.g., '[' for lists, '(' for tuples)
 /// * `end` - The closing character (e.g., ']' for lists, ')' for tuples)
-/// * `items` - The slice of values to format
+/// * `items` - The values to format
 /// * `f` - The formatter to write to
 /// * `heap` - The heap for resolving value references
 /// * `heap_ids` - Set of heap IDs being repr'd (for cycle detection)
 /// * `guard` - Recursion depth tracker to prevent stack overflow on deeply nested structures
 /// * `interns` - The interned strings table for looking up string/bytes literals
 #[expect(clippy::too_many_arguments)]
-pub(crate) fn repr_sequence_fmt(
+pub(crate) fn repr_sequence_fmt<'a>(
     start: char,
     end: char,
-    items: &[Value],
+    items: impl IntoIterator<Item = &'a Value>,
     f: &mut impl Write,
     heap: &Heap<impl ResourceTracker>,
     heap_ids: &mut AHashSet<HeapId>,
@@ -802,7 +952,7 @@ pub(crate) fn repr_sequence_fmt(
     }
 
     f.write_char(start)?;
-    let mut iter = items.iter();
+    let mut iter = items.into_iter();
     if let Some(first) = iter.next() {
         first.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
         for item in iter {
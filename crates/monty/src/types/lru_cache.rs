@@ -0,0 +1,296 @@
+//! `functools.lru_cache` wrappers.
+//!
+//! The wrapper stores results keyed by the call's arguments: a tuple of the positional
+//! arguments, followed by a marker and the keyword names and values when keywords are
+//! passed (like CPython's `kwd_mark`). The VM performs the call itself (see
+//! `call_lru_cache`), storing the result once the wrapped function returns, so the wrapped
+//! callable can be any Monty callable. Every cached entry is charged to the resource
+//! tracker, and released again when it is evicted or the cache is cleared.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use crate::{
+    args::{ArgValues, KwargsValues},
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Dict, NamedTuple, PyTrait, Type, allocate_tuple, tuple::TupleVec},
+    value::{EitherStr, Marker, Value},
+};
+
+/// Memory charged for each cached entry, matching what a dict reserves per entry.
+const ENTRY_SIZE: usize = Dict::ENTRY_SIZE;
+
+/// The wrapper returned by `functools.lru_cache(maxsize)(func)` and `functools.cache(func)`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct LruCache {
+    /// The wrapped callable.
+    func: Value,
+    /// Cached results keyed by argument tuples, least recently used first.
+    cache: Dict,
+    /// The maximum number of cached results, `None` for an unbounded cache.
+    maxsize: Option<usize>,
+    /// Number of calls answered from the cache.
+    hits: usize,
+    /// Number of calls that had to call the wrapped function.
+    misses: usize,
+}
+
+impl LruCache {
+    /// Creates an empty cache wrapping `func`, taking ownership of the reference.
+    pub fn new(func: Value, maxsize: Option<usize>) -> Self {
+        Self {
+            func,
+            cache: Dict::new(),
+            maxsize,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the wrapped callable.
+    pub fn func(&self) -> &Value {
+        &self.func
+    }
+
+    /// Returns whether this wrapper holds any heap references.
+    pub fn has_refs(&self) -> bool {
+        matches!(self.func, Value::Ref(_)) || self.cache.has_refs()
+    }
+
+    /// Returns every value held by this wrapper, for GC traversal.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        std::iter::once(&self.func).chain(self.cache.iter().flat_map(|(k, v)| [k, v]))
+    }
+
+    /// Looks up the result cached for `key`, counting a hit or a miss.
+    ///
+    /// A hit marks the entry as the most recently used and returns a new reference to the
+    /// cached result.
+    pub fn lookup(
+        &mut self,
+        key: &Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<Value>> {
+        let Some((key, value)) = self.cache.pop(key, heap, interns)? else {
+            self.misses += 1;
+            return Ok(None);
+        };
+        self.hits += 1;
+        let result = value.clone_with_heap(heap);
+        // Reinsert at the end so eviction removes the least recently used entry
+        match self.cache.set(key, value, heap, interns) {
+            Ok(old) => old.drop_with_heap(heap),
+            Err(err) => {
+                result.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// Caches `value` as the result for `key`, evicting the least recently used entry
+    /// when the cache is full.
+    pub fn store(
+        &mut self,
+        key: Value,
+        value: Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<()> {
+        if self.maxsize == Some(0) {
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+            return Ok(());
+        }
+        if let Err(err) = heap.tracker_mut().on_allocate(|| ENTRY_SIZE) {
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+            return Err(err.into());
+        }
+        match self.cache.set(key, value, heap, interns) {
+            Ok(None) => {}
+            // A recursive call with the same arguments already stored a result
+            Ok(Some(old)) => {
+                old.drop_with_heap(heap);
+                heap.tracker_mut().on_free(|| ENTRY_SIZE);
+            }
+            Err(err) => {
+                heap.tracker_mut().on_free(|| ENTRY_SIZE);
+                return Err(err);
+            }
+        }
+        if let Some(maxsize) = self.maxsize
+            && self.cache.len() > maxsize
+            && let Some(evicted) = self.cache.pop_first()
+        {
+            evicted.drop_with_heap(heap);
+            heap.tracker_mut().on_free(|| ENTRY_SIZE);
+        }
+        Ok(())
+    }
+
+    /// Implements `cache_info()`, returning `CacheInfo(hits, misses, maxsize, currsize)`.
+    fn cache_info(&self, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let count = |n: usize| Value::Int(i64::try_from(n).expect("cache count fits in i64"));
+        let maxsize = self.maxsize.map_or(Value::None, count);
+        let info = NamedTuple::new(
+            StaticStrings::CacheInfoType,
+            vec![
+                StaticStrings::Hits.into(),
+                StaticStrings::Misses.into(),
+                StaticStrings::Maxsize.into(),
+                StaticStrings::Currsize.into(),
+            ],
+            vec![count(self.hits), count(self.misses), maxsize, count(self.cache.len())],
+        );
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(info))?))
+    }
+
+    /// Implements `cache_clear()`, dropping every cached result and resetting the statistics.
+    fn cache_clear(&mut self, heap: &mut Heap<impl ResourceTracker>) {
+        let cache = std::mem::take(&mut self.cache);
+        let size = cache.len() * ENTRY_SIZE;
+        cache.drop_with_heap(heap);
+        heap.tracker_mut().on_free(|| size);
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// Builds the cache key for a call with `args`.
+///
+/// Returns the key together with the arguments to call the wrapped function with. Raises
+/// `TypeError` if any argument is unhashable.
+pub(crate) fn lru_cache_key(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<(Value, ArgValues)> {
+    let (positional, kwargs) = args.into_parts();
+    let positional: Vec<Value> = positional.collect();
+    let kwargs: Vec<(Value, Value)> = kwargs.into_iter().collect();
+    let mut guard = HeapGuard::new((positional, kwargs), heap);
+    let ((positional, kwargs), heap) = guard.as_parts();
+
+    let mut items: TupleVec = positional.iter().map(|arg| arg.clone_with_heap(heap)).collect();
+    if !kwargs.is_empty() {
+        // Separates the positional arguments from the keywords, like CPython's `kwd_mark`
+        items.push(Value::Marker(Marker(StaticStrings::LruCache)));
+        for (name, value) in kwargs {
+            items.push(name.clone_with_heap(heap));
+            items.push(value.clone_with_heap(heap));
+        }
+    }
+    let unhashable = items
+        .iter()
+        .find(|item| item.py_hash(heap, interns).is_none())
+        .map(|item| item.py_type(heap));
+    if let Some(type_) = unhashable {
+        for item in items {
+            item.drop_with_heap(heap);
+        }
+        return Err(ExcType::type_error_unhashable(type_));
+    }
+    let key = allocate_tuple(items, heap)?;
+
+    let ((positional, kwargs), heap) = guard.into_parts();
+    let kwargs = if kwargs.is_empty() {
+        KwargsValues::Empty
+    } else {
+        match Dict::from_pairs(kwargs, heap, interns) {
+            Ok(dict) => KwargsValues::Dict(dict),
+            Err(err) => {
+                key.drop_with_heap(heap);
+                positional.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    };
+    Ok((key, ArgValues::from_parts(positional, kwargs)))
+}
+
+impl PyTrait for LruCache {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::LruCache
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Wrappers compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("<functools._lru_cache_wrapper object>")
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.func.py_dec_ref_ids(stack);
+        self.cache.py_dec_ref_ids(stack);
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.cache.len() * ENTRY_SIZE
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::CacheInfo) => {
+                args.check_zero_args("cache_info", heap)?;
+                self.cache_info(heap)
+            }
+            Some(StaticStrings::CacheClear) => {
+                args.check_zero_args("cache_clear", heap)?;
+                self.cache_clear(heap);
+                Ok(Value::None)
+            }
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::LruCache, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Wrapped) => Ok(Some(AttrCallResult::Value(self.func.clone_with_heap(heap)))),
+            _ => Ok(None),
+        }
+    }
+}
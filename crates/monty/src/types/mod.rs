@@ -5,38 +5,64 @@
 ///
 /// The `AbstractValue` trait provides a common interface for all heap-allocated
 /// types, enabling efficient dispatch via `enum_dispatch`.
+pub mod bytearray;
 pub mod bytes;
+pub mod context_manager;
+pub mod conversions;
+pub mod counter;
 pub mod dataclass;
+pub mod date;
+pub mod datetime;
+pub mod deque;
 pub mod dict;
+pub mod dict_view;
+pub mod external_object;
 pub mod iter;
 pub mod list;
 pub mod long_int;
+pub mod lru_cache;
 pub mod module;
 pub mod namedtuple;
+pub mod partial;
 pub mod path;
 pub mod property;
 pub mod py_trait;
 pub mod range;
+pub mod re_match;
+pub mod re_pattern;
 pub mod set;
 pub mod slice;
 pub mod str;
+pub mod timedelta;
 pub mod tuple;
 pub mod r#type;
 
+pub(crate) use bytearray::ByteArray;
 pub(crate) use bytes::Bytes;
+pub(crate) use context_manager::ContextManager;
 pub(crate) use dataclass::Dataclass;
-pub(crate) use dict::Dict;
+pub(crate) use date::Date;
+pub(crate) use datetime::DateTime;
+pub(crate) use deque::Deque;
+pub(crate) use dict::{Dict, dict_copy, py_dict_inplace_merge, py_dict_merge};
+pub(crate) use dict_view::{DictView, DictViewKind, py_dict_view_set_op};
+pub(crate) use external_object::ExternalObject;
 pub(crate) use iter::MontyIter;
 pub(crate) use list::List;
 pub(crate) use long_int::LongInt;
+pub(crate) use lru_cache::LruCache;
 pub(crate) use module::Module;
-pub(crate) use namedtuple::NamedTuple;
+pub(crate) use namedtuple::{NamedTuple, NamedTupleType};
+pub(crate) use partial::Partial;
 pub(crate) use path::Path;
 pub(crate) use property::Property;
 pub(crate) use py_trait::{AttrCallResult, PyTrait};
 pub(crate) use range::Range;
-pub(crate) use set::{FrozenSet, Set};
+pub(crate) use re_match::ReMatch;
+pub(crate) use re_pattern::RePattern;
+pub(crate) use set::{FrozenSet, Set, SetBinaryOp, py_set_binary_op, py_set_inplace_op};
 pub(crate) use slice::Slice;
 pub(crate) use str::Str;
+pub(crate) use timedelta::{TemporalOp, TimeDelta, py_temporal_op};
 pub(crate) use tuple::{Tuple, allocate_tuple};
-pub(crate) use r#type::Type;
+pub(crate) use r#type::{BUILTIN_TYPES, Type};
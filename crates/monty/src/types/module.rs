@@ -122,7 +122,8 @@ impl Module {
     /// Calls an attribute as a function on this module.
     ///
     /// Modules don't have methods - they have callable attributes. This looks up
-    /// the attribute and calls it if it's a `ModuleFunction`.
+    /// the attribute and calls it if it's a `ModuleFunction`, or yields an external call
+    /// for the functions of native modules.
     ///
     /// Returns `AttrCallResult` because module functions may need OS operations
     /// (e.g., `os.getenv()`) that require host involvement.
@@ -147,8 +148,12 @@ impl Module {
 
         match self.get_attr(&attr_key, args_guard.heap(), interns) {
             Some(Value::ModuleFunction(mf)) => {
-                let (args, heap) = args_guard.into_parts();
-                mf.call(heap, args)
+                let args = args_guard.into_inner();
+                mf.call(vm, args)
+            }
+            Some(Value::ExtFunction(ext_id)) => {
+                // Functions of host-provided native modules
+                Ok(AttrCallResult::ExternalCall(ext_id, args_guard.into_inner()))
             }
             Some(func) => {
                 // Found attribute but it's not callable
@@ -21,11 +21,13 @@ use ahash::AHashSet;
 
 use super::PyTrait;
 use crate::{
+    args::ArgValues,
+    defer_drop, defer_drop_mut,
     exception_private::{ExcType, RunResult},
-    heap::{Heap, HeapId},
-    intern::{Interns, StringId},
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings, StringId},
     resource::{DepthGuard, ResourceError, ResourceTracker},
-    types::{AttrCallResult, Type},
+    types::{AttrCallResult, Dict, MontyIter, Type, allocate_tuple, str::allocate_string},
     value::{EitherStr, Value},
 };
 
@@ -149,6 +151,48 @@ impl NamedTuple {
     }
 }
 
+impl NamedTuple {
+    /// Implements `_replace(**kwargs)`: a new named tuple with the given fields replaced.
+    fn replace(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let (pos, kwargs) = args.into_parts();
+        defer_drop!(pos, heap);
+        let kwargs = kwargs.into_iter();
+        defer_drop_mut!(kwargs, heap);
+        if pos.len() > 0 {
+            return Err(ExcType::type_error_no_args("_replace", pos.len()));
+        }
+
+        let items: Vec<Value> = self.items.iter().map(|item| item.clone_with_heap(heap)).collect();
+        let mut items_guard = HeapGuard::new(items, heap);
+        let (items, heap) = items_guard.as_parts_mut();
+        let mut unexpected = Vec::new();
+        for (key, value) in kwargs {
+            defer_drop!(key, heap);
+            let mut value = HeapGuard::new(value, heap);
+            let Some(keyword_name) = key.as_either_str(value.heap()) else {
+                return Err(ExcType::type_error("keywords must be strings"));
+            };
+            let key_str = keyword_name.as_str(interns);
+            if let Some(index) = self.field_names.iter().position(|name| name.as_str(interns) == key_str) {
+                let old = std::mem::replace(&mut items[index], value.into_inner());
+                old.drop_with_heap(heap);
+            } else {
+                unexpected.push(format!("'{key_str}'"));
+            }
+        }
+        if !unexpected.is_empty() {
+            return Err(ExcType::type_error(format!(
+                "Got unexpected field names: [{}]",
+                unexpected.join(", ")
+            )));
+        }
+
+        let (items, heap) = items_guard.into_parts();
+        let named_tuple = Self::new(self.name.clone(), self.field_names.clone(), items);
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(named_tuple))?))
+    }
+}
+
 impl PyTrait for NamedTuple {
     fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
         Type::NamedTuple
@@ -256,6 +300,33 @@ impl PyTrait for NamedTuple {
         Ok(())
     }
 
+    /// Implements `_asdict()` and `_replace(**kwargs)`.
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::NamedTupleAsdict) => {
+                args.check_zero_args("_asdict", heap)?;
+                let mut pairs = Vec::with_capacity(self.items.len());
+                for (field_name, value) in self.field_names.iter().zip(&self.items) {
+                    let key = field_name_value(field_name, heap)?;
+                    pairs.push((key, value.clone_with_heap(heap)));
+                }
+                let dict = Dict::from_pairs(pairs, heap, interns)?;
+                Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+            }
+            Some(StaticStrings::NamedTupleReplace) => self.replace(args, heap, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(self.name(interns), attr.as_str(interns)))
+            }
+        }
+    }
+
     fn py_getattr(
         &self,
         attr_id: StringId,
@@ -264,9 +335,245 @@ impl PyTrait for NamedTuple {
     ) -> RunResult<Option<AttrCallResult>> {
         if let Some(value) = self.get_by_name(attr_id, interns) {
             Ok(Some(AttrCallResult::Value(value.clone_with_heap(heap))))
+        } else if attr_id == StaticStrings::NamedTupleFields {
+            Ok(Some(AttrCallResult::Value(fields_tuple(&self.field_names, heap)?)))
         } else {
             // we use name here, not `self.py_type(heap)` hence returning a Ok(None)
             Err(ExcType::attribute_error(self.name(interns), interns.get_str(attr_id)))
         }
     }
 }
+
+/// A named tuple class created by `collections.namedtuple()`.
+///
+/// Calling it creates a [`NamedTuple`] instance, binding the arguments to the field names
+/// by position or keyword, with `defaults` filling the rightmost fields (see
+/// `call_heap_callable`). Instances are not linked back to their class, so `type()` and
+/// `isinstance()` see them as plain named tuples.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct NamedTupleType {
+    /// The class name, also used by the repr of instances.
+    name: EitherStr,
+    /// Field names in order.
+    field_names: Vec<EitherStr>,
+    /// Default values for the last `defaults.len()` fields.
+    defaults: Vec<Value>,
+}
+
+impl NamedTupleType {
+    /// Creates a named tuple class, taking ownership of `defaults`.
+    ///
+    /// `defaults` must not be longer than `field_names`.
+    #[must_use]
+    pub fn new(name: EitherStr, field_names: Vec<EitherStr>, defaults: Vec<Value>) -> Self {
+        debug_assert!(defaults.len() <= field_names.len());
+        Self {
+            name,
+            field_names,
+            defaults,
+        }
+    }
+
+    /// Returns whether any default value is a heap reference.
+    pub fn has_refs(&self) -> bool {
+        self.defaults.iter().any(|v| matches!(v, Value::Ref(_)))
+    }
+
+    /// Returns the default values, for GC traversal.
+    pub fn defaults(&self) -> &[Value] {
+        &self.defaults
+    }
+
+    /// Creates an instance from the arguments of a call of the class.
+    pub fn instantiate(
+        &self,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let name = format!("{}.__new__", self.name.as_str(interns));
+        let field_count = self.field_names.len();
+        let (pos, kwargs) = args.into_parts();
+        let pos_count = pos.len();
+        if pos_count > field_count {
+            pos.drop_with_heap(heap);
+            kwargs.drop_with_heap(heap);
+            // CPython counts `cls` in both numbers
+            return Err(ExcType::type_error_too_many_positional(
+                &name,
+                field_count + 1,
+                pos_count + 1,
+                0,
+            ));
+        }
+
+        let kwargs = kwargs.into_iter();
+        defer_drop_mut!(kwargs, heap);
+        let mut slots_guard = HeapGuard::new(Vec::with_capacity(field_count), heap);
+        let (slots, heap) = slots_guard.as_parts_mut();
+        slots.extend(pos.map(Some));
+        slots.resize_with(field_count, || None);
+
+        for (key, value) in kwargs {
+            defer_drop!(key, heap);
+            let mut value = HeapGuard::new(value, heap);
+            let Some(keyword_name) = key.as_either_str(value.heap()) else {
+                return Err(ExcType::type_error("keywords must be strings"));
+            };
+            let key_str = keyword_name.as_str(interns);
+            let Some(index) = self
+                .field_names
+                .iter()
+                .position(|field| field.as_str(interns) == key_str)
+            else {
+                return Err(ExcType::type_error_unexpected_keyword(&name, key_str));
+            };
+            if slots[index].is_some() {
+                return Err(ExcType::type_error_duplicate_arg(&name, key_str));
+            }
+            slots[index] = Some(value.into_inner());
+        }
+
+        // Fill the rightmost missing fields from the defaults
+        let first_default = field_count - self.defaults.len();
+        for (slot, default) in slots[first_default..].iter_mut().zip(&self.defaults) {
+            if slot.is_none() {
+                *slot = Some(default.clone_with_heap(heap));
+            }
+        }
+        let missing: Vec<&str> = self
+            .field_names
+            .iter()
+            .zip(slots.iter())
+            .filter(|(_, slot)| slot.is_none())
+            .map(|(field, _)| field.as_str(interns))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ExcType::type_error_missing_positional_with_names(&name, &missing));
+        }
+
+        let (slots, heap) = slots_guard.into_parts();
+        let items = slots.into_iter().map(|slot| slot.expect("all slots filled")).collect();
+        let named_tuple = NamedTuple::new(self.name.clone(), self.field_names.clone(), items);
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(named_tuple))?))
+    }
+
+    /// Implements the `_make(iterable)` classmethod: an instance from the items of `iterable`.
+    fn make(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let iterable = args.get_one_arg("_make", heap)?;
+        let items: Vec<Value> = MontyIter::new(iterable, heap, interns)?.collect(heap, interns)?;
+        if items.len() != self.field_names.len() {
+            let err = ExcType::type_error(format!(
+                "Expected {} arguments, got {}",
+                self.field_names.len(),
+                items.len()
+            ));
+            items.drop_with_heap(heap);
+            return Err(err);
+        }
+        let named_tuple = NamedTuple::new(self.name.clone(), self.field_names.clone(), items);
+        Ok(Value::Ref(heap.allocate(HeapData::NamedTuple(named_tuple))?))
+    }
+}
+
+impl PyTrait for NamedTupleType {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Type
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Classes compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        write!(f, "<class '{}'>", self.name.as_str(interns))
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        for default in &mut self.defaults {
+            default.py_dec_ref_ids(stack);
+        }
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.py_estimate_size()
+            + self.field_names.iter().map(EitherStr::py_estimate_size).sum::<usize>()
+            + self.defaults.len() * std::mem::size_of::<Value>()
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        if attr.static_string() == Some(StaticStrings::NamedTupleMake) {
+            self.make(args, heap, interns)
+        } else {
+            args.drop_with_heap(heap);
+            Err(ExcType::attribute_error(Type::Type, attr.as_str(interns)))
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        if attr_id == StaticStrings::NamedTupleFields {
+            Ok(Some(AttrCallResult::Value(fields_tuple(&self.field_names, heap)?)))
+        } else if attr_id == StaticStrings::DunderName {
+            Ok(Some(AttrCallResult::Value(field_name_value(&self.name, heap)?)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl DropWithHeap for NamedTupleType {
+    fn drop_with_heap<T: ResourceTracker>(self, heap: &mut Heap<T>) {
+        self.defaults.drop_with_heap(heap);
+    }
+}
+
+/// Returns the `_fields` tuple of field name strings.
+fn fields_tuple(field_names: &[EitherStr], heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let names = field_names
+        .iter()
+        .map(|field_name| field_name_value(field_name, heap))
+        .collect::<RunResult<_>>()?;
+    Ok(allocate_tuple(names, heap)?)
+}
+
+/// Converts a field name to a `str` value.
+fn field_name_value(name: &EitherStr, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    match name {
+        EitherStr::Interned(id) => Ok(Value::InternString(*id)),
+        EitherStr::Heap(s) => allocate_string(s.clone(), heap),
+    }
+}
@@ -0,0 +1,205 @@
+//! `functools.partial` objects.
+//!
+//! A partial stores a callable together with leading positional arguments and default
+//! keyword arguments. Calling it calls the stored callable with the stored positional
+//! arguments followed by the call's, and the stored keywords updated with the call's.
+//! The VM performs the call itself (see `call_heap_callable`), so the wrapped callable
+//! can be any Monty callable, including user-defined functions that push frames.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+
+use crate::{
+    args::{ArgValues, KwargsValues},
+    exception_private::RunResult,
+    heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Dict, PyTrait, Type, allocate_tuple},
+    value::Value,
+};
+
+/// A `functools.partial(func, *args, **keywords)` object.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Partial {
+    /// The wrapped callable.
+    func: Value,
+    /// Positional arguments passed before the call's own positional arguments.
+    args: Vec<Value>,
+    /// Keyword arguments, overridden by keywords of the same name passed to the call.
+    keywords: Dict,
+}
+
+impl Partial {
+    /// Creates a partial, taking ownership of `func`, `args` and `keywords`.
+    pub fn new(func: Value, args: Vec<Value>, keywords: Dict) -> Self {
+        Self { func, args, keywords }
+    }
+
+    /// Returns whether this partial holds any heap references.
+    pub fn has_refs(&self) -> bool {
+        matches!(self.func, Value::Ref(_))
+            || self.args.iter().any(|v| matches!(v, Value::Ref(_)))
+            || self.keywords.has_refs()
+    }
+
+    /// Returns every value held by this partial, for GC traversal.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        std::iter::once(&self.func)
+            .chain(&self.args)
+            .chain(self.keywords.iter().flat_map(|(k, v)| [k, v]))
+    }
+}
+
+/// Prepares a call of the partial stored at `partial_id` with `args`.
+///
+/// Returns a new reference to the wrapped callable and the merged arguments: the stored
+/// positional arguments followed by `args`' positional arguments, and the stored keywords
+/// updated with `args`' keywords.
+pub(crate) fn bind_partial_args(
+    partial_id: HeapId,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<(Value, ArgValues)> {
+    // Copy the stored values first, then take the references once the heap borrow ends
+    let HeapData::Partial(partial) = heap.get(partial_id) else {
+        unreachable!("bind_partial_args called on a non-partial")
+    };
+    let func = partial.func.copy_for_extend();
+    let stored_args: Vec<Value> = partial.args.iter().map(Value::copy_for_extend).collect();
+    let stored_keywords: Vec<(Value, Value)> = partial
+        .keywords
+        .iter()
+        .map(|(k, v)| (k.copy_for_extend(), v.copy_for_extend()))
+        .collect();
+    let copied = std::iter::once(&func)
+        .chain(&stored_args)
+        .chain(stored_keywords.iter().flat_map(|(k, v)| [k, v]));
+    for value in copied {
+        if let Value::Ref(id) = value {
+            heap.inc_ref(*id);
+        }
+    }
+    let (pos, kwargs) = args.into_parts();
+    let mut positional = stored_args;
+    positional.extend(pos);
+    let mut guard = HeapGuard::new((func, positional), heap);
+    let heap = guard.heap();
+
+    let kwargs = if stored_keywords.is_empty() {
+        kwargs
+    } else {
+        let keywords = match Dict::from_pairs(stored_keywords, heap, interns) {
+            Ok(keywords) => keywords,
+            Err(err) => {
+                kwargs.drop_with_heap(heap);
+                return Err(err);
+            }
+        };
+        let mut keywords_guard = HeapGuard::new(keywords, heap);
+        let (keywords, heap) = keywords_guard.as_parts_mut();
+        let mut kwargs = kwargs.into_iter();
+        while let Some((key, value)) = kwargs.next() {
+            match keywords.set(key, value, heap, interns) {
+                Ok(old) => old.drop_with_heap(heap),
+                Err(err) => {
+                    kwargs.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
+        }
+        KwargsValues::Dict(keywords_guard.into_inner())
+    };
+
+    let (func, positional) = guard.into_inner();
+    let args = ArgValues::from_parts(positional, kwargs);
+    Ok((func, args))
+}
+
+impl PyTrait for Partial {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::Partial
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Partials compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        heap_ids: &mut AHashSet<HeapId>,
+        guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        f.write_str("functools.partial(")?;
+        self.func.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        for arg in &self.args {
+            f.write_str(", ")?;
+            arg.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        for (key, value) in &self.keywords {
+            f.write_str(", ")?;
+            f.write_str(&key.py_str(heap, guard, interns))?;
+            f.write_char('=')?;
+            value.py_repr_fmt(f, heap, heap_ids, guard, interns)?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.func.py_dec_ref_ids(stack);
+        for arg in &mut self.args {
+            arg.py_dec_ref_ids(stack);
+        }
+        self.keywords.py_dec_ref_ids(stack);
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.args.len() * std::mem::size_of::<Value>() + self.keywords.py_estimate_size()
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Func) => self.func.clone_with_heap(heap),
+            Some(StaticStrings::Args) => {
+                let args = self.args.iter().map(|arg| arg.clone_with_heap(heap)).collect();
+                allocate_tuple(args, heap)?
+            }
+            Some(StaticStrings::Keywords) => {
+                let pairs = self
+                    .keywords
+                    .iter()
+                    .map(|(k, v)| (k.clone_with_heap(heap), v.clone_with_heap(heap)))
+                    .collect();
+                let keywords = Dict::from_pairs(pairs, heap, interns)?;
+                Value::Ref(heap.allocate(HeapData::Dict(keywords))?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
@@ -17,7 +17,7 @@ use crate::{
     args::ArgValues,
     bytecode::VM,
     exception_private::{ExcType, RunResult, SimpleException},
-    heap::{Heap, HeapId},
+    heap::{DropWithHeap, Heap, HeapId},
     intern::{ExtFunctionId, Interns, StringId},
     os::OsFunction,
     resource::{DepthGuard, ResourceTracker},
@@ -54,9 +54,7 @@ pub enum AttrCallResult {
 
     /// The method needs to call an external function. VM should yield `FrameExit::ExternalCall`.
     ///
-    /// Used when attribute methods delegate to registered external functions.
-    /// Currently unused - will be used when types need to call external functions from attribute methods.
-    #[expect(dead_code)]
+    /// Used when calling a function of a host-provided native module as a module attribute.
     ExternalCall(ExtFunctionId, ArgValues),
 
     /// Dataclass method call — VM should yield `FrameExit::MethodCall` to host.
@@ -363,7 +361,8 @@ pub trait PyTrait {
     ///
     /// Used by resource tracking to enforce memory limits. Returns the approximate
     /// heap footprint including struct overhead and variable-length data (e.g., string
-    /// contents, list elements).
+    /// contents, list elements). Containers count the capacity they have reserved, not
+    /// just their length.
     ///
     /// Note: For containers holding `Value::Ref` entries, this counts the size of
     /// the reference slots, not the referenced objects. Nested objects are sized
@@ -405,6 +404,17 @@ pub trait PyTrait {
         .into())
     }
 
+    /// Python subscript delete operation (`__delitem__`), e.g., `del d[key]`.
+    ///
+    /// Removes the item associated with the key, or returns an error if the key is invalid
+    /// or the type doesn't support item deletion.
+    ///
+    /// Default implementation returns TypeError.
+    fn py_delitem(&mut self, key: Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<()> {
+        key.drop_with_heap(heap);
+        Err(ExcType::type_error_item_deletion(self.py_type(heap)))
+    }
+
     /// Python attribute get operation (`__getattr__`), e.g., `obj.attr`.
     ///
     /// Returns the value associated with the attribute (owned), or `Ok(None)` if the type
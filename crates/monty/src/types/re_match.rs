@@ -0,0 +1,342 @@
+//! Match objects returned by the `re` module (`re.Match`).
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+use regex::Regex;
+use smallvec::smallvec;
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Dict, PyTrait, Type, allocate_tuple,
+        re_pattern::{GroupSpans, expand_template, parse_template, subject_str},
+        str::{allocate_string, string_repr_fmt},
+    },
+    value::{EitherStr, Value},
+};
+
+/// The result of a successful `search()`, `match()` or `fullmatch()`.
+///
+/// Holds the subject string and the pattern so that group text and names can be looked up
+/// later, plus the span of every group both in bytes (for slicing) and in characters (for
+/// `start()`, `end()` and `span()`).
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ReMatch {
+    /// The subject string, `Match.string`.
+    string: Value,
+    /// The `re.Pattern` that produced the match, `Match.re`.
+    pattern: Value,
+    /// Byte offsets of each group in `string`.
+    spans: GroupSpans,
+    /// Character offsets of each group in `string`.
+    char_spans: GroupSpans,
+    /// `Match.pos`, the start of the searched window in characters.
+    pos: usize,
+    /// `Match.endpos`, the end of the searched window in characters.
+    endpos: usize,
+}
+
+impl ReMatch {
+    /// Creates a match, taking ownership of the references to `string` and `pattern`.
+    pub fn new(
+        string: Value,
+        pattern: Value,
+        spans: GroupSpans,
+        char_spans: GroupSpans,
+        pos: usize,
+        endpos: usize,
+    ) -> Self {
+        Self {
+            string,
+            pattern,
+            spans,
+            char_spans,
+            pos,
+            endpos,
+        }
+    }
+
+    /// Returns the values held by this match, for reference tracking.
+    pub fn values(&self) -> [&Value; 2] {
+        [&self.string, &self.pattern]
+    }
+
+    /// Returns the compiled regex of the pattern that produced this match.
+    fn regex(&self, heap: &Heap<impl ResourceTracker>) -> Regex {
+        match &self.pattern {
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::RePattern(pattern) => pattern.regex().clone(),
+                _ => unreachable!("re.Match always refers to a re.Pattern"),
+            },
+            _ => unreachable!("re.Match always refers to a re.Pattern"),
+        }
+    }
+
+    /// Resolves a group reference, an index or a group name, to a group index.
+    ///
+    /// Raises `IndexError` for anything else, including `bool`, like CPython.
+    fn group_index(&self, group: &Value, heap: &Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<usize> {
+        let index = if let Value::Int(index) = group {
+            usize::try_from(*index).ok()
+        } else if let Some(name) = group.as_str_ref(heap, interns) {
+            self.regex(heap).capture_names().position(|n| n == Some(name))
+        } else {
+            None
+        };
+        index
+            .filter(|&index| index < self.spans.len())
+            .ok_or_else(ExcType::index_error_no_such_group)
+    }
+
+    /// Returns the text matched by `group`, or `default` if the group did not participate.
+    fn group_value(
+        &self,
+        group: usize,
+        default: &Value,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let Some((start, end)) = self.spans[group] else {
+            return Ok(default.clone_with_heap(heap));
+        };
+        let text = subject_str(&self.string, heap, interns)?[start..end].to_owned();
+        allocate_string(text, heap)
+    }
+
+    /// Returns the character span of `group` as a tuple, `(-1, -1)` if it did not participate.
+    fn span_value(&self, group: usize, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+        let (start, end) = self.char_span(group);
+        Ok(allocate_tuple(smallvec![Value::Int(start), Value::Int(end)], heap)?)
+    }
+
+    /// Returns the character span of `group`, `(-1, -1)` if it did not participate.
+    fn char_span(&self, group: usize) -> (i64, i64) {
+        self.char_spans[group].map_or((-1, -1), |(start, end)| {
+            (
+                i64::try_from(start).expect("offset fits in i64"),
+                i64::try_from(end).expect("offset fits in i64"),
+            )
+        })
+    }
+
+    /// Parses the optional group argument of `start()`, `end()` and `span()`.
+    fn optional_group(
+        &self,
+        name: &str,
+        args: ArgValues,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<usize> {
+        let Some(group) = args.get_zero_one_arg(name, heap)? else {
+            return Ok(0);
+        };
+        defer_drop!(group, heap);
+        self.group_index(group, heap, interns)
+    }
+
+    /// Implements `group(*groups)`: one group's text, or a tuple for several groups.
+    fn group(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let groups = args.into_pos_only("group", heap)?;
+        defer_drop!(groups, heap);
+        match groups.as_slice() {
+            [] => self.group_value(0, &Value::None, heap, interns),
+            [group] => {
+                let index = self.group_index(group, heap, interns)?;
+                self.group_value(index, &Value::None, heap, interns)
+            }
+            groups => {
+                let mut items = Vec::with_capacity(groups.len());
+                for group in groups {
+                    let item = self
+                        .group_index(group, heap, interns)
+                        .and_then(|index| self.group_value(index, &Value::None, heap, interns));
+                    match item {
+                        Ok(item) => items.push(item),
+                        Err(err) => {
+                            items.drop_with_heap(heap);
+                            return Err(err);
+                        }
+                    }
+                }
+                Ok(allocate_tuple(items.into(), heap)?)
+            }
+        }
+    }
+
+    /// Implements `groups(default=None)`: a tuple of the text of every group.
+    fn groups(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let [default] = args.bind("groups", ["default"], 0, heap, interns)?;
+        let default = default.unwrap_or(Value::None);
+        defer_drop!(default, heap);
+        let mut items = Vec::with_capacity(self.spans.len() - 1);
+        for group in 1..self.spans.len() {
+            match self.group_value(group, default, heap, interns) {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(allocate_tuple(items.into(), heap)?)
+    }
+
+    /// Implements `groupdict(default=None)`: a dict from each group name to its text.
+    fn groupdict(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let [default] = args.bind("groupdict", ["default"], 0, heap, interns)?;
+        let default = default.unwrap_or(Value::None);
+        defer_drop!(default, heap);
+        let regex = self.regex(heap);
+        let mut dict = Dict::new();
+        for (group, name) in regex.capture_names().enumerate() {
+            let Some(name) = name else { continue };
+            let entry = allocate_string(name.to_owned(), heap).and_then(|key| {
+                match self.group_value(group, default, heap, interns) {
+                    Ok(value) => dict.set(key, value, heap, interns),
+                    Err(err) => {
+                        key.drop_with_heap(heap);
+                        Err(err)
+                    }
+                }
+            });
+            match entry {
+                Ok(old) => old.drop_with_heap(heap),
+                Err(err) => {
+                    dict.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::Dict(dict))?))
+    }
+
+    /// Implements `expand(template)`: the template with group references substituted, as
+    /// `sub()` would produce for this match.
+    fn expand(&self, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let template = args.get_one_arg("expand", heap)?;
+        defer_drop!(template, heap);
+        let Some(template) = template.as_str_ref(heap, interns) else {
+            return Err(ExcType::type_error_re_subject(
+                template.py_type(heap),
+                template.as_bytes_like(heap, interns).is_some(),
+            ));
+        };
+        let parts = parse_template(template, &self.regex(heap))?;
+        let mut result = String::new();
+        expand_template(
+            &parts,
+            subject_str(&self.string, heap, interns)?,
+            &self.spans,
+            &mut result,
+        );
+        allocate_string(result, heap)
+    }
+}
+
+impl PyTrait for ReMatch {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::ReMatch
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        _other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        // Match objects compare by identity, which is handled at the Value level
+        Ok(false)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        interns: &Interns,
+    ) -> std::fmt::Result {
+        let (start, end) = self.char_span(0);
+        write!(f, "<re.Match object; span=({start}, {end}), match=")?;
+        let (start, end) = self.spans[0].expect("group 0 always participates");
+        let text = self.string.as_str_ref(heap, interns).unwrap_or_default();
+        string_repr_fmt(&text[start..end], f)?;
+        f.write_char('>')
+    }
+
+    fn py_dec_ref_ids(&mut self, stack: &mut Vec<HeapId>) {
+        self.string.py_dec_ref_ids(stack);
+        self.pattern.py_dec_ref_ids(stack);
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + 2 * self.spans.len() * std::mem::size_of::<Option<(usize, usize)>>()
+    }
+
+    fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let group = self.group_index(key, heap, interns)?;
+        self.group_value(group, &Value::None, heap, interns)
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        match attr.static_string() {
+            Some(StaticStrings::Group) => self.group(args, heap, interns),
+            Some(StaticStrings::Groups) => self.groups(args, heap, interns),
+            Some(StaticStrings::Groupdict) => self.groupdict(args, heap, interns),
+            Some(StaticStrings::Start) => {
+                let group = self.optional_group("start", args, heap, interns)?;
+                Ok(Value::Int(self.char_span(group).0))
+            }
+            Some(StaticStrings::End) => {
+                let group = self.optional_group("end", args, heap, interns)?;
+                Ok(Value::Int(self.char_span(group).1))
+            }
+            Some(StaticStrings::Span) => {
+                let group = self.optional_group("span", args, heap, interns)?;
+                self.span_value(group, heap)
+            }
+            Some(StaticStrings::Expand) => self.expand(args, heap, interns),
+            _ => {
+                args.drop_with_heap(heap);
+                Err(ExcType::attribute_error(Type::ReMatch, attr.as_str(interns)))
+            }
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        _interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::StringAttr) => self.string.clone_with_heap(heap),
+            Some(StaticStrings::Re) => self.pattern.clone_with_heap(heap),
+            Some(StaticStrings::Pos) => Value::Int(i64::try_from(self.pos).expect("offset fits in i64")),
+            Some(StaticStrings::Endpos) => Value::Int(i64::try_from(self.endpos).expect("offset fits in i64")),
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
@@ -0,0 +1,1244 @@
+//! Compiled regular expressions for the `re` module (`re.Pattern`).
+//!
+//! Patterns are compiled with the Rust `regex` crate after a light translation of Python's
+//! syntax (`\Z`, octal escapes, `(?#...)` comments, literal braces, character class quirks
+//! and the `ASCII` flag). The crate matches in time linear in the input, so there is no
+//! catastrophic backtracking; in exchange look-around assertions and backreferences are not
+//! supported and raise `re.error`. Other differences from CPython:
+//! - `$` without `MULTILINE` only matches at the very end, not before a trailing newline.
+//! - After an empty match the next match starts at least one character later, so
+//!   `re.findall('|a', 'a')` returns `['', '']` rather than `['', 'a', '']`.
+//! - Only `str` patterns and subjects are supported.
+//!
+//! Resource limits apply to regular expressions like any other operation: compiled automata
+//! are capped at `REGEX_SIZE_LIMIT` bytes, matching loops check the time limit after every
+//! match, and results that grow past `LARGE_RESULT_THRESHOLD` are checked against the memory
+//! limit before they are built.
+
+use std::fmt::Write;
+
+use ahash::AHashSet;
+use regex::{CaptureLocations, Regex, RegexBuilder};
+
+use crate::{
+    args::ArgValues,
+    bytecode::VM,
+    defer_drop,
+    exception_private::{ExcType, RunResult, SimpleException},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, LARGE_RESULT_THRESHOLD, ResourceError, ResourceTracker},
+    types::{
+        AttrCallResult, Dict, List, PyTrait, ReMatch, Type, allocate_tuple, iter::get_iter, str::allocate_string,
+        str::string_repr_fmt,
+    },
+    value::{EitherStr, Value},
+};
+
+/// `re.IGNORECASE`: case-insensitive matching.
+pub(crate) const IGNORECASE: i64 = 2;
+/// `re.LOCALE`: locale dependent matching, which CPython only allows for bytes patterns.
+const LOCALE: i64 = 4;
+/// `re.MULTILINE`: `^` and `$` also match at line boundaries.
+pub(crate) const MULTILINE: i64 = 8;
+/// `re.DOTALL`: `.` also matches a newline.
+pub(crate) const DOTALL: i64 = 16;
+/// `re.UNICODE`: Unicode matching, the default for `str` patterns.
+pub(crate) const UNICODE: i64 = 32;
+/// `re.VERBOSE`: whitespace and `#` comments in the pattern are ignored.
+pub(crate) const VERBOSE: i64 = 64;
+/// `re.ASCII`: `\w`, `\d`, `\s` and `\b` only consider ASCII characters.
+pub(crate) const ASCII: i64 = 256;
+
+/// Flags shown in a pattern's repr, in CPython's order.
+const FLAG_NAMES: [(i64, &str); 5] = [
+    (IGNORECASE, "re.IGNORECASE"),
+    (MULTILINE, "re.MULTILINE"),
+    (DOTALL, "re.DOTALL"),
+    (VERBOSE, "re.VERBOSE"),
+    (ASCII, "re.ASCII"),
+];
+
+/// Upper bound in bytes on a compiled regex and on its lazy DFA cache.
+///
+/// Patterns whose compiled form would be larger raise `OverflowError`, like patterns beyond
+/// CPython's code size limit.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Byte offsets of each group of a match, `None` for groups that did not participate.
+pub(crate) type GroupSpans = Vec<Option<(usize, usize)>>;
+
+/// How a single match attempt is anchored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchMode {
+    /// `search()`: the match may start anywhere in the window.
+    Search,
+    /// `match()`: the match must start at the beginning of the window.
+    Match,
+    /// `fullmatch()`: the match must cover the whole window.
+    Full,
+}
+
+/// A compiled regular expression, the result of `re.compile()`.
+///
+/// Patterns are immutable. The compiled `Regex` is reference counted internally, so cloning a
+/// pattern is cheap; snapshots store only the source and flags and recompile on load.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(into = "PatternSource", try_from = "PatternSource")]
+pub(crate) struct RePattern {
+    /// The pattern as written by the user.
+    pattern: String,
+    /// The effective flags, including inline flags and the implied `UNICODE` flag.
+    flags: i64,
+    /// The translated pattern, used for `search()`, `match()` and iteration.
+    regex: Regex,
+    /// The translated pattern anchored to the end of the input, used for `fullmatch()`.
+    full_regex: Regex,
+}
+
+/// Serialized form of a `RePattern`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PatternSource {
+    pattern: String,
+    flags: i64,
+}
+
+impl From<RePattern> for PatternSource {
+    fn from(pattern: RePattern) -> Self {
+        Self {
+            pattern: pattern.pattern,
+            flags: pattern.flags,
+        }
+    }
+}
+
+impl TryFrom<PatternSource> for RePattern {
+    type Error = String;
+
+    fn try_from(source: PatternSource) -> Result<Self, Self::Error> {
+        Self::new(source.pattern, source.flags).map_err(|_| "invalid regular expression in snapshot".to_owned())
+    }
+}
+
+impl RePattern {
+    /// Compiles `pattern` with the given `re` flags.
+    ///
+    /// Raises `re.error` for invalid or unsupported syntax, `ValueError` for incompatible
+    /// flags and `OverflowError` for patterns exceeding `REGEX_SIZE_LIMIT`.
+    pub fn new(pattern: String, flags: i64) -> RunResult<Self> {
+        let (inline_flags, body) = split_inline_flags(&pattern);
+        let flags = flags | inline_flags;
+        if flags & LOCALE != 0 {
+            return Err(
+                SimpleException::new_msg(ExcType::ValueError, "cannot use LOCALE flag with a str pattern").into(),
+            );
+        }
+        let ascii = flags & ASCII != 0;
+        if ascii && flags & UNICODE != 0 {
+            return Err(
+                SimpleException::new_msg(ExcType::ValueError, "ASCII and UNICODE flags are incompatible").into(),
+            );
+        }
+        let flags = if ascii { flags } else { flags | UNICODE };
+
+        let verbose = flags & VERBOSE != 0;
+        let translated = translate(body, ascii, verbose);
+        let regex = build_regex(&translated, flags)?;
+        // In verbose mode the pattern may end inside a `#` comment, which the newline closes
+        let anchored = if verbose {
+            format!("(?:{translated}\n)\\z")
+        } else {
+            format!("(?:{translated})\\z")
+        };
+        let full_regex = build_regex(&anchored, flags)?;
+        Ok(Self {
+            pattern,
+            flags,
+            regex,
+            full_regex,
+        })
+    }
+
+    /// Returns the pattern source as written by the user.
+    #[must_use]
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the effective flags, `Pattern.flags`.
+    #[must_use]
+    pub fn flags(&self) -> i64 {
+        self.flags
+    }
+
+    /// Returns the compiled regex, used by `re.Match` to resolve group names.
+    #[must_use]
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Returns the number of capturing groups, `Pattern.groups`.
+    #[must_use]
+    pub fn groups(&self) -> usize {
+        self.regex.captures_len() - 1
+    }
+
+    /// Finds the first match in `text[..end]` starting at or after byte offset `start`.
+    ///
+    /// Text before `start` is still visible to `^` and `\b`, as with CPython's `pos`, while
+    /// `end` behaves as the end of the string, as with `endpos`.
+    fn find(&self, text: &str, start: usize, end: usize, mode: MatchMode) -> Option<GroupSpans> {
+        if start > end {
+            return None;
+        }
+        let regex = if mode == MatchMode::Full {
+            &self.full_regex
+        } else {
+            &self.regex
+        };
+        let mut locs = regex.capture_locations();
+        let found = regex.captures_read_at(&mut locs, &text[..end], start)?;
+        if mode != MatchMode::Search && found.start() != start {
+            return None;
+        }
+        Some(spans_of(&locs))
+    }
+
+    /// Finds successive non-overlapping matches in `text[start..end]`, at most `limit` of
+    /// them when `limit` is non-zero.
+    ///
+    /// An empty match directly after a non-empty match is allowed, as in CPython. The time
+    /// limit is checked after every match and the collected spans are checked against the
+    /// memory limit as they grow.
+    fn find_all(
+        &self,
+        text: &str,
+        start: usize,
+        end: usize,
+        limit: usize,
+        tracker: &impl ResourceTracker,
+    ) -> Result<Vec<GroupSpans>, ResourceError> {
+        let haystack = &text[..end];
+        let mut locs = self.regex.capture_locations();
+        let span_size = std::mem::size_of::<GroupSpans>() + locs.len() * std::mem::size_of::<Option<(usize, usize)>>();
+        let mut growth = GrowthCheck::default();
+        let mut matches = Vec::new();
+        let mut at = start;
+        while at <= end && (limit == 0 || matches.len() < limit) {
+            let Some(found) = self.regex.captures_read_at(&mut locs, haystack, at) else {
+                break;
+            };
+            at = if found.is_empty() {
+                found.end() + haystack[found.end()..].chars().next().map_or(1, char::len_utf8)
+            } else {
+                found.end()
+            };
+            matches.push(spans_of(&locs));
+            tracker.check_time()?;
+            growth.check(matches.len() * span_size, tracker)?;
+        }
+        Ok(matches)
+    }
+
+    /// Implements `search()`, `match()` and `fullmatch()`, returning a `re.Match` or `None`.
+    pub fn match_value(
+        &self,
+        self_id: HeapId,
+        string: &Value,
+        pos: i64,
+        endpos: i64,
+        mode: MatchMode,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let found = {
+            let text = subject_str(string, heap, interns)?;
+            let window = Window::new(text, pos, endpos);
+            self.find(text, window.start, window.end, mode).map(|spans| {
+                let char_spans = CharIndexer::new(text).char_spans(&spans);
+                (spans, char_spans, window)
+            })
+        };
+        let Some((spans, char_spans, window)) = found else {
+            return Ok(Value::None);
+        };
+        let found = ReMatch::new(
+            string.clone_with_heap(heap),
+            self_value(self_id, heap),
+            spans,
+            char_spans,
+            window.pos,
+            window.endpos,
+        );
+        Ok(Value::Ref(heap.allocate(HeapData::ReMatch(found))?))
+    }
+
+    /// Implements `findall()`.
+    ///
+    /// Returns the whole matches when the pattern has no groups, the first group's text when
+    /// it has exactly one, and tuples of every group's text otherwise. Groups that did not
+    /// participate give `''`.
+    pub fn findall(
+        &self,
+        string: &Value,
+        pos: i64,
+        endpos: i64,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let groups = self.groups();
+        let found: Vec<Vec<String>> = {
+            let text = subject_str(string, heap, interns)?;
+            let window = Window::new(text, pos, endpos);
+            let matches = self.find_all(text, window.start, window.end, 0, heap.tracker())?;
+            let wanted = if groups == 0 { 0..1 } else { 1..groups + 1 };
+            matches
+                .iter()
+                .map(|spans| {
+                    spans[wanted.clone()]
+                        .iter()
+                        .map(|span| span.map_or_else(String::new, |(s, e)| text[s..e].to_owned()))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let mut items = Vec::with_capacity(found.len());
+        for texts in found {
+            let item = if texts.len() == 1 {
+                texts
+                    .into_iter()
+                    .next()
+                    .map_or(Ok(Value::None), |s| allocate_string(s, heap))
+            } else {
+                allocate_group_tuple(texts, heap)
+            };
+            match item {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+    }
+
+    /// Implements `finditer()`: an iterator over `re.Match` objects for every match.
+    ///
+    /// The matches are found eagerly, then wrapped in an iterator.
+    pub fn finditer(
+        &self,
+        self_id: HeapId,
+        string: &Value,
+        pos: i64,
+        endpos: i64,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let (found, window) = {
+            let text = subject_str(string, heap, interns)?;
+            let window = Window::new(text, pos, endpos);
+            let matches = self.find_all(text, window.start, window.end, 0, heap.tracker())?;
+            let mut indexer = CharIndexer::new(text);
+            let found: Vec<(GroupSpans, GroupSpans)> = matches
+                .into_iter()
+                .map(|spans| {
+                    let char_spans = indexer.char_spans(&spans);
+                    (spans, char_spans)
+                })
+                .collect();
+            (found, window)
+        };
+
+        let mut items = Vec::with_capacity(found.len());
+        for (spans, char_spans) in found {
+            let string = string.clone_with_heap(heap);
+            let pattern = self_value(self_id, heap);
+            let found = ReMatch::new(string, pattern, spans, char_spans, window.pos, window.endpos);
+            match heap.allocate(HeapData::ReMatch(found)) {
+                Ok(id) => items.push(Value::Ref(id)),
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err.into());
+                }
+            }
+        }
+        let list = Value::Ref(heap.allocate(HeapData::List(List::new(items)))?);
+        get_iter(list, heap, interns)
+    }
+
+    /// Implements `split()`: the pieces of the string between matches, with the text of
+    /// every group (or `None`) inserted after each piece.
+    pub fn split(
+        &self,
+        string: &Value,
+        maxsplit: i64,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        let pieces: Vec<Option<String>> = {
+            let text = subject_str(string, heap, interns)?;
+            // A negative maxsplit means no splitting at all
+            let matches = match usize::try_from(maxsplit) {
+                Ok(limit) => self.find_all(text, 0, text.len(), limit, heap.tracker())?,
+                Err(_) => Vec::new(),
+            };
+            let mut pieces = Vec::new();
+            let mut last = 0;
+            for spans in &matches {
+                let (start, end) = spans[0].expect("group 0 always participates");
+                pieces.push(Some(text[last..start].to_owned()));
+                pieces.extend(spans[1..].iter().map(|span| span.map(|(s, e)| text[s..e].to_owned())));
+                last = end;
+            }
+            pieces.push(Some(text[last..].to_owned()));
+            pieces
+        };
+
+        let mut items = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            match piece.map_or(Ok(Value::None), |s| allocate_string(s, heap)) {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    items.drop_with_heap(heap);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(Value::Ref(heap.allocate(HeapData::List(List::new(items)))?))
+    }
+
+    /// Implements `sub()` and `subn()`, returning the new string and the number of
+    /// replacements made.
+    ///
+    /// `repl` is either a template string, where `\1` and `\g<name>` refer to groups, or a
+    /// callable that receives each `re.Match` and returns the replacement (`None` for `''`).
+    pub fn sub(
+        &self,
+        self_id: HeapId,
+        repl: &Value,
+        string: &Value,
+        count: i64,
+        vm: &mut VM<impl ResourceTracker>,
+    ) -> RunResult<(Value, usize)> {
+        // A negative count means no replacements at all
+        let Ok(limit) = usize::try_from(count) else {
+            subject_str(string, vm.heap, vm.interns)?;
+            return Ok((string.clone_with_heap(vm.heap), 0));
+        };
+
+        if let Some(template) = repl.as_str_ref(vm.heap, vm.interns) {
+            let parts = parse_template(template, &self.regex)?;
+            let (result, replaced) = {
+                let text = subject_str(string, vm.heap, vm.interns)?;
+                let matches = self.find_all(text, 0, text.len(), limit, vm.heap.tracker())?;
+                let mut result = String::with_capacity(text.len());
+                let mut growth = GrowthCheck::default();
+                let mut last = 0;
+                for spans in &matches {
+                    let (start, end) = spans[0].expect("group 0 always participates");
+                    result.push_str(&text[last..start]);
+                    expand_template(&parts, text, spans, &mut result);
+                    last = end;
+                    growth.check(result.len(), vm.heap.tracker())?;
+                }
+                result.push_str(&text[last..]);
+                (result, matches.len())
+            };
+            return Ok((allocate_string(result, vm.heap)?, replaced));
+        }
+
+        let (found, window) = {
+            let text = subject_str(string, vm.heap, vm.interns)?;
+            let window = Window::new(text, 0, i64::MAX);
+            let matches = self.find_all(text, 0, text.len(), limit, vm.heap.tracker())?;
+            let mut indexer = CharIndexer::new(text);
+            let found: Vec<(GroupSpans, GroupSpans)> = matches
+                .into_iter()
+                .map(|spans| {
+                    let char_spans = indexer.char_spans(&spans);
+                    (spans, char_spans)
+                })
+                .collect();
+            (found, window)
+        };
+        let replaced = found.len();
+
+        let mut result = String::new();
+        let mut growth = GrowthCheck::default();
+        // Index of the next piece CPython would join, reported for non-string replacements
+        let mut piece_index = 0;
+        let mut last = 0;
+        for (spans, char_spans) in found {
+            let (start, end) = spans[0].expect("group 0 always participates");
+            let text = subject_str(string, vm.heap, vm.interns)?;
+            if start > last {
+                result.push_str(&text[last..start]);
+                piece_index += 1;
+            }
+            last = end;
+
+            let found = ReMatch::new(
+                string.clone_with_heap(vm.heap),
+                self_value(self_id, vm.heap),
+                spans,
+                char_spans,
+                window.pos,
+                window.endpos,
+            );
+            let found = Value::Ref(vm.heap.allocate(HeapData::ReMatch(found))?);
+            let replacement = vm.evaluate_function("re.sub()", repl, ArgValues::One(found))?;
+            defer_drop!(replacement, vm);
+            if matches!(replacement, Value::None) {
+                continue;
+            }
+            let Some(replacement) = replacement.as_str_ref(vm.heap, vm.interns) else {
+                return Err(ExcType::type_error_join_item(piece_index, replacement.py_type(vm.heap)));
+            };
+            result.push_str(replacement);
+            piece_index += 1;
+            growth.check(result.len(), vm.heap.tracker())?;
+        }
+        let text = subject_str(string, vm.heap, vm.interns)?;
+        result.push_str(&text[last..]);
+        Ok((allocate_string(result, vm.heap)?, replaced))
+    }
+
+    /// Runs a `re.Pattern` method, or the corresponding `re` module function once the
+    /// pattern has been compiled.
+    ///
+    /// `args` holds the arguments after the pattern itself.
+    pub fn call_method(
+        &self,
+        self_id: HeapId,
+        method: StaticStrings,
+        args: ArgValues,
+        vm: &mut VM<impl ResourceTracker>,
+    ) -> RunResult<Value> {
+        let name: &'static str = method.into();
+        match method {
+            StaticStrings::Search | StaticStrings::Match | StaticStrings::Fullmatch => {
+                let mode = match method {
+                    StaticStrings::Search => MatchMode::Search,
+                    StaticStrings::Match => MatchMode::Match,
+                    _ => MatchMode::Full,
+                };
+                let [string, pos, endpos] = args.bind(name, ["string", "pos", "endpos"], 1, vm.heap, vm.interns)?;
+                let string = string.expect("required argument");
+                defer_drop!(string, vm);
+                let pos = int_arg(pos, 0, vm.heap)?;
+                let endpos = int_arg(endpos, i64::MAX, vm.heap)?;
+                self.match_value(self_id, string, pos, endpos, mode, vm.heap, vm.interns)
+            }
+            StaticStrings::Findall | StaticStrings::Finditer => {
+                let [string, pos, endpos] = args.bind(name, ["string", "pos", "endpos"], 1, vm.heap, vm.interns)?;
+                let string = string.expect("required argument");
+                defer_drop!(string, vm);
+                let pos = int_arg(pos, 0, vm.heap)?;
+                let endpos = int_arg(endpos, i64::MAX, vm.heap)?;
+                if method == StaticStrings::Findall {
+                    self.findall(string, pos, endpos, vm.heap, vm.interns)
+                } else {
+                    self.finditer(self_id, string, pos, endpos, vm.heap, vm.interns)
+                }
+            }
+            StaticStrings::Sub | StaticStrings::Subn => {
+                let [repl, string, count] = args.bind(name, ["repl", "string", "count"], 2, vm.heap, vm.interns)?;
+                let repl = repl.expect("required argument");
+                defer_drop!(repl, vm);
+                let string = string.expect("required argument");
+                defer_drop!(string, vm);
+                let count = int_arg(count, 0, vm.heap)?;
+                let (result, replaced) = self.sub(self_id, repl, string, count, vm)?;
+                if method == StaticStrings::Sub {
+                    Ok(result)
+                } else {
+                    let replaced = i64::try_from(replaced).expect("match count fits in i64");
+                    Ok(allocate_tuple([result, Value::Int(replaced)].into(), vm.heap)?)
+                }
+            }
+            StaticStrings::Split => {
+                let [string, maxsplit] = args.bind(name, ["string", "maxsplit"], 1, vm.heap, vm.interns)?;
+                let string = string.expect("required argument");
+                defer_drop!(string, vm);
+                let maxsplit = int_arg(maxsplit, 0, vm.heap)?;
+                self.split(string, maxsplit, vm.heap, vm.interns)
+            }
+            _ => {
+                args.drop_with_heap(vm.heap);
+                Err(ExcType::attribute_error(Type::RePattern, name))
+            }
+        }
+    }
+}
+
+impl PyTrait for RePattern {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::RePattern
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self.pattern == other.pattern && self.flags == other.flags)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        true
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        // Like CPython, the pattern's repr is cut off after 200 characters
+        let mut pattern_repr = String::new();
+        string_repr_fmt(&self.pattern, &mut pattern_repr)?;
+        f.write_str("re.compile(")?;
+        f.write_str(
+            pattern_repr
+                .char_indices()
+                .nth(200)
+                .map_or(pattern_repr.as_str(), |(i, _)| &pattern_repr[..i]),
+        )?;
+
+        let mut remaining = self.flags & !UNICODE;
+        if remaining != 0 {
+            let mut sep = ", ";
+            for (flag, name) in FLAG_NAMES {
+                if remaining & flag != 0 {
+                    write!(f, "{sep}{name}")?;
+                    sep = "|";
+                    remaining &= !flag;
+                }
+            }
+            if remaining != 0 {
+                write!(f, "{sep}0x{remaining:x}")?;
+            }
+        }
+        f.write_char(')')
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // Patterns don't contain heap references, nothing to do
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.pattern.capacity()
+    }
+
+    fn py_call_attr_raw(
+        &mut self,
+        self_id: HeapId,
+        vm: &mut VM<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+    ) -> RunResult<AttrCallResult> {
+        let Some(method) = attr.static_string() else {
+            args.drop_with_heap(vm.heap);
+            return Err(ExcType::attribute_error(Type::RePattern, attr.as_str(vm.interns)));
+        };
+        self.call_method(self_id, method, args, vm).map(AttrCallResult::Value)
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Pattern) => allocate_string(self.pattern.clone(), heap)?,
+            Some(StaticStrings::Flags) => Value::Int(self.flags),
+            Some(StaticStrings::Groups) => Value::Int(i64::try_from(self.groups()).expect("group count fits in i64")),
+            Some(StaticStrings::Groupindex) => {
+                let mut dict = Dict::new();
+                for (index, name) in self.regex.capture_names().enumerate() {
+                    let Some(name) = name else { continue };
+                    let key = allocate_string(name.to_owned(), heap)?;
+                    let index = Value::Int(i64::try_from(index).expect("group index fits in i64"));
+                    if let Some(old) = dict.set(key, index, heap, interns)? {
+                        old.drop_with_heap(heap);
+                    }
+                }
+                Value::Ref(heap.allocate(HeapData::Dict(dict))?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AttrCallResult::Value(value)))
+    }
+}
+
+/// Returns a new reference to the pattern stored at `self_id`, for a `re.Match` to hold.
+fn self_value(self_id: HeapId, heap: &mut Heap<impl ResourceTracker>) -> Value {
+    heap.inc_ref(self_id);
+    Value::Ref(self_id)
+}
+
+/// Borrows the subject string of a match operation.
+///
+/// Raises `TypeError` for bytes-like objects, since only `str` patterns are supported, and
+/// for every other non-string type.
+pub(crate) fn subject_str<'a>(
+    string: &'a Value,
+    heap: &'a Heap<impl ResourceTracker>,
+    interns: &'a Interns,
+) -> RunResult<&'a str> {
+    string.as_str_ref(heap, interns).ok_or_else(|| {
+        let bytes_like = string.as_bytes_like(heap, interns).is_some();
+        ExcType::type_error_re_subject(string.py_type(heap), bytes_like)
+    })
+}
+
+/// Converts an optional integer argument, using `default` when it was not passed.
+pub(crate) fn int_arg(value: Option<Value>, default: i64, heap: &mut Heap<impl ResourceTracker>) -> RunResult<i64> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    defer_drop!(value, heap);
+    value.as_int(heap)
+}
+
+/// Allocates a tuple of group texts, as returned by `findall()` for patterns with groups.
+fn allocate_group_tuple(texts: Vec<String>, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
+    let mut items = Vec::with_capacity(texts.len());
+    for text in texts {
+        match allocate_string(text, heap) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                items.drop_with_heap(heap);
+                return Err(err);
+            }
+        }
+    }
+    Ok(allocate_tuple(items.into(), heap)?)
+}
+
+/// Returns the byte span of every group from the capture locations of a match.
+fn spans_of(locs: &CaptureLocations) -> GroupSpans {
+    (0..locs.len()).map(|group| locs.get(group)).collect()
+}
+
+/// Checks a growing result against the memory limit each time its size doubles past
+/// `LARGE_RESULT_THRESHOLD`.
+struct GrowthCheck {
+    /// Size in bytes above which the next check happens.
+    next: usize,
+}
+
+impl Default for GrowthCheck {
+    fn default() -> Self {
+        Self {
+            next: LARGE_RESULT_THRESHOLD,
+        }
+    }
+}
+
+impl GrowthCheck {
+    /// Reports the current size of the result, failing if it would exceed the memory limit.
+    fn check(&mut self, size: usize, tracker: &impl ResourceTracker) -> Result<(), ResourceError> {
+        if size > self.next {
+            tracker.check_large_result(size)?;
+            self.next = size.saturating_mul(2);
+        }
+        Ok(())
+    }
+}
+
+/// The `pos`/`endpos` window of a subject string, in characters and in bytes.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    /// Start of the window in characters, `Match.pos`.
+    pos: usize,
+    /// End of the window in characters, `Match.endpos`.
+    endpos: usize,
+    /// Start of the window in bytes.
+    start: usize,
+    /// End of the window in bytes.
+    end: usize,
+}
+
+impl Window {
+    /// Clamps `pos` and `endpos` to the string like CPython and converts them to byte offsets.
+    fn new(text: &str, pos: i64, endpos: i64) -> Self {
+        let clamp = |n: i64| usize::try_from(n.max(0)).unwrap_or(usize::MAX);
+        let (pos, endpos) = (clamp(pos), clamp(endpos));
+        if text.is_ascii() {
+            let pos = pos.min(text.len());
+            let endpos = endpos.min(text.len());
+            return Self {
+                pos,
+                endpos,
+                start: pos,
+                end: endpos,
+            };
+        }
+        let len = text.chars().count();
+        let (pos, endpos) = (pos.min(len), endpos.min(len));
+        let byte_offset = |chars: usize| text.char_indices().nth(chars).map_or(text.len(), |(i, _)| i);
+        Self {
+            pos,
+            endpos,
+            start: byte_offset(pos),
+            end: byte_offset(endpos),
+        }
+    }
+}
+
+/// Converts byte offsets in a string to character offsets.
+///
+/// Offsets are converted relative to the previous one, so converting the spans of
+/// successive matches is linear in the length of the string.
+pub(crate) struct CharIndexer<'a> {
+    text: &'a str,
+    /// Whether the text is ASCII, in which case byte and character offsets coincide.
+    ascii: bool,
+    /// The byte offset of the previous conversion.
+    byte: usize,
+    /// The character offset of the previous conversion.
+    chars: usize,
+}
+
+impl<'a> CharIndexer<'a> {
+    /// Creates an indexer for `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            ascii: text.is_ascii(),
+            byte: 0,
+            chars: 0,
+        }
+    }
+
+    /// Returns the character offset of the byte offset `byte`.
+    pub fn char_offset(&mut self, byte: usize) -> usize {
+        if self.ascii {
+            return byte;
+        }
+        if byte >= self.byte {
+            self.chars += self.text[self.byte..byte].chars().count();
+        } else {
+            self.chars -= self.text[byte..self.byte].chars().count();
+        }
+        self.byte = byte;
+        self.chars
+    }
+
+    /// Converts the byte spans of a match into character spans.
+    pub fn char_spans(&mut self, spans: &GroupSpans) -> GroupSpans {
+        spans
+            .iter()
+            .map(|span| span.map(|(start, end)| (self.char_offset(start), self.char_offset(end))))
+            .collect()
+    }
+}
+
+/// Builds a regex with the options corresponding to `flags`.
+///
+/// Syntax errors raise `re.error` with the crate's message; patterns too large to compile
+/// within `REGEX_SIZE_LIMIT` raise `OverflowError`.
+fn build_regex(pattern: &str, flags: i64) -> RunResult<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags & IGNORECASE != 0)
+        .multi_line(flags & MULTILINE != 0)
+        .dot_matches_new_line(flags & DOTALL != 0)
+        .ignore_whitespace(flags & VERBOSE != 0)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|err| match err {
+            regex::Error::CompiledTooBig(_) => {
+                SimpleException::new_msg(ExcType::OverflowError, "regular expression code size limit exceeded").into()
+            }
+            // Syntax errors render the pattern with a caret underneath; the last line is the message
+            err => {
+                let msg = err.to_string();
+                let msg = msg.lines().last().unwrap_or_default();
+                ExcType::re_error(msg.strip_prefix("error: ").unwrap_or(msg))
+            }
+        })
+}
+
+/// Splits global inline flags such as `(?im)` off the start of a pattern.
+///
+/// Returns the flags and the rest of the pattern. Scoped flags like `(?i:...)` are left in
+/// place, since the regex crate understands them directly.
+fn split_inline_flags(pattern: &str) -> (i64, &str) {
+    let mut flags = 0;
+    let mut rest = pattern;
+    while let Some(group) = rest.strip_prefix("(?")
+        && let Some(close) = group.find(')')
+        && close > 0
+    {
+        let mut group_flags = 0;
+        for c in group[..close].chars() {
+            group_flags |= match c {
+                'a' => ASCII,
+                'i' => IGNORECASE,
+                'L' => LOCALE,
+                'm' => MULTILINE,
+                's' => DOTALL,
+                'u' => UNICODE,
+                'x' => VERBOSE,
+                _ => return (flags, rest),
+            };
+        }
+        flags |= group_flags;
+        rest = &group[close + 1..];
+    }
+    (flags, rest)
+}
+
+/// Rewrites Python regular expression syntax into the equivalent `regex` crate syntax.
+///
+/// Only constructs whose meaning differs are rewritten. Anything the crate does not support
+/// (look-around, backreferences, ...) is passed through so that compilation reports it.
+fn translate(pattern: &str, ascii: bool, verbose: bool) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::with_capacity(pattern.len());
+    // Index of the first item of the character class being translated, if inside one
+    let mut class_start = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(start) = class_start {
+            match c {
+                ']' if i > start => {
+                    out.push(']');
+                    class_start = None;
+                }
+                // A `]` first in a class is a literal
+                ']' => out.push_str("\\]"),
+                '\\' => {
+                    i = translate_escape(&chars, i, true, ascii, &mut out);
+                    continue;
+                }
+                // The crate reads these as nested classes and set operations
+                '[' | '&' | '~' | '#' => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    out.push_str("-\\-");
+                    i += 1;
+                }
+                // Verbose mode does not ignore whitespace inside classes in Python
+                c if verbose && c.is_whitespace() => push_hex(&mut out, c),
+                c => out.push(c),
+            }
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '\\' => {
+                i = translate_escape(&chars, i, false, ascii, &mut out);
+                continue;
+            }
+            '[' => {
+                out.push('[');
+                if chars.get(i + 1) == Some(&'^') {
+                    out.push('^');
+                    i += 1;
+                }
+                class_start = Some(i + 1);
+            }
+            '(' if chars.get(i + 1) == Some(&'?') && chars.get(i + 2) == Some(&'#') => {
+                // `(?#...)` comments cannot contain `)`; an unterminated one is left for the crate to reject
+                if let Some(len) = chars[i..].iter().position(|&c| c == ')') {
+                    i += len + 1;
+                    continue;
+                }
+                out.push(c);
+            }
+            '{' => {
+                let (repeat, len) = translate_repeat(&chars[i..]);
+                out.push_str(&repeat);
+                i += len;
+                continue;
+            }
+            '#' if verbose => {
+                // Comments run to the end of the line and are ignored by the crate in verbose mode
+                let len = chars[i..].iter().position(|&c| c == '\n').unwrap_or(chars.len() - i);
+                out.extend(&chars[i..i + len]);
+                i += len;
+                continue;
+            }
+            // Python only ignores ASCII whitespace in verbose mode, the crate all whitespace
+            c if verbose && !c.is_ascii() && c.is_whitespace() => push_hex(&mut out, c),
+            c => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Translates the escape sequence starting with the backslash at `chars[i]`, returning the
+/// index just after it.
+fn translate_escape(chars: &[char], i: usize, in_class: bool, ascii: bool, out: &mut String) -> usize {
+    let Some(&c) = chars.get(i + 1) else {
+        // A trailing backslash is left for the crate to reject
+        out.push('\\');
+        return i + 1;
+    };
+    match c {
+        'Z' if !in_class => out.push_str("\\z"),
+        'b' if in_class => out.push_str("\\x08"),
+        'b' if ascii => out.push_str("(?-u:\\b)"),
+        'B' if ascii => out.push_str("(?-u:\\B)"),
+        'd' if ascii => out.push_str("[0-9]"),
+        'D' if ascii => out.push_str("[^0-9]"),
+        'w' if ascii => out.push_str("[0-9A-Za-z_]"),
+        'W' if ascii => out.push_str("[^0-9A-Za-z_]"),
+        's' if ascii => out.push_str("[\\t\\n\\x0B\\x0C\\r\\x20]"),
+        'S' if ascii => out.push_str("[^\\t\\n\\x0B\\x0C\\r\\x20]"),
+        '0'..='7' => {
+            // Outside classes only `\0` and three-digit escapes are octal; the rest are backreferences
+            let digits = chars[i + 1..].iter().take(3).take_while(|d| d.is_digit(8)).count();
+            let octal = if in_class || c == '0' {
+                Some(digits)
+            } else {
+                Some(3).filter(|&n| digits == n)
+            };
+            let value = octal.and_then(|n| {
+                let digits: String = chars[i + 1..i + 1 + n].iter().collect();
+                u32::from_str_radix(&digits, 8).ok().filter(|&v| v <= 0o377)
+            });
+            match (octal, value) {
+                (Some(n), Some(value)) => {
+                    write!(out, "\\x{{{value:X}}}").expect("writing to a String cannot fail");
+                    return i + 1 + n;
+                }
+                _ => {
+                    out.push('\\');
+                    out.push(c);
+                }
+            }
+        }
+        // The crate reads `\<` and `\>` as word boundaries, Python as literals
+        '<' | '>' => out.push(c),
+        c if c.is_whitespace() => push_hex(out, c),
+        c if !c.is_ascii() => out.push(c),
+        c => {
+            out.push('\\');
+            out.push(c);
+        }
+    }
+    i + 2
+}
+
+/// Translates a `{` at the start of `chars`, returning the output and the number of
+/// characters consumed.
+///
+/// Python treats a brace that does not start a valid repeat as a literal and allows the
+/// lower bound to be omitted (`{,n}`), unlike the crate.
+fn translate_repeat(chars: &[char]) -> (String, usize) {
+    let digits = |from: usize| chars[from..].iter().take_while(|c| c.is_ascii_digit()).count();
+    let lo = digits(1);
+    let mut i = 1 + lo;
+    let hi = if chars.get(i) == Some(&',') {
+        let hi = digits(i + 1);
+        i += 1 + hi;
+        Some(hi)
+    } else {
+        None
+    };
+    if chars.get(i) != Some(&'}') || (lo == 0 && hi.is_none()) {
+        return ("\\{".to_owned(), 1);
+    }
+    let repeat: String = chars[..=i].iter().collect();
+    if lo == 0 {
+        (format!("{{0{}", &repeat[1..]), i + 1)
+    } else {
+        (repeat, i + 1)
+    }
+}
+
+/// Writes `c` as a `\x{..}` escape, which means the same character in every mode.
+fn push_hex(out: &mut String, c: char) {
+    write!(out, "\\x{{{:X}}}", u32::from(c)).expect("writing to a String cannot fail");
+}
+
+/// One piece of a parsed `sub()` replacement template.
+#[derive(Debug, PartialEq)]
+pub(crate) enum TemplatePart {
+    /// Text copied to the output as is.
+    Literal(String),
+    /// The text matched by a group, empty if the group did not participate.
+    Group(usize),
+}
+
+/// Parses a replacement template such as `r'\1-\g<name>'` following CPython's rules.
+///
+/// Positions in error messages are character offsets into the template.
+pub(crate) fn parse_template(template: &str, regex: &Regex) -> RunResult<Vec<TemplatePart>> {
+    let groups = regex.captures_len() - 1;
+    let chars: Vec<char> = template.chars().collect();
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '\\' {
+            literal.push(c);
+            i += 1;
+            continue;
+        }
+        let Some(&next) = chars.get(i + 1) else {
+            return Err(ExcType::re_error_at("bad escape (end of pattern)", i));
+        };
+        let group = match next {
+            'g' => {
+                if chars.get(i + 2) != Some(&'<') {
+                    return Err(ExcType::re_error_at("missing <", i + 2));
+                }
+                let name_start = i + 3;
+                let Some(len) = chars[name_start..].iter().position(|&c| c == '>') else {
+                    let msg = if name_start == chars.len() {
+                        "missing group name"
+                    } else {
+                        "missing >, unterminated name"
+                    };
+                    return Err(ExcType::re_error_at(msg, name_start));
+                };
+                if len == 0 {
+                    return Err(ExcType::re_error_at("missing group name", name_start));
+                }
+                let name: String = chars[name_start..name_start + len].iter().collect();
+                i = name_start + len + 1;
+                if name.bytes().all(|b| b.is_ascii_digit()) {
+                    match name.parse::<usize>() {
+                        Ok(index) if index <= groups => index,
+                        Ok(index) => {
+                            return Err(ExcType::re_error_at(
+                                format!("invalid group reference {index}"),
+                                name_start,
+                            ));
+                        }
+                        Err(_) => {
+                            return Err(ExcType::re_error_at(
+                                format!("invalid group reference {name}"),
+                                name_start,
+                            ));
+                        }
+                    }
+                } else if !is_identifier(&name) {
+                    return Err(ExcType::re_error_at(
+                        format!("bad character in group name '{name}'"),
+                        name_start,
+                    ));
+                } else if let Some(index) = regex.capture_names().position(|n| n == Some(name.as_str())) {
+                    index
+                } else {
+                    return Err(
+                        SimpleException::new_msg(ExcType::IndexError, format!("unknown group name '{name}'")).into(),
+                    );
+                }
+            }
+            '0' => {
+                let digits = chars[i + 2..].iter().take(2).take_while(|d| d.is_digit(8)).count();
+                literal.push(octal_char(&chars[i + 1..i + 2 + digits]));
+                i += 2 + digits;
+                continue;
+            }
+            '1'..='9' => {
+                if let Some(digits) = chars.get(i + 1..i + 4)
+                    && digits.iter().all(|d| d.is_digit(8))
+                {
+                    if digits[0] > '3' {
+                        let digits: String = digits.iter().collect();
+                        return Err(ExcType::re_error_at(
+                            format!("octal escape value \\{digits} outside of range 0-0o377"),
+                            i,
+                        ));
+                    }
+                    literal.push(octal_char(digits));
+                    i += 4;
+                    continue;
+                }
+                let len = if chars.get(i + 2).is_some_and(char::is_ascii_digit) {
+                    2
+                } else {
+                    1
+                };
+                let index: usize = chars[i + 1..i + 1 + len]
+                    .iter()
+                    .fold(0, |n, d| n * 10 + d.to_digit(10).expect("checked digit") as usize);
+                if index > groups {
+                    return Err(ExcType::re_error_at(format!("invalid group reference {index}"), i + 1));
+                }
+                i += 1 + len;
+                index
+            }
+            _ => {
+                match next {
+                    'a' => literal.push('\x07'),
+                    'b' => literal.push('\x08'),
+                    'f' => literal.push('\x0c'),
+                    'n' => literal.push('\n'),
+                    'r' => literal.push('\r'),
+                    't' => literal.push('\t'),
+                    'v' => literal.push('\x0b'),
+                    '\\' => literal.push('\\'),
+                    c if c.is_ascii_alphabetic() => return Err(ExcType::re_error_at(format!("bad escape \\{c}"), i)),
+                    // Other escapes are kept, backslash included
+                    c => {
+                        literal.push('\\');
+                        literal.push(c);
+                    }
+                }
+                i += 2;
+                continue;
+            }
+        };
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+        }
+        parts.push(TemplatePart::Group(group));
+    }
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// Appends the expansion of a parsed template for one match to `out`.
+pub(crate) fn expand_template(parts: &[TemplatePart], text: &str, spans: &GroupSpans, out: &mut String) {
+    for part in parts {
+        match part {
+            TemplatePart::Literal(s) => out.push_str(s),
+            TemplatePart::Group(group) => {
+                if let Some((start, end)) = spans[*group] {
+                    out.push_str(&text[start..end]);
+                }
+            }
+        }
+    }
+}
+
+/// Converts octal digits to a character, keeping the low byte like CPython.
+fn octal_char(digits: &[char]) -> char {
+    let value = digits
+        .iter()
+        .fold(0, |n, d| n * 8 + d.to_digit(8).expect("checked octal digit"));
+    char::from_u32(value & 0xff).expect("a byte is a valid character")
+}
+
+/// Returns whether `name` is a valid Python identifier, as required for group names.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_alphabetic()) && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
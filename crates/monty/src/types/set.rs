@@ -1,4 +1,4 @@
-use std::fmt::Write;
+use std::{fmt::Write, mem};
 
 use ahash::AHashSet;
 use hashbrown::HashTable;
@@ -12,7 +12,7 @@ use crate::{
     intern::{Interns, StaticStrings},
     resource::{DepthGuard, ResourceError, ResourceTracker},
     types::Type,
-    value::{EitherStr, Value},
+    value::{BitwiseOp, EitherStr, Value},
 };
 
 /// Entry in the set storage, containing a value and its cached hash.
@@ -467,9 +467,11 @@ impl SetStorage {
         Ok(())
     }
 
-    /// Estimates the memory size of this storage.
+    /// Estimates the memory size of this storage from its reserved entry and index slots.
     fn estimate_size(&self) -> usize {
-        std::mem::size_of::<Self>() + self.len() * std::mem::size_of::<SetEntry>()
+        std::mem::size_of::<Self>()
+            + self.entries.capacity() * std::mem::size_of::<SetEntry>()
+            + self.indices.capacity() * std::mem::size_of::<usize>()
     }
 }
 
@@ -1371,6 +1373,145 @@ impl<'de> serde::Deserialize<'de> for SetStorage {
     }
 }
 
+/// A set operator (`|`, `&`, `-`, `^`) applied between two sets or frozensets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetBinaryOp {
+    /// `a | b`
+    Union,
+    /// `a & b`
+    Intersection,
+    /// `a - b`
+    Difference,
+    /// `a ^ b`
+    SymmetricDifference,
+}
+
+impl SetBinaryOp {
+    /// Maps a bitwise operator onto the set operator it denotes, if any.
+    ///
+    /// Shifts have no set meaning and return `None`.
+    pub fn from_bitwise(op: BitwiseOp) -> Option<Self> {
+        match op {
+            BitwiseOp::Or => Some(Self::Union),
+            BitwiseOp::And => Some(Self::Intersection),
+            BitwiseOp::Xor => Some(Self::SymmetricDifference),
+            BitwiseOp::LShift | BitwiseOp::RShift => None,
+        }
+    }
+
+    /// Computes the operator over two storages, returning a fresh storage.
+    fn apply(
+        self,
+        lhs: &SetStorage,
+        rhs: &SetStorage,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<SetStorage> {
+        match self {
+            Self::Union => lhs.union(rhs, heap, interns),
+            Self::Intersection => lhs.intersection(rhs, heap, interns),
+            Self::Difference => lhs.difference(rhs, heap, interns),
+            Self::SymmetricDifference => lhs.symmetric_difference(rhs, heap, interns),
+        }
+    }
+}
+
+/// Applies a set operator when both operands are sets or frozensets.
+///
+/// Unlike the named methods (`union()` etc.), the operators only accept sets on both
+/// sides, so `Ok(None)` is returned for any other operand type and the caller raises
+/// the usual `unsupported operand type(s)` TypeError.
+///
+/// The result takes the type of the left operand, matching CPython:
+/// `set | frozenset` is a `set`, `frozenset | set` is a `frozenset`.
+pub(crate) fn py_set_binary_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: SetBinaryOp,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    let Some(storage) = set_binary_op_storage(lhs, rhs, op, heap, interns)? else {
+        return Ok(None);
+    };
+    let Value::Ref(lhs_id) = lhs else {
+        unreachable!("set_binary_op_storage only succeeds for heap sets")
+    };
+    let data = if matches!(heap.get(*lhs_id), HeapData::FrozenSet(_)) {
+        HeapData::FrozenSet(FrozenSet(storage))
+    } else {
+        HeapData::Set(Set(storage))
+    };
+    Ok(Some(Value::Ref(heap.allocate(data)?)))
+}
+
+/// Applies an in-place set operator (`|=`, `&=`, `-=`, `^=`).
+///
+/// Mutable sets are updated in place so other references observe the change.
+/// Returns `Ok(false)` when the left operand is not a mutable set or the right operand
+/// is not a set, in which case the caller falls back to the binary operator (which
+/// yields a new frozenset for frozenset operands, like CPython).
+pub(crate) fn py_set_inplace_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: SetBinaryOp,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    let Value::Ref(lhs_id) = lhs else {
+        return Ok(false);
+    };
+    if !matches!(heap.get(*lhs_id), HeapData::Set(_)) {
+        return Ok(false);
+    }
+    let Some(storage) = set_binary_op_storage(lhs, rhs, op, heap, interns)? else {
+        return Ok(false);
+    };
+    let old = heap.with_entry_mut(*lhs_id, |_heap, data| match data {
+        HeapData::Set(set) => mem::replace(&mut set.0, storage),
+        _ => unreachable!("checked above that lhs is a set"),
+    });
+    old.drop_all_values(heap);
+    Ok(true)
+}
+
+/// Shared implementation of the set operators, returning the resulting storage.
+///
+/// The right operand's entries are copied (with refcounts incremented) before the left
+/// operand is borrowed, so `s | s` and similar self-referencing operations are safe.
+fn set_binary_op_storage(
+    lhs: &Value,
+    rhs: &Value,
+    op: SetBinaryOp,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<SetStorage>> {
+    let (Value::Ref(lhs_id), Value::Ref(rhs_id)) = (lhs, rhs) else {
+        return Ok(None);
+    };
+    if !matches!(heap.get(*lhs_id), HeapData::Set(_) | HeapData::FrozenSet(_)) {
+        return Ok(None);
+    }
+    let rhs_entries = match heap.get(*rhs_id) {
+        HeapData::Set(set) => set.0.copy_entries(),
+        HeapData::FrozenSet(set) => set.0.copy_entries(),
+        _ => return Ok(None),
+    };
+    SetStorage::inc_refs_for_entries(&rhs_entries, heap);
+    let rhs_storage = SetStorage::from_entries(rhs_entries);
+
+    let result = heap.with_entry_mut(*lhs_id, |heap, data| {
+        let lhs_storage = match data {
+            HeapData::Set(set) => &set.0,
+            HeapData::FrozenSet(set) => &set.0,
+            _ => unreachable!("checked above that lhs is a set"),
+        };
+        op.apply(lhs_storage, &rhs_storage, heap, interns)
+    });
+    rhs_storage.drop_all_values(heap);
+    result.map(Some)
+}
+
 impl serde::Serialize for Set {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         self.0.serialize(serializer)
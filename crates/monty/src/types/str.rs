@@ -3,20 +3,26 @@ use std::fmt::Write;
 ///
 /// This type provides Python string semantics. Currently supports basic
 /// operations like length and equality comparison.
-use std::{borrow::Cow, fmt};
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{LazyLock, OnceLock},
+};
 
 use ahash::AHashSet;
+use regex::Regex;
 use smallvec::smallvec;
 
 use super::{Bytes, MontyIter, PyTrait};
 use crate::{
     args::ArgValues,
     defer_drop, defer_drop_mut,
-    exception_private::{ExcType, RunResult},
+    exception_private::{ExcType, RunError, RunResult, SimpleException},
+    fstring::{self, ConversionFlag, format_value_with_spec_str, percent_format},
     heap::{DropWithHeap, Heap, HeapData, HeapGuard, HeapId},
     intern::{Interns, StaticStrings, StringId},
-    resource::{DepthGuard, ResourceError, ResourceTracker},
-    types::Type,
+    resource::{DepthGuard, ResourceError, ResourceTracker, check_repeat_size},
+    types::{Dict, Type},
     value::{EitherStr, Value},
 };
 
@@ -24,14 +30,17 @@ use crate::{
 ///
 /// Wraps a Rust `String` and provides Python-compatible operations.
 /// `len()` returns the number of Unicode codepoints (characters), matching Python semantics.
-#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
-pub(crate) struct Str(String);
+///
+/// The second field caches where the code points are in the string (see `CharIndex`), built
+/// the first time the string is measured or indexed and extended by `push_str`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Str(String, OnceLock<CharIndex>);
 
 impl Str {
     /// Creates a new Str from a Rust String.
     #[must_use]
     pub fn new(s: String) -> Self {
-        Self(s)
+        Self(s, OnceLock::new())
     }
 
     /// Returns a reference to the inner string.
@@ -40,52 +49,156 @@ impl Str {
         &self.0
     }
 
-    /// Returns a mutable reference to the inner string.
-    pub fn as_string_mut(&mut self) -> &mut String {
-        &mut self.0
+    /// Appends `s` to the string.
+    ///
+    /// Keeps the code point index up to date rather than discarding it, so a loop that grows
+    /// a string with `+=` and indexes or measures it as it goes stays linear.
+    pub fn push_str(&mut self, s: &str) {
+        if let Some(index) = self.1.get_mut() {
+            index.extend(&self.0, s);
+        }
+        self.0.push_str(s);
+    }
+
+    /// Returns the number of code points in the string, which is its Python `len()`.
+    pub fn char_count(&self) -> usize {
+        self.char_index().char_count
     }
 
-    /// Creates a string from the `str()` constructor call.
+    /// Returns the code point at a Python index, where negative indices count from the end.
     ///
-    /// - `str()` with no args returns an empty string
-    /// - `str(x)` converts x to its string representation using `py_str`
-    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
-        let value = args.get_zero_one_arg("str", heap)?;
-        match value {
-            None => Ok(Value::InternString(StaticStrings::EmptyString.into())),
-            Some(v) => {
-                defer_drop!(v, heap);
-                let mut guard = DepthGuard::default();
-                let s = v.py_str(heap, &mut guard, interns).into_owned();
-                allocate_string(s, heap)
-            }
+    /// Returns `None` if the index is out of bounds.
+    pub fn char_at(&self, index: i64) -> Option<char> {
+        let len = i64::try_from(self.char_count()).ok()?;
+        let normalized = if index < 0 { index + len } else { index };
+        if normalized < 0 || normalized >= len {
+            return None;
         }
+        let offset = self.byte_offset(usize::try_from(normalized).ok()?);
+        self.0[offset..].chars().next()
+    }
+
+    /// Returns the byte offset of the code point at `index`, or the string's length if `index`
+    /// is the number of code points.
+    fn byte_offset(&self, index: usize) -> usize {
+        self.char_index().byte_offset(&self.0, index)
+    }
+
+    fn char_index(&self) -> &CharIndex {
+        self.1.get_or_init(|| CharIndex::new(&self.0))
     }
 
     /// Handles slice-based indexing for strings.
     ///
     /// Returns a new string containing the selected characters (Unicode-aware).
     fn getitem_slice(&self, slice: &crate::types::Slice, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
-        let char_count = self.0.chars().count();
         let (start, stop, step) = slice
-            .indices(char_count)
+            .indices(self.char_count())
             .map_err(|()| ExcType::value_error_slice_step_zero())?;
 
-        let result_str = get_str_slice(&self.0, start, stop, step);
+        let result_str = if step != 1 {
+            get_str_slice(&self.0, start, stop, step)
+        } else if start < stop {
+            // contiguous, so the result is the bytes between the two code points
+            self.0[self.byte_offset(start)..self.byte_offset(stop)].to_owned()
+        } else {
+            String::new()
+        };
         let heap_id = heap.allocate(HeapData::Str(Self::from(result_str)))?;
         Ok(Value::Ref(heap_id))
     }
 }
 
+impl PartialEq for Str {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+// The code point index isn't serialized, it's rebuilt when it's next needed.
+impl serde::Serialize for Str {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("Str", &self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Str {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Str")]
+        struct StrFields(String);
+        let StrFields(s) = StrFields::deserialize(deserializer)?;
+        Ok(Self::new(s))
+    }
+}
+
+/// Number of code points between the byte offsets recorded by a `CharIndex`.
+const CHAR_INDEX_STRIDE: usize = 64;
+
+/// Maps code point positions in a string to byte offsets, so `s[i]` doesn't scan from the start.
+///
+/// ASCII strings need no offsets since every code point is one byte. Other strings record the
+/// byte offset of every `CHAR_INDEX_STRIDE`th code point, so finding a code point scans at most
+/// `CHAR_INDEX_STRIDE - 1` others while the index stays a fraction of the string's size.
+#[derive(Debug, Clone)]
+struct CharIndex {
+    char_count: usize,
+    /// `offsets[n]` is the byte offset of code point `n * CHAR_INDEX_STRIDE`, empty for ASCII strings.
+    offsets: Vec<usize>,
+}
+
+impl CharIndex {
+    fn new(s: &str) -> Self {
+        let mut index = Self {
+            char_count: 0,
+            offsets: Vec::new(),
+        };
+        index.extend("", s);
+        index
+    }
+
+    /// Updates the index for `added` being appended to `s`.
+    fn extend(&mut self, s: &str, added: &str) {
+        if self.offsets.is_empty() {
+            if added.is_ascii() {
+                self.char_count += added.len();
+                return;
+            }
+            // `s` is all ASCII so far, so its code point positions are its byte offsets
+            self.offsets.extend((0..s.len()).step_by(CHAR_INDEX_STRIDE));
+        }
+        for (offset, _) in added.char_indices() {
+            if self.char_count % CHAR_INDEX_STRIDE == 0 {
+                self.offsets.push(s.len() + offset);
+            }
+            self.char_count += 1;
+        }
+    }
+
+    fn byte_offset(&self, s: &str, index: usize) -> usize {
+        if self.offsets.is_empty() {
+            return index.min(s.len());
+        }
+        if index >= self.char_count {
+            return s.len();
+        }
+        let checkpoint = self.offsets[index / CHAR_INDEX_STRIDE];
+        s[checkpoint..]
+            .char_indices()
+            .nth(index % CHAR_INDEX_STRIDE)
+            .map_or(s.len(), |(offset, _)| checkpoint + offset)
+    }
+}
+
 impl From<String> for Str {
     fn from(s: String) -> Self {
-        Self(s)
+        Self::new(s)
     }
 }
 
 impl From<&str> for Str {
     fn from(s: &str) -> Self {
-        Self(s.to_string())
+        Self::new(s.to_string())
     }
 }
 
@@ -100,7 +213,9 @@ impl From<Str> for String {
 /// Optimizations:
 /// - Empty strings return the pre-interned `StaticStrings::EmptyString`
 /// - Single ASCII characters return pre-interned ASCII strings
-/// - Other strings are allocated on the heap
+/// - Other short strings go through `Heap::allocate_str`, which reuses a cached entry
+///   with the same content when one is still alive
+/// - Longer strings are allocated on the heap
 ///
 /// This avoids heap allocation for common cases like results from `strip()`,
 /// `split()`, string iteration, etc.
@@ -113,7 +228,7 @@ pub fn allocate_string(s: String, heap: &mut Heap<impl ResourceTracker>) -> RunR
             Ok(Value::InternString(StringId::from_ascii(byte)))
         }
         _ => {
-            let heap_id = heap.allocate(HeapData::Str(Str::new(s)))?;
+            let heap_id = heap.allocate_str(s)?;
             Ok(Value::Ref(heap_id))
         }
     }
@@ -134,6 +249,23 @@ pub fn allocate_char(c: char, heap: &mut Heap<impl ResourceTracker>) -> Result<V
     }
 }
 
+/// Applies printf-style formatting for `str % args`.
+///
+/// Returns `Ok(None)` when the left operand is not a string, so the caller can raise the
+/// usual `unsupported operand type(s)` TypeError.
+pub(crate) fn py_str_mod(
+    lhs: &Value,
+    rhs: &Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Option<Value>> {
+    let Some(template) = lhs.as_either_str(heap) else {
+        return Ok(None);
+    };
+    let formatted = percent_format(template.as_str(interns), rhs, heap, interns)?;
+    allocate_string(formatted, heap).map(Some)
+}
+
 /// Gets the character at a given index in a string, handling negative indices.
 ///
 /// Returns `None` if the index is out of bounds. This uses a single-pass scan
@@ -218,7 +350,7 @@ impl PyTrait for Str {
 
     fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
         // Count Unicode characters, not bytes, to match Python semantics
-        Some(self.0.chars().count())
+        Some(self.char_count())
     }
 
     fn py_getitem(&self, key: &Value, heap: &mut Heap<impl ResourceTracker>, _interns: &Interns) -> RunResult<Value> {
@@ -234,8 +366,7 @@ impl PyTrait for Str {
         // Extract integer index, accepting Int, Bool (True=1, False=0), and LongInt
         let index = key.as_index(heap, Type::Str)?;
 
-        // Use single-pass indexing to avoid Vec<char> allocation
-        let c = get_char_at_index(&self.0, index).ok_or_else(ExcType::str_index_error)?;
+        let c = self.char_at(index).ok_or_else(ExcType::str_index_error)?;
         Ok(allocate_char(c, heap)?)
     }
 
@@ -300,9 +431,9 @@ impl PyTrait for Str {
             Value::Ref(other_id) => {
                 if Some(*other_id) == self_id {
                     let rhs = self.0.clone();
-                    self.0.push_str(&rhs);
+                    self.push_str(&rhs);
                 } else if let HeapData::Str(rhs) = heap.get(*other_id) {
-                    self.0.push_str(rhs.as_str());
+                    self.push_str(rhs.as_str());
                 } else {
                     return Ok(false);
                 }
@@ -311,7 +442,7 @@ impl PyTrait for Str {
                 Ok(true)
             }
             Value::InternString(string_id) => {
-                self.0.push_str(interns.get_str(*string_id));
+                self.push_str(interns.get_str(*string_id));
                 Ok(true)
             }
             _ => Ok(false),
@@ -364,13 +495,6 @@ pub fn call_str_method(
 ///
 /// The following Python string methods are not yet implemented:
 ///
-/// - `format()` - Requires implementing the format spec mini-language (PEP 3101),
-///   which is complex and involves parsing format specifications like `{:>10.2f}`.
-/// - `format_map(mapping)` - Similar to `format()` but takes a mapping; depends on
-///   `format()` implementation.
-/// - `maketrans()` / `translate()` - Character translation tables; moderate complexity,
-///   requires building and applying Unicode translation maps.
-/// - `expandtabs(tabsize=8)` - Tab expansion; simple but rarely used in practice.
 /// - `isprintable()` - Checks if all characters are printable; requires accurate Unicode
 ///   category data for the "printable" property.
 fn call_str_method_impl(
@@ -471,6 +595,12 @@ fn call_str_method_impl(
         StaticStrings::Zfill => str_zfill(s, args, heap),
         // Additional methods
         StaticStrings::Encode => str_encode(s, args, heap, interns),
+        StaticStrings::Format => str_format(s, args, heap, interns),
+        StaticStrings::FormatMap => str_format_map(s, args, heap, interns),
+        StaticStrings::Expandtabs => str_expandtabs(s, args, heap, interns),
+        StaticStrings::Translate => str_translate(s, args, heap, interns),
+        // `maketrans` is a static method, so calling it on an instance ignores the instance
+        StaticStrings::Maketrans => str_maketrans(args, heap, interns),
         StaticStrings::Isidentifier => {
             args.check_zero_args("str.isidentifier", heap)?;
             Ok(Value::Bool(str_isidentifier(s)))
@@ -557,36 +687,55 @@ fn str_join(
 /// - Uses double quotes if the string contains single quotes but not double quotes
 /// - Uses single quotes by default, escaping any contained single quotes
 ///
-/// Common escape sequences (backslash, newline, tab, carriage return) are always escaped.
+/// Backslash, newline, tab and carriage return use their short escapes, and other characters
+/// that aren't printable (see `is_printable`) are escaped as `\xhh`, `\uhhhh` or `\Uhhhhhhhh`
+/// depending on their size, exactly as CPython does.
 pub fn string_repr_fmt(s: &str, f: &mut impl Write) -> fmt::Result {
-    // Check if the string contains single quotes but not double quotes
-    if s.contains('\'') && !s.contains('"') {
-        // Use double quotes if string contains only single quotes
-        f.write_char('"')?;
-        for c in s.chars() {
-            match c {
-                '\\' => f.write_str("\\\\")?,
-                '\n' => f.write_str("\\n")?,
-                '\t' => f.write_str("\\t")?,
-                '\r' => f.write_str("\\r")?,
-                _ => f.write_char(c)?,
-            }
-        }
-        f.write_char('"')
+    let quote = if s.contains('\'') && !s.contains('"') {
+        '"'
     } else {
-        // Use single quotes by default, escape any single quotes in the string
-        f.write_char('\'')?;
-        for c in s.chars() {
-            match c {
-                '\\' => f.write_str("\\\\")?,
-                '\n' => f.write_str("\\n")?,
-                '\t' => f.write_str("\\t")?,
-                '\r' => f.write_str("\\r")?,
-                '\'' => f.write_str("\\'")?,
-                _ => f.write_char(c)?,
+        '\''
+    };
+    f.write_char(quote)?;
+    for c in s.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            _ if c == quote => {
+                f.write_char('\\')?;
+                f.write_char(c)?;
+            }
+            _ if is_printable(c) => f.write_char(c)?,
+            _ => {
+                let code = u32::from(c);
+                if code <= 0xff {
+                    write!(f, "\\x{code:02x}")?;
+                } else if code <= 0xffff {
+                    write!(f, "\\u{code:04x}")?;
+                } else {
+                    write!(f, "\\U{code:08x}")?;
+                }
             }
         }
-        f.write_char('\'')
+    }
+    f.write_char(quote)
+}
+
+/// Returns whether Python considers a character printable, which decides whether `repr()` escapes it.
+///
+/// Everything is printable except the Unicode "Other" (control, format, private use and
+/// unassigned) and "Separator" categories, apart from the ASCII space.
+fn is_printable(c: char) -> bool {
+    /// Matches any single non-printable character.
+    static NON_PRINTABLE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"[\p{C}\p{Z}]").expect("the non-printable pattern is valid"));
+
+    if c.is_ascii() {
+        c == ' ' || c.is_ascii_graphic()
+    } else {
+        !char_matches(&NON_PRINTABLE, c)
     }
 }
 
@@ -616,37 +765,34 @@ fn str_upper(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value>
 
 /// Implements Python's `str.capitalize()` method.
 ///
-/// Returns a copy of the string with its first character capitalized and the rest lowercased.
+/// Returns a copy of the string with its first character titlecased and the rest lowercased.
 fn str_capitalize(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
-    let mut chars = s.chars();
-    let result = match chars.next() {
-        None => String::new(),
-        Some(first) => {
-            let mut result = first.to_uppercase().to_string();
-            for c in chars {
-                result.extend(c.to_lowercase());
-            }
-            result
-        }
-    };
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.char_indices();
+    if let Some((_, first)) = chars.next() {
+        push_titlecase(first, &mut result);
+    }
+    for (index, c) in chars {
+        push_lowercase(s, index, c, &mut result);
+    }
     allocate_string(result, heap)
 }
 
 /// Implements Python's `str.title()` method.
 ///
-/// Returns a titlecased version of the string where words start with an uppercase
-/// character and the remaining characters are lowercase.
+/// Returns a titlecased version of the string where words start with a titlecase
+/// character and the remaining characters are lowercase. A word is any run of cased characters.
 fn str_title(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
     let mut result = String::with_capacity(s.len());
     let mut prev_is_cased = false;
 
-    for c in s.chars() {
+    for (index, c) in s.char_indices() {
         if prev_is_cased {
-            result.extend(c.to_lowercase());
+            push_lowercase(s, index, c, &mut result);
         } else {
-            result.extend(c.to_uppercase());
+            push_titlecase(c, &mut result);
         }
-        prev_is_cased = c.is_alphabetic();
+        prev_is_cased = is_cased(c);
     }
 
     allocate_string(result, heap)
@@ -655,12 +801,13 @@ fn str_title(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value>
 /// Implements Python's `str.swapcase()` method.
 ///
 /// Returns a copy of the string with uppercase characters converted to lowercase and vice versa.
+/// Titlecase characters like `ǅ` are left alone.
 fn str_swapcase(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
     let mut result = String::with_capacity(s.len());
 
-    for c in s.chars() {
+    for (index, c) in s.char_indices() {
         if c.is_uppercase() {
-            result.extend(c.to_lowercase());
+            push_lowercase(s, index, c, &mut result);
         } else if c.is_lowercase() {
             result.extend(c.to_uppercase());
         } else {
@@ -675,9 +822,141 @@ fn str_swapcase(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Val
 ///
 /// Returns a casefolded copy of the string. Casefolding is similar to lowercasing
 /// but more aggressive because it is intended for caseless string matching.
+///
+/// Characters are lowercased one at a time (so there is no final-sigma rule), except
+/// for those whose full case folding differs from their lowercase form, see
+/// [`casefold_special`].
 fn str_casefold(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
-    // Rust's to_lowercase() is equivalent to Unicode casefolding for most purposes
-    allocate_string(s.to_lowercase(), heap)
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        if let Some(folded) = casefold_special(c) {
+            result.push_str(folded);
+        } else if let Some(folded) = casefold_cherokee(c) {
+            result.push(folded);
+        } else {
+            result.extend(c.to_lowercase());
+        }
+    }
+    allocate_string(result, heap)
+}
+
+/// Returns the full case folding of `c` from Unicode's `CaseFolding.txt` where it
+/// differs from `char::to_lowercase`.
+///
+/// Covers the sharp s, the Greek symbol variants and letters with an iota subscript, the
+/// Cyrillic historical variants, and the Latin and Armenian ligatures.
+fn casefold_special(c: char) -> Option<&'static str> {
+    let folded = match c {
+        '\u{DF}' | '\u{1E9E}' => "ss",
+        '\u{B5}' => "\u{3BC}",
+        '\u{17F}' => "s",
+        '\u{149}' => "\u{2BC}n",
+        '\u{1F0}' => "j\u{30C}",
+        '\u{345}' | '\u{1FBE}' => "\u{3B9}",
+        '\u{390}' => "\u{3B9}\u{308}\u{301}",
+        '\u{3B0}' => "\u{3C5}\u{308}\u{301}",
+        '\u{3C2}' => "\u{3C3}",
+        '\u{3D0}' => "\u{3B2}",
+        '\u{3D1}' => "\u{3B8}",
+        '\u{3D5}' => "\u{3C6}",
+        '\u{3D6}' => "\u{3C0}",
+        '\u{3F0}' => "\u{3BA}",
+        '\u{3F1}' => "\u{3C1}",
+        '\u{3F5}' => "\u{3B5}",
+        '\u{587}' => "\u{565}\u{582}",
+        '\u{1C80}' => "\u{432}",
+        '\u{1C81}' => "\u{434}",
+        '\u{1C82}' => "\u{43E}",
+        '\u{1C83}' => "\u{441}",
+        '\u{1C84}' | '\u{1C85}' => "\u{442}",
+        '\u{1C86}' => "\u{44A}",
+        '\u{1C87}' => "\u{463}",
+        '\u{1C88}' => "\u{A64B}",
+        '\u{1E96}' => "h\u{331}",
+        '\u{1E97}' => "t\u{308}",
+        '\u{1E98}' => "w\u{30A}",
+        '\u{1E99}' => "y\u{30A}",
+        '\u{1E9A}' => "a\u{2BE}",
+        '\u{1E9B}' => "\u{1E61}",
+        '\u{1F50}' => "\u{3C5}\u{313}",
+        '\u{1F52}' => "\u{3C5}\u{313}\u{300}",
+        '\u{1F54}' => "\u{3C5}\u{313}\u{301}",
+        '\u{1F56}' => "\u{3C5}\u{313}\u{342}",
+        '\u{1F80}' | '\u{1F88}' => "\u{1F00}\u{3B9}",
+        '\u{1F81}' | '\u{1F89}' => "\u{1F01}\u{3B9}",
+        '\u{1F82}' | '\u{1F8A}' => "\u{1F02}\u{3B9}",
+        '\u{1F83}' | '\u{1F8B}' => "\u{1F03}\u{3B9}",
+        '\u{1F84}' | '\u{1F8C}' => "\u{1F04}\u{3B9}",
+        '\u{1F85}' | '\u{1F8D}' => "\u{1F05}\u{3B9}",
+        '\u{1F86}' | '\u{1F8E}' => "\u{1F06}\u{3B9}",
+        '\u{1F87}' | '\u{1F8F}' => "\u{1F07}\u{3B9}",
+        '\u{1F90}' | '\u{1F98}' => "\u{1F20}\u{3B9}",
+        '\u{1F91}' | '\u{1F99}' => "\u{1F21}\u{3B9}",
+        '\u{1F92}' | '\u{1F9A}' => "\u{1F22}\u{3B9}",
+        '\u{1F93}' | '\u{1F9B}' => "\u{1F23}\u{3B9}",
+        '\u{1F94}' | '\u{1F9C}' => "\u{1F24}\u{3B9}",
+        '\u{1F95}' | '\u{1F9D}' => "\u{1F25}\u{3B9}",
+        '\u{1F96}' | '\u{1F9E}' => "\u{1F26}\u{3B9}",
+        '\u{1F97}' | '\u{1F9F}' => "\u{1F27}\u{3B9}",
+        '\u{1FA0}' | '\u{1FA8}' => "\u{1F60}\u{3B9}",
+        '\u{1FA1}' | '\u{1FA9}' => "\u{1F61}\u{3B9}",
+        '\u{1FA2}' | '\u{1FAA}' => "\u{1F62}\u{3B9}",
+        '\u{1FA3}' | '\u{1FAB}' => "\u{1F63}\u{3B9}",
+        '\u{1FA4}' | '\u{1FAC}' => "\u{1F64}\u{3B9}",
+        '\u{1FA5}' | '\u{1FAD}' => "\u{1F65}\u{3B9}",
+        '\u{1FA6}' | '\u{1FAE}' => "\u{1F66}\u{3B9}",
+        '\u{1FA7}' | '\u{1FAF}' => "\u{1F67}\u{3B9}",
+        '\u{1FB2}' => "\u{1F70}\u{3B9}",
+        '\u{1FB3}' | '\u{1FBC}' => "\u{3B1}\u{3B9}",
+        '\u{1FB4}' => "\u{3AC}\u{3B9}",
+        '\u{1FB6}' => "\u{3B1}\u{342}",
+        '\u{1FB7}' => "\u{3B1}\u{342}\u{3B9}",
+        '\u{1FC2}' => "\u{1F74}\u{3B9}",
+        '\u{1FC3}' | '\u{1FCC}' => "\u{3B7}\u{3B9}",
+        '\u{1FC4}' => "\u{3AE}\u{3B9}",
+        '\u{1FC6}' => "\u{3B7}\u{342}",
+        '\u{1FC7}' => "\u{3B7}\u{342}\u{3B9}",
+        '\u{1FD2}' => "\u{3B9}\u{308}\u{300}",
+        '\u{1FD3}' => "\u{3B9}\u{308}\u{301}",
+        '\u{1FD6}' => "\u{3B9}\u{342}",
+        '\u{1FD7}' => "\u{3B9}\u{308}\u{342}",
+        '\u{1FE2}' => "\u{3C5}\u{308}\u{300}",
+        '\u{1FE3}' => "\u{3C5}\u{308}\u{301}",
+        '\u{1FE4}' => "\u{3C1}\u{313}",
+        '\u{1FE6}' => "\u{3C5}\u{342}",
+        '\u{1FE7}' => "\u{3C5}\u{308}\u{342}",
+        '\u{1FF2}' => "\u{1F7C}\u{3B9}",
+        '\u{1FF3}' | '\u{1FFC}' => "\u{3C9}\u{3B9}",
+        '\u{1FF4}' => "\u{3CE}\u{3B9}",
+        '\u{1FF6}' => "\u{3C9}\u{342}",
+        '\u{1FF7}' => "\u{3C9}\u{342}\u{3B9}",
+        '\u{FB00}' => "ff",
+        '\u{FB01}' => "fi",
+        '\u{FB02}' => "fl",
+        '\u{FB03}' => "ffi",
+        '\u{FB04}' => "ffl",
+        '\u{FB05}' | '\u{FB06}' => "st",
+        '\u{FB13}' => "\u{574}\u{576}",
+        '\u{FB14}' => "\u{574}\u{565}",
+        '\u{FB15}' => "\u{574}\u{56B}",
+        '\u{FB16}' => "\u{57E}\u{576}",
+        '\u{FB17}' => "\u{574}\u{56D}",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Returns the case folding of a Cherokee letter, which is the capital letter
+/// (unlike every other script, Cherokee folds to uppercase).
+fn casefold_cherokee(c: char) -> Option<char> {
+    let code = u32::from(c);
+    let folded = match code {
+        0x13A0..=0x13F5 => code,
+        0x13F8..=0x13FD => code - 8,
+        0xAB70..=0xABBF => code - 0xAB70 + 0x13A0,
+        _ => return None,
+    };
+    char::from_u32(folded)
 }
 
 // =============================================================================
@@ -686,9 +965,11 @@ fn str_casefold(s: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Val
 
 /// Implements Python's `str.isalpha()` method.
 ///
-/// Returns True if all characters in the string are alphabetic and there is at least one character.
+/// Returns True if all characters in the string are letters (Unicode category L) and there is
+/// at least one character. Unlike Rust's `char::is_alphabetic`, combining vowel signs and
+/// letter numerals don't count.
 fn str_isalpha(s: &str) -> bool {
-    !s.is_empty() && s.chars().all(char::is_alphabetic)
+    !s.is_empty() && s.chars().all(is_letter)
 }
 
 /// Implements Python's `str.isdigit()` method.
@@ -702,25 +983,24 @@ fn str_isdigit(s: &str) -> bool {
 
 /// Implements Python's `str.isalnum()` method.
 ///
-/// Returns True if all characters in the string are alphanumeric and there is at least one character.
+/// Returns True if all characters in the string are letters or numeric and there is at least one character.
 fn str_isalnum(s: &str) -> bool {
-    !s.is_empty() && s.chars().all(char::is_alphanumeric)
+    !s.is_empty() && s.chars().all(|c| is_letter(c) || is_numeric_char(c))
 }
 
 /// Implements Python's `str.isnumeric()` method.
 ///
 /// Returns True if all characters in the string are numeric and there is at least one character.
-/// In Python, numeric includes decimal digits (Nd), letter numerals (Nl), and other numerals (No).
-/// Rust's `char::is_numeric()` checks for all of these categories.
+/// See `is_numeric_char` for what counts as numeric.
 fn str_isnumeric(s: &str) -> bool {
-    !s.is_empty() && s.chars().all(char::is_numeric)
+    !s.is_empty() && s.chars().all(is_numeric_char)
 }
 
 /// Implements Python's `str.isspace()` method.
 ///
 /// Returns True if all characters in the string are whitespace and there is at least one character.
 fn str_isspace(s: &str) -> bool {
-    !s.is_empty() && s.chars().all(char::is_whitespace)
+    !s.is_empty() && s.chars().all(is_python_space)
 }
 
 /// Implements Python's `str.islower()` method.
@@ -729,7 +1009,7 @@ fn str_isspace(s: &str) -> bool {
 fn str_islower(s: &str) -> bool {
     let mut has_cased = false;
     for c in s.chars() {
-        if c.is_uppercase() {
+        if c.is_uppercase() || is_titlecase(c) {
             return false;
         }
         if c.is_lowercase() {
@@ -745,7 +1025,7 @@ fn str_islower(s: &str) -> bool {
 fn str_isupper(s: &str) -> bool {
     let mut has_cased = false;
     for c in s.chars() {
-        if c.is_lowercase() {
+        if c.is_lowercase() || is_titlecase(c) {
             return false;
         }
         if c.is_uppercase() {
@@ -765,126 +1045,15 @@ fn str_isdecimal(s: &str) -> bool {
 }
 
 /// Checks if a character is a Unicode decimal digit (Nd category).
-///
-/// This covers decimal digit ranges from various scripts including ASCII, Arabic-Indic,
-/// Devanagari, Bengali, Thai, Fullwidth, and many others.
 fn is_unicode_decimal(c: char) -> bool {
-    let cp = c as u32;
-    matches!(
-        cp,
-        // Basic Latin (ASCII digits)
-        0x0030..=0x0039
-        // Arabic-Indic digits
-        | 0x0660..=0x0669
-        // Extended Arabic-Indic digits
-        | 0x06F0..=0x06F9
-        // NKo digits
-        | 0x07C0..=0x07C9
-        // Devanagari digits
-        | 0x0966..=0x096F
-        // Bengali digits
-        | 0x09E6..=0x09EF
-        // Gurmukhi digits
-        | 0x0A66..=0x0A6F
-        // Gujarati digits
-        | 0x0AE6..=0x0AEF
-        // Oriya digits
-        | 0x0B66..=0x0B6F
-        // Tamil digits
-        | 0x0BE6..=0x0BEF
-        // Telugu digits
-        | 0x0C66..=0x0C6F
-        // Kannada digits
-        | 0x0CE6..=0x0CEF
-        // Malayalam digits
-        | 0x0D66..=0x0D6F
-        // Sinhala Lith digits
-        | 0x0DE6..=0x0DEF
-        // Thai digits
-        | 0x0E50..=0x0E59
-        // Lao digits
-        | 0x0ED0..=0x0ED9
-        // Tibetan digits
-        | 0x0F20..=0x0F29
-        // Myanmar digits
-        | 0x1040..=0x1049
-        // Myanmar Shan digits
-        | 0x1090..=0x1099
-        // Khmer digits
-        | 0x17E0..=0x17E9
-        // Mongolian digits
-        | 0x1810..=0x1819
-        // Limbu digits
-        | 0x1946..=0x194F
-        // New Tai Lue digits
-        | 0x19D0..=0x19D9
-        // Tai Tham Hora digits
-        | 0x1A80..=0x1A89
-        // Tai Tham Tham digits
-        | 0x1A90..=0x1A99
-        // Balinese digits
-        | 0x1B50..=0x1B59
-        // Sundanese digits
-        | 0x1BB0..=0x1BB9
-        // Lepcha digits
-        | 0x1C40..=0x1C49
-        // Ol Chiki digits
-        | 0x1C50..=0x1C59
-        // Vai digits
-        | 0xA620..=0xA629
-        // Saurashtra digits
-        | 0xA8D0..=0xA8D9
-        // Kayah Li digits
-        | 0xA900..=0xA909
-        // Javanese digits
-        | 0xA9D0..=0xA9D9
-        // Myanmar Tai Laing digits
-        | 0xA9F0..=0xA9F9
-        // Cham digits
-        | 0xAA50..=0xAA59
-        // Meetei Mayek digits
-        | 0xABF0..=0xABF9
-        // Fullwidth digits
-        | 0xFF10..=0xFF19
-        // Osmanya digits
-        | 0x104A0..=0x104A9
-        // Hanifi Rohingya digits
-        | 0x10D30..=0x10D39
-        // Brahmi digits
-        | 0x11066..=0x1106F
-        // Sora Sompeng digits
-        | 0x110F0..=0x110F9
-        // Chakma digits
-        | 0x11136..=0x1113F
-        // Sharada digits
-        | 0x111D0..=0x111D9
-        // Khudawadi digits
-        | 0x112F0..=0x112F9
-        // Newa digits
-        | 0x11450..=0x11459
-        // Tirhuta digits
-        | 0x114D0..=0x114D9
-        // Modi digits
-        | 0x11650..=0x11659
-        // Takri digits
-        | 0x116C0..=0x116C9
-        // Ahom digits
-        | 0x11730..=0x11739
-        // Warang Citi digits
-        | 0x118E0..=0x118E9
-        // Dives Akuru digits
-        | 0x11950..=0x11959
-        // Bhaiksuki digits
-        | 0x11C50..=0x11C59
-        // Masaram Gondi digits
-        | 0x11D50..=0x11D59
-        // Gunjala Gondi digits
-        | 0x11DA0..=0x11DA9
-        // Adlam digits
-        | 0x1E950..=0x1E959
-        // Segmented digits
-        | 0x1FBF0..=0x1FBF9
-    )
+    /// Matches a single decimal digit from any script.
+    static DECIMAL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\p{Nd}").expect("the decimal pattern is valid"));
+
+    if c.is_ascii() {
+        c.is_ascii_digit()
+    } else {
+        char_matches(&DECIMAL, c)
+    }
 }
 
 /// Checks if a character is a Unicode digit (isdigit).
@@ -904,6 +1073,10 @@ fn is_unicode_digit(c: char) -> bool {
         0x00B2..=0x00B3
         // Superscript 1
         | 0x00B9
+        // Ethiopic digits 1-9
+        | 0x1369..=0x1371
+        // New Tai Lue tham digit one
+        | 0x19DA
         // Superscript digits 0, 4-9
         | 0x2070
         | 0x2074..=0x2079
@@ -911,27 +1084,208 @@ fn is_unicode_digit(c: char) -> bool {
         | 0x2080..=0x2089
         // Circled digits 1-9
         | 0x2460..=0x2468
-        // Circled digit 0
-        | 0x24EA
-        // Circled digits 10-20
-        | 0x2469..=0x2473
         // Parenthesized digits 1-9
         | 0x2474..=0x247C
-        // Period digits 1-9
+        // Digits 1-9 with full stop
         | 0x2488..=0x2490
-        // Double circled digits 1-10
-        | 0x24F5..=0x24FE
-        // Dingbat circled sans-serif digits 1-10
-        | 0x2780..=0x2789
-        // Dingbat negative circled digits 1-10
-        | 0x278A..=0x2793
-        // Dingbat circled sans-serif digits 1-10
+        // Circled digit 0
+        | 0x24EA
+        // Double circled digits 1-9
+        | 0x24F5..=0x24FD
+        // Negative circled digit 0
         | 0x24FF
-        // Fullwidth digit zero (already in decimal, but include for completeness)
-        // | 0xFF10..=0xFF19  // Already covered by is_unicode_decimal
+        // Dingbat negative circled digits 1-9
+        | 0x2776..=0x277E
+        // Dingbat circled sans-serif digits 1-9
+        | 0x2780..=0x2788
+        // Dingbat negative circled sans-serif digits 1-9
+        | 0x278A..=0x2792
+        // Kharoshthi digits 1-4
+        | 0x10A40..=0x10A43
+        // Rumi digits 1-9
+        | 0x10E60..=0x10E68
+        // Brahmi numbers 1-9
+        | 0x11052..=0x1105A
+        // Digits 0-9 with full stop or comma
+        | 0x1F100..=0x1F10A
     )
 }
 
+// =============================================================================
+// Unicode character properties
+// =============================================================================
+
+/// Returns whether `c` matches a regex that matches a single character class.
+fn char_matches(class: &Regex, c: char) -> bool {
+    class.is_match(c.encode_utf8(&mut [0; 4]))
+}
+
+/// Returns whether a character is a letter (Unicode category L), which is what `str.isalpha()` checks.
+fn is_letter(c: char) -> bool {
+    /// Matches a single letter.
+    static LETTER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\p{L}").expect("the letter pattern is valid"));
+
+    if c.is_ascii() {
+        c.is_ascii_alphabetic()
+    } else {
+        char_matches(&LETTER, c)
+    }
+}
+
+/// Returns whether a character is a titlecase letter (Unicode category Lt) such as `ǅ`.
+///
+/// These are cased but neither uppercase nor lowercase.
+fn is_titlecase(c: char) -> bool {
+    /// Matches a single titlecase letter.
+    static TITLECASE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\p{Lt}").expect("the titlecase pattern is valid"));
+
+    !c.is_ascii() && char_matches(&TITLECASE, c)
+}
+
+/// Returns whether a character is cased, meaning it's uppercase, lowercase or titlecase.
+fn is_cased(c: char) -> bool {
+    c.is_lowercase() || c.is_uppercase() || is_titlecase(c)
+}
+
+/// Returns whether a character is case-ignorable, so it doesn't end a word when deciding
+/// whether a sigma is final (apostrophes, combining marks, modifier letters and the like).
+fn is_case_ignorable(c: char) -> bool {
+    /// Matches a single case-ignorable character.
+    static CASE_IGNORABLE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\p{Case_Ignorable}").expect("the case-ignorable pattern is valid"));
+
+    char_matches(&CASE_IGNORABLE, c)
+}
+
+/// Returns whether Python considers a character whitespace.
+///
+/// This is the Unicode White_Space property plus the ASCII file, group, record and unit
+/// separators, which Python treats as line boundaries.
+fn is_python_space(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '\u{1C}'..='\u{1F}')
+}
+
+/// Returns whether Python considers a character numeric.
+///
+/// This covers the Unicode number categories (Nd, Nl and No), which `char::is_numeric` checks,
+/// plus the CJK ideographs that have a numeric value such as `一` and `万`.
+fn is_numeric_char(c: char) -> bool {
+    /// CJK ideographs with a Unicode numeric value, which are letters rather than numbers.
+    const CJK_NUMERALS: [u32; 91] = [
+        0x3405, 0x3483, 0x382A, 0x3B4D, 0x4E00, 0x4E03, 0x4E07, 0x4E09, 0x4E24, 0x4E5D, 0x4E8C, 0x4E94, 0x4E96, 0x4EAC,
+        0x4EBF, 0x4EC0, 0x4EDF, 0x4EE8, 0x4F0D, 0x4F70, 0x4FE9, 0x5006, 0x5104, 0x5146, 0x5169, 0x516B, 0x516D, 0x5341,
+        0x5343, 0x5344, 0x5345, 0x534C, 0x53C1, 0x53C2, 0x53C3, 0x53C4, 0x56DB, 0x58F1, 0x58F9, 0x5E7A, 0x5EFE, 0x5EFF,
+        0x5F0C, 0x5F0D, 0x5F0E, 0x5F10, 0x62D0, 0x62FE, 0x634C, 0x67D2, 0x6D1E, 0x6F06, 0x7396, 0x767E, 0x7695, 0x79ED,
+        0x8086, 0x842C, 0x8CAE, 0x8CB3, 0x8D30, 0x920E, 0x94A9, 0x9621, 0x9646, 0x964C, 0x9678, 0x96F6, 0xF96B, 0xF973,
+        0xF978, 0xF9B2, 0xF9D1, 0xF9D3, 0xF9FD, 0x20001, 0x20064, 0x200E2, 0x20121, 0x2092A, 0x20983, 0x2098C, 0x2099C,
+        0x20AEA, 0x20AFD, 0x20B19, 0x22390, 0x22998, 0x23B1B, 0x2626D, 0x2F890,
+    ];
+
+    c.is_numeric() || CJK_NUMERALS.binary_search(&u32::from(c)).is_ok()
+}
+
+/// Appends the titlecase form of `c` to `out`.
+///
+/// This is the uppercase form except for the characters in `titlecase_special`, and for
+/// Georgian Mkhedruli letters, which are their own titlecase even though they have uppercase forms.
+fn push_titlecase(c: char, out: &mut String) {
+    if let Some(title) = titlecase_special(c) {
+        out.push_str(title);
+    } else if matches!(c, '\u{10D0}'..='\u{10FA}' | '\u{10FD}'..='\u{10FF}') {
+        out.push(c);
+    } else {
+        out.extend(c.to_uppercase());
+    }
+}
+
+/// Returns the titlecase form of `c` from Unicode's `UnicodeData.txt` and `SpecialCasing.txt`
+/// where it differs from `char::to_uppercase`.
+///
+/// Covers the Latin digraphs, the sharp s, Greek letters with an iota subscript, and the Latin
+/// and Armenian ligatures.
+fn titlecase_special(c: char) -> Option<&'static str> {
+    let title = match c {
+        '\u{DF}' => "Ss",
+        '\u{1C4}' | '\u{1C5}' | '\u{1C6}' => "\u{1C5}",
+        '\u{1C7}' | '\u{1C8}' | '\u{1C9}' => "\u{1C8}",
+        '\u{1CA}' | '\u{1CB}' | '\u{1CC}' => "\u{1CB}",
+        '\u{1F1}' | '\u{1F2}' | '\u{1F3}' => "\u{1F2}",
+        '\u{587}' => "\u{535}\u{582}",
+        '\u{1F80}' | '\u{1F88}' => "\u{1F88}",
+        '\u{1F81}' | '\u{1F89}' => "\u{1F89}",
+        '\u{1F82}' | '\u{1F8A}' => "\u{1F8A}",
+        '\u{1F83}' | '\u{1F8B}' => "\u{1F8B}",
+        '\u{1F84}' | '\u{1F8C}' => "\u{1F8C}",
+        '\u{1F85}' | '\u{1F8D}' => "\u{1F8D}",
+        '\u{1F86}' | '\u{1F8E}' => "\u{1F8E}",
+        '\u{1F87}' | '\u{1F8F}' => "\u{1F8F}",
+        '\u{1F90}' | '\u{1F98}' => "\u{1F98}",
+        '\u{1F91}' | '\u{1F99}' => "\u{1F99}",
+        '\u{1F92}' | '\u{1F9A}' => "\u{1F9A}",
+        '\u{1F93}' | '\u{1F9B}' => "\u{1F9B}",
+        '\u{1F94}' | '\u{1F9C}' => "\u{1F9C}",
+        '\u{1F95}' | '\u{1F9D}' => "\u{1F9D}",
+        '\u{1F96}' | '\u{1F9E}' => "\u{1F9E}",
+        '\u{1F97}' | '\u{1F9F}' => "\u{1F9F}",
+        '\u{1FA0}' | '\u{1FA8}' => "\u{1FA8}",
+        '\u{1FA1}' | '\u{1FA9}' => "\u{1FA9}",
+        '\u{1FA2}' | '\u{1FAA}' => "\u{1FAA}",
+        '\u{1FA3}' | '\u{1FAB}' => "\u{1FAB}",
+        '\u{1FA4}' | '\u{1FAC}' => "\u{1FAC}",
+        '\u{1FA5}' | '\u{1FAD}' => "\u{1FAD}",
+        '\u{1FA6}' | '\u{1FAE}' => "\u{1FAE}",
+        '\u{1FA7}' | '\u{1FAF}' => "\u{1FAF}",
+        '\u{1FB2}' => "\u{1FBA}\u{345}",
+        '\u{1FB3}' | '\u{1FBC}' => "\u{1FBC}",
+        '\u{1FB4}' => "\u{386}\u{345}",
+        '\u{1FB7}' => "\u{391}\u{342}\u{345}",
+        '\u{1FC2}' => "\u{1FCA}\u{345}",
+        '\u{1FC3}' | '\u{1FCC}' => "\u{1FCC}",
+        '\u{1FC4}' => "\u{389}\u{345}",
+        '\u{1FC7}' => "\u{397}\u{342}\u{345}",
+        '\u{1FF2}' => "\u{1FFA}\u{345}",
+        '\u{1FF3}' | '\u{1FFC}' => "\u{1FFC}",
+        '\u{1FF4}' => "\u{38F}\u{345}",
+        '\u{1FF7}' => "\u{3A9}\u{342}\u{345}",
+        '\u{FB00}' => "Ff",
+        '\u{FB01}' => "Fi",
+        '\u{FB02}' => "Fl",
+        '\u{FB03}' => "Ffi",
+        '\u{FB04}' => "Ffl",
+        '\u{FB05}' | '\u{FB06}' => "St",
+        '\u{FB13}' => "\u{544}\u{576}",
+        '\u{FB14}' => "\u{544}\u{565}",
+        '\u{FB15}' => "\u{544}\u{56B}",
+        '\u{FB16}' => "\u{54E}\u{576}",
+        '\u{FB17}' => "\u{544}\u{56D}",
+        _ => return None,
+    };
+    Some(title)
+}
+
+/// Appends the lowercase form of `c`, found at byte offset `index` in `s`, to `out`.
+///
+/// A capital sigma becomes the final form `ς` at the end of a word and `σ` elsewhere,
+/// which is why the surrounding string is needed.
+fn push_lowercase(s: &str, index: usize, c: char, out: &mut String) {
+    if c == 'Σ' {
+        out.push(if is_final_sigma(s, index) { 'ς' } else { 'σ' });
+    } else {
+        out.extend(c.to_lowercase());
+    }
+}
+
+/// Returns whether the capital sigma at byte offset `index` in `s` ends a word.
+///
+/// Following Unicode's Final_Sigma condition, it does when, skipping case-ignorable characters,
+/// it's preceded by a cased character and not followed by one.
+fn is_final_sigma(s: &str, index: usize) -> bool {
+    let before = s[..index].chars().rev().find(|&c| !is_case_ignorable(c));
+    let after = s[index + 'Σ'.len_utf8()..].chars().find(|&c| !is_case_ignorable(c));
+    before.is_some_and(is_cased) && !after.is_some_and(is_cased)
+}
+
 // =============================================================================
 // Search methods
 // =============================================================================
@@ -1996,30 +2350,618 @@ fn str_zfill(s: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>) ->
     allocate_string(result, heap)
 }
 
+/// Implements Python's `str.expandtabs(tabsize=8)` method.
+///
+/// Replaces each tab with spaces up to the next multiple of `tabsize` columns. The
+/// column count restarts after each newline or carriage return. A `tabsize` of zero or
+/// less removes tabs.
+fn str_expandtabs(
+    s: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let [tabsize] = args.bind("expandtabs", ["tabsize"], 0, heap, interns)?;
+    defer_drop!(tabsize, heap);
+    let tabsize = match tabsize {
+        Some(value) => usize::try_from(extract_int_arg(value, heap)?).unwrap_or(0),
+        None => 8,
+    };
+    check_repeat_size(tabsize, s.matches('\t').count(), heap.tracker())?;
+
+    let mut result = String::with_capacity(s.len());
+    let mut column = 0usize;
+    for c in s.chars() {
+        match c {
+            '\t' => {
+                if tabsize > 0 {
+                    let spaces = tabsize - column % tabsize;
+                    result.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                }
+            }
+            '\n' | '\r' => {
+                result.push(c);
+                column = 0;
+            }
+            _ => {
+                result.push(c);
+                column += 1;
+            }
+        }
+    }
+    allocate_string(result, heap)
+}
+
+/// Implements Python's `str.translate(table)` method.
+///
+/// Looks each character's ordinal up with `table[ord(c)]`: an int maps to that
+/// character, a str replaces the character, `None` deletes it, and a missing key
+/// (`LookupError`) leaves the character unchanged.
+fn str_translate(
+    s: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let table = args.get_one_arg("str.translate", heap)?;
+    defer_drop!(table, heap);
+
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        let key = Value::Int(i64::from(u32::from(c)));
+        let mapped = match table.py_getitem(&key, heap, interns) {
+            Ok(mapped) => mapped,
+            Err(RunError::Exc(exc)) if exc.exc.exc_type().is_subclass_of(ExcType::LookupError) => {
+                result.push(c);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        defer_drop!(mapped, heap);
+        match mapped {
+            Value::None => {}
+            Value::Int(_) | Value::Bool(_) => {
+                let code = match mapped {
+                    Value::Bool(b) => i64::from(*b),
+                    _ => extract_int_arg(mapped, heap)?,
+                };
+                let Some(mapped_char) = u32::try_from(code).ok().and_then(char::from_u32) else {
+                    return Err(SimpleException::new_msg(
+                        ExcType::ValueError,
+                        "character mapping must be in range(0x110000)",
+                    )
+                    .into());
+                };
+                result.push(mapped_char);
+            }
+            _ => {
+                let Some(replacement) = mapped.as_either_str(heap) else {
+                    return Err(ExcType::type_error(
+                        "character mapping must return integer, None or str",
+                    ));
+                };
+                result.push_str(replacement.as_str(interns));
+            }
+        }
+    }
+    allocate_string(result, heap)
+}
+
+/// Implements Python's static method `str.maketrans(x[, y[, z]])`.
+///
+/// Returns a translation table for `str.translate()`, a dict mapping ordinals:
+/// - with one argument, `x` must be a dict whose keys are ordinals or single characters
+/// - with two, `x` and `y` are equal length strings, mapping each character of `x` to
+///   the character at the same position in `y`
+/// - a third string `z` lists characters to map to `None`
+pub(crate) fn str_maketrans(
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let pos = args.into_pos_only("maketrans", heap)?;
+    defer_drop!(pos, heap);
+
+    let pairs = match pos.as_slice() {
+        [mapping] => maketrans_from_dict(mapping, heap, interns)?,
+        [from, to] => maketrans_from_strs(from, to, None, heap, interns)?,
+        [from, to, delete] => maketrans_from_strs(from, to, Some(delete), heap, interns)?,
+        [] => return Err(ExcType::type_error_at_least("maketrans", 1, 0)),
+        _ => return Err(ExcType::type_error_at_most("maketrans", 3, pos.len())),
+    };
+    let table = Dict::from_pairs(pairs, heap, interns)?;
+    Ok(Value::Ref(heap.allocate(HeapData::Dict(table))?))
+}
+
+/// Builds the `str.maketrans(dict)` table, converting single character keys to ordinals.
+fn maketrans_from_dict(
+    mapping: &Value,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Vec<(Value, Value)>> {
+    let dict = match mapping {
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Dict(dict) => Some(dict),
+            _ => None,
+        },
+        _ => None,
+    };
+    let Some(dict) = dict else {
+        return Err(ExcType::type_error(
+            "if you give only one argument to maketrans it must be a dict",
+        ));
+    };
+
+    let mut keys = Vec::with_capacity(dict.len());
+    for (key, _) in dict {
+        let ordinal = match key {
+            Value::Int(i) => *i,
+            Value::Bool(b) => i64::from(*b),
+            _ => {
+                let Some(key) = key.as_either_str(heap) else {
+                    return Err(ExcType::type_error(
+                        "keys in translate table must be strings or integers",
+                    ));
+                };
+                let mut chars = key.as_str(interns).chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => i64::from(u32::from(c)),
+                    _ => {
+                        return Err(SimpleException::new_msg(
+                            ExcType::ValueError,
+                            "string keys in translate table must be of length 1",
+                        )
+                        .into());
+                    }
+                }
+            }
+        };
+        keys.push(Value::Int(ordinal));
+    }
+    let values: Vec<Value> = dict.iter().map(|(_, value)| value.copy_for_extend()).collect();
+    for value in &values {
+        if let Value::Ref(id) = value {
+            heap.inc_ref(*id);
+        }
+    }
+    Ok(keys.into_iter().zip(values).collect())
+}
+
+/// Builds the `str.maketrans(x, y[, z])` table from strings.
+fn maketrans_from_strs(
+    from: &Value,
+    to: &Value,
+    delete: Option<&Value>,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Vec<(Value, Value)>> {
+    let Some(from) = from.as_either_str(heap) else {
+        return Err(ExcType::type_error(
+            "first maketrans argument must be a string if there is a second argument",
+        ));
+    };
+    let Some(to) = to.as_either_str(heap) else {
+        return Err(ExcType::type_error(format!(
+            "maketrans() argument 2 must be str, not {}",
+            to.py_type(heap)
+        )));
+    };
+    let (from, to) = (from.as_str(interns), to.as_str(interns));
+    if from.chars().count() != to.chars().count() {
+        return Err(SimpleException::new_msg(
+            ExcType::ValueError,
+            "the first two maketrans arguments must have equal length",
+        )
+        .into());
+    }
+
+    let ordinal = |c: char| Value::Int(i64::from(u32::from(c)));
+    let mut pairs: Vec<(Value, Value)> = from
+        .chars()
+        .zip(to.chars())
+        .map(|(a, b)| (ordinal(a), ordinal(b)))
+        .collect();
+    if let Some(delete) = delete {
+        let Some(delete) = delete.as_either_str(heap) else {
+            return Err(ExcType::type_error(format!(
+                "maketrans() argument 3 must be str, not {}",
+                delete.py_type(heap)
+            )));
+        };
+        pairs.extend(delete.as_str(interns).chars().map(|c| (ordinal(c), Value::None)));
+    }
+    Ok(pairs)
+}
+
+/// Implements Python's `str.format(*args, **kwargs)` method.
+///
+/// Replacement fields have the form `{field_name!conversion:format_spec}`, where every part
+/// is optional:
+/// - `field_name` is empty (automatic numbering), a positional index or a keyword name,
+///   optionally followed by `[key]` item lookups (integer keys if all digits, else strings)
+/// - `conversion` is `s`, `r` or `a`
+/// - `format_spec` uses the same mini-language as `format()` and may itself contain
+///   replacement fields, e.g. `'{:>{width}}'`
+///
+/// `{{` and `}}` produce literal braces. Attribute lookups like `{0.real}` are not supported.
+fn str_format(s: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+    let (positional, keywords) = args.into_parts();
+    let positional: Vec<Value> = positional.collect();
+    defer_drop!(positional, heap);
+    let keywords: Vec<(Value, Value)> = keywords.into_iter().collect();
+    defer_drop!(keywords, heap);
+
+    let mut fields = FormatFields {
+        positional,
+        keywords,
+        mapping: None,
+        numbering: FieldNumbering::Unset,
+    };
+    let result = fields.render(s, 0, heap, interns)?;
+    allocate_string(result, heap)
+}
+
+/// Implements Python's `str.format_map(mapping)` method.
+///
+/// Like `str.format(**mapping)`, except that named fields are looked up with
+/// `mapping[name]` when they are used, so any subscriptable object works and a
+/// `Counter`'s missing keys format as zero. Positional fields are an error.
+fn str_format_map(
+    s: &str,
+    args: ArgValues,
+    heap: &mut Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<Value> {
+    let mapping = args.get_one_arg("str.format_map", heap)?;
+    defer_drop!(mapping, heap);
+
+    let mut fields = FormatFields {
+        positional: &[],
+        keywords: &[],
+        mapping: Some(mapping),
+        numbering: FieldNumbering::Unset,
+    };
+    let result = fields.render(s, 0, heap, interns)?;
+    allocate_string(result, heap)
+}
+
+/// How a `str.format()` call numbers its fields; mixing the two styles is an error.
+#[derive(Debug, Clone, Copy)]
+enum FieldNumbering {
+    /// No numbered field seen yet.
+    Unset,
+    /// Empty field names (`{}`), holding the next positional index to use.
+    Automatic(usize),
+    /// Explicit indexes (`{0}`).
+    Manual,
+}
+
+/// Arguments of a `str.format()` call, plus the field numbering state shared between
+/// the template and any replacement fields nested in format specs.
+struct FormatFields<'a> {
+    /// Positional arguments, referenced by `{}` or `{0}`.
+    positional: &'a [Value],
+    /// Keyword arguments as (name, value) pairs, referenced by `{name}`.
+    keywords: &'a [(Value, Value)],
+    /// The mapping of a `str.format_map()` call, subscripted by `{name}` instead of `keywords`.
+    mapping: Option<&'a Value>,
+    /// Numbering style used so far.
+    numbering: FieldNumbering,
+}
+
+impl FormatFields<'_> {
+    /// Renders a template, replacing each field with its formatted argument.
+    ///
+    /// `depth` is 0 for the template itself and 1 for a format spec containing fields;
+    /// CPython refuses to expand fields nested any deeper.
+    fn render(
+        &mut self,
+        template: &str,
+        depth: u8,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<String> {
+        if depth > 1 {
+            return Err(ExcType::value_error_format_string("Max string recursion exceeded"));
+        }
+
+        let mut result = String::with_capacity(template.len());
+        let mut chars = template.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|&(_, c)| c == '{').is_some() => result.push('{'),
+                '}' if chars.next_if(|&(_, c)| c == '}').is_some() => result.push('}'),
+                '}' => {
+                    return Err(ExcType::value_error_format_string(
+                        "Single '}' encountered in format string",
+                    ));
+                }
+                '{' => {
+                    let start = i + 1;
+                    let Some(end) = find_field_end(&mut chars) else {
+                        let msg = if start == template.len() {
+                            "Single '{' encountered in format string"
+                        } else {
+                            "expected '}' before end of string"
+                        };
+                        return Err(ExcType::value_error_format_string(msg));
+                    };
+                    let formatted = self.render_field(&template[start..end], depth, heap, interns)?;
+                    result.push_str(&formatted);
+                }
+                _ => result.push(c),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Formats a single replacement field, given the text between its braces.
+    fn render_field(
+        &mut self,
+        field: &str,
+        depth: u8,
+        heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<String> {
+        let name_end = field_name_end(field);
+        let (name, rest) = field.split_at(name_end);
+
+        let (conversion, spec) = if let Some(rest) = rest.strip_prefix('!') {
+            let mut rest_chars = rest.chars();
+            let conversion = match rest_chars.next() {
+                None => return Err(ExcType::value_error_format_string("unmatched '{' in format spec")),
+                Some('s') => ConversionFlag::Str,
+                Some('r') => ConversionFlag::Repr,
+                Some('a') => ConversionFlag::Ascii,
+                Some(c) => {
+                    return Err(ExcType::value_error_format_string(format!(
+                        "Unknown conversion specifier {c}"
+                    )));
+                }
+            };
+            let rest = rest_chars.as_str();
+            if !rest.is_empty() && !rest.starts_with(':') {
+                return Err(ExcType::value_error_format_string(
+                    "expected ':' after conversion specifier",
+                ));
+            }
+            (conversion, rest.strip_prefix(':').unwrap_or(""))
+        } else {
+            (ConversionFlag::None, rest.strip_prefix(':').unwrap_or(""))
+        };
+
+        // Look the value up before expanding the spec, so automatic numbering matches CPython
+        let value = self.lookup(name, heap, interns)?;
+        defer_drop!(value, heap);
+
+        // Format specs can themselves contain replacement fields
+        let spec = if spec.contains(['{', '}']) {
+            Cow::Owned(self.render(spec, depth + 1, heap, interns)?)
+        } else {
+            Cow::Borrowed(spec)
+        };
+        format_value_with_spec_str(value, conversion, &spec, heap, interns)
+    }
+
+    /// Resolves a field name like `0`, `name` or `0[key][1]` to a new reference to its value.
+    fn lookup(&mut self, name: &str, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
+        let first_end = name.find(['.', '[']).unwrap_or(name.len());
+        let (first, mut rest) = name.split_at(first_end);
+
+        let mut value = if first.is_empty() || first.bytes().all(|b| b.is_ascii_digit()) {
+            if self.mapping.is_some() {
+                return Err(ExcType::value_error_format_string(
+                    "Format string contains positional fields",
+                ));
+            }
+            let index = self.next_index(first)?;
+            match self.positional.get(index) {
+                Some(value) => value.clone_with_heap(heap),
+                None => return Err(ExcType::index_error_format_replacement(index)),
+            }
+        } else if let Some(mapping) = self.mapping {
+            let key = allocate_string(first.to_owned(), heap)?;
+            defer_drop!(key, heap);
+            mapping.py_getitem(key, heap, interns)?
+        } else {
+            let found = self
+                .keywords
+                .iter()
+                .find(|(key, _)| key.as_either_str(heap).is_some_and(|key| key.as_str(interns) == first));
+            match found {
+                Some((_, value)) => value.clone_with_heap(heap),
+                None => return Err(ExcType::key_error_format_field(first)),
+            }
+        };
+
+        while !rest.is_empty() {
+            let Some(after_bracket) = rest.strip_prefix('[') else {
+                value.drop_with_heap(heap);
+                if rest.starts_with('.') {
+                    return Err(
+                        ExcType::not_implemented("attribute lookups in str.format() fields are not supported").into(),
+                    );
+                }
+                return Err(ExcType::value_error_format_string(
+                    "Only '.' or '[' may follow ']' in format field specifier",
+                ));
+            };
+            // the closing bracket is guaranteed by `field_name_end`
+            let close = after_bracket.find(']').unwrap_or(after_bracket.len());
+            let key = &after_bracket[..close];
+            rest = after_bracket.get(close + 1..).unwrap_or("");
+            if key.is_empty() {
+                value.drop_with_heap(heap);
+                return Err(ExcType::value_error_format_string("Empty attribute in format string"));
+            }
+
+            let key = match key.parse::<i64>() {
+                Ok(index) if key.bytes().all(|b| b.is_ascii_digit()) => Value::Int(index),
+                _ => match allocate_string(key.to_owned(), heap) {
+                    Ok(key) => key,
+                    Err(err) => {
+                        value.drop_with_heap(heap);
+                        return Err(err);
+                    }
+                },
+            };
+            let item = value.py_getitem(&key, heap, interns);
+            key.drop_with_heap(heap);
+            value.drop_with_heap(heap);
+            value = item?;
+        }
+        Ok(value)
+    }
+
+    /// Returns the positional index for a numeric or empty field name, enforcing that
+    /// automatic and manual numbering aren't mixed.
+    fn next_index(&mut self, digits: &str) -> RunResult<usize> {
+        if digits.is_empty() {
+            match self.numbering {
+                FieldNumbering::Manual => Err(ExcType::value_error_format_string(
+                    "cannot switch from manual field specification to automatic field numbering",
+                )),
+                FieldNumbering::Unset => {
+                    self.numbering = FieldNumbering::Automatic(1);
+                    Ok(0)
+                }
+                FieldNumbering::Automatic(next) => {
+                    self.numbering = FieldNumbering::Automatic(next + 1);
+                    Ok(next)
+                }
+            }
+        } else if let FieldNumbering::Automatic(_) = self.numbering {
+            Err(ExcType::value_error_format_string(
+                "cannot switch from automatic field numbering to manual field specification",
+            ))
+        } else {
+            self.numbering = FieldNumbering::Manual;
+            digits
+                .parse()
+                .map_err(|_| ExcType::value_error_format_string("Too many decimal digits in format string"))
+        }
+    }
+}
+
+/// Advances past a replacement field's contents, returning the byte offset of its closing `}`.
+///
+/// Braces nested inside the format spec are balanced, and braces inside `[...]` lookups in
+/// the field name are ignored. Returns `None` if the template ends before the field closes.
+fn find_field_end(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>) -> Option<usize> {
+    let mut depth = 1usize;
+    let mut in_name = true;
+    let mut in_brackets = false;
+    for (i, c) in chars.by_ref() {
+        match c {
+            ']' if in_brackets => in_brackets = false,
+            _ if in_brackets => {}
+            '[' if in_name => in_brackets = true,
+            '!' | ':' => in_name = false,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the byte offset where a field's name ends: the first `!` or `:` outside `[...]`.
+fn field_name_end(field: &str) -> usize {
+    let mut in_brackets = false;
+    for (i, c) in field.char_indices() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            '!' | ':' if !in_brackets => return i,
+            _ => {}
+        }
+    }
+    field.len()
+}
+
 /// Implements Python's `str.encode(encoding='utf-8', errors='strict')` method.
 ///
-/// Returns an encoded version of the string as a bytes object. Only supports
-/// UTF-8 encoding (the native encoding for Rust strings).
+/// Returns an encoded version of the string as a bytes object. Supports the UTF-8,
+/// ASCII and Latin-1 codecs, with the `strict`, `ignore`, `replace`, `backslashreplace`
+/// and `xmlcharrefreplace` error handlers for characters the codec can't encode.
 fn str_encode(s: &str, args: ArgValues, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<Value> {
     let (encoding, errors) = parse_encode_args(args, heap, interns)?;
 
-    // Only UTF-8 is supported - Rust strings are always valid UTF-8
-    let encoding_lower = encoding.to_ascii_lowercase();
-    if encoding_lower != "utf-8" && encoding_lower != "utf8" {
-        return Err(ExcType::lookup_error_unknown_encoding(&encoding));
-    }
+    let normalized = encoding.to_ascii_lowercase().replace(['-', ' '], "_");
+    let (codec, limit) = match normalized.as_str() {
+        "utf_8" | "utf8" | "u8" => ("utf-8", None),
+        "ascii" | "us_ascii" | "646" => ("ascii", Some(0x80)),
+        "latin_1" | "latin1" | "latin" | "l1" | "iso_8859_1" | "iso8859_1" | "8859" | "cp819" => {
+            ("latin-1", Some(0x100))
+        }
+        _ => return Err(ExcType::lookup_error_unknown_encoding(&encoding)),
+    };
 
-    // For UTF-8 encoding of a valid UTF-8 string, errors mode doesn't matter
-    // since there's nothing to handle - the string is already valid UTF-8
-    if errors != "strict" && errors != "ignore" && errors != "replace" && errors != "backslashreplace" {
-        return Err(ExcType::lookup_error_unknown_error_handler(&errors));
-    }
+    // `surrogateescape` and `surrogatepass` only differ from `strict` for lone surrogates,
+    // which Rust strings can't contain
+    let handler = match errors.as_str() {
+        "strict" | "surrogateescape" | "surrogatepass" => EncodeErrors::Strict,
+        "ignore" => EncodeErrors::Ignore,
+        "replace" => EncodeErrors::Replace,
+        "backslashreplace" => EncodeErrors::BackslashReplace,
+        "xmlcharrefreplace" => EncodeErrors::XmlCharRefReplace,
+        _ => return Err(ExcType::lookup_error_unknown_error_handler(&errors)),
+    };
+
+    // Every character of a Rust string can be encoded as UTF-8
+    let Some(limit) = limit else {
+        let heap_id = heap.allocate(HeapData::Bytes(Bytes::new(s.as_bytes().to_vec())))?;
+        return Ok(Value::Ref(heap_id));
+    };
 
-    let bytes = s.as_bytes().to_vec();
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut chars = s.chars().enumerate().peekable();
+    while let Some((position, c)) = chars.next() {
+        let code = u32::from(c);
+        if code < limit {
+            bytes.push(u8::try_from(code).expect("code is below the codec limit"));
+            continue;
+        }
+        match handler {
+            EncodeErrors::Strict => {
+                // CPython reports the whole run of characters the codec can't encode
+                let mut end = position;
+                while chars.next_if(|&(_, c)| u32::from(c) >= limit).is_some() {
+                    end += 1;
+                }
+                return Err(ExcType::unicode_encode_error(codec, s, position, end, limit));
+            }
+            EncodeErrors::Ignore => {}
+            EncodeErrors::Replace => bytes.push(b'?'),
+            EncodeErrors::BackslashReplace => bytes.extend_from_slice(fstring::ascii_escape(&c.to_string()).as_bytes()),
+            EncodeErrors::XmlCharRefReplace => bytes.extend_from_slice(format!("&#{code};").as_bytes()),
+        }
+    }
     let heap_id = heap.allocate(HeapData::Bytes(Bytes::new(bytes)))?;
     Ok(Value::Ref(heap_id))
 }
 
+/// The `errors` handlers of `str.encode()`, for characters the codec can't encode.
+#[derive(Debug, Clone, Copy)]
+enum EncodeErrors {
+    /// Raise `UnicodeEncodeError`.
+    Strict,
+    /// Skip the character.
+    Ignore,
+    /// Encode a `?` instead.
+    Replace,
+    /// Encode a `\xNN`, `\uNNNN` or `\UNNNNNNNN` escape sequence instead.
+    BackslashReplace,
+    /// Encode an XML character reference like `&#233;` instead.
+    XmlCharRefReplace,
+}
+
 /// Parses arguments for `str.encode()`.
 ///
 /// Returns (encoding, errors) with defaults "utf-8" and "strict".
@@ -2028,17 +2970,17 @@ fn parse_encode_args(
     heap: &mut Heap<impl ResourceTracker>,
     interns: &Interns,
 ) -> RunResult<(String, String)> {
-    let (first, second) = args.get_zero_one_two_args("str.encode", heap)?;
+    let [encoding, errors] = args.bind("encode", ["encoding", "errors"], 0, heap, interns)?;
+    defer_drop!(encoding, heap);
+    defer_drop!(errors, heap);
 
-    let encoding = if let Some(v) = first {
-        defer_drop!(v, heap);
+    let encoding = if let Some(v) = encoding {
         extract_string_arg(v, heap, interns)?
     } else {
         "utf-8".to_owned()
     };
 
-    let errors = if let Some(v) = second {
-        defer_drop!(v, heap);
+    let errors = if let Some(v) = errors {
         extract_string_arg(v, heap, interns)?
     } else {
         "strict".to_owned()
@@ -2087,7 +3029,7 @@ fn is_xid_continue(c: char) -> bool {
 
 /// Implements Python's `str.istitle()` predicate.
 ///
-/// Returns True if the string is titlecased: uppercase characters follow
+/// Returns True if the string is titlecased: uppercase and titlecase characters follow
 /// uncased characters and lowercase characters follow cased characters.
 /// Empty strings return False.
 fn str_istitle(s: &str) -> bool {
@@ -2099,8 +3041,8 @@ fn str_istitle(s: &str) -> bool {
     let mut has_cased = false;
 
     for c in s.chars() {
-        if c.is_uppercase() {
-            // Uppercase must follow uncased
+        if c.is_uppercase() || is_titlecase(c) {
+            // Uppercase and titlecase must follow uncased
             if prev_cased {
                 return false;
             }
@@ -0,0 +1,442 @@
+//! Python `datetime.timedelta` type implementation, plus the arithmetic shared by the
+//! `datetime` types.
+
+use std::fmt::{self, Write};
+
+use ahash::AHashSet;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+
+use crate::{
+    args::ArgValues,
+    defer_drop,
+    exception_private::{ExcType, RunResult},
+    heap::{DropWithHeap, Heap, HeapData, HeapId},
+    intern::{Interns, StaticStrings, StringId},
+    resource::{DepthGuard, ResourceError, ResourceTracker},
+    types::{AttrCallResult, Date, DateTime, LongInt, PyTrait, Type},
+    value::{EitherStr, Value},
+};
+
+/// The largest magnitude of `timedelta.days`.
+const MAX_DAYS: i128 = 999_999_999;
+/// Microseconds in one second.
+pub(crate) const US_PER_SECOND: i128 = 1_000_000;
+/// Microseconds in one day.
+pub(crate) const US_PER_DAY: i128 = 86_400 * US_PER_SECOND;
+
+/// Python `datetime.timedelta`, a signed duration with microsecond resolution.
+///
+/// Stored normalized like CPython: `0 <= seconds < 86400`, `0 <= microseconds < 1000000`
+/// and `|days| <= 999999999`, so the derived ordering compares durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TimeDelta {
+    days: i32,
+    seconds: i32,
+    microseconds: i32,
+}
+
+impl TimeDelta {
+    /// Creates a normalized timedelta from a total number of microseconds.
+    ///
+    /// Returns an `OverflowError` if the day count exceeds 999999999 in magnitude.
+    pub fn from_microseconds(total: i128) -> RunResult<Self> {
+        let (days, rest) = total.div_mod_floor(&US_PER_DAY);
+        if days.abs() > MAX_DAYS {
+            return Err(ExcType::overflow_error_timedelta_days(days));
+        }
+        let (seconds, microseconds) = rest.div_mod_floor(&US_PER_SECOND);
+        Ok(Self {
+            days: i32::try_from(days).expect("days checked above"),
+            seconds: i32::try_from(seconds).expect("seconds < 86400"),
+            microseconds: i32::try_from(microseconds).expect("microseconds < 1000000"),
+        })
+    }
+
+    /// Like `from_microseconds`, for results of arbitrary-precision arithmetic.
+    fn from_big_microseconds(total: &BigInt) -> RunResult<Self> {
+        match total.to_i128() {
+            Some(total) => Self::from_microseconds(total),
+            None => Err(ExcType::overflow_error_timedelta_days(
+                total.div_floor(&BigInt::from(US_PER_DAY)),
+            )),
+        }
+    }
+
+    /// The `days` attribute.
+    #[must_use]
+    pub fn days(self) -> i32 {
+        self.days
+    }
+
+    /// The `seconds` attribute, in `0..86400`.
+    #[must_use]
+    pub fn seconds(self) -> i32 {
+        self.seconds
+    }
+
+    /// The `microseconds` attribute, in `0..1000000`.
+    #[must_use]
+    pub fn microseconds(self) -> i32 {
+        self.microseconds
+    }
+
+    /// Returns the whole duration in microseconds.
+    #[must_use]
+    pub fn total_microseconds(self) -> i128 {
+        i128::from(self.days) * US_PER_DAY + i128::from(self.seconds) * US_PER_SECOND + i128::from(self.microseconds)
+    }
+
+    /// Returns `-self`, which overflows for durations just above `-timedelta.min`.
+    pub fn negate(self) -> RunResult<Self> {
+        Self::from_microseconds(-self.total_microseconds())
+    }
+
+    /// Creates a `timedelta` from the `timedelta()` constructor call.
+    ///
+    /// Accepts `days`, `seconds`, `microseconds`, `milliseconds`, `minutes`, `hours` and
+    /// `weeks`, each an int or a float. Integer components are summed exactly; the float
+    /// components are summed in microseconds and rounded half to even, like CPython.
+    pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
+        const PARAMS: [&str; 7] = [
+            "days",
+            "seconds",
+            "microseconds",
+            "milliseconds",
+            "minutes",
+            "hours",
+            "weeks",
+        ];
+        const SCALES: [i128; 7] = [
+            US_PER_DAY,
+            US_PER_SECOND,
+            1,
+            1000,
+            60 * US_PER_SECOND,
+            3600 * US_PER_SECOND,
+            7 * US_PER_DAY,
+        ];
+        let values = args.bind("timedelta", PARAMS, 0, heap, interns)?;
+        defer_drop!(values, heap);
+
+        let mut exact = BigInt::zero();
+        let mut fractional = 0.0;
+        for ((name, value), scale) in PARAMS.iter().zip(values).zip(SCALES) {
+            match value {
+                None => {}
+                Some(Value::Int(i)) => exact += BigInt::from(*i) * BigInt::from(scale),
+                Some(Value::Bool(b)) => exact += BigInt::from(i64::from(*b)) * BigInt::from(scale),
+                Some(Value::Float(f)) => fractional += f * scale as f64,
+                Some(other) => {
+                    if let Value::Ref(id) = other
+                        && let HeapData::LongInt(li) = heap.get(*id)
+                    {
+                        exact += li.inner() * BigInt::from(scale);
+                    } else {
+                        return Err(ExcType::type_error(format!(
+                            "unsupported type for timedelta {name} component: {}",
+                            other.py_type(heap)
+                        )));
+                    }
+                }
+            }
+        }
+        if fractional.is_nan() {
+            return Err(ExcType::value_error_float_nan_to_int());
+        }
+        if fractional.is_infinite() {
+            return Err(ExcType::overflow_error_float_inf_to_int());
+        }
+        let rounded = BigInt::from_f64(fractional.round_ties_even()).expect("finite float");
+        let delta = Self::from_big_microseconds(&(exact + rounded))?;
+        Ok(Value::Ref(heap.allocate(HeapData::TimeDelta(delta))?))
+    }
+}
+
+/// Formats like `str(timedelta)`: `[D day[s], ]H:MM:SS[.ffffff]`.
+impl fmt::Display for TimeDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.days != 0 {
+            let plural = if self.days.abs() == 1 { "" } else { "s" };
+            write!(f, "{} day{plural}, ", self.days)?;
+        }
+        let (hours, rest) = (self.seconds / 3600, self.seconds % 3600);
+        write!(f, "{hours}:{:02}:{:02}", rest / 60, rest % 60)?;
+        if self.microseconds != 0 {
+            write!(f, ".{:06}", self.microseconds)?;
+        }
+        Ok(())
+    }
+}
+
+impl PyTrait for TimeDelta {
+    fn py_type(&self, _heap: &Heap<impl ResourceTracker>) -> Type {
+        Type::TimeDelta
+    }
+
+    fn py_len(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> Option<usize> {
+        None
+    }
+
+    fn py_eq(
+        &self,
+        other: &Self,
+        _heap: &mut Heap<impl ResourceTracker>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> Result<bool, ResourceError> {
+        Ok(self == other)
+    }
+
+    fn py_bool(&self, _heap: &Heap<impl ResourceTracker>, _interns: &Interns) -> bool {
+        self.total_microseconds() != 0
+    }
+
+    fn py_repr_fmt(
+        &self,
+        f: &mut impl Write,
+        _heap: &Heap<impl ResourceTracker>,
+        _heap_ids: &mut AHashSet<HeapId>,
+        _guard: &mut DepthGuard,
+        _interns: &Interns,
+    ) -> std::fmt::Result {
+        // Only non-zero fields are shown, e.g. `datetime.timedelta(days=1, microseconds=5)`
+        let fields = [
+            ("days", self.days),
+            ("seconds", self.seconds),
+            ("microseconds", self.microseconds),
+        ];
+        f.write_str("datetime.timedelta(")?;
+        let mut first = true;
+        for (name, value) in fields {
+            if value != 0 {
+                if !first {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{name}={value}")?;
+                first = false;
+            }
+        }
+        if first {
+            f.write_char('0')?;
+        }
+        f.write_char(')')
+    }
+
+    fn py_dec_ref_ids(&mut self, _stack: &mut Vec<HeapId>) {
+        // TimeDelta doesn't contain heap references, nothing to do
+    }
+
+    fn py_estimate_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+    }
+
+    fn py_call_attr(
+        &mut self,
+        heap: &mut Heap<impl ResourceTracker>,
+        attr: &EitherStr,
+        args: ArgValues,
+        interns: &Interns,
+    ) -> RunResult<Value> {
+        if attr.static_string() == Some(StaticStrings::TotalSeconds) {
+            args.check_zero_args("total_seconds", heap)?;
+            Ok(Value::Float(self.total_microseconds() as f64 / US_PER_SECOND as f64))
+        } else {
+            args.drop_with_heap(heap);
+            Err(ExcType::attribute_error(Type::TimeDelta, attr.as_str(interns)))
+        }
+    }
+
+    fn py_getattr(
+        &self,
+        attr_id: StringId,
+        _heap: &mut Heap<impl ResourceTracker>,
+        interns: &Interns,
+    ) -> RunResult<Option<AttrCallResult>> {
+        let value = match StaticStrings::from_string_id(attr_id) {
+            Some(StaticStrings::Days) => self.days,
+            Some(StaticStrings::Seconds) => self.seconds,
+            Some(StaticStrings::Microseconds) => self.microseconds,
+            _ => return Err(ExcType::attribute_error(Type::TimeDelta, interns.get_str(attr_id))),
+        };
+        Ok(Some(AttrCallResult::Value(Value::Int(i64::from(value)))))
+    }
+}
+
+/// A binary operator that may involve `date`, `datetime` or `timedelta` operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TemporalOp {
+    Add,
+    Sub,
+    Mult,
+    Div,
+    FloorDiv,
+    Mod,
+}
+
+/// An operand of a temporal binary operation, copied out of the heap.
+enum Operand {
+    Date(Date),
+    DateTime(DateTime),
+    TimeDelta(TimeDelta),
+    Int(BigInt),
+    Float(f64),
+    Other,
+}
+
+impl Operand {
+    /// Classifies `value`, copying temporal values and integers out of the heap.
+    fn of(value: &Value, heap: &Heap<impl ResourceTracker>) -> Self {
+        match value {
+            Value::Int(i) => Self::Int(BigInt::from(*i)),
+            Value::Bool(b) => Self::Int(BigInt::from(i64::from(*b))),
+            Value::Float(f) => Self::Float(*f),
+            Value::Ref(id) => match heap.get(*id) {
+                HeapData::Date(d) => Self::Date(*d),
+                HeapData::DateTime(dt) => Self::DateTime(*dt),
+                HeapData::TimeDelta(td) => Self::TimeDelta(*td),
+                HeapData::LongInt(li) => Self::Int(li.inner().clone()),
+                _ => Self::Other,
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Performs a binary operation between `date`, `datetime` and `timedelta` values, and
+/// between a `timedelta` and a number.
+///
+/// Called by the VM once the regular operator dispatch has found no implementation.
+/// Returns `Ok(None)` for unsupported operand combinations so the caller can raise the
+/// usual `TypeError`.
+pub(crate) fn py_temporal_op(
+    lhs: &Value,
+    rhs: &Value,
+    op: TemporalOp,
+    heap: &mut Heap<impl ResourceTracker>,
+) -> RunResult<Option<Value>> {
+    use Operand::{Date as D, DateTime as Dt, Float, Int, TimeDelta as Td};
+    use TemporalOp::{Add, Div, FloorDiv, Mod, Mult, Sub};
+
+    let (lhs, rhs) = (Operand::of(lhs, heap), Operand::of(rhs, heap));
+    let data = match (op, lhs, rhs) {
+        (Add, Td(a), Td(b)) => HeapData::TimeDelta(TimeDelta::from_microseconds(
+            a.total_microseconds() + b.total_microseconds(),
+        )?),
+        (Sub, Td(a), Td(b)) => HeapData::TimeDelta(TimeDelta::from_microseconds(
+            a.total_microseconds() - b.total_microseconds(),
+        )?),
+        // Date arithmetic ignores the seconds and microseconds of the timedelta
+        (Add, D(d), Td(td)) | (Add, Td(td), D(d)) => HeapData::Date(d.add_days(i64::from(td.days()))?),
+        (Sub, D(d), Td(td)) => HeapData::Date(d.add_days(-i64::from(td.days()))?),
+        (Sub, D(a), D(b)) => HeapData::TimeDelta(TimeDelta::from_microseconds(
+            i128::from(a.to_ordinal() - b.to_ordinal()) * US_PER_DAY,
+        )?),
+        (Add, Dt(dt), Td(td)) | (Add, Td(td), Dt(dt)) => {
+            HeapData::DateTime(dt.add_microseconds(td.total_microseconds())?)
+        }
+        (Sub, Dt(dt), Td(td)) => HeapData::DateTime(dt.add_microseconds(-td.total_microseconds())?),
+        (Sub, Dt(a), Dt(b)) => HeapData::TimeDelta(TimeDelta::from_microseconds(
+            a.total_microseconds() - b.total_microseconds(),
+        )?),
+        (Mult, Td(td), Int(n)) | (Mult, Int(n), Td(td)) => HeapData::TimeDelta(TimeDelta::from_big_microseconds(
+            &(BigInt::from(td.total_microseconds()) * n),
+        )?),
+        (Mult, Td(td), Float(f)) | (Mult, Float(f), Td(td)) => {
+            let (numerator, denominator) = float_ratio(f)?;
+            let total = BigInt::from(td.total_microseconds()) * numerator;
+            HeapData::TimeDelta(TimeDelta::from_big_microseconds(&divide_and_round(
+                &total,
+                &denominator,
+            ))?)
+        }
+        (Div, Td(a), Td(b)) => {
+            if b.total_microseconds() == 0 {
+                return Err(ExcType::zero_division().into());
+            }
+            return Ok(Some(Value::Float(
+                a.total_microseconds() as f64 / b.total_microseconds() as f64,
+            )));
+        }
+        (Div, Td(td), Int(n)) => {
+            if n.is_zero() {
+                return Err(ExcType::zero_division_timedelta());
+            }
+            let total = BigInt::from(td.total_microseconds());
+            HeapData::TimeDelta(TimeDelta::from_big_microseconds(&divide_and_round(&total, &n))?)
+        }
+        (Div, Td(td), Float(f)) => {
+            if f == 0.0 {
+                return Err(ExcType::zero_division_timedelta());
+            }
+            let (numerator, denominator) = float_ratio(f)?;
+            let total = BigInt::from(td.total_microseconds()) * denominator;
+            HeapData::TimeDelta(TimeDelta::from_big_microseconds(&divide_and_round(&total, &numerator))?)
+        }
+        (FloorDiv, Td(a), Td(b)) => {
+            if b.total_microseconds() == 0 {
+                return Err(ExcType::zero_division_timedelta());
+            }
+            let quotient = a.total_microseconds().div_floor(&b.total_microseconds());
+            return Ok(Some(LongInt::new(BigInt::from(quotient)).into_value(heap)?));
+        }
+        (FloorDiv, Td(td), Int(n)) => {
+            if n.is_zero() {
+                return Err(ExcType::zero_division_timedelta());
+            }
+            let total = BigInt::from(td.total_microseconds());
+            HeapData::TimeDelta(TimeDelta::from_big_microseconds(&total.div_floor(&n))?)
+        }
+        (Mod, Td(a), Td(b)) => {
+            if b.total_microseconds() == 0 {
+                return Err(ExcType::zero_division_timedelta_modulo());
+            }
+            HeapData::TimeDelta(TimeDelta::from_microseconds(
+                a.total_microseconds().mod_floor(&b.total_microseconds()),
+            )?)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(Value::Ref(heap.allocate(data)?)))
+}
+
+/// Returns `value` as a fraction, like `float.as_integer_ratio()` but not reduced.
+fn float_ratio(value: f64) -> RunResult<(BigInt, BigInt)> {
+    if !value.is_finite() {
+        return Err(ExcType::float_integer_ratio_error(value.is_nan()));
+    }
+    // Every finite float is mantissa * 2**exponent with a 53-bit integer mantissa
+    let bits = value.to_bits();
+    let biased_exponent = i32::try_from((bits >> 52) & 0x7ff).expect("11-bit exponent");
+    let fraction = i64::try_from(bits & 0x000f_ffff_ffff_ffff).expect("52-bit fraction");
+    let (mantissa, exponent) = if biased_exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 0x0010_0000_0000_0000, biased_exponent - 1075)
+    };
+    let mantissa = BigInt::from(if value.is_sign_negative() { -mantissa } else { mantissa });
+    if exponent >= 0 {
+        Ok((mantissa << exponent.unsigned_abs(), BigInt::from(1)))
+    } else {
+        Ok((mantissa, BigInt::from(1) << exponent.unsigned_abs()))
+    }
+}
+
+/// Divides and rounds half to even, like CPython's `datetime._divide_and_round`.
+fn divide_and_round(numerator: &BigInt, denominator: &BigInt) -> BigInt {
+    let (quotient, remainder) = numerator.div_mod_floor(denominator);
+    // Round up if the remainder is more than half the denominator, or exactly half and
+    // the quotient is odd; the remainder has the sign of the denominator
+    let doubled = remainder * 2;
+    let greater = if denominator.is_negative() {
+        doubled < *denominator
+    } else {
+        doubled > *denominator
+    };
+    if greater || (doubled == *denominator && quotient.is_odd()) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
@@ -99,6 +99,7 @@ impl Tuple {
     ///
     /// - `tuple()` with no args returns an empty tuple (singleton)
     /// - `tuple(iterable)` creates a tuple from any iterable (list, tuple, range, str, bytes, dict)
+    /// - `tuple(t)` for a tuple `t` returns `t` itself, since tuples are immutable
     pub fn init(heap: &mut Heap<impl ResourceTracker>, args: ArgValues, interns: &Interns) -> RunResult<Value> {
         let value = args.get_zero_one_arg("tuple", heap)?;
         match value {
@@ -106,9 +107,10 @@ impl Tuple {
                 // Use empty tuple singleton
                 Ok(heap.get_empty_tuple())
             }
+            Some(v) if matches!(v, Value::Ref(id) if matches!(heap.get(id), HeapData::Tuple(_))) => Ok(v),
             Some(v) => {
                 let items = MontyIter::new(v, heap, interns)?.collect(heap, interns)?;
-                Ok(allocate_tuple(items, heap)?)
+                Ok(allocate_tuple(items.into(), heap)?)
             }
         }
     }
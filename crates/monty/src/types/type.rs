@@ -1,20 +1,38 @@
 use std::fmt;
 
-use num_bigint::BigInt;
-
 use crate::{
     args::ArgValues,
-    defer_drop,
-    exception_private::{ExcType, RunError, RunResult, SimpleException},
-    heap::{Heap, HeapData},
+    exception_private::{ExcType, RunResult},
+    heap::Heap,
     intern::Interns,
     resource::ResourceTracker,
     types::{
-        Bytes, Dict, FrozenSet, List, LongInt, MontyIter, Path, PyTrait, Range, Set, Slice, Str, Tuple, str::StringRepr,
+        ByteArray, Bytes, Date, DateTime, Deque, Dict, FrozenSet, List, MontyIter, Path, Range, Set, Slice, TimeDelta,
+        Tuple, conversions, counter::counter_init,
     },
     value::Value,
 };
 
+/// The types that are Python builtins, with the names they're accessible by without an import.
+pub(crate) const BUILTIN_TYPES: [(&str, Type); 16] = [
+    ("bool", Type::Bool),
+    ("int", Type::Int),
+    ("float", Type::Float),
+    ("str", Type::Str),
+    ("bytes", Type::Bytes),
+    ("bytearray", Type::ByteArray),
+    ("list", Type::List),
+    ("tuple", Type::Tuple),
+    ("dict", Type::Dict),
+    ("set", Type::Set),
+    ("frozenset", Type::FrozenSet),
+    ("range", Type::Range),
+    ("slice", Type::Slice),
+    ("iter", Type::Iterator),
+    ("type", Type::Type),
+    ("property", Type::Property),
+];
+
 /// Represents the Python type of a value.
 ///
 /// This enum is used both for type checking and as a callable constructor.
@@ -34,10 +52,17 @@ pub enum Type {
     Slice,
     Str,
     Bytes,
+    ByteArray,
     List,
     Tuple,
     NamedTuple,
     Dict,
+    /// A `dict.keys()` view - displays as "dict_keys"
+    DictKeys,
+    /// A `dict.values()` view - displays as "dict_values"
+    DictValues,
+    /// A `dict.items()` view - displays as "dict_items"
+    DictItems,
     Set,
     FrozenSet,
     Dataclass,
@@ -57,6 +82,32 @@ pub enum Type {
     Path,
     /// A property descriptor - displays as "property"
     Property,
+    /// A `contextlib.nullcontext` context manager
+    NullContext,
+    /// A `contextlib.suppress` context manager
+    Suppress,
+    /// A compiled regular expression from `re.compile()` - displays as "re.Pattern"
+    RePattern,
+    /// A regular expression match - displays as "re.Match"
+    ReMatch,
+    /// A calendar date from `datetime.date` - displays as "datetime.date"
+    Date,
+    /// A date and time from `datetime.datetime` - displays as "datetime.datetime"
+    DateTime,
+    /// A duration from `datetime.timedelta` - displays as "datetime.timedelta"
+    TimeDelta,
+    /// A `functools.partial` object - displays as "functools.partial"
+    Partial,
+    /// A `functools.lru_cache` wrapper - displays as "functools._lru_cache_wrapper"
+    LruCache,
+    /// A dict with a default factory from `collections.defaultdict` - displays as "collections.defaultdict"
+    DefaultDict,
+    /// A dict of counts from `collections.Counter` - displays as "collections.Counter"
+    Counter,
+    /// A double-ended queue from `collections.deque` - displays as "collections.deque"
+    Deque,
+    /// An opaque host object - displays as "external"
+    ExternalObject,
 }
 
 impl fmt::Display for Type {
@@ -72,10 +123,14 @@ impl fmt::Display for Type {
             Self::Slice => f.write_str("slice"),
             Self::Str => f.write_str("str"),
             Self::Bytes => f.write_str("bytes"),
+            Self::ByteArray => f.write_str("bytearray"),
             Self::List => f.write_str("list"),
             Self::Tuple => f.write_str("tuple"),
             Self::NamedTuple => f.write_str("namedtuple"),
             Self::Dict => f.write_str("dict"),
+            Self::DictKeys => f.write_str("dict_keys"),
+            Self::DictValues => f.write_str("dict_values"),
+            Self::DictItems => f.write_str("dict_items"),
             Self::Set => f.write_str("set"),
             Self::FrozenSet => f.write_str("frozenset"),
             Self::Dataclass => f.write_str("dataclass"),
@@ -90,6 +145,19 @@ impl fmt::Display for Type {
             Self::SpecialForm => f.write_str("typing._SpecialForm"),
             Self::Path => f.write_str("PosixPath"),
             Self::Property => f.write_str("property"),
+            Self::NullContext => f.write_str("nullcontext"),
+            Self::Suppress => f.write_str("suppress"),
+            Self::RePattern => f.write_str("re.Pattern"),
+            Self::ReMatch => f.write_str("re.Match"),
+            Self::Date => f.write_str("datetime.date"),
+            Self::DateTime => f.write_str("datetime.datetime"),
+            Self::TimeDelta => f.write_str("datetime.timedelta"),
+            Self::Partial => f.write_str("functools.partial"),
+            Self::LruCache => f.write_str("functools._lru_cache_wrapper"),
+            Self::DefaultDict => f.write_str("collections.defaultdict"),
+            Self::Counter => f.write_str("collections.Counter"),
+            Self::Deque => f.write_str("collections.deque"),
+            Self::ExternalObject => f.write_str("external"),
         }
     }
 }
@@ -105,30 +173,18 @@ impl Type {
     /// including internal types that shouldn't be resolvable from bare names.
     #[must_use]
     pub fn from_builtin_name(name: &str) -> Option<Self> {
-        match name {
-            "bool" => Some(Self::Bool),
-            "int" => Some(Self::Int),
-            "float" => Some(Self::Float),
-            "str" => Some(Self::Str),
-            "bytes" => Some(Self::Bytes),
-            "list" => Some(Self::List),
-            "tuple" => Some(Self::Tuple),
-            "dict" => Some(Self::Dict),
-            "set" => Some(Self::Set),
-            "frozenset" => Some(Self::FrozenSet),
-            "range" => Some(Self::Range),
-            "slice" => Some(Self::Slice),
-            "iter" => Some(Self::Iterator),
-            "type" => Some(Self::Type),
-            "property" => Some(Self::Property),
-            _ => None,
-        }
+        BUILTIN_TYPES
+            .iter()
+            .find(|(builtin_name, _)| *builtin_name == name)
+            .map(|&(_, t)| t)
     }
 
     /// Checks if a value of type `self` is an instance of `other`.
     ///
     /// This handles Python's subtype relationships:
     /// - `bool` is a subtype of `int` (so `isinstance(True, int)` returns True)
+    /// - `datetime.datetime` is a subtype of `datetime.date`
+    /// - `collections.defaultdict` and `collections.Counter` are subtypes of `dict`
     #[must_use]
     pub fn is_instance_of(self, other: Self) -> bool {
         if self == other {
@@ -136,8 +192,12 @@ impl Type {
         } else if self == Self::Bool && other == Self::Int {
             // bool is a subtype of int in Python
             true
+        } else if matches!(self, Self::DefaultDict | Self::Counter) {
+            // defaultdict and Counter are subtypes of dict in Python
+            other == Self::Dict
         } else {
-            false
+            // datetime is a subtype of date in Python
+            self == Self::DateTime && other == Self::Date
         }
     }
 
@@ -162,6 +222,7 @@ impl Type {
             Self::Slice => Some(11),
             Self::Iterator => Some(12),
             Self::Path => Some(13),
+            Self::ByteArray => Some(14),
             _ => None,
         }
     }
@@ -186,6 +247,7 @@ impl Type {
             11 => Some(Self::Slice),
             12 => Some(Self::Iterator),
             13 => Some(Self::Path),
+            14 => Some(Self::ByteArray),
             _ => None,
         }
     }
@@ -193,7 +255,7 @@ impl Type {
     /// Calls this type as a constructor (e.g., `list(x)`, `int(x)`).
     ///
     /// Dispatches to the appropriate type's init method for container types,
-    /// or handles primitive type conversions inline.
+    /// or to the `conversions` module for `int()`, `float()`, `bool()` and `str()`.
     pub(crate) fn call(
         self,
         heap: &mut Heap<impl ResourceTracker>,
@@ -204,169 +266,30 @@ impl Type {
             // Container types - delegate to init methods
             Self::List => List::init(heap, args, interns),
             Self::Tuple => Tuple::init(heap, args, interns),
-            Self::Dict => Dict::init(heap, args, interns),
+            Self::Dict => Dict::init(heap, args),
             Self::Set => Set::init(heap, args, interns),
             Self::FrozenSet => FrozenSet::init(heap, args, interns),
-            Self::Str => Str::init(heap, args, interns),
             Self::Bytes => Bytes::init(heap, args, interns),
+            Self::ByteArray => ByteArray::init(heap, args, interns),
             Self::Range => Range::init(heap, args),
             Self::Slice => Slice::init(heap, args),
             Self::Iterator => MontyIter::init(heap, args, interns),
             Self::Path => Path::init(heap, args, interns),
+            Self::Date => Date::init(heap, args, interns),
+            Self::DateTime => DateTime::init(heap, args, interns),
+            Self::TimeDelta => TimeDelta::init(heap, args, interns),
+            Self::DefaultDict => Dict::init_defaultdict(heap, args, interns),
+            Self::Counter => counter_init(heap, args, interns),
+            Self::Deque => Deque::init(heap, args, interns),
 
-            // Primitive types - inline implementation
-            Self::Int => {
-                let Some(v) = args.get_zero_one_arg("int", heap)? else {
-                    return Ok(Value::Int(0));
-                };
-                defer_drop!(v, heap);
-                match v {
-                    Value::Int(i) => Ok(Value::Int(*i)),
-                    Value::Float(f) => Ok(Value::Int(f64_to_i64_truncate(*f))),
-                    Value::Bool(b) => Ok(Value::Int(i64::from(*b))),
-                    Value::InternString(string_id) => parse_int_from_str(interns.get_str(*string_id), heap),
-                    Value::Ref(heap_id) => {
-                        // Clone data to release the borrow on heap before mutation
-                        match heap.get(*heap_id) {
-                            HeapData::Str(s) => {
-                                let s = s.to_string();
-                                parse_int_from_str(&s, heap)
-                            }
-                            HeapData::LongInt(li) => li.clone().into_value(heap).map_err(Into::into),
-                            _ => Err(ExcType::type_error_int_conversion(v.py_type(heap))),
-                        }
-                    }
-                    _ => Err(ExcType::type_error_int_conversion(v.py_type(heap))),
-                }
-            }
-            Self::Float => {
-                let Some(v) = args.get_zero_one_arg("float", heap)? else {
-                    return Ok(Value::Float(0.0));
-                };
-                defer_drop!(v, heap);
-                match v {
-                    Value::Float(f) => Ok(Value::Float(*f)),
-                    Value::Int(i) => Ok(Value::Float(*i as f64)),
-                    Value::Bool(b) => Ok(Value::Float(if *b { 1.0 } else { 0.0 })),
-                    Value::InternString(string_id) => {
-                        Ok(Value::Float(parse_f64_from_str(interns.get_str(*string_id))?))
-                    }
-                    Value::Ref(heap_id) => match heap.get(*heap_id) {
-                        HeapData::Str(s) => Ok(Value::Float(parse_f64_from_str(s.as_str())?)),
-                        _ => Err(ExcType::type_error_float_conversion(v.py_type(heap))),
-                    },
-                    _ => Err(ExcType::type_error_float_conversion(v.py_type(heap))),
-                }
-            }
-            Self::Bool => {
-                let Some(v) = args.get_zero_one_arg("bool", heap)? else {
-                    return Ok(Value::Bool(false));
-                };
-                defer_drop!(v, heap);
-                Ok(Value::Bool(v.py_bool(heap, interns)))
-            }
+            // Primitive conversions
+            Self::Int => conversions::int_new(heap, args, interns),
+            Self::Float => conversions::float_new(heap, args, interns),
+            Self::Bool => conversions::bool_new(heap, args, interns),
+            Self::Str => conversions::str_new(heap, args, interns),
 
             // Non-callable types - raise TypeError
             _ => Err(ExcType::type_error_not_callable(self)),
         }
     }
 }
-
-/// Truncates f64 to i64 with clamping for out-of-range values.
-///
-/// Python's `int(float)` truncates toward zero. For values outside i64 range,
-/// we clamp to i64::MAX/MIN (Python would use arbitrary precision ints, which
-/// we don't support).
-fn f64_to_i64_truncate(value: f64) -> i64 {
-    // trunc() rounds toward zero, matching Python's int(float) behavior
-    let truncated = value.trunc();
-    if truncated >= i64::MAX as f64 {
-        i64::MAX
-    } else if truncated <= i64::MIN as f64 {
-        i64::MIN
-    } else {
-        // SAFETY for clippy: truncated is guaranteed to be in (i64::MIN, i64::MAX)
-        // after the bounds checks above, so truncation cannot overflow
-        #[expect(clippy::cast_possible_truncation, reason = "bounds checked above")]
-        let result = truncated as i64;
-        result
-    }
-}
-
-/// Parses a Python `float()` string argument into an `f64`.
-///
-/// This supports:
-/// - Leading/trailing whitespace (e.g. `"  1.5  "`)
-/// - The special values `inf`, `-inf`, `infinity`, and `nan` (case-insensitive)
-///
-/// Underscore digit separators are not currently supported.
-fn parse_f64_from_str(value: &str) -> RunResult<f64> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return Err(value_error_could_not_convert_string_to_float(value));
-    }
-
-    let lower = trimmed.to_ascii_lowercase();
-    let parsed = match lower.as_str() {
-        "inf" | "+inf" | "infinity" | "+infinity" => f64::INFINITY,
-        "-inf" | "-infinity" => f64::NEG_INFINITY,
-        "nan" | "+nan" => f64::NAN,
-        "-nan" => -f64::NAN,
-        _ => trimmed
-            .parse::<f64>()
-            .map_err(|_| value_error_could_not_convert_string_to_float(value))?,
-    };
-
-    Ok(parsed)
-}
-
-/// Creates the `ValueError` raised by `float()` when a string cannot be parsed.
-///
-/// Matches CPython's message format: `could not convert string to float: '...'`.
-fn value_error_could_not_convert_string_to_float(value: &str) -> RunError {
-    SimpleException::new_msg(
-        ExcType::ValueError,
-        format!("could not convert string to float: {}", StringRepr(value)),
-    )
-    .into()
-}
-
-/// Parses a Python `int()` string argument into an `Int` or `LongInt`.
-///
-/// Handles whitespace stripping and removing `_` separators. Returns `Value::Int` if the value
-/// fits in i64, otherwise allocates a `LongInt` on the heap. Returns `ValueError` on failure.
-fn parse_int_from_str(value: &str, heap: &mut Heap<impl ResourceTracker>) -> RunResult<Value> {
-    // Try parsing as i64 first (fast path)
-    if let Ok(int) = value.parse::<i64>() {
-        return Ok(Value::Int(int));
-    }
-    let trimmed = value.trim();
-
-    if let Ok(int) = trimmed.parse::<i64>() {
-        return Ok(Value::Int(int));
-    }
-
-    // Try with underscores removed
-    let normalized = trimmed.replace('_', "");
-    if let Ok(int) = normalized.parse::<i64>() {
-        return Ok(Value::Int(int));
-    }
-
-    // Try parsing as BigInt for values too large for i64
-    if let Ok(bi) = normalized.parse::<BigInt>() {
-        return Ok(LongInt::new(bi).into_value(heap)?);
-    }
-
-    Err(value_error_invalid_literal_for_int(value))
-}
-
-/// Creates the `ValueError` raised by `int()` when a string cannot be parsed.
-///
-/// Matches CPython's message format: `invalid literal for int() with base 10: '...'`.
-fn value_error_invalid_literal_for_int(value: &str) -> RunError {
-    SimpleException::new_msg(
-        ExcType::ValueError,
-        format!("invalid literal for int() with base 10: {}", StringRepr(value)),
-    )
-    .into()
-}
@@ -218,17 +218,17 @@ impl PyTrait for Value {
                 // Fast path: same BytesId means same content
                 Ok(b1 == b2 || interns.get_bytes(*b1) == interns.get_bytes(*b2))
             }
-            // same for bytes
+            // same for bytes, which also compare equal to a bytearray with the same content
             (Self::InternBytes(bytes_id), Self::Ref(id2)) => {
-                if let HeapData::Bytes(b2) = heap.get(*id2) {
-                    Ok(interns.get_bytes(*bytes_id) == b2.as_slice())
+                if let Some(b2) = heap.get(*id2).as_bytes_like() {
+                    Ok(interns.get_bytes(*bytes_id) == b2)
                 } else {
                     Ok(false)
                 }
             }
             (Self::Ref(id1), Self::InternBytes(bytes_id)) => {
-                if let HeapData::Bytes(b1) = heap.get(*id1) {
-                    Ok(b1.as_slice() == interns.get_bytes(*bytes_id))
+                if let Some(b1) = heap.get(*id1).as_bytes_like() {
+                    Ok(b1 == interns.get_bytes(*bytes_id))
                 } else {
                     Ok(false)
                 }
@@ -263,7 +263,7 @@ impl PyTrait for Value {
         guard: &mut DepthGuard,
         interns: &Interns,
     ) -> Result<Option<Ordering>, ResourceError> {
-        // py_cmp currently only handles non-recursive types (numbers, strings, bytes)
+        // py_cmp currently only handles non-recursive types (numbers, strings, bytes, bytearrays)
         // so we don't need to recurse through the guard. The guard parameter exists
         // for API consistency with py_eq.
         match (self, other) {
@@ -291,12 +291,15 @@ impl PyTrait for Value {
                     Ok(None)
                 }
             }
-            // Ref vs Ref comparison: handles LongInt and Str
+            // Ref vs Ref comparison: handles LongInt, Str, bytes-like and datetime values
             (Self::Ref(id1), Self::Ref(id2)) => {
                 Ok(heap.with_two(*id1, *id2, |_heap, left, right| match (left, right) {
                     (HeapData::LongInt(a), HeapData::LongInt(b)) => a.inner().partial_cmp(b.inner()),
                     (HeapData::Str(a), HeapData::Str(b)) => a.as_str().partial_cmp(b.as_str()),
-                    _ => None,
+                    (HeapData::Date(a), HeapData::Date(b)) => a.partial_cmp(b),
+                    (HeapData::DateTime(a), HeapData::DateTime(b)) => a.partial_cmp(b),
+                    (HeapData::TimeDelta(a), HeapData::TimeDelta(b)) => a.partial_cmp(b),
+                    _ => left.as_bytes_like()?.partial_cmp(right.as_bytes_like()?),
                 }))
             }
             // Interned string comparisons
@@ -321,6 +324,15 @@ impl PyTrait for Value {
             (Self::InternBytes(b1), Self::InternBytes(b2)) => {
                 Ok(interns.get_bytes(*b1).partial_cmp(interns.get_bytes(*b2)))
             }
+            // Cross-type bytes comparisons: interned vs heap-allocated bytes or bytearray
+            (Self::InternBytes(b1), Self::Ref(id2)) => Ok(heap
+                .get(*id2)
+                .as_bytes_like()
+                .and_then(|b2| interns.get_bytes(*b1).partial_cmp(b2))),
+            (Self::Ref(id1), Self::InternBytes(b2)) => Ok(heap
+                .get(*id1)
+                .as_bytes_like()
+                .and_then(|b1| b1.partial_cmp(interns.get_bytes(*b2)))),
             _ => Ok(None),
         }
     }
@@ -373,15 +385,8 @@ impl PyTrait for Value {
             Self::Bool(false) => f.write_str("False"),
             Self::Int(v) => write!(f, "{v}"),
             Self::InternLongInt(long_int_id) => write!(f, "{}", interns.get_long_int(*long_int_id)),
-            Self::Float(v) => {
-                let s = v.to_string();
-                if s.contains('.') {
-                    f.write_str(&s)
-                } else {
-                    write!(f, "{s}.0")
-                }
-            }
-            Self::Builtin(b) => b.py_repr_fmt(f),
+            Self::Float(v) => float_repr_fmt(*v, f),
+            Self::Builtin(b) => b.py_repr_fmt(f, heap, interns),
             Self::ModuleFunction(mf) => mf.py_repr_fmt(f, self.id()),
             Self::DefFunction(f_id) => interns.get_function(*f_id).py_repr_fmt(f, interns, self.id()),
             Self::ExtFunction(f_id) => {
@@ -396,7 +401,7 @@ impl PyTrait for Value {
                 if heap_ids.contains(id) {
                     // Cycle detected - write type-specific placeholder following Python semantics
                     match heap.get(*id) {
-                        HeapData::List(_) => f.write_str("[...]"),
+                        HeapData::List(_) | HeapData::Deque(_) => f.write_str("[...]"),
                         HeapData::Tuple(_) => f.write_str("(...)"),
                         HeapData::Dict(_) => f.write_str("{...}"),
                         // Other types don't typically have cycles, but handle gracefully
@@ -485,13 +490,13 @@ impl PyTrait for Value {
             }
             (Self::InternString(s1), Self::InternString(s2)) => {
                 let concat = format!("{}{}", interns.get_str(*s1), interns.get_str(*s2));
-                Ok(Some(Self::Ref(heap.allocate(HeapData::Str(concat.into()))?)))
+                Ok(Some(Self::Ref(heap.allocate_str(concat)?)))
             }
             // for strings we need to account for the fact they might be either interned or not
             (Self::InternString(string_id), Self::Ref(id2)) => {
                 if let HeapData::Str(s2) = heap.get(*id2) {
                     let concat = format!("{}{}", interns.get_str(*string_id), s2.as_str());
-                    Ok(Some(Self::Ref(heap.allocate(HeapData::Str(concat.into()))?)))
+                    Ok(Some(Self::Ref(heap.allocate_str(concat)?)))
                 } else {
                     Ok(None)
                 }
@@ -499,7 +504,7 @@ impl PyTrait for Value {
             (Self::Ref(id1), Self::InternString(string_id)) => {
                 if let HeapData::Str(s1) = heap.get(*id1) {
                     let concat = format!("{}{}", s1.as_str(), interns.get_str(*string_id));
-                    Ok(Some(Self::Ref(heap.allocate(HeapData::Str(concat.into()))?)))
+                    Ok(Some(Self::Ref(heap.allocate_str(concat)?)))
                 } else {
                     Ok(None)
                 }
@@ -513,8 +518,9 @@ impl PyTrait for Value {
                 b.extend_from_slice(bytes2);
                 Ok(Some(Self::Ref(heap.allocate(HeapData::Bytes(b.into()))?)))
             }
+            // bytes + bytearray is bytes, while bytearray + bytes is a bytearray
             (Self::InternBytes(bytes_id), Self::Ref(id2)) => {
-                if let HeapData::Bytes(b2) = heap.get(*id2) {
+                if let Some(b2) = heap.get(*id2).as_bytes_like() {
                     let bytes1 = interns.get_bytes(*bytes_id);
                     let mut b = Vec::with_capacity(bytes1.len() + b2.len());
                     b.extend_from_slice(bytes1);
@@ -525,15 +531,13 @@ impl PyTrait for Value {
                 }
             }
             (Self::Ref(id1), Self::InternBytes(bytes_id)) => {
-                if let HeapData::Bytes(b1) = heap.get(*id1) {
-                    let bytes2 = interns.get_bytes(*bytes_id);
-                    let mut b = Vec::with_capacity(b1.len() + bytes2.len());
-                    b.extend_from_slice(b1);
-                    b.extend_from_slice(bytes2);
-                    Ok(Some(Self::Ref(heap.allocate(HeapData::Bytes(b.into()))?)))
-                } else {
-                    Ok(None)
-                }
+                let bytes2 = interns.get_bytes(*bytes_id);
+                let data = match heap.get(*id1) {
+                    HeapData::Bytes(b1) => HeapData::Bytes([b1.as_slice(), bytes2].concat().into()),
+                    HeapData::ByteArray(b1) => HeapData::ByteArray([b1.as_slice(), bytes2].concat().into()),
+                    _ => return Ok(None),
+                };
+                Ok(Some(Self::Ref(heap.allocate(data)?)))
             }
             _ => Ok(None),
         }
@@ -713,6 +717,16 @@ impl PyTrait for Value {
         _self_id: Option<HeapId>,
         interns: &Interns,
     ) -> Result<bool, crate::resource::ResourceError> {
+        // `str` and `bytes` are immutable, so extending one in place is only safe when the
+        // `+=` target and the operand on the VM stack are its sole holders. Anything else
+        // (an alias, or a string shared via the heap's small-string cache) gets a new object.
+        if let Self::Ref(id) = self
+            && matches!(heap.get(*id), HeapData::Str(_) | HeapData::Bytes(_))
+            && heap.get_refcount(*id) > 2
+        {
+            other.drop_with_heap(heap);
+            return Ok(false);
+        }
         match (&self, &other) {
             (Self::Int(v1), Self::Int(v2)) => {
                 if let Some(result) = v1.checked_add(*v2) {
@@ -747,7 +761,7 @@ impl PyTrait for Value {
             }
             (Self::Ref(id1), Self::InternString(string_id)) => {
                 if let HeapData::Str(s1) = heap.get_mut(*id1) {
-                    s1.as_string_mut().push_str(interns.get_str(*string_id));
+                    s1.push_str(interns.get_str(*string_id));
                     Ok(true)
                 } else {
                     Ok(false)
@@ -764,7 +778,7 @@ impl PyTrait for Value {
                 Ok(true)
             }
             (Self::InternBytes(bytes_id), Self::Ref(id2)) => {
-                let result = if let HeapData::Bytes(b2) = heap.get(*id2) {
+                let result = if let Some(b2) = heap.get(*id2).as_bytes_like() {
                     let bytes1 = interns.get_bytes(*bytes_id);
                     let mut b = Vec::with_capacity(bytes1.len() + b2.len());
                     b.extend_from_slice(bytes1);
@@ -778,14 +792,17 @@ impl PyTrait for Value {
                 other.drop_with_heap(heap);
                 Ok(result)
             }
-            (Self::Ref(id1), Self::InternBytes(bytes_id)) => {
-                if let HeapData::Bytes(b1) = heap.get_mut(*id1) {
+            (Self::Ref(id1), Self::InternBytes(bytes_id)) => match heap.get_mut(*id1) {
+                HeapData::Bytes(b1) => {
                     b1.as_vec_mut().extend_from_slice(interns.get_bytes(*bytes_id));
                     Ok(true)
-                } else {
-                    Ok(false)
                 }
-            }
+                HeapData::ByteArray(b1) => {
+                    b1.as_vec_mut().extend_from_slice(interns.get_bytes(*bytes_id));
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
             (Self::Ref(id), Self::Ref(_)) => {
                 heap.with_entry_mut(*id, |heap, data| data.py_iadd(other, heap, Some(*id), interns))
             }
@@ -1475,6 +1492,23 @@ impl PyTrait for Value {
             ))),
         }
     }
+
+    fn py_delitem(&mut self, key: Self, heap: &mut Heap<impl ResourceTracker>, interns: &Interns) -> RunResult<()> {
+        match self {
+            Self::Ref(id) => {
+                let id = *id;
+                heap.with_entry_mut(id, |heap, data| data.py_delitem(key, heap, interns))
+            }
+            Self::InternString(_) | Self::InternBytes(_) => {
+                key.drop_with_heap(heap);
+                Err(ExcType::type_error_item_deletion(self.py_type(heap)))
+            }
+            _ => {
+                key.drop_with_heap(heap);
+                Err(ExcType::type_error_not_sub_deletion(self.py_type(heap)))
+            }
+        }
+    }
 }
 
 impl Value {
@@ -1641,6 +1675,14 @@ impl Value {
     ) -> RunResult<bool> {
         match self {
             Self::Ref(heap_id) => {
+                // Substring checks only need shared access, and must not take the entry
+                // out of the heap since the item may be the container itself (`s in s`).
+                match heap.get(*heap_id) {
+                    HeapData::Str(s) => return str_contains(s.as_str(), item, heap, interns),
+                    HeapData::Bytes(b) => return bytes_contains(b.as_slice(), item, heap, interns),
+                    HeapData::ByteArray(b) => return bytes_contains(b.as_slice(), item, heap, interns),
+                    _ => {}
+                }
                 // Use with_entry_mut to temporarily take ownership of the container.
                 // This allows iterating over container elements while calling py_eq
                 // (which needs &mut Heap for comparing nested heap values).
@@ -1663,10 +1705,19 @@ impl Value {
                         }
                         Ok(false)
                     }
+                    HeapData::Deque(deque) => {
+                        let mut guard = DepthGuard::default();
+                        for el in deque.iter() {
+                            if item.py_eq(el, heap, &mut guard, interns)? {
+                                return Ok(true);
+                            }
+                        }
+                        Ok(false)
+                    }
                     HeapData::Dict(dict) => dict.get(item, heap, interns).map(|m| m.is_some()),
+                    HeapData::DictView(view) => view.contains(item, heap, interns),
                     HeapData::Set(set) => set.contains(item, heap, interns),
                     HeapData::FrozenSet(fset) => fset.contains(item, heap, interns),
-                    HeapData::Str(s) => str_contains(s.as_str(), item, heap, interns),
                     HeapData::Range(range) => {
                         // Range containment is O(1) - check bounds and step alignment
                         let n = match item {
@@ -1705,6 +1756,10 @@ impl Value {
                 let container_str = interns.get_str(*string_id);
                 str_contains(container_str, item, heap, interns)
             }
+            Self::InternBytes(bytes_id) => {
+                let container_bytes = interns.get_bytes(*bytes_id);
+                bytes_contains(container_bytes, item, heap, interns)
+            }
             _ => {
                 let type_name = self.py_type(heap);
                 Err(ExcType::type_error(format!(
@@ -1727,6 +1782,14 @@ impl Value {
     ) -> RunResult<AttrCallResult> {
         match self {
             Self::Ref(heap_id) => {
+                // External objects forward attribute reads to the host
+                if let HeapData::ExternalObject(obj) = heap.get(*heap_id) {
+                    let result = obj.getattr(*heap_id, name_id, interns);
+                    if result.is_ok() {
+                        heap.inc_ref(*heap_id);
+                    }
+                    return result;
+                }
                 // Use with_entry_mut to get access to both data and heap without borrow conflicts.
                 // This allows py_getattr to allocate (for computed attributes) while we hold the data.
                 let opt_result = heap.with_entry_mut(*heap_id, |heap, data| data.py_getattr(name_id, heap, interns))?;
@@ -1742,6 +1805,20 @@ impl Value {
                     return Ok(AttrCallResult::Value(Self::Ref(str_id)));
                 }
             }
+            Self::Builtin(Builtins::ExcType(exc_type)) => {
+                if name_id == StaticStrings::DunderName {
+                    let name_str = exc_type.to_string();
+                    let str_id = heap.allocate(HeapData::Str(Str::from(name_str)))?;
+                    return Ok(AttrCallResult::Value(Self::Ref(str_id)));
+                }
+            }
+            Self::Builtin(Builtins::ExcClass(class_id)) => {
+                if name_id == StaticStrings::DunderName {
+                    return Ok(AttrCallResult::Value(Self::InternString(
+                        interns.get_exc_class(*class_id).name,
+                    )));
+                }
+            }
             _ => {}
         }
         let type_name = self.py_type(heap);
@@ -1851,8 +1928,10 @@ impl Value {
 
     /// Performs a binary bitwise operation on two values.
     ///
-    /// Python only supports bitwise operations on integers (and bools, which coerce to int).
-    /// Returns a `TypeError` if either operand is not an integer, bool, or LongInt.
+    /// Python only supports bitwise operations on integers (and bools, which coerce to int,
+    /// except that `&`, `|` and `^` between two bools produce a bool).
+    /// Returns `Ok(None)` if either operand is not an integer, bool, or LongInt, so the
+    /// caller can raise a `TypeError` naming the operator it ran.
     ///
     /// For shift operations:
     /// - Negative shift counts raise `ValueError`
@@ -1863,10 +1942,16 @@ impl Value {
         other: &Self,
         op: BitwiseOp,
         heap: &mut Heap<impl ResourceTracker>,
-    ) -> Result<Self, RunError> {
-        // Capture types for error messages
-        let lhs_type = self.py_type(heap);
-        let rhs_type = other.py_type(heap);
+    ) -> Result<Option<Self>, RunError> {
+        // `&`, `|` and `^` on two bools return a bool, matching `bool.__and__` etc.
+        if let (Self::Bool(l), Self::Bool(r)) = (self, other) {
+            match op {
+                BitwiseOp::And => return Ok(Some(Self::Bool(*l & *r))),
+                BitwiseOp::Or => return Ok(Some(Self::Bool(*l | *r))),
+                BitwiseOp::Xor => return Ok(Some(Self::Bool(*l ^ *r))),
+                BitwiseOp::LShift | BitwiseOp::RShift => {}
+            }
+        }
 
         // Extract BigInt from all numeric types
         let lhs_bigint = extract_bigint(self, heap);
@@ -1922,9 +2007,9 @@ impl Value {
                 }
             };
             // Convert result back to Value, demoting to i64 if it fits
-            LongInt::new(result).into_value(heap).map_err(Into::into)
+            Ok(Some(LongInt::new(result).into_value(heap)?))
         } else {
-            Err(ExcType::binary_type_error(op.as_str(), lhs_type, rhs_type))
+            Ok(None)
         }
     }
 
@@ -2051,6 +2136,48 @@ impl Value {
         }
     }
 
+    /// Returns whether this value can be called, for arguments that must be callable
+    /// (e.g. `functools.partial()`'s or `defaultdict()`'s).
+    pub fn is_callable(&self, heap: &Heap<impl ResourceTracker>) -> bool {
+        match self {
+            Self::Builtin(_) | Self::ModuleFunction(_) | Self::DefFunction(_) | Self::ExtFunction(_) => true,
+            Self::Ref(id) => matches!(
+                heap.get(*id),
+                HeapData::Closure(..)
+                    | HeapData::FunctionDefaults(..)
+                    | HeapData::Partial(_)
+                    | HeapData::LruCache(_)
+                    | HeapData::NamedTupleType(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns the contents of a `bytes` or `bytearray` value (interned or heap-allocated).
+    ///
+    /// Returns `None` for all other types, including `str`.
+    pub fn as_bytes_like<'a>(&'a self, heap: &'a Heap<impl ResourceTracker>, interns: &'a Interns) -> Option<&'a [u8]> {
+        match self {
+            Self::InternBytes(id) => Some(interns.get_bytes(*id)),
+            Self::Ref(heap_id) => heap.get(*heap_id).as_bytes_like(),
+            _ => None,
+        }
+    }
+
+    /// Borrows the contents of a `str` value (interned or heap-allocated) without copying.
+    ///
+    /// Returns `None` for all other types.
+    pub fn as_str_ref<'a>(&'a self, heap: &'a Heap<impl ResourceTracker>, interns: &'a Interns) -> Option<&'a str> {
+        match self {
+            Self::InternString(id) => Some(interns.get_str(*id)),
+            Self::Ref(heap_id) => match heap.get(*heap_id) {
+                HeapData::Str(s) => Some(s.as_str()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// check if the value is a string.
     pub fn is_str(&self, heap: &Heap<impl ResourceTracker>) -> bool {
         match self {
@@ -2171,6 +2298,17 @@ impl BitwiseOp {
             Self::RShift => ">>",
         }
     }
+
+    /// Returns the in-place operator symbol for error messages.
+    pub fn as_inplace_str(self) -> &'static str {
+        match self {
+            Self::And => "&=",
+            Self::Or => "|=",
+            Self::Xor => "^=",
+            Self::LShift => "<<=",
+            Self::RShift => ">>=",
+        }
+    }
 }
 
 /// Marker values for special objects that exist but have minimal functionality.
@@ -2302,6 +2440,48 @@ pub(crate) fn floor_divmod(a: i64, b: i64) -> Option<(i64, i64)> {
     }
 }
 
+/// Writes the Python repr() of a float.
+///
+/// Like CPython this uses the shortest digits that round-trip, switching to scientific notation
+/// when the exponent is below -4 or at least 16 (e.g. `1e-05`, `1e+16`), and always including a
+/// decimal point otherwise.
+pub(crate) fn float_repr_fmt(v: f64, f: &mut impl Write) -> std::fmt::Result {
+    if v.is_nan() {
+        return f.write_str("nan");
+    }
+    if v.is_sign_negative() {
+        f.write_char('-')?;
+    }
+    if v.is_infinite() {
+        return f.write_str("inf");
+    }
+    // Rust's `{:e}` gives the shortest round-trip digits, e.g. `1.2345e2`
+    let sci = format!("{:e}", v.abs());
+    let (mantissa, exponent) = sci.split_once('e').expect("`{:e}` always writes an exponent");
+    let exponent: i32 = exponent.parse().expect("`{:e}` writes a valid exponent");
+    let digits = mantissa.replace('.', "");
+    if !(-4..16).contains(&exponent) {
+        let (first, rest) = digits.split_at(1);
+        f.write_str(first)?;
+        if !rest.is_empty() {
+            write!(f, ".{rest}")?;
+        }
+        let sign = if exponent < 0 { '-' } else { '+' };
+        write!(f, "e{sign}{:02}", exponent.abs())
+    } else if exponent < 0 {
+        let zeros = usize::try_from(-exponent - 1).expect("exponent is negative");
+        write!(f, "0.{}{digits}", "0".repeat(zeros))
+    } else {
+        let int_len = usize::try_from(exponent + 1).expect("exponent is positive");
+        if digits.len() > int_len {
+            let (int_part, fraction) = digits.split_at(int_len);
+            write!(f, "{int_part}.{fraction}")
+        } else {
+            write!(f, "{digits}{}.0", "0".repeat(int_len - digits.len()))
+        }
+    }
+}
+
 /// Converts a heap `HeapId` into its tagged `id()` value, ensuring it never collides with other spaces.
 #[inline]
 pub fn heap_tagged_id(heap_id: HeapId) -> usize {
@@ -2445,7 +2625,7 @@ fn extract_bigint(value: &Value, heap: &Heap<impl ResourceTracker>) -> Option<Bi
 fn str_contains(
     container_str: &str,
     item: &Value,
-    heap: &mut Heap<impl ResourceTracker>,
+    heap: &Heap<impl ResourceTracker>,
     interns: &Interns,
 ) -> RunResult<bool> {
     match item {
@@ -2453,15 +2633,43 @@ fn str_contains(
             let item_str = interns.get_str(*item_id);
             Ok(container_str.contains(item_str))
         }
-        Value::Ref(item_heap_id) => {
-            if let HeapData::Str(item_str) = heap.get(*item_heap_id) {
-                Ok(container_str.contains(item_str.as_str()))
-            } else {
-                Err(ExcType::type_error("'in <str>' requires string as left operand"))
-            }
+        Value::Ref(item_heap_id) => match heap.get(*item_heap_id) {
+            HeapData::Str(item_str) => Ok(container_str.contains(item_str.as_str())),
+            other => Err(ExcType::type_error_str_contains(other.py_type(heap))),
+        },
+        _ => Err(ExcType::type_error_str_contains(item.py_type(heap))),
+    }
+}
+
+/// Checks if a bytes or bytearray object contains an integer byte value or a subsequence.
+///
+/// Matches CPython: an int item must be in `range(0, 256)` (otherwise `ValueError`),
+/// any other item must be bytes-like (otherwise `TypeError`).
+fn bytes_contains(
+    container: &[u8],
+    item: &Value,
+    heap: &Heap<impl ResourceTracker>,
+    interns: &Interns,
+) -> RunResult<bool> {
+    let needle: &[u8] = match item {
+        Value::Int(i) => {
+            let byte = u8::try_from(*i).map_err(|_| ExcType::value_error_byte_range())?;
+            return Ok(container.contains(&byte));
         }
-        _ => Err(ExcType::type_error("'in <str>' requires string as left operand")),
+        Value::Bool(b) => return Ok(container.contains(&u8::from(*b))),
+        Value::InternBytes(id) => interns.get_bytes(*id),
+        Value::Ref(id) => match heap.get(*id) {
+            HeapData::Bytes(b) => b.as_slice(),
+            HeapData::ByteArray(b) => b.as_slice(),
+            HeapData::LongInt(_) => return Err(ExcType::value_error_byte_range()),
+            other => return Err(ExcType::type_error_bytes_like_required(other.py_type(heap))),
+        },
+        other => return Err(ExcType::type_error_bytes_like_required(other.py_type(heap))),
+    };
+    if needle.is_empty() {
+        return Ok(true);
     }
+    Ok(container.windows(needle.len()).any(|window| window == needle))
 }
 
 /// Computes the number of significant bits in an i64.
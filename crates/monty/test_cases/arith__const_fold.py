@@ -0,0 +1,87 @@
+# Constant expressions are folded at compile time; results must match runtime evaluation
+
+# === ints ===
+assert 60 * 60 * 24 == 86400, 'fold mult chain'
+assert -5 // 2 == -3, 'fold floordiv negative'
+assert -5 % 3 == 1, 'fold mod negative'
+assert 5 % -3 == -1, 'fold mod negative divisor'
+assert 2**10 == 1024, 'fold pow'
+assert 2**-1 == 0.5, 'negative exponent not folded to int'
+assert 1 << 62 == 4611686018427387904, 'fold lshift'
+assert 1 << 64 == 18446744073709551616, 'lshift overflow promotes'
+assert -1 >> 100 == -1, 'rshift large'
+assert 9223372036854775807 + 1 == 9223372036854775808, 'add overflow promotes'
+assert -(-9223372036854775807 - 1) == 9223372036854775808, 'neg overflow promotes'
+assert ~5 == -6, 'fold invert'
+assert 7 / 2 == 3.5, 'fold true division'
+assert 9007199254740993 / 1 == 9007199254740992.0, 'inexact true division'
+assert (1 | 6) ^ 3 & 5 == 6, 'fold bitwise'
+
+# === floats ===
+assert 1.5 * 2 == 3.0, 'fold float mult'
+assert 1 + 0.5 == 1.5, 'fold int float add'
+assert -2.5 == 0 - 2.5, 'fold float neg'
+assert +3 == 3, 'fold unary plus'
+
+# === bool ops ===
+assert (0 or 5) == 5, 'fold or'
+assert (3 and 0) == 0, 'fold and'
+assert (not '') is True, 'fold not empty str'
+assert (not b'x') is False, 'fold not bytes'
+assert (None or 'x') == 'x', 'fold or none'
+
+# === errors still raised at runtime ===
+try:
+    1 // 0
+    raise AssertionError('floordiv by zero should raise')
+except ZeroDivisionError:
+    pass
+
+try:
+    1.0 / 0
+    raise AssertionError('float div by zero should raise')
+except ZeroDivisionError:
+    pass
+
+try:
+    1 >> -1
+    raise AssertionError('negative shift should raise')
+except ValueError:
+    pass
+
+# === dead branches ===
+x = 0
+if False:
+    x = 1
+elif 0:
+    x = 2
+else:
+    x = 3
+assert x == 3, 'constant if chain'
+
+if 1 + 1 == 2 and True:
+    x = 4
+assert x == 4, 'non-literal comparison still evaluated'
+
+count = 0
+while True:
+    count += 1
+    if count == 3:
+        break
+else:
+    count = 100
+assert count == 3, 'while True with break skips else'
+
+while False:
+    count = -1
+else:
+    count += 10
+assert count == 13, 'while False runs only else'
+
+assert ('yes' if 1 else 'no') == 'yes', 'constant ternary'
+assert ('yes' if 0 else 'no') == 'no', 'constant ternary false'
+
+# === f-string literal fragments ===
+name = 'monty'
+assert 'hi ' f'{name}' ' and ' 'bye' == 'hi monty and bye', 'merged fstring literals'
+assert f'a' 'b' f'c' == 'abc', 'all literal fstring'
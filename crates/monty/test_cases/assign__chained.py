@@ -0,0 +1,79 @@
+# === Simple chained assignment ===
+a = b = 0
+assert a == 0 and b == 0, 'two names'
+
+x = y = z = 'v'
+assert (x, y, z) == ('v', 'v', 'v'), 'three names'
+
+# === All targets share the same object ===
+first = second = []
+first.append(1)
+assert second == [1], 'mutation visible through both names'
+assert first is second, 'targets are the same object'
+
+# === Value is evaluated once ===
+calls = []
+
+
+def make():
+    calls.append(1)
+    return len(calls)
+
+
+p = q = make()
+assert (p, q) == (1, 1), 'both targets get the single result'
+assert calls == [1], 'value evaluated once'
+
+# === Tuple unpacking targets ===
+pair = (m, n) = (1, 2)
+assert pair == (1, 2), 'whole value target'
+assert (m, n) == (1, 2), 'unpacked target'
+
+head, *tail = [h2, *t2] = [1, 2, 3]
+assert head == 1 and tail == [2, 3], 'starred first target'
+assert h2 == 1 and t2 == [2, 3], 'starred list target'
+
+# === Subscript and attribute targets ===
+d = {}
+lst = [0, 0]
+d['k'] = lst[1] = v = 5
+assert d == {'k': 5}, 'dict subscript target'
+assert lst == [0, 5], 'list subscript target'
+assert v == 5, 'name target after subscripts'
+
+# === Targets are assigned left to right ===
+i = 0
+items = [10, 20]
+i = items[i] = 1
+assert items == [10, 1], 'later target sees earlier assignment'
+
+
+# === Chained assignment in functions and closures ===
+def local_chain():
+    u = w = [0]
+    u[0] += 1
+    return w
+
+
+assert local_chain() == [1], 'chained locals share object'
+
+
+def outer():
+    c1 = c2 = 'start'
+
+    def inner():
+        return c1 + c2
+
+    return inner()
+
+
+assert outer() == 'startstart', 'chained names captured by closure'
+
+
+def global_chain():
+    global g1, g2
+    g1 = g2 = 'g'
+
+
+global_chain()
+assert g1 == 'g' and g2 == 'g', 'chained globals'
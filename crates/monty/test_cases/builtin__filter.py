@@ -0,0 +1,49 @@
+assert list(filter(None, [0, 1, '', 'a', [], [2], None])) == [1, 'a', [2]], 'filter None keeps truthy items'
+assert list(filter(None, [])) == [], 'filter None on empty list'
+
+
+def is_even(x):
+    return x % 2 == 0
+
+
+assert list(filter(is_even, range(7))) == [0, 2, 4, 6], 'filter with custom function'
+assert list(filter(lambda x: x > 1, [1, 2, 3])) == [2, 3], 'filter with lambda'
+assert list(filter(len, ['', 'a', 'bc'])) == ['a', 'bc'], 'filter with builtin function'
+assert list(filter(bool, (0, 1, 2))) == [1, 2], 'filter with type'
+assert list(filter(lambda c: c != 'b', 'abc')) == ['a', 'c'], 'filter a string'
+assert sorted(filter(None, {0, 1, 2})) == [1, 2], 'filter a set'
+
+# === filter returns a single-pass iterator ===
+f = filter(None, [1, 0, 2])
+assert next(f) == 1, 'next on filter'
+assert list(f) == [2], 'list of partially consumed filter'
+assert list(f) == [], 'exhausted filter'
+
+total = 0
+for x in filter(is_even, [1, 2, 3, 4]):
+    total += x
+assert total == 6, 'for loop over filter'
+
+# === errors ===
+try:
+    filter(None)
+    assert False, 'filter with one argument should raise'
+except TypeError as e:
+    assert str(e) == 'filter expected 2 arguments, got 1', 'filter arg count error'
+
+try:
+    filter(None, 1)
+    assert False, 'filter over a non-iterable should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'filter not iterable error'
+
+
+def fail(x):
+    raise ValueError('bad item')
+
+
+try:
+    list(filter(fail, [1]))
+    assert False, 'filter should propagate function errors'
+except ValueError as e:
+    assert str(e) == 'bad item', 'filter function error'
@@ -0,0 +1,91 @@
+# === Default and empty spec ===
+assert format(42) == '42', 'format int no spec'
+assert format('hi') == 'hi', 'format str no spec'
+assert format(1.5) == '1.5', 'format float no spec'
+assert format(None) == 'None', 'format None no spec'
+assert format([1, 2], '') == '[1, 2]', 'format list empty spec'
+assert format(True) == 'True', 'format bool no spec'
+
+# === Same mini-language as f-strings ===
+assert format(3.14159, '.2f') == '3.14', 'float precision'
+assert format(42, '>6') == '    42', 'int right align'
+assert format('ab', '^6') == '  ab  ', 'str center'
+assert format(-7, '05d') == '-0007', 'zero pad negative'
+assert format(0.5, '.0%') == '50%', 'percent'
+
+# === Alternate form ===
+assert format(255, '#x') == '0xff', 'hex prefix'
+assert format(255, '#X') == '0XFF', 'upper hex prefix'
+assert format(5, '#b') == '0b101', 'binary prefix'
+assert format(8, '#o') == '0o10', 'octal prefix'
+assert format(255, '#010x') == '0x000000ff', 'prefix with zero padding'
+assert format(-255, '#x') == '-0xff', 'negative hex prefix'
+assert format(255, '*>8X') == '******FF', 'uppercase hex keeps fill'
+assert format(3.0, '#.0f') == '3.', 'alternate float keeps point'
+assert format(2.5, '#g') == '2.50000', 'alternate g keeps zeros'
+
+# === Grouping ===
+assert format(1234567, ',') == '1,234,567', 'comma grouping'
+assert format(1234567, '_') == '1_234_567', 'underscore grouping'
+assert format(-1234, ',d') == '-1,234', 'negative grouping'
+assert format(123, ',') == '123', 'grouping short number'
+assert format(1234567.891, ',.2f') == '1,234,567.89', 'float grouping'
+assert format(123456789.0, ',') == '123,456,789.0', 'float repr grouping'
+assert format(0xABCDEF, '_x') == 'ab_cdef', 'hex grouping by four'
+assert format(255, '#_b') == '0b1111_1111', 'binary grouping with prefix'
+assert format(1234, '08,') == '0,001,234', 'grouped zero padding'
+assert format(1234, '010,') == '00,001,234', 'grouped zero padding wider'
+assert format(1234, '>10,') == '     1,234', 'grouping with alignment'
+assert format(10**20, ',') == '100,000,000,000,000,000,000', 'long int grouping'
+assert format(-(10**20), 'x') == '-56bc75e2d63100000', 'long int hex'
+assert format(10**20, '.2e') == '1.00e+20', 'long int exponent'
+
+# === Bools format as ints once a spec is given ===
+assert format(True, 'd') == '1', 'bool d'
+assert format(True, '>5') == '    1', 'bool aligned'
+assert format(False, '.1f') == '0.0', 'bool float'
+
+# === Floats with no type ===
+assert format(1.0, '>5') == '  1.0', 'float repr aligned'
+assert format(-1.5, '+08') == '-00001.5', 'float repr zero pad'
+assert format(1.5, '+') == '+1.5', 'float repr sign'
+assert format(-0.0, '') == '-0.0', 'negative zero'
+assert format(1e20, ',') == '1e+20', 'float repr exponent'
+assert format(1.0, '.3') == '1.0', 'no type keeps a fractional digit'
+assert format(100.0, '.3') == '1e+02', 'no type precision exponent'
+assert format(123.0, '.4') == '123.0', 'no type precision fixed'
+
+# === Special floats ===
+assert format(float('inf'), 'f') == 'inf', 'inf f'
+assert format(float('inf'), 'F') == 'INF', 'inf F'
+assert format(float('nan'), 'e') == 'nan', 'nan e'
+assert format(float('-inf'), '010') == '-000000inf', 'inf zero padded'
+assert format(1234567890.0, 'G') == '1.23457E+09', 'upper general'
+
+# === Zero flag on strings and explicit alignment ===
+assert format('ab', '05') == 'ab000', 'zero flag on str'
+assert format(5, '<05') == '50000', 'zero flag with left align'
+assert format(12, 'n') == '12', 'int n'
+assert format(1.5, 'n') == '1.5', 'float n'
+
+
+# === Errors ===
+def error(value, spec):
+    try:
+        format(value, spec)
+    except (TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+assert error(5, '.2') == 'ValueError: Precision not allowed in integer format specifier', 'int precision'
+assert error('a', ',') == "ValueError: Cannot specify ',' with 's'.", 'str grouping'
+assert error('a', '#') == 'ValueError: Alternate form (#) not allowed in string format specifier', 'str alternate'
+assert error('a', '+') == 'ValueError: Sign not allowed in string format specifier', 'str sign'
+assert error(None, '>5') == 'TypeError: unsupported format string passed to NoneType.__format__', 'None spec'
+assert error([1], 's') == 'TypeError: unsupported format string passed to list.__format__', 'list spec'
+assert error(1.5, 'd') == "ValueError: Unknown format code 'd' for object of type 'float'", 'float d'
+assert error(1, '!!') == "ValueError: Invalid format specifier '!!' for object of type 'int'", 'bad spec'
+assert error(1, 2) == 'TypeError: format() argument 2 must be str, not int', 'spec not str'
+assert error(1, '99999999999999999999') == 'ValueError: Too many decimal digits in format string', 'width overflow'
+assert error(1.5, '.99999999999999999999') == 'ValueError: Too many decimal digits in format string', 'precision overflow'
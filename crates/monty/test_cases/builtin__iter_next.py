@@ -64,3 +64,29 @@ assert next(it1) == 2, 'independent iterators: it1 second should be 2'
 assert next(it2) == 1, 'independent iterators: it2 first should be 1 (independent)'
 assert next(it1) == 3, 'independent iterators: it1 third should be 3'
 assert next(it2) == 2, 'independent iterators: it2 second should be 2'
+
+# === iter() identity and next() errors ===
+it = iter([1])
+assert iter(it) is it, 'iter on iterator returns the same object'
+assert iter(iter(it)) is it, 'nested iter on iterator returns the same object'
+
+try:
+    next([1, 2])
+    assert False, 'next on a list should raise'
+except TypeError as e:
+    assert str(e) == "'list' object is not an iterator", 'next on list error'
+
+try:
+    next(5)
+    assert False, 'next on an int should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not an iterator", 'next on int error'
+
+try:
+    next(5, 'default')
+    assert False, 'next on an int with default should still raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not an iterator", 'next on int with default error'
+
+it = iter(range(2))
+assert [next(it, None) for _ in range(3)] == [0, 1, None], 'next with default drives range iterator'
@@ -0,0 +1,88 @@
+# === key= ===
+assert min([3, -5, 2], key=abs) == 2, 'min with key'
+assert max([3, -5, 2], key=abs) == -5, 'max with key'
+assert min(3, -5, 2, key=abs) == 2, 'min of args with key'
+assert max('apple', 'fig', 'banana', key=len) == 'banana', 'max of args with key'
+assert min([3, 1, 2], key=None) == 1, 'min with key=None'
+assert max(['a', 'bb'], key=lambda s: -len(s)) == 'a', 'max with lambda key'
+
+
+def second(pair):
+    return pair[1]
+
+
+assert min([('a', 3), ('b', 1), ('c', 2)], key=second) == ('b', 1), 'min with custom key function'
+
+# the first of several equal items wins
+assert min([(1, 'a'), (1, 'b')], key=lambda p: p[0]) == (1, 'a'), 'min keeps first equal item'
+assert max([(1, 'a'), (1, 'b')], key=lambda p: p[0]) == (1, 'a'), 'max keeps first equal item'
+assert max(1, 1.0) == 1 and type(max(1, 1.0)) == int, 'max of equal args keeps first'
+
+# === default= ===
+assert min([], default=None) is None, 'min of empty with default'
+assert max([], default='none') == 'none', 'max of empty with default'
+assert min([2, 1], default=0) == 1, 'default ignored when not empty'
+assert max((), key=len, default=-1) == -1, 'max of empty with key and default'
+assert min(iter([]), default=[]) == [], 'min of empty iterator with default'
+
+# === any iterable ===
+assert min('hello') == 'e', 'min of string'
+assert max(range(5)) == 4, 'max of range'
+assert min({3: 'a', 1: 'b'}) == 1, 'min of dict keys'
+assert max(x * x for x in [-3, 2]) == 9, 'max of generator'
+
+# === errors ===
+try:
+    min()
+    assert False, 'min() should raise'
+except TypeError as e:
+    assert str(e) == 'min expected at least 1 argument, got 0', 'min no args error'
+
+try:
+    max([])
+    assert False, 'max of empty should raise'
+except ValueError as e:
+    assert str(e) == 'max() iterable argument is empty', 'max empty error'
+
+try:
+    min(1, 2, default=0)
+    assert False, 'default with multiple args should raise'
+except TypeError as e:
+    assert str(e) == 'Cannot specify a default for min() with multiple positional arguments', 'default error'
+
+try:
+    max([1], foo=1)
+    assert False, 'unknown keyword should raise'
+except TypeError as e:
+    assert str(e) == "max() got an unexpected keyword argument 'foo'", 'unknown keyword error'
+
+try:
+    min(1, 'a')
+    assert False, 'mixed types should raise'
+except TypeError as e:
+    assert str(e) == "'<' not supported between instances of 'str' and 'int'", 'min compare error'
+
+try:
+    max([1, 'a'])
+    assert False, 'mixed types should raise'
+except TypeError as e:
+    assert str(e) == "'>' not supported between instances of 'str' and 'int'", 'max compare error'
+
+try:
+    min([1, 2], key=1)
+    assert False, 'non-callable key should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not callable", 'non-callable key error'
+
+try:
+    max(1)
+    assert False, 'max of non-iterable should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'max not iterable error'
+
+# === pow() with a negative exponent and modulus ===
+assert pow(2, -1, 5) == 3, 'modular inverse'
+assert pow(3, -2, 7) == 4, 'modular inverse squared'
+assert pow(-2, -1, 5) == 2, 'modular inverse of negative base'
+assert pow(2, -1, -5) == -2, 'modular inverse with negative modulus'
+assert pow(4, -1, 1) == 0, 'modular inverse modulo 1'
@@ -146,3 +146,44 @@ assert list(zip([1, 2, 3])) == [(1,), (2,), (3,)], 'zip single iterable'
 # zip with empty
 assert list(zip([1, 2], [])) == [], 'zip with empty second'
 assert list(zip([], [1, 2])) == [], 'zip with empty first'
+
+# === enumerate(), zip() and map() return iterators ===
+e = enumerate(['a', 'b', 'c'])
+assert next(e) == (0, 'a'), 'next on enumerate'
+assert list(e) == [(1, 'b'), (2, 'c')], 'list of partially consumed enumerate'
+assert list(e) == [], 'exhausted enumerate'
+
+z = zip([1, 2, 3], 'ab')
+assert next(z) == (1, 'a'), 'next on zip'
+assert list(z) == [(2, 'b')], 'list of partially consumed zip'
+assert list(zip()) == [], 'zip with no arguments'
+
+m = map(abs, [-1, -2])
+assert next(m) == 1, 'next on map'
+assert list(m) == [2], 'list of partially consumed map'
+
+# enumerate and zip pull items lazily from their iterables
+it = iter([1, 2, 3, 4])
+pairs = zip(it, it)
+assert next(pairs) == (1, 2), 'zip of the same iterator takes consecutive items'
+assert next(it) == 3, 'zip leaves the rest of the iterator alone'
+assert list(pairs) == [], 'zip stops when the shared iterator runs out'
+
+items = [1, 2]
+e = enumerate(items)
+items.append(3)
+assert list(e) == [(0, 1), (1, 2), (2, 3)], 'enumerate sees items appended after creation'
+
+# nested and chained iterators
+assert list(enumerate(zip('ab', 'cd'), 1)) == [(1, ('a', 'c')), (2, ('b', 'd'))], 'enumerate over zip'
+assert {k: v for k, v in zip(['k1', 'k2'], [1, 2])} == {'k1': 1, 'k2': 2}, 'dict comprehension over zip'
+assert sum(x * y for x, y in zip([1, 2], [3, 4])) == 11, 'zip in generator expression'
+
+# consumers accept any iterator
+it = iter([3, 1, 2])
+next(it)
+assert list(it) == [1, 2], 'list of partially consumed iterator'
+assert sorted(iter([2, 1])) == [1, 2], 'sorted over iterator'
+assert tuple(iter('ab')) == ('a', 'b'), 'tuple over iterator'
+assert sum(iter([1, 2, 3])) == 6, 'sum over iterator'
+assert [x * 2 for x in iter([1, 2])] == [2, 4], 'comprehension over iterator'
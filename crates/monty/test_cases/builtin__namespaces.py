@@ -0,0 +1,109 @@
+# === globals() ===
+x = 1
+items = [1, 2]
+g = globals()
+assert g['x'] == 1, 'globals sees module variables'
+assert g['items'] is items, 'globals holds the same objects'
+assert 'not_defined_yet' not in g, 'globals only has bound names'
+
+[i for i in range(3)]
+assert 'i' not in globals(), 'comprehension variables do not leak into globals'
+
+
+def setter():
+    global late
+    late = 5
+
+
+setter()
+assert globals()['late'] == 5, 'globals sees names only assigned by functions'
+
+
+def read_globals():
+    return globals()['x']
+
+
+assert read_globals() == 1, 'globals inside a function is the module namespace'
+
+# === locals() ===
+assert locals()['x'] == 1, 'locals at module level are the globals'
+
+
+def f(a, b=2, *args, **kwargs):
+    c = a + b
+    return locals()
+
+
+assert f(1) == {'a': 1, 'b': 2, 'args': (), 'kwargs': {}, 'c': 3}, 'locals includes parameters'
+
+
+def unused_param(a):
+    return locals()
+
+
+assert unused_param(7) == {'a': 7}, 'locals includes parameters the body never reads'
+
+
+def outer():
+    captured = 10
+
+    def inner():
+        return captured, locals()
+
+    return inner()
+
+
+assert outer() == (10, {'captured': 10}), 'locals includes captured variables'
+
+
+def snapshot():
+    value = 1
+    loc = locals()
+    loc['value'] = 2
+    return value
+
+
+assert snapshot() == 1, 'writing to the locals dict does not change variables'
+
+
+def unbound():
+    before = locals()
+    later = 1
+    return before
+
+
+assert unbound() == {}, 'locals skips unbound variables'
+
+# === vars() ===
+
+
+def vars_no_args(n):
+    return vars()
+
+
+assert vars_no_args(3) == {'n': 3}, 'vars without arguments is locals'
+
+import math
+
+assert vars(math)['pi'] == math.pi, 'vars of a module'
+
+try:
+    vars(1)
+    assert False, 'vars of an int should raise'
+except TypeError as e:
+    assert str(e) == 'vars() argument must have __dict__ attribute', str(e)
+
+# === dir() ===
+
+
+def dir_in_function(b, a):
+    z = 1
+    return dir()
+
+
+assert dir_in_function(1, 2) == ['a', 'b', 'z'], 'dir lists sorted local names'
+module_names = dir()
+assert 'x' in module_names, 'dir at module level lists globals'
+assert 'setter' in module_names, 'dir lists functions'
+assert module_names == sorted(module_names), 'dir is sorted'
+assert 'pi' in dir(math), 'dir of a module'
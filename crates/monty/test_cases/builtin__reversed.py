@@ -0,0 +1,86 @@
+# === reversed() returns a lazy iterator ===
+r = reversed([1, 2, 3])
+assert iter(r) is r, 'reversed object is its own iterator'
+assert next(r) == 3, 'first item is the last element'
+assert list(r) == [2, 1], 'remaining items'
+assert list(r) == [], 'exhausted reversed iterator stays empty'
+
+# mutation is visible because the list is read lazily
+lst = [1, 2, 3, 4]
+r = reversed(lst)
+assert next(r) == 4, 'reversed list first item'
+lst[0] = 10
+assert list(r) == [3, 2, 10], 'reversed sees item assignment'
+
+lst = [1, 2, 3]
+r = reversed(lst)
+lst.append(4)
+assert list(r) == [3, 2, 1], 'items appended after reversed() are not visited'
+
+lst = [1, 2, 3, 4]
+r = reversed(lst)
+assert next(r) == 4, 'reversed before shrink'
+lst.clear()
+assert list(r) == [], 'shrunk list ends the reversed iteration'
+
+# === sequences ===
+assert list(reversed((1, 2, 3))) == [3, 2, 1], 'reversed tuple'
+assert list(reversed(())) == [], 'reversed empty tuple'
+assert list(reversed('héllo')) == ['o', 'l', 'l', 'é', 'h'], 'reversed non-ascii string'
+assert ''.join(reversed('abc')) == 'cba', 'join reversed string'
+assert list(reversed(b'abc')) == [99, 98, 97], 'reversed bytes'
+assert list(reversed(bytearray(b'xy'))) == [121, 120], 'reversed bytearray'
+
+# === ranges ===
+assert list(reversed(range(5))) == [4, 3, 2, 1, 0], 'reversed range'
+assert list(reversed(range(1, 10, 3))) == [7, 4, 1], 'reversed range with step'
+assert list(reversed(range(10, 0, -3))) == [1, 4, 7, 10], 'reversed negative step range'
+assert list(reversed(range(0))) == [], 'reversed empty range'
+assert list(range(10, 0, -2)) == [10, 8, 6, 4, 2], 'negative step range'
+assert list(range(0, -5, -1)) == [0, -1, -2, -3, -4], 'negative step to negative stop'
+assert list(range(0, 5, -1)) == [], 'negative step with stop above start is empty'
+
+countdown = []
+for i in range(3, 0, -1):
+    countdown.append(i)
+assert countdown == [3, 2, 1], 'descending for loop'
+
+total = 0
+for i in reversed(range(1, 4)):
+    total = total * 10 + i
+assert total == 321, 'for loop over reversed range'
+
+# === dicts ===
+d = {'a': 1, 'b': 2, 'c': 3}
+assert list(reversed(d)) == ['c', 'b', 'a'], 'reversed dict yields keys'
+assert list(reversed(d.keys())) == ['c', 'b', 'a'], 'reversed dict keys'
+assert list(reversed(d.values())) == [3, 2, 1], 'reversed dict values'
+assert list(reversed(d.items())) == [('c', 3), ('b', 2), ('a', 1)], 'reversed dict items'
+
+r = reversed(d)
+next(r)
+d['d'] = 4
+try:
+    next(r)
+    assert False, 'dict size change should raise'
+except RuntimeError as e:
+    assert str(e) == 'dictionary changed size during iteration', 'dict changed size message'
+
+# === errors ===
+try:
+    reversed({1, 2})
+    assert False, 'reversed(set) should raise'
+except TypeError as e:
+    assert str(e) == "'set' object is not reversible", 'set not reversible message'
+
+try:
+    reversed(5)
+    assert False, 'reversed(int) should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not reversible", 'int not reversible message'
+
+try:
+    reversed(iter([1, 2]))
+    assert False, 'reversed(iterator) should raise'
+except TypeError:
+    pass
@@ -0,0 +1,130 @@
+def error(f):
+    try:
+        f()
+    except (IndexError, TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+# === construction ===
+assert bytearray() == bytearray(b''), 'empty bytearray'
+assert bytearray(3) == bytearray(b'\x00\x00\x00'), 'bytearray from count'
+assert bytearray(b'abc') == b'abc', 'bytearray from bytes'
+assert bytearray([104, 105]) == b'hi', 'bytearray from list of ints'
+assert bytearray(range(97, 100)) == b'abc', 'bytearray from range'
+assert bytearray(bytearray(b'xy')) == b'xy', 'bytearray from bytearray'
+assert bytes(bytearray(b'q')) == b'q', 'bytes from bytearray'
+assert error(lambda: bytearray('x')) == 'TypeError: string argument without an encoding', 'str without encoding'
+assert error(lambda: bytearray(1.0)) == "TypeError: cannot convert 'float' object to bytearray", 'float arg'
+assert error(lambda: bytearray(-1)) == 'ValueError: negative count', 'negative count'
+assert error(lambda: bytearray([300])) == 'ValueError: byte must be in range(0, 256)', 'item out of range'
+assert error(lambda: bytearray([1, 'a'])) == "TypeError: 'str' object cannot be interpreted as an integer", 'non-int item'
+
+# === repr and type ===
+assert repr(bytearray(b'ab')) == "bytearray(b'ab')", 'repr'
+assert repr(bytearray(b"a'b")) == 'bytearray(b"a\\\'b")', 'repr with quote'
+assert str(bytearray(b'ab')) == "bytearray(b'ab')", 'str is repr'
+assert type(bytearray()) == bytearray, 'type'
+assert error(lambda: hash(bytearray())) == "TypeError: unhashable type: 'bytearray'", 'unhashable'
+
+# === comparison and operators ===
+assert bytearray(b'ab') == b'ab', 'eq bytes'
+assert b'ab' == bytearray(b'ab'), 'bytes eq bytearray'
+assert bytearray(b'ab') != b'ac', 'ne bytes'
+assert bytearray(b'ab') < b'b', 'lt bytes'
+assert b'b' > bytearray(b'ab'), 'bytes gt bytearray'
+assert bytearray(b'ab') + b'c' == bytearray(b'abc'), 'bytearray + bytes'
+assert type(bytearray(b'ab') + b'c') == bytearray, 'bytearray + bytes is bytearray'
+assert type(b'c' + bytearray(b'ab')) == bytes, 'bytes + bytearray is bytes'
+assert bytearray(b'a') * 3 == b'aaa', 'repetition'
+assert type(bytearray(b'a') * 3) == bytearray, 'repetition type'
+assert len(bytearray(b'abc')) == 3, 'len'
+assert not bytearray(), 'empty is falsy'
+assert 113 in bytearray(b'q'), 'int in bytearray'
+assert b'q' in bytearray(b'xq'), 'bytes in bytearray'
+assert bytearray(b'x') in bytearray(b'xq'), 'bytearray in bytearray'
+assert list(bytearray(b'ab')) == [97, 98], 'iteration'
+
+ba = bytearray(b'ab')
+ba += b'cd'
+assert ba == b'abcd', 'iadd bytes'
+
+# === indexing ===
+ba = bytearray(b'abc')
+assert ba[0] == 97, 'getitem'
+assert ba[-1] == 99, 'getitem negative'
+assert ba[::-1] == bytearray(b'cba'), 'slice'
+assert type(ba[1:]) == bytearray, 'slice type'
+assert error(lambda: ba[5]) == 'IndexError: bytearray index out of range', 'getitem out of range'
+assert error(lambda: ba['x']) == 'TypeError: bytearray indices must be integers or slices, not str', 'bad index'
+ba[0] = 65
+assert ba == b'Abc', 'setitem'
+ba[-1] = 67
+assert ba == b'AbC', 'setitem negative'
+
+
+def set_300():
+    ba[0] = 300
+
+
+assert error(set_300) == 'ValueError: byte must be in range(0, 256)', 'setitem out of range'
+
+b = bytearray(b'abcdef')
+del b[0]
+assert b == b'bcdef', 'del index'
+del b[::2]
+assert b == b'ce', 'del extended slice'
+
+# === mutating methods ===
+ba = bytearray(b'ab')
+ba.append(99)
+assert ba == b'abc', 'append'
+ba.extend(b'de')
+assert ba == b'abcde', 'extend bytes'
+ba.extend([102])
+assert ba == b'abcdef', 'extend list'
+ba.extend(ba)
+assert ba == b'abcdefabcdef', 'extend self'
+ba.insert(0, 122)
+assert ba[0] == 122, 'insert'
+ba.insert(100, 121)
+assert ba[-1] == 121, 'insert past end appends'
+assert ba.pop() == 121, 'pop'
+assert ba.pop(0) == 122, 'pop index'
+ba.remove(97)
+assert ba == b'bcdefabcdef', 'remove'
+ba.reverse()
+assert ba == b'fedcbafedcb', 'reverse'
+c = ba.copy()
+ba.clear()
+assert ba == b'', 'clear'
+assert c == b'fedcbafedcb', 'copy is independent'
+assert error(lambda: bytearray().pop()) == 'IndexError: pop from empty bytearray', 'pop empty'
+assert error(lambda: bytearray(b'a').pop(5)) == 'IndexError: pop index out of range', 'pop out of range'
+assert error(lambda: bytearray(b'a').remove(98)) == 'ValueError: value not found in bytearray', 'remove missing'
+assert error(lambda: bytearray().extend(1)) == "TypeError: can't extend bytearray with int", 'extend int'
+assert error(lambda: bytearray().append(256)) == 'ValueError: byte must be in range(0, 256)', 'append out of range'
+
+# === shared bytes methods ===
+assert bytearray(b'Hello').upper() == b'HELLO', 'upper'
+assert type(bytearray(b'Hello').upper()) == bytearray, 'upper type'
+assert bytearray(b'abc').find(b'c') == 2, 'find'
+assert b'abc'.find(bytearray(b'c')) == 2, 'bytes find bytearray'
+assert bytearray(b'abc').startswith(b'ab'), 'startswith'
+assert bytearray(b'abc').decode() == 'abc', 'decode'
+assert bytearray(b'\x01\xff').hex() == '01ff', 'hex'
+assert bytearray(b'a b').split() == [bytearray(b'a'), bytearray(b'b')], 'split'
+assert type(bytearray(b'a b').split()[0]) == bytearray, 'split item type'
+parts = bytearray(b'a=b').partition(b'=')
+assert parts == (bytearray(b'a'), bytearray(b'='), bytearray(b'b')), 'partition'
+assert type(parts[2]) == bytearray, 'partition item type'
+assert bytearray(b'-').join([b'x', bytearray(b'y')]) == bytearray(b'x-y'), 'join'
+assert b'a'.join([bytearray(b'x'), b'y']) == b'xay', 'bytes join bytearray'
+assert type(b'a'.join([bytearray(b'x'), b'y'])) == bytes, 'bytes join type'
+assert bytearray(b'  x ').strip() == bytearray(b'x'), 'strip'
+assert bytearray.fromhex('41 42') == bytearray(b'AB'), 'fromhex'
+assert type(bytearray.fromhex('41')) == bytearray, 'fromhex type'
+assert error(lambda: bytearray(b'ab').index(b'z')) == 'ValueError: subsection not found', 'index missing'
+ba = bytearray(b'abab')
+assert ba.count(ba[:2]) == 2, 'count with bytearray arg'
+assert ba.find(ba) == 0, 'find self'
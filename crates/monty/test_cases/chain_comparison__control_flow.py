@@ -0,0 +1,32 @@
+# === Chains in control flow ===
+def in_range(v):
+    if 0 <= v < 10:
+        return 'inside'
+    return 'outside'
+
+
+assert in_range(0) == 'inside', 'lower bound inclusive'
+assert in_range(9) == 'inside', 'upper bound exclusive'
+assert in_range(10) == 'outside', 'upper bound'
+assert in_range(-1) == 'outside', 'below lower bound'
+
+i = 0
+while 0 <= i < 3:
+    i += 1
+assert i == 3, 'chain as while condition'
+
+assert [v for v in range(10) if 2 < v <= 5] == [3, 4, 5], 'chain in comprehension filter'
+assert (not 1 < 2 < 3) == False, 'not applied to chain'
+assert (1 < 2 < 3 and 3 > 2 > 1) == True, 'chains combined with and'
+
+# === Short-circuit skips later type errors ===
+assert (2 < 1 < 'a') == False, 'failing first link skips invalid comparison'
+
+# === Negated membership and identity ===
+assert (1 not in [2, 3] not in [[1]]) == True, 'not in chain'
+n = None
+assert (n is None is not False) == True, 'is / is not chain'
+
+# === Chain result is the last evaluated comparison ===
+assert (1 < 2 == 2.0) == True, 'int and float in chain'
+assert ('a' < 'b' < 'c') == True, 'string chain'
@@ -0,0 +1,218 @@
+import collections
+from collections import Counter, defaultdict, deque, namedtuple
+
+# === defaultdict ===
+dd = defaultdict(list)
+dd['a'].append(1)
+dd['a'].append(2)
+dd['b'].append(3)
+assert dd == {'a': [1, 2], 'b': [3]}, 'defaultdict creates missing lists'
+assert repr(dd) == "defaultdict(<class 'list'>, {'a': [1, 2], 'b': [3]})", 'defaultdict repr'
+assert dd.default_factory is list, 'defaultdict.default_factory'
+assert isinstance(dd, dict), 'defaultdict is a dict'
+assert repr(type(dd)) == "<class 'collections.defaultdict'>", 'defaultdict type'
+
+counts = defaultdict(int)
+for word in ['x', 'y', 'x']:
+    counts[word] += 1
+assert counts == {'x': 2, 'y': 1}, 'defaultdict(int) counts'
+assert 'z' not in counts, 'in does not create keys'
+assert counts.get('z') is None, 'get does not create keys'
+assert len(counts) == 2, 'get does not insert'
+
+counts_copy = counts.copy()
+counts_copy['z'] += 1
+assert type(counts_copy) is defaultdict, 'defaultdict copy keeps its type'
+assert counts_copy == {'x': 2, 'y': 1, 'z': 1}, 'defaultdict copy inserts missing keys'
+assert counts == {'x': 2, 'y': 1}, 'defaultdict copy is independent'
+assert type(dict(counts)) is dict, 'dict() of a defaultdict is a plain dict'
+
+calls = []
+
+
+def factory():
+    calls.append(1)
+    return 'made'
+
+
+dd = defaultdict(factory, {'k': 'v'})
+assert dd['k'] == 'v', 'existing key skips the factory'
+assert dd['new'] == 'made', 'factory result returned'
+assert dd['new'] == 'made', 'factory result stored'
+assert len(calls) == 1, 'factory called once'
+
+dd = defaultdict(None, a=1)
+assert dd == {'a': 1}, 'defaultdict with keywords'
+assert dd.default_factory is None, 'None factory'
+try:
+    dd['missing']
+    assert False, 'None factory should raise KeyError'
+except KeyError as e:
+    assert str(e) == "'missing'", 'KeyError message'
+
+assert repr(defaultdict()) == 'defaultdict(None, {})', 'empty defaultdict repr'
+try:
+    defaultdict(1)
+    assert False, 'non-callable factory should raise'
+except TypeError as e:
+    assert str(e) == 'first argument must be callable or None', 'non-callable factory message'
+
+nested = defaultdict(lambda: defaultdict(int))
+nested['a']['b'] += 3
+assert nested['a']['b'] == 3, 'nested defaultdict'
+
+# === Counter ===
+c = Counter('abracadabra')
+assert c['a'] == 5, 'Counter counts'
+assert c['z'] == 0, 'missing counts are zero'
+assert 'z' not in c, 'missing lookups do not insert'
+assert c.most_common(2) == [('a', 5), ('b', 2)], 'most_common(n)'
+assert c.most_common()[-1] == ('d', 1), 'most_common keeps insertion order for ties'
+assert c.total() == 11, 'Counter.total'
+assert repr(Counter('aab')) == "Counter({'a': 2, 'b': 1})", 'Counter repr'
+assert repr(Counter()) == 'Counter()', 'empty Counter repr'
+assert repr(Counter({'a': 1, 'b': 3})) == "Counter({'b': 3, 'a': 1})", 'Counter repr is ordered by count'
+assert isinstance(c, dict), 'Counter is a dict'
+
+c = Counter(a=2, b=1)
+c.update('aac')
+assert c == {'a': 4, 'b': 1, 'c': 1}, 'update adds counts of an iterable'
+c.update({'b': 5})
+assert c['b'] == 6, 'update adds counts of a mapping'
+c.subtract(a=5)
+assert c['a'] == -1, 'subtract allows negative counts'
+c.update(c)
+assert c == {'a': -2, 'b': 12, 'c': 2}, 'update with itself'
+assert sorted(Counter(x=2, y=0, z=-1, w=1).elements()) == ['w', 'x', 'x'], 'elements skips counts below one'
+
+c = Counter()
+c['k'] += 1
+c['k'] += 1
+assert c == {'k': 2}, 'Counter increment'
+del c['k']
+assert c == {}, 'Counter del'
+
+# === deque ===
+d = deque([1, 2, 3])
+d.append(4)
+d.appendleft(0)
+assert list(d) == [0, 1, 2, 3, 4], 'append and appendleft'
+assert d.pop() == 4, 'pop'
+assert d.popleft() == 0, 'popleft'
+assert repr(d) == 'deque([1, 2, 3])', 'deque repr'
+assert len(d) == 3, 'deque len'
+assert d[0] == 1 and d[-1] == 3, 'deque indexing'
+d[1] = 20
+assert d[1] == 20, 'deque item assignment'
+del d[1]
+assert list(d) == [1, 3], 'deque item deletion'
+assert 3 in d and 2 not in d, 'deque contains'
+
+d = deque()
+d.extend([1, 2])
+d.extendleft([3, 4])
+assert list(d) == [4, 3, 1, 2], 'extend and extendleft'
+d.rotate(1)
+assert list(d) == [2, 4, 3, 1], 'rotate right'
+d.rotate(-2)
+assert list(d) == [3, 1, 2, 4], 'rotate left'
+d.reverse()
+assert list(d) == [4, 2, 1, 3], 'reverse'
+d.remove(2)
+assert list(d) == [4, 1, 3], 'remove'
+assert d.count(4) == 1, 'count'
+e = d.copy()
+d.clear()
+assert list(e) == [4, 1, 3] and not d, 'copy and clear'
+d = deque([1, 2])
+d.extend(d)
+assert list(d) == [1, 2, 1, 2], 'extend with itself'
+assert list(reversed(deque([1, 2, 3]))) == [3, 2, 1], 'reversed deque'
+assert deque([1, 2]) == deque([1, 2]) and deque([1]) != deque([2]), 'deque equality'
+
+bounded = deque(range(5), maxlen=3)
+assert list(bounded) == [2, 3, 4], 'maxlen keeps the last items'
+bounded.append(5)
+assert list(bounded) == [3, 4, 5], 'append discards from the left'
+bounded.appendleft(2)
+assert list(bounded) == [2, 3, 4], 'appendleft discards from the right'
+assert bounded.maxlen == 3, 'deque.maxlen'
+assert deque().maxlen is None, 'unbounded maxlen'
+assert repr(bounded) == 'deque([2, 3, 4], maxlen=3)', 'bounded deque repr'
+
+try:
+    deque().pop()
+    assert False, 'pop from empty should raise'
+except IndexError as e:
+    assert str(e) == 'pop from an empty deque', 'pop from empty message'
+try:
+    deque([1])[5]
+    assert False, 'out of range index should raise'
+except IndexError as e:
+    assert str(e) == 'deque index out of range', 'index error message'
+try:
+    deque([], maxlen=-1)
+    assert False, 'negative maxlen should raise'
+except ValueError as e:
+    assert str(e) == 'maxlen must be non-negative', 'negative maxlen message'
+
+# === namedtuple ===
+Point = namedtuple('Point', ['x', 'y'])
+p = Point(1, y=2)
+assert p.x == 1 and p.y == 2, 'namedtuple attributes'
+assert p[0] == 1 and p[1] == 2, 'namedtuple indexing'
+assert repr(p) == 'Point(x=1, y=2)', 'namedtuple repr'
+assert p == (1, 2), 'namedtuple equals tuple'
+assert len(p) == 2, 'namedtuple len'
+assert Point._fields == ('x', 'y'), 'class _fields'
+assert p._fields == ('x', 'y'), 'instance _fields'
+assert Point.__name__ == 'Point', 'class name'
+assert p._asdict() == {'x': 1, 'y': 2}, '_asdict'
+assert p._replace(y=5) == Point(1, 5), '_replace'
+assert Point._make([3, 4]) == Point(3, 4), '_make'
+x, y = p
+assert (x, y) == (1, 2), 'namedtuple unpacking'
+
+Color = collections.namedtuple('Color', 'red, green blue', defaults=[0, 255])
+assert Color(1) == (1, 0, 255), 'defaults fill the rightmost fields'
+assert Color(1, 2, 3).blue == 3, 'string field names'
+Renamed = namedtuple('Renamed', ['a', 'class', '_b', 'a'], rename=True)
+assert Renamed._fields == ('a', '_1', '_2', '_3'), 'rename invalid field names'
+
+try:
+    Point(1)
+    assert False, 'missing field should raise'
+except TypeError as e:
+    assert str(e) == "Point.__new__() missing 1 required positional argument: 'y'", 'missing field message'
+try:
+    Point(1, 2, 3)
+    assert False, 'too many fields should raise'
+except TypeError as e:
+    assert str(e) == 'Point.__new__() takes 3 positional arguments but 4 were given', 'too many fields message'
+try:
+    p._replace(z=1)
+    assert False, 'unknown _replace field should raise'
+except TypeError as e:
+    assert str(e) == "Got unexpected field names: ['z']", '_replace message'
+try:
+    Point._make([1])
+    assert False, '_make with wrong length should raise'
+except TypeError as e:
+    assert str(e) == 'Expected 2 arguments, got 1', '_make message'
+
+for args, message in [
+    (('1a', 'x'), "Type names and field names must be valid identifiers: '1a'"),
+    (('P', 'x class'), "Type names and field names cannot be a keyword: 'class'"),
+    (('P', 'x _y'), "Field names cannot start with an underscore: '_y'"),
+    (('P', 'x x'), "Encountered duplicate field name: 'x'"),
+]:
+    try:
+        namedtuple(*args)
+        assert False, 'invalid names should raise'
+    except ValueError as e:
+        assert str(e) == message, 'invalid name message'
+try:
+    namedtuple('P', 'x', defaults=[1, 2])
+    assert False, 'too many defaults should raise'
+except TypeError as e:
+    assert str(e) == 'Got more default values than field names', 'too many defaults message'
@@ -0,0 +1,70 @@
+# === list / tuple ===
+assert 1 in [1, 2, 3], 'int in list'
+assert 4 not in [1, 2, 3], 'int not in list'
+assert 'a' in ('a', 'b'), 'str in tuple'
+assert [1] in [[1], [2]], 'nested list in list'
+assert 1.0 in [1, 2], 'float equal to int in list'
+
+# === dict (keys only) ===
+d = {'a': 1, 'b': 2}
+assert 'a' in d, 'key in dict'
+assert 1 not in d, 'value not in dict'
+
+# === set / frozenset ===
+assert 2 in {1, 2, 3}, 'int in set'
+assert 'z' not in frozenset(['x', 'y']), 'str not in frozenset'
+
+# === str ===
+assert 'ell' in 'hello', 'substring in str'
+assert '' in 'hello', 'empty substring'
+assert 'xyz' not in 'hello', 'missing substring'
+s = 'abc' + 'def'
+assert s in s, 'heap str contains itself'
+assert 'cd' in s, 'substring of heap str'
+
+# === bytes ===
+assert b'ell' in b'hello', 'subsequence in bytes'
+assert b'' in b'hello', 'empty subsequence'
+assert b'xyz' not in b'hello', 'missing subsequence'
+assert 104 in b'hello', 'int byte value in bytes'
+assert 0 not in b'hello', 'int byte value not in bytes'
+assert True not in b'hello', 'bool is treated as int'
+b = b'abc' + b'def'
+assert b in b, 'heap bytes contains itself'
+assert b'cd' in b, 'subsequence of heap bytes'
+
+# === range ===
+assert 3 in range(5), 'int in range'
+assert 3.0 in range(5), 'whole float in range'
+assert 3.5 not in range(5), 'fractional float not in range'
+
+# === errors ===
+try:
+    256 in b'abc'
+    assert False, 'should raise ValueError'
+except ValueError as e:
+    assert str(e) == 'byte must be in range(0, 256)', 'byte range message'
+
+try:
+    'a' in b'abc'
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "a bytes-like object is required, not 'str'", 'bytes-like message'
+
+try:
+    [1] in bytearray(b'abc')
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "a bytes-like object is required, not 'list'", 'bytes-like message names the type'
+
+try:
+    1 in 'abc'
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "'in <string>' requires string as left operand, not int", 'str containment message'
+
+try:
+    1 in 5
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "argument of type 'int' is not iterable", 'not iterable message'
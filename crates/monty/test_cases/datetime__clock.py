@@ -0,0 +1,22 @@
+# call-external
+# Tests for reading the host clock via time.time(), datetime.now() and date.today()
+import time
+from datetime import date, datetime, timedelta
+
+# === time.time ===
+now_ts = time.time()
+assert isinstance(now_ts, float), 'time.time returns a float'
+assert now_ts > 1_700_000_000, 'time.time is after 2023'
+
+# === datetime.now ===
+now = datetime.now()
+assert isinstance(now, datetime), 'datetime.now returns a datetime'
+assert isinstance(now, date), 'datetime is a subclass of date'
+assert now.year >= 2024, 'datetime.now year'
+assert now + timedelta(days=1) > now, 'datetime.now arithmetic'
+
+# === date.today ===
+today = date.today()
+assert isinstance(today, date), 'date.today returns a date'
+assert today.year >= 2024, 'date.today year'
+assert today - timedelta(days=1) < today, 'date.today arithmetic'
@@ -0,0 +1,158 @@
+import datetime
+from datetime import MAXYEAR, MINYEAR, date, datetime as dt, timedelta
+
+# === module attributes ===
+assert MINYEAR == 1, 'MINYEAR'
+assert MAXYEAR == 9999, 'MAXYEAR'
+assert datetime.date is date, 'date class'
+assert datetime.timedelta is timedelta, 'timedelta class'
+
+# === timedelta construction and normalization ===
+assert repr(timedelta()) == 'datetime.timedelta(0)', 'zero repr'
+assert repr(timedelta(seconds=-1)) == 'datetime.timedelta(days=-1, seconds=86399)', 'negative normalized'
+td = timedelta(weeks=1, days=1, hours=1, minutes=1, seconds=1, milliseconds=1, microseconds=1)
+assert str(td) == '8 days, 1:01:01.001001', 'all components'
+assert (td.days, td.seconds, td.microseconds) == (8, 3661, 1001), 'attributes'
+assert str(timedelta(days=0.5, seconds=-1.25)) == '11:59:58.750000', 'float components'
+assert str(timedelta(days=-1, seconds=5)) == '-1 day, 0:00:05', 'negative str'
+assert str(timedelta(days=2, microseconds=5)) == '2 days, 0:00:00.000005', 'plural days'
+assert str(timedelta(0)) == '0:00:00', 'zero str'
+assert timedelta(days=1).total_seconds() == 86400.0, 'total_seconds'
+assert timedelta(microseconds=1).total_seconds() == 1e-06, 'total_seconds micro'
+assert not timedelta(0), 'zero is falsy'
+assert timedelta(microseconds=1), 'nonzero is truthy'
+
+# === timedelta arithmetic ===
+assert timedelta(1) + timedelta(hours=12) == timedelta(hours=36), 'add'
+assert timedelta(1) - timedelta(days=2) == timedelta(-1), 'sub'
+assert -timedelta(hours=1) == timedelta(seconds=-3600), 'neg'
+assert +timedelta(hours=1) == timedelta(hours=1), 'pos'
+assert abs(timedelta(hours=-1)) == timedelta(hours=1), 'abs'
+assert timedelta(hours=1) * 3 == timedelta(hours=3), 'mul int'
+assert 2 * timedelta(hours=1) == timedelta(hours=2), 'rmul int'
+assert str(timedelta(seconds=1) * 0.5) == '0:00:00.500000', 'mul float'
+assert timedelta(1) / timedelta(hours=5) == 4.8, 'div td'
+assert timedelta(1) // timedelta(hours=5) == 4, 'floordiv td'
+assert timedelta(1) % timedelta(hours=5) == timedelta(hours=4), 'mod td'
+assert timedelta(1) / 3 == timedelta(hours=8), 'div int'
+assert timedelta(microseconds=3) / 2 == timedelta(microseconds=2), 'div rounds half even'
+assert timedelta(microseconds=5) / 2 == timedelta(microseconds=2), 'div rounds half even down'
+assert str(timedelta(1) // 7) == '3:25:42.857142', 'floordiv int'
+acc = timedelta()
+acc += timedelta(minutes=5)
+acc *= 2
+assert acc == timedelta(minutes=10), 'inplace ops'
+
+# === timedelta comparison and hashing ===
+assert timedelta(hours=1) < timedelta(hours=2), 'lt'
+assert timedelta(days=-1) < timedelta(0), 'negative lt'
+assert max(timedelta(1), timedelta(2), timedelta(-3)) == timedelta(2), 'max'
+assert {timedelta(hours=24): 'a'}[timedelta(days=1)] == 'a', 'hash'
+
+# === date ===
+d = date(2024, 2, 29)
+assert repr(d) == 'datetime.date(2024, 2, 29)', 'date repr'
+assert str(d) == '2024-02-29', 'date str'
+assert (d.year, d.month, d.day) == (2024, 2, 29), 'date attributes'
+assert d.weekday() == 3, 'weekday'
+assert d.isoweekday() == 4, 'isoweekday'
+assert d.toordinal() == 738945, 'toordinal'
+assert date.fromordinal(738945) == d, 'fromordinal'
+assert date.fromordinal(1) == date(1, 1, 1), 'fromordinal min'
+assert d.isoformat() == '2024-02-29', 'isoformat'
+assert date.fromisoformat('2024-02-29') == d, 'fromisoformat'
+assert date.fromisoformat('20240229') == d, 'fromisoformat basic'
+assert d.replace(day=1) == date(2024, 2, 1), 'replace'
+assert d.replace(year=2023, day=28) == date(2023, 2, 28), 'replace multiple'
+
+# === date arithmetic ===
+assert d + timedelta(days=1) == date(2024, 3, 1), 'add days'
+assert timedelta(days=1) + d == date(2024, 3, 1), 'radd days'
+assert d - timedelta(days=60) == date(2023, 12, 31), 'sub days'
+assert d + timedelta(hours=23) == d, 'sub-day deltas are ignored'
+assert date(2024, 3, 1) - date(2024, 1, 1) == timedelta(days=60), 'date difference'
+assert date(2024, 1, 1) < date(2024, 1, 2), 'date lt'
+assert sorted([date(2024, 5, 1), date(2023, 1, 1)]) == [date(2023, 1, 1), date(2024, 5, 1)], 'date sort'
+assert len({date(2024, 1, 1), date(2024, 1, 1)}) == 1, 'date hash'
+
+# === datetime ===
+t = dt(2024, 1, 2, 3, 4, 5, 60)
+assert repr(t) == 'datetime.datetime(2024, 1, 2, 3, 4, 5, 60)', 'datetime repr'
+assert repr(dt(2024, 1, 2)) == 'datetime.datetime(2024, 1, 2, 0, 0)', 'datetime repr short'
+assert repr(dt(2024, 1, 2, 0, 0, 7)) == 'datetime.datetime(2024, 1, 2, 0, 0, 7)', 'datetime repr seconds'
+assert str(t) == '2024-01-02 03:04:05.000060', 'datetime str'
+assert str(dt(2024, 1, 2, 3)) == '2024-01-02 03:00:00', 'datetime str no micro'
+assert t.isoformat() == '2024-01-02T03:04:05.000060', 'datetime isoformat'
+assert t.isoformat(' ') == '2024-01-02 03:04:05.000060', 'datetime isoformat sep'
+assert t.isoformat(sep='_') == '2024-01-02_03:04:05.000060', 'datetime isoformat sep kwarg'
+assert (t.hour, t.minute, t.second, t.microsecond) == (3, 4, 5, 60), 'datetime attributes'
+assert t.date() == date(2024, 1, 2), 'datetime date'
+assert t.weekday() == 1, 'datetime weekday'
+assert t.replace(hour=23, microsecond=0) == dt(2024, 1, 2, 23, 4, 5), 'datetime replace'
+assert dt.fromisoformat('2024-01-02T03:04:05.000060') == t, 'datetime fromisoformat'
+assert dt.fromisoformat('2024-01-02 03:04') == dt(2024, 1, 2, 3, 4), 'fromisoformat minutes'
+assert dt.fromisoformat('20240102T030405') == dt(2024, 1, 2, 3, 4, 5), 'fromisoformat basic'
+assert dt.fromisoformat('2024-01-02T03:04:05.1') == dt(2024, 1, 2, 3, 4, 5, 100000), 'fromisoformat fraction'
+assert dt.fromisoformat('2024-01-02') == dt(2024, 1, 2), 'fromisoformat date only'
+
+# === datetime arithmetic ===
+assert t + timedelta(hours=21) == dt(2024, 1, 3, 0, 4, 5, 60), 'datetime add'
+assert t - timedelta(microseconds=61) == dt(2024, 1, 2, 3, 4, 4, 999999), 'datetime sub'
+assert dt(2024, 1, 2) - dt(2023, 12, 31, 12) == timedelta(days=1, hours=12), 'datetime difference'
+assert dt(2024, 1, 1) - dt(2024, 1, 2) == timedelta(days=-1), 'negative difference'
+assert dt(2024, 1, 1) < dt(2024, 1, 1, 0, 0, 0, 1), 'datetime lt'
+
+# === strftime ===
+s = dt(5, 3, 7, 13, 4, 5, 60).strftime('%Y|%y|%C|%m|%d|%e|%H|%I|%M|%S|%f|%p|%j|%a|%A|%b|%B|%w|%u')
+assert s == '5|05|0|03|07| 7|13|01|04|05|000060|PM|066|Mon|Monday|Mar|March|1|1', 'strftime fields'
+assert dt(2024, 12, 30).strftime('%G %V %U %W %j') == '2025 01 52 53 365', 'strftime iso week'
+assert date(2024, 1, 2).strftime('%F %T %D %R') == '2024-01-02 00:00:00 01/02/24 00:00', 'strftime composites'
+assert date(2024, 1, 2).strftime('%c') == 'Tue Jan  2 00:00:00 2024', 'strftime %c'
+assert date(2024, 1, 2).strftime('100%% %Q %') == '100% %Q %', 'strftime literal percent'
+
+# === errors ===
+try:
+    date(2024, 2, 30)
+    assert False, 'day out of range should raise'
+except ValueError as e:
+    assert str(e) == 'day is out of range for month', 'day error'
+try:
+    date(2024, 13, 1)
+    assert False, 'month out of range should raise'
+except ValueError as e:
+    assert str(e) == 'month must be in 1..12', 'month error'
+try:
+    dt(2024, 1, 1, 24)
+    assert False, 'hour out of range should raise'
+except ValueError as e:
+    assert str(e) == 'hour must be in 0..23', 'hour error'
+try:
+    date(9999, 12, 31) + timedelta(days=1)
+    assert False, 'date overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'date value out of range', 'overflow error'
+try:
+    timedelta(days=1000000000)
+    assert False, 'timedelta overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'days=1000000000; must have magnitude <= 999999999', 'timedelta overflow error'
+try:
+    timedelta(1) / 0
+    assert False, 'division by zero should raise'
+except ZeroDivisionError as e:
+    assert str(e) == 'integer division or modulo by zero', 'division error'
+try:
+    date.fromisoformat('2020-1-1')
+    assert False, 'bad isoformat should raise'
+except ValueError as e:
+    assert str(e) == "Invalid isoformat string: '2020-1-1'", 'isoformat error'
+try:
+    date(2024, 1, 1) + 1
+    assert False, 'date + int should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for +: 'datetime.date' and 'int'", 'date + int error'
+try:
+    timedelta('a')
+    assert False, 'timedelta str should raise'
+except TypeError as e:
+    assert str(e) == 'unsupported type for timedelta days component: str', 'timedelta type error'
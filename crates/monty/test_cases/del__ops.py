@@ -0,0 +1,200 @@
+# === Deleting names ===
+x = 1
+del x
+try:
+    x
+    assert False, 'x should be unbound after del'
+except NameError as e:
+    assert str(e) == "name 'x' is not defined", f'unexpected message: {e}'
+
+x = 'rebound'
+assert x == 'rebound', 'a deleted name can be assigned again'
+
+a = 1
+b = 2
+del a, b
+try:
+    b
+    assert False, 'b should be unbound after del'
+except NameError:
+    pass
+
+try:
+    del never_defined  # type: ignore
+    assert False, 'deleting an undefined name should raise'
+except NameError as e:
+    assert str(e) == "name 'never_defined' is not defined", f'unexpected message: {e}'
+
+# === Deleting dict items ===
+d = {'a': 1, 'b': 2, 'c': 3}
+del d['b']
+assert d == {'a': 1, 'c': 3}, 'del removes the key'
+assert list(d) == ['a', 'c'], 'del preserves order of remaining keys'
+
+try:
+    del d['missing']
+    assert False, 'deleting a missing key should raise'
+except KeyError as e:
+    assert e.args == ('missing',), f'unexpected args: {e.args}'
+
+try:
+    del d[[1]]  # type: ignore
+    assert False, 'deleting an unhashable key should raise'
+except TypeError:
+    pass
+
+# === Deleting list items ===
+lst = [0, 1, 2, 3, 4]
+del lst[0]
+assert lst == [1, 2, 3, 4], 'del first item'
+del lst[-1]
+assert lst == [1, 2, 3], 'del negative index'
+del lst[True]
+assert lst == [1, 3], 'bool index'
+
+try:
+    del lst[10]
+    assert False, 'out of range should raise'
+except IndexError as e:
+    assert str(e) == 'list assignment index out of range', f'unexpected message: {e}'
+
+try:
+    del lst['a']  # type: ignore
+    assert False, 'str index should raise'
+except TypeError as e:
+    assert str(e) == 'list indices must be integers or slices, not str', f'unexpected message: {e}'
+
+# === Deleting list slices ===
+lst = list(range(10))
+del lst[2:5]
+assert lst == [0, 1, 5, 6, 7, 8, 9], 'contiguous slice'
+del lst[::2]
+assert lst == [1, 6, 8], 'stepped slice'
+lst = list(range(10))
+del lst[::-3]
+assert lst == [1, 2, 4, 5, 7, 8], 'negative step slice'
+lst = list(range(5))
+del lst[3:1]
+assert lst == [0, 1, 2, 3, 4], 'empty slice deletes nothing'
+del lst[:]
+assert lst == [], 'full slice clears the list'
+
+# === Multiple and nested targets ===
+d = {'k': [1, 2, 3]}
+del d['k'][1]
+assert d == {'k': [1, 3]}, 'nested subscript'
+lst = [1, 2, 3]
+y = 5
+del lst[0], y
+assert lst == [2, 3], 'first target deleted'
+del (lst[0], lst[0])
+assert lst == [], 'targets are deleted left to right'
+
+# === Unsupported types ===
+for value, message in [
+    ('abc', "'str' object doesn't support item deletion"),
+    ((1, 2), "'tuple' object doesn't support item deletion"),
+    (1, "'int' object does not support item deletion"),
+    (None, "'NoneType' object does not support item deletion"),
+]:
+    try:
+        del value[0]  # type: ignore
+        assert False, 'deletion should raise'
+    except TypeError as e:
+        assert str(e) == message, f'unexpected message: {e}'
+
+
+# === Function scope ===
+def delete_local():
+    v = 1
+    del v
+    try:
+        return v
+    except UnboundLocalError as e:
+        return str(e)
+
+
+assert delete_local() == "cannot access local variable 'v' where it is not associated with a value", 'del in function'
+
+
+def delete_twice():
+    v = 1
+    del v
+    del v
+
+
+try:
+    delete_twice()
+    assert False, 'second del should raise'
+except UnboundLocalError as e:
+    assert str(e) == "cannot access local variable 'v' where it is not associated with a value", f'msg: {e}'
+
+
+def delete_makes_local():
+    if False:
+        del g
+    return g  # type: ignore
+
+
+g = 'global'
+try:
+    delete_makes_local()
+    assert False, 'del makes the name local'
+except UnboundLocalError:
+    pass
+
+counter = 1
+
+
+def delete_global():
+    global counter
+    del counter
+
+
+delete_global()
+try:
+    counter
+    assert False, 'global was deleted'
+except NameError:
+    pass
+
+
+def delete_nonlocal():
+    captured = [1]
+
+    def inner():
+        nonlocal captured
+        del captured
+
+    def read():
+        return captured
+
+    inner()
+    messages = []
+    for func in (read, inner):
+        try:
+            func()
+        except NameError as e:
+            messages.append(str(e))
+    return messages
+
+
+free_var_msg = "cannot access free variable 'captured' where it is not associated with a value in enclosing scope"
+assert delete_nonlocal() == [free_var_msg, free_var_msg], 'nonlocal del clears the shared cell'
+
+# === Except handler variables ===
+try:
+    raise ValueError('x')
+except ValueError as err:
+    del err  # deleting the handler variable inside the handler is allowed
+
+
+def handler_in_function():
+    try:
+        raise ValueError('x')
+    except ValueError as exc:
+        del exc
+    return 'ok'
+
+
+assert handler_in_function() == 'ok', 'handler cleanup after del'
@@ -40,6 +40,12 @@ d = {'a': 1, 'b': 2}
 assert d.pop('a') == 1, 'pop existing'
 assert d == {'b': 2}, 'pop removes key'
 
+d = {'a': 1, 'b': 2, 'c': 3}
+d.pop('a')
+assert d['b'] == 2 and d['c'] == 3, 'lookups after popping the first key'
+d['d'] = 4
+assert list(d.items()) == [('b', 2), ('c', 3), ('d', 4)], 'insert after popping the first key'
+
 d = {'a': 1}
 assert d.pop('missing', 'default') == 'default', 'pop missing with default'
 
@@ -58,3 +64,71 @@ assert d['self'] is d, 'getitem self'
 
 d = {}
 assert d.get('missing', d) is d, 'get default same dict'
+
+# === Dict merge operators ===
+a = {'x': 1, 'y': 2}
+b = {'y': 3, 'z': 4}
+assert a | b == {'x': 1, 'y': 3, 'z': 4}, 'merge prefers right operand'
+assert list(a | b) == ['x', 'y', 'z'], 'merge preserves left insertion order'
+assert a == {'x': 1, 'y': 2}, 'merge does not mutate left operand'
+assert a | a == a, 'merge with self'
+
+alias = a
+a |= b
+assert alias == {'x': 1, 'y': 3, 'z': 4}, 'in-place merge mutates the dict'
+a |= [('w', 0)]
+assert alias['w'] == 0, 'in-place merge accepts iterable of pairs'
+a |= a
+assert a is alias, 'in-place merge with self'
+
+try:
+    {} | [('a', 1)]
+    assert False, 'dict | list should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for |: 'dict' and 'list'", 'dict | list error'
+
+try:
+    d = {}
+    d |= 1
+    assert False, 'dict |= int should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'dict |= int error'
+
+# === copies are independent of the original ===
+orig = {'a': 1, 'b': [2]}
+copies = [orig.copy(), dict(orig)]
+for c in copies:
+    assert c == orig, 'copy equals original'
+    assert c is not orig, 'copy is a new dict'
+    assert c['b'] is orig['b'], 'copy is shallow'
+orig['c'] = 3
+orig['a'] = 10
+assert orig == {'a': 10, 'b': [2], 'c': 3}, 'mutating the original'
+for c in copies:
+    assert c == {'a': 1, 'b': [2]}, 'copy unchanged by mutating the original'
+assert copies[0].pop('a') == 1, 'pop from a copy'
+copies[1].setdefault('d', 4)
+assert copies[0] == {'b': [2]}, 'copy after pop'
+assert copies[1] == {'a': 1, 'b': [2], 'd': 4}, 'copy after setdefault'
+assert list(copies[1]) == ['a', 'b', 'd'], 'copy keeps insertion order'
+assert orig == {'a': 10, 'b': [2], 'c': 3}, 'original unchanged by mutating copies'
+orig['b'].append(3)
+assert copies[0]['b'] == [2, 3], 'nested list shared by copies'
+
+a = {'x': 1, 'y': 2}
+b = a.copy()
+c = b.copy()
+assert b.popitem() == ('y', 2), 'popitem from a copy'
+c.update({'z': 3})
+a.clear()
+assert a == {}, 'clear the original'
+assert b == {'x': 1}, 'copy after popitem'
+assert c == {'x': 1, 'y': 2, 'z': 3}, 'copy of a copy after update'
+del c['x']
+assert 'x' not in c, 'delete from a copy'
+assert b['x'] == 1, 'other copy unchanged by delete'
+
+# === literals with repeated keys ===
+d = {'a': [1], 'b': 2, 'a': [3]}
+assert d == {'a': [3], 'b': 2}, 'last value wins'
+assert list(d) == ['a', 'b'], 'first position kept'
@@ -0,0 +1,109 @@
+# === views reflect later mutations ===
+d = {'a': 1, 'b': 2}
+keys = d.keys()
+values = d.values()
+items = d.items()
+d['c'] = 3
+assert len(keys) == 3, 'keys len after insert'
+assert list(values) == [1, 2, 3], 'values after insert'
+assert list(items) == [('a', 1), ('b', 2), ('c', 3)], 'items after insert'
+del d['a']
+assert list(keys) == ['b', 'c'], 'keys after delete'
+d.clear()
+assert len(items) == 0, 'items len after clear'
+assert not keys, 'empty view is falsy'
+
+# === repr and type ===
+d = {'a': 1, 'b': (2,)}
+assert repr(d.keys()) == "dict_keys(['a', 'b'])", 'keys repr'
+assert repr(d.values()) == 'dict_values([1, (2,)])', 'values repr'
+assert repr(d.items()) == "dict_items([('a', 1), ('b', (2,))])", 'items repr'
+assert repr({}.keys()) == 'dict_keys([])', 'empty keys repr'
+assert type(d.keys()).__name__ == 'dict_keys', 'keys type name'
+assert type(d.values()).__name__ == 'dict_values', 'values type name'
+assert type(d.items()).__name__ == 'dict_items', 'items type name'
+
+# === membership ===
+d = {'a': 1, 'b': [2]}
+assert 'a' in d.keys(), 'key in keys'
+assert 'z' not in d.keys(), 'missing key not in keys'
+assert [2] in d.values(), 'value in values'
+assert 3 not in d.values(), 'missing value not in values'
+assert ('a', 1) in d.items(), 'pair in items'
+assert ('b', [2]) in d.items(), 'pair with unhashable value in items'
+assert ('a', 2) not in d.items(), 'pair with wrong value not in items'
+assert 'a' not in d.items(), 'non-tuple not in items'
+
+# === iteration ===
+d = {'x': 10, 'y': 20}
+pairs = []
+for k, v in d.items():
+    pairs.append(k + str(v))
+assert pairs == ['x10', 'y20'], 'unpack items in for loop'
+assert sum(d.values()) == 30, 'sum values'
+assert sorted(d.keys(), reverse=True) == ['y', 'x'], 'sorted keys'
+it = iter(d.keys())
+assert next(it) == 'x', 'next on keys iterator'
+
+try:
+    for k in d.keys():
+        d['new'] = 1
+    assert False, 'resizing during view iteration should raise'
+except RuntimeError as e:
+    assert str(e) == 'dictionary changed size during iteration', 'resize error message'
+
+# === equality ===
+d = {'a': 1, 'b': 2}
+assert d.keys() == {'a', 'b'}, 'keys eq set'
+assert {'a', 'b'} == d.keys(), 'set eq keys'
+assert d.keys() == frozenset(['a', 'b']), 'keys eq frozenset'
+assert d.keys() != {'a'}, 'keys ne smaller set'
+assert d.keys() == {'b': 0, 'a': 0}.keys(), 'keys eq keys'
+assert d.items() == {('a', 1), ('b', 2)}, 'items eq set of pairs'
+assert d.items() == {'b': 2, 'a': 1}.items(), 'items eq items'
+assert d.items() != {'a': 1, 'b': 3}.items(), 'items ne items'
+assert d.keys() != ['a', 'b'], 'keys ne list'
+assert d.values() != d.values(), 'values views compare by identity'
+v = d.values()
+assert v == v, 'values view equals itself'
+
+# === set operations ===
+d = {'a': 1, 'b': 2, 'c': 3}
+assert d.keys() & {'a', 'z'} == {'a'}, 'keys and set'
+assert d.keys() | ['z'] == {'a', 'b', 'c', 'z'}, 'keys or list'
+assert d.keys() - {'a'} == {'b', 'c'}, 'keys minus set'
+assert {'a', 'z'} - d.keys() == {'z'}, 'set minus keys'
+assert d.keys() ^ {'a', 'z'} == {'b', 'c', 'z'}, 'keys xor set'
+assert type(d.keys() & {'a'}) == set, 'keys op returns set'
+assert type(frozenset(['a']) | d.keys()) == set, 'frozenset or keys returns set'
+assert d.items() & {('a', 1), ('b', 0)} == {('a', 1)}, 'items and set'
+s = {'z'}
+s |= d.keys()
+assert s == {'a', 'b', 'c', 'z'}, 'set ior keys'
+
+try:
+    d.values() | {1}
+    assert False, 'values | set should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for |: 'dict_values' and 'set'", 'values or error'
+
+try:
+    d.keys() | 1
+    assert False, 'keys | int should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'keys or int error'
+
+try:
+    d.keys()[0]
+    assert False, 'indexing a view should raise'
+except TypeError as e:
+    assert str(e) == "'dict_keys' object is not subscriptable", 'keys subscript error'
+
+# === views keep the dict alive ===
+
+
+def make_view():
+    return {'k': 'v'}.items()
+
+
+assert list(make_view()) == [('k', 'v')], 'view outlives dict name'
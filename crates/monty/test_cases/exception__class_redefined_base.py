@@ -0,0 +1,22 @@
+# xfail=cpython
+# Monty identifies an exception class by its `class` statement, so running the
+# statement again can't give the class a different base.
+def make(base):
+    class Error(base):
+        pass
+
+    return Error
+
+
+first = make(ValueError)
+err = first('boom')
+assert make(ValueError) is first, 'same base reuses the class'
+
+try:
+    make(KeyError)
+    assert False, 'should raise NotImplementedError'
+except NotImplementedError as e:
+    assert str(e) == "redefining exception class 'Error' with a different base is not supported", str(e)
+
+assert isinstance(err, ValueError), 'earlier instances keep their base'
+assert not isinstance(err, KeyError), 'earlier instances are not re-parented'
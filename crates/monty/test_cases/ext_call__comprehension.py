@@ -0,0 +1,72 @@
+# call-external
+# === External calls in comprehensions ===
+
+# Ext call in list comprehension element
+assert [add_ints(x, 1) for x in range(3)] == [1, 2, 3], 'ext call in list comp element'
+
+# Ext call in comprehension filter
+assert [x for x in range(5) if return_value(x) % 2 == 0] == [0, 2, 4], 'ext call in list comp filter'
+
+# Ext call as comprehension iterable
+assert [add_ints(x, x) for x in get_list()] == [2, 4, 6], 'ext call as list comp iterable'
+
+# Ext calls in element, filter and iterable together
+assert [add_ints(x, 10) for x in get_list() if return_value(x) > 1] == [12, 13], 'ext call everywhere in list comp'
+
+# Nested for clauses with ext calls
+pairs = [concat_strings(return_value(str(x)), str(y)) for x in range(2) for y in range(2)]
+assert pairs == ['00', '01', '10', '11'], f'ext call in nested for clauses {pairs=}'
+
+# Set comprehension
+assert {add_ints(x % 2, 0) for x in range(5)} == {0, 1}, 'ext call in set comp'
+
+# Dict comprehension, ext calls in key and value
+assert {return_value(str(x)): add_ints(x, x) for x in range(3)} == {'0': 0, '1': 2, '2': 4}, 'ext call in dict comp'
+
+# Comprehension inside a comprehension
+grid = [[add_ints(x, y) for y in range(2)] for x in range(2)]
+assert grid == [[0, 1], [1, 2]], f'ext call in nested comprehensions {grid=}'
+
+# Comprehension using enclosing variables
+offset = 100
+assert [add_ints(x, offset) for x in range(2)] == [100, 101], 'ext call in comp with closure var'
+
+
+# === Comprehensions inside functions ===
+def doubled(items):
+    return [add_ints(x, x) for x in items]
+
+
+assert doubled([1, 2, 3]) == [2, 4, 6], 'ext call in comp inside function'
+
+
+def filtered_sum(n):
+    total = 0
+    for v in [x for x in range(n) if return_value(x) > 0]:
+        total = add_ints(total, v)
+    return total
+
+
+assert filtered_sum(4) == 6, 'ext call in comp used as for iterable inside function'
+
+
+def comp_of_calls(n):
+    return [doubled([x]) for x in range(n)]
+
+
+assert comp_of_calls(3) == [[0], [2], [4]], 'comp calling function that suspends'
+
+# === Exceptions from ext calls inside comprehensions ===
+try:
+    [raise_error('ValueError', 'in comp') for x in range(3)]
+    assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'in comp', 'ext exception propagates out of comp'
+
+caught = []
+for i in range(3):
+    try:
+        caught.append([add_ints(i, 0)] + [raise_error('KeyError', 'k') for x in range(i)])
+    except KeyError:
+        caught.append('err')
+assert caught == [[0], 'err', 'err'], f'ext exception in comp inside loop {caught=}'
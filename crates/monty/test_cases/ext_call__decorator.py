@@ -0,0 +1,43 @@
+# call-external
+# === External function used directly as a decorator ===
+@register_handler
+def handler():
+    return 'handled'
+
+
+assert handler == 'registered', 'name is bound to the external result'
+
+
+# === External call inside a decorator factory ===
+def prefix_with(value):
+    def decorator(func):
+        def wrapper():
+            return value + func()
+
+        return wrapper
+
+    return decorator
+
+
+@prefix_with(concat_strings('a', 'b'))
+def joined():
+    return 'c'
+
+
+assert joined() == 'abc', 'decorator factory argument from external call'
+
+
+# === External call inside the wrapper ===
+def add_one(func):
+    def wrapper(x):
+        return add_ints(func(x), 1)
+
+    return wrapper
+
+
+@add_one
+def square(x):
+    return x * x
+
+
+assert square(3) == 10, 'external call in wrapper'
@@ -0,0 +1,36 @@
+# call-external
+# === Keyword arguments to external functions ===
+
+args, kwargs = collect_args(1, 2)
+assert args == [1, 2], 'positional args only'
+assert kwargs == {}, 'no kwargs'
+
+args, kwargs = collect_args(a=1, b='two')
+assert args == [], 'kwargs only has no positional args'
+assert kwargs == {'a': 1, 'b': 'two'}, 'kwargs only'
+
+args, kwargs = collect_args(1, x=[1, 2], y=None)
+assert args == [1], 'mixed positional args'
+assert kwargs == {'x': [1, 2], 'y': None}, 'mixed kwargs'
+
+# keyword order is preserved
+_, kwargs = collect_args(z=1, a=2, m=3)
+assert list(kwargs) == ['z', 'a', 'm'], 'kwargs keep call order'
+
+# === Unpacking ===
+options = {'sep': ', ', 'end': '!'}
+args, kwargs = collect_args(*[1, 2], **options)
+assert args == [1, 2], 'unpacked positional args'
+assert kwargs == {'sep': ', ', 'end': '!'}, 'unpacked kwargs'
+
+_, kwargs = collect_args(first=0, **options)
+assert list(kwargs) == ['first', 'sep', 'end'], 'explicit kwargs before unpacked'
+
+# === Inside functions ===
+def wrapper(*a, **kw):
+    return collect_args(*a, **kw)
+
+
+args, kwargs = wrapper(3, flag=True)
+assert args == [3], 'forwarded positional args'
+assert kwargs == {'flag': True}, 'forwarded kwargs'
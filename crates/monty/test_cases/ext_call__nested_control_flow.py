@@ -0,0 +1,101 @@
+# call-external
+# === External calls in while loops ===
+
+n = 0
+while add_ints(n, 0) < 3:
+    n = add_ints(n, 1)
+assert n == 3, 'ext call in while condition and body'
+
+# === While inside try/finally ===
+log = []
+try:
+    i = 0
+    while i < 3:
+        log.append(add_ints(i, 10))
+        i += 1
+finally:
+    log.append(return_value('finally'))
+assert log == [10, 11, 12, 'finally'], f'ext calls in while inside try/finally {log=}'
+
+# === try/finally inside while, with comprehensions ===
+log = []
+i = 0
+while i < 2:
+    try:
+        log.append([add_ints(i, x) for x in range(2)])
+    finally:
+        log.append(concat_strings('f', str(return_value(i))))
+    i += 1
+assert log == [[0, 1], 'f0', [1, 2], 'f1'], f'comp in try/finally in while {log=}'
+
+
+# === Everything together inside a function ===
+def process(limit):
+    out = []
+    i = 0
+    while i < limit:
+        try:
+            if return_value(i) == 1:
+                raise_error('ValueError', concat_strings('bad ', str(i)))
+            out.append({add_ints(i, 0): [add_ints(i, y) for y in range(2) if return_value(y) >= 0]})
+        except ValueError as e:
+            out.append(return_value(str(e)))
+        finally:
+            out.append(add_ints(i, 100))
+        i = add_ints(i, 1)
+    return out
+
+
+assert process(3) == [{0: [0, 1]}, 100, 'bad 1', 101, {2: [2, 3]}, 102], 'while, try and comp in function'
+
+# === finally runs after an ext exception raised in a comprehension ===
+cleanup = []
+
+
+def fails_in_comp():
+    try:
+        return [raise_error('RuntimeError', 'boom') for _ in range(2)]
+    finally:
+        cleanup.append(add_ints(1, 1))
+
+
+try:
+    fails_in_comp()
+    assert False, 'should have raised'
+except RuntimeError as e:
+    assert str(e) == 'boom', 'exception from comp propagates through finally'
+assert cleanup == [2], f'finally with ext call ran after comp raised {cleanup=}'
+
+# === break and continue with ext calls in nested loops ===
+found = []
+for a in range(3):
+    b = 0
+    while True:
+        b = add_ints(b, 1)
+        if b > 2:
+            break
+        if return_value(b) == 1:
+            continue
+        found.append([add_ints(a, b) for _ in range(1)][0])
+assert found == [2, 3, 4], f'break/continue with ext calls {found=}'
+
+# === deep nesting of functions, loops and comprehensions ===
+def level3(x):
+    return [add_ints(x, y) for y in range(2)]
+
+
+def level2(x):
+    results = []
+    for v in level3(x):
+        try:
+            results.append(add_ints(v, 0))
+        finally:
+            pass
+    return results
+
+
+def level1(n):
+    return [level2(x) for x in range(n)]
+
+
+assert level1(3) == [[0, 1], [1, 2], [2, 3]], 'ext calls deep in nested functions and comps'
@@ -0,0 +1,47 @@
+# call-external
+from contextlib import nullcontext, suppress
+
+# === External calls in the with expression and body ===
+with nullcontext(add_ints(1, 2)) as value:
+    result = add_ints(value, 10)
+assert result == 13, 'ext calls in with expression and body'
+
+# === External call raising inside a suppressing with ===
+reached = []
+with suppress(ValueError):
+    reached.append(add_ints(1, 1))
+    raise_error('ValueError', 'boom')
+    reached.append(3)
+assert reached == [2], 'ext call exception suppressed'
+
+# === External call raising through a non-suppressing with ===
+caught = None
+try:
+    with nullcontext():
+        raise_error('KeyError', 'missing')
+except KeyError as e:
+    caught = e.args[0]
+assert caught == 'missing', 'ext call exception propagates'
+
+# === Host object as a context manager ===
+user = make_user('alice')
+with user as name:
+    greeting = concat_strings('hi ', name)
+assert greeting == 'hi alice', 'host __enter__ result'
+
+caught = None
+try:
+    with make_user('bob'):
+        raise_error('RuntimeError', 'failed')
+except RuntimeError as e:
+    caught = str(e)
+assert caught == 'failed', 'host __exit__ does not suppress'
+
+
+# === with inside a function ===
+def total(a, b):
+    with suppress(KeyError):
+        return add_ints(a, b)
+
+
+assert total(2, 3) == 5, 'ext call returned from with in function'
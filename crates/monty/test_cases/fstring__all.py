@@ -234,3 +234,26 @@ assert f'{name = }' == "name = 'test'", 'debug uses repr for strings'
 assert f'{name=!s}' == 'name=test', 'debug with !s conversion'
 assert f'{name=!r}' == "name='test'", 'debug with !r conversion'
 assert f'{1+1=}' == '1+1=2', 'debug with expression'
+
+# === Alternate form and grouping ===
+n = 1234567
+assert f'{n:,}' == '1,234,567', 'comma grouping'
+assert f'{n:_}' == '1_234_567', 'underscore grouping'
+assert f'{n:>12,}' == '   1,234,567', 'grouping with width'
+assert f'{1234.5:,.1f}' == '1,234.5', 'float grouping'
+assert f'{255:#x}' == '0xff', 'alternate hex'
+assert f'{255:#06x}' == '0x00ff', 'alternate hex zero padded'
+assert f'{10:#b}' == '0b1010', 'alternate binary'
+w = 8
+assert f'{n:{w},}' == '1,234,567', 'dynamic spec with grouping'
+
+# === Numbers without a type keep their str() form ===
+assert f'{1.0:>6}' == '   1.0', 'float aligned'
+assert f'{2.5:+}' == '+2.5', 'float sign'
+assert f'{True:>3}' == '  1', 'bool with spec formats as int'
+assert f'{True:}' == 'True', 'bool with empty spec'
+
+# === Specs that don't fit the compact bytecode encoding ===
+assert f'{1:→>3}' == '→→1', 'non-latin-1 fill'
+assert len(f'{1:70000}') == 70000, 'width above 16 bits'
+assert f'{1.5:.70000f}'[-3:] == '000', 'precision above 16 bits'
@@ -0,0 +1,3 @@
+# a width too large for a usize is reported when the value is formatted, not at parse time
+f'{1:99999999999999999999}'
+# Raise=ValueError('Too many decimal digits in format string')
@@ -0,0 +1,142 @@
+# === Simple decorator replacing the function ===
+def shout(func):
+    def wrapper(*args, **kwargs):
+        return func(*args, **kwargs).upper()
+
+    return wrapper
+
+
+@shout
+def greet(name, punctuation='!'):
+    return 'hello ' + name + punctuation
+
+
+assert greet('world') == 'HELLO WORLD!', 'decorated call'
+assert greet('bob', punctuation='?') == 'HELLO BOB?', 'decorated call with kwargs'
+
+# === Decorator returning something other than a function ===
+def to_value(func):
+    return func()
+
+
+@to_value
+def answer():
+    return 42
+
+
+assert answer == 42, 'decorator result is bound to the name'
+
+# === Decorator factories ===
+def repeat(times):
+    def decorator(func):
+        def wrapper(x):
+            for _ in range(times):
+                x = func(x)
+            return x
+
+        return wrapper
+
+    return decorator
+
+
+@repeat(3)
+def double(x):
+    return x * 2
+
+
+assert double(1) == 8, 'decorator factory'
+
+# === Stacked decorators apply bottom-up ===
+order = []
+
+
+def tag(label):
+    order.append('eval ' + label)
+
+    def decorator(func):
+        order.append('apply ' + label)
+
+        def wrapper():
+            return label + '(' + func() + ')'
+
+        return wrapper
+
+    return decorator
+
+
+@tag('outer')
+@tag('inner')
+def base():
+    return 'x'
+
+
+assert base() == 'outer(inner(x))', 'stacked decorators wrap bottom-up'
+assert order == ['eval outer', 'eval inner', 'apply inner', 'apply outer'], f'evaluation order: {order}'
+
+# === Registration decorators see the undecorated function ===
+registry = []
+
+
+def register(func):
+    registry.append(func)
+    return func
+
+
+@register
+def first():
+    return 1
+
+
+@register
+def second():
+    return 2
+
+
+assert registry == [first, second], 'registry holds the original functions'
+assert registry[1]() == 2, 'registered function callable'
+
+# === Decorator expressions: attribute access and lambdas ===
+decorators = {'ident': lambda f: f}
+
+
+@decorators['ident']
+def via_subscript():
+    return 'sub'
+
+
+assert via_subscript() == 'sub', 'subscript decorator expression'
+
+
+@(lambda f: lambda: f() + 1)
+def via_lambda():
+    return 1
+
+
+assert via_lambda() == 2, 'lambda decorator expression'
+
+
+# === Decorators inside functions capture enclosing locals ===
+def make_counter_decorator():
+    calls = []
+
+    def counted(func):
+        def wrapper():
+            calls.append(1)
+            return func()
+
+        return wrapper
+
+    def build():
+        @counted
+        def inner():
+            return len(calls)
+
+        return inner
+
+    f = build()
+    f()
+    return f(), len(calls)
+
+
+assert make_counter_decorator() == (2, 2), 'decorator from enclosing scope'
+
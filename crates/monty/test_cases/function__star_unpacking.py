@@ -0,0 +1,45 @@
+# Tests for multiple and interleaved *args / **kwargs unpacking in calls
+
+
+def collect(*args, **kwargs):
+    return args, kwargs
+
+
+# === Multiple *args unpacking ===
+assert collect(*[1, 2], *(3, 4)) == ((1, 2, 3, 4), {}), 'two *args'
+assert collect(*[], *[], *[]) == ((), {}), 'several empty *args'
+
+# === Positional args after *args ===
+assert collect(0, *[1, 2], 3) == ((0, 1, 2, 3), {}), 'positional after *args'
+assert collect(*[1], 2, *[3], 4) == ((1, 2, 3, 4), {}), 'interleaved positional and *args'
+
+# === Unpacking arbitrary iterables ===
+assert collect(*range(3)) == ((0, 1, 2), {}), '*range'
+assert collect(*'ab', *range(2)) == (('a', 'b', 0, 1), {}), '*str and *range'
+assert collect(*iter([5, 6])) == ((5, 6), {}), '*iterator'
+assert collect(*{'k': 1}) == (('k',), {}), '*dict yields keys'
+
+# === Multiple **kwargs unpacking ===
+assert collect(**{'a': 1}, **{'b': 2}) == ((), {'a': 1, 'b': 2}), 'two **kwargs'
+assert collect(x=0, **{'a': 1}, y=2, **{'b': 3}) == ((), {'x': 0, 'a': 1, 'y': 2, 'b': 3}), 'mixed kwargs'
+
+# === Everything together ===
+args, kwargs = collect(1, *[2], 3, *(4,), k=5, **{'m': 6}, **{'n': 7})
+assert args == (1, 2, 3, 4), f'combined args: {args}'
+assert kwargs == {'k': 5, 'm': 6, 'n': 7}, f'combined kwargs: {kwargs}'
+
+
+# === Unpacking into named parameters ===
+def point(x, y, z=0):
+    return (x, y, z)
+
+
+assert point(*[1], *[2]) == (1, 2, 0), 'split positional args'
+assert point(*[1], 2, *[3]) == (1, 2, 3), 'positional between *args'
+assert point(**{'x': 1}, **{'y': 2}) == (1, 2, 0), 'split keyword args'
+
+# === Builtins and methods ===
+assert max(*[1, 5], *(3,), 2) == 5, 'builtin with multiple *args'
+assert 'abc'.replace(*['a'], *['z']) == 'zbc', 'method with multiple *args'
+assert 'a-b-c'.split(*['-'], **{'maxsplit': 1}) == ['a', 'b-c'], 'method with *args and **kwargs'
+assert (lambda *a: a)(*[1], 2, *[3]) == (1, 2, 3), 'expression call with multiple *args'
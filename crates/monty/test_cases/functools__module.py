@@ -0,0 +1,141 @@
+import functools
+from functools import cache, lru_cache, partial, reduce
+
+# === reduce ===
+assert reduce(lambda a, b: a + b, [1, 2, 3, 4]) == 10, 'reduce sums'
+assert reduce(lambda a, b: a * b, range(1, 6), 1) == 120, 'reduce with initial'
+assert reduce(lambda a, b: a + b, [], 0) == 0, 'reduce of empty with initial'
+assert reduce(lambda a, b: a + b, [5]) == 5, 'reduce of a single item'
+assert reduce(max, [3, 9, 2]) == 9, 'reduce with a builtin'
+assert reduce(lambda acc, s: acc + [s.upper()], 'ab', []) == ['A', 'B'], 'reduce builds a list'
+
+try:
+    reduce(lambda a, b: a + b, [])
+    assert False, 'empty reduce should raise'
+except TypeError as e:
+    assert str(e) == 'reduce() of empty iterable with no initial value', 'empty reduce message'
+
+# === partial ===
+
+
+def power(base, exponent):
+    return base**exponent
+
+
+square = partial(power, exponent=2)
+assert square(5) == 25, 'partial with keyword'
+assert square(3, exponent=3) == 27, 'call keywords override partial keywords'
+two_to = partial(power, 2)
+assert two_to(10) == 1024, 'partial with positional'
+assert partial(power, 2, 3)() == 8, 'partial with every argument'
+assert partial(partial(power, 3), 2)() == 9, 'nested partial'
+
+int_base2 = partial(int, base=2)
+assert int_base2('101') == 5, 'partial of a builtin'
+assert square.func is power, 'partial.func'
+assert two_to.args == (2,), 'partial.args'
+assert square.keywords == {'exponent': 2}, 'partial.keywords'
+assert list(map(partial(power, exponent=3), [1, 2])) == [1, 8], 'partial with map'
+
+try:
+    partial(1)
+    assert False, 'non-callable should raise'
+except TypeError as e:
+    assert str(e) == 'the first argument must be callable', 'partial non-callable message'
+
+# === lru_cache ===
+calls = []
+
+
+@lru_cache(maxsize=None)
+def fib(n):
+    calls.append(n)
+    if n < 2:
+        return n
+    return fib(n - 1) + fib(n - 2)
+
+
+assert fib(30) == 832040, 'cached recursion'
+assert len(calls) == 31, 'each argument computed once'
+info = fib.cache_info()
+assert info.hits == 28, 'cache hits'
+assert info.misses == 31, 'cache misses'
+assert info.maxsize is None, 'unbounded maxsize'
+assert info.currsize == 31, 'cache size'
+fib.cache_clear()
+assert fib.cache_info() == (0, 0, None, 0), 'cache_clear resets the statistics'
+assert fib.__wrapped__(1) == 1, '__wrapped__ is the original function'
+
+
+@lru_cache(maxsize=2)
+def double(n):
+    calls.append(n)
+    return n * 2
+
+
+calls = []
+assert double(1) == 2, 'bounded cache miss'
+assert double(2) == 4, 'second entry'
+assert double(1) == 2, 'bounded cache hit'
+assert double(3) == 6, 'third entry evicts the least recently used'
+assert double(1) == 2, 'recently used entry kept'
+assert double(2) == 4, 'evicted entry recomputed'
+assert calls == [1, 2, 3, 2], 'least recently used eviction'
+assert double.cache_info().currsize == 2, 'bounded size'
+
+
+@lru_cache
+def greet(name, punctuation='!'):
+    calls.append(name)
+    return 'hello ' + name + punctuation
+
+
+calls = []
+assert greet('a') == 'hello a!', 'bare decorator'
+assert greet('a') == 'hello a!', 'bare decorator hit'
+assert greet('a', punctuation='?') == 'hello a?', 'keywords are part of the key'
+assert greet('a', punctuation='?') == 'hello a?', 'keyword hit'
+assert calls == ['a', 'a'], 'keyword calls cached separately'
+assert greet.cache_info().maxsize == 128, 'default maxsize'
+
+
+@lru_cache()
+def ident(x):
+    return x
+
+
+assert ident(1) == 1, 'decorator with no arguments'
+
+try:
+    ident([1])
+    assert False, 'unhashable argument should raise'
+except TypeError as e:
+    assert str(e) == "unhashable type: 'list'", 'unhashable argument message'
+
+
+@lru_cache(maxsize=0)
+def no_cache(x):
+    calls.append(x)
+    return x
+
+
+calls = []
+no_cache(1)
+no_cache(1)
+assert calls == [1, 1], 'maxsize=0 caches nothing'
+assert no_cache.cache_info().currsize == 0, 'maxsize=0 size'
+
+# === cache ===
+
+
+@cache
+def factorial(n):
+    return n * factorial(n - 1) if n else 1
+
+
+assert factorial(10) == 3628800, 'cache decorator'
+assert factorial.cache_info().currsize == 11, 'cache stores every result'
+assert factorial.cache_info().maxsize is None, 'cache is unbounded'
+
+# === module attributes ===
+assert functools.reduce(lambda a, b: a - b, [10, 1, 2]) == 7, 'module attribute access'
@@ -0,0 +1,6 @@
+def f(x):
+    global x  # type: ignore
+
+
+f(1)
+# Raise=SyntaxError("name 'x' is parameter and global")
@@ -0,0 +1,62 @@
+# === and / or / xor ===
+assert 0b1100 & 0b1010 == 0b1000, 'int and'
+assert 0b1100 | 0b1010 == 0b1110, 'int or'
+assert 0b1100 ^ 0b1010 == 0b0110, 'int xor'
+assert -1 & 0xFF == 255, 'negative and mask'
+assert -8 | 3 == -5, 'negative or'
+assert -1 ^ 5 == -6, 'negative xor'
+
+# === shifts ===
+assert 1 << 10 == 1024, 'left shift'
+assert 1024 >> 3 == 128, 'right shift'
+assert -16 >> 2 == -4, 'negative right shift floors'
+assert -1 >> 100 == -1, 'negative right shift saturates at -1'
+assert 5 >> 100 == 0, 'positive right shift saturates at 0'
+assert 1 << 70 == 1180591620717411303424, 'left shift promotes to long int'
+assert (1 << 70) >> 68 == 4, 'right shift of long int'
+
+# === invert ===
+assert ~0 == -1, 'invert zero'
+assert ~5 == -6, 'invert positive'
+assert ~-1 == 0, 'invert negative'
+
+# === bools ===
+assert (True & False) is False, 'bool and bool is bool'
+assert (True | False) is True, 'bool or bool is bool'
+assert (True ^ True) is False, 'bool xor bool is bool'
+assert True & 3 == 1, 'bool and int is int'
+assert type(True & 3) is int, 'bool and int type'
+assert True << 3 == 8, 'bool left shift'
+assert type(True << 0) is int, 'bool shift returns int'
+
+# === augmented assignment ===
+x = 0b1111
+x &= 0b0101
+assert x == 0b0101, 'and-assign'
+x |= 0b1000
+assert x == 0b1101, 'or-assign'
+x ^= 0b0001
+assert x == 0b1100, 'xor-assign'
+x <<= 2
+assert x == 0b110000, 'lshift-assign'
+x >>= 4
+assert x == 0b11, 'rshift-assign'
+
+# === errors ===
+try:
+    1 << -1
+    assert False, 'should raise ValueError'
+except ValueError as e:
+    assert str(e) == 'negative shift count', 'negative shift message'
+
+try:
+    1.0 & 1
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for &: 'float' and 'int'", 'float and message'
+
+try:
+    'a' | 1
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for |: 'str' and 'int'", 'str or message'
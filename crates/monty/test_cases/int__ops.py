@@ -217,3 +217,28 @@ except ValueError as e:
     assert str(e) == "invalid literal for int() with base 10: 'a\\tbc'", f'got err: {e}'
 else:
     raise AssertionError('int conversion from string should fail')
+
+# === int-int fast paths at the edges ===
+big = 9223372036854775807
+assert big + 1 == 9223372036854775808, 'add overflow promotes'
+assert -big - 2 == -9223372036854775809, 'sub overflow promotes'
+assert big * 2 == 18446744073709551614, 'mul overflow promotes'
+small = -9223372036854775808
+assert small // -1 == 9223372036854775808, 'floordiv overflow promotes'
+assert small % -1 == 0, 'mod min by -1'
+assert -7 // 2 == -4, 'floordiv rounds toward negative infinity'
+assert -7 % 2 == 1, 'mod takes sign of divisor'
+assert 7 % -2 == -1, 'mod negative divisor'
+n = big
+n += 1
+assert n == 9223372036854775808, 'iadd overflow promotes'
+n = 10
+n %= 4
+assert n == 2, 'imod'
+n //= 1
+assert n == 2, 'ifloordiv'
+total = 0
+for i in range(100):
+    if i % 7 == 3 and i >= 10 and i < 90 and i != 52:
+        total += i
+assert total == 530, 'int compare and iadd in a loop'
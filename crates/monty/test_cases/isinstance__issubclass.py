@@ -0,0 +1,63 @@
+# === issubclass() with builtin types ===
+assert issubclass(int, int), 'class is a subclass of itself'
+assert issubclass(bool, int), 'bool is a subclass of int'
+assert not issubclass(int, bool), 'int is not a subclass of bool'
+assert not issubclass(str, bytes), 'str is not a subclass of bytes'
+assert issubclass(list, (tuple, list)), 'tuple of classes'
+assert issubclass(bool, (str, (float, int))), 'nested tuple of classes'
+assert not issubclass(dict, ()), 'empty tuple'
+
+# === issubclass() with exceptions ===
+assert issubclass(KeyError, LookupError), 'builtin exception hierarchy'
+assert issubclass(ZeroDivisionError, ArithmeticError), 'ZeroDivisionError is an ArithmeticError'
+assert issubclass(ValueError, Exception), 'ValueError is an Exception'
+assert not issubclass(Exception, ValueError), 'Exception is not a ValueError'
+assert not issubclass(ValueError, int), 'exception is not a subclass of int'
+
+
+class AppError(Exception):
+    pass
+
+
+class ConfigError(AppError):
+    pass
+
+
+assert issubclass(ConfigError, AppError), 'user exception subclass'
+assert issubclass(ConfigError, Exception), 'user exception derives from builtin'
+assert not issubclass(AppError, ConfigError), 'parent is not a subclass of child'
+assert not issubclass(ConfigError, ValueError), 'unrelated builtin exception'
+assert issubclass(ConfigError, (KeyError, AppError)), 'user exception in tuple'
+
+# === type() results are comparable type objects ===
+assert type(1) == int, 'type of int'
+assert type(True) is bool, 'type of bool'
+assert type('a') is not int, 'type of str is not int'
+assert type([]) == type([1]), 'types of two lists are equal'
+assert issubclass(type(True), int), 'issubclass with type() result'
+assert type(ValueError('x')) is ValueError, 'type of builtin exception is the exception class'
+assert type(KeyError()) == KeyError, 'type of exception compares equal to its class'
+assert issubclass(type(KeyError()), LookupError), 'issubclass with exception type() result'
+assert isinstance(KeyError(), type(KeyError())), 'isinstance with exception type() result'
+assert type(ValueError()).__name__ == 'ValueError', 'exception type name'
+assert ValueError.__name__ == 'ValueError', 'exception class name'
+assert {int: 'int', ValueError: 'value'}[type(ValueError())] == 'value', 'type objects as dict keys'
+
+# === errors ===
+try:
+    issubclass(1, int)
+    assert False, 'non-class arg 1 should raise'
+except TypeError as e:
+    assert str(e) == 'issubclass() arg 1 must be a class', 'arg 1 error'
+
+try:
+    issubclass(int, 1)
+    assert False, 'non-class arg 2 should raise'
+except TypeError as e:
+    assert str(e) == 'issubclass() arg 2 must be a class, a tuple of classes, or a union', 'arg 2 error'
+
+try:
+    issubclass(int)
+    assert False, 'one argument should raise'
+except TypeError as e:
+    assert str(e) == 'issubclass expected 2 arguments, got 1', 'arg count error'
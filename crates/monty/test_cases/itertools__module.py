@@ -0,0 +1,110 @@
+import itertools
+from itertools import chain, count, groupby, islice, product
+
+# === chain ===
+assert list(chain([1, 2], (3,), 'ab')) == [1, 2, 3, 'a', 'b'], 'chain concatenates iterables'
+assert list(chain()) == [], 'chain of nothing'
+assert list(chain([], [1], [])) == [1], 'chain skips empty iterables'
+it = chain(iter([1, 2]), range(3, 5))
+assert next(it) == 1, 'chain is lazy'
+assert list(it) == [2, 3, 4], 'chain continues after next()'
+assert iter(it) is it, 'chain is an iterator'
+
+try:
+    list(chain([1], 5))
+    assert False, 'non-iterable should raise'
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable", 'chain non-iterable message'
+
+# === count ===
+c = count()
+assert [next(c) for _ in range(3)] == [0, 1, 2], 'count from zero'
+c = count(10, 5)
+assert [next(c) for _ in range(3)] == [10, 15, 20], 'count with start and step'
+c = count(step=-2)
+assert [next(c) for _ in range(3)] == [0, -2, -4], 'count with keyword step'
+assert list(zip('abc', count(1))) == [('a', 1), ('b', 2), ('c', 3)], 'count with zip'
+total = 0
+for i in count(1):
+    if i > 4:
+        break
+    total += i
+assert total == 10, 'count in a for loop'
+
+# === islice ===
+assert list(islice('abcdefg', 2)) == ['a', 'b'], 'islice with stop'
+assert list(islice('abcdefg', 2, 4)) == ['c', 'd'], 'islice with start and stop'
+assert list(islice('abcdefg', 2, None)) == ['c', 'd', 'e', 'f', 'g'], 'islice without stop'
+assert list(islice('abcdefg', 0, None, 2)) == ['a', 'c', 'e', 'g'], 'islice with step'
+assert list(islice('abcdefg', 1, 6, 3)) == ['b', 'e'], 'islice with start, stop and step'
+assert list(islice([1, 2, 3], 10)) == [1, 2, 3], 'islice past the end'
+assert list(islice([1, 2, 3], 5, 10)) == [], 'islice start past the end'
+assert list(islice(count(), 3)) == [0, 1, 2], 'islice of an endless iterator'
+assert list(islice(count(5), 2, 8, 2)) == [7, 9, 11], 'islice steps over an endless iterator'
+
+it = iter(range(10))
+assert list(islice(it, 3)) == [0, 1, 2], 'islice consumes the iterator'
+assert next(it) == 3, 'islice leaves the rest of the iterator'
+
+try:
+    islice([1], -1)
+    assert False, 'negative stop should raise'
+except ValueError as e:
+    assert str(e) == 'Stop argument for islice() must be None or an integer: 0 <= x <= sys.maxsize.', 'stop message'
+
+try:
+    islice([1], -1, 2)
+    assert False, 'negative start should raise'
+except ValueError as e:
+    assert str(e) == 'Indices for islice() must be None or an integer: 0 <= x <= sys.maxsize.', 'start message'
+
+try:
+    islice([1], 0, 2, 0)
+    assert False, 'zero step should raise'
+except ValueError as e:
+    assert str(e) == 'Step for islice() must be a positive integer or None.', 'step message'
+
+# === product ===
+assert list(product('ab', [1, 2])) == [('a', 1), ('a', 2), ('b', 1), ('b', 2)], 'product of two'
+assert list(product([0, 1], repeat=2)) == [(0, 0), (0, 1), (1, 0), (1, 1)], 'product with repeat'
+assert list(product('ab', 'c', repeat=2)) == [
+    ('a', 'c', 'a', 'c'),
+    ('a', 'c', 'b', 'c'),
+    ('b', 'c', 'a', 'c'),
+    ('b', 'c', 'b', 'c'),
+], 'product repeats the whole sequence of iterables'
+assert list(product()) == [()], 'product of nothing'
+assert list(product([1, 2], [])) == [], 'product with an empty iterable'
+assert list(product([], repeat=0)) == [()], 'product with zero repeat'
+assert len(list(product(range(3), range(4), range(5)))) == 60, 'product size'
+
+try:
+    product([1], repeat=-1)
+    assert False, 'negative repeat should raise'
+except ValueError as e:
+    assert str(e) == 'repeat argument cannot be negative', 'repeat message'
+
+# === groupby ===
+groups = [(k, list(g)) for k, g in groupby('aaabbca')]
+assert groups == [('a', ['a', 'a', 'a']), ('b', ['b', 'b']), ('c', ['c']), ('a', ['a'])], 'groupby runs'
+assert [k for k, _ in groupby([1, 1, 2, 2, 2, 1])] == [1, 2, 1], 'groupby keys'
+assert list(groupby([])) == [], 'groupby of nothing'
+
+words = ['apple', 'avocado', 'banana', 'blueberry', 'cherry']
+by_letter = {k: list(g) for k, g in groupby(words, key=lambda w: w[0])}
+assert by_letter == {'a': ['apple', 'avocado'], 'b': ['banana', 'blueberry'], 'c': ['cherry']}, 'groupby key'
+
+
+def parity(n):
+    return n % 2
+
+
+assert [(k, list(g)) for k, g in groupby([1, 3, 2, 4, 5], parity)] == [
+    (1, [1, 3]),
+    (0, [2, 4]),
+    (1, [5]),
+], 'groupby with a def key'
+assert [k for k, _ in groupby([1, 1, 2], key=None)] == [1, 2], 'groupby with key=None'
+
+# === module attributes ===
+assert list(itertools.islice(itertools.count(), 2)) == [0, 1], 'module attribute access'
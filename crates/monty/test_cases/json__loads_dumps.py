@@ -0,0 +1,159 @@
+import json
+from json import dumps, loads
+
+# === loads ===
+assert loads('null') is None, 'null'
+assert loads('true') is True, 'true'
+assert loads('false') is False, 'false'
+assert loads('42') == 42, 'int'
+assert loads('-7') == -7, 'negative int'
+assert loads('-0') == 0, 'negative zero int'
+assert loads('123456789012345678901234567890') == 123456789012345678901234567890, 'big int'
+assert loads('1.5') == 1.5, 'float'
+assert loads('1e3') == 1000.0, 'exponent'
+assert isinstance(loads('1e3'), float), 'exponent is float'
+assert loads('-2.5E-2') == -0.025, 'negative exponent'
+assert loads('"hello"') == 'hello', 'string'
+assert loads('""') == '', 'empty string'
+assert loads('"a\\nb\\t\\"c\\" \\\\ \\/"') == 'a\nb\t"c" \\ /', 'escapes'
+assert loads('"\\u00e9\\u4e2d"') == 'é中', 'unicode escapes'
+assert loads('"\\ud83d\\ude00"') == '😀', 'surrogate pair'
+assert loads('"é😀"') == 'é😀', 'raw unicode'
+assert loads('[]') == [], 'empty list'
+assert loads('[1, "two", [3.0, null]]') == [1, 'two', [3.0, None]], 'nested list'
+assert loads('{}') == {}, 'empty object'
+assert loads('{"a": 1, "b": [true, false]}') == {'a': 1, 'b': [True, False]}, 'object'
+assert loads('{"a": 1, "a": 2}') == {'a': 2}, 'duplicate keys keep the last value'
+assert list(loads('{"z": 1, "a": 2, "m": 3}')) == ['z', 'a', 'm'], 'object keeps key order'
+assert loads(' \n\t{ "a" : [ 1 , 2 ] } \r\n') == {'a': [1, 2]}, 'whitespace'
+assert loads(b'[1, 2]') == [1, 2], 'bytes input'
+assert loads(bytearray(b'{"k": "v"}')) == {'k': 'v'}, 'bytearray input'
+assert loads(b'\xef\xbb\xbf[1]') == [1], 'bytes with BOM'
+nan = loads('NaN')
+assert nan != nan, 'NaN'
+assert loads('Infinity') == float('inf'), 'Infinity'
+assert loads('-Infinity') == float('-inf'), '-Infinity'
+
+# === dumps ===
+assert dumps(None) == 'null', 'dumps None'
+assert dumps(True) == 'true', 'dumps True'
+assert dumps(False) == 'false', 'dumps False'
+assert dumps(42) == '42', 'dumps int'
+assert dumps(10**30) == '1000000000000000000000000000000', 'dumps big int'
+assert dumps(1.5) == '1.5', 'dumps float'
+assert dumps(2.0) == '2.0', 'dumps whole float'
+assert dumps(float('nan')) == 'NaN', 'dumps nan'
+assert dumps(float('inf')) == 'Infinity', 'dumps inf'
+assert dumps(float('-inf')) == '-Infinity', 'dumps -inf'
+assert dumps('hi') == '"hi"', 'dumps str'
+assert dumps('a"b\\c\n\t\x01') == '"a\\"b\\\\c\\n\\t\\u0001"', 'dumps escapes'
+assert dumps('é中😀') == '"\\u00e9\\u4e2d\\ud83d\\ude00"', 'dumps ensure_ascii'
+assert dumps('é中😀', ensure_ascii=False) == '"é中😀"', 'dumps without ensure_ascii'
+assert dumps('\x7f') == '"\\u007f"', 'dumps DEL is escaped'
+assert dumps([1, 'a', None]) == '[1, "a", null]', 'dumps list'
+assert dumps((1, 2)) == '[1, 2]', 'dumps tuple'
+assert dumps([]) == '[]', 'dumps empty list'
+assert dumps({'a': 1, 'b': [2, 3]}) == '{"a": 1, "b": [2, 3]}', 'dumps dict'
+assert dumps({}) == '{}', 'dumps empty dict'
+assert dumps({3: 'a', 2.5: 'b', True: 'c', None: 'd'}) == '{"3": "a", "2.5": "b", "true": "c", "null": "d"}', (
+    'dumps non-str keys'
+)
+
+# === dumps options ===
+assert dumps({'b': 1, 'a': 2}, sort_keys=True) == '{"a": 2, "b": 1}', 'sort_keys'
+assert dumps({3: 'x', 1: 'y', 2: 'z'}, sort_keys=True) == '{"1": "y", "2": "z", "3": "x"}', 'sort_keys numeric'
+assert dumps({'a': [1, 2], 'b': {}}, indent=2) == '{\n  "a": [\n    1,\n    2\n  ],\n  "b": {}\n}', 'indent'
+assert dumps([1, [2]], indent='\t') == '[\n\t1,\n\t[\n\t\t2\n\t]\n]', 'indent string'
+assert dumps([1, 2], indent=0) == '[\n1,\n2\n]', 'indent zero'
+assert dumps([], indent=4) == '[]', 'indent empty list'
+assert dumps({'a': 1, 'b': 2}, separators=(',', ':')) == '{"a":1,"b":2}', 'compact separators'
+assert dumps({'a': [1]}, indent=1, separators=(', ', ' = ')) == '{\n "a" = [\n  1\n ]\n}', 'indent and separators'
+
+# === round trip ===
+data = {'name': 'monty', 'tags': ['a', 'b'], 'nested': {'x': 1.25, 'y': None, 'z': [True, False]}, 'n': -3}
+assert loads(dumps(data)) == data, 'round trip'
+assert loads(dumps(data, indent=4, sort_keys=True)) == data, 'round trip pretty'
+
+# === decode errors ===
+assert issubclass(json.JSONDecodeError, ValueError), 'JSONDecodeError is a ValueError'
+
+decode_errors = [
+    ('', 'Expecting value: line 1 column 1 (char 0)'),
+    ('[1,', 'Expecting value: line 1 column 4 (char 3)'),
+    ('{"a" 1}', "Expecting ':' delimiter: line 1 column 6 (char 5)"),
+    ('{a:1}', 'Expecting property name enclosed in double quotes: line 1 column 2 (char 1)'),
+    ('[1 2]', "Expecting ',' delimiter: line 1 column 4 (char 3)"),
+    ('"abc', 'Unterminated string starting at: line 1 column 1 (char 0)'),
+    ('"a\x01"', 'Invalid control character at: line 1 column 3 (char 2)'),
+    ('"\\x"', 'Invalid \\escape: line 1 column 2 (char 1)'),
+    ('"\\u12"', 'Invalid \\uXXXX escape: line 1 column 3 (char 2)'),
+    ('1 2', 'Extra data: line 1 column 3 (char 2)'),
+    ('{"a":1,}', 'Illegal trailing comma before end of object: line 1 column 7 (char 6)'),
+    ('[1,]', 'Illegal trailing comma before end of array: line 1 column 3 (char 2)'),
+    ('tru', 'Expecting value: line 1 column 1 (char 0)'),
+    ('01', 'Extra data: line 1 column 2 (char 1)'),
+    ('\n\n  [', 'Expecting value: line 3 column 4 (char 5)'),
+    ('["é", x]', 'Expecting value: line 1 column 7 (char 6)'),
+    ('{"a":1', "Expecting ',' delimiter: line 1 column 7 (char 6)"),
+    ('﻿[]', 'Unexpected UTF-8 BOM (decode using utf-8-sig): line 1 column 1 (char 0)'),
+]
+for doc, message in decode_errors:
+    try:
+        loads(doc)
+        assert False, f'loads({doc!r}) should raise'
+    except json.JSONDecodeError as e:
+        assert str(e) == message, f'loads({doc!r}) message: {e}'
+
+try:
+    loads('[')
+    assert False, 'should raise'
+except ValueError:
+    pass
+
+try:
+    loads(1)
+    assert False, 'loads int should raise'
+except TypeError as e:
+    assert str(e) == 'the JSON object must be str, bytes or bytearray, not int', 'loads type message'
+
+# === encode errors ===
+try:
+    dumps({1, 2})
+    assert False, 'dumps set should raise'
+except TypeError as e:
+    assert str(e) == 'Object of type set is not JSON serializable', 'set message'
+
+try:
+    dumps(b'x')
+    assert False, 'dumps bytes should raise'
+except TypeError as e:
+    assert str(e) == 'Object of type bytes is not JSON serializable', 'bytes message'
+
+try:
+    dumps({(1, 2): 3})
+    assert False, 'tuple key should raise'
+except TypeError as e:
+    assert str(e) == 'keys must be str, int, float, bool or None, not tuple', 'key message'
+
+try:
+    dumps(float('nan'), allow_nan=False)
+    assert False, 'allow_nan=False should raise'
+except ValueError as e:
+    assert str(e) == 'Out of range float values are not JSON compliant: nan', 'allow_nan message'
+
+try:
+    dumps({'b': 1, 2: 3}, sort_keys=True)
+    assert False, 'mixed keys should raise'
+except TypeError as e:
+    assert str(e) == "'<' not supported between instances of 'int' and 'str'", 'sort_keys message'
+
+cycle = []
+cycle.append(cycle)
+try:
+    dumps(cycle)
+    assert False, 'cycle should raise'
+except ValueError as e:
+    assert str(e) == 'Circular reference detected', 'cycle message'
+
+shared = [1]
+assert dumps([shared, shared]) == '[[1], [1]]', 'shared references are not cycles'
@@ -190,6 +190,11 @@ lst = []
 lst.extend([])
 assert lst == [], 'extend empty with empty'
 
+lst = [1, [2]]
+lst.extend(lst)
+assert lst == [1, [2], 1, [2]], 'extend with self'
+assert lst[1] is lst[3], 'extend with self shares items'
+
 # === list.index() ===
 lst = [1, 2, 3, 2]
 assert lst.index(2) == 1, 'index finds first occurrence'
@@ -417,3 +422,35 @@ assert [].count([1]) == 0, 'count on empty list'
 assert [1, 2] in [[1, 2], [3, 4]], 'nested list in'
 assert [5, 6] not in [[1, 2], [3, 4]], 'nested list not in'
 assert [] in [[], [1]], 'empty list in list of lists'
+
+# === copies are independent of the original ===
+orig = [1, [2], 'three']
+copies = [orig.copy(), orig[:], list(orig)]
+for c in copies:
+    assert c == orig, 'copy equals original'
+    assert c is not orig, 'copy is a new list'
+    assert c[1] is orig[1], 'copy is shallow'
+orig.append(4)
+orig[0] = 10
+assert orig == [10, [2], 'three', 4], 'mutating the original'
+for c in copies:
+    assert c == [1, [2], 'three'], 'copy unchanged by mutating the original'
+copies[0].pop()
+copies[1].insert(0, 0)
+copies[2].clear()
+assert copies[0] == [1, [2]], 'pop from a copy'
+assert copies[1] == [0, 1, [2], 'three'], 'insert into a copy'
+assert copies[2] == [], 'clear a copy'
+assert orig == [10, [2], 'three', 4], 'original unchanged by mutating copies'
+orig[1].append(3)
+assert copies[0][1] == [2, 3], 'nested list shared by copies'
+
+a = [1, 2, 3]
+b = a.copy()
+c = b.copy()
+b += [4]
+c.reverse()
+del a[0]
+assert a == [2, 3], 'delete from the original'
+assert b == [1, 2, 3, 4], 'extend a copy of a copy'
+assert c == [3, 2, 1], 'reverse a copy of a copy'
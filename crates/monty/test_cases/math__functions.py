@@ -0,0 +1,148 @@
+import math
+from math import sqrt, pi
+
+# === constants ===
+assert pi == 3.141592653589793, 'pi'
+assert math.e == 2.718281828459045, 'e'
+assert math.tau == 2 * math.pi, 'tau'
+assert math.inf > 1e308, 'inf'
+assert math.isnan(math.nan), 'nan'
+
+# === rounding ===
+assert math.floor(2.7) == 2, 'floor positive'
+assert math.floor(-2.1) == -3, 'floor negative'
+assert isinstance(math.floor(2.0), int), 'floor returns int'
+assert math.ceil(2.1) == 3, 'ceil positive'
+assert math.ceil(-2.7) == -2, 'ceil negative'
+assert math.trunc(-2.7) == -2, 'trunc'
+assert math.floor(5) == 5, 'floor of int'
+assert math.floor(True) == 1, 'floor of bool'
+assert math.floor(10**30) == 10**30, 'floor of bigint'
+assert math.ceil(1e20) == 100000000000000000000, 'ceil of large float'
+assert math.modf(3.5) == (0.5, 3.0), 'modf'
+assert math.modf(-2.25) == (-0.25, -2.0), 'modf negative'
+
+# === powers and logarithms ===
+assert sqrt(16) == 4.0, 'sqrt'
+assert math.sqrt(2.25) == 1.5, 'sqrt float'
+assert math.exp(0) == 1.0, 'exp'
+assert math.log(math.e) == 1.0, 'natural log'
+assert math.log(8, 2) == 3.0, 'log with base'
+assert math.log2(1024) == 10.0, 'log2'
+assert math.log10(1000) == 3.0, 'log10'
+assert math.log10(10**400) == 400.0, 'log10 of huge int'
+assert math.isclose(math.log(2**2000), 2000 * math.log(2)), 'log of huge int'
+assert math.pow(2, 10) == 1024.0, 'pow'
+assert math.pow(4, 0.5) == 2.0, 'pow fractional'
+assert math.pow(2, -1) == 0.5, 'pow negative exponent'
+assert math.fabs(-3) == 3.0, 'fabs'
+
+# === trigonometry ===
+assert math.sin(0) == 0.0, 'sin'
+assert math.cos(0) == 1.0, 'cos'
+assert math.isclose(math.sin(math.pi / 2), 1.0), 'sin pi/2'
+assert math.isclose(math.tan(math.pi / 4), 1.0), 'tan pi/4'
+assert math.isclose(math.asin(1), math.pi / 2), 'asin'
+assert math.acos(1) == 0.0, 'acos'
+assert math.isclose(math.atan(1), math.pi / 4), 'atan'
+assert math.isclose(math.atan2(1, 1), math.pi / 4), 'atan2'
+assert math.hypot(3, 4) == 5.0, 'hypot'
+assert math.hypot() == 0.0, 'hypot no args'
+assert math.degrees(math.pi) == 180.0, 'degrees'
+assert math.radians(180) == math.pi, 'radians'
+
+# === floating point helpers ===
+assert math.copysign(3, -0.0) == -3.0, 'copysign'
+assert math.fmod(7, 3) == 1.0, 'fmod'
+assert math.fmod(-7, 3) == -1.0, 'fmod keeps sign of x'
+assert math.fsum([0.1] * 10) == 1.0, 'fsum is exact'
+assert math.fsum([1e100, 1.0, -1e100]) == 1.0, 'fsum cancellation'
+assert math.fsum([]) == 0.0, 'fsum empty'
+assert math.fsum(range(5)) == 10.0, 'fsum over range'
+assert math.fsum([1, math.inf]) == math.inf, 'fsum with inf'
+assert math.isclose(1.0, 1.0 + 1e-10), 'isclose default tolerance'
+assert not math.isclose(1.0, 1.1), 'isclose not close'
+assert math.isclose(1.0, 1.1, rel_tol=0.2), 'isclose rel_tol'
+assert math.isclose(0.0, 1e-10, abs_tol=1e-9), 'isclose abs_tol'
+assert math.isclose(math.inf, math.inf), 'isclose inf'
+assert not math.isclose(math.inf, 1e308), 'inf not close to finite'
+assert not math.isclose(math.nan, math.nan), 'nan not close'
+assert math.isinf(-math.inf), 'isinf'
+assert not math.isinf(1.0), 'isinf finite'
+assert math.isfinite(1), 'isfinite'
+assert not math.isfinite(math.nan), 'isfinite nan'
+
+# === integer functions ===
+assert math.gcd(12, 18) == 6, 'gcd'
+assert math.gcd(-12, 18, 27) == 3, 'gcd multiple'
+assert math.gcd() == 0, 'gcd no args'
+assert math.gcd(2**100, 2**80 * 3) == 2**80, 'gcd bigint'
+assert math.lcm(4, 6) == 12, 'lcm'
+assert math.lcm(4, 0) == 0, 'lcm with zero'
+assert math.lcm() == 1, 'lcm no args'
+assert math.factorial(0) == 1, 'factorial 0'
+assert math.factorial(5) == 120, 'factorial 5'
+assert math.factorial(25) == 15511210043330985984000000, 'factorial bigint'
+assert len(str(math.factorial(1000))) == 2568, 'factorial 1000'
+
+# === errors ===
+for func, arg in [(math.sqrt, -1), (math.log, 0), (math.log, -1.5), (math.acos, 2), (math.sin, math.inf)]:
+    try:
+        func(arg)
+        assert False, f'{func} should raise'
+    except ValueError:
+        pass
+
+try:
+    math.sqrt('x')
+    assert False, 'sqrt str should raise'
+except TypeError as e:
+    assert str(e) == 'must be real number, not str', 'real number message'
+
+try:
+    math.exp(1000)
+    assert False, 'exp overflow should raise'
+except OverflowError as e:
+    assert str(e) == 'math range error', 'range error message'
+
+try:
+    math.floor(math.inf)
+    assert False, 'floor inf should raise'
+except OverflowError as e:
+    assert str(e) == 'cannot convert float infinity to integer', 'floor inf message'
+
+try:
+    math.factorial(-1)
+    assert False, 'factorial negative should raise'
+except ValueError as e:
+    assert str(e) == 'factorial() not defined for negative values', 'factorial negative message'
+
+try:
+    math.factorial(2.0)
+    assert False, 'factorial float should raise'
+except TypeError as e:
+    assert str(e) == "'float' object cannot be interpreted as an integer", 'factorial float message'
+
+try:
+    math.gcd(1.5)
+    assert False, 'gcd float should raise'
+except TypeError as e:
+    assert str(e) == "'float' object cannot be interpreted as an integer", 'gcd float message'
+
+try:
+    math.isclose(1, 1, rel_tol=-1)
+    assert False, 'negative tolerance should raise'
+except ValueError as e:
+    assert str(e) == 'tolerances must be non-negative', 'tolerance message'
+
+try:
+    math.fsum([math.inf, -math.inf])
+    assert False, 'inf - inf should raise'
+except ValueError as e:
+    assert str(e) == '-inf + inf in fsum', 'fsum inf message'
+
+try:
+    math.nosuch
+    assert False, 'missing attribute should raise'
+except AttributeError as e:
+    assert str(e) == "module 'math' has no attribute 'nosuch'", 'attribute error message'
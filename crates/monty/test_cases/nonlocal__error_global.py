@@ -0,0 +1,12 @@
+def outer():
+    x = 1
+
+    def inner():
+        global x
+        nonlocal x  # type: ignore
+
+    return inner
+
+
+outer()
+# Raise=SyntaxError("name 'x' is nonlocal and global")
@@ -0,0 +1,11 @@
+def outer():
+    x = 1
+
+    def inner(x):
+        nonlocal x  # type: ignore
+
+    return inner
+
+
+outer()
+# Raise=SyntaxError("name 'x' is parameter and nonlocal")
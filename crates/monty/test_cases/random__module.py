@@ -0,0 +1,116 @@
+import random
+from random import choice, randint, shuffle, uniform
+
+# === random ===
+for _ in range(100):
+    x = random.random()
+    assert isinstance(x, float), 'random returns a float'
+    assert 0.0 <= x < 1.0, 'random is in [0, 1)'
+
+# === randint ===
+seen = set()
+for _ in range(200):
+    n = randint(1, 6)
+    assert 1 <= n <= 6, 'randint is inclusive'
+    seen.add(n)
+assert seen == {1, 2, 3, 4, 5, 6}, 'randint covers the whole range'
+assert randint(5, 5) == 5, 'single value range'
+assert -3 <= randint(-3, -1) <= -1, 'negative range'
+assert randint(True, 1) == 1, 'bools are ints'
+big = randint(-(2**63), 2**63 - 1)
+assert -(2**63) <= big < 2**63, 'full 64-bit range'
+
+try:
+    randint(5, 1)
+    assert False, 'empty range should raise'
+except ValueError as e:
+    assert str(e) == 'empty range in randrange(5, 2)', 'empty range message'
+
+try:
+    randint(1.5, 2)
+    assert False, 'float bound should raise'
+except TypeError as e:
+    assert str(e) == "'float' object cannot be interpreted as an integer", 'float bound message'
+
+# === uniform ===
+for _ in range(100):
+    x = uniform(2, 5)
+    assert 2.0 <= x <= 5.0, 'uniform is between the bounds'
+    y = uniform(1.5, -1.5)
+    assert -1.5 <= y <= 1.5, 'uniform with reversed bounds'
+assert uniform(3, 3) == 3.0, 'uniform with equal bounds'
+
+try:
+    uniform('a', 1)
+    assert False, 'non-number should raise'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for -: 'int' and 'str'", 'uniform message'
+
+# === choice ===
+items = ['a', 'b', 'c']
+picked = set()
+for _ in range(100):
+    picked.add(choice(items))
+assert picked == {'a', 'b', 'c'}, 'choice covers the list'
+assert choice((7,)) == 7, 'choice from a tuple'
+assert choice('xyz') in 'xyz', 'choice from a string'
+assert choice(range(10, 20)) in range(10, 20), 'choice from a range'
+
+try:
+    choice([])
+    assert False, 'empty sequence should raise'
+except IndexError as e:
+    assert str(e) == 'Cannot choose from an empty sequence', 'empty choice message'
+
+try:
+    choice(5)
+    assert False, 'non-sequence should raise'
+except TypeError as e:
+    assert str(e) == "object of type 'int' has no len()", 'choice without len message'
+
+# === shuffle ===
+nums = list(range(20))
+assert shuffle(nums) is None, 'shuffle returns None'
+assert sorted(nums) == list(range(20)), 'shuffle keeps the elements'
+empty = []
+shuffle(empty)
+assert empty == [], 'shuffle an empty list'
+
+try:
+    shuffle((1, 2))
+    assert False, 'tuple should raise'
+except TypeError as e:
+    assert str(e) == "'tuple' object does not support item assignment", 'shuffle tuple message'
+
+try:
+    shuffle(5)
+    assert False, 'non-sequence should raise'
+except TypeError as e:
+    assert str(e) == "object of type 'int' has no len()", 'shuffle without len message'
+
+# === seed ===
+random.seed(1234)
+first = [random.random(), randint(0, 10**9), choice(items)]
+deck = list(range(10))
+shuffle(deck)
+random.seed(1234)
+second = [random.random(), randint(0, 10**9), choice(items)]
+deck2 = list(range(10))
+shuffle(deck2)
+assert first == second, 'same seed gives the same values'
+assert deck == deck2, 'same seed gives the same shuffle'
+
+random.seed('monty')
+a = random.random()
+random.seed('monty')
+assert random.random() == a, 'string seeds are deterministic'
+random.seed(b'monty')
+random.seed(1.5)
+random.seed(2**100)
+assert random.seed() is None, 'seed returns None'
+
+try:
+    random.seed([1])
+    assert False, 'list seed should raise'
+except TypeError as e:
+    assert str(e) == 'The only supported seed types are:\nNone, int, float, str, bytes, and bytearray.', 'seed message'
@@ -0,0 +1,211 @@
+import re
+from re import compile, findall, fullmatch, search, split, sub
+
+# === module attributes ===
+assert re.IGNORECASE == re.I == 2, 'IGNORECASE'
+assert re.MULTILINE == re.M == 8, 'MULTILINE'
+assert re.DOTALL == re.S == 16, 'DOTALL'
+assert re.VERBOSE == re.X == 64, 'VERBOSE'
+assert re.ASCII == re.A == 256, 'ASCII'
+assert re.UNICODE == re.U == 32, 'UNICODE'
+assert re.NOFLAG == 0, 'NOFLAG'
+assert re.error is re.PatternError, 'error alias'
+assert issubclass(re.error, Exception), 'error is an Exception'
+
+# === search / match / fullmatch ===
+m = search(r'(\d+)-(\d+)', 'tel: 555-1234 ok')
+assert m is not None, 'search finds'
+assert m.group() == '555-1234', 'group()'
+assert m.group(0) == '555-1234', 'group(0)'
+assert m.group(1) == '555', 'group(1)'
+assert m.group(1, 2) == ('555', '1234'), 'group(1, 2)'
+assert m[2] == '1234', 'getitem'
+assert m.groups() == ('555', '1234'), 'groups()'
+assert m.span() == (5, 13), 'span()'
+assert m.start() == 5, 'start()'
+assert m.end(2) == 13, 'end(2)'
+assert m.string == 'tel: 555-1234 ok', 'string'
+assert m.pos == 0, 'pos'
+assert m.endpos == 16, 'endpos'
+assert repr(m) == "<re.Match object; span=(5, 13), match='555-1234'>", 'match repr'
+assert bool(m), 'match is truthy'
+
+assert re.match(r'\d', 'a1') is None, 'match anchors at start'
+assert re.match(r'a\d', 'a1b').group() == 'a1', 'match prefix'
+assert fullmatch(r'a\d', 'a1b') is None, 'fullmatch needs whole string'
+assert fullmatch(r'a\d|a\db', 'a1b').group() == 'a1b', 'fullmatch tries alternatives'
+assert search('x', 'abc') is None, 'search miss'
+
+# === groups ===
+m = search(r'(?P<key>\w+)=(?P<value>\w+)?', 'key=')
+assert m.group('key') == 'key', 'named group'
+assert m.group('value') is None, 'unmatched group'
+assert m.groups() == ('key', None), 'groups with None'
+assert m.groups('') == ('key', ''), 'groups default'
+assert m.groupdict() == {'key': 'key', 'value': None}, 'groupdict'
+assert m.groupdict('-') == {'key': 'key', 'value': '-'}, 'groupdict default'
+assert m.span('value') == (-1, -1), 'span of unmatched group'
+assert m.start(2) == -1, 'start of unmatched group'
+assert m['key'] == 'key', 'getitem by name'
+assert m.expand(r'\g<key>:\1') == 'key:key', 'expand'
+
+try:
+    m.group(3)
+    assert False, 'group(3) should raise'
+except IndexError as e:
+    assert str(e) == 'no such group', 'no such group message'
+
+try:
+    m.group('missing')
+    assert False, 'unknown group name should raise'
+except IndexError as e:
+    assert str(e) == 'no such group', 'unknown name message'
+
+# === unicode positions ===
+m = search('é+', 'café crème')
+assert m.span() == (3, 4), 'spans count characters'
+assert search('m', 'café crème').start() == 8, 'start after multi-byte chars'
+
+# === findall / finditer ===
+assert findall(r'\d+', 'a1b22c333') == ['1', '22', '333'], 'findall no groups'
+assert findall(r'(\w)=(\d)', 'a=1, b=2') == [('a', '1'), ('b', '2')], 'findall groups'
+assert findall(r'(\w)=\d', 'a=1, b=2') == ['a', 'b'], 'findall one group'
+assert findall(r'(a)|b', 'ab') == ['a', ''], 'findall unmatched group'
+assert findall('x*', 'axx') == ['', 'xx', ''], 'findall empty matches'
+assert findall('', 'ab') == ['', '', ''], 'findall empty pattern'
+assert [m.span() for m in re.finditer(r'\w+', 'hi there')] == [(0, 2), (3, 8)], 'finditer'
+assert list(re.finditer('z', 'abc')) == [], 'finditer empty'
+
+# === sub / subn ===
+assert sub(r'\s+', ' ', 'a  b\t\nc') == 'a b c', 'sub'
+assert sub(r'(\w+)@(\w+)', r'\2 at \1', 'me@home') == 'home at me', 'sub backrefs'
+assert sub(r'(?P<n>\d)', r'<\g<n>>', 'a1b2') == 'a<1>b<2>', 'sub named backref'
+assert sub(r'(\d)', r'\g<1>0', '1 2') == '10 20', 'sub \\g<1> before a digit'
+assert sub('a', r'\n\t\\', 'a') == '\n\t\\', 'sub template escapes'
+assert sub('a', 'b', 'aaa', count=2) == 'bba', 'sub count'
+assert sub('a', 'b', 'aaa', count=-1) == 'aaa', 'sub negative count'
+assert sub('x*', '-', 'abc') == '-a-b-c-', 'sub empty matches'
+assert sub(r'\d', lambda m: str(int(m.group()) * 2), 'a1b4') == 'a2b8', 'sub callable'
+assert sub(r'\d', lambda m: None, 'a1b') == 'ab', 'sub callable returning None'
+assert re.subn('a', 'b', 'banana') == ('bbnbnb', 3), 'subn'
+
+try:
+    sub('a', lambda m: 1, 'xa')
+    assert False, 'non-str replacement should raise'
+except TypeError as e:
+    assert str(e) == 'sequence item 1: expected str instance, int found', 'replacement type message'
+
+try:
+    sub('(a)', r'\2', 'a')
+    assert False, 'bad group reference should raise'
+except re.error as e:
+    assert str(e) == 'invalid group reference 2 at position 1', 'invalid group reference message'
+
+try:
+    sub('a', '\\', 'a')
+    assert False, 'trailing backslash should raise'
+except re.error as e:
+    assert str(e) == 'bad escape (end of pattern) at position 0', 'trailing backslash message'
+
+# === split ===
+assert split(r',\s*', 'a, b,c') == ['a', 'b', 'c'], 'split'
+assert split(r'(,)', 'a,b') == ['a', ',', 'b'], 'split keeps groups'
+assert split(r'(,)|;', 'a,b;c') == ['a', ',', 'b', None, 'c'], 'split unmatched group'
+assert split(',', 'a,b,c', maxsplit=1) == ['a', 'b,c'], 'split maxsplit'
+assert split(',', 'a,b', maxsplit=-1) == ['a,b'], 'split negative maxsplit'
+assert split('x*', 'axb') == ['', 'a', '', 'b', ''], 'split empty matches'
+
+# === flags ===
+assert search('abc', 'xABC', re.I).group() == 'ABC', 'IGNORECASE'
+assert findall('^a', 'a\na', re.M) == ['a', 'a'], 'MULTILINE'
+assert findall('^a', 'a\na') == ['a'], 'no MULTILINE'
+assert search('a.b', 'a\nb') is None, 'dot excludes newline'
+assert search('a.b', 'a\nb', re.S).group() == 'a\nb', 'DOTALL'
+assert search(r'\w+', 'héllo', re.A).group() == 'h', 'ASCII'
+assert search(r'\w+', 'héllo').group() == 'héllo', 'unicode by default'
+assert search('(?i)abc', 'ABC').group() == 'ABC', 'inline flag'
+pattern = compile(
+    r"""
+    (\d+)  # number
+    \s*    # gap
+    (\w+)  # unit
+    """,
+    re.VERBOSE,
+)
+assert pattern.match('10 kg').groups() == ('10', 'kg'), 'VERBOSE'
+
+# === compiled patterns ===
+p = compile(r'(?P<word>\w+)')
+assert p.pattern == r'(?P<word>\w+)', 'pattern attribute'
+assert p.flags == re.UNICODE, 'default flags'
+assert compile('a', re.I).flags == re.I | re.U, 'flags attribute'
+assert p.groups == 1, 'groups attribute'
+assert p.groupindex == {'word': 1}, 'groupindex attribute'
+assert repr(p) == "re.compile('(?P<word>\\\\w+)')", 'pattern repr'
+assert repr(compile('a', re.I | re.M)) == "re.compile('a', re.IGNORECASE|re.MULTILINE)", 'pattern repr flags'
+assert compile(p) is p, 'compile of a pattern returns it'
+assert compile('a') == compile('a'), 'pattern equality'
+assert compile('a') != compile('a', re.I), 'pattern inequality'
+assert hash(compile('a')) == hash(compile('a')), 'pattern hash'
+assert search(p, 'hi').group() == 'hi', 'module function with compiled pattern'
+
+assert p.search('  hi', 3).group() == 'i', 'search pos'
+assert p.search('hello', 0, 2).group() == 'he', 'search endpos'
+assert p.match('  hi', 2).group() == 'hi', 'match pos'
+m = p.match('  hi', 2)
+assert m.pos == 2, 'match pos attribute'
+assert m.re is p, 'match re attribute'
+assert p.fullmatch('hi!', 0, 2).group() == 'hi', 'fullmatch endpos'
+assert p.findall('a b c', 2) == ['b', 'c'], 'findall pos'
+assert p.sub('x', 'a b') == 'x x', 'pattern sub'
+assert p.subn('x', 'a b', 1) == ('x b', 1), 'pattern subn'
+assert p.split('a b', 1) == ['', 'a', ' b'], 'pattern split'
+
+try:
+    compile(p, re.I)
+    assert False, 'flags with a compiled pattern should raise'
+except ValueError as e:
+    assert str(e) == 'cannot process flags argument with a compiled pattern', 'compiled flags message'
+
+# === escape ===
+assert re.escape('a.b*c') == 'a\\.b\\*c', 'escape'
+assert re.escape('1 + 1 = 2') == '1\\ \\+\\ 1\\ =\\ 2', 'escape spaces'
+assert re.escape('é_x') == 'é_x', 'escape leaves word chars'
+assert search(re.escape('a.b[c]'), 'xa.b[c]').start() == 1, 'escaped pattern matches literally'
+
+# === errors ===
+try:
+    compile('(')
+    assert False, 'unbalanced paren should raise'
+except re.error:
+    pass
+
+try:
+    compile('a', re.ASCII | re.UNICODE)
+    assert False, 'ASCII with UNICODE should raise'
+except ValueError as e:
+    assert str(e) == 'ASCII and UNICODE flags are incompatible', 'incompatible flags message'
+
+try:
+    search(1, 'a')
+    assert False, 'non-string pattern should raise'
+except TypeError as e:
+    assert str(e) == 'first argument must be string or compiled pattern', 'pattern type message'
+
+try:
+    search('a', 1)
+    assert False, 'non-string subject should raise'
+except TypeError as e:
+    assert str(e) == "expected string or bytes-like object, got 'int'", 'subject type message'
+
+try:
+    search('a', b'a')
+    assert False, 'bytes subject should raise'
+except TypeError as e:
+    assert str(e) == 'cannot use a string pattern on a bytes-like object', 'bytes subject message'
+
+try:
+    search('a')
+    assert False, 'missing argument should raise'
+except TypeError as e:
+    assert str(e) == "search() missing 1 required positional argument: 'string'", 'missing argument message'
@@ -0,0 +1,4 @@
+a = b = [1]
+c = [a]
+c
+# ref-counts={'a': 3, 'b': 3, 'c': 2}
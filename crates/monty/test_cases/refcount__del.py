@@ -0,0 +1,9 @@
+a = [1]
+b = [a, a]
+d = {'k': a}
+del b[0]
+del d['k']
+c = a
+del c
+b
+# ref-counts={'a': 2, 'b': 2, 'd': 1}
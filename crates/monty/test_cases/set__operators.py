@@ -0,0 +1,89 @@
+# === Binary operators ===
+a = {1, 2, 3}
+b = {2, 3, 4}
+assert a | b == {1, 2, 3, 4}, 'union operator'
+assert a & b == {2, 3}, 'intersection operator'
+assert a - b == {1}, 'difference operator'
+assert a ^ b == {1, 4}, 'symmetric difference operator'
+assert a == {1, 2, 3}, 'operands are not mutated'
+assert a | a == a, 'union with itself'
+assert a - a == set(), 'difference with itself'
+assert set() | set() == set(), 'empty union'
+
+# === Result type follows the left operand ===
+f = frozenset([2, 3, 5])
+assert type(a | f) is set, 'set | frozenset is set'
+assert type(f | a) is frozenset, 'frozenset | set is frozenset'
+assert type(f & a) is frozenset, 'frozenset & set is frozenset'
+assert f - a == frozenset([5]), 'frozenset difference'
+assert f ^ frozenset([5, 6]) == frozenset([2, 3, 6]), 'frozenset symmetric difference'
+
+# === Chained operators ===
+assert {1} | {2} | {3} == {1, 2, 3}, 'chained union'
+assert ({1, 2, 3} - {1}) & {2, 9} == {2}, 'mixed operators'
+
+# === In-place operators mutate the set ===
+s = {1, 2}
+alias = s
+s |= {3}
+assert alias == {1, 2, 3}, 'in-place union mutates'
+s &= {2, 3, 4}
+assert alias == {2, 3}, 'in-place intersection mutates'
+s -= frozenset([2])
+assert alias == {3}, 'in-place difference mutates'
+s ^= {3, 4}
+assert alias == {4}, 'in-place symmetric difference mutates'
+assert s is alias, 'in-place keeps identity'
+
+# === In-place on a frozenset rebinds ===
+fs = frozenset([1])
+orig = fs
+fs |= {2}
+assert fs == frozenset([1, 2]), 'frozenset in-place union value'
+assert orig == frozenset([1]), 'original frozenset unchanged'
+assert type(fs) is frozenset, 'frozenset in-place result type'
+
+# === Frozensets are hashable ===
+d = {frozenset([1, 2]): 'a'}
+assert d[frozenset([2, 1])] == 'a', 'frozenset as dict key'
+assert hash(frozenset([1, 2])) == hash(frozenset([2, 1])), 'frozenset hash is order independent'
+assert frozenset([1]) in {frozenset([1]), frozenset([2])}, 'frozenset in set'
+
+# === Errors ===
+try:
+    {1} | [2]
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for |: 'set' and 'list'", 'union with list'
+
+try:
+    {1} - (1,)
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for -: 'set' and 'tuple'", 'difference with tuple'
+
+try:
+    {1} << {2}
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for <<: 'set' and 'set'", 'shift on sets'
+
+s = {1, 2}
+try:
+    s -= [1]
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for -=: 'set' and 'list'", 'in-place difference with list'
+assert s == {1, 2}, 'failed in-place difference leaves the set unchanged'
+
+try:
+    s |= [3]
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for |=: 'set' and 'list'", 'in-place union with list'
+
+try:
+    s <<= 1
+    assert False, 'should raise TypeError'
+except TypeError as e:
+    assert str(e) == "unsupported operand type(s) for <<=: 'set' and 'int'", 'in-place shift on a set'
@@ -0,0 +1,95 @@
+# === Automatic and manual numbering ===
+assert '{} + {} = {}'.format(1, 2, 3) == '1 + 2 = 3', 'automatic numbering'
+assert '{1} {0} {1}'.format('a', 'b') == 'b a b', 'manual numbering'
+assert 'no fields'.format(1, 2) == 'no fields', 'unused args ignored'
+assert ''.format() == '', 'empty template'
+
+# === Keyword fields ===
+assert '{name} is {age}'.format(name='Ann', age=30) == 'Ann is 30', 'keyword fields'
+assert '{0} {x}'.format('pos', x='kw') == 'pos kw', 'mixed positional and keyword'
+d = {'a': 1}
+assert '{a}'.format(**d) == '1', 'keywords from dict unpacking'
+
+# === Escaped braces ===
+assert '{{}}'.format() == '{}', 'escaped braces'
+assert '{{{}}}'.format(5) == '{5}', 'value inside escaped braces'
+assert 'a}}b{{c'.format() == 'a}b{c', 'escaped braces in text'
+
+# === Conversions ===
+assert '{!r}'.format('hi') == "'hi'", 'repr conversion'
+assert '{!s}'.format('hi') == 'hi', 'str conversion'
+assert '{0!a}'.format('café') == "'caf\\xe9'", 'ascii conversion'
+assert '{!r:>6}'.format('hi') == "  'hi'", 'conversion with spec'
+
+# === Format specs ===
+assert '{:.2f}'.format(3.14159) == '3.14', 'float spec'
+assert '{:>5}|{:<5}|{:^5}'.format(1, 2, 3) == '    1|2    |  3  ', 'alignment specs'
+assert '{0:,}'.format(1234567) == '1,234,567', 'grouping spec'
+assert '{x:#x}'.format(x=255) == '0xff', 'keyword with spec'
+assert '{:}'.format([1]) == '[1]', 'empty spec'
+
+# === Nested replacement fields in specs ===
+assert '{:>{}}'.format('a', 4) == '   a', 'nested automatic field'
+assert '{0:{1}{2}}'.format('a', '>', 4) == '   a', 'nested manual fields'
+assert '{:{fill}^{width}}'.format('x', fill='*', width=5) == '**x**', 'nested keyword fields'
+assert '{0:.{prec}f}'.format(2.71828, prec=2) == '2.72', 'nested precision'
+
+# === Item lookups ===
+assert '{0[1]}'.format([10, 20]) == '20', 'list index lookup'
+assert '{0[a]}'.format({'a': 5}) == '5', 'dict key lookup'
+assert '{data[k]}'.format(data={'k': 'v'}) == 'v', 'keyword then key lookup'
+assert '{0[1][0]}'.format([[1], [2]]) == '2', 'chained lookups'
+assert '{[0]}'.format(['first']) == 'first', 'automatic field with lookup'
+
+# === Works on heap strings too ===
+template = '-'.join(['{}', '{}'])
+assert template.format('a', 'b') == 'a-b', 'heap string template'
+
+
+# === Errors ===
+def error(template, *args, **kwargs):
+    try:
+        template.format(*args, **kwargs)
+    except (IndexError, KeyError, TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+assert error('}') == "ValueError: Single '}' encountered in format string", 'single close'
+assert error('x{') == "ValueError: Single '{' encountered in format string", 'single open'
+assert error('{0') == "ValueError: expected '}' before end of string", 'unclosed field'
+assert error('{}{0}', 1) == (
+    'ValueError: cannot switch from automatic field numbering to manual field specification'
+), 'auto then manual'
+assert error('{0}{}', 1) == (
+    'ValueError: cannot switch from manual field specification to automatic field numbering'
+), 'manual then auto'
+assert error('{2}', 1) == 'IndexError: Replacement index 2 out of range for positional args tuple', 'bad index'
+assert error('{}') == 'IndexError: Replacement index 0 out of range for positional args tuple', 'no args'
+assert error('{a}') == "KeyError: 'a'", 'missing keyword'
+assert error('{!x}', 1) == 'ValueError: Unknown conversion specifier x', 'bad conversion'
+assert error('{!rx}', 1) == "ValueError: expected ':' after conversion specifier", 'junk after conversion'
+assert error('{0[]}', [1]) == 'ValueError: Empty attribute in format string', 'empty lookup'
+assert error('{:{:{}}}', 1, 2, 3) == 'ValueError: Max string recursion exceeded', 'nesting too deep'
+assert error('{:d}', 'a') == "ValueError: Unknown format code 'd' for object of type 'str'", 'bad spec for value'
+assert error('{!r:d}', 1) == "ValueError: Unknown format code 'd' for object of type 'str'", 'spec after conversion'
+assert error('{:99999999999999999999}', 1) == 'ValueError: Too many decimal digits in format string', 'width overflow'
+
+# === format_map ===
+assert '{name} is {age}'.format_map({'name': 'Ann', 'age': 30}) == 'Ann is 30', 'format_map dict'
+assert '{x[0]:>3}'.format_map({'x': [7]}) == '  7', 'format_map lookup and spec'
+assert '{{{a}}}'.format_map({'a': 1}) == '{1}', 'format_map escaped braces'
+assert 'plain'.format_map({}) == 'plain', 'format_map no fields'
+
+
+def map_error(template, mapping):
+    try:
+        template.format_map(mapping)
+    except (KeyError, TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+assert map_error('{a}', {}) == "KeyError: 'a'", 'format_map missing key'
+assert map_error('{}', {}) == 'ValueError: Format string contains positional fields', 'format_map positional'
+assert map_error('{0}', {0: 1}) == 'ValueError: Format string contains positional fields', 'format_map index'
@@ -325,3 +325,91 @@ assert '½'.isnumeric() == True, 'isnumeric fraction'
 assert '²'.isnumeric() == True, 'isnumeric superscript'
 assert '٠١٢٣٤٥٦٧٨٩'.isnumeric() == True, 'isnumeric Arabic-Indic'
 assert '0123456789'.isnumeric() == True, 'isnumeric ASCII'
+
+# === Phase 11: expandtabs, translate, maketrans, casefold and encode error handlers ===
+
+# expandtabs()
+assert 'a\tb'.expandtabs() == 'a       b', 'expandtabs default'
+assert 'a\tbc\td'.expandtabs(4) == 'a   bc  d', 'expandtabs columns'
+assert '\tx\n\ty'.expandtabs(2) == '  x\n  y', 'expandtabs newline resets column'
+assert 'a\tb'.expandtabs(tabsize=3) == 'a  b', 'expandtabs keyword'
+assert 'a\tb'.expandtabs(0) == 'ab', 'expandtabs zero removes tabs'
+assert 'a\tb'.expandtabs(-1) == 'ab', 'expandtabs negative removes tabs'
+assert 'no tabs'.expandtabs() == 'no tabs', 'expandtabs no tabs'
+
+# maketrans() and translate()
+table = str.maketrans('abc', 'xyz')
+assert table == {97: 120, 98: 121, 99: 122}, 'maketrans two strings'
+assert 'aabbcc'.translate(table) == 'xxyyzz', 'translate with maketrans'
+assert str.maketrans('a', 'b', 'cd') == {97: 98, 99: None, 100: None}, 'maketrans delete chars'
+assert str.maketrans({'a': 'A', 98: None}) == {97: 'A', 98: None}, 'maketrans dict'
+assert ''.maketrans('a', 'b') == {97: 98}, 'maketrans on instance'
+assert 'abcd'.translate(str.maketrans('ab', 'AB', 'd')) == 'ABc', 'translate delete'
+assert 'hello'.translate({ord('l'): 'LL', ord('o'): None}) == 'heLLLL', 'translate str and None'
+assert 'abc'.translate({97: 65}) == 'Abc', 'translate int'
+assert 'abc'.translate(['x'] * 98) == 'xbc', 'translate list table'
+assert ''.translate({}) == '', 'translate empty'
+
+
+def trans_error(*args):
+    try:
+        str.maketrans(*args)
+    except (TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+assert trans_error('ab', 'c') == 'ValueError: the first two maketrans arguments must have equal length', (
+    'maketrans unequal lengths'
+)
+assert trans_error({'ab': 1}) == 'ValueError: string keys in translate table must be of length 1', 'maketrans long key'
+assert trans_error({1.5: 1}) == 'TypeError: keys in translate table must be strings or integers', 'maketrans bad key'
+assert trans_error('a') == 'TypeError: if you give only one argument to maketrans it must be a dict', (
+    'maketrans one non-dict'
+)
+assert trans_error(1, 'a') == 'TypeError: first maketrans argument must be a string if there is a second argument', (
+    'maketrans non-str first'
+)
+try:
+    'a'.translate({97: 1.5})
+    assert False, 'translate bad mapping should raise'
+except TypeError as e:
+    assert str(e) == 'character mapping must return integer, None or str', 'translate bad mapping'
+
+# casefold() edge cases
+assert 'Straße'.casefold() == 'strasse', 'casefold sharp s'
+assert 'ΣΑΣ'.casefold() == 'σασ', 'casefold no final sigma'
+assert 'ς'.casefold() == 'σ', 'casefold final sigma'
+assert 'ﬁne'.casefold() == 'fine', 'casefold ligature'
+assert 'µ'.casefold() == 'μ', 'casefold micro sign'
+assert 'ꭰ'.casefold() == 'Ꭰ', 'casefold cherokee'
+assert 'Straße'.casefold() == 'STRASSE'.casefold(), 'casefold caseless match'
+
+# encode() codecs and error handlers
+assert 'café'.encode('latin-1') == b'caf\xe9', 'encode latin-1'
+assert 'abc'.encode('ascii') == b'abc', 'encode ascii'
+assert 'café'.encode('ascii', 'ignore') == b'caf', 'encode ignore'
+assert 'café'.encode('ascii', 'replace') == b'caf?', 'encode replace'
+assert 'café €'.encode('ascii', 'backslashreplace') == b'caf\\xe9 \\u20ac', 'encode backslashreplace'
+assert 'café'.encode('ascii', errors='xmlcharrefreplace') == b'caf&#233;', 'encode xmlcharrefreplace'
+assert '€'.encode('latin-1', 'replace') == b'?', 'encode latin-1 replace'
+assert 'café'.encode(encoding='utf-8') == b'caf\xc3\xa9', 'encode keyword encoding'
+try:
+    'café'.encode('ascii')
+    assert False, 'encode ascii should raise'
+except UnicodeEncodeError as e:
+    assert str(e) == "'ascii' codec can't encode character '\\xe9' in position 3: ordinal not in range(128)", (
+        'encode error message'
+    )
+try:
+    'aéé'.encode('ascii')
+    assert False, 'encode ascii run should raise'
+except ValueError as e:
+    assert str(e) == "'ascii' codec can't encode characters in position 1-2: ordinal not in range(128)", (
+        'encode error run message'
+    )
+try:
+    'a'.encode('bogus')
+    assert False, 'unknown encoding should raise'
+except LookupError as e:
+    assert str(e) == 'unknown encoding: bogus', 'encode unknown encoding'
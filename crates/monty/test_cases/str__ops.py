@@ -23,6 +23,33 @@ s = 'ab'
 s += s
 assert s == 'abab', 'iadd self'
 
+s = str(12345)
+t = s
+s += '6'
+assert s == '123456', 'iadd aliased result'
+assert t == '12345', 'iadd does not mutate alias'
+
+a = str(4242)
+b = str(4242)
+a += 'y'
+assert a == '4242y', 'iadd equal runtime string'
+assert b == '4242', 'iadd does not mutate equal runtime string'
+
+s = ''
+for i in range(200):
+    s += 'x' if i < 100 else 'é'
+    assert len(s) == i + 1, 'iadd len while growing'
+    assert s[i] == s[-1], 'iadd index while growing'
+assert s[99] == 'x' and s[100] == 'é' and s[199] == 'é', 'iadd index after switching to non-ascii'
+assert s[64:130] == 'x' * 36 + 'é' * 30, 'iadd slice across strides'
+
+keys = [str(i % 3) + '!' for i in range(9)]
+assert keys == ['0!', '1!', '2!'] * 3, 'repeated runtime strings'
+d = {}
+for k in keys:
+    d[k] = d.get(k, 0) + 1
+assert d == {'0!': 3, '1!': 3, '2!': 3}, 'repeated runtime strings as keys'
+
 # === String length ===
 assert len('') == 0, 'len empty'
 assert len('a') == 1, 'len single'
@@ -0,0 +1,113 @@
+# === Basic conversions ===
+assert 'Hello, %s!' % 'world' == 'Hello, world!', 'single value'
+assert '%s and %s' % ('a', 'b') == 'a and b', 'tuple of values'
+assert '%s' % ((1, 2),) == '(1, 2)', 'tuple wrapped in a tuple'
+assert '%s' % [1, 2] == '[1, 2]', 'list value'
+assert '%s' % None == 'None', 'None value'
+assert '%r' % 'x' == "'x'", 'repr conversion'
+assert '%a' % 'é' == "'\\xe9'", 'ascii conversion'
+assert '100%%' % () == '100%', 'escaped percent'
+assert '%5.2f%%' % 12.345 == '12.35%', 'percent after a conversion'
+assert 'no conversions' % () == 'no conversions', 'empty tuple'
+
+# === Integers ===
+assert '%d|%i|%u' % (5, -2, 7) == '5|-2|7', 'decimal types'
+assert '%d' % True == '1', 'bool as int'
+assert '%d' % 3.99 == '3', 'float truncated'
+assert '%i' % -0.5 == '0', 'negative float truncated'
+assert '%d' % 10**20 == '100000000000000000000', 'long int'
+assert '%x|%X|%o' % (255, 255, 8) == 'ff|FF|10', 'other bases'
+assert '%x' % -255 == '-ff', 'negative hex'
+assert '%#x|%#X|%#o' % (255, 255, 8) == '0xff|0XFF|0o10', 'alternate form'
+assert '%#X' % 2**70 == '0X400000000000000000', 'long int alternate hex'
+assert '%ld' % 5 == '5', 'length modifier ignored'
+
+# === Flags, width and precision ===
+assert '%5d|%-5d|%05d|%-05d|' % (5, 5, 5, 5) == '    5|5    |00005|5    |', 'width flags'
+assert '%+d|% d|%+05d' % (5, 5, 3) == '+5| 5|+0003', 'sign flags'
+assert '%.3d|%5.3d|%+.3d|%#.3x' % (5, 5, 5, 5) == '005|  005|+005|0x005', 'int precision'
+assert '%.3x' % -5 == '-005', 'negative int precision'
+assert '%010.3d' % 5 == '0000000005', 'zero padding with precision'
+assert '%#010x' % 255 == '0x000000ff', 'zero padding after prefix'
+assert '%*d|%-*d|' % (4, 7, 4, 7) == '   7|7   |', 'star width'
+assert '%*d|' % (-4, 1) == '1   |', 'negative star width'
+assert '%*.*f' % (8, 2, 3.14159) == '    3.14', 'star width and precision'
+assert '%0*d' % (5, 42) == '00042', 'zero flag with star width'
+
+# === Floats ===
+assert '%f' % 1.5 == '1.500000', 'default precision'
+assert '%.2f|%5.1f' % (3.14159, 2.25) == '3.14|  2.2', 'precision and width'
+assert '%.0f' % 2.5 == '2', 'zero precision'
+assert '%#.0f' % 2 == '2.', 'alternate zero precision'
+assert '%e|%E' % (12345.678, 0.5) == '1.234568e+04|5.000000E-01', 'exponent types'
+assert '% .3e' % 1.5 == ' 1.500e+00', 'space sign with exponent'
+assert '%g|%g|%G' % (0.0001, 10**20, 1e-10) == '0.0001|1e+20|1E-10', 'general types'
+assert '%#g' % 1.0 == '1.00000', 'alternate general'
+assert '%010.3f|%-010.3f|' % (-1.5, -1.5) == '-00001.500|-1.500    |', 'float padding'
+assert '%F' % float('inf') == 'INF', 'uppercase infinity'
+assert '%f' % 3 == '3.000000', 'int as float'
+
+# === Strings and characters ===
+assert '%5s|%-5s|%05s|' % ('a', 'a', 'a') == '    a|a    |    a|', 'string width'
+assert '%.2s|%5.1s|' % ('abc', 'abc') == 'ab|    a|', 'string precision'
+assert '%.3s' % 12345 == '123', 'precision truncates str of value'
+assert '%+s' % 'a' == 'a', 'sign flag ignored for strings'
+assert '%c%c' % (65, 'z') == 'Az', 'char from int and str'
+assert '%5c|%-3c|' % (65, 'é') == '    A|é  |', 'char width'
+
+# === Mapping keys ===
+assert '%(a)s-%(b)05.1f' % {'a': 'A', 'b': 2.5} == 'A-002.5', 'mapping keys'
+assert '%(a)s %(a)r' % {'a': 'x'} == "x 'x'", 'key used twice'
+assert '%(a(b))s' % {'a(b)': 1} == '1', 'nested parentheses in key'
+assert '%s' % {'a': 1} == "{'a': 1}", 'dict as single value'
+assert '%s %(a)s' % {'a': 1} == "{'a': 1} 1", 'dict then key'
+assert 'text' % {'a': 1} == 'text', 'unused mapping'
+assert 'text' % [] == 'text', 'unused list'
+
+# === In-place and fused operations ===
+s = 'x=%d'
+s %= 42
+assert s == 'x=42', 'in-place percent'
+assert ('%d' % 5 == 5) is False, 'formatted string never equals an int'
+assert 10 % 3 == 1, 'int modulo still works'
+
+
+# === Errors ===
+def error(template, args):
+    try:
+        template % args
+    except (KeyError, OverflowError, TypeError, ValueError) as e:
+        return f'{type(e).__name__}: {e}'
+    return 'no error'
+
+
+assert error('%s %s', ('a',)) == 'TypeError: not enough arguments for format string', 'too few args'
+assert error('%s', ()) == 'TypeError: not enough arguments for format string', 'empty tuple'
+assert error('%s', (1, 2)) == 'TypeError: not all arguments converted during string formatting', 'too many'
+assert error('abc', 5) == 'TypeError: not all arguments converted during string formatting', 'no conversions'
+assert error('%', 1) == 'ValueError: incomplete format', 'incomplete'
+assert error('%s%', 1) == 'ValueError: incomplete format', 'trailing percent'
+assert error('%q', 1) == "ValueError: unsupported format character 'q' (0x71) at index 1", 'bad type'
+assert error('%5%', 1) == "ValueError: unsupported format character '%' (0x25) at index 2", 'percent type'
+assert error('%é', 1) == "ValueError: unsupported format character '?' (0xe9) at index 1", 'non-ascii type'
+assert error('%d', 'x') == 'TypeError: %d format: a real number is required, not str', 'd with str'
+assert error('%d', None) == 'TypeError: %d format: a real number is required, not NoneType', 'd with None'
+assert error('%x', 1.0) == 'TypeError: %x format: an integer is required, not float', 'x with float'
+assert error('%f', 'a') == 'TypeError: must be real number, not str', 'f with str'
+assert error('%f', 10**400) == 'OverflowError: int too large to convert to float', 'f with huge int'
+assert error('%d', float('inf')) == 'OverflowError: cannot convert float infinity to integer', 'd with inf'
+assert error('%d', float('nan')) == 'ValueError: cannot convert float NaN to integer', 'd with nan'
+assert error('%c', 'ab') == 'TypeError: %c requires int or char', 'c with long str'
+assert error('%c', 1.0) == 'TypeError: %c requires int or char', 'c with float'
+assert error('%c', -1) == 'OverflowError: %c arg not in range(0x110000)', 'c out of range'
+assert error('%(a)s', (1,)) == 'TypeError: format requires a mapping', 'key without mapping'
+assert error('%(a', {}) == 'ValueError: incomplete format key', 'unclosed key'
+assert error('%(a)s', {}) == "KeyError: 'a'", 'missing key'
+assert error('%(a)s %s', {'a': 1}) == 'TypeError: not enough arguments for format string', 'key then positional'
+assert error('%*d', ('a', 1)) == 'TypeError: * wants int', 'star with str'
+assert error('%*s', (3,)) == 'TypeError: not enough arguments for format string', 'star consumes arg'
+assert error(5, 'x') == "TypeError: unsupported operand type(s) for %: 'int' and 'str'", 'int lhs'
+assert error('%99999999999999999999d', 1) == 'ValueError: width too big', 'width overflow'
+assert error('%.99999999999999999999f', 1) == 'ValueError: precision too big', 'precision overflow'
+assert error('%.2147483648s', 'a') == 'ValueError: precision too big', 'precision above int max'
+assert error('%.*f', (2**31, 1.0)) == 'OverflowError: Python int too large to convert to C int', 'star precision overflow'
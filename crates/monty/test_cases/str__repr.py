@@ -0,0 +1,45 @@
+# === quote selection ===
+assert repr('abc') == "'abc'", 'plain string'
+assert repr("it's") == '"it\'s"', 'single quote uses double quotes'
+assert repr('say "hi"') == '\'say "hi"\'', 'double quote uses single quotes'
+assert repr('it\'s "nice"') == '\'it\\\'s "nice"\'', 'both quotes escapes the single quote'
+assert repr('') == "''", 'empty string'
+
+# === short escapes ===
+assert repr('a\nb') == "'a\\nb'", 'newline'
+assert repr('a\tb\rc') == "'a\\tb\\rc'", 'tab and carriage return'
+assert repr('back\\slash') == "'back\\\\slash'", 'backslash'
+
+# === non-printable ASCII and Latin-1 use \x escapes ===
+assert repr('\x00') == "'\\x00'", 'null'
+assert repr('\x07\x1b') == "'\\x07\\x1b'", 'control characters'
+assert repr('\x7f') == "'\\x7f'", 'delete'
+assert repr('\x85') == "'\\x85'", 'C1 control'
+assert repr('\xa0') == "'\\xa0'", 'no-break space'
+assert repr('\xad') == "'\\xad'", 'soft hyphen'
+
+# === other non-printable characters use \u and \U escapes ===
+assert repr('\u200b') == "'\\u200b'", 'zero width space'
+assert repr('\u2028') == "'\\u2028'", 'line separator'
+assert repr('\u3000') == "'\\u3000'", 'ideographic space'
+assert repr('\ufeff') == "'\\ufeff'", 'byte order mark'
+assert repr('\ue000') == "'\\ue000'", 'private use'
+assert repr('\U000e0001') == "'\\U000e0001'", 'language tag'
+assert repr('\U0010ffff') == "'\\U0010ffff'", 'noncharacter'
+
+# === printable unicode is kept ===
+assert repr('café') == "'café'", 'accented letter'
+assert repr('日本語') == "'日本語'", 'CJK'
+assert repr('🐍') == "'🐍'", 'emoji'
+assert repr('ß\u0301') == "'ß\u0301'", 'combining mark'
+
+# === containers and exceptions use the same escaping ===
+assert repr(['\x00', 'é']) == "['\\x00', 'é']", 'list of strings'
+assert repr({'\t': '\u200b'}) == "{'\\t': '\\u200b'}", 'dict of strings'
+assert repr(ValueError('a\x00b')) == "ValueError('a\\x00b')", 'exception repr'
+assert str(KeyError('\x01')) == "'\\x01'", 'KeyError str uses repr'
+
+try:
+    int('\u200b1')
+except ValueError as e:
+    assert str(e) == "invalid literal for int() with base 10: '\\u200b1'", 'int error message'
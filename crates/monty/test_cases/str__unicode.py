@@ -0,0 +1,102 @@
+# === len counts code points ===
+assert len('café') == 4, 'len with accent'
+assert len('日本語') == 3, 'len cjk'
+assert len('a\U0001f600b') == 3, 'len astral'
+assert len('e\u0301') == 2, 'len combining mark is its own code point'
+
+# === indexing ===
+s = 'héllo 世界 \U0001f600!'
+assert s[1] == 'é', 'index accent'
+assert s[6] == '世', 'index cjk'
+assert s[9] == '\U0001f600', 'index astral'
+assert s[-1] == '!', 'negative index'
+assert s[-2] == '\U0001f600', 'negative index astral'
+try:
+    s[11]
+    assert False, 'index past end should raise'
+except IndexError:
+    pass
+
+# === indexing long strings in a loop ===
+long = ''.join(chr(0x3b1 + i % 24) + str(i % 10) for i in range(150))
+assert len(long) == 300, 'long len'
+chars = []
+for i in range(len(long)):
+    chars.append(long[i])
+assert ''.join(chars) == long, 'index every code point'
+assert long[200] == chr(0x3b1 + 100 % 24), 'index after several strides'
+assert long[-1] == '9', 'negative index in long string'
+assert long[-300] == 'α', 'most negative index in long string'
+
+# === slicing ===
+assert s[1:4] == 'éll', 'slice with accent'
+assert s[6:8] == '世界', 'slice cjk'
+assert s[-3:] == ' \U0001f600!', 'slice from negative start'
+assert s[::2] == 'hlo世 !', 'slice with step'
+assert s[::-1] == '!\U0001f600 界世 olléh', 'reversed'
+assert long[100:104] == long[100] + long[101] + long[102] + long[103], 'slice long string'
+assert s[5:2] == '', 'empty slice'
+
+# === mutating resets the index ===
+t = 'été'
+assert t[2] == 'é', 'index before append'
+t += '世'
+assert t[3] == '世', 'index after append'
+assert len(t) == 4, 'len after append'
+
+# === title and capitalize use titlecase ===
+assert 'ǆungla'.title() == 'ǅungla', 'title digraph'
+assert 'ǄUNGLA'.capitalize() == 'ǅungla', 'capitalize digraph'
+assert 'ßa'.title() == 'Ssa', 'title sharp s'
+assert 'ﬁsh'.title() == 'Fish', 'title ligature'
+assert 'აბ'.title() == 'აბ', 'georgian is its own titlecase'
+assert 'あa'.title() == 'あA', 'title after uncased letter'
+assert 'x\u0301y'.title() == 'X\u0301Y', 'title after combining mark'
+assert 'ᾀ'.title() == 'ᾈ', 'title iota subscript'
+
+# === final sigma ===
+assert 'ΑΣ'.capitalize() == 'Ας', 'capitalize final sigma'
+assert 'ΑΣ ΒΣ'.lower() == 'ας βς', 'lower final sigma'
+assert 'ΣΑ'.lower() == 'σα', 'lower initial sigma'
+assert 'ΑΣΑ'.title() == 'Ασα', 'title medial sigma'
+assert 'ΑΣ. x'.title() == 'Ας. X', 'title sigma before punctuation'
+assert 'ΑΣ\u0301'.swapcase() == 'ας\u0301', 'swapcase sigma before combining mark'
+
+# === swapcase ===
+assert 'Été'.swapcase() == 'éTÉ', 'swapcase accents'
+assert 'ǅ'.swapcase() == 'ǅ', 'swapcase leaves titlecase alone'
+assert 'ß'.swapcase() == 'SS', 'swapcase sharp s'
+
+# === casefold ===
+assert 'Ꭰᏸ'.casefold() == 'ᎠᏰ', 'cherokee folds to capitals'
+assert 'ꭰ'.casefold() == 'Ꭰ', 'cherokee small letter'
+assert 'ᾳ'.casefold() == 'αι', 'casefold iota subscript'
+assert 'ᲀ'.casefold() == 'в', 'casefold cyrillic variant'
+assert 'Straße'.casefold() == 'strasse', 'casefold sharp s'
+
+# === predicates ===
+assert not '\u0915\u093f'.isalpha(), 'vowel sign is not alpha'
+assert 'é世'.isalpha(), 'letters are alpha'
+assert not 'Ⅰ'.isalpha(), 'roman numeral is not alpha'
+assert 'Ⅰ'.isnumeric(), 'roman numeral is numeric'
+assert '一万'.isnumeric(), 'cjk numerals are numeric'
+assert '一'.isalnum(), 'cjk numeral is alnum'
+assert not '一'.isdigit(), 'cjk numeral is not a digit'
+assert '\x1c\x1f'.isspace(), 'separators are space'
+assert '\u3000 '.isspace(), 'unicode spaces'
+assert not '\u200b'.isspace(), 'zero width space is not space'
+assert '٣๑'.isdecimal(), 'decimals from other scripts'
+assert '\U0001d7ce'.isdecimal(), 'mathematical digit is decimal'
+assert '²①'.isdigit(), 'superscript and circled digits'
+assert not '²'.isdecimal(), 'superscript is not decimal'
+assert not '⑩'.isdigit(), 'circled ten is not a digit'
+assert '⑩'.isnumeric(), 'circled ten is numeric'
+assert '١٢'.isdigit(), 'arabic-indic digits'
+
+# === titlecase characters ===
+assert not 'ǅ'.islower(), 'titlecase is not lower'
+assert not 'ǅ'.isupper(), 'titlecase is not upper'
+assert 'ǅungla'.istitle(), 'titlecase starts a title word'
+assert not 'aǅ'.istitle(), 'titlecase after cased'
+assert 'été'.islower(), 'lower accents'
+assert 'ÉTÉ'.isupper(), 'upper accents'
@@ -0,0 +1,19 @@
+class AppError(Exception):
+    pass
+
+
+def foo():
+    raise AppError('something went wrong')
+
+
+foo()
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "traceback__custom_exception.py", line 9, in <module>
+    foo()
+    ~~~~~
+  File "traceback__custom_exception.py", line 6, in foo
+    raise AppError('something went wrong')
+AppError: something went wrong
+"""
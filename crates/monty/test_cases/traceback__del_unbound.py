@@ -0,0 +1,18 @@
+def f():
+    x = 1
+    del x
+    del x
+
+
+f()
+"""
+TRACEBACK:
+Traceback (most recent call last):
+  File "traceback__del_unbound.py", line 7, in <module>
+    f()
+    ~~~
+  File "traceback__del_unbound.py", line 4, in f
+    del x
+        ~
+UnboundLocalError: cannot access local variable 'x' where it is not associated with a value
+"""
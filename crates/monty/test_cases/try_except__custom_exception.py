@@ -0,0 +1,119 @@
+class AppError(Exception):
+    """Base error for the application."""
+
+
+class ConfigError(AppError):
+    pass
+
+
+class MissingKeyError(KeyError): ...
+
+
+# === raise and catch by class ===
+try:
+    raise AppError('boom')
+except AppError as e:
+    assert repr(e) == "AppError('boom')", 'repr uses class name'
+    assert str(e) == 'boom', 'str is the message'
+    assert e.args == ('boom',), 'args'
+
+# === catch by builtin parent ===
+try:
+    raise ConfigError('bad config')
+except Exception as e:
+    assert repr(e) == "ConfigError('bad config')", 'caught by Exception'
+
+# === catch subclass by user-defined parent ===
+try:
+    raise ConfigError('nested')
+except AppError as e:
+    assert str(e) == 'nested', 'caught by user parent'
+
+# === parent is not caught by subclass handler ===
+caught = None
+try:
+    try:
+        raise AppError('outer')
+    except ConfigError:
+        caught = 'config'
+except AppError:
+    caught = 'app'
+assert caught == 'app', 'parent not matched by subclass handler'
+
+# === builtin exceptions are not caught by user classes ===
+caught = None
+try:
+    try:
+        raise ValueError('v')
+    except AppError:
+        caught = 'app'
+except ValueError:
+    caught = 'value'
+assert caught == 'value', 'builtin not matched by user class'
+
+# === tuple of handlers ===
+try:
+    raise MissingKeyError('k')
+except (ValueError, MissingKeyError) as e:
+    assert repr(e) == "MissingKeyError('k')", 'tuple handler'
+
+# === subclass of a builtin other than Exception ===
+try:
+    raise MissingKeyError('key')
+except LookupError as e:
+    assert isinstance(e, KeyError), 'isinstance builtin parent'
+
+# === raise class without instantiation ===
+try:
+    raise ConfigError
+except AppError as e:
+    assert repr(e) == 'ConfigError()', 'bare class is instantiated'
+    assert e.args == (), 'no args'
+
+# === type, isinstance and __name__ ===
+err = ConfigError('x')
+assert type(err) is ConfigError, 'type is the class'
+assert type(err) is not AppError, 'type is not the parent'
+assert isinstance(err, ConfigError), 'isinstance own class'
+assert isinstance(err, AppError), 'isinstance user parent'
+assert isinstance(err, Exception), 'isinstance builtin ancestor'
+assert not isinstance(AppError('y'), ConfigError), 'not isinstance subclass'
+assert isinstance(err, (ValueError, AppError)), 'isinstance tuple'
+assert ConfigError.__name__ == 'ConfigError', '__name__'
+assert repr(ConfigError) == "<class '__main__.ConfigError'>", 'class repr'
+
+
+# === classes usable inside functions ===
+def fail(msg):
+    raise ConfigError(msg)
+
+
+try:
+    fail('from function')
+except AppError as e:
+    assert str(e) == 'from function', 'raised in function'
+
+# === base is evaluated like any other expression ===
+Alias = KeyError
+
+
+class AliasedError(Alias):
+    pass
+
+
+assert issubclass(AliasedError, KeyError), 'base read from a variable'
+assert not issubclass(AliasedError, ValueError), 'base is the value, not the name'
+
+
+def shadowed_base():
+    ValueError = TypeError
+
+    class ShadowedError(ValueError):
+        pass
+
+    return ShadowedError
+
+
+ShadowedError = shadowed_base()
+assert issubclass(ShadowedError, TypeError), 'local name shadows the builtin'
+assert not issubclass(ShadowedError, ValueError), 'builtin with the same name is not the base'
@@ -0,0 +1,100 @@
+# === raise ... from exception instance ===
+try:
+    try:
+        int('x')
+    except ValueError as e:
+        raise KeyError('lookup failed') from e
+except KeyError as e:
+    assert repr(e) == "KeyError('lookup failed')", 'raised exception'
+    assert type(e.__cause__) is ValueError, 'cause type'
+    assert str(e.__cause__) == "invalid literal for int() with base 10: 'x'", 'cause message'
+
+# === raise ... from exception type ===
+try:
+    raise TypeError('bad') from IndexError
+except TypeError as e:
+    assert repr(e.__cause__) == 'IndexError()', 'cause type is instantiated'
+
+# === raise ... from None ===
+try:
+    try:
+        {}['k']
+    except KeyError:
+        raise ValueError('no cause') from None
+except ValueError as e:
+    assert e.__cause__ is None, 'from None clears cause'
+
+# === plain raise has no cause ===
+try:
+    raise RuntimeError('plain')
+except RuntimeError as e:
+    assert e.__cause__ is None, 'no cause by default'
+
+
+# === cause survives re-raise and function boundaries ===
+def inner():
+    raise OSError('inner') from ZeroDivisionError('zero')
+
+
+def outer():
+    try:
+        inner()
+    except OSError:
+        raise
+
+
+try:
+    outer()
+except OSError as e:
+    assert repr(e.__cause__) == "ZeroDivisionError('zero')", 'cause kept through bare raise'
+
+# === nested causes ===
+try:
+    try:
+        raise ValueError('a') from KeyError('b')
+    except ValueError as e:
+        raise TypeError('c') from e
+except TypeError as e:
+    assert repr(e.__cause__) == "ValueError('a')", 'outer cause'
+    assert repr(e.__cause__.__cause__) == "KeyError('b')", 'inner cause'
+
+# === invalid cause ===
+try:
+    raise ValueError('x') from 1
+except TypeError as e:
+    assert str(e) == 'exception causes must derive from BaseException', 'invalid cause message'
+
+# === invalid exception takes precedence over cause ===
+try:
+    raise 1 from ValueError()
+except TypeError as e:
+    assert str(e) == 'exceptions must derive from BaseException', 'invalid exception message'
+
+# === __cause__ is the cause object itself ===
+cause = KeyError('c')
+raised = ValueError('v')
+try:
+    raise raised from cause
+except ValueError as e:
+    assert e is raised, 'handler binds the raised object'
+    assert e.__cause__ is cause, 'cause is the same object'
+    assert e.__cause__ is e.__cause__, 'repeated reads return the same object'
+
+try:
+    raise ValueError('t') from IndexError
+except ValueError as e:
+    assert e.__cause__ is e.__cause__, 'instantiated cause is kept'
+
+
+def reraise_with_cause():
+    try:
+        raise raised from cause
+    except ValueError:
+        raise
+
+
+try:
+    reraise_with_cause()
+except ValueError as e:
+    assert e is raised, 'bare raise keeps the object'
+    assert e.__cause__ is cause, 'bare raise keeps the cause object'
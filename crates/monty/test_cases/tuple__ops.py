@@ -50,6 +50,8 @@ assert (1, 2) * 1 == (1, 2), 'tuple mult one'
 assert tuple() == (), 'tuple() empty'
 assert tuple([1, 2, 3]) == (1, 2, 3), 'tuple from list'
 assert tuple((1, 2, 3)) == (1, 2, 3), 'tuple from tuple'
+t = (1, [2], 3)
+assert tuple(t) is t, 'tuple from tuple is the same object'
 assert tuple(range(3)) == (0, 1, 2), 'tuple from range'
 assert tuple('abc') == ('a', 'b', 'c'), 'tuple from string'
 assert tuple(b'abc') == (97, 98, 99), 'tuple from bytes'
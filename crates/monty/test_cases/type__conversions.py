@@ -0,0 +1,247 @@
+# === int() ===
+assert int() == 0, 'int no args'
+assert int(7) == 7, 'int from int'
+assert int(True) == 1, 'int from bool'
+assert int(3.9) == 3, 'int truncates positive float'
+assert int(-3.9) == -3, 'int truncates negative float'
+assert int(1e20) == 100000000000000000000, 'int from large float'
+assert int(-1e19) == -10000000000000000000, 'int from large negative float'
+assert int(2**70) == 2**70, 'int from bigint'
+
+# strings, whitespace and underscores
+assert int('42') == 42, 'int from str'
+assert int('  -17\n') == -17, 'int strips whitespace'
+assert int('+5') == 5, 'int with plus sign'
+assert int('1_000_000') == 1000000, 'int with underscores'
+assert int('123456789012345678901234567890') == 123456789012345678901234567890, 'int from long str'
+assert int(b'12') == 12, 'int from bytes'
+assert int(bytearray(b' 34 ')) == 34, 'int from bytearray'
+
+# explicit base
+assert int('ff', 16) == 255, 'int base 16'
+assert int('FF', base=16) == 255, 'int base keyword'
+assert int('0xff', 16) == 255, 'int base 16 with prefix'
+assert int('0x_ff', 16) == 255, 'underscore after prefix'
+assert int('-0b101', 2) == -5, 'int base 2 with prefix and sign'
+assert int('777', 8) == 511, 'int base 8'
+assert int('z', 36) == 35, 'int base 36'
+assert int('0b1', 16) == 177, 'b is a hex digit without base 0'
+assert int('ffffffffffffffffffff', 16) == 2**80 - 1, 'int base 16 bigint'
+
+# base 0 infers the base from the prefix
+assert int('0x1F', 0) == 31, 'base 0 hex'
+assert int('0o17', 0) == 15, 'base 0 octal'
+assert int('0B11', 0) == 3, 'base 0 binary'
+assert int('10', 0) == 10, 'base 0 decimal'
+assert int('000', 0) == 0, 'base 0 allows all zeros'
+assert int('1_0', 0) == 10, 'base 0 with underscore'
+
+# invalid literals
+for text, base in [
+    ('', 10),
+    ('   ', 10),
+    ('abc', 10),
+    ('1.5', 10),
+    ('_1', 10),
+    ('1_', 10),
+    ('1__0', 10),
+    ('0x', 16),
+    ('0x_', 16),
+    ('0xff', 10),
+    ('010', 0),
+    ('12', 2),
+    ('- 1', 10),
+]:
+    try:
+        int(text, base)
+        assert False, f'int({text!r}, {base}) should raise'
+    except ValueError as e:
+        assert str(e) == f'invalid literal for int() with base {base}: {text!r}', f'message for {text!r}'
+
+try:
+    int(b'x')
+    assert False, 'int(bytes) invalid literal'
+except ValueError as e:
+    assert str(e) == "invalid literal for int() with base 10: b'x'", 'bytes literal message'
+
+try:
+    int('1' * 10 + 'x' * 300)
+    assert False, 'long invalid literal'
+except ValueError as e:
+    assert len(str(e)) == len('invalid literal for int() with base 10: ') + 200, 'repr truncated to 200 chars'
+
+# invalid bases and arguments
+try:
+    int('1', 1)
+    assert False, 'base 1 should raise'
+except ValueError as e:
+    assert str(e) == 'int() base must be >= 2 and <= 36, or 0', 'base range message'
+
+try:
+    int('1', 37)
+    assert False, 'base 37 should raise'
+except ValueError as e:
+    assert str(e) == 'int() base must be >= 2 and <= 36, or 0', 'base 37 message'
+
+try:
+    int('1', '10')
+    assert False, 'str base should raise'
+except TypeError as e:
+    assert str(e) == "'str' object cannot be interpreted as an integer", 'base type message'
+
+try:
+    int(10, 16)
+    assert False, 'non-string with base should raise'
+except TypeError as e:
+    assert str(e) == "int() can't convert non-string with explicit base", 'explicit base message'
+
+try:
+    int(base=16)
+    assert False, 'missing string should raise'
+except TypeError as e:
+    assert str(e) == 'int() missing string argument', 'missing string message'
+
+try:
+    int(x='1')
+    assert False, 'x keyword should raise'
+except TypeError as e:
+    assert str(e) == "int() got an unexpected keyword argument 'x'", 'positional-only x message'
+
+try:
+    int('1', 10, 2)
+    assert False, 'three args should raise'
+except TypeError as e:
+    assert str(e) == 'int expected at most 2 arguments, got 3', 'int arg count message'
+
+try:
+    int('1', 10, base=2)
+    assert False, 'base given twice should raise'
+except TypeError as e:
+    assert str(e) == 'int() takes at most 2 arguments (3 given)', 'int total arg count message'
+
+try:
+    int(float('nan'))
+    assert False, 'int(nan) should raise'
+except ValueError as e:
+    assert str(e) == 'cannot convert float NaN to integer', 'nan message'
+
+try:
+    int(float('-inf'))
+    assert False, 'int(inf) should raise'
+except OverflowError as e:
+    assert str(e) == 'cannot convert float infinity to integer', 'inf message'
+
+# === float() ===
+assert float() == 0.0, 'float no args'
+assert float(3) == 3.0, 'float from int'
+assert float(False) == 0.0, 'float from bool'
+assert float(2**70) == 1.1805916207174113e21, 'float from bigint'
+assert float('1.5') == 1.5, 'float from str'
+assert float('  -2.5e3 ') == -2500.0, 'float with exponent and whitespace'
+assert float('.5') == 0.5, 'float leading dot'
+assert float('5.') == 5.0, 'float trailing dot'
+assert float('1_000.000_1') == 1000.0001, 'float with underscores'
+assert float('1e1_0') == 1e10, 'float underscore in exponent'
+assert float(b'0.25') == 0.25, 'float from bytes'
+assert float('inf') == float('infinity'), 'inf spellings'
+assert float('-Infinity') < -1e308, 'negative infinity'
+assert float('+INF') > 1e308, 'case-insensitive inf'
+nan = float('nan')
+assert nan != nan, 'nan is not equal to itself'
+assert float('-NaN') != float('-NaN'), 'signed nan'
+
+for text in ['', ' ', 'abc', '1.5.5', '1_', '_1', '1__0', '1_.5', '1._5', 'in_f', 'infinit', '1e', '--1', 'nan(1)']:
+    try:
+        float(text)
+        assert False, f'float({text!r}) should raise'
+    except ValueError as e:
+        assert str(e) == f'could not convert string to float: {text!r}', f'message for {text!r}'
+
+try:
+    float(b'x')
+    assert False, 'float(bytes) invalid literal'
+except ValueError as e:
+    assert str(e) == "could not convert string to float: b'x'", 'bytes float message'
+
+try:
+    float(10**400)
+    assert False, 'huge int to float should raise'
+except OverflowError as e:
+    assert str(e) == 'int too large to convert to float', 'int too large message'
+
+try:
+    float(x='1')
+    assert False, 'float keyword should raise'
+except TypeError as e:
+    assert str(e) == 'float() takes no keyword arguments', 'float kwargs message'
+
+try:
+    float(1, 2)
+    assert False, 'float two args should raise'
+except TypeError as e:
+    assert str(e) == 'float expected at most 1 argument, got 2', 'float arg count message'
+
+# === bool() ===
+assert bool() is False, 'bool no args'
+assert bool(0) is False, 'bool of zero'
+assert bool('x') is True, 'bool of non-empty str'
+assert bool([]) is False, 'bool of empty list'
+
+try:
+    bool(x=1)
+    assert False, 'bool keyword should raise'
+except TypeError as e:
+    assert str(e) == 'bool() takes no keyword arguments', 'bool kwargs message'
+
+# === str() ===
+assert str() == '', 'str no args'
+assert str(12) == '12', 'str of int'
+assert str(object=1.5) == '1.5', 'str object keyword'
+assert str(b'abc') == "b'abc'", 'str of bytes without encoding is the repr'
+assert str(b'caf\xc3\xa9', 'utf-8') == 'café', 'str decodes bytes'
+assert str(bytearray(b'hi'), encoding='utf8') == 'hi', 'str decodes bytearray'
+assert str(encoding='utf-8') == '', 'str with only encoding'
+assert str(b'a\xffb', errors='replace') == 'a�b', 'str with replace handler'
+assert str(b'a\xffb', 'utf-8', 'ignore') == 'ab', 'str with ignore handler'
+
+try:
+    str('abc', 'utf-8')
+    assert False, 'decoding str should raise'
+except TypeError as e:
+    assert str(e) == 'decoding str is not supported', 'decoding str message'
+
+try:
+    str(1, 'utf-8')
+    assert False, 'decoding int should raise'
+except TypeError as e:
+    assert str(e) == 'decoding to str: need a bytes-like object, int found', 'decoding int message'
+
+try:
+    str(b'a\xff', 'utf-8')
+    assert False, 'invalid utf-8 should raise'
+except UnicodeDecodeError:
+    pass
+
+try:
+    str(b'a', 'rot13x')
+    assert False, 'unknown encoding should raise'
+except LookupError as e:
+    assert str(e) == 'unknown encoding: rot13x', 'unknown encoding message'
+
+try:
+    str(b'a', encoding=1)
+    assert False, 'non-str encoding should raise'
+except TypeError as e:
+    assert str(e) == "str() argument 'encoding' must be str, not int", 'encoding type message'
+
+try:
+    str(b'a', 'utf-8', 'strict', 1)
+    assert False, 'four args should raise'
+except TypeError as e:
+    assert str(e) == 'str expected at most 3 arguments, got 4', 'str arg count message'
+
+try:
+    str(1, object=2)
+    assert False, 'object given twice should raise'
+except TypeError as e:
+    assert str(e) == "argument for str() given by name ('object') and position (1)", 'str duplicate message'
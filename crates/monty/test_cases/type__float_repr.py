@@ -0,0 +1,35 @@
+# === shortest round-trip digits ===
+assert repr(0.1) == '0.1', 'repr(0.1)'
+assert repr(0.1 + 0.2) == '0.30000000000000004', 'repr(0.1 + 0.2)'
+assert repr(1 / 3) == '0.3333333333333333', 'repr(1 / 3)'
+assert repr(2.5) == '2.5', 'repr(2.5)'
+assert repr(123.456) == '123.456', 'repr(123.456)'
+
+# === whole numbers keep a decimal point ===
+assert repr(1.0) == '1.0', 'repr(1.0)'
+assert repr(100.0) == '100.0', 'repr(100.0)'
+assert repr(0.0) == '0.0', 'repr(0.0)'
+assert repr(-0.0) == '-0.0', 'repr(-0.0)'
+assert repr(9999999999999998.0) == '9999999999999998.0', 'largest exponent without scientific notation'
+
+# === scientific notation for large and small exponents ===
+assert repr(1e16) == '1e+16', 'repr(1e16)'
+assert repr(1.5e16) == '1.5e+16', 'repr(1.5e16)'
+assert repr(1e300) == '1e+300', 'repr(1e300)'
+assert repr(-2.5e22) == '-2.5e+22', 'repr(-2.5e22)'
+assert repr(0.0001) == '0.0001', 'repr(0.0001)'
+assert repr(0.00001) == '1e-05', 'repr(0.00001)'
+assert repr(1.25e-7) == '1.25e-07', 'repr(1.25e-7)'
+assert repr(5e-324) == '5e-324', 'smallest subnormal'
+assert repr(1.7976931348623157e308) == '1.7976931348623157e+308', 'largest float'
+
+# === special values ===
+assert repr(float('inf')) == 'inf', 'repr(inf)'
+assert repr(float('-inf')) == '-inf', 'repr(-inf)'
+assert repr(float('nan')) == 'nan', 'repr(nan)'
+
+# === str, containers and f-strings use the same formatting ===
+assert str(1e20) == '1e+20', 'str(1e20)'
+assert str([1e-10, 2.0]) == '[1e-10, 2.0]', 'str of list'
+assert f'{1e16}' == '1e+16', 'f-string'
+assert f'{1e16!r}' == '1e+16', 'f-string repr'
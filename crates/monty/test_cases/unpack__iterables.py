@@ -0,0 +1,58 @@
+# Tests for unpacking assignments from iterables other than lists, tuples and strings
+
+# === Plain unpacking ===
+a, b, c = range(3)
+assert (a, b, c) == (0, 1, 2), 'unpack range'
+
+x, y = {'k1': 1, 'k2': 2}
+assert (x, y) == ('k1', 'k2'), 'unpack dict keys'
+
+(only,) = {7}
+assert only == 7, 'unpack single-element set'
+
+p, q = iter([[1], [2]])
+assert p == [1] and q == [2], 'unpack iterator of lists'
+
+k, v = next(iter({'key': 'val'}.items()))
+assert (k, v) == ('key', 'val'), 'unpack dict item'
+
+# === Starred unpacking ===
+first, *rest = range(5)
+assert first == 0, 'starred first from range'
+assert rest == [1, 2, 3, 4], 'starred rest from range'
+
+*init, last = iter(['a', 'b', 'c'])
+assert init == ['a', 'b'], 'starred init from iterator'
+assert last == 'c', 'starred last from iterator'
+
+head, *middle, tail = [[1], [2], [3], [4]]
+assert head == [1] and tail == [4], 'starred with list items'
+assert middle == [[2], [3]], 'starred middle list items'
+
+a, *b = range(1)
+assert a == 0 and b == [], 'starred empty remainder'
+
+# === For loop targets ===
+total = 0
+for i, *others in [range(3), range(2)]:
+    total += i + len(others)
+assert total == 3, 'starred for loop target from ranges'
+
+# === Errors ===
+try:
+    a, b = range(3)
+    assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'too many values to unpack (expected 2)', f'too many: {e}'
+
+try:
+    a, b, c = range(2)
+    assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'not enough values to unpack (expected 3, got 2)', f'not enough: {e}'
+
+try:
+    a, b, *c = iter([1])
+    assert False, 'should have raised'
+except ValueError as e:
+    assert str(e) == 'not enough values to unpack (expected at least 2, got 1)', f'starred not enough: {e}'
@@ -0,0 +1,32 @@
+# === Walrus in def defaults binds in the enclosing function scope ===
+def outer_def():
+    def inner(a=(b := 10)):
+        return a
+
+    return b, inner()
+
+
+assert outer_def() == (10, 10), 'walrus in def default binds in enclosing scope'
+
+
+# === Walrus in lambda defaults binds in the enclosing function scope ===
+def outer_lambda():
+    f = lambda x=(y := 5): x * 2
+    return y, f(), f(1)
+
+
+assert outer_lambda() == (5, 10, 2), 'walrus in lambda default binds in enclosing scope'
+
+# === Walrus in defaults at module scope ===
+g = lambda v=(module_default := 'm'): v
+assert module_default == 'm', 'walrus in lambda default binds module global'
+assert g() == 'm', 'lambda default from walrus'
+
+
+# === Walrus inside a ternary in a function ===
+def ternary_walrus(items):
+    return (n := len(items)) if items else (n := -1), n
+
+
+assert ternary_walrus([1, 2]) == (2, 2), 'walrus in ternary body'
+assert ternary_walrus([]) == (-1, -1), 'walrus in ternary orelse'
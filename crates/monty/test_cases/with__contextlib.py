@@ -0,0 +1,127 @@
+from contextlib import nullcontext, suppress
+
+# === nullcontext returns its enter result ===
+with nullcontext(42) as value:
+    assert value == 42, 'nullcontext enter result'
+
+with nullcontext() as value:
+    assert value is None, 'nullcontext default enter result'
+
+with nullcontext([1, 2]) as (a, b):
+    assert (a, b) == (1, 2), 'with target unpacking'
+
+# === exceptions propagate through nullcontext ===
+caught = None
+try:
+    with nullcontext():
+        raise ValueError('inside')
+except ValueError as e:
+    caught = str(e)
+assert caught == 'inside', 'nullcontext does not suppress'
+
+# === suppress swallows matching exceptions ===
+reached = []
+with suppress(KeyError):
+    reached.append(1)
+    {}['missing']
+    reached.append(2)
+assert reached == [1], 'suppress stops the body at the exception'
+
+with suppress(LookupError):
+    [][0]
+
+with suppress(TypeError, (ValueError, ZeroDivisionError)):
+    1 / 0
+
+with suppress():
+    pass
+
+# === suppress does not swallow other exceptions ===
+caught = None
+try:
+    with suppress(KeyError):
+        raise ValueError('not suppressed')
+except ValueError as e:
+    caught = repr(e)
+assert caught == "ValueError('not suppressed')", 'suppress only matches given classes'
+
+
+# === user-defined exception classes ===
+class AppError(Exception):
+    pass
+
+
+class ConfigError(AppError):
+    pass
+
+
+with suppress(AppError):
+    raise ConfigError('swallowed')
+
+# === multiple items ===
+log = []
+with nullcontext('a') as first, nullcontext('b') as second:
+    log.append(first + second)
+assert log == ['ab'], 'multiple with items'
+
+with suppress(KeyError), nullcontext():
+    {}['missing']
+
+
+# === return, break and continue inside with ===
+def find(items, target):
+    for item in items:
+        with nullcontext():
+            if item == target:
+                return item
+    return None
+
+
+assert find([1, 2, 3], 2) == 2, 'return inside with'
+
+count = 0
+for i in range(5):
+    with suppress(ValueError):
+        if i == 1:
+            continue
+        if i == 3:
+            break
+        count += 1
+assert count == 2, 'break and continue inside with'
+
+# === nested with ===
+with nullcontext(1) as outer:
+    with nullcontext(2) as inner:
+        assert outer + inner == 3, 'nested with'
+
+# === with in functions ===
+def safe_div(a, b):
+    with suppress(ZeroDivisionError):
+        return a / b
+    return 'div by zero'
+
+
+assert safe_div(6, 3) == 2, 'with in function'
+assert safe_div(1, 0) == 'div by zero', 'suppressed in function'
+
+# === calling the protocol directly ===
+cm = suppress(KeyError)
+assert cm.__enter__() is None, 'suppress __enter__'
+assert cm.__exit__(KeyError, KeyError('k'), None) is True, 'suppress __exit__ matches'
+assert cm.__exit__(ValueError, ValueError('v'), None) is False, 'suppress __exit__ no match'
+assert cm.__exit__(None, None, None) is None, 'suppress __exit__ no exception'
+
+# === hidden with state doesn't leak into namespaces ===
+with suppress(KeyError):
+    assert not [name for name in globals() if name.startswith('.')], 'globals inside with'
+assert not [name for name in globals() if name.startswith('.')], 'globals after with'
+assert not [name for name in dir() if name.startswith('.')], 'dir after with'
+
+
+def with_locals():
+    with nullcontext(1) as value:
+        inside = sorted(locals())
+    return inside, sorted(locals())
+
+
+assert with_locals() == (['value'], ['inside', 'value']), 'locals in function with'
@@ -0,0 +1,162 @@
+//! Tests for `MontyRun::analyze`, which checks code without running it.
+
+use monty::{Analysis, DiagnosticKind, MontyRun};
+
+fn analyze(code: &str) -> Analysis {
+    MontyRun::analyze(code, "test.py", &[], &[])
+}
+
+/// Returns each diagnostic as `file:line:column: message`.
+fn messages(analysis: &Analysis) -> Vec<String> {
+    analysis.diagnostics.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn clean_code() {
+    let code = "
+import json
+
+def main(items):
+    total = 0
+    for item in items:
+        total += len(item)
+    return json.dumps({'total': total, 'max': max(items, default=None)})
+
+main(['a', 'bc'])
+";
+    let analysis = analyze(code);
+    assert!(analysis.is_ok(), "{analysis}");
+}
+
+#[test]
+fn undefined_names() {
+    let code = "
+def f(a):
+    return a + missing
+
+x = undefined_global
+[z for z in range(3) if z > w]
+";
+    let analysis = analyze(code);
+    assert_eq!(
+        messages(&analysis),
+        [
+            "test.py:3:16: name 'missing' is not defined",
+            "test.py:5:5: name 'undefined_global' is not defined",
+            "test.py:6:29: name 'w' is not defined",
+        ]
+    );
+    assert!(
+        analysis
+            .diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.kind == DiagnosticKind::UndefinedName)
+    );
+}
+
+#[test]
+fn names_bound_later_at_module_level() {
+    let code = "
+for i in range(2):
+    if i:
+        print(y)
+    y = i
+
+def set_total():
+    global total
+    total = 1
+
+set_total()
+total
+";
+    let analysis = analyze(code);
+    assert!(analysis.is_ok(), "{analysis}");
+}
+
+#[test]
+fn closures_and_lambdas() {
+    let code = "
+def outer():
+    a = 1
+    def inner():
+        return a + b
+    return inner
+
+f = lambda x: x + c
+";
+    assert_eq!(
+        messages(&analyze(code)),
+        [
+            "test.py:5:20: name 'b' is not defined",
+            "test.py:8:19: name 'c' is not defined",
+        ]
+    );
+}
+
+#[test]
+fn unknown_functions() {
+    let code = "result = fetch('https://example.com')\nsave(result)";
+    let analysis = analyze(code);
+    let unknown: Vec<_> = analysis.of_kind(DiagnosticKind::UnknownFunction).collect();
+    assert_eq!(unknown.len(), 2);
+    assert_eq!(
+        unknown[0].message,
+        "name 'fetch' is not defined, is it an external function that wasn't registered?"
+    );
+
+    let analysis = MontyRun::analyze(code, "test.py", &[], &["fetch".to_owned(), "save".to_owned()]);
+    assert!(analysis.is_ok(), "{analysis}");
+}
+
+#[test]
+fn unused_inputs() {
+    let code = "
+def main():
+    return a * 2
+
+main()
+";
+    let inputs = ["a".to_owned(), "b".to_owned()];
+    let analysis = MontyRun::analyze(code, "test.py", &inputs, &["save".to_owned()]);
+    assert_eq!(messages(&analysis), ["input 'b' is never used"]);
+    assert!(analysis.diagnostics[0].location.is_none());
+}
+
+#[test]
+fn every_unsupported_feature_is_reported() {
+    let code = "
+x = 1
+match x:
+    case 1:
+        pass
+
+def f():
+    import os, sys
+    return y
+";
+    let analysis = analyze(code);
+    let kinds: Vec<_> = analysis.diagnostics.iter().map(|diagnostic| diagnostic.kind).collect();
+    assert_eq!(kinds, [DiagnosticKind::Unsupported, DiagnosticKind::Unsupported]);
+    assert_eq!(
+        messages(&analysis),
+        [
+            "test.py:3:1: The monty syntax parser does not yet support pattern matching (match statements)",
+            "test.py:8:5: The monty syntax parser does not yet support multi-module import statements",
+        ]
+    );
+}
+
+#[test]
+fn syntax_error() {
+    let analysis = analyze("x = (1,\ny = 2");
+    assert_eq!(analysis.diagnostics.len(), 1);
+    assert_eq!(analysis.diagnostics[0].kind, DiagnosticKind::SyntaxError);
+}
+
+#[test]
+fn analyzed_code_still_runs() {
+    let code = "def main():\n    return 1\nmain()";
+    assert!(analyze(code).is_ok());
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    assert_eq!(runner.run_no_limits(vec![]).unwrap(), monty::MontyObject::Int(1));
+}
@@ -0,0 +1,157 @@
+//! Tests for `MontyRun::ast_json`, which exports the prepared AST as JSON.
+
+use monty::{ExcType, MontyRun};
+use serde_json::{Value, json};
+
+fn ast(code: &str) -> Value {
+    MontyRun::ast_json(code, "test.py", &[], &[]).unwrap()
+}
+
+fn span(start: (u16, u16), end: (u16, u16)) -> Value {
+    json!({
+        "start": {"line": start.0, "column": start.1},
+        "end": {"line": end.0, "column": end.1},
+    })
+}
+
+#[test]
+fn full_module() {
+    assert_eq!(
+        ast("x = 1"),
+        json!({
+            "type": "Module",
+            "body": [{
+                "type": "Assign",
+                "targets": [{"type": "Name", "id": "x", "scope": "global", "slot": 0, "span": span((1, 1), (1, 2))}],
+                "value": {"type": "Constant", "kind": "int", "value": 1, "span": span((1, 5), (1, 6))},
+            }],
+            "slot_names": ["x"],
+        })
+    );
+}
+
+#[test]
+fn resolved_scopes() {
+    let code = "
+x = 1
+def f(a):
+    global g
+    g = a
+    def inner():
+        return a + x
+    return inner
+";
+    let ast = ast(code);
+    let f = &ast["body"][1];
+    assert_eq!(f["type"], "FunctionDef");
+    assert_eq!(f["name"]["scope"], "global");
+    assert_eq!(
+        f["params"],
+        json!([{"name": "a", "kind": "positional_or_keyword", "default": null}])
+    );
+
+    // `global g` only affects how names resolve, so it has no node
+    let assign_g = &f["body"][0];
+    assert_eq!(assign_g["targets"][0]["id"], "g");
+    assert_eq!(assign_g["targets"][0]["scope"], "global");
+    assert_eq!(assign_g["value"]["scope"], "cell");
+
+    let inner = &f["body"][1];
+    let sum = &inner["body"][0]["value"];
+    assert_eq!(sum["type"], "BinOp");
+    assert_eq!(sum["op"], "Add");
+    assert_eq!(sum["left"]["scope"], "cell");
+    assert_eq!(sum["right"]["scope"], "global");
+    assert_eq!(f["body"][2]["value"]["scope"], "local");
+}
+
+#[test]
+fn calls() {
+    let ast = ast("print(len(items), sep='-')\nitems.append(1)");
+    let print = &ast["body"][0]["value"];
+    assert_eq!(print["type"], "Call");
+    assert_eq!(
+        print["func"],
+        json!({"type": "Name", "id": "print", "scope": "builtin", "slot": null})
+    );
+    assert_eq!(print["args"][0]["func"]["id"], "len");
+    assert_eq!(print["args"][0]["args"][0]["id"], "items");
+    assert_eq!(print["keywords"][0]["arg"], "sep");
+    assert_eq!(print["keywords"][0]["value"]["value"], "-");
+    assert_eq!(print["span"]["start"], json!({"line": 1, "column": 1}));
+
+    let append = &ast["body"][1]["value"];
+    assert_eq!(append["func"]["type"], "Attribute");
+    assert_eq!(append["func"]["attr"], "append");
+    assert_eq!(append["func"]["value"]["id"], "items");
+    assert_eq!(append["span"]["start"], json!({"line": 2, "column": 1}));
+}
+
+#[test]
+fn optimizations_are_undone() {
+    let ast = ast("n = 9\nn % 3 == 0 and n > 1");
+    let test = &ast["body"][1]["value"];
+    assert_eq!(test["type"], "BoolOp");
+    assert_eq!(test["op"], "And");
+    let modulo = &test["values"][0];
+    assert_eq!(modulo["type"], "Compare");
+    assert_eq!(modulo["left"]["type"], "BinOp");
+    assert_eq!(modulo["left"]["op"], "Mod");
+    assert_eq!(modulo["ops"], json!(["Eq"]));
+    assert_eq!(modulo["comparators"][0]["value"], 0);
+}
+
+#[test]
+fn params() {
+    let ast = ast("def f(a, /, b=1, *args, c, d=2, **kw):\n    pass");
+    let params: Vec<_> = ast["body"][0]["params"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| {
+            (
+                param["name"].clone(),
+                param["kind"].clone(),
+                param["default"]["value"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        params,
+        [
+            (json!("a"), json!("positional_only"), Value::Null),
+            (json!("b"), json!("positional_or_keyword"), json!(1)),
+            (json!("args"), json!("var_positional"), Value::Null),
+            (json!("c"), json!("keyword_only"), Value::Null),
+            (json!("d"), json!("keyword_only"), json!(2)),
+            (json!("kw"), json!("var_keyword"), Value::Null),
+        ]
+    );
+}
+
+#[test]
+fn undefined_names() {
+    let ast = ast("def f():\n    return missing\nprint(undefined)");
+    assert_eq!(ast["body"][0]["body"][0]["value"]["scope"], "undefined");
+    assert_eq!(ast["body"][1]["value"]["args"][0]["scope"], "undefined");
+}
+
+#[test]
+fn fstrings_and_comprehensions() {
+    let ast = ast("x = 1\ny = [f'{i!r:>10}' for i in range(x) if i]");
+    let comp = &ast["body"][1]["value"];
+    assert_eq!(comp["type"], "ListComp");
+    assert_eq!(comp["generators"][0]["target"]["id"], "i");
+    assert_eq!(comp["generators"][0]["ifs"][0]["id"], "i");
+    let value = &comp["elt"]["values"][0];
+    assert_eq!(value["type"], "FormattedValue");
+    assert_eq!(value["conversion"], "r");
+    assert_eq!(value["format_spec"]["values"][0]["value"], ">10");
+}
+
+#[test]
+fn errors() {
+    let err = MontyRun::ast_json("x = (1,", "test.py", &[], &[]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::SyntaxError);
+    assert!(MontyRun::ast_json("match x:\n    case 1:\n        pass", "test.py", &[], &[]).is_err());
+}
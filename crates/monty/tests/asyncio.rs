@@ -72,6 +72,9 @@ fn drive_to_resolve_futures<T: monty::ResourceTracker>(
             RunProgress::OsCall { function, .. } => {
                 panic!("unexpected OsCall: {function:?}");
             }
+            RunProgress::Paused(_) => {
+                panic!("unexpected Paused");
+            }
         }
     }
 }
@@ -774,6 +777,9 @@ fn drive_collecting_calls<T: monty::ResourceTracker>(
             RunProgress::OsCall { function, .. } => {
                 panic!("unexpected OsCall: {function:?}");
             }
+            RunProgress::Paused(_) => {
+                panic!("unexpected Paused");
+            }
         }
     }
 }
@@ -0,0 +1,210 @@
+//! Tests for `MontyRun::call_function`, which calls a function defined by the code.
+
+use monty::{ExcType, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResultSchema};
+
+fn call(
+    code: &str,
+    name: &str,
+    args: Vec<MontyObject>,
+    kwargs: Vec<(&str, MontyObject)>,
+) -> Result<MontyObject, MontyException> {
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let kwargs = kwargs.into_iter().map(|(k, v)| (k.to_owned(), v)).collect();
+    runner.call_function(vec![], name, args, kwargs, NoLimitTracker, &mut PrintWriter::Stdout)
+}
+
+fn str_obj(s: &str) -> MontyObject {
+    MontyObject::String(s.to_owned())
+}
+
+#[test]
+fn positional_and_keyword_arguments() {
+    let code = "
+def main(a, b, *rest, sep='-', **extra):
+    return (a, b, rest, sep, extra)
+";
+    let result = call(
+        code,
+        "main",
+        vec![MontyObject::Int(1), str_obj("x"), MontyObject::Int(3)],
+        vec![("sep", str_obj("+")), ("flag", MontyObject::Bool(true))],
+    )
+    .unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::Int(1),
+            str_obj("x"),
+            MontyObject::Tuple(vec![MontyObject::Int(3)]),
+            str_obj("+"),
+            MontyObject::dict(vec![(str_obj("flag"), MontyObject::Bool(true))]),
+        ])
+    );
+}
+
+#[test]
+fn module_globals_are_visible() {
+    let code = "
+PREFIX = 'item-'
+calls = []
+
+def label(n):
+    return PREFIX + str(n)
+
+def main(payload):
+    calls.append(payload)
+    return [label(n) for n in payload['ids']] + [len(calls)]
+
+'the module result is discarded'
+";
+    let payload = MontyObject::dict(vec![(
+        str_obj("ids"),
+        MontyObject::List(vec![MontyObject::Int(1), MontyObject::Int(2)]),
+    )]);
+    let result = call(code, "main", vec![payload], vec![]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::List(vec![str_obj("item-1"), str_obj("item-2"), MontyObject::Int(1)])
+    );
+}
+
+#[test]
+fn runner_can_be_called_again() {
+    let runner = MontyRun::new("def double(x):\n    return x * 2".to_owned(), "test.py", vec![], vec![]).unwrap();
+    for n in [1, 5] {
+        let result = runner
+            .call_function(
+                vec![],
+                "double",
+                vec![MontyObject::Int(n)],
+                vec![],
+                NoLimitTracker,
+                &mut PrintWriter::Stdout,
+            )
+            .unwrap();
+        assert_eq!(result, MontyObject::Int(n * 2));
+    }
+}
+
+#[test]
+fn inputs_are_available() {
+    let runner = MontyRun::new(
+        "def main(n):\n    return n + offset".to_owned(),
+        "test.py",
+        vec!["offset".to_owned()],
+        vec![],
+    )
+    .unwrap();
+    let result = runner
+        .call_function(
+            vec![MontyObject::Int(100)],
+            "main",
+            vec![MontyObject::Int(1)],
+            vec![],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap();
+    assert_eq!(result, MontyObject::Int(101));
+}
+
+#[test]
+fn builtins_and_lambdas_can_be_called() {
+    assert_eq!(
+        call("main = lambda x: x + 1", "main", vec![MontyObject::Int(1)], vec![]).unwrap(),
+        MontyObject::Int(2)
+    );
+    assert_eq!(
+        call("main = len", "main", vec![str_obj("abc")], vec![]).unwrap(),
+        MontyObject::Int(3)
+    );
+}
+
+#[test]
+fn missing_function() {
+    let err = call("def main():\n    pass", "run", vec![], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::NameError);
+    assert_eq!(err.message(), Some("name 'run' is not defined"));
+
+    let err = call("if False:\n    def main():\n        pass", "main", vec![], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::NameError);
+}
+
+#[test]
+fn not_callable() {
+    let err = call("main = 1", "main", vec![], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+    assert_eq!(err.message(), Some("'int' object is not callable"));
+}
+
+#[test]
+fn bad_arguments() {
+    let err = call("def main(a):\n    return a", "main", vec![], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+
+    let err = call(
+        "def main(a):\n    return a",
+        "main",
+        vec![],
+        vec![("b", MontyObject::None)],
+    )
+    .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+}
+
+#[test]
+fn exception_in_function_has_traceback() {
+    let code = "
+def check(n):
+    if n < 0:
+        raise ValueError(f'negative: {n}')
+    return n
+
+def main(n):
+    return check(n)
+";
+    let err = call(code, "main", vec![MontyObject::Int(-1)], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ValueError);
+    assert_eq!(err.message(), Some("negative: -1"));
+    let names: Vec<_> = err.traceback().iter().map(|frame| frame.frame_name.clone()).collect();
+    assert_eq!(names, [Some("main".to_owned()), Some("check".to_owned())]);
+}
+
+#[test]
+fn exception_in_module_is_returned() {
+    let err = call("def main():\n    return 1\n1 / 0", "main", vec![], vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ZeroDivisionError);
+}
+
+#[test]
+fn result_schema_applies_to_the_function_result() {
+    let runner = MontyRun::new(
+        "def main(x):\n    return x\n'module'".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+    )
+    .unwrap()
+    .with_result_schema("list[int]".parse::<ResultSchema>().unwrap());
+    let result = runner.call_function(
+        vec![],
+        "main",
+        vec![MontyObject::List(vec![MontyObject::Int(1)])],
+        vec![],
+        NoLimitTracker,
+        &mut PrintWriter::Stdout,
+    );
+    assert_eq!(result.unwrap(), MontyObject::List(vec![MontyObject::Int(1)]));
+
+    let err = runner
+        .call_function(
+            vec![],
+            "main",
+            vec![str_obj("a")],
+            vec![],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap_err();
+    assert!(err.schema_mismatch().is_some());
+}
@@ -0,0 +1,75 @@
+//! Tests for `monty::capabilities`, which describes the Python surface Monty supports.
+
+use monty::{Capabilities, capabilities};
+
+fn type_methods<'a>(capabilities: &'a Capabilities, name: &str) -> &'a [String] {
+    &capabilities
+        .builtin_types
+        .iter()
+        .find(|builtin_type| builtin_type.name == name)
+        .unwrap()
+        .methods
+}
+
+fn contains(names: &[String], name: &str) -> bool {
+    names.iter().any(|n| n == name)
+}
+
+#[test]
+fn builtins_and_exceptions() {
+    let capabilities = capabilities();
+    for name in ["print", "len", "sorted", "isinstance"] {
+        assert!(contains(&capabilities.builtin_functions, name), "{name}");
+    }
+    assert!(!contains(&capabilities.builtin_functions, "int"));
+    assert!(contains(&capabilities.exceptions, "ValueError"));
+    // only accessible through the json module
+    assert!(!contains(&capabilities.exceptions, "JSONDecodeError"));
+}
+
+#[test]
+fn type_methods_are_probed() {
+    let capabilities = capabilities();
+    let list_methods = type_methods(&capabilities, "list");
+    for method in ["append", "pop", "sort"] {
+        assert!(contains(list_methods, method), "{method}");
+    }
+    assert!(contains(type_methods(&capabilities, "str"), "upper"));
+    assert!(contains(type_methods(&capabilities, "dict"), "items"));
+    assert!(type_methods(&capabilities, "range").is_empty());
+}
+
+#[test]
+fn syntax_features() {
+    let capabilities = capabilities();
+    let supported = |name: &str| {
+        capabilities
+            .syntax
+            .iter()
+            .find(|feature| feature.name == name)
+            .unwrap()
+            .supported
+    };
+    assert!(supported("list comprehensions"));
+    assert!(supported("f-strings"));
+    assert!(!supported("match statements"));
+    assert!(!supported("multi-module imports"));
+}
+
+#[test]
+fn modules() {
+    let capabilities = capabilities();
+    let module = |name: &str| capabilities.modules.iter().find(|module| module.name == name).unwrap();
+    assert!(contains(&module("math").attributes, "sqrt"));
+    assert!(contains(&module("json").attributes, "dumps"));
+    assert!(capabilities.modules.iter().all(|module| !module.attributes.is_empty()));
+}
+
+#[test]
+fn json_round_trip() {
+    let capabilities = capabilities();
+    let json: serde_json::Value = serde_json::from_str(&capabilities.to_json()).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    let parsed: Capabilities = serde_json::from_str(&capabilities.to_json()).unwrap();
+    assert_eq!(parsed, capabilities);
+}
@@ -0,0 +1,231 @@
+//! Tests for checkpoints: pausing at statement boundaries so the host can dump state to recover from.
+
+use std::time::Duration;
+
+use monty::{
+    ExcType, LimitedTracker, MontyObject, MontyRepl, MontyRun, PauseReason, PrintWriter, ReplProgress, ResourceError,
+    ResourceLimits, ResourceTracker, RunProgress,
+};
+
+const LOOP_CODE: &str = "
+total = 0
+for i in range(10):
+    total += i
+print('done')
+total
+";
+
+fn start(code: &str, limits: ResourceLimits) -> RunProgress<LimitedTracker> {
+    let runner = MontyRun::new(code.to_owned(), "job.py", vec![], vec![]).unwrap();
+    runner
+        .start(vec![], LimitedTracker::new(limits), &mut PrintWriter::Disabled)
+        .unwrap()
+}
+
+#[test]
+fn checkpoint_every_n_statements() {
+    let mut progress = start(LOOP_CODE, ResourceLimits::new().checkpoint_every(5));
+    let mut checkpoints = 0;
+    let result = loop {
+        match progress {
+            RunProgress::Paused(mut state) => {
+                assert_eq!(state.reason(), PauseReason::Checkpoint);
+                assert_eq!(state.tracker_mut().statements() % 5, 0);
+                checkpoints += 1;
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(45));
+    // 10 loop body statements plus the module-level ones
+    assert!(checkpoints >= 2, "expected several checkpoints, got {checkpoints}");
+}
+
+#[test]
+fn resumed_statement_is_not_counted_twice() {
+    // with a checkpoint before every statement, resuming must still make progress
+    let mut progress = start(LOOP_CODE, ResourceLimits::new().checkpoint_every(1));
+    let mut last_count = 0;
+    let result = loop {
+        match progress {
+            RunProgress::Paused(mut state) => {
+                let count = state.tracker_mut().statements();
+                assert_eq!(count, last_count + 1, "each checkpoint is one statement further");
+                last_count = count;
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(45));
+}
+
+#[test]
+fn recover_from_last_checkpoint() {
+    let mut progress = start(LOOP_CODE, ResourceLimits::new().checkpoint_every(4));
+    let mut last_checkpoint = None;
+    let mut output = PrintWriter::Collect(String::new());
+    let expected = loop {
+        match progress {
+            RunProgress::Paused(state) => {
+                last_checkpoint = Some(RunProgress::Paused(state).dump().unwrap());
+                let state = RunProgress::<LimitedTracker>::load(last_checkpoint.as_ref().unwrap())
+                    .unwrap()
+                    .into_paused()
+                    .unwrap();
+                progress = state.resume(&mut output).unwrap();
+            }
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+
+    let PrintWriter::Collect(output) = output else {
+        unreachable!()
+    };
+    assert_eq!(output, "done\n", "output is printed once across checkpoints");
+
+    // simulate a crash after the last checkpoint: a new process loads it and finishes the job
+    let mut recovered = RunProgress::<LimitedTracker>::load(&last_checkpoint.unwrap()).unwrap();
+    let result = loop {
+        match recovered {
+            RunProgress::Paused(state) => recovered = state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn checkpoints_wait_for_nested_calls() {
+    // the key function runs in a nested VM loop, checkpoints requested there are deferred
+    let code = "
+def key(x):
+    y = -x
+    return y
+result = sorted(range(20), key=key)
+result[0]
+";
+    let mut progress = start(code, ResourceLimits::new().checkpoint_every(3));
+    let result = loop {
+        match progress {
+            RunProgress::Paused(state) => progress = state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(19));
+}
+
+#[test]
+fn checkpoints_keep_the_time_budget() {
+    let limits = ResourceLimits::new()
+        .checkpoint_every(1)
+        .max_duration(Duration::from_millis(1))
+        .deterministic(true);
+    let mut progress = start("x = 0\nwhile True:\n    x += 1", limits);
+    let err = loop {
+        match progress {
+            RunProgress::Paused(state) => match state.resume(&mut PrintWriter::Disabled) {
+                Ok(next) => progress = next,
+                Err(err) => break err,
+            },
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(err.exc_type(), ExcType::TimeoutError);
+}
+
+/// Tracker that asks a host callback whether to checkpoint at each statement.
+#[derive(Debug)]
+struct CallbackTracker {
+    statements: u64,
+    checkpoint_due: fn(u64) -> bool,
+}
+
+impl ResourceTracker for CallbackTracker {
+    fn on_allocate(&mut self, _: impl FnOnce() -> usize) -> Result<(), ResourceError> {
+        Ok(())
+    }
+
+    fn on_free(&mut self, _: impl FnOnce() -> usize) {}
+
+    fn check_time(&self) -> Result<(), ResourceError> {
+        Ok(())
+    }
+
+    fn check_recursion_depth(&self, _: usize) -> Result<(), ResourceError> {
+        Ok(())
+    }
+
+    fn check_large_result(&self, _: usize) -> Result<(), ResourceError> {
+        Ok(())
+    }
+
+    fn checkpoints_enabled(&self) -> bool {
+        true
+    }
+
+    fn on_statement(&mut self) -> bool {
+        self.statements += 1;
+        (self.checkpoint_due)(self.statements)
+    }
+}
+
+#[test]
+fn custom_tracker_decides_when_to_checkpoint() {
+    let tracker = CallbackTracker {
+        statements: 0,
+        checkpoint_due: |statements| statements == 7,
+    };
+    let runner = MontyRun::new(LOOP_CODE.to_owned(), "job.py", vec![], vec![]).unwrap();
+    let progress = runner.start(vec![], tracker, &mut PrintWriter::Disabled).unwrap();
+    let mut state = progress.into_paused().expect("expected a checkpoint");
+    assert_eq!(state.reason(), PauseReason::Checkpoint);
+    assert_eq!(state.tracker_mut().statements, 7);
+
+    let progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+    assert_eq!(progress.into_complete().unwrap(), MontyObject::Int(45));
+}
+
+#[test]
+fn repl_snippets_checkpoint() {
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(2));
+    let (repl, _) = MontyRepl::new(
+        "base = 100".to_owned(),
+        "repl.py",
+        vec![],
+        vec![],
+        vec![],
+        tracker,
+        &mut PrintWriter::Disabled,
+    )
+    .unwrap();
+
+    let mut progress = repl
+        .start("a = 1\nb = 2\nc = 3\nbase + a + b + c", &mut PrintWriter::Disabled)
+        .unwrap();
+    let mut checkpoints = 0;
+    let value = loop {
+        match progress {
+            ReplProgress::Paused(state) => {
+                assert_eq!(state.reason(), PauseReason::Checkpoint);
+                checkpoints += 1;
+                let bytes = ReplProgress::Paused(state).dump().unwrap();
+                let state = ReplProgress::<LimitedTracker>::load(&bytes)
+                    .unwrap()
+                    .into_paused()
+                    .unwrap();
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            ReplProgress::Complete { value, .. } => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(value, MontyObject::Int(106));
+    assert!(checkpoints >= 1);
+}
@@ -0,0 +1,104 @@
+//! Tests for `CompileOptions` passed via `MontyRun::new_with_options`.
+
+use monty::{CompileOptions, ExcType, MontyObject, MontyRun};
+
+/// Code whose assert fails, and whose message would have a visible side effect.
+const FAILING_ASSERT: &str = "
+log = []
+
+def message():
+    log.append('evaluated')
+    return 'boom'
+
+assert len(log) > 0, message()
+log
+";
+
+#[test]
+fn asserts_enabled_by_default() {
+    let ex = MontyRun::new(FAILING_ASSERT.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let err = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::AssertionError);
+    assert_eq!(err.message(), Some("boom"));
+}
+
+#[test]
+fn strip_asserts_skips_condition_and_message() {
+    let options = CompileOptions::default().strip_asserts(true);
+    let ex = MontyRun::new_with_options(FAILING_ASSERT.to_owned(), "test.py", vec![], vec![], options).unwrap();
+    let result = ex.run_no_limits(vec![]).unwrap();
+    assert_eq!(result, MontyObject::List(vec![]));
+}
+
+#[test]
+fn strip_asserts_applies_to_nested_functions() {
+    let code = "
+def outer():
+    def inner():
+        assert False, 'inner'
+        return 1
+    assert False, 'outer'
+    return inner() + 1
+
+outer()
+";
+    let options = CompileOptions::default().strip_asserts(true);
+    let ex = MontyRun::new_with_options(code.to_owned(), "test.py", vec![], vec![], options).unwrap();
+    let result = ex.run_no_limits(vec![]).unwrap();
+    assert_eq!(result, MontyObject::Int(2));
+}
+
+#[test]
+fn strip_asserts_survives_dump_and_load() {
+    let options = CompileOptions::default().strip_asserts(true);
+    let ex = MontyRun::new_with_options("assert False\n1".to_owned(), "test.py", vec![], vec![], options).unwrap();
+    let loaded = MontyRun::load(&ex.dump().unwrap()).unwrap();
+    assert_eq!(loaded.run_no_limits(vec![]).unwrap(), MontyObject::Int(1));
+}
+
+/// Code mixing foldable constants, dead branches and values only known at runtime.
+const FOLDABLE: &str = "
+seconds = 60 * 60 * 24
+if False:
+    seconds = 0
+n = 0
+while True:
+    n += 1
+    if n == 3:
+        break
+(seconds, -(2 ** 10), not '', 7 / 2 if 1 else None, n, 1 << 64)
+";
+
+#[test]
+fn optimize_enabled_by_default() {
+    assert!(CompileOptions::default().optimize);
+}
+
+#[test]
+fn optimize_does_not_change_results() {
+    let run = |options: CompileOptions| {
+        let ex = MontyRun::new_with_options(FOLDABLE.to_owned(), "test.py", vec![], vec![], options).unwrap();
+        ex.run_no_limits(vec![]).unwrap()
+    };
+    let optimized = run(CompileOptions::default());
+    let unoptimized = run(CompileOptions::default().optimize(false));
+    assert_eq!(optimized, unoptimized);
+    let MontyObject::Tuple(items) = optimized else {
+        panic!("expected tuple, got {optimized:?}");
+    };
+    assert_eq!(items[0], MontyObject::Int(86400));
+    assert_eq!(items[1], MontyObject::Int(-1024));
+    assert_eq!(items[2], MontyObject::Bool(true));
+    assert_eq!(items[3], MontyObject::Float(3.5));
+    assert_eq!(items[4], MontyObject::Int(3));
+}
+
+#[test]
+fn optimize_keeps_runtime_errors() {
+    for optimize in [true, false] {
+        let options = CompileOptions::default().optimize(optimize);
+        let ex = MontyRun::new_with_options("x = 1 // 0".to_owned(), "test.py", vec![], vec![], options).unwrap();
+        let err = ex.run_no_limits(vec![]).unwrap_err();
+        assert_eq!(err.exc_type(), ExcType::ZeroDivisionError);
+    }
+}
@@ -0,0 +1,85 @@
+//! Tests for line coverage collected with `Coverage`.
+
+use std::collections::HashMap;
+
+use monty::{CompileOptions, Coverage, ExcType, MontyObject, MontyRun, NoLimitTracker, PrintWriter, PythonModule};
+
+const CODE: &str = "
+def classify(n):
+    if n > 0:
+        return 'positive'
+    # never reached with the inputs below
+    return 'other'
+
+labels = []
+for n in [1, 2, 3]:
+    labels.append(classify(n))
+labels
+";
+
+#[test]
+fn counts_statements_per_line() {
+    let runner = MontyRun::new(CODE.to_owned(), "cov.py", vec![], vec![]).unwrap();
+    let (result, coverage) = runner.run_with_coverage(vec![], NoLimitTracker, &mut PrintWriter::Disabled);
+    let positive = MontyObject::String("positive".to_owned());
+    assert_eq!(result.unwrap(), MontyObject::List(vec![positive; 3]));
+    let expected = HashMap::from([(2, 1), (3, 3), (4, 3), (8, 1), (9, 1), (10, 3), (11, 1)]);
+    assert_eq!(coverage.line_hits("cov.py"), expected);
+}
+
+#[test]
+fn coverage_is_kept_when_the_code_raises() {
+    let code = "x = 1\nraise ValueError(x)\ny = 2";
+    let runner = MontyRun::new(code.to_owned(), "cov.py", vec![], vec![]).unwrap();
+    let (result, coverage) = runner.run_with_coverage(vec![], NoLimitTracker, &mut PrintWriter::Disabled);
+    assert_eq!(result.unwrap_err().exc_type(), ExcType::ValueError);
+    assert_eq!(coverage.line_hits("cov.py"), HashMap::from([(1, 1), (2, 1)]));
+}
+
+#[test]
+fn clones_share_counts_across_runs() {
+    let runner = MontyRun::new("a = 1\nb = 2".to_owned(), "cov.py", vec![], vec![]).unwrap();
+    let coverage = Coverage::new();
+    for _ in 0..2 {
+        runner
+            .run_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, coverage.clone())
+            .unwrap();
+    }
+    assert_eq!(coverage.executed_lines("cov.py"), [1, 2]);
+    assert_eq!(coverage.line_hits("cov.py"), HashMap::from([(1, 2), (2, 2)]));
+
+    coverage.clear();
+    assert!(coverage.executed_lines("cov.py").is_empty());
+    assert!(coverage.filenames().is_empty());
+}
+
+#[test]
+fn imported_module_lines_are_counted_separately() {
+    let module = PythonModule::new("helpers", "def double(x):\n    return x * 2\n");
+    let code = "import helpers\nx = helpers.double(2)\ny = x";
+    let runner = MontyRun::new_with_modules(
+        code.to_owned(),
+        "main.py",
+        vec![],
+        vec![],
+        vec![],
+        vec![module],
+        CompileOptions::default(),
+    )
+    .unwrap();
+    let (result, coverage) = runner.run_with_coverage(vec![], NoLimitTracker, &mut PrintWriter::Disabled);
+    assert_eq!(result.unwrap(), MontyObject::Int(4));
+    assert_eq!(coverage.filenames(), ["helpers.py", "main.py"]);
+    assert_eq!(coverage.line_hits("main.py"), HashMap::from([(1, 1), (2, 1), (3, 1)]));
+    assert_eq!(coverage.line_hits("helpers.py"), HashMap::from([(1, 1), (2, 1)]));
+}
+
+#[test]
+fn exec_lines_are_counted_separately() {
+    let code = "exec('a = 1\\nb = 2')\nc = 3";
+    let runner = MontyRun::new(code.to_owned(), "cov.py", vec![], vec![]).unwrap();
+    let (result, coverage) = runner.run_with_coverage(vec![], NoLimitTracker, &mut PrintWriter::Disabled);
+    result.unwrap();
+    assert_eq!(coverage.line_hits("cov.py"), HashMap::from([(1, 1), (2, 1)]));
+    assert_eq!(coverage.line_hits("<string>"), HashMap::from([(1, 1), (2, 1)]));
+}
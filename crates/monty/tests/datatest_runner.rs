@@ -241,7 +241,9 @@ const ITER_EXT_FUNCTIONS: &[&str] = &[
     "make_mutable_point", // () -> Dataclass Point(x=1, y=2) (mutable)
     "make_user",          // (name) -> Dataclass User(name=name, active=True) (immutable)
     "make_empty",         // () -> Dataclass Empty() (immutable, no fields)
+    "register_handler",   // (func) -> 'registered' (decorator-style)
     "async_call",         // (x) -> async: returns x (coroutine that returns its argument)
+    "collect_args",       // (*args, **kwargs) -> (list(args), kwargs)
 ];
 
 /// Python implementations of external functions for running iter mode tests in CPython.
@@ -301,8 +303,19 @@ enum DispatchResult {
 /// for coroutine calls that should use `run_pending()`.
 ///
 /// # Panics
-/// Panics if the function name is unknown or arguments are invalid types.
-fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult {
+/// Panics if the function name is unknown or arguments are invalid types, or if keyword
+/// arguments are passed to a function that doesn't accept them.
+fn dispatch_external_call(
+    name: &str,
+    args: Vec<MontyObject>,
+    kwargs: Vec<(MontyObject, MontyObject)>,
+) -> DispatchResult {
+    if name == "collect_args" {
+        return DispatchResult::Sync(
+            MontyObject::Tuple(vec![MontyObject::List(args), MontyObject::dict(kwargs)]).into(),
+        );
+    }
+    assert!(kwargs.is_empty(), "{name} does not accept keyword arguments");
     match name {
         "add_ints" => {
             assert!(args.len() == 2, "add_ints requires 2 arguments");
@@ -413,6 +426,16 @@ fn dispatch_external_call(name: &str, args: Vec<MontyObject>) -> DispatchResult
                 .into(),
             )
         }
+        "register_handler" => {
+            // Functions cross the boundary as their repr
+            assert!(args.len() == 1, "register_handler requires 1 argument");
+            assert!(
+                matches!(&args[0], MontyObject::Repr(repr) if repr.starts_with("<function")),
+                "register_handler requires a function, got {:?}",
+                args[0]
+            );
+            DispatchResult::Sync(MontyObject::String("registered".to_string()).into())
+        }
         "async_call" => {
             // async_call(x) -> coroutine that returns x
             // This is an async function - use run_pending() and resolve later
@@ -505,6 +528,9 @@ fn dispatch_method_call(
             let name = extract_user_name(&args[0]);
             MontyObject::String(format!("Hello, {name}!")).into()
         }
+        // User.__enter__(self) -> str, User.__exit__(self, *exc_info) -> False
+        ("User", "__enter__") => MontyObject::String(extract_user_name(&args[0])).into(),
+        ("User", "__exit__") => MontyObject::Bool(false).into(),
         // Unknown method — return AttributeError
         _ => {
             let message = format!("'{class_name}' object has no attribute '{method_name}'");
@@ -816,6 +842,34 @@ fn dispatch_os_call(
         return MontyObject::Dict(env_dict.into()).into();
     }
 
+    // The clock functions take no arguments and return a fixed time: 2024-01-02 03:04:05 (UTC)
+    match function {
+        OsFunction::Time => return MontyObject::Float(1_704_164_645.0).into(),
+        OsFunction::DateTimeNow => {
+            return MontyObject::DateTime {
+                year: 2024,
+                month: 1,
+                day: 2,
+                hour: 3,
+                minute: 4,
+                second: 5,
+                microsecond: 0,
+            }
+            .into();
+        }
+        OsFunction::DateToday => {
+            return MontyObject::Date {
+                year: 2024,
+                month: 1,
+                day: 2,
+            }
+            .into();
+        }
+        // There's no stdin, so input() always reads an empty line
+        OsFunction::Input => return MontyObject::String(String::new()).into(),
+        _ => {}
+    }
+
     // Extract path from MontyObject::Path (or String for backwards compatibility)
     let path = match &args[0] {
         MontyObject::Path(p) => p.clone(),
@@ -824,7 +878,13 @@ fn dispatch_os_call(
     };
 
     match function {
-        OsFunction::GetEnviron => unreachable!("handled above"),
+        OsFunction::GetEnviron
+        | OsFunction::Time
+        | OsFunction::DateTimeNow
+        | OsFunction::DateToday
+        | OsFunction::Input => {
+            unreachable!("handled above")
+        }
         OsFunction::Exists => {
             let exists = get_virtual_file(&path).is_some() || is_virtual_dir(&path);
             MontyObject::Bool(exists).into()
@@ -1474,7 +1534,7 @@ fn run_iter_loop(exec: MontyRun) -> Result<MontyObject, MontyException> {
                     progress = state.run(result, &mut PrintWriter::Stdout)?;
                     continue;
                 }
-                let dispatch_result = dispatch_external_call(&function_name, args);
+                let dispatch_result = dispatch_external_call(&function_name, args, kwargs);
                 match dispatch_result {
                     DispatchResult::Sync(return_value) => {
                         progress = state.run(return_value, &mut PrintWriter::Stdout)?;
@@ -1518,6 +1578,7 @@ fn run_iter_loop(exec: MontyRun) -> Result<MontyObject, MontyException> {
                 let result = dispatch_os_call(function, &args, &kwargs);
                 progress = state.run(result, &mut PrintWriter::Stdout)?;
             }
+            RunProgress::Paused(_) => panic!("unexpected Paused, test cases run without a time limit"),
         }
     }
 }
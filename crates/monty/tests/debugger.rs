@@ -0,0 +1,269 @@
+//! Tests for `DebugSession`: breakpoints, stepping and inspecting paused executions.
+
+use monty::{
+    DebugSession, DebugStatus, ExternalFunctions, LimitedTracker, MontyException, MontyObject, MontyRun,
+    NoLimitTracker, PauseReason, PrintWriter, ResourceLimits, RunProgress, StatementKind, TraceEvent, TraceHook,
+};
+
+const CODE: &str = "
+def double(n):
+    result = n * 2
+    return result
+
+x = 1
+y = double(x)
+z = double(y)
+z
+";
+
+fn start(code: &str) -> DebugSession<NoLimitTracker> {
+    let runner = MontyRun::new(code.to_owned(), "debug.py", vec![], vec![]).unwrap();
+    DebugSession::start(
+        runner,
+        vec![],
+        ExternalFunctions::new(),
+        NoLimitTracker,
+        &mut PrintWriter::Disabled,
+    )
+    .unwrap()
+}
+
+/// Returns the line and function the session is paused at.
+fn paused_at(status: &DebugStatus) -> (u16, &str) {
+    match status {
+        DebugStatus::Paused(location) => (location.line, location.function_name.as_str()),
+        DebugStatus::Complete(value) => panic!("expected a pause, completed with {value:?}"),
+    }
+}
+
+#[test]
+fn start_stops_before_the_first_statement() {
+    let session = start(CODE);
+    let DebugStatus::Paused(location) = session.status() else {
+        panic!("expected a pause");
+    };
+    assert_eq!(location.line, 2);
+    assert_eq!(location.kind, StatementKind::FunctionDef);
+    assert_eq!(location.function_name, "<module>");
+    assert_eq!(location.depth, 1);
+    assert!(session.locals().is_empty(), "nothing has run yet");
+}
+
+#[test]
+fn step_enters_calls() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    let mut lines = vec![paused_at(session.status()).0];
+    while let DebugStatus::Paused(location) = session.step(print).unwrap() {
+        lines.push(location.line);
+    }
+    assert_eq!(lines, [2, 6, 7, 3, 4, 8, 3, 4, 9]);
+    assert_eq!(session.status(), &DebugStatus::Complete(MontyObject::Int(4)));
+}
+
+#[test]
+fn next_steps_over_calls() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    let mut lines = vec![paused_at(session.status()).0];
+    while let DebugStatus::Paused(location) = session.next(print).unwrap() {
+        lines.push(location.line);
+    }
+    assert_eq!(lines, [2, 6, 7, 8, 9]);
+}
+
+#[test]
+fn next_stops_in_the_caller_after_a_return() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    session.set_breakpoint(4);
+    assert_eq!(paused_at(session.cont(print).unwrap()), (4, "double"));
+    assert_eq!(paused_at(session.next(print).unwrap()), (8, "<module>"));
+}
+
+#[test]
+fn cont_runs_to_breakpoints() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    assert!(session.set_breakpoint(3));
+    assert!(!session.set_breakpoint(3), "already set");
+    assert!(session.set_breakpoint(9));
+    assert_eq!(session.breakpoints(), [3, 9]);
+
+    assert_eq!(paused_at(session.cont(print).unwrap()), (3, "double"));
+    assert_eq!(session.variable("n"), Some(MontyObject::Int(1)));
+    assert_eq!(paused_at(session.cont(print).unwrap()), (3, "double"));
+    assert_eq!(session.variable("n"), Some(MontyObject::Int(2)));
+
+    assert!(session.clear_breakpoint(3));
+    assert!(!session.clear_breakpoint(3), "already cleared");
+    assert_eq!(paused_at(session.cont(print).unwrap()), (9, "<module>"));
+    assert_eq!(
+        session.cont(print).unwrap(),
+        &DebugStatus::Complete(MontyObject::Int(4))
+    );
+    // commands after completion keep reporting the result
+    assert_eq!(
+        session.step(print).unwrap(),
+        &DebugStatus::Complete(MontyObject::Int(4))
+    );
+    assert!(session.locals().is_empty());
+}
+
+#[test]
+fn breakpoints_stop_next_inside_calls() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    session.set_breakpoint(4);
+    assert_eq!(paused_at(session.next(print).unwrap()), (6, "<module>"));
+    assert_eq!(paused_at(session.next(print).unwrap()), (7, "<module>"));
+    assert_eq!(paused_at(session.next(print).unwrap()), (4, "double"));
+}
+
+#[test]
+fn locals_and_globals_at_a_pause() {
+    let mut session = start(CODE);
+    let print = &mut PrintWriter::Disabled;
+    session.set_breakpoint(4);
+    session.cont(print).unwrap();
+
+    let name = |name: &str, value: i64| (name.to_owned(), MontyObject::Int(value));
+    assert_eq!(session.locals(), [name("n", 1), name("result", 2)]);
+    let globals = session.globals();
+    let names: Vec<_> = globals.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["double", "x"], "y isn't bound until double returns");
+    // locals shadow globals, and unknown names have no value
+    assert_eq!(session.variable("x"), Some(MontyObject::Int(1)));
+    assert_eq!(session.variable("nope"), None);
+}
+
+#[test]
+fn inspecting_does_not_change_the_execution() {
+    let code = "
+items = [1]
+items.append(2)
+items
+";
+    let mut session = start(code);
+    let print = &mut PrintWriter::Disabled;
+    session.set_breakpoint(3);
+    session.cont(print).unwrap();
+    let Some(MontyObject::List(mut items)) = session.variable("items") else {
+        panic!("expected a list");
+    };
+    items.push(MontyObject::Int(99));
+    assert_eq!(
+        session.cont(print).unwrap(),
+        &DebugStatus::Complete(MontyObject::List(vec![MontyObject::Int(1), MontyObject::Int(2)]))
+    );
+}
+
+#[test]
+fn pretty_wraps_long_containers() {
+    let code = "
+short = [1, 2]
+numbers = list(range(30))
+nested = {'name': 'x' * 30, 'items': [('a' * 40, 1), ('b' * 40, 2)]}
+short
+";
+    let mut session = start(code);
+    session.set_breakpoint(5);
+    session.cont(&mut PrintWriter::Disabled).unwrap();
+    assert_eq!(session.pretty("short").unwrap(), "[1, 2]");
+    let numbers = session.pretty("numbers").unwrap();
+    assert_eq!(numbers.lines().count(), 30);
+    assert!(numbers.starts_with("[0,\n 1,\n 2,\n"), "{numbers}");
+    assert_eq!(
+        session.pretty("nested").unwrap(),
+        "{'name': 'xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx',
+ 'items': [('aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa', 1),
+           ('bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb', 2)]}"
+    );
+    assert_eq!(session.pretty("missing"), None);
+}
+
+#[test]
+fn external_calls_and_checkpoints_are_handled() {
+    let code = "
+a = ext(1)
+b = ext(a)
+b
+";
+    let runner = MontyRun::new(code.to_owned(), "debug.py", vec![], vec!["ext".to_owned()]).unwrap();
+    let functions = ExternalFunctions::new().function("ext", |args, _| match args.as_slice() {
+        [MontyObject::Int(n)] => Ok(MontyObject::Int(n + 10)),
+        _ => Ok(MontyObject::None),
+    });
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(1));
+    let print = &mut PrintWriter::Disabled;
+    let mut session = DebugSession::start(runner, vec![], functions, tracker, print).unwrap();
+    session.set_breakpoint(4);
+    assert_eq!(paused_at(session.cont(print).unwrap()), (4, "<module>"));
+    assert_eq!(session.variable("b"), Some(MontyObject::Int(21)));
+    assert_eq!(
+        session.cont(print).unwrap(),
+        &DebugStatus::Complete(MontyObject::Int(21))
+    );
+}
+
+#[test]
+fn exceptions_end_the_session() {
+    let mut session = start("x = 1\nraise ValueError('boom')\nx");
+    let print = &mut PrintWriter::Disabled;
+    let exc = session.cont(print).unwrap_err();
+    assert_eq!(exc.message(), Some("boom"));
+    let exc = session.step(print).unwrap_err();
+    assert_eq!(exc.message(), Some("the debug session ended with an exception"));
+}
+
+/// Hook asking to pause before every statement.
+struct AlwaysPause;
+
+impl TraceHook for AlwaysPause {
+    fn on_statement(&mut self, _event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        Ok(())
+    }
+
+    fn pause_requested(&mut self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn breakpoint_pauses_survive_dump_and_load() {
+    let runner = MontyRun::new("x = 1\ny = x + 1\ny".to_owned(), "debug.py", vec![], vec![]).unwrap();
+    let progress = runner
+        .start_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, AlwaysPause)
+        .unwrap();
+    let state = progress.into_paused().unwrap();
+    assert_eq!(state.reason(), PauseReason::Breakpoint);
+    assert!(state.locals().is_empty());
+
+    // the loaded snapshot has no hook, so it runs to completion without stopping again
+    let bytes = RunProgress::Paused(state).dump().unwrap();
+    let state = RunProgress::<NoLimitTracker>::load(&bytes)
+        .unwrap()
+        .into_paused()
+        .unwrap();
+    let result = state.resume(&mut PrintWriter::Disabled).unwrap();
+    assert_eq!(result.into_complete(), Some(MontyObject::Int(2)));
+}
+
+#[test]
+fn pauses_requested_in_nested_frames_wait_for_a_suspendable_statement() {
+    let code = "
+def key(v):
+    return -v
+result = sorted([1, 2], key=key)
+result
+";
+    let mut session = start(code);
+    let print = &mut PrintWriter::Disabled;
+    // the key function runs inside `sorted()`, where execution can't be suspended
+    session.set_breakpoint(3);
+    assert_eq!(paused_at(session.cont(print).unwrap()), (5, "<module>"));
+    assert_eq!(
+        session.variable("result"),
+        Some(MontyObject::List(vec![MontyObject::Int(2), MontyObject::Int(1)]))
+    );
+}
@@ -0,0 +1,130 @@
+//! Tests for `ResourceLimits::deterministic`, which makes runs replay to identical results.
+
+use std::time::Duration;
+
+use monty::{ExcType, LimitedTracker, MontyObject, MontyRun, PrintWriter, ResourceLimits, RunProgress};
+
+fn deterministic_tracker() -> LimitedTracker {
+    LimitedTracker::new(ResourceLimits::new().deterministic(true))
+}
+
+fn run(code: &str, tracker: LimitedTracker) -> Result<MontyObject, monty::MontyException> {
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    runner.run(vec![], tracker, &mut PrintWriter::Disabled)
+}
+
+#[test]
+fn random_is_seeded_without_explicit_seed() {
+    let code = "import random\n[random.randint(1, 1_000_000) for _ in range(10)]";
+    let first = run(code, deterministic_tracker()).unwrap();
+    let second = run(code, deterministic_tracker()).unwrap();
+    assert_eq!(first, second);
+
+    // an explicit seed still takes precedence over the deterministic default of zero
+    let seeded = LimitedTracker::new(ResourceLimits::new().deterministic(true).random_seed(7));
+    let seeded_again = LimitedTracker::new(ResourceLimits::new().random_seed(7));
+    assert_eq!(run(code, seeded).unwrap(), run(code, seeded_again).unwrap());
+}
+
+#[test]
+fn nondeterministic_builtins_raise() {
+    let cases = [
+        (
+            "import time\ntime.time()",
+            "time.time() is not allowed in deterministic mode",
+        ),
+        (
+            "import datetime\ndatetime.datetime.now()",
+            "datetime.now() is not allowed in deterministic mode",
+        ),
+        (
+            "import datetime\ndatetime.date.today()",
+            "date.today() is not allowed in deterministic mode",
+        ),
+        (
+            "import random\nrandom.seed()",
+            "random.seed() without a seed is not allowed in deterministic mode",
+        ),
+    ];
+    for (code, message) in cases {
+        let exc = run(code, deterministic_tracker()).unwrap_err();
+        assert_eq!(exc.exc_type(), ExcType::RuntimeError, "{code}");
+        assert_eq!(exc.message(), Some(message), "{code}");
+    }
+}
+
+#[test]
+fn refused_builtins_can_be_caught() {
+    let code = "
+import time
+try:
+    time.time()
+except RuntimeError:
+    result = 'refused'
+result
+";
+    let result = run(code, deterministic_tracker()).unwrap();
+    assert_eq!(result, MontyObject::String("refused".to_owned()));
+}
+
+#[test]
+fn time_limit_uses_virtual_clock() {
+    let code = "
+x = 0
+while True:
+    x += 1
+";
+    let tracker = || {
+        LimitedTracker::new(
+            ResourceLimits::new()
+                .deterministic(true)
+                .max_duration(Duration::from_millis(1)),
+        )
+    };
+    let first = run(code, tracker()).unwrap_err();
+    let second = run(code, tracker()).unwrap_err();
+    assert_eq!(first.exc_type(), ExcType::TimeoutError);
+    // the elapsed time in the message is virtual, so both runs stop at the same step
+    assert_eq!(first.message(), second.message());
+}
+
+/// Runs `code` in one-millisecond virtual time slices, round-tripping each pause through bytes,
+/// and returns the number of pauses along with the result.
+fn run_in_slices(code: &str) -> (usize, MontyObject) {
+    let limits = ResourceLimits::new()
+        .deterministic(true)
+        .max_duration(Duration::from_millis(1))
+        .pause_on_timeout(true);
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut progress = runner
+        .start(vec![], LimitedTracker::new(limits), &mut PrintWriter::Disabled)
+        .unwrap();
+    let mut pauses = 0;
+    loop {
+        let bytes = progress.dump().unwrap();
+        progress = RunProgress::load(&bytes).unwrap();
+        match progress {
+            RunProgress::Paused(state) => {
+                pauses += 1;
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            RunProgress::Complete(value) => return (pauses, value),
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn paused_runs_replay_identically() {
+    let code = "
+import random
+total = 0
+for i in range(5000):
+    total += random.randint(0, i)
+total
+";
+    let first = run_in_slices(code);
+    let second = run_in_slices(code);
+    assert!(first.0 > 1, "expected several time slices, got {}", first.0);
+    assert_eq!(first, second);
+}
@@ -0,0 +1,290 @@
+//! Tests for the `eval()` and `exec()` builtins and `ExecutionOptions`.
+
+use monty::{
+    ExcType, ExecutionOptions, LimitedTracker, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResourceLimits,
+    RunProgress,
+};
+
+fn run_with(code: &str, options: ExecutionOptions) -> MontyRun {
+    MontyRun::new(code.to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .with_execution_options(options)
+}
+
+fn run(code: &str) -> MontyRun {
+    run_with(code, ExecutionOptions::default().allow_eval(true))
+}
+
+#[test]
+fn disabled_by_default() {
+    let ex = MontyRun::new("eval('1 + 1')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let err = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::RuntimeError);
+    assert_eq!(err.message(), Some("eval() is not enabled"));
+
+    let ex = MontyRun::new("exec('x = 1')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let err = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.message(), Some("exec() is not enabled"));
+}
+
+#[test]
+fn eval_expression() {
+    let ex = run("x = 20\neval(' x * 2 + len(\"ab\")')");
+    assert_eq!(ex.run_no_limits(vec![]).unwrap(), MontyObject::Int(42));
+}
+
+#[test]
+fn eval_rejects_statements() {
+    let ex = run("eval('x = 1')");
+    let err = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::SyntaxError);
+}
+
+#[test]
+fn exec_defines_globals_at_module_level() {
+    let code = "
+exec('''
+def square(n):
+    return n * n
+
+total = square(x)
+''')
+(total, square(3))
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec!["x".to_owned()], vec![])
+        .unwrap()
+        .with_execution_options(ExecutionOptions::default().allow_eval(true));
+    let result = ex.run_no_limits(vec![MontyObject::Int(4)]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![MontyObject::Int(16), MontyObject::Int(9)])
+    );
+}
+
+#[test]
+fn exec_returns_none() {
+    let ex = run("exec('y = 1')");
+    assert_eq!(ex.run_no_limits(vec![]).unwrap(), MontyObject::None);
+}
+
+#[test]
+fn eval_in_function_sees_locals_and_globals() {
+    let code = "
+scale = 10
+
+def f(a):
+    b = a + 1
+    return eval('(a + b) * scale')
+
+f(1)
+";
+    assert_eq!(run(code).run_no_limits(vec![]).unwrap(), MontyObject::Int(30));
+}
+
+#[test]
+fn exec_in_function_does_not_change_locals() {
+    let code = "
+def f():
+    a = 1
+    exec('a = 2')
+    return a
+
+f()
+";
+    assert_eq!(run(code).run_no_limits(vec![]).unwrap(), MontyObject::Int(1));
+}
+
+#[test]
+fn eval_with_globals_and_locals_dicts() {
+    let code = "
+hidden = 100
+(eval('a + b', {'a': 1, 'b': 2}, {'b': 40}), eval('x', None, {'x': 5}))
+";
+    let result = run(code).run_no_limits(vec![]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![MontyObject::Int(41), MontyObject::Int(5)])
+    );
+
+    let err = run("hidden = 1\neval('hidden', {})").run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::NameError);
+}
+
+#[test]
+fn argument_errors() {
+    let err = run("eval(1)").run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+    assert_eq!(
+        err.message(),
+        Some("eval() arg 1 must be a string, bytes or code object")
+    );
+
+    let err = run("eval('1', [])").run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+    assert_eq!(err.message(), Some("eval() globals must be a dict, not list"));
+}
+
+#[test]
+fn syntax_error_can_be_caught() {
+    let code = "
+try:
+    eval('1 +')
+except SyntaxError:
+    result = 'caught'
+result
+";
+    assert_eq!(
+        run(code).run_no_limits(vec![]).unwrap(),
+        MontyObject::String("caught".to_owned())
+    );
+}
+
+#[test]
+fn runtime_error_traceback_uses_string_filename() {
+    let err = run("eval('1 / 0')").run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ZeroDivisionError);
+    let frame = err.location().unwrap();
+    assert_eq!(frame.filename, "<string>");
+    assert_eq!(frame.preview_line, None);
+}
+
+#[test]
+fn source_length_limit() {
+    let options = ExecutionOptions::default().allow_eval(true).max_eval_source_len(5);
+    assert_eq!(
+        run_with("eval('1 + 2')", options).run_no_limits(vec![]).unwrap(),
+        MontyObject::Int(3)
+    );
+    let err = run_with("eval('1 + 2 + 3')", options)
+        .run_no_limits(vec![])
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ValueError);
+}
+
+#[test]
+fn nesting_depth_limit() {
+    let options = ExecutionOptions::default().allow_eval(true).max_eval_depth(2);
+    let ex = run_with("eval(\"eval('1')\")", options);
+    assert_eq!(ex.run_no_limits(vec![]).unwrap(), MontyObject::Int(1));
+
+    let ex = run_with("eval(\"eval(\\\"eval('1')\\\")\")", options);
+    let err = ex.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::RecursionError);
+}
+
+#[test]
+fn nesting_depth_ignores_functions_defined_by_exec() {
+    let options = ExecutionOptions::default().allow_eval(true).max_eval_depth(2);
+    let code = "
+exec('''
+def f(n):
+    if n == 0:
+        return eval('1')
+    return f(n - 1) + 1
+''')
+f(5)
+";
+    assert_eq!(
+        run_with(code, options).run_no_limits(vec![]).unwrap(),
+        MontyObject::Int(6)
+    );
+}
+
+#[test]
+fn repeated_calls_reuse_compiled_code() {
+    let code = "
+total = 0
+for i in range(50):
+    total += eval('i * 2')
+total
+";
+    assert_eq!(run(code).run_no_limits(vec![]).unwrap(), MontyObject::Int(2450));
+}
+
+#[test]
+fn uses_the_same_resource_tracker() {
+    let limits = ResourceLimits::new().max_allocations(50);
+    let ex = run("eval('[[i] for i in range(1000)]')");
+    let err = ex
+        .run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::MemoryError);
+}
+
+#[test]
+fn compiled_sources_limit() {
+    let options = ExecutionOptions::default().allow_eval(true).max_eval_compiled(2);
+    let code = "
+for _ in range(10):
+    eval('1')
+    eval('2')
+";
+    run_with(code, options).run_no_limits(vec![]).unwrap();
+
+    let err = run_with("eval('1')\nexec('x = 2')\neval('3')", options)
+        .run_no_limits(vec![])
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::MemoryError);
+    assert_eq!(err.message(), Some("eval() can't compile more than 2 distinct sources"));
+}
+
+#[test]
+fn compiled_code_counts_towards_memory_limit() {
+    let options = ExecutionOptions::default()
+        .allow_eval(true)
+        .max_eval_compiled(usize::MAX);
+    let code = "
+def run(i):
+    exec(f'y = {i}')
+
+for i in range(10000):
+    run(i)
+";
+    let limits = ResourceLimits::new().max_memory(500_000);
+    let err = run_with(code, options)
+        .run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::MemoryError);
+}
+
+#[test]
+fn compiled_code_survives_dump_and_load() {
+    let options = ExecutionOptions::default().allow_eval(true).max_eval_compiled(1);
+    let ex = MontyRun::new(
+        "a = eval('6 * 7')\nfetch()\neval('6 * 7') + eval(source)".to_owned(),
+        "test.py",
+        vec!["source".to_owned()],
+        vec!["fetch".to_owned()],
+    )
+    .unwrap()
+    .with_execution_options(options);
+
+    for (source, expected) in [("6 * 7", Ok(84)), ("a", Err(ExcType::MemoryError))] {
+        let inputs = vec![MontyObject::String(source.to_owned())];
+        let progress = ex
+            .clone()
+            .start(inputs, NoLimitTracker, &mut PrintWriter::Stdout)
+            .unwrap();
+        let loaded: RunProgress<NoLimitTracker> = RunProgress::load(&progress.dump().unwrap()).unwrap();
+        let (_, _, _, _, _, state) = loaded.into_function_call().expect("external call");
+        let result = state.run(MontyObject::None, &mut PrintWriter::Stdout);
+        match expected {
+            Ok(value) => assert_eq!(result.unwrap().into_complete(), Some(MontyObject::Int(value))),
+            Err(exc_type) => assert_eq!(result.unwrap_err().exc_type(), exc_type),
+        }
+    }
+}
+
+#[test]
+fn runner_can_be_run_again() {
+    let ex = run("exec('x = 1')\neval('x + 1')");
+    assert_eq!(ex.run_no_limits(vec![]).unwrap(), MontyObject::Int(2));
+    assert_eq!(ex.run_no_limits(vec![]).unwrap(), MontyObject::Int(2));
+}
+
+#[test]
+fn options_survive_dump_and_load() {
+    let ex = run("eval('6 * 7')");
+    let loaded = MontyRun::load(&ex.dump().unwrap()).unwrap();
+    assert_eq!(loaded.run_no_limits(vec![]).unwrap(), MontyObject::Int(42));
+}
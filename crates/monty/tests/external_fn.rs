@@ -0,0 +1,111 @@
+//! Tests for `#[monty::external_fn]`, which wraps typed Rust functions for sandboxed code.
+#![cfg(feature = "macros")]
+
+use monty::{CompileOptions, ExcType, MontyException, MontyObject, MontyRun, NativeModule};
+
+/// Repeats `word` `times` times, optionally joined with `sep`.
+#[monty::external_fn]
+fn repeat(word: &str, times: i64, sep: Option<String>) -> Result<Vec<String>, MontyException> {
+    if times < 0 {
+        return Err(MontyException::new(
+            ExcType::ValueError,
+            Some("times must not be negative".to_owned()),
+        ));
+    }
+    let words = (0..times).map(|_| word.to_owned());
+    Ok(match sep {
+        Some(sep) => vec![words.collect::<Vec<_>>().join(&sep)],
+        None => words.collect(),
+    })
+}
+
+#[monty::external_fn]
+fn mean(values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+#[monty::external_fn]
+fn noop() {}
+
+fn run(code: &str) -> Result<MontyObject, MontyException> {
+    let module = NativeModule::new("text")
+        .function("repeat", repeat)
+        .function("mean", mean)
+        .function("noop", noop);
+    let code = format!("import text\n{code}");
+    let runner = MontyRun::new_with_modules(
+        code,
+        "test.py",
+        vec![],
+        vec![],
+        vec![module],
+        vec![],
+        CompileOptions::default(),
+    )
+    .unwrap();
+    runner.run_no_limits(vec![])
+}
+
+fn type_error_message(code: &str) -> String {
+    let exc = run(code).unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::TypeError, "{exc}");
+    exc.message().unwrap().to_owned()
+}
+
+#[test]
+fn positional_and_keyword_arguments() {
+    assert_eq!(
+        run("text.repeat('a', 2)").unwrap(),
+        MontyObject::List(vec![MontyObject::String("a".to_owned()); 2])
+    );
+    assert_eq!(
+        run("text.repeat(times=3, word='b', sep='-')").unwrap(),
+        MontyObject::List(vec![MontyObject::String("b-b-b".to_owned())])
+    );
+    assert_eq!(run("text.mean([1, 2.5, 3.5])").unwrap(), MontyObject::Float(7.0 / 3.0));
+    assert_eq!(run("text.mean(())").unwrap(), MontyObject::None);
+    assert_eq!(run("text.noop()").unwrap(), MontyObject::None);
+}
+
+#[test]
+fn errors_from_function_are_raised() {
+    let exc = run("text.repeat('a', -1)").unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::ValueError);
+    assert_eq!(exc.message(), Some("times must not be negative"));
+}
+
+#[test]
+fn argument_errors_are_type_errors() {
+    assert_eq!(
+        type_error_message("text.repeat('a', 1, '', 4)"),
+        "repeat() takes 3 positional arguments but 4 were given"
+    );
+    assert_eq!(
+        type_error_message("text.repeat()"),
+        "repeat() missing 2 required arguments: 'word' and 'times'"
+    );
+    assert_eq!(
+        type_error_message("text.repeat('a', times='2')"),
+        "repeat() argument 'times' must be int, not str"
+    );
+    assert_eq!(
+        type_error_message("text.repeat('a', 1, count=2)"),
+        "repeat() got an unexpected keyword argument 'count'"
+    );
+    assert_eq!(
+        type_error_message("text.repeat('a', 1, word='b')"),
+        "repeat() got multiple values for argument 'word'"
+    );
+    assert_eq!(
+        type_error_message("text.mean(['x'])"),
+        "mean() argument 'values' must be list[float], not list"
+    );
+    assert_eq!(
+        type_error_message("text.noop(1)"),
+        "noop() takes 0 positional arguments but 1 was given"
+    );
+}
@@ -0,0 +1,113 @@
+//! Tests for `MontyObject::ExternalObject`, opaque host objects whose attributes and methods are resolved by the host.
+
+use monty::{ExcType, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter, RunProgress};
+
+fn database() -> MontyObject {
+    MontyObject::ExternalObject {
+        class_name: "Database".to_owned(),
+        handle: 1,
+    }
+}
+
+fn try_start(code: &str) -> Result<RunProgress<NoLimitTracker>, MontyException> {
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec!["db".to_owned()], vec![]).unwrap();
+    runner.start(vec![database()], NoLimitTracker, &mut PrintWriter::Disabled)
+}
+
+fn start(code: &str) -> RunProgress<NoLimitTracker> {
+    try_start(code).unwrap()
+}
+
+#[test]
+fn method_call_is_forwarded_to_host() {
+    let progress = start("db.query('select 1', limit=2)");
+    let (function_name, args, kwargs, _, method_call, state) = progress.into_function_call().unwrap();
+    assert_eq!(function_name, "query");
+    assert!(method_call);
+    assert_eq!(args, vec![database(), MontyObject::String("select 1".to_owned())]);
+    assert_eq!(
+        kwargs,
+        vec![(MontyObject::String("limit".to_owned()), MontyObject::Int(2))]
+    );
+
+    let progress = state
+        .run(MontyObject::List(vec![MontyObject::Int(1)]), &mut PrintWriter::Disabled)
+        .unwrap();
+    assert_eq!(
+        progress.into_complete().unwrap(),
+        MontyObject::List(vec![MontyObject::Int(1)])
+    );
+}
+
+#[test]
+fn attribute_read_is_forwarded_to_host() {
+    let progress = start("db.name + '!'");
+    let (function_name, args, kwargs, _, method_call, state) = progress.into_function_call().unwrap();
+    assert_eq!(function_name, "__getattr__");
+    assert!(method_call);
+    assert_eq!(args, vec![database(), MontyObject::String("name".to_owned())]);
+    assert!(kwargs.is_empty());
+
+    let progress = state
+        .run(MontyObject::String("main".to_owned()), &mut PrintWriter::Disabled)
+        .unwrap();
+    assert_eq!(
+        progress.into_complete().unwrap(),
+        MontyObject::String("main!".to_owned())
+    );
+}
+
+#[test]
+fn private_attributes_are_not_forwarded() {
+    for code in ["db._secret", "db._reset()", "db.__dict__"] {
+        let exc = try_start(code).map(|_| ()).unwrap_err();
+        assert_eq!(exc.exc_type(), ExcType::AttributeError, "{code}");
+    }
+}
+
+#[test]
+fn setting_attributes_raises() {
+    let exc = try_start("db.name = 'x'").map(|_| ()).unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::AttributeError);
+}
+
+#[test]
+fn repr_equality_and_hashing() {
+    let code = "
+other = db
+seen = {db: 'db'}
+(repr(db), db == other, seen[other], bool(db))
+";
+    let result = start(code).into_complete().unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::String("<external Database #1>".to_owned()),
+            MontyObject::Bool(true),
+            MontyObject::String("db".to_owned()),
+            MontyObject::Bool(true),
+        ])
+    );
+}
+
+#[test]
+fn external_object_round_trips() {
+    let result = start("[db, db]").into_complete().unwrap();
+    assert_eq!(result, MontyObject::List(vec![database(), database()]));
+}
+
+#[test]
+fn external_object_returned_from_host() {
+    let code = "cursor = db.cursor()\ncursor.fetch()";
+    let progress = start(code);
+    let (_, _, _, _, _, state) = progress.into_function_call().unwrap();
+    let cursor = MontyObject::ExternalObject {
+        class_name: "Cursor".to_owned(),
+        handle: 7,
+    };
+    let progress = state.run(cursor.clone(), &mut PrintWriter::Disabled).unwrap();
+    let (function_name, args, _, _, method_call, _) = progress.into_function_call().unwrap();
+    assert_eq!(function_name, "fetch");
+    assert!(method_call);
+    assert_eq!(args, vec![cursor]);
+}
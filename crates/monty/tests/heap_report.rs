@@ -0,0 +1,147 @@
+//! Tests for heap introspection: `run_with_heap_report` and the heap reports and dumps of paused executions.
+
+use monty::{
+    ExcType, HeapReport, LimitedTracker, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter,
+    ResourceLimits, RunProgress,
+};
+
+fn report(code: &str, limits: Option<ResourceLimits>) -> (Result<MontyObject, MontyException>, HeapReport) {
+    let runner = MontyRun::new(code.to_owned(), "heap.py", vec![], vec![]).unwrap();
+    match limits {
+        Some(limits) => runner.run_with_heap_report(vec![], LimitedTracker::new(limits), &mut PrintWriter::Disabled),
+        None => runner.run_with_heap_report(vec![], NoLimitTracker, &mut PrintWriter::Disabled),
+    }
+}
+
+#[test]
+fn retainers_are_ranked_by_bytes() {
+    let code = "
+big = [[i] for i in range(50)]
+small = [1]
+count = len(big)
+count
+";
+    let (result, report) = report(code, None);
+    assert_eq!(result.unwrap(), MontyObject::Int(50));
+
+    let names: Vec<_> = report.retainers.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["big", "small"], "ints aren't heap values");
+    assert_eq!(report.retainers[0].objects, 51, "the outer list and its 50 items");
+    assert_eq!(report.retainers[1].objects, 1);
+    assert!(report.retainers[0].bytes > report.retainers[1].bytes);
+
+    let lists = report.by_type.iter().find(|usage| usage.type_name == "list").unwrap();
+    assert_eq!(lists.count, 52);
+    assert_eq!(report.by_type[0].type_name, "list", "lists use the most memory");
+    assert_eq!(
+        report.total_bytes,
+        report.by_type.iter().map(|usage| usage.bytes).sum::<usize>()
+    );
+    assert_eq!(
+        report.live_objects,
+        report.by_type.iter().map(|usage| usage.count).sum::<usize>()
+    );
+}
+
+#[test]
+fn shared_values_count_for_each_retainer() {
+    let (_, report) = report("inner = [1, 2]\nouter = (inner, inner)\nNone", None);
+    let retainer = |name: &str| report.retainers.iter().find(|r| r.name == name).unwrap();
+    assert_eq!(retainer("inner").objects, 1);
+    assert_eq!(retainer("outer").objects, 2);
+}
+
+#[test]
+fn report_is_returned_when_memory_runs_out() {
+    let code = "
+data = []
+while True:
+    data.append('x' * 1000)
+";
+    let (result, report) = report(code, Some(ResourceLimits::new().max_memory(100_000)));
+    assert_eq!(result.unwrap_err().exc_type(), ExcType::MemoryError);
+
+    let data = &report.retainers[0];
+    assert_eq!(data.name, "data");
+    assert!(data.objects > 50, "{data:?}");
+    let strings = report.by_type.iter().find(|usage| usage.type_name == "str").unwrap();
+    assert!(strings.bytes > 50_000, "{strings:?}");
+}
+
+/// Resumes checkpoints until `done` accepts one, returning its report.
+fn paused_report(code: &str, done: impl Fn(&HeapReport) -> bool) -> HeapReport {
+    let runner = MontyRun::new(code.to_owned(), "heap.py", vec![], vec![]).unwrap();
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(1));
+    let mut progress = runner.start(vec![], tracker, &mut PrintWriter::Disabled).unwrap();
+    loop {
+        let state = match progress {
+            RunProgress::Paused(state) => state,
+            other => panic!("unexpected progress: {other:?}"),
+        };
+        let report = state.heap_report();
+        if done(&report) {
+            return report;
+        }
+        progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+    }
+}
+
+#[test]
+fn paused_reports_include_function_locals() {
+    let code = "
+def build(n):
+    rows = []
+    for i in range(n):
+        rows.append([i])
+    return rows
+
+table = build(3)
+table
+";
+    let report = paused_report(code, |report| {
+        report
+            .retainers
+            .iter()
+            .any(|r| r.name == "build.rows" && r.objects == 4)
+    });
+    assert!(report.retainers.iter().all(|r| r.name != "table"), "not assigned yet");
+}
+
+#[test]
+fn heap_dump_serializes_the_object_graph() {
+    let code = "
+items = ['a' * 10, 'b' * 10]
+pair = (items, items)
+pair
+";
+    let runner = MontyRun::new(code.to_owned(), "heap.py", vec![], vec![]).unwrap();
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(1));
+    let mut progress = runner.start(vec![], tracker, &mut PrintWriter::Disabled).unwrap();
+    // pause before the last statement, once both variables are bound
+    let state = loop {
+        let RunProgress::Paused(state) = progress else {
+            panic!("expected a pause");
+        };
+        if state.globals().len() == 2 {
+            break state;
+        }
+        progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+    };
+
+    let dump = state.heap_dump();
+    let root = |name: &str| dump.roots.iter().find(|root| root.name == name).unwrap().id;
+    let object = |id: usize| dump.objects.iter().find(|object| object.id == id).unwrap();
+
+    let pair = object(root("pair"));
+    assert_eq!(pair.type_name, "tuple");
+    assert_eq!(pair.references, [root("items"), root("items")]);
+    let items = object(root("items"));
+    assert_eq!(items.type_name, "list");
+    assert_eq!(items.refcount, 3, "the variable and both tuple items");
+    assert_eq!(items.references.len(), 2);
+    assert!(items.references.iter().all(|&id| object(id).type_name == "str"));
+
+    let json: serde_json::Value = serde_json::from_str(&dump.to_json()).unwrap();
+    assert_eq!(json["objects"].as_array().unwrap().len(), dump.objects.len());
+    assert_eq!(json["roots"].as_array().unwrap().len(), 2);
+}
@@ -147,6 +147,60 @@ fn input_dict_get() {
     assert_eq!(result, MontyObject::Int(42));
 }
 
+#[test]
+fn input_defaultdict() {
+    let input = MontyObject::DefaultDict {
+        default_factory: Box::new(MontyObject::builtin_type("list").unwrap()),
+        items: vec![(MontyObject::String("a".to_string()), MontyObject::List(vec![]))].into(),
+    };
+
+    let ex = MontyRun::new(
+        "x['b'].append(1)\nx".to_owned(),
+        "test.py",
+        vec!["x".to_owned()],
+        vec![],
+    )
+    .unwrap();
+    let result = ex.run_no_limits(vec![input]).unwrap();
+    assert_eq!(
+        result.py_repr(),
+        "defaultdict(<class 'list'>, {'a': [], 'b': [1]})",
+        "the factory fills missing keys"
+    );
+}
+
+#[test]
+fn input_counter() {
+    let input = MontyObject::Counter(vec![(MontyObject::String("a".to_string()), MontyObject::Int(2))].into());
+
+    let ex = MontyRun::new("x.update('ab')\nx".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    let result = ex.run_no_limits(vec![input]).unwrap();
+    let expected = vec![
+        (MontyObject::String("a".to_string()), MontyObject::Int(3)),
+        (MontyObject::String("b".to_string()), MontyObject::Int(1)),
+    ];
+    assert_eq!(result, MontyObject::Counter(expected.into()));
+}
+
+#[test]
+fn input_deque() {
+    let input = MontyObject::Deque {
+        items: vec![MontyObject::Int(1), MontyObject::Int(2)],
+        maxlen: Some(2),
+    };
+
+    let ex = MontyRun::new("x.append(3)\nx".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    let result = ex.run_no_limits(vec![input]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Deque {
+            items: vec![MontyObject::Int(2), MontyObject::Int(3)],
+            maxlen: Some(2),
+        }
+    );
+    assert_eq!(result.py_repr(), "deque([2, 3], maxlen=2)");
+}
+
 // === Multiple Inputs ===
 
 #[test]
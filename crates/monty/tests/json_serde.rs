@@ -3,7 +3,8 @@
 //! `MontyObject` uses derived serde with externally tagged enum format.
 //! This means each variant is wrapped in an object with the variant name as key.
 
-use monty::{ExcType, MontyObject, MontyRun};
+use monty::{BytesPolicy, ExcType, FallbackPolicy, JsonPolicy, MontyObject, MontyRun, ToJsonError};
+use serde_json::json;
 
 // === JSON Serialization Tests ===
 
@@ -250,3 +251,99 @@ fn cycle_equality_different_ids() {
         panic!("expected outer list");
     }
 }
+
+// === Plain JSON Conversion Tests ===
+
+fn run_to_json(code: &str, policy: &JsonPolicy) -> Result<serde_json::Value, ToJsonError> {
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    ex.run_no_limits(vec![]).unwrap().to_json(policy)
+}
+
+#[test]
+fn to_json_is_untagged() {
+    let json = run_to_json(
+        "{'a': 1, 'b': [1.5, 'two', None, True], 'c': (1, 2), 'd': {3}}",
+        &JsonPolicy::default(),
+    );
+    assert_eq!(
+        json.unwrap(),
+        json!({"a": 1, "b": [1.5, "two", null, true], "c": [1, 2], "d": [3]})
+    );
+}
+
+#[test]
+fn to_json_stringifies_keys_like_python() {
+    let json = run_to_json("{1: 'a', 2.5: 'b', True: 'c', None: 'd'}", &JsonPolicy::default());
+    assert_eq!(json.unwrap(), json!({"1": "a", "2.5": "b", "true": "c", "null": "d"}));
+
+    let err = run_to_json("{(1, 2): 'a'}", &JsonPolicy::default().fallback(FallbackPolicy::Error)).unwrap_err();
+    assert!(err.is_key);
+    assert_eq!(err.to_string(), "keys must be str, int, float, bool or None, not tuple");
+
+    let json = run_to_json("{(1, 2): 'a'}", &JsonPolicy::default());
+    assert_eq!(json.unwrap(), json!({"(1, 2)": "a"}));
+}
+
+#[test]
+fn to_json_bytes_policy() {
+    let bytes = MontyObject::Bytes(b"hello".to_vec());
+    assert_eq!(bytes.to_json(&JsonPolicy::default()).unwrap(), json!("aGVsbG8="));
+    assert_eq!(
+        bytes.to_json(&JsonPolicy::default().bytes(BytesPolicy::Array)).unwrap(),
+        json!([104, 101, 108, 108, 111])
+    );
+    let err = bytes
+        .to_json(&JsonPolicy::default().bytes(BytesPolicy::Error))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Object of type bytes is not JSON serializable");
+}
+
+#[test]
+fn to_json_fallback_policy() {
+    let policy = JsonPolicy::default();
+    assert_eq!(run_to_json("...", &policy).unwrap(), json!("Ellipsis"));
+    assert_eq!(run_to_json("float('inf')", &policy).unwrap(), json!("inf"));
+    assert_eq!(
+        run_to_json("[len]", &policy).unwrap(),
+        json!(["<built-in function len>"])
+    );
+
+    let policy = JsonPolicy::default().fallback(FallbackPolicy::Error);
+    let err = run_to_json("[1, ...]", &policy).unwrap_err();
+    assert_eq!(err.type_name, "ellipsis");
+    assert!(!err.is_key);
+}
+
+#[test]
+fn to_json_dates_and_big_ints() {
+    let code = "
+from datetime import date, datetime, timedelta
+[date(2024, 1, 2), datetime(2024, 1, 2, 3, 4, 5), datetime(2024, 1, 2, 3, 4, 5, 6), timedelta(minutes=1), 2 ** 64]
+";
+    assert_eq!(
+        run_to_json(code, &JsonPolicy::default()).unwrap(),
+        json!([
+            "2024-01-02",
+            "2024-01-02T03:04:05",
+            "2024-01-02T03:04:05.000006",
+            60,
+            "18446744073709551616"
+        ])
+    );
+}
+
+#[test]
+fn from_json_round_trip() {
+    let json = json!({"a": [1, 2.5, "x", null, false], "b": {"c": 18_446_744_073_709_551_615_u64}});
+    let obj = MontyObject::from_json(&json);
+
+    let ex = MontyRun::new(
+        "v['a'][0] + len(v['b'])".to_owned(),
+        "test.py",
+        vec!["v".to_owned()],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(ex.run_no_limits(vec![obj.clone()]).unwrap(), MontyObject::Int(2));
+    assert_eq!(obj.to_json(&JsonPolicy::default()).unwrap(), json);
+}
@@ -0,0 +1,197 @@
+//! Tests for host-provided native modules passed via `MontyRun::new_with_modules`.
+
+use monty::{
+    CompileOptions, ExcType, MontyException, MontyObject, MontyRun, NativeModule, NoLimitTracker, PrintWriter,
+    RunProgress,
+};
+
+/// A module with a constant, a function using positional and keyword arguments, and a
+/// function that raises.
+fn units_module() -> NativeModule {
+    NativeModule::new("units")
+        .constant("INCH", MontyObject::Float(2.54))
+        .constant("NAMES", MontyObject::List(vec![MontyObject::String("cm".to_owned())]))
+        .function("scale", |args, kwargs| {
+            let [MontyObject::Int(value)] = args.as_slice() else {
+                return Err(MontyException::new(
+                    ExcType::TypeError,
+                    Some("scale() takes one int".to_owned()),
+                ));
+            };
+            let factor = match kwargs.as_slice() {
+                [(MontyObject::String(key), MontyObject::Int(factor))] if key == "factor" => *factor,
+                _ => 2,
+            };
+            Ok(MontyObject::Int(value * factor))
+        })
+        .function("fail", |_args, _kwargs| {
+            Err(MontyException::new(
+                ExcType::ValueError,
+                Some("native failure".to_owned()),
+            ))
+        })
+}
+
+fn run_with_units(code: &str) -> Result<MontyObject, MontyException> {
+    let runner = MontyRun::new_with_modules(
+        code.to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+        vec![units_module()],
+        vec![],
+        CompileOptions::default(),
+    )?;
+    runner.run_no_limits(vec![])
+}
+
+#[test]
+fn import_module_and_call_functions() {
+    let result = run_with_units("import units\n(units.scale(21), units.scale(5, factor=3), units.INCH)").unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::Int(42),
+            MontyObject::Int(15),
+            MontyObject::Float(2.54)
+        ])
+    );
+}
+
+#[test]
+fn from_import_names() {
+    let result = run_with_units("from units import NAMES, scale as s\nNAMES + [s(4)]").unwrap();
+    assert_eq!(
+        result,
+        MontyObject::List(vec![MontyObject::String("cm".to_owned()), MontyObject::Int(8)])
+    );
+}
+
+#[test]
+fn constants_are_fresh_per_import() {
+    let code = "
+import units
+units.NAMES.append('in')
+def reimport():
+    import units
+    return units.NAMES
+reimport()
+";
+    let result = run_with_units(code).unwrap();
+    assert_eq!(result, MontyObject::List(vec![MontyObject::String("cm".to_owned())]));
+}
+
+#[test]
+fn native_errors_are_catchable() {
+    let code = "
+import units
+try:
+    units.fail()
+except ValueError as e:
+    caught = str(e)
+caught
+";
+    let result = run_with_units(code).unwrap();
+    assert_eq!(result, MontyObject::String("native failure".to_owned()));
+}
+
+#[test]
+fn uncaught_native_error_propagates() {
+    let err = run_with_units("import units\nunits.scale('x')").unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+    assert_eq!(err.message(), Some("scale() takes one int"));
+}
+
+#[test]
+fn unknown_attribute_and_module() {
+    let err = run_with_units("from units import missing").unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ImportError);
+
+    let err = run_with_units("import unknown_mod").unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ModuleNotFoundError);
+}
+
+#[test]
+fn builtin_modules_take_precedence() {
+    let shadow = NativeModule::new("math").constant("pi", MontyObject::Int(3));
+    let runner = MontyRun::new_with_modules(
+        "import math\nmath.pi > 3".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+        vec![shadow],
+        vec![],
+        CompileOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(runner.run_no_limits(vec![]).unwrap(), MontyObject::Bool(true));
+}
+
+#[test]
+fn duplicate_module_names_are_rejected() {
+    let result = MontyRun::new_with_modules(
+        "1".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+        vec![NativeModule::new("dup"), NativeModule::new("dup")],
+        vec![],
+        CompileOptions::default(),
+    );
+    let err = result.unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::RuntimeError);
+    assert_eq!(err.message(), Some("native module 'dup' is registered more than once"));
+}
+
+#[test]
+fn native_calls_run_inline_with_flat_external_functions() {
+    let runner = MontyRun::new_with_modules(
+        "import units\nunits.scale(ext())".to_owned(),
+        "test.py",
+        vec![],
+        vec!["ext".to_owned()],
+        vec![units_module()],
+        vec![],
+        CompileOptions::default(),
+    )
+    .unwrap();
+
+    let progress = runner.start(vec![], NoLimitTracker, &mut PrintWriter::Stdout).unwrap();
+    let (name, _, _, _, _, state) = progress.into_function_call().expect("flat external call");
+    assert_eq!(name, "ext");
+
+    // The native call is resolved in place, so the next progress is completion
+    let progress = state.run(MontyObject::Int(10), &mut PrintWriter::Stdout).unwrap();
+    assert_eq!(progress.into_complete(), Some(MontyObject::Int(20)));
+}
+
+#[test]
+fn native_calls_surface_to_host_after_load() {
+    let runner = MontyRun::new_with_modules(
+        "import units\nunits.scale(4) + units.INCH".to_owned(),
+        "test.py",
+        vec![],
+        vec![],
+        vec![units_module()],
+        vec![],
+        CompileOptions::default(),
+    )
+    .unwrap();
+    let loaded = MontyRun::load(&runner.dump().unwrap()).unwrap();
+
+    let progress = loaded.start(vec![], NoLimitTracker, &mut PrintWriter::Stdout).unwrap();
+    let RunProgress::FunctionCall {
+        function_name,
+        args,
+        state,
+        ..
+    } = progress
+    else {
+        panic!("expected a function call");
+    };
+    assert_eq!(function_name, "units.scale");
+    assert_eq!(args, vec![MontyObject::Int(4)]);
+
+    let progress = state.run(MontyObject::Int(1), &mut PrintWriter::Stdout).unwrap();
+    assert_eq!(progress.into_complete(), Some(MontyObject::Float(3.54)));
+}
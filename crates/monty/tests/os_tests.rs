@@ -38,6 +38,22 @@ fn run_to_oscall(code: &str) -> (OsFunction, Vec<MontyObject>) {
                 | OsFunction::Rename => MontyObject::None,
                 OsFunction::Getenv => MontyObject::String("mock_env_value".to_owned()),
                 OsFunction::GetEnviron => MontyObject::Dict(vec![].into()),
+                OsFunction::Time => MontyObject::Float(0.0),
+                OsFunction::DateTimeNow => MontyObject::DateTime {
+                    year: 2024,
+                    month: 1,
+                    day: 2,
+                    hour: 0,
+                    minute: 0,
+                    second: 0,
+                    microsecond: 0,
+                },
+                OsFunction::DateToday => MontyObject::Date {
+                    year: 2024,
+                    month: 1,
+                    day: 2,
+                },
+                OsFunction::Input => MontyObject::String("mock input".to_owned()),
             };
             let _ = state.run(mock_result, &mut PrintWriter::Stdout);
             (function, args)
@@ -457,3 +473,125 @@ import os
     assert_eq!(func, OsFunction::GetEnviron);
     assert_eq!(result, MontyObject::Bool(true));
 }
+
+// =============================================================================
+// Clock tests
+// =============================================================================
+
+#[test]
+fn time_time_yields_oscall() {
+    let code = r"
+import time
+time.time()
+";
+    let (func, args, result) = run_oscall_with_result(code, MontyObject::Float(1_704_164_645.5));
+    assert_eq!(func, OsFunction::Time);
+    assert!(args.is_empty(), "expected empty args, got {args:?}");
+    assert_eq!(result, MontyObject::Float(1_704_164_645.5));
+}
+
+#[test]
+fn datetime_now_yields_oscall() {
+    let code = r"
+from datetime import datetime
+datetime.now().isoformat()
+";
+    let now = MontyObject::DateTime {
+        year: 2024,
+        month: 1,
+        day: 2,
+        hour: 3,
+        minute: 4,
+        second: 5,
+        microsecond: 6,
+    };
+    let (func, args, result) = run_oscall_with_result(code, now);
+    assert_eq!(func, OsFunction::DateTimeNow);
+    assert!(args.is_empty(), "expected empty args, got {args:?}");
+    assert_eq!(result, MontyObject::String("2024-01-02T03:04:05.000006".to_owned()));
+}
+
+#[test]
+fn date_today_yields_oscall() {
+    let code = r"
+import datetime
+datetime.date.today() + datetime.timedelta(days=30)
+";
+    let today = MontyObject::Date {
+        year: 2024,
+        month: 1,
+        day: 2,
+    };
+    let (func, _, result) = run_oscall_with_result(code, today);
+    assert_eq!(func, OsFunction::DateToday);
+    assert_eq!(
+        result,
+        MontyObject::Date {
+            year: 2024,
+            month: 2,
+            day: 1,
+        }
+    );
+}
+
+#[test]
+fn input_yields_oscall_after_prompt() {
+    let code = r"
+name = input('name? ')
+f'hello {name}'
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut print = PrintWriter::Collect(String::new());
+    let progress = runner.start(vec![], NoLimitTracker, &mut print).unwrap();
+    let RunProgress::OsCall {
+        function, args, state, ..
+    } = progress
+    else {
+        panic!("expected OsCall, got {progress:?}");
+    };
+    assert_eq!(function, OsFunction::Input);
+    assert!(args.is_empty(), "expected empty args, got {args:?}");
+    assert_eq!(
+        print.collected_output(),
+        Some("name? "),
+        "prompt is printed before pausing"
+    );
+
+    let result = state
+        .run(MontyObject::String("monty".to_owned()), &mut print)
+        .unwrap()
+        .into_complete()
+        .expect("expected Complete after resume");
+    assert_eq!(result, MontyObject::String("hello monty".to_owned()));
+}
+
+#[test]
+fn input_in_loop_yields_each_time() {
+    let code = r"
+total = 0
+for _ in range(3):
+    total += int(input())
+total
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut progress = runner.start(vec![], NoLimitTracker, &mut PrintWriter::Stdout).unwrap();
+    for line in ["1", "20", "300"] {
+        let RunProgress::OsCall { function, state, .. } = progress else {
+            panic!("expected OsCall, got {progress:?}");
+        };
+        assert_eq!(function, OsFunction::Input);
+        progress = state
+            .run(MontyObject::String(line.to_owned()), &mut PrintWriter::Stdout)
+            .unwrap();
+    }
+    assert_eq!(progress.into_complete(), Some(MontyObject::Int(321)));
+}
+
+#[test]
+fn input_rejects_extra_args() {
+    let runner = MontyRun::new("input('a', 'b')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let exc = runner
+        .start(vec![], NoLimitTracker, &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), monty::ExcType::TypeError);
+}
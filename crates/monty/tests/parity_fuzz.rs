@@ -0,0 +1,731 @@
+//! Differential testing of Monty against CPython.
+//!
+//! Generates random small programs from the subset of Python Monty supports, runs each one
+//! through both interpreters, and reports every program where they disagree on the value
+//! returned or the exception raised. Programs are built from a seed, so each divergence can
+//! be reproduced and turned into a test case.
+//!
+//! This is behind the `parity-fuzz` feature since what it finds are gaps to fix rather than
+//! regressions, run it with `make test-parity-fuzz`. `MONTY_FUZZ_SEED` sets the first seed
+//! (random by default) and `MONTY_FUZZ_CASES` the number of programs (500 by default).
+#![cfg(feature = "parity-fuzz")]
+
+use std::{
+    env,
+    ffi::CString,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use monty::{LimitedTracker, MontyRun, PrintWriter, ResourceLimits};
+use pyo3::{prelude::*, types::PyDict};
+
+/// Number of programs generated when `MONTY_FUZZ_CASES` isn't set.
+const DEFAULT_CASES: u64 = 500;
+
+/// Maximum nesting of generated expressions.
+const MAX_DEPTH: u32 = 3;
+
+/// Memory limit for Monty, programs are small so only runaway ones reach it.
+const MAX_MEMORY: usize = 64 * 1024 * 1024;
+
+#[test]
+fn monty_matches_cpython() {
+    let first_seed = env_u64("MONTY_FUZZ_SEED").unwrap_or_else(random_seed);
+    let cases = env_u64("MONTY_FUZZ_CASES").unwrap_or(DEFAULT_CASES);
+
+    let mut divergences = Vec::new();
+    for seed in (0..cases).map(|case| first_seed.wrapping_add(case)) {
+        let code = Generator::new(seed).program();
+        let Some(monty) = run_monty(&code) else {
+            continue;
+        };
+        let cpython = run_cpython(&code);
+        if monty != cpython {
+            divergences.push(format!("seed {seed}:\n{code}\nmonty:   {monty}\ncpython: {cpython}"));
+        }
+    }
+    assert!(
+        divergences.is_empty(),
+        "{} of {cases} programs diverged, rerun one with MONTY_FUZZ_SEED=<seed> MONTY_FUZZ_CASES=1\n\n{}",
+        divergences.len(),
+        divergences.join("\n\n"),
+    );
+}
+
+#[test]
+fn generated_programs_compile() {
+    Python::attach(|py| {
+        let compile = py.import("builtins").unwrap().getattr("compile").unwrap();
+        for seed in 0..200 {
+            let code = Generator::new(seed).program();
+            if let Err(e) = compile.call1((code.as_str(), "fuzz.py", "exec")) {
+                panic!("seed {seed} generated invalid code:\n{code}\n{e}");
+            }
+        }
+    });
+}
+
+#[test]
+fn programs_are_reproducible() {
+    assert_eq!(Generator::new(7).program(), Generator::new(7).program());
+    assert_ne!(Generator::new(7).program(), Generator::new(8).program());
+}
+
+/// Outcome of running a program, compared between the two interpreters.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    /// `repr()` of the value of the program's last line.
+    Return(String),
+    /// The exception raised, formatted like `ValueError('message')`.
+    Raise(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Return(repr) => write!(f, "returned {repr}"),
+            Self::Raise(exc) => write!(f, "raised {exc}"),
+        }
+    }
+}
+
+/// Runs a program in Monty, returning `None` if it hit a resource limit, which CPython doesn't have.
+fn run_monty(code: &str) -> Option<Outcome> {
+    let runner = match MontyRun::new(code.to_owned(), "fuzz.py", vec![], vec![]) {
+        Ok(runner) => runner,
+        Err(e) => return Some(Outcome::Raise(e.py_repr())),
+    };
+    let limits = ResourceLimits::new()
+        .max_duration(Duration::from_secs(2))
+        .max_memory(MAX_MEMORY);
+    match runner.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Disabled) {
+        Ok(value) => Some(Outcome::Return(value.py_repr())),
+        Err(e) if e.limit_exceeded().is_some() => None,
+        Err(e) => Some(Outcome::Raise(e.py_repr())),
+    }
+}
+
+/// Runs a program in CPython, evaluating the last line as an expression to get its value.
+fn run_cpython(code: &str) -> Outcome {
+    let (statements, expr) = code.rsplit_once('\n').expect("programs end with an expression");
+    Python::attach(|py| {
+        let globals = PyDict::new(py);
+        let statements = CString::new(statements).expect("programs don't contain null bytes");
+        if let Err(e) = py.run(&statements, Some(&globals), None) {
+            return Outcome::Raise(format_exception(py, &e));
+        }
+        let expr = CString::new(expr).expect("programs don't contain null bytes");
+        match py.eval(&expr, Some(&globals), None) {
+            Ok(value) => Outcome::Return(value.repr().unwrap().to_string()),
+            Err(e) => Outcome::Raise(format_exception(py, &e)),
+        }
+    })
+}
+
+/// Formats a CPython exception the same way as `MontyException::py_repr`.
+fn format_exception(py: Python<'_>, e: &PyErr) -> String {
+    let exc_type = e.get_type(py).name().unwrap();
+    let message: String = e
+        .value(py)
+        .getattr("args")
+        .and_then(|args| args.get_item(0))
+        .and_then(|item| item.extract())
+        .unwrap_or_default();
+    if message.is_empty() {
+        format!("{exc_type}()")
+    } else if message.contains('\'') {
+        format!("{exc_type}(\"{message}\")")
+    } else {
+        format!("{exc_type}('{message}')")
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    let value = env::var(name).ok()?;
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} must be a number, got {value:?}")),
+    )
+}
+
+fn random_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    now.as_secs() ^ u64::from(now.subsec_nanos())
+}
+
+/// xorshift64* generator, so programs can be reproduced from a seed without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero, and adjacent seeds should give unrelated programs
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a number in `0..n`.
+    #[expect(clippy::cast_possible_truncation, reason = "the result is less than n")]
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns a number in `low..=high`.
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        let span = usize::try_from(high - low + 1).unwrap();
+        low + i64::try_from(self.below(span)).unwrap()
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// Type of value an expression is generated to produce, so most programs get past type errors
+/// and exercise the operations themselves.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Int,
+    Float,
+    Str,
+    Bool,
+    /// A list of ints.
+    List,
+}
+
+const KINDS: [Kind; 5] = [Kind::Int, Kind::Float, Kind::Str, Kind::Bool, Kind::List];
+
+const FLOATS: [&str; 12] = [
+    "0.0",
+    "-0.0",
+    "0.1",
+    "0.5",
+    "1.5",
+    "-2.25",
+    "3.14159",
+    "1e-07",
+    "2.5e16",
+    "1e300",
+    "float('inf')",
+    "float('nan')",
+];
+
+/// String literals, these can't contain backslashes as they're also used inside f-strings.
+const STRS: [&str; 13] = [
+    "''",
+    "'a'",
+    "'abc'",
+    "'Hello World'",
+    "'  padded  '",
+    "'123'",
+    "'1.5'",
+    "'a,b,,c'",
+    "'MiXeD cAsE'",
+    "'ünïcödé'",
+    "'ß'",
+    "'日本語'",
+    "chr(10)",
+];
+
+/// Integers big enough to need arbitrary precision.
+const BIG_INTS: [&str; 3] = ["18446744073709551616", "-9223372036854775809", "(10 ** 30)"];
+
+/// Generates random programs that assign a few variables and end with a tuple of them all.
+///
+/// Sequence repetition only uses literal counts, so no program can build a huge string or list.
+struct Generator {
+    rng: Rng,
+    /// Variables in scope, with the kind of value they hold.
+    vars: Vec<(String, Kind)>,
+    /// Functions defined so far, which all take two ints.
+    functions: Vec<String>,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            vars: Vec::new(),
+            functions: Vec::new(),
+        }
+    }
+
+    fn program(mut self) -> String {
+        let mut lines = Vec::new();
+        for index in 0..self.rng.range(2, 6) {
+            self.statement(&format!("v{index}"), &mut lines);
+        }
+        let names: Vec<&str> = self.vars.iter().map(|(name, _)| name.as_str()).collect();
+        lines.push(format!("({},)", names.join(", ")));
+        lines.join("\n")
+    }
+
+    /// Generates statements assigning a new variable `name`.
+    fn statement(&mut self, name: &str, lines: &mut Vec<String>) {
+        let mut kind = *self.rng.pick(&KINDS);
+        match self.rng.below(8) {
+            0 => {
+                let test = self.expr(Kind::Bool, MAX_DEPTH);
+                let body = self.expr(kind, MAX_DEPTH);
+                let orelse = self.expr(kind, MAX_DEPTH);
+                lines.extend([
+                    format!("if {test}:"),
+                    format!("    {name} = {body}"),
+                    "else:".to_owned(),
+                    format!("    {name} = {orelse}"),
+                ]);
+            }
+            1 => {
+                kind = Kind::Int;
+                let start = self.expr(Kind::Int, 2);
+                let iter = self.expr(Kind::List, 2);
+                self.vars.push(("x".to_owned(), Kind::Int));
+                let step = self.expr(Kind::Int, 2);
+                self.vars.pop();
+                let op = self.rng.pick(&["+", "-", "*", "^", "|"]);
+                lines.extend([
+                    format!("{name} = {start}"),
+                    format!("for x in {iter}:"),
+                    format!("    {name} {op}= {step}"),
+                ]);
+            }
+            2 => {
+                kind = Kind::Int;
+                let limit = self.rng.range(0, 20);
+                let step = self.rng.range(1, 4);
+                lines.extend([
+                    format!("{name} = 0"),
+                    format!("while {name} < {limit}:"),
+                    format!("    {name} += {step}"),
+                ]);
+            }
+            3 if self.functions.len() < 2 => {
+                kind = Kind::Int;
+                let function = format!("f{}", self.functions.len());
+                let module_vars = std::mem::replace(
+                    &mut self.vars,
+                    vec![("a".to_owned(), Kind::Int), ("b".to_owned(), Kind::Int)],
+                );
+                let body = self.expr(Kind::Int, MAX_DEPTH);
+                self.vars = module_vars;
+                let first = self.expr(Kind::Int, 2);
+                let second = self.expr(Kind::Int, 2);
+                lines.extend([
+                    format!("def {function}(a, b):"),
+                    format!("    return {body}"),
+                    format!("{name} = {function}({first}, {second})"),
+                ]);
+                self.functions.push(function);
+            }
+            4 if kind != Kind::Bool => {
+                let ops: &[&str] = match kind {
+                    Kind::Int => &["+", "-", "*", "//", "%", "&", "|", "^"],
+                    Kind::Float => &["+", "-", "*", "/"],
+                    _ => &["+"],
+                };
+                let op = self.rng.pick(ops);
+                let value = self.expr(kind, MAX_DEPTH);
+                let update = self.expr(kind, MAX_DEPTH);
+                lines.extend([format!("{name} = {value}"), format!("{name} {op}= {update}")]);
+            }
+            _ => {
+                let value = self.expr(kind, MAX_DEPTH);
+                lines.push(format!("{name} = {value}"));
+            }
+        }
+        self.vars.push((name.to_owned(), kind));
+    }
+
+    fn expr(&mut self, kind: Kind, depth: u32) -> String {
+        if depth == 0 || self.rng.chance(25) {
+            return self.atom(kind);
+        }
+        let depth = depth - 1;
+        // occasionally ignore the kind to exercise type errors and mixed-type operations
+        if self.rng.chance(5) {
+            return self.mixed();
+        }
+        match kind {
+            Kind::Int => self.int_expr(depth),
+            Kind::Float => self.float_expr(depth),
+            Kind::Str => self.str_expr(depth),
+            Kind::Bool => self.bool_expr(depth),
+            Kind::List => self.list_expr(depth),
+        }
+    }
+
+    fn int_expr(&mut self, depth: u32) -> String {
+        match self.rng.below(12) {
+            0 => self.binary(Kind::Int, &["+", "-", "*"], Kind::Int, depth),
+            1 => self.binary(Kind::Int, &["//", "%"], Kind::Int, depth),
+            2 => {
+                // a literal exponent keeps results a reasonable size
+                let base = self.expr(Kind::Int, depth);
+                let exponent = self.rng.range(-1, 6);
+                format!("({base} ** {exponent})")
+            }
+            3 => {
+                let value = self.expr(Kind::Int, depth);
+                let op = self.rng.pick(&["<<", ">>"]);
+                let shift = self.rng.range(0, 70);
+                format!("({value} {op} {shift})")
+            }
+            4 => self.binary(Kind::Int, &["&", "|", "^"], Kind::Int, depth),
+            5 => {
+                let op = self.rng.pick(&["-", "+", "~"]);
+                let operand = self.expr(Kind::Int, depth);
+                format!("({op}{operand})")
+            }
+            6 => {
+                let kind = *self.rng.pick(&[Kind::Str, Kind::List]);
+                self.call("len", &[kind], depth)
+            }
+            7 => match self.rng.below(8) {
+                0 => self.call("abs", &[Kind::Int], depth),
+                1 => self.call("min", &[Kind::Int, Kind::Int], depth),
+                2 => self.call("max", &[Kind::Int, Kind::Int, Kind::Int], depth),
+                3 => self.call("sum", &[Kind::List], depth),
+                4 => self.call("int", &[Kind::Float], depth),
+                5 => self.call("int", &[Kind::Str], depth),
+                6 => self.call("int", &[Kind::Bool], depth),
+                _ => self.call("round", &[Kind::Float], depth),
+            },
+            8 => {
+                let list = self.expr(Kind::List, depth);
+                let index = self.expr(Kind::Int, depth);
+                format!("{list}[{index}]")
+            }
+            9 => self.if_exp(Kind::Int, depth),
+            10 => {
+                let method = self.rng.pick(&["count", "find"]);
+                self.method(Kind::Str, method, &[Kind::Str], depth)
+            }
+            _ => {
+                if self.functions.is_empty() {
+                    return self.binary(Kind::Int, &["+", "-"], Kind::Int, depth);
+                }
+                let function = self.rng.pick(&self.functions).clone();
+                self.call(&function, &[Kind::Int, Kind::Int], depth)
+            }
+        }
+    }
+
+    fn float_expr(&mut self, depth: u32) -> String {
+        match self.rng.below(9) {
+            0 => self.binary(Kind::Float, &["+", "-", "*", "/"], Kind::Float, depth),
+            1 => self.binary(Kind::Int, &["/"], Kind::Int, depth),
+            2 => {
+                let right = *self.rng.pick(&[Kind::Float, Kind::Int]);
+                self.binary(Kind::Float, &["//", "%"], right, depth)
+            }
+            3 => self.call("float", &[Kind::Int], depth),
+            4 => match self.rng.below(3) {
+                0 => self.call("abs", &[Kind::Float], depth),
+                1 => self.call("min", &[Kind::Float, Kind::Int], depth),
+                _ => {
+                    let value = self.expr(Kind::Float, depth);
+                    let digits = self.rng.range(0, 3);
+                    format!("round({value}, {digits})")
+                }
+            },
+            5 => {
+                let base = self.expr(Kind::Float, depth);
+                let exponent = self.rng.range(-2, 4);
+                format!("({base} ** {exponent})")
+            }
+            6 => {
+                let operand = self.expr(Kind::Float, depth);
+                format!("(-{operand})")
+            }
+            7 => self.if_exp(Kind::Float, depth),
+            _ => self.call("float", &[Kind::Str], depth),
+        }
+    }
+
+    fn str_expr(&mut self, depth: u32) -> String {
+        match self.rng.below(11) {
+            0 => self.binary(Kind::Str, &["+"], Kind::Str, depth),
+            1 => {
+                let value = self.expr(Kind::Str, depth);
+                let count = self.rng.range(-1, 4);
+                format!("({value} * {count})")
+            }
+            2 => {
+                let function = self.rng.pick(&["str", "repr"]);
+                let kind = *self.rng.pick(&KINDS);
+                self.call(function, &[kind], depth)
+            }
+            3 => {
+                let value = self.expr(Kind::Str, depth);
+                let index = self.expr(Kind::Int, depth);
+                format!("{value}[{index}]")
+            }
+            4 => self.slice(Kind::Str, depth),
+            5 => {
+                let method = self.rng.pick(&[
+                    "upper",
+                    "lower",
+                    "strip",
+                    "lstrip",
+                    "rstrip",
+                    "title",
+                    "capitalize",
+                    "swapcase",
+                ]);
+                self.method(Kind::Str, method, &[], depth)
+            }
+            6 => {
+                if self.rng.chance(50) {
+                    return self.method(Kind::Str, "replace", &[Kind::Str, Kind::Str], depth);
+                }
+                let method = self.rng.pick(&["center", "ljust", "rjust", "zfill"]);
+                let value = self.expr(Kind::Str, depth);
+                let width = self.rng.range(0, 12);
+                format!("{value}.{method}({width})")
+            }
+            7 => self.fstring(),
+            8 => {
+                let separator = self.expr(Kind::Str, depth);
+                let first = self.expr(Kind::Str, depth);
+                let second = self.expr(Kind::Str, depth);
+                format!("{separator}.join([{first}, {second}])")
+            }
+            9 => self.if_exp(Kind::Str, depth),
+            _ => {
+                let code_point = self.rng.range(32, 1000);
+                format!("chr({code_point})")
+            }
+        }
+    }
+
+    fn bool_expr(&mut self, depth: u32) -> String {
+        match self.rng.below(9) {
+            0 => {
+                let kind = *self.rng.pick(&[Kind::Int, Kind::Float, Kind::Str, Kind::List]);
+                self.binary(kind, &["==", "!=", "<", "<=", ">", ">="], kind, depth)
+            }
+            1 => {
+                let first = self.expr(Kind::Int, depth);
+                let second = self.expr(Kind::Int, depth);
+                let third = self.expr(Kind::Float, depth);
+                format!("({first} < {second} <= {third})")
+            }
+            2 => self.binary(Kind::Bool, &["and", "or"], Kind::Bool, depth),
+            3 => {
+                let kind = *self.rng.pick(&KINDS);
+                let operand = self.expr(kind, depth);
+                format!("(not {operand})")
+            }
+            4 => {
+                if self.rng.chance(50) {
+                    self.binary(Kind::Str, &["in", "not in"], Kind::Str, depth)
+                } else {
+                    self.binary(Kind::Int, &["in", "not in"], Kind::List, depth)
+                }
+            }
+            5 => {
+                let kind = *self.rng.pick(&KINDS);
+                let value = self.expr(kind, depth);
+                let type_name = self.rng.pick(&["int", "float", "str", "bool", "list", "tuple"]);
+                format!("isinstance({value}, {type_name})")
+            }
+            6 => {
+                let kind = *self.rng.pick(&KINDS);
+                self.call("bool", &[kind], depth)
+            }
+            7 => {
+                if self.rng.chance(50) {
+                    let method = self.rng.pick(&["startswith", "endswith"]);
+                    return self.method(Kind::Str, method, &[Kind::Str], depth);
+                }
+                let method = self.rng.pick(&["isdigit", "isalpha", "isupper", "islower", "isspace"]);
+                self.method(Kind::Str, method, &[], depth)
+            }
+            _ => {
+                let kind = *self.rng.pick(&KINDS);
+                let value = self.expr(kind, depth);
+                format!("({value} is None)")
+            }
+        }
+    }
+
+    fn list_expr(&mut self, depth: u32) -> String {
+        match self.rng.below(7) {
+            0 => self.list_literal(depth),
+            1 => self.binary(Kind::List, &["+"], Kind::List, depth),
+            2 => {
+                let value = self.expr(Kind::List, depth);
+                let count = self.rng.range(-1, 3);
+                format!("({value} * {count})")
+            }
+            3 => {
+                let list = self.expr(Kind::List, depth);
+                match self.rng.below(4) {
+                    0 => format!("sorted({list})"),
+                    1 => format!("sorted({list}, reverse=True)"),
+                    2 => format!("list(reversed({list}))"),
+                    _ => format!("sorted(set({list}))"),
+                }
+            }
+            4 => self.slice(Kind::List, depth),
+            5 => {
+                let iter = self.expr(Kind::List, depth);
+                self.vars.push(("x".to_owned(), Kind::Int));
+                let elt = self.expr(Kind::Int, depth);
+                let condition = if self.rng.chance(50) {
+                    format!(" if {}", self.expr(Kind::Bool, depth))
+                } else {
+                    String::new()
+                };
+                self.vars.pop();
+                format!("[{elt} for x in {iter}{condition}]")
+            }
+            _ => {
+                let start = self.rng.range(-5, 10);
+                let stop = self.rng.range(-5, 15);
+                let step = self.rng.pick(&[1, 2, 3, -1, -2]);
+                format!("list(range({start}, {stop}, {step}))")
+            }
+        }
+    }
+
+    fn atom(&mut self, kind: Kind) -> String {
+        if self.rng.chance(50)
+            && let Some(var) = self.var(kind)
+        {
+            return var;
+        }
+        match kind {
+            Kind::Int if self.rng.chance(10) => (*self.rng.pick(&BIG_INTS)).to_owned(),
+            Kind::Int => self.rng.range(-10, 20).to_string(),
+            Kind::Float => (*self.rng.pick(&FLOATS)).to_owned(),
+            Kind::Str => (*self.rng.pick(&STRS)).to_owned(),
+            Kind::Bool => (*self.rng.pick(&["True", "False"])).to_owned(),
+            Kind::List => self.list_literal(0),
+        }
+    }
+
+    /// Returns a random variable holding `kind`, if there are any.
+    fn var(&mut self, kind: Kind) -> Option<String> {
+        let names: Vec<&String> = self
+            .vars
+            .iter()
+            .filter(|(_, var_kind)| *var_kind == kind)
+            .map(|(name, _)| name)
+            .collect();
+        if names.is_empty() {
+            None
+        } else {
+            Some(names[self.rng.below(names.len())].clone())
+        }
+    }
+
+    /// Applies a random operator to literals of random kinds.
+    ///
+    /// Only literals are used so a sequence can't be repeated by a huge int from a variable.
+    fn mixed(&mut self) -> String {
+        let left_kind = *self.rng.pick(&KINDS);
+        let right_kind = *self.rng.pick(&KINDS);
+        let left = self.literal(left_kind);
+        let right = self.literal(right_kind);
+        let op = self
+            .rng
+            .pick(&["+", "-", "*", "/", "//", "%", "<", "==", "in", "and", "or", "&"]);
+        format!("({left} {op} {right})")
+    }
+
+    fn literal(&mut self, kind: Kind) -> String {
+        match kind {
+            Kind::Int => self.rng.range(-10, 20).to_string(),
+            Kind::Float => (*self.rng.pick(&FLOATS)).to_owned(),
+            Kind::Str => (*self.rng.pick(&STRS)).to_owned(),
+            Kind::Bool => (*self.rng.pick(&["True", "False"])).to_owned(),
+            Kind::List => {
+                let items: Vec<String> = (0..self.rng.below(5))
+                    .map(|_| self.rng.range(-10, 20).to_string())
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+
+    fn list_literal(&mut self, depth: u32) -> String {
+        let items: Vec<String> = (0..self.rng.below(5)).map(|_| self.expr(Kind::Int, depth)).collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn binary(&mut self, left: Kind, ops: &[&str], right: Kind, depth: u32) -> String {
+        let op = self.rng.pick(ops);
+        let left = self.expr(left, depth);
+        let right = self.expr(right, depth);
+        format!("({left} {op} {right})")
+    }
+
+    fn call(&mut self, function: &str, args: &[Kind], depth: u32) -> String {
+        let args: Vec<String> = args.iter().map(|&kind| self.expr(kind, depth)).collect();
+        format!("{function}({})", args.join(", "))
+    }
+
+    fn method(&mut self, receiver: Kind, method: &str, args: &[Kind], depth: u32) -> String {
+        let receiver = self.expr(receiver, depth);
+        let args: Vec<String> = args.iter().map(|&kind| self.expr(kind, depth)).collect();
+        format!("{receiver}.{method}({})", args.join(", "))
+    }
+
+    fn if_exp(&mut self, kind: Kind, depth: u32) -> String {
+        let body = self.expr(kind, depth);
+        let test = self.expr(Kind::Bool, depth);
+        let orelse = self.expr(kind, depth);
+        format!("({body} if {test} else {orelse})")
+    }
+
+    fn slice(&mut self, kind: Kind, depth: u32) -> String {
+        let value = self.expr(kind, depth);
+        let bound = |rng: &mut Rng| {
+            if rng.chance(30) {
+                String::new()
+            } else {
+                rng.range(-4, 6).to_string()
+            }
+        };
+        let lower = bound(&mut self.rng);
+        let upper = bound(&mut self.rng);
+        if self.rng.chance(30) {
+            let step = self.rng.pick(&[-2, -1, 0, 1, 2, 3]);
+            format!("{value}[{lower}:{upper}:{step}]")
+        } else {
+            format!("{value}[{lower}:{upper}]")
+        }
+    }
+
+    /// Generates an f-string, interpolating only atoms since older CPython versions don't allow
+    /// nested quotes or backslashes in replacement fields.
+    fn fstring(&mut self) -> String {
+        let field = match self.rng.below(6) {
+            0 => self.atom(Kind::Str),
+            1 => format!("{}!r", self.atom(Kind::Str)),
+            2 => format!("{}:^9", self.atom(Kind::Str)),
+            3 => format!("{}:.3f", self.atom(Kind::Float)),
+            4 => {
+                let spec = self.rng.pick(&[",", "x", ">6", "+", "08b"]);
+                format!("{}:{spec}", self.atom(Kind::Int))
+            }
+            _ => {
+                let kind = *self.rng.pick(&KINDS);
+                self.atom(kind)
+            }
+        };
+        format!("f\"<{{{field}}}>\"")
+    }
+}
@@ -439,3 +439,25 @@ fn del_statement_returns_not_implemented_error() {
     let result = MontyRun::new("x = 1\ndel x".to_owned(), "test.py", vec![], vec![]);
     assert_eq!(get_exc_type(result), ExcType::NotImplementedError);
 }
+
+#[test]
+fn syntax_error_location() {
+    let exc = MontyRun::new("x = 1\ny = = 2".to_owned(), "test.py", vec![], vec![]).expect_err("expected parse error");
+    assert_eq!(exc.exc_type(), ExcType::SyntaxError);
+    let location = exc.location().expect("syntax error should have a location");
+    assert_eq!(location.filename, "test.py");
+    assert_eq!(location.start.line, 2);
+    assert_eq!(location.preview_line.as_deref(), Some("y = = 2"));
+}
+
+#[test]
+fn runtime_error_location_is_innermost_frame() {
+    let code = "def foo():\n    return 1 / 0\n\nfoo()";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let exc = ex.run_no_limits(vec![]).expect_err("expected runtime error");
+    let location = exc.location().expect("runtime error should have a location");
+    assert_eq!(location.frame_name.as_deref(), Some("foo"));
+    assert_eq!((location.start.line, location.start.column), (2, 12));
+    assert_eq!((location.end.line, location.end.column), (2, 17));
+    assert_eq!(location, exc.traceback().last().unwrap());
+}
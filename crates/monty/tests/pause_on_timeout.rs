@@ -0,0 +1,175 @@
+//! Tests for `ResourceLimits::pause_on_timeout`, which turns time limits into resumable pauses.
+
+use std::time::Duration;
+
+use monty::{
+    ExcType, LimitedTracker, MontyObject, MontyRepl, MontyRun, PrintWriter, ReplProgress, ResourceLimits, RunProgress,
+};
+
+/// Fake clock that advances one second every time it is read.
+fn ticking_clock() -> Duration {
+    static TICKS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    Duration::from_secs(TICKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+}
+
+/// A tracker that pauses after a few clock reads, i.e. every few dozen instructions.
+fn pausing_tracker() -> LimitedTracker {
+    let limits = ResourceLimits::new()
+        .max_duration(Duration::from_secs(5))
+        .pause_on_timeout(true);
+    LimitedTracker::new(limits).with_clock(ticking_clock)
+}
+
+const SUM_CODE: &str = "
+total = 0
+for i in range(2000):
+    total += i
+total
+";
+
+#[test]
+fn paused_run_resumes_to_completion() {
+    let runner = MontyRun::new(SUM_CODE.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut progress = runner
+        .start(vec![], pausing_tracker(), &mut PrintWriter::Disabled)
+        .unwrap();
+
+    let mut pauses = 0;
+    let result = loop {
+        match progress {
+            RunProgress::Paused(state) => {
+                pauses += 1;
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(1_999_000));
+    assert!(pauses > 1, "expected several time slices, got {pauses}");
+}
+
+#[test]
+fn paused_run_survives_dump_and_load() {
+    let runner = MontyRun::new(SUM_CODE.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut progress = runner
+        .start(vec![], pausing_tracker(), &mut PrintWriter::Disabled)
+        .unwrap();
+
+    let result = loop {
+        // round-trip every pause through bytes, as a host resuming in another process would
+        let bytes = progress.dump().unwrap();
+        progress = RunProgress::load(&bytes).unwrap();
+        match progress {
+            RunProgress::Paused(state) => progress = state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(1_999_000));
+}
+
+#[test]
+fn pause_preserves_external_calls_and_exceptions() {
+    let code = "
+results = []
+for i in range(300):
+    try:
+        if i % 100 == 0:
+            results.append(double(i))
+        else:
+            1 / 0
+    except ZeroDivisionError:
+        pass
+results
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["double".to_owned()]).unwrap();
+    let mut progress = runner
+        .start(vec![], pausing_tracker(), &mut PrintWriter::Disabled)
+        .unwrap();
+
+    let result = loop {
+        progress = match progress {
+            RunProgress::Paused(state) => state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::FunctionCall { args, state, .. } => {
+                let MontyObject::Int(i) = args[0] else {
+                    panic!("expected int argument, got {args:?}");
+                };
+                state.run(MontyObject::Int(i * 2), &mut PrintWriter::Disabled).unwrap()
+            }
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        };
+    };
+    assert_eq!(
+        result,
+        MontyObject::List(vec![MontyObject::Int(0), MontyObject::Int(200), MontyObject::Int(400)])
+    );
+}
+
+#[test]
+fn nested_calls_still_raise_timeout_error() {
+    // the key function runs in a nested VM loop which can't be suspended
+    let code = "sorted(range(2000), key=lambda x: -x)[0]";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut result = runner.start(vec![], pausing_tracker(), &mut PrintWriter::Disabled);
+    // the clock is shared with other tests, so execution may still pause before `sorted()` is called
+    while let Ok(RunProgress::Paused(state)) = result {
+        result = state.resume(&mut PrintWriter::Disabled);
+    }
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::TimeoutError);
+}
+
+#[test]
+fn standard_run_rejects_pausing() {
+    let runner = MontyRun::new(SUM_CODE.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let exc = runner
+        .run(vec![], pausing_tracker(), &mut PrintWriter::Disabled)
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::NotImplementedError);
+}
+
+/// Separate fake clock for the REPL test, whose setup code must not pause.
+fn repl_clock() -> Duration {
+    static TICKS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    Duration::from_secs(TICKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+}
+
+#[test]
+fn repl_snippet_resumes_after_pause() {
+    let limits = ResourceLimits::new()
+        .max_duration(Duration::from_secs(5))
+        .pause_on_timeout(true);
+    let tracker = LimitedTracker::new(limits).with_clock(repl_clock);
+    let (repl, _) = MontyRepl::new(
+        "base = 10".to_owned(),
+        "repl.py",
+        vec![],
+        vec![],
+        vec![],
+        tracker,
+        &mut PrintWriter::Disabled,
+    )
+    .unwrap();
+
+    let mut progress = repl.start(SUM_CODE, &mut PrintWriter::Disabled).unwrap();
+    let mut pauses = 0;
+    let (repl, result) = loop {
+        match progress {
+            ReplProgress::Paused(state) => {
+                pauses += 1;
+                progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+            }
+            ReplProgress::Complete { repl, value } => break (repl, value),
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(1_999_000));
+    assert!(pauses > 1, "expected several time slices, got {pauses}");
+
+    // globals from the paused snippet are kept in the session
+    let progress = repl.start("total + base", &mut PrintWriter::Disabled).unwrap();
+    let (_, value) = progress.into_complete().unwrap();
+    assert_eq!(value, MontyObject::Int(1_999_010));
+}
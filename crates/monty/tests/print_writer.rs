@@ -1,4 +1,9 @@
-use monty::{MontyRun, NoLimitTracker, PrintWriter};
+use std::borrow::Cow;
+
+use monty::{
+    ExcType, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintEvent, PrintEventWriter, PrintStream,
+    PrintWriter, PrintWriterCallback,
+};
 
 #[test]
 fn print_single_string() {
@@ -220,3 +225,168 @@ fn print_multiline_sep() {
     ex.run(vec![], NoLimitTracker, &mut writer).unwrap();
     assert_eq!(writer.collected_output().unwrap(), "1\n2\n3\n");
 }
+
+/// Print callback that fails every write, like a closed pipe.
+struct BrokenPipe;
+
+impl PrintWriterCallback for BrokenPipe {
+    fn stdout_write(&mut self, _output: Cow<'_, str>) -> Result<(), MontyException> {
+        Err(MontyException::new(ExcType::OSError, Some("Broken pipe".to_owned())))
+    }
+
+    fn stdout_push(&mut self, _end: char) -> Result<(), MontyException> {
+        Err(MontyException::new(ExcType::OSError, Some("Broken pipe".to_owned())))
+    }
+}
+
+#[test]
+fn print_write_error_raises() {
+    let ex = MontyRun::new("print('hello')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut cb = BrokenPipe;
+    let exc = ex
+        .run(vec![], NoLimitTracker, &mut PrintWriter::Callback(&mut cb))
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::OSError);
+    assert_eq!(exc.message(), Some("Broken pipe"));
+}
+
+#[test]
+fn print_write_error_catchable() {
+    let code = "
+try:
+    print('hello')
+    result = 'printed'
+except OSError as e:
+    result = str(e)
+result
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut cb = BrokenPipe;
+    let result = ex
+        .run(vec![], NoLimitTracker, &mut PrintWriter::Callback(&mut cb))
+        .unwrap();
+    assert_eq!(result, MontyObject::String("Broken pipe".to_owned()));
+}
+
+/// Print callback that records which stream each write went to.
+#[derive(Default)]
+struct StreamRecorder {
+    stdout: String,
+    stderr: String,
+}
+
+impl PrintWriterCallback for StreamRecorder {
+    fn stdout_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stdout.push_str(&output);
+        Ok(())
+    }
+
+    fn stdout_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stdout.push(end);
+        Ok(())
+    }
+
+    fn stderr_write(&mut self, output: Cow<'_, str>) -> Result<(), MontyException> {
+        self.stderr.push_str(&output);
+        Ok(())
+    }
+
+    fn stderr_push(&mut self, end: char) -> Result<(), MontyException> {
+        self.stderr.push(end);
+        Ok(())
+    }
+}
+
+#[test]
+fn print_file_stderr() {
+    let code = "
+import sys
+print('out')
+print('err', 1, file=sys.stderr)
+print('out', 2, file=sys.stdout)
+print('none', file=None)
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut cb = StreamRecorder::default();
+    ex.run(vec![], NoLimitTracker, &mut PrintWriter::Callback(&mut cb))
+        .unwrap();
+    assert_eq!(cb.stdout, "out\nout 2\nnone\n");
+    assert_eq!(cb.stderr, "err 1\n");
+}
+
+#[test]
+fn print_file_stderr_collect() {
+    let code = "import sys\nprint('a')\nprint('b', file=sys.stderr)";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut writer = PrintWriter::Collect(String::new());
+    ex.run(vec![], NoLimitTracker, &mut writer).unwrap();
+    assert_eq!(writer.collected_output().unwrap(), "a\nb\n");
+}
+
+#[test]
+fn run_capture_separates_streams() {
+    let code = "import sys\nprint('a', 1)\nprint('b', file=sys.stderr)\n2 + 3";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let (result, stdout, stderr) = ex.run_capture(vec![], NoLimitTracker);
+    assert_eq!(result.unwrap(), MontyObject::Int(5));
+    assert_eq!(stdout, "a 1\n");
+    assert_eq!(stderr, "b\n");
+}
+
+#[test]
+fn run_capture_keeps_output_on_error() {
+    let ex = MontyRun::new("print('before')\n1 / 0".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let (result, stdout, stderr) = ex.run_capture(vec![], NoLimitTracker);
+    assert_eq!(result.unwrap_err().exc_type(), ExcType::ZeroDivisionError);
+    assert_eq!(stdout, "before\n");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn print_file_unsupported() {
+    let ex = MontyRun::new("print('x', file=1)".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let exc = ex
+        .run(vec![], NoLimitTracker, &mut PrintWriter::Collect(String::new()))
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::TypeError);
+    assert_eq!(
+        exc.message(),
+        Some("print() 'file' argument must be sys.stdout or sys.stderr")
+    );
+}
+
+#[test]
+fn print_events_one_per_call() {
+    let code =
+        "import sys\nprint('a', 'b', sep='-')\nprint('err', file=sys.stderr, end='')\nfor i in range(2):\n    print(i)";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut events: Vec<PrintEvent> = Vec::new();
+    let mut writer = PrintEventWriter::new(|event| {
+        events.push(event);
+        Ok(())
+    });
+    ex.run(vec![], NoLimitTracker, &mut PrintWriter::Callback(&mut writer))
+        .unwrap();
+    drop(writer);
+
+    let summary: Vec<_> = events.iter().map(|e| (e.stream, e.text.as_str(), e.index)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (PrintStream::Stdout, "a-b\n", 0),
+            (PrintStream::Stderr, "err", 1),
+            (PrintStream::Stdout, "0\n", 2),
+            (PrintStream::Stdout, "1\n", 3),
+        ]
+    );
+}
+
+#[test]
+fn print_event_handler_error_raises() {
+    let ex = MontyRun::new("print('x')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let mut writer = PrintEventWriter::new(|_| Err(MontyException::new(ExcType::ValueError, Some("no".to_owned()))));
+    let exc = ex
+        .run(vec![], NoLimitTracker, &mut PrintWriter::Callback(&mut writer))
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::ValueError);
+}
@@ -0,0 +1,114 @@
+//! Tests for `ProfilingTracker` and `MontyRun::run_with_profile`.
+
+use monty::{
+    LimitedTracker, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ProfileReport, ProfilingTracker, ResourceLimits,
+};
+
+const CODE: &str = "
+def slow(n):
+    total = 0
+    for i in range(n):
+        total += i
+    return total
+
+def fast():
+    return [1, 2, 3]
+
+def both():
+    return slow(5000) + len(fast())
+
+both()
+";
+
+fn profile(code: &str) -> (MontyObject, ProfileReport) {
+    let runner = MontyRun::new(code.to_owned(), "profile.py", vec![], vec![]).unwrap();
+    let (result, report) = runner.run_with_profile(vec![], NoLimitTracker, &mut PrintWriter::Disabled);
+    (result.unwrap(), report)
+}
+
+#[test]
+fn functions_are_ranked_by_self_time() {
+    let (result, report) = profile(CODE);
+    assert_eq!(result, MontyObject::Int(12_497_503));
+    assert_eq!(report.functions[0].name, "slow", "{:#?}", report.functions);
+
+    let function = |name: &str| report.functions.iter().find(|f| f.name == name).unwrap();
+    // `total = 0`, `for` and `return`, plus the loop body 5000 times
+    assert_eq!(function("slow").statements, 5003);
+    assert_eq!(function("fast").statements, 1);
+    assert_eq!(function("both").statements, 1);
+    assert_eq!(function("<module>").statements, 4);
+    // time spent in callees counts towards the callers' total time only
+    assert!(function("both").total_time >= function("slow").self_time);
+    assert!(function("<module>").total_time >= function("both").total_time);
+    assert!(function("both").self_time <= function("both").total_time);
+}
+
+#[test]
+fn lines_are_attributed_to_their_function() {
+    let (_, report) = profile(CODE);
+    let line = |number: u16| report.lines.iter().find(|l| l.line == number).unwrap();
+    assert_eq!(line(5).hits, 5000);
+    assert_eq!(line(5).function_name, "slow");
+    assert_eq!(line(9).function_name, "fast");
+    assert!(line(9).allocations >= 1, "the list is allocated on line 9");
+    assert_eq!(report.lines.iter().map(|l| l.hits).sum::<u64>(), 5009);
+}
+
+#[test]
+fn folded_stacks_list_every_call_path() {
+    let (_, report) = profile(CODE);
+    let folded = report.folded_stacks();
+    let mut stacks: Vec<_> = folded
+        .lines()
+        .map(|line| {
+            let (stack, micros) = line.rsplit_once(' ').unwrap();
+            micros.parse::<u128>().expect("weights are whole microseconds");
+            stack
+        })
+        .collect();
+    stacks.sort_unstable();
+    assert_eq!(
+        stacks,
+        ["<module>", "<module>;both", "<module>;both;fast", "<module>;both;slow"]
+    );
+}
+
+#[test]
+fn recursion_counts_total_time_once() {
+    let code = "
+def fact(n):
+    if n <= 1:
+        return 1
+    return n * fact(n - 1)
+fact(20)
+";
+    let (result, report) = profile(code);
+    assert_eq!(result, MontyObject::Int(2_432_902_008_176_640_000));
+    let fact = report.functions.iter().find(|f| f.name == "fact").unwrap();
+    let module = report.functions.iter().find(|f| f.name == "<module>").unwrap();
+    assert!(fact.total_time <= module.total_time, "{fact:?} {module:?}");
+    assert_eq!(report.stacks.iter().map(|(stack, _)| stack.len()).max(), Some(21));
+}
+
+#[test]
+fn profile_is_returned_when_a_limit_is_hit() {
+    let runner = MontyRun::new(CODE.to_owned(), "profile.py", vec![], vec![]).unwrap();
+    let tracker = LimitedTracker::new(ResourceLimits::new().max_steps(2_000));
+    let (result, report) = runner.run_with_profile(vec![], tracker, &mut PrintWriter::Disabled);
+    let exc = result.unwrap_err();
+    assert!(exc.message().unwrap().starts_with("step limit exceeded"), "{exc:?}");
+    let slow = report.functions.iter().find(|f| f.name == "slow").unwrap();
+    assert!(slow.statements > 0 && slow.statements < 5003, "{slow:?}");
+}
+
+#[test]
+fn tracker_can_be_lent() {
+    let runner = MontyRun::new("x = [1]\ny = [x, x]".to_owned(), "profile.py", vec![], vec![]).unwrap();
+    let mut tracker = ProfilingTracker::new(NoLimitTracker);
+    runner.run(vec![], &mut tracker, &mut PrintWriter::Disabled).unwrap();
+    let report = tracker.report();
+    assert_eq!(report.functions.len(), 1);
+    assert_eq!(report.functions[0].statements, 2);
+    assert!(report.functions[0].allocations >= 2, "{report:?}");
+}
@@ -0,0 +1,260 @@
+//! Tests for host-supplied pure-Python modules passed via `MontyRun::new_with_modules`.
+
+use monty::{
+    CompileOptions, ExcType, MontyException, MontyObject, MontyRun, NativeModule, NoLimitTracker, PrintWriter,
+    PythonModule, RunProgress,
+};
+
+fn runner_with(code: &str, modules: Vec<PythonModule>) -> Result<MontyRun, MontyException> {
+    MontyRun::new_with_modules(
+        code.to_owned(),
+        "main.py",
+        vec![],
+        vec![],
+        vec![],
+        modules,
+        CompileOptions::default(),
+    )
+}
+
+fn run_with(code: &str, modules: Vec<PythonModule>) -> Result<MontyObject, MontyException> {
+    runner_with(code, modules)?.run_no_limits(vec![])
+}
+
+/// A module with a constant, a function reading it and a function raising an error.
+fn helpers_module() -> PythonModule {
+    PythonModule::new(
+        "helpers",
+        "\
+SCALE = 3
+
+def scale(x):
+    return x * SCALE
+
+def fail():
+    raise ValueError('boom')
+",
+    )
+}
+
+#[test]
+fn import_module_and_call_functions() {
+    let result = run_with(
+        "import helpers\n(helpers.scale(5), helpers.SCALE)",
+        vec![helpers_module()],
+    )
+    .unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![MontyObject::Int(15), MontyObject::Int(3)])
+    );
+}
+
+#[test]
+fn from_import_names() {
+    let result = run_with(
+        "from helpers import scale as s, SCALE\ns(SCALE)",
+        vec![helpers_module()],
+    )
+    .unwrap();
+    assert_eq!(result, MontyObject::Int(9));
+}
+
+#[test]
+fn module_globals_are_separate_from_main() {
+    let module = PythonModule::new(
+        "state",
+        "\
+name = 'module'
+count = 0
+
+def get_name():
+    return name
+
+def bump():
+    global count
+    count += 1
+    return count
+",
+    );
+    let code = "
+from state import get_name, bump
+name = 'main'
+count = 100
+bump()
+(name, get_name(), bump(), count)
+";
+    let result = run_with(code, vec![module]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::String("main".to_owned()),
+            MontyObject::String("module".to_owned()),
+            MontyObject::Int(2),
+            MontyObject::Int(100),
+        ])
+    );
+}
+
+#[test]
+fn module_body_runs_once() {
+    let module = PythonModule::new("once", "print('loading')\nitems = []\n");
+    let code = "
+import once
+once.items.append(1)
+
+def reimport():
+    import once as again
+    return again
+
+reimport() is once, once.items
+";
+    let runner = runner_with(code, vec![module]).unwrap();
+    let mut writer = PrintWriter::Collect(String::new());
+    let result = runner.run(vec![], NoLimitTracker, &mut writer).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::Bool(true),
+            MontyObject::List(vec![MontyObject::Int(1)])
+        ])
+    );
+    assert_eq!(writer.collected_output().unwrap(), "loading\n");
+}
+
+#[test]
+fn modules_import_each_other() {
+    let base = PythonModule::new("base", "def double(x):\n    return x * 2\n");
+    let derived = PythonModule::new(
+        "derived",
+        "from base import double\n\ndef quadruple(x):\n    return double(double(x))\n",
+    );
+    let result = run_with("import derived\nderived.quadruple(3)", vec![derived, base]).unwrap();
+    assert_eq!(result, MontyObject::Int(12));
+}
+
+#[test]
+fn circular_import_raises_import_error() {
+    let a = PythonModule::new("a", "import b\n");
+    let b = PythonModule::new("b", "import a\n");
+    let err = run_with("import a", vec![a, b]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ImportError);
+    assert_eq!(
+        err.message(),
+        Some("cannot import partially initialized module 'a' (most likely due to a circular import)")
+    );
+}
+
+#[test]
+fn exception_classes_resolve_bases_from_values() {
+    let errors = PythonModule::new("errors", "class Error(Exception):\n    pass\n");
+    let code = "
+import errors
+
+class Error(ValueError):
+    pass
+
+class Wrapped(errors.Error):
+    pass
+
+(repr(errors.Error), repr(Error), issubclass(Wrapped, errors.Error), issubclass(Wrapped, Error))
+";
+    let result = run_with(code, vec![errors]).unwrap();
+    assert_eq!(
+        result,
+        MontyObject::Tuple(vec![
+            MontyObject::String("<class 'errors.Error'>".to_owned()),
+            MontyObject::String("<class '__main__.Error'>".to_owned()),
+            MontyObject::Bool(true),
+            MontyObject::Bool(false),
+        ])
+    );
+}
+
+#[test]
+fn errors_in_modules_show_module_source() {
+    let err = run_with("import helpers\nhelpers.fail()", vec![helpers_module()]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ValueError);
+    let frames = err.traceback();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].filename, "main.py");
+    assert_eq!(frames[0].preview_line.as_deref(), Some("helpers.fail()"));
+    assert_eq!(frames[1].filename, "helpers.py");
+    assert_eq!(frames[1].frame_name.as_deref(), Some("fail"));
+    assert_eq!(frames[1].preview_line.as_deref(), Some("    raise ValueError('boom')"));
+}
+
+#[test]
+fn failed_import_runs_body_again() {
+    let module = PythonModule::new("flaky", "print('loading')\nraise RuntimeError('not yet')\n");
+    let code = "
+errors = []
+for _ in range(2):
+    try:
+        import flaky
+    except RuntimeError as e:
+        errors.append(str(e))
+errors
+";
+    let runner = runner_with(code, vec![module]).unwrap();
+    let mut writer = PrintWriter::Collect(String::new());
+    let result = runner.run(vec![], NoLimitTracker, &mut writer).unwrap();
+    let expected = MontyObject::String("not yet".to_owned());
+    assert_eq!(result, MontyObject::List(vec![expected.clone(), expected]));
+    assert_eq!(writer.collected_output().unwrap(), "loading\nloading\n");
+}
+
+#[test]
+fn syntax_errors_in_modules_are_reported() {
+    let err = runner_with("import broken", vec![PythonModule::new("broken", "def f(:\n")]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::SyntaxError);
+    assert_eq!(err.traceback()[0].filename, "broken.py");
+}
+
+#[test]
+fn duplicate_module_names_are_rejected() {
+    let err = runner_with("1", vec![PythonModule::new("dup", ""), PythonModule::new("dup", "")]).unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::RuntimeError);
+    assert_eq!(err.message(), Some("module 'dup' is registered more than once"));
+
+    let err = MontyRun::new_with_modules(
+        "1".to_owned(),
+        "main.py",
+        vec![],
+        vec![],
+        vec![NativeModule::new("dup")],
+        vec![PythonModule::new("dup", "")],
+        CompileOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.message(), Some("module 'dup' is registered more than once"));
+}
+
+#[test]
+fn external_calls_from_module_functions_survive_serialization() {
+    let module = PythonModule::new(
+        "calls",
+        "OFFSET = 1\n\ndef call_twice(f):\n    return f() + f() + OFFSET\n",
+    );
+    let runner = MontyRun::new_with_modules(
+        "import calls\ncalls.call_twice(fetch)".to_owned(),
+        "main.py",
+        vec![],
+        vec!["fetch".to_owned()],
+        vec![],
+        vec![module],
+        CompileOptions::default(),
+    )
+    .unwrap();
+    let runner = MontyRun::load(&runner.dump().unwrap()).unwrap();
+
+    let mut progress = runner.start(vec![], NoLimitTracker, &mut PrintWriter::Stdout).unwrap();
+    for value in [10, 20] {
+        let bytes = progress.dump().unwrap();
+        let loaded: RunProgress<NoLimitTracker> = RunProgress::load(&bytes).unwrap();
+        let (name, _, _, _, _, state) = loaded.into_function_call().expect("external call");
+        assert_eq!(name, "fetch");
+        progress = state.run(MontyObject::Int(value), &mut PrintWriter::Stdout).unwrap();
+    }
+    assert_eq!(progress.into_complete(), Some(MontyObject::Int(31)));
+}
@@ -4,8 +4,8 @@
 //! only the newly fed snippet each time.
 
 use monty::{
-    ExternalResult, MontyObject, MontyRepl, NoLimitTracker, PrintWriter, ReplContinuationMode, ReplProgress,
-    detect_repl_continuation_mode,
+    ExcType, ExternalResult, LimitedTracker, MontyObject, MontyRepl, NoLimitTracker, PrintWriter, ReplContinuationMode,
+    ReplProgress, ResourceLimits, detect_repl_continuation_mode,
 };
 
 fn init_repl(code: &str, external_functions: Vec<String>) -> (MontyRepl<NoLimitTracker>, MontyObject) {
@@ -278,3 +278,34 @@ fn repl_dataclass_method_call_yields_function_call_with_method_flag() {
     // Verify REPL state is preserved after method call
     assert_eq!(repl.feed_no_print("1 + 1").unwrap(), MontyObject::Int(2));
 }
+
+#[test]
+fn repl_reset_budget_applies_step_limit_per_snippet() {
+    let limits = ResourceLimits::new().max_steps(500);
+    let (mut repl, _) = MontyRepl::new(
+        "total = 0".to_owned(),
+        "repl.py",
+        vec![],
+        vec![],
+        vec![],
+        LimitedTracker::new(limits),
+        &mut PrintWriter::Stdout,
+    )
+    .unwrap();
+
+    // each snippet fits in the budget on its own, but not all of them together
+    for _ in 0..5 {
+        repl.tracker_mut().reset_budget();
+        repl.feed_no_print("for i in range(20):\n    total += i").unwrap();
+    }
+    assert_eq!(repl.feed_no_print("total").unwrap(), MontyObject::Int(950));
+
+    repl.tracker_mut().reset_budget();
+    let err = repl.feed_no_print("while True:\n    total += 1").unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TimeoutError);
+
+    // state survives a snippet that hit the limit
+    repl.tracker_mut().reset_budget();
+    let output = repl.feed_no_print("total > 950").unwrap();
+    assert_eq!(output, MontyObject::Bool(true));
+}
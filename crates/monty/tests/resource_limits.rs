@@ -4,7 +4,10 @@
 /// allocation limits, time limits, and triggers garbage collection.
 use std::time::{Duration, Instant};
 
-use monty::{ExcType, LimitedTracker, MontyObject, MontyRun, PrintWriter, ResourceLimits};
+use monty::{
+    CancelToken, ExcType, LimitExceeded, LimitedTracker, MontyException, MontyObject, MontyRun, PrintWriter,
+    ResourceLimits, RunProgress,
+};
 
 /// Test that GC properly collects dict cycles via the has_refs() check in allocate().
 ///
@@ -24,9 +27,9 @@ fn gc_collects_dict_cycles_via_has_refs() {
     //   Dict setitem does NOT call mark_potential_cycle()
     // - On next iteration, both dicts are reassigned, making the cycle unreachable
     //
-    // GC runs every 100,000 allocations. With 200,001 iterations:
-    // - GC runs at 100k (collects cycles 0-49,999 approximately)
-    // - GC runs at 200k (collects more cycles)
+    // GC runs every 10,000 allocations, mostly as young collections which see each
+    // cycle while it is still young. With 200,001 iterations, every completed batch
+    // of cycles is collected.
     // After GC runs, only the final cycle should remain.
     let code = r"
 # Create many dict cycles
@@ -43,7 +46,7 @@ result
 
     let output = ex.run_ref_counts(vec![]).expect("should succeed");
 
-    // GC_INTERVAL is 100,000. With 200,001 iterations creating dict cycles,
+    // GC_INTERVAL is 10,000. With 200,001 iterations creating dict cycles,
     // GC must have run at least once, resetting allocations_since_gc.
     // If may_have_cycles was never set (has_refs() disabled), GC never runs
     // and allocations_since_gc would be ~400k (2 dicts per iteration).
@@ -76,9 +79,8 @@ fn gc_collects_list_cycles() {
     //   This calls mark_potential_cycle() and sets may_have_cycles = true
     // - On next iteration, `a` is reassigned, making the cycle unreachable
     //
-    // GC runs every 100,000 allocations. With 200,001 iterations:
-    // - GC runs at 100k (collects cycles 0-99,999)
-    // - GC runs at 200k (collects cycles 100k-199,999)
+    // GC runs every 10,000 allocations, mostly as young collections. With 200,001
+    // iterations, every completed batch of cycles is collected.
     // After GC runs, only the final cycle should remain.
     let code = r"
 # Create many self-referencing list cycles
@@ -94,7 +96,7 @@ len(result)
 
     let output = ex.run_ref_counts(vec![]).expect("should succeed");
 
-    // GC_INTERVAL is 100,000. With 200,001 iterations creating list cycles,
+    // GC_INTERVAL is 10,000. With 200,001 iterations creating list cycles,
     // GC must have run at least twice, resetting allocations_since_gc.
     assert!(
         output.allocations_since_gc < 100_000,
@@ -196,6 +198,154 @@ x
     );
 }
 
+/// Fake clock that advances one second every time it is read.
+fn ticking_clock() -> Duration {
+    static TICKS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    Duration::from_secs(TICKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+}
+
+/// Test that time limits use an injected clock instead of `Instant`.
+#[test]
+fn injected_clock_drives_time_limit() {
+    let code = r"
+x = 0
+for i in range(100000000):
+    x = x + 1
+x
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    // an hour-long limit, but every clock read advances the fake clock by a second
+    let limits = ResourceLimits::new().max_duration(Duration::from_secs(3600));
+    let tracker = LimitedTracker::new(limits).with_clock(ticking_clock);
+    let started = Instant::now();
+    let exc = ex.run(vec![], tracker, &mut PrintWriter::Stdout).unwrap_err();
+
+    assert_eq!(exc.exc_type(), ExcType::TimeoutError);
+    assert!(
+        started.elapsed() < Duration::from_secs(60),
+        "limit should be reached by fake clock, not real time"
+    );
+}
+
+/// Test that a host thread can cancel a run that has no time limit.
+#[test]
+fn cancel_token_aborts_run_from_another_thread() {
+    let code = r"
+x = 0
+try:
+    while True:
+        x = x + 1
+except BaseException:
+    x = -1
+x
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let token = CancelToken::new();
+    let host_token = token.clone();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        host_token.cancel();
+    });
+
+    let start = Instant::now();
+    let result = ex.run_with_cancel(
+        vec![],
+        LimitedTracker::new(ResourceLimits::new()),
+        &mut PrintWriter::Stdout,
+        token.clone(),
+    );
+    canceller.join().unwrap();
+
+    let exc = result.expect_err("run should be cancelled");
+    assert_eq!(
+        exc.exc_type(),
+        ExcType::KeyboardInterrupt,
+        "cancellation is not catchable"
+    );
+    assert_eq!(exc.message(), Some("execution cancelled"));
+    assert!(token.is_cancelled());
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "cancellation should be prompt"
+    );
+}
+
+/// Test that `max_steps` stops a loop after a deterministic amount of work.
+#[test]
+fn step_limit_exceeded() {
+    let code = r"
+x = 0
+while True:
+    x = x + 1
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_steps(10_000);
+    let first = ex
+        .run(vec![], LimitedTracker::new(limits.clone()), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(first.exc_type(), ExcType::TimeoutError);
+    assert_eq!(first.message(), Some("step limit exceeded: 10001 > 10000"));
+
+    let second = ex
+        .run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(
+        first.traceback(),
+        second.traceback(),
+        "step limit should stop at the same place every run"
+    );
+}
+
+/// Test that exceptions caused by a resource limit report which limit was exceeded.
+#[test]
+fn limit_exceeded_reported_on_exception() {
+    let code = r"
+x = 0
+while True:
+    x = x + 1
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let limits = ResourceLimits::new().max_steps(100);
+    let err = ex
+        .run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(
+        err.limit_exceeded(),
+        Some(LimitExceeded::Steps { limit: 100, count: 101 })
+    );
+
+    // the same exception type raised by user code is not a limit
+    let ex = MontyRun::new("raise TimeoutError('slow')".to_owned(), "test.py", vec![], vec![]).unwrap();
+    let err = ex
+        .run(
+            vec![],
+            LimitedTracker::new(ResourceLimits::new()),
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TimeoutError);
+    assert_eq!(err.limit_exceeded(), None);
+}
+
+/// Test that builtin loops count towards `max_steps`, not just bytecode instructions.
+#[test]
+fn step_limit_counts_builtin_iteration() {
+    let ex = MontyRun::new("sum(range(1_000_000))".to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_steps(1000);
+    let exc = ex
+        .run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::TimeoutError);
+
+    let limits = ResourceLimits::new().max_steps(10_000_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout);
+    assert_eq!(result.unwrap(), MontyObject::Int(499_999_500_000));
+}
+
 #[test]
 fn time_limit_not_exceeded() {
     // Simple code that runs quickly
@@ -289,6 +439,86 @@ len(result)
     assert!(result.is_ok(), "should succeed with GC enabled");
 }
 
+/// Runs code using the `random` module with the given seed.
+fn run_seeded(code: &str, seed: u64) -> MontyObject {
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let limits = ResourceLimits::new().random_seed(seed);
+    ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+        .unwrap()
+}
+
+#[test]
+fn random_seed_makes_runs_reproducible() {
+    let code = "import random
+items = list(range(10))
+random.shuffle(items)
+(random.random(), random.randint(0, 10**9), items)";
+    assert_eq!(run_seeded(code, 7), run_seeded(code, 7));
+    assert_ne!(run_seeded(code, 7), run_seeded(code, 8));
+}
+
+#[test]
+fn random_state_survives_snapshot() {
+    // The generator state is part of the heap, so a snapshot resumed after an external call
+    // continues the same sequence as an uninterrupted run.
+    let code = "import random
+a = random.random()
+foo()
+(a, random.random())";
+    let run = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["foo".to_owned()]).unwrap();
+    let limits = ResourceLimits::new().random_seed(42);
+
+    let start = || {
+        run.clone()
+            .start(vec![], LimitedTracker::new(limits.clone()), &mut PrintWriter::Stdout)
+            .unwrap()
+    };
+
+    let (.., state) = start().into_function_call().expect("function call");
+    let direct = state.run(MontyObject::None, &mut PrintWriter::Stdout).unwrap();
+
+    let bytes = start().dump().unwrap();
+    let loaded: RunProgress<LimitedTracker> = RunProgress::load(&bytes).unwrap();
+    let (.., state) = loaded.into_function_call().expect("function call");
+    let resumed = state.run(MontyObject::None, &mut PrintWriter::Stdout).unwrap();
+
+    assert_eq!(
+        direct.into_complete().expect("complete"),
+        resumed.into_complete().expect("complete")
+    );
+}
+
+/// Runs code defining an `lru_cache`-decorated function with the given decorator and
+/// calling it with many distinct arguments.
+fn run_cached_calls(decorator: &str) -> Result<MontyObject, MontyException> {
+    let code = format!(
+        "from functools import lru_cache
+{decorator}
+def square(n):
+    return n * n
+for i in range(20000):
+    square(i)
+square(3)"
+    );
+    let ex = MontyRun::new(code, "test.py", vec![], vec![]).unwrap();
+    let limits = ResourceLimits::new().max_memory(200_000);
+    ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout)
+}
+
+#[test]
+#[cfg_attr(
+    feature = "ref-count-panic",
+    ignore = "resource exhaustion doesn't guarantee heap state consistency"
+)]
+fn lru_cache_entries_count_towards_memory_limit() {
+    let exc = run_cached_calls("@lru_cache(maxsize=None)").unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+
+    // Evicted entries are released again, so a bounded cache stays within the limit
+    let result = run_cached_calls("@lru_cache(maxsize=100)").unwrap();
+    assert_eq!(result, MontyObject::Int(9));
+}
+
 #[test]
 #[cfg_attr(
     feature = "ref-count-panic",
@@ -495,6 +725,29 @@ recurse(5)
     assert!(result.is_ok(), "should not exceed recursion depth limit");
 }
 
+/// Test that deeply nested `zip()`/`enumerate()` iterators are rejected.
+///
+/// Advancing a composite iterator recurses into its inner iterators, so the nesting
+/// depth is capped when the iterator is created rather than overflowing the Rust stack.
+#[test]
+#[cfg_attr(
+    feature = "ref-count-panic",
+    ignore = "resource exhaustion doesn't guarantee heap state consistency"
+)]
+fn nested_iterator_depth_limit_exceeded() {
+    let code = r"
+it = iter([1])
+for i in range(10000):
+    it = zip(enumerate(it))
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+    let result = ex.run_no_limits(vec![]);
+
+    assert!(result.is_err(), "should exceed iterator nesting limit");
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::RecursionError);
+}
+
 // === BigInt large result pre-check tests ===
 // These tests verify that operations that would produce very large BigInt results
 // are rejected before the computation begins, preventing DoS attacks.
@@ -894,6 +1147,39 @@ fn string_mult_rejected_before_allocation() {
     );
 }
 
+/// Test that a huge `%` width or precision is rejected before the padded result is built.
+#[test]
+fn percent_format_width_rejected_before_allocation() {
+    for code in ["'%*d' % (200000, 1)", "'%.200000f' % 1.5", "'%.200000d' % 1"] {
+        let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+        let limits = ResourceLimits::new().max_memory(100_000);
+        let result = ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout);
+
+        let exc = result.expect_err(code);
+        assert_eq!(exc.exc_type(), ExcType::MemoryError, "{code}");
+    }
+}
+
+/// Test that a huge format spec width or float precision is rejected before the result is built.
+#[test]
+fn format_spec_width_rejected_before_allocation() {
+    for code in [
+        "format(1, '200000')",
+        "'{:>200000}'.format('x')",
+        "f'{1.5:.200000f}'",
+        "w = 200000\nf'{1:{w}}'",
+    ] {
+        let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+        let limits = ResourceLimits::new().max_memory(100_000);
+        let result = ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout);
+
+        let exc = result.expect_err(code);
+        assert_eq!(exc.exc_type(), ExcType::MemoryError, "{code}");
+    }
+}
+
 /// Test that large list multiplication is rejected before allocation.
 #[test]
 fn list_mult_memory_limit() {
@@ -1027,6 +1313,25 @@ fn longint_times_string_memory_limit() {
     );
 }
 
+/// Test that `re.findall` checks the memory limit while collecting matches, before
+/// allocating the result list.
+#[test]
+fn re_findall_memory_limit() {
+    let code = "import re\nre.findall('a', 'a' * 200_000)";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let limits = ResourceLimits::new().max_memory(1_000_000);
+    let result = ex.run(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout);
+
+    assert!(result.is_err(), "findall with many matches should be rejected");
+    let exc = result.unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::MemoryError);
+    assert!(
+        exc.message().is_some_and(|m| m.contains("memory limit exceeded")),
+        "expected memory limit error, got: {exc}"
+    );
+}
+
 /// Test that small tuple multiplication works within limits.
 #[test]
 fn tuple_mult_within_limit() {
@@ -1369,3 +1674,162 @@ repr(x)
 ";
     assert_repr_timeout(code, "set repr");
 }
+
+/// Test that slots freed inside a loop are reused, so the heap stays proportional to
+/// live data, and that the tracker sees the heap's slot usage.
+#[test]
+fn heap_slots_reused_in_allocating_loop() {
+    let code = r"
+for i in range(10_000):
+    x = [i, i + 1]
+    y = str(i) * 20
+interrupt()
+";
+    let run = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["interrupt".to_owned()]).unwrap();
+    let (_, _, _, _, _, mut state) = run
+        .start(
+            vec![],
+            LimitedTracker::new(ResourceLimits::new()),
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap()
+        .into_function_call()
+        .expect("interrupt call");
+
+    let stats = state.tracker_mut().heap_slots();
+    assert!(stats.total_slots > 0, "tracker should have seen allocations");
+    assert!(
+        stats.total_slots < 100,
+        "freed slots should be reused, heap grew to {} slots",
+        stats.total_slots
+    );
+    assert!(stats.live_slots() <= stats.total_slots);
+    assert!((0.0..=1.0).contains(&stats.fragmentation()));
+}
+
+/// Test that young collections free cycles without disturbing live values they referenced,
+/// and that GC counts and pauses reach the tracker.
+#[test]
+fn gc_young_collections_reported_to_tracker() {
+    let code = r"
+shared = ['shared']
+keep = []
+for i in range(60_000):
+    a = [shared]
+    a.append(a)
+    if i % 10_000 == 0:
+        keep.append({'i': i, 'shared': shared})
+interrupt()
+[len(shared), shared[0], len(keep), keep[-1]['i'], keep[-1]['shared'] is shared]
+";
+    let run = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["interrupt".to_owned()]).unwrap();
+    let (_, _, _, _, _, mut state) = run
+        .start(
+            vec![],
+            LimitedTracker::new(ResourceLimits::new()),
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap()
+        .into_function_call()
+        .expect("interrupt call");
+
+    let totals = state.tracker_mut().gc_totals();
+    assert!(totals.young_collections >= 5, "expected young collections: {totals:?}");
+    assert!(totals.freed > 50_000, "cycles should have been freed: {totals:?}");
+    assert!(totals.max_pause <= totals.total_pause);
+
+    let result = state
+        .run(MontyObject::None, &mut PrintWriter::Stdout)
+        .unwrap()
+        .into_complete()
+        .expect("complete");
+    assert_eq!(
+        result,
+        MontyObject::List(vec![
+            MontyObject::Int(1),
+            MontyObject::String("shared".to_owned()),
+            MontyObject::Int(6),
+            MontyObject::Int(50_000),
+            MontyObject::Bool(true),
+        ])
+    );
+}
+
+/// Test that `run_with_metrics` reports usage for successful and failed runs.
+#[test]
+fn run_with_metrics_reports_usage() {
+    let code = r"
+rows = []
+for i in range(100):
+    rows.append([i, str(i) * 10])
+len(rows)
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let (result, metrics) = ex.run_with_metrics(
+        vec![],
+        LimitedTracker::new(ResourceLimits::new()),
+        &mut PrintWriter::Stdout,
+    );
+    assert_eq!(result.unwrap(), MontyObject::Int(100));
+    assert!(metrics.allocations >= 200, "each row allocates a list and a string");
+    assert!(metrics.peak_memory >= metrics.current_memory);
+    assert!(metrics.peak_memory > 100 * 20, "all rows are alive at the end");
+    assert!(metrics.steps > 100, "every loop iteration takes steps");
+
+    let limits = ResourceLimits::new().max_allocations(50);
+    let (result, metrics) = ex.run_with_metrics(vec![], LimitedTracker::new(limits), &mut PrintWriter::Stdout);
+    assert_eq!(result.unwrap_err().exc_type(), ExcType::MemoryError);
+    assert_eq!(
+        metrics.allocations, 50,
+        "metrics only count allocations that were allowed"
+    );
+}
+
+/// Test that `+=` on an unaliased string appends in place rather than allocating a new string
+/// each iteration, including when the string is measured as it grows.
+#[test]
+fn string_iadd_appends_in_place() {
+    let code = r"
+v = ''
+for i in range(10_000):
+    v += 'é'
+    assert len(v) == i + 1
+len(v)
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let (result, metrics) = ex.run_with_metrics(
+        vec![],
+        LimitedTracker::new(ResourceLimits::new()),
+        &mut PrintWriter::Stdout,
+    );
+    assert_eq!(result.unwrap(), MontyObject::Int(10_000));
+    assert!(
+        metrics.allocations < 20,
+        "expected a handful of allocations, got {}",
+        metrics.allocations
+    );
+}
+
+/// Test that `list(range(n))` reserves exactly `n` slots and that the tracker is charged
+/// for the slots reserved, so memory grows linearly with `n`.
+#[test]
+fn list_from_range_reserves_exact_capacity() {
+    let peak_memory = |n: i64| {
+        let code = format!("x = list(range({n}))\nlen(x)");
+        let ex = MontyRun::new(code, "test.py", vec![], vec![]).unwrap();
+        let (result, metrics) = ex.run_with_metrics(
+            vec![],
+            LimitedTracker::new(ResourceLimits::new()),
+            &mut PrintWriter::Stdout,
+        );
+        assert_eq!(result.unwrap(), MontyObject::Int(n));
+        metrics.peak_memory
+    };
+
+    let (small, medium, large) = (peak_memory(1000), peak_memory(1100), peak_memory(1200));
+    // Growing the list by doubling would jump from 1024 to 2048 slots between the first two
+    assert!(medium > small, "more items should use more memory");
+    assert_eq!(medium - small, large - medium);
+}
@@ -0,0 +1,198 @@
+//! Tests for checking the result of a run against a `ResultSchema`.
+
+use monty::{ExcType, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResultSchema, RunProgress, SchemaMismatch};
+use serde_json::json;
+
+fn schema(expr: &str) -> ResultSchema {
+    expr.parse().unwrap()
+}
+
+fn run(code: &str, expr: &str) -> Result<MontyObject, SchemaMismatch> {
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .with_result_schema(schema(expr));
+    runner.run_no_limits(vec![]).map_err(|err| {
+        assert_eq!(err.exc_type(), ExcType::TypeError);
+        err.schema_mismatch()
+            .expect("error should be a schema mismatch")
+            .clone()
+    })
+}
+
+fn mismatch(path: &str, expected: &str, actual: &str) -> SchemaMismatch {
+    SchemaMismatch {
+        path: path.to_owned(),
+        expected: expected.to_owned(),
+        actual: actual.to_owned(),
+    }
+}
+
+#[test]
+fn matching_result_is_returned() {
+    let result = run("['a', 'b']", "list[str]").unwrap();
+    assert_eq!(
+        result,
+        MontyObject::List(vec![
+            MontyObject::String("a".to_owned()),
+            MontyObject::String("b".to_owned())
+        ])
+    );
+}
+
+#[test]
+fn scalar_types() {
+    assert!(run("1", "int").is_ok());
+    assert!(run("2 ** 100", "int").is_ok());
+    assert!(run("1", "float").is_ok());
+    assert!(run("1.5", "float").is_ok());
+    assert!(run("None", "None").is_ok());
+    assert!(run("b'x'", "bytes").is_ok());
+    assert!(run("[1, None]", "Any").is_ok());
+    assert_eq!(run("True", "int"), Err(mismatch("result", "int", "bool")));
+    assert_eq!(run("1.5", "int"), Err(mismatch("result", "int", "float")));
+    assert_eq!(run("'1'", "int"), Err(mismatch("result", "int", "str")));
+}
+
+#[test]
+fn nested_mismatch_has_path() {
+    assert_eq!(run("['a', 1]", "list[str]"), Err(mismatch("result[1]", "str", "int")));
+    assert_eq!(
+        run(
+            "{'users': [{'name': 'a'}, {'name': None}]}",
+            "dict[str, list[dict[str, str]]]"
+        ),
+        Err(mismatch("result['users'][1]['name']", "str", "NoneType"))
+    );
+    assert_eq!(
+        run("{1: 'a'}", "dict[str, str]"),
+        Err(mismatch("result key 1", "str", "int"))
+    );
+}
+
+#[test]
+fn tuples() {
+    assert!(run("(1, 'a')", "tuple[int, str]").is_ok());
+    assert!(run("(1, 2, 3)", "tuple[int, ...]").is_ok());
+    assert!(run("()", "tuple[()]").is_ok());
+    assert_eq!(
+        run("(1, 2, 3)", "tuple[int, int]"),
+        Err(mismatch("result", "tuple[int, int]", "tuple of length 3"))
+    );
+    assert_eq!(
+        run("[1, 2]", "tuple[int, ...]"),
+        Err(mismatch("result", "tuple[int, ...]", "list"))
+    );
+}
+
+#[test]
+fn unions() {
+    assert!(run("None", "int | None").is_ok());
+    assert!(run("1", "Optional[int]").is_ok());
+    assert!(run("'a'", "Union[int, str]").is_ok());
+    assert_eq!(
+        run("[1.5]", "list[int | None]"),
+        Err(mismatch("result[0]", "int | None", "float"))
+    );
+}
+
+#[test]
+fn objects() {
+    let expr = "{'name': str, 'age': NotRequired[int]}";
+    assert!(run("{'name': 'a', 'age': 3, 'extra': []}", expr).is_ok());
+    assert!(run("{'name': 'a'}", expr).is_ok());
+    assert_eq!(
+        run("{'age': 3}", expr),
+        Err(mismatch("result['name']", "str", "missing"))
+    );
+    assert_eq!(
+        run("{'name': 'a', 'age': '3'}", expr),
+        Err(mismatch("result['age']", "int", "str"))
+    );
+    assert_eq!(run("['a']", expr), Err(mismatch("result", expr, "list")));
+}
+
+#[test]
+fn type_expressions_round_trip() {
+    for expr in [
+        "Any",
+        "list[str]",
+        "dict[str, list[int | None]]",
+        "tuple[int, str]",
+        "tuple[float, ...]",
+        "tuple[()]",
+        "set[bytes]",
+        "{'name': str, 'tags': NotRequired[list[str]]}",
+    ] {
+        assert_eq!(schema(expr).to_string(), expr);
+    }
+    assert_eq!(schema("typing.List[Optional[int]]").to_string(), "list[int | None]");
+    assert_eq!(schema("dict").to_string(), "dict[Any, Any]");
+}
+
+#[test]
+fn invalid_type_expressions() {
+    for (expr, message) in [
+        ("", "expected a type, got end of input"),
+        ("list[", "expected a type, got end of input"),
+        ("list[int", "expected ',', got end of input"),
+        ("dict[str]", "'dict' takes 2 parameters"),
+        ("int[str]", "'int' doesn't take parameters"),
+        ("Foo", "unsupported type 'Foo'"),
+        ("int str", "expected end of input, got 'str'"),
+        ("{name: str}", "expected a quoted key, got 'name'"),
+    ] {
+        let err = expr.parse::<ResultSchema>().unwrap_err();
+        assert_eq!(err.message, message, "{expr}");
+    }
+}
+
+#[test]
+fn json_schema() {
+    let from_json = ResultSchema::from_json_schema(&json!({
+        "type": "object",
+        "title": "User",
+        "properties": {
+            "name": {"type": "string"},
+            "scores": {"type": "array", "items": {"type": ["integer", "null"]}},
+            "meta": {"type": "object", "additionalProperties": {"anyOf": [{"type": "number"}, {"type": "boolean"}]}},
+        },
+        "required": ["name", "scores"],
+    }))
+    .unwrap();
+    assert_eq!(
+        from_json.to_string(),
+        "{'meta': NotRequired[dict[str, float | bool]], 'name': str, 'scores': list[int | None]}"
+    );
+
+    let err = ResultSchema::from_json_schema(&json!({"type": "integer", "minimum": 0})).unwrap_err();
+    assert_eq!(err.message, "unsupported JSON schema keyword \"minimum\"");
+}
+
+#[test]
+fn checked_when_started() {
+    let runner = MontyRun::new("fetch()".to_owned(), "test.py", vec![], vec!["fetch".to_owned()])
+        .unwrap()
+        .with_result_schema(schema("list[int]"));
+    let progress = runner.start(vec![], NoLimitTracker, &mut PrintWriter::Stdout).unwrap();
+    let RunProgress::FunctionCall { state, .. } = progress else {
+        panic!("expected a function call")
+    };
+    let err = state
+        .run(MontyObject::String("x".to_owned()), &mut PrintWriter::Stdout)
+        .unwrap_err();
+    assert_eq!(err.schema_mismatch(), Some(&mismatch("result", "list[int]", "str")));
+    assert_eq!(
+        err.message(),
+        Some("result doesn't match the expected type, result: expected list[int], got str")
+    );
+}
+
+#[test]
+fn schema_survives_dump_and_load() {
+    let runner = MontyRun::new("'a'".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .with_result_schema(schema("int"));
+    let loaded = MontyRun::load(&runner.dump().unwrap()).unwrap();
+    let err = loaded.run_no_limits(vec![]).unwrap_err();
+    assert_eq!(err.schema_mismatch(), Some(&mismatch("result", "int", "str")));
+}
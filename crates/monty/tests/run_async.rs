@@ -0,0 +1,87 @@
+//! Tests for `MontyRun::run_async`, which drives execution with an async external function handler.
+#![cfg(feature = "async")]
+
+use std::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use monty::{ExcType, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter};
+
+/// Minimal executor for futures that never actually wait on anything.
+fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn run_async_resolves_external_calls() {
+    let runner = MontyRun::new(
+        "add(1, 2) + add(x=10, y=20)".to_owned(),
+        "test.py",
+        vec![],
+        vec!["add".to_owned()],
+    )
+    .unwrap();
+
+    let result = block_on(runner.run_async(
+        vec![],
+        NoLimitTracker,
+        &mut PrintWriter::Disabled,
+        async |name: &str, args: Vec<MontyObject>, kwargs: Vec<(MontyObject, MontyObject)>| {
+            assert_eq!(name, "add");
+            let sum = args
+                .into_iter()
+                .chain(kwargs.into_iter().map(|(_, v)| v))
+                .map(|v| match v {
+                    MontyObject::Int(i) => i,
+                    other => panic!("unexpected argument {other:?}"),
+                })
+                .sum();
+            Ok(MontyObject::Int(sum))
+        },
+    ));
+    assert_eq!(result.unwrap(), MontyObject::Int(33));
+}
+
+#[test]
+fn run_async_handler_error_is_raised() {
+    let code = "
+try:
+    fetch('x')
+except ValueError as e:
+    result = f'caught: {e}'
+result
+";
+    let runner = MontyRun::new(code.to_owned(), "test.py", vec![], vec!["fetch".to_owned()]).unwrap();
+
+    let result = block_on(runner.run_async(
+        vec![],
+        NoLimitTracker,
+        &mut PrintWriter::Disabled,
+        async |_: &str, _: Vec<MontyObject>, _: Vec<(MontyObject, MontyObject)>| {
+            Err(MontyException::new(ExcType::ValueError, Some("not found".to_owned())))
+        },
+    ));
+    assert_eq!(result.unwrap(), MontyObject::String("caught: not found".to_owned()));
+}
+
+#[test]
+fn run_async_os_call_not_implemented() {
+    let runner = MontyRun::new("import os\nos.getenv('HOME')".to_owned(), "test.py", vec![], vec![]).unwrap();
+
+    let err = block_on(runner.run_async(
+        vec![],
+        NoLimitTracker,
+        &mut PrintWriter::Disabled,
+        async |_: &str, _: Vec<MontyObject>, _: Vec<(MontyObject, MontyObject)>| Ok(MontyObject::None),
+    ))
+    .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::NotImplementedError);
+    assert_eq!(err.message(), Some("OS function 'os.getenv' not implemented"));
+}
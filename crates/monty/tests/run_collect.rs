@@ -0,0 +1,132 @@
+//! Tests for `MontyRun::run_collect`, which returns the final values of named globals.
+
+use monty::{ExcType, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResultSchema};
+
+fn runner(code: &str) -> MontyRun {
+    MontyRun::new(code.to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap()
+}
+
+#[test]
+fn collects_requested_globals_in_order() {
+    let code = "
+errors = []
+stats = {'count': 0}
+for i in range(x):
+    stats['count'] += 1
+    if i % 2:
+        errors.append(i)
+result = 'done'
+";
+    let collected = runner(code)
+        .run_collect(
+            vec![MontyObject::Int(4)],
+            &["stats", "result", "errors"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap();
+    assert_eq!(collected.result, MontyObject::None);
+    let names: Vec<_> = collected.variables.keys().map(String::as_str).collect();
+    assert_eq!(names, ["stats", "result", "errors"]);
+    assert_eq!(collected.variables["result"], MontyObject::String("done".to_owned()));
+    assert_eq!(
+        collected.variables["errors"],
+        MontyObject::List(vec![MontyObject::Int(1), MontyObject::Int(3)])
+    );
+    assert_eq!(
+        collected.variables["stats"],
+        MontyObject::dict(vec![(MontyObject::String("count".to_owned()), MontyObject::Int(4))])
+    );
+}
+
+#[test]
+fn keeps_last_expression() {
+    let collected = runner("y = x + 1\ny * 2")
+        .run_collect(
+            vec![MontyObject::Int(1)],
+            &["y", "x"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap();
+    assert_eq!(collected.result, MontyObject::Int(4));
+    assert_eq!(collected.variables["y"], MontyObject::Int(2));
+    assert_eq!(collected.variables["x"], MontyObject::Int(1));
+}
+
+#[test]
+fn unbound_names_are_left_out() {
+    let code = "
+a = 1
+b = 2
+del b
+
+def f():
+    local = 3
+    return local
+
+f()
+";
+    let collected = runner(code)
+        .run_collect(
+            vec![MontyObject::None],
+            &["a", "b", "local", "missing"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap();
+    let names: Vec<_> = collected.variables.keys().map(String::as_str).collect();
+    assert_eq!(names, ["a"]);
+}
+
+#[test]
+fn globals_assigned_by_functions() {
+    let code = "
+total = 0
+
+def add(n):
+    global total
+    total += n
+
+add(x)
+add(x)
+";
+    let collected = runner(code)
+        .run_collect(
+            vec![MontyObject::Int(5)],
+            &["total"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap();
+    assert_eq!(collected.variables["total"], MontyObject::Int(10));
+}
+
+#[test]
+fn errors_are_returned() {
+    let err = runner("result = 1\nraise ValueError('bad')")
+        .run_collect(
+            vec![MontyObject::None],
+            &["result"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ValueError);
+}
+
+#[test]
+fn result_schema_applies_to_the_last_expression() {
+    let schema: ResultSchema = "int".parse().unwrap();
+    let err = runner("value = 'a'\nvalue")
+        .with_result_schema(schema)
+        .run_collect(
+            vec![MontyObject::None],
+            &["value"],
+            NoLimitTracker,
+            &mut PrintWriter::Stdout,
+        )
+        .unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::TypeError);
+    assert!(err.schema_mismatch().is_some());
+}
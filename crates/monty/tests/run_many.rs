@@ -0,0 +1,96 @@
+//! Tests for running one compiled program over many input sets.
+
+use monty::{ExcType, LimitedTracker, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResourceLimits};
+
+fn runner() -> MontyRun {
+    let code = "
+if x < 0:
+    raise ValueError('negative')
+x * 2
+";
+    MontyRun::new(code.to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap()
+}
+
+#[test]
+fn run_many_returns_results_in_order() {
+    let inputs = (0..5).map(|i| vec![MontyObject::Int(i)]).collect();
+    let results = runner().run_many(inputs, || NoLimitTracker, &mut PrintWriter::Disabled);
+
+    let values: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(values, (0..5).map(|i| MontyObject::Int(i * 2)).collect::<Vec<_>>());
+}
+
+#[test]
+fn run_many_failure_does_not_stop_other_runs() {
+    let inputs = vec![
+        vec![MontyObject::Int(1)],
+        vec![MontyObject::Int(-1)],
+        vec![MontyObject::Int(3)],
+    ];
+    let results = runner().run_many(inputs, || NoLimitTracker, &mut PrintWriter::Disabled);
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap(), &MontyObject::Int(2));
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!(err.exc_type(), ExcType::ValueError);
+    assert_eq!(results[2].as_ref().unwrap(), &MontyObject::Int(6));
+}
+
+#[test]
+fn run_many_fresh_tracker_per_run() {
+    let code = "
+total = 0
+for i in range(n):
+    total += i
+total
+";
+    let ex = MontyRun::new(code.to_owned(), "test.py", vec!["n".to_owned()], vec![]).unwrap();
+    let inputs = vec![
+        vec![MontyObject::Int(10)],
+        vec![MontyObject::Int(1_000_000)],
+        vec![MontyObject::Int(10)],
+    ];
+    let results = ex.run_many(
+        inputs,
+        || LimitedTracker::new(ResourceLimits::new().max_steps(10_000)),
+        &mut PrintWriter::Disabled,
+    );
+
+    assert_eq!(results[0].as_ref().unwrap(), &MontyObject::Int(45));
+    assert!(results[1].is_err(), "long run should exceed its own step limit");
+    assert_eq!(
+        results[2].as_ref().unwrap(),
+        &MontyObject::Int(45),
+        "limit should not carry over from the previous run"
+    );
+}
+
+#[test]
+fn run_many_shares_print_writer() {
+    let ex = MontyRun::new("print(x)".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    let inputs = vec![vec![MontyObject::Int(1)], vec![MontyObject::Int(2)]];
+    let mut writer = PrintWriter::Collect(String::new());
+    let results = ex.run_many(inputs, || NoLimitTracker, &mut writer);
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(writer.collected_output().unwrap(), "1\n2\n");
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn run_many_parallel_matches_sequential() {
+    let ex = runner();
+    let inputs: Vec<_> = (-2..100).map(|i| vec![MontyObject::Int(i)]).collect();
+
+    let parallel = ex.run_many_parallel(inputs.clone(), || NoLimitTracker);
+    let sequential = ex.run_many(inputs, || NoLimitTracker, &mut PrintWriter::Disabled);
+
+    assert_eq!(parallel.len(), sequential.len());
+    for (p, s) in parallel.iter().zip(&sequential) {
+        match (p, s) {
+            (Ok(p), Ok(s)) => assert_eq!(p, s),
+            (Err(p), Err(s)) => assert_eq!(p.exc_type(), s.exc_type()),
+            _ => panic!("parallel and sequential results differ: {p:?} vs {s:?}"),
+        }
+    }
+}
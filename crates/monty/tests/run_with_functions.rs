@@ -0,0 +1,79 @@
+//! Tests for `MontyRun::run_with_functions`, which resolves external calls from an `ExternalFunctions` registry.
+
+use monty::{
+    ExcType, ExternalFunctions, LimitedTracker, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter,
+    ResourceLimits,
+};
+
+fn functions() -> ExternalFunctions {
+    ExternalFunctions::new()
+        .function("add", |args, kwargs| {
+            let sum = args
+                .iter()
+                .chain(kwargs.iter().map(|(_, v)| v))
+                .map(|v| match v {
+                    MontyObject::Int(i) => *i,
+                    _ => 0,
+                })
+                .sum();
+            Ok(MontyObject::Int(sum))
+        })
+        .function("fail", |_, _| {
+            Err(MontyException::new(ExcType::ValueError, Some("boom".to_owned())))
+        })
+}
+
+fn runner(code: &str, external_functions: &[&str]) -> MontyRun {
+    let external_functions = external_functions.iter().map(|&name| name.to_owned()).collect();
+    MontyRun::new(code.to_owned(), "test.py", vec![], external_functions).unwrap()
+}
+
+#[test]
+fn resolves_external_calls() {
+    let runner = runner("add(1, 2) + add(x=10, y=20)", &["add"]);
+    let result = runner
+        .run_with_functions(vec![], &functions(), NoLimitTracker, &mut PrintWriter::Disabled)
+        .unwrap();
+    assert_eq!(result, MontyObject::Int(33));
+}
+
+#[test]
+fn errors_are_raised_in_sandboxed_code() {
+    let code = "
+try:
+    fail()
+except ValueError as e:
+    result = str(e)
+result
+";
+    let runner = runner(code, &["fail"]);
+    let result = runner
+        .run_with_functions(vec![], &functions(), NoLimitTracker, &mut PrintWriter::Disabled)
+        .unwrap();
+    assert_eq!(result, MontyObject::String("boom".to_owned()));
+}
+
+#[test]
+fn missing_function_raises_key_error() {
+    let runner = runner("missing()", &["missing"]);
+    let exc = runner
+        .run_with_functions(vec![], &functions(), NoLimitTracker, &mut PrintWriter::Disabled)
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::KeyError);
+    assert_eq!(exc.message(), Some("\"External function 'missing' not found\""));
+}
+
+#[test]
+fn runner_is_reusable_with_limits() {
+    let runner = runner("add(1, 1)", &["add"]);
+    let funcs = functions();
+    assert!(funcs.contains("add"));
+    assert!(!funcs.contains("missing"));
+    for _ in 0..3 {
+        let tracker = LimitedTracker::new(ResourceLimits::new().max_allocations(1000));
+        let result = runner
+            .run_with_functions(vec![], &funcs, tracker, &mut PrintWriter::Disabled)
+            .unwrap();
+        assert_eq!(result, MontyObject::Int(2));
+    }
+}
@@ -0,0 +1,395 @@
+//! Tests for the versioned snapshot format written by every `dump()` method.
+//!
+//! The golden files in `tests/snapshot_golden` are named `v{format_version}_{case}.bin`. Each
+//! one must either load and resume correctly, or, once its format version is no longer
+//! supported, fail with `SnapshotError::UnsupportedVersion`. Every case needs a committed file
+//! for the current format version; after bumping it, write them with
+//! `MONTY_BLESS_SNAPSHOTS=1 cargo test -p monty --test snapshot_format -- --include-ignored`
+//! and commit them. Never edit or delete old ones.
+
+use std::{env, fs, path::PathBuf};
+
+use monty::{
+    LimitedTracker, MIN_SNAPSHOT_FORMAT_VERSION, MontyObject, MontyRepl, MontyRun, NoLimitTracker, PrintWriter,
+    ResourceLimits, RunProgress, SNAPSHOT_FORMAT_VERSION, SnapshotCompression, SnapshotError, snapshot_format_version,
+};
+
+const FIB_CODE: &str = "
+def fib(n):
+    if n <= 1:
+        return n
+    return fib(n - 1) + fib(n - 2)
+fib(n)
+";
+
+const SUSPENDED_CODE: &str = "
+data = {'items': [1, 2, 3], 'label': 'sum'}
+total = fetch(data['label']) + sum(data['items'])
+(data['label'], total)
+";
+
+/// A kind of snapshot covered by golden files: how to create it, and how to check a loaded one.
+struct GoldenCase {
+    name: &'static str,
+    dump: fn() -> Vec<u8>,
+    check: fn(&[u8]) -> Result<(), SnapshotError>,
+}
+
+fn cases() -> [GoldenCase; 3] {
+    [
+        GoldenCase {
+            name: "runner",
+            dump: || {
+                let runner = MontyRun::new(FIB_CODE.to_owned(), "fib.py", vec!["n".to_owned()], vec![]).unwrap();
+                runner.dump().unwrap()
+            },
+            check: |bytes| {
+                let runner = MontyRun::load(bytes)?;
+                let result = runner.run_no_limits(vec![MontyObject::Int(10)]).unwrap();
+                assert_eq!(result, MontyObject::Int(55));
+                Ok(())
+            },
+        },
+        GoldenCase {
+            name: "function_call",
+            dump: || {
+                let runner =
+                    MontyRun::new(SUSPENDED_CODE.to_owned(), "fetch.py", vec![], vec!["fetch".to_owned()]).unwrap();
+                let progress = runner
+                    .start(vec![], NoLimitTracker, &mut PrintWriter::Disabled)
+                    .unwrap();
+                progress.dump().unwrap()
+            },
+            check: |bytes| {
+                let progress = RunProgress::<NoLimitTracker>::load(bytes)?;
+                let (name, args, _, _, _, state) = progress.into_function_call().unwrap();
+                assert_eq!(name, "fetch");
+                assert_eq!(args, vec![MontyObject::String("sum".to_owned())]);
+                let result = state
+                    .run(MontyObject::Int(100), &mut PrintWriter::Disabled)
+                    .unwrap()
+                    .into_complete()
+                    .unwrap();
+                assert_eq!(
+                    result,
+                    MontyObject::Tuple(vec![MontyObject::String("sum".to_owned()), MontyObject::Int(106)])
+                );
+                Ok(())
+            },
+        },
+        GoldenCase {
+            name: "repl",
+            dump: || {
+                let (mut repl, _) = MontyRepl::new(
+                    "counter = 40".to_owned(),
+                    "repl.py",
+                    vec![],
+                    vec![],
+                    vec![],
+                    NoLimitTracker,
+                    &mut PrintWriter::Disabled,
+                )
+                .unwrap();
+                repl.feed_no_print("def bump(by):\n    return counter + by").unwrap();
+                repl.dump().unwrap()
+            },
+            check: |bytes| {
+                let mut repl = MontyRepl::<NoLimitTracker>::load(bytes)?;
+                assert_eq!(repl.feed_no_print("bump(2)").unwrap(), MontyObject::Int(42));
+                Ok(())
+            },
+        },
+    ]
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshot_golden")
+}
+
+/// Parses `v{version}_{case}.bin` into its format version and case name.
+fn parse_golden_name(file_name: &str) -> Option<(u16, &str)> {
+    let (version, case) = file_name.strip_prefix('v')?.strip_suffix(".bin")?.split_once('_')?;
+    Some((version.parse().ok()?, case))
+}
+
+#[test]
+fn current_snapshots_round_trip() {
+    for case in cases() {
+        (case.check)(&(case.dump)()).unwrap_or_else(|err| panic!("{}: {err}", case.name));
+    }
+}
+
+#[test]
+#[ignore = "the v1 golden files aren't committed yet, bless them and remove this"]
+fn golden_snapshots_load_or_fail_loudly() {
+    let dir = golden_dir();
+    let cases = cases();
+
+    // golden files are only written on request, so a missing one can't pass silently
+    let bless = env::var_os("MONTY_BLESS_SNAPSHOTS").is_some();
+    for case in &cases {
+        let path = dir.join(format!("v{SNAPSHOT_FORMAT_VERSION}_{}.bin", case.name));
+        if bless {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&path, (case.dump)()).unwrap();
+        }
+        assert!(
+            path.exists(),
+            "missing golden file {}, write it with MONTY_BLESS_SNAPSHOTS=1 and commit it",
+            path.display()
+        );
+    }
+
+    let mut checked = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let file_name = path.file_name().unwrap().to_str().unwrap().to_owned();
+        let (version, case_name) =
+            parse_golden_name(&file_name).unwrap_or_else(|| panic!("unexpected file in golden dir: {file_name}"));
+        let case = cases
+            .iter()
+            .find(|case| case.name == case_name)
+            .unwrap_or_else(|| panic!("{file_name}: unknown golden case {case_name:?}"));
+        let bytes = fs::read(&path).unwrap();
+
+        assert_eq!(snapshot_format_version(&bytes).unwrap(), version, "{file_name}");
+        let result = (case.check)(&bytes);
+        if (MIN_SNAPSHOT_FORMAT_VERSION..=SNAPSHOT_FORMAT_VERSION).contains(&version) {
+            result.unwrap_or_else(|err| {
+                panic!("{file_name}: supported snapshot failed to load, bump SNAPSHOT_FORMAT_VERSION? {err}")
+            });
+        } else {
+            match result {
+                Err(SnapshotError::UnsupportedVersion { found, .. }) => assert_eq!(found, version, "{file_name}"),
+                other => panic!("{file_name}: expected UnsupportedVersion, got {other:?}"),
+            }
+        }
+        checked += 1;
+    }
+    assert!(checked >= cases.len(), "expected golden files in {}", dir.display());
+}
+
+#[test]
+fn dump_writes_versioned_header() {
+    let bytes = MontyRun::new("1".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .dump()
+        .unwrap();
+    assert_eq!(&bytes[..4], b"MNTY");
+    assert_eq!(snapshot_format_version(&bytes).unwrap(), SNAPSHOT_FORMAT_VERSION);
+    assert_eq!(bytes[6], 0, "dump() doesn't compress");
+}
+
+#[test]
+fn load_rejects_data_without_header() {
+    for bytes in [&b""[..], b"MNT", b"not a snapshot"] {
+        let err = MontyRun::load(bytes).unwrap_err();
+        assert!(matches!(err, SnapshotError::NotASnapshot), "{bytes:?}: {err:?}");
+        assert_eq!(err.to_string(), "data is not a Monty snapshot, the header is missing");
+    }
+}
+
+#[test]
+fn load_rejects_newer_format_version() {
+    let mut bytes = MontyRun::new("1".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .dump()
+        .unwrap();
+    let newer = SNAPSHOT_FORMAT_VERSION + 1;
+    bytes[4..6].copy_from_slice(&newer.to_le_bytes());
+
+    let err = MontyRun::load(&bytes).unwrap_err();
+    let SnapshotError::UnsupportedVersion {
+        found,
+        min_supported,
+        max_supported,
+    } = &err
+    else {
+        panic!("expected UnsupportedVersion, got {err:?}");
+    };
+    assert_eq!(*found, newer);
+    assert_eq!(*min_supported, MIN_SNAPSHOT_FORMAT_VERSION);
+    assert_eq!(*max_supported, SNAPSHOT_FORMAT_VERSION);
+    assert!(err.to_string().ends_with("upgrade Monty to load it"), "{err}");
+}
+
+#[test]
+fn load_reports_corrupt_payload() {
+    let bytes = MontyRun::new("1".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .dump()
+        .unwrap();
+    let err = MontyRun::load(&bytes[..8]).unwrap_err();
+    assert!(matches!(err, SnapshotError::Postcard(_)), "{err:?}");
+    assert!(err.to_string().starts_with("invalid snapshot data: "), "{err}");
+}
+
+#[test]
+fn load_rejects_unknown_compression() {
+    let mut bytes = MontyRun::new("1".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .dump()
+        .unwrap();
+    bytes[6] = 9;
+    let err = MontyRun::load(&bytes).unwrap_err();
+    assert!(
+        matches!(err, SnapshotError::UnsupportedCompression { id: 9 }),
+        "{err:?}"
+    );
+    assert_eq!(err.to_string(), "snapshot uses unknown compression 9");
+}
+
+const BIG_HEAP_CODE: &str = "
+rows = [{'id': i, 'name': 'row ' + str(i)} for i in range(500)]
+fetch(len(rows))
+";
+
+/// Starts `BIG_HEAP_CODE` and returns the progress suspended at the `fetch()` call.
+fn big_heap_progress(limits: ResourceLimits) -> RunProgress<LimitedTracker> {
+    let runner = MontyRun::new(BIG_HEAP_CODE.to_owned(), "big.py", vec![], vec!["fetch".to_owned()]).unwrap();
+    runner
+        .start(vec![], LimitedTracker::new(limits), &mut PrintWriter::Disabled)
+        .unwrap()
+}
+
+#[test]
+fn dump_stats_breaks_down_snapshot_size() {
+    let progress = big_heap_progress(ResourceLimits::new());
+    let stats = progress.dump_stats().unwrap();
+
+    assert_eq!(stats.total_bytes, progress.dump().unwrap().len());
+    assert_eq!(
+        stats.sections.iter().map(|(_, size)| size).sum::<usize>(),
+        stats.total_bytes
+    );
+    let names: Vec<_> = stats.sections.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["code", "vm_state", "heap", "namespaces", "other"]);
+
+    // 500 dicts and their name strings, plus the list holding them
+    assert!(stats.heap_objects > 1000, "{stats:?}");
+    assert_eq!(stats.namespaces, 1, "{stats:?}");
+    let (_, heap_bytes) = stats.sections[2];
+    assert!(heap_bytes > stats.total_bytes / 2, "heap should dominate: {stats:?}");
+
+    let complete = progress.into_function_call().unwrap().5;
+    let complete = complete.run(MontyObject::None, &mut PrintWriter::Disabled).unwrap();
+    let stats = complete.dump_stats().unwrap();
+    assert_eq!(stats.heap_objects, 0);
+    assert_eq!(stats.sections, [("other", complete.dump().unwrap().len())]);
+}
+
+#[test]
+fn repl_dump_stats() {
+    let (repl, _) = MontyRepl::new(
+        "names = ['x' * i for i in range(100)]".to_owned(),
+        "repl.py",
+        vec![],
+        vec![],
+        vec![],
+        NoLimitTracker,
+        &mut PrintWriter::Disabled,
+    )
+    .unwrap();
+    let stats = repl.dump_stats().unwrap();
+    assert_eq!(stats.total_bytes, repl.dump().unwrap().len());
+    assert!(stats.heap_objects > 100, "{stats:?}");
+    let names: Vec<_> = stats.sections.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["code", "heap", "namespaces", "other"]);
+}
+
+#[test]
+fn max_snapshot_size_rejects_large_snapshots() {
+    let size = big_heap_progress(ResourceLimits::new()).dump().unwrap().len();
+
+    let progress = big_heap_progress(ResourceLimits::new().max_snapshot_size(size));
+    assert_eq!(progress.dump().unwrap().len(), size);
+
+    let progress = big_heap_progress(ResourceLimits::new().max_snapshot_size(size - 1));
+    let err = progress.dump().unwrap_err();
+    let SnapshotError::TooLarge { size: found, limit } = err else {
+        panic!("expected TooLarge, got {err:?}");
+    };
+    assert_eq!((found, limit), (size, size - 1));
+}
+
+#[test]
+fn max_size_rejects_large_snapshots_on_load() {
+    let bytes = big_heap_progress(ResourceLimits::new()).dump().unwrap();
+    assert!(RunProgress::<LimitedTracker>::load_with_max_size(&bytes, bytes.len()).is_ok());
+
+    let err = RunProgress::<LimitedTracker>::load_with_max_size(&bytes, bytes.len() - 1).unwrap_err();
+    let SnapshotError::TooLarge { size, limit } = err else {
+        panic!("expected TooLarge, got {err:?}");
+    };
+    assert_eq!((size, limit), (bytes.len(), bytes.len() - 1));
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4_snapshots_are_smaller_and_load_transparently() {
+    let progress = big_heap_progress(ResourceLimits::new());
+    let plain = progress.dump().unwrap();
+    let compressed = progress.dump_with(SnapshotCompression::Lz4).unwrap();
+    assert_eq!(compressed[6], 1);
+    assert!(
+        compressed.len() * 2 < plain.len(),
+        "{} vs {}",
+        compressed.len(),
+        plain.len()
+    );
+
+    let progress = RunProgress::<LimitedTracker>::load(&compressed).unwrap();
+    let (name, args, _, _, _, state) = progress.into_function_call().unwrap();
+    assert_eq!(name, "fetch");
+    assert_eq!(args, vec![MontyObject::Int(500)]);
+    let result = state.run(MontyObject::None, &mut PrintWriter::Disabled).unwrap();
+    assert_eq!(result.into_complete().unwrap(), MontyObject::None);
+
+    // the size limit applies after compression
+    let limited = big_heap_progress(ResourceLimits::new().max_snapshot_size(compressed.len()));
+    assert!(matches!(limited.dump(), Err(SnapshotError::TooLarge { .. })));
+    assert_eq!(
+        limited.dump_with(SnapshotCompression::Lz4).unwrap().len(),
+        compressed.len()
+    );
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4_decompression_is_capped() {
+    let progress = big_heap_progress(ResourceLimits::new());
+    let plain_len = progress.dump().unwrap().len();
+    let compressed = progress.dump_with(SnapshotCompression::Lz4).unwrap();
+
+    // the payload fits the limit compressed, but not once decompressed
+    let err = RunProgress::<LimitedTracker>::load_with_max_size(&compressed, compressed.len()).unwrap_err();
+    assert!(matches!(err, SnapshotError::TooLarge { .. }), "{err:?}");
+    assert!(RunProgress::<LimitedTracker>::load_with_max_size(&compressed, plain_len).is_ok());
+
+    // a forged size larger than the compressed bytes could hold is rejected before allocating
+    let mut forged = compressed.clone();
+    forged[7..11].copy_from_slice(&u32::MAX.to_le_bytes());
+    let err = RunProgress::<LimitedTracker>::load(&forged).unwrap_err();
+    assert!(matches!(err, SnapshotError::Compression(_)), "{err:?}");
+
+    // a size smaller than the real payload fails instead of writing past it
+    let mut forged = compressed;
+    forged[7..11].copy_from_slice(&100u32.to_le_bytes());
+    let err = RunProgress::<LimitedTracker>::load(&forged).unwrap_err();
+    assert!(matches!(err, SnapshotError::Compression(_)), "{err:?}");
+}
+
+#[cfg(not(feature = "lz4"))]
+#[test]
+fn lz4_snapshots_need_the_feature() {
+    let mut bytes = MontyRun::new("1".to_owned(), "test.py", vec![], vec![])
+        .unwrap()
+        .dump_with(SnapshotCompression::None)
+        .unwrap();
+    bytes[6] = 1;
+    let err = MontyRun::load(&bytes).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "snapshot is lz4-compressed, but this build of Monty was compiled without the `lz4` feature"
+    );
+}
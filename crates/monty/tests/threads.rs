@@ -0,0 +1,102 @@
+//! Tests for sharing one compiled program between threads.
+
+use std::{sync::Arc, thread};
+
+use monty::{LimitedTracker, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResourceLimits, RunProgress};
+
+const fn assert_send_sync<T: Send + Sync>() {}
+const _: () = assert_send_sync::<MontyRun>();
+
+fn sum_runner() -> MontyRun {
+    let code = "
+items = []
+for i in range(n):
+    items.append(i)
+sum(items)
+";
+    MontyRun::new(code.to_owned(), "test.py", vec!["n".to_owned()], vec![]).unwrap()
+}
+
+#[test]
+fn concurrent_runs_share_program() {
+    let runner = sum_runner();
+
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|n| {
+                let runner = &runner;
+                s.spawn(move || {
+                    runner.run(
+                        vec![MontyObject::Int(n * 100)],
+                        NoLimitTracker,
+                        &mut PrintWriter::Disabled,
+                    )
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    for (n, result) in (0..8).zip(results) {
+        let n = n * 100;
+        assert_eq!(result.unwrap(), MontyObject::Int(n * (n - 1) / 2));
+    }
+}
+
+#[test]
+fn concurrent_runs_have_separate_trackers() {
+    let runner = Arc::new(sum_runner());
+
+    let handles: Vec<_> = [10, 100_000, 10, 100_000]
+        .into_iter()
+        .map(|n| {
+            let runner = Arc::clone(&runner);
+            thread::spawn(move || {
+                let tracker = LimitedTracker::new(ResourceLimits::new().max_allocations(1_000));
+                runner.run(vec![MontyObject::Int(n)], tracker, &mut PrintWriter::Disabled)
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(results[0].as_ref().unwrap(), &MontyObject::Int(45));
+    assert!(results[1].is_err(), "large run should exceed its own allocation limit");
+    assert_eq!(results[2].as_ref().unwrap(), &MontyObject::Int(45));
+    assert!(results[3].is_err(), "large run should exceed its own allocation limit");
+}
+
+#[test]
+fn cloned_runners_start_on_separate_threads() {
+    let runner = MontyRun::new(
+        "fetch(x) + 1".to_owned(),
+        "test.py",
+        vec!["x".to_owned()],
+        vec!["fetch".to_owned()],
+    )
+    .unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let runner = runner.clone();
+            thread::spawn(move || {
+                let progress = runner
+                    .start(vec![MontyObject::Int(i)], NoLimitTracker, &mut PrintWriter::Disabled)
+                    .unwrap();
+                let RunProgress::FunctionCall { args, state, .. } = progress else {
+                    panic!("expected function call");
+                };
+                let result = state.run(args[0].clone(), &mut PrintWriter::Disabled).unwrap();
+                result.into_complete().unwrap()
+            })
+        })
+        .collect();
+
+    for (i, handle) in (0..4).zip(handles) {
+        assert_eq!(handle.join().unwrap(), MontyObject::Int(i + 1));
+    }
+    // the original is still usable after its clones were consumed by `start()`
+    let progress = runner
+        .start(vec![MontyObject::Int(9)], NoLimitTracker, &mut PrintWriter::Disabled)
+        .unwrap();
+    assert_eq!(progress.into_function_call().unwrap().0, "fetch");
+}
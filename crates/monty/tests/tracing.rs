@@ -0,0 +1,264 @@
+//! Tests for trace hooks, called before each statement with its line and kind.
+
+use std::sync::{Arc, Mutex};
+
+use monty::{
+    ExcType, LimitedTracker, MontyException, MontyObject, MontyRun, NoLimitTracker, PrintWriter, ResourceLimits,
+    RunProgress, StatementKind, TraceEvent, TraceHook,
+};
+
+const CODE: &str = "
+def double(n):
+    result = n * 2
+    return result
+
+total = 0
+for i in range(2):
+    total += double(i)
+    pass
+total
+";
+
+/// Runs `code` with a hook recording each statement's line and kind.
+fn trace(code: &str) -> (MontyObject, Vec<(u16, StatementKind)>) {
+    let runner = MontyRun::new(code.to_owned(), "trace.py", vec![], vec![]).unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&events);
+    let hook = move |event: &TraceEvent<'_>| -> Result<(), MontyException> {
+        recorded.lock().unwrap().push((event.line, event.kind));
+        Ok(())
+    };
+    let result = runner
+        .run_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, hook)
+        .unwrap();
+    let events = events.lock().unwrap().clone();
+    (result, events)
+}
+
+#[test]
+fn hook_sees_every_statement_in_order() {
+    use StatementKind::{Assign, AugAssign, For, FunctionDef, Return};
+
+    let (result, events) = trace(CODE);
+    assert_eq!(result, MontyObject::Int(2));
+    let function_body = [(3, Assign), (4, Return)];
+    let mut expected = vec![(2, FunctionDef), (6, Assign), (7, For)];
+    for _ in 0..2 {
+        expected.push((8, AugAssign));
+        expected.extend(function_body);
+    }
+    // `pass` compiles to nothing, so it is never reported; the last expression is the module's result
+    expected.push((10, Return));
+    assert_eq!(events, expected);
+}
+
+#[test]
+fn statement_kinds() {
+    let code = "
+import math
+x = [1, 2]
+x[0] = 3
+del x[1]
+while x:
+    if x[0] > 0:
+        break
+    continue
+try:
+    assert x, 'empty'
+    raise ValueError('boom')
+except ValueError:
+    print(len(x))
+";
+    let (_, events) = trace(code);
+    let kinds: Vec<_> = events.iter().map(|(_, kind)| kind.name()).collect();
+    assert_eq!(
+        kinds,
+        [
+            "import", "assign", "assign", "delete", "while", "if", "break", "assert", "raise", "expr"
+        ]
+    );
+    let lines: Vec<_> = events.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, [2, 3, 4, 5, 6, 7, 8, 11, 12, 14]);
+}
+
+/// Hook that records the locals of `double` at each of its `return`s.
+struct LocalsHook(Arc<Mutex<Vec<Vec<(String, String)>>>>);
+
+impl TraceHook for LocalsHook {
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        assert_eq!(event.function_name == "double", event.depth == 2, "{event:?}");
+        if event.kind == StatementKind::Return && event.function_name == "double" {
+            let locals = event.locals.clone().expect("locals were requested");
+            self.0.lock().unwrap().push(locals);
+        }
+        Ok(())
+    }
+
+    fn wants_locals(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn hook_can_request_locals() {
+    let runner = MontyRun::new(CODE.to_owned(), "trace.py", vec![], vec![]).unwrap();
+    let at_return = Arc::new(Mutex::new(Vec::new()));
+    let hook = LocalsHook(Arc::clone(&at_return));
+    let progress = runner
+        .start_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, hook)
+        .unwrap();
+    assert_eq!(progress.into_complete().unwrap(), MontyObject::Int(2));
+
+    let local = |name: &str, repr: &str| (name.to_owned(), repr.to_owned());
+    assert_eq!(
+        *at_return.lock().unwrap(),
+        [
+            vec![local("n", "0"), local("result", "0")],
+            vec![local("n", "1"), local("result", "2")],
+        ]
+    );
+}
+
+/// Hook that records the repr of the local `s`.
+struct ReprHook(Arc<Mutex<Option<String>>>);
+
+impl TraceHook for ReprHook {
+    fn on_statement(&mut self, event: &TraceEvent<'_>) -> Result<(), MontyException> {
+        if let Some([(name, repr)]) = event.locals.as_deref()
+            && name == "s"
+        {
+            *self.0.lock().unwrap() = Some(repr.clone());
+        }
+        Ok(())
+    }
+
+    fn wants_locals(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn long_local_reprs_are_truncated() {
+    let code = "
+def f(s):
+    return s
+f('a' * 500)
+";
+    let runner = MontyRun::new(code.to_owned(), "trace.py", vec![], vec![]).unwrap();
+    let seen = Arc::new(Mutex::new(None));
+    let hook = ReprHook(Arc::clone(&seen));
+    runner
+        .run_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, hook)
+        .unwrap();
+    let repr = seen.lock().unwrap().clone().unwrap();
+    assert_eq!(repr.chars().count(), 103);
+    assert!(repr.starts_with("'aaa") && repr.ends_with("..."), "{repr}");
+}
+
+#[test]
+fn hook_error_aborts_and_cannot_be_caught() {
+    let code = "
+try:
+    x = 1
+except Exception:
+    x = 2
+x
+";
+    let runner = MontyRun::new(code.to_owned(), "trace.py", vec![], vec![]).unwrap();
+    let hook = |event: &TraceEvent<'_>| -> Result<(), MontyException> {
+        if event.line == 3 {
+            Err(MontyException::new(
+                ExcType::RuntimeError,
+                Some("audit failed".to_owned()),
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    let exc = runner
+        .run_with_trace(vec![], NoLimitTracker, &mut PrintWriter::Disabled, hook)
+        .unwrap_err();
+    assert_eq!(exc.exc_type(), ExcType::RuntimeError);
+    assert_eq!(exc.message(), Some("audit failed"));
+}
+
+#[test]
+fn hook_follows_external_calls_and_checkpoints() {
+    let code = "
+a = ext(1)
+b = ext(a)
+b
+";
+    let runner = MontyRun::new(code.to_owned(), "trace.py", vec![], vec!["ext".to_owned()]).unwrap();
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&lines);
+    let hook = move |event: &TraceEvent<'_>| -> Result<(), MontyException> {
+        recorded.lock().unwrap().push(event.line);
+        Ok(())
+    };
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(1));
+    let mut progress = runner
+        .start_with_trace(vec![], tracker, &mut PrintWriter::Disabled, hook)
+        .unwrap();
+    let result = loop {
+        progress = match progress {
+            RunProgress::FunctionCall { args, state, .. } => {
+                let MontyObject::Int(n) = args[0] else {
+                    panic!("expected int argument, got {args:?}");
+                };
+                state.run(MontyObject::Int(n + 1), &mut PrintWriter::Disabled).unwrap()
+            }
+            RunProgress::Paused(state) => state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        };
+    };
+    assert_eq!(result, MontyObject::Int(3));
+    // each statement is reported once, even though execution stopped before each of them
+    assert_eq!(*lines.lock().unwrap(), [2, 3, 4]);
+}
+
+#[test]
+fn loaded_snapshots_run_untraced_until_a_hook_is_set() {
+    let runner = MontyRun::new("x = 1\ny = 2\nx + y".to_owned(), "trace.py", vec![], vec![]).unwrap();
+    let tracker = LimitedTracker::new(ResourceLimits::new().checkpoint_every(1));
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&lines);
+    let progress = runner
+        .start_with_trace(
+            vec![],
+            tracker,
+            &mut PrintWriter::Disabled,
+            move |event: &TraceEvent<'_>| -> Result<(), MontyException> {
+                recorded.lock().unwrap().push(event.line);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    // paused before line 1, which hasn't been reported yet
+    let bytes = progress.dump().unwrap();
+    let state = RunProgress::<LimitedTracker>::load(&bytes)
+        .unwrap()
+        .into_paused()
+        .unwrap();
+    let mut progress = state.resume(&mut PrintWriter::Disabled).unwrap();
+
+    // paused before line 2: trace the rest with a new hook
+    let resumed = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&resumed);
+    progress.set_trace_hook(move |event: &TraceEvent<'_>| -> Result<(), MontyException> {
+        recorded.lock().unwrap().push(event.line);
+        Ok(())
+    });
+    let result = loop {
+        match progress {
+            RunProgress::Paused(state) => progress = state.resume(&mut PrintWriter::Disabled).unwrap(),
+            RunProgress::Complete(value) => break value,
+            other => panic!("unexpected progress: {other:?}"),
+        }
+    };
+    assert_eq!(result, MontyObject::Int(3));
+    assert!(lines.lock().unwrap().is_empty());
+    assert_eq!(*resumed.lock().unwrap(), [2, 3]);
+}
@@ -165,3 +165,102 @@ fn try_from_err_none_to_bool() {
     let err = TryInto::<bool>::try_into(&result).expect_err("conversion should fail");
     assert_eq!(err.to_string(), "expected bool, got NoneType");
 }
+
+/// Tests for conversions between `MontyObject` and serde_json and optional host types.
+
+#[test]
+fn json_value_conversions() {
+    let obj: monty::MontyObject = serde_json::json!({"a": [1, "b"]}).into();
+    let ex = MontyRun::new("x['a'][1]".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    assert_eq!(
+        ex.run_no_limits(vec![obj.clone()]).unwrap(),
+        monty::MontyObject::String("b".to_owned())
+    );
+    let json: serde_json::Value = (&obj).try_into().expect("conversion should succeed");
+    assert_eq!(json, serde_json::json!({"a": [1, "b"]}));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_conversions() {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+    use monty::MontyObject;
+
+    let ex = MontyRun::new(
+        "from datetime import timedelta\n(d, dt, td, d + td, dt.hour)".to_owned(),
+        "test.py",
+        vec!["d".to_owned(), "dt".to_owned(), "td".to_owned()],
+        vec![],
+    )
+    .unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+    let datetime = date.and_hms_micro_opt(13, 14, 15, 16).unwrap();
+    let delta = TimeDelta::seconds(-90);
+    let inputs = vec![date.into(), datetime.into(), delta.try_into().unwrap()];
+    let MontyObject::Tuple(items) = ex.run_no_limits(inputs).unwrap() else {
+        panic!("expected tuple");
+    };
+
+    assert_eq!(NaiveDate::try_from(&items[0]).unwrap(), date);
+    assert_eq!(NaiveDateTime::try_from(&items[1]).unwrap(), datetime);
+    assert_eq!(DateTime::<Utc>::try_from(&items[1]).unwrap(), datetime.and_utc());
+    assert_eq!(TimeDelta::try_from(&items[2]).unwrap(), delta);
+    assert_eq!(
+        NaiveDate::try_from(&items[3]).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 2, 27).unwrap(),
+        "negative timedelta should be normalized like Python"
+    );
+    let err = NaiveDate::try_from(&items[4]).unwrap_err();
+    assert_eq!(err.to_string(), "expected date, got int");
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn decimal_conversions() {
+    use std::str::FromStr;
+
+    use monty::MontyObject;
+    use rust_decimal::Decimal;
+
+    assert_eq!(MontyObject::from(Decimal::from(42)), MontyObject::Int(42));
+    assert_eq!(
+        MontyObject::from(Decimal::from_str("2.5").unwrap()),
+        MontyObject::Float(2.5)
+    );
+
+    let ex = MontyRun::new("(x * 2, str(x))".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    let MontyObject::Tuple(items) = ex
+        .run_no_limits(vec![Decimal::from_str("1.25").unwrap().into()])
+        .unwrap()
+    else {
+        panic!("expected tuple");
+    };
+    assert_eq!(Decimal::try_from(&items[0]).unwrap(), Decimal::from_str("2.5").unwrap());
+    assert_eq!(
+        Decimal::try_from(&items[1]).unwrap(),
+        Decimal::from_str("1.25").unwrap()
+    );
+    assert!(Decimal::try_from(&MontyObject::None).is_err());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuid_conversions() {
+    use monty::MontyObject;
+    use uuid::Uuid;
+
+    let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let ex = MontyRun::new("x.upper()".to_owned(), "test.py", vec!["x".to_owned()], vec![]).unwrap();
+    let result = ex.run_no_limits(vec![uuid.into()]).unwrap();
+
+    assert_eq!(
+        result,
+        MontyObject::String("67E55044-10B1-426F-9247-BB680E5FE0C8".to_owned())
+    );
+    assert_eq!(Uuid::try_from(&result).unwrap(), uuid);
+    assert_eq!(
+        Uuid::try_from(&MontyObject::Bytes(uuid.as_bytes().to_vec())).unwrap(),
+        uuid
+    );
+    assert!(Uuid::try_from(&MontyObject::String("nope".to_owned())).is_err());
+}
@@ -90,6 +90,12 @@ class User:
     def greeting(self) -> str:
         return f'Hello, {self.name}!'
 
+    def __enter__(self) -> str:
+        return self.name
+
+    def __exit__(self, *exc_info: object) -> bool:
+        return False
+
 
 def make_user(name: str) -> User:
     return User(name=name, active=True)
@@ -104,6 +110,17 @@ def make_empty() -> Empty:
     return Empty()
 
 
+def register_handler(func: object) -> str:
+    """Decorator-style function that accepts a callable and returns a marker string."""
+    assert callable(func), 'register_handler requires a function'
+    return 'registered'
+
+
+def collect_args(*args: object, **kwargs: object) -> tuple[list[object], dict[str, object]]:
+    """Returns the positional and keyword arguments it was called with, in order."""
+    return list(args), kwargs
+
+
 async def async_call(x: object) -> object:
     """Async function that returns its argument.
 
@@ -527,4 +544,5 @@ ITER_MODE_GLOBALS: dict[str, object] = {
     'make_user': make_user,
     'make_empty': make_empty,
     'async_call': async_call,
+    'collect_args': collect_args,
 }